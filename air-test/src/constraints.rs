@@ -0,0 +1,82 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use air::{Air, EvaluationFrame};
+use core::fmt;
+use math::FieldElement;
+use prover::{Trace, TraceTable};
+
+// CONSTRAINT VIOLATION
+// ================================================================================================
+
+/// Describes the first constraint of an AIR which is not satisfied by an execution trace.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConstraintViolation {
+    /// An assertion against the specified column is not satisfied at the specified step.
+    Assertion(usize, usize),
+    /// The transition constraint with the specified index does not evaluate to zero at the
+    /// specified step.
+    Transition(usize, usize),
+}
+
+impl fmt::Display for ConstraintViolation {
+    #[rustfmt::skip]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Assertion(column, step) => {
+                write!(f, "assertion against column {column} is not satisfied at step {step}")
+            }
+            Self::Transition(constraint, step) => {
+                write!(f, "transition constraint {constraint} does not evaluate to zero at step {step}")
+            }
+        }
+    }
+}
+
+// CONSTRAINT CHECKER
+// ================================================================================================
+
+/// Checks that the specified execution trace satisfies all assertions and transition constraints
+/// of the specified AIR.
+///
+/// Unlike [Trace::validate()], this function does not panic; instead, the first unsatisfied
+/// constraint is returned as an error. Only the main segment of the trace is checked.
+pub fn check_constraints<A: Air>(
+    air: &A,
+    trace: &TraceTable<A::BaseField>,
+) -> Result<(), ConstraintViolation> {
+    // check assertions
+    let main_segment = trace.main_segment();
+    for assertion in air.get_assertions() {
+        let mut unsatisfied_step = None;
+        assertion.apply(trace.length(), |step, value| {
+            if unsatisfied_step.is_none()
+                && assertion.combine_columns(|column| main_segment.get(column, step)) != value
+            {
+                unsatisfied_step = Some(step);
+            }
+        });
+        if let Some(step) = unsatisfied_step {
+            return Err(ConstraintViolation::Assertion(assertion.column(), step));
+        }
+    }
+
+    // check transition constraints on all steps except the ones exempt from them
+    let g = air.trace_domain_generator();
+    let mut x = A::BaseField::ONE;
+    let mut frame = EvaluationFrame::new(trace.main_trace_width());
+    let mut evaluations = vec![A::BaseField::ZERO; air.context().num_main_transition_constraints()];
+    for step in 0..trace.length() - air.context().num_transition_exemptions() {
+        let periodic_values = air.get_periodic_column_values_at(x);
+        trace.read_main_frame(step, &mut frame);
+        air.evaluate_transition(&frame, &periodic_values, &mut evaluations);
+        if let Some(i) = evaluations.iter().position(|&e| e != A::BaseField::ZERO) {
+            return Err(ConstraintViolation::Transition(i, step));
+        }
+        x *= g;
+    }
+
+    Ok(())
+}
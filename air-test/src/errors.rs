@@ -0,0 +1,47 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use crate::ConstraintViolation;
+use core::fmt;
+use prover::ProverError;
+use verifier::VerifierError;
+
+// AIR TEST ERROR
+// ================================================================================================
+/// Represents an error returned when a trial of an [AirTester](crate::AirTester) fails for a
+/// valid execution trace. The first value of each variant is the index of the trial.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AirTestError {
+    /// The execution trace consists of more than one segment; only single-segment traces are
+    /// supported.
+    MultiSegmentTrace(usize),
+    /// The execution trace returned by the generator does not satisfy constraints of the AIR.
+    InvalidTrace(usize, ConstraintViolation),
+    /// A proof could not be generated for the execution trace returned by the generator.
+    ProofGenerationFailed(usize, ProverError),
+    /// A proof generated for the execution trace returned by the generator was rejected by the
+    /// verifier.
+    VerificationFailed(usize, VerifierError),
+}
+
+impl fmt::Display for AirTestError {
+    #[rustfmt::skip]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MultiSegmentTrace(trial) => {
+                write!(f, "execution trace of trial {trial} consists of more than one segment")
+            }
+            Self::InvalidTrace(trial, violation) => {
+                write!(f, "execution trace of trial {trial} is invalid: {violation}")
+            }
+            Self::ProofGenerationFailed(trial, err) => {
+                write!(f, "failed to generate a proof for the execution trace of trial {trial}: {err}")
+            }
+            Self::VerificationFailed(trial, err) => {
+                write!(f, "proof for the execution trace of trial {trial} was rejected: {err}")
+            }
+        }
+    }
+}
@@ -0,0 +1,318 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+//! This crate contains a randomized test harness for AIRs defined using the
+//! [Air](air::Air) trait.
+//!
+//! An AIR which does not constrain some cell of the execution trace accepts proofs of incorrect
+//! computations; such under-constrained AIRs are the most dangerous class of bugs in AIR
+//! development because all honestly generated proofs still verify. The harness catches them by
+//! running randomized trials against a generator of valid execution traces. In each trial:
+//! * A valid execution trace and the corresponding public inputs are generated. The trace must
+//!   satisfy all assertions and transition constraints of the AIR, and a proof generated for the
+//!   trace must be accepted by the verifier.
+//! * The trace is mutated many times by perturbing a single randomly selected cell. Each mutated
+//!   trace must violate at least one assertion or transition constraint of the AIR; mutations
+//!   which do not are reported as [Mutation]s in an [AirTestReport].
+//!
+//! The simplest way to use the harness is via [AirTester::check()], which panics if any of the
+//! trials fails:
+//! ```ignore
+//! use winter_air_test::AirTester;
+//!
+//! #[test]
+//! fn fib_air_is_sound() {
+//!     AirTester::<FibAir>::new(options).check(|trial| {
+//!         let trace = build_trace(8 << trial);
+//!         let result = trace.get(1, trace.length() - 1);
+//!         (trace, result)
+//!     });
+//! }
+//! ```
+//!
+//! Mutated traces are checked by evaluating constraints of the AIR directly (see
+//! [check_constraints()]) rather than by generating and verifying proofs for them. This is what
+//! the verifier checks (up to the soundness error of the protocol), and is much faster; still,
+//! traces used in trials should be short. Currently, only execution traces which consist of a
+//! single segment are supported.
+
+use air::{Air, ProofOptions};
+use core::marker::PhantomData;
+use crypto::{hashers::Blake3_256, DefaultRandomCoin};
+use math::{ExtensibleField, FieldElement};
+use prover::{Prover, Trace, TraceTable};
+use rand_utils::prng_vector;
+use verifier::AcceptableOptions;
+
+mod constraints;
+pub use constraints::{check_constraints, ConstraintViolation};
+
+mod errors;
+pub use errors::AirTestError;
+
+mod test_prover;
+use test_prover::TestProver;
+
+#[cfg(test)]
+mod tests;
+
+// AIR TESTER
+// ================================================================================================
+
+/// Randomized soundness test harness for AIR `A`.
+///
+/// A tester runs the specified number of trials; in each trial, a valid execution trace is
+/// generated and mutated the specified number of times (see [crate docs](crate) for details).
+/// Cells to mutate and values added to them are generated pseudo-randomly from the seed of the
+/// tester, and thus, failures are reproducible.
+pub struct AirTester<A: Air> {
+    options: ProofOptions,
+    num_trials: usize,
+    num_mutations: usize,
+    seed: [u8; 32],
+    _air: PhantomData<A>,
+}
+
+impl<A> AirTester<A>
+where
+    A: Air + 'static,
+    A::BaseField: ExtensibleField<2> + ExtensibleField<3>,
+    A::PublicInputs: Clone,
+{
+    /// Default number of trials run by a tester.
+    pub const DEFAULT_NUM_TRIALS: usize = 4;
+
+    /// Default number of mutations applied to the trace of each trial.
+    pub const DEFAULT_NUM_MUTATIONS: usize = 64;
+
+    // CONSTRUCTORS
+    // --------------------------------------------------------------------------------------------
+    /// Returns a new tester which generates proofs for valid traces using the specified options.
+    pub fn new(options: ProofOptions) -> Self {
+        Self {
+            options,
+            num_trials: Self::DEFAULT_NUM_TRIALS,
+            num_mutations: Self::DEFAULT_NUM_MUTATIONS,
+            seed: [0; 32],
+            _air: PhantomData,
+        }
+    }
+
+    /// Sets the number of trials run by this tester.
+    ///
+    /// # Panics
+    /// Panics if `num_trials` is zero.
+    pub fn with_num_trials(mut self, num_trials: usize) -> Self {
+        assert!(num_trials > 0, "number of trials must be greater than zero");
+        self.num_trials = num_trials;
+        self
+    }
+
+    /// Sets the number of mutations applied to the trace of each trial.
+    pub fn with_num_mutations(mut self, num_mutations: usize) -> Self {
+        self.num_mutations = num_mutations;
+        self
+    }
+
+    /// Sets the seed from which mutations are generated.
+    pub fn with_seed(mut self, seed: [u8; 32]) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    // TEST RUNNERS
+    // --------------------------------------------------------------------------------------------
+
+    /// Runs the trials using the specified trace generator and returns a report listing all
+    /// mutations which did not violate constraints of the AIR.
+    ///
+    /// The generator is invoked once per trial with the index of the trial, and must return a
+    /// valid execution trace together with the public inputs of the computation.
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// * An execution trace consists of more than one segment.
+    /// * An execution trace does not satisfy constraints of the AIR.
+    /// * A proof could not be generated for an execution trace, or the proof was not accepted by
+    ///   the verifier.
+    pub fn run<G>(&self, mut generator: G) -> Result<AirTestReport, AirTestError>
+    where
+        G: FnMut(usize) -> (TraceTable<A::BaseField>, A::PublicInputs),
+    {
+        let mut report = AirTestReport {
+            num_trials: self.num_trials,
+            num_mutations: self.num_trials * self.num_mutations,
+            undetected_mutations: Vec::new(),
+        };
+
+        for trial in 0..self.num_trials {
+            let (trace, pub_inputs) = generator(trial);
+            if trace.layout().num_aux_segments() != 0 {
+                return Err(AirTestError::MultiSegmentTrace(trial));
+            }
+
+            // a valid trace must satisfy constraints of the AIR and must be provable
+            let air = A::new(trace.get_info(), pub_inputs.clone(), self.options.clone());
+            check_constraints(&air, &trace)
+                .map_err(|violation| AirTestError::InvalidTrace(trial, violation))?;
+            self.prove_and_verify(trial, trace.clone(), pub_inputs)?;
+
+            // every mutated trace must violate at least one constraint
+            for mutation in self.build_mutations(trial, &trace) {
+                let mut mutated_trace = trace.clone();
+                let value = trace.get(mutation.column, mutation.step) + mutation.delta;
+                mutated_trace.set(mutation.column, mutation.step, value);
+                if check_constraints(&air, &mutated_trace).is_ok() {
+                    report.undetected_mutations.push(mutation.into());
+                }
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Runs the trials using the specified trace generator, and panics if any of the trials
+    /// fails or if any of the mutations did not violate constraints of the AIR.
+    ///
+    /// This is a convenience wrapper around [AirTester::run()] intended to be called from tests.
+    pub fn check<G>(&self, generator: G)
+    where
+        G: FnMut(usize) -> (TraceTable<A::BaseField>, A::PublicInputs),
+    {
+        match self.run(generator) {
+            Ok(report) => {
+                if let Some(mutation) = report.undetected_mutations.first() {
+                    panic!(
+                        "AIR is under-constrained: {} of {} mutations did not violate any \
+                        constraint; first such mutation: {}",
+                        report.undetected_mutations.len(),
+                        report.num_mutations,
+                        mutation
+                    );
+                }
+            }
+            Err(err) => panic!("AIR test failed: {err}"),
+        }
+    }
+
+    // HELPER METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Generates a proof for the specified trace and checks that it is accepted by the verifier.
+    fn prove_and_verify(
+        &self,
+        trial: usize,
+        trace: TraceTable<A::BaseField>,
+        pub_inputs: A::PublicInputs,
+    ) -> Result<(), AirTestError> {
+        let prover = TestProver::<A>::new(self.options.clone(), pub_inputs.clone());
+        let proof = prover
+            .prove(trace)
+            .map_err(|err| AirTestError::ProofGenerationFailed(trial, err))?;
+
+        let acceptable_options = AcceptableOptions::OptionSet(vec![self.options.clone()]);
+        verifier::verify::<A, Blake3_256<A::BaseField>, DefaultRandomCoin<Blake3_256<A::BaseField>>>(
+            proof,
+            pub_inputs,
+            &acceptable_options,
+        )
+        .map_err(|err| AirTestError::VerificationFailed(trial, err))
+    }
+
+    /// Returns pseudo-random single-cell mutations for the trace of the specified trial.
+    fn build_mutations(
+        &self,
+        trial: usize,
+        trace: &TraceTable<A::BaseField>,
+    ) -> Vec<CellMutation<A::BaseField>> {
+        // derive independent seeds for cell positions and values from the seed of the tester
+        let mut seed = self.seed;
+        for (byte, trial_byte) in seed.iter_mut().zip((trial as u64).to_le_bytes()) {
+            *byte ^= trial_byte;
+        }
+        let positions = prng_vector::<u64>(seed, self.num_mutations);
+        seed[31] ^= 1;
+        let deltas = prng_vector::<A::BaseField>(seed, self.num_mutations);
+
+        let num_cells = (trace.main_trace_width() * trace.length()) as u64;
+        positions
+            .into_iter()
+            .zip(deltas)
+            .map(|(position, delta)| {
+                let cell = (position % num_cells) as usize;
+                CellMutation {
+                    trial,
+                    column: cell % trace.main_trace_width(),
+                    step: cell / trace.main_trace_width(),
+                    // a zero delta would not mutate the trace
+                    delta: if delta == A::BaseField::ZERO {
+                        A::BaseField::ONE
+                    } else {
+                        delta
+                    },
+                }
+            })
+            .collect()
+    }
+}
+
+// AIR TEST REPORT
+// ================================================================================================
+
+/// Results of running trials of an [AirTester].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AirTestReport {
+    /// Number of trials which were run.
+    pub num_trials: usize,
+    /// Total number of mutations applied across all trials.
+    pub num_mutations: usize,
+    /// Mutations which did not violate any constraint of the AIR.
+    pub undetected_mutations: Vec<Mutation>,
+}
+
+impl AirTestReport {
+    /// Returns true if every mutation violated at least one constraint of the AIR.
+    pub fn is_sound(&self) -> bool {
+        self.undetected_mutations.is_empty()
+    }
+}
+
+// MUTATION
+// ================================================================================================
+
+/// Describes a single-cell mutation of an execution trace.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Mutation {
+    /// Index of the trial in which the mutation was applied.
+    pub trial: usize,
+    /// Index of the mutated column of the execution trace.
+    pub column: usize,
+    /// Index of the mutated step (row) of the execution trace.
+    pub step: usize,
+}
+
+impl core::fmt::Display for Mutation {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "column {} at step {} in trial {}", self.column, self.step, self.trial)
+    }
+}
+
+/// A mutation together with the value added to the mutated cell.
+struct CellMutation<B: FieldElement> {
+    trial: usize,
+    column: usize,
+    step: usize,
+    delta: B,
+}
+
+impl<B: FieldElement> From<CellMutation<B>> for Mutation {
+    fn from(mutation: CellMutation<B>) -> Self {
+        Self {
+            trial: mutation.trial,
+            column: mutation.column,
+            step: mutation.step,
+        }
+    }
+}
@@ -0,0 +1,75 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use air::{Air, AuxTraceRandElements, ConstraintCompositionCoefficients, ProofOptions, TraceInfo};
+use core::marker::PhantomData;
+use crypto::{hashers::Blake3_256, DefaultRandomCoin};
+use math::{ExtensibleField, FieldElement};
+use prover::{
+    matrix::ColMatrix, DefaultConstraintEvaluator, DefaultTraceLde, Prover, StarkDomain,
+    TracePolyTable, TraceTable,
+};
+
+// TEST PROVER
+// ================================================================================================
+
+/// Prover which generates proofs for any AIR `A` and fixed public inputs.
+pub struct TestProver<A: Air> {
+    options: ProofOptions,
+    pub_inputs: A::PublicInputs,
+    _air: PhantomData<A>,
+}
+
+impl<A: Air> TestProver<A> {
+    pub fn new(options: ProofOptions, pub_inputs: A::PublicInputs) -> Self {
+        Self {
+            options,
+            pub_inputs,
+            _air: PhantomData,
+        }
+    }
+}
+
+impl<A> Prover for TestProver<A>
+where
+    A: Air + 'static,
+    A::BaseField: ExtensibleField<2> + ExtensibleField<3>,
+    A::PublicInputs: Clone,
+{
+    type BaseField = A::BaseField;
+    type Air = A;
+    type Trace = TraceTable<A::BaseField>;
+    type HashFn = Blake3_256<A::BaseField>;
+    type RandomCoin = DefaultRandomCoin<Self::HashFn>;
+    type TraceLde<E: FieldElement<BaseField = Self::BaseField>> = DefaultTraceLde<E, Self::HashFn>;
+    type ConstraintEvaluator<'a, E: FieldElement<BaseField = Self::BaseField>> =
+        DefaultConstraintEvaluator<'a, Self::Air, E>;
+
+    fn get_pub_inputs(&self, _trace: &Self::Trace) -> A::PublicInputs {
+        self.pub_inputs.clone()
+    }
+
+    fn options(&self) -> &ProofOptions {
+        &self.options
+    }
+
+    fn new_trace_lde<E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        trace_info: &TraceInfo,
+        main_trace: &ColMatrix<Self::BaseField>,
+        domain: &StarkDomain<Self::BaseField>,
+    ) -> (Self::TraceLde<E>, TracePolyTable<E>) {
+        DefaultTraceLde::new(trace_info, main_trace, domain)
+    }
+
+    fn new_evaluator<'a, E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        air: &'a Self::Air,
+        aux_rand_elements: AuxTraceRandElements<E>,
+        composition_coefficients: ConstraintCompositionCoefficients<E>,
+    ) -> Self::ConstraintEvaluator<'a, E> {
+        DefaultConstraintEvaluator::new(air, aux_rand_elements, composition_coefficients)
+    }
+}
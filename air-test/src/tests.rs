@@ -0,0 +1,157 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{check_constraints, AirTestError, AirTester, ConstraintViolation};
+use air::{
+    Air, AirContext, Assertion, EvaluationFrame, FieldExtension, ProofOptions, TraceInfo,
+    TransitionConstraintDegree,
+};
+use math::{fields::f128::BaseElement, FieldElement};
+use prover::{Trace, TraceTable};
+
+// AIR TESTER
+// ================================================================================================
+
+#[test]
+fn sound_air_detects_all_mutations() {
+    let report = AirTester::<CopyFibAir<true>>::new(build_options()).run(build_trial).unwrap();
+    assert_eq!(AirTester::<CopyFibAir<true>>::DEFAULT_NUM_TRIALS, report.num_trials);
+    assert_eq!(
+        report.num_trials * AirTester::<CopyFibAir<true>>::DEFAULT_NUM_MUTATIONS,
+        report.num_mutations
+    );
+    assert!(report.is_sound());
+}
+
+#[test]
+fn under_constrained_air_reports_mutations() {
+    let tester = AirTester::<CopyFibAir<false>>::new(build_options()).with_num_trials(2);
+    let report = tester.run(build_trial).unwrap();
+    assert!(!report.is_sound());
+
+    // only the copy column is left unconstrained
+    assert!(report.undetected_mutations.iter().all(|m| m.column == 2));
+
+    // mutations are reproducible for the same seed, and differ for different seeds
+    assert_eq!(report, tester.run(build_trial).unwrap());
+    let tester = AirTester::<CopyFibAir<false>>::new(build_options())
+        .with_num_trials(2)
+        .with_seed([1; 32]);
+    assert_ne!(report, tester.run(build_trial).unwrap());
+}
+
+#[test]
+#[should_panic(expected = "AIR is under-constrained")]
+fn under_constrained_air_fails_check() {
+    AirTester::<CopyFibAir<false>>::new(build_options()).check(build_trial);
+}
+
+#[test]
+fn invalid_trace_is_reported() {
+    let tester = AirTester::<CopyFibAir<true>>::new(build_options());
+    let result = tester.run(|trial| {
+        let (trace, result) = build_trial(trial);
+        (trace, result + BaseElement::ONE)
+    });
+    assert_eq!(Err(AirTestError::InvalidTrace(0, ConstraintViolation::Assertion(1, 7))), result);
+}
+
+// CONSTRAINT CHECKER
+// ================================================================================================
+
+#[test]
+fn check_constraints_reports_first_violation() {
+    let (mut trace, result) = build_trial(0);
+    let air = CopyFibAir::<true>::new(trace.get_info(), result, build_options());
+    assert_eq!(Ok(()), check_constraints(&air, &trace));
+
+    trace.set(2, 3, BaseElement::ZERO);
+    assert_eq!(Err(ConstraintViolation::Transition(2, 2)), check_constraints(&air, &trace));
+
+    trace.set(0, 0, BaseElement::ZERO);
+    assert_eq!(Err(ConstraintViolation::Assertion(0, 0)), check_constraints(&air, &trace));
+}
+
+// TEST AIR
+// ================================================================================================
+
+/// Fibonacci sequence (2 terms per step) with a third column which copies the first one. When
+/// `CONSTRAIN_COPY` is false, the copy column is not constrained.
+struct CopyFibAir<const CONSTRAIN_COPY: bool> {
+    context: AirContext<BaseElement>,
+    result: BaseElement,
+}
+
+impl<const CONSTRAIN_COPY: bool> Air for CopyFibAir<CONSTRAIN_COPY> {
+    type BaseField = BaseElement;
+    type PublicInputs = BaseElement;
+
+    fn new(trace_info: TraceInfo, pub_inputs: BaseElement, options: ProofOptions) -> Self {
+        let num_constraints = if CONSTRAIN_COPY { 3 } else { 2 };
+        let degrees = vec![TransitionConstraintDegree::new(1); num_constraints];
+        let num_assertions = if CONSTRAIN_COPY { 4 } else { 3 };
+        Self {
+            context: AirContext::new(trace_info, degrees, num_assertions, options),
+            result: pub_inputs,
+        }
+    }
+
+    fn context(&self) -> &AirContext<Self::BaseField> {
+        &self.context
+    }
+
+    fn evaluate_transition<E: FieldElement + From<Self::BaseField>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        _periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        let current = frame.current();
+        let next = frame.next();
+        result[0] = next[0] - (current[0] + current[1]);
+        result[1] = next[1] - (current[1] + next[0]);
+        if CONSTRAIN_COPY {
+            result[2] = next[2] - next[0];
+        }
+    }
+
+    fn get_assertions(&self) -> Vec<Assertion<Self::BaseField>> {
+        let last_step = self.trace_length() - 1;
+        let mut assertions = vec![
+            Assertion::single(0, 0, BaseElement::ONE),
+            Assertion::single(1, 0, BaseElement::ONE),
+            Assertion::single(1, last_step, self.result),
+        ];
+        if CONSTRAIN_COPY {
+            assertions.push(Assertion::single(2, 0, BaseElement::ONE));
+        }
+        assertions
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+fn build_options() -> ProofOptions {
+    ProofOptions::new(32, 8, 0, FieldExtension::None, 4, 31)
+}
+
+fn build_trial(trial: usize) -> (TraceTable<BaseElement>, BaseElement) {
+    let mut trace = TraceTable::new(3, 8 << trial);
+    trace.fill(
+        |state| {
+            state[0] = BaseElement::ONE;
+            state[1] = BaseElement::ONE;
+            state[2] = BaseElement::ONE;
+        },
+        |_, state| {
+            state[0] += state[1];
+            state[1] += state[0];
+            state[2] = state[0];
+        },
+    );
+    let result = trace.get(1, trace.length() - 1);
+    (trace, result)
+}
@@ -0,0 +1,227 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{Air, AirContext, Assertion, EvaluationFrame, TraceInfo};
+use crate::{errors::PublicInputsError, ProofOptions};
+use math::{FieldElement, ToElements};
+use utils::collections::Vec;
+
+// AGGREGATE PUBLIC INPUTS
+// ================================================================================================
+
+/// Public inputs for an [AggregateAir].
+///
+/// Contains public inputs of every aggregated instance in the same order in which the instances
+/// are laid out in the execution trace. When converted into field elements, public inputs of all
+/// instances are concatenated together.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AggregateInputs<P>(Vec<P>);
+
+impl<P> AggregateInputs<P> {
+    /// Returns a new set of aggregate public inputs instantiated from the provided list.
+    ///
+    /// # Panics
+    /// Panics if `inputs` is empty.
+    pub fn new(inputs: Vec<P>) -> Self {
+        assert!(!inputs.is_empty(), "at least one instance must be aggregated");
+        Self(inputs)
+    }
+
+    /// Returns the number of instances described by these public inputs.
+    pub fn num_instances(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns public inputs of the individual instances.
+    pub fn inputs(&self) -> &[P] {
+        &self.0
+    }
+}
+
+impl<E: FieldElement, P: ToElements<E>> ToElements<E> for AggregateInputs<P> {
+    fn to_elements(&self) -> Vec<E> {
+        self.0.iter().flat_map(|inputs| inputs.to_elements()).collect()
+    }
+}
+
+// AGGREGATE AIR
+// ================================================================================================
+
+/// AIR for proving multiple instances of the same computation in a single proof.
+///
+/// Execution traces of individual instances are stacked side by side: the trace for instance `i`
+/// occupies columns `[i * w, (i + 1) * w)` of the aggregate execution trace, where `w` is the
+/// width of a single instance trace. All instances must have the same trace length. The number
+/// of instances is inferred from the number of entries in [AggregateInputs].
+///
+/// The framework takes care of bridging the individual instances into a single AIR: transition
+/// constraints of each instance are evaluated against the instance's slice of the evaluation
+/// frame, assertions are shifted to the instance's columns, and periodic columns of all instances
+/// are concatenated. All instances are committed to in a single set of Merkle trees and share the
+/// same constraint composition polynomial and FRI proof. The width of the aggregate trace, and
+/// thus, the size of trace openings at each query, still grows linearly with the number of
+/// instances; only Merkle authentication paths, constraint openings, and the FRI proof are shared.
+///
+/// Only computations with a single trace segment can currently be aggregated. Trace metadata is
+/// not split between instances: each instance is instantiated with the metadata of the aggregate
+/// trace (which `TraceTable::stack()` in the prover requires to be the same for all stacked
+/// traces), and thus, data which differs between instances must be passed via public inputs.
+pub struct AggregateAir<A: Air> {
+    context: AirContext<A::BaseField>,
+    instances: Vec<A>,
+    instance_width: usize,
+    num_periodic_columns: Vec<usize>,
+}
+
+impl<A: Air> AggregateAir<A> {
+    /// Returns the number of instances aggregated by this AIR.
+    pub fn num_instances(&self) -> usize {
+        self.instances.len()
+    }
+
+    /// Returns the number of trace columns consumed by a single instance.
+    pub fn instance_width(&self) -> usize {
+        self.instance_width
+    }
+
+    /// Returns AIR of the individual instances aggregated by this AIR.
+    pub fn instances(&self) -> &[A] {
+        &self.instances
+    }
+}
+
+impl<A: Air> Air for AggregateAir<A> {
+    type BaseField = A::BaseField;
+    type PublicInputs = AggregateInputs<A::PublicInputs>;
+
+    /// Returns a new AIR aggregating an instance of `A` for each entry in `pub_inputs`.
+    ///
+    /// # Panics
+    /// Panics if:
+    /// * The trace described by `trace_info` consists of more than one segment.
+    /// * The width of the trace is not divisible by the number of instances.
+    /// * Aggregated instances define different numbers of transition exemptions.
+    fn new(trace_info: TraceInfo, pub_inputs: Self::PublicInputs, options: ProofOptions) -> Self {
+        assert!(
+            !trace_info.is_multi_segment(),
+            "aggregation of multi-segment execution traces is not supported"
+        );
+        let num_instances = pub_inputs.num_instances();
+        assert!(
+            trace_info.width() % num_instances == 0,
+            "trace width {} is not divisible by the number of instances {}",
+            trace_info.width(),
+            num_instances
+        );
+        let instance_width = trace_info.width() / num_instances;
+
+        let instances = pub_inputs
+            .0
+            .into_iter()
+            .map(|inputs| {
+                let instance_info = TraceInfo::with_meta(
+                    instance_width,
+                    trace_info.length(),
+                    trace_info.meta().to_vec(),
+                );
+                A::new(instance_info, inputs, options.clone())
+            })
+            .collect::<Vec<_>>();
+
+        // all instances describe the same computation, and thus, must be constrained in the
+        // same way; we only need to check the things which could differ between instances
+        let num_exemptions = instances[0].context().num_transition_exemptions();
+        let mut degrees = Vec::new();
+        let mut num_assertions = 0;
+        let mut num_periodic_columns = Vec::with_capacity(instances.len());
+        for instance in instances.iter() {
+            assert_eq!(
+                num_exemptions,
+                instance.context().num_transition_exemptions(),
+                "all aggregated instances must have the same number of transition exemptions"
+            );
+            degrees.extend_from_slice(&instance.context().main_transition_constraint_degrees);
+            num_assertions += instance.context().num_main_assertions;
+            num_periodic_columns.push(instance.get_periodic_column_values().len());
+        }
+
+        let context = AirContext::new(trace_info, degrees, num_assertions, options)
+            .set_num_transition_exemptions(num_exemptions);
+
+        AggregateAir {
+            context,
+            instances,
+            instance_width,
+            num_periodic_columns,
+        }
+    }
+
+    fn context(&self) -> &AirContext<Self::BaseField> {
+        &self.context
+    }
+
+    /// Derives public inputs of each aggregated instance independently.
+    fn derive_public_inputs(pub_inputs: Self::PublicInputs) -> Self::PublicInputs {
+        AggregateInputs(pub_inputs.0.into_iter().map(A::derive_public_inputs).collect())
+    }
+
+    /// Validates public inputs of each aggregated instance independently.
+    fn validate_public_inputs(pub_inputs: &Self::PublicInputs) -> Result<(), PublicInputsError> {
+        pub_inputs.0.iter().try_for_each(A::validate_public_inputs)
+    }
+
+    fn evaluate_transition<E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        debug_assert_eq!(self.num_periodic_columns.iter().sum::<usize>(), periodic_values.len());
+        let mut instance_frame =
+            EvaluationFrame::with_offsets(self.instance_width, frame.offsets());
+        let mut periodic_values = periodic_values;
+        let mut result = result;
+
+        for (i, instance) in self.instances.iter().enumerate() {
+            let columns = i * self.instance_width..(i + 1) * self.instance_width;
+            for row in 0..frame.num_rows() {
+                instance_frame.row_mut(row).copy_from_slice(&frame.row(row)[columns.clone()]);
+            }
+
+            let (instance_periodic_values, rest) =
+                periodic_values.split_at(self.num_periodic_columns[i]);
+            periodic_values = rest;
+            let (instance_result, rest) =
+                result.split_at_mut(instance.context().num_main_transition_constraints());
+            result = rest;
+
+            instance.evaluate_transition(
+                &instance_frame,
+                instance_periodic_values,
+                instance_result,
+            );
+        }
+    }
+
+    fn get_assertions(&self) -> Vec<Assertion<Self::BaseField>> {
+        let mut result = Vec::with_capacity(self.context.num_main_assertions);
+        for (i, instance) in self.instances.iter().enumerate() {
+            let offset = i * self.instance_width;
+            result.extend(instance.get_assertions().into_iter().map(|assertion| Assertion {
+                column: assertion.column + offset,
+                terms: assertion.terms.iter().map(|&(column, c)| (column + offset, c)).collect(),
+                ..assertion
+            }));
+        }
+        result
+    }
+
+    fn get_periodic_column_values(&self) -> Vec<Vec<Self::BaseField>> {
+        self.instances
+            .iter()
+            .flat_map(|instance| instance.get_periodic_column_values())
+            .collect()
+    }
+}
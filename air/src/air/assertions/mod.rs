@@ -31,15 +31,21 @@ const NO_STRIDE: usize = 0;
 /// 1. **Single** assertion - which requires that a value in a single cell of an execution trace
 ///    is equal to the specified value.
 /// 2. **Periodic** assertion - which requires that values in multiple cells of a single column
-///   are equal to the specified value. The cells must be evenly spaced at intervals with lengths
-///   equal to powers of two. For example, we can specify that values in a column must be equal
-///   to 0 at steps 0, 8, 16, 24, 32 etc. Steps can also start at some offset - e.g., 1, 9, 17,
-///   25, 33 is also a valid sequence of steps.
+///    are equal to the specified value. The cells must be evenly spaced at intervals with lengths
+///    equal to powers of two. For example, we can specify that values in a column must be equal
+///    to 0 at steps 0, 8, 16, 24, 32 etc. Steps can also start at some offset - e.g., 1, 9, 17,
+///    25, 33 is also a valid sequence of steps.
 /// 3. **Sequence** assertion - which requires that multiple cells in a single column are equal
-///   to the values from the provided list. The cells must be evenly spaced at intervals with
-///   lengths equal to powers of two. For example, we can specify that values in a column must
-///   be equal to a sequence 1, 2, 3, 4 at steps 0, 8, 16, 24. That is, value at step 0 should be
-///   equal to 1, value at step 8 should be equal to 2 etc.
+///    to the values from the provided list. The cells must be evenly spaced at intervals with
+///    lengths equal to powers of two. For example, we can specify that values in a column must
+///    be equal to a sequence 1, 2, 3, 4 at steps 0, 8, 16, 24. That is, value at step 0 should be
+///    equal to 1, value at step 8 should be equal to 2 etc.
+///
+/// In addition to assertions against a single column, a single-value assertion can also be placed
+/// against a linear combination of columns with public coefficients (see
+/// [Assertion::linear_combination()]). This is useful when a value is encoded in the execution
+/// trace across multiple columns (e.g., as a set of limbs), as such a value can be asserted
+/// without dedicating an extra column to recombining it.
 ///
 /// Note that single and periodic assertions are succinct. That is, a verifier can evaluate them
 /// very efficiently. However, sequence assertions have liner complexity in the number of
@@ -51,6 +57,7 @@ pub struct Assertion<E: FieldElement> {
     pub(super) first_step: usize,
     pub(super) stride: usize,
     pub(super) values: Vec<E>,
+    pub(super) terms: Vec<(usize, E)>,
 }
 
 impl<E: FieldElement> Assertion<E> {
@@ -66,6 +73,7 @@ impl<E: FieldElement> Assertion<E> {
             first_step: step,
             stride: NO_STRIDE,
             values: vec![value],
+            terms: Vec::new(),
         }
     }
 
@@ -86,6 +94,7 @@ impl<E: FieldElement> Assertion<E> {
             first_step,
             stride,
             values: vec![value],
+            terms: Vec::new(),
         }
     }
 
@@ -117,6 +126,38 @@ impl<E: FieldElement> Assertion<E> {
             first_step,
             stride: if values.len() == 1 { NO_STRIDE } else { stride },
             values,
+            terms: Vec::new(),
+        }
+    }
+
+    /// Returns an assertion against a linear combination of cells in a single row of an execution
+    /// trace.
+    ///
+    /// The returned assertion requires that $\sum_i c_i \cdot trace[i][step]$ is equal to the
+    /// provided `value`, where `terms` is a list of tuples $(i, c_i)$ specifying column indexes
+    /// and their coefficients.
+    ///
+    /// # Panics
+    /// Panics if:
+    /// * `terms` is empty.
+    /// * The same column appears in `terms` more than once.
+    pub fn linear_combination(terms: Vec<(usize, E)>, step: usize, value: E) -> Self {
+        assert!(
+            !terms.is_empty(),
+            "invalid linear combination assertion: number of terms must be greater than zero"
+        );
+        for (i, &(column, _)) in terms.iter().enumerate() {
+            assert!(
+                terms[..i].iter().all(|&(c, _)| c != column),
+                "invalid linear combination assertion: column {column} appears more than once"
+            );
+        }
+        Assertion {
+            column: terms[0].0,
+            first_step: step,
+            stride: NO_STRIDE,
+            values: vec![value],
+            terms,
         }
     }
 
@@ -124,10 +165,21 @@ impl<E: FieldElement> Assertion<E> {
     // --------------------------------------------------------------------------------------------
 
     /// Returns index of the column against which this assertion is placed.
+    ///
+    /// For assertions against a linear combination of columns, this is the column of the first
+    /// term of the combination.
     pub fn column(&self) -> usize {
         self.column
     }
 
+    /// Returns column indexes and coefficients of the linear combination against which this
+    /// assertion is placed.
+    ///
+    /// For assertions against a single column, this will be an empty slice.
+    pub fn terms(&self) -> &[(usize, E)] {
+        &self.terms
+    }
+
     /// Returns the first step of the execution trace against which this assertion is placed.
     ///
     /// For single value assertions this is equivalent to the assertion step.
@@ -164,14 +216,20 @@ impl<E: FieldElement> Assertion<E> {
         self.values.len() > 1
     }
 
+    /// Returns true if this assertion is placed against a linear combination of columns.
+    pub fn is_linear_combination(&self) -> bool {
+        !self.terms.is_empty()
+    }
+
     // PUBLIC METHODS
     // --------------------------------------------------------------------------------------------
 
     /// Checks if this assertion overlaps with the provided assertion.
     ///
-    /// Overlap is defined as asserting a value for the same step in the same column.
+    /// Overlap is defined as asserting a value for the same step in the same column, or for the
+    /// same step of the same linear combination of columns.
     pub fn overlaps_with(&self, other: &Assertion<E>) -> bool {
-        if self.column != other.column {
+        if self.column != other.column || self.terms != other.terms {
             return false;
         }
         if self.first_step == other.first_step {
@@ -207,8 +265,9 @@ impl<E: FieldElement> Assertion<E> {
 
     /// Panics if the assertion cannot be placed against an execution trace of the specified width.
     pub fn validate_trace_width(&self, trace_width: usize) -> Result<(), AssertionError> {
-        if self.column >= trace_width {
-            return Err(AssertionError::TraceWidthTooShort(self.column, trace_width));
+        let max_column = self.terms.iter().map(|&(column, _)| column).fold(self.column, usize::max);
+        if max_column >= trace_width {
+            return Err(AssertionError::TraceWidthTooShort(max_column, trace_width));
         }
         Ok(())
     }
@@ -270,6 +329,25 @@ impl<E: FieldElement> Assertion<E> {
         }
     }
 
+    /// Returns the value of the expression against which this assertion is placed at some step of
+    /// an execution trace; `get_value` must return the value of the specified column at that step.
+    ///
+    /// For assertions against a single column, this is the value of that column; otherwise, this
+    /// is the linear combination of the values of the columns specified by the terms of this
+    /// assertion.
+    pub fn combine_columns<F>(&self, mut get_value: F) -> E
+    where
+        F: FnMut(usize) -> E,
+    {
+        if self.terms.is_empty() {
+            get_value(self.column)
+        } else {
+            self.terms
+                .iter()
+                .fold(E::ZERO, |acc, &(column, coefficient)| acc + coefficient * get_value(column))
+        }
+    }
+
     /// Returns the number of steps against which this assertion will be applied given an
     /// execution trace of the specified length.
     ///
@@ -297,12 +375,16 @@ impl<E: FieldElement> Assertion<E> {
 // =================================================================================================
 
 /// We define ordering of assertions to be first by stride, then by first_step, and finally by
-/// column in ascending order.
+/// column in ascending order. Assertions against linear combinations of columns are ordered after
+/// assertions against single columns with the same first column, and among themselves by their
+/// terms.
 impl<E: FieldElement> Ord for Assertion<E> {
     fn cmp(&self, other: &Self) -> Ordering {
         if self.stride == other.stride {
             if self.first_step == other.first_step {
-                self.column.partial_cmp(&other.column).unwrap()
+                self.column
+                    .cmp(&other.column)
+                    .then_with(|| cmp_terms(&self.terms, &other.terms))
             } else {
                 self.first_step.partial_cmp(&other.first_step).unwrap()
             }
@@ -320,7 +402,16 @@ impl<E: FieldElement> PartialOrd for Assertion<E> {
 
 impl<E: FieldElement> Display for Assertion<E> {
     fn fmt(&self, f: &mut Formatter) -> core::fmt::Result {
-        write!(f, "(column={}, ", self.column)?;
+        if self.terms.is_empty() {
+            write!(f, "(column={}, ", self.column)?;
+        } else {
+            write!(f, "(columns=[")?;
+            for (i, (column, coefficient)) in self.terms.iter().enumerate() {
+                let separator = if i == 0 { "" } else { " + " };
+                write!(f, "{separator}{coefficient} * {column}")?;
+            }
+            write!(f, "], ")?;
+        }
         match self.stride {
             0 => write!(f, "step={}, ", self.first_step)?,
             _ => {
@@ -339,6 +430,20 @@ impl<E: FieldElement> Display for Assertion<E> {
 // HELPER FUNCTIONS
 // =================================================================================================
 
+/// Compares terms of linear combination assertions first by the number of terms, and then term by
+/// term by column index and by canonical encoding of the coefficient.
+fn cmp_terms<E: FieldElement>(a: &[(usize, E)], b: &[(usize, E)]) -> Ordering {
+    a.len().cmp(&b.len()).then_with(|| {
+        a.iter()
+            .zip(b)
+            .map(|(&(c1, v1), &(c2, v2))| {
+                c1.cmp(&c2).then_with(|| v1.to_bytes().cmp(&v2.to_bytes()))
+            })
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or(Ordering::Equal)
+    })
+}
+
 fn validate_stride(stride: usize, first_step: usize, column: usize) {
     assert!(
         stride.is_power_of_two(),
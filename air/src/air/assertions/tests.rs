@@ -7,6 +7,7 @@ use super::{Assertion, AssertionError};
 use math::{fields::f128::BaseElement, FieldElement};
 use rand_utils::{rand_value, rand_vector};
 use utils::collections::Vec;
+use utils::string::ToString;
 
 // SINGLE ASSERTIONS
 // ================================================================================================
@@ -174,6 +175,65 @@ fn sequence_assertion_num_values_not_power_of_two() {
         Assertion::sequence(3, 2, 4, vec![BaseElement::ONE, BaseElement::ZERO, BaseElement::ONE]);
 }
 
+// LINEAR COMBINATION ASSERTIONS
+// ================================================================================================
+
+#[test]
+fn linear_combination_assertion() {
+    let value = rand_value::<BaseElement>();
+    let terms = vec![(2, BaseElement::ONE), (0, BaseElement::new(1 << 32))];
+    let a = Assertion::linear_combination(terms.clone(), 8, value);
+    assert_eq!(2, a.column());
+    assert_eq!(&terms[..], a.terms());
+    assert_eq!(8, a.first_step());
+    assert_eq!(0, a.stride());
+    assert!(a.is_single());
+    assert!(a.is_linear_combination());
+    assert!(!Assertion::single(2, 8, value).is_linear_combination());
+    assert_eq!(1, a.get_num_steps(16));
+
+    a.apply(16, |step, val| {
+        assert_eq!(8, step);
+        assert_eq!(value, val);
+    });
+
+    // the asserted expression is the linear combination of column values
+    let row = rand_vector::<BaseElement>(3);
+    assert_eq!(row[2] + row[0] * BaseElement::new(1 << 32), a.combine_columns(|i| row[i]));
+    assert_eq!(row[2], Assertion::single(2, 8, value).combine_columns(|i| row[i]));
+
+    // all columns of the combination must fit into the trace
+    assert_eq!(Ok(()), a.validate_trace_width(3));
+    assert_eq!(Err(AssertionError::TraceWidthTooShort(2, 2)), a.validate_trace_width(2));
+    let b = Assertion::linear_combination(vec![(0, BaseElement::ONE), (4, value)], 8, value);
+    assert_eq!(Err(AssertionError::TraceWidthTooShort(4, 3)), b.validate_trace_width(3));
+
+    assert_eq!(Ok(()), a.validate_trace_length(16));
+    assert_eq!(Err(AssertionError::TraceLengthTooShort(16, 8)), a.validate_trace_length(8));
+}
+
+#[test]
+fn linear_combination_assertion_display() {
+    let terms = vec![(2, BaseElement::new(3)), (0, BaseElement::ONE)];
+    let a = Assertion::linear_combination(terms, 8, BaseElement::new(5));
+    assert_eq!("(columns=[3 * 2 + 1 * 0], step=8, value=5)", a.to_string());
+}
+
+#[test]
+#[should_panic(
+    expected = "invalid linear combination assertion: number of terms must be greater than zero"
+)]
+fn linear_combination_assertion_no_terms() {
+    let _ = Assertion::linear_combination(vec![], 2, BaseElement::ONE);
+}
+
+#[test]
+#[should_panic(expected = "invalid linear combination assertion: column 1 appears more than once")]
+fn linear_combination_assertion_duplicate_columns() {
+    let terms = vec![(1, BaseElement::ONE), (0, BaseElement::ONE), (1, BaseElement::ONE)];
+    let _ = Assertion::linear_combination(terms, 2, BaseElement::ONE);
+}
+
 // OVERLAPPING ASSERTIONS
 // ================================================================================================
 
@@ -337,4 +397,23 @@ fn assertion_overlap() {
     let b = Assertion::periodic(0, 0, 16, BaseElement::ONE);
     assert!(!a.overlaps_with(&b));
     assert!(!b.overlaps_with(&a));
+
+    // ----- linear combination overlap -----------------------------------------------------------
+
+    let terms = vec![(3, BaseElement::ONE), (1, BaseElement::new(2))];
+    let a = Assertion::linear_combination(terms.clone(), 2, BaseElement::ONE);
+    let b = Assertion::linear_combination(terms, 2, BaseElement::ZERO);
+    assert!(a.overlaps_with(&b));
+
+    // assertions against a column and against a combination starting with that column, or
+    // against different combinations: no overlap
+    let b = Assertion::single(3, 2, BaseElement::ONE);
+    assert!(!a.overlaps_with(&b));
+    assert!(!b.overlaps_with(&a));
+    let b = Assertion::linear_combination(
+        vec![(3, BaseElement::ONE), (1, BaseElement::new(3))],
+        2,
+        BaseElement::ONE,
+    );
+    assert!(!a.overlaps_with(&b));
 }
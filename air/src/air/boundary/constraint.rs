@@ -12,7 +12,9 @@ use math::{fft, polynom};
 ///
 /// A boundary constraint is described by a rational function $\frac{f(x) - b(x)}{z(x)}$, where:
 ///
-/// * $f(x)$ is a trace polynomial for the column against which the constraint is placed.
+/// * $f(x)$ is a trace polynomial for the column against which the constraint is placed; for
+///   constraints placed against a linear combination of columns, $f(x)$ is the same linear
+///   combination of trace polynomials for these columns.
 /// * $b(b)$ is the value polynomial for this constraint.
 /// * $z(x)$ is the constraint divisor polynomial.
 ///
@@ -34,6 +36,7 @@ where
     E: FieldElement<BaseField = F::BaseField> + ExtensionOf<F>,
 {
     column: usize,
+    terms: Vec<(usize, F)>,
     poly: Vec<F>,
     poly_offset: (usize, F::BaseField),
     cc: E,
@@ -79,6 +82,7 @@ where
 
         BoundaryConstraint {
             column: assertion.column,
+            terms: assertion.terms,
             poly,
             poly_offset,
             cc: composition_coefficient,
@@ -89,10 +93,21 @@ where
     // --------------------------------------------------------------------------------------------
 
     /// Returns index of the column against which this constraint applies.
+    ///
+    /// For constraints against a linear combination of columns, this is the column of the first
+    /// term of the combination.
     pub fn column(&self) -> usize {
         self.column
     }
 
+    /// Returns column indexes and coefficients of the linear combination against which this
+    /// constraint applies.
+    ///
+    /// For constraints against a single column, this will be an empty slice.
+    pub fn terms(&self) -> &[(usize, F)] {
+        &self.terms
+    }
+
     /// Returns a value polynomial for this constraint.
     pub fn poly(&self) -> &[F] {
         &self.poly
@@ -114,6 +129,22 @@ where
 
     // CONSTRAINT EVALUATOR
     // --------------------------------------------------------------------------------------------
+    /// Returns the value of the trace expression against which this constraint is placed given
+    /// a row of trace values `state`.
+    ///
+    /// For constraints against a single column, this is the value of that column; otherwise,
+    /// this is the linear combination of the values of the columns specified by the terms of this
+    /// constraint.
+    pub fn get_trace_value(&self, state: &[E]) -> E {
+        if self.terms.is_empty() {
+            state[self.column]
+        } else {
+            self.terms.iter().fold(E::ZERO, |acc, &(column, coefficient)| {
+                acc + state[column].mul_base(coefficient)
+            })
+        }
+    }
+
     /// Evaluates this constraint at the specified point `x`.
     ///
     /// The constraint is evaluated by computing $f(x) - b(x)$, where:
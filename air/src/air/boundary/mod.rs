@@ -148,7 +148,7 @@ impl<E: FieldElement> BoundaryConstraints<E> {
 // ================================================================================================
 
 /// Translates the provided assertions into boundary constraints, groups the constraints by their
-/// divisor, and sorts the resulting groups by the degree adjustment factor.
+/// divisor, and sorts the resulting groups by stride and first step of their assertions.
 fn group_constraints<F, E>(
     assertions: Vec<Assertion<F>>,
     context: &AirContext<F::BaseField>,
@@ -66,6 +66,48 @@ fn boundary_constraint_from_single_assertion() {
     assert!(twiddle_map.is_empty());
 }
 
+#[test]
+fn boundary_constraint_from_linear_combination_assertion() {
+    let mut test_prng = build_prng();
+    let (inv_g, mut twiddle_map, mut prng) = build_constraint_params(16);
+
+    // constraint should be built correctly for columns 2 and 0, step 8
+    let value = rand_value::<BaseElement>();
+    let terms = vec![(2, BaseElement::ONE), (0, BaseElement::new(1 << 32))];
+    let assertion = Assertion::linear_combination(terms.clone(), 8, value);
+    let constraint = BoundaryConstraint::<BaseElement, BaseElement>::new(
+        assertion,
+        inv_g,
+        &mut twiddle_map,
+        prng.draw().unwrap(),
+    );
+    assert_eq!(2, constraint.column());
+    assert_eq!(&terms[..], constraint.terms());
+    assert_eq!(vec![value], constraint.poly());
+    assert_eq!((0, BaseElement::ONE), constraint.poly_offset());
+    assert_eq!(&test_prng.draw::<BaseElement>().unwrap(), constraint.cc());
+
+    // the trace value of the constraint is the linear combination of column values
+    let state = rand_vector::<BaseElement>(3);
+    let trace_value = state[2] + state[0] * BaseElement::new(1 << 32);
+    assert_eq!(trace_value, constraint.get_trace_value(&state));
+    assert_eq!(
+        trace_value - value,
+        constraint.evaluate_at(rand_value::<BaseElement>(), trace_value)
+    );
+
+    // for constraints against a single column, the trace value is the value of the column
+    let assertion = Assertion::single(1, 8, value);
+    let constraint = BoundaryConstraint::<BaseElement, BaseElement>::new(
+        assertion,
+        inv_g,
+        &mut twiddle_map,
+        prng.draw().unwrap(),
+    );
+    assert!(constraint.terms().is_empty());
+    assert_eq!(state[1], constraint.get_trace_value(&state));
+}
+
 #[test]
 fn boundary_constraint_from_periodic_assertion() {
     let mut test_prng = build_prng();
@@ -218,6 +260,32 @@ fn prepare_assertions() {
     assert_eq!(expected, result);
 }
 
+#[test]
+fn prepare_assertions_with_linear_combinations() {
+    let terms = vec![(0, BaseElement::ONE), (1, BaseElement::new(1 << 32))];
+    let mut assertions = vec![
+        Assertion::linear_combination(terms.clone(), 9, BaseElement::new(6)),
+        Assertion::single(1, 9, BaseElement::new(4)),
+        Assertion::linear_combination(terms[..1].to_vec(), 9, BaseElement::new(5)),
+        Assertion::single(0, 9, BaseElement::new(5)),
+    ];
+
+    // assertions against a column and against combinations which start with that column do not
+    // overlap; combinations are sorted after single-column assertions and by their terms
+    let expected = vec![
+        Assertion::single(0, 9, BaseElement::new(5)),
+        Assertion::linear_combination(terms[..1].to_vec(), 9, BaseElement::new(5)),
+        Assertion::linear_combination(terms.clone(), 9, BaseElement::new(6)),
+        Assertion::single(1, 9, BaseElement::new(4)),
+    ];
+    let result = super::prepare_assertions(assertions.clone(), 2, 16);
+    assert_eq!(expected, result);
+
+    shuffle(&mut assertions);
+    let result = super::prepare_assertions(assertions, 2, 16);
+    assert_eq!(expected, result);
+}
+
 #[test]
 #[should_panic(
     expected = "assertion (column=0, steps=[1, 9, ...], value=7) overlaps with assertion (column=0, step=9, value=5)"
@@ -248,6 +316,16 @@ fn prepare_assertions_with_invalid_trace_width() {
     let _ = super::prepare_assertions(assertions, 2, 16);
 }
 
+#[test]
+#[should_panic(
+    expected = "assertion (columns=[1 * 0 + 1 * 2], step=3, value=5) is invalid: expected trace width to be at least 2, but was 2"
+)]
+fn prepare_assertions_with_invalid_linear_combination_width() {
+    let terms = vec![(0, BaseElement::ONE), (2, BaseElement::ONE)];
+    let assertions = vec![Assertion::linear_combination(terms, 3, BaseElement::new(5))];
+    let _ = super::prepare_assertions(assertions, 2, 16);
+}
+
 // HELPER FUNCTIONS
 // ================================================================================================
 
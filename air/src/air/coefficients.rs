@@ -100,13 +100,18 @@ pub struct ConstraintCompositionCoefficients<E: FieldElement> {
 /// https://eprint.iacr.org/2022/1216 and it relies on two points:
 ///
 /// 1. The evaluation proofs for each trace polynomial at $z$ and $g \cdot z$ can be batched using
-/// the non-normalized Lagrange kernel over the set $\{z, g \cdot z\}$. This, however, requires
-/// that the FRI protocol is run with rate $\rho^{+} := \frac{\kappa + 2}{\nu}$ where $\kappa$ and
-/// $\nu$ are the length of the execution trace and the LDE domain size, respectively.
+///    the non-normalized Lagrange kernel over the set $\{z, g \cdot z\}$. This, however, requires
+///    that the FRI protocol is run with rate $\rho^{+} := \frac{\kappa + 2}{\nu}$ where $\kappa$ and
+///    $\nu$ are the length of the execution trace and the LDE domain size, respectively.
 /// 2. The resulting $Y(x)$ do not need to be degree adjusted but the soundness error of the
-/// protocol needs to be updated. For most combinations of batching parameters, this leads to a
-/// negligible increase in soundness error. The formula for the updated error can be found in
-/// Theorem 8 of https://eprint.iacr.org/2022/1216.
+///    protocol needs to be updated. For most combinations of batching parameters, this leads to a
+///    negligible increase in soundness error. The formula for the updated error can be found in
+///    Theorem 8 of https://eprint.iacr.org/2022/1216.
+///
+/// When the AIR specifies additional out-of-domain offsets (see
+/// [AirContext::ood_offsets()](crate::AirContext::ood_offsets)), the term for each trace
+/// polynomial contains one quotient $\frac{T_i(x) - T_i(z \cdot g^k)}{x - z \cdot g^k}$ for every
+/// offset $k$, and all of these quotients share the same coefficient $\alpha_i$.
 #[derive(Debug, Clone)]
 pub struct DeepCompositionCoefficients<E: FieldElement> {
     /// Trace polynomial composition coefficients $\alpha_i$.
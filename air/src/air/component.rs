@@ -0,0 +1,515 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{Assertion, EvaluationFrame, TransitionConstraintDegree};
+use core::ops::Range;
+use math::{FieldElement, StarkField};
+use utils::collections::Vec;
+
+// COMPONENT
+// ================================================================================================
+
+/// A self-contained part of a computation which occupies a rectangular area of an execution
+/// trace.
+///
+/// A component (sometimes also called a chiplet) describes its trace area in local coordinates:
+/// columns are numbered from `0` to `width - 1` and rows from `0` to `height - 1`. Transition
+/// constraints, assertions, and the trace-fill method of a component are all expressed in these
+/// coordinates; a [ComponentLayout] maps them onto the shared execution trace.
+pub trait Component<B: StarkField> {
+    /// Returns the number of trace columns used by this component.
+    fn width(&self) -> usize;
+
+    /// Returns the number of trace rows used by this component; this must be a power of two.
+    fn height(&self) -> usize;
+
+    /// Returns degree descriptors of the transition constraints of this component.
+    fn transition_constraint_degrees(&self) -> Vec<TransitionConstraintDegree>;
+
+    /// Returns values of periodic columns used by this component.
+    ///
+    /// Cycle lengths of the periodic columns must not exceed the height of the component.
+    fn get_periodic_column_values(&self) -> Vec<Vec<B>> {
+        Vec::new()
+    }
+
+    /// Returns assertions placed against the trace area of this component.
+    fn get_assertions(&self) -> Vec<Assertion<B>>;
+
+    /// Evaluates transition constraints of this component over the provided evaluation frame.
+    ///
+    /// The frame contains only the columns of this component, and `periodic_values` contains
+    /// only the values of periodic columns defined by this component.
+    fn evaluate_transition<E: FieldElement + From<B>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        periodic_values: &[E],
+        result: &mut [E],
+    );
+
+    /// Fills the trace area of this component; `columns` contains a slice of `height` rows for
+    /// each of the `width` columns of the component.
+    fn fill_trace(&self, columns: &mut [&mut [B]]);
+}
+
+// COMPONENT ID
+// ================================================================================================
+
+/// Identifier of a component placed into an execution trace by a [TraceAllocator].
+///
+/// Identifiers are assigned sequentially in the order in which components are placed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ComponentId(usize);
+
+impl ComponentId {
+    /// Returns the index of this component in the order of placement.
+    pub fn index(&self) -> usize {
+        self.0
+    }
+}
+
+// TRACE ALLOCATOR
+// ================================================================================================
+
+/// Allocates areas of an execution trace to a set of [Component]s.
+///
+/// Components can be laid out in two ways:
+/// * [place()](TraceAllocator::place) puts a component into its own set of columns, side by side
+///   with all previously placed components.
+/// * [stack()](TraceAllocator::stack) puts a component into the same set of columns as some
+///   previously placed component; the two components then occupy different rows.
+///
+/// Once all components have been placed, [build()](TraceAllocator::build) computes the final
+/// [ComponentLayout] for an execution trace of a given length.
+pub struct TraceAllocator<B: StarkField> {
+    components: Vec<ComponentInfo<B>>,
+    groups: Vec<Vec<usize>>,
+}
+
+impl<B: StarkField> TraceAllocator<B> {
+    /// Returns a new allocator without any components.
+    pub fn new() -> Self {
+        TraceAllocator {
+            components: Vec::new(),
+            groups: Vec::new(),
+        }
+    }
+
+    /// Places the specified component into a new set of columns, side by side with all
+    /// previously placed components.
+    ///
+    /// # Panics
+    /// Panics if the component is not valid (see [Component] for details).
+    pub fn place<C: Component<B>>(&mut self, component: &C) -> ComponentId {
+        self.groups.push(Vec::new());
+        self.add_to_group(self.groups.len() - 1, component)
+    }
+
+    /// Places the specified component into the same set of columns as the component identified by
+    /// `other`; rows of the trace are split between all components stacked in the same columns.
+    ///
+    /// # Panics
+    /// Panics if the component is not valid (see [Component] for details), or if `other` was not
+    /// issued by this allocator.
+    pub fn stack<C: Component<B>>(&mut self, other: ComponentId, component: &C) -> ComponentId {
+        assert!(other.0 < self.components.len(), "component {} does not exist", other.0);
+        let group = self.components[other.0].group;
+        self.add_to_group(group, component)
+    }
+
+    /// Returns a layout of all placed components in an execution trace of the specified length.
+    ///
+    /// Within each set of columns, components are assigned rows in the order of decreasing
+    /// height; thus, every component starts at a row which is a multiple of its height, and
+    /// periodic columns of the component stay aligned with its rows.
+    ///
+    /// # Panics
+    /// Panics if components stacked in the same set of columns do not fit into `trace_length`
+    /// rows.
+    pub fn build(self, trace_length: usize) -> ComponentLayout<B> {
+        let TraceAllocator {
+            mut components,
+            groups,
+        } = self;
+
+        let mut trace_width = 0;
+        let mut num_selectors = 0;
+        let mut groups_info = Vec::with_capacity(groups.len());
+        for mut group in groups {
+            group.sort_by_key(|&i| core::cmp::Reverse(components[i].height));
+
+            let total_height: usize = group.iter().map(|&i| components[i].height).sum();
+            assert!(
+                total_height <= trace_length,
+                "components with a total height of {total_height} do not fit into a trace of {trace_length} rows"
+            );
+            let width = group.iter().map(|&i| components[i].width).max().unwrap_or(0);
+
+            // a selector column is needed at every boundary between two components, and at the
+            // boundary between the last component and the unused rows at the end of the trace
+            let num_boundaries = group.len() - 1 + usize::from(total_height < trace_length);
+            let selectors = trace_width + width..trace_width + width + num_boundaries;
+
+            let mut boundaries = Vec::with_capacity(num_boundaries);
+            let mut start = 0;
+            for (position, &i) in group.iter().enumerate() {
+                let component = &mut components[i];
+                component.columns = trace_width..trace_width + component.width;
+                component.rows = start..start + component.height;
+                component.entry_selector = (position > 0).then(|| selectors.start + position - 1);
+                component.exit_selector =
+                    (position < num_boundaries).then(|| selectors.start + position);
+
+                start += component.height;
+                if position < num_boundaries {
+                    boundaries.push(start);
+                }
+            }
+
+            groups_info.push((selectors.clone(), boundaries));
+            trace_width = selectors.end;
+            num_selectors += num_boundaries;
+        }
+
+        let selectors = groups_info
+            .into_iter()
+            .flat_map(|(columns, boundaries)| columns.zip(boundaries))
+            .collect::<Vec<_>>();
+        debug_assert_eq!(num_selectors, selectors.len());
+
+        ComponentLayout {
+            trace_width,
+            trace_length,
+            components,
+            selectors,
+        }
+    }
+
+    // HELPER METHODS
+    // --------------------------------------------------------------------------------------------
+
+    fn add_to_group<C: Component<B>>(&mut self, group: usize, component: &C) -> ComponentId {
+        let width = component.width();
+        let height = component.height();
+        assert!(width > 0, "component width must be greater than zero");
+        assert!(
+            height.is_power_of_two() && height > 1,
+            "component height must be a power of two greater than one, but was {height}"
+        );
+
+        let periodic_values = component.get_periodic_column_values();
+        for column in periodic_values.iter() {
+            assert!(
+                column.len() <= height,
+                "cycle length {} of a periodic column exceeds component height {height}",
+                column.len()
+            );
+        }
+
+        let assertions = component.get_assertions();
+        for assertion in assertions.iter() {
+            assertion.validate_trace_width(width).unwrap_or_else(|err| {
+                panic!("assertion {assertion} is not valid for the component: {err}")
+            });
+            assertion.validate_trace_length(height).unwrap_or_else(|err| {
+                panic!("assertion {assertion} is not valid for the component: {err}")
+            });
+        }
+
+        let id = self.components.len();
+        self.components.push(ComponentInfo {
+            group,
+            width,
+            height,
+            degrees: component.transition_constraint_degrees(),
+            periodic_values,
+            assertions,
+            columns: 0..0,
+            rows: 0..0,
+            entry_selector: None,
+            exit_selector: None,
+        });
+        self.groups[group].push(id);
+        ComponentId(id)
+    }
+}
+
+impl<B: StarkField> Default for TraceAllocator<B> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// COMPONENT LAYOUT
+// ================================================================================================
+
+/// Placement of a set of [Component]s within a single execution trace.
+///
+/// The layout takes care of bridging individual components into a single AIR:
+/// * Transition constraints of a component are evaluated against the component's columns only,
+///   and are enforced only for transitions between two rows of the component.
+/// * Assertions of a component are shifted to the component's columns and rows.
+/// * Periodic columns of all components are concatenated.
+///
+/// Transition constraints, assertions, and periodic columns are ordered by [ComponentId], and
+/// are followed by the constraints and assertions of the selector columns (if any).
+///
+/// # Selector columns
+/// When components are stacked in the same columns, or when a component is shorter than the
+/// trace, the layout appends binary selector columns to the columns of the component. A
+/// selector column is associated with each boundary between adjacent row ranges; the selector
+/// is set to `0` above the boundary and to `1` at and below it. This is enforced by requiring
+/// the selector to be binary and never to change from `1` back to `0`, and by asserting its
+/// values at the two rows around the boundary.
+///
+/// Transition constraints of a component are multiplied by a flag which is `1` if the current
+/// row is at or below the component's first row and the next row is above the component's
+/// end; this increases the degree of these constraints by up to `2`.
+pub struct ComponentLayout<B: StarkField> {
+    trace_width: usize,
+    trace_length: usize,
+    components: Vec<ComponentInfo<B>>,
+    selectors: Vec<(usize, usize)>,
+}
+
+impl<B: StarkField> ComponentLayout<B> {
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the number of columns in the execution trace described by this layout.
+    pub fn trace_width(&self) -> usize {
+        self.trace_width
+    }
+
+    /// Returns the number of rows in the execution trace described by this layout.
+    pub fn trace_length(&self) -> usize {
+        self.trace_length
+    }
+
+    /// Returns the number of components in this layout.
+    pub fn num_components(&self) -> usize {
+        self.components.len()
+    }
+
+    /// Returns trace columns occupied by the specified component.
+    pub fn columns(&self, id: ComponentId) -> Range<usize> {
+        self.components[id.0].columns.clone()
+    }
+
+    /// Returns trace rows occupied by the specified component.
+    pub fn rows(&self, id: ComponentId) -> Range<usize> {
+        self.components[id.0].rows.clone()
+    }
+
+    /// Returns the number of selector columns added by this layout.
+    pub fn num_selectors(&self) -> usize {
+        self.selectors.len()
+    }
+
+    /// Returns degree descriptors of all transition constraints described by this layout.
+    pub fn transition_constraint_degrees(&self) -> Vec<TransitionConstraintDegree> {
+        let mut result = Vec::new();
+        for component in self.components.iter() {
+            let num_flags = component.num_flag_columns();
+            result.extend(component.degrees.iter().map(|degree| degree.mul_columns(num_flags)));
+        }
+        for _ in self.selectors.iter() {
+            result.push(TransitionConstraintDegree::new(2));
+            result.push(TransitionConstraintDegree::new(2));
+        }
+        result
+    }
+
+    /// Returns all assertions described by this layout.
+    pub fn get_assertions(&self) -> Vec<Assertion<B>> {
+        let mut result = Vec::new();
+        for component in self.components.iter() {
+            let offset = component.columns.start;
+            let shift_terms = |terms: &[(usize, B)]| -> Vec<(usize, B)> {
+                terms.iter().map(|&(column, c)| (column + offset, c)).collect()
+            };
+
+            if component.height == self.trace_length {
+                result.extend(component.assertions.iter().map(|assertion| Assertion {
+                    column: assertion.column + offset,
+                    terms: shift_terms(&assertion.terms),
+                    ..assertion.clone()
+                }));
+            } else {
+                // periodic and sequence assertions cannot be restricted to a range of rows, and
+                // thus, are expanded into single assertions
+                for assertion in component.assertions.iter() {
+                    assertion.apply(component.height, |step, value| {
+                        result.push(Assertion {
+                            terms: shift_terms(&assertion.terms),
+                            ..Assertion::single(
+                                assertion.column + offset,
+                                component.rows.start + step,
+                                value,
+                            )
+                        });
+                    });
+                }
+            }
+        }
+
+        for &(column, boundary) in self.selectors.iter() {
+            result.push(Assertion::single(column, boundary - 1, B::ZERO));
+            result.push(Assertion::single(column, boundary, B::ONE));
+        }
+        result
+    }
+
+    /// Returns values of periodic columns of all components in this layout.
+    pub fn get_periodic_column_values(&self) -> Vec<Vec<B>> {
+        self.components
+            .iter()
+            .flat_map(|component| component.periodic_values.clone())
+            .collect()
+    }
+
+    // CONSTRAINT EVALUATION
+    // --------------------------------------------------------------------------------------------
+
+    /// Evaluates transition constraints of the specified component.
+    ///
+    /// `frame`, `periodic_values`, and `result` are expected to cover the entire execution trace
+    /// described by this layout; only the parts of `periodic_values` and `result` which belong to
+    /// the component are read and written.
+    pub fn evaluate_component<C, E>(
+        &self,
+        id: ComponentId,
+        component: &C,
+        frame: &EvaluationFrame<E>,
+        periodic_values: &[E],
+        result: &mut [E],
+    ) where
+        C: Component<B>,
+        E: FieldElement + From<B>,
+    {
+        let (periodic_offset, constraint_offset) = self.offsets(id.0);
+        let info = &self.components[id.0];
+
+        let mut component_frame = EvaluationFrame::with_offsets(info.width, frame.offsets());
+        for row in 0..frame.num_rows() {
+            component_frame
+                .row_mut(row)
+                .copy_from_slice(&frame.row(row)[info.columns.clone()]);
+        }
+
+        let periodic_values =
+            &periodic_values[periodic_offset..periodic_offset + info.periodic_values.len()];
+        let result = &mut result[constraint_offset..constraint_offset + info.degrees.len()];
+        component.evaluate_transition(&component_frame, periodic_values, result);
+
+        let mut flag = E::ONE;
+        if let Some(column) = info.entry_selector {
+            flag *= frame.current()[column];
+        }
+        if let Some(column) = info.exit_selector {
+            flag *= E::ONE - frame.next()[column];
+        }
+        if info.num_flag_columns() > 0 {
+            result.iter_mut().for_each(|value| *value *= flag);
+        }
+    }
+
+    /// Evaluates transition constraints of the selector columns of this layout.
+    ///
+    /// `frame` and `result` are expected to cover the entire execution trace described by this
+    /// layout; only the part of `result` which follows the constraints of all components is
+    /// written.
+    pub fn evaluate_selectors<E: FieldElement + From<B>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        result: &mut [E],
+    ) {
+        let offset = self.components.iter().map(|component| component.degrees.len()).sum::<usize>();
+        let current = frame.current();
+        let next = frame.next();
+        for (i, &(column, _)) in self.selectors.iter().enumerate() {
+            // the selector must be binary, and once set to one, must stay one
+            result[offset + 2 * i] = current[column] * current[column] - current[column];
+            result[offset + 2 * i + 1] = current[column] * (E::ONE - next[column]);
+        }
+    }
+
+    // TRACE GENERATION
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns columns of an execution trace described by this layout with selector columns
+    /// filled in and all other cells set to zero.
+    pub fn new_trace_columns(&self) -> Vec<Vec<B>> {
+        let mut columns = vec![vec![B::ZERO; self.trace_length]; self.trace_width];
+        for &(column, boundary) in self.selectors.iter() {
+            columns[column][boundary..].fill(B::ONE);
+        }
+        columns
+    }
+
+    /// Fills the trace area of the specified component in the provided execution trace.
+    ///
+    /// # Panics
+    /// Panics if `columns` does not describe an execution trace of this layout.
+    pub fn fill_component<C: Component<B>>(
+        &self,
+        id: ComponentId,
+        component: &C,
+        columns: &mut [Vec<B>],
+    ) {
+        assert_eq!(self.trace_width, columns.len(), "invalid number of trace columns");
+        let info = &self.components[id.0];
+        let rows = info.rows.clone();
+        let mut area = columns[info.columns.clone()]
+            .iter_mut()
+            .map(|column| {
+                assert_eq!(self.trace_length, column.len(), "invalid trace column length");
+                &mut column[rows.clone()]
+            })
+            .collect::<Vec<_>>();
+        component.fill_trace(&mut area);
+    }
+
+    // HELPER METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns offsets of the first periodic column and the first transition constraint of the
+    /// specified component.
+    fn offsets(&self, index: usize) -> (usize, usize) {
+        self.components[..index]
+            .iter()
+            .fold((0, 0), |(periodic, constraints), component| {
+                (
+                    periodic + component.periodic_values.len(),
+                    constraints + component.degrees.len(),
+                )
+            })
+    }
+}
+
+// COMPONENT INFO
+// ================================================================================================
+
+/// Properties of a component captured at the time of placement, together with its location in
+/// the execution trace.
+struct ComponentInfo<B: StarkField> {
+    group: usize,
+    width: usize,
+    height: usize,
+    degrees: Vec<TransitionConstraintDegree>,
+    periodic_values: Vec<Vec<B>>,
+    assertions: Vec<Assertion<B>>,
+    columns: Range<usize>,
+    rows: Range<usize>,
+    entry_selector: Option<usize>,
+    exit_selector: Option<usize>,
+}
+
+impl<B: StarkField> ComponentInfo<B> {
+    /// Returns the number of selector columns which the flag of this component depends on.
+    fn num_flag_columns(&self) -> usize {
+        usize::from(self.entry_selector.is_some()) + usize::from(self.exit_selector.is_some())
+    }
+}
@@ -23,6 +23,8 @@ pub struct AirContext<B: StarkField> {
     pub(super) trace_domain_generator: B,
     pub(super) lde_domain_generator: B,
     pub(super) num_transition_exemptions: usize,
+    pub(super) ood_offsets: Vec<usize>,
+    pub(super) num_preprocessed_columns: usize,
 }
 
 impl<B: StarkField> AirContext<B> {
@@ -119,20 +121,19 @@ impl<B: StarkField> AirContext<B> {
             );
         }
 
-        // determine minimum blowup factor needed to evaluate transition constraints by taking
-        // the blowup factor of the highest degree constraint
-        let mut ce_blowup_factor = 0;
-        for degree in main_transition_constraint_degrees.iter() {
-            if degree.min_blowup_factor() > ce_blowup_factor {
-                ce_blowup_factor = degree.min_blowup_factor();
-            }
-        }
-
-        for degree in aux_transition_constraint_degrees.iter() {
-            if degree.min_blowup_factor() > ce_blowup_factor {
-                ce_blowup_factor = degree.min_blowup_factor();
-            }
-        }
+        // determine minimum blowup factor needed to evaluate transition constraints from the
+        // exact degree of the constraint composition polynomial; this could be smaller than the
+        // estimate given by the highest degree constraint (see
+        // TransitionConstraintDegree::min_blowup_factor()) when constraints involve periodic
+        // columns
+        let composition_degree = get_composition_degree(
+            main_transition_constraint_degrees
+                .iter()
+                .chain(aux_transition_constraint_degrees.iter()),
+            trace_info.length(),
+            1,
+        );
+        let ce_blowup_factor = get_ce_blowup_factor(composition_degree, trace_info.length());
 
         assert!(
             options.blowup_factor() >= ce_blowup_factor,
@@ -155,6 +156,8 @@ impl<B: StarkField> AirContext<B> {
             trace_domain_generator: B::get_root_of_unity(trace_length.ilog2()),
             lde_domain_generator: B::get_root_of_unity(lde_domain_size.ilog2()),
             num_transition_exemptions: 1,
+            ood_offsets: vec![0, 1],
+            num_preprocessed_columns: 0,
         }
     }
 
@@ -217,6 +220,16 @@ impl<B: StarkField> AirContext<B> {
         self.num_main_assertions + self.num_aux_assertions
     }
 
+    /// Returns the number of assertions placed against the main segment of an execution trace.
+    pub fn num_main_assertions(&self) -> usize {
+        self.num_main_assertions
+    }
+
+    /// Returns the number of assertions placed against all auxiliary trace segments.
+    pub fn num_aux_assertions(&self) -> usize {
+        self.num_aux_assertions
+    }
+
     /// Returns the number of rows at the end of an execution trace to which transition constraints
     /// do not apply.
     ///
@@ -227,11 +240,37 @@ impl<B: StarkField> AirContext<B> {
         self.num_transition_exemptions
     }
 
+    /// Returns offsets of the out-of-domain points at which trace polynomials are opened.
+    ///
+    /// An offset `k` corresponds to the point `z * g^k`, where `z` is the out-of-domain point and
+    /// `g` is the generator of the trace domain. The first two offsets are always `0` and `1`
+    /// (the current and the next rows of the evaluation frame), and by default, these are the
+    /// only offsets.
+    pub fn ood_offsets(&self) -> &[usize] {
+        &self.ood_offsets
+    }
+
+    /// Returns the number of rows in the out-of-domain trace frame.
+    pub fn num_ood_points(&self) -> usize {
+        self.ood_offsets.len()
+    }
+
+    /// Returns the number of preprocessed columns at the end of the main trace segment.
+    ///
+    /// Preprocessed columns hold values which are the same for all executions of a computation
+    /// (e.g., instruction ROMs or lookup tables). The prover commits to them once, and all proofs
+    /// for the computation are verified against the same commitment supplied to the verifier.
+    /// By default, there are no preprocessed columns.
+    pub fn num_preprocessed_columns(&self) -> usize {
+        self.num_preprocessed_columns
+    }
+
     /// Returns the number of columns needed to store the constraint composition polynomial.
     ///
     /// This is the maximum of:
-    /// 1. The maximum evaluation degree over all transition constraints minus the degree
-    /// of the transition constraint divisor divided by trace length.
+    /// 1. The number of coefficients of the constraint composition polynomial (i.e., the maximum
+    ///    evaluation degree over all transition constraints minus the degree of the transition
+    ///    constraint divisor plus one) divided by trace length.
     /// 2. `1`, because the constraint composition polynomial requires at least one column.
     ///
     /// Since the degree of a constraint `C(x)` can be well approximated by
@@ -249,25 +288,15 @@ impl<B: StarkField> AirContext<B> {
     /// composition polynomial will require four columns and if the highest constraint degree is
     /// equal to `7`, it will require six columns to store.
     pub fn num_constraint_composition_columns(&self) -> usize {
-        let mut highest_constraint_degree = 0_usize;
-        for degree in self
-            .main_transition_constraint_degrees
-            .iter()
-            .chain(self.aux_transition_constraint_degrees.iter())
-        {
-            let eval_degree = degree.get_evaluation_degree(self.trace_len());
-            if eval_degree > highest_constraint_degree {
-                highest_constraint_degree = eval_degree
-            }
-        }
-        let trace_length = self.trace_len();
-        let transition_divisior_degree = trace_length - self.num_transition_exemptions();
-
-        // we use the identity: ceil(a/b) = (a + b - 1)/b
-        let num_constraint_col =
-            (highest_constraint_degree - transition_divisior_degree + trace_length - 1)
-                / trace_length;
+        let composition_degree = get_composition_degree(
+            self.main_transition_constraint_degrees
+                .iter()
+                .chain(self.aux_transition_constraint_degrees.iter()),
+            self.trace_len(),
+            self.num_transition_exemptions,
+        );
 
+        let num_constraint_col = (composition_degree + 1).div_ceil(self.trace_len());
         cmp::max(num_constraint_col, 1)
     }
 
@@ -294,10 +323,10 @@ impl<B: StarkField> AirContext<B> {
         );
         // make sure the composition polynomial can be computed correctly with the specified
         // number of exemptions.
-        // The `ce_blowup` factor puts a ceiling on the maximal degree of a constraint composition
+        // The LDE blowup factor puts a ceiling on the maximal degree of a constraint composition
         // polynomial we can accommodate. On the other hand, adding exemption points reduces the
         // degree of the divisor which results in an increase of the resulting constraint composition
-        // polynomial.Thus we need to check that the number of exemption points is not too large
+        // polynomial. Thus we need to check that the number of exemption points is not too large
         // given the above.
         for degree in self
             .main_transition_constraint_degrees
@@ -305,7 +334,7 @@ impl<B: StarkField> AirContext<B> {
             .chain(self.aux_transition_constraint_degrees.iter())
         {
             let eval_degree = degree.get_evaluation_degree(self.trace_len());
-            let max_constraint_composition_degree = self.ce_domain_size() - 1;
+            let max_constraint_composition_degree = self.lde_domain_size() - 1;
             let max_exemptions = max_constraint_composition_degree + self.trace_len() - eval_degree;
             assert!(
                 n <= max_exemptions,
@@ -313,7 +342,111 @@ impl<B: StarkField> AirContext<B> {
             )
         }
 
+        // the constraint evaluation domain may need to grow to accommodate the increased degree
+        // of the constraint composition polynomial
+        let composition_degree = get_composition_degree(
+            self.main_transition_constraint_degrees
+                .iter()
+                .chain(self.aux_transition_constraint_degrees.iter()),
+            self.trace_len(),
+            n,
+        );
+        self.ce_blowup_factor = get_ce_blowup_factor(composition_degree, self.trace_len());
         self.num_transition_exemptions = n;
         self
     }
+
+    /// Sets offsets of the out-of-domain points at which trace polynomials are opened.
+    ///
+    /// Trace polynomials are evaluated at `z * g^k` for every offset `k` in `offsets`, and the
+    /// verifier checks that evaluations at each of these points are consistent with the trace
+    /// commitment. This is useful for computations which need to relate trace rows beyond the
+    /// current and the next ones (e.g., consistency checks across distant rows).
+    ///
+    /// Evaluation frames passed to transition constraint evaluation contain one row for each of
+    /// the offsets; row `i` of a frame holds the trace row at `step + offsets[i]` (see
+    /// [EvaluationFrame::row()](crate::EvaluationFrame::row)). Rows beyond the end of the trace
+    /// wrap around to its beginning; thus, the number of transition exemptions usually needs to
+    /// be at least as large as the largest offset.
+    ///
+    /// Every offset beyond the default ones reduces the security level of generated proofs (see
+    /// [StarkProof::security_level()](crate::proof::StarkProof::security_level)).
+    ///
+    /// # Panics
+    /// Panics if:
+    /// * `offsets` does not start with `0` and `1`.
+    /// * `offsets` contains duplicate values or values greater than or equal to the trace length.
+    /// * `offsets` contains more than 255 values.
+    pub fn set_ood_offsets(mut self, offsets: Vec<usize>) -> Self {
+        assert!(
+            offsets.starts_with(&[0, 1]),
+            "out-of-domain offsets must start with 0 and 1, but were {offsets:?}"
+        );
+        assert!(
+            offsets.len() <= u8::MAX as usize,
+            "number of out-of-domain offsets cannot exceed {}, but was {}",
+            u8::MAX,
+            offsets.len()
+        );
+        for (i, &offset) in offsets.iter().enumerate() {
+            assert!(
+                offset < self.trace_len(),
+                "out-of-domain offset must be smaller than trace length {}, but was {offset}",
+                self.trace_len()
+            );
+            assert!(
+                !offsets[..i].contains(&offset),
+                "out-of-domain offset {offset} is specified more than once"
+            );
+        }
+
+        self.ood_offsets = offsets;
+        self
+    }
+
+    /// Sets the number of preprocessed columns for this context.
+    ///
+    /// The last `n` columns of the main trace segment are treated as preprocessed columns: they
+    /// are committed to separately from the rest of the main trace segment, and the verifier
+    /// authenticates queried values of these columns against a commitment known in advance
+    /// rather than against a commitment sent by the prover. Transition constraints and
+    /// assertions can refer to preprocessed columns in the same way as to any other column.
+    ///
+    /// # Panics
+    /// Panics if `n` is not smaller than the width of the main trace segment (i.e., at least one
+    /// column of the main trace segment must not be preprocessed).
+    pub fn set_num_preprocessed_columns(mut self, n: usize) -> Self {
+        let main_trace_width = self.trace_info.layout().main_trace_width();
+        assert!(
+            n < main_trace_width,
+            "number of preprocessed columns must be smaller than main trace width {main_trace_width}, but was {n}"
+        );
+        self.num_preprocessed_columns = n;
+        self
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns the degree of the constraint composition polynomial for transition constraints of the
+/// specified degrees; the degree of the transition constraint divisor is defined by the trace
+/// length and the number of transition exemptions.
+fn get_composition_degree<'a>(
+    degrees: impl Iterator<Item = &'a TransitionConstraintDegree>,
+    trace_length: usize,
+    num_exemptions: usize,
+) -> usize {
+    let max_eval_degree = degrees
+        .map(|degree| degree.get_evaluation_degree(trace_length))
+        .max()
+        .unwrap_or(0);
+    (max_eval_degree + num_exemptions).saturating_sub(trace_length)
+}
+
+/// Returns the smallest blowup factor of a constraint evaluation domain large enough to hold a
+/// constraint composition polynomial of the specified degree.
+fn get_ce_blowup_factor(composition_degree: usize, trace_length: usize) -> usize {
+    let blowup_factor = (composition_degree + 1).div_ceil(trace_length).next_power_of_two();
+    cmp::max(blowup_factor, ProofOptions::MIN_BLOWUP_FACTOR)
 }
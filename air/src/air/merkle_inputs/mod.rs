@@ -0,0 +1,294 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use crate::errors::PublicInputsError;
+use core::fmt;
+use crypto::{BatchMerkleProof, Digest, ElementHasher, MerkleTree, MerkleTreeError};
+use math::{log2, StarkField, ToElements};
+use utils::{
+    collections::Vec, ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable,
+};
+
+#[cfg(test)]
+mod tests;
+
+// MERKLE-IZED PUBLIC INPUTS
+// ================================================================================================
+
+/// Public inputs committed to via a Merkle tree with a subset of leaves revealed to the verifier.
+///
+/// Each leaf of the tree is a list of field elements hashed with `H`. Only the root of the tree,
+/// the number of leaves, and the revealed leaves (together with their indexes) are bound to the
+/// proof: these are the only values returned by [ToElements::to_elements()], and thus, the only
+/// values which are absorbed into the public coin and which an AIR can use to define its
+/// constraints. Values of the leaves which are not revealed remain hidden from the verifier.
+///
+/// Revealed leaves are accompanied by a batch Merkle proof against the root. The proof is checked
+/// by [MerkleInputs::verify()]; to have the verifier run this check before a proof is verified,
+/// an AIR should call it from [Air::validate_public_inputs()](crate::Air::validate_public_inputs).
+///
+/// If the number of leaves is not a power of two, the tree is padded with default digests. At
+/// most 255 leaves can be revealed.
+pub struct MerkleInputs<B: StarkField, H: ElementHasher<BaseField = B>> {
+    root: H::Digest,
+    num_leaves: usize,
+    revealed: Vec<(usize, Vec<B>)>,
+    proof: Option<BatchMerkleProof<H>>,
+}
+
+impl<B: StarkField, H: ElementHasher<BaseField = B>> MerkleInputs<B, H> {
+    // CONSTRUCTORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns Merkle-ized public inputs built from the provided `leaves` with leaves at the
+    /// specified `indexes` revealed.
+    ///
+    /// This is intended to be used by the prover, which knows values of all leaves. Indexes do
+    /// not need to be sorted.
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// * `leaves` is empty.
+    /// * Any of the `indexes` is greater than or equal to the number of leaves.
+    /// * `indexes` contains duplicates or more than 255 values.
+    pub fn new(leaves: &[Vec<B>], indexes: &[usize]) -> Result<Self, MerkleTreeError> {
+        if leaves.is_empty() {
+            return Err(MerkleTreeError::TooFewLeaves(1, 0));
+        }
+        if let Some(&index) = indexes.iter().find(|&&index| index >= leaves.len()) {
+            return Err(MerkleTreeError::LeafIndexOutOfBounds(leaves.len(), index));
+        }
+
+        let mut digests = leaves.iter().map(|leaf| H::hash_elements(leaf)).collect::<Vec<_>>();
+        digests.resize(padded_num_leaves(leaves.len()), H::Digest::default());
+        let tree = MerkleTree::<H>::new(digests)?;
+
+        let mut indexes = indexes.to_vec();
+        indexes.sort_unstable();
+        let proof = if indexes.is_empty() {
+            None
+        } else {
+            Some(tree.prove_batch(&indexes)?)
+        };
+        let revealed = indexes.into_iter().map(|index| (index, leaves[index].clone())).collect();
+
+        Ok(Self {
+            root: *tree.root(),
+            num_leaves: leaves.len(),
+            revealed,
+            proof,
+        })
+    }
+
+    /// Returns Merkle-ized public inputs assembled from the specified parts.
+    ///
+    /// This is intended to be used by the verifier. The parts are not validated; to check that
+    /// the revealed leaves are consistent with the `root`, [MerkleInputs::verify()] should be
+    /// called.
+    pub fn from_parts(
+        root: H::Digest,
+        num_leaves: usize,
+        mut revealed: Vec<(usize, Vec<B>)>,
+        proof: Option<BatchMerkleProof<H>>,
+    ) -> Self {
+        revealed.sort_by_key(|(index, _)| *index);
+        Self {
+            root,
+            num_leaves,
+            revealed,
+            proof,
+        }
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the root of the Merkle tree committing to all leaves.
+    pub fn root(&self) -> &H::Digest {
+        &self.root
+    }
+
+    /// Returns the number of leaves committed to by the Merkle tree (excluding padding).
+    pub fn num_leaves(&self) -> usize {
+        self.num_leaves
+    }
+
+    /// Returns the revealed leaves together with their indexes, sorted by index.
+    pub fn revealed(&self) -> &[(usize, Vec<B>)] {
+        &self.revealed
+    }
+
+    /// Returns the value of the leaf at the specified index, or None if the leaf is not revealed.
+    pub fn get(&self, index: usize) -> Option<&[B]> {
+        self.revealed
+            .binary_search_by_key(&index, |(i, _)| *i)
+            .ok()
+            .map(|position| self.revealed[position].1.as_slice())
+    }
+
+    /// Returns the batch Merkle proof for the revealed leaves, or None if no leaves are revealed.
+    pub fn proof(&self) -> Option<&BatchMerkleProof<H>> {
+        self.proof.as_ref()
+    }
+
+    // VERIFICATION
+    // --------------------------------------------------------------------------------------------
+
+    /// Checks that the revealed leaves are committed to by the root of the Merkle tree.
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// * Any of the revealed leaves has an index greater than or equal to the number of leaves.
+    /// * The batch Merkle proof is missing, was built for a tree of a different depth, or does
+    ///   not resolve to the root for the hashes of the revealed leaves.
+    pub fn verify(&self) -> Result<(), PublicInputsError> {
+        if let Some((index, _)) = self.revealed.iter().find(|(i, _)| *i >= self.num_leaves) {
+            return Err(PublicInputsError::RevealedLeafOutOfBounds(self.num_leaves, *index));
+        }
+
+        let proof = match (&self.proof, self.revealed.is_empty()) {
+            (None, true) => return Ok(()),
+            (Some(proof), false) => proof,
+            _ => return Err(PublicInputsError::InvalidMerkleProof),
+        };
+        if proof.depth as u32 != log2(padded_num_leaves(self.num_leaves)) {
+            return Err(PublicInputsError::InvalidMerkleProof);
+        }
+
+        // the leaves of the proof are recomputed from the revealed values so that the proof
+        // cannot vouch for values different from the ones exposed to the AIR
+        let proof = BatchMerkleProof::<H> {
+            leaves: self.revealed.iter().map(|(_, leaf)| H::hash_elements(leaf)).collect(),
+            nodes: proof.nodes.clone(),
+            depth: proof.depth,
+        };
+        let indexes = self.revealed.iter().map(|(index, _)| *index).collect::<Vec<_>>();
+        MerkleTree::verify_batch(&self.root, &indexes, &proof)
+            .map_err(|_| PublicInputsError::InvalidMerkleProof)
+    }
+}
+
+// TRAIT IMPLEMENTATIONS
+// ================================================================================================
+
+impl<B: StarkField, H: ElementHasher<BaseField = B>> ToElements<B> for MerkleInputs<B, H> {
+    /// Returns the root (as a sequence of 32-bit limbs), the number of leaves, and the index,
+    /// length, and values of each revealed leaf.
+    fn to_elements(&self) -> Vec<B> {
+        let mut result = self
+            .root
+            .as_bytes()
+            .chunks(4)
+            .map(|chunk| B::from(u32::from_le_bytes(chunk.try_into().unwrap())))
+            .collect::<Vec<_>>();
+        result.push(B::from(self.num_leaves as u64));
+        for (index, leaf) in self.revealed.iter() {
+            result.push(B::from(*index as u64));
+            result.push(B::from(leaf.len() as u64));
+            result.extend_from_slice(leaf);
+        }
+        result
+    }
+}
+
+impl<B: StarkField, H: ElementHasher<BaseField = B>> Clone for MerkleInputs<B, H> {
+    fn clone(&self) -> Self {
+        Self {
+            root: self.root,
+            num_leaves: self.num_leaves,
+            revealed: self.revealed.clone(),
+            proof: self.proof.as_ref().map(|proof| BatchMerkleProof {
+                leaves: proof.leaves.clone(),
+                nodes: proof.nodes.clone(),
+                depth: proof.depth,
+            }),
+        }
+    }
+}
+
+impl<B: StarkField, H: ElementHasher<BaseField = B>> PartialEq for MerkleInputs<B, H> {
+    fn eq(&self, other: &Self) -> bool {
+        self.root == other.root
+            && self.num_leaves == other.num_leaves
+            && self.revealed == other.revealed
+            && match (&self.proof, &other.proof) {
+                (Some(a), Some(b)) => a.nodes == b.nodes && a.depth == b.depth,
+                (a, b) => a.is_none() && b.is_none(),
+            }
+    }
+}
+
+impl<B: StarkField, H: ElementHasher<BaseField = B>> Eq for MerkleInputs<B, H> {}
+
+impl<B: StarkField, H: ElementHasher<BaseField = B>> fmt::Debug for MerkleInputs<B, H> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MerkleInputs")
+            .field("root", &self.root)
+            .field("num_leaves", &self.num_leaves)
+            .field("revealed", &self.revealed)
+            .finish()
+    }
+}
+
+impl<B: StarkField, H: ElementHasher<BaseField = B>> Serializable for MerkleInputs<B, H> {
+    /// Serializes `self` and writes the resulting bytes into the `target`.
+    ///
+    /// # Panics
+    /// Panics if the number of leaves or the number of values in a revealed leaf does not fit
+    /// into a `u32`, or if more than 255 leaves are revealed.
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        self.root.write_into(target);
+        target.write_u32(self.num_leaves.try_into().expect("too many leaves"));
+        target.write_u8(self.revealed.len().try_into().expect("too many revealed leaves"));
+        for (index, leaf) in self.revealed.iter() {
+            target.write_u32(*index as u32);
+            target.write_u32(leaf.len().try_into().expect("revealed leaf is too long"));
+            B::write_batch_into(leaf, target);
+        }
+        if let Some(proof) = &self.proof {
+            target.write_u8(proof.depth);
+            target.write_bytes(&proof.serialize_nodes());
+        }
+    }
+}
+
+impl<B: StarkField, H: ElementHasher<BaseField = B>> Deserializable for MerkleInputs<B, H> {
+    /// Reads Merkle-ized public inputs from the specified `source` and returns the result.
+    ///
+    /// Leaves of the batch Merkle proof are recomputed from the revealed values.
+    ///
+    /// # Errors
+    /// Returns an error if a valid instance could not be read from the specified `source`.
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        let root = H::Digest::read_from(source)?;
+        let num_leaves = source.read_u32()? as usize;
+        let num_revealed = source.read_u8()? as usize;
+        let mut revealed = Vec::with_capacity(num_revealed);
+        for _ in 0..num_revealed {
+            let index = source.read_u32()? as usize;
+            let num_values = source.read_u32()? as usize;
+            revealed.push((index, B::read_batch_from(source, num_values)?));
+        }
+
+        let proof = if revealed.is_empty() {
+            None
+        } else {
+            let depth = source.read_u8()?;
+            let leaves = revealed.iter().map(|(_, leaf)| H::hash_elements(leaf)).collect();
+            Some(BatchMerkleProof::deserialize(source, leaves, depth)?)
+        };
+
+        Ok(Self::from_parts(root, num_leaves, revealed, proof))
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns the number of leaves in a Merkle tree committing to `num_leaves` leaves; this is the
+/// smallest power of two which is at least `num_leaves` and at least 2.
+fn padded_num_leaves(num_leaves: usize) -> usize {
+    num_leaves.next_power_of_two().max(2)
+}
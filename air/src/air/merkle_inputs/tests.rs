@@ -0,0 +1,134 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{MerkleInputs, PublicInputsError};
+use crypto::{hashers::Blake3_256, BatchMerkleProof, ElementHasher, MerkleTreeError};
+use math::{fields::f128::BaseElement, FieldElement, ToElements};
+use utils::{collections::Vec, Deserializable, Serializable};
+
+type Inputs = MerkleInputs<BaseElement, Blake3_256<BaseElement>>;
+
+// MERKLE INPUTS TESTS
+// ================================================================================================
+
+#[test]
+fn merkle_inputs_new() {
+    let leaves = build_leaves(5);
+    let inputs = Inputs::new(&leaves, &[3, 0]).unwrap();
+    assert_eq!(5, inputs.num_leaves());
+    assert_eq!(&[(0, leaves[0].clone()), (3, leaves[3].clone())], inputs.revealed());
+    assert_eq!(Some(leaves[3].as_slice()), inputs.get(3));
+    assert_eq!(None, inputs.get(1));
+    assert_eq!(Ok(()), inputs.verify());
+
+    // the root does not depend on which leaves are revealed
+    let other = Inputs::new(&leaves, &[1]).unwrap();
+    assert_eq!(inputs.root(), other.root());
+
+    // no leaves revealed
+    let hidden = Inputs::new(&leaves, &[]).unwrap();
+    assert!(hidden.proof().is_none());
+    assert_eq!(Ok(()), hidden.verify());
+
+    // a single leaf is padded into a tree of two leaves
+    assert_eq!(Ok(()), Inputs::new(&leaves[..1], &[0]).unwrap().verify());
+}
+
+#[test]
+fn merkle_inputs_new_invalid() {
+    let leaves = build_leaves(5);
+    assert_eq!(Err(MerkleTreeError::TooFewLeaves(1, 0)), Inputs::new(&[], &[]));
+    assert_eq!(Err(MerkleTreeError::LeafIndexOutOfBounds(5, 5)), Inputs::new(&leaves, &[5]));
+    assert_eq!(Err(MerkleTreeError::DuplicateLeafIndex), Inputs::new(&leaves, &[1, 1]));
+}
+
+#[test]
+fn merkle_inputs_verify_tampered() {
+    let leaves = build_leaves(6);
+    let inputs = Inputs::new(&leaves, &[2, 5]).unwrap();
+    let root = *inputs.root();
+    let proof = || inputs.proof().map(clone_proof);
+
+    // a revealed value which differs from the committed one
+    let mut revealed = inputs.revealed().to_vec();
+    revealed[1].1[0] += BaseElement::ONE;
+    let tampered = Inputs::from_parts(root, 6, revealed, proof());
+    assert_eq!(Err(PublicInputsError::InvalidMerkleProof), tampered.verify());
+
+    // a value revealed at a different index
+    let mut revealed = inputs.revealed().to_vec();
+    revealed[1].0 = 4;
+    let tampered = Inputs::from_parts(root, 6, revealed, proof());
+    assert_eq!(Err(PublicInputsError::InvalidMerkleProof), tampered.verify());
+
+    // a revealed value without a proof
+    let tampered = Inputs::from_parts(root, 6, inputs.revealed().to_vec(), None);
+    assert_eq!(Err(PublicInputsError::InvalidMerkleProof), tampered.verify());
+
+    // a leaf revealed beyond the committed leaves (but within the padded tree)
+    let padded = Inputs::new(&build_leaves(8), &[6]).unwrap();
+    let tampered = Inputs::from_parts(*padded.root(), 6, padded.revealed().to_vec(), None);
+    assert_eq!(Err(PublicInputsError::RevealedLeafOutOfBounds(6, 6)), tampered.verify());
+
+    // a different number of leaves implying a different tree depth
+    let tampered = Inputs::from_parts(root, 12, inputs.revealed().to_vec(), proof());
+    assert_eq!(Err(PublicInputsError::InvalidMerkleProof), tampered.verify());
+
+    // a different root
+    let other = Inputs::new(&build_leaves(7), &[]).unwrap();
+    let tampered = Inputs::from_parts(*other.root(), 6, inputs.revealed().to_vec(), proof());
+    assert_eq!(Err(PublicInputsError::InvalidMerkleProof), tampered.verify());
+}
+
+#[test]
+fn merkle_inputs_to_elements() {
+    let leaves = build_leaves(4);
+    let inputs = Inputs::new(&leaves, &[1]).unwrap();
+    let elements: Vec<BaseElement> = inputs.to_elements();
+
+    // 8 elements for the root, followed by the number of leaves, and the index, length, and
+    // values of the revealed leaf
+    assert_eq!(8 + 1 + 2 + leaves[1].len(), elements.len());
+    assert_eq!(BaseElement::new(4), elements[8]);
+    assert_eq!(BaseElement::new(1), elements[9]);
+    assert_eq!(BaseElement::new(leaves[1].len() as u128), elements[10]);
+    assert_eq!(&leaves[1], &elements[11..]);
+
+    // hidden leaves do not affect the elements apart from the root
+    let mut other_leaves = leaves.clone();
+    other_leaves[2][0] += BaseElement::ONE;
+    let other: Vec<BaseElement> = Inputs::new(&other_leaves, &[1]).unwrap().to_elements();
+    assert_ne!(elements[..8], other[..8]);
+    assert_eq!(elements[8..], other[8..]);
+}
+
+#[test]
+fn merkle_inputs_serialization() {
+    let leaves = build_leaves(5);
+    for indexes in [&[][..], &[4], &[0, 2, 3]] {
+        let inputs = Inputs::new(&leaves, indexes).unwrap();
+        let bytes = inputs.to_bytes();
+        let parsed = Inputs::read_from_bytes(&bytes).unwrap();
+        assert_eq!(inputs, parsed);
+        assert_eq!(Ok(()), parsed.verify());
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+fn build_leaves(num_leaves: usize) -> Vec<Vec<BaseElement>> {
+    (0..num_leaves)
+        .map(|i| (0..=i % 3).map(|j| BaseElement::new((i * 10 + j) as u128)).collect())
+        .collect()
+}
+
+fn clone_proof<H: ElementHasher>(proof: &BatchMerkleProof<H>) -> BatchMerkleProof<H> {
+    BatchMerkleProof {
+        leaves: proof.leaves.clone(),
+        nodes: proof.nodes.clone(),
+        depth: proof.depth,
+    }
+}
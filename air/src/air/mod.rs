@@ -3,9 +3,9 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use crate::ProofOptions;
+use crate::{errors::PublicInputsError, ProofOptions};
 use crypto::{RandomCoin, RandomCoinError};
-use math::{fft, ExtensibleField, ExtensionOf, FieldElement, StarkField, ToElements};
+use math::{fft, polynom, ExtensibleField, ExtensionOf, FieldElement, StarkField, ToElements};
 use utils::collections::{BTreeMap, Vec};
 
 mod trace_info;
@@ -20,6 +20,9 @@ pub use assertions::Assertion;
 mod boundary;
 pub use boundary::{BoundaryConstraint, BoundaryConstraintGroup, BoundaryConstraints};
 
+mod merkle_inputs;
+pub use merkle_inputs::MerkleInputs;
+
 mod transition;
 pub use transition::{EvaluationFrame, TransitionConstraintDegree, TransitionConstraints};
 
@@ -31,6 +34,12 @@ pub use coefficients::{
 mod divisor;
 pub use divisor::ConstraintDivisor;
 
+mod aggregate;
+pub use aggregate::{AggregateAir, AggregateInputs};
+
+mod component;
+pub use component::{Component, ComponentId, ComponentLayout, TraceAllocator};
+
 #[cfg(test)]
 mod tests;
 
@@ -200,6 +209,9 @@ pub trait Air: Send + Sync {
 
     /// Evaluates transition constraints over the specified evaluation frame.
     ///
+    /// The frame contains a row for every out-of-domain offset of this AIR (see
+    /// [AirContext::set_ood_offsets()]); by default, these are the current and the next rows.
+    ///
     /// The evaluations should be written into the `results` slice in the same order as the
     /// the order of transition constraint degree descriptors used to instantiate [AirContext]
     /// for this AIR. Thus, the length of the `result` slice will equal to the number of
@@ -285,6 +297,33 @@ pub trait Air: Send + Sync {
     // PROVIDED METHODS
     // --------------------------------------------------------------------------------------------
 
+    /// Returns public inputs with values which can be derived from other public inputs
+    /// recomputed from these inputs.
+    ///
+    /// This method is invoked by both the prover and the verifier before public inputs are used
+    /// to seed the public coin or to instantiate the AIR. Thus, derived values (e.g., a digest of
+    /// a list of values included in public inputs) do not need to be computed by the caller, and
+    /// the prover and the verifier always derive them in the same way.
+    ///
+    /// The default implementation returns `pub_inputs` unchanged.
+    fn derive_public_inputs(pub_inputs: Self::PublicInputs) -> Self::PublicInputs {
+        pub_inputs
+    }
+
+    /// Checks that public inputs are well-formed before they are used to verify a proof.
+    ///
+    /// This method is invoked by the verifier right after public inputs are derived via
+    /// [derive_public_inputs()](Air::derive_public_inputs), and before they are used to seed the
+    /// public coin or to instantiate the AIR. It is intended for checks which cannot be expressed
+    /// as constraints; for example, for public inputs which contain [MerkleInputs], this method
+    /// should call [MerkleInputs::verify()] so that only authenticated leaves are used in
+    /// constraint evaluation.
+    ///
+    /// The default implementation accepts all public inputs.
+    fn validate_public_inputs(_pub_inputs: &Self::PublicInputs) -> Result<(), PublicInputsError> {
+        Ok(())
+    }
+
     /// Returns values for all periodic columns used in the computation.
     ///
     /// These values will be used to compute column values at specific states of the computation
@@ -367,6 +406,142 @@ pub trait Air: Send + Sync {
         )
     }
 
+    /// Returns values of all periodic columns at the specified point `x`.
+    ///
+    /// The values are computed by evaluating polynomials returned from the
+    /// [get_periodic_column_polys()](Air::get_periodic_column_polys) method; for a point in the
+    /// trace domain, these are the values of periodic columns at the corresponding step.
+    fn get_periodic_column_values_at<E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        x: E,
+    ) -> Vec<E> {
+        self.get_periodic_column_polys()
+            .iter()
+            .map(|poly| {
+                let num_cycles = self.trace_length() / poly.len();
+                let x = x.exp_vartime((num_cycles as u32).into());
+                polynom::eval(poly, x)
+            })
+            .collect()
+    }
+
+    /// Evaluates the constraint composition polynomial at the specified point `z`.
+    ///
+    /// This is the same evaluation which the verifier performs at the out-of-domain point to
+    /// check consistency between the trace and constraint composition polynomials: transition
+    /// constraints are evaluated over `main_frame` and `aux_frame` (if any), divided by their
+    /// divisor, and combined with boundary constraints evaluated over the current rows of the
+    /// frames using `composition_coefficients`. Values of periodic columns at `z` can be computed
+    /// via [get_periodic_column_values_at()](Air::get_periodic_column_values_at).
+    ///
+    /// Exposing this evaluation enables external tools (e.g., recursive verifier witness
+    /// generators and differential testers) to reuse the exact evaluation logic of the verifier.
+    fn evaluate_constraints_at<E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        z: E,
+        main_frame: &EvaluationFrame<E>,
+        aux_frame: Option<&EvaluationFrame<E>>,
+        periodic_values: &[E],
+        aux_rand_elements: &AuxTraceRandElements<E>,
+        composition_coefficients: &ConstraintCompositionCoefficients<E>,
+    ) -> E {
+        // evaluate transition constraints for the main trace segment and auxiliary trace
+        // segments (if any)
+        let t_constraints = self.get_transition_constraints(&composition_coefficients.transition);
+        let mut t_evaluations1 = E::zeroed_vector(t_constraints.num_main_constraints());
+        self.evaluate_transition(main_frame, periodic_values, &mut t_evaluations1);
+
+        let mut t_evaluations2 = E::zeroed_vector(t_constraints.num_aux_constraints());
+        if let Some(aux_frame) = aux_frame {
+            self.evaluate_aux_transition(
+                main_frame,
+                aux_frame,
+                periodic_values,
+                aux_rand_elements,
+                &mut t_evaluations2,
+            );
+        }
+
+        // merge all transition constraint evaluations into a single value; this also divides the
+        // result by the divisor of transition constraints
+        let mut result =
+            t_constraints.combine_evaluations::<E>(&t_evaluations1, &t_evaluations2, z);
+
+        // evaluate boundary constraint groups (each group has a distinct divisor) and add their
+        // combinations to the result
+        let b_constraints =
+            self.get_boundary_constraints(aux_rand_elements, &composition_coefficients.boundary);
+        for group in b_constraints.main_constraints().iter() {
+            result += group.evaluate_at(main_frame.current(), z);
+        }
+        if let Some(aux_frame) = aux_frame {
+            for group in b_constraints.aux_constraints().iter() {
+                result += group.evaluate_at(aux_frame.current(), z);
+            }
+        }
+
+        result
+    }
+
+    // CONSTRAINT DIVISORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the divisor shared by all transition constraints of this AIR.
+    ///
+    /// The divisor specifies that transition constraints must hold on all steps of the execution
+    /// trace except for the last [num_transition_exemptions()](AirContext::num_transition_exemptions)
+    /// steps (see [ConstraintDivisor::from_transition()]).
+    fn get_transition_divisor(&self) -> ConstraintDivisor<Self::BaseField> {
+        ConstraintDivisor::from_transition(
+            self.trace_length(),
+            self.context().num_transition_exemptions(),
+        )
+    }
+
+    /// Returns all distinct constraint divisors of this AIR.
+    ///
+    /// The transition constraint divisor is always the first one in the list. It is followed by
+    /// divisors of boundary constraint groups against the main trace segment, and then by
+    /// divisors of boundary constraint groups against auxiliary trace segments which are not
+    /// already in the list. This is the order in which the prover evaluates divided constraints
+    /// over the constraint evaluation domain; provers other than the default one can use this to
+    /// make sure they divide constraint evaluations by the same divisors.
+    fn get_divisors<E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        aux_rand_elements: &AuxTraceRandElements<E>,
+    ) -> Vec<ConstraintDivisor<Self::BaseField>> {
+        // composition coefficients do not affect divisors, and thus, any values can be used here
+        let coefficients = vec![E::ZERO; self.context().num_assertions()];
+        let b_constraints = self.get_boundary_constraints(aux_rand_elements, &coefficients);
+
+        let mut divisors = vec![self.get_transition_divisor()];
+        let boundary_divisors = b_constraints
+            .main_constraints()
+            .iter()
+            .map(|group| group.divisor())
+            .chain(b_constraints.aux_constraints().iter().map(|group| group.divisor()));
+        for divisor in boundary_divisors {
+            if !divisors.contains(divisor) {
+                divisors.push(divisor.clone());
+            }
+        }
+        divisors
+    }
+
+    /// Evaluates all constraint divisors of this AIR at the specified point `x`.
+    ///
+    /// The divisors are evaluated in the order returned by [get_divisors()](Air::get_divisors).
+    fn evaluate_divisors_at<E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        aux_rand_elements: &AuxTraceRandElements<E>,
+        x: E,
+    ) -> Vec<E> {
+        self.get_divisors(aux_rand_elements)
+            .iter()
+            .map(|divisor| divisor.evaluate_at(x))
+            .collect()
+    }
+
     // PUBLIC ACCESSORS
     // --------------------------------------------------------------------------------------------
 
@@ -431,6 +606,25 @@ pub trait Air: Send + Sync {
         self.context().ce_domain_size()
     }
 
+    /// Returns the generator of the constraint evaluation domain for an instance of the
+    /// computation described by this AIR.
+    ///
+    /// The generator is the $n$th root of unity where $n$ is the size of the constraint
+    /// evaluation domain.
+    fn ce_domain_generator(&self) -> Self::BaseField {
+        Self::BaseField::get_root_of_unity(self.ce_domain_size().ilog2())
+    }
+
+    /// Returns the element of the constraint evaluation domain at the specified step.
+    ///
+    /// Constraints are evaluated over a coset of the subgroup generated by
+    /// [ce_domain_generator()](Air::ce_domain_generator), shifted by the
+    /// [domain_offset()](Air::domain_offset). Thus, the element at step $i$ is $s \cdot g^i$,
+    /// where $g$ is the generator and $s$ is the domain offset.
+    fn get_ce_domain_element_at(&self, step: usize) -> Self::BaseField {
+        self.ce_domain_generator().exp((step as u64).into()) * self.domain_offset()
+    }
+
     /// Returns low-degree extension domain blowup factor for the computation described by this
     /// AIR. This is guaranteed to be a power of two, and is always either equal to or greater
     /// than ce_blowup_factor.
@@ -539,4 +733,32 @@ pub trait Air: Send + Sync {
             constraints: c_coefficients,
         })
     }
+
+    // QUERY POSITIONS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a set of positions in the LDE domain at which the prover opens trace and
+    /// constraint commitments.
+    ///
+    /// [ProofOptions::num_queries()] positions are drawn from the provided public coin after it
+    /// is reseeded with the proof-of-work `nonce`, using the query sampling strategy specified in
+    /// the proof options. Duplicate positions are removed, and the remaining positions are
+    /// returned in ascending order; when queries are normalized, positions are redrawn as
+    /// described in [ProofOptions::draw_query_positions_with()]. Since the prover and the
+    /// verifier both derive query positions via this method, the layout of the openings included
+    /// in a proof is the same on both sides.
+    ///
+    /// The default implementation supports only the built-in strategies; AIRs which use a
+    /// [custom](crate::QuerySampling::Custom) strategy should override this method and draw the
+    /// positions via [ProofOptions::draw_query_positions_with()] with their own sampler.
+    fn get_query_positions<R>(
+        &self,
+        public_coin: &mut R,
+        nonce: u64,
+    ) -> Result<Vec<usize>, RandomCoinError>
+    where
+        R: RandomCoin<BaseField = Self::BaseField>,
+    {
+        self.options().draw_query_positions(public_coin, self.lde_domain_size(), nonce)
+    }
 }
@@ -4,12 +4,14 @@
 // LICENSE file in the root directory of this source tree.
 
 use super::{
-    Air, AirContext, Assertion, EvaluationFrame, ProofOptions, TraceInfo,
-    TransitionConstraintDegree,
+    AggregateAir, AggregateInputs, Air, AirContext, Assertion, Component, ConstraintDivisor,
+    EvaluationFrame, ProofOptions, TraceAllocator, TraceInfo, TransitionConstraintDegree,
 };
-use crate::{AuxTraceRandElements, FieldExtension};
+use crate::{AuxTraceRandElements, ConstraintCompositionCoefficients, FieldExtension};
 use crypto::{hashers::Blake3_256, DefaultRandomCoin, RandomCoin};
-use math::{fields::f64::BaseElement, get_power_series, polynom, FieldElement, StarkField};
+use math::{
+    fields::f64::BaseElement, get_power_series, polynom, FieldElement, StarkField, ToElements,
+};
 use utils::collections::{BTreeMap, Vec};
 
 // PERIODIC COLUMNS
@@ -55,6 +57,104 @@ fn get_periodic_column_polys_num_values_not_power_of_two() {
 
 // TODO
 
+// CONSTRAINT EVALUATION
+// ================================================================================================
+
+#[test]
+fn evaluate_constraints_at() {
+    let air = CounterAir::new(
+        TraceInfo::new(1, 16),
+        BaseElement::new(3),
+        ProofOptions::new(32, 8, 0, FieldExtension::None, 4, 31),
+    );
+    let z = BaseElement::new(1234);
+
+    // the periodic column of the counter contains only ones
+    let periodic_values = air.get_periodic_column_values_at(z);
+    assert_eq!(vec![BaseElement::ONE], periodic_values);
+
+    let frame = EvaluationFrame::from_rows(vec![BaseElement::new(10)], vec![BaseElement::new(15)]);
+    let coefficients = ConstraintCompositionCoefficients {
+        transition: vec![BaseElement::new(2)],
+        boundary: vec![BaseElement::new(5)],
+    };
+    let result = air.evaluate_constraints_at(
+        z,
+        &frame,
+        None,
+        &periodic_values,
+        &AuxTraceRandElements::new(),
+        &coefficients,
+    );
+
+    // transition constraint is divided by (z^n - 1) / (z - g^(n - 1)), and the assertion against
+    // the first step is divided by (z - 1)
+    let g = BaseElement::get_root_of_unity(4);
+    let transition_divisor = (z.exp(16) - BaseElement::ONE) / (z - g.exp(15));
+    let expected = BaseElement::new(4 * 2) / transition_divisor
+        + BaseElement::new(7 * 5) / (z - BaseElement::ONE);
+    assert_eq!(expected, result);
+}
+
+// CONSTRAINT DIVISORS
+// ================================================================================================
+
+#[test]
+fn ce_domain_accessors() {
+    let air = MockAir::with_trace_length(16);
+    // transition constraints of degree 2 require the constraint evaluation domain to be twice as
+    // large as the trace domain
+    assert_eq!(32, air.ce_domain_size());
+
+    let g = air.ce_domain_generator();
+    assert_eq!(BaseElement::get_root_of_unity(5), g);
+    assert_eq!(BaseElement::ONE, g.exp(32));
+    assert_ne!(BaseElement::ONE, g.exp(16));
+
+    let offset = air.domain_offset();
+    assert_eq!(offset, air.get_ce_domain_element_at(0));
+    assert_eq!(g.exp(5) * offset, air.get_ce_domain_element_at(5));
+    assert_eq!(offset, air.get_ce_domain_element_at(32));
+}
+
+#[test]
+fn get_divisors() {
+    let trace_length = 16;
+    let assertions = vec![
+        Assertion::single(0, 0, BaseElement::ONE),
+        Assertion::periodic(1, 1, 8, BaseElement::ONE),
+        Assertion::single(2, 0, BaseElement::ONE),
+        Assertion::single(3, 15, BaseElement::ONE),
+    ];
+    let air = MockAir::with_assertions(assertions, trace_length);
+    let aux_rand_elements = AuxTraceRandElements::<BaseElement>::new();
+
+    // the transition divisor is first, and is followed by a single divisor per boundary
+    // constraint group
+    let transition_divisor = air.get_transition_divisor();
+    assert_eq!(ConstraintDivisor::from_transition(trace_length, 1), transition_divisor);
+
+    let divisors = air.get_divisors(&aux_rand_elements);
+    let b_constraints = air.get_boundary_constraints(&aux_rand_elements, &[BaseElement::ZERO; 4]);
+    assert_eq!(1 + b_constraints.main_constraints().len(), divisors.len());
+    assert_eq!(transition_divisor, divisors[0]);
+    for (group, divisor) in b_constraints.main_constraints().iter().zip(&divisors[1..]) {
+        assert_eq!(group.divisor(), divisor);
+    }
+
+    // assertions against the first step of columns 0 and 2 share a divisor
+    assert_eq!(4, divisors.len());
+
+    // divisors are evaluated in the same order
+    let x = BaseElement::new(1234);
+    let evaluations = air.evaluate_divisors_at(&aux_rand_elements, x);
+    let g = air.trace_domain_generator();
+    assert_eq!((x.exp(16) - BaseElement::ONE) / (x - g.exp(15)), evaluations[0]);
+    for (divisor, evaluation) in divisors.iter().zip(evaluations) {
+        assert_eq!(divisor.evaluate_at(x), evaluation);
+    }
+}
+
 // BOUNDARY CONSTRAINTS
 // ================================================================================================
 
@@ -99,7 +199,7 @@ fn get_boundary_constraints() {
     expected_cc.insert(5, prng.draw().unwrap());
 
     // get boundary constraints from AIR, and sort constraint groups so that the order
-    // is stable; the original order is just by degree_adjustment
+    // is stable; the original order is by stride and first step of the assertions
     let mut prng = build_prng();
     let coefficients = (0..8).map(|_| prng.draw().unwrap()).collect::<Vec<BaseElement>>();
     let constraints = air.get_boundary_constraints(&AuxTraceRandElements::new(), &coefficients);
@@ -186,6 +286,330 @@ fn get_boundary_constraints() {
     assert_eq!(expected_cc[&5], constraint.cc().clone());
 }
 
+// QUERY POSITIONS
+// ================================================================================================
+
+#[test]
+fn get_query_positions() {
+    let air = MockAir::with_trace_length(16);
+    let positions = air.get_query_positions(&mut build_prng(), 0).unwrap();
+
+    // positions must be unique, sorted, and within the LDE domain
+    assert!(!positions.is_empty());
+    assert!(positions.len() <= air.options().num_queries());
+    assert!(positions.windows(2).all(|w| w[0] < w[1]));
+    assert!(positions.iter().all(|&p| p < air.lde_domain_size()));
+
+    // drawing positions from the same coin state must result in the same positions
+    assert_eq!(positions, air.get_query_positions(&mut build_prng(), 0).unwrap());
+}
+
+// CONSTRAINT EVALUATION DOMAIN
+// ================================================================================================
+
+#[test]
+fn ce_blowup_factor_from_composition_degree() {
+    // the estimate based on the number of periodic columns requires blowup factor 4, but the
+    // composition polynomial has degree 2 * 63 + 2 * 32 - 63 = 127 and thus, fits into 128 rows
+    let degree = TransitionConstraintDegree::with_cycles(2, vec![2, 2]);
+    assert_eq!(4, degree.min_blowup_factor());
+
+    let options = ProofOptions::new(32, 2, 0, FieldExtension::None, 4, 31);
+    let context = AirContext::<BaseElement>::new(TraceInfo::new(1, 64), vec![degree], 1, options);
+    assert_eq!(128, context.ce_domain_size());
+    assert_eq!(2, context.num_constraint_composition_columns());
+}
+
+#[test]
+fn ce_blowup_factor_with_exemptions() {
+    // with 3 exemptions, the composition polynomial has degree 3 * 63 - 61 = 128 and thus, needs
+    // a domain of 256 rows and 3 columns
+    let options = ProofOptions::new(32, 4, 0, FieldExtension::None, 4, 31);
+    let degrees = vec![TransitionConstraintDegree::new(3)];
+    let context = AirContext::<BaseElement>::new(TraceInfo::new(1, 64), degrees, 1, options);
+    assert_eq!(128, context.ce_domain_size());
+    assert_eq!(2, context.num_constraint_composition_columns());
+
+    let context = context.set_num_transition_exemptions(3);
+    assert_eq!(256, context.ce_domain_size());
+    assert_eq!(3, context.num_constraint_composition_columns());
+}
+
+#[test]
+#[should_panic(expected = "number of transition exemptions cannot exceed: 2, but was 3")]
+fn ce_blowup_factor_with_exemptions_too_large() {
+    let options = ProofOptions::new(32, 2, 0, FieldExtension::None, 4, 31);
+    let degrees = vec![TransitionConstraintDegree::new(3)];
+    let _ = AirContext::<BaseElement>::new(TraceInfo::new(1, 64), degrees, 1, options)
+        .set_num_transition_exemptions(3);
+}
+
+// AGGREGATION
+// ================================================================================================
+
+#[test]
+fn aggregate_air() {
+    let inputs = vec![BaseElement::new(3), BaseElement::new(5), BaseElement::new(7)];
+    let air = AggregateAir::<CounterAir>::new(
+        TraceInfo::new(3, 16),
+        AggregateInputs::new(inputs),
+        ProofOptions::new(32, 8, 0, FieldExtension::None, 4, 31),
+    );
+    assert_eq!(3, air.num_instances());
+    assert_eq!(1, air.instance_width());
+    assert_eq!(3, air.context().num_main_transition_constraints());
+    assert_eq!(3, air.context().num_assertions());
+
+    // assertions of each instance are shifted to the columns of that instance
+    let expected = vec![
+        Assertion::single(0, 0, BaseElement::new(3)),
+        Assertion::single(1, 0, BaseElement::new(5)),
+        Assertion::single(2, 0, BaseElement::new(7)),
+    ];
+    assert_eq!(expected, air.get_assertions());
+
+    // periodic columns of all instances are concatenated
+    assert_eq!(3, air.get_periodic_column_values().len());
+
+    // transition constraints of each instance are evaluated against its own columns
+    let current = vec![BaseElement::new(1), BaseElement::new(2), BaseElement::new(3)];
+    let next = vec![BaseElement::new(2), BaseElement::new(5), BaseElement::new(4)];
+    let frame = EvaluationFrame::from_rows(current, next);
+    let periodic_values = vec![BaseElement::ONE; 3];
+    let mut result = vec![BaseElement::ZERO; 3];
+    air.evaluate_transition(&frame, &periodic_values, &mut result);
+    assert_eq!(vec![BaseElement::ZERO, BaseElement::new(2), BaseElement::ZERO], result);
+}
+
+#[test]
+#[should_panic(expected = "trace width 4 is not divisible by the number of instances 3")]
+fn aggregate_air_invalid_width() {
+    let inputs = vec![BaseElement::new(3), BaseElement::new(5), BaseElement::new(7)];
+    let _ = AggregateAir::<CounterAir>::new(
+        TraceInfo::new(4, 16),
+        AggregateInputs::new(inputs),
+        ProofOptions::new(32, 8, 0, FieldExtension::None, 4, 31),
+    );
+}
+
+// COMPONENTS
+// ================================================================================================
+
+#[test]
+fn component_layout() {
+    let counter = CounterComponent {
+        height: 16,
+        start: BaseElement::new(3),
+    };
+    let fib = FibComponent { height: 8 };
+    let short_counter = CounterComponent {
+        height: 4,
+        start: BaseElement::new(5),
+    };
+
+    let mut allocator = TraceAllocator::new();
+    let a = allocator.place(&counter);
+    let b = allocator.stack(a, &short_counter);
+    let c = allocator.place(&fib);
+    let d = allocator.stack(c, &short_counter);
+    let layout = allocator.build(32);
+
+    // the taller component is placed first regardless of the order of placement
+    assert_eq!(7, layout.trace_width());
+    assert_eq!(4, layout.num_selectors());
+    assert_eq!((0..1, 0..16), (layout.columns(a), layout.rows(a)));
+    assert_eq!((0..1, 16..20), (layout.columns(b), layout.rows(b)));
+    assert_eq!((3..5, 0..8), (layout.columns(c), layout.rows(c)));
+    assert_eq!((3..4, 8..12), (layout.columns(d), layout.rows(d)));
+
+    // constraints of components are multiplied by selector flags
+    let expected = vec![
+        TransitionConstraintDegree::with_cycles(2, vec![2]),
+        TransitionConstraintDegree::with_cycles(3, vec![2]),
+        TransitionConstraintDegree::new(2),
+        TransitionConstraintDegree::new(2),
+        TransitionConstraintDegree::with_cycles(3, vec![2]),
+    ];
+    let degrees = layout.transition_constraint_degrees();
+    assert_eq!(expected, degrees[..5]);
+    assert_eq!(vec![TransitionConstraintDegree::new(2); 8], degrees[5..]);
+    assert_eq!(3, layout.get_periodic_column_values().len());
+
+    // assertions of components are shifted to their columns and rows; sequence assertions of
+    // components shorter than the trace are expanded into single assertions
+    let assertions = layout.get_assertions();
+    assert_eq!(Assertion::single(0, 8, BaseElement::new(11)), assertions[1]);
+    assert_eq!(Assertion::single(0, 16, BaseElement::new(5)), assertions[2]);
+    assert_eq!(Assertion::single(4, 0, BaseElement::ONE), assertions[5]);
+    assert_eq!(Assertion::single(3, 10, BaseElement::new(7)), assertions[7]);
+    assert_eq!(Assertion::single(1, 15, BaseElement::ZERO), assertions[8]);
+    assert_eq!(Assertion::single(1, 16, BaseElement::ONE), assertions[9]);
+    assert_eq!(16, assertions.len());
+
+    // a trace filled in by the components satisfies all constraints
+    let mut columns = layout.new_trace_columns();
+    layout.fill_component(a, &counter, &mut columns);
+    layout.fill_component(b, &short_counter, &mut columns);
+    layout.fill_component(c, &fib, &mut columns);
+    layout.fill_component(d, &short_counter, &mut columns);
+    for assertion in assertions.iter() {
+        assertion.apply(32, |step, value| {
+            assert_eq!(value, columns[assertion.column()][step]);
+        });
+    }
+    let components = [(a, &counter), (b, &short_counter), (d, &short_counter)];
+    let evaluate = |columns: &[Vec<BaseElement>], step: usize| {
+        let current = columns.iter().map(|column| column[step]).collect::<Vec<_>>();
+        let next = columns.iter().map(|column| column[step + 1]).collect::<Vec<_>>();
+        let frame = EvaluationFrame::from_rows(current, next);
+        let periodic_values = vec![BaseElement::ONE; 3];
+        let mut result = vec![BaseElement::ZERO; degrees.len()];
+        for &(id, component) in components.iter() {
+            layout.evaluate_component(id, component, &frame, &periodic_values, &mut result);
+        }
+        layout.evaluate_component(c, &fib, &frame, &periodic_values, &mut result);
+        layout.evaluate_selectors(&frame, &mut result);
+        result
+    };
+    for step in 0..31 {
+        assert!(evaluate(&columns, step).iter().all(|&value| value == BaseElement::ZERO));
+    }
+
+    // changing a value within a component violates the constraints of that component
+    columns[3][9] += BaseElement::ONE;
+    assert_ne!(BaseElement::ZERO, evaluate(&columns, 8)[4]);
+    columns[3][9] -= BaseElement::ONE;
+
+    // values in rows not allocated to any component are not constrained
+    columns[3][13] += BaseElement::ONE;
+    assert!(evaluate(&columns, 12).iter().all(|&value| value == BaseElement::ZERO));
+}
+
+#[test]
+#[should_panic(
+    expected = "components with a total height of 20 do not fit into a trace of 16 rows"
+)]
+fn component_layout_too_tall() {
+    let counter = CounterComponent {
+        height: 16,
+        start: BaseElement::ONE,
+    };
+    let short_counter = CounterComponent {
+        height: 4,
+        start: BaseElement::ONE,
+    };
+    let mut allocator = TraceAllocator::new();
+    let a = allocator.place(&counter);
+    allocator.stack(a, &short_counter);
+    let _ = allocator.build(16);
+}
+
+// PUBLIC INPUTS
+// ================================================================================================
+
+#[test]
+fn derive_public_inputs() {
+    // AIRs which do not derive public inputs return them unchanged
+    assert_eq!(BaseElement::new(3), CounterAir::derive_public_inputs(BaseElement::new(3)));
+
+    let inputs = SumInputs {
+        values: vec![BaseElement::new(3), BaseElement::new(5), BaseElement::new(7)],
+        sum: BaseElement::ZERO,
+    };
+    let derived = SumAir::derive_public_inputs(inputs.clone());
+    assert_eq!(inputs.values, derived.values);
+    assert_eq!(BaseElement::new(15), derived.sum);
+
+    // public inputs of aggregated instances are derived independently
+    let other = SumInputs {
+        values: vec![BaseElement::new(11)],
+        sum: BaseElement::new(42),
+    };
+    let derived =
+        AggregateAir::<SumAir>::derive_public_inputs(AggregateInputs::new(vec![inputs, other]));
+    assert_eq!(BaseElement::new(15), derived.inputs()[0].sum);
+    assert_eq!(BaseElement::new(11), derived.inputs()[1].sum);
+}
+
+// OUT-OF-DOMAIN OFFSETS
+// ================================================================================================
+
+#[test]
+fn ood_offsets() {
+    // by default, trace polynomials are opened at z and z * g
+    let context = build_context::<BaseElement>(16, 2, 1);
+    assert_eq!(&[0, 1], context.ood_offsets());
+    assert_eq!(2, context.num_ood_points());
+
+    let context = context.set_ood_offsets(vec![0, 1, 8, 15]);
+    assert_eq!(&[0, 1, 8, 15], context.ood_offsets());
+    assert_eq!(4, context.num_ood_points());
+}
+
+#[test]
+fn evaluation_frame_with_offsets() {
+    // by default, a frame contains the current and the next rows
+    let frame = EvaluationFrame::<BaseElement>::new(2);
+    assert_eq!(2, frame.num_rows());
+    assert_eq!(&[0, 1], frame.offsets());
+
+    // a frame contains a row for every offset
+    let rows = (0..4u32)
+        .map(|i| vec![BaseElement::from(i), BaseElement::from(i + 10)])
+        .collect();
+    let frame = EvaluationFrame::from_offset_rows(&[0, 1, 8, 15], rows);
+    assert_eq!(4, frame.num_rows());
+    assert_eq!(&[0, 1, 8, 15], frame.offsets());
+    assert_eq!(frame.current(), frame.row(0));
+    assert_eq!(frame.next(), frame.row(1));
+    assert_eq!(&[BaseElement::from(3u32), BaseElement::from(13u32)], frame.row(3));
+}
+
+#[test]
+#[should_panic(expected = "number of rows must match the number of offsets")]
+fn evaluation_frame_with_missing_rows() {
+    let rows = vec![vec![BaseElement::ONE]; 3];
+    let _ = EvaluationFrame::from_offset_rows(&[0, 1, 8, 15], rows);
+}
+
+#[test]
+#[should_panic(expected = "out-of-domain offsets must start with 0 and 1, but were [0, 2]")]
+fn ood_offsets_without_next_row() {
+    let _ = build_context::<BaseElement>(16, 2, 1).set_ood_offsets(vec![0, 2]);
+}
+
+#[test]
+#[should_panic(expected = "out-of-domain offset must be smaller than trace length 16, but was 16")]
+fn ood_offsets_too_large() {
+    let _ = build_context::<BaseElement>(16, 2, 1).set_ood_offsets(vec![0, 1, 16]);
+}
+
+#[test]
+#[should_panic(expected = "out-of-domain offset 3 is specified more than once")]
+fn ood_offsets_duplicate() {
+    let _ = build_context::<BaseElement>(16, 2, 1).set_ood_offsets(vec![0, 1, 3, 3]);
+}
+
+// PREPROCESSED COLUMNS
+// ================================================================================================
+
+#[test]
+fn num_preprocessed_columns() {
+    let context = build_context::<BaseElement>(16, 4, 1);
+    assert_eq!(0, context.num_preprocessed_columns());
+
+    let context = context.set_num_preprocessed_columns(3);
+    assert_eq!(3, context.num_preprocessed_columns());
+}
+
+#[test]
+#[should_panic(
+    expected = "number of preprocessed columns must be smaller than main trace width 4, but was 4"
+)]
+fn num_preprocessed_columns_too_large() {
+    let _ = build_context::<BaseElement>(16, 4, 1).set_num_preprocessed_columns(4);
+}
+
 // MOCK AIR
 // ================================================================================================
 
@@ -209,6 +633,14 @@ impl MockAir {
         result
     }
 
+    pub fn with_trace_length(trace_length: usize) -> Self {
+        Self::new(
+            TraceInfo::with_meta(4, trace_length, vec![1]),
+            (),
+            ProofOptions::new(32, 8, 0, FieldExtension::None, 4, 31),
+        )
+    }
+
     pub fn with_assertions(assertions: Vec<Assertion<BaseElement>>, trace_length: usize) -> Self {
         let mut result = Self::new(
             TraceInfo::with_meta(4, trace_length, vec![assertions.len() as u8]),
@@ -255,6 +687,197 @@ impl Air for MockAir {
     }
 }
 
+/// A single-column AIR which increments the column by the value of a periodic column at every
+/// step, starting with the value provided via public inputs.
+struct CounterAir {
+    context: AirContext<BaseElement>,
+    start: BaseElement,
+}
+
+impl Air for CounterAir {
+    type BaseField = BaseElement;
+    type PublicInputs = BaseElement;
+
+    fn new(trace_info: TraceInfo, start: BaseElement, options: ProofOptions) -> Self {
+        let degrees = vec![TransitionConstraintDegree::with_cycles(1, vec![2])];
+        CounterAir {
+            context: AirContext::new(trace_info, degrees, 1, options),
+            start,
+        }
+    }
+
+    fn context(&self) -> &AirContext<Self::BaseField> {
+        &self.context
+    }
+
+    fn get_periodic_column_values(&self) -> Vec<Vec<Self::BaseField>> {
+        vec![vec![BaseElement::ONE, BaseElement::ONE]]
+    }
+
+    fn get_assertions(&self) -> Vec<Assertion<Self::BaseField>> {
+        vec![Assertion::single(0, 0, self.start)]
+    }
+
+    fn evaluate_transition<E: FieldElement + From<Self::BaseField>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        result[0] = frame.next()[0] - frame.current()[0] - periodic_values[0];
+    }
+}
+
+/// Public inputs of [SumAir]: a list of values and the sum of these values.
+#[derive(Clone)]
+struct SumInputs {
+    values: Vec<BaseElement>,
+    sum: BaseElement,
+}
+
+impl ToElements<BaseElement> for SumInputs {
+    fn to_elements(&self) -> Vec<BaseElement> {
+        let mut result = self.values.clone();
+        result.push(self.sum);
+        result
+    }
+}
+
+/// A single-column AIR which asserts that the last value of the column is equal to the sum of
+/// values provided via public inputs; the sum is derived from the values.
+struct SumAir {
+    context: AirContext<BaseElement>,
+    sum: BaseElement,
+}
+
+impl Air for SumAir {
+    type BaseField = BaseElement;
+    type PublicInputs = SumInputs;
+
+    fn new(trace_info: TraceInfo, pub_inputs: SumInputs, options: ProofOptions) -> Self {
+        let degrees = vec![TransitionConstraintDegree::new(1)];
+        SumAir {
+            context: AirContext::new(trace_info, degrees, 1, options),
+            sum: pub_inputs.sum,
+        }
+    }
+
+    fn context(&self) -> &AirContext<Self::BaseField> {
+        &self.context
+    }
+
+    fn derive_public_inputs(pub_inputs: SumInputs) -> SumInputs {
+        let sum = pub_inputs.values.iter().fold(BaseElement::ZERO, |acc, &v| acc + v);
+        SumInputs { sum, ..pub_inputs }
+    }
+
+    fn get_assertions(&self) -> Vec<Assertion<Self::BaseField>> {
+        vec![Assertion::single(0, self.trace_length() - 1, self.sum)]
+    }
+
+    fn evaluate_transition<E: FieldElement + From<Self::BaseField>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        _periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        result[0] = frame.next()[0] - frame.current()[0];
+    }
+}
+
+/// A single-column component which increments the column by the value of a periodic column at
+/// every step, starting with the specified value.
+struct CounterComponent {
+    height: usize,
+    start: BaseElement,
+}
+
+impl Component<BaseElement> for CounterComponent {
+    fn width(&self) -> usize {
+        1
+    }
+
+    fn height(&self) -> usize {
+        self.height
+    }
+
+    fn transition_constraint_degrees(&self) -> Vec<TransitionConstraintDegree> {
+        vec![TransitionConstraintDegree::with_cycles(1, vec![2])]
+    }
+
+    fn get_periodic_column_values(&self) -> Vec<Vec<BaseElement>> {
+        vec![vec![BaseElement::ONE, BaseElement::ONE]]
+    }
+
+    fn get_assertions(&self) -> Vec<Assertion<BaseElement>> {
+        let middle = self.start + BaseElement::new(self.height as u64 / 2);
+        vec![Assertion::sequence(0, 0, self.height / 2, vec![self.start, middle])]
+    }
+
+    fn evaluate_transition<E: FieldElement + From<BaseElement>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        result[0] = frame.next()[0] - frame.current()[0] - periodic_values[0];
+    }
+
+    fn fill_trace(&self, columns: &mut [&mut [BaseElement]]) {
+        columns[0][0] = self.start;
+        for i in 1..self.height {
+            columns[0][i] = columns[0][i - 1] + BaseElement::ONE;
+        }
+    }
+}
+
+/// A two-column component which computes a Fibonacci sequence starting with two ones.
+struct FibComponent {
+    height: usize,
+}
+
+impl Component<BaseElement> for FibComponent {
+    fn width(&self) -> usize {
+        2
+    }
+
+    fn height(&self) -> usize {
+        self.height
+    }
+
+    fn transition_constraint_degrees(&self) -> Vec<TransitionConstraintDegree> {
+        vec![TransitionConstraintDegree::new(1); 2]
+    }
+
+    fn get_assertions(&self) -> Vec<Assertion<BaseElement>> {
+        vec![
+            Assertion::single(0, 0, BaseElement::ONE),
+            Assertion::single(1, 0, BaseElement::ONE),
+        ]
+    }
+
+    fn evaluate_transition<E: FieldElement + From<BaseElement>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        _periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        let current = frame.current();
+        let next = frame.next();
+        result[0] = next[0] - current[1];
+        result[1] = next[1] - current[0] - current[1];
+    }
+
+    fn fill_trace(&self, columns: &mut [&mut [BaseElement]]) {
+        columns[0][0] = BaseElement::ONE;
+        columns[1][0] = BaseElement::ONE;
+        for i in 1..self.height {
+            columns[0][i] = columns[1][i - 1];
+            columns[1][i] = columns[0][i - 1] + columns[1][i - 1];
+        }
+    }
+}
+
 // UTILITY FUNCTIONS
 // ================================================================================================
 
@@ -23,6 +23,12 @@ const NUM_AUX_SEGMENTS: usize = 1;
 /// specifies the number of columns for all trace segments. Currently, a trace can consist of at
 /// most two segments. Metadata is just a vector of bytes and can store any values up to 64KB in
 /// size.
+///
+/// Metadata allows applications to bind context of a computation (e.g., a program hash or a VM
+/// version) to a proof without encoding it into public inputs: it is included in the proof
+/// context, which is absorbed into the transcript of the public coin, and is available to the
+/// verifier via [Air::trace_info()](crate::Air::trace_info). Verifiers can also restrict
+/// acceptable metadata before instantiating the AIR via an acceptance policy.
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct TraceInfo {
     layout: TraceLayout,
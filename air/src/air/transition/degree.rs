@@ -71,6 +71,15 @@ impl TransitionConstraintDegree {
         }
     }
 
+    /// Returns a copy of this degree descriptor with the base degree increased by the specified
+    /// number of trace columns.
+    pub(crate) fn mul_columns(&self, num_columns: usize) -> Self {
+        TransitionConstraintDegree {
+            base: self.base + num_columns,
+            cycles: self.cycles.clone(),
+        }
+    }
+
     /// Computes a degree to which this degree description expands in the context of execution
     /// trace of the specified length.
     ///
@@ -101,7 +110,10 @@ impl TransitionConstraintDegree {
 
     /// Returns a minimum blowup factor needed to evaluate constraint of this degree.
     ///
-    /// This is guaranteed to be a power of two, greater than one.
+    /// This is guaranteed to be a power of two, greater than one. This is a conservative estimate
+    /// which does not depend on trace length; [AirContext](crate::AirContext) derives the blowup
+    /// factor from the exact degree of the constraint composition polynomial instead, which could
+    /// be smaller for constraints involving periodic columns.
     pub fn min_blowup_factor(&self) -> usize {
         // The blowup factor needs to be a power of two large enough to accommodate degree of
         // transition constraints defined by rational functions `C(x) / z(x)` where `C(x)` is the
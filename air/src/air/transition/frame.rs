@@ -9,13 +9,16 @@ use super::{FieldElement, Vec};
 // ================================================================================================
 /// A set of execution trace rows required for evaluation of transition constraints.
 ///
-/// In the current implementation, an evaluation frame always contains two consecutive rows of the
-/// execution trace. It is passed in as one of the parameters into
-/// [Air::evaluate_transition()](crate::Air::evaluate_transition) function.
+/// An evaluation frame contains one row of the execution trace for every out-of-domain offset
+/// specified by the AIR (see [AirContext::ood_offsets()](crate::AirContext::ood_offsets)). By
+/// default, the offsets are 0 and 1, and thus, the frame contains two consecutive rows of the
+/// execution trace: the current row and the next row. When additional offsets are specified,
+/// row `i` of the frame holds the trace row at `step + offsets[i]`. The frame is passed in as one
+/// of the parameters into [Air::evaluate_transition()](crate::Air::evaluate_transition) function.
 #[derive(Debug, Clone)]
 pub struct EvaluationFrame<E: FieldElement> {
-    current: Vec<E>,
-    next: Vec<E>,
+    rows: Vec<Vec<E>>,
+    offsets: Vec<usize>,
 }
 
 impl<E: FieldElement> EvaluationFrame<E> {
@@ -24,13 +27,27 @@ impl<E: FieldElement> EvaluationFrame<E> {
 
     /// Returns a new evaluation frame instantiated with the specified number of columns.
     ///
+    /// The frame contains the current and the next rows.
+    ///
     /// # Panics
     /// Panics if `num_columns` is zero.
     pub fn new(num_columns: usize) -> Self {
+        Self::with_offsets(num_columns, &[0, 1])
+    }
+
+    /// Returns a new evaluation frame instantiated with the specified number of columns and
+    /// holding one row for each of the specified offsets.
+    ///
+    /// # Panics
+    /// Panics if:
+    /// * `num_columns` is zero.
+    /// * `offsets` does not start with `0` and `1`.
+    pub fn with_offsets(num_columns: usize, offsets: &[usize]) -> Self {
         assert!(num_columns > 0, "number of columns must be greater than zero");
+        assert!(offsets.starts_with(&[0, 1]), "offsets must start with 0 and 1");
         EvaluationFrame {
-            current: E::zeroed_vector(num_columns),
-            next: E::zeroed_vector(num_columns),
+            rows: offsets.iter().map(|_| E::zeroed_vector(num_columns)).collect(),
+            offsets: offsets.to_vec(),
         }
     }
 
@@ -41,9 +58,29 @@ impl<E: FieldElement> EvaluationFrame<E> {
     /// * Lengths of the provided rows are zero.
     /// * Lengths of the provided rows are not the same.
     pub fn from_rows(current: Vec<E>, next: Vec<E>) -> Self {
-        assert!(!current.is_empty(), "a row must contain at least one value");
-        assert_eq!(current.len(), next.len(), "number of values in the rows must be the same");
-        Self { current, next }
+        Self::from_offset_rows(&[0, 1], vec![current, next])
+    }
+
+    /// Returns a new evaluation frame instantiated from the provided rows, where row `i` is the
+    /// trace row at offset `offsets[i]` from the current row.
+    ///
+    /// # Panics
+    /// Panics if:
+    /// * `offsets` does not start with `0` and `1`.
+    /// * The number of rows is not the same as the number of offsets.
+    /// * Lengths of the provided rows are zero.
+    /// * Lengths of the provided rows are not the same.
+    pub fn from_offset_rows(offsets: &[usize], rows: Vec<Vec<E>>) -> Self {
+        assert!(offsets.starts_with(&[0, 1]), "offsets must start with 0 and 1");
+        assert_eq!(offsets.len(), rows.len(), "number of rows must match the number of offsets");
+        assert!(!rows[0].is_empty(), "a row must contain at least one value");
+        for row in rows.iter().skip(1) {
+            assert_eq!(rows[0].len(), row.len(), "number of values in the rows must be the same");
+        }
+        Self {
+            rows,
+            offsets: offsets.to_vec(),
+        }
     }
 
     // ROW ACCESSORS
@@ -52,13 +89,33 @@ impl<E: FieldElement> EvaluationFrame<E> {
     /// Returns a reference to the current row.
     #[inline(always)]
     pub fn current(&self) -> &[E] {
-        &self.current
+        &self.rows[0]
     }
 
     /// Returns a reference to the next row.
     #[inline(always)]
     pub fn next(&self) -> &[E] {
-        &self.next
+        &self.rows[1]
+    }
+
+    /// Returns a reference to the row at the specified index; this is the trace row at offset
+    /// `offsets()[index]` from the current row.
+    ///
+    /// # Panics
+    /// Panics if `index` is greater than or equal to the number of rows in this frame.
+    #[inline(always)]
+    pub fn row(&self, index: usize) -> &[E] {
+        &self.rows[index]
+    }
+
+    /// Returns the number of rows in this frame.
+    pub fn num_rows(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Returns offsets of the rows in this frame relative to the current row.
+    pub fn offsets(&self) -> &[usize] {
+        &self.offsets
     }
 
     // DATA MUTATORS
@@ -67,12 +124,21 @@ impl<E: FieldElement> EvaluationFrame<E> {
     /// Returns a mutable reference to the current row.
     #[inline(always)]
     pub fn current_mut(&mut self) -> &mut [E] {
-        &mut self.current
+        &mut self.rows[0]
     }
 
     /// Returns a mutable reference to the next row.
     #[inline(always)]
     pub fn next_mut(&mut self) -> &mut [E] {
-        &mut self.next
+        &mut self.rows[1]
+    }
+
+    /// Returns a mutable reference to the row at the specified index.
+    ///
+    /// # Panics
+    /// Panics if `index` is greater than or equal to the number of rows in this frame.
+    #[inline(always)]
+    pub fn row_mut(&mut self, index: usize) -> &mut [E] {
+        &mut self.rows[index]
     }
 }
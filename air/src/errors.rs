@@ -4,11 +4,12 @@
 // LICENSE file in the root directory of this source tree.
 
 use core::fmt;
+use utils::string::String;
 
 // ASSERTION ERROR
 // ================================================================================================
 /// Represents an error returned during assertion evaluation.
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum AssertionError {
     /// This error occurs when an assertion is evaluated against an execution trace which does not
     /// contain a column specified by the assertion.
@@ -42,3 +43,35 @@ impl fmt::Display for AssertionError {
         }
     }
 }
+
+// PUBLIC INPUTS ERROR
+// ================================================================================================
+/// Represents an error returned during validation of public inputs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PublicInputsError {
+    /// This error occurs when a revealed leaf of Merkle-ized public inputs has an index which is
+    /// greater than or equal to the number of leaves committed to by the Merkle root.
+    RevealedLeafOutOfBounds(usize, usize),
+    /// This error occurs when revealed leaves of Merkle-ized public inputs cannot be authenticated
+    /// against the Merkle root.
+    InvalidMerkleProof,
+    /// This error occurs when public inputs fail a validity check defined by the AIR.
+    InvalidValue(String),
+}
+
+impl fmt::Display for PublicInputsError {
+    #[rustfmt::skip]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::RevealedLeafOutOfBounds(num_leaves, index) => {
+                write!(f, "revealed leaf index {index} is out of bounds for {num_leaves} committed leaves")
+            }
+            Self::InvalidMerkleProof => {
+                write!(f, "revealed leaves do not match the Merkle root of public inputs")
+            }
+            Self::InvalidValue(msg) => {
+                write!(f, "public inputs are invalid: {msg}")
+            }
+        }
+    }
+}
@@ -0,0 +1,422 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+//! Constraints for bitwise AND, XOR, and OR of 32-bit values.
+//!
+//! Hash functions and virtual machines frequently need bitwise operations, but field elements
+//! support only arithmetic. The naive approach decomposes both operands of an operation into
+//! bits in a single row, which requires close to a hundred columns per 32-bit operation.
+//! [BitwiseColumns] instead decomposes operands into 4-bit limbs, one limb per row: an operation
+//! takes [CYCLE_LENGTH] rows, but only [NUM_COLUMNS] columns regardless of how many operations
+//! are performed.
+//!
+//! 64-bit operations are performed as two 32-bit operations over the high and the low halves of
+//! the operands (see [BitwiseOperation::split_u64()]); this also keeps all intermediate values
+//! below the modulus of the 64-bit field.
+
+use super::BaseElement;
+use crate::{Assertion, EvaluationFrame, TransitionConstraintDegree};
+use core::ops::Range;
+use math::FieldElement;
+use utils::collections::Vec;
+
+// CONSTANTS
+// ================================================================================================
+
+/// Number of bits of each operand processed in a single row.
+pub const LIMB_BITS: usize = 4;
+
+/// Number of bits in the operands of a single operation.
+pub const OPERAND_BITS: usize = 32;
+
+/// Number of trace rows per operation: one row per limb of the operands.
+pub const CYCLE_LENGTH: usize = OPERAND_BITS / LIMB_BITS;
+
+/// Number of trace columns occupied by the gadget: two operation selectors, accumulators for
+/// both operands and the result, and bits of the current limb of both operands.
+pub const NUM_COLUMNS: usize = 5 + 2 * LIMB_BITS;
+
+/// Number of constraints enforced by [BitwiseColumns::evaluate_transition()].
+pub const NUM_CONSTRAINTS: usize = 11 + 2 * LIMB_BITS;
+
+/// Number of periodic columns expected by [BitwiseColumns::evaluate_transition()].
+pub const NUM_PERIODIC_COLUMNS: usize = 2;
+
+/// Value by which an accumulator is multiplied when a limb is appended to it.
+const LIMB_SHIFT: u64 = 1 << LIMB_BITS;
+
+// BITWISE OPERATIONS
+// ================================================================================================
+
+/// A bitwise operation supported by [BitwiseColumns].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BitwiseOp {
+    And,
+    Xor,
+    Or,
+}
+
+impl BitwiseOp {
+    /// Returns the result of applying this operation to the specified values.
+    pub fn apply(&self, a: u32, b: u32) -> u32 {
+        match self {
+            Self::And => a & b,
+            Self::Xor => a ^ b,
+            Self::Or => a | b,
+        }
+    }
+
+    /// Returns values of the XOR and the OR selector columns for this operation; AND is selected
+    /// when both selectors are zero.
+    fn selectors(&self) -> [BaseElement; 2] {
+        match self {
+            Self::And => [BaseElement::ZERO, BaseElement::ZERO],
+            Self::Xor => [BaseElement::ONE, BaseElement::ZERO],
+            Self::Or => [BaseElement::ZERO, BaseElement::ONE],
+        }
+    }
+}
+
+/// A bitwise operation applied to a pair of 32-bit operands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BitwiseOperation {
+    pub op: BitwiseOp,
+    pub a: u32,
+    pub b: u32,
+}
+
+impl BitwiseOperation {
+    /// Returns a new operation applying `op` to `a` and `b`.
+    pub fn new(op: BitwiseOp, a: u32, b: u32) -> Self {
+        Self { op, a, b }
+    }
+
+    /// Returns two operations which apply `op` to the high and the low halves of the specified
+    /// 64-bit operands (in this order).
+    ///
+    /// The 64-bit result can be recovered from the results of the two operations via
+    /// [combine_u64()].
+    pub fn split_u64(op: BitwiseOp, a: u64, b: u64) -> [Self; 2] {
+        [
+            Self::new(op, (a >> 32) as u32, (b >> 32) as u32),
+            Self::new(op, a as u32, b as u32),
+        ]
+    }
+
+    /// Returns the result of this operation.
+    pub fn result(&self) -> u32 {
+        self.op.apply(self.a, self.b)
+    }
+}
+
+/// Returns a 64-bit value assembled from its high and low 32-bit halves.
+pub fn combine_u64(high: u32, low: u32) -> u64 {
+    ((high as u64) << 32) | low as u64
+}
+
+// BITWISE COLUMNS
+// ================================================================================================
+
+/// A group of trace columns which computes bitwise operations over 32-bit values.
+///
+/// Starting at the column offset specified on construction, the gadget occupies [NUM_COLUMNS]
+/// columns of the main trace segment laid out as follows:
+/// - the XOR and the OR selector columns; the operation is AND if both selectors are zero. The
+///   selectors remain constant throughout a cycle;
+/// - accumulators for the operands `a` and `b`, and for the result `z`. On each row of a cycle,
+///   the next limb of the operands (starting from the most significant one) and the result of
+///   the operation over these limbs are appended to the accumulators; thus, on the last row of
+///   the cycle, the accumulators hold the operands and the result of the operation;
+/// - [LIMB_BITS] columns with bits of the current limb of `a`, followed by [LIMB_BITS] columns
+///   with bits of the current limb of `b` (least significant bit first).
+///
+/// Operation `i` occupies rows `[i * CYCLE_LENGTH, (i + 1) * CYCLE_LENGTH)`; other constraints of
+/// the AIR can refer to its operands and result at row
+/// [result_step(i)](Self::result_step). Cycles after the last operation are filled with AND
+/// operations over zeros; since bits of the last row of the trace are not constrained, the
+/// trace must contain at least one such cycle.
+///
+/// To use the gadget, an AIR needs to:
+/// - include [constraint_degrees()](Self::constraint_degrees) into its transition constraint
+///   degrees and call [evaluate_transition()](Self::evaluate_transition) from
+///   [Air::evaluate_transition()](crate::Air::evaluate_transition);
+/// - include [get_periodic_column_values()](Self::get_periodic_column_values) into its periodic
+///   columns;
+/// - either bind the accumulators at the result steps via its own constraints, or include
+///   [get_assertions()](Self::get_assertions) into its assertions when the operations are a
+///   part of the public inputs.
+///
+/// The prover fills the gadget's columns with [build_trace_columns()](Self::build_trace_columns).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BitwiseColumns {
+    offset: usize,
+}
+
+impl BitwiseColumns {
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a gadget occupying [NUM_COLUMNS] columns of the main trace segment starting at
+    /// the specified column.
+    pub fn new(offset: usize) -> Self {
+        Self { offset }
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the range of all trace columns occupied by this gadget.
+    pub fn columns(&self) -> Range<usize> {
+        self.offset..self.offset + NUM_COLUMNS
+    }
+
+    /// Returns the index of the XOR selector column.
+    pub fn xor_column(&self) -> usize {
+        self.offset
+    }
+
+    /// Returns the index of the OR selector column.
+    pub fn or_column(&self) -> usize {
+        self.offset + 1
+    }
+
+    /// Returns the index of the column accumulating the first operand.
+    pub fn a_column(&self) -> usize {
+        self.offset + 2
+    }
+
+    /// Returns the index of the column accumulating the second operand.
+    pub fn b_column(&self) -> usize {
+        self.offset + 3
+    }
+
+    /// Returns the index of the column accumulating the result.
+    pub fn z_column(&self) -> usize {
+        self.offset + 4
+    }
+
+    /// Returns the range of columns holding bits of the current limb of the first operand.
+    pub fn a_bit_columns(&self) -> Range<usize> {
+        self.offset + 5..self.offset + 5 + LIMB_BITS
+    }
+
+    /// Returns the range of columns holding bits of the current limb of the second operand.
+    pub fn b_bit_columns(&self) -> Range<usize> {
+        self.offset + 5 + LIMB_BITS..self.offset + NUM_COLUMNS
+    }
+
+    /// Returns the step at which the accumulators hold the operands and the result of the
+    /// operation with the specified index.
+    pub fn result_step(index: usize) -> usize {
+        (index + 1) * CYCLE_LENGTH - 1
+    }
+
+    /// Returns the minimum length of a trace which can perform the specified number of
+    /// operations.
+    pub fn min_trace_length(num_operations: usize) -> usize {
+        ((num_operations + 1) * CYCLE_LENGTH).next_power_of_two()
+    }
+
+    // PROVER
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns [NUM_COLUMNS] trace columns of the specified length which perform the specified
+    /// operations; the columns are ordered as described in [BitwiseColumns].
+    ///
+    /// # Panics
+    /// Panics if `trace_length` is not a multiple of [CYCLE_LENGTH] or is smaller than
+    /// [min_trace_length()](Self::min_trace_length) for the operations.
+    pub fn build_trace_columns(
+        operations: &[BitwiseOperation],
+        trace_length: usize,
+    ) -> Vec<Vec<BaseElement>> {
+        validate_trace_length(operations.len(), trace_length);
+
+        let mut columns = vec![vec![BaseElement::ZERO; trace_length]; NUM_COLUMNS];
+        for (i, operation) in operations.iter().enumerate() {
+            let [xor, or] = operation.op.selectors();
+            let (mut a, mut b, mut z) = (0u64, 0u64, 0u64);
+            for row in 0..CYCLE_LENGTH {
+                let step = i * CYCLE_LENGTH + row;
+                let shift = OPERAND_BITS - LIMB_BITS * (row + 1);
+                let a_limb = (operation.a >> shift) & (LIMB_SHIFT as u32 - 1);
+                let b_limb = (operation.b >> shift) & (LIMB_SHIFT as u32 - 1);
+                a = a * LIMB_SHIFT + a_limb as u64;
+                b = b * LIMB_SHIFT + b_limb as u64;
+                z = z * LIMB_SHIFT + operation.op.apply(a_limb, b_limb) as u64;
+
+                columns[0][step] = xor;
+                columns[1][step] = or;
+                columns[2][step] = BaseElement::new(a);
+                columns[3][step] = BaseElement::new(b);
+                columns[4][step] = BaseElement::new(z);
+                for bit in 0..LIMB_BITS {
+                    columns[5 + bit][step] = BaseElement::from((a_limb >> bit) & 1);
+                    columns[5 + LIMB_BITS + bit][step] = BaseElement::from((b_limb >> bit) & 1);
+                }
+            }
+        }
+
+        columns
+    }
+
+    // CONSTRAINTS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns degrees of the constraints enforced by
+    /// [evaluate_transition()](Self::evaluate_transition).
+    pub fn constraint_degrees() -> Vec<TransitionConstraintDegree> {
+        let cycle = |degree| TransitionConstraintDegree::with_cycles(degree, vec![CYCLE_LENGTH]);
+        let mut result = vec![TransitionConstraintDegree::new(2); 3];
+        result.extend([cycle(1), cycle(1)]);
+        result.resize(5 + 2 * LIMB_BITS, TransitionConstraintDegree::new(2));
+        result.extend([cycle(1), cycle(1), cycle(3), cycle(1), cycle(1), cycle(3)]);
+        result
+    }
+
+    /// Returns values of the periodic columns expected by
+    /// [evaluate_transition()](Self::evaluate_transition).
+    ///
+    /// The first column is a flag which is set to one on the first row of each cycle; the second
+    /// column is a flag which is set to one on all rows of a cycle except for the last one.
+    pub fn get_periodic_column_values() -> Vec<Vec<BaseElement>> {
+        let mut first_row = vec![BaseElement::ZERO; CYCLE_LENGTH];
+        first_row[0] = BaseElement::ONE;
+        let mut not_last_row = vec![BaseElement::ONE; CYCLE_LENGTH];
+        not_last_row[CYCLE_LENGTH - 1] = BaseElement::ZERO;
+        vec![first_row, not_last_row]
+    }
+
+    /// Evaluates [NUM_CONSTRAINTS] transition constraints of this gadget over the specified
+    /// frame and writes the results into `result`.
+    ///
+    /// `periodic_values` must contain values of the columns returned by
+    /// [get_periodic_column_values()](Self::get_periodic_column_values) in the same order. The
+    /// constraints require:
+    /// - the selectors to be binary, mutually exclusive, and constant throughout a cycle;
+    /// - all limb bits to be binary;
+    /// - on the first row of a cycle, the accumulators to be equal to the current limbs of the
+    ///   operands and to the result of the operation over these limbs;
+    /// - on other rows, the accumulators to be equal to the accumulators of the previous row
+    ///   shifted by [LIMB_BITS] bits, plus the current limbs and the result over them.
+    pub fn evaluate_transition<E>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        periodic_values: &[E],
+        result: &mut [E],
+    ) where
+        E: FieldElement + From<BaseElement>,
+    {
+        let current = frame.current();
+        let next = frame.next();
+        let first_row = periodic_values[0];
+        let not_last_row = periodic_values[1];
+        let xor = current[self.xor_column()];
+        let or = current[self.or_column()];
+
+        result[0] = xor * (xor - E::ONE);
+        result[1] = or * (or - E::ONE);
+        result[2] = xor * or;
+        result[3] = not_last_row * (next[self.xor_column()] - xor);
+        result[4] = not_last_row * (next[self.or_column()] - or);
+
+        let bits = current[self.a_bit_columns()].iter().chain(&current[self.b_bit_columns()]);
+        for (i, &bit) in bits.enumerate() {
+            result[5 + i] = bit * (bit - E::ONE);
+        }
+
+        let offset = 5 + 2 * LIMB_BITS;
+        let (a, b, z) = self.evaluate_limbs(current, xor, or);
+        result[offset] = first_row * (current[self.a_column()] - a);
+        result[offset + 1] = first_row * (current[self.b_column()] - b);
+        result[offset + 2] = first_row * (current[self.z_column()] - z);
+
+        let shift = E::from(BaseElement::new(LIMB_SHIFT));
+        let (a, b, z) = self.evaluate_limbs(next, xor, or);
+        let mut accumulate = |i: usize, column: usize, limb: E| {
+            result[offset + 3 + i] = not_last_row * (next[column] - current[column] * shift - limb);
+        };
+        accumulate(0, self.a_column(), a);
+        accumulate(1, self.b_column(), b);
+        accumulate(2, self.z_column(), z);
+    }
+
+    // ASSERTIONS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns assertions which bind this gadget to the specified operations.
+    ///
+    /// For each operation, the assertions fix the selectors on the first row of its cycle, and
+    /// the operands and the result on the last row of its cycle.
+    ///
+    /// # Panics
+    /// Panics if `trace_length` is not a multiple of [CYCLE_LENGTH] or is smaller than
+    /// [min_trace_length()](Self::min_trace_length) for the operations.
+    pub fn get_assertions(
+        &self,
+        operations: &[BitwiseOperation],
+        trace_length: usize,
+    ) -> Vec<Assertion<BaseElement>> {
+        validate_trace_length(operations.len(), trace_length);
+
+        let mut result = Vec::with_capacity(5 * operations.len());
+        for (i, operation) in operations.iter().enumerate() {
+            let [xor, or] = operation.op.selectors();
+            let first_step = i * CYCLE_LENGTH;
+            result.push(Assertion::single(self.xor_column(), first_step, xor));
+            result.push(Assertion::single(self.or_column(), first_step, or));
+
+            let last_step = Self::result_step(i);
+            let values = [operation.a, operation.b, operation.result()];
+            for (column, value) in
+                [self.a_column(), self.b_column(), self.z_column()].into_iter().zip(values)
+            {
+                result.push(Assertion::single(column, last_step, BaseElement::from(value)));
+            }
+        }
+
+        result
+    }
+
+    // HELPER METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the limbs of both operands and the result of the operation selected by `xor` and
+    /// `or` over these limbs, all computed from limb bits in the specified row.
+    fn evaluate_limbs<E>(&self, row: &[E], xor: E, or: E) -> (E, E, E)
+    where
+        E: FieldElement + From<BaseElement>,
+    {
+        let (mut a, mut b, mut z) = (E::ZERO, E::ZERO, E::ZERO);
+        let mut power = E::ONE;
+        for (&a_bit, &b_bit) in row[self.a_bit_columns()].iter().zip(&row[self.b_bit_columns()]) {
+            // AND is a * b, XOR is a + b - 2 * a * b, and OR is a + b - a * b
+            let and = a_bit * b_bit;
+            let sum = a_bit + b_bit;
+            let bit = and + xor * (sum - and.double() - and) + or * (sum - and.double());
+            a += a_bit * power;
+            b += b_bit * power;
+            z += bit * power;
+            power = power.double();
+        }
+        (a, b, z)
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+fn validate_trace_length(num_operations: usize, trace_length: usize) {
+    assert_eq!(
+        trace_length % CYCLE_LENGTH,
+        0,
+        "trace length must be a multiple of {CYCLE_LENGTH}, but was {trace_length}"
+    );
+    let min_trace_length = (num_operations + 1) * CYCLE_LENGTH;
+    assert!(
+        trace_length >= min_trace_length,
+        "trace length must be at least {min_trace_length} to perform {num_operations} operations, but was {trace_length}"
+    );
+}
@@ -0,0 +1,704 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+//! Constraints for the Keccak-f\[1600\] permutation.
+//!
+//! The usual approach to constraining Keccak decomposes the entire 1600-bit state into bits in
+//! every row, which requires thousands of columns; the width of a trace, however, is limited to
+//! [TraceInfo::MAX_TRACE_WIDTH](crate::TraceInfo::MAX_TRACE_WIDTH) columns. [KeccakColumns]
+//! instead keeps the state as 32-bit halves of its 25 lanes, and executes every round as a
+//! fixed schedule of [ROUND_LENGTH] row operations. Each row decomposes at most three lanes into
+//! bits and writes a single lane computed from these bits, so the gadget occupies
+//! [NUM_COLUMNS] columns at the cost of [ROUND_LENGTH] rows per round.
+//!
+//! The schedule of a round is:
+//! 1. theta, part one: column parities `C[x]` of the state are computed into five temporary
+//!    lanes, with each parity taking two rows over three and two lanes of a column;
+//! 2. theta, part two, together with rho and pi: every lane `A[x, y]` is combined with
+//!    `C[x - 1]` and `C[x + 1]` rotated by one bit, rotated by the rho offset of the lane, and
+//!    written into its position after pi. Lanes are processed in the order of the cycle formed
+//!    by pi, so that every lane is read before it is overwritten;
+//! 3. chi and iota: every plane of the state is copied into the temporary lanes, and the lanes
+//!    of the plane are overwritten with the results of chi over the copy; the round constant is
+//!    added to the first lane of the first plane.
+//!
+//! All row operations are selected by periodic columns (see
+//! [KeccakColumns::get_periodic_column_values()]), including the round constants.
+
+use super::BaseElement;
+use crate::{Assertion, EvaluationFrame, TransitionConstraintDegree};
+use core::ops::Range;
+use math::FieldElement;
+use utils::collections::Vec;
+
+// CONSTANTS
+// ================================================================================================
+
+/// Number of lanes in the state of the permutation.
+pub const NUM_LANES: usize = 25;
+
+/// Number of rounds of the permutation.
+pub const NUM_ROUNDS: usize = 24;
+
+/// Number of trace rows per round of the permutation.
+pub const ROUND_LENGTH: usize = 64;
+
+/// Number of trace rows per permutation.
+///
+/// This is the number of rows in [NUM_ROUNDS] rounds, rounded up to the next power of two; the
+/// rows after the last round execute rounds without round constants, and their results are
+/// ignored.
+pub const CYCLE_LENGTH: usize = 2048;
+
+/// Number of lanes stored in the trace: the lanes of the state and the temporary lanes.
+const NUM_STORED_LANES: usize = NUM_LANES + NUM_TEMP_LANES;
+
+/// Number of temporary lanes.
+const NUM_TEMP_LANES: usize = 5;
+
+/// Number of registers holding bits of lanes read by a row.
+const NUM_REGISTERS: usize = 3;
+
+/// Number of bits in a lane.
+const LANE_BITS: usize = 64;
+
+/// Number of trace columns occupied by the gadget: two columns per lane of the state and per
+/// temporary lane, and bits of the three registers.
+pub const NUM_COLUMNS: usize = 2 * NUM_STORED_LANES + NUM_REGISTERS * LANE_BITS;
+
+/// Number of constraints enforced by [KeccakColumns::evaluate_transition()].
+pub const NUM_CONSTRAINTS: usize =
+    NUM_REGISTERS * LANE_BITS + 2 * NUM_REGISTERS + 2 * NUM_STORED_LANES;
+
+/// Rotation offsets of the rho step indexed by lane.
+const RHO_OFFSETS: [u32; NUM_LANES] = [
+    0, 1, 62, 28, 27, 36, 44, 6, 55, 20, 3, 10, 43, 25, 39, 41, 45, 15, 21, 8, 18, 2, 61, 56, 14,
+];
+
+/// Round constants of the iota step.
+const ROUND_CONSTANTS: [u64; NUM_ROUNDS] = [
+    0x0000000000000001,
+    0x0000000000008082,
+    0x800000000000808a,
+    0x8000000080008000,
+    0x000000000000808b,
+    0x0000000080000001,
+    0x8000000080008081,
+    0x8000000000008009,
+    0x000000000000008a,
+    0x0000000000000088,
+    0x0000000080008009,
+    0x000000008000000a,
+    0x000000008000808b,
+    0x800000000000008b,
+    0x8000000000008089,
+    0x8000000000008003,
+    0x8000000000008002,
+    0x8000000000000080,
+    0x000000000000800a,
+    0x800000008000000a,
+    0x8000000080008081,
+    0x8000000000008080,
+    0x0000000080000001,
+    0x8000000080008008,
+];
+
+/// Positions of bits which are set in at least one round constant.
+const ROUND_CONSTANT_BITS: [usize; 7] = [0, 1, 3, 7, 15, 31, 63];
+
+/// Row of a round at which chi is computed for the first lane, and the round constant is added.
+const IOTA_ROW: usize = 38;
+
+// Layout of periodic columns.
+const READ_SELECTORS: usize = 0;
+const ROTATED_READ_FLAG: usize = READ_SELECTORS + NUM_REGISTERS * NUM_STORED_LANES;
+const WRITE_SELECTORS: usize = ROTATED_READ_FLAG + 1;
+const LOAD_SELECTORS: usize = WRITE_SELECTORS + NUM_STORED_LANES;
+const XOR_COEFFICIENTS: usize = LOAD_SELECTORS + NUM_TEMP_LANES;
+const CHI_FLAG: usize = XOR_COEFFICIENTS + 2 * LANE_BITS;
+const ROUND_CONSTANT_COLUMNS: usize = CHI_FLAG + 1;
+const CONTINUATION_FLAG: usize = ROUND_CONSTANT_COLUMNS + ROUND_CONSTANT_BITS.len();
+
+/// Number of periodic columns expected by [KeccakColumns::evaluate_transition()].
+pub const NUM_PERIODIC_COLUMNS: usize = CONTINUATION_FLAG + 1;
+
+// KECCAK PERMUTATION
+// ================================================================================================
+
+/// State of the Keccak-f\[1600\] permutation; lane `(x, y)` is at index `x + 5 * y`.
+pub type KeccakState = [u64; NUM_LANES];
+
+/// Applies the Keccak-f\[1600\] permutation to the specified state.
+pub fn permute(state: &mut KeccakState) {
+    for round_constant in ROUND_CONSTANTS {
+        // theta
+        let mut c = [0u64; 5];
+        for (x, parity) in c.iter_mut().enumerate() {
+            *parity = (0..5).fold(0, |acc, y| acc ^ state[x + 5 * y]);
+        }
+        for (i, lane) in state.iter_mut().enumerate() {
+            let x = i % 5;
+            *lane ^= c[(x + 4) % 5] ^ c[(x + 1) % 5].rotate_left(1);
+        }
+
+        // rho and pi
+        let mut b = [0u64; NUM_LANES];
+        for (i, &lane) in state.iter().enumerate() {
+            b[pi(i)] = lane.rotate_left(RHO_OFFSETS[i]);
+        }
+
+        // chi and iota
+        for (i, lane) in state.iter_mut().enumerate() {
+            let (x, y) = (i % 5, i / 5);
+            *lane = b[i] ^ (!b[(x + 1) % 5 + 5 * y] & b[(x + 2) % 5 + 5 * y]);
+        }
+        state[0] ^= round_constant;
+    }
+}
+
+// KECCAK COLUMNS
+// ================================================================================================
+
+/// A group of trace columns which computes Keccak-f\[1600\] permutations.
+///
+/// Starting at the column offset specified on construction, the gadget occupies [NUM_COLUMNS]
+/// columns of the main trace segment laid out as follows:
+/// - the low and the high 32 bits of each of the 25 lanes of the state (see
+///   [lane_columns()](Self::lane_columns)), followed by the low and the high 32 bits of each of
+///   the five temporary lanes;
+/// - three registers of 64 columns each, holding bits of the lanes read by the current row
+///   (least significant bit first).
+///
+/// Permutation `i` occupies rows `[i * CYCLE_LENGTH, (i + 1) * CYCLE_LENGTH)`; its input is in
+/// the lane columns at row [input_step(i)](Self::input_step), and its output at row
+/// [result_step(i)](Self::result_step). Cycles after the last permutation are filled with
+/// permutations of the zero state.
+///
+/// To use the gadget, an AIR needs to:
+/// - include [constraint_degrees()](Self::constraint_degrees) into its transition constraint
+///   degrees and call [evaluate_transition()](Self::evaluate_transition) from
+///   [Air::evaluate_transition()](crate::Air::evaluate_transition);
+/// - include [get_periodic_column_values()](Self::get_periodic_column_values) into its periodic
+///   columns;
+/// - either bind the lanes at the input and result steps via its own constraints, or include
+///   [get_assertions()](Self::get_assertions) into its assertions when the inputs are a part
+///   of the public inputs.
+///
+/// The prover fills the gadget's columns with [build_trace_columns()](Self::build_trace_columns).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeccakColumns {
+    offset: usize,
+}
+
+impl KeccakColumns {
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a gadget occupying [NUM_COLUMNS] columns of the main trace segment starting at
+    /// the specified column.
+    pub fn new(offset: usize) -> Self {
+        Self { offset }
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the range of all trace columns occupied by this gadget.
+    pub fn columns(&self) -> Range<usize> {
+        self.offset..self.offset + NUM_COLUMNS
+    }
+
+    /// Returns indexes of the columns holding the low and the high 32 bits of the lane with the
+    /// specified index (in this order).
+    ///
+    /// # Panics
+    /// Panics if `lane` is not smaller than [NUM_LANES].
+    pub fn lane_columns(&self, lane: usize) -> [usize; 2] {
+        assert!(lane < NUM_LANES, "lane index must be smaller than {NUM_LANES}, but was {lane}");
+        self.stored_lane_columns(lane)
+    }
+
+    /// Returns the step at which the lane columns hold the input of the permutation with the
+    /// specified index.
+    pub fn input_step(index: usize) -> usize {
+        index * CYCLE_LENGTH
+    }
+
+    /// Returns the step at which the lane columns hold the output of the permutation with the
+    /// specified index.
+    pub fn result_step(index: usize) -> usize {
+        index * CYCLE_LENGTH + NUM_ROUNDS * ROUND_LENGTH - 1
+    }
+
+    /// Returns the minimum length of a trace which can compute the specified number of
+    /// permutations.
+    pub fn min_trace_length(num_permutations: usize) -> usize {
+        (num_permutations.max(1) * CYCLE_LENGTH).next_power_of_two()
+    }
+
+    // PROVER
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns [NUM_COLUMNS] trace columns of the specified length which compute permutations
+    /// of the specified states; the columns are ordered as described in [KeccakColumns].
+    ///
+    /// # Panics
+    /// Panics if `trace_length` is not a multiple of [CYCLE_LENGTH] or is smaller than
+    /// [min_trace_length()](Self::min_trace_length) for the inputs.
+    pub fn build_trace_columns(
+        inputs: &[KeccakState],
+        trace_length: usize,
+    ) -> Vec<Vec<BaseElement>> {
+        validate_trace_length(inputs.len(), trace_length);
+
+        let schedule = build_schedule();
+        let gadget = Self::new(0);
+        let mut columns = vec![vec![BaseElement::ZERO; trace_length]; NUM_COLUMNS];
+        for cycle in 0..trace_length / CYCLE_LENGTH {
+            let mut lanes = [0u64; NUM_STORED_LANES];
+            lanes[..NUM_LANES].copy_from_slice(inputs.get(cycle).unwrap_or(&[0; NUM_LANES]));
+            let mut prev_lanes = lanes;
+
+            for row in 0..CYCLE_LENGTH {
+                let step = cycle * CYCLE_LENGTH + row;
+                let op = &schedule[row % ROUND_LENGTH];
+
+                // registers hold bits of the lanes as of the previous row
+                let registers = op.reads.map(|read| read.map_or(0, |read| read.value(&prev_lanes)));
+                for (lane, value) in lanes.iter().enumerate() {
+                    let [lo, hi] = gadget.stored_lane_columns(lane);
+                    columns[lo][step] = BaseElement::from(*value as u32);
+                    columns[hi][step] = BaseElement::from((*value >> 32) as u32);
+                }
+                for (i, register) in registers.iter().enumerate() {
+                    for (bit, column) in gadget.register_columns(i).enumerate() {
+                        columns[column][step] = BaseElement::from((register >> bit) & 1);
+                    }
+                }
+
+                // the lanes of the next row are computed from the lanes and registers of this row
+                prev_lanes = lanes;
+                if let Some(write) = op.write {
+                    let round = row / ROUND_LENGTH;
+                    lanes[write.lane] = write.value(&registers, round);
+                }
+                if let Some(plane) = op.load {
+                    for x in 0..NUM_TEMP_LANES {
+                        lanes[NUM_LANES + x] = prev_lanes[x + 5 * plane];
+                    }
+                }
+            }
+        }
+
+        columns
+    }
+
+    // CONSTRAINTS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns degrees of the constraints enforced by
+    /// [evaluate_transition()](Self::evaluate_transition).
+    pub fn constraint_degrees() -> Vec<TransitionConstraintDegree> {
+        let mut result = vec![TransitionConstraintDegree::new(2); NUM_REGISTERS * LANE_BITS];
+        let read = TransitionConstraintDegree::with_cycles(1, vec![ROUND_LENGTH]);
+        result.resize(result.len() + 2 * NUM_REGISTERS, read);
+        let write = TransitionConstraintDegree::with_cycles(3, vec![ROUND_LENGTH, CYCLE_LENGTH]);
+        result.resize(result.len() + 2 * NUM_STORED_LANES, write);
+        result
+    }
+
+    /// Returns values of the periodic columns expected by
+    /// [evaluate_transition()](Self::evaluate_transition).
+    ///
+    /// All columns except for the round constants and the continuation flag have a period of
+    /// [ROUND_LENGTH] rows; these two have a period of [CYCLE_LENGTH] rows. Values of the columns
+    /// describe the operation of each row; in the order of the columns, these are:
+    /// - for each register and each stored lane, a flag which is set when the register of the
+    ///   next row holds bits of the lane;
+    /// - a flag which is set when the third register of the next row holds bits of a lane
+    ///   rotated left by one bit;
+    /// - for each stored lane, a flag which is set when the lane is written in the next row;
+    /// - for each plane of the state, a flag which is set when the plane is copied into the
+    ///   temporary lanes in the next row;
+    /// - for each bit of the registers, coefficients by which the bit of the XOR of all
+    ///   registers contributes to the low and to the high half of the written lane (these are
+    ///   powers of two which encode the rotation of the written lane, or zeros if the written
+    ///   lane is computed via chi);
+    /// - a flag which is set when the written lane is the result of chi over the registers;
+    /// - for each bit position set in at least one round constant, the bit of the round constant
+    ///   added to the written lane;
+    /// - a flag which is cleared on the last row of each cycle, so that the next cycle can start
+    ///   with an arbitrary state.
+    pub fn get_periodic_column_values() -> Vec<Vec<BaseElement>> {
+        let schedule = build_schedule();
+        let mut result = vec![vec![BaseElement::ZERO; ROUND_LENGTH]; CHI_FLAG + 1];
+        for (row, op) in schedule.iter().enumerate() {
+            let next_op = &schedule[(row + 1) % ROUND_LENGTH];
+            for (i, read) in next_op.reads.iter().enumerate() {
+                if let Some(read) = read {
+                    result[READ_SELECTORS + i * NUM_STORED_LANES + read.lane][row] =
+                        BaseElement::ONE;
+                }
+            }
+            if matches!(next_op.reads[2], Some(Read { rotated: true, .. })) {
+                result[ROTATED_READ_FLAG][row] = BaseElement::ONE;
+            }
+            if let Some(write) = op.write {
+                result[WRITE_SELECTORS + write.lane][row] = BaseElement::ONE;
+                match write.kind {
+                    WriteKind::Xor => {
+                        for bit in 0..LANE_BITS {
+                            let position = (bit + write.rotation as usize) % LANE_BITS;
+                            let half = position / 32;
+                            let coefficient = BaseElement::new(1 << (position % 32));
+                            result[XOR_COEFFICIENTS + half * LANE_BITS + bit][row] = coefficient;
+                        }
+                    }
+                    WriteKind::Chi => result[CHI_FLAG][row] = BaseElement::ONE,
+                }
+            }
+            if let Some(plane) = op.load {
+                result[LOAD_SELECTORS + plane][row] = BaseElement::ONE;
+            }
+        }
+
+        for &bit in ROUND_CONSTANT_BITS.iter() {
+            let mut column = vec![BaseElement::ZERO; CYCLE_LENGTH];
+            for (round, constant) in ROUND_CONSTANTS.iter().enumerate() {
+                column[round * ROUND_LENGTH + IOTA_ROW] = BaseElement::from((constant >> bit) & 1);
+            }
+            result.push(column);
+        }
+
+        let mut continuation = vec![BaseElement::ONE; CYCLE_LENGTH];
+        continuation[CYCLE_LENGTH - 1] = BaseElement::ZERO;
+        result.push(continuation);
+
+        result
+    }
+
+    /// Evaluates [NUM_CONSTRAINTS] transition constraints of this gadget over the specified
+    /// frame and writes the results into `result`.
+    ///
+    /// `periodic_values` must contain values of the columns returned by
+    /// [get_periodic_column_values()](Self::get_periodic_column_values) in the same order. The
+    /// constraints require:
+    /// - all register bits to be binary;
+    /// - registers of the next row to hold bits of the lanes of the current row selected by the
+    ///   read flags (and to be zero if no lane is selected);
+    /// - lanes of the next row to be equal to lanes of the current row, except for the lane
+    ///   selected by the write flags, which must be equal to the lane computed from the
+    ///   registers of the current row, and for the temporary lanes when a plane is copied into
+    ///   them. Lanes are not constrained on the transition into the next cycle.
+    pub fn evaluate_transition<E>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        periodic_values: &[E],
+        result: &mut [E],
+    ) where
+        E: FieldElement + From<BaseElement>,
+    {
+        let current = frame.current();
+        let next = frame.next();
+
+        let mut offset = 0;
+        for i in 0..NUM_REGISTERS {
+            for &bit in current[self.register_columns(i)].iter() {
+                result[offset] = bit * (bit - E::ONE);
+                offset += 1;
+            }
+        }
+
+        // registers of the next row hold bits of the selected lanes of the current row
+        let rotated = periodic_values[ROTATED_READ_FLAG];
+        for i in 0..NUM_REGISTERS {
+            let bits = &next[self.register_columns(i)];
+            let selectors = &periodic_values[READ_SELECTORS + i * NUM_STORED_LANES..];
+            for half in 0..2 {
+                let mut value = pack(bits, half, 0);
+                if i == NUM_REGISTERS - 1 {
+                    value += rotated * (pack(bits, half, 1) - value);
+                }
+                let lane = (0..NUM_STORED_LANES).fold(E::ZERO, |acc, lane| {
+                    acc + selectors[lane] * current[self.stored_lane_columns(lane)[half]]
+                });
+                result[offset] = value - lane;
+                offset += 1;
+            }
+        }
+
+        // compute the lane written by the current row
+        let registers: [&[E]; NUM_REGISTERS] =
+            [0, 1, 2].map(|i| &current[self.register_columns(i)]);
+        let chi_flag = periodic_values[CHI_FLAG];
+        let mut written = [E::ZERO; 2];
+        let mut chi = [E::ZERO; LANE_BITS];
+        for bit in 0..LANE_BITS {
+            let [a, b, c] = registers.map(|register| register[bit]);
+            let xor = xor3(a, b, c);
+            written[0] += xor * periodic_values[XOR_COEFFICIENTS + bit];
+            written[1] += xor * periodic_values[XOR_COEFFICIENTS + LANE_BITS + bit];
+            chi[bit] = xor2(a, (E::ONE - b) * c);
+        }
+        for (half, value) in written.iter_mut().enumerate() {
+            *value += chi_flag * pack(&chi, half, 0);
+        }
+        for (i, &bit) in ROUND_CONSTANT_BITS.iter().enumerate() {
+            // the round constant is non-zero only when chi_flag is set; XOR with its bit flips
+            // the bit of chi
+            let flip = periodic_values[ROUND_CONSTANT_COLUMNS + i] * (E::ONE - chi[bit].double());
+            written[bit / 32] += flip * E::from(BaseElement::new(1 << (bit % 32)));
+        }
+
+        // lanes of the next row are the lanes of the current row updated by the current row
+        let continuation = periodic_values[CONTINUATION_FLAG];
+        let loads = &periodic_values[LOAD_SELECTORS..LOAD_SELECTORS + NUM_TEMP_LANES];
+        let load_flag = loads.iter().fold(E::ZERO, |acc, &flag| acc + flag);
+        for lane in 0..NUM_STORED_LANES {
+            let write_flag = periodic_values[WRITE_SELECTORS + lane];
+            for (half, &value) in written.iter().enumerate() {
+                let column = self.stored_lane_columns(lane)[half];
+                let mut expected = write_flag * value;
+                let mut keep_flag = continuation - write_flag;
+                if lane >= NUM_LANES {
+                    let x = lane - NUM_LANES;
+                    for (plane, &flag) in loads.iter().enumerate() {
+                        expected += flag * current[self.stored_lane_columns(x + 5 * plane)[half]];
+                    }
+                    keep_flag -= load_flag;
+                }
+                result[offset] =
+                    continuation * next[column] - keep_flag * current[column] - expected;
+                offset += 1;
+            }
+        }
+    }
+
+    // ASSERTIONS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns assertions which bind this gadget to permutations of the specified states.
+    ///
+    /// For each permutation, the assertions fix the lanes at its input step to the input state,
+    /// and at its result step to the permuted state.
+    ///
+    /// # Panics
+    /// Panics if `trace_length` is not a multiple of [CYCLE_LENGTH] or is smaller than
+    /// [min_trace_length()](Self::min_trace_length) for the inputs.
+    pub fn get_assertions(
+        &self,
+        inputs: &[KeccakState],
+        trace_length: usize,
+    ) -> Vec<Assertion<BaseElement>> {
+        validate_trace_length(inputs.len(), trace_length);
+
+        let mut result = Vec::with_capacity(4 * NUM_LANES * inputs.len());
+        for (i, input) in inputs.iter().enumerate() {
+            let mut output = *input;
+            permute(&mut output);
+            for (step, state) in [(Self::input_step(i), input), (Self::result_step(i), &output)] {
+                for (lane, &value) in state.iter().enumerate() {
+                    let [lo, hi] = self.lane_columns(lane);
+                    result.push(Assertion::single(lo, step, BaseElement::from(value as u32)));
+                    result.push(Assertion::single(
+                        hi,
+                        step,
+                        BaseElement::from((value >> 32) as u32),
+                    ));
+                }
+            }
+        }
+
+        result
+    }
+
+    // HELPER METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns indexes of the columns holding halves of a lane of the state or of a temporary
+    /// lane; temporary lanes follow the lanes of the state.
+    fn stored_lane_columns(&self, lane: usize) -> [usize; 2] {
+        let column = self.offset + 2 * lane;
+        [column, column + 1]
+    }
+
+    /// Returns the range of columns holding bits of the specified register.
+    fn register_columns(&self, register: usize) -> Range<usize> {
+        let start = self.offset + 2 * NUM_STORED_LANES + register * LANE_BITS;
+        start..start + LANE_BITS
+    }
+}
+
+// ROUND SCHEDULE
+// ================================================================================================
+
+/// A lane read into a register.
+#[derive(Debug, Clone, Copy)]
+struct Read {
+    lane: usize,
+    /// When set, the register holds the lane rotated left by one bit.
+    rotated: bool,
+}
+
+impl Read {
+    fn new(lane: usize) -> Option<Self> {
+        Some(Self {
+            lane,
+            rotated: false,
+        })
+    }
+
+    fn rotated(lane: usize) -> Option<Self> {
+        Some(Self {
+            lane,
+            rotated: true,
+        })
+    }
+
+    fn value(&self, lanes: &[u64; NUM_STORED_LANES]) -> u64 {
+        lanes[self.lane].rotate_left(self.rotated as u32)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum WriteKind {
+    /// XOR of all registers, rotated left.
+    Xor,
+    /// Chi over the registers: `a ^ (!b & c)`, plus the round constant at [IOTA_ROW].
+    Chi,
+}
+
+/// A lane written by a row, and the function of the registers written into it.
+#[derive(Debug, Clone, Copy)]
+struct Write {
+    lane: usize,
+    kind: WriteKind,
+    rotation: u32,
+}
+
+impl Write {
+    fn value(&self, registers: &[u64; NUM_REGISTERS], round: usize) -> u64 {
+        let [a, b, c] = *registers;
+        match self.kind {
+            WriteKind::Xor => (a ^ b ^ c).rotate_left(self.rotation),
+            WriteKind::Chi if self.lane == 0 && round < NUM_ROUNDS => {
+                a ^ (!b & c) ^ ROUND_CONSTANTS[round]
+            }
+            WriteKind::Chi => a ^ (!b & c),
+        }
+    }
+}
+
+/// An operation performed by a single row of a round: registers of the row hold bits of the
+/// lanes read from the previous row; the lane written by the row, and the plane loaded into the
+/// temporary lanes by the row, appear in the next row.
+#[derive(Debug, Clone, Copy, Default)]
+struct RowOp {
+    reads: [Option<Read>; NUM_REGISTERS],
+    write: Option<Write>,
+    load: Option<usize>,
+}
+
+/// Returns operations performed by rows of a round.
+fn build_schedule() -> [RowOp; ROUND_LENGTH] {
+    let mut schedule = [RowOp::default(); ROUND_LENGTH];
+    let temp = |x: usize| NUM_LANES + x % 5;
+    let xor = |lane, rotation| {
+        Some(Write {
+            lane,
+            kind: WriteKind::Xor,
+            rotation,
+        })
+    };
+
+    // column parities: rows 1 - 5 compute parities of the first three lanes of each column,
+    // rows 6 - 10 add the remaining two lanes
+    for x in 0..5 {
+        schedule[1 + x] = RowOp {
+            reads: [Read::new(x), Read::new(x + 5), Read::new(x + 10)],
+            write: xor(temp(x), 0),
+            load: None,
+        };
+        schedule[6 + x] = RowOp {
+            reads: [Read::new(temp(x)), Read::new(x + 15), Read::new(x + 20)],
+            write: xor(temp(x), 0),
+            load: None,
+        };
+    }
+
+    // theta, rho and pi: rows 11 - 34 follow the cycle formed by pi starting at lane (2, 0),
+    // and row 35 processes lane (0, 0) which is fixed by pi. parities read by row 11 are already
+    // computed for lane (2, 0), and with this start no lane is written by two rows which are 32
+    // rows apart; such writes would reduce the degree of the write selector of the lane
+    let mut lanes = [0; NUM_LANES];
+    lanes[0] = 2;
+    for i in 1..NUM_LANES - 1 {
+        lanes[i] = pi(lanes[i - 1]);
+    }
+    for (op, lane) in schedule[11..36].iter_mut().zip(lanes) {
+        let x = lane % 5;
+        *op = RowOp {
+            reads: [Read::new(lane), Read::new(temp(x + 4)), Read::rotated(temp(x + 1))],
+            write: xor(pi(lane), RHO_OFFSETS[lane]),
+            load: None,
+        };
+    }
+
+    // chi and iota: plane y is loaded into temporary lanes by row 36 + 5y, and chi is computed
+    // for its lanes by rows 38 + 5y - 42 + 5y
+    for y in 0..5 {
+        schedule[36 + 5 * y].load = Some(y);
+        for x in 0..5 {
+            let row = &mut schedule[38 + 5 * y + x];
+            row.reads = [Read::new(temp(x)), Read::new(temp(x + 1)), Read::new(temp(x + 2))];
+            row.write = Some(Write {
+                lane: x + 5 * y,
+                kind: WriteKind::Chi,
+                rotation: 0,
+            });
+        }
+    }
+
+    schedule
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns the index of the lane into which pi moves the lane with the specified index.
+fn pi(lane: usize) -> usize {
+    let (x, y) = (lane % 5, lane / 5);
+    y + 5 * ((2 * x + 3 * y) % 5)
+}
+
+/// Returns the specified half of the value whose bit `i` is `bits[(i + rotation) % 64]`.
+fn pack<E: FieldElement>(bits: &[E], half: usize, rotation: usize) -> E {
+    (32 * half..32 * half + 32)
+        .rev()
+        .fold(E::ZERO, |acc, i| acc.double() + bits[(i + rotation) % LANE_BITS])
+}
+
+/// Returns XOR of two binary values.
+fn xor2<E: FieldElement>(a: E, b: E) -> E {
+    a + b - (a * b).double()
+}
+
+/// Returns XOR of three binary values.
+fn xor3<E: FieldElement>(a: E, b: E, c: E) -> E {
+    xor2(xor2(a, b), c)
+}
+
+fn validate_trace_length(num_permutations: usize, trace_length: usize) {
+    assert_eq!(
+        trace_length % CYCLE_LENGTH,
+        0,
+        "trace length must be a multiple of {CYCLE_LENGTH}, but was {trace_length}"
+    );
+    let min_trace_length = num_permutations * CYCLE_LENGTH;
+    assert!(
+        trace_length >= min_trace_length,
+        "trace length must be at least {min_trace_length} to compute {num_permutations} permutations, but was {trace_length}"
+    );
+}
@@ -0,0 +1,39 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+//! Reusable constraint gadgets for AIRs over the 64-bit field [f64](math::fields::f64).
+//!
+//! A gadget occupies a group of trace columns starting at an offset specified on construction,
+//! builds these columns, and writes evaluations of its constraints into a slice, in the same way
+//! as [Air::evaluate_transition()](crate::Air::evaluate_transition) does. Thus, gadgets can be
+//! combined with each other and with hand-written constraints in the transition constraints of
+//! an AIR, or wrapped into a [Component](crate::Component). Each gadget also describes the
+//! degrees of its constraints, its periodic columns, and the assertions binding its columns to
+//! public inputs.
+//!
+//! The following gadgets are available:
+//! * [bitwise] - 32-bit AND, XOR, and OR operations.
+//! * [uint32] - 32-bit ADD, SUB, and MUL operations, including comparisons.
+//! * [keccak] - Keccak-f\[1600\] permutations.
+//! * [running_hash] - binding a stream of data consumed by an AIR to its [Rp64_256] digest.
+//! * [rescue] - rounds of the [Rp64_256] permutation.
+
+use crypto::hashers::Rp64_256;
+use math::fields::f64::BaseElement;
+
+pub mod bitwise;
+pub mod keccak;
+pub mod rescue;
+pub mod running_hash;
+pub mod uint32;
+
+#[cfg(test)]
+mod tests;
+
+// CONSTANTS
+// ================================================================================================
+
+/// Number of elements in the state of the [Rp64_256] permutation.
+const STATE_WIDTH: usize = Rp64_256::STATE_WIDTH;
@@ -0,0 +1,108 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+//! Constraints for rounds of the [Rp64_256] permutation.
+//!
+//! A single round maps `state` to `mds(sbox^-1(mds(sbox(state)) + ark1)) + ark2`. Since the
+//! inverse S-box has high degree, the round is constrained by computing the midpoint of the
+//! round from both sides: `mds(sbox(current)) + ark1 = sbox(mds^-1(next - ark2))`, which results
+//! in constraints of degree 7.
+
+use super::{BaseElement, Rp64_256, STATE_WIDTH};
+use math::FieldElement;
+use utils::collections::Vec;
+
+// CONSTANTS
+// ================================================================================================
+
+/// Number of rounds in a single permutation.
+pub const NUM_ROUNDS: usize = Rp64_256::NUM_ROUNDS;
+
+/// Number of trace rows per permutation: the input state followed by the state after each
+/// round.
+pub const CYCLE_LENGTH: usize = NUM_ROUNDS + 1;
+
+/// Number of constraints enforced by [enforce_round()].
+pub const NUM_CONSTRAINTS: usize = STATE_WIDTH;
+
+/// Degree of constraints enforced by [enforce_round()].
+pub const CONSTRAINT_DEGREE: usize = 7;
+
+// ROUND CONSTRAINTS
+// ================================================================================================
+
+/// Enforces that `next` is the result of applying a single permutation round to `current` when
+/// `flag` is one.
+///
+/// `ark` must contain the first and the second set of round constants for the round (i.e.,
+/// `2 * STATE_WIDTH` values); these can be supplied via periodic columns returned by
+/// [get_periodic_column_values()].
+pub fn enforce_round<E>(current: &[E], next: &[E], ark: &[E], flag: E, result: &mut [E])
+where
+    E: FieldElement + From<BaseElement>,
+{
+    // compute the midpoint of the round from the current state
+    let mut forward = [E::ZERO; STATE_WIDTH];
+    forward.iter_mut().zip(current).for_each(|(f, &c)| *f = sbox(c));
+    let mut forward = apply_matrix(&Rp64_256::MDS, &forward);
+    forward.iter_mut().zip(&ark[..STATE_WIDTH]).for_each(|(f, &k)| *f += k);
+
+    // compute the midpoint of the round from the next state
+    let mut backward = [E::ZERO; STATE_WIDTH];
+    backward
+        .iter_mut()
+        .zip(next.iter().zip(&ark[STATE_WIDTH..]))
+        .for_each(|(b, (&n, &k))| *b = n - k);
+    let mut backward = apply_matrix(&Rp64_256::INV_MDS, &backward);
+    backward.iter_mut().for_each(|b| *b = sbox(*b));
+
+    for i in 0..STATE_WIDTH {
+        result[i] = flag * (backward[i] - forward[i]);
+    }
+}
+
+/// Returns values of periodic columns for permutations aligned with cycles of [CYCLE_LENGTH]
+/// rows.
+///
+/// The first column is a flag which is set to one on steps at which a round is applied (i.e.,
+/// all steps of a cycle but the last one); it is followed by `STATE_WIDTH` columns of the first
+/// set of round constants, and `STATE_WIDTH` columns of the second set of round constants.
+pub fn get_periodic_column_values() -> Vec<Vec<BaseElement>> {
+    let mut result = vec![vec![BaseElement::ZERO; CYCLE_LENGTH]; 1 + 2 * STATE_WIDTH];
+    for (round, (ark1, ark2)) in Rp64_256::ARK1.iter().zip(Rp64_256::ARK2.iter()).enumerate() {
+        result[0][round] = BaseElement::ONE;
+        for i in 0..STATE_WIDTH {
+            result[1 + i][round] = ark1[i];
+            result[1 + STATE_WIDTH + i][round] = ark2[i];
+        }
+    }
+    result
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+#[inline(always)]
+fn sbox<E: FieldElement>(x: E) -> E {
+    let x2 = x.square();
+    let x4 = x2.square();
+    x4 * x2 * x
+}
+
+fn apply_matrix<E>(
+    matrix: &[[BaseElement; STATE_WIDTH]; STATE_WIDTH],
+    state: &[E],
+) -> [E; STATE_WIDTH]
+where
+    E: FieldElement + From<BaseElement>,
+{
+    let mut result = [E::ZERO; STATE_WIDTH];
+    for (r, row) in result.iter_mut().zip(matrix) {
+        for (&m, &s) in row.iter().zip(state) {
+            *r += E::from(m) * s;
+        }
+    }
+    result
+}
@@ -0,0 +1,338 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+//! Constraints binding a stream of data consumed by an AIR to a digest in public inputs.
+//!
+//! Delegated computations (e.g., rollups) often consume data which is too large to be passed to
+//! the verifier as public inputs, such as transaction calldata. Instead, only a digest of the
+//! data is made public, and the AIR must prove that the data it consumed hashes to this digest.
+//! [RunningHashColumn] absorbs the data into an [Rp64_256] sponge in the trace;
+//! its assertions bind the trace to the number of data elements and to a digest equal to
+//! `Rp64_256::hash_elements(data)`, which the verifier can compute with [hash_data()].
+//!
+//! The data is absorbed in blocks of [RATE_WIDTH] elements; absorbing a block takes
+//! [CYCLE_LENGTH] rows: the first row of a cycle holds the block in the data columns and the
+//! state of the sponge before the block is absorbed, and each of the remaining rows applies a
+//! single permutation round. After the last block is absorbed, the state remains unchanged until
+//! the end of the trace. Common mistakes in hand-written versions of this pattern which the
+//! gadget guards against include: not binding the initial state of the sponge, not binding the
+//! number of absorbed blocks (which lets the prover append data), and checking the digest at a
+//! row other than the one at which the last permutation ends.
+
+use super::{rescue, BaseElement, Rp64_256, STATE_WIDTH};
+use crate::{Assertion, EvaluationFrame, TransitionConstraintDegree};
+use core::ops::Range;
+use crypto::ElementHasher;
+use math::FieldElement;
+use utils::collections::Vec;
+
+// CONSTANTS
+// ================================================================================================
+
+/// Number of elements in an [Rp64_256] digest.
+pub const DIGEST_SIZE: usize = 4;
+
+/// An [Rp64_256] digest represented by its elements.
+pub type Word = [BaseElement; DIGEST_SIZE];
+
+/// Number of data elements absorbed by a single permutation.
+pub const RATE_WIDTH: usize = Rp64_256::RATE_RANGE.end - Rp64_256::RATE_RANGE.start;
+
+/// Number of trace rows per absorbed block: a row at which the block is absorbed, followed by a
+/// row per permutation round.
+pub const CYCLE_LENGTH: usize = rescue::CYCLE_LENGTH;
+
+/// Number of trace columns occupied by the gadget: the active flag, the state of the sponge, and
+/// the data block.
+pub const NUM_COLUMNS: usize = 1 + STATE_WIDTH + RATE_WIDTH;
+
+/// Number of constraints enforced by [RunningHashColumn::evaluate_transition()].
+pub const NUM_CONSTRAINTS: usize = 2 + STATE_WIDTH;
+
+/// Number of periodic columns expected by [RunningHashColumn::evaluate_transition()].
+pub const NUM_PERIODIC_COLUMNS: usize = 1 + 2 * STATE_WIDTH;
+
+// RUNNING HASH COLUMN
+// ================================================================================================
+
+/// A group of trace columns which computes the [Rp64_256] hash of a stream of
+/// data elements.
+///
+/// Starting at the column offset specified on construction, the gadget occupies [NUM_COLUMNS]
+/// columns of the main trace segment laid out as follows:
+/// - the active flag, which is set to one while blocks of data are being absorbed;
+/// - `STATE_WIDTH` columns of the sponge state;
+/// - [RATE_WIDTH] data columns; values in these columns are absorbed only on the first row of
+///   each active cycle, and thus, other constraints of the AIR can refer to data elements at
+///   these rows. The last block is padded with zeros.
+///
+/// To use the gadget, an AIR needs to:
+/// - include [constraint_degrees()](Self::constraint_degrees) into its transition constraint
+///   degrees and call [evaluate_transition()](Self::evaluate_transition) from
+///   [Air::evaluate_transition()](crate::Air::evaluate_transition);
+/// - include [get_periodic_column_values()](Self::get_periodic_column_values) into its periodic
+///   columns;
+/// - include [get_assertions()](Self::get_assertions) into its assertions; this requires the
+///   number of data elements and the digest to be a part of the public inputs.
+///
+/// The prover fills the gadget's columns with [build_trace_columns()](Self::build_trace_columns).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RunningHashColumn {
+    offset: usize,
+}
+
+impl RunningHashColumn {
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a gadget occupying [NUM_COLUMNS] columns of the main trace segment starting at
+    /// the specified column.
+    pub fn new(offset: usize) -> Self {
+        Self { offset }
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the range of all trace columns occupied by this gadget.
+    pub fn columns(&self) -> Range<usize> {
+        self.offset..self.offset + NUM_COLUMNS
+    }
+
+    /// Returns the index of the active flag column.
+    pub fn active_column(&self) -> usize {
+        self.offset
+    }
+
+    /// Returns the range of columns holding the state of the sponge.
+    pub fn state_columns(&self) -> Range<usize> {
+        self.offset + 1..self.offset + 1 + STATE_WIDTH
+    }
+
+    /// Returns the range of columns holding blocks of data absorbed into the sponge.
+    pub fn data_columns(&self) -> Range<usize> {
+        self.offset + 1 + STATE_WIDTH..self.offset + NUM_COLUMNS
+    }
+
+    /// Returns the minimum length of a trace which can absorb the specified number of data
+    /// elements.
+    ///
+    /// The trace must contain at least one row after the last permutation so that the digest
+    /// can be read at the last row of the trace.
+    pub fn min_trace_length(num_elements: usize) -> usize {
+        let num_rows = num_blocks(num_elements) * CYCLE_LENGTH + 1;
+        num_rows.next_power_of_two().max(CYCLE_LENGTH)
+    }
+
+    // PROVER
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns [NUM_COLUMNS] trace columns of the specified length which absorb the specified
+    /// data; the columns are ordered as described in [RunningHashColumn].
+    ///
+    /// # Panics
+    /// Panics if `trace_length` is not a multiple of [CYCLE_LENGTH] or is smaller than
+    /// [min_trace_length()](Self::min_trace_length) for the data.
+    pub fn build_trace_columns(data: &[BaseElement], trace_length: usize) -> Vec<Vec<BaseElement>> {
+        validate_trace_length(data.len(), trace_length);
+
+        let mut columns = vec![vec![BaseElement::ZERO; trace_length]; NUM_COLUMNS];
+        let mut state = [BaseElement::ZERO; STATE_WIDTH];
+        state[Rp64_256::CAPACITY_RANGE.start] = BaseElement::new(data.len() as u64);
+
+        let num_active_rows = num_blocks(data.len()) * CYCLE_LENGTH;
+        for step in 0..trace_length {
+            columns[1..=STATE_WIDTH].iter_mut().zip(state).for_each(|(c, s)| c[step] = s);
+            if step >= num_active_rows {
+                continue;
+            }
+
+            columns[0][step] = BaseElement::ONE;
+            let position = step % CYCLE_LENGTH;
+            if position == 0 {
+                let block_start = step / CYCLE_LENGTH * RATE_WIDTH;
+                let block = &data[block_start..data.len().min(block_start + RATE_WIDTH)];
+                for (i, &element) in block.iter().enumerate() {
+                    columns[1 + STATE_WIDTH + i][step] = element;
+                    state[Rp64_256::RATE_RANGE.start + i] += element;
+                }
+            } else {
+                Rp64_256::apply_round(&mut state, position - 1);
+            }
+        }
+
+        columns
+    }
+
+    // CONSTRAINTS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns degrees of the constraints enforced by
+    /// [evaluate_transition()](Self::evaluate_transition).
+    pub fn constraint_degrees() -> Vec<TransitionConstraintDegree> {
+        let mut result = vec![TransitionConstraintDegree::new(2); 2];
+        result.resize(
+            NUM_CONSTRAINTS,
+            TransitionConstraintDegree::with_cycles(
+                rescue::CONSTRAINT_DEGREE + 1,
+                vec![CYCLE_LENGTH],
+            ),
+        );
+        result
+    }
+
+    /// Returns values of the periodic columns expected by
+    /// [evaluate_transition()](Self::evaluate_transition).
+    ///
+    /// The first column is a flag which is set to one on the first row of each cycle (i.e., the
+    /// row at which a block is absorbed); it is followed by `STATE_WIDTH` columns of the first
+    /// set of round constants, and `STATE_WIDTH` columns of the second set of round constants,
+    /// aligned with the rows at which rounds are applied.
+    pub fn get_periodic_column_values() -> Vec<Vec<BaseElement>> {
+        let mut result = vec![vec![BaseElement::ZERO; CYCLE_LENGTH]; NUM_PERIODIC_COLUMNS];
+        result[0][0] = BaseElement::ONE;
+        for (round, (ark1, ark2)) in Rp64_256::ARK1.iter().zip(Rp64_256::ARK2.iter()).enumerate() {
+            for i in 0..STATE_WIDTH {
+                result[1 + i][round + 1] = ark1[i];
+                result[1 + STATE_WIDTH + i][round + 1] = ark2[i];
+            }
+        }
+        result
+    }
+
+    /// Evaluates [NUM_CONSTRAINTS] transition constraints of this gadget over the specified
+    /// frame and writes the results into `result`.
+    ///
+    /// `periodic_values` must contain values of the columns returned by
+    /// [get_periodic_column_values()](Self::get_periodic_column_values) in the same order. The
+    /// constraints require:
+    /// - the active flag to be binary and, once reset to zero, to remain zero;
+    /// - the data block to be added to the rate portion of the state on the first row of each
+    ///   active cycle, and a permutation round to be applied to the state on other active rows;
+    /// - the state to remain unchanged on inactive rows.
+    pub fn evaluate_transition<E>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        periodic_values: &[E],
+        result: &mut [E],
+    ) where
+        E: FieldElement + From<BaseElement>,
+    {
+        let current = frame.current();
+        let next = frame.next();
+        let active = current[self.active_column()];
+        let next_active = next[self.active_column()];
+
+        result[0] = active * (active - E::ONE);
+        result[1] = next_active * (E::ONE - active);
+
+        let state = &current[self.state_columns()];
+        let next_state = &next[self.state_columns()];
+        let data = &current[self.data_columns()];
+
+        let mut round = [E::ZERO; rescue::NUM_CONSTRAINTS];
+        let ark = &periodic_values[1..NUM_PERIODIC_COLUMNS];
+        rescue::enforce_round(state, next_state, ark, E::ONE, &mut round);
+
+        let absorb_flag = active * periodic_values[0];
+        let round_flag = active - absorb_flag;
+        let copy_flag = E::ONE - active;
+        for i in 0..STATE_WIDTH {
+            let delta = next_state[i] - state[i];
+            let absorbed = if Rp64_256::RATE_RANGE.contains(&i) {
+                delta - data[i - Rp64_256::RATE_RANGE.start]
+            } else {
+                delta
+            };
+            result[2 + i] = absorb_flag * absorbed + round_flag * round[i] + copy_flag * delta;
+        }
+    }
+
+    // ASSERTIONS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the number of assertions returned by [get_assertions()](Self::get_assertions) for
+    /// the specified number of data elements.
+    pub fn num_assertions(num_elements: usize) -> usize {
+        let num_active_flag_assertions = if num_blocks(num_elements) > 0 { 2 } else { 1 };
+        STATE_WIDTH + num_active_flag_assertions + DIGEST_SIZE
+    }
+
+    /// Returns assertions which bind this gadget to a stream of `num_elements` data elements
+    /// hashing to the specified digest.
+    ///
+    /// The assertions fix the initial state of the sponge, require the active flag to be set
+    /// for exactly as many cycles as there are blocks of data, and require the digest portion
+    /// of the state at the last step of the trace to be equal to `digest`.
+    ///
+    /// # Panics
+    /// Panics if `trace_length` is not a multiple of [CYCLE_LENGTH] or is smaller than
+    /// [min_trace_length()](Self::min_trace_length) for the specified number of elements.
+    pub fn get_assertions(
+        &self,
+        num_elements: usize,
+        digest: &Word,
+        trace_length: usize,
+    ) -> Vec<Assertion<BaseElement>> {
+        validate_trace_length(num_elements, trace_length);
+        let mut result = Vec::with_capacity(Self::num_assertions(num_elements));
+
+        let state_columns = self.state_columns();
+        for (i, column) in state_columns.clone().enumerate() {
+            let value = if i == Rp64_256::CAPACITY_RANGE.start {
+                BaseElement::new(num_elements as u64)
+            } else {
+                BaseElement::ZERO
+            };
+            result.push(Assertion::single(column, 0, value));
+        }
+
+        let num_active_rows = num_blocks(num_elements) * CYCLE_LENGTH;
+        if num_active_rows > 0 {
+            result.push(Assertion::single(
+                self.active_column(),
+                num_active_rows - 1,
+                BaseElement::ONE,
+            ));
+        }
+        result.push(Assertion::single(self.active_column(), num_active_rows, BaseElement::ZERO));
+
+        let digest_start = state_columns.start + Rp64_256::DIGEST_RANGE.start;
+        for (i, &value) in digest.iter().enumerate() {
+            result.push(Assertion::single(digest_start + i, trace_length - 1, value));
+        }
+
+        result
+    }
+}
+
+// VERIFIER
+// ================================================================================================
+
+/// Returns the digest of the specified data which a [RunningHashColumn] absorbing the data is
+/// bound to.
+pub fn hash_data(data: &[BaseElement]) -> Word {
+    Rp64_256::hash_elements(data).into()
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+fn num_blocks(num_elements: usize) -> usize {
+    num_elements.div_ceil(RATE_WIDTH)
+}
+
+fn validate_trace_length(num_elements: usize, trace_length: usize) {
+    assert_eq!(
+        trace_length % CYCLE_LENGTH,
+        0,
+        "trace length must be a multiple of {CYCLE_LENGTH}, but was {trace_length}"
+    );
+    let min_trace_length = num_blocks(num_elements) * CYCLE_LENGTH + 1;
+    assert!(
+        trace_length >= min_trace_length,
+        "trace length must be at least {min_trace_length} to absorb {num_elements} elements, but was {trace_length}"
+    );
+}
@@ -0,0 +1,349 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{
+    bitwise::{self, BitwiseColumns, BitwiseOp, BitwiseOperation},
+    keccak::{self, KeccakColumns, KeccakState},
+    rescue,
+    running_hash::{self, RunningHashColumn, Word},
+    uint32::{self, U32Columns, U32Op, U32Operation},
+    BaseElement, Rp64_256, STATE_WIDTH,
+};
+use crate::EvaluationFrame;
+use math::FieldElement;
+use rand_utils::rand_array;
+use utils::collections::Vec;
+
+// GADGETS
+// ================================================================================================
+
+#[test]
+fn rescue_round_gadget() {
+    let periodic_values = rescue::get_periodic_column_values();
+    let mut state: [BaseElement; STATE_WIDTH] = rand_array();
+    let mut result = [BaseElement::ZERO; rescue::NUM_CONSTRAINTS];
+    for round in 0..rescue::NUM_ROUNDS {
+        let current = state;
+        Rp64_256::apply_round(&mut state, round);
+        let ark = periodic_values[1..].iter().map(|column| column[round]).collect::<Vec<_>>();
+
+        rescue::enforce_round(&current, &state, &ark, BaseElement::ONE, &mut result);
+        assert!(result.iter().all(|&v| v == BaseElement::ZERO));
+
+        // a wrong next state violates the constraints
+        let mut next = state;
+        next[3] += BaseElement::ONE;
+        rescue::enforce_round(&current, &next, &ark, BaseElement::ONE, &mut result);
+        assert_ne!(BaseElement::ZERO, result[3]);
+    }
+}
+
+#[test]
+fn running_hash_gadget() {
+    let gadget = RunningHashColumn::new(0);
+    let periodic_values = RunningHashColumn::get_periodic_column_values();
+    for num_elements in [0, 1, 8, 13, 24] {
+        let data = (0..num_elements)
+            .map(|i| BaseElement::new(3 * i as u64 + 1))
+            .collect::<Vec<_>>();
+        let trace_length = RunningHashColumn::min_trace_length(num_elements);
+        let columns = RunningHashColumn::build_trace_columns(&data, trace_length);
+
+        // the digest at the last step is the hash of the data
+        let digest: Word = columns[gadget.state_columns()]
+            .iter()
+            .skip(Rp64_256::DIGEST_RANGE.start)
+            .take(4)
+            .map(|column| column[trace_length - 1])
+            .collect::<Vec<_>>()
+            .try_into()
+            .unwrap();
+        assert_eq!(running_hash::hash_data(&data), digest);
+
+        // all transition constraints are satisfied
+        let mut result = [BaseElement::ZERO; running_hash::NUM_CONSTRAINTS];
+        for step in 0..trace_length - 1 {
+            let mut frame = EvaluationFrame::new(running_hash::NUM_COLUMNS);
+            for (i, column) in columns.iter().enumerate() {
+                frame.current_mut()[i] = column[step];
+                frame.next_mut()[i] = column[step + 1];
+            }
+            let periodic_row = periodic_values
+                .iter()
+                .map(|column| column[step % running_hash::CYCLE_LENGTH])
+                .collect::<Vec<_>>();
+            gadget.evaluate_transition(&frame, &periodic_row, &mut result);
+            assert!(result.iter().all(|&v| v == BaseElement::ZERO), "step {step}");
+        }
+
+        // and so are all assertions
+        let assertions = gadget.get_assertions(num_elements, &digest, trace_length);
+        assert_eq!(RunningHashColumn::num_assertions(num_elements), assertions.len());
+        for assertion in assertions {
+            assertion.apply(trace_length, |step, value| {
+                assert_eq!(value, columns[assertion.column()][step]);
+            });
+        }
+    }
+}
+
+#[test]
+fn bitwise_gadget() {
+    let gadget = BitwiseColumns::new(0);
+    let periodic_values = BitwiseColumns::get_periodic_column_values();
+    let operations = build_bitwise_operations();
+    let trace_length = BitwiseColumns::min_trace_length(operations.len());
+    let columns = BitwiseColumns::build_trace_columns(&operations, trace_length);
+
+    // the accumulators hold the operands and the result at the last row of each cycle
+    for (i, operation) in operations.iter().enumerate() {
+        let step = BitwiseColumns::result_step(i);
+        assert_eq!(BaseElement::from(operation.a), columns[gadget.a_column()][step]);
+        assert_eq!(BaseElement::from(operation.b), columns[gadget.b_column()][step]);
+        assert_eq!(BaseElement::from(operation.result()), columns[gadget.z_column()][step]);
+    }
+
+    // all transition constraints are satisfied
+    let evaluate = |columns: &[Vec<BaseElement>], step: usize| {
+        let mut frame = EvaluationFrame::new(bitwise::NUM_COLUMNS);
+        for (i, column) in columns.iter().enumerate() {
+            frame.current_mut()[i] = column[step];
+            frame.next_mut()[i] = column[step + 1];
+        }
+        let periodic_row = periodic_values
+            .iter()
+            .map(|column| column[step % bitwise::CYCLE_LENGTH])
+            .collect::<Vec<_>>();
+        let mut result = [BaseElement::ZERO; bitwise::NUM_CONSTRAINTS];
+        gadget.evaluate_transition(&frame, &periodic_row, &mut result);
+        result
+    };
+    for step in 0..trace_length - 1 {
+        assert!(evaluate(&columns, step).iter().all(|&v| v == BaseElement::ZERO), "step {step}");
+    }
+    assert_eq!(bitwise::NUM_CONSTRAINTS, BitwiseColumns::constraint_degrees().len());
+
+    // and so are all assertions
+    let assertions = gadget.get_assertions(&operations, trace_length);
+    for assertion in assertions {
+        assertion.apply(trace_length, |step, value| {
+            assert_eq!(value, columns[assertion.column()][step]);
+        });
+    }
+
+    // a result which differs from the result of the selected operation violates the constraints
+    let mut tampered = columns.clone();
+    tampered[gadget.xor_column()][..bitwise::CYCLE_LENGTH].fill(BaseElement::ONE);
+    let step = bitwise::CYCLE_LENGTH - 2;
+    assert!(evaluate(&tampered, step).iter().any(|&v| v != BaseElement::ZERO));
+
+    // so does switching the operation in the middle of a cycle
+    let mut tampered = columns.clone();
+    tampered[gadget.xor_column()][step + 1] = BaseElement::ONE;
+    assert!(evaluate(&tampered, step).iter().any(|&v| v != BaseElement::ZERO));
+}
+
+#[test]
+fn bitwise_split_u64() {
+    let (a, b) = (0x0123_4567_89ab_cdef_u64, 0xfedc_ba98_7654_3210_u64);
+    for (op, expected) in [(BitwiseOp::And, a & b), (BitwiseOp::Xor, a ^ b), (BitwiseOp::Or, a | b)]
+    {
+        let [high, low] = BitwiseOperation::split_u64(op, a, b);
+        assert_eq!(expected, bitwise::combine_u64(high.result(), low.result()));
+    }
+}
+
+#[test]
+fn u32_gadget() {
+    let gadget = U32Columns::new(0);
+    let periodic_values = U32Columns::get_periodic_column_values();
+    let operations = build_u32_operations();
+    let trace_length = U32Columns::min_trace_length(operations.len());
+    let columns = U32Columns::build_trace_columns(&operations, trace_length);
+
+    // the accumulators hold the operands and the result at the last row of each cycle
+    for (i, operation) in operations.iter().enumerate() {
+        let step = U32Columns::result_step(i);
+        let (hi, lo) = operation.result();
+        assert_eq!(BaseElement::from(operation.a), columns[gadget.a_column()][step]);
+        assert_eq!(BaseElement::from(operation.b), columns[gadget.b_column()][step]);
+        assert_eq!(BaseElement::from(hi), columns[gadget.hi_column()][step]);
+        assert_eq!(BaseElement::from(lo), columns[gadget.lo_column()][step]);
+    }
+
+    // all transition constraints are satisfied
+    let evaluate = |columns: &[Vec<BaseElement>], step: usize| {
+        let mut frame = EvaluationFrame::new(uint32::NUM_COLUMNS);
+        for (i, column) in columns.iter().enumerate() {
+            frame.current_mut()[i] = column[step];
+            frame.next_mut()[i] = column[step + 1];
+        }
+        let periodic_row = periodic_values
+            .iter()
+            .map(|column| column[step % uint32::CYCLE_LENGTH])
+            .collect::<Vec<_>>();
+        let mut result = [BaseElement::ZERO; uint32::NUM_CONSTRAINTS];
+        gadget.evaluate_transition(&frame, &periodic_row, &mut result);
+        result
+    };
+    for step in 0..trace_length - 1 {
+        assert!(evaluate(&columns, step).iter().all(|&v| v == BaseElement::ZERO), "step {step}");
+    }
+    assert_eq!(uint32::NUM_CONSTRAINTS, U32Columns::constraint_degrees().len());
+
+    // and so are all assertions
+    let assertions = gadget.get_assertions(&operations, trace_length);
+    for assertion in assertions {
+        assertion.apply(trace_length, |step, value| {
+            assert_eq!(value, columns[assertion.column()][step]);
+        });
+    }
+
+    // a result which differs from the result of the selected operation violates the constraints
+    let step = U32Columns::result_step(0);
+    let mut tampered = columns.clone();
+    tampered[gadget.sub_column()][..uint32::CYCLE_LENGTH].fill(BaseElement::ONE);
+    assert!(evaluate(&tampered, step).iter().any(|&v| v != BaseElement::ZERO));
+
+    // so does a result which is equal to the correct one only modulo the field modulus: since
+    // (2^32 - 1) * 2^32 = -1 mod p, hi = 2^32 - 1 and lo = a * b + 1 satisfy the MUL equation
+    let step = U32Columns::result_step(2);
+    let mut tampered = columns;
+    let (a, b) = (operations[2].a as u64, operations[2].b as u64);
+    tampered[gadget.hi_column()][step] = BaseElement::from(u32::MAX);
+    tampered[gadget.lo_column()][step] = BaseElement::new(a * b + 1);
+    let result = evaluate(&tampered, step);
+    assert_eq!(BaseElement::ZERO, result[uint32::NUM_CONSTRAINTS - 2]);
+    assert_ne!(BaseElement::ZERO, result[uint32::NUM_CONSTRAINTS - 1]);
+}
+
+#[test]
+fn u32_operations() {
+    let (hi, lo) = U32Op::Add.apply(u32::MAX, 2);
+    assert_eq!((1, 1), (hi, lo));
+    let (hi, lo) = U32Op::Sub.apply(1, 2);
+    assert_eq!((1, u32::MAX), (hi, lo));
+    let (hi, lo) = U32Op::Mul.apply(u32::MAX, u32::MAX);
+    assert_eq!(u32::MAX as u64 * u32::MAX as u64, ((hi as u64) << 32) | lo as u64);
+
+    assert!(U32Operation::new(U32Op::Sub, 1, 2).is_lt());
+    assert!(!U32Operation::new(U32Op::Sub, 2, 2).is_lt());
+    assert!(!U32Operation::new(U32Op::Add, 1, 2).is_lt());
+}
+
+#[test]
+fn keccak_permutation() {
+    // lanes of the permuted zero state from the Keccak reference test vectors
+    let mut state = [0; keccak::NUM_LANES];
+    keccak::permute(&mut state);
+    assert_eq!(0xf1258f7940e1dde7, state[0]);
+    assert_eq!(0x84d5ccf933c0478a, state[1]);
+    assert_eq!(0xeaf1ff7b5ceca249, state[24]);
+}
+
+#[test]
+fn keccak_gadget() {
+    let gadget = KeccakColumns::new(0);
+    let periodic_values = KeccakColumns::get_periodic_column_values();
+    assert_eq!(keccak::NUM_PERIODIC_COLUMNS, periodic_values.len());
+    let inputs = build_keccak_inputs(2);
+    let trace_length = KeccakColumns::min_trace_length(inputs.len());
+    let columns = KeccakColumns::build_trace_columns(&inputs, trace_length);
+
+    // the lanes hold the inputs and the outputs of the permutations at the expected steps
+    for (i, input) in inputs.iter().enumerate() {
+        let mut output = *input;
+        keccak::permute(&mut output);
+        for (step, state) in
+            [(KeccakColumns::input_step(i), input), (KeccakColumns::result_step(i), &output)]
+        {
+            for (lane, &value) in state.iter().enumerate() {
+                let [lo, hi] = gadget.lane_columns(lane);
+                assert_eq!(BaseElement::from(value as u32), columns[lo][step]);
+                assert_eq!(BaseElement::from((value >> 32) as u32), columns[hi][step]);
+            }
+        }
+    }
+
+    // all transition constraints are satisfied
+    let evaluate = |columns: &[Vec<BaseElement>], step: usize| {
+        let mut frame = EvaluationFrame::new(keccak::NUM_COLUMNS);
+        for (i, column) in columns.iter().enumerate() {
+            frame.current_mut()[i] = column[step];
+            frame.next_mut()[i] = column[step + 1];
+        }
+        let periodic_row = periodic_values
+            .iter()
+            .map(|column| column[step % column.len()])
+            .collect::<Vec<_>>();
+        let mut result = [BaseElement::ZERO; keccak::NUM_CONSTRAINTS];
+        gadget.evaluate_transition(&frame, &periodic_row, &mut result);
+        result
+    };
+    for step in 0..trace_length - 1 {
+        assert!(evaluate(&columns, step).iter().all(|&v| v == BaseElement::ZERO), "step {step}");
+    }
+    assert_eq!(keccak::NUM_CONSTRAINTS, KeccakColumns::constraint_degrees().len());
+
+    // and so are all assertions
+    let assertions = gadget.get_assertions(&inputs, trace_length);
+    for assertion in assertions {
+        assertion.apply(trace_length, |step, value| {
+            assert_eq!(value, columns[assertion.column()][step]);
+        });
+    }
+
+    // changing a lane in the middle of a permutation violates the constraints, except on the
+    // transition into the next permutation
+    let [lo, _] = gadget.lane_columns(7);
+    for step in [100, keccak::CYCLE_LENGTH] {
+        let mut tampered = columns.clone();
+        tampered[lo][step] += BaseElement::ONE;
+        let violated = evaluate(&tampered, step - 1).iter().any(|&v| v != BaseElement::ZERO);
+        assert_eq!(step != keccak::CYCLE_LENGTH, violated);
+    }
+
+    // so does a register which does not hold bits of the lane it reads
+    let mut tampered = columns;
+    let register = keccak::NUM_COLUMNS - 1;
+    let step = (1..trace_length)
+        .find(|&step| tampered[register][step] == BaseElement::ONE)
+        .unwrap();
+    tampered[register][step] = BaseElement::ZERO;
+    assert!(evaluate(&tampered, step - 1).iter().any(|&v| v != BaseElement::ZERO));
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+fn build_bitwise_operations() -> Vec<BitwiseOperation> {
+    let mut operations = vec![
+        BitwiseOperation::new(BitwiseOp::And, 0xdead_beef, 0x0f0f_f0f0),
+        BitwiseOperation::new(BitwiseOp::Xor, 0x1234_5678, 0xffff_0000),
+        BitwiseOperation::new(BitwiseOp::Or, 0x8000_0001, 0x7fff_fffe),
+    ];
+    operations.extend(BitwiseOperation::split_u64(BitwiseOp::Xor, u64::MAX, 0x0123_4567_89ab_cdef));
+    operations
+}
+
+fn build_u32_operations() -> Vec<U32Operation> {
+    vec![
+        U32Operation::new(U32Op::Add, 0xffff_fff0, 0x0000_0123),
+        U32Operation::new(U32Op::Sub, 0x1234_5678, 0x8765_4321),
+        U32Operation::new(U32Op::Mul, 0x0000_0003, 0x0000_0005),
+        U32Operation::new(U32Op::Mul, u32::MAX, u32::MAX),
+        U32Operation::new(U32Op::Sub, 0x8765_4321, 0x1234_5678),
+    ]
+}
+
+fn build_keccak_inputs(num_permutations: usize) -> Vec<KeccakState> {
+    (0..num_permutations)
+        .map(|i| {
+            core::array::from_fn(|lane| {
+                (lane as u64 + 1).wrapping_mul(0x9e37_79b9_7f4a_7c15) ^ i as u64
+            })
+        })
+        .collect()
+}
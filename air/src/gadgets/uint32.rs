@@ -0,0 +1,476 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+//! Constraints for 32-bit integer arithmetic: addition with carry, subtraction with borrow, and
+//! multiplication with a 64-bit result.
+//!
+//! Arithmetic over field elements wraps around the modulus of the field rather than around
+//! `2^32`, so emulating 32-bit integers requires decomposing operands and results into limbs,
+//! range checking the limbs, and constraining carries. [U32Columns] performs all of these: the
+//! operands and both 32-bit halves of the result are decomposed into 4-bit limbs, one limb per
+//! row, so that an operation takes [CYCLE_LENGTH] rows and [NUM_COLUMNS] columns.
+//!
+//! Comparisons are performed via subtraction: `a < b` if and only if subtracting `b` from `a`
+//! results in a borrow (see [U32Operation::is_lt()]).
+
+use super::BaseElement;
+use crate::{Assertion, EvaluationFrame, TransitionConstraintDegree};
+use core::ops::Range;
+use math::FieldElement;
+use utils::collections::Vec;
+
+// CONSTANTS
+// ================================================================================================
+
+/// Number of bits of each value processed in a single row.
+pub const LIMB_BITS: usize = 4;
+
+/// Number of bits in the operands of a single operation.
+pub const OPERAND_BITS: usize = 32;
+
+/// Number of trace rows per operation: one row per limb of the operands.
+pub const CYCLE_LENGTH: usize = OPERAND_BITS / LIMB_BITS;
+
+/// Number of values decomposed into limbs: both operands and both halves of the result.
+const NUM_VALUES: usize = 4;
+
+/// Number of trace columns occupied by the gadget: two operation selectors, accumulators for
+/// both operands and both halves of the result, an inverse used to range check the result, and
+/// bits of the current limb of all accumulated values.
+pub const NUM_COLUMNS: usize = 7 + NUM_VALUES * LIMB_BITS;
+
+/// Number of constraints enforced by [U32Columns::evaluate_transition()].
+pub const NUM_CONSTRAINTS: usize = 7 + NUM_VALUES * (LIMB_BITS + 2);
+
+/// Number of periodic columns expected by [U32Columns::evaluate_transition()].
+pub const NUM_PERIODIC_COLUMNS: usize = 2;
+
+/// Value by which an accumulator is multiplied when a limb is appended to it.
+const LIMB_SHIFT: u64 = 1 << LIMB_BITS;
+
+/// Value by which the high half of a result is multiplied to get its contribution to the result.
+const HIGH_SHIFT: u64 = 1 << OPERAND_BITS;
+
+// U32 OPERATIONS
+// ================================================================================================
+
+/// An arithmetic operation supported by [U32Columns].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum U32Op {
+    /// Addition; the high half of the result is the carry.
+    Add,
+    /// Subtraction; the high half of the result is the borrow.
+    Sub,
+    /// Multiplication; the result is split into the high and the low 32 bits.
+    Mul,
+}
+
+impl U32Op {
+    /// Returns the high and the low halves of the result of applying this operation to the
+    /// specified values (in this order).
+    pub fn apply(&self, a: u32, b: u32) -> (u32, u32) {
+        match self {
+            Self::Add => {
+                let (lo, carry) = a.overflowing_add(b);
+                (carry as u32, lo)
+            }
+            Self::Sub => {
+                let (lo, borrow) = a.overflowing_sub(b);
+                (borrow as u32, lo)
+            }
+            Self::Mul => {
+                let result = a as u64 * b as u64;
+                ((result >> OPERAND_BITS) as u32, result as u32)
+            }
+        }
+    }
+
+    /// Returns values of the SUB and the MUL selector columns for this operation; ADD is
+    /// selected when both selectors are zero.
+    fn selectors(&self) -> [BaseElement; 2] {
+        match self {
+            Self::Add => [BaseElement::ZERO, BaseElement::ZERO],
+            Self::Sub => [BaseElement::ONE, BaseElement::ZERO],
+            Self::Mul => [BaseElement::ZERO, BaseElement::ONE],
+        }
+    }
+}
+
+/// An arithmetic operation applied to a pair of 32-bit operands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct U32Operation {
+    pub op: U32Op,
+    pub a: u32,
+    pub b: u32,
+}
+
+impl U32Operation {
+    /// Returns a new operation applying `op` to `a` and `b`.
+    pub fn new(op: U32Op, a: u32, b: u32) -> Self {
+        Self { op, a, b }
+    }
+
+    /// Returns the high and the low halves of the result of this operation (in this order).
+    pub fn result(&self) -> (u32, u32) {
+        self.op.apply(self.a, self.b)
+    }
+
+    /// Returns true if this is a subtraction with `a` smaller than `b`.
+    ///
+    /// The gadget computes this flag as the high half of the result of the subtraction; thus,
+    /// `a < b` is proven via a [U32Op::Sub] operation over `a` and `b`, and `a <= b` via a
+    /// [U32Op::Sub] operation over `b` and `a` which has no borrow.
+    pub fn is_lt(&self) -> bool {
+        self.op == U32Op::Sub && self.a < self.b
+    }
+}
+
+// U32 COLUMNS
+// ================================================================================================
+
+/// A group of trace columns which performs arithmetic operations over 32-bit values.
+///
+/// Starting at the column offset specified on construction, the gadget occupies [NUM_COLUMNS]
+/// columns of the main trace segment laid out as follows:
+/// - the SUB and the MUL selector columns; the operation is ADD if both selectors are zero. The
+///   selectors remain constant throughout a cycle;
+/// - accumulators for the operands `a` and `b`, and for the high and the low halves of the
+///   result. On each row of a cycle, the next limb of each value (starting from the most
+///   significant one) is appended to its accumulator; thus, on the last row of the cycle, the
+///   accumulators hold the operands and the result;
+/// - a column which holds the inverse of `hi - (2^32 - 1)` on the last row of a cycle;
+/// - [LIMB_BITS] columns with bits of the current limb of each of `a`, `b`, `hi`, and `lo`, in
+///   this order (least significant bit first).
+///
+/// On the last row of a cycle, the gadget requires:
+/// - `a + b = hi * 2^32 + lo` for ADD;
+/// - `a + hi * 2^32 = b + lo` for SUB;
+/// - `a * b = hi * 2^32 + lo` for MUL.
+///
+/// Since all values are decomposed into 32 bits, the only way for these equations to hold in the
+/// field but not over integers is for `hi * 2^32 + lo` to exceed the modulus of the field, which
+/// requires `hi` to be `2^32 - 1`; the gadget rules this out by requiring `hi - (2^32 - 1)` to
+/// have an inverse. No valid result has such `hi`, and the equations above in turn imply that
+/// `hi` is binary for ADD and SUB.
+///
+/// Operation `i` occupies rows `[i * CYCLE_LENGTH, (i + 1) * CYCLE_LENGTH)`; other constraints of
+/// the AIR can refer to its operands and result at row [result_step(i)](Self::result_step).
+/// Cycles after the last operation are filled with ADD operations over zeros; since the last row
+/// of the trace is not constrained, the trace must contain at least one such cycle.
+///
+/// To use the gadget, an AIR needs to:
+/// - include [constraint_degrees()](Self::constraint_degrees) into its transition constraint
+///   degrees and call [evaluate_transition()](Self::evaluate_transition) from
+///   [Air::evaluate_transition()](crate::Air::evaluate_transition);
+/// - include [get_periodic_column_values()](Self::get_periodic_column_values) into its periodic
+///   columns;
+/// - either bind the accumulators at the result steps via its own constraints, or include
+///   [get_assertions()](Self::get_assertions) into its assertions when the operations are a
+///   part of the public inputs.
+///
+/// The prover fills the gadget's columns with [build_trace_columns()](Self::build_trace_columns).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct U32Columns {
+    offset: usize,
+}
+
+impl U32Columns {
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a gadget occupying [NUM_COLUMNS] columns of the main trace segment starting at
+    /// the specified column.
+    pub fn new(offset: usize) -> Self {
+        Self { offset }
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the range of all trace columns occupied by this gadget.
+    pub fn columns(&self) -> Range<usize> {
+        self.offset..self.offset + NUM_COLUMNS
+    }
+
+    /// Returns the index of the SUB selector column.
+    pub fn sub_column(&self) -> usize {
+        self.offset
+    }
+
+    /// Returns the index of the MUL selector column.
+    pub fn mul_column(&self) -> usize {
+        self.offset + 1
+    }
+
+    /// Returns the index of the column accumulating the first operand.
+    pub fn a_column(&self) -> usize {
+        self.offset + 2
+    }
+
+    /// Returns the index of the column accumulating the second operand.
+    pub fn b_column(&self) -> usize {
+        self.offset + 3
+    }
+
+    /// Returns the index of the column accumulating the high half of the result.
+    pub fn hi_column(&self) -> usize {
+        self.offset + 4
+    }
+
+    /// Returns the index of the column accumulating the low half of the result.
+    pub fn lo_column(&self) -> usize {
+        self.offset + 5
+    }
+
+    /// Returns the index of the column holding the inverse of `hi - (2^32 - 1)`.
+    pub fn inverse_column(&self) -> usize {
+        self.offset + 6
+    }
+
+    /// Returns the range of columns holding bits of the current limb of the value accumulated in
+    /// the specified column.
+    ///
+    /// # Panics
+    /// Panics if `column` is not one of the accumulator columns.
+    pub fn bit_columns(&self, column: usize) -> Range<usize> {
+        let index = self.accumulator_columns().iter().position(|&c| c == column);
+        let index = index.expect("column is not an accumulator column");
+        let start = self.offset + 7 + index * LIMB_BITS;
+        start..start + LIMB_BITS
+    }
+
+    /// Returns the step at which the accumulators hold the operands and the result of the
+    /// operation with the specified index.
+    pub fn result_step(index: usize) -> usize {
+        (index + 1) * CYCLE_LENGTH - 1
+    }
+
+    /// Returns the minimum length of a trace which can perform the specified number of
+    /// operations.
+    pub fn min_trace_length(num_operations: usize) -> usize {
+        ((num_operations + 1) * CYCLE_LENGTH).next_power_of_two()
+    }
+
+    // PROVER
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns [NUM_COLUMNS] trace columns of the specified length which perform the specified
+    /// operations; the columns are ordered as described in [U32Columns].
+    ///
+    /// # Panics
+    /// Panics if `trace_length` is not a multiple of [CYCLE_LENGTH] or is smaller than
+    /// [min_trace_length()](Self::min_trace_length) for the operations.
+    pub fn build_trace_columns(
+        operations: &[U32Operation],
+        trace_length: usize,
+    ) -> Vec<Vec<BaseElement>> {
+        validate_trace_length(operations.len(), trace_length);
+
+        let num_operations = trace_length / CYCLE_LENGTH;
+        let padding = U32Operation::new(U32Op::Add, 0, 0);
+        let gadget = Self::new(0);
+
+        let mut columns = vec![vec![BaseElement::ZERO; trace_length]; NUM_COLUMNS];
+        for i in 0..num_operations {
+            let operation = operations.get(i).unwrap_or(&padding);
+            let [sub, mul] = operation.op.selectors();
+            let (hi, lo) = operation.result();
+            let values = [operation.a, operation.b, hi, lo];
+            let mut accumulators = [0u64; NUM_VALUES];
+            for row in 0..CYCLE_LENGTH {
+                let step = i * CYCLE_LENGTH + row;
+                let shift = OPERAND_BITS - LIMB_BITS * (row + 1);
+                columns[gadget.sub_column()][step] = sub;
+                columns[gadget.mul_column()][step] = mul;
+                for ((column, value), accumulator) in
+                    gadget.accumulator_columns().into_iter().zip(values).zip(&mut accumulators)
+                {
+                    let limb = (value >> shift) & (LIMB_SHIFT as u32 - 1);
+                    *accumulator = *accumulator * LIMB_SHIFT + limb as u64;
+                    columns[column][step] = BaseElement::new(*accumulator);
+                    for (bit, bit_column) in gadget.bit_columns(column).enumerate() {
+                        columns[bit_column][step] = BaseElement::from((limb >> bit) & 1);
+                    }
+                }
+            }
+
+            let max_hi = BaseElement::from(u32::MAX);
+            columns[gadget.inverse_column()][Self::result_step(i)] =
+                (BaseElement::from(hi) - max_hi).inv();
+        }
+
+        columns
+    }
+
+    // CONSTRAINTS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns degrees of the constraints enforced by
+    /// [evaluate_transition()](Self::evaluate_transition).
+    pub fn constraint_degrees() -> Vec<TransitionConstraintDegree> {
+        let cycle = |degree| TransitionConstraintDegree::with_cycles(degree, vec![CYCLE_LENGTH]);
+        let mut result = vec![TransitionConstraintDegree::new(2); 3];
+        result.extend([cycle(1), cycle(1)]);
+        result.resize(5 + NUM_VALUES * LIMB_BITS, TransitionConstraintDegree::new(2));
+        result.resize(5 + NUM_VALUES * (LIMB_BITS + 2), cycle(1));
+        result.extend([cycle(3), cycle(2)]);
+        result
+    }
+
+    /// Returns values of the periodic columns expected by
+    /// [evaluate_transition()](Self::evaluate_transition).
+    ///
+    /// The first column is a flag which is set to one on the first row of each cycle; the second
+    /// column is a flag which is set to one on all rows of a cycle except for the last one.
+    pub fn get_periodic_column_values() -> Vec<Vec<BaseElement>> {
+        let mut first_row = vec![BaseElement::ZERO; CYCLE_LENGTH];
+        first_row[0] = BaseElement::ONE;
+        let mut not_last_row = vec![BaseElement::ONE; CYCLE_LENGTH];
+        not_last_row[CYCLE_LENGTH - 1] = BaseElement::ZERO;
+        vec![first_row, not_last_row]
+    }
+
+    /// Evaluates [NUM_CONSTRAINTS] transition constraints of this gadget over the specified
+    /// frame and writes the results into `result`.
+    ///
+    /// `periodic_values` must contain values of the columns returned by
+    /// [get_periodic_column_values()](Self::get_periodic_column_values) in the same order. The
+    /// constraints require:
+    /// - the selectors to be binary, mutually exclusive, and constant throughout a cycle;
+    /// - all limb bits to be binary;
+    /// - on the first row of a cycle, the accumulators to be equal to the current limbs;
+    /// - on other rows, the accumulators to be equal to the accumulators of the previous row
+    ///   shifted by [LIMB_BITS] bits, plus the current limbs;
+    /// - on the last row of a cycle, the result to be the result of the selected operation over
+    ///   the operands, and `hi - (2^32 - 1)` to be the inverse of the value in the inverse column.
+    pub fn evaluate_transition<E>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        periodic_values: &[E],
+        result: &mut [E],
+    ) where
+        E: FieldElement + From<BaseElement>,
+    {
+        let current = frame.current();
+        let next = frame.next();
+        let first_row = periodic_values[0];
+        let not_last_row = periodic_values[1];
+        let last_row = E::ONE - not_last_row;
+        let sub = current[self.sub_column()];
+        let mul = current[self.mul_column()];
+
+        result[0] = sub * (sub - E::ONE);
+        result[1] = mul * (mul - E::ONE);
+        result[2] = sub * mul;
+        result[3] = not_last_row * (next[self.sub_column()] - sub);
+        result[4] = not_last_row * (next[self.mul_column()] - mul);
+
+        let columns = self.accumulator_columns();
+        let bits = columns.iter().flat_map(|&column| &current[self.bit_columns(column)]);
+        for (i, &bit) in bits.enumerate() {
+            result[5 + i] = bit * (bit - E::ONE);
+        }
+
+        let offset = 5 + NUM_VALUES * LIMB_BITS;
+        let shift = E::from(BaseElement::new(LIMB_SHIFT));
+        for (i, &column) in columns.iter().enumerate() {
+            let limb = self.evaluate_limb(current, column);
+            result[offset + i] = first_row * (current[column] - limb);
+
+            let limb = self.evaluate_limb(next, column);
+            result[offset + NUM_VALUES + i] =
+                not_last_row * (next[column] - current[column] * shift - limb);
+        }
+
+        let offset = offset + 2 * NUM_VALUES;
+        let a = current[self.a_column()];
+        let b = current[self.b_column()];
+        let hi = current[self.hi_column()];
+        let lo = current[self.lo_column()];
+        let high = hi * E::from(BaseElement::new(HIGH_SHIFT));
+        let add = E::ONE - sub - mul;
+        let relation =
+            add * (a + b - high - lo) + sub * (a + high - b - lo) + mul * (a * b - high - lo);
+        result[offset] = last_row * relation;
+
+        let max_hi = E::from(BaseElement::from(u32::MAX));
+        result[offset + 1] = last_row * ((hi - max_hi) * current[self.inverse_column()] - E::ONE);
+    }
+
+    // ASSERTIONS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns assertions which bind this gadget to the specified operations.
+    ///
+    /// For each operation, the assertions fix the selectors on the first row of its cycle, and
+    /// the operands and both halves of the result on the last row of its cycle.
+    ///
+    /// # Panics
+    /// Panics if `trace_length` is not a multiple of [CYCLE_LENGTH] or is smaller than
+    /// [min_trace_length()](Self::min_trace_length) for the operations.
+    pub fn get_assertions(
+        &self,
+        operations: &[U32Operation],
+        trace_length: usize,
+    ) -> Vec<Assertion<BaseElement>> {
+        validate_trace_length(operations.len(), trace_length);
+
+        let mut result = Vec::with_capacity((2 + NUM_VALUES) * operations.len());
+        for (i, operation) in operations.iter().enumerate() {
+            let [sub, mul] = operation.op.selectors();
+            let first_step = i * CYCLE_LENGTH;
+            result.push(Assertion::single(self.sub_column(), first_step, sub));
+            result.push(Assertion::single(self.mul_column(), first_step, mul));
+
+            let last_step = Self::result_step(i);
+            let (hi, lo) = operation.result();
+            let values = [operation.a, operation.b, hi, lo];
+            for (column, value) in self.accumulator_columns().into_iter().zip(values) {
+                result.push(Assertion::single(column, last_step, BaseElement::from(value)));
+            }
+        }
+
+        result
+    }
+
+    // HELPER METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns indexes of the accumulator columns for `a`, `b`, `hi`, and `lo` (in this order).
+    fn accumulator_columns(&self) -> [usize; NUM_VALUES] {
+        [self.a_column(), self.b_column(), self.hi_column(), self.lo_column()]
+    }
+
+    /// Returns the limb of the value accumulated in the specified column, computed from limb bits
+    /// in the specified row.
+    fn evaluate_limb<E>(&self, row: &[E], column: usize) -> E
+    where
+        E: FieldElement + From<BaseElement>,
+    {
+        let mut limb = E::ZERO;
+        let mut power = E::ONE;
+        for &bit in row[self.bit_columns(column)].iter() {
+            limb += bit * power;
+            power = power.double();
+        }
+        limb
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+fn validate_trace_length(num_operations: usize, trace_length: usize) {
+    assert_eq!(
+        trace_length % CYCLE_LENGTH,
+        0,
+        "trace length must be a multiple of {CYCLE_LENGTH}, but was {trace_length}"
+    );
+    let min_trace_length = (num_operations + 1) * CYCLE_LENGTH;
+    assert!(
+        trace_length >= min_trace_length,
+        "trace length must be at least {min_trace_length} to perform {num_operations} operations, but was {trace_length}"
+    );
+}
@@ -0,0 +1,742 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+//! Canonical JSON encoding of STARK proofs and proof options.
+//!
+//! The JSON encoding mirrors the binary serialization format of [StarkProof] (format version 5),
+//! but represents every value in a form which can be consumed without knowledge of the binary
+//! layout:
+//!
+//! * Digests (commitments) are encoded as lowercase hex strings of their serialized bytes.
+//! * Field elements are encoded as lowercase hex strings of their canonical little-endian
+//!   representation in the base field; elements of extension fields are encoded as sequences of
+//!   their base field coefficients. Sequences of elements are encoded as arrays of such strings.
+//! * Compressed Merkle authentication paths and opaque byte strings (e.g., trace metadata) are
+//!   encoded as lowercase hex strings.
+//! * Nonces are encoded as decimal strings (since they may not be representable exactly by JSON
+//!   numbers in some languages); all other integers are encoded as JSON numbers.
+//!
+//! The encoding is canonical: object keys are sorted lexicographically and no insignificant
+//! whitespace is emitted. Thus, the same proof is always encoded as the same string, and encodings
+//! of proofs can be compared byte by byte.
+//!
+//! A proof is encoded as an object with the following keys:
+//!
+//! * `format_version` - the binary format version mirrored by the encoding (currently 5).
+//! * `context` - an object with keys `trace_layout` (an object with keys `main_segment_width`,
+//!   `aux_segment_widths`, and `aux_segment_rands`), `trace_length`, `trace_meta`,
+//!   `field_modulus` (little-endian bytes of the modulus), `options` (see below), and
+//!   `hash_function` (the name of the hash function, or `null` if it is not recorded).
+//! * `num_unique_queries` - the number of unique query positions.
+//! * `commitments` - an array of digests.
+//! * `trace_queries` - an array of queries for each trace segment; queries are encoded as an
+//!   object with keys `values` (an array of element arrays, one for each queried position) and
+//!   `paths`.
+//! * `preprocessed_queries` - queries of preprocessed columns, or `null` if the proof does not
+//!   contain such queries.
+//! * `constraint_queries` - queries of the constraint composition polynomial.
+//! * `ood_frame` - an object with keys `frame_size` (the number of trace rows in the frame, or
+//!   `null` if the frame is empty), `trace_states` (trace values with rows interleaved for each
+//!   column), and `constraint_evaluations`.
+//! * `fri_proof` - an object with keys `layers` (an array of objects with keys `num_queries`,
+//!   which is `null` unless the layer is deduplicated, `values`, and `paths`), `remainder`,
+//!   `ood_evaluations`, `layer_nonces`, and `num_partitions`.
+//! * `pow_nonce` - the proof-of-work nonce.
+//!
+//! Proof options are encoded as an object with keys `num_queries`, `blowup_factor`,
+//! `grinding_factor`, `field_extension` (`"none"`, `"quadratic"`, or `"cubic"`),
+//! `fri_folding_factor`, `fri_remainder_max_degree`, `normalized_queries`,
+//! `fri_folding_schedule`, `fri_layer_grinding_factor`, `fri_query_deduplication`,
+//! `low_degree_test` (`"fri"` or `"stir"`), and `leaf_encoding` (`"packed"`, `"padded_le"`, or
+//! `"padded_be"`), and `query_sampling` (`"uniform"`, `"stratified"`, or `"custom:<id>"` where
+//! `<id>` is the decimal identifier of a custom strategy); `leaf_encoding` and `query_sampling`
+//! may be omitted, in which case the packed encoding and uniform sampling are assumed.
+
+use crate::{
+    proof::{
+        Commitments, Context, OodFrame, Queries, StarkProof, CONTEXT_BINDING_VERSION,
+        FORMAT_VERSION_MARKER,
+    },
+    FieldExtension, LeafEncoding, LowDegreeTest, ProofOptions, QuerySampling, TraceInfo,
+    TraceLayout,
+};
+use core::str::FromStr;
+use crypto::{HashFunction, Hasher};
+use fri::FriProof;
+use math::StarkField;
+use serde_json::{Map, Value};
+use utils::{
+    collections::Vec,
+    string::{String, ToString},
+    ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable, SliceReader,
+};
+
+// PROOF OPTIONS
+// ================================================================================================
+
+impl ProofOptions {
+    /// Returns the canonical JSON encoding of these proof options.
+    pub fn to_json(&self) -> String {
+        to_canonical_string(&options_to_value(self))
+    }
+
+    /// Returns proof options decoded from the specified JSON encoding.
+    ///
+    /// # Errors
+    /// Returns an error if `json` is not a valid JSON encoding of proof options.
+    pub fn from_json(json: &str) -> Result<Self, DeserializationError> {
+        options_from_value(&parse_json(json)?)
+    }
+}
+
+fn options_to_value(options: &ProofOptions) -> Value {
+    let fri_options = options.to_fri_options();
+    let field_extension = match options.field_extension() {
+        FieldExtension::None => "none",
+        FieldExtension::Quadratic => "quadratic",
+        FieldExtension::Cubic => "cubic",
+    };
+    let low_degree_test = match options.low_degree_test() {
+        LowDegreeTest::Fri => "fri",
+        LowDegreeTest::Stir => "stir",
+    };
+    let leaf_encoding = match options.leaf_encoding() {
+        LeafEncoding::Packed => "packed",
+        LeafEncoding::PaddedLe => "padded_le",
+        LeafEncoding::PaddedBe => "padded_be",
+    };
+    let query_sampling = match options.query_sampling() {
+        QuerySampling::Uniform => "uniform".to_string(),
+        QuerySampling::Stratified => "stratified".to_string(),
+        QuerySampling::Custom(id) => format!("custom:{id}"),
+    };
+
+    let mut result = Map::new();
+    result.insert("num_queries".into(), options.num_queries().into());
+    result.insert("blowup_factor".into(), options.blowup_factor().into());
+    result.insert("grinding_factor".into(), options.grinding_factor().into());
+    result.insert("field_extension".into(), field_extension.into());
+    result.insert("fri_folding_factor".into(), fri_options.folding_factor().into());
+    result.insert("fri_remainder_max_degree".into(), fri_options.remainder_max_degree().into());
+    result.insert("normalized_queries".into(), options.normalized_queries().into());
+    result.insert("fri_folding_schedule".into(), options.fri_folding_schedule().into());
+    result.insert("fri_layer_grinding_factor".into(), options.fri_layer_grinding_factor().into());
+    result.insert("fri_query_deduplication".into(), options.fri_query_deduplication().into());
+    result.insert("low_degree_test".into(), low_degree_test.into());
+    result.insert("leaf_encoding".into(), leaf_encoding.into());
+    result.insert("query_sampling".into(), query_sampling.into());
+    Value::Object(result)
+}
+
+/// Decodes proof options by converting them into their binary form, so that they are validated
+/// in the same way as deserialized options.
+fn options_from_value(value: &Value) -> Result<ProofOptions, DeserializationError> {
+    let field_extension = match get_str(value, "field_extension")? {
+        "none" => FieldExtension::None,
+        "quadratic" => FieldExtension::Quadratic,
+        "cubic" => FieldExtension::Cubic,
+        other => return Err(invalid(format!("unknown field extension '{other}'"))),
+    };
+    let low_degree_test = match get_str(value, "low_degree_test")? {
+        "fri" => LowDegreeTest::Fri,
+        #[cfg(feature = "experimental-stir")]
+        "stir" => LowDegreeTest::Stir,
+        other => return Err(invalid(format!("unknown low-degree test '{other}'"))),
+    };
+    let leaf_encoding = match value.get("leaf_encoding") {
+        None => LeafEncoding::Packed,
+        Some(leaf_encoding) => match as_str(leaf_encoding, "leaf_encoding")? {
+            "packed" => LeafEncoding::Packed,
+            "padded_le" => LeafEncoding::PaddedLe,
+            "padded_be" => LeafEncoding::PaddedBe,
+            other => return Err(invalid(format!("unknown leaf encoding '{other}'"))),
+        },
+    };
+    let query_sampling = match value.get("query_sampling") {
+        None => QuerySampling::Uniform,
+        Some(query_sampling) => match as_str(query_sampling, "query_sampling")? {
+            "uniform" => QuerySampling::Uniform,
+            "stratified" => QuerySampling::Stratified,
+            other => match other.strip_prefix("custom:").map(u8::from_str) {
+                Some(Ok(id)) => QuerySampling::Custom(id),
+                _ => return Err(invalid(format!("unknown query sampling strategy '{other}'"))),
+            },
+        },
+    };
+    let schedule = get_array(value, "fri_folding_schedule")?
+        .iter()
+        .map(|factor| as_u8(factor, "fri_folding_schedule"))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut bytes = Vec::new();
+    bytes.write_u8(get_u8(value, "num_queries")?);
+    bytes.write_u8(get_u8(value, "blowup_factor")?);
+    bytes.write_u8(get_u8(value, "grinding_factor")?);
+    field_extension.write_into(&mut bytes);
+    bytes.write_u8(get_u8(value, "fri_folding_factor")?);
+    bytes.write_u8(get_u8(value, "fri_remainder_max_degree")?);
+    bytes.write_bool(get_bool(value, "normalized_queries")?);
+    bytes.write_u8(to_u8(schedule.len(), "fri_folding_schedule")?);
+    bytes.write_bytes(&schedule);
+    bytes.write_u8(get_u8(value, "fri_layer_grinding_factor")?);
+    bytes.write_bool(get_bool(value, "fri_query_deduplication")?);
+    bytes.write_u8(low_degree_test as u8);
+    leaf_encoding.write_into(&mut bytes);
+    query_sampling.write_into(&mut bytes);
+
+    ProofOptions::read_from_bytes(&bytes)
+}
+
+// STARK PROOF
+// ================================================================================================
+
+impl StarkProof {
+    /// Returns the canonical JSON encoding of this proof.
+    ///
+    /// `B` must be the base field of the proof, and `H` must be the hash function used to
+    /// generate the proof; these are needed to split the proof into field elements and digests.
+    ///
+    /// # Errors
+    /// Returns an error if the base field of the proof is not `B`, if the proof could not be
+    /// split into field elements of `B` and digests of `H`, or if the proof has a format version
+    /// earlier than 4 (the transcripts of such proofs depend on their format version, and thus,
+    /// they cannot be encoded in the current format).
+    pub fn to_json<B: StarkField, H: Hasher>(&self) -> Result<String, DeserializationError> {
+        if self.context.field_modulus_bytes() != B::get_modulus_le_bytes() {
+            return Err(invalid("base field of the proof does not match the specified field"));
+        }
+        let version = self.context.format_version();
+        if version < CONTEXT_BINDING_VERSION {
+            return Err(invalid(format!("proofs with format version {version} cannot be encoded")));
+        }
+        let num_queries = self.num_unique_queries as usize;
+
+        let mut result = Map::new();
+        result.insert("format_version".into(), StarkProof::FORMAT_VERSION.into());
+        result.insert("context".into(), context_to_value(&self.context));
+        result.insert("num_unique_queries".into(), num_queries.into());
+        result.insert("commitments".into(), commitments_to_value::<H>(&self.commitments)?);
+        let trace_queries = self
+            .trace_queries
+            .iter()
+            .map(|queries| queries_to_value::<B>(queries, num_queries))
+            .collect::<Result<Vec<_>, _>>()?;
+        result.insert("trace_queries".into(), Value::Array(trace_queries));
+        let preprocessed_queries = match &self.preprocessed_queries {
+            Some(queries) => queries_to_value::<B>(queries, num_queries)?,
+            None => Value::Null,
+        };
+        result.insert("preprocessed_queries".into(), preprocessed_queries);
+        result.insert(
+            "constraint_queries".into(),
+            queries_to_value::<B>(&self.constraint_queries, num_queries)?,
+        );
+        result.insert("ood_frame".into(), ood_frame_to_value::<B>(&self.ood_frame)?);
+        result.insert("fri_proof".into(), fri_proof_to_value::<B>(&self.fri_proof)?);
+        result.insert("pow_nonce".into(), self.pow_nonce.to_string().into());
+
+        Ok(to_canonical_string(&Value::Object(result)))
+    }
+
+    /// Returns a proof decoded from the specified JSON encoding.
+    ///
+    /// `B` must be the base field of the proof, and `H` must be the hash function used to
+    /// generate the proof. The decoded proof is converted into the binary format and deserialized,
+    /// and thus, is validated in the same way as a deserialized proof.
+    ///
+    /// # Errors
+    /// Returns an error if `json` is not a valid JSON encoding of a proof generated in the base
+    /// field `B` with hash function `H`.
+    pub fn from_json<B: StarkField, H: Hasher>(json: &str) -> Result<Self, DeserializationError> {
+        let value = parse_json(json)?;
+        let version = get_u64(&value, "format_version")?;
+        if version != StarkProof::FORMAT_VERSION as u64 {
+            return Err(invalid(format!("proof format version {version} is not supported")));
+        }
+        let num_queries = get_u8(&value, "num_unique_queries")?;
+
+        let mut bytes = Vec::new();
+        bytes.write_u8(FORMAT_VERSION_MARKER);
+        bytes.write_u8(StarkProof::FORMAT_VERSION);
+        let context = context_from_value::<B, H>(get(&value, "context")?)?;
+        context.write_into(&mut bytes);
+        bytes.write_u8(num_queries);
+        commitments_from_value::<H>(get(&value, "commitments")?)?.write_into(&mut bytes);
+        for queries in get_array(&value, "trace_queries")? {
+            queries_from_value::<B>(queries)?.write_into(&mut bytes);
+        }
+        match get(&value, "preprocessed_queries")? {
+            Value::Null => bytes.write_u8(0),
+            queries => {
+                bytes.write_u8(1);
+                queries_from_value::<B>(queries)?.write_into(&mut bytes);
+            }
+        }
+        queries_from_value::<B>(get(&value, "constraint_queries")?)?.write_into(&mut bytes);
+        ood_frame_from_value::<B>(get(&value, "ood_frame")?)?.write_into(&mut bytes);
+        write_fri_proof::<B>(get(&value, "fri_proof")?, &mut bytes)?;
+        bytes.write_u64(get_decimal(&value, "pow_nonce")?);
+
+        StarkProof::from_bytes(&bytes)
+    }
+}
+
+// PROOF SECTIONS
+// ================================================================================================
+
+fn context_to_value(context: &Context) -> Value {
+    // the trace layout is serialized as the main segment width followed by the widths and the
+    // numbers of random elements of all auxiliary segments
+    let layout = context.trace_layout().to_bytes();
+    let (aux_widths, aux_rands) = layout[1..].split_at((layout.len() - 1) / 2);
+    let mut trace_layout = Map::new();
+    trace_layout.insert("main_segment_width".into(), layout[0].into());
+    trace_layout.insert("aux_segment_widths".into(), aux_widths.into());
+    trace_layout.insert("aux_segment_rands".into(), aux_rands.into());
+
+    let hash_function = match context.hash_function() {
+        Some(hash_fn) => Value::String(hash_fn.to_string()),
+        None => Value::Null,
+    };
+
+    let mut result = Map::new();
+    result.insert("trace_layout".into(), Value::Object(trace_layout));
+    result.insert("trace_length".into(), context.trace_length().into());
+    result.insert("trace_meta".into(), to_hex(context.get_trace_info().meta()).into());
+    result.insert("field_modulus".into(), to_hex(context.field_modulus_bytes()).into());
+    result.insert("options".into(), options_to_value(context.options()));
+    result.insert("hash_function".into(), hash_function);
+    Value::Object(result)
+}
+
+fn context_from_value<B: StarkField, H: Hasher>(
+    value: &Value,
+) -> Result<Context, DeserializationError> {
+    if from_hex(get_str(value, "field_modulus")?)? != B::get_modulus_le_bytes() {
+        return Err(invalid("field modulus of the proof does not match the specified field"));
+    }
+
+    let layout = get(value, "trace_layout")?;
+    let mut layout_bytes = vec![get_u8(layout, "main_segment_width")?];
+    for key in ["aux_segment_widths", "aux_segment_rands"] {
+        for item in get_array(layout, key)? {
+            layout_bytes.push(as_u8(item, key)?);
+        }
+    }
+    let layout = TraceLayout::read_from_bytes(&layout_bytes)?;
+
+    let trace_length = get_u64(value, "trace_length")?;
+    if trace_length < TraceInfo::MIN_TRACE_LENGTH as u64
+        || !trace_length.is_power_of_two()
+        || trace_length > usize::MAX as u64
+    {
+        return Err(invalid(format!("trace length {trace_length} is not valid")));
+    }
+    let meta = from_hex(get_str(value, "trace_meta")?)?;
+    if meta.len() > TraceInfo::MAX_META_LENGTH {
+        return Err(invalid("trace metadata is too long"));
+    }
+    let trace_info = TraceInfo::new_multi_segment(layout, trace_length as usize, meta);
+
+    let options = options_from_value(get(value, "options")?)?;
+    let context = Context::new::<B>(&trace_info, options);
+    match get(value, "hash_function")? {
+        Value::Null => Ok(context),
+        Value::String(name) => {
+            let hash_fn = HashFunction::from_str(name).map_err(invalid)?;
+            if H::HASH_FUNCTION != Some(hash_fn) {
+                return Err(invalid(format!(
+                    "hash function {hash_fn} does not match the specified hash function"
+                )));
+            }
+            Ok(context.with_hash_function::<H>())
+        }
+        _ => Err(invalid("'hash_function' must be a string or null")),
+    }
+}
+
+fn commitments_to_value<H: Hasher>(
+    commitments: &Commitments,
+) -> Result<Value, DeserializationError> {
+    let bytes = commitments.to_bytes();
+    let mut reader = SliceReader::new(&bytes);
+    let num_bytes = reader.read_u16()? as usize;
+    let digests = reader.read_vec(num_bytes)?;
+
+    let digest_size = H::Digest::default().to_bytes().len();
+    if digests.len() % digest_size != 0 {
+        return Err(invalid("commitments cannot be split into digests"));
+    }
+    Ok(digests
+        .chunks(digest_size)
+        .map(|digest| Value::String(to_hex(digest)))
+        .collect())
+}
+
+fn commitments_from_value<H: Hasher>(value: &Value) -> Result<Commitments, DeserializationError> {
+    let mut digests = Vec::new();
+    for digest in as_array(value, "commitments")? {
+        let digest = from_hex(as_str(digest, "commitments")?)?;
+        H::Digest::read_from_bytes(&digest)?;
+        digests.extend_from_slice(&digest);
+    }
+
+    let mut bytes = Vec::new();
+    bytes.write_u16(to_u16(digests.len(), "commitments")?);
+    bytes.write_bytes(&digests);
+    Commitments::read_from_bytes(&bytes)
+}
+
+fn queries_to_value<B: StarkField>(
+    queries: &Queries,
+    num_queries: usize,
+) -> Result<Value, DeserializationError> {
+    let bytes = queries.to_bytes();
+    let mut reader = SliceReader::new(&bytes);
+    let num_value_bytes = reader.read_u32()? as usize;
+    let values = reader.read_vec(num_value_bytes)?;
+    let num_path_bytes = reader.read_u32()? as usize;
+    let paths = reader.read_vec(num_path_bytes)?;
+
+    if num_queries == 0 || values.len() % num_queries != 0 {
+        return Err(invalid("query values cannot be split into queries"));
+    }
+    let values = values
+        .chunks(values.len() / num_queries)
+        .map(elements_to_value::<B>)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut result = Map::new();
+    result.insert("values".into(), Value::Array(values));
+    result.insert("paths".into(), to_hex(&paths).into());
+    Ok(Value::Object(result))
+}
+
+fn queries_from_value<B: StarkField>(value: &Value) -> Result<Queries, DeserializationError> {
+    let mut values = Vec::new();
+    let mut query_size = None;
+    for query in get_array(value, "values")? {
+        let query = elements_from_value::<B>(query, "values")?;
+        if *query_size.get_or_insert(query.len()) != query.len() {
+            return Err(invalid("all queries must contain the same number of values"));
+        }
+        values.extend_from_slice(&query);
+    }
+    let paths = from_hex(get_str(value, "paths")?)?;
+
+    let mut bytes = Vec::new();
+    bytes.write_u32(to_u32(values.len(), "values")?);
+    bytes.write_bytes(&values);
+    bytes.write_u32(to_u32(paths.len(), "paths")?);
+    bytes.write_bytes(&paths);
+    Queries::read_from_bytes(&bytes)
+}
+
+fn ood_frame_to_value<B: StarkField>(frame: &OodFrame) -> Result<Value, DeserializationError> {
+    let bytes = frame.to_bytes();
+    let mut reader = SliceReader::new(&bytes);
+    let num_trace_state_bytes = reader.read_u16()? as usize;
+    let trace_states = reader.read_vec(num_trace_state_bytes)?;
+    let num_evaluation_bytes = reader.read_u16()? as usize;
+    let evaluations = reader.read_vec(num_evaluation_bytes)?;
+
+    // trace states are prefixed with the number of rows in the frame unless they are empty
+    let (frame_size, trace_states) = match trace_states.split_first() {
+        Some((&frame_size, trace_states)) => (Value::from(frame_size), trace_states),
+        None => (Value::Null, &trace_states[..]),
+    };
+
+    let mut result = Map::new();
+    result.insert("frame_size".into(), frame_size);
+    result.insert("trace_states".into(), elements_to_value::<B>(trace_states)?);
+    result.insert("constraint_evaluations".into(), elements_to_value::<B>(&evaluations)?);
+    Ok(Value::Object(result))
+}
+
+fn ood_frame_from_value<B: StarkField>(value: &Value) -> Result<OodFrame, DeserializationError> {
+    let mut trace_states = match get(value, "frame_size")? {
+        Value::Null => Vec::new(),
+        frame_size => vec![as_u8(frame_size, "frame_size")?],
+    };
+    trace_states
+        .extend_from_slice(&elements_from_value::<B>(get(value, "trace_states")?, "trace_states")?);
+    let evaluations =
+        elements_from_value::<B>(get(value, "constraint_evaluations")?, "constraint_evaluations")?;
+
+    let mut bytes = Vec::new();
+    bytes.write_u16(to_u16(trace_states.len(), "trace_states")?);
+    bytes.write_bytes(&trace_states);
+    bytes.write_u16(to_u16(evaluations.len(), "constraint_evaluations")?);
+    bytes.write_bytes(&evaluations);
+    OodFrame::read_from_bytes(&bytes)
+}
+
+fn fri_proof_to_value<B: StarkField>(proof: &FriProof) -> Result<Value, DeserializationError> {
+    let bytes = proof.to_bytes();
+    let mut reader = SliceReader::new(&bytes);
+
+    let num_layers = reader.read_u8()? as usize;
+    let is_deduplicated = reader.read_bool()?;
+    let mut layers = Vec::with_capacity(num_layers);
+    for _ in 0..num_layers {
+        let num_queries = if is_deduplicated {
+            Value::from(reader.read_u8()?)
+        } else {
+            Value::Null
+        };
+        let num_value_bytes = reader.read_u32()? as usize;
+        let values = reader.read_vec(num_value_bytes)?;
+        let num_path_bytes = reader.read_u32()? as usize;
+        let paths = reader.read_vec(num_path_bytes)?;
+
+        let mut layer = Map::new();
+        layer.insert("num_queries".into(), num_queries);
+        layer.insert("values".into(), elements_to_value::<B>(&values)?);
+        layer.insert("paths".into(), to_hex(&paths).into());
+        layers.push(Value::Object(layer));
+    }
+
+    let num_remainder_bytes = reader.read_u16()? as usize;
+    let remainder = reader.read_vec(num_remainder_bytes)?;
+    let num_ood_bytes = reader.read_u16()? as usize;
+    let ood_evaluations = reader.read_vec(num_ood_bytes)?;
+    let num_nonces = reader.read_u8()? as usize;
+    let mut layer_nonces = Vec::with_capacity(num_nonces);
+    for _ in 0..num_nonces {
+        layer_nonces.push(Value::String(reader.read_u64()?.to_string()));
+    }
+
+    let mut result = Map::new();
+    result.insert("layers".into(), Value::Array(layers));
+    result.insert("remainder".into(), elements_to_value::<B>(&remainder)?);
+    result.insert("ood_evaluations".into(), elements_to_value::<B>(&ood_evaluations)?);
+    result.insert("layer_nonces".into(), Value::Array(layer_nonces));
+    result.insert("num_partitions".into(), proof.num_partitions().into());
+    Ok(Value::Object(result))
+}
+
+/// Writes the binary form of the FRI proof encoded by `value` into `target`.
+fn write_fri_proof<B: StarkField>(
+    value: &Value,
+    target: &mut Vec<u8>,
+) -> Result<(), DeserializationError> {
+    let layers = get_array(value, "layers")?;
+    let is_deduplicated = layers
+        .iter()
+        .any(|layer| !matches!(layer.get("num_queries"), Some(Value::Null)));
+    target.write_u8(to_u8(layers.len(), "layers")?);
+    target.write_bool(is_deduplicated);
+    for layer in layers {
+        match (is_deduplicated, get(layer, "num_queries")?) {
+            (false, _) => (),
+            (true, Value::Null) => {
+                return Err(invalid("either all or none of FRI layers must be deduplicated"))
+            }
+            (true, num_queries) => target.write_u8(as_u8(num_queries, "num_queries")?),
+        }
+        let values = elements_from_value::<B>(get(layer, "values")?, "values")?;
+        target.write_u32(to_u32(values.len(), "values")?);
+        target.write_bytes(&values);
+        let paths = from_hex(get_str(layer, "paths")?)?;
+        target.write_u32(to_u32(paths.len(), "paths")?);
+        target.write_bytes(&paths);
+    }
+
+    for key in ["remainder", "ood_evaluations"] {
+        let elements = elements_from_value::<B>(get(value, key)?, key)?;
+        target.write_u16(to_u16(elements.len(), key)?);
+        target.write_bytes(&elements);
+    }
+
+    let layer_nonces = get_array(value, "layer_nonces")?;
+    target.write_u8(to_u8(layer_nonces.len(), "layer_nonces")?);
+    for nonce in layer_nonces {
+        target.write_u64(parse_decimal(nonce, "layer_nonces")?);
+    }
+
+    let num_partitions = get_u64(value, "num_partitions")?;
+    if !num_partitions.is_power_of_two() {
+        return Err(invalid("number of FRI partitions must be a power of two"));
+    }
+    target.write_u8(num_partitions.ilog2() as u8);
+    Ok(())
+}
+
+// ELEMENT AND BYTE ENCODING
+// ================================================================================================
+
+/// Encodes the specified bytes as an array of base field elements.
+fn elements_to_value<B: StarkField>(bytes: &[u8]) -> Result<Value, DeserializationError> {
+    if bytes.len() % B::ELEMENT_BYTES != 0 {
+        return Err(invalid("values cannot be split into field elements"));
+    }
+    Ok(bytes
+        .chunks(B::ELEMENT_BYTES)
+        .map(|element| Value::String(to_hex(element)))
+        .collect())
+}
+
+/// Decodes an array of base field elements into bytes; each element is checked to be a valid
+/// element of `B`.
+fn elements_from_value<B: StarkField>(
+    value: &Value,
+    key: &str,
+) -> Result<Vec<u8>, DeserializationError> {
+    let mut result = Vec::new();
+    for element in as_array(value, key)? {
+        let bytes = from_hex(as_str(element, key)?)?;
+        B::read_from_bytes(&bytes)?;
+        result.extend_from_slice(&bytes);
+    }
+    Ok(result)
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    const DIGITS: &[u8; 16] = b"0123456789abcdef";
+    let mut result = String::with_capacity(bytes.len() * 2);
+    for &byte in bytes {
+        result.push(DIGITS[(byte >> 4) as usize] as char);
+        result.push(DIGITS[(byte & 0xf) as usize] as char);
+    }
+    result
+}
+
+fn from_hex(value: &str) -> Result<Vec<u8>, DeserializationError> {
+    fn digit(c: u8) -> Result<u8, DeserializationError> {
+        match c {
+            b'0'..=b'9' => Ok(c - b'0'),
+            b'a'..=b'f' => Ok(c - b'a' + 10),
+            _ => Err(invalid(format!("'{}' is not a lowercase hex digit", c as char))),
+        }
+    }
+
+    if value.len() % 2 != 0 {
+        return Err(invalid("hex string must contain an even number of digits"));
+    }
+    value
+        .as_bytes()
+        .chunks(2)
+        .map(|pair| Ok(digit(pair[0])? << 4 | digit(pair[1])?))
+        .collect()
+}
+
+// JSON HELPERS
+// ================================================================================================
+
+fn parse_json(json: &str) -> Result<Value, DeserializationError> {
+    serde_json::from_str(json).map_err(|err| invalid(format!("invalid JSON: {err}")))
+}
+
+/// Writes the specified value as JSON with sorted object keys and without whitespace.
+fn to_canonical_string(value: &Value) -> String {
+    fn write(value: &Value, target: &mut String) {
+        match value {
+            Value::Object(map) => {
+                let mut keys = map.keys().collect::<Vec<_>>();
+                keys.sort();
+                target.push('{');
+                for (i, key) in keys.into_iter().enumerate() {
+                    if i > 0 {
+                        target.push(',');
+                    }
+                    target.push_str(&Value::String(key.clone()).to_string());
+                    target.push(':');
+                    write(&map[key], target);
+                }
+                target.push('}');
+            }
+            Value::Array(items) => {
+                target.push('[');
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        target.push(',');
+                    }
+                    write(item, target);
+                }
+                target.push(']');
+            }
+            _ => target.push_str(&value.to_string()),
+        }
+    }
+
+    let mut result = String::new();
+    write(value, &mut result);
+    result
+}
+
+fn invalid(message: impl ToString) -> DeserializationError {
+    DeserializationError::InvalidValue(message.to_string())
+}
+
+fn get<'a>(value: &'a Value, key: &str) -> Result<&'a Value, DeserializationError> {
+    value.get(key).ok_or_else(|| invalid(format!("missing key '{key}'")))
+}
+
+fn get_array<'a>(value: &'a Value, key: &str) -> Result<&'a Vec<Value>, DeserializationError> {
+    as_array(get(value, key)?, key)
+}
+
+fn get_str<'a>(value: &'a Value, key: &str) -> Result<&'a str, DeserializationError> {
+    as_str(get(value, key)?, key)
+}
+
+fn get_bool(value: &Value, key: &str) -> Result<bool, DeserializationError> {
+    get(value, key)?
+        .as_bool()
+        .ok_or_else(|| invalid(format!("'{key}' must be a boolean")))
+}
+
+fn get_u64(value: &Value, key: &str) -> Result<u64, DeserializationError> {
+    get(value, key)?
+        .as_u64()
+        .ok_or_else(|| invalid(format!("'{key}' must be a non-negative integer")))
+}
+
+fn get_u8(value: &Value, key: &str) -> Result<u8, DeserializationError> {
+    as_u8(get(value, key)?, key)
+}
+
+fn get_decimal(value: &Value, key: &str) -> Result<u64, DeserializationError> {
+    parse_decimal(get(value, key)?, key)
+}
+
+fn as_array<'a>(value: &'a Value, key: &str) -> Result<&'a Vec<Value>, DeserializationError> {
+    value.as_array().ok_or_else(|| invalid(format!("'{key}' must be an array")))
+}
+
+fn as_str<'a>(value: &'a Value, key: &str) -> Result<&'a str, DeserializationError> {
+    value.as_str().ok_or_else(|| invalid(format!("'{key}' must be a string")))
+}
+
+fn as_u8(value: &Value, key: &str) -> Result<u8, DeserializationError> {
+    let value = value
+        .as_u64()
+        .ok_or_else(|| invalid(format!("'{key}' must be a non-negative integer")))?;
+    u8::try_from(value).map_err(|_| invalid(format!("'{key}' is too large")))
+}
+
+fn parse_decimal(value: &Value, key: &str) -> Result<u64, DeserializationError> {
+    let value = as_str(value, key)?;
+    // leading zeros and signs are not allowed so that every value has a single encoding
+    if value.is_empty()
+        || !value.bytes().all(|c| c.is_ascii_digit())
+        || (value.len() > 1 && value.starts_with('0'))
+    {
+        return Err(invalid(format!("'{key}' must be a decimal string")));
+    }
+    value.parse().map_err(|_| invalid(format!("'{key}' does not fit into u64")))
+}
+
+fn to_u8(value: usize, key: &str) -> Result<u8, DeserializationError> {
+    u8::try_from(value).map_err(|_| invalid(format!("'{key}' is too large")))
+}
+
+fn to_u16(value: usize, key: &str) -> Result<u16, DeserializationError> {
+    u16::try_from(value).map_err(|_| invalid(format!("'{key}' is too large")))
+}
+
+fn to_u32(value: usize, key: &str) -> Result<u32, DeserializationError> {
+    u32::try_from(value).map_err(|_| invalid(format!("'{key}' is too large")))
+}
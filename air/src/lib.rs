@@ -25,7 +25,8 @@
 //! details, please refer to the documentation of the [Air] trait itself.
 //!
 //! This crate also contains components describing STARK protocol parameters ([ProofOptions]) and
-//! proof structure ([StarkProof](proof::StarkProof)).
+//! proof structure ([StarkProof](proof::StarkProof)), as well as reusable constraint [gadgets] for
+//! AIRs over the 64-bit field.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
@@ -35,16 +36,32 @@ extern crate alloc;
 
 pub mod proof;
 
+#[cfg(feature = "json")]
+mod json;
+
+#[cfg(feature = "protobuf")]
+pub mod protobuf;
+
 mod errors;
-pub use errors::AssertionError;
+pub use errors::{AssertionError, PublicInputsError};
 
 mod options;
-pub use options::{FieldExtension, ProofOptions};
+pub use fri::LowDegreeTest;
+pub use options::{
+    FieldExtension, LeafEncoding, ProofOptions, QuerySampler, QuerySampling, StratifiedSampler,
+    UniformSampler,
+};
+
+#[cfg(feature = "std")]
+pub mod symbolic;
 
 mod air;
 pub use air::{
-    Air, AirContext, Assertion, AuxTraceRandElements, BoundaryConstraint, BoundaryConstraintGroup,
-    BoundaryConstraints, ConstraintCompositionCoefficients, ConstraintDivisor,
-    DeepCompositionCoefficients, EvaluationFrame, TraceInfo, TraceLayout,
-    TransitionConstraintDegree, TransitionConstraints,
+    AggregateAir, AggregateInputs, Air, AirContext, Assertion, AuxTraceRandElements,
+    BoundaryConstraint, BoundaryConstraintGroup, BoundaryConstraints, Component, ComponentId,
+    ComponentLayout, ConstraintCompositionCoefficients, ConstraintDivisor,
+    DeepCompositionCoefficients, EvaluationFrame, MerkleInputs, TraceAllocator, TraceInfo,
+    TraceLayout, TransitionConstraintDegree, TransitionConstraints,
 };
+
+pub mod gadgets;
@@ -3,10 +3,16 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use fri::FriOptions;
-use math::{StarkField, ToElements};
+use crate::{
+    proof::{powf, Context, StarkProof, CONTEXT_BINDING_VERSION},
+    AirContext, TraceInfo,
+};
+use crypto::{ElementHasher, Hasher, RandomCoin, RandomCoinError};
+use fri::{FriOptions, LowDegreeTest};
+use math::{FieldElement, StarkField, ToElements};
 use utils::{
-    collections::Vec, ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable,
+    collections::Vec, string::ToString, ByteReader, ByteWriter, Deserializable,
+    DeserializationError, Serializable,
 };
 
 // CONSTANTS
@@ -24,6 +30,10 @@ const MAX_GRINDING_FACTOR: u32 = 32;
 const FRI_MIN_FOLDING_FACTOR: usize = 2;
 const FRI_MAX_FOLDING_FACTOR: usize = 16;
 const FRI_MAX_REMAINDER_DEGREE: usize = 255;
+const FRI_MAX_FOLDING_SCHEDULE_LENGTH: usize = 32;
+
+/// Maximum number of times query positions are redrawn when queries are normalized.
+const MAX_QUERY_DRAW_ROUNDS: usize = 1000;
 
 // TYPES AND INTERFACES
 // ================================================================================================
@@ -52,6 +62,108 @@ pub enum FieldExtension {
     Cubic = 3,
 }
 
+/// Defines how rows of an extended execution trace are encoded into Merkle tree leaves.
+///
+/// A commitment to a trace segment is the root of a Merkle tree whose leaves are hashes of the
+/// rows of the extended segment. To recompute a leaf from the row values opened in a proof, an
+/// external verifier must encode the row exactly as the prover did. The encodings below are a
+/// stable contract: the byte layout of an encoding never changes once it is defined, and new
+/// layouts are added as new variants.
+///
+/// In all encodings, a row is treated as a sequence of base field elements (elements of an
+/// extension field are split into their base field coefficients, lowest coefficient first), and
+/// every base field element is written in its canonical (i.e., non-Montgomery) form:
+/// * [Packed](LeafEncoding::Packed) - elements are written in little-endian byte order using
+///   exactly `ELEMENT_BYTES` bytes each, without any padding. The leaf is computed via
+///   [ElementHasher::hash_elements()]; for hash functions which operate on bytes (e.g., BLAKE3,
+///   SHA3, Keccak) this is the same as hashing the encoded row, while algebraic hash functions
+///   (e.g., Rescue Prime) absorb the elements directly. This is the default encoding.
+/// * [PaddedLe](LeafEncoding::PaddedLe) - elements are written in little-endian byte order and
+///   each element is padded with zero bytes to 32 bytes.
+/// * [PaddedBe](LeafEncoding::PaddedBe) - elements are written in big-endian byte order and
+///   each element is prefixed with zero bytes to 32 bytes; this matches the encoding of an array
+///   of 256-bit words in the EVM.
+///
+/// For padded encodings, the leaf is computed by hashing the encoded row via [Hasher::hash()].
+/// The encoding applies to commitments to all trace segments; commitments to the constraint
+/// composition polynomial and FRI layers always use [ElementHasher::hash_elements()].
+#[repr(u8)]
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub enum LeafEncoding {
+    /// Canonical little-endian elements without padding.
+    #[default]
+    Packed = 0,
+    /// Canonical little-endian elements, each padded to 32 bytes.
+    PaddedLe = 1,
+    /// Canonical big-endian elements, each padded to 32 bytes.
+    PaddedBe = 2,
+}
+
+/// Defines how query positions are sampled from the LDE domain.
+///
+/// The strategy is recorded in proof options, and thus, is bound to the proof transcript and can
+/// be restricted by the verifier (e.g., via an acceptance policy). The following strategies are
+/// supported:
+/// * [Uniform](QuerySampling::Uniform) - every position is drawn uniformly at random from the
+///   entire LDE domain (see [UniformSampler]). This is the default strategy.
+/// * [Stratified](QuerySampling::Stratified) - the LDE domain is split into `blowup_factor`
+///   cosets of the trace domain, and the i-th position is drawn uniformly at random from the coset
+///   with index `i mod blowup_factor` (see [StratifiedSampler]). Thus, the positions are spread
+///   evenly across all cosets.
+/// * [Custom](QuerySampling::Custom) - positions are sampled by an application-defined
+///   [QuerySampler] identified by the specified value (e.g., a sampler which derives positions
+///   from an externally audited randomness beacon). Since such samplers are not known to this
+///   crate, AIRs which use them must override
+///   [Air::get_query_positions()](crate::Air::get_query_positions).
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub enum QuerySampling {
+    /// Positions are drawn uniformly at random from the entire LDE domain.
+    #[default]
+    Uniform,
+    /// Positions are drawn uniformly at random from each coset of the trace domain in turn.
+    Stratified,
+    /// Positions are drawn by an application-defined sampler with the specified identifier.
+    Custom(u8),
+}
+
+/// Defines a strategy for drawing query positions from a public coin.
+///
+/// [ProofOptions::draw_query_positions()] uses the sampler defined by the query sampling strategy
+/// of the options; samplers for [custom](QuerySampling::Custom) strategies can be used via
+/// [ProofOptions::draw_query_positions_with()].
+pub trait QuerySampler {
+    /// Returns the sampling strategy implemented by this sampler.
+    fn strategy(&self) -> QuerySampling;
+
+    /// Draws `num_queries` positions from a domain of size `lde_domain_size` using the provided
+    /// public coin reseeded with the proof-of-work `nonce`.
+    ///
+    /// The returned positions may contain duplicates and do not need to be sorted.
+    ///
+    /// # Errors
+    /// Returns an error if the required number of positions could not be drawn.
+    fn draw_positions<R: RandomCoin>(
+        &self,
+        public_coin: &mut R,
+        num_queries: usize,
+        lde_domain_size: usize,
+        nonce: u64,
+    ) -> Result<Vec<usize>, RandomCoinError>;
+}
+
+/// Query sampler which draws every position uniformly at random from the entire LDE domain.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub struct UniformSampler;
+
+/// Query sampler which spreads positions evenly across the cosets of a subgroup of the LDE domain.
+///
+/// Position `p` belongs to the coset with index `p mod num_cosets`; the i-th position is drawn
+/// uniformly at random from the coset with index `i mod num_cosets`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct StratifiedSampler {
+    num_cosets: usize,
+}
+
 /// STARK protocol parameters.
 ///
 /// These parameters have a direct impact on proof soundness, proof generation time, and proof
@@ -70,6 +182,20 @@ pub enum FieldExtension {
 ///    generation time. More precisely, conjectured proof soundness is bounded by
 ///    `num_queries * log2(blowup_factor) + grinding_factor`.
 ///
+/// Additionally, queries can be normalized (see [ProofOptions::with_normalized_queries()]) so
+/// that the number of openings in a proof does not depend on the choice of query positions, and
+/// FRI layers can be folded by different factors (see [ProofOptions::with_fri_folding_schedule()])
+/// to trade off proof size against verification cost. For small fields, proof-of-work can also be
+/// required after each FRI layer commitment (see
+/// [ProofOptions::with_fri_layer_grinding_factor()]), and FRI query values which the verifier can
+/// derive on its own can be omitted from proofs (see
+/// [ProofOptions::with_fri_query_deduplication()]). When `experimental-stir` feature is enabled,
+/// an experimental STIR low-degree test can also be used instead of FRI (see
+/// [ProofOptions::with_low_degree_test()]). Lastly, the encoding of
+/// trace rows into Merkle tree leaves can be selected so that external verifiers can recompute
+/// trace commitments cheaply (see [ProofOptions::with_leaf_encoding()]), and the strategy for
+/// sampling query positions can be selected (see [ProofOptions::with_query_sampling()]).
+///
 /// Another important parameter in defining STARK security level, which is not a part of [ProofOptions]
 /// is the hash function used in the protocol. The soundness of a STARK proof is limited by the
 /// collision resistance of the hash function used by the protocol. For example, if a hash function
@@ -82,6 +208,13 @@ pub struct ProofOptions {
     field_extension: FieldExtension,
     fri_folding_factor: u8,
     fri_remainder_max_degree: u8,
+    fri_folding_schedule: Vec<u8>,
+    fri_layer_grinding_factor: u8,
+    fri_query_deduplication: bool,
+    normalized_queries: bool,
+    low_degree_test: LowDegreeTest,
+    leaf_encoding: LeafEncoding,
+    query_sampling: QuerySampling,
 }
 
 // PROOF OPTIONS IMPLEMENTATION
@@ -147,7 +280,140 @@ impl ProofOptions {
             field_extension,
             fri_folding_factor: fri_folding_factor as u8,
             fri_remainder_max_degree: fri_remainder_max_degree as u8,
+            fri_folding_schedule: Vec::new(),
+            fri_layer_grinding_factor: 0,
+            fri_query_deduplication: false,
+            normalized_queries: false,
+            low_degree_test: LowDegreeTest::Fri,
+            leaf_encoding: LeafEncoding::Packed,
+            query_sampling: QuerySampling::Uniform,
+        }
+    }
+
+    /// Returns these proof options with the specified per-layer FRI folding schedule.
+    ///
+    /// The i-th FRI layer is folded by the i-th factor in the `schedule`, and the layers beyond
+    /// the schedule are folded by the FRI folding factor specified when these options were
+    /// created. Folding stops once the remainder polynomial is of small enough degree, and thus,
+    /// for example, a folding factor of 4 combined with a schedule `[8, 8]` folds the first two
+    /// layers by 8, and all subsequent layers by 4. Larger folding factors result in fewer FRI
+    /// layers (and thus, fewer Merkle authentication paths in a proof), but in more evaluations
+    /// opened per query in each layer; a schedule makes it possible to pick the factors so that
+    /// the remainder domain is of the desired size.
+    ///
+    /// # Panics
+    /// Panics if:
+    /// - Any of the factors in the `schedule` is not 2, 4, 8, or 16.
+    /// - The `schedule` contains more than 32 factors.
+    pub fn with_fri_folding_schedule(mut self, schedule: &[usize]) -> Self {
+        assert!(
+            schedule.len() <= FRI_MAX_FOLDING_SCHEDULE_LENGTH,
+            "FRI folding schedule cannot contain more than 32 factors"
+        );
+        for &folding_factor in schedule {
+            assert!(
+                is_valid_fri_folding_factor(folding_factor),
+                "FRI folding factor {folding_factor} is not supported"
+            );
         }
+        self.fri_folding_schedule = schedule.iter().map(|&f| f as u8).collect();
+        self
+    }
+
+    /// Returns these proof options with the specified per-layer FRI grinding factor.
+    ///
+    /// When the factor is greater than zero, the prover must perform a proof-of-work after
+    /// committing to each FRI layer and before the folding challenge for the layer is drawn; the
+    /// nonces are included in the FRI proof and are checked by the verifier. This makes it more
+    /// expensive for a malicious prover to search for favorable folding challenges, and thus,
+    /// increases soundness of the FRI commit phase by `grinding_factor` bits. This is primarily
+    /// useful for small fields, where the soundness of the commit phase may otherwise limit the
+    /// security level of a proof.
+    ///
+    /// # Panics
+    /// Panics if `grinding_factor` is greater than 32.
+    pub fn with_fri_layer_grinding_factor(mut self, grinding_factor: u32) -> Self {
+        assert!(
+            grinding_factor <= MAX_GRINDING_FACTOR,
+            "FRI layer grinding factor cannot be greater than 32"
+        );
+        self.fri_layer_grinding_factor = grinding_factor as u8;
+        self
+    }
+
+    /// Returns these proof options with FRI query deduplication enabled.
+    ///
+    /// In every FRI layer after the first one, the verifier already knows the values at the
+    /// positions folded from the previous layer, and in the first layer, it knows the values at
+    /// the queried positions. When deduplication is enabled, these values are omitted from FRI
+    /// layer openings, and the verifier restores them from the values it has computed. This
+    /// reduces proof size at the expense of slightly more complex verification; soundness of the
+    /// protocol is not affected.
+    pub fn with_fri_query_deduplication(mut self) -> Self {
+        self.fri_query_deduplication = true;
+        self
+    }
+
+    /// Returns these proof options with query normalization enabled.
+    ///
+    /// By default, query positions are drawn independently, and thus, some positions may be
+    /// drawn more than once; also, distinct positions may fold into the same position in one of
+    /// the FRI layers. As a result, the number of openings included in a proof varies from proof
+    /// to proof. When queries are normalized, positions are redrawn until there are exactly
+    /// [num_queries()](ProofOptions::num_queries) positions which remain distinct in all FRI
+    /// layers (i.e., which are distinct modulo the size of the FRI remainder domain). Thus, for
+    /// a given trace length and set of proof options, every proof contains the same number of
+    /// openings, and the work done by a verifier which checks each opening separately (e.g., an
+    /// on-chain verifier) is constant.
+    ///
+    /// Authentication paths of the openings are not padded: Merkle trees committing to longer
+    /// execution traces are deeper, and thus, verification cost still grows with trace length.
+    ///
+    /// Sampling positions without repetition does not reduce soundness of the protocol; however,
+    /// the number of queries must not exceed the size of the FRI remainder domain.
+    pub fn with_normalized_queries(mut self) -> Self {
+        self.normalized_queries = true;
+        self
+    }
+
+    /// Returns these proof options with the specified low-degree test.
+    ///
+    /// By default, FRI is used to prove that the constraint composition polynomial is of low
+    /// degree. When `experimental-stir` feature is enabled, `LowDegreeTest::Stir` replaces FRI
+    /// with the experimental STIR protocol, in which the evaluation domain of every committed
+    /// layer is only half the size of the domain of the previous layer, and thus, fewer queries
+    /// are needed at later layers. STIR does not support FRI folding schedules, per-layer
+    /// proof-of-work, or FRI query deduplication.
+    ///
+    /// No security is claimed for STIR proofs: both the conjectured and the proven security
+    /// levels of such proofs are reported as 0, and thus, verifiers which accept proofs by their
+    /// security level reject STIR proofs.
+    pub fn with_low_degree_test(mut self, low_degree_test: LowDegreeTest) -> Self {
+        self.low_degree_test = low_degree_test;
+        self
+    }
+
+    /// Returns these proof options with the specified encoding of trace rows into Merkle tree
+    /// leaves.
+    ///
+    /// By default, rows are encoded as packed canonical little-endian elements (see
+    /// [LeafEncoding] for the description of all encodings). Padded encodings make trace
+    /// commitments cheaper to recompute for verifiers which operate on fixed-size words (e.g.,
+    /// on-chain verifiers), at the expense of slower hashing; soundness of the protocol is not
+    /// affected.
+    pub fn with_leaf_encoding(mut self, leaf_encoding: LeafEncoding) -> Self {
+        self.leaf_encoding = leaf_encoding;
+        self
+    }
+
+    /// Returns these proof options with the specified strategy for sampling query positions.
+    ///
+    /// By default, query positions are drawn uniformly at random from the entire LDE domain (see
+    /// [QuerySampling] for the description of all strategies). The strategy is a part of the
+    /// proof context, and thus, a verifier can accept only proofs with the strategies it trusts.
+    pub fn with_query_sampling(mut self, query_sampling: QuerySampling) -> Self {
+        self.query_sampling = query_sampling;
+        self
     }
 
     // PUBLIC ACCESSORS
@@ -200,56 +466,522 @@ impl ProofOptions {
         B::GENERATOR
     }
 
+    /// Returns `true` if query positions are normalized (see
+    /// [with_normalized_queries()](ProofOptions::with_normalized_queries)).
+    pub const fn normalized_queries(&self) -> bool {
+        self.normalized_queries
+    }
+
+    /// Returns the per-layer FRI folding schedule (see
+    /// [with_fri_folding_schedule()](ProofOptions::with_fri_folding_schedule)); the schedule is
+    /// empty if all FRI layers are folded by the same factor.
+    pub fn fri_folding_schedule(&self) -> Vec<usize> {
+        self.fri_folding_schedule.iter().map(|&f| f as usize).collect()
+    }
+
+    /// Returns the number of leading zeros required from the proof-of-work performed after
+    /// committing to each FRI layer (see
+    /// [with_fri_layer_grinding_factor()](ProofOptions::with_fri_layer_grinding_factor)); zero
+    /// means that no per-layer proof-of-work is performed.
+    pub const fn fri_layer_grinding_factor(&self) -> u32 {
+        self.fri_layer_grinding_factor as u32
+    }
+
+    /// Returns `true` if FRI query values known to the verifier are omitted from proofs (see
+    /// [with_fri_query_deduplication()](ProofOptions::with_fri_query_deduplication)).
+    pub const fn fri_query_deduplication(&self) -> bool {
+        self.fri_query_deduplication
+    }
+
+    /// Returns the low-degree test used to prove that the constraint composition polynomial is of
+    /// low degree (see [with_low_degree_test()](ProofOptions::with_low_degree_test)).
+    pub const fn low_degree_test(&self) -> LowDegreeTest {
+        self.low_degree_test
+    }
+
+    /// Returns the encoding of trace rows into Merkle tree leaves (see
+    /// [with_leaf_encoding()](ProofOptions::with_leaf_encoding)).
+    pub const fn leaf_encoding(&self) -> LeafEncoding {
+        self.leaf_encoding
+    }
+
+    /// Returns the strategy for sampling query positions (see
+    /// [with_query_sampling()](ProofOptions::with_query_sampling)).
+    pub const fn query_sampling(&self) -> QuerySampling {
+        self.query_sampling
+    }
+
     /// Returns options for FRI protocol instantiated with parameters from this proof options.
     pub fn to_fri_options(&self) -> FriOptions {
         let folding_factor = self.fri_folding_factor as usize;
         let remainder_max_degree = self.fri_remainder_max_degree as usize;
-        FriOptions::new(self.blowup_factor(), folding_factor, remainder_max_degree)
+        let options = FriOptions::new(self.blowup_factor(), folding_factor, remainder_max_degree)
+            .with_folding_schedule(&self.fri_folding_schedule())
+            .with_layer_grinding_factor(self.fri_layer_grinding_factor())
+            .with_low_degree_test(self.low_degree_test);
+        if self.fri_query_deduplication {
+            options.with_query_deduplication()
+        } else {
+            options
+        }
+    }
+
+    // QUERY POSITIONS
+    // --------------------------------------------------------------------------------------------
+
+    /// Draws query positions from the provided public coin after reseeding it with the
+    /// proof-of-work `nonce`, and returns them sorted in ascending order.
+    ///
+    /// Positions are drawn by the sampler defined by the query sampling strategy of these options
+    /// (see [ProofOptions::draw_query_positions_with()] for details).
+    ///
+    /// # Errors
+    /// Returns an error if the required number of positions could not be drawn. In particular,
+    /// an error is returned if the number of queries is not smaller than `lde_domain_size`,
+    /// when queries are normalized, if the number of queries is greater than the size of the FRI
+    /// remainder domain. [RandomCoinError::SamplerMismatch] is returned if the query sampling
+    /// strategy is a [custom](QuerySampling::Custom) one.
+    pub fn draw_query_positions<R: RandomCoin>(
+        &self,
+        public_coin: &mut R,
+        lde_domain_size: usize,
+        nonce: u64,
+    ) -> Result<Vec<usize>, RandomCoinError> {
+        match self.query_sampling {
+            QuerySampling::Uniform => {
+                self.draw_query_positions_with(&UniformSampler, public_coin, lde_domain_size, nonce)
+            }
+            QuerySampling::Stratified => {
+                let sampler = StratifiedSampler::new(self.blowup_factor());
+                self.draw_query_positions_with(&sampler, public_coin, lde_domain_size, nonce)
+            }
+            QuerySampling::Custom(_) => Err(RandomCoinError::SamplerMismatch),
+        }
+    }
+
+    /// Draws query positions from the provided public coin using the specified `sampler`, and
+    /// returns them sorted in ascending order.
+    ///
+    /// [num_queries()](ProofOptions::num_queries) positions are drawn from a domain of size
+    /// `lde_domain_size`, and duplicate positions are removed. When queries are normalized, more
+    /// positions are drawn until there are exactly `num_queries()` positions which are distinct
+    /// modulo the size of the FRI remainder domain; positions which collide with previously drawn
+    /// positions are discarded.
+    ///
+    /// # Errors
+    /// Returns [RandomCoinError::SamplerMismatch] if the strategy implemented by the `sampler` is
+    /// different from the query sampling strategy of these options, and an error if the required
+    /// number of positions could not be drawn.
+    pub fn draw_query_positions_with<S: QuerySampler, R: RandomCoin>(
+        &self,
+        sampler: &S,
+        public_coin: &mut R,
+        lde_domain_size: usize,
+        nonce: u64,
+    ) -> Result<Vec<usize>, RandomCoinError> {
+        if sampler.strategy() != self.query_sampling {
+            return Err(RandomCoinError::SamplerMismatch);
+        }
+        let num_queries = self.num_queries();
+        if num_queries >= lde_domain_size {
+            return Err(RandomCoinError::FailedToDrawIntegers(num_queries, 0, 0));
+        }
+        let mut positions =
+            sampler.draw_positions(public_coin, num_queries, lde_domain_size, nonce)?;
+
+        if self.normalized_queries {
+            let remainder_domain_size =
+                self.to_fri_options().remainder_domain_size(lde_domain_size);
+            if num_queries > remainder_domain_size {
+                return Err(RandomCoinError::FailedToDrawIntegers(
+                    num_queries,
+                    remainder_domain_size,
+                    0,
+                ));
+            }
+
+            let mut candidates = positions;
+            positions = Vec::with_capacity(num_queries);
+            for _ in 0..MAX_QUERY_DRAW_ROUNDS {
+                for position in candidates {
+                    let residue = position % remainder_domain_size;
+                    if !positions.iter().any(|&p| p % remainder_domain_size == residue) {
+                        positions.push(position);
+                    }
+                }
+                if positions.len() == num_queries {
+                    break;
+                }
+                let num_missing = num_queries - positions.len();
+                candidates =
+                    sampler.draw_positions(public_coin, num_missing, lde_domain_size, nonce)?;
+            }
+
+            if positions.len() < num_queries {
+                return Err(RandomCoinError::FailedToDrawIntegers(
+                    num_queries,
+                    positions.len(),
+                    MAX_QUERY_DRAW_ROUNDS,
+                ));
+            }
+        }
+
+        // remove any duplicate positions from the list
+        positions.sort_unstable();
+        positions.dedup();
+
+        Ok(positions)
+    }
+
+    // PROOF SIZE ESTIMATION
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns an estimate of the size (in bytes) of a serialized STARK proof generated with these
+    /// options for a computation described by the specified trace info and AIR context, assuming
+    /// hash function `H` is used for all commitments.
+    ///
+    /// The estimate accounts for the proof context, trace, constraint and FRI layer commitments,
+    /// query openings against all committed Merkle trees, out-of-domain evaluations, the FRI
+    /// remainder, and the proof-of-work nonce. Only the number of constraint composition columns
+    /// is taken from `air_context`; all other protocol parameters are taken from these options.
+    /// Thus, the same context can be used to compare proof sizes for different choices of blowup
+    /// factor, number of queries, and FRI folding factor without running the prover.
+    ///
+    /// Since query positions are drawn at random, the number of unique queries and the number of
+    /// Merkle authentication path nodes shared between queries vary from proof to proof. The
+    /// returned value is the expected size over the choice of query positions; sizes of actual
+    /// proofs are usually within a few percent of it.
+    pub fn estimate_proof_size<H: Hasher, B: StarkField>(
+        &self,
+        trace_info: &TraceInfo,
+        air_context: &AirContext<B>,
+    ) -> usize {
+        let digest_size = H::Digest::default().to_bytes().len();
+        let base_element_size = B::ELEMENT_BYTES;
+        let element_size = base_element_size * self.field_extension.degree() as usize;
+        let num_draws = self.num_queries();
+        let lde_domain_size = trace_info.length() * self.blowup_factor();
+        let num_composition_columns = air_context.num_constraint_composition_columns();
+
+        let fri_options = self.to_fri_options();
+        let folding_factors = fri_options.layer_folding_factors(lde_domain_size);
+        let num_fri_layers = folding_factors.len();
+
+        // format version header, proof context, number of unique queries, and proof-of-work nonce
+        let mut result = 2 + Context::new::<B>(trace_info, self.clone()).to_bytes().len() + 1 + 8;
+
+        // commitments to all trace segments, the constraint composition polynomial, all FRI
+        // layers and the FRI remainder
+        let num_commitments = trace_info.layout().num_segments() + 1 + num_fri_layers + 1;
+        result += 2 + num_commitments * digest_size;
+
+        // out-of-domain frame consists of trace states at two points and evaluations of all
+        // constraint composition columns
+        let ood_frame_size = (2 * trace_info.width() + num_composition_columns) * element_size;
+        result += 4 + ood_frame_size;
+
+        // openings of the trace segments and the constraint composition polynomial; all of
+        // these are opened at the same positions of the LDE domain
+        let mut queries_size = batch_opening_size(
+            num_draws,
+            lde_domain_size,
+            trace_info.layout().main_trace_width() * base_element_size,
+            digest_size,
+        );
+        for i in 0..trace_info.layout().num_aux_segments() {
+            queries_size += batch_opening_size(
+                num_draws,
+                lde_domain_size,
+                trace_info.layout().get_aux_segment_width(i) * element_size,
+                digest_size,
+            );
+        }
+        queries_size += batch_opening_size(
+            num_draws,
+            lde_domain_size,
+            num_composition_columns * element_size,
+            digest_size,
+        );
+
+        // FRI layer openings; in each layer, every leaf contains as many evaluations as the
+        // folding factor of the layer, and positions are folded into the domain of the next layer;
+        // when queries are deduplicated, values at positions entering a layer are omitted, and
+        // the number of queries is written for every layer instead
+        let mut domain_size = lde_domain_size;
+        match self.low_degree_test {
+            LowDegreeTest::Fri => {
+                for folding_factor in folding_factors {
+                    queries_size += batch_opening_size(
+                        num_draws,
+                        domain_size / folding_factor,
+                        folding_factor * element_size,
+                        digest_size,
+                    );
+                    if self.fri_query_deduplication {
+                        queries_size -=
+                            expected_num_unique(num_draws, domain_size) * element_size as f64;
+                        queries_size += 1.0;
+                    }
+                    domain_size /= folding_factor;
+                }
+            }
+            #[cfg(feature = "experimental-stir")]
+            LowDegreeTest::Stir => {
+                // in STIR, the first layer is opened at the query positions and at the shift queries
+                // of the first round, and every other layer is opened only at the shift queries of its
+                // round; the domain of every layer is half the size of the domain of the previous one
+                let layer_num_queries =
+                    fri::stir::layer_num_queries(&fri_options, num_draws, lde_domain_size);
+                let layer_domain_sizes = fri_options.layer_domain_sizes(lde_domain_size);
+                for (i, (&folding_factor, &layer_domain_size)) in
+                    folding_factors.iter().zip(&layer_domain_sizes).enumerate()
+                {
+                    let num_layer_draws = if i == 0 {
+                        num_draws + layer_num_queries[0]
+                    } else {
+                        layer_num_queries[i]
+                    };
+                    queries_size += batch_opening_size(
+                        num_layer_draws,
+                        layer_domain_size / folding_factor,
+                        folding_factor * element_size,
+                        digest_size,
+                    );
+                    domain_size /= folding_factor;
+                }
+                // out-of-domain evaluations of all layers except for the first one
+                queries_size += (num_fri_layers.saturating_sub(1) * element_size) as f64;
+            }
+            // STIR proofs cannot be generated without `experimental-stir` feature
+            #[cfg(not(feature = "experimental-stir"))]
+            LowDegreeTest::Stir => (),
+        }
+        result += (queries_size + 0.5) as usize;
+
+        // FRI layers, deduplication flag, FRI remainder, out-of-domain evaluations length, FRI
+        // layer nonces, and number of partitions
+        let remainder_size = domain_size / self.blowup_factor() * element_size;
+        let num_layer_nonces = if self.fri_layer_grinding_factor > 0 {
+            num_fri_layers
+        } else {
+            0
+        };
+        result += 1 + 1 + 2 + remainder_size + 2 + 1 + num_layer_nonces * 8 + 1;
+
+        result
     }
 }
 
 impl<E: StarkField> ToElements<E> for ProofOptions {
     fn to_elements(&self) -> Vec<E> {
+        self.to_elements_for_version(StarkProof::FORMAT_VERSION)
+    }
+}
+
+impl ProofOptions {
+    /// Converts these options into field elements in the same way as they are converted for
+    /// proofs with the specified format `version` (see [StarkProof]).
+    ///
+    /// For proofs with format versions earlier than 4, the length of FRI folding schedule is not
+    /// encoded together with the grinding factors.
+    pub(crate) fn to_elements_for_version<E: StarkField>(&self, version: u8) -> Vec<E> {
         // encode field extension and FRI parameters into a single field element
         let mut buf = self.field_extension as u32;
         buf = (buf << 8) | self.fri_folding_factor as u32;
         buf = (buf << 8) | self.fri_remainder_max_degree as u32;
 
-        vec![
+        // query normalization and FRI query deduplication flags, as well as the low-degree test
+        // and the leaf encoding, are encoded into the most significant byte
+        buf |= (self.normalized_queries as u32) << 24;
+        buf |= (self.fri_query_deduplication as u32) << 25;
+        buf |= (self.low_degree_test as u32) << 26;
+        buf |= (self.leaf_encoding as u32) << 27;
+        buf |= (self.query_sampling.tag() as u32) << 29;
+
+        // FRI layer grinding factor, the length of FRI folding schedule, and the identifier of a
+        // custom query sampling strategy are encoded together with the query grinding factor
+        let schedule_length = if version >= CONTEXT_BINDING_VERSION {
+            self.fri_folding_schedule.len() as u32
+        } else {
+            0
+        };
+        let grinding = (self.query_sampling.custom_id() as u32) << 24
+            | schedule_length << 16
+            | (self.fri_layer_grinding_factor as u32) << 8
+            | self.grinding_factor as u32;
+
+        let mut result = vec![
             E::from(buf),
-            E::from(self.grinding_factor),
+            E::from(grinding),
             E::from(self.blowup_factor),
             E::from(self.num_queries),
-        ]
+        ];
+
+        // FRI folding schedule is appended only if it is specified
+        result.extend(self.fri_folding_schedule.iter().map(|&f| E::from(f)));
+        result
     }
 }
 
 impl Serializable for ProofOptions {
     /// Serializes `self` and writes the resulting bytes into the `target`.
     fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        self.write_into_version(target, StarkProof::FORMAT_VERSION);
+    }
+}
+
+impl Deserializable for ProofOptions {
+    /// Reads proof options from the specified `source` and returns the result.
+    ///
+    /// # Errors
+    /// Returns an error of a valid proof options could not be read from the specified `source`.
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        Self::read_from_version(source, StarkProof::FORMAT_VERSION)
+    }
+}
+
+impl ProofOptions {
+    /// Serializes `self` into the `target` in the same way as options are serialized as a part of
+    /// proofs with the specified format `version` (see [StarkProof]).
+    ///
+    /// Options serialized for format version 1 include only the number of queries, the blowup
+    /// factor, the grinding factor, the field extension, and the FRI folding factor and remainder
+    /// degree. Options serialized for format versions 1 and 2 do not include the leaf encoding,
+    /// and options serialized for format versions 1 to 3 do not include the query sampling
+    /// strategy.
+    pub(crate) fn write_into_version<W: ByteWriter>(&self, target: &mut W, version: u8) {
         target.write_u8(self.num_queries);
         target.write_u8(self.blowup_factor);
         target.write_u8(self.grinding_factor);
         target.write(self.field_extension);
         target.write_u8(self.fri_folding_factor);
         target.write_u8(self.fri_remainder_max_degree);
+        if version == 1 {
+            return;
+        }
+        target.write_bool(self.normalized_queries);
+        target.write_u8(self.fri_folding_schedule.len() as u8);
+        target.write_bytes(&self.fri_folding_schedule);
+        target.write_u8(self.fri_layer_grinding_factor);
+        target.write_bool(self.fri_query_deduplication);
+        target.write_u8(self.low_degree_test as u8);
+        if version >= 3 {
+            target.write(self.leaf_encoding);
+        }
+        if version >= 4 {
+            target.write(self.query_sampling);
+        }
     }
-}
 
-impl Deserializable for ProofOptions {
-    /// Reads proof options from the specified `source` and returns the result.
+    /// Reads proof options serialized as a part of a proof with the specified format `version`
+    /// (see [StarkProof]) from the specified `source`.
+    ///
+    /// Options serialized for format version 1 include only the parameters which can be passed
+    /// to [ProofOptions::new()], options serialized for format versions 1 and 2 do not include the
+    /// leaf encoding, and options serialized for format versions 1 to 3 do not include the query
+    /// sampling strategy; for such options, the defaults are assumed.
     ///
     /// # Errors
     /// Returns an error of a valid proof options could not be read from the specified `source`.
-    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
-        Ok(ProofOptions::new(
-            source.read_u8()? as usize,
-            source.read_u8()? as usize,
-            source.read_u8()? as u32,
-            FieldExtension::read_from(source)?,
-            source.read_u8()? as usize,
-            source.read_u8()? as usize,
-        ))
+    pub(crate) fn read_from_version<R: ByteReader>(
+        source: &mut R,
+        version: u8,
+    ) -> Result<Self, DeserializationError> {
+        // validate the parameters before passing them to the constructor, which panics on
+        // invalid parameters
+        let num_queries = source.read_u8()? as usize;
+        if num_queries == 0 {
+            return Err(DeserializationError::InvalidValue(
+                "number of queries must be greater than 0".to_string(),
+            ));
+        }
+
+        let blowup_factor = source.read_u8()? as usize;
+        if !blowup_factor.is_power_of_two() || blowup_factor < MIN_BLOWUP_FACTOR {
+            return Err(DeserializationError::InvalidValue(format!(
+                "blowup factor must be a power of two greater than or equal to {MIN_BLOWUP_FACTOR}, but was {blowup_factor}"
+            )));
+        }
+
+        let grinding_factor = source.read_u8()? as u32;
+        if grinding_factor > MAX_GRINDING_FACTOR {
+            return Err(DeserializationError::InvalidValue(format!(
+                "grinding factor cannot be greater than {MAX_GRINDING_FACTOR}, but was {grinding_factor}"
+            )));
+        }
+
+        let field_extension = FieldExtension::read_from(source)?;
+
+        let fri_folding_factor = source.read_u8()? as usize;
+        if !is_valid_fri_folding_factor(fri_folding_factor) {
+            return Err(DeserializationError::InvalidValue(format!(
+                "FRI folding factor {fri_folding_factor} is not supported"
+            )));
+        }
+
+        let fri_remainder_max_degree = source.read_u8()? as usize;
+        if !(fri_remainder_max_degree + 1).is_power_of_two() {
+            return Err(DeserializationError::InvalidValue(format!(
+                "FRI polynomial remainder degree must be one less than a power of two, but was {fri_remainder_max_degree}"
+            )));
+        }
+
+        let mut result = ProofOptions::new(
+            num_queries,
+            blowup_factor,
+            grinding_factor,
+            field_extension,
+            fri_folding_factor,
+            fri_remainder_max_degree,
+        );
+        if version == 1 {
+            return Ok(result);
+        }
+        if source.read_bool()? {
+            result = result.with_normalized_queries();
+        }
+
+        let schedule_length = source.read_u8()? as usize;
+        if schedule_length > FRI_MAX_FOLDING_SCHEDULE_LENGTH {
+            return Err(DeserializationError::InvalidValue(format!(
+                "FRI folding schedule cannot contain more than {FRI_MAX_FOLDING_SCHEDULE_LENGTH} factors, but was {schedule_length}"
+            )));
+        }
+        let schedule = source.read_vec(schedule_length)?;
+        if let Some(&factor) = schedule.iter().find(|&&f| !is_valid_fri_folding_factor(f as usize))
+        {
+            return Err(DeserializationError::InvalidValue(format!(
+                "FRI folding factor {factor} is not supported"
+            )));
+        }
+        result.fri_folding_schedule = schedule;
+
+        let layer_grinding_factor = source.read_u8()? as u32;
+        if layer_grinding_factor > MAX_GRINDING_FACTOR {
+            return Err(DeserializationError::InvalidValue(format!(
+                "FRI layer grinding factor cannot be greater than {MAX_GRINDING_FACTOR}, but was {layer_grinding_factor}"
+            )));
+        }
+        result.fri_layer_grinding_factor = layer_grinding_factor as u8;
+        result.fri_query_deduplication = source.read_bool()?;
+        result.low_degree_test = match source.read_u8()? {
+            0 => LowDegreeTest::Fri,
+            #[cfg(feature = "experimental-stir")]
+            1 => LowDegreeTest::Stir,
+            value => {
+                return Err(DeserializationError::InvalidValue(format!(
+                    "value {value} cannot be deserialized as LowDegreeTest enum"
+                )))
+            }
+        };
+        if version >= 3 {
+            result.leaf_encoding = LeafEncoding::read_from(source)?;
+        }
+        if version >= 4 {
+            result.query_sampling = QuerySampling::read_from(source)?;
+        }
+
+        Ok(result)
     }
 }
 
@@ -272,6 +1004,144 @@ impl FieldExtension {
     }
 }
 
+// LEAF ENCODING IMPLEMENTATION
+// ================================================================================================
+
+impl LeafEncoding {
+    /// Number of bytes taken up by a single base field element in padded encodings.
+    pub const PADDED_ELEMENT_BYTES: usize = 32;
+
+    /// Appends the encoding of the specified trace `row` to the `target`.
+    ///
+    /// # Panics
+    /// Panics if this is a padded encoding and base field elements are wider than 32 bytes.
+    pub fn encode_row<E: FieldElement>(&self, row: &[E], target: &mut Vec<u8>) {
+        let elements = E::slice_as_base_elements(row);
+        let element_bytes = E::BaseField::ELEMENT_BYTES;
+        match self {
+            Self::Packed => {
+                target.reserve(elements.len() * element_bytes);
+                elements.iter().for_each(|element| element.write_into(target));
+            }
+            Self::PaddedLe | Self::PaddedBe => {
+                assert!(
+                    element_bytes <= Self::PADDED_ELEMENT_BYTES,
+                    "base field elements cannot be wider than {} bytes, but were {} bytes",
+                    Self::PADDED_ELEMENT_BYTES,
+                    element_bytes
+                );
+                let padding = Self::PADDED_ELEMENT_BYTES - element_bytes;
+                target.reserve(elements.len() * Self::PADDED_ELEMENT_BYTES);
+                for element in elements {
+                    let start = target.len();
+                    element.write_into(target);
+                    if *self == Self::PaddedBe {
+                        target[start..].reverse();
+                        target.splice(start..start, core::iter::repeat(0).take(padding));
+                    } else {
+                        target.resize(start + Self::PADDED_ELEMENT_BYTES, 0);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns a hash of the specified trace `row` encoded using this encoding; this is the
+    /// value of the Merkle tree leaf committing to the row.
+    pub fn hash_row<H, E>(&self, row: &[E]) -> H::Digest
+    where
+        H: ElementHasher,
+        E: FieldElement<BaseField = H::BaseField>,
+    {
+        match self {
+            Self::Packed => H::hash_elements(row),
+            Self::PaddedLe | Self::PaddedBe => {
+                let mut bytes = Vec::new();
+                self.encode_row(row, &mut bytes);
+                H::hash(&bytes)
+            }
+        }
+    }
+}
+
+// QUERY SAMPLING IMPLEMENTATION
+// ================================================================================================
+
+impl QuerySampling {
+    /// Returns the tag identifying the kind of this strategy in serialized proof options.
+    const fn tag(&self) -> u8 {
+        match self {
+            Self::Uniform => 0,
+            Self::Stratified => 1,
+            Self::Custom(_) => 2,
+        }
+    }
+
+    /// Returns the identifier of a custom strategy, or zero for built-in strategies.
+    const fn custom_id(&self) -> u8 {
+        match self {
+            Self::Custom(id) => *id,
+            _ => 0,
+        }
+    }
+}
+
+impl QuerySampler for UniformSampler {
+    fn strategy(&self) -> QuerySampling {
+        QuerySampling::Uniform
+    }
+
+    fn draw_positions<R: RandomCoin>(
+        &self,
+        public_coin: &mut R,
+        num_queries: usize,
+        lde_domain_size: usize,
+        nonce: u64,
+    ) -> Result<Vec<usize>, RandomCoinError> {
+        public_coin.draw_integers(num_queries, lde_domain_size, nonce)
+    }
+}
+
+impl StratifiedSampler {
+    /// Returns a new sampler which spreads positions across the specified number of cosets.
+    ///
+    /// # Panics
+    /// Panics if `num_cosets` is not a power of two.
+    pub fn new(num_cosets: usize) -> Self {
+        assert!(num_cosets.is_power_of_two(), "number of cosets must be a power of two");
+        Self { num_cosets }
+    }
+
+    /// Returns the number of cosets across which positions are spread.
+    pub fn num_cosets(&self) -> usize {
+        self.num_cosets
+    }
+}
+
+impl QuerySampler for StratifiedSampler {
+    fn strategy(&self) -> QuerySampling {
+        QuerySampling::Stratified
+    }
+
+    fn draw_positions<R: RandomCoin>(
+        &self,
+        public_coin: &mut R,
+        num_queries: usize,
+        lde_domain_size: usize,
+        nonce: u64,
+    ) -> Result<Vec<usize>, RandomCoinError> {
+        // positions are drawn from the entire domain, and then moved into the coset assigned to
+        // them; this keeps positions uniformly distributed within each coset
+        let num_cosets = self.num_cosets.min(lde_domain_size);
+        let positions = public_coin.draw_integers(num_queries, lde_domain_size, nonce)?;
+        Ok(positions
+            .into_iter()
+            .enumerate()
+            .map(|(i, p)| p - p % num_cosets + i % num_cosets)
+            .collect())
+    }
+}
+
 // SERIALIZATION
 // ================================================================================================
 
@@ -301,13 +1171,127 @@ impl Deserializable for FieldExtension {
     }
 }
 
+impl Serializable for LeafEncoding {
+    /// Serializes `self` and writes the resulting bytes into the `target`.
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write_u8(*self as u8);
+    }
+
+    /// Returns an estimate of how many bytes are needed to represent self.
+    fn get_size_hint(&self) -> usize {
+        1
+    }
+}
+
+impl Deserializable for LeafEncoding {
+    /// Reads a leaf encoding enum from the specified `source`.
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        match source.read_u8()? {
+            0 => Ok(LeafEncoding::Packed),
+            1 => Ok(LeafEncoding::PaddedLe),
+            2 => Ok(LeafEncoding::PaddedBe),
+            value => Err(DeserializationError::InvalidValue(format!(
+                "value {value} cannot be deserialized as LeafEncoding enum"
+            ))),
+        }
+    }
+}
+
+impl Serializable for QuerySampling {
+    /// Serializes `self` and writes the resulting bytes into the `target`.
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write_u8(self.tag());
+        if let Self::Custom(id) = self {
+            target.write_u8(*id);
+        }
+    }
+
+    /// Returns an estimate of how many bytes are needed to represent self.
+    fn get_size_hint(&self) -> usize {
+        match self {
+            Self::Custom(_) => 2,
+            _ => 1,
+        }
+    }
+}
+
+impl Deserializable for QuerySampling {
+    /// Reads a query sampling enum from the specified `source`.
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        match source.read_u8()? {
+            0 => Ok(QuerySampling::Uniform),
+            1 => Ok(QuerySampling::Stratified),
+            2 => Ok(QuerySampling::Custom(source.read_u8()?)),
+            value => Err(DeserializationError::InvalidValue(format!(
+                "value {value} cannot be deserialized as QuerySampling enum"
+            ))),
+        }
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns `true` if FRI layers can be folded by the specified factor.
+const fn is_valid_fri_folding_factor(folding_factor: usize) -> bool {
+    folding_factor.is_power_of_two()
+        && folding_factor >= FRI_MIN_FOLDING_FACTOR
+        && folding_factor <= FRI_MAX_FOLDING_FACTOR
+}
+
+/// Returns the expected number of unique values among `num_draws` values drawn uniformly at
+/// random from a set of size `set_size`.
+fn expected_num_unique(num_draws: usize, set_size: usize) -> f64 {
+    let set_size = set_size as f64;
+    set_size * (1.0 - powf(1.0 - 1.0 / set_size, num_draws as f64))
+}
+
+/// Returns the expected number of bytes needed to serialize values and a batch Merkle proof for
+/// `num_draws` leaves drawn uniformly at random from a tree with `num_leaves` leaves.
+///
+/// A node at a given level of the tree is included in a batch proof only if the node itself is
+/// not on an authentication path but its sibling is.
+fn batch_opening_size(
+    num_draws: usize,
+    num_leaves: usize,
+    leaf_size: usize,
+    digest_size: usize,
+) -> f64 {
+    let num_unique = expected_num_unique(num_draws, num_leaves);
+
+    let mut num_nodes = 0.0;
+    let mut level_size = num_leaves;
+    while level_size > 1 {
+        let n = level_size as f64;
+        let p_untouched = powf(1.0 - 1.0 / n, num_draws as f64);
+        let p_pair_untouched = powf(1.0 - 2.0 / n, num_draws as f64);
+        num_nodes += n * (p_untouched - p_pair_untouched);
+        level_size /= 2;
+    }
+
+    // values are prefixed with their length; paths are prefixed with their length, the number
+    // of opened leaves, and the number of nodes for each leaf
+    let values_size = 4.0 + num_unique * leaf_size as f64;
+    let paths_size = 4.0 + 1.0 + num_unique + num_nodes * digest_size as f64;
+    values_size + paths_size
+}
+
 // TESTS
 // ================================================================================================
 
 #[cfg(test)]
 mod tests {
-    use super::{FieldExtension, ProofOptions, ToElements};
-    use math::fields::f64::BaseElement;
+    use super::{
+        FieldExtension, LeafEncoding, LowDegreeTest, ProofOptions, QuerySampler, QuerySampling,
+        StratifiedSampler, ToElements, UniformSampler,
+    };
+    use crypto::{hashers, DefaultRandomCoin, Hasher, RandomCoin, RandomCoinError};
+    use math::fields::{f64::BaseElement, QuadExtension};
+    use rand_utils::rand_vector;
+    use utils::{collections::Vec, Deserializable, Serializable};
+
+    type Blake3_256 = hashers::Blake3_256<BaseElement>;
+    type Sha3_256 = hashers::Sha3_256<BaseElement>;
 
     #[test]
     fn proof_options_to_elements() {
@@ -326,7 +1310,7 @@ mod tests {
         ]);
         let expected = vec![
             BaseElement::from(ext_fri),
-            BaseElement::from(grinding_factor as u32),
+            BaseElement::from(grinding_factor),
             BaseElement::from(blowup_factor as u32),
             BaseElement::from(num_queries as u32),
         ];
@@ -341,4 +1325,291 @@ mod tests {
         );
         assert_eq!(expected, options.to_elements());
     }
+
+    #[test]
+    fn proof_options_fri_folding_schedule() {
+        let options = ProofOptions::new(30, 8, 20, FieldExtension::None, 4, 7)
+            .with_fri_folding_schedule(&[8, 16]);
+        assert_eq!(vec![8, 16], options.fri_folding_schedule());
+        assert_eq!(vec![8, 16, 4], options.to_fri_options().layer_folding_factors(1 << 14));
+
+        // the schedule is appended to the elements of options without a schedule, and the length
+        // of the schedule is encoded together with the grinding factors
+        let mut expected: Vec<BaseElement> =
+            ProofOptions::new(30, 8, 20, FieldExtension::None, 4, 7).to_elements();
+        expected[1] = BaseElement::from(2u32 << 16 | 20);
+        expected.extend([BaseElement::from(8u8), BaseElement::from(16u8)]);
+        assert_eq!(expected, options.to_elements());
+
+        let bytes = options.to_bytes();
+        assert_eq!(options, ProofOptions::read_from_bytes(&bytes).unwrap());
+
+        // unsupported folding factors must be rejected
+        let mut bytes = bytes;
+        let num_bytes = bytes.len();
+        bytes[num_bytes - 6] = 32;
+        assert!(ProofOptions::read_from_bytes(&bytes).is_err());
+    }
+
+    #[test]
+    fn proof_options_fri_layer_grinding_factor() {
+        let options = ProofOptions::new(30, 8, 20, FieldExtension::None, 4, 7)
+            .with_fri_layer_grinding_factor(12);
+        assert_eq!(12, options.fri_layer_grinding_factor());
+        assert_eq!(12, options.to_fri_options().layer_grinding_factor());
+
+        // layer grinding factor is encoded together with the query grinding factor
+        let elements: Vec<BaseElement> = options.to_elements();
+        assert_eq!(BaseElement::from(12u32 << 8 | 20), elements[1]);
+
+        let bytes = options.to_bytes();
+        assert_eq!(options, ProofOptions::read_from_bytes(&bytes).unwrap());
+
+        // grinding factors greater than 32 must be rejected
+        let mut bytes = bytes;
+        let num_bytes = bytes.len();
+        bytes[num_bytes - 5] = 33;
+        assert!(ProofOptions::read_from_bytes(&bytes).is_err());
+    }
+
+    #[test]
+    fn proof_options_fri_query_deduplication() {
+        let options = ProofOptions::new(30, 8, 20, FieldExtension::None, 4, 7);
+        assert!(!options.fri_query_deduplication());
+        assert!(!options.to_fri_options().query_deduplication());
+
+        let options = options.with_fri_query_deduplication();
+        assert!(options.fri_query_deduplication());
+        assert!(options.to_fri_options().query_deduplication());
+
+        // deduplication flag is encoded next to the query normalization flag
+        let elements: Vec<BaseElement> = options.to_elements();
+        let expected = (1u32 << 25) | (1 << 16) | (4 << 8) | 7;
+        assert_eq!(BaseElement::from(expected), elements[0]);
+
+        let bytes = options.to_bytes();
+        assert_eq!(options, ProofOptions::read_from_bytes(&bytes).unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "experimental-stir")]
+    fn proof_options_low_degree_test() {
+        let options = ProofOptions::new(30, 8, 20, FieldExtension::None, 4, 7);
+        assert_eq!(LowDegreeTest::Fri, options.low_degree_test());
+        assert_eq!(LowDegreeTest::Fri, options.to_fri_options().low_degree_test());
+
+        let options = options.with_low_degree_test(LowDegreeTest::Stir);
+        assert_eq!(LowDegreeTest::Stir, options.low_degree_test());
+        assert_eq!(LowDegreeTest::Stir, options.to_fri_options().low_degree_test());
+
+        // low-degree test is encoded next to the FRI query deduplication flag
+        let elements: Vec<BaseElement> = options.to_elements();
+        let expected = (1u32 << 26) | (1 << 16) | (4 << 8) | 7;
+        assert_eq!(BaseElement::from(expected), elements[0]);
+
+        let bytes = options.to_bytes();
+        assert_eq!(options, ProofOptions::read_from_bytes(&bytes).unwrap());
+
+        // unknown low-degree tests must be rejected
+        let mut bytes = bytes;
+        let num_bytes = bytes.len();
+        bytes[num_bytes - 3] = 2;
+        assert!(ProofOptions::read_from_bytes(&bytes).is_err());
+    }
+
+    #[test]
+    #[cfg(not(feature = "experimental-stir"))]
+    fn proof_options_low_degree_test_without_stir() {
+        let options = ProofOptions::new(30, 8, 20, FieldExtension::None, 4, 7);
+        assert_eq!(LowDegreeTest::Fri, options.low_degree_test());
+
+        // STIR low-degree test is rejected when `experimental-stir` feature is disabled
+        let mut bytes = options.to_bytes();
+        let num_bytes = bytes.len();
+        bytes[num_bytes - 3] = 1;
+        assert!(ProofOptions::read_from_bytes(&bytes).is_err());
+    }
+
+    #[test]
+    fn proof_options_leaf_encoding() {
+        let options = ProofOptions::new(30, 8, 20, FieldExtension::None, 4, 7);
+        assert_eq!(LeafEncoding::Packed, options.leaf_encoding());
+
+        let options = options.with_leaf_encoding(LeafEncoding::PaddedBe);
+        assert_eq!(LeafEncoding::PaddedBe, options.leaf_encoding());
+
+        // leaf encoding is encoded next to the low-degree test
+        let elements: Vec<BaseElement> = options.to_elements();
+        let expected = (2u32 << 27) | (1 << 16) | (4 << 8) | 7;
+        assert_eq!(BaseElement::from(expected), elements[0]);
+
+        let bytes = options.to_bytes();
+        assert_eq!(options, ProofOptions::read_from_bytes(&bytes).unwrap());
+
+        // unknown leaf encodings must be rejected
+        let mut bytes = bytes;
+        let num_bytes = bytes.len();
+        bytes[num_bytes - 2] = 3;
+        assert!(ProofOptions::read_from_bytes(&bytes).is_err());
+    }
+
+    #[test]
+    fn proof_options_query_sampling() {
+        let options = ProofOptions::new(30, 8, 20, FieldExtension::None, 4, 7);
+        assert_eq!(QuerySampling::Uniform, options.query_sampling());
+
+        let options = options.with_query_sampling(QuerySampling::Stratified);
+        assert_eq!(QuerySampling::Stratified, options.query_sampling());
+
+        // query sampling strategy is encoded next to the leaf encoding
+        let elements: Vec<BaseElement> = options.to_elements();
+        let expected = (1u32 << 29) | (1 << 16) | (4 << 8) | 7;
+        assert_eq!(BaseElement::from(expected), elements[0]);
+
+        let bytes = options.to_bytes();
+        assert_eq!(options, ProofOptions::read_from_bytes(&bytes).unwrap());
+
+        // identifiers of custom strategies are encoded together with the grinding factors
+        let options = options.with_query_sampling(QuerySampling::Custom(5));
+        let elements: Vec<BaseElement> = options.to_elements();
+        assert_eq!(BaseElement::from((2u32 << 29) | (1 << 16) | (4 << 8) | 7), elements[0]);
+        assert_eq!(BaseElement::from(5u32 << 24 | 20), elements[1]);
+
+        let bytes = options.to_bytes();
+        assert_eq!(options, ProofOptions::read_from_bytes(&bytes).unwrap());
+
+        // unknown strategies must be rejected
+        let mut bytes = bytes;
+        let num_bytes = bytes.len();
+        bytes[num_bytes - 2] = 3;
+        assert!(ProofOptions::read_from_bytes(&bytes).is_err());
+    }
+
+    #[test]
+    fn draw_query_positions_stratified() {
+        let seed = [BaseElement::new(1), BaseElement::new(2)];
+        let options = ProofOptions::new(20, 8, 0, FieldExtension::None, 4, 7);
+        let lde_domain_size = 1024;
+
+        // the i-th position is drawn from the coset with index i mod 8
+        let sampler = StratifiedSampler::new(options.blowup_factor());
+        let mut coin = DefaultRandomCoin::<Blake3_256>::new(&seed);
+        let positions = sampler.draw_positions(&mut coin, 20, lde_domain_size, 0).unwrap();
+        for (i, &position) in positions.iter().enumerate() {
+            assert_eq!(i % 8, position % 8);
+            assert!(position < lde_domain_size);
+        }
+
+        // positions drawn via proof options are sorted and deduplicated
+        let options = options.with_query_sampling(QuerySampling::Stratified);
+        let mut coin = DefaultRandomCoin::<Blake3_256>::new(&seed);
+        let mut expected = positions;
+        expected.sort_unstable();
+        expected.dedup();
+        assert_eq!(expected, options.draw_query_positions(&mut coin, lde_domain_size, 0).unwrap());
+
+        // samplers must implement the strategy of the options
+        let mut coin = DefaultRandomCoin::<Blake3_256>::new(&seed);
+        let result =
+            options.draw_query_positions_with(&UniformSampler, &mut coin, lde_domain_size, 0);
+        assert_eq!(Err(RandomCoinError::SamplerMismatch), result);
+
+        // custom strategies cannot be used without a sampler
+        let options = options.with_query_sampling(QuerySampling::Custom(1));
+        let mut coin = DefaultRandomCoin::<Blake3_256>::new(&seed);
+        let result = options.draw_query_positions(&mut coin, lde_domain_size, 0);
+        assert_eq!(Err(RandomCoinError::SamplerMismatch), result);
+    }
+
+    #[test]
+    fn draw_query_positions_custom() {
+        /// Sampler which ignores the public coin and returns consecutive positions starting at
+        /// the nonce.
+        struct SequentialSampler;
+
+        impl QuerySampler for SequentialSampler {
+            fn strategy(&self) -> QuerySampling {
+                QuerySampling::Custom(7)
+            }
+
+            fn draw_positions<R: RandomCoin>(
+                &self,
+                _public_coin: &mut R,
+                num_queries: usize,
+                lde_domain_size: usize,
+                nonce: u64,
+            ) -> Result<Vec<usize>, RandomCoinError> {
+                let start = nonce as usize;
+                Ok((start..start + num_queries).rev().map(|p| p % lde_domain_size).collect())
+            }
+        }
+
+        let options = ProofOptions::new(4, 8, 0, FieldExtension::None, 4, 7)
+            .with_query_sampling(QuerySampling::Custom(7));
+        let mut coin = DefaultRandomCoin::<Blake3_256>::new(&[BaseElement::new(1)]);
+        let positions = options.draw_query_positions_with(&SequentialSampler, &mut coin, 64, 62);
+        assert_eq!(vec![0, 1, 62, 63], positions.unwrap());
+
+        // the sampler must match the identifier of the custom strategy
+        let options = options.with_query_sampling(QuerySampling::Custom(8));
+        let result = options.draw_query_positions_with(&SequentialSampler, &mut coin, 64, 62);
+        assert_eq!(Err(RandomCoinError::SamplerMismatch), result);
+    }
+
+    #[test]
+    fn leaf_encoding_encode_row() {
+        let row = [BaseElement::new(1), BaseElement::new(0x0203)];
+
+        let mut bytes = Vec::new();
+        LeafEncoding::Packed.encode_row(&row, &mut bytes);
+        assert_eq!(vec![1, 0, 0, 0, 0, 0, 0, 0, 3, 2, 0, 0, 0, 0, 0, 0], bytes);
+
+        let mut bytes = Vec::new();
+        LeafEncoding::PaddedLe.encode_row(&row, &mut bytes);
+        let mut expected = vec![0u8; 64];
+        expected[0] = 1;
+        expected[32] = 3;
+        expected[33] = 2;
+        assert_eq!(expected, bytes);
+
+        let mut bytes = Vec::new();
+        LeafEncoding::PaddedBe.encode_row(&row, &mut bytes);
+        let mut expected = vec![0u8; 64];
+        expected[31] = 1;
+        expected[62] = 2;
+        expected[63] = 3;
+        assert_eq!(expected, bytes);
+
+        // elements of extension fields are split into base field elements
+        let ext_row = [QuadExtension::new(row[0], row[1])];
+        let mut ext_bytes = Vec::new();
+        LeafEncoding::PaddedBe.encode_row(&ext_row, &mut ext_bytes);
+        assert_eq!(bytes, ext_bytes);
+    }
+
+    #[test]
+    fn leaf_encoding_hash_row() {
+        let row = rand_vector::<BaseElement>(7);
+        for encoding in [LeafEncoding::Packed, LeafEncoding::PaddedLe, LeafEncoding::PaddedBe] {
+            let mut bytes = Vec::new();
+            encoding.encode_row(&row, &mut bytes);
+            assert_eq!(Blake3_256::hash(&bytes), encoding.hash_row::<Blake3_256, _>(&row));
+            assert_eq!(Sha3_256::hash(&bytes), encoding.hash_row::<Sha3_256, _>(&row));
+        }
+    }
+
+    #[test]
+    fn proof_options_invalid_parameters() {
+        let options = ProofOptions::new(30, 8, 20, FieldExtension::None, 4, 7);
+        let bytes = options.to_bytes();
+
+        // parameters which would make the constructor panic must be rejected; the parameters are
+        // serialized in the order: number of queries, blowup factor, grinding factor, field
+        // extension, FRI folding factor, and FRI remainder max degree
+        for (position, value) in [(0, 0), (1, 1), (1, 12), (2, 33), (4, 1), (4, 6), (5, 8)] {
+            let mut bytes = bytes.clone();
+            bytes[position] = value;
+            assert!(ProofOptions::read_from_bytes(&bytes).is_err());
+        }
+    }
 }
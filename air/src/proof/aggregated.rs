@@ -0,0 +1,277 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{Context, StarkProof, CONTEXT_BINDING_VERSION};
+use crate::ProofOptions;
+use crypto::{Digest, Hasher};
+use utils::{
+    collections::Vec, string::String, ByteReader, ByteWriter, Deserializable, DeserializationError,
+    Serializable, SliceReader,
+};
+
+// AGGREGATED PROOF
+// ================================================================================================
+
+/// A container for multiple STARK proofs which are shipped together.
+///
+/// Proofs in the container may attest to executions of different computations; every proof is
+/// accompanied by a label identifying the computation (e.g., the name of its AIR) so that the
+/// verifier can select the AIR against which the proof should be verified. The container also
+/// holds application-defined metadata shared by all proofs (e.g., a batch number).
+///
+/// When the container is serialized, each distinct set of [ProofOptions] is written only once,
+/// and proofs refer to the options they were generated with by index. A single digest committing
+/// to the metadata, the labels, and all proofs in the container can be computed via
+/// [digest()](AggregatedProof::digest).
+///
+/// Proofs in the container can be verified via `verify_all()` function of the verifier crate.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AggregatedProof {
+    metadata: Vec<u8>,
+    labels: Vec<String>,
+    proofs: Vec<StarkProof>,
+}
+
+impl AggregatedProof {
+    /// Version of the serialization format used by [to_bytes()](AggregatedProof::to_bytes).
+    ///
+    /// Containers serialized with format version 1 hold proofs serialized with proof format
+    /// version 4 (see [StarkProof::FORMAT_VERSION]), and can still be deserialized.
+    pub const FORMAT_VERSION: u8 = 2;
+
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a new empty container with the specified metadata.
+    ///
+    /// # Panics
+    /// Panics if the metadata is longer than `u32::MAX` bytes.
+    pub fn new(metadata: Vec<u8>) -> Self {
+        assert!(
+            metadata.len() <= u32::MAX as usize,
+            "metadata cannot be longer than {} bytes, but was {} bytes",
+            u32::MAX,
+            metadata.len()
+        );
+        Self {
+            metadata,
+            labels: Vec::new(),
+            proofs: Vec::new(),
+        }
+    }
+
+    /// Appends the specified proof to this container.
+    ///
+    /// # Panics
+    /// Panics if:
+    /// * The label is longer than `u16::MAX` bytes.
+    /// * The proof has a format version earlier than 4 (see [Context::format_version()]); proofs
+    ///   are stored in the current format, and the transcripts of such proofs depend on their
+    ///   format version.
+    pub fn push<L: Into<String>>(&mut self, label: L, proof: StarkProof) {
+        let label = label.into();
+        assert!(
+            label.len() <= u16::MAX as usize,
+            "proof label cannot be longer than {} bytes, but was {} bytes",
+            u16::MAX,
+            label.len()
+        );
+        assert!(
+            proof.context.format_version() >= CONTEXT_BINDING_VERSION,
+            "proofs with format version {} cannot be stored in a container",
+            proof.context.format_version()
+        );
+        self.labels.push(label);
+        self.proofs.push(proof);
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns metadata shared by all proofs in this container.
+    pub fn metadata(&self) -> &[u8] {
+        &self.metadata
+    }
+
+    /// Returns the number of proofs in this container.
+    pub fn num_proofs(&self) -> usize {
+        self.proofs.len()
+    }
+
+    /// Returns true if this container does not contain any proofs.
+    pub fn is_empty(&self) -> bool {
+        self.proofs.is_empty()
+    }
+
+    /// Returns the labels of proofs in this container in the order in which the proofs were
+    /// added.
+    pub fn labels(&self) -> &[String] {
+        &self.labels
+    }
+
+    /// Returns proofs in this container in the order in which they were added.
+    pub fn proofs(&self) -> &[StarkProof] {
+        &self.proofs
+    }
+
+    /// Returns the label and the proof at the specified index, or None if the index is out of
+    /// bounds.
+    pub fn get(&self, index: usize) -> Option<(&str, &StarkProof)> {
+        Some((self.labels.get(index)?.as_str(), self.proofs.get(index)?))
+    }
+
+    /// Returns the distinct sets of proof options used by proofs in this container in the order
+    /// in which they first occur.
+    pub fn distinct_options(&self) -> Vec<&ProofOptions> {
+        let mut result: Vec<&ProofOptions> = Vec::new();
+        for proof in self.proofs.iter() {
+            if !result.contains(&proof.options()) {
+                result.push(proof.options());
+            }
+        }
+        result
+    }
+
+    /// Consumes this container and returns its proofs together with their labels.
+    pub fn into_proofs(self) -> Vec<(String, StarkProof)> {
+        self.labels.into_iter().zip(self.proofs).collect()
+    }
+
+    // DIGESTS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a digest of the proof at the specified index and its label computed using hash
+    /// function `H`.
+    ///
+    /// # Panics
+    /// Panics if the index is out of bounds.
+    pub fn proof_digest<H: Hasher>(&self, index: usize) -> H::Digest {
+        let mut bytes = Vec::new();
+        write_label(&self.labels[index], &mut bytes);
+        self.proofs[index].write_into(&mut bytes);
+        H::hash(&bytes)
+    }
+
+    /// Returns a digest committing to the metadata of this container and to all proofs in it
+    /// (together with their labels) computed using hash function `H`.
+    ///
+    /// The digest is computed by hashing the metadata followed by digests of individual proofs
+    /// (see [proof_digest()](AggregatedProof::proof_digest)). Thus, a commitment to a single proof
+    /// can be checked against the digest given only the digests of other proofs.
+    pub fn digest<H: Hasher>(&self) -> H::Digest {
+        let mut bytes = Vec::new();
+        bytes.write_u32(self.metadata.len() as u32);
+        bytes.write_bytes(&self.metadata);
+        bytes.write_u32(self.proofs.len() as u32);
+        for index in 0..self.proofs.len() {
+            bytes.write_bytes(&self.proof_digest::<H>(index).as_bytes());
+        }
+        H::hash(&bytes)
+    }
+
+    // SERIALIZATION / DESERIALIZATION
+    // --------------------------------------------------------------------------------------------
+
+    /// Serializes this container into a vector of bytes.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut result = Vec::new();
+        self.write_into(&mut result);
+        result
+    }
+
+    /// Returns a container read from the specified `source`.
+    ///
+    /// # Errors
+    /// Returns an error if a valid container could not be read from the specified `source`.
+    pub fn from_bytes(source: &[u8]) -> Result<Self, DeserializationError> {
+        let mut source = SliceReader::new(source);
+        let result = Self::read_from(&mut source)?;
+        if source.has_more_bytes() {
+            return Err(DeserializationError::UnconsumedBytes);
+        }
+        Ok(result)
+    }
+}
+
+// SERIALIZATION
+// ================================================================================================
+
+impl Serializable for AggregatedProof {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write_u8(Self::FORMAT_VERSION);
+        target.write_u32(self.metadata.len() as u32);
+        target.write_bytes(&self.metadata);
+
+        let options = self.distinct_options();
+        target.write_u16(options.len() as u16);
+        options.iter().for_each(|options| options.write_into(target));
+
+        target.write_u32(self.proofs.len() as u32);
+        for (label, proof) in self.labels.iter().zip(self.proofs.iter()) {
+            write_label(label, target);
+            proof.context.write_with_options(target, |proof_options, target| {
+                let index = options.iter().position(|&o| o == proof_options);
+                target.write_u16(index.expect("proof options not found") as u16);
+            });
+            proof.write_body_into(target, StarkProof::FORMAT_VERSION);
+        }
+    }
+}
+
+impl Deserializable for AggregatedProof {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        let version = source.read_u8()?;
+        let proof_version = match version {
+            1 => 4,
+            Self::FORMAT_VERSION => StarkProof::FORMAT_VERSION,
+            _ => {
+                return Err(DeserializationError::InvalidValue(format!(
+                    "aggregated proof format version {version} is not supported"
+                )))
+            }
+        };
+
+        let num_metadata_bytes = source.read_u32()? as usize;
+        let metadata = source.read_vec(num_metadata_bytes)?;
+
+        let num_options = source.read_u16()? as usize;
+        let mut options = Vec::with_capacity(num_options);
+        for _ in 0..num_options {
+            options.push(ProofOptions::read_from(source)?);
+        }
+
+        let num_proofs = source.read_u32()? as usize;
+        let mut result = Self::new(metadata);
+        for _ in 0..num_proofs {
+            let label = read_label(source)?;
+            let context = Context::read_with_options(source, proof_version, |source| {
+                let index = source.read_u16()? as usize;
+                options.get(index).cloned().ok_or_else(|| {
+                    DeserializationError::InvalidValue(format!(
+                        "proof options index {index} is out of bounds for {num_options} options"
+                    ))
+                })
+            })?;
+            let proof = StarkProof::read_body_from(source, context, proof_version)?;
+            result.push(label, proof);
+        }
+
+        Ok(result)
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+fn write_label<W: ByteWriter>(label: &str, target: &mut W) {
+    target.write_u16(label.len() as u16);
+    target.write_bytes(label.as_bytes());
+}
+
+fn read_label<R: ByteReader>(source: &mut R) -> Result<String, DeserializationError> {
+    let num_bytes = source.read_u16()? as usize;
+    String::from_utf8(source.read_vec(num_bytes)?)
+        .map_err(|_| DeserializationError::InvalidValue("proof label is not valid UTF-8".into()))
+}
@@ -0,0 +1,213 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::StarkProof;
+use crypto::{Digest, Hasher};
+use utils::{
+    collections::Vec, ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable,
+    SliceReader,
+};
+
+// PROOF CHUNK
+// ================================================================================================
+
+/// A part of a serialized STARK proof which can be submitted separately from other parts.
+///
+/// Proofs are split into chunks via [StarkProof::to_chunks()] when a serialized proof exceeds
+/// the maximum size of a single message (e.g., the calldata limit of a single transaction).
+/// Every chunk carries a small linking header consisting of the index of the chunk, the total
+/// number of chunks, and a digest of the serialized proof; thus, a chunk can be checked against
+/// other chunks of the same proof as soon as it is received, and chunks can be received in any
+/// order. Once all chunks are received, the proof can be reassembled via
+/// [StarkProof::from_chunks()] and verified; the verifier crate also provides
+/// `ChunkedProofVerifier` which accepts chunks incrementally.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProofChunk {
+    index: u16,
+    num_chunks: u16,
+    proof_digest: [u8; 32],
+    data: Vec<u8>,
+}
+
+impl ProofChunk {
+    /// Number of bytes in a serialized chunk in addition to the bytes of the proof carried by
+    /// the chunk.
+    pub const HEADER_SIZE: usize = 40;
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the index of this chunk.
+    pub fn index(&self) -> usize {
+        self.index as usize
+    }
+
+    /// Returns the total number of chunks the proof was split into.
+    pub fn num_chunks(&self) -> usize {
+        self.num_chunks as usize
+    }
+
+    /// Returns the digest of the serialized proof this chunk belongs to.
+    ///
+    /// For hash functions with digests shorter than 32 bytes, the unused bytes are set to 0.
+    pub fn proof_digest(&self) -> &[u8; 32] {
+        &self.proof_digest
+    }
+
+    /// Returns bytes of the serialized proof carried by this chunk.
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    // SERIALIZATION / DESERIALIZATION
+    // --------------------------------------------------------------------------------------------
+
+    /// Serializes this chunk into a vector of bytes.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut result = Vec::with_capacity(Self::HEADER_SIZE + self.data.len());
+        self.write_into(&mut result);
+        result
+    }
+
+    /// Returns a chunk read from the specified `source`.
+    ///
+    /// # Errors
+    /// Returns an error if a valid chunk could not be read from the specified `source`.
+    pub fn from_bytes(source: &[u8]) -> Result<Self, DeserializationError> {
+        let mut source = SliceReader::new(source);
+        let result = Self::read_from(&mut source)?;
+        if source.has_more_bytes() {
+            return Err(DeserializationError::UnconsumedBytes);
+        }
+        Ok(result)
+    }
+}
+
+// PROOF SPLITTING
+// ================================================================================================
+
+impl StarkProof {
+    /// Splits this proof into chunks such that every serialized chunk (including its header) is
+    /// at most `max_chunk_size` bytes long.
+    ///
+    /// The proof is serialized via [to_bytes()](StarkProof::to_bytes), and the digest linking
+    /// the chunks together is computed over the serialized proof using hash function `H`.
+    ///
+    /// # Panics
+    /// Panics if:
+    /// * `max_chunk_size` is not greater than [ProofChunk::HEADER_SIZE].
+    /// * The proof would be split into more than `u16::MAX` chunks.
+    pub fn to_chunks<H: Hasher>(&self, max_chunk_size: usize) -> Vec<ProofChunk> {
+        assert!(
+            max_chunk_size > ProofChunk::HEADER_SIZE,
+            "max chunk size must be greater than {} bytes, but was {} bytes",
+            ProofChunk::HEADER_SIZE,
+            max_chunk_size
+        );
+        let bytes = self.to_bytes();
+        let proof_digest = H::hash(&bytes).as_bytes();
+        let chunks = bytes.chunks(max_chunk_size - ProofChunk::HEADER_SIZE);
+        assert!(
+            chunks.len() <= u16::MAX as usize,
+            "proof cannot be split into more than {} chunks, but {} chunks were needed",
+            u16::MAX,
+            chunks.len()
+        );
+
+        let num_chunks = chunks.len() as u16;
+        chunks
+            .enumerate()
+            .map(|(index, data)| ProofChunk {
+                index: index as u16,
+                num_chunks,
+                proof_digest,
+                data: data.to_vec(),
+            })
+            .collect()
+    }
+
+    /// Returns a proof reassembled from the specified chunks; the chunks may be provided in any
+    /// order.
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// * The chunks do not belong to the same proof, or some chunks are missing or duplicated.
+    /// * The digest of the reassembled proof computed using hash function `H` does not match the
+    ///   digest in the chunk headers.
+    /// * A valid proof could not be deserialized from the reassembled bytes.
+    pub fn from_chunks<H: Hasher>(chunks: &[ProofChunk]) -> Result<Self, DeserializationError> {
+        let first = chunks.first().ok_or_else(|| {
+            DeserializationError::InvalidValue("proof must consist of at least one chunk".into())
+        })?;
+        if chunks.len() != first.num_chunks() {
+            return Err(DeserializationError::InvalidValue(format!(
+                "expected {} proof chunks, but {} were provided",
+                first.num_chunks(),
+                chunks.len()
+            )));
+        }
+
+        let mut ordered: Vec<Option<&[u8]>> = vec![None; chunks.len()];
+        for chunk in chunks {
+            if chunk.num_chunks != first.num_chunks || chunk.proof_digest != first.proof_digest {
+                return Err(DeserializationError::InvalidValue(format!(
+                    "proof chunk {} does not belong to the same proof as other chunks",
+                    chunk.index
+                )));
+            }
+            match ordered.get_mut(chunk.index()) {
+                Some(slot @ None) => *slot = Some(&chunk.data),
+                _ => {
+                    return Err(DeserializationError::InvalidValue(format!(
+                        "proof chunk {} is out of bounds or duplicated",
+                        chunk.index
+                    )))
+                }
+            }
+        }
+
+        let bytes = ordered.into_iter().flatten().collect::<Vec<_>>().concat();
+        if H::hash(&bytes).as_bytes() != first.proof_digest {
+            return Err(DeserializationError::InvalidValue(
+                "digest of the reassembled proof does not match the digest of the chunks".into(),
+            ));
+        }
+        Self::from_bytes(&bytes)
+    }
+}
+
+// SERIALIZATION
+// ================================================================================================
+
+impl Serializable for ProofChunk {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write_u16(self.index);
+        target.write_u16(self.num_chunks);
+        target.write_bytes(&self.proof_digest);
+        target.write_u32(self.data.len() as u32);
+        target.write_bytes(&self.data);
+    }
+}
+
+impl Deserializable for ProofChunk {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        let index = source.read_u16()?;
+        let num_chunks = source.read_u16()?;
+        if index >= num_chunks {
+            return Err(DeserializationError::InvalidValue(format!(
+                "proof chunk index {index} is out of bounds for {num_chunks} chunks"
+            )));
+        }
+        let proof_digest = source.read_array()?;
+        let num_data_bytes = source.read_u32()? as usize;
+        let data = source.read_vec(num_data_bytes)?;
+        Ok(Self {
+            index,
+            num_chunks,
+            proof_digest,
+            data,
+        })
+    }
+}
@@ -0,0 +1,168 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{StarkProof, COMPRESSED_PROOF_MARKER, FORMAT_VERSION_MARKER};
+use core::fmt;
+use std::io::{Read, Write};
+use utils::{ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable};
+
+// COMPRESSION CODEC
+// ================================================================================================
+
+/// Defines a compression algorithm which can be used to compress serialized STARK proofs.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[repr(u8)]
+pub enum CompressionCodec {
+    /// Raw DEFLATE stream as specified in RFC 1951.
+    Deflate = 1,
+    /// Zstandard frame as specified in RFC 8878.
+    Zstd = 2,
+}
+
+impl CompressionCodec {
+    /// Compresses the specified bytes with this codec.
+    fn compress(&self, bytes: &[u8]) -> Vec<u8> {
+        match self {
+            Self::Deflate => {
+                let mut encoder =
+                    flate2::write::DeflateEncoder::new(Vec::new(), flate2::Compression::best());
+                encoder.write_all(bytes).expect("failed to compress proof");
+                encoder.finish().expect("failed to compress proof")
+            }
+            Self::Zstd => zstd::stream::encode_all(bytes, zstd::DEFAULT_COMPRESSION_LEVEL)
+                .expect("failed to compress proof"),
+        }
+    }
+
+    /// Decompresses the specified bytes with this codec; at most `max_len + 1` decompressed bytes
+    /// are produced.
+    fn decompress(&self, bytes: &[u8], max_len: usize) -> Result<Vec<u8>, DeserializationError> {
+        let limit = max_len as u64 + 1;
+        let mut result = Vec::new();
+        let read_result = match self {
+            Self::Deflate => {
+                flate2::read::DeflateDecoder::new(bytes).take(limit).read_to_end(&mut result)
+            }
+            Self::Zstd => zstd::stream::read::Decoder::new(bytes)
+                .and_then(|decoder| decoder.take(limit).read_to_end(&mut result)),
+        };
+        read_result.map_err(|err| {
+            DeserializationError::InvalidValue(format!("failed to decompress proof: {err}"))
+        })?;
+        Ok(result)
+    }
+}
+
+impl fmt::Display for CompressionCodec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Deflate => write!(f, "deflate"),
+            Self::Zstd => write!(f, "zstd"),
+        }
+    }
+}
+
+impl Serializable for CompressionCodec {
+    /// Serializes `self` and writes the resulting bytes into the `target`.
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write_u8(*self as u8);
+    }
+}
+
+impl Deserializable for CompressionCodec {
+    /// Reads a compression codec from the specified `source`.
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        match source.read_u8()? {
+            1 => Ok(Self::Deflate),
+            2 => Ok(Self::Zstd),
+            value => Err(DeserializationError::InvalidValue(format!(
+                "value {value} cannot be deserialized as CompressionCodec enum"
+            ))),
+        }
+    }
+}
+
+// COMPRESSED PROOFS
+// ================================================================================================
+
+impl StarkProof {
+    /// Serializes this proof into a vector of bytes and compresses the result with the specified
+    /// `codec`.
+    ///
+    /// The compressed proof is prefixed with a header consisting of a zero byte, a byte with value
+    /// 255 (which can never be a valid format version), the codec, and the length of the
+    /// uncompressed proof (as a `u32` value). The header allows compressed proofs to be
+    /// distinguished from uncompressed ones, and thus, compressed proofs can be read either via
+    /// [from_compressed_bytes()](StarkProof::from_compressed_bytes) or via
+    /// [from_bytes()](StarkProof::from_bytes).
+    ///
+    /// Digests and field elements which make up most of a proof are close to uniformly random,
+    /// and thus, the size reduction depends on the structure of the proof; compressing a small
+    /// proof may produce a result which is slightly larger than the uncompressed proof.
+    pub fn to_compressed_bytes(&self, codec: CompressionCodec) -> Vec<u8> {
+        let bytes = self.to_bytes();
+        let len = u32::try_from(bytes.len()).expect("proof is too large to be compressed");
+
+        let mut result = Vec::new();
+        result.write_u8(FORMAT_VERSION_MARKER);
+        result.write_u8(COMPRESSED_PROOF_MARKER);
+        codec.write_into(&mut result);
+        result.write_u32(len);
+        result.write_bytes(&codec.compress(&bytes));
+        result
+    }
+
+    /// Returns a STARK proof read from the specified compressed `source`.
+    ///
+    /// # Errors
+    /// Returns an error if the `source` is not a compressed proof, or if a valid STARK proof could
+    /// not be decompressed from the `source`.
+    pub fn from_compressed_bytes(source: &[u8]) -> Result<Self, DeserializationError> {
+        if !Self::is_compressed(source) {
+            return Err(DeserializationError::InvalidValue("proof is not compressed".into()));
+        }
+        Self::from_bytes(source)
+    }
+
+    /// Returns true if the specified bytes start with the header of a compressed proof.
+    pub fn is_compressed(source: &[u8]) -> bool {
+        source.starts_with(&[FORMAT_VERSION_MARKER, COMPRESSED_PROOF_MARKER])
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Reads a compressed proof from the specified `source`, assuming that the zero byte and the
+/// compression marker have already been read from it.
+///
+/// All remaining bytes of the `source` are consumed.
+pub(super) fn read_compressed_proof<R: ByteReader>(
+    source: &mut R,
+) -> Result<StarkProof, DeserializationError> {
+    let codec = CompressionCodec::read_from(source)?;
+    let len = source.read_u32()? as usize;
+
+    let mut compressed = Vec::new();
+    while source.has_more_bytes() {
+        compressed.push(source.read_u8()?);
+    }
+
+    let bytes = codec.decompress(&compressed, len)?;
+    if bytes.len() != len {
+        return Err(DeserializationError::InvalidValue(format!(
+            "expected decompressed proof to be {len} bytes long, but was {} bytes long",
+            bytes.len()
+        )));
+    }
+
+    // compressed proofs cannot be nested
+    if StarkProof::is_compressed(&bytes) {
+        return Err(DeserializationError::InvalidValue(
+            "decompressed proof is compressed again".into(),
+        ));
+    }
+    StarkProof::from_bytes(&bytes)
+}
@@ -3,7 +3,9 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
+use super::{get_conjectured_security, get_proven_security, StarkProof, CONTEXT_BINDING_VERSION};
 use crate::{ProofOptions, TraceInfo, TraceLayout};
+use crypto::{HashFunction, Hasher};
 use math::{StarkField, ToElements};
 use utils::{
     collections::Vec, string::ToString, ByteReader, ByteWriter, Deserializable,
@@ -20,6 +22,8 @@ pub struct Context {
     trace_meta: Vec<u8>,
     field_modulus_bytes: Vec<u8>,
     options: ProofOptions,
+    hash_fn: Option<HashFunction>,
+    format_version: u8,
 }
 
 impl Context {
@@ -34,9 +38,21 @@ impl Context {
             trace_meta: trace_info.meta().to_vec(),
             field_modulus_bytes: B::get_modulus_le_bytes(),
             options,
+            hash_fn: None,
+            format_version: StarkProof::FORMAT_VERSION,
         }
     }
 
+    /// Records hash function `H` as the hash function used to generate a proof in this context.
+    ///
+    /// If `H` cannot be selected at runtime (see [HashFunction]), no hash function is recorded.
+    /// The recorded hash function is included in the elements of the context (see
+    /// [ToElements::to_elements()]), and thus, is bound to the proof transcript.
+    pub fn with_hash_function<H: Hasher>(mut self) -> Self {
+        self.hash_fn = H::HASH_FUNCTION;
+        self
+    }
+
     // PUBLIC ACCESSORS
     // --------------------------------------------------------------------------------------------
 
@@ -51,6 +67,11 @@ impl Context {
         self.trace_length
     }
 
+    /// Returns application-defined metadata of the execution trace described by this context.
+    pub fn trace_meta(&self) -> &[u8] {
+        &self.trace_meta
+    }
+
     /// Returns execution trace info for the computation described by this context.
     pub fn get_trace_info(&self) -> TraceInfo {
         TraceInfo::new_multi_segment(
@@ -91,44 +112,155 @@ impl Context {
     pub fn options(&self) -> &ProofOptions {
         &self.options
     }
-}
 
-impl<E: StarkField> ToElements<E> for Context {
-    /// Converts this [Context] into a vector of field elements.
+    /// Returns the hash function which was used to generate a proof in this context, or `None`
+    /// if the hash function cannot be selected at runtime.
+    pub fn hash_function(&self) -> Option<HashFunction> {
+        self.hash_fn
+    }
+
+    /// Returns the format version of the proof which this context is a part of (see
+    /// [StarkProof::FORMAT_VERSION]).
     ///
-    /// The elements are laid out as follows:
-    /// - trace layout info [1 or more elements].
-    /// - field modulus bytes [2 field elements].
-    /// - field extension and FRI parameters [1 element].
-    /// - grinding factor [1 element].
-    /// - blowup factor [1 element].
-    /// - number of queries [1 element].
-    /// - trace length [1 element].
-    /// - trace metadata [0 or more elements].
-    fn to_elements(&self) -> Vec<E> {
+    /// For contexts read from proofs serialized with an earlier format version, this is the
+    /// version of that format; otherwise, this is the current format version. The format version
+    /// determines how the context is serialized and converted into field elements, and thus, is
+    /// preserved when the context is serialized again.
+    pub fn format_version(&self) -> u8 {
+        self.format_version
+    }
+
+    // SECURITY LEVEL
+    // --------------------------------------------------------------------------------------------
+    /// Returns security level (in bits) of a proof generated in this context using hash function
+    /// `H`.
+    ///
+    /// When `conjectured` is true, conjectured security level is returned; otherwise, provable
+    /// security level is returned.
+    ///
+    /// The proof is assumed to open trace polynomials at the default out-of-domain points *z* and
+    /// *z * g*; for computations with additional out-of-domain points, use
+    /// [security_level_with_ood_points()](Context::security_level_with_ood_points).
+    pub fn security_level<H: Hasher>(&self, conjectured: bool) -> u32 {
+        self.security_level_with_ood_points::<H>(conjectured, 2)
+    }
+
+    /// Returns security level (in bits) of a proof generated in this context using hash function
+    /// `H`, where trace polynomials are opened at `num_ood_points` out-of-domain points.
+    ///
+    /// When `conjectured` is true, conjectured security level is returned; otherwise, provable
+    /// security level is returned.
+    pub fn security_level_with_ood_points<H: Hasher>(
+        &self,
+        conjectured: bool,
+        num_ood_points: usize,
+    ) -> u32 {
+        self.security_level_for(H::COLLISION_RESISTANCE, conjectured, num_ood_points)
+    }
+
+    /// Returns security level (in bits) of a proof generated in this context using a hash
+    /// function with the specified collision resistance (in bits), where trace polynomials are
+    /// opened at `num_ood_points` out-of-domain points.
+    pub(crate) fn security_level_for(
+        &self,
+        collision_resistance: u32,
+        conjectured: bool,
+        num_ood_points: usize,
+    ) -> u32 {
+        if conjectured {
+            get_conjectured_security(
+                &self.options,
+                self.num_modulus_bits(),
+                self.trace_length,
+                num_ood_points,
+                collision_resistance,
+            )
+        } else {
+            get_proven_security(
+                &self.options,
+                self.num_modulus_bits(),
+                self.trace_length,
+                num_ood_points,
+                collision_resistance,
+            )
+        }
+    }
+
+    // CONVERSIONS
+    // --------------------------------------------------------------------------------------------
+
+    /// Converts this context into a vector of field elements in the same way as
+    /// [ToElements::to_elements()] does, or returns `None` if the field modulus recorded in this
+    /// context cannot be converted into elements of field `E`.
+    ///
+    /// A context deserialized from untrusted bytes may record an arbitrary field modulus, which
+    /// does not necessarily fit into two elements of `E`.
+    pub fn try_to_elements<E: StarkField>(&self) -> Option<Vec<E>> {
         // convert trace layout
         let mut result = self.trace_layout.to_elements();
 
         // convert field modulus bytes into 2 elements
         let num_modulus_bytes = self.field_modulus_bytes.len();
         let (m1, m2) = self.field_modulus_bytes.split_at(num_modulus_bytes / 2);
-        result.push(bytes_to_element(m1));
-        result.push(bytes_to_element(m2));
+        result.push(bytes_to_element(m1)?);
+        result.push(bytes_to_element(m2)?);
 
         // convert proof options and trace length to elements
-        result.append(&mut self.options.to_elements());
+        result.append(&mut self.options.to_elements_for_version(self.format_version));
         result.push(E::from(self.trace_length as u64));
 
+        // the hash function and the length of trace metadata go into a single element; without
+        // the length, metadata which differs only in trailing zero bytes would be ambiguous
+        if self.format_version >= CONTEXT_BINDING_VERSION {
+            let hash_fn = hash_function_to_u8(self.hash_fn) as u32;
+            result.push(E::from(hash_fn << 16 | self.trace_meta.len() as u32));
+        }
+
         // convert trace metadata to elements; this is done by breaking trace metadata into chunks
         // of bytes which are slightly smaller than the number of bytes needed to encode a field
         // element, and then converting these chunks into field elements.
         if !self.trace_meta.is_empty() {
             for chunk in self.trace_meta.chunks(E::ELEMENT_BYTES - 1) {
-                result.push(bytes_to_element(chunk));
+                result.push(bytes_to_element(chunk)?);
             }
         }
 
-        result
+        Some(result)
+    }
+}
+
+impl<E: StarkField> ToElements<E> for Context {
+    /// Converts this [Context] into a vector of field elements.
+    ///
+    /// The elements are laid out as follows:
+    /// - trace layout info [1 or more elements].
+    /// - field modulus bytes [2 field elements].
+    /// - field extension and FRI parameters [1 element].
+    /// - grinding factor [1 element].
+    /// - blowup factor [1 element].
+    /// - number of queries [1 element].
+    /// - FRI folding schedule [0 or more elements].
+    /// - trace length [1 element].
+    /// - hash function and trace metadata length [1 element].
+    /// - trace metadata [0 or more elements].
+    ///
+    /// All parameters of the context are included, and the number of elements of each
+    /// variable-length section is determined by the preceding elements; thus, two contexts which
+    /// differ in any serialized byte are converted into different vectors of elements.
+    ///
+    /// Contexts of proofs with format versions earlier than 4 are converted into elements in the
+    /// same way as they were converted when such proofs were generated, so that the proofs can
+    /// still be verified: the length of FRI folding schedule is not encoded together with the
+    /// grinding factors, and the element with the hash function and trace metadata length is
+    /// omitted.
+    ///
+    /// # Panics
+    /// Panics if the field modulus recorded in this context cannot be converted into elements of
+    /// field `E`; this can happen only for contexts deserialized from untrusted bytes (see
+    /// [Context::try_to_elements()]).
+    fn to_elements(&self) -> Vec<E> {
+        self.try_to_elements()
+            .expect("field modulus cannot be converted into field elements")
     }
 }
 
@@ -137,7 +269,27 @@ impl<E: StarkField> ToElements<E> for Context {
 
 impl Serializable for Context {
     /// Serializes `self` and writes the resulting bytes into the `target`.
+    ///
+    /// The context is serialized in the same way as it is serialized as a part of a proof with
+    /// the [format version](Context::format_version) of this context.
     fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        let version = self.format_version;
+        self.write_with_options(target, |options, target| {
+            options.write_into_version(target, version)
+        });
+    }
+}
+
+impl Context {
+    /// Serializes `self` into the `target` using `write_options` to serialize proof options.
+    ///
+    /// This allows containers of multiple proofs to serialize proof options shared by several
+    /// proofs only once.
+    pub(super) fn write_with_options<W: ByteWriter>(
+        &self,
+        target: &mut W,
+        write_options: impl FnOnce(&ProofOptions, &mut W),
+    ) {
         self.trace_layout.write_into(target);
         target.write_u8(self.trace_length.ilog2() as u8); // store as power of two
         target.write_u16(self.trace_meta.len() as u16);
@@ -145,16 +297,22 @@ impl Serializable for Context {
         assert!(self.field_modulus_bytes.len() < u8::MAX as usize);
         target.write_u8(self.field_modulus_bytes.len() as u8);
         target.write_bytes(&self.field_modulus_bytes);
-        self.options.write_into(target);
+        write_options(&self.options, target);
+
+        // proofs with format version 1 do not record the hash function
+        if self.format_version > 1 {
+            target.write_u8(hash_function_to_u8(self.hash_fn));
+        }
     }
-}
 
-impl Deserializable for Context {
-    /// Reads proof context from the specified `source` and returns the result.
-    ///
-    /// # Errors
-    /// Returns an error of a valid Context struct could not be read from the specified `source`.
-    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+    /// Reads proof context serialized as a part of a proof with the specified format `version`
+    /// from the specified `source` using `read_options` to read proof options; this is the
+    /// inverse of [Context::write_with_options()].
+    pub(super) fn read_with_options<R: ByteReader>(
+        source: &mut R,
+        version: u8,
+        read_options: impl FnOnce(&mut R) -> Result<ProofOptions, DeserializationError>,
+    ) -> Result<Self, DeserializationError> {
         // read and validate trace layout info
         let trace_layout = TraceLayout::read_from(source)?;
 
@@ -167,6 +325,13 @@ impl Deserializable for Context {
                 trace_length
             )));
         }
+        if trace_length as u32 >= usize::BITS {
+            return Err(DeserializationError::InvalidValue(format!(
+                "trace length cannot be greater than or equal to 2^{}, but was 2^{}",
+                usize::BITS,
+                trace_length
+            )));
+        }
         let trace_length = 2_usize.pow(trace_length as u32);
 
         // read trace metadata
@@ -187,7 +352,14 @@ impl Deserializable for Context {
         let field_modulus_bytes = source.read_vec(num_modulus_bytes)?;
 
         // read options
-        let options = ProofOptions::read_from(source)?;
+        let options = read_options(source)?;
+
+        // read hash function; proofs with format version 1 do not record it
+        let hash_fn = if version > 1 {
+            hash_function_from_u8(source.read_u8()?)?
+        } else {
+            None
+        };
 
         Ok(Context {
             trace_layout,
@@ -195,28 +367,86 @@ impl Deserializable for Context {
             trace_meta,
             field_modulus_bytes,
             options,
+            hash_fn,
+            format_version: version,
         })
     }
 }
 
+impl Context {
+    /// Reads proof context serialized as a part of a proof with the specified format `version`
+    /// (see [StarkProof]) from the specified `source`.
+    ///
+    /// Contexts serialized by format version 1 do not record the hash function, and their proof
+    /// options include only the parameters which can be passed to [ProofOptions::new()]. Proof
+    /// options serialized by format versions 1 and 2 do not include the leaf encoding, and proof
+    /// options serialized by format versions 1 to 3 do not include the query sampling strategy;
+    /// for such proofs, the defaults are assumed. The returned context retains the format version
+    /// (see [Context::format_version()]).
+    ///
+    /// # Errors
+    /// Returns an error if a valid Context struct could not be read from the specified `source`.
+    pub fn read_from_version<R: ByteReader>(
+        source: &mut R,
+        version: u8,
+    ) -> Result<Self, DeserializationError> {
+        Self::read_with_options(source, version, |source| {
+            ProofOptions::read_from_version(source, version)
+        })
+    }
+}
+
+impl Deserializable for Context {
+    /// Reads proof context from the specified `source` and returns the result.
+    ///
+    /// # Errors
+    /// Returns an error of a valid Context struct could not be read from the specified `source`.
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        Self::read_from_version(source, StarkProof::FORMAT_VERSION)
+    }
+}
+
 // HELPER FUNCTIONS
 // ================================================================================================
 
 /// Converts a slice of bytes into a field element.
 ///
-/// Assumes that the length of `bytes` is smaller than the number of bytes needed to encode an
-/// element.
-#[allow(clippy::let_and_return)]
-fn bytes_to_element<B: StarkField>(bytes: &[u8]) -> B {
-    debug_assert!(bytes.len() < B::ELEMENT_BYTES);
+/// Returns `None` if the length of `bytes` is not smaller than the number of bytes needed to
+/// encode an element, or if the bytes do not encode a valid element.
+fn bytes_to_element<B: StarkField>(bytes: &[u8]) -> Option<B> {
+    if bytes.len() >= B::ELEMENT_BYTES {
+        return None;
+    }
 
     let mut buf = bytes.to_vec();
     buf.resize(B::ELEMENT_BYTES, 0);
-    let element = match B::try_from(&buf) {
-        Ok(element) => element,
-        Err(_) => panic!("element deserialization failed"),
-    };
-    element
+    B::try_from(&buf).ok()
+}
+
+/// Encodes an optional hash function as a single byte; `0` means that no hash function is
+/// specified.
+fn hash_function_to_u8(hash_fn: Option<HashFunction>) -> u8 {
+    match hash_fn {
+        None => 0,
+        Some(HashFunction::Blake3_192) => 1,
+        Some(HashFunction::Blake3_256) => 2,
+        Some(HashFunction::Sha3_256) => 3,
+        Some(HashFunction::Keccak256) => 4,
+    }
+}
+
+/// Decodes an optional hash function from a single byte.
+fn hash_function_from_u8(value: u8) -> Result<Option<HashFunction>, DeserializationError> {
+    match value {
+        0 => Ok(None),
+        1 => Ok(Some(HashFunction::Blake3_192)),
+        2 => Ok(Some(HashFunction::Blake3_256)),
+        3 => Ok(Some(HashFunction::Sha3_256)),
+        4 => Ok(Some(HashFunction::Keccak256)),
+        _ => Err(DeserializationError::InvalidValue(format!(
+            "value {value} cannot be deserialized as a hash function"
+        ))),
+    }
 }
 
 // TESTS
@@ -226,7 +456,9 @@ fn bytes_to_element<B: StarkField>(bytes: &[u8]) -> B {
 mod tests {
     use super::{Context, ProofOptions, ToElements, TraceInfo};
     use crate::{FieldExtension, TraceLayout};
+    use crypto::{hashers::Blake3_256, hashers::Rp64_256, HashFunction};
     use math::fields::f64::BaseElement;
+    use utils::{Deserializable, Serializable, SliceReader};
 
     #[test]
     fn context_to_elements() {
@@ -257,10 +489,11 @@ mod tests {
             BaseElement::from(1_u32),    // lower bits of field modulus
             BaseElement::from(u32::MAX), // upper bits of field modulus
             BaseElement::from(ext_fri),
-            BaseElement::from(grinding_factor as u32),
+            BaseElement::from(grinding_factor),
             BaseElement::from(blowup_factor as u32),
             BaseElement::from(num_queries as u32),
             BaseElement::from(trace_length as u32),
+            BaseElement::from(0_u32), // hash function and trace metadata length
         ];
 
         let options = ProofOptions::new(
@@ -277,4 +510,138 @@ mod tests {
         let context = Context::new::<BaseElement>(&trace_info, options);
         assert_eq!(expected, context.to_elements());
     }
+
+    #[test]
+    fn context_hash_function() {
+        let options = ProofOptions::new(30, 8, 0, FieldExtension::None, 4, 31);
+        let trace_info = TraceInfo::new(4, 64);
+        let context = Context::new::<BaseElement>(&trace_info, options);
+        assert_eq!(None, context.hash_function());
+
+        // the hash function is serialized, and is bound to elements of the context
+        let with_hash_fn = context.clone().with_hash_function::<Blake3_256<BaseElement>>();
+        assert_eq!(Some(HashFunction::Blake3_256), with_hash_fn.hash_function());
+        assert_ne!(
+            ToElements::<BaseElement>::to_elements(&context),
+            ToElements::<BaseElement>::to_elements(&with_hash_fn)
+        );
+        let bytes = with_hash_fn.to_bytes();
+        assert_eq!(with_hash_fn, Context::read_from_bytes(&bytes).unwrap());
+
+        // hash functions which cannot be selected at runtime are not recorded
+        let with_hash_fn = context.clone().with_hash_function::<Rp64_256>();
+        assert_eq!(context, with_hash_fn);
+    }
+
+    #[test]
+    fn context_to_elements_binds_all_bytes() {
+        let options = ProofOptions::new(30, 8, 0, FieldExtension::None, 4, 31)
+            .with_fri_folding_schedule(&[8]);
+        let trace_info = TraceInfo::with_meta(4, 64, vec![1, 0]);
+        let context = Context::new::<BaseElement>(&trace_info, options)
+            .with_hash_function::<Blake3_256<BaseElement>>();
+        let elements = ToElements::<BaseElement>::to_elements(&context);
+
+        // changing any byte of a serialized context either makes the context invalid, or changes
+        // its elements
+        let bytes = context.to_bytes();
+        for i in 0..bytes.len() {
+            for delta in [1, 2, 128] {
+                let mut bytes = bytes.clone();
+                bytes[i] = bytes[i].wrapping_add(delta);
+                if let Ok(mutated) = Context::read_from_bytes(&bytes) {
+                    let mutated_elements = ToElements::<BaseElement>::to_elements(&mutated);
+                    assert_ne!(elements, mutated_elements, "byte {i} is not bound");
+                }
+            }
+        }
+
+        // trace metadata which differs only in trailing zero bytes is not ambiguous
+        let options = ProofOptions::new(30, 8, 0, FieldExtension::None, 4, 31);
+        let elements = [vec![1], vec![1, 0]].map(|meta| {
+            let trace_info = TraceInfo::with_meta(4, 64, meta);
+            let context = Context::new::<BaseElement>(&trace_info, options.clone());
+            ToElements::<BaseElement>::to_elements(&context)
+        });
+        assert_ne!(elements[0], elements[1]);
+
+        // neither is an FRI folding schedule followed by the trace length
+        let elements =
+            [(vec![], 8, vec![16]), (vec![8], 16, vec![])].map(|(schedule, trace_length, meta)| {
+                let options = options.clone().with_fri_folding_schedule(&schedule);
+                let trace_info = TraceInfo::with_meta(4, trace_length, meta);
+                let context = Context::new::<BaseElement>(&trace_info, options);
+                ToElements::<BaseElement>::to_elements(&context)
+            });
+        assert_ne!(elements[0], elements[1]);
+    }
+
+    #[test]
+    fn context_to_elements_for_earlier_versions() {
+        let options = ProofOptions::new(30, 8, 20, FieldExtension::None, 4, 31)
+            .with_fri_folding_schedule(&[8]);
+        let trace_info = TraceInfo::with_meta(4, 64, vec![1, 2]);
+        let context = Context::new::<BaseElement>(&trace_info, options)
+            .with_hash_function::<Blake3_256<BaseElement>>();
+        let elements = ToElements::<BaseElement>::to_elements(&context);
+
+        // a context read from a proof with format version 3 (which does not include the query
+        // sampling strategy written just before the hash function) retains the format version,
+        // and is serialized again in the same way
+        let mut bytes = context.to_bytes();
+        bytes.remove(bytes.len() - 2);
+        let legacy_context = Context::read_from_version(&mut SliceReader::new(&bytes), 3).unwrap();
+        assert_eq!(3, legacy_context.format_version());
+        assert_eq!(bytes, legacy_context.to_bytes());
+
+        // for such contexts, the length of FRI folding schedule is not encoded together with the
+        // grinding factor, and the hash function and trace metadata length are not bound
+        let grinding_index =
+            ToElements::<BaseElement>::to_elements(context.trace_layout()).len() + 3;
+        let mut expected = elements.clone();
+        expected[grinding_index] = BaseElement::from(20_u32);
+        expected.remove(grinding_index + 5);
+        assert_eq!(expected, ToElements::<BaseElement>::to_elements(&legacy_context));
+    }
+
+    #[test]
+    fn context_with_invalid_field_modulus() {
+        let options = ProofOptions::new(30, 8, 0, FieldExtension::None, 4, 31);
+        let trace_info = TraceInfo::new(4, 64);
+        let context = Context::new::<BaseElement>(&trace_info, options);
+        assert_eq!(Some(context.to_elements()), context.try_to_elements::<BaseElement>());
+
+        // a context read from untrusted bytes may record a modulus which does not fit into two
+        // field elements; such contexts cannot be converted into field elements
+        let modulus = context.field_modulus_bytes().to_vec();
+        let bytes = context.to_bytes();
+        let offset = bytes.windows(modulus.len()).position(|w| w == modulus).unwrap();
+        let mut corrupted = bytes[..offset - 1].to_vec();
+        corrupted.push(32);
+        corrupted.extend_from_slice(&[0xff; 32]);
+        corrupted.extend_from_slice(&bytes[offset + modulus.len()..]);
+
+        let context = Context::read_from_bytes(&corrupted).unwrap();
+        assert_eq!([0xff; 32].as_slice(), context.field_modulus_bytes());
+        assert_eq!(None, context.try_to_elements::<BaseElement>());
+    }
+
+    #[test]
+    fn context_trace_length_bounds() {
+        let options = ProofOptions::new(30, 8, 0, FieldExtension::None, 4, 31);
+        let trace_info = TraceInfo::new(4, 64);
+        let context = Context::new::<BaseElement>(&trace_info, options);
+
+        // trace length is serialized as a power of two right after the trace layout
+        let mut bytes = context.to_bytes();
+        let position = trace_info.layout().to_bytes().len();
+        assert_eq!(6, bytes[position]);
+        assert_eq!(context, Context::read_from_bytes(&bytes).unwrap());
+
+        // trace lengths which do not fit into usize must be rejected
+        bytes[position] = usize::BITS as u8;
+        assert!(Context::read_from_bytes(&bytes).is_err());
+        bytes[position] = u8::MAX;
+        assert!(Context::read_from_bytes(&bytes).is_err());
+    }
 }
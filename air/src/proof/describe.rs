@@ -0,0 +1,198 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::StarkProof;
+use core::fmt::{self, Write};
+use utils::{string::String, Serializable};
+
+// PROOF DESCRIPTION
+// ================================================================================================
+
+impl StarkProof {
+    /// Returns a human-readable description of this proof.
+    ///
+    /// The description lists parameters of the proof context, commitments made by the prover (as
+    /// hex strings), the number of queries, the number of bytes taken up by each section of the
+    /// proof, the sizes of FRI layers, and the estimated security level of the proof. It is
+    /// intended for debugging and for triaging interoperability issues; the format of the
+    /// description is not stable and should not be parsed.
+    ///
+    /// When `display` feature is enabled, the same description is produced by the [fmt::Display]
+    /// implementation for [StarkProof].
+    pub fn describe(&self) -> String {
+        let mut result = String::new();
+        self.write_description(&mut result).expect("failed to describe proof");
+        result
+    }
+
+    /// Writes a human-readable description of this proof into the `target`.
+    fn write_description<W: Write>(&self, target: &mut W) -> fmt::Result {
+        let options = self.options();
+        let fri_options = options.to_fri_options();
+        let layout = self.trace_layout();
+        let lde_domain_size = self.lde_domain_size();
+        let size = self.size_breakdown();
+
+        writeln!(target, "STARK proof")?;
+        write_field(target, "format version", self.context.format_version())?;
+        write_field(target, "total size", format_args!("{} bytes", size.total()))?;
+
+        writeln!(target, "context")?;
+        write_field(target, "trace length", self.trace_length())?;
+        write_field(target, "main trace width", layout.main_trace_width())?;
+        for i in 0..layout.num_aux_segments() {
+            write_field(
+                target,
+                format_args!("aux segment {i} width"),
+                format_args!(
+                    "{} ({} random elements)",
+                    layout.get_aux_segment_width(i),
+                    layout.get_aux_segment_rand_elements(i)
+                ),
+            )?;
+        }
+        write_field(
+            target,
+            "trace meta",
+            format_args!("{} bytes", self.get_trace_info().meta().len()),
+        )?;
+        write_field(target, "lde domain size", lde_domain_size)?;
+        write_field(target, "field modulus", format_args!("{} bits", self.field_modulus_bits()))?;
+        let extension = self.field_extension();
+        write_field(
+            target,
+            "field extension",
+            format_args!("{:?} (degree {})", extension, extension.degree()),
+        )?;
+        match self.hash_function() {
+            Some(hash_fn) => write_field(target, "hash function", hash_fn)?,
+            None => write_field(target, "hash function", "not recorded")?,
+        }
+
+        writeln!(target, "options")?;
+        write_field(target, "blowup factor", options.blowup_factor())?;
+        write_field(target, "grinding factor", options.grinding_factor())?;
+        write_field(target, "low-degree test", format_args!("{:?}", options.low_degree_test()))?;
+        write_field(target, "leaf encoding", format_args!("{:?}", options.leaf_encoding()))?;
+        write_field(target, "fri folding factor", fri_options.folding_factor())?;
+        if !fri_options.folding_schedule().is_empty() {
+            write_field(
+                target,
+                "fri folding schedule",
+                format_args!("{:?}", fri_options.folding_schedule()),
+            )?;
+        }
+        write_field(target, "fri remainder max degree", fri_options.remainder_max_degree())?;
+        write_field(target, "fri layer grinding factor", options.fri_layer_grinding_factor())?;
+        write_field(target, "fri query deduplication", options.fri_query_deduplication())?;
+        write_field(target, "normalized queries", options.normalized_queries())?;
+
+        writeln!(target, "queries")?;
+        write_field(target, "num queries", self.num_queries())?;
+        write_field(target, "num unique queries", self.num_unique_queries)?;
+        write_field(target, "pow nonce", self.pow_nonce)?;
+
+        // all commitments are digests of the same hash function, and thus, the size of a digest
+        // can be inferred from the total size of the commitments
+        let commitment_bytes = self.commitments.to_bytes();
+        let commitment_bytes = &commitment_bytes[2..];
+        let num_fri_layers = fri_options.num_fri_layers(lde_domain_size);
+        let num_commitments = layout.num_segments() + 1 + num_fri_layers + 1;
+        if commitment_bytes.len() % num_commitments == 0 && !commitment_bytes.is_empty() {
+            let digest_size = commitment_bytes.len() / num_commitments;
+            writeln!(target, "commitments ({digest_size} bytes each)")?;
+            let mut digests = commitment_bytes.chunks(digest_size);
+            for i in 0..layout.num_segments() {
+                let digest = to_hex(digests.next().expect("not enough digests"));
+                write_field(target, format_args!("trace segment {i}"), digest)?;
+            }
+            write_field(
+                target,
+                "constraints",
+                to_hex(digests.next().expect("not enough digests")),
+            )?;
+            for i in 0..num_fri_layers {
+                let digest = to_hex(digests.next().expect("not enough digests"));
+                write_field(target, format_args!("fri layer {i}"), digest)?;
+            }
+            write_field(
+                target,
+                "fri remainder",
+                to_hex(digests.next().expect("not enough digests")),
+            )?;
+        } else {
+            writeln!(target, "commitments (malformed, {num_commitments} digests expected)")?;
+            if !commitment_bytes.is_empty() {
+                write_field(target, "bytes", to_hex(commitment_bytes))?;
+            }
+        }
+
+        writeln!(target, "section sizes (bytes)")?;
+        write_field(target, "context", size.context)?;
+        write_field(target, "commitments", size.commitments)?;
+        write_field(target, "trace queries", size.trace_queries)?;
+        write_field(target, "constraint queries", size.constraint_queries)?;
+        write_field(target, "ood frame", size.ood_frame)?;
+        write_field(target, "fri proof", size.fri_proof)?;
+        write_field(target, "other", size.other)?;
+
+        writeln!(target, "fri proof")?;
+        let layer_domain_sizes = fri_options.layer_domain_sizes(lde_domain_size);
+        for (i, layer_size) in self.fri_proof.layer_sizes().into_iter().enumerate() {
+            let value = match layer_domain_sizes.get(i) {
+                Some(domain_size) => format!("{layer_size} bytes (domain size {domain_size})"),
+                None => format!("{layer_size} bytes"),
+            };
+            write_field(target, format_args!("layer {i}"), value)?;
+        }
+        let element_bytes = self.context.field_modulus_bytes().len() * extension.degree() as usize;
+        write_field(
+            target,
+            "remainder",
+            format_args!(
+                "{} bytes ({} elements)",
+                self.fri_proof.remainder_size(),
+                self.fri_proof.remainder_size() / element_bytes
+            ),
+        )?;
+        write_field(target, "num partitions", self.fri_proof.num_partitions())?;
+
+        writeln!(target, "security")?;
+        match (self.security_level_auto(true), self.security_level_auto(false)) {
+            (Some(conjectured), Some(proven)) => {
+                write_field(target, "conjectured", format_args!("{conjectured} bits"))?;
+                write_field(target, "proven", format_args!("{proven} bits"))
+            }
+            _ => write_field(target, "estimate", "unknown (hash function not recorded)"),
+        }
+    }
+}
+
+#[cfg(feature = "display")]
+impl fmt::Display for StarkProof {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_description(f)
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Writes a single indented `name: value` line of a proof description into the `target`.
+fn write_field<W: Write>(
+    target: &mut W,
+    name: impl fmt::Display,
+    value: impl fmt::Display,
+) -> fmt::Result {
+    let name: String = format!("{name}:");
+    writeln!(target, "  {name:<28}{value}")
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().fold(String::with_capacity(bytes.len() * 2), |mut result, byte| {
+        write!(result, "{byte:02x}").expect("failed to write hex digit");
+        result
+    })
+}
@@ -5,18 +5,25 @@
 
 //! Contains STARK proof struct and associated components.
 
-use crate::{ProofOptions, TraceInfo, TraceLayout};
+use crate::{FieldExtension, ProofOptions, TraceInfo, TraceLayout};
 use core::cmp;
-use crypto::Hasher;
+use crypto::{HashFunction, Hasher};
 use fri::FriProof;
-use math::FieldElement;
+use math::{FieldElement, StarkField};
 use utils::{
-    collections::Vec, ByteReader, Deserializable, DeserializationError, Serializable, SliceReader,
+    collections::Vec, ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable,
+    SliceReader,
 };
 
 mod context;
 pub use context::Context;
 
+mod aggregated;
+pub use aggregated::AggregatedProof;
+
+mod chunks;
+pub use chunks::ProofChunk;
+
 mod commitments;
 pub use commitments::Commitments;
 
@@ -29,6 +36,16 @@ pub use ood_frame::OodFrame;
 mod table;
 pub use table::Table;
 
+mod size;
+pub use size::ProofSize;
+
+mod describe;
+
+#[cfg(feature = "compression")]
+mod compression;
+#[cfg(feature = "compression")]
+pub use compression::CompressionCodec;
+
 #[cfg(test)]
 mod tests;
 
@@ -38,6 +55,20 @@ mod tests;
 const GRINDING_CONTRIBUTION_FLOOR: u32 = 80;
 const MAX_PROXIMITY_PARAMETER: u64 = 1000;
 
+/// Byte which precedes the format version in serialized proofs; since the width of the main trace
+/// segment (the first byte of proofs serialized with format version 1) cannot be zero, this byte
+/// distinguishes versioned proofs from proofs serialized with format version 1.
+pub(crate) const FORMAT_VERSION_MARKER: u8 = 0;
+
+/// Byte which follows the format version marker in compressed proofs instead of the format
+/// version.
+const COMPRESSED_PROOF_MARKER: u8 = u8::MAX;
+
+/// Earliest proof format version in which all parameters of the proof context are bound to the
+/// proof transcript; contexts of proofs with earlier format versions are converted into field
+/// elements in the same way as they were converted when such proofs were generated.
+pub(crate) const CONTEXT_BINDING_VERSION: u8 = 4;
+
 // STARK PROOF
 // ================================================================================================
 /// A proof generated by Winterfell prover.
@@ -50,8 +81,47 @@ const MAX_PROXIMITY_PARAMETER: u64 = 1000;
 /// function, and deserialized from a sequence of bytes using [from_bytes()](StarkProof::from_bytes)
 /// function.
 ///
+/// Serialized proofs start with the version of the serialization format, and proofs serialized
+/// with any format version between [MIN_FORMAT_VERSION](StarkProof::MIN_FORMAT_VERSION) and
+/// [FORMAT_VERSION](StarkProof::FORMAT_VERSION) can be deserialized. Thus, proofs archived by
+/// long-lived systems remain readable after the format changes. Support for a format version is
+/// dropped only in a breaking release, and at least the version preceding the current one is
+/// always supported. The following format versions are defined:
+/// * Version 1 - the format used by winterfell v0.7, before format versions were introduced;
+///   proofs are not prefixed with a version header, and start directly with the proof context.
+///   The context does not record the hash function, proof options include only the parameters
+///   which can be passed to [ProofOptions::new()], and FRI proofs are serialized in the legacy
+///   format (see [FriProof::write_legacy_into()]).
+/// * Version 2 - proofs are prefixed with a zero byte followed by the format version. The proof
+///   context records the hash function, proof options include the settings for normalized
+///   queries, FRI folding schedules, FRI layer grinding, FRI query deduplication, and the
+///   low-degree test, and FRI proofs include a deduplication flag, out-of-domain evaluations, and
+///   layer nonces. Proofs with format version 1 are read as if they used the defaults for all of
+///   these and recorded no hash function.
+/// * Version 3 - proof options in the proof context include the encoding of trace rows into
+///   Merkle tree leaves (see [LeafEncoding](crate::LeafEncoding)). Proofs with earlier format
+///   versions are read as if they used the default leaf encoding.
+/// * Version 4 - proof options in the proof context include the strategy for sampling query
+///   positions (see [QuerySampling](crate::QuerySampling)). Proofs with earlier format versions
+///   are read as if they used uniform query sampling. In addition, the hash function, the length
+///   of trace metadata, and the length of FRI folding schedule are bound to the proof
+///   transcript; proofs with earlier format versions are verified against the transcript of
+///   their format version.
+/// * Version 5 - the current format; trace queries are followed by optional queries of
+///   preprocessed columns (see
+///   [AirContext::num_preprocessed_columns()](crate::AirContext::num_preprocessed_columns)).
+///   Proofs with earlier format versions are read as if they contained no such queries.
+///
+/// Deserialized proofs retain their format version (see
+/// [Context::format_version()](Context::format_version)), and are serialized again with the same
+/// format version; thus, the transcript of a proof does not change when the proof is
+/// re-serialized.
+///
 /// To estimate soundness of a proof (in bits), [security_level()](StarkProof::security_level)
-/// function can be used.
+/// function can be used; other parameters of a proof are exposed via accessors such as
+/// [trace_width()](StarkProof::trace_width) and [size_breakdown()](StarkProof::size_breakdown).
+/// A human-readable summary of all of the above can be obtained via
+/// [describe()](StarkProof::describe).
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct StarkProof {
     /// Basic metadata about the execution of the computation described by this proof.
@@ -64,6 +134,11 @@ pub struct StarkProof {
     /// Decommitments of extended execution trace values (for all trace segments) at position
     ///  queried by the verifier.
     pub trace_queries: Vec<Queries>,
+    /// Decommitments of extended preprocessed column values at positions queried by the verifier,
+    /// or `None` if the computation has no preprocessed columns. These are authenticated against
+    /// a commitment known to the verifier in advance, and thus, the commitment itself is not
+    /// included in the proof.
+    pub preprocessed_queries: Option<Queries>,
     /// Decommitments of constraint composition polynomial evaluations at positions queried by
     /// the verifier.
     pub constraint_queries: Queries,
@@ -76,6 +151,15 @@ pub struct StarkProof {
 }
 
 impl StarkProof {
+    /// Version of the serialization format used by [to_bytes()](StarkProof::to_bytes).
+    pub const FORMAT_VERSION: u8 = 5;
+
+    /// Oldest version of the serialization format which can be deserialized.
+    pub const MIN_FORMAT_VERSION: u8 = 1;
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
     /// Returns STARK protocol parameters used to generate this proof.
     pub fn options(&self) -> &ProofOptions {
         self.context.options()
@@ -92,6 +176,14 @@ impl StarkProof {
         self.context.trace_length()
     }
 
+    /// Returns application-defined metadata of the execution trace described by this proof.
+    ///
+    /// The metadata is bound to the proof transcript, and thus, cannot be modified without
+    /// invalidating the proof.
+    pub fn trace_meta(&self) -> &[u8] {
+        self.context.trace_meta()
+    }
+
     /// Returns trace info for the computation described by this proof.
     pub fn get_trace_info(&self) -> TraceInfo {
         self.context.get_trace_info()
@@ -102,6 +194,94 @@ impl StarkProof {
         self.context.lde_domain_size()
     }
 
+    /// Returns the total width of the execution trace (across all segments) for the computation
+    /// described by this proof.
+    pub fn trace_width(&self) -> usize {
+        let layout = self.trace_layout();
+        layout.main_trace_width() + layout.aux_trace_width()
+    }
+
+    /// Returns the number of bits in the modulus of the base field in which this proof was
+    /// generated.
+    pub fn field_modulus_bits(&self) -> u32 {
+        self.context.num_modulus_bits()
+    }
+
+    /// Returns the field extension used to generate this proof.
+    pub fn field_extension(&self) -> FieldExtension {
+        self.options().field_extension()
+    }
+
+    /// Returns the hash function used to generate this proof, or `None` if the hash function
+    /// is not recorded in the proof context (see [Context::hash_function()]).
+    pub fn hash_function(&self) -> Option<HashFunction> {
+        self.context.hash_function()
+    }
+
+    /// Returns the number of queries made by the verifier, including duplicate queries.
+    pub fn num_queries(&self) -> usize {
+        self.options().num_queries()
+    }
+
+    /// Returns the number of bytes taken up by each section of this proof when serialized.
+    pub fn size_breakdown(&self) -> ProofSize {
+        ProofSize::new(self)
+    }
+
+    // PROOF CONTENTS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns commitments made by the prover during the commit phase of the protocol, parsed
+    /// using hash function `H`.
+    ///
+    /// The commitments are returned as a tuple containing commitments to all trace segments, the
+    /// commitment to the constraint composition polynomial evaluations, and commitments to all
+    /// FRI layers (the last of which is the commitment to the FRI remainder).
+    ///
+    /// # Errors
+    /// Returns an error if the commitments could not be parsed using hash function `H`.
+    #[allow(clippy::type_complexity)]
+    pub fn parse_commitments<H: Hasher>(
+        &self,
+    ) -> Result<(Vec<H::Digest>, H::Digest, Vec<H::Digest>), DeserializationError> {
+        let num_fri_layers = self.options().to_fri_options().num_fri_layers(self.lde_domain_size());
+        self.commitments
+            .clone()
+            .parse::<H>(self.trace_layout().num_segments(), num_fri_layers)
+    }
+
+    /// Returns evaluations of trace polynomials (across all trace segments) at the out-of-domain
+    /// points of this proof.
+    ///
+    /// The evaluations are returned as one row per out-of-domain point: by default, the first row
+    /// contains evaluations at `z` and the second row contains evaluations at `z * g`, where `g`
+    /// is the generator of the trace domain.
+    ///
+    /// # Errors
+    /// Returns an error if `E` is not the field in which this proof was generated, or if the
+    /// out-of-domain frame could not be parsed.
+    pub fn ood_trace_rows<E: FieldElement>(&self) -> Result<Vec<Vec<E>>, DeserializationError> {
+        let (trace, _) = self.parse_ood_frame::<E>()?;
+        let width = self.trace_width();
+        let num_rows = trace.len() / width;
+        Ok((0..num_rows)
+            .map(|row| trace.iter().skip(row).step_by(num_rows).copied().collect())
+            .collect())
+    }
+
+    /// Returns evaluations of constraint composition column polynomials at the out-of-domain
+    /// point `z`.
+    ///
+    /// # Errors
+    /// Returns an error if `E` is not the field in which this proof was generated, or if the
+    /// out-of-domain frame could not be parsed.
+    pub fn ood_constraint_evaluations<E: FieldElement>(
+        &self,
+    ) -> Result<Vec<E>, DeserializationError> {
+        let (_, evaluations) = self.parse_ood_frame::<E>()?;
+        Ok(evaluations)
+    }
+
     // SECURITY LEVEL
     // --------------------------------------------------------------------------------------------
     /// Returns security level of this proof (in bits).
@@ -109,23 +289,36 @@ impl StarkProof {
     /// When `conjectured` is true, conjectured security level is returned; otherwise, provable
     /// security level is returned. Usually, the number of queries needed for provable security is
     /// 2x - 3x higher than the number of queries needed for conjectured security at the same
-    /// security level.
+    /// security level. Security level of proofs generated with the experimental STIR low-degree
+    /// test is always 0. Security level accounts for the number of out-of-domain points at which
+    /// trace polynomials are opened in this proof.
     pub fn security_level<H: Hasher>(&self, conjectured: bool) -> u32 {
-        if conjectured {
-            get_conjectured_security(
-                self.context.options(),
-                self.context.num_modulus_bits(),
-                self.trace_length(),
-                H::COLLISION_RESISTANCE,
-            )
-        } else {
-            get_proven_security(
-                self.context.options(),
-                self.context.num_modulus_bits(),
-                self.trace_length(),
-                H::COLLISION_RESISTANCE,
-            )
-        }
+        self.context
+            .security_level_for(H::COLLISION_RESISTANCE, conjectured, self.num_ood_points())
+    }
+
+    /// Returns security level of this proof (in bits) for the hash function recorded in the proof
+    /// context, or `None` if the hash function is not recorded.
+    ///
+    /// Hash functions which cannot be selected at runtime (see [HashFunction]) are not recorded
+    /// in the proof context; security level of proofs generated with such hash functions can be
+    /// computed via [security_level()](StarkProof::security_level).
+    pub fn security_level_auto(&self, conjectured: bool) -> Option<u32> {
+        let hash_fn = self.hash_function()?;
+        Some(self.context.security_level_for(
+            hash_fn.collision_resistance(),
+            conjectured,
+            self.num_ood_points(),
+        ))
+    }
+
+    /// Returns the number of out-of-domain points at which trace polynomials are opened in this
+    /// proof.
+    ///
+    /// The verifier checks that this number is the same as the number of out-of-domain points
+    /// specified by the AIR (see [AirContext::ood_offsets()](crate::AirContext::ood_offsets)).
+    pub fn num_ood_points(&self) -> usize {
+        self.ood_frame.num_trace_rows()
     }
 
     // SERIALIZATION / DESERIALIZATION
@@ -138,12 +331,76 @@ impl StarkProof {
 
     /// Returns a STARK proof read from the specified `source`.
     ///
+    /// The proof may be serialized with any supported format version. When `compression` feature
+    /// is enabled, proofs compressed via
+    /// [to_compressed_bytes()](StarkProof::to_compressed_bytes) are decompressed transparently.
+    ///
     /// # Errors
     /// Returns an error of a valid STARK proof could not be read from the specified `source`.
     pub fn from_bytes(source: &[u8]) -> Result<Self, DeserializationError> {
         Deserializable::read_from_bytes(source)
     }
 
+    /// Reads the serialization format version of a proof from the specified `source`.
+    ///
+    /// For proofs serialized with format version 1, nothing is consumed from the `source`;
+    /// otherwise, the version header is consumed, and the `source` is positioned at the start of
+    /// the proof context.
+    ///
+    /// # Errors
+    /// Returns an error if the format version is not supported, or if the proof is compressed.
+    pub fn read_format_version<R: ByteReader>(source: &mut R) -> Result<u8, DeserializationError> {
+        Self::read_version_header(source)?.ok_or_else(|| {
+            DeserializationError::InvalidValue(
+                "proof is compressed and must be read via StarkProof::from_bytes()".into(),
+            )
+        })
+    }
+
+    /// Serializes this proof into a vector of bytes in which the parts of the proof are ordered
+    /// for incremental verification.
+    ///
+    /// See [write_streaming_into()](StarkProof::write_streaming_into) for details.
+    pub fn to_streaming_bytes(&self) -> Vec<u8> {
+        let mut result = Vec::with_capacity(self.size_breakdown().total());
+        self.write_streaming_into(&mut result);
+        result
+    }
+
+    /// Serializes this proof into the `target` with the parts of the proof ordered for
+    /// incremental verification.
+    ///
+    /// The format version and the proof context are written in the same way as via
+    /// [to_bytes()](StarkProof::to_bytes), but the remaining parts are written in the order in
+    /// which the verifier consumes them: commitments, the out-of-domain frame, the FRI proof
+    /// without its layers, and the proof-of-work nonce are followed by trace queries, constraint
+    /// queries, and finally FRI layers. Since query positions can be derived before any queries
+    /// are read, every section of queries can be checked against the commitments and discarded as
+    /// soon as it is read. Proofs serialized this way can be verified via `verify_from_reader()`
+    /// of the verifier crate, but cannot be read via [from_bytes()](StarkProof::from_bytes).
+    pub fn write_streaming_into<W: ByteWriter>(&self, target: &mut W) {
+        let version = self.context.format_version();
+        Self::write_version_header_into(target, version);
+        self.context.write_into(target);
+
+        target.write_u8(self.num_unique_queries);
+        self.commitments.write_into(target);
+        self.ood_frame.write_into(target);
+        if version == 1 {
+            self.fri_proof.write_legacy_header_into(target);
+        } else {
+            self.fri_proof.write_header_into(target);
+        }
+        target.write_u64(self.pow_nonce);
+
+        self.trace_queries.write_into(target);
+        self.write_preprocessed_queries_into(target, version);
+        self.constraint_queries.write_into(target);
+        for i in 0..self.fri_proof.num_layers() {
+            self.fri_proof.write_layer_into(i, target);
+        }
+    }
+
     /// Creates a dummy `StarkProof` for use in tests.
     pub fn new_dummy() -> Self {
         use crate::FieldExtension;
@@ -159,6 +416,7 @@ impl StarkProof {
             num_unique_queries: 0,
             commitments: Commitments::default(),
             trace_queries: Vec::new(),
+            preprocessed_queries: None,
             constraint_queries: Queries::new::<_, DummyField>(
                 BatchMerkleProof::<DummyHasher<DummyField>> {
                     leaves: Vec::new(),
@@ -174,25 +432,149 @@ impl StarkProof {
     }
 }
 
+// HELPER METHODS
+// ================================================================================================
+
+impl StarkProof {
+    /// Parses the out-of-domain frame of this proof in field `E`, and returns interleaved trace
+    /// evaluations together with constraint composition column evaluations.
+    fn parse_ood_frame<E: FieldElement>(&self) -> Result<(Vec<E>, Vec<E>), DeserializationError> {
+        if E::EXTENSION_DEGREE != self.field_extension().degree() as usize
+            || E::BaseField::get_modulus_le_bytes() != self.context.field_modulus_bytes()
+        {
+            return Err(DeserializationError::InvalidValue(
+                "proof was not generated in the specified field".into(),
+            ));
+        }
+
+        let layout = self.trace_layout();
+        let num_evaluations = self.ood_frame.num_constraint_evaluations::<E>();
+        if num_evaluations == 0 {
+            return Err(DeserializationError::InvalidValue(
+                "out-of-domain frame does not contain constraint evaluations".into(),
+            ));
+        }
+        self.ood_frame.clone().parse::<E>(
+            layout.main_trace_width(),
+            layout.aux_trace_width(),
+            num_evaluations,
+        )
+    }
+
+    /// Writes the header of a proof with the specified format `version` into the `target`; proofs
+    /// with format version 1 have no header.
+    fn write_version_header_into<W: ByteWriter>(target: &mut W, version: u8) {
+        if version > 1 {
+            target.write_u8(FORMAT_VERSION_MARKER);
+            target.write_u8(version);
+        }
+    }
+
+    /// Reads the header of a serialized proof from the specified `source`, and returns the format
+    /// version of the proof, or `None` if the proof is compressed.
+    ///
+    /// For proofs serialized with format version 1, nothing is consumed from the `source`;
+    /// otherwise, the whole header (but not the compression header) is consumed.
+    fn read_version_header<R: ByteReader>(
+        source: &mut R,
+    ) -> Result<Option<u8>, DeserializationError> {
+        if source.peek_u8()? != FORMAT_VERSION_MARKER {
+            return Ok(Some(1));
+        }
+        source.read_u8()?;
+        let version = source.read_u8()?;
+        if version == COMPRESSED_PROOF_MARKER {
+            return Ok(None);
+        }
+        if version <= 1 || version > Self::FORMAT_VERSION {
+            return Err(DeserializationError::InvalidValue(format!(
+                "proof format version {version} is not supported; supported versions are {} to {}",
+                Self::MIN_FORMAT_VERSION,
+                Self::FORMAT_VERSION
+            )));
+        }
+        Ok(Some(version))
+    }
+}
+
 // SERIALIZATION
 // ================================================================================================
 
 impl Serializable for StarkProof {
-    fn write_into<W: utils::ByteWriter>(&self, target: &mut W) {
+    /// Serializes `self` with the format version of its context (see
+    /// [Context::format_version()]) and writes the resulting bytes into the `target`.
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        let version = self.context.format_version();
+        Self::write_version_header_into(target, version);
         self.context.write_into(target);
+        self.write_body_into(target, version);
+    }
+}
+
+impl Deserializable for StarkProof {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        let version = match Self::read_version_header(source)? {
+            Some(version) => version,
+            #[cfg(feature = "compression")]
+            None => return compression::read_compressed_proof(source),
+            #[cfg(not(feature = "compression"))]
+            None => {
+                return Err(DeserializationError::InvalidValue(
+                    "reading compressed proofs requires the `compression` feature".into(),
+                ))
+            }
+        };
+
+        let context = Context::read_from_version(source, version)?;
+        let proof = Self::read_body_from(source, context, version)?;
+        if source.has_more_bytes() {
+            return Err(DeserializationError::UnconsumedBytes);
+        }
+        Ok(proof)
+    }
+}
+
+impl StarkProof {
+    /// Serializes all parts of this proof which follow the proof context into the `target` in
+    /// the same way as they are serialized for the specified format `version`.
+    pub(super) fn write_body_into<W: ByteWriter>(&self, target: &mut W, version: u8) {
         target.write_u8(self.num_unique_queries);
         self.commitments.write_into(target);
         self.trace_queries.write_into(target);
+        self.write_preprocessed_queries_into(target, version);
         self.constraint_queries.write_into(target);
         self.ood_frame.write_into(target);
-        self.fri_proof.write_into(target);
+        if version == 1 {
+            self.fri_proof.write_legacy_into(target);
+        } else {
+            self.fri_proof.write_into(target);
+        }
         self.pow_nonce.write_into(target)
     }
-}
 
-impl Deserializable for StarkProof {
-    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
-        let context = Context::read_from(source)?;
+    /// Serializes queries of preprocessed columns into the `target`; proofs with format versions
+    /// earlier than 5 do not contain such queries.
+    fn write_preprocessed_queries_into<W: ByteWriter>(&self, target: &mut W, version: u8) {
+        if version < 5 {
+            debug_assert!(self.preprocessed_queries.is_none());
+            return;
+        }
+        match &self.preprocessed_queries {
+            Some(queries) => {
+                target.write_u8(1);
+                queries.write_into(target);
+            }
+            None => target.write_u8(0),
+        }
+    }
+
+    /// Reads all parts of a proof serialized with the specified format version which follow the
+    /// specified proof context from the `source`.
+    pub(super) fn read_body_from<R: ByteReader>(
+        source: &mut R,
+        context: Context,
+        version: u8,
+    ) -> Result<Self, DeserializationError> {
         let num_unique_queries = source.read_u8()?;
         let commitments = Commitments::read_from(source)?;
         let num_trace_segments = context.trace_layout().num_segments();
@@ -200,21 +582,39 @@ impl Deserializable for StarkProof {
         for _ in 0..num_trace_segments {
             trace_queries.push(Queries::read_from(source)?);
         }
+        let preprocessed_queries = if version >= 5 {
+            match source.read_u8()? {
+                0 => None,
+                1 => Some(Queries::read_from(source)?),
+                value => {
+                    return Err(DeserializationError::InvalidValue(format!(
+                        "value {value} cannot be deserialized as a preprocessed queries flag"
+                    )))
+                }
+            }
+        } else {
+            None
+        };
 
-        let proof = StarkProof {
+        let constraint_queries = Queries::read_from(source)?;
+        let ood_frame = OodFrame::read_from(source)?;
+        let fri_proof = if version == 1 {
+            FriProof::read_legacy_from(source)?
+        } else {
+            FriProof::read_from(source)?
+        };
+
+        Ok(StarkProof {
             context,
             num_unique_queries,
             commitments,
             trace_queries,
-            constraint_queries: Queries::read_from(source)?,
-            ood_frame: OodFrame::read_from(source)?,
-            fri_proof: FriProof::read_from(source)?,
+            preprocessed_queries,
+            constraint_queries,
+            ood_frame,
+            fri_proof,
             pow_nonce: source.read_u64()?,
-        };
-        if source.has_more_bytes() {
-            return Err(DeserializationError::UnconsumedBytes);
-        }
-        Ok(proof)
+        })
     }
 }
 
@@ -222,15 +622,27 @@ impl Deserializable for StarkProof {
 // ================================================================================================
 
 /// Computes conjectured security level for the specified proof parameters.
+///
+/// `num_ood_points` is the number of out-of-domain points at which trace polynomials are opened;
+/// every point beyond *z* and *z * g* increases the probability that the DEEP composition
+/// polynomial is of low degree for an invalid trace, and thus, reduces field security.
 fn get_conjectured_security(
     options: &ProofOptions,
     base_field_bits: u32,
     trace_domain_size: usize,
+    num_ood_points: usize,
     collision_resistance: u32,
 ) -> u32 {
+    // soundness of STIR has not been analyzed yet; thus, no security is claimed for STIR proofs
+    if options.low_degree_test() == crate::LowDegreeTest::Stir {
+        return 0;
+    }
+
     // compute max security we can get for a given field size
     let field_size = base_field_bits * options.field_extension().degree();
-    let field_security = field_size - (trace_domain_size * options.blowup_factor()).ilog2();
+    let num_shifted_points = cmp::max(num_ood_points, 2) - 1;
+    let field_security =
+        field_size - (trace_domain_size * options.blowup_factor() * num_shifted_points).ilog2();
 
     // compute security we get by executing multiple query rounds
     let security_per_query = options.blowup_factor().ilog2();
@@ -245,12 +657,20 @@ fn get_conjectured_security(
 }
 
 /// Estimates proven security level for the specified proof parameters.
+///
+/// `num_ood_points` is the number of out-of-domain points at which trace polynomials are opened.
 fn get_proven_security(
     options: &ProofOptions,
     base_field_bits: u32,
     trace_domain_size: usize,
+    num_ood_points: usize,
     collision_resistance: u32,
 ) -> u32 {
+    // soundness of STIR has not been analyzed yet; thus, no security is claimed for STIR proofs
+    if options.low_degree_test() == crate::LowDegreeTest::Stir {
+        return 0;
+    }
+
     let m_min: usize = 3;
     let m_max = compute_upper_m(trace_domain_size);
 
@@ -260,6 +680,7 @@ fn get_proven_security(
                 options,
                 base_field_bits,
                 trace_domain_size,
+                num_ood_points,
                 a as usize,
             )
         })
@@ -272,6 +693,7 @@ fn get_proven_security(
             options,
             base_field_bits,
             trace_domain_size,
+            num_ood_points,
             m_optimal as usize,
         ),
         collision_resistance as u64,
@@ -284,6 +706,7 @@ fn proven_security_protocol_for_m(
     options: &ProofOptions,
     base_field_bits: u32,
     trace_domain_size: usize,
+    num_ood_points: usize,
     m: usize,
 ) -> u64 {
     let extension_field_bits = (base_field_bits * options.field_extension().degree()) as f64;
@@ -296,12 +719,16 @@ fn proven_security_protocol_for_m(
 
     let lde_domain_size = (trace_domain_size * options.blowup_factor()) as f64;
     let trace_domain_size = trace_domain_size as f64;
+    let num_ood_points = cmp::max(num_ood_points, 2) as f64;
 
     // Computes FRI commit-phase (i.e., pre-query) soundness error.
     // This considers only the first term given in eq. 7 in https://eprint.iacr.org/2022/1216.pdf,
     // i.e. 0.5 * (m + 0.5)^7 * n^2 / (rho^1.5.q) as all other terms are negligible in comparison.
+    // Proof-of-work performed before drawing each folding challenge increases the cost of every
+    // attempt to find a bad challenge, and thus, contributes to commit-phase soundness.
     let fri_commit_err_bits = extension_field_bits
-        - log2((0.5 * powf(m + 0.5, 7.0) / powf(rho, 1.5)) * powf(lde_domain_size, 2.0));
+        - log2((0.5 * powf(m + 0.5, 7.0) / powf(rho, 1.5)) * powf(lde_domain_size, 2.0))
+        + options.fri_layer_grinding_factor() as f64;
 
     // Compute FRI query-phase soundness error
     let fri_queries_err_bits =
@@ -317,7 +744,8 @@ fn proven_security_protocol_for_m(
     // To apply Theorem 8 in https://eprint.iacr.org/2022/1216.pdf, we need to apply FRI with
     // a slightly larger agreement parameter alpha.
     // More concretely, we need alpha > rho_plus.sqrt() where rho_plus is the rate in function field
-    // F(Z) and defined as (trace_domain_size + 2.0) / lde_domain_size .
+    // F(Z) and defined as (trace_domain_size + k) / lde_domain_size, where k is the number of
+    // out-of-domain points (k = 2 when only z and z * g are used).
     // This means that the range of m needs to be restricted in order to ensure that
     // alpha := 1 - theta := rho.sqrt() * (1 + 1/2m) is greater than rho_plus.sqrt().
     // Determining the range of m is the responsibility of the calling function.
@@ -326,7 +754,7 @@ fn proven_security_protocol_for_m(
     // the list-decoding list size in F(Z).
 
     // Modified rate in function field F(Z)
-    let rho_plus = (trace_domain_size + 2.0) / lde_domain_size;
+    let rho_plus = (trace_domain_size + num_ood_points) / lde_domain_size;
     // New proximity parameter m_plus, corresponding to rho_plus, needed to make sure that
     //  alpha < rho_plus.sqrt() * (1 + 1 / (2 * m_plus))
     let m_plus = ceil(1.0 / (2.0 * (alpha / sqrt(rho_plus) - 1.0)));
@@ -340,10 +768,13 @@ fn proven_security_protocol_for_m(
 
     // DEEP related soundness error. Note that this uses that the denominator |F| - |D ∪ H|
     // can be approximated by |F| for all practical domain sizes. We also use the blow-up factor
-    // as an upper bound for the maximal constraint degree.
-    let deep_err_bits =
-        -log2(l_plus * (max_deg * (trace_domain_size + 1.0) + (trace_domain_size - 1.0)))
-            + extension_field_bits;
+    // as an upper bound for the maximal constraint degree. Every out-of-domain point other than z
+    // contributes a term of (trace_domain_size - 1).
+    let deep_err_bits = -log2(
+        l_plus
+            * (max_deg * (trace_domain_size + 1.0)
+                + (num_ood_points - 1.0) * (trace_domain_size - 1.0)),
+    ) + extension_field_bits;
 
     let min = cmp::min(cmp::min(fri_err_bits, ali_err_bits as u64), deep_err_bits as u64);
     if min < 1 {
@@ -19,8 +19,9 @@ type ParsedOodFrame<E> = (Vec<E>, Vec<E>);
 /// Trace and constraint polynomial evaluations at an out-of-domain point.
 ///
 /// This struct contains the following evaluations:
-/// * Evaluations of all trace polynomials at *z*.
-/// * Evaluations of all trace polynomials at *z * g*.
+/// * Evaluations of all trace polynomials at *z * g^k* for every out-of-domain offset *k*
+///   specified by the AIR (by default, the offsets are 0 and 1, i.e., the points are *z* and
+///   *z * g*).
 /// * Evaluations of constraint composition column polynomials at *z*.
 ///
 /// where *z* is an out-of-domain point and *g* is the generator of the trace domain.
@@ -46,7 +47,7 @@ impl OodFrame {
     pub fn set_trace_states<E: FieldElement>(&mut self, trace_states: &[Vec<E>]) -> Vec<E> {
         assert!(self.trace_states.is_empty(), "trace sates have already been set");
 
-        // save the evaluations with the current and next evaluations interleaved for each polynomial
+        // save the evaluations with the evaluations at all points interleaved for each polynomial
         let frame_size = trace_states.len();
         let width = trace_states[0].len();
 
@@ -75,6 +76,22 @@ impl OodFrame {
         evaluations.write_into(&mut self.evaluations)
     }
 
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the number of rows in the trace state portion of this frame, i.e., the number of
+    /// out-of-domain points at which trace polynomials are evaluated, or zero if trace states
+    /// have not been set.
+    pub fn num_trace_rows(&self) -> usize {
+        self.trace_states.first().map_or(0, |&num_rows| num_rows as usize)
+    }
+
+    /// Returns the number of constraint composition column evaluations in this frame assuming
+    /// the evaluations are elements of field `E`.
+    pub fn num_constraint_evaluations<E: FieldElement>(&self) -> usize {
+        self.evaluations.len() / E::ELEMENT_BYTES
+    }
+
     // PARSER
     // --------------------------------------------------------------------------------------------
     /// Returns main and auxiliary (if any) trace evaluation frames and a vector of out-of-domain
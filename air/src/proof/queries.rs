@@ -4,6 +4,7 @@
 // LICENSE file in the root directory of this source tree.
 
 use super::Table;
+use crate::LeafEncoding;
 use crypto::{BatchMerkleProof, ElementHasher, Hasher};
 use math::FieldElement;
 use utils::{
@@ -76,6 +77,42 @@ impl Queries {
         Queries { paths, values }
     }
 
+    /// Returns queries constructed from evaluations of a mixed set of base field and extension
+    /// field functions at multiple points in a domain and their corresponding Merkle
+    /// authentication paths.
+    ///
+    /// For each evaluation point, values of the base field functions are followed by values of
+    /// the extension field functions. A hash of these values, with extension field elements
+    /// represented by their base field coefficients, must be equal to a leaf node in the
+    /// corresponding Merkle authentication path.
+    ///
+    /// # Panics
+    /// Panics if:
+    /// * The number of base field and extension field queries is different.
+    /// * Any of the conditions listed for [Queries::new()] is violated.
+    pub fn new_mixed<H: Hasher, E: FieldElement>(
+        merkle_proof: BatchMerkleProof<H>,
+        base_values: Vec<Vec<E::BaseField>>,
+        ext_values: Vec<Vec<E>>,
+    ) -> Self {
+        assert_eq!(
+            base_values.len(),
+            ext_values.len(),
+            "number of base field and extension field queries must be the same"
+        );
+
+        let query_values = base_values
+            .into_iter()
+            .zip(ext_values.iter())
+            .map(|(mut values, ext_values)| {
+                values.extend_from_slice(E::slice_as_base_elements(ext_values));
+                values
+            })
+            .collect();
+
+        Self::new::<H, E::BaseField>(merkle_proof, query_values)
+    }
+
     // PARSER
     // --------------------------------------------------------------------------------------------
     /// Convert internally stored bytes into a set of query values and the corresponding Merkle
@@ -92,6 +129,29 @@ impl Queries {
         num_queries: usize,
         values_per_query: usize,
     ) -> Result<(BatchMerkleProof<H>, Table<E>), DeserializationError>
+    where
+        E: FieldElement,
+        H: ElementHasher<BaseField = E::BaseField>,
+    {
+        self.parse_with_encoding(domain_size, num_queries, values_per_query, LeafEncoding::Packed)
+    }
+
+    /// Convert internally stored bytes into a set of query values and the corresponding Merkle
+    /// authentication paths, assuming that query values were encoded into Merkle tree leaves
+    /// using the specified `leaf_encoding`.
+    ///
+    /// # Panics
+    /// Panics if:
+    /// * `domain_size` is not a power of two.
+    /// * `num_queries` is zero.
+    /// * `values_per_query` is zero.
+    pub fn parse_with_encoding<H, E>(
+        self,
+        domain_size: usize,
+        num_queries: usize,
+        values_per_query: usize,
+        leaf_encoding: LeafEncoding,
+    ) -> Result<(BatchMerkleProof<H>, Table<E>), DeserializationError>
     where
         E: FieldElement,
         H: ElementHasher<BaseField = E::BaseField>,
@@ -114,7 +174,8 @@ impl Queries {
         // read bytes corresponding to each query, convert them into field elements,
         // and also hash them to build leaf nodes of the batch Merkle proof
         let query_values = Table::<E>::from_bytes(&self.values, num_queries, values_per_query)?;
-        let hashed_queries = query_values.rows().map(|row| H::hash_elements(row)).collect();
+        let hashed_queries =
+            query_values.rows().map(|row| leaf_encoding.hash_row::<H, E>(row)).collect();
 
         // build batch Merkle proof
         let mut reader = SliceReader::new(&self.paths);
@@ -126,6 +187,40 @@ impl Queries {
 
         Ok((merkle_proof, query_values))
     }
+
+    /// Convert internally stored bytes into a set of query values for a mixed set of base field
+    /// and extension field functions and the corresponding Merkle authentication paths.
+    ///
+    /// This is the counterpart of [Queries::new_mixed()]: leaf nodes of the batch Merkle proof
+    /// are reconstructed by hashing base field values of each query followed by the base field
+    /// coefficients of its extension field values.
+    ///
+    /// # Panics
+    /// Panics if:
+    /// * `domain_size` is not a power of two.
+    /// * `num_queries` is zero.
+    /// * `num_ext_values` is zero.
+    #[allow(clippy::type_complexity)]
+    pub fn parse_mixed<H, E>(
+        self,
+        domain_size: usize,
+        num_queries: usize,
+        num_base_values: usize,
+        num_ext_values: usize,
+    ) -> Result<(BatchMerkleProof<H>, Table<E::BaseField>, Table<E>), DeserializationError>
+    where
+        E: FieldElement,
+        H: ElementHasher<BaseField = E::BaseField>,
+    {
+        assert!(num_ext_values > 0, "a query must contain at least one extension field value");
+
+        let values_per_query = num_base_values + num_ext_values * E::EXTENSION_DEGREE;
+        let (merkle_proof, query_values) =
+            self.parse::<H, E::BaseField>(domain_size, num_queries, values_per_query)?;
+        let (base_values, ext_values) = query_values.split_mixed::<E>(num_base_values);
+
+        Ok((merkle_proof, base_values, ext_values))
+    }
 }
 
 // SERIALIZATION
@@ -0,0 +1,73 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::StarkProof;
+use utils::{collections::Vec, Serializable};
+
+// PROOF SIZE
+// ================================================================================================
+/// Number of bytes taken up by each section of a serialized [StarkProof].
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct ProofSize {
+    /// Proof context.
+    pub context: usize,
+    /// Commitments to the execution trace, constraint composition polynomial, and FRI layers.
+    pub commitments: usize,
+    /// Decommitments of trace values for all trace segments and for preprocessed columns
+    /// (including the byte which indicates whether preprocessed columns are present).
+    pub trace_queries: usize,
+    /// Decommitments of constraint composition polynomial evaluations.
+    pub constraint_queries: usize,
+    /// Out-of-domain trace and constraint evaluations.
+    pub ood_frame: usize,
+    /// FRI proof, including the remainder polynomial.
+    pub fri_proof: usize,
+    /// Format version header, number of unique queries, and the proof-of-work nonce.
+    pub other: usize,
+}
+
+impl ProofSize {
+    /// Returns the size breakdown of the specified proof.
+    pub fn new(proof: &StarkProof) -> Self {
+        // proofs with format version 1 have no version header and serialize FRI proofs in the
+        // legacy format, and proofs with format versions earlier than 5 have no preprocessed
+        // queries flag
+        let version = proof.context.format_version();
+        let header_size = if version > 1 { 2 } else { 0 };
+        let preprocessed_flag_size = if version >= 5 { 1 } else { 0 };
+        ProofSize {
+            context: proof.context.to_bytes().len(),
+            commitments: proof.commitments.to_bytes().len(),
+            trace_queries: proof
+                .trace_queries
+                .iter()
+                .chain(proof.preprocessed_queries.iter())
+                .map(|q| q.to_bytes().len())
+                .sum::<usize>()
+                + preprocessed_flag_size,
+            constraint_queries: proof.constraint_queries.to_bytes().len(),
+            ood_frame: proof.ood_frame.to_bytes().len(),
+            fri_proof: if version == 1 {
+                let mut bytes = Vec::new();
+                proof.fri_proof.write_legacy_into(&mut bytes);
+                bytes.len()
+            } else {
+                proof.fri_proof.to_bytes().len()
+            },
+            other: header_size + 1 + 8,
+        }
+    }
+
+    /// Returns the total size of the proof in bytes.
+    pub fn total(&self) -> usize {
+        self.context
+            + self.commitments
+            + self.trace_queries
+            + self.constraint_queries
+            + self.ood_frame
+            + self.fri_proof
+            + self.other
+    }
+}
@@ -5,7 +5,7 @@
 
 use super::{DeserializationError, SliceReader, Vec};
 use core::iter::FusedIterator;
-use math::FieldElement;
+use math::{FieldElement, StarkField};
 
 // CONSTANTS
 // ================================================================================================
@@ -81,7 +81,7 @@ impl<E: FieldElement> Table<E> {
     }
 
     /// Returns an iterator over rows of this table.
-    pub fn rows(&self) -> RowIterator<E> {
+    pub fn rows(&self) -> RowIterator<'_, E> {
         RowIterator::new(self)
     }
 
@@ -91,17 +91,74 @@ impl<E: FieldElement> Table<E> {
     /// Combines multiple tables together into a single table by stacking tables column-wise (e.g.
     /// the number of rows remains the same but the number of columns changes).
     ///
-    /// Currently, this method does not support inputs containing more than one table.
-    ///
     /// # Panics
-    /// Panics if the list of tables is empty.
+    /// Panics if the list of tables is empty, or if the tables do not all have the same number of
+    /// rows.
     pub fn merge(mut tables: Vec<Table<E>>) -> Table<E> {
         assert!(!tables.is_empty(), "cannot merge an empty set of tables");
         if tables.len() == 1 {
-            tables.remove(0)
-        } else {
-            unimplemented!("merging of multiple tables is not yet implemented")
+            return tables.remove(0);
         }
+
+        let num_rows = tables[0].num_rows();
+        assert!(
+            tables.iter().all(|table| table.num_rows() == num_rows),
+            "cannot merge tables with different numbers of rows"
+        );
+        let row_width = tables.iter().map(|table| table.row_width).sum();
+        let mut data = Vec::with_capacity(num_rows * row_width);
+        for row_idx in 0..num_rows {
+            for table in tables.iter() {
+                data.extend_from_slice(table.get_row(row_idx));
+            }
+        }
+
+        Table { data, row_width }
+    }
+}
+
+impl<B: StarkField> Table<B> {
+    /// Splits this table into a table of base field values and a table of extension field values.
+    ///
+    /// The first `num_base_cols` columns of this table are moved into the first returned table.
+    /// The remaining columns are assumed to contain coefficients of extension field elements (in
+    /// the order defined by [FieldElement::slice_as_base_elements()]), and are moved into the
+    /// second returned table.
+    ///
+    /// # Panics
+    /// Panics if the number of remaining columns is zero or is not divisible by the extension
+    /// degree of `E`.
+    pub fn split_mixed<E>(&self, num_base_cols: usize) -> (Table<B>, Table<E>)
+    where
+        E: FieldElement<BaseField = B>,
+    {
+        assert!(num_base_cols < self.row_width, "at least one extension column is required");
+        let num_ext_base_cols = self.row_width - num_base_cols;
+        assert_eq!(
+            num_ext_base_cols % E::EXTENSION_DEGREE,
+            0,
+            "number of extension field coefficients {} is not divisible by extension degree {}",
+            num_ext_base_cols,
+            E::EXTENSION_DEGREE
+        );
+
+        let mut base_data = Vec::with_capacity(self.num_rows() * num_base_cols);
+        let mut ext_data = Vec::with_capacity(self.num_rows() * num_ext_base_cols);
+        for row in self.rows() {
+            let (base_values, ext_values) = row.split_at(num_base_cols);
+            base_data.extend_from_slice(base_values);
+            ext_data.extend_from_slice(E::slice_from_base_elements(ext_values));
+        }
+
+        let base_table = Table {
+            data: base_data,
+            row_width: num_base_cols,
+        };
+        let ext_table = Table {
+            data: ext_data,
+            row_width: num_ext_base_cols / E::EXTENSION_DEGREE,
+        };
+        (base_table, ext_table)
     }
 }
 
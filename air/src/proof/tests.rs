@@ -1,6 +1,373 @@
-use super::StarkProof;
+use super::{
+    get_conjectured_security, get_proven_security, AggregatedProof, Context, ProofChunk, StarkProof,
+};
+use crate::{FieldExtension, LowDegreeTest, ProofOptions, TraceInfo};
+use crypto::hashers::Blake3_256;
+use math::fields::f64::BaseElement;
+use utils::{DeserializationError, Serializable, SliceReader};
 
 #[test]
 pub fn starkproof_new_dummy_doesnt_panic() {
     let _ = StarkProof::new_dummy();
 }
+
+#[test]
+pub fn starkproof_parameters() {
+    let proof = StarkProof::new_dummy();
+    assert_eq!(1, proof.trace_width());
+    assert_eq!(64, proof.field_modulus_bits());
+    assert_eq!(FieldExtension::None, proof.field_extension());
+    assert_eq!(1, proof.num_queries());
+
+    // the hash function is not recorded in the context of a dummy proof
+    assert_eq!(None, proof.hash_function());
+    assert_eq!(None, proof.security_level_auto(true));
+
+    let size = proof.size_breakdown();
+    assert_eq!(proof.to_bytes().len(), size.total());
+    assert_eq!(proof.context.to_bytes().len(), size.context);
+}
+
+#[test]
+pub fn starkproof_format_versions() {
+    // a dummy proof contains no trace queries, and thus, could not be deserialized
+    let mut proof = StarkProof::new_dummy();
+    proof.trace_queries.push(proof.constraint_queries.clone());
+
+    // proofs are serialized with the current format version
+    let bytes = proof.to_bytes();
+    assert_eq!(&[0, StarkProof::FORMAT_VERSION], &bytes[..2]);
+    assert_eq!(proof, StarkProof::from_bytes(&bytes).unwrap());
+
+    // proofs read from earlier format versions retain the format version, and are serialized
+    // again in the same way as they were read
+    let check_version = |bytes: &[u8], version: u8| {
+        let mut source = SliceReader::new(bytes);
+        assert_eq!(version, StarkProof::read_format_version(&mut source).unwrap());
+        let legacy_proof = StarkProof::from_bytes(bytes).unwrap();
+        assert_eq!(version, legacy_proof.context.format_version());
+        assert_eq!(proof.options(), legacy_proof.options());
+        assert_eq!(bytes, legacy_proof.to_bytes());
+        assert_eq!(bytes.len(), legacy_proof.size_breakdown().total());
+    };
+
+    // proofs serialized with format version 4 do not include the byte which indicates whether
+    // preprocessed queries are present; this byte follows the trace queries
+    let context_len = proof.context.to_bytes().len();
+    let flag_offset = 2
+        + context_len
+        + 1
+        + proof.commitments.to_bytes().len()
+        + proof.trace_queries.to_bytes().len();
+    let mut legacy_bytes = proof.to_bytes();
+    assert_eq!(0, legacy_bytes.remove(flag_offset));
+    legacy_bytes[1] = 4;
+    check_version(&legacy_bytes, 4);
+
+    // proofs serialized with format version 3 do not include the query sampling strategy in proof
+    // options; the strategy is written just before the hash function at the end of the context
+    legacy_bytes.remove(2 + context_len - 2);
+    legacy_bytes[1] = 3;
+    check_version(&legacy_bytes, 3);
+
+    // proofs serialized with format versions 1 and 2 also do not include the leaf encoding, which
+    // precedes the query sampling strategy
+    legacy_bytes.remove(2 + context_len - 3);
+    legacy_bytes[1] = 2;
+    check_version(&legacy_bytes, 2);
+
+    // proofs serialized with format version 1 (i.e., by winterfell v0.7, without the version
+    // header) can still be read; in such proofs, the context ends with the six parameters passed
+    // to ProofOptions::new() (and thus, neither the five subsequent option bytes nor the hash
+    // function are present), and FRI proofs do not include the deduplication flag, out-of-domain
+    // evaluations, and layer nonces; a proof generated by winterfell v0.7 is also checked in the
+    // integration tests of the winterfell crate
+    let v2_context_len = context_len - 2;
+    let fri_offset = legacy_bytes.len() - proof.fri_proof.to_bytes().len() - 8;
+    let mut bytes = legacy_bytes[2..fri_offset].to_vec();
+    bytes.drain(v2_context_len - 6..v2_context_len);
+    // the FRI proof of a dummy proof has no layers and an empty remainder
+    let num_partitions = proof.fri_proof.num_partitions().ilog2() as u8;
+    bytes.extend_from_slice(&[0, 0, 0, num_partitions]);
+    bytes.extend_from_slice(&proof.pow_nonce.to_le_bytes());
+    check_version(&bytes, 1);
+
+    // proofs with the current format version must include the leaf encoding and the query
+    // sampling strategy
+    legacy_bytes[1] = StarkProof::FORMAT_VERSION;
+    assert!(StarkProof::from_bytes(&legacy_bytes).is_err());
+
+    // unknown format versions are rejected
+    let mut bytes = proof.to_bytes();
+    for version in [1, StarkProof::FORMAT_VERSION + 1] {
+        bytes[1] = version;
+        assert!(StarkProof::from_bytes(&bytes).is_err());
+    }
+}
+
+#[test]
+pub fn starkproof_preprocessed_queries() {
+    let mut proof = StarkProof::new_dummy();
+    proof.trace_queries.push(proof.constraint_queries.clone());
+    let bytes_without_queries = proof.to_bytes();
+
+    // preprocessed queries survive a roundtrip, and are accounted for in the size breakdown
+    proof.preprocessed_queries = Some(proof.constraint_queries.clone());
+    let bytes = proof.to_bytes();
+    assert_eq!(proof, StarkProof::from_bytes(&bytes).unwrap());
+    assert_eq!(bytes.len(), proof.size_breakdown().total());
+    assert_eq!(
+        bytes.len() - bytes_without_queries.len(),
+        proof.constraint_queries.to_bytes().len()
+    );
+
+    // flags other than 0 and 1 are rejected
+    let flag_offset = 2
+        + proof.context.to_bytes().len()
+        + 1
+        + proof.commitments.to_bytes().len()
+        + proof.trace_queries.to_bytes().len();
+    assert_eq!(1, bytes[flag_offset]);
+    let mut corrupted = bytes.clone();
+    corrupted[flag_offset] = 2;
+    assert!(StarkProof::from_bytes(&corrupted).is_err());
+}
+
+#[test]
+pub fn starkproof_describe() {
+    let mut proof = StarkProof::new_dummy();
+    let description = proof.describe();
+    assert!(description.starts_with("STARK proof\n"));
+    assert!(description.contains("  trace length:               8\n"));
+    assert!(description.contains("  hash function:              not recorded\n"));
+    assert!(description.contains("commitments (malformed, 4 digests expected)\n"));
+    assert!(description.contains("  estimate:                   unknown"));
+    let total_size = format!("  total size:                 {} bytes\n", proof.to_bytes().len());
+    assert!(description.contains(&total_size));
+
+    proof.context = proof.context.with_hash_function::<Blake3_256<BaseElement>>();
+    let description = proof.describe();
+    assert!(description.contains("  hash function:              blake3_256\n"));
+    let conjectured = format!(
+        "  conjectured:                {} bits\n",
+        proof.security_level_auto(true).unwrap()
+    );
+    assert!(description.contains(&conjectured));
+
+    #[cfg(feature = "display")]
+    assert_eq!(description, format!("{proof}"));
+}
+
+#[test]
+pub fn starkproof_structured_accessors() {
+    let proof = StarkProof::new_dummy();
+
+    // commitments of a dummy proof are empty
+    assert!(proof.parse_commitments::<Blake3_256<BaseElement>>().is_err());
+
+    // the OOD frame of a dummy proof is empty; it must be rejected rather than cause a panic
+    assert!(proof.ood_trace_rows::<BaseElement>().is_err());
+    assert!(proof.ood_constraint_evaluations::<BaseElement>().is_err());
+}
+
+#[cfg(feature = "compression")]
+#[test]
+pub fn starkproof_compression() {
+    use super::CompressionCodec;
+
+    let mut proof = StarkProof::new_dummy();
+    proof.trace_queries.push(proof.constraint_queries.clone());
+
+    for codec in [CompressionCodec::Deflate, CompressionCodec::Zstd] {
+        let bytes = proof.to_compressed_bytes(codec);
+        assert_eq!(&[0, u8::MAX, codec as u8], &bytes[..3]);
+        assert!(StarkProof::is_compressed(&bytes));
+
+        // compressed proofs can be read explicitly or transparently
+        assert_eq!(proof, StarkProof::from_compressed_bytes(&bytes).unwrap());
+        assert_eq!(proof, StarkProof::from_bytes(&bytes).unwrap());
+
+        // compressed proofs are not accepted where only a format version is expected
+        let mut source = SliceReader::new(&bytes);
+        assert!(StarkProof::read_format_version(&mut source).is_err());
+
+        // corrupted or truncated compressed proofs are rejected
+        assert!(StarkProof::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+        let mut corrupted = bytes.clone();
+        corrupted[3] ^= 1;
+        assert!(StarkProof::from_bytes(&corrupted).is_err());
+
+        // unknown codecs are rejected
+        let mut corrupted = bytes.clone();
+        corrupted[2] = 0;
+        assert!(StarkProof::from_bytes(&corrupted).is_err());
+    }
+
+    // uncompressed proofs are not accepted as compressed proofs
+    assert!(StarkProof::from_compressed_bytes(&proof.to_bytes()).is_err());
+}
+
+#[test]
+pub fn aggregated_proof_serialization() {
+    let mut proof = StarkProof::new_dummy();
+    proof.trace_queries.push(proof.constraint_queries.clone());
+
+    // an empty container can be serialized
+    let aggregated = AggregatedProof::new(vec![1, 2, 3]);
+    assert!(aggregated.is_empty());
+    assert_eq!(aggregated, AggregatedProof::from_bytes(&aggregated.to_bytes()).unwrap());
+
+    // proofs generated with different options and labels survive a roundtrip
+    let mut other_proof = proof.clone();
+    other_proof.context = Context::new::<BaseElement>(
+        &TraceInfo::new(2, 16),
+        ProofOptions::new(2, 4, 0, FieldExtension::Quadratic, 4, 7),
+    );
+    let mut aggregated = AggregatedProof::new(vec![1, 2, 3]);
+    aggregated.push("fib", proof.clone());
+    aggregated.push("rescue", other_proof.clone());
+    aggregated.push("fib", proof.clone());
+    assert_eq!(3, aggregated.num_proofs());
+    assert_eq!(2, aggregated.distinct_options().len());
+    assert_eq!(Some(("rescue", &other_proof)), aggregated.get(1));
+
+    let bytes = aggregated.to_bytes();
+    assert_eq!(AggregatedProof::FORMAT_VERSION, bytes[0]);
+    let result = AggregatedProof::from_bytes(&bytes).unwrap();
+    assert_eq!(aggregated, result);
+    assert_eq!(&[1, 2, 3], result.metadata());
+    assert_eq!(&["fib", "rescue", "fib"], result.labels());
+
+    // options shared by several proofs are written only once: an additional proof takes up the
+    // space of its label and of the proof itself without the version header, with the options
+    // replaced by a 2-byte index
+    let mut extended = aggregated.clone();
+    extended.push("fib", proof.clone());
+    let options_size = proof.options().to_bytes().len();
+    let expected_size = bytes.len() + 2 + 3 + proof.to_bytes().len() - 2 - options_size + 2;
+    assert_eq!(expected_size, extended.to_bytes().len());
+
+    // trailing bytes and unknown format versions are rejected
+    let mut corrupted = bytes.clone();
+    corrupted.push(0);
+    assert!(AggregatedProof::from_bytes(&corrupted).is_err());
+    let mut corrupted = bytes.clone();
+    corrupted[0] = AggregatedProof::FORMAT_VERSION + 1;
+    assert!(AggregatedProof::from_bytes(&corrupted).is_err());
+}
+
+#[test]
+pub fn aggregated_proof_invalid_options_index() {
+    let mut proof = StarkProof::new_dummy();
+    proof.trace_queries.push(proof.constraint_queries.clone());
+    let mut aggregated = AggregatedProof::new(Vec::new());
+    aggregated.push("fib", proof.clone());
+    let mut bytes = aggregated.to_bytes();
+
+    // the options index is followed by the hash function byte of the context and the body of
+    // the proof
+    let body_len = proof.to_bytes().len() - 2 - proof.context.to_bytes().len();
+    let offset = bytes.len() - body_len - 3;
+    assert_eq!(&[0, 0], &bytes[offset..offset + 2]);
+
+    bytes[offset] = 1;
+    let err = AggregatedProof::from_bytes(&bytes).unwrap_err();
+    assert!(matches!(err, DeserializationError::InvalidValue(_)));
+}
+
+#[test]
+pub fn aggregated_proof_digest() {
+    let proof = StarkProof::new_dummy();
+    let mut aggregated = AggregatedProof::new(vec![1]);
+    aggregated.push("fib", proof.clone());
+    let digest = aggregated.digest::<Blake3_256<BaseElement>>();
+    assert_eq!(digest, aggregated.clone().digest::<Blake3_256<BaseElement>>());
+
+    // the digest commits to the metadata, the labels, and the proofs
+    let mut other = AggregatedProof::new(vec![2]);
+    other.push("fib", proof.clone());
+    assert_ne!(digest, other.digest::<Blake3_256<BaseElement>>());
+
+    let mut other = AggregatedProof::new(vec![1]);
+    other.push("rescue", proof.clone());
+    assert_ne!(digest, other.digest::<Blake3_256<BaseElement>>());
+
+    let mut other = AggregatedProof::new(vec![1]);
+    other.push("fib", proof.clone());
+    other.push("fib", proof);
+    assert_ne!(digest, other.digest::<Blake3_256<BaseElement>>());
+}
+
+#[test]
+pub fn proof_chunks() {
+    type H = Blake3_256<BaseElement>;
+    let mut proof = StarkProof::new_dummy();
+    proof.trace_queries.push(proof.constraint_queries.clone());
+    let max_chunk_size = ProofChunk::HEADER_SIZE + 8;
+    let chunks = proof.to_chunks::<H>(max_chunk_size);
+    assert_eq!(proof.to_bytes().len().div_ceil(8), chunks.len());
+    for (index, chunk) in chunks.iter().enumerate() {
+        assert_eq!(index, chunk.index());
+        assert_eq!(chunks.len(), chunk.num_chunks());
+        assert!(chunk.to_bytes().len() <= max_chunk_size);
+    }
+    assert_eq!(proof, StarkProof::from_chunks::<H>(&chunks).unwrap());
+
+    // duplicated chunks are rejected
+    let mut duplicated = chunks.clone();
+    duplicated[1] = duplicated[0].clone();
+    assert!(StarkProof::from_chunks::<H>(&duplicated).is_err());
+
+    // chunk indexes must be smaller than the number of chunks
+    let mut bytes = chunks[0].to_bytes();
+    bytes[0] = bytes[2];
+    bytes[1] = bytes[3];
+    let err = ProofChunk::from_bytes(&bytes).unwrap_err();
+    assert!(matches!(err, DeserializationError::InvalidValue(_)));
+}
+
+#[test]
+pub fn security_level_with_ood_points() {
+    // with 96 queries, field security is lower than conjectured query security
+    let context = Context::new::<BaseElement>(
+        &TraceInfo::new(2, 1024),
+        ProofOptions::new(96, 8, 0, FieldExtension::Quadratic, 4, 7),
+    );
+
+    // opening trace polynomials at z and z * g is the default
+    for conjectured in [true, false] {
+        let security = context.security_level::<Blake3_256<BaseElement>>(conjectured);
+        assert_eq!(
+            security,
+            context.security_level_with_ood_points::<Blake3_256<BaseElement>>(conjectured, 2)
+        );
+
+        // additional out-of-domain points reduce security level; proven security is bounded by
+        // query security for these parameters, and thus, it does not decrease for a few points
+        let mut prev_security = security;
+        for num_ood_points in [3, 5, 9] {
+            let security = context.security_level_with_ood_points::<Blake3_256<BaseElement>>(
+                conjectured,
+                num_ood_points,
+            );
+            if conjectured {
+                assert!(security < prev_security);
+            } else {
+                assert!(security <= prev_security);
+            }
+            prev_security = security;
+        }
+    }
+}
+
+#[test]
+pub fn stir_security_level() {
+    let options = ProofOptions::new(28, 8, 16, FieldExtension::Quadratic, 4, 7);
+    assert!(get_conjectured_security(&options, 64, 1 << 16, 2, 128) > 0);
+    assert!(get_proven_security(&options, 64, 1 << 16, 2, 128) > 0);
+
+    // no security is claimed for STIR proofs until their soundness has been analyzed
+    let options = options.with_low_degree_test(LowDegreeTest::Stir);
+    assert_eq!(0, get_conjectured_security(&options, 64, 1 << 16, 2, 128));
+    assert_eq!(0, get_proven_security(&options, 64, 1 << 16, 2, 128));
+}
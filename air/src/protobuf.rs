@@ -0,0 +1,874 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+//! Protobuf encoding of STARK proofs, proof options, and public inputs.
+//!
+//! The schema of the encoding is defined in `proto/winterfell.proto` of this crate; it can be
+//! used to generate codecs in other languages, or imported into other schemas (e.g., to embed
+//! proofs into service messages). Message types in this module correspond one-to-one to the
+//! messages of the schema and are kept in the form generated for the schema by `prost-build`, so
+//! that building this crate does not require the protobuf compiler.
+//!
+//! Like the JSON encoding, the protobuf encoding mirrors the binary serialization format of
+//! [StarkProof](crate::proof::StarkProof) (format version 5), but represents every section of a
+//! proof as a separate field:
+//!
+//! * Digests (commitments) are encoded as their serialized bytes.
+//! * Field elements are encoded as their canonical little-endian representation in the base
+//!   field; elements of extension fields are encoded as sequences of their base field
+//!   coefficients. Sequences of elements are encoded as concatenations of such representations.
+//! * Compressed Merkle authentication paths are encoded as opaque bytes.
+//!
+//! Decoded proofs are converted into the binary format and deserialized, and thus, are validated
+//! in the same way as deserialized proofs; in addition, every field element and digest is checked
+//! to be valid for the specified base field and hash function.
+//!
+//! Proofs, proof options, and queries can be encoded to and decoded from bytes directly via
+//! [StarkProof::to_protobuf()](crate::proof::StarkProof::to_protobuf),
+//! [ProofOptions::to_protobuf()](crate::ProofOptions::to_protobuf), and
+//! [Queries::to_protobuf()](crate::proof::Queries::to_protobuf) (and the respective
+//! `from_protobuf()` methods). Public inputs are encoded via [PublicInputs] messages.
+
+use crate::{
+    proof::{self, Commitments, CONTEXT_BINDING_VERSION, FORMAT_VERSION_MARKER},
+    TraceInfo,
+};
+use core::str::FromStr;
+use crypto::{HashFunction, Hasher};
+use math::StarkField;
+use utils::{
+    collections::Vec,
+    string::{String, ToString},
+    ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable, SliceReader,
+};
+
+pub use prost::Message;
+
+// MESSAGES
+// ================================================================================================
+
+/// Protobuf message describing [ProofOptions](crate::ProofOptions).
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ProofOptions {
+    #[prost(uint32, tag = "1")]
+    pub num_queries: u32,
+    #[prost(uint32, tag = "2")]
+    pub blowup_factor: u32,
+    #[prost(uint32, tag = "3")]
+    pub grinding_factor: u32,
+    #[prost(enumeration = "FieldExtension", tag = "4")]
+    pub field_extension: i32,
+    #[prost(uint32, tag = "5")]
+    pub fri_folding_factor: u32,
+    #[prost(uint32, tag = "6")]
+    pub fri_remainder_max_degree: u32,
+    #[prost(bool, tag = "7")]
+    pub normalized_queries: bool,
+    #[prost(uint32, repeated, tag = "8")]
+    pub fri_folding_schedule: Vec<u32>,
+    #[prost(uint32, tag = "9")]
+    pub fri_layer_grinding_factor: u32,
+    #[prost(bool, tag = "10")]
+    pub fri_query_deduplication: bool,
+    #[prost(enumeration = "LowDegreeTest", tag = "11")]
+    pub low_degree_test: i32,
+    #[prost(enumeration = "LeafEncoding", tag = "12")]
+    pub leaf_encoding: i32,
+    #[prost(enumeration = "QuerySampling", tag = "13")]
+    pub query_sampling: i32,
+    #[prost(uint32, tag = "14")]
+    pub custom_query_sampling_id: u32,
+}
+
+/// Protobuf message describing [TraceLayout](crate::TraceLayout).
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TraceLayout {
+    #[prost(uint32, tag = "1")]
+    pub main_segment_width: u32,
+    #[prost(uint32, repeated, tag = "2")]
+    pub aux_segment_widths: Vec<u32>,
+    #[prost(uint32, repeated, tag = "3")]
+    pub aux_segment_rands: Vec<u32>,
+}
+
+/// Protobuf message describing the context of a proof.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Context {
+    #[prost(message, optional, tag = "1")]
+    pub trace_layout: Option<TraceLayout>,
+    #[prost(uint64, tag = "2")]
+    pub trace_length: u64,
+    #[prost(bytes = "vec", tag = "3")]
+    pub trace_meta: Vec<u8>,
+    #[prost(bytes = "vec", tag = "4")]
+    pub field_modulus: Vec<u8>,
+    #[prost(message, optional, tag = "5")]
+    pub options: Option<ProofOptions>,
+    #[prost(string, optional, tag = "6")]
+    pub hash_function: Option<String>,
+}
+
+/// Protobuf message describing [Queries](crate::proof::Queries).
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Queries {
+    #[prost(bytes = "vec", repeated, tag = "1")]
+    pub values: Vec<Vec<u8>>,
+    #[prost(bytes = "vec", tag = "2")]
+    pub paths: Vec<u8>,
+}
+
+/// Protobuf message describing the out-of-domain frame of a proof.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct OodFrame {
+    #[prost(uint32, optional, tag = "1")]
+    pub frame_size: Option<u32>,
+    #[prost(bytes = "vec", tag = "2")]
+    pub trace_states: Vec<u8>,
+    #[prost(bytes = "vec", tag = "3")]
+    pub constraint_evaluations: Vec<u8>,
+}
+
+/// Protobuf message describing a single layer of a FRI proof.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FriLayer {
+    #[prost(uint32, optional, tag = "1")]
+    pub num_queries: Option<u32>,
+    #[prost(bytes = "vec", tag = "2")]
+    pub values: Vec<u8>,
+    #[prost(bytes = "vec", tag = "3")]
+    pub paths: Vec<u8>,
+}
+
+/// Protobuf message describing a FRI proof.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FriProof {
+    #[prost(message, repeated, tag = "1")]
+    pub layers: Vec<FriLayer>,
+    #[prost(bytes = "vec", tag = "2")]
+    pub remainder: Vec<u8>,
+    #[prost(bytes = "vec", tag = "3")]
+    pub ood_evaluations: Vec<u8>,
+    #[prost(uint64, repeated, tag = "4")]
+    pub layer_nonces: Vec<u64>,
+    #[prost(uint32, tag = "5")]
+    pub num_partitions: u32,
+}
+
+/// Protobuf message describing [StarkProof](crate::proof::StarkProof).
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct StarkProof {
+    #[prost(uint32, tag = "1")]
+    pub format_version: u32,
+    #[prost(message, optional, tag = "2")]
+    pub context: Option<Context>,
+    #[prost(uint32, tag = "3")]
+    pub num_unique_queries: u32,
+    #[prost(bytes = "vec", repeated, tag = "4")]
+    pub commitments: Vec<Vec<u8>>,
+    #[prost(message, repeated, tag = "5")]
+    pub trace_queries: Vec<Queries>,
+    #[prost(message, optional, tag = "6")]
+    pub preprocessed_queries: Option<Queries>,
+    #[prost(message, optional, tag = "7")]
+    pub constraint_queries: Option<Queries>,
+    #[prost(message, optional, tag = "8")]
+    pub ood_frame: Option<OodFrame>,
+    #[prost(message, optional, tag = "9")]
+    pub fri_proof: Option<FriProof>,
+    #[prost(uint64, tag = "10")]
+    pub pow_nonce: u64,
+}
+
+/// Protobuf message describing public inputs of a computation as a list of base field elements
+/// (e.g., as returned by [ToElements::to_elements()](math::ToElements::to_elements)).
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PublicInputs {
+    #[prost(bytes = "vec", tag = "1")]
+    pub field_modulus: Vec<u8>,
+    #[prost(bytes = "vec", repeated, tag = "2")]
+    pub elements: Vec<Vec<u8>>,
+}
+
+/// Protobuf enum describing [FieldExtension](crate::FieldExtension).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum FieldExtension {
+    None = 0,
+    Quadratic = 1,
+    Cubic = 2,
+}
+
+/// Protobuf enum describing [LowDegreeTest](crate::LowDegreeTest).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum LowDegreeTest {
+    Fri = 0,
+    Stir = 1,
+}
+
+/// Protobuf enum describing [LeafEncoding](crate::LeafEncoding).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum LeafEncoding {
+    Packed = 0,
+    PaddedLe = 1,
+    PaddedBe = 2,
+}
+
+/// Protobuf enum describing [QuerySampling](crate::QuerySampling); custom strategies are
+/// identified by [ProofOptions::custom_query_sampling_id].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum QuerySampling {
+    Uniform = 0,
+    Stratified = 1,
+    Custom = 2,
+}
+
+// PROOF OPTIONS
+// ================================================================================================
+
+impl ProofOptions {
+    /// Returns the protobuf message describing the specified proof options.
+    pub fn from_options(options: &crate::ProofOptions) -> Self {
+        let fri_options = options.to_fri_options();
+        let field_extension = match options.field_extension() {
+            crate::FieldExtension::None => FieldExtension::None,
+            crate::FieldExtension::Quadratic => FieldExtension::Quadratic,
+            crate::FieldExtension::Cubic => FieldExtension::Cubic,
+        };
+        let low_degree_test = match options.low_degree_test() {
+            crate::LowDegreeTest::Fri => LowDegreeTest::Fri,
+            crate::LowDegreeTest::Stir => LowDegreeTest::Stir,
+        };
+        let leaf_encoding = match options.leaf_encoding() {
+            crate::LeafEncoding::Packed => LeafEncoding::Packed,
+            crate::LeafEncoding::PaddedLe => LeafEncoding::PaddedLe,
+            crate::LeafEncoding::PaddedBe => LeafEncoding::PaddedBe,
+        };
+        let (query_sampling, custom_query_sampling_id) = match options.query_sampling() {
+            crate::QuerySampling::Uniform => (QuerySampling::Uniform, 0),
+            crate::QuerySampling::Stratified => (QuerySampling::Stratified, 0),
+            crate::QuerySampling::Custom(id) => (QuerySampling::Custom, id as u32),
+        };
+
+        Self {
+            num_queries: options.num_queries() as u32,
+            blowup_factor: options.blowup_factor() as u32,
+            grinding_factor: options.grinding_factor(),
+            field_extension: field_extension as i32,
+            fri_folding_factor: fri_options.folding_factor() as u32,
+            fri_remainder_max_degree: fri_options.remainder_max_degree() as u32,
+            normalized_queries: options.normalized_queries(),
+            fri_folding_schedule: options
+                .fri_folding_schedule()
+                .iter()
+                .map(|&f| f as u32)
+                .collect(),
+            fri_layer_grinding_factor: options.fri_layer_grinding_factor(),
+            fri_query_deduplication: options.fri_query_deduplication(),
+            low_degree_test: low_degree_test as i32,
+            leaf_encoding: leaf_encoding as i32,
+            query_sampling: query_sampling as i32,
+            custom_query_sampling_id,
+        }
+    }
+
+    /// Returns proof options described by this message.
+    ///
+    /// The options are converted into their binary form and deserialized, so that they are
+    /// validated in the same way as deserialized options.
+    ///
+    /// # Errors
+    /// Returns an error if this message does not describe valid proof options.
+    pub fn to_options(&self) -> Result<crate::ProofOptions, DeserializationError> {
+        let field_extension = match FieldExtension::try_from(self.field_extension) {
+            Ok(FieldExtension::None) => crate::FieldExtension::None,
+            Ok(FieldExtension::Quadratic) => crate::FieldExtension::Quadratic,
+            Ok(FieldExtension::Cubic) => crate::FieldExtension::Cubic,
+            Err(_) => return Err(unknown("field extension", self.field_extension)),
+        };
+        let low_degree_test = match LowDegreeTest::try_from(self.low_degree_test) {
+            Ok(LowDegreeTest::Fri) => crate::LowDegreeTest::Fri,
+            #[cfg(feature = "experimental-stir")]
+            Ok(LowDegreeTest::Stir) => crate::LowDegreeTest::Stir,
+            #[cfg(not(feature = "experimental-stir"))]
+            Ok(LowDegreeTest::Stir) => {
+                return Err(unknown("low-degree test", self.low_degree_test))
+            }
+            Err(_) => return Err(unknown("low-degree test", self.low_degree_test)),
+        };
+        let leaf_encoding = match LeafEncoding::try_from(self.leaf_encoding) {
+            Ok(LeafEncoding::Packed) => crate::LeafEncoding::Packed,
+            Ok(LeafEncoding::PaddedLe) => crate::LeafEncoding::PaddedLe,
+            Ok(LeafEncoding::PaddedBe) => crate::LeafEncoding::PaddedBe,
+            Err(_) => return Err(unknown("leaf encoding", self.leaf_encoding)),
+        };
+        let query_sampling = match QuerySampling::try_from(self.query_sampling) {
+            Ok(QuerySampling::Uniform) => crate::QuerySampling::Uniform,
+            Ok(QuerySampling::Stratified) => crate::QuerySampling::Stratified,
+            Ok(QuerySampling::Custom) => crate::QuerySampling::Custom(to_u8(
+                self.custom_query_sampling_id,
+                "custom_query_sampling_id",
+            )?),
+            Err(_) => return Err(unknown("query sampling strategy", self.query_sampling)),
+        };
+        let schedule = self
+            .fri_folding_schedule
+            .iter()
+            .map(|&factor| to_u8(factor, "fri_folding_schedule"))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut bytes = Vec::new();
+        bytes.write_u8(to_u8(self.num_queries, "num_queries")?);
+        bytes.write_u8(to_u8(self.blowup_factor, "blowup_factor")?);
+        bytes.write_u8(to_u8(self.grinding_factor, "grinding_factor")?);
+        field_extension.write_into(&mut bytes);
+        bytes.write_u8(to_u8(self.fri_folding_factor, "fri_folding_factor")?);
+        bytes.write_u8(to_u8(self.fri_remainder_max_degree, "fri_remainder_max_degree")?);
+        bytes.write_bool(self.normalized_queries);
+        bytes.write_u8(to_u8(schedule.len(), "fri_folding_schedule")?);
+        bytes.write_bytes(&schedule);
+        bytes.write_u8(to_u8(self.fri_layer_grinding_factor, "fri_layer_grinding_factor")?);
+        bytes.write_bool(self.fri_query_deduplication);
+        bytes.write_u8(low_degree_test as u8);
+        leaf_encoding.write_into(&mut bytes);
+        query_sampling.write_into(&mut bytes);
+
+        crate::ProofOptions::read_from_bytes(&bytes)
+    }
+}
+
+impl crate::ProofOptions {
+    /// Returns the protobuf encoding of these proof options (see [protobuf](crate::protobuf)
+    /// module).
+    pub fn to_protobuf(&self) -> Vec<u8> {
+        ProofOptions::from_options(self).encode_to_vec()
+    }
+
+    /// Returns proof options decoded from the specified protobuf encoding.
+    ///
+    /// # Errors
+    /// Returns an error if `bytes` is not a valid protobuf encoding of proof options.
+    pub fn from_protobuf(bytes: &[u8]) -> Result<Self, DeserializationError> {
+        decode::<ProofOptions>(bytes)?.to_options()
+    }
+}
+
+// STARK PROOF
+// ================================================================================================
+
+impl StarkProof {
+    /// Returns the protobuf message describing the specified proof.
+    ///
+    /// `B` must be the base field of the proof, and `H` must be the hash function used to
+    /// generate the proof; these are needed to split the proof into field elements and digests.
+    ///
+    /// # Errors
+    /// Returns an error if the base field of the proof is not `B`, if the proof could not be
+    /// split into field elements of `B` and digests of `H`, or if the proof has a format version
+    /// earlier than 4 (the transcripts of such proofs depend on their format version, and thus,
+    /// they cannot be encoded in the current format).
+    pub fn from_proof<B: StarkField, H: Hasher>(
+        proof: &proof::StarkProof,
+    ) -> Result<Self, DeserializationError> {
+        if proof.context.field_modulus_bytes() != B::get_modulus_le_bytes() {
+            return Err(invalid("base field of the proof does not match the specified field"));
+        }
+        let version = proof.context.format_version();
+        if version < CONTEXT_BINDING_VERSION {
+            return Err(invalid(format!("proofs with format version {version} cannot be encoded")));
+        }
+        let num_queries = proof.num_unique_queries as usize;
+        let trace_queries = proof
+            .trace_queries
+            .iter()
+            .map(|queries| Queries::from_queries::<B>(queries, num_queries))
+            .collect::<Result<Vec<_>, _>>()?;
+        let preprocessed_queries = proof
+            .preprocessed_queries
+            .as_ref()
+            .map(|queries| Queries::from_queries::<B>(queries, num_queries))
+            .transpose()?;
+
+        Ok(Self {
+            format_version: proof::StarkProof::FORMAT_VERSION as u32,
+            context: Some(context_to_message(&proof.context)),
+            num_unique_queries: num_queries as u32,
+            commitments: commitments_to_message::<H>(&proof.commitments)?,
+            trace_queries,
+            preprocessed_queries,
+            constraint_queries: Some(Queries::from_queries::<B>(
+                &proof.constraint_queries,
+                num_queries,
+            )?),
+            ood_frame: Some(ood_frame_to_message::<B>(&proof.ood_frame)?),
+            fri_proof: Some(fri_proof_to_message::<B>(&proof.fri_proof)?),
+            pow_nonce: proof.pow_nonce,
+        })
+    }
+
+    /// Returns the proof described by this message.
+    ///
+    /// `B` must be the base field of the proof, and `H` must be the hash function used to
+    /// generate the proof. The proof is converted into the binary format and deserialized, and
+    /// thus, is validated in the same way as a deserialized proof.
+    ///
+    /// # Errors
+    /// Returns an error if this message does not describe a valid proof generated in the base
+    /// field `B` with hash function `H`.
+    pub fn to_proof<B: StarkField, H: Hasher>(
+        &self,
+    ) -> Result<proof::StarkProof, DeserializationError> {
+        if self.format_version != proof::StarkProof::FORMAT_VERSION as u32 {
+            return Err(invalid(format!(
+                "proof format version {} is not supported",
+                self.format_version
+            )));
+        }
+
+        let mut bytes = Vec::new();
+        bytes.write_u8(FORMAT_VERSION_MARKER);
+        bytes.write_u8(proof::StarkProof::FORMAT_VERSION);
+        context_from_message::<B, H>(required(&self.context, "context")?)?.write_into(&mut bytes);
+        bytes.write_u8(to_u8(self.num_unique_queries, "num_unique_queries")?);
+        commitments_from_message::<H>(&self.commitments)?.write_into(&mut bytes);
+        for queries in self.trace_queries.iter() {
+            queries.to_queries::<B>()?.write_into(&mut bytes);
+        }
+        match &self.preprocessed_queries {
+            None => bytes.write_u8(0),
+            Some(queries) => {
+                bytes.write_u8(1);
+                queries.to_queries::<B>()?.write_into(&mut bytes);
+            }
+        }
+        required(&self.constraint_queries, "constraint_queries")?
+            .to_queries::<B>()?
+            .write_into(&mut bytes);
+        ood_frame_from_message::<B>(required(&self.ood_frame, "ood_frame")?)?
+            .write_into(&mut bytes);
+        write_fri_proof::<B>(required(&self.fri_proof, "fri_proof")?, &mut bytes)?;
+        bytes.write_u64(self.pow_nonce);
+
+        proof::StarkProof::from_bytes(&bytes)
+    }
+}
+
+impl proof::StarkProof {
+    /// Returns the protobuf encoding of this proof (see [protobuf](crate::protobuf) module).
+    ///
+    /// `B` must be the base field of the proof, and `H` must be the hash function used to
+    /// generate the proof; these are needed to split the proof into field elements and digests.
+    ///
+    /// # Errors
+    /// Returns an error if the base field of the proof is not `B`, or if the proof could not be
+    /// split into field elements of `B` and digests of `H`.
+    pub fn to_protobuf<B: StarkField, H: Hasher>(&self) -> Result<Vec<u8>, DeserializationError> {
+        Ok(StarkProof::from_proof::<B, H>(self)?.encode_to_vec())
+    }
+
+    /// Returns a proof decoded from the specified protobuf encoding.
+    ///
+    /// `B` must be the base field of the proof, and `H` must be the hash function used to
+    /// generate the proof. The decoded proof is validated in the same way as a deserialized
+    /// proof.
+    ///
+    /// # Errors
+    /// Returns an error if `bytes` is not a valid protobuf encoding of a proof generated in the
+    /// base field `B` with hash function `H`.
+    pub fn from_protobuf<B: StarkField, H: Hasher>(
+        bytes: &[u8],
+    ) -> Result<Self, DeserializationError> {
+        decode::<StarkProof>(bytes)?.to_proof::<B, H>()
+    }
+}
+
+// QUERIES
+// ================================================================================================
+
+impl Queries {
+    /// Returns the protobuf message describing the specified queries.
+    ///
+    /// `B` must be the base field of the proof the queries belong to, and `num_queries` must be
+    /// the number of unique query positions; these are needed to split the queries into values
+    /// of individual queries.
+    ///
+    /// # Errors
+    /// Returns an error if the queries could not be split into `num_queries` queries of
+    /// elements of `B`.
+    pub fn from_queries<B: StarkField>(
+        queries: &proof::Queries,
+        num_queries: usize,
+    ) -> Result<Self, DeserializationError> {
+        let bytes = queries.to_bytes();
+        let mut reader = SliceReader::new(&bytes);
+        let num_value_bytes = reader.read_u32()? as usize;
+        let values = reader.read_vec(num_value_bytes)?;
+        let num_path_bytes = reader.read_u32()? as usize;
+        let paths = reader.read_vec(num_path_bytes)?;
+
+        if num_queries == 0 || values.len() % num_queries != 0 {
+            return Err(invalid("query values cannot be split into queries"));
+        }
+        let values = values
+            .chunks(values.len() / num_queries)
+            .map(|query| check_elements::<B>(query, "values").map(|_| query.to_vec()))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self { values, paths })
+    }
+
+    /// Returns the queries described by this message; `B` must be the base field of the proof
+    /// the queries belong to.
+    ///
+    /// # Errors
+    /// Returns an error if this message does not describe valid queries of elements of `B`.
+    pub fn to_queries<B: StarkField>(&self) -> Result<proof::Queries, DeserializationError> {
+        let mut values = Vec::new();
+        for query in self.values.iter() {
+            if query.len() != self.values[0].len() {
+                return Err(invalid("all queries must contain the same number of values"));
+            }
+            check_elements::<B>(query, "values")?;
+            values.extend_from_slice(query);
+        }
+
+        let mut bytes = Vec::new();
+        bytes.write_u32(to_u32(values.len(), "values")?);
+        bytes.write_bytes(&values);
+        bytes.write_u32(to_u32(self.paths.len(), "paths")?);
+        bytes.write_bytes(&self.paths);
+        proof::Queries::read_from_bytes(&bytes)
+    }
+}
+
+impl proof::Queries {
+    /// Returns the protobuf encoding of these queries (see [protobuf](crate::protobuf) module).
+    ///
+    /// `B` must be the base field of the proof the queries belong to, and `num_queries` must be
+    /// the number of unique query positions.
+    ///
+    /// # Errors
+    /// Returns an error if the queries could not be split into `num_queries` queries of
+    /// elements of `B`.
+    pub fn to_protobuf<B: StarkField>(
+        &self,
+        num_queries: usize,
+    ) -> Result<Vec<u8>, DeserializationError> {
+        Ok(Queries::from_queries::<B>(self, num_queries)?.encode_to_vec())
+    }
+
+    /// Returns queries decoded from the specified protobuf encoding; `B` must be the base field
+    /// of the proof the queries belong to.
+    ///
+    /// # Errors
+    /// Returns an error if `bytes` is not a valid protobuf encoding of queries of elements of `B`.
+    pub fn from_protobuf<B: StarkField>(bytes: &[u8]) -> Result<Self, DeserializationError> {
+        decode::<Queries>(bytes)?.to_queries::<B>()
+    }
+}
+
+// PUBLIC INPUTS
+// ================================================================================================
+
+impl PublicInputs {
+    /// Returns the protobuf message describing public inputs consisting of the specified
+    /// elements.
+    pub fn from_elements<B: StarkField>(elements: &[B]) -> Self {
+        Self {
+            field_modulus: B::get_modulus_le_bytes(),
+            elements: elements.iter().map(|element| element.to_bytes()).collect(),
+        }
+    }
+
+    /// Returns elements of the public inputs described by this message.
+    ///
+    /// # Errors
+    /// Returns an error if the public inputs are not defined in the base field `B`, or if any of
+    /// the elements is not a valid element of `B`.
+    pub fn to_elements<B: StarkField>(&self) -> Result<Vec<B>, DeserializationError> {
+        if self.field_modulus != B::get_modulus_le_bytes() {
+            return Err(invalid("field of the public inputs does not match the specified field"));
+        }
+        self.elements.iter().map(|element| B::read_from_bytes(element)).collect()
+    }
+}
+
+// PROOF SECTIONS
+// ================================================================================================
+
+fn context_to_message(context: &proof::Context) -> Context {
+    // the trace layout is serialized as the main segment width followed by the widths and the
+    // numbers of random elements of all auxiliary segments
+    let layout = context.trace_layout().to_bytes();
+    let (aux_widths, aux_rands) = layout[1..].split_at((layout.len() - 1) / 2);
+
+    Context {
+        trace_layout: Some(TraceLayout {
+            main_segment_width: layout[0] as u32,
+            aux_segment_widths: aux_widths.iter().map(|&w| w as u32).collect(),
+            aux_segment_rands: aux_rands.iter().map(|&r| r as u32).collect(),
+        }),
+        trace_length: context.trace_length() as u64,
+        trace_meta: context.get_trace_info().meta().to_vec(),
+        field_modulus: context.field_modulus_bytes().to_vec(),
+        options: Some(ProofOptions::from_options(context.options())),
+        hash_function: context.hash_function().map(|hash_fn| hash_fn.to_string()),
+    }
+}
+
+fn context_from_message<B: StarkField, H: Hasher>(
+    message: &Context,
+) -> Result<proof::Context, DeserializationError> {
+    if message.field_modulus != B::get_modulus_le_bytes() {
+        return Err(invalid("field modulus of the proof does not match the specified field"));
+    }
+
+    let layout = required(&message.trace_layout, "trace_layout")?;
+    let mut layout_bytes = vec![to_u8(layout.main_segment_width, "main_segment_width")?];
+    for &width in layout.aux_segment_widths.iter() {
+        layout_bytes.push(to_u8(width, "aux_segment_widths")?);
+    }
+    for &rands in layout.aux_segment_rands.iter() {
+        layout_bytes.push(to_u8(rands, "aux_segment_rands")?);
+    }
+    let layout = crate::TraceLayout::read_from_bytes(&layout_bytes)?;
+
+    let trace_length = message.trace_length;
+    if trace_length < TraceInfo::MIN_TRACE_LENGTH as u64
+        || !trace_length.is_power_of_two()
+        || trace_length > usize::MAX as u64
+    {
+        return Err(invalid(format!("trace length {trace_length} is not valid")));
+    }
+    if message.trace_meta.len() > TraceInfo::MAX_META_LENGTH {
+        return Err(invalid("trace metadata is too long"));
+    }
+    let trace_info =
+        TraceInfo::new_multi_segment(layout, trace_length as usize, message.trace_meta.clone());
+
+    let options = required(&message.options, "options")?.to_options()?;
+    let context = proof::Context::new::<B>(&trace_info, options);
+    match &message.hash_function {
+        None => Ok(context),
+        Some(name) => {
+            let hash_fn = HashFunction::from_str(name).map_err(invalid)?;
+            if H::HASH_FUNCTION != Some(hash_fn) {
+                return Err(invalid(format!(
+                    "hash function {hash_fn} does not match the specified hash function"
+                )));
+            }
+            Ok(context.with_hash_function::<H>())
+        }
+    }
+}
+
+fn commitments_to_message<H: Hasher>(
+    commitments: &Commitments,
+) -> Result<Vec<Vec<u8>>, DeserializationError> {
+    let bytes = commitments.to_bytes();
+    let mut reader = SliceReader::new(&bytes);
+    let num_bytes = reader.read_u16()? as usize;
+    let digests = reader.read_vec(num_bytes)?;
+
+    let digest_size = H::Digest::default().to_bytes().len();
+    if digests.len() % digest_size != 0 {
+        return Err(invalid("commitments cannot be split into digests"));
+    }
+    Ok(digests.chunks(digest_size).map(|digest| digest.to_vec()).collect())
+}
+
+fn commitments_from_message<H: Hasher>(
+    message: &[Vec<u8>],
+) -> Result<Commitments, DeserializationError> {
+    let mut digests = Vec::new();
+    for digest in message {
+        H::Digest::read_from_bytes(digest)?;
+        digests.extend_from_slice(digest);
+    }
+
+    let mut bytes = Vec::new();
+    bytes.write_u16(to_u16(digests.len(), "commitments")?);
+    bytes.write_bytes(&digests);
+    Commitments::read_from_bytes(&bytes)
+}
+
+fn ood_frame_to_message<B: StarkField>(
+    frame: &proof::OodFrame,
+) -> Result<OodFrame, DeserializationError> {
+    let bytes = frame.to_bytes();
+    let mut reader = SliceReader::new(&bytes);
+    let num_trace_state_bytes = reader.read_u16()? as usize;
+    let trace_states = reader.read_vec(num_trace_state_bytes)?;
+    let num_evaluation_bytes = reader.read_u16()? as usize;
+    let evaluations = reader.read_vec(num_evaluation_bytes)?;
+
+    // trace states are prefixed with the number of rows in the frame unless they are empty
+    let (frame_size, trace_states) = match trace_states.split_first() {
+        Some((&frame_size, trace_states)) => (Some(frame_size as u32), trace_states),
+        None => (None, &trace_states[..]),
+    };
+    check_elements::<B>(trace_states, "trace_states")?;
+    check_elements::<B>(&evaluations, "constraint_evaluations")?;
+
+    Ok(OodFrame {
+        frame_size,
+        trace_states: trace_states.to_vec(),
+        constraint_evaluations: evaluations,
+    })
+}
+
+fn ood_frame_from_message<B: StarkField>(
+    message: &OodFrame,
+) -> Result<proof::OodFrame, DeserializationError> {
+    let mut trace_states = match message.frame_size {
+        None => Vec::new(),
+        Some(frame_size) => vec![to_u8(frame_size, "frame_size")?],
+    };
+    check_elements::<B>(&message.trace_states, "trace_states")?;
+    trace_states.extend_from_slice(&message.trace_states);
+    check_elements::<B>(&message.constraint_evaluations, "constraint_evaluations")?;
+
+    let mut bytes = Vec::new();
+    bytes.write_u16(to_u16(trace_states.len(), "trace_states")?);
+    bytes.write_bytes(&trace_states);
+    bytes.write_u16(to_u16(message.constraint_evaluations.len(), "constraint_evaluations")?);
+    bytes.write_bytes(&message.constraint_evaluations);
+    proof::OodFrame::read_from_bytes(&bytes)
+}
+
+fn fri_proof_to_message<B: StarkField>(
+    proof: &fri::FriProof,
+) -> Result<FriProof, DeserializationError> {
+    let bytes = proof.to_bytes();
+    let mut reader = SliceReader::new(&bytes);
+
+    let num_layers = reader.read_u8()? as usize;
+    let is_deduplicated = reader.read_bool()?;
+    let mut layers = Vec::with_capacity(num_layers);
+    for _ in 0..num_layers {
+        let num_queries = if is_deduplicated {
+            Some(reader.read_u8()? as u32)
+        } else {
+            None
+        };
+        let num_value_bytes = reader.read_u32()? as usize;
+        let values = reader.read_vec(num_value_bytes)?;
+        check_elements::<B>(&values, "values")?;
+        let num_path_bytes = reader.read_u32()? as usize;
+        let paths = reader.read_vec(num_path_bytes)?;
+        layers.push(FriLayer {
+            num_queries,
+            values,
+            paths,
+        });
+    }
+
+    let num_remainder_bytes = reader.read_u16()? as usize;
+    let remainder = reader.read_vec(num_remainder_bytes)?;
+    check_elements::<B>(&remainder, "remainder")?;
+    let num_ood_bytes = reader.read_u16()? as usize;
+    let ood_evaluations = reader.read_vec(num_ood_bytes)?;
+    check_elements::<B>(&ood_evaluations, "ood_evaluations")?;
+    let num_nonces = reader.read_u8()? as usize;
+    let mut layer_nonces = Vec::with_capacity(num_nonces);
+    for _ in 0..num_nonces {
+        layer_nonces.push(reader.read_u64()?);
+    }
+
+    Ok(FriProof {
+        layers,
+        remainder,
+        ood_evaluations,
+        layer_nonces,
+        num_partitions: proof.num_partitions() as u32,
+    })
+}
+
+/// Writes the binary form of the FRI proof described by `message` into `target`.
+fn write_fri_proof<B: StarkField>(
+    message: &FriProof,
+    target: &mut Vec<u8>,
+) -> Result<(), DeserializationError> {
+    let is_deduplicated = message.layers.iter().any(|layer| layer.num_queries.is_some());
+    target.write_u8(to_u8(message.layers.len(), "layers")?);
+    target.write_bool(is_deduplicated);
+    for layer in message.layers.iter() {
+        match (is_deduplicated, layer.num_queries) {
+            (false, _) => (),
+            (true, None) => {
+                return Err(invalid("either all or none of FRI layers must be deduplicated"))
+            }
+            (true, Some(num_queries)) => target.write_u8(to_u8(num_queries, "num_queries")?),
+        }
+        check_elements::<B>(&layer.values, "values")?;
+        target.write_u32(to_u32(layer.values.len(), "values")?);
+        target.write_bytes(&layer.values);
+        target.write_u32(to_u32(layer.paths.len(), "paths")?);
+        target.write_bytes(&layer.paths);
+    }
+
+    for (elements, key) in
+        [(&message.remainder, "remainder"), (&message.ood_evaluations, "ood_evaluations")]
+    {
+        check_elements::<B>(elements, key)?;
+        target.write_u16(to_u16(elements.len(), key)?);
+        target.write_bytes(elements);
+    }
+
+    target.write_u8(to_u8(message.layer_nonces.len(), "layer_nonces")?);
+    for &nonce in message.layer_nonces.iter() {
+        target.write_u64(nonce);
+    }
+
+    if !message.num_partitions.is_power_of_two() {
+        return Err(invalid("number of FRI partitions must be a power of two"));
+    }
+    target.write_u8(message.num_partitions.ilog2() as u8);
+    Ok(())
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns an error if the specified bytes are not a sequence of valid elements of `B`.
+fn check_elements<B: StarkField>(bytes: &[u8], key: &str) -> Result<(), DeserializationError> {
+    if bytes.len() % B::ELEMENT_BYTES != 0 {
+        return Err(invalid(format!("'{key}' cannot be split into field elements")));
+    }
+    for element in bytes.chunks(B::ELEMENT_BYTES) {
+        B::read_from_bytes(element)?;
+    }
+    Ok(())
+}
+
+fn decode<M: Message + Default>(bytes: &[u8]) -> Result<M, DeserializationError> {
+    M::decode(bytes).map_err(|err| invalid(format!("invalid protobuf: {err}")))
+}
+
+fn required<'a, T>(value: &'a Option<T>, key: &str) -> Result<&'a T, DeserializationError> {
+    value.as_ref().ok_or_else(|| invalid(format!("missing field '{key}'")))
+}
+
+fn invalid(message: impl ToString) -> DeserializationError {
+    DeserializationError::InvalidValue(message.to_string())
+}
+
+fn unknown(kind: &str, value: i32) -> DeserializationError {
+    invalid(format!("unknown {kind} {value}"))
+}
+
+fn to_u8<T: TryInto<u8>>(value: T, key: &str) -> Result<u8, DeserializationError> {
+    value.try_into().map_err(|_| invalid(format!("'{key}' is too large")))
+}
+
+fn to_u16(value: usize, key: &str) -> Result<u16, DeserializationError> {
+    u16::try_from(value).map_err(|_| invalid(format!("'{key}' is too large")))
+}
+
+fn to_u32(value: usize, key: &str) -> Result<u32, DeserializationError> {
+    u32::try_from(value).map_err(|_| invalid(format!("'{key}' is too large")))
+}
@@ -0,0 +1,549 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+//! Symbolic evaluation of transition constraints.
+//!
+//! Transition constraints of an AIR are usually written as plain [FieldElement] arithmetic in
+//! [Air::evaluate_transition()]. Evaluating this method over symbolic field elements records the
+//! performed operations as a [ConstraintGraph], which can then be compiled into other targets
+//! (e.g., a Solidity verifier or a GPU kernel) without any changes to the AIR.
+
+use crate::{Air, EvaluationFrame};
+use core::{
+    any::Any,
+    cell::RefCell,
+    fmt::{self, Debug, Display, Formatter},
+    marker::PhantomData,
+    ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign},
+};
+use math::{
+    fields::{f128, f62, f64},
+    ExtensionOf, FieldElement, StarkField,
+};
+use utils::{
+    AsBytes, ByteReader, ByteWriter, Deserializable, DeserializationError, Randomizable,
+    Serializable,
+};
+
+// CONSTRAINT GRAPH
+// ================================================================================================
+
+/// A node of a [ConstraintGraph].
+///
+/// Operands of a node always refer to nodes which precede it in the graph.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Node<B: StarkField> {
+    /// A constant value.
+    Constant(B),
+    /// Value of the specified column in the current row of the evaluation frame.
+    Current(usize),
+    /// Value of the specified column in the next row of the evaluation frame.
+    Next(usize),
+    /// Value of the specified periodic column.
+    Periodic(usize),
+    /// Sum of two nodes.
+    Add(usize, usize),
+    /// Difference of two nodes.
+    Sub(usize, usize),
+    /// Product of two nodes.
+    Mul(usize, usize),
+    /// Negation of a node.
+    Neg(usize),
+    /// Multiplicative inverse of a node.
+    Inv(usize),
+    /// A node raised to a constant power.
+    Exp(usize, u128),
+}
+
+/// Transition constraints of the main trace segment of a computation expressed as a directed
+/// acyclic graph of field operations.
+///
+/// The graph is obtained by evaluating [Air::evaluate_transition()] over symbolic field elements
+/// which record operations performed on them instead of computing their results. Thus, any AIR
+/// whose transition constraints are expressed via [FieldElement] arithmetic can be turned into a
+/// graph, without the AIR being aware of it. Constant sub-expressions are folded, and identical
+/// sub-expressions are recorded only once.
+///
+/// Symbolic field elements support only arithmetic operations; calling any other method of
+/// [FieldElement] (e.g., serializing an element) while the graph is being built results in a
+/// panic.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConstraintGraph<B: StarkField> {
+    nodes: Vec<Node<B>>,
+    outputs: Vec<usize>,
+}
+
+impl<B: SymbolicField> ConstraintGraph<B> {
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+    /// Returns a graph of main trace segment transition constraints of the specified AIR.
+    pub fn new<A: Air<BaseField = B>>(air: &A) -> Self {
+        B::build_constraint_graph(air)
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns nodes of this graph in topological order.
+    pub fn nodes(&self) -> &[Node<B>] {
+        &self.nodes
+    }
+
+    /// Returns indexes of nodes holding results of evaluating each transition constraint.
+    pub fn outputs(&self) -> &[usize] {
+        &self.outputs
+    }
+
+    // EVALUATOR
+    // --------------------------------------------------------------------------------------------
+
+    /// Evaluates the transition constraints described by this graph over the specified
+    /// evaluation frame and periodic values.
+    pub fn evaluate(&self, current: &[B], next: &[B], periodic_values: &[B]) -> Vec<B> {
+        let mut values: Vec<B> = Vec::with_capacity(self.nodes.len());
+        for node in self.nodes.iter() {
+            let value = match *node {
+                Node::Constant(value) => value,
+                Node::Current(i) => current[i],
+                Node::Next(i) => next[i],
+                Node::Periodic(i) => periodic_values[i],
+                Node::Add(a, b) => values[a] + values[b],
+                Node::Sub(a, b) => values[a] - values[b],
+                Node::Mul(a, b) => values[a] * values[b],
+                Node::Neg(a) => -values[a],
+                Node::Inv(a) => values[a].inv(),
+                Node::Exp(a, power) => exp(values[a], power),
+            };
+            values.push(value);
+        }
+        self.outputs.iter().map(|&i| values[i]).collect()
+    }
+}
+
+// SYMBOLIC FIELD
+// ================================================================================================
+
+/// A base field over which transition constraints can be evaluated symbolically.
+///
+/// This trait is implemented for all base fields provided by the `math` crate.
+pub trait SymbolicField: StarkField + 'static {
+    /// Returns a graph of main trace segment transition constraints of the specified AIR.
+    fn build_constraint_graph<A: Air<BaseField = Self>>(air: &A) -> ConstraintGraph<Self>;
+}
+
+// symbolic elements can be converted from base field elements only for concrete fields, because
+// a blanket conversion would conflict with conversions from integers
+macro_rules! impl_symbolic_field {
+    ($($field:ty),*) => {
+        $(impl From<$field> for Sym<$field> {
+            fn from(value: $field) -> Self {
+                Self::insert(Node::Constant(value))
+            }
+        }
+
+        impl SymbolicField for $field {
+            fn build_constraint_graph<A: Air<BaseField = Self>>(air: &A) -> ConstraintGraph<Self> {
+                build_constraint_graph(air)
+            }
+        })*
+    };
+}
+
+impl_symbolic_field!(f62::BaseElement, f64::BaseElement, f128::BaseElement);
+
+/// Evaluates transition constraints of the specified AIR over symbolic field elements and
+/// returns the graph of recorded operations.
+fn build_constraint_graph<B, A>(air: &A) -> ConstraintGraph<B>
+where
+    B: StarkField + 'static,
+    A: Air<BaseField = B>,
+    Sym<B>: FieldElement<BaseField = B>,
+{
+    ARENA.with(|arena| {
+        *arena.borrow_mut() = Some(Box::new(vec![Node::Constant(B::ZERO), Node::Constant(B::ONE)]))
+    });
+
+    let width = air.trace_layout().main_trace_width();
+    let current = (0..width).map(|i| Sym::insert(Node::Current(i))).collect();
+    let next = (0..width).map(|i| Sym::insert(Node::Next(i))).collect();
+    let frame = EvaluationFrame::from_rows(current, next);
+    let periodic_values = (0..air.get_periodic_column_values().len())
+        .map(|i| Sym::insert(Node::Periodic(i)))
+        .collect::<Vec<_>>();
+
+    let mut result = vec![Sym::ZERO; air.context().num_main_transition_constraints()];
+    air.evaluate_transition(&frame, &periodic_values, &mut result);
+
+    let nodes = ARENA
+        .with(|arena| arena.borrow_mut().take())
+        .and_then(|nodes| nodes.downcast::<Vec<Node<B>>>().ok())
+        .expect("symbolic arena has been replaced while building a constraint graph");
+
+    prune(*nodes, result.iter().map(|value| value.index).collect())
+}
+
+// SYMBOLIC FIELD ELEMENT
+// ================================================================================================
+
+/// Indexes of nodes holding constants zero and one; these nodes are recorded before any other
+/// node when a constraint graph is being built.
+const ZERO: usize = 0;
+const ONE: usize = 1;
+
+thread_local! {
+    /// Nodes recorded while a constraint graph is being built on the current thread.
+    static ARENA: RefCell<Option<Box<dyn Any>>> = RefCell::new(None);
+}
+
+/// A field element which refers to a node of the constraint graph being built.
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct Sym<B: StarkField> {
+    index: usize,
+    _field: PhantomData<B>,
+}
+
+impl<B: StarkField + 'static> Sym<B> {
+    const fn at(index: usize) -> Self {
+        Self {
+            index,
+            _field: PhantomData,
+        }
+    }
+
+    /// Records the specified node (unless an identical node has been recorded already), and
+    /// returns a symbolic element referring to it.
+    fn insert(node: Node<B>) -> Self {
+        ARENA.with(|arena| {
+            let mut arena = arena.borrow_mut();
+            let nodes =
+                arena.as_mut().and_then(|nodes| nodes.downcast_mut::<Vec<Node<B>>>()).expect(
+                    "symbolic field elements can be used only while building a constraint graph",
+                );
+            let index = match nodes.iter().position(|n| *n == node) {
+                Some(index) => index,
+                None => {
+                    nodes.push(node);
+                    nodes.len() - 1
+                }
+            };
+            Self::at(index)
+        })
+    }
+
+    /// Returns the value of this element if it refers to a constant node.
+    fn constant(self) -> Option<B> {
+        ARENA.with(|arena| {
+            match arena.borrow().as_ref()?.downcast_ref::<Vec<Node<B>>>()?[self.index] {
+                Node::Constant(value) => Some(value),
+                _ => None,
+            }
+        })
+    }
+
+    fn binary(self, other: Self, op: fn(B, B) -> B, node: fn(usize, usize) -> Node<B>) -> Self {
+        match (self.constant(), other.constant()) {
+            (Some(a), Some(b)) => Self::insert(Node::Constant(op(a, b))),
+            _ => Self::insert(node(self.index, other.index)),
+        }
+    }
+
+    fn invert(self) -> Self {
+        match self.constant() {
+            Some(value) => Self::insert(Node::Constant(value.inv())),
+            None => Self::insert(Node::Inv(self.index)),
+        }
+    }
+
+    fn unsupported(operation: &str) -> ! {
+        panic!("{operation} is not supported by symbolic field elements")
+    }
+}
+
+impl<B> FieldElement for Sym<B>
+where
+    B: StarkField + 'static,
+    Self: From<B>,
+{
+    type PositiveInteger = u128;
+    type BaseField = B;
+
+    const EXTENSION_DEGREE: usize = 1;
+    const ELEMENT_BYTES: usize = B::ELEMENT_BYTES;
+    const IS_CANONICAL: bool = false;
+    const ZERO: Self = Self::at(ZERO);
+    const ONE: Self = Self::at(ONE);
+
+    fn exp(self, power: Self::PositiveInteger) -> Self {
+        self.exp_vartime(power)
+    }
+
+    fn exp_vartime(self, power: Self::PositiveInteger) -> Self {
+        match (self.constant(), power) {
+            (Some(value), _) => Self::insert(Node::Constant(exp(value, power))),
+            (None, 0) => Self::ONE,
+            (None, 1) => self,
+            (None, _) => Self::insert(Node::Exp(self.index, power)),
+        }
+    }
+
+    fn inv(self) -> Self {
+        self.invert()
+    }
+
+    fn conjugate(&self) -> Self {
+        *self
+    }
+
+    fn base_element(&self, _i: usize) -> Self::BaseField {
+        Self::unsupported("conversion into base field elements")
+    }
+
+    fn slice_as_base_elements(_elements: &[Self]) -> &[Self::BaseField] {
+        Self::unsupported("conversion into base field elements")
+    }
+
+    fn slice_from_base_elements(_elements: &[Self::BaseField]) -> &[Self] {
+        Self::unsupported("conversion from base field elements")
+    }
+
+    fn elements_as_bytes(_elements: &[Self]) -> &[u8] {
+        Self::unsupported("conversion into bytes")
+    }
+
+    unsafe fn bytes_as_elements(_bytes: &[u8]) -> Result<&[Self], DeserializationError> {
+        Err(DeserializationError::InvalidValue(
+            "symbolic field elements cannot be read from bytes".into(),
+        ))
+    }
+}
+
+impl<B: StarkField + 'static> ExtensionOf<B> for Sym<B>
+where
+    Self: From<B>,
+{
+    fn mul_base(self, other: B) -> Self {
+        self * Self::from(other)
+    }
+}
+
+impl<B: StarkField + 'static> Default for Sym<B> {
+    fn default() -> Self {
+        Self::at(ZERO)
+    }
+}
+
+impl<B: StarkField> Debug for Sym<B> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "v{}", self.index)
+    }
+}
+
+impl<B: StarkField> Display for Sym<B> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "v{}", self.index)
+    }
+}
+
+// OVERLOADED OPERATORS
+// ------------------------------------------------------------------------------------------------
+
+impl<B: StarkField + 'static> Add for Sym<B> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        if self.index == ZERO {
+            rhs
+        } else if rhs.index == ZERO {
+            self
+        } else {
+            self.binary(rhs, |a, b| a + b, Node::Add)
+        }
+    }
+}
+
+impl<B: StarkField + 'static> AddAssign for Sym<B> {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs
+    }
+}
+
+impl<B: StarkField + 'static> Sub for Sym<B> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        if rhs.index == ZERO {
+            self
+        } else {
+            self.binary(rhs, |a, b| a - b, Node::Sub)
+        }
+    }
+}
+
+impl<B: StarkField + 'static> SubAssign for Sym<B> {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs
+    }
+}
+
+impl<B: StarkField + 'static> Mul for Sym<B> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        if self.index == ZERO || rhs.index == ZERO {
+            Self::at(ZERO)
+        } else if self.index == ONE {
+            rhs
+        } else if rhs.index == ONE {
+            self
+        } else {
+            self.binary(rhs, |a, b| a * b, Node::Mul)
+        }
+    }
+}
+
+impl<B: StarkField + 'static> MulAssign for Sym<B> {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs
+    }
+}
+
+impl<B: StarkField + 'static> Div for Sym<B> {
+    type Output = Self;
+
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, rhs: Self) -> Self {
+        self * rhs.invert()
+    }
+}
+
+impl<B: StarkField + 'static> DivAssign for Sym<B> {
+    fn div_assign(&mut self, rhs: Self) {
+        *self = *self / rhs
+    }
+}
+
+impl<B: StarkField + 'static> Neg for Sym<B> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        match self.constant() {
+            Some(value) => Self::insert(Node::Constant(-value)),
+            None => Self::insert(Node::Neg(self.index)),
+        }
+    }
+}
+
+// TYPE CONVERSIONS
+// ------------------------------------------------------------------------------------------------
+
+macro_rules! impl_from_int {
+    ($($int:ty),*) => {
+        $(impl<B: StarkField + 'static> From<$int> for Sym<B> {
+            fn from(value: $int) -> Self {
+                Self::insert(Node::Constant(B::from(value)))
+            }
+        })*
+    };
+}
+
+impl_from_int!(u8, u16, u32, u64, u128);
+
+impl<B: StarkField> TryFrom<&[u8]> for Sym<B> {
+    type Error = DeserializationError;
+
+    fn try_from(_bytes: &[u8]) -> Result<Self, Self::Error> {
+        Err(DeserializationError::InvalidValue(
+            "symbolic field elements cannot be read from bytes".into(),
+        ))
+    }
+}
+
+impl<B: StarkField + 'static> AsBytes for Sym<B> {
+    fn as_bytes(&self) -> &[u8] {
+        Self::unsupported("conversion into bytes")
+    }
+}
+
+impl<B: StarkField> Randomizable for Sym<B> {
+    const VALUE_SIZE: usize = B::VALUE_SIZE;
+
+    fn from_random_bytes(_bytes: &[u8]) -> Option<Self> {
+        None
+    }
+}
+
+// SERIALIZATION / DESERIALIZATION
+// ------------------------------------------------------------------------------------------------
+
+impl<B: StarkField + 'static> Serializable for Sym<B> {
+    fn write_into<W: ByteWriter>(&self, _target: &mut W) {
+        Self::unsupported("serialization")
+    }
+}
+
+impl<B: StarkField> Deserializable for Sym<B> {
+    fn read_from<R: ByteReader>(_source: &mut R) -> Result<Self, DeserializationError> {
+        Err(DeserializationError::InvalidValue(
+            "symbolic field elements cannot be deserialized".into(),
+        ))
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns a graph consisting only of nodes on which the specified outputs depend; nodes are
+/// re-indexed while preserving their relative order.
+fn prune<B: StarkField>(nodes: Vec<Node<B>>, outputs: Vec<usize>) -> ConstraintGraph<B> {
+    let mut used = vec![false; nodes.len()];
+    outputs.iter().for_each(|&i| used[i] = true);
+    for i in (0..nodes.len()).rev() {
+        if used[i] {
+            match nodes[i] {
+                Node::Add(a, b) | Node::Sub(a, b) | Node::Mul(a, b) => {
+                    used[a] = true;
+                    used[b] = true;
+                }
+                Node::Neg(a) | Node::Inv(a) | Node::Exp(a, _) => used[a] = true,
+                _ => (),
+            }
+        }
+    }
+
+    let mut new_index = vec![0; nodes.len()];
+    let mut result = Vec::new();
+    for (i, node) in nodes.into_iter().enumerate() {
+        if !used[i] {
+            continue;
+        }
+        new_index[i] = result.len();
+        result.push(match node {
+            Node::Add(a, b) => Node::Add(new_index[a], new_index[b]),
+            Node::Sub(a, b) => Node::Sub(new_index[a], new_index[b]),
+            Node::Mul(a, b) => Node::Mul(new_index[a], new_index[b]),
+            Node::Neg(a) => Node::Neg(new_index[a]),
+            Node::Inv(a) => Node::Inv(new_index[a]),
+            Node::Exp(a, power) => Node::Exp(new_index[a], power),
+            node => node,
+        });
+    }
+
+    ConstraintGraph {
+        nodes: result,
+        outputs: outputs.into_iter().map(|i| new_index[i]).collect(),
+    }
+}
+
+/// Raises `base` to the specified power.
+fn exp<B: StarkField>(base: B, power: u128) -> B {
+    let mut result = B::ONE;
+    for i in (0..128 - power.leading_zeros()).rev() {
+        result = result.square();
+        if (power >> i) & 1 == 1 {
+            result *= base;
+        }
+    }
+    result
+}
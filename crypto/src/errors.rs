@@ -72,6 +72,9 @@ pub enum RandomCoinError {
     /// The required number of integer values could not be drawn from the specified domain after
     /// the specified number of tries.
     FailedToDrawIntegers(usize, usize, usize),
+    /// Integer values could not be drawn because the provided sampler does not implement the
+    /// sampling strategy with which the values are required to be drawn.
+    SamplerMismatch,
 }
 
 impl fmt::Display for RandomCoinError {
@@ -86,6 +89,9 @@ impl fmt::Display for RandomCoinError {
                     "needed to draw {num_expected} integers from a domain, but drew only {num_actual} after {num_tries} tries"
                 )
             }
+            Self::SamplerMismatch => {
+                write!(f, "sampler does not implement the required sampling strategy")
+            }
         }
     }
 }
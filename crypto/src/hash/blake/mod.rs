@@ -3,14 +3,25 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use super::{ByteDigest, ElementHasher, Hasher};
+use super::{ByteDigest, ElementHasher, HashFunction, Hasher};
 use core::{convert::TryInto, fmt::Debug, marker::PhantomData};
 use math::{FieldElement, StarkField};
 use utils::ByteWriter;
 
+#[cfg(feature = "concurrent")]
+use utils::collections::Vec;
+
 #[cfg(test)]
 mod tests;
 
+// CONSTANTS
+// ================================================================================================
+
+/// Inputs of at least this many bytes are hashed using multiple threads when `concurrent` feature
+/// is enabled; for smaller inputs, the overhead of splitting the work outweighs the benefits.
+#[cfg(feature = "concurrent")]
+const MIN_CONCURRENT_INPUT_SIZE: usize = 128 * 1024;
+
 // BLAKE3 256-BIT OUTPUT
 // ================================================================================================
 
@@ -24,8 +35,10 @@ impl<B: StarkField> Hasher for Blake3_256<B> {
 
     const COLLISION_RESISTANCE: u32 = 128;
 
+    const HASH_FUNCTION: Option<HashFunction> = Some(HashFunction::Blake3_256);
+
     fn hash(bytes: &[u8]) -> Self::Digest {
-        ByteDigest(*blake3::hash(bytes).as_bytes())
+        ByteDigest(*hash_bytes(bytes).as_bytes())
     }
 
     fn merge(values: &[Self::Digest; 2]) -> Self::Digest {
@@ -48,13 +61,11 @@ impl<B: StarkField> ElementHasher for Blake3_256<B> {
             // when element's internal and canonical representations are the same, we can hash
             // element bytes directly
             let bytes = E::elements_as_bytes(elements);
-            ByteDigest(*blake3::hash(bytes).as_bytes())
+            ByteDigest(*hash_bytes(bytes).as_bytes())
         } else {
             // when elements' internal and canonical representations differ, we need to serialize
             // them before hashing
-            let mut hasher = BlakeHasher::new();
-            hasher.write(elements);
-            ByteDigest(hasher.finalize())
+            ByteDigest(*hash_serialized(elements).as_bytes())
         }
     }
 }
@@ -72,8 +83,10 @@ impl<B: StarkField> Hasher for Blake3_192<B> {
 
     const COLLISION_RESISTANCE: u32 = 96;
 
+    const HASH_FUNCTION: Option<HashFunction> = Some(HashFunction::Blake3_192);
+
     fn hash(bytes: &[u8]) -> Self::Digest {
-        let result = blake3::hash(bytes);
+        let result = hash_bytes(bytes);
         ByteDigest(result.as_bytes()[..24].try_into().unwrap())
     }
 
@@ -100,15 +113,13 @@ impl<B: StarkField> ElementHasher for Blake3_192<B> {
             // when element's internal and canonical representations are the same, we can hash
             // element bytes directly
             let bytes = E::elements_as_bytes(elements);
-            let result = blake3::hash(bytes);
+            let result = hash_bytes(bytes);
             ByteDigest(result.as_bytes()[..24].try_into().unwrap())
         } else {
             // when elements' internal and canonical representations differ, we need to serialize
             // them before hashing
-            let mut hasher = BlakeHasher::new();
-            hasher.write(elements);
-            let result = hasher.finalize();
-            ByteDigest(result[..24].try_into().unwrap())
+            let result = hash_serialized(elements);
+            ByteDigest(result.as_bytes()[..24].try_into().unwrap())
         }
     }
 }
@@ -124,8 +135,8 @@ impl BlakeHasher {
         Self(blake3::Hasher::new())
     }
 
-    pub fn finalize(&self) -> [u8; 32] {
-        *self.0.finalize().as_bytes()
+    pub fn finalize(&self) -> blake3::Hash {
+        self.0.finalize()
     }
 }
 
@@ -138,3 +149,34 @@ impl ByteWriter for BlakeHasher {
         self.0.update(values);
     }
 }
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Hashes the provided bytes; when `concurrent` feature is enabled, large inputs are hashed using
+/// multiple threads.
+fn hash_bytes(bytes: &[u8]) -> blake3::Hash {
+    #[cfg(feature = "concurrent")]
+    if bytes.len() >= MIN_CONCURRENT_INPUT_SIZE {
+        let mut hasher = blake3::Hasher::new();
+        hasher.update_rayon(bytes);
+        return hasher.finalize();
+    }
+    blake3::hash(bytes)
+}
+
+/// Serializes the provided elements into their canonical representation and hashes the result.
+fn hash_serialized<E: FieldElement>(elements: &[E]) -> blake3::Hash {
+    // large inputs are serialized into a single buffer so that they could be hashed using
+    // multiple threads; this results in the same digest as incremental hashing
+    #[cfg(feature = "concurrent")]
+    if elements.len() * E::ELEMENT_BYTES >= MIN_CONCURRENT_INPUT_SIZE {
+        let mut bytes = Vec::with_capacity(elements.len() * E::ELEMENT_BYTES);
+        bytes.write(elements);
+        return hash_bytes(&bytes);
+    }
+
+    let mut hasher = BlakeHasher::new();
+    hasher.write(elements);
+    hasher.finalize()
+}
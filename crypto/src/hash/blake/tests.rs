@@ -4,7 +4,7 @@
 // LICENSE file in the root directory of this source tree.
 
 use super::{Blake3_256, ElementHasher, Hasher};
-use math::{fields::f62::BaseElement, FieldElement};
+use math::{fields::f62::BaseElement, FieldElement, StarkField};
 use rand_utils::rand_array;
 
 #[test]
@@ -28,3 +28,17 @@ fn hash_elements_padding() {
     let r2 = Blake3_256::hash_elements(&e2);
     assert_ne!(r1, r2);
 }
+
+#[test]
+fn hash_large_inputs() {
+    // large inputs may be hashed using multiple threads, but this must not affect the digest
+    let bytes = (0..300_000).map(|i| i as u8).collect::<Vec<_>>();
+    assert_eq!(*blake3::hash(&bytes).as_bytes(), Blake3_256::<BaseElement>::hash(&bytes).0);
+
+    let elements = (0..40_000).map(BaseElement::new).collect::<Vec<_>>();
+    let mut hasher = blake3::Hasher::new();
+    for element in elements.iter() {
+        hasher.update(&element.as_int().to_le_bytes());
+    }
+    assert_eq!(*hasher.finalize().as_bytes(), Blake3_256::hash_elements(&elements).0);
+}
@@ -3,15 +3,15 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use core::{fmt::Debug, slice};
+use core::{fmt, fmt::Debug, slice, str::FromStr};
 use math::{FieldElement, StarkField};
-use utils::{ByteReader, Deserializable, DeserializationError, Serializable};
+use utils::{string::String, ByteReader, Deserializable, DeserializationError, Serializable};
 
 mod blake;
 pub use blake::{Blake3_192, Blake3_256};
 
 mod sha;
-pub use sha::Sha3_256;
+pub use sha::{Keccak256, Sha3_256};
 
 mod mds;
 
@@ -37,6 +37,10 @@ pub trait Hasher {
     /// Collision resistance of the hash function measured in bits.
     const COLLISION_RESISTANCE: u32;
 
+    /// Identifies this hash function among hash functions which can be selected at runtime, or
+    /// `None` if this hash function cannot be selected at runtime (see [HashFunction]).
+    const HASH_FUNCTION: Option<HashFunction> = None;
+
     /// Returns a hash of the provided sequence of bytes.
     fn hash(bytes: &[u8]) -> Self::Digest;
 
@@ -62,6 +66,66 @@ pub trait ElementHasher: Hasher {
         E: FieldElement<BaseField = Self::BaseField>;
 }
 
+// HASH FUNCTION SELECTOR
+// ================================================================================================
+
+/// Identifies one of the hash functions which can be used with any STARK field.
+///
+/// This enum is intended for cases when a hash function needs to be selected at runtime (e.g.,
+/// from a configuration file), rather than at compile time via a type parameter. Only hash
+/// functions which are generic over the base field are included; algebraic hash functions such
+/// as [Rp64_256] work only in a specific field, and thus, need to be selected statically.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum HashFunction {
+    /// BLAKE3 hash function with 192 bit output ([Blake3_192]).
+    Blake3_192,
+    /// BLAKE3 hash function with 256 bit output ([Blake3_256]).
+    Blake3_256,
+    /// SHA3 hash function with 256 bit output ([Sha3_256]).
+    Sha3_256,
+    /// Keccak hash function with 256 bit output ([Keccak256]).
+    Keccak256,
+}
+
+impl HashFunction {
+    /// Returns collision resistance of this hash function (in bits).
+    pub fn collision_resistance(&self) -> u32 {
+        // collision resistance of these hash functions does not depend on the base field
+        type B = math::fields::f64::BaseElement;
+        match self {
+            Self::Blake3_192 => <Blake3_192<B> as Hasher>::COLLISION_RESISTANCE,
+            Self::Blake3_256 => <Blake3_256<B> as Hasher>::COLLISION_RESISTANCE,
+            Self::Sha3_256 => <Sha3_256<B> as Hasher>::COLLISION_RESISTANCE,
+            Self::Keccak256 => <Keccak256<B> as Hasher>::COLLISION_RESISTANCE,
+        }
+    }
+}
+
+impl fmt::Display for HashFunction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Blake3_192 => write!(f, "blake3_192"),
+            Self::Blake3_256 => write!(f, "blake3_256"),
+            Self::Sha3_256 => write!(f, "sha3_256"),
+            Self::Keccak256 => write!(f, "keccak256"),
+        }
+    }
+}
+
+impl FromStr for HashFunction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "blake3_192" => Ok(Self::Blake3_192),
+            "blake3_256" => Ok(Self::Blake3_256),
+            "sha3_256" => Ok(Self::Sha3_256),
+            "keccak256" => Ok(Self::Keccak256),
+            _ => Err(format!("'{s}' is not a supported hash function")),
+        }
+    }
+}
+
 // DIGEST TRAIT
 // ================================================================================================
 
@@ -78,6 +142,31 @@ pub trait Digest:
     fn as_bytes(&self) -> [u8; 32];
 }
 
+/// Defines a digest which consists of base field elements.
+///
+/// Digests of algebraic hash functions (e.g., [Rp64_256]) are arrays of field elements. This
+/// trait exposes these elements directly, so that commitments, Merkle tree nodes, and states of
+/// random coins can be processed in field-element form (e.g., by a verifier running inside of a
+/// STARK) without converting digests to and from bytes. Deserialization of such digests rejects
+/// encodings of values which are not canonical field elements; thus, every serialized digest
+/// corresponds to exactly one sequence of elements.
+pub trait FieldDigest: Digest {
+    /// Base field of the elements of this digest.
+    type BaseField: StarkField;
+
+    /// Number of field elements in this digest.
+    const NUM_ELEMENTS: usize;
+
+    /// Returns the field elements of this digest.
+    fn as_elements(&self) -> &[Self::BaseField];
+
+    /// Returns a digest consisting of the specified field elements.
+    ///
+    /// # Panics
+    /// Panics if the number of `elements` is not [NUM_ELEMENTS](FieldDigest::NUM_ELEMENTS).
+    fn from_elements(elements: &[Self::BaseField]) -> Self;
+}
+
 // BYTE DIGEST
 // ================================================================================================
 
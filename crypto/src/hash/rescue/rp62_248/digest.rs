@@ -3,7 +3,7 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use super::{Digest, DIGEST_SIZE};
+use super::{Digest, FieldDigest, DIGEST_SIZE};
 use core::slice;
 use math::{fields::f62::BaseElement, StarkField};
 use utils::{ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable};
@@ -47,6 +47,20 @@ impl Digest for ElementDigest {
     }
 }
 
+impl FieldDigest for ElementDigest {
+    type BaseField = BaseElement;
+
+    const NUM_ELEMENTS: usize = DIGEST_SIZE;
+
+    fn as_elements(&self) -> &[BaseElement] {
+        &self.0
+    }
+
+    fn from_elements(elements: &[BaseElement]) -> Self {
+        Self(elements.try_into().expect("invalid number of digest elements"))
+    }
+}
+
 impl Default for ElementDigest {
     fn default() -> Self {
         ElementDigest([BaseElement::default(); DIGEST_SIZE])
@@ -68,13 +82,21 @@ impl Deserializable for ElementDigest {
         let v5 = source.read_u16()?;
         let v6 = source.read_u8()?;
 
-        let e1 = BaseElement::new(v1 & 0x3FFFFFFFFFFFFFFF);
-        let e2 = BaseElement::new(((v2 << 4) >> 2) | (v1 >> 62) & 0x3FFFFFFFFFFFFFFF);
-        let e3 = BaseElement::new(((v3 << 6) >> 2) | (v2 >> 60) & 0x3FFFFFFFFFFFFFFF);
-        let e4 =
-            BaseElement::new(v3 >> 58 | (v4 as u64) << 6 | (v5 as u64) << 38 | (v6 as u64) << 54);
-
-        Ok(Self([e1, e2, e3, e4]))
+        let values = [
+            v1 & 0x3FFFFFFFFFFFFFFF,
+            ((v2 << 4) >> 2) | (v1 >> 62) & 0x3FFFFFFFFFFFFFFF,
+            ((v3 << 6) >> 2) | (v2 >> 60) & 0x3FFFFFFFFFFFFFFF,
+            v3 >> 58 | (v4 as u64) << 6 | (v5 as u64) << 38 | (v6 as u64) << 54,
+        ];
+
+        // packed values are not reduced; non-canonical values are rejected
+        if let Some(value) = values.iter().find(|&&v| v >= BaseElement::MODULUS) {
+            return Err(DeserializationError::InvalidValue(format!(
+                "invalid field element: value {value} is greater than or equal to the field modulus"
+            )));
+        }
+
+        Ok(Self(values.map(BaseElement::new)))
     }
 }
 
@@ -84,7 +106,8 @@ impl Deserializable for ElementDigest {
 #[cfg(test)]
 mod tests {
 
-    use super::ElementDigest;
+    use super::{ElementDigest, FieldDigest};
+    use math::{fields::f62::BaseElement, FieldElement, StarkField};
     use rand_utils::rand_array;
     use utils::{Deserializable, Serializable, SliceReader};
 
@@ -101,4 +124,16 @@ mod tests {
 
         assert_eq!(d1, d2);
     }
+
+    #[test]
+    fn digest_elements() {
+        let elements: [BaseElement; 4] = rand_array();
+        let digest = ElementDigest::from_elements(&elements);
+        assert_eq!(&elements, digest.as_elements());
+
+        // elements are packed into 62 bits each, and non-canonical values are rejected
+        let mut bytes = ElementDigest::from_elements(&[BaseElement::ZERO; 4]).to_bytes();
+        bytes[..8].copy_from_slice(&BaseElement::MODULUS.to_le_bytes());
+        assert!(ElementDigest::read_from_bytes(&bytes).is_err());
+    }
 }
@@ -130,6 +130,32 @@ fn hash_padding() {
     let r1 = Rp62_248::hash(&[1_u8, 2, 3, 4, 5, 6, 7, 0, 0]);
     let r2 = Rp62_248::hash(&[1_u8, 2, 3, 4, 5, 6, 7, 0, 0, 0, 0]);
     assert_ne!(r1, r2);
+
+    // same as above but with inputs spanning multiple permutations
+    let bytes = [7_u8; 100];
+    let r1 = Rp62_248::hash(&bytes[..98]);
+    let r2 = Rp62_248::hash(&bytes[..99]);
+    let r3 = Rp62_248::hash(&[&bytes[..98], &[0]].concat());
+    assert_ne!(r1, r2);
+    assert_ne!(r1, r3);
+}
+
+#[test]
+fn hash_padding_known_answer() {
+    // the last chunk of a 98-byte input is 7 bytes long; digests of such inputs spanning multiple
+    // permutations must not change
+    let bytes: [u8; 98] = core::array::from_fn(|i| i as u8);
+    let digest = Rp62_248::hash(&bytes);
+    let actual: [u64; 4] = core::array::from_fn(|i| digest.as_elements()[i].as_int());
+    assert_eq!(
+        [
+            1125101293122417523,
+            4581876374206970706,
+            3038149178576909761,
+            1825410905207708211,
+        ],
+        actual
+    );
 }
 
 #[test]
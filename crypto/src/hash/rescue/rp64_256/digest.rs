@@ -3,7 +3,7 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use super::{Digest, DIGEST_SIZE};
+use super::{Digest, FieldDigest, DIGEST_SIZE};
 use core::slice;
 use math::{fields::f64::BaseElement, StarkField};
 use utils::{ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable};
@@ -43,6 +43,20 @@ impl Digest for ElementDigest {
     }
 }
 
+impl FieldDigest for ElementDigest {
+    type BaseField = BaseElement;
+
+    const NUM_ELEMENTS: usize = DIGEST_SIZE;
+
+    fn as_elements(&self) -> &[BaseElement] {
+        &self.0
+    }
+
+    fn from_elements(elements: &[BaseElement]) -> Self {
+        Self(elements.try_into().expect("invalid number of digest elements"))
+    }
+}
+
 impl Default for ElementDigest {
     fn default() -> Self {
         ElementDigest([BaseElement::default(); DIGEST_SIZE])
@@ -57,11 +71,11 @@ impl Serializable for ElementDigest {
 
 impl Deserializable for ElementDigest {
     fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
-        // TODO: check if the field elements are valid?
-        let e1 = BaseElement::new(source.read_u64()?);
-        let e2 = BaseElement::new(source.read_u64()?);
-        let e3 = BaseElement::new(source.read_u64()?);
-        let e4 = BaseElement::new(source.read_u64()?);
+        // elements are read in canonical form; non-canonical values are rejected
+        let e1 = BaseElement::read_from(source)?;
+        let e2 = BaseElement::read_from(source)?;
+        let e3 = BaseElement::read_from(source)?;
+        let e4 = BaseElement::read_from(source)?;
 
         Ok(Self([e1, e2, e3, e4]))
     }
@@ -91,7 +105,8 @@ impl From<ElementDigest> for [u8; 32] {
 #[cfg(test)]
 mod tests {
 
-    use super::ElementDigest;
+    use super::{ElementDigest, FieldDigest};
+    use math::{fields::f64::BaseElement, StarkField};
     use rand_utils::rand_array;
     use utils::{Deserializable, Serializable, SliceReader};
 
@@ -108,4 +123,17 @@ mod tests {
 
         assert_eq!(d1, d2);
     }
+
+    #[test]
+    fn digest_elements() {
+        let elements: [BaseElement; 4] = rand_array();
+        let digest = ElementDigest::from_elements(&elements);
+        assert_eq!(&elements, digest.as_elements());
+
+        // elements are serialized in canonical form, and non-canonical values are rejected
+        let mut bytes = digest.to_bytes();
+        assert_eq!(elements[0].to_bytes(), bytes[..8].to_vec());
+        bytes[8..16].copy_from_slice(&BaseElement::MODULUS.to_le_bytes());
+        assert!(ElementDigest::read_from_bytes(&bytes).is_err());
+    }
 }
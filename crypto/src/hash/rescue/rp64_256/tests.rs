@@ -166,6 +166,32 @@ fn hash_padding() {
     let r1 = Rp64_256::hash(&[1_u8, 2, 3, 4, 5, 6, 7, 0, 0]);
     let r2 = Rp64_256::hash(&[1_u8, 2, 3, 4, 5, 6, 7, 0, 0, 0, 0]);
     assert_ne!(r1, r2);
+
+    // same as above but with inputs spanning multiple permutations
+    let bytes = [7_u8; 100];
+    let r1 = Rp64_256::hash(&bytes[..98]);
+    let r2 = Rp64_256::hash(&bytes[..99]);
+    let r3 = Rp64_256::hash(&[&bytes[..98], &[0]].concat());
+    assert_ne!(r1, r2);
+    assert_ne!(r1, r3);
+}
+
+#[test]
+fn hash_padding_known_answer() {
+    // the last chunk of a 98-byte input is 7 bytes long; digests of such inputs spanning multiple
+    // permutations must not change
+    let bytes: [u8; 98] = core::array::from_fn(|i| i as u8);
+    let digest = Rp64_256::hash(&bytes);
+    let actual: [u64; 4] = core::array::from_fn(|i| digest.as_elements()[i].as_int());
+    assert_eq!(
+        [
+            1121330849364348914,
+            7959963977793309043,
+            14131443556386987508,
+            1068091904962496054,
+        ],
+        actual
+    );
 }
 
 #[test]
@@ -199,7 +225,7 @@ proptest! {
         for i in 0..STATE_WIDTH {
             v1[i] = BaseElement::new(a[i]);
         }
-        v2 = v1.clone();
+        v2 = v1;
 
         apply_mds_naive(&mut v1);
         Rp64_256::apply_mds(&mut v2);
@@ -4,7 +4,7 @@
 // LICENSE file in the root directory of this source tree.
 
 use super::super::mds::mds_f64_8x8::mds_multiply;
-use super::{exp_acc, Digest, ElementHasher, Hasher};
+use super::{exp_acc, Digest, ElementHasher, FieldDigest, Hasher};
 use core::convert::TryInto;
 use core::ops::Range;
 use math::{fields::f64::BaseElement, FieldElement, StarkField};
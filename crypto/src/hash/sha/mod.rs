@@ -3,7 +3,7 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use super::{ByteDigest, ElementHasher, Hasher};
+use super::{ByteDigest, ElementHasher, HashFunction, Hasher};
 use core::marker::PhantomData;
 use math::{FieldElement, StarkField};
 use sha3::Digest;
@@ -21,6 +21,8 @@ impl<B: StarkField> Hasher for Sha3_256<B> {
 
     const COLLISION_RESISTANCE: u32 = 128;
 
+    const HASH_FUNCTION: Option<HashFunction> = Some(HashFunction::Sha3_256);
+
     fn hash(bytes: &[u8]) -> Self::Digest {
         ByteDigest(sha3::Sha3_256::digest(bytes).into())
     }
@@ -49,7 +51,59 @@ impl<B: StarkField> ElementHasher for Sha3_256<B> {
         } else {
             // when elements' internal and canonical representations differ, we need to serialize
             // them before hashing
-            let mut hasher = ShaHasher::new();
+            let mut hasher = ShaHasher::<sha3::Sha3_256>::new();
+            hasher.write(elements);
+            ByteDigest(hasher.finalize())
+        }
+    }
+}
+
+// KECCAK WITH 256-BIT OUTPUT
+// ================================================================================================
+
+/// Implementation of the [Hasher](super::Hasher) trait for Keccak hash function with 256-bit
+/// output.
+///
+/// This is the original Keccak padding variant (as used by Ethereum's `keccak256`), which
+/// differs from the standardized [Sha3_256] only in padding of the hashed message.
+pub struct Keccak256<B: StarkField>(PhantomData<B>);
+
+impl<B: StarkField> Hasher for Keccak256<B> {
+    type Digest = ByteDigest<32>;
+
+    const COLLISION_RESISTANCE: u32 = 128;
+
+    const HASH_FUNCTION: Option<HashFunction> = Some(HashFunction::Keccak256);
+
+    fn hash(bytes: &[u8]) -> Self::Digest {
+        ByteDigest(sha3::Keccak256::digest(bytes).into())
+    }
+
+    fn merge(values: &[Self::Digest; 2]) -> Self::Digest {
+        ByteDigest(sha3::Keccak256::digest(ByteDigest::digests_as_bytes(values)).into())
+    }
+
+    fn merge_with_int(seed: Self::Digest, value: u64) -> Self::Digest {
+        let mut data = [0; 40];
+        data[..32].copy_from_slice(&seed.0);
+        data[32..].copy_from_slice(&value.to_le_bytes());
+        ByteDigest(sha3::Keccak256::digest(data).into())
+    }
+}
+
+impl<B: StarkField> ElementHasher for Keccak256<B> {
+    type BaseField = B;
+
+    fn hash_elements<E: FieldElement<BaseField = Self::BaseField>>(elements: &[E]) -> Self::Digest {
+        if B::IS_CANONICAL {
+            // when element's internal and canonical representations are the same, we can hash
+            // element bytes directly
+            let bytes = E::elements_as_bytes(elements);
+            ByteDigest(sha3::Keccak256::digest(bytes).into())
+        } else {
+            // when elements' internal and canonical representations differ, we need to serialize
+            // them before hashing
+            let mut hasher = ShaHasher::<sha3::Keccak256>::new();
             hasher.write(elements);
             ByteDigest(hasher.finalize())
         }
@@ -59,20 +113,22 @@ impl<B: StarkField> ElementHasher for Sha3_256<B> {
 // SHA HASHER
 // ================================================================================================
 
-/// Wrapper around SHA3 hasher to implement [ByteWriter] trait for it.
-struct ShaHasher(sha3::Sha3_256);
+/// Wrapper around SHA3 and Keccak hashers to implement [ByteWriter] trait for them.
+struct ShaHasher<D: Digest>(D);
 
-impl ShaHasher {
+impl<D: Digest> ShaHasher<D> {
     pub fn new() -> Self {
-        Self(sha3::Sha3_256::new())
+        Self(D::new())
     }
 
     pub fn finalize(self) -> [u8; 32] {
-        self.0.finalize().into()
+        let mut result = [0; 32];
+        result.copy_from_slice(&self.0.finalize());
+        result
     }
 }
 
-impl ByteWriter for ShaHasher {
+impl<D: Digest> ByteWriter for ShaHasher<D> {
     fn write_u8(&mut self, value: u8) {
         self.0.update([value]);
     }
@@ -14,7 +14,9 @@
 //!   [Octopus](https://eprint.iacr.org/2017/933) algorithm.
 //! * **PRNG** - which is used to generate pseudo-random elements in a finite field. The
 //!   [RandomCoin] implementation uses a cryptographic hash function to generate pseudo-random
-//!   elements form a seed.
+//!   elements form a seed. [EthStarkRandomCoin] follows the transcript conventions of the
+//!   ethSTARK prover, and [ElementRandomCoin] keeps its state in field-element form for use with
+//!   algebraic hash functions whose digests implement [FieldDigest].
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
@@ -23,13 +25,14 @@
 extern crate alloc;
 
 mod hash;
-pub use hash::{Digest, ElementHasher, Hasher};
+pub use hash::{Digest, ElementHasher, FieldDigest, HashFunction, Hasher};
 pub mod hashers {
     //! Contains implementations of currently supported hash functions.
 
     pub use super::hash::Blake3_192;
     pub use super::hash::Blake3_256;
     pub use super::hash::GriffinJive64_256;
+    pub use super::hash::Keccak256;
     pub use super::hash::Rp62_248;
     pub use super::hash::Rp64_256;
     pub use super::hash::RpJive64_256;
@@ -43,7 +46,10 @@ pub use merkle::{build_merkle_nodes, BatchMerkleProof, MerkleTree};
 pub use merkle::concurrent;
 
 mod random;
-pub use random::{DefaultRandomCoin, RandomCoin};
+pub use random::{
+    DefaultRandomCoin, ElementRandomCoin, EthStarkRandomCoin, RandomCoin, Transcript,
+    TranscriptCoin, TranscriptEvent,
+};
 
 mod errors;
 pub use errors::{MerkleTreeError, RandomCoinError};
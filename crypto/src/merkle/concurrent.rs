@@ -5,7 +5,7 @@
 
 use crate::Hasher;
 use core::slice;
-use utils::{collections::Vec, iterators::*, rayon};
+use utils::{collections::Vec, group_slice_elements, iterators::*, rayon};
 
 // CONSTANTS
 // ================================================================================================
@@ -27,7 +27,7 @@ pub fn build_merkle_nodes<H: Hasher>(leaves: &[H::Digest]) -> Vec<H::Digest> {
 
     // re-interpret leaves as an array of two leaves fused together and use it to
     // build first row of internal nodes (parents of leaves)
-    let two_leaves = unsafe { slice::from_raw_parts(leaves.as_ptr() as *const [H::Digest; 2], n) };
+    let two_leaves = group_slice_elements::<_, 2>(leaves);
     nodes[n..]
         .par_iter_mut()
         .zip(two_leaves.par_iter())
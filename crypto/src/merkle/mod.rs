@@ -5,7 +5,10 @@
 
 use crate::{errors::MerkleTreeError, hash::Hasher};
 use core::slice;
-use utils::collections::{BTreeMap, BTreeSet, Vec};
+use utils::{
+    collections::{BTreeMap, BTreeSet, Vec},
+    group_slice_elements,
+};
 
 mod proofs;
 pub use proofs::BatchMerkleProof;
@@ -340,7 +343,7 @@ pub fn build_merkle_nodes<H: Hasher>(leaves: &[H::Digest]) -> Vec<H::Digest> {
     nodes[0] = H::Digest::default();
 
     // re-interpret leaves as an array of two leaves fused together
-    let two_leaves = unsafe { slice::from_raw_parts(leaves.as_ptr() as *const [H::Digest; 2], n) };
+    let two_leaves = group_slice_elements::<_, 2>(leaves);
 
     // build first row of internal nodes (parents of leaves)
     for (i, j) in (0..n).zip(n..nodes.len()) {
@@ -46,6 +46,10 @@ impl<H: Hasher> BatchMerkleProof<H> {
     /// * More than 255 paths have been provided.
     /// * Number of paths is not equal to the number of indexes.
     /// * Not all paths have the same length.
+    /// * List of indexes contains duplicates.
+    ///
+    /// Indexes do not need to be sorted; the returned proof is the same as the one returned by
+    /// [MerkleTree::prove_batch()](crate::MerkleTree::prove_batch) for the same indexes.
     pub fn from_paths(paths: &[Vec<H::Digest>], indexes: &[usize]) -> BatchMerkleProof<H> {
         // TODO: optimize this to reduce amount of vector cloning.
         assert!(!paths.is_empty(), "at least one path must be provided");
@@ -60,9 +64,11 @@ impl<H: Hasher> BatchMerkleProof<H> {
             assert_eq!(depth, path.len(), "not all paths have the same length");
             path_map.insert(index, path);
         }
+        let provided_indexes = indexes;
         let indexes = path_map.keys().cloned().collect::<Vec<_>>();
         let paths = path_map.values().cloned().collect::<Vec<_>>();
         path_map.clear();
+        assert_eq!(indexes.len(), provided_indexes.len(), "indexes must be unique");
 
         let mut leaves = vec![H::Digest::default(); indexes.len()];
         let mut nodes: Vec<Vec<H::Digest>> = Vec::with_capacity(indexes.len());
@@ -103,6 +109,13 @@ impl<H: Hasher> BatchMerkleProof<H> {
             core::mem::swap(&mut path_map, &mut next_path_map);
         }
 
+        // leaves must be listed in the order of the provided indexes (as is done by
+        // MerkleTree::prove_batch())
+        let leaves = provided_indexes
+            .iter()
+            .map(|index| leaves[indexes.binary_search(index).expect("index not found")])
+            .collect();
+
         BatchMerkleProof {
             leaves,
             nodes,
@@ -273,6 +273,21 @@ proptest! {
         prop_assert!(proof1 == proof2);
     }
 
+    #[test]
+    fn batch_proof_from_unsorted_paths(tree in random_blake3_merkle_tree(128),
+                      proof_indices in prop::collection::vec(any::<prop::sample::Index>(), 10..20)
+    )  {
+        let mut indices: Vec<usize> = proof_indices.iter().map(|idx| idx.index(128)).collect();
+        indices.sort_unstable(); indices.dedup(); indices.reverse();
+        let proof1 = tree.prove_batch(&indices[..]).unwrap();
+
+        let paths = indices.iter().map(|&idx| tree.prove(idx).unwrap()).collect::<Vec<_>>();
+        let proof2 = BatchMerkleProof::from_paths(&paths, &indices);
+
+        prop_assert!(proof1 == proof2);
+        prop_assert!(MerkleTree::verify_batch(tree.root(), &indices[..], &proof2).is_ok());
+    }
+
     #[test]
     fn into_paths(tree in random_blake3_merkle_tree(32),
                       proof_indices in prop::collection::vec(any::<prop::sample::Index>(), 1..30)
@@ -139,13 +139,17 @@ impl<B: StarkField, H: ElementHasher<BaseField = B>> RandomCoin for DefaultRando
     /// PRNG.
     fn draw<E: FieldElement>(&mut self) -> Result<E, RandomCoinError> {
         for _ in 0..1000 {
-            // get the next pseudo-random value and take the first ELEMENT_BYTES from it
-            let value = self.next();
-            let bytes = &value.as_bytes()[..E::ELEMENT_BYTES];
+            // get the next pseudo-random value and take the first ELEMENT_BYTES from it; if an
+            // element is wider than a single value (e.g., cubic extension of a 128-bit field),
+            // bytes of several consecutive values are concatenated
+            let mut bytes = Vec::with_capacity(E::ELEMENT_BYTES);
+            while bytes.len() < E::ELEMENT_BYTES {
+                bytes.extend_from_slice(&self.next().as_bytes());
+            }
 
             // check if the bytes can be converted into a valid field element; if they can,
             // return; otherwise try again
-            if let Some(element) = E::from_random_bytes(bytes) {
+            if let Some(element) = E::from_random_bytes(&bytes[..E::ELEMENT_BYTES]) {
                 return Ok(element);
             }
         }
@@ -0,0 +1,163 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use crate::{errors::RandomCoinError, ElementHasher, FieldDigest, RandomCoin};
+use core::convert::TryInto;
+use math::{FieldElement, StarkField};
+use utils::{collections::Vec, Serializable};
+
+// ELEMENT RANDOM COIN IMPLEMENTATION
+// ================================================================================================
+
+/// Pseudo-random element generator for finite fields which keeps its state in field-element form.
+///
+/// The coin is intended to be used with algebraic hash functions (e.g.,
+/// [Rp64_256](crate::hashers::Rp64_256)), whose digests consist of base field elements. Unlike
+/// [DefaultRandomCoin](crate::DefaultRandomCoin), which draws elements by interpreting bytes of
+/// digests as field elements and rejecting invalid values, this coin uses elements of digests
+/// directly. Thus, every operation of the coin can be expressed via permutations of the hash
+/// function alone, which simplifies replicating the coin inside of a STARK (e.g., in recursive
+/// verification). For hash functions whose digests are serialized as canonical 64-bit elements
+/// (e.g., [Rp64_256](crate::hashers::Rp64_256)), the coin draws the same values as the default
+/// coin; for hash functions whose digests are packed more tightly (e.g.,
+/// [Rp62_248](crate::hashers::Rp62_248)), only this coin preserves the element structure. The
+/// coin works as follows:
+/// - The internal state of the coin consists of a `seed` and a `counter`. At instantiation time,
+///   the `seed` is set to a hash of the provided elements, and the `counter` is set to 0.
+/// - To draw the next digest, the `counter` is incremented and hash(`seed` || `counter`) is
+///   computed. An element of an extension field of degree `d` is assembled from the first `d`
+///   elements of the next digest (or of several consecutive digests, if a digest contains fewer
+///   than `d` elements); drawing an element never fails.
+/// - During reseeding with `data`, the seed is set to hash(`seed` || `data`), and the counter is
+///   reset to 0.
+/// - Integers (e.g., query positions) are drawn after reseeding the coin with a nonce; each
+///   integer is derived from the low bits of the canonical representation of the first element
+///   of the next digest.
+/// - A proof-of-work nonce is valid if the canonical representation of the first element of
+///   hash(`seed` || `nonce`) has at least `grinding_factor` trailing zeros.
+///
+/// # Examples
+/// ```
+/// # use winter_crypto::{RandomCoin, ElementRandomCoin, Hasher, hashers::Rp64_256};
+/// # use math::fields::f64::BaseElement;
+/// let seed = &[BaseElement::new(1), BaseElement::new(2), BaseElement::new(3), BaseElement::new(4)];
+///
+/// let mut coin1 = ElementRandomCoin::<Rp64_256>::new(seed);
+/// let mut coin2 = ElementRandomCoin::<Rp64_256>::new(seed);
+///
+/// // should draw the same elements from both coins
+/// let e1 = coin1.draw::<BaseElement>().unwrap();
+/// let e2 = coin2.draw::<BaseElement>().unwrap();
+/// assert_eq!(e1, e2);
+///
+/// // after reseeding should draw different elements
+/// coin2.reseed(Rp64_256::hash(&[2, 3, 4, 5]));
+/// let e1 = coin1.draw::<BaseElement>().unwrap();
+/// let e2 = coin2.draw::<BaseElement>().unwrap();
+/// assert_ne!(e1, e2);
+/// ```
+pub struct ElementRandomCoin<H: ElementHasher> {
+    seed: H::Digest,
+    counter: u64,
+}
+
+impl<H> ElementRandomCoin<H>
+where
+    H: ElementHasher,
+    H::Digest: FieldDigest<BaseField = H::BaseField>,
+{
+    /// Updates the state by incrementing the counter and returns hash(seed || counter).
+    fn next(&mut self) -> H::Digest {
+        self.counter += 1;
+        H::merge_with_int(self.seed, self.counter)
+    }
+
+    /// Returns the low 64 bits of the canonical representation of the first element of the
+    /// specified digest.
+    fn first_element_bits(digest: &H::Digest) -> u64 {
+        let bytes = digest.as_elements()[0].to_bytes();
+        u64::from_le_bytes(bytes[..8].try_into().unwrap())
+    }
+}
+
+impl<B, H> RandomCoin for ElementRandomCoin<H>
+where
+    B: StarkField,
+    H: ElementHasher<BaseField = B>,
+    H::Digest: FieldDigest<BaseField = B>,
+{
+    type BaseField = B;
+    type Hasher = H;
+
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+    /// Returns a new random coin instantiated with the provided `seed`.
+    fn new(seed: &[Self::BaseField]) -> Self {
+        let seed = H::hash_elements(seed);
+        Self { seed, counter: 0 }
+    }
+
+    // RESEEDING
+    // --------------------------------------------------------------------------------------------
+
+    /// Reseeds the coin with the specified data by setting the new seed to hash(`seed` || `data`).
+    fn reseed(&mut self, data: H::Digest) {
+        self.seed = H::merge(&[self.seed, data]);
+        self.counter = 0;
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Computes hash(`seed` || `value`) and returns the number of trailing zeros in the canonical
+    /// representation of the first element of the result.
+    fn check_leading_zeros(&self, value: u64) -> u32 {
+        let new_seed = H::merge_with_int(self.seed, value);
+        Self::first_element_bits(&new_seed).trailing_zeros()
+    }
+
+    // DRAW METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the next pseudo-random field element assembled from elements of the next digest.
+    ///
+    /// Drawing an element never fails.
+    fn draw<E: FieldElement<BaseField = B>>(&mut self) -> Result<E, RandomCoinError> {
+        let mut elements = Vec::with_capacity(E::EXTENSION_DEGREE);
+        while elements.len() < E::EXTENSION_DEGREE {
+            elements.extend_from_slice(self.next().as_elements());
+        }
+        Ok(E::slice_from_base_elements(&elements[..E::EXTENSION_DEGREE])[0])
+    }
+
+    /// Returns a vector of integers selected from the range [0, domain_size) after reseeding
+    /// the PRNG with the specified `nonce` by setting the new seed to hash(`seed` || `nonce`).
+    ///
+    /// # Panics
+    /// Panics if:
+    /// - `domain_size` is not a power of two.
+    /// - `num_values` is greater than or equal to `domain_size`.
+    fn draw_integers(
+        &mut self,
+        num_values: usize,
+        domain_size: usize,
+        nonce: u64,
+    ) -> Result<Vec<usize>, RandomCoinError> {
+        assert!(domain_size.is_power_of_two(), "domain size must be a power of two");
+        assert!(num_values < domain_size, "number of values must be smaller than domain size");
+
+        // reseed with nonce
+        self.seed = H::merge_with_int(self.seed, nonce);
+        self.counter = 0;
+
+        // take as many low bits of each value as are needed to represent values in the domain
+        let v_mask = (domain_size - 1) as u64;
+        let values = (0..num_values)
+            .map(|_| (Self::first_element_bits(&self.next()) & v_mask) as usize)
+            .collect();
+
+        Ok(values)
+    }
+}
@@ -0,0 +1,247 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use crate::{errors::RandomCoinError, ElementHasher, Hasher, RandomCoin};
+use core::convert::TryInto;
+use math::{FieldElement, StarkField};
+use utils::{collections::Vec, Serializable};
+
+// CONSTANTS
+// ================================================================================================
+
+/// Prefix of the data hashed to initialize a proof-of-work search.
+const POW_MAGIC: u64 = 0x0123_4567_89ab_cded;
+
+/// Number of bytes used to encode the counter appended to the seed when drawing random bytes.
+const COUNTER_BYTES: usize = 32;
+
+// ETHSTARK RANDOM COIN IMPLEMENTATION
+// ================================================================================================
+
+/// Pseudo-random element generator for finite fields which follows the transcript conventions
+/// of the ethSTARK prover.
+///
+/// The coin is intended to be used with [Keccak256](crate::hashers::Keccak256) (the default
+/// hash function of ethSTARK) for proofs which need to interoperate with verifiers written
+/// against ethSTARK conventions. Compared to [DefaultRandomCoin](crate::DefaultRandomCoin), the
+/// coin differs as follows:
+/// - All integers and field elements are encoded in big-endian byte order.
+/// - At instantiation time, the `seed` is set to a hash of the big-endian encoding of the
+///   provided elements, and the `counter` is set to 0.
+/// - Random bytes are produced as a stream of hash(`seed` || `counter`) blocks, where `counter`
+///   is encoded as a 32-byte big-endian integer and is incremented after each block. Bytes of a
+///   block which are not consumed by one draw are used by the next draw.
+/// - To draw a field element, `ELEMENT_BYTES` bytes are taken from the stream and interpreted as
+///   big-endian encodings of base field elements; if they do not encode a valid element, more
+///   bytes are drawn until a valid element is found or the number of allowed tries is exceeded.
+/// - During reseeding with `data` (a commitment or a proof-of-work nonce), the seed is set to
+///   hash(`seed + 1` || `data`), where `seed + 1` is computed by interpreting the seed as a
+///   big-endian integer, and the counter and the unused bytes are reset.
+/// - Query positions are drawn after reseeding the coin with the proof-of-work nonce; each
+///   position is derived from 8 bytes of the stream interpreted as a big-endian integer.
+/// - A proof-of-work nonce with `grinding_factor` bits is valid if hash(`init` || `nonce`) starts
+///   with `grinding_factor` zero bits, where `init` = hash(`0x0123456789abcded` || `seed` ||
+///   `grinding_factor`) and `grinding_factor` is encoded as a single byte.
+///
+/// The coin only covers the transcript of the protocol. Thus, a proof is readable by ethSTARK
+/// verifiers only if the rest of the protocol (i.e., the field, proof options, and proof layout)
+/// is agreed upon as well.
+///
+/// # Examples
+/// ```
+/// # use winter_crypto::{RandomCoin, EthStarkRandomCoin, Hasher, hashers::Keccak256};
+/// # use math::fields::f128::BaseElement;
+/// let seed = &[BaseElement::new(1), BaseElement::new(2), BaseElement::new(3), BaseElement::new(4)];
+///
+/// let mut coin1 = EthStarkRandomCoin::<Keccak256<BaseElement>>::new(seed);
+/// let mut coin2 = EthStarkRandomCoin::<Keccak256<BaseElement>>::new(seed);
+///
+/// // should draw same elements for the same seed
+/// let e1 = coin1.draw::<BaseElement>().unwrap();
+/// let e2 = coin2.draw::<BaseElement>().unwrap();
+/// assert_eq!(e1, e2);
+///
+/// // after reseeding should draw different elements
+/// coin2.reseed(Keccak256::<BaseElement>::hash(&[2, 3, 4, 5]));
+/// let e1 = coin1.draw::<BaseElement>().unwrap();
+/// let e2 = coin2.draw::<BaseElement>().unwrap();
+/// assert_ne!(e1, e2);
+///
+/// // a proof-of-work nonce can be found for the current state of the coin
+/// let nonce = (1..).find(|&nonce| coin1.check_proof_of_work(nonce, 8)).unwrap();
+/// assert!(coin1.check_proof_of_work(nonce, 8));
+/// ```
+pub struct EthStarkRandomCoin<H: ElementHasher> {
+    seed: H::Digest,
+    counter: u64,
+    spare_bytes: Vec<u8>,
+}
+
+impl<H: ElementHasher> EthStarkRandomCoin<H> {
+    /// Sets the seed to hash(`seed + 1` || `bytes`), and resets the counter and unused bytes.
+    fn mix_seed_with_bytes(&mut self, bytes: &[u8]) {
+        let mut data = self.seed.to_bytes();
+        for byte in data.iter_mut().rev() {
+            let (value, overflow) = byte.overflowing_add(1);
+            *byte = value;
+            if !overflow {
+                break;
+            }
+        }
+        data.extend_from_slice(bytes);
+
+        self.seed = H::hash(&data);
+        self.counter = 0;
+        self.spare_bytes.clear();
+    }
+
+    /// Fills `out` with the next bytes of the stream, computing hash(`seed` || `counter`) blocks
+    /// as needed.
+    fn fill_bytes(&mut self, out: &mut [u8]) {
+        let mut num_filled = 0;
+        while num_filled < out.len() {
+            if self.spare_bytes.is_empty() {
+                let mut data = self.seed.to_bytes();
+                data.resize(data.len() + COUNTER_BYTES - 8, 0);
+                data.extend_from_slice(&self.counter.to_be_bytes());
+                self.counter += 1;
+                self.spare_bytes = H::hash(&data).to_bytes();
+            }
+
+            let num_bytes = usize::min(self.spare_bytes.len(), out.len() - num_filled);
+            out[num_filled..num_filled + num_bytes].copy_from_slice(&self.spare_bytes[..num_bytes]);
+            self.spare_bytes.drain(..num_bytes);
+            num_filled += num_bytes;
+        }
+    }
+}
+
+impl<B: StarkField, H: ElementHasher<BaseField = B>> RandomCoin for EthStarkRandomCoin<H> {
+    type BaseField = B;
+    type Hasher = H;
+
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+    /// Returns a new random coin instantiated with the provided `seed`.
+    fn new(seed: &[Self::BaseField]) -> Self {
+        let mut bytes = Vec::with_capacity(seed.len() * B::ELEMENT_BYTES);
+        for element in seed {
+            let mut element_bytes = element.to_bytes();
+            element_bytes.reverse();
+            bytes.extend_from_slice(&element_bytes);
+        }
+
+        Self {
+            seed: H::hash(&bytes),
+            counter: 0,
+            spare_bytes: Vec::new(),
+        }
+    }
+
+    // RESEEDING
+    // --------------------------------------------------------------------------------------------
+
+    /// Reseeds the coin with the specified data by setting the new seed to
+    /// hash(`seed + 1` || `data`).
+    fn reseed(&mut self, data: H::Digest) {
+        self.mix_seed_with_bytes(&data.to_bytes());
+    }
+
+    /// Reseeds the coin with the big-endian byte representation of the specified `value`.
+    fn reseed_with_int(&mut self, value: u64) {
+        self.mix_seed_with_bytes(&value.to_be_bytes());
+    }
+
+    // PROOF-OF-WORK
+    // --------------------------------------------------------------------------------------------
+
+    /// Computes hash(`seed` || `value`) and returns the number of leading zeros in the resulting
+    /// value if it is interpreted as an integer in big-endian byte order.
+    ///
+    /// This is not the proof-of-work used by ethSTARK; see
+    /// [check_proof_of_work()](RandomCoin::check_proof_of_work) for that.
+    fn check_leading_zeros(&self, value: u64) -> u32 {
+        let mut data = self.seed.to_bytes();
+        data.extend_from_slice(&value.to_be_bytes());
+        leading_zeros::<H>(H::hash(&data))
+    }
+
+    /// Returns `true` if hash(`init` || `nonce`) has at least `grinding_factor` leading zeros,
+    /// where `init` = hash(`0x0123456789abcded` || `seed` || `grinding_factor`).
+    fn check_proof_of_work(&self, nonce: u64, grinding_factor: u32) -> bool {
+        let mut init = POW_MAGIC.to_be_bytes().to_vec();
+        init.extend_from_slice(&self.seed.to_bytes());
+        init.push(grinding_factor as u8);
+
+        let mut data = H::hash(&init).to_bytes();
+        data.extend_from_slice(&nonce.to_be_bytes());
+        leading_zeros::<H>(H::hash(&data)) >= grinding_factor
+    }
+
+    // DRAW METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the next pseudo-random field element.
+    ///
+    /// # Errors
+    /// Returns an error if a valid field element could not be generated after 1000 attempts.
+    fn draw<E: FieldElement>(&mut self) -> Result<E, RandomCoinError> {
+        let mut bytes = vec![0u8; E::ELEMENT_BYTES];
+        for _ in 0..1000 {
+            self.fill_bytes(&mut bytes);
+
+            // base field elements are encoded in big-endian byte order, while field elements
+            // are read from little-endian bytes
+            bytes.chunks_mut(B::ELEMENT_BYTES).for_each(|chunk| chunk.reverse());
+            if let Some(element) = E::from_random_bytes(&bytes) {
+                return Ok(element);
+            }
+        }
+
+        Err(RandomCoinError::FailedToDrawFieldElement(1000))
+    }
+
+    /// Returns a vector of integers selected from the range [0, domain_size) after reseeding
+    /// the coin with the specified `nonce`.
+    ///
+    /// # Panics
+    /// Panics if:
+    /// - `domain_size` is not a power of two.
+    /// - `num_values` is greater than or equal to `domain_size`.
+    fn draw_integers(
+        &mut self,
+        num_values: usize,
+        domain_size: usize,
+        nonce: u64,
+    ) -> Result<Vec<usize>, RandomCoinError> {
+        assert!(domain_size.is_power_of_two(), "domain size must be a power of two");
+        assert!(num_values < domain_size, "number of values must be smaller than domain size");
+
+        self.reseed_with_int(nonce);
+
+        // since the domain size is a power of two, reducing the value modulo the domain size
+        // does not introduce a bias
+        let v_mask = (domain_size - 1) as u64;
+        let mut bytes = [0u8; 8];
+        let values = (0..num_values)
+            .map(|_| {
+                self.fill_bytes(&mut bytes);
+                (u64::from_be_bytes(bytes) & v_mask) as usize
+            })
+            .collect();
+
+        Ok(values)
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns the number of leading zeros in the digest interpreted as a big-endian integer.
+fn leading_zeros<H: Hasher>(digest: H::Digest) -> u32 {
+    let bytes = digest.to_bytes();
+    let head = u64::from_be_bytes(bytes[..8].try_into().unwrap());
+    head.leading_zeros()
+}
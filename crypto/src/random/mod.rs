@@ -10,6 +10,15 @@ use utils::collections::Vec;
 mod default;
 pub use default::DefaultRandomCoin;
 
+mod element;
+pub use element::ElementRandomCoin;
+
+mod ethstark;
+pub use ethstark::EthStarkRandomCoin;
+
+mod transcript;
+pub use transcript::{Transcript, TranscriptCoin, TranscriptEvent};
+
 // RANDOM COIN TRAIT
 // ================================================================================================
 
@@ -20,6 +29,7 @@ pub use default::DefaultRandomCoin;
 ///
 /// Internally we use a cryptographic hash function (which is specified via the `Hasher` associated
 /// type), to draw elements from the field.
+#[allow(unused_variables)]
 pub trait RandomCoin: Sync {
     /// Base field for random elements which can be generated by this random coin.
     type BaseField: StarkField;
@@ -64,4 +74,38 @@ pub trait RandomCoin: Sync {
         domain_size: usize,
         nonce: u64,
     ) -> Result<Vec<usize>, RandomCoinError>;
+
+    // PROVIDED METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Reseeds the coin with the specified integer `value` (e.g., a proof-of-work nonce).
+    ///
+    /// By default, the coin is reseeded with a hash of the little-endian byte representation of
+    /// the `value`.
+    fn reseed_with_int(&mut self, value: u64) {
+        self.reseed(Self::Hasher::hash(&value.to_le_bytes()));
+    }
+
+    /// Returns `true` if the specified `nonce` is a valid proof-of-work for the current state of
+    /// the coin which requires `grinding_factor` leading zeros.
+    ///
+    /// By default, the nonce is valid if [check_leading_zeros()](RandomCoin::check_leading_zeros)
+    /// returns at least `grinding_factor` for it.
+    fn check_proof_of_work(&self, nonce: u64, grinding_factor: u32) -> bool {
+        self.check_leading_zeros(nonce) >= grinding_factor
+    }
+
+    /// Records the specified labeled `data` in the transcript of this coin without affecting the
+    /// state of the coin.
+    ///
+    /// This is a no-op by default; coins which record transcripts (e.g., [TranscriptCoin]) use
+    /// this to log values such as out-of-domain evaluations next to the reseeds derived from them.
+    fn record_note(&mut self, label: &str, data: &[u8]) {}
+
+    /// Returns the transcript of all interactions with this coin, if this coin records one.
+    ///
+    /// Returns None by default.
+    fn transcript(&self) -> Option<&Transcript> {
+        None
+    }
 }
@@ -0,0 +1,323 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use crate::{errors::RandomCoinError, Hasher, RandomCoin};
+use core::fmt;
+use math::FieldElement;
+use utils::{
+    collections::Vec, string::String, ByteReader, ByteWriter, Deserializable, DeserializationError,
+    Serializable,
+};
+
+// TRANSCRIPT EVENT
+// ================================================================================================
+
+/// An interaction with a random coin recorded in a [Transcript].
+///
+/// Field elements and digests are recorded as bytes in their canonical serialization format. This
+/// makes transcripts independent of the field and hash function used by the protocol, and allows
+/// comparing them against transcripts recorded by external implementations.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TranscriptEvent {
+    /// The coin was instantiated with the specified seed elements.
+    Seed(Vec<u8>),
+    /// The coin was reseeded with the specified digest (e.g., a commitment).
+    Reseed(Vec<u8>),
+    /// The coin was reseeded with the specified integer (e.g., a proof-of-work nonce).
+    ReseedWithInt(u64),
+    /// The specified field element was drawn from the coin.
+    Draw(Vec<u8>),
+    /// The specified integers were drawn from the coin after reseeding it with the nonce.
+    DrawIntegers(u64, Vec<u64>),
+    /// The specified labeled data (e.g., out-of-domain evaluations) was sent by the prover.
+    ///
+    /// Notes do not affect the state of the coin, but make it possible to tell which of the values
+    /// absorbed by the coin diverged.
+    Note(String, Vec<u8>),
+}
+
+impl TranscriptEvent {
+    fn tag(&self) -> u8 {
+        match self {
+            Self::Seed(_) => 0,
+            Self::Reseed(_) => 1,
+            Self::ReseedWithInt(_) => 2,
+            Self::Draw(_) => 3,
+            Self::DrawIntegers(..) => 4,
+            Self::Note(..) => 5,
+        }
+    }
+}
+
+impl fmt::Display for TranscriptEvent {
+    #[rustfmt::skip]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Seed(bytes) => write!(f, "seed {}", Hex(bytes)),
+            Self::Reseed(bytes) => write!(f, "reseed {}", Hex(bytes)),
+            Self::ReseedWithInt(value) => write!(f, "reseed with int {value}"),
+            Self::Draw(bytes) => write!(f, "draw {}", Hex(bytes)),
+            Self::DrawIntegers(nonce, values) => write!(f, "draw integers with nonce {nonce}: {values:?}"),
+            Self::Note(label, bytes) => write!(f, "note {label}: {}", Hex(bytes)),
+        }
+    }
+}
+
+impl Serializable for TranscriptEvent {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write_u8(self.tag());
+        match self {
+            Self::Seed(bytes) | Self::Reseed(bytes) | Self::Draw(bytes) => {
+                write_bytes(bytes, target)
+            }
+            Self::ReseedWithInt(value) => target.write_u64(*value),
+            Self::DrawIntegers(nonce, values) => {
+                target.write_u64(*nonce);
+                target.write_u32(values.len() as u32);
+                values.iter().for_each(|&value| target.write_u64(value));
+            }
+            Self::Note(label, bytes) => {
+                write_bytes(label.as_bytes(), target);
+                write_bytes(bytes, target);
+            }
+        }
+    }
+}
+
+impl Deserializable for TranscriptEvent {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        match source.read_u8()? {
+            0 => Ok(Self::Seed(read_bytes(source)?)),
+            1 => Ok(Self::Reseed(read_bytes(source)?)),
+            2 => Ok(Self::ReseedWithInt(source.read_u64()?)),
+            3 => Ok(Self::Draw(read_bytes(source)?)),
+            4 => {
+                let nonce = source.read_u64()?;
+                let num_values = source.read_u32()? as usize;
+                let values =
+                    (0..num_values).map(|_| source.read_u64()).collect::<Result<_, _>>()?;
+                Ok(Self::DrawIntegers(nonce, values))
+            }
+            5 => {
+                let label = String::from_utf8(read_bytes(source)?).map_err(|_| {
+                    DeserializationError::InvalidValue("note label is not valid UTF-8".into())
+                })?;
+                Ok(Self::Note(label, read_bytes(source)?))
+            }
+            tag => Err(DeserializationError::InvalidValue(format!(
+                "transcript event tag {tag} is not valid"
+            ))),
+        }
+    }
+}
+
+// TRANSCRIPT
+// ================================================================================================
+
+/// A log of all interactions with a random coin over the course of a protocol.
+///
+/// Transcripts are recorded by [TranscriptCoin]. Since the prover and the verifier are expected
+/// to interact with their coins in exactly the same way, transcripts recorded by both sides for
+/// the same proof must be identical; [first_divergence()](Transcript::first_divergence) can be
+/// used to pinpoint the first event at which this is not the case.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Transcript {
+    events: Vec<TranscriptEvent>,
+}
+
+impl Transcript {
+    /// Returns a new empty transcript.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends the specified event to this transcript.
+    pub fn record(&mut self, event: TranscriptEvent) {
+        self.events.push(event);
+    }
+
+    /// Returns the events recorded in this transcript in the order in which they occurred.
+    pub fn events(&self) -> &[TranscriptEvent] {
+        &self.events
+    }
+
+    /// Returns the number of events in this transcript.
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    /// Returns true if no events have been recorded in this transcript.
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    /// Returns the index of the first event at which this transcript and the `other` transcript
+    /// differ, or None if the transcripts are identical.
+    ///
+    /// If one transcript is a prefix of the other, the length of the shorter transcript is
+    /// returned.
+    pub fn first_divergence(&self, other: &Transcript) -> Option<usize> {
+        match self.events.iter().zip(other.events.iter()).position(|(a, b)| a != b) {
+            Some(index) => Some(index),
+            None if self.len() != other.len() => Some(usize::min(self.len(), other.len())),
+            None => None,
+        }
+    }
+}
+
+impl fmt::Display for Transcript {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, event) in self.events.iter().enumerate() {
+            writeln!(f, "{index}: {event}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Serializable for Transcript {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write_u32(self.events.len() as u32);
+        self.events.iter().for_each(|event| event.write_into(target));
+    }
+}
+
+impl Deserializable for Transcript {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        let num_events = source.read_u32()? as usize;
+        let events = (0..num_events).map(|_| source.read()).collect::<Result<_, _>>()?;
+        Ok(Self { events })
+    }
+}
+
+// TRANSCRIPT COIN
+// ================================================================================================
+
+/// A random coin which records all interactions with an underlying random coin into a
+/// [Transcript].
+///
+/// The coin delegates all operations to the wrapped coin `R`, and thus, produces exactly the same
+/// values as `R`. Proof-of-work checks are not recorded since they do not alter the state of the
+/// coin; the nonce which is eventually selected is recorded when the coin is reseeded with it.
+///
+/// # Examples
+/// ```
+/// # use winter_crypto::{RandomCoin, DefaultRandomCoin, TranscriptCoin, TranscriptEvent, hashers::Blake3_256};
+/// # use math::fields::f128::BaseElement;
+/// type Coin = DefaultRandomCoin<Blake3_256<BaseElement>>;
+///
+/// let seed = &[BaseElement::new(1), BaseElement::new(2)];
+/// let mut coin1 = TranscriptCoin::<Coin>::new(seed);
+/// let mut coin2 = Coin::new(seed);
+///
+/// // the transcript coin draws the same elements as the wrapped coin
+/// let e1 = coin1.draw::<BaseElement>().unwrap();
+/// let e2 = coin2.draw::<BaseElement>().unwrap();
+/// assert_eq!(e1, e2);
+///
+/// // and records all interactions with it
+/// let transcript = coin1.transcript().unwrap();
+/// assert_eq!(2, transcript.len());
+/// assert!(matches!(transcript.events()[1], TranscriptEvent::Draw(_)));
+/// ```
+pub struct TranscriptCoin<R: RandomCoin> {
+    coin: R,
+    transcript: Transcript,
+}
+
+impl<R: RandomCoin> TranscriptCoin<R> {
+    /// Returns the wrapped random coin.
+    pub fn inner(&self) -> &R {
+        &self.coin
+    }
+
+    /// Consumes this coin and returns the transcript recorded by it.
+    pub fn into_transcript(self) -> Transcript {
+        self.transcript
+    }
+}
+
+impl<R: RandomCoin> RandomCoin for TranscriptCoin<R> {
+    type BaseField = R::BaseField;
+    type Hasher = R::Hasher;
+
+    fn new(seed: &[Self::BaseField]) -> Self {
+        let mut transcript = Transcript::new();
+        transcript.record(TranscriptEvent::Seed(Self::BaseField::elements_as_bytes(seed).to_vec()));
+        Self {
+            coin: R::new(seed),
+            transcript,
+        }
+    }
+
+    fn reseed(&mut self, data: <Self::Hasher as Hasher>::Digest) {
+        self.transcript.record(TranscriptEvent::Reseed(data.to_bytes()));
+        self.coin.reseed(data);
+    }
+
+    fn check_leading_zeros(&self, value: u64) -> u32 {
+        self.coin.check_leading_zeros(value)
+    }
+
+    fn draw<E: FieldElement<BaseField = Self::BaseField>>(&mut self) -> Result<E, RandomCoinError> {
+        let value = self.coin.draw::<E>()?;
+        self.transcript
+            .record(TranscriptEvent::Draw(E::elements_as_bytes(&[value]).to_vec()));
+        Ok(value)
+    }
+
+    fn draw_integers(
+        &mut self,
+        num_values: usize,
+        domain_size: usize,
+        nonce: u64,
+    ) -> Result<Vec<usize>, RandomCoinError> {
+        let values = self.coin.draw_integers(num_values, domain_size, nonce)?;
+        self.transcript.record(TranscriptEvent::DrawIntegers(
+            nonce,
+            values.iter().map(|&value| value as u64).collect(),
+        ));
+        Ok(values)
+    }
+
+    fn reseed_with_int(&mut self, value: u64) {
+        self.transcript.record(TranscriptEvent::ReseedWithInt(value));
+        self.coin.reseed_with_int(value);
+    }
+
+    fn check_proof_of_work(&self, nonce: u64, grinding_factor: u32) -> bool {
+        self.coin.check_proof_of_work(nonce, grinding_factor)
+    }
+
+    fn record_note(&mut self, label: &str, data: &[u8]) {
+        self.transcript.record(TranscriptEvent::Note(label.into(), data.to_vec()));
+    }
+
+    fn transcript(&self) -> Option<&Transcript> {
+        Some(&self.transcript)
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+fn write_bytes<W: ByteWriter>(bytes: &[u8], target: &mut W) {
+    target.write_u32(bytes.len() as u32);
+    target.write_bytes(bytes);
+}
+
+fn read_bytes<R: ByteReader>(source: &mut R) -> Result<Vec<u8>, DeserializationError> {
+    let num_bytes = source.read_u32()? as usize;
+    source.read_vec(num_bytes)
+}
+
+/// Formats a byte slice as a hex string.
+struct Hex<'a>(&'a [u8]);
+
+impl fmt::Display for Hex<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("0x")?;
+        self.0.iter().try_for_each(|byte| write!(f, "{byte:02x}"))
+    }
+}
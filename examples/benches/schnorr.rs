@@ -0,0 +1,37 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use examples::{schnorr, Example};
+
+use std::time::Duration;
+use winterfell::{
+    crypto::hashers::Blake3_256, math::fields::f64::BaseElement, FieldExtension, ProofOptions,
+};
+
+const SIZES: [usize; 3] = [2, 4, 8];
+
+fn schnorr(c: &mut Criterion) {
+    let mut group = c.benchmark_group("schnorr");
+    group.sample_size(10);
+    group.measurement_time(Duration::from_secs(25));
+
+    let options = ProofOptions::new(32, 8, 0, FieldExtension::Quadratic, 4, 255);
+
+    for &size in SIZES.iter() {
+        let schnorr = schnorr::SchnorrExample::<Blake3_256<BaseElement>>::with_seed(
+            size,
+            options.clone(),
+            [0; 32],
+        );
+        group.bench_function(BenchmarkId::from_parameter(size), |bench| {
+            bench.iter(|| schnorr.prove());
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(schnorr_group, schnorr);
+criterion_main!(schnorr_group);
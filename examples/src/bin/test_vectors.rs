@@ -0,0 +1,46 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use examples::test_vectors;
+use std::{path::PathBuf, process};
+use structopt::StructOpt;
+
+// TEST VECTOR RUNNER
+// ================================================================================================
+
+#[derive(StructOpt, Debug)]
+#[structopt(name = "test-vectors", about = "Winterfell golden test vectors")]
+enum Command {
+    /// Write test vectors computed by the current implementation into the fixtures directory
+    Emit {
+        /// Fixtures directory; defaults to the test-vectors directory of the examples crate
+        #[structopt(parse(from_os_str))]
+        dir: Option<PathBuf>,
+    },
+    /// Check that test vectors in the fixtures directory match the current implementation
+    Check {
+        /// Fixtures directory; defaults to the test-vectors directory of the examples crate
+        #[structopt(parse(from_os_str))]
+        dir: Option<PathBuf>,
+    },
+}
+
+fn main() {
+    match Command::from_args() {
+        Command::Emit { dir } => {
+            let dir = dir.unwrap_or_else(test_vectors::fixtures_dir);
+            test_vectors::write(&dir).expect("failed to write test vectors");
+            println!("Test vectors written to {}", dir.display());
+        }
+        Command::Check { dir } => {
+            let dir = dir.unwrap_or_else(test_vectors::fixtures_dir);
+            if let Err(err) = test_vectors::check(&dir) {
+                eprintln!("Test vectors do not match:\n{err}");
+                process::exit(1);
+            }
+            println!("Test vectors match");
+        }
+    }
+}
@@ -22,3 +22,15 @@ fn fib2_test_basic_proof_verification_fail() {
     let fib = Box::new(super::FibExample::<Blake3_256>::new(16, build_proof_options(false)));
     crate::tests::test_basic_proof_verification_fail(fib);
 }
+
+#[test]
+fn fib2_test_malformed_proof_rejection() {
+    let fib = Box::new(super::FibExample::<Blake3_256>::new(16, build_proof_options(false)));
+    crate::tests::test_malformed_proof_rejection(fib);
+}
+
+#[test]
+fn fib2_test_malformed_proof_rejection_extension() {
+    let fib = Box::new(super::FibExample::<Blake3_256>::new(16, build_proof_options(true)));
+    crate::tests::test_malformed_proof_rejection(fib);
+}
@@ -18,8 +18,13 @@ pub mod merkle;
 pub mod rescue;
 #[cfg(feature = "std")]
 pub mod rescue_raps;
+#[cfg(feature = "std")]
+pub mod schnorr;
+#[cfg(feature = "test-vectors")]
+pub mod test_vectors;
 pub mod utils;
 pub mod vdf;
+pub mod vm;
 
 #[cfg(test)]
 mod tests;
@@ -69,9 +74,74 @@ pub struct ExampleOptions {
     /// Folding factor for FRI protocol
     #[structopt(short = "f", long = "folding", default_value = "8")]
     folding_factor: usize,
+
+    /// Maximum degree of the FRI remainder polynomial; must be one less than a power of two
+    #[structopt(short = "r", long = "remainder_degree", default_value = "31")]
+    fri_remainder_max_degree: usize,
+
+    /// Seed for randomly generated inputs of the example; random if not specified
+    #[structopt(short = "s", long = "seed")]
+    #[cfg_attr(not(feature = "std"), allow(dead_code))]
+    seed: Option<u64>,
 }
 
 impl ExampleOptions {
+    /// Returns the example specified by these options.
+    pub fn get_example(&self) -> Result<Box<dyn Example>, String> {
+        match self.example {
+            ExampleType::Fib { sequence_length } => {
+                fibonacci::fib2::get_example(self, sequence_length)
+            }
+            ExampleType::Fib8 { sequence_length } => {
+                fibonacci::fib8::get_example(self, sequence_length)
+            }
+            ExampleType::Mulfib { sequence_length } => {
+                fibonacci::mulfib2::get_example(self, sequence_length)
+            }
+            ExampleType::Mulfib8 { sequence_length } => {
+                fibonacci::mulfib8::get_example(self, sequence_length)
+            }
+            ExampleType::FibSmall { sequence_length } => {
+                fibonacci::fib_small::get_example(self, sequence_length)
+            }
+            ExampleType::Vdf { num_steps } => vdf::regular::get_example(self, num_steps),
+            ExampleType::VdfExempt { num_steps } => vdf::exempt::get_example(self, num_steps),
+            ExampleType::Rescue { chain_length } => rescue::get_example(self, chain_length),
+            #[cfg(feature = "std")]
+            ExampleType::RescueRaps { chain_length } => {
+                rescue_raps::get_example(self, chain_length)
+            }
+            #[cfg(feature = "std")]
+            ExampleType::Merkle { tree_depth } => merkle::get_example(self, tree_depth),
+            #[cfg(feature = "std")]
+            ExampleType::LamportA { num_signatures } => {
+                lamport::aggregate::get_example(self, num_signatures)
+            }
+            #[cfg(feature = "std")]
+            ExampleType::LamportT { num_signers } => {
+                lamport::threshold::get_example(self, num_signers)
+            }
+            #[cfg(feature = "std")]
+            ExampleType::Schnorr { num_signatures } => schnorr::get_example(self, num_signatures),
+            ExampleType::Vm { num_iterations } => vm::get_example(self, num_iterations),
+        }
+    }
+
+    /// Returns the seed from which randomly generated inputs of the example are derived.
+    ///
+    /// If the seed was not specified, a random seed is returned.
+    #[cfg(feature = "std")]
+    pub fn input_seed(&self) -> [u8; 32] {
+        match self.seed {
+            Some(seed) => {
+                let mut result = [0; 32];
+                result[..8].copy_from_slice(&seed.to_le_bytes());
+                result
+            }
+            None => rand_utils::rand_array(),
+        }
+    }
+
     pub fn to_proof_options(&self, q: usize, b: usize) -> (ProofOptions, HashFunction) {
         let num_queries = self.num_queries.unwrap_or(q);
         let blowup_factor = self.blowup_factor.unwrap_or(b);
@@ -99,7 +169,7 @@ impl ExampleOptions {
                 self.grinding_factor,
                 field_extension,
                 self.folding_factor,
-                31,
+                self.fri_remainder_max_degree,
             ),
             hash_fn,
         )
@@ -182,7 +252,7 @@ pub enum ExampleType {
     /// Compute a root of a Merkle path using Rescue hash function
     #[cfg(feature = "std")]
     Merkle {
-        /// Depth of the Merkle tree; must be one less than a power of two
+        /// Depth of the Merkle tree
         #[structopt(short = "n", default_value = "7")]
         tree_depth: usize,
     },
@@ -200,6 +270,19 @@ pub enum ExampleType {
         #[structopt(short = "n", default_value = "3")]
         num_signers: usize,
     },
+    /// Verify Schnorr signatures over an elliptic curve defined over an extension of `f64` field
+    #[cfg(feature = "std")]
+    Schnorr {
+        /// Number of signatures to verify; must be a power of two greater than 1
+        #[structopt(short = "n", default_value = "4")]
+        num_signatures: usize,
+    },
+    /// Execute a program of a minimal register VM in a loop
+    Vm {
+        /// Number of times the program is executed; must be a power of two
+        #[structopt(short = "n", default_value = "1024")]
+        num_iterations: usize,
+    },
 }
 
 /// Defines a set of hash functions available for the provided examples. Some examples may not
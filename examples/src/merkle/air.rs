@@ -15,17 +15,19 @@ use winterfell::{
 
 pub struct PublicInputs {
     pub tree_root: [BaseElement; 2],
+    pub tree_depth: usize,
 }
 
 impl ToElements<BaseElement> for PublicInputs {
     fn to_elements(&self) -> Vec<BaseElement> {
-        self.tree_root.to_vec()
+        vec![self.tree_root[0], self.tree_root[1], BaseElement::from(self.tree_depth as u64)]
     }
 }
 
 pub struct MerkleAir {
     context: AirContext<BaseElement>,
     tree_root: [BaseElement; 2],
+    tree_depth: usize,
 }
 
 impl Air for MerkleAir {
@@ -45,9 +47,15 @@ impl Air for MerkleAir {
             TransitionConstraintDegree::new(2),
         ];
         assert_eq!(TRACE_WIDTH, trace_info.width());
+        assert!(
+            (pub_inputs.tree_depth + 1) * HASH_CYCLE_LEN <= trace_info.length(),
+            "execution trace is too short for a Merkle tree of depth {}",
+            pub_inputs.tree_depth
+        );
         MerkleAir {
             context: AirContext::new(trace_info, degrees, 4, options),
             tree_root: pub_inputs.tree_root,
+            tree_depth: pub_inputs.tree_depth,
         }
     }
 
@@ -100,12 +108,13 @@ impl Air for MerkleAir {
     }
 
     fn get_assertions(&self) -> Vec<Assertion<Self::BaseField>> {
-        // assert that Merkle path resolves to the tree root, and that hash capacity
-        // registers (registers 4 and 5) are reset to ZERO every 8 steps
-        let last_step = self.trace_length() - 1;
+        // assert that Merkle path resolves to the tree root at the end of the last hash cycle of
+        // the path (cycles after it only pad the trace to a power of two length), and that hash
+        // capacity registers (registers 4 and 5) are reset to ZERO every 8 steps
+        let root_step = (self.tree_depth + 1) * HASH_CYCLE_LEN - 1;
         vec![
-            Assertion::single(0, last_step, self.tree_root[0]),
-            Assertion::single(1, last_step, self.tree_root[1]),
+            Assertion::single(0, root_step, self.tree_root[0]),
+            Assertion::single(1, root_step, self.tree_root[1]),
             Assertion::periodic(4, 0, HASH_CYCLE_LEN, BaseElement::ZERO),
             Assertion::periodic(5, 0, HASH_CYCLE_LEN, BaseElement::ZERO),
         ]
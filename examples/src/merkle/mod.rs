@@ -13,7 +13,7 @@ use crate::{
 };
 use core::marker::PhantomData;
 use log::debug;
-use rand_utils::{rand_value, rand_vector};
+use rand_utils::{prng_vector, rand_array};
 use std::time::Instant;
 use winterfell::{
     crypto::{DefaultRandomCoin, Digest, ElementHasher, MerkleTree},
@@ -41,16 +41,19 @@ pub fn get_example(
     options: &ExampleOptions,
     tree_depth: usize,
 ) -> Result<Box<dyn Example>, String> {
+    let seed = options.input_seed();
     let (options, hash_fn) = options.to_proof_options(28, 8);
 
     match hash_fn {
         HashFunction::Blake3_192 => {
-            Ok(Box::new(MerkleExample::<Blake3_192>::new(tree_depth, options)))
+            Ok(Box::new(MerkleExample::<Blake3_192>::with_seed(tree_depth, options, seed)))
         }
         HashFunction::Blake3_256 => {
-            Ok(Box::new(MerkleExample::<Blake3_256>::new(tree_depth, options)))
+            Ok(Box::new(MerkleExample::<Blake3_256>::with_seed(tree_depth, options, seed)))
+        }
+        HashFunction::Sha3_256 => {
+            Ok(Box::new(MerkleExample::<Sha3_256>::with_seed(tree_depth, options, seed)))
         }
-        HashFunction::Sha3_256 => Ok(Box::new(MerkleExample::<Sha3_256>::new(tree_depth, options))),
         _ => Err("The specified hash function cannot be used with this example.".to_string()),
     }
 }
@@ -66,17 +69,21 @@ pub struct MerkleExample<H: ElementHasher> {
 
 impl<H: ElementHasher> MerkleExample<H> {
     pub fn new(tree_depth: usize, options: ProofOptions) -> Self {
-        assert!(
-            (tree_depth + 1).is_power_of_two(),
-            "tree depth must be one less than a power of 2"
-        );
+        Self::with_seed(tree_depth, options, rand_array())
+    }
+
+    /// Returns a new example in which the index of the leaf and values of all other leaves are
+    /// derived from the specified seed.
+    pub fn with_seed(tree_depth: usize, options: ProofOptions, seed: [u8; 32]) -> Self {
+        assert!(tree_depth > 0, "tree depth must be greater than zero");
         let value = [BaseElement::new(42), BaseElement::new(43)];
-        let index =
-            (rand_value::<BaseElement>().as_int() % u128::pow(2, tree_depth as u32)) as usize;
+        let num_leaves = usize::pow(2, tree_depth as u32);
+        let elements: Vec<BaseElement> = prng_vector(seed, num_leaves * 2 + 1);
+        let index = (elements[0].as_int() % num_leaves as u128) as usize;
 
         // build Merkle tree of the specified depth
         let now = Instant::now();
-        let tree = build_merkle_tree(tree_depth, value, index);
+        let tree = build_merkle_tree(&elements[1..], value, index);
         debug!("Built Merkle tree of depth {} in {} ms", tree_depth, now.elapsed().as_millis(),);
 
         // compute Merkle path form the leaf specified by the index
@@ -98,6 +105,12 @@ impl<H: ElementHasher> MerkleExample<H> {
             _hasher: PhantomData,
         }
     }
+
+    /// Returns the depth of the Merkle tree; the path includes the hash of the leaf value, and
+    /// thus, is one node longer than the depth of the tree.
+    fn tree_depth(&self) -> usize {
+        self.path.len() - 1
+    }
 }
 
 // EXAMPLE IMPLEMENTATION
@@ -112,10 +125,10 @@ where
         debug!(
             "Generating proof for proving membership in a Merkle tree of depth {}\n\
             ---------------------",
-            self.path.len()
+            self.tree_depth()
         );
         // create the prover
-        let prover = MerkleProver::<H>::new(self.options.clone());
+        let prover = MerkleProver::<H>::new(self.tree_depth(), self.options.clone());
 
         // generate the execution trace
         let now = Instant::now();
@@ -135,6 +148,7 @@ where
     fn verify(&self, proof: StarkProof) -> Result<(), VerifierError> {
         let pub_inputs = PublicInputs {
             tree_root: self.tree_root.to_elements(),
+            tree_depth: self.tree_depth(),
         };
         let acceptable_options =
             winterfell::AcceptableOptions::OptionSet(vec![proof.options().clone()]);
@@ -149,6 +163,7 @@ where
         let tree_root = self.tree_root.to_elements();
         let pub_inputs = PublicInputs {
             tree_root: [tree_root[1], tree_root[0]],
+            tree_depth: self.tree_depth(),
         };
         let acceptable_options =
             winterfell::AcceptableOptions::OptionSet(vec![proof.options().clone()]);
@@ -162,9 +177,11 @@ where
 
 // HELPER FUNCTIONS
 // ================================================================================================
-fn build_merkle_tree(depth: usize, value: [BaseElement; 2], index: usize) -> MerkleTree<Rescue128> {
-    let num_leaves = usize::pow(2, depth as u32);
-    let leaf_elements: Vec<BaseElement> = rand_vector(num_leaves * 2);
+fn build_merkle_tree(
+    leaf_elements: &[BaseElement],
+    value: [BaseElement; 2],
+    index: usize,
+) -> MerkleTree<Rescue128> {
     let mut leaves = Vec::new();
     for i in (0..leaf_elements.len()).step_by(2) {
         leaves.push(Hash::new(leaf_elements[i], leaf_elements[i + 1]));
@@ -10,7 +10,7 @@ use super::{
 };
 use winterfell::{
     matrix::ColMatrix, AuxTraceRandElements, ConstraintCompositionCoefficients,
-    DefaultConstraintEvaluator, DefaultTraceLde, StarkDomain, Trace, TraceInfo, TracePolyTable,
+    DefaultConstraintEvaluator, DefaultTraceLde, StarkDomain, TraceInfo, TracePolyTable,
     TraceTable,
 };
 
@@ -19,13 +19,15 @@ use winterfell::{
 
 pub struct MerkleProver<H: ElementHasher> {
     options: ProofOptions,
+    tree_depth: usize,
     _hasher: PhantomData<H>,
 }
 
 impl<H: ElementHasher> MerkleProver<H> {
-    pub fn new(options: ProofOptions) -> Self {
+    pub fn new(tree_depth: usize, options: ProofOptions) -> Self {
         Self {
             options,
+            tree_depth,
             _hasher: PhantomData,
         }
     }
@@ -36,8 +38,11 @@ impl<H: ElementHasher> MerkleProver<H> {
         branch: &[rescue::Hash],
         index: usize,
     ) -> TraceTable<BaseElement> {
-        // allocate memory to hold the trace table
-        let trace_length = branch.len() * HASH_CYCLE_LEN;
+        assert_eq!(self.tree_depth + 1, branch.len(), "branch length does not match tree depth");
+
+        // allocate memory to hold the trace table; the trace is padded with extra hash cycles so
+        // that its length is a power of two
+        let trace_length = branch.len().next_power_of_two() * HASH_CYCLE_LEN;
         let mut trace = TraceTable::new(TRACE_WIDTH, trace_length);
 
         // skip the first node of the branch because it will be computed in the trace as hash(value)
@@ -65,8 +70,14 @@ impl<H: ElementHasher> MerkleProver<H> {
                 if cycle_pos < NUM_HASH_ROUNDS {
                     rescue::apply_round(&mut state[..HASH_STATE_WIDTH], step);
                 } else {
-                    let branch_node = branch[cycle_num].to_elements();
-                    let index_bit = BaseElement::new(((index >> cycle_num) & 1) as u128);
+                    // in padding cycles, the accumulated hash is merged with ZERO nodes
+                    let (branch_node, index_bit) = match branch.get(cycle_num) {
+                        Some(node) => (
+                            node.to_elements(),
+                            BaseElement::new(((index >> cycle_num) & 1) as u128),
+                        ),
+                        None => ([BaseElement::ZERO; 2], BaseElement::ZERO),
+                    };
                     if index_bit == BaseElement::ZERO {
                         // if index bit is zero, new branch node goes into registers [2, 3]; values
                         // in registers [0, 1] (the accumulated hash) remain unchanged
@@ -113,9 +124,10 @@ where
         DefaultConstraintEvaluator<'a, Self::Air, E>;
 
     fn get_pub_inputs(&self, trace: &Self::Trace) -> PublicInputs {
-        let last_step = trace.length() - 1;
+        let root_step = (self.tree_depth + 1) * HASH_CYCLE_LEN - 1;
         PublicInputs {
-            tree_root: [trace.get(0, last_step), trace.get(1, last_step)],
+            tree_root: [trace.get(0, root_step), trace.get(1, root_step)],
+            tree_depth: self.tree_depth,
         }
     }
 
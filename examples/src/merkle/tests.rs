@@ -24,6 +24,19 @@ fn merkle_test_basic_proof_verification_fail() {
     crate::tests::test_basic_proof_verification_fail(merkle);
 }
 
+#[test]
+fn merkle_test_any_tree_depth() {
+    // execution traces for these trees are padded to a power of two length
+    for tree_depth in [1, 4, 5] {
+        let merkle =
+            Box::new(super::MerkleExample::<Blake3_256>::new(tree_depth, build_options(false)));
+        crate::tests::test_basic_proof_verification(merkle);
+    }
+
+    let merkle = Box::new(super::MerkleExample::<Blake3_256>::new(5, build_options(false)));
+    crate::tests::test_basic_proof_verification_fail(merkle);
+}
+
 fn build_options(use_extension_field: bool) -> ProofOptions {
     let extension = if use_extension_field {
         FieldExtension::Quadratic
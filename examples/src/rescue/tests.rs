@@ -3,8 +3,10 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use super::Blake3_256;
-use winterfell::{FieldExtension, ProofOptions};
+use super::{BaseElement, Blake3_256, DefaultRandomCoin, Prover, RescueAir, RescueProver, Trace};
+use winterfell::{
+    AcceptableOptions, Air, FieldExtension, ProofOptions, ProverSession, SessionStats,
+};
 
 #[test]
 fn rescue_test_basic_proof_verification() {
@@ -24,6 +26,50 @@ fn rescue_test_basic_proof_verification_fail() {
     crate::tests::test_basic_proof_verification_fail(rescue_eg);
 }
 
+#[test]
+fn rescue_test_prove_in_session() {
+    let prover = RescueProver::<Blake3_256>::new(build_options(false));
+    let seed = [BaseElement::from(42u8), BaseElement::from(43u8)];
+    let trace = prover.build_trace(seed, 32);
+    let air = RescueAir::new(trace.get_info(), prover.get_pub_inputs(&trace), build_options(false));
+    let session = ProverSession::new(&air);
+
+    // proofs generated in a session must be identical to proofs generated without it
+    let proof = prover.prove_in_session(trace.clone(), &session).unwrap();
+    assert_eq!(proof.to_bytes(), prover.prove(trace.clone()).unwrap().to_bytes());
+
+    let pub_inputs = prover.get_pub_inputs(&trace);
+    let acceptable_options = AcceptableOptions::OptionSet(vec![proof.options().clone()]);
+    let verified = winterfell::verify::<RescueAir, Blake3_256, DefaultRandomCoin<Blake3_256>>(
+        proof,
+        pub_inputs,
+        &acceptable_options,
+    );
+    assert!(verified.is_ok());
+
+    // periodic values, the transition divisor, and both assertion divisors are served from the
+    // session; this holds for traces with different inputs as well
+    let seed = [BaseElement::from(1u8), BaseElement::from(2u8)];
+    prover.prove_in_session(prover.build_trace(seed, 32), &session).unwrap();
+    let stats = session.stats();
+    assert_eq!(
+        SessionStats {
+            num_proofs: 2,
+            num_rejected: 0,
+            num_hits: 8,
+            num_misses: 0,
+            size_in_bytes: session.size_in_bytes(),
+        },
+        stats
+    );
+    assert!(stats.size_in_bytes > 0);
+
+    // a session built for a different trace length is ignored
+    prover.prove_in_session(prover.build_trace(seed, 64), &session).unwrap();
+    assert_eq!(1, session.stats().num_rejected);
+    assert_eq!(8, session.stats().num_hits);
+}
+
 fn build_options(use_extension_field: bool) -> ProofOptions {
     let extension = if use_extension_field {
         FieldExtension::Quadratic
@@ -172,9 +172,10 @@ impl<B: StarkField> Trace for RapTraceTable<B> {
     }
 
     fn read_main_frame(&self, row_idx: usize, frame: &mut EvaluationFrame<Self::BaseField>) {
-        let next_row_idx = (row_idx + 1) % self.length();
-        self.trace.read_row_into(row_idx, frame.current_mut());
-        self.trace.read_row_into(next_row_idx, frame.next_mut());
+        for row in 0..frame.num_rows() {
+            let offset_row_idx = (row_idx + frame.offsets()[row]) % self.length();
+            self.trace.read_row_into(offset_row_idx, frame.row_mut(row));
+        }
     }
 
     fn main_segment(&self) -> &ColMatrix<B> {
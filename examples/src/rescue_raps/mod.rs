@@ -6,7 +6,7 @@
 use crate::{Blake3_192, Blake3_256, Example, ExampleOptions, HashFunction, Sha3_256};
 use core::marker::PhantomData;
 use log::debug;
-use rand_utils::rand_array;
+use rand_utils::{prng_vector, rand_array};
 use std::time::Instant;
 use winterfell::{
     crypto::{DefaultRandomCoin, ElementHasher},
@@ -42,17 +42,22 @@ pub fn get_example(
     options: &ExampleOptions,
     chain_length: usize,
 ) -> Result<Box<dyn Example>, String> {
+    let seed = options.input_seed();
     let (options, hash_fn) = options.to_proof_options(42, 4);
 
     match hash_fn {
-        HashFunction::Blake3_192 => {
-            Ok(Box::new(RescueRapsExample::<Blake3_192>::new(chain_length, options)))
-        }
-        HashFunction::Blake3_256 => {
-            Ok(Box::new(RescueRapsExample::<Blake3_256>::new(chain_length, options)))
-        }
+        HashFunction::Blake3_192 => Ok(Box::new(RescueRapsExample::<Blake3_192>::with_seed(
+            chain_length,
+            options,
+            seed,
+        ))),
+        HashFunction::Blake3_256 => Ok(Box::new(RescueRapsExample::<Blake3_256>::with_seed(
+            chain_length,
+            options,
+            seed,
+        ))),
         HashFunction::Sha3_256 => {
-            Ok(Box::new(RescueRapsExample::<Sha3_256>::new(chain_length, options)))
+            Ok(Box::new(RescueRapsExample::<Sha3_256>::with_seed(chain_length, options, seed)))
         }
         _ => Err("The specified hash function cannot be used with this example.".to_string()),
     }
@@ -69,13 +74,18 @@ pub struct RescueRapsExample<H: ElementHasher> {
 
 impl<H: ElementHasher> RescueRapsExample<H> {
     pub fn new(chain_length: usize, options: ProofOptions) -> Self {
+        Self::with_seed(chain_length, options, rand_array())
+    }
+
+    /// Returns a new example in which the values absorbed by the hash chains are derived from
+    /// the specified seed.
+    pub fn with_seed(chain_length: usize, options: ProofOptions, seed: [u8; 32]) -> Self {
         assert!(chain_length.is_power_of_two(), "chain length must a power of 2");
         assert!(chain_length > 2, "chain length must be at least 4");
 
-        let mut seeds = vec![[BaseElement::ZERO; 2]; chain_length];
-        for internal_seed in seeds.iter_mut() {
-            *internal_seed = rand_array();
-        }
+        let elements: Vec<BaseElement> = prng_vector(seed, chain_length * 2);
+        let seeds: Vec<[BaseElement; 2]> =
+            elements.chunks(2).map(|chunk| [chunk[0], chunk[1]]).collect();
         let mut permuted_seeds = seeds[2..].to_vec();
         permuted_seeds.push(seeds[0]);
         permuted_seeds.push(seeds[1]);
@@ -0,0 +1,298 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{
+    curve::{ext_add, ext_elements, ext_mul, ext_scale, ext_sub, CURVE_D},
+    AffinePoint, BaseElement, FieldElement, ProofOptions, Scalar, Signature, D_COL, E_ACC_COL,
+    E_BIT_COL, GENERATOR, LIMB_BITS, N_COL, PK_COL, P_COL, Q_COL, SIG_CYCLE_LENGTH, S_ACC_COL,
+    S_BIT_COL, TRACE_WIDTH, U_COL, V_COL,
+};
+use crate::utils::{are_equal, is_binary, not, EvaluationResult};
+use winterfell::{
+    math::ToElements, Air, AirContext, Assertion, EvaluationFrame, TraceInfo,
+    TransitionConstraintDegree,
+};
+
+// TYPES
+// ================================================================================================
+
+/// Coordinates (x, y) of a curve point, each given by 3 elements.
+type Point<E> = ([E; 3], [E; 3]);
+
+// SCHNORR AIR
+// ================================================================================================
+
+#[derive(Clone)]
+pub struct PublicInputs {
+    pub pub_keys: Vec<AffinePoint>,
+    pub signatures: Vec<Signature>,
+    pub challenges: Vec<Scalar>,
+}
+
+impl ToElements<BaseElement> for PublicInputs {
+    fn to_elements(&self) -> Vec<BaseElement> {
+        let mut result = Vec::new();
+        for ((pub_key, signature), challenge) in
+            self.pub_keys.iter().zip(&self.signatures).zip(&self.challenges)
+        {
+            result.extend_from_slice(&pub_key.to_elements());
+            result.extend_from_slice(&signature.r.to_elements());
+            result.extend(signature.s.limbs().map(BaseElement::from));
+            result.extend(challenge.limbs().map(BaseElement::from));
+        }
+        result
+    }
+}
+
+pub struct SchnorrAir {
+    context: AirContext<BaseElement>,
+    pub_inputs: PublicInputs,
+}
+
+impl Air for SchnorrAir {
+    type BaseField = BaseElement;
+    type PublicInputs = PublicInputs;
+
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+    fn new(trace_info: TraceInfo, pub_inputs: PublicInputs, options: ProofOptions) -> Self {
+        let point_update = TransitionConstraintDegree::with_cycles(3, vec![SIG_CYCLE_LENGTH]);
+        let product = TransitionConstraintDegree::with_cycles(2, vec![SIG_CYCLE_LENGTH]);
+        let key_copy = TransitionConstraintDegree::with_cycles(1, vec![SIG_CYCLE_LENGTH]);
+        let limb = TransitionConstraintDegree::with_cycles(1, vec![LIMB_BITS]);
+
+        let mut degrees = vec![point_update.clone(); 6]; // doubling
+        degrees.append(&mut vec![product; 6]); // products of coordinates
+        degrees.append(&mut vec![point_update; 12]); // addition and point selection
+        degrees.append(&mut vec![TransitionConstraintDegree::new(3); 6]); // G - P
+        degrees.append(&mut vec![key_copy; 6]); // public key copy
+        degrees.append(&mut vec![TransitionConstraintDegree::new(2); 2]); // binary bits
+        degrees.append(&mut vec![limb; 4]); // limb accumulators
+
+        assert_eq!(TRACE_WIDTH, trace_info.width());
+        assert_eq!(
+            trace_info.length(),
+            pub_inputs.signatures.len() * SIG_CYCLE_LENGTH,
+            "execution trace must have {} steps per signature",
+            SIG_CYCLE_LENGTH
+        );
+        SchnorrAir {
+            context: AirContext::new(trace_info, degrees, 22, options),
+            pub_inputs,
+        }
+    }
+
+    fn context(&self) -> &AirContext<Self::BaseField> {
+        &self.context
+    }
+
+    fn evaluate_transition<E: FieldElement + From<Self::BaseField>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        let current = frame.current();
+        let next = frame.next();
+        debug_assert_eq!(TRACE_WIDTH, current.len());
+        debug_assert_eq!(TRACE_WIDTH, next.len());
+
+        // the accumulator is updated on all steps except for the last step of each signature
+        let update_flag = periodic_values[0];
+        let limb_end = periodic_values[1];
+        let limb_start = periodic_values[2];
+
+        let acc = read_point(current, N_COL);
+        let doubled = read_point(next, D_COL);
+        let addend = read_point(next, P_COL);
+        let next_acc = read_point(next, N_COL);
+        let u = read_ext(next, U_COL);
+        let v = read_ext(next, V_COL);
+        let pub_key = read_point(current, PK_COL);
+        let next_pub_key = read_point(next, PK_COL);
+        let next_q = read_point(next, Q_COL);
+
+        // the accumulator is doubled: this is the addition law specialized for doubling, in which
+        // 1 + d * x^2 * y^2 = x^2 + y^2 and 1 - d * x^2 * y^2 = 2 - x^2 - y^2 since the point
+        // lies on the curve
+        let x2 = ext_mul(acc.0, acc.0);
+        let y2 = ext_mul(acc.1, acc.1);
+        let x2_plus_y2 = ext_add(x2, y2);
+        let xy = ext_mul(acc.0, acc.1);
+        let two = E::from(2u32);
+        let two_minus_x2_minus_y2 = ext_sub([two, E::ZERO, E::ZERO], x2_plus_y2);
+        enforce_ext(
+            result,
+            0,
+            update_flag,
+            ext_sub(ext_mul(doubled.0, x2_plus_y2), ext_scale(xy, two)),
+        );
+        enforce_ext(
+            result,
+            3,
+            update_flag,
+            ext_sub(ext_mul(doubled.1, two_minus_x2_minus_y2), ext_sub(y2, x2)),
+        );
+
+        // then, the selected point is added to the doubled accumulator; products of coordinates
+        // are kept in separate columns to keep the degree of constraints low
+        enforce_ext(result, 6, update_flag, ext_sub(u, ext_mul(doubled.0, addend.0)));
+        enforce_ext(result, 9, update_flag, ext_sub(v, ext_mul(doubled.1, addend.1)));
+        let (x, y) = add_points(doubled, addend, u, v, next_acc);
+        enforce_ext(result, 12, update_flag, x);
+        enforce_ext(result, 15, update_flag, y);
+
+        // the added point is selected by the bits of the scalars: identity for (0, 0), G for
+        // (1, 0), -P for (0, 1), and G - P for (1, 1)
+        let s_bit = next[S_BIT_COL];
+        let e_bit = next[E_BIT_COL];
+        let g_flag = s_bit * not(e_bit);
+        let p_flag = e_bit * not(s_bit);
+        let q_flag = s_bit * e_bit;
+        let identity_flag = not(s_bit) * not(e_bit);
+        let g = generator::<E>();
+        let selected_x = ext_add(
+            ext_sub(ext_scale(g.0, g_flag), ext_scale(next_pub_key.0, p_flag)),
+            ext_scale(next_q.0, q_flag),
+        );
+        let selected_y = ext_add(
+            ext_add(ext_scale(g.1, g_flag), ext_scale(next_pub_key.1, p_flag)),
+            ext_add(ext_scale(next_q.1, q_flag), [identity_flag, E::ZERO, E::ZERO]),
+        );
+        enforce_ext(result, 18, update_flag, ext_sub(addend.0, selected_x));
+        enforce_ext(result, 21, update_flag, ext_sub(addend.1, selected_y));
+
+        // G - P is computed on every step
+        let neg_pub_key =
+            ([-next_pub_key.0[0], -next_pub_key.0[1], -next_pub_key.0[2]], next_pub_key.1);
+        let g_x_neg_p_x = ext_mul(g.0, neg_pub_key.0);
+        let g_y_p_y = ext_mul(g.1, neg_pub_key.1);
+        let (x, y) = add_points(g, neg_pub_key, g_x_neg_p_x, g_y_p_y, next_q);
+        enforce_ext(result, 24, E::ONE, x);
+        enforce_ext(result, 27, E::ONE, y);
+
+        // the public key stays the same for all steps of a signature
+        enforce_ext(result, 30, update_flag, ext_sub(next_pub_key.0, pub_key.0));
+        enforce_ext(result, 33, update_flag, ext_sub(next_pub_key.1, pub_key.1));
+
+        // bits of the scalars are binary, and are accumulated into 32-bit limbs; the limbs are
+        // checked against the public inputs by the assertions, and thus, this also checks that
+        // the limbs are in range
+        result[36] = is_binary(s_bit);
+        result[37] = is_binary(e_bit);
+        result[38] = are_equal(next[S_ACC_COL], current[S_ACC_COL] * two * not(limb_end) + s_bit);
+        result[39] = are_equal(next[E_ACC_COL], current[E_ACC_COL] * two * not(limb_end) + e_bit);
+        result[40] = limb_start * are_equal(current[S_ACC_COL], current[S_BIT_COL]);
+        result[41] = limb_start * are_equal(current[E_ACC_COL], current[E_BIT_COL]);
+    }
+
+    fn get_assertions(&self) -> Vec<Assertion<Self::BaseField>> {
+        let num_signatures = self.pub_inputs.signatures.len();
+        let mut pub_keys = vec![Vec::new(); 6];
+        let mut results = vec![Vec::new(); 6];
+        let mut s_limbs = Vec::with_capacity(num_signatures * 8);
+        let mut e_limbs = Vec::with_capacity(num_signatures * 8);
+        for ((pub_key, signature), challenge) in self
+            .pub_inputs
+            .pub_keys
+            .iter()
+            .zip(&self.pub_inputs.signatures)
+            .zip(&self.pub_inputs.challenges)
+        {
+            for (i, value) in pub_key.to_elements().into_iter().enumerate() {
+                pub_keys[i].push(value);
+            }
+            for (i, value) in signature.r.to_elements().into_iter().enumerate() {
+                results[i].push(value);
+            }
+            s_limbs.extend(signature.s.limbs().map(BaseElement::from));
+            e_limbs.extend(challenge.limbs().map(BaseElement::from));
+        }
+
+        let mut assertions = Vec::new();
+        let identity = AffinePoint::IDENTITY.to_elements();
+        for i in 0..6 {
+            // at the first step of each signature, the accumulator is set to the identity, and
+            // public key is set to the public key of the signer
+            assertions.push(Assertion::periodic(N_COL + i, 0, SIG_CYCLE_LENGTH, identity[i]));
+            assertions.push(Assertion::sequence(
+                PK_COL + i,
+                0,
+                SIG_CYCLE_LENGTH,
+                pub_keys[i].clone(),
+            ));
+
+            // at the last step, the accumulator must be equal to s * G - e * P = R
+            assertions.push(Assertion::sequence(
+                N_COL + i,
+                SIG_CYCLE_LENGTH - 1,
+                SIG_CYCLE_LENGTH,
+                results[i].clone(),
+            ));
+        }
+
+        // the most significant bits of scalars are not used and must be 0; limbs of the scalars
+        // must match the public inputs
+        assertions.push(Assertion::periodic(S_BIT_COL, 0, SIG_CYCLE_LENGTH, BaseElement::ZERO));
+        assertions.push(Assertion::periodic(E_BIT_COL, 0, SIG_CYCLE_LENGTH, BaseElement::ZERO));
+        assertions.push(Assertion::sequence(S_ACC_COL, LIMB_BITS - 1, LIMB_BITS, s_limbs));
+        assertions.push(Assertion::sequence(E_ACC_COL, LIMB_BITS - 1, LIMB_BITS, e_limbs));
+
+        assertions
+    }
+
+    fn get_periodic_column_values(&self) -> Vec<Vec<Self::BaseField>> {
+        let mut update_flags = vec![BaseElement::ONE; SIG_CYCLE_LENGTH];
+        update_flags[SIG_CYCLE_LENGTH - 1] = BaseElement::ZERO;
+
+        let mut limb_end = vec![BaseElement::ZERO; LIMB_BITS];
+        limb_end[LIMB_BITS - 1] = BaseElement::ONE;
+
+        let mut limb_start = vec![BaseElement::ZERO; LIMB_BITS];
+        limb_start[0] = BaseElement::ONE;
+
+        vec![update_flags, limb_end, limb_start]
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns coordinates of the generator.
+fn generator<E: FieldElement + From<BaseElement>>() -> Point<E> {
+    (ext_elements(GENERATOR.x), ext_elements(GENERATOR.y))
+}
+
+fn read_ext<E: FieldElement>(row: &[E], col: usize) -> [E; 3] {
+    [row[col], row[col + 1], row[col + 2]]
+}
+
+fn read_point<E: FieldElement>(row: &[E], col: usize) -> Point<E> {
+    (read_ext(row, col), read_ext(row, col + 3))
+}
+
+/// Returns evaluations of constraints which enforce that `sum` = `a` + `b`, where
+/// `u` = a.x * b.x and `v` = a.y * b.y; the constraints for x and y coordinates are returned
+/// separately.
+fn add_points<E: FieldElement + From<BaseElement>>(
+    a: Point<E>,
+    b: Point<E>,
+    u: [E; 3],
+    v: [E; 3],
+    sum: Point<E>,
+) -> ([E; 3], [E; 3]) {
+    let one = [E::ONE, E::ZERO, E::ZERO];
+    let t = ext_scale(ext_mul(u, v), E::from(CURVE_D));
+    let x = ext_sub(ext_mul(sum.0, ext_add(one, t)), ext_add(ext_mul(a.0, b.1), ext_mul(a.1, b.0)));
+    let y = ext_sub(ext_mul(sum.1, ext_sub(one, t)), ext_sub(v, u));
+    (x, y)
+}
+
+/// Enforces 3 constraints starting at `index` for each coordinate of the `value`.
+fn enforce_ext<E: FieldElement>(result: &mut [E], index: usize, flag: E, value: [E; 3]) {
+    for (i, value) in value.into_iter().enumerate() {
+        result.agg_constraint(index + i, flag, value);
+    }
+}
@@ -0,0 +1,144 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{BaseElement, ExtElement, FieldElement};
+use core::ops::{Add, Neg};
+
+// CONSTANTS
+// ================================================================================================
+
+/// Coefficient `d` of the curve equation x^2 + y^2 = 1 + d * x^2 * y^2.
+///
+/// 7 is not a square in the base field, and thus, it is not a square in the cubic extension of
+/// the base field either. This makes the addition law of the curve complete: the same formulas
+/// work for all pairs of points, including doubling and the identity.
+pub const CURVE_D: BaseElement = BaseElement::new(7);
+
+/// Generator of the group used for signatures.
+///
+/// The generator was computed as 4 * (x, y), where y = 3 + z (with z being the generator of
+/// the cubic extension), and x is a square root of (1 - y^2) / (1 - d * y^2).
+pub const GENERATOR: AffinePoint = AffinePoint {
+    x: ExtElement::new(
+        BaseElement::new(18353372609304405513),
+        BaseElement::new(1477374065740959999),
+        BaseElement::new(3319362216678943609),
+    ),
+    y: ExtElement::new(
+        BaseElement::new(11848574179864524171),
+        BaseElement::new(15583098690635041669),
+        BaseElement::new(3521415438544084729),
+    ),
+};
+
+// AFFINE POINT
+// ================================================================================================
+
+/// A point on a twisted Edwards curve x^2 + y^2 = 1 + d * x^2 * y^2 defined over the cubic
+/// extension of the 64-bit base field.
+///
+/// The group order of the curve has not been determined, and the curve has not been vetted for
+/// cryptographic use; the curve is used only to illustrate elliptic curve arithmetic in AIR.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AffinePoint {
+    pub x: ExtElement,
+    pub y: ExtElement,
+}
+
+impl AffinePoint {
+    /// Identity of the group.
+    pub const IDENTITY: Self = Self {
+        x: ExtElement::ZERO,
+        y: ExtElement::ONE,
+    };
+
+    /// Returns true if this point lies on the curve.
+    pub fn is_on_curve(&self) -> bool {
+        let x2 = self.x.square();
+        let y2 = self.y.square();
+        x2 + y2 == ExtElement::ONE + ExtElement::from(CURVE_D) * x2 * y2
+    }
+
+    /// Returns this point multiplied by a scalar defined by its bits in big-endian order.
+    pub fn mul_by_bits(self, bits: impl Iterator<Item = bool>) -> Self {
+        let mut result = Self::IDENTITY;
+        for bit in bits {
+            result = result + result;
+            if bit {
+                result = result + self;
+            }
+        }
+        result
+    }
+
+    /// Returns coordinates of this point as base field elements.
+    pub fn to_elements(&self) -> [BaseElement; 6] {
+        let [x0, x1, x2] = self.x.to_base_elements();
+        let [y0, y1, y2] = self.y.to_base_elements();
+        [x0, x1, x2, y0, y1, y2]
+    }
+}
+
+impl Add for AffinePoint {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        let t = ExtElement::from(CURVE_D) * self.x * other.x * self.y * other.y;
+        Self {
+            x: (self.x * other.y + self.y * other.x) / (ExtElement::ONE + t),
+            y: (self.y * other.y - self.x * other.x) / (ExtElement::ONE - t),
+        }
+    }
+}
+
+impl Neg for AffinePoint {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self {
+            x: -self.x,
+            y: self.y,
+        }
+    }
+}
+
+// EXTENSION FIELD ARITHMETIC
+// ================================================================================================
+// Elements of the cubic extension are represented by their coordinates with respect to the
+// basis (1, z, z^2), where z^3 = z + 1; these helpers are used to evaluate constraints over
+// such coordinates.
+
+/// Returns the sum of two extension field elements.
+pub fn ext_add<E: FieldElement>(a: [E; 3], b: [E; 3]) -> [E; 3] {
+    [a[0] + b[0], a[1] + b[1], a[2] + b[2]]
+}
+
+/// Returns the difference of two extension field elements.
+pub fn ext_sub<E: FieldElement>(a: [E; 3], b: [E; 3]) -> [E; 3] {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+/// Returns an extension field element multiplied by an element of the base field.
+pub fn ext_scale<E: FieldElement>(a: [E; 3], s: E) -> [E; 3] {
+    [a[0] * s, a[1] * s, a[2] * s]
+}
+
+/// Returns the product of two extension field elements.
+pub fn ext_mul<E: FieldElement>(a: [E; 3], b: [E; 3]) -> [E; 3] {
+    // reduce the product of two degree 2 polynomials using z^3 = z + 1 and z^4 = z^2 + z
+    let c3 = a[1] * b[2] + a[2] * b[1];
+    let c4 = a[2] * b[2];
+    [
+        a[0] * b[0] + c3,
+        a[0] * b[1] + a[1] * b[0] + c3 + c4,
+        a[0] * b[2] + a[1] * b[1] + a[2] * b[0] + c4,
+    ]
+}
+
+/// Returns the coordinates of the specified extension field element.
+pub fn ext_elements<E: FieldElement + From<BaseElement>>(a: ExtElement) -> [E; 3] {
+    let [a0, a1, a2] = a.to_base_elements();
+    [E::from(a0), E::from(a1), E::from(a2)]
+}
@@ -0,0 +1,259 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use crate::{Example, ExampleOptions, HashFunction};
+use core::marker::PhantomData;
+use log::debug;
+use rand_utils::{prng_vector, rand_array};
+use std::time::Instant;
+use winterfell::{
+    crypto::{DefaultRandomCoin, ElementHasher},
+    math::{
+        fields::{f64::BaseElement, CubeExtension},
+        FieldElement, StarkField,
+    },
+    ProofOptions, Prover, StarkProof, Trace, VerifierError,
+};
+
+mod air;
+use air::{PublicInputs, SchnorrAir};
+
+mod curve;
+pub use curve::{AffinePoint, GENERATOR};
+
+mod prover;
+use prover::SchnorrProver;
+
+mod signature;
+pub use signature::{challenge, verify, PrivateKey, Scalar, Signature};
+
+#[cfg(test)]
+mod tests;
+
+// CONSTANTS AND TYPES
+// ================================================================================================
+
+/// Elements of the field over which the curve is defined.
+type ExtElement = CubeExtension<BaseElement>;
+
+type Blake3_192 = winterfell::crypto::hashers::Blake3_192<BaseElement>;
+type Blake3_256 = winterfell::crypto::hashers::Blake3_256<BaseElement>;
+type Sha3_256 = winterfell::crypto::hashers::Sha3_256<BaseElement>;
+type Rp64_256 = winterfell::crypto::hashers::Rp64_256;
+type RpJive64_256 = winterfell::crypto::hashers::RpJive64_256;
+type GriffinJive64_256 = winterfell::crypto::hashers::GriffinJive64_256;
+
+/// Number of steps needed to verify a single signature: one step per bit of a scalar.
+const SIG_CYCLE_LENGTH: usize = Scalar::NUM_BITS;
+
+/// Number of bits in limbs into which scalars are decomposed.
+const LIMB_BITS: usize = 32;
+
+// Trace layout; each curve point takes 6 columns (3 per coordinate), and each element of the
+// extension field takes 3 columns.
+/// Doubled value of the accumulator.
+const D_COL: usize = 0;
+/// Point added to the doubled accumulator: one of identity, G, -P, and G - P.
+const P_COL: usize = 6;
+/// Next value of the accumulator.
+const N_COL: usize = 12;
+/// Product of x coordinates of the doubled accumulator and of the added point.
+const U_COL: usize = 18;
+/// Product of y coordinates of the doubled accumulator and of the added point.
+const V_COL: usize = 21;
+/// Public key P.
+const PK_COL: usize = 24;
+/// Sum of the generator and the negated public key (G - P).
+const Q_COL: usize = 30;
+/// Current bits of the response s and of the challenge e.
+const S_BIT_COL: usize = 36;
+const E_BIT_COL: usize = 37;
+/// Values of the current 32-bit limbs of s and e accumulated from their bits.
+const S_ACC_COL: usize = 38;
+const E_ACC_COL: usize = 39;
+
+const TRACE_WIDTH: usize = 40;
+
+// SCHNORR SIGNATURE EXAMPLE
+// ================================================================================================
+
+pub fn get_example(
+    options: &ExampleOptions,
+    num_signatures: usize,
+) -> Result<Box<dyn Example>, String> {
+    let seed = options.input_seed();
+    let (options, hash_fn) = options.to_proof_options(28, 8);
+
+    match hash_fn {
+        HashFunction::Blake3_192 => {
+            Ok(Box::new(SchnorrExample::<Blake3_192>::with_seed(num_signatures, options, seed)))
+        }
+        HashFunction::Blake3_256 => {
+            Ok(Box::new(SchnorrExample::<Blake3_256>::with_seed(num_signatures, options, seed)))
+        }
+        HashFunction::Sha3_256 => {
+            Ok(Box::new(SchnorrExample::<Sha3_256>::with_seed(num_signatures, options, seed)))
+        }
+        HashFunction::Rp64_256 => {
+            Ok(Box::new(SchnorrExample::<Rp64_256>::with_seed(num_signatures, options, seed)))
+        }
+        HashFunction::RpJive64_256 => Ok(Box::new(SchnorrExample::<RpJive64_256>::with_seed(
+            num_signatures,
+            options,
+            seed,
+        ))),
+        HashFunction::GriffinJive64_256 => Ok(Box::new(
+            SchnorrExample::<GriffinJive64_256>::with_seed(num_signatures, options, seed),
+        )),
+    }
+}
+
+pub struct SchnorrExample<H: ElementHasher> {
+    options: ProofOptions,
+    pub_keys: Vec<AffinePoint>,
+    messages: Vec<String>,
+    signatures: Vec<Signature>,
+    _hasher: PhantomData<H>,
+}
+
+impl<H: ElementHasher> SchnorrExample<H> {
+    pub fn new(num_signatures: usize, options: ProofOptions) -> Self {
+        Self::with_seed(num_signatures, options, rand_array())
+    }
+
+    /// Returns a new example in which the private keys are derived from the specified seed.
+    ///
+    /// # Panics
+    /// Panics if `num_signatures` is not a power of two or is smaller than 2.
+    pub fn with_seed(num_signatures: usize, options: ProofOptions, seed: [u8; 32]) -> Self {
+        assert!(
+            num_signatures.is_power_of_two() && num_signatures > 1,
+            "number of signatures must be a power of 2 greater than 1"
+        );
+
+        // generate private/public key pairs for the specified number of signatures
+        let now = Instant::now();
+        let key_seeds: Vec<u8> = prng_vector(seed, num_signatures * 32);
+        let private_keys = key_seeds
+            .chunks(32)
+            .map(|seed| PrivateKey::from_seed(seed.try_into().unwrap()))
+            .collect::<Vec<_>>();
+        let pub_keys = private_keys.iter().map(PrivateKey::pub_key).collect::<Vec<_>>();
+        debug!(
+            "Generated {} private-public key pairs in {} ms",
+            num_signatures,
+            now.elapsed().as_millis()
+        );
+
+        // sign messages
+        let now = Instant::now();
+        let messages = (0..num_signatures).map(|i| format!("test message {i}")).collect::<Vec<_>>();
+        let signatures = private_keys
+            .iter()
+            .zip(messages.iter())
+            .map(|(key, msg)| key.sign(msg.as_bytes()))
+            .collect::<Vec<_>>();
+        debug!("Signed {} messages in {} ms", num_signatures, now.elapsed().as_millis());
+
+        // verify signatures
+        let now = Instant::now();
+        for ((pub_key, msg), signature) in pub_keys.iter().zip(&messages).zip(&signatures) {
+            assert!(verify(pub_key, msg.as_bytes(), signature));
+        }
+        debug!("Verified {} signatures in {} ms", num_signatures, now.elapsed().as_millis());
+
+        SchnorrExample {
+            options,
+            pub_keys,
+            messages,
+            signatures,
+            _hasher: PhantomData,
+        }
+    }
+}
+
+// EXAMPLE IMPLEMENTATION
+// ================================================================================================
+
+impl<H: ElementHasher> Example for SchnorrExample<H>
+where
+    H: ElementHasher<BaseField = BaseElement>,
+{
+    fn prove(&self) -> StarkProof {
+        debug!(
+            "Generating proof for verifying {} Schnorr signatures\n\
+            ---------------------",
+            self.signatures.len(),
+        );
+
+        // create a prover
+        let pub_inputs = build_pub_inputs(&self.pub_keys, &self.messages, &self.signatures);
+        let prover = SchnorrProver::<H>::new(pub_inputs, self.options.clone());
+
+        // generate the execution trace
+        let now = Instant::now();
+        let trace = prover.build_trace(&self.pub_keys, &self.signatures);
+        let trace_length = trace.length();
+        debug!(
+            "Generated execution trace of {} registers and 2^{} steps in {} ms",
+            trace.width(),
+            trace_length.ilog2(),
+            now.elapsed().as_millis()
+        );
+
+        // generate the proof
+        prover.prove(trace).unwrap()
+    }
+
+    fn verify(&self, proof: StarkProof) -> Result<(), VerifierError> {
+        let pub_inputs = build_pub_inputs(&self.pub_keys, &self.messages, &self.signatures);
+        let acceptable_options =
+            winterfell::AcceptableOptions::OptionSet(vec![proof.options().clone()]);
+        winterfell::verify::<SchnorrAir, H, DefaultRandomCoin<H>>(
+            proof,
+            pub_inputs,
+            &acceptable_options,
+        )
+    }
+
+    fn verify_with_wrong_inputs(&self, proof: StarkProof) -> Result<(), VerifierError> {
+        let mut messages = self.messages.clone();
+        messages[1] = "wrong message".to_string();
+        let pub_inputs = build_pub_inputs(&self.pub_keys, &messages, &self.signatures);
+        let acceptable_options =
+            winterfell::AcceptableOptions::OptionSet(vec![proof.options().clone()]);
+        winterfell::verify::<SchnorrAir, H, DefaultRandomCoin<H>>(
+            proof,
+            pub_inputs,
+            &acceptable_options,
+        )
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns public inputs for verifying the specified signatures; challenges of the signatures
+/// are computed from the messages.
+fn build_pub_inputs(
+    pub_keys: &[AffinePoint],
+    messages: &[String],
+    signatures: &[Signature],
+) -> PublicInputs {
+    let challenges = pub_keys
+        .iter()
+        .zip(messages)
+        .zip(signatures)
+        .map(|((pub_key, msg), signature)| {
+            Scalar::from(challenge(pub_key, &signature.r, msg.as_bytes()))
+        })
+        .collect();
+
+    PublicInputs {
+        pub_keys: pub_keys.to_vec(),
+        signatures: signatures.to_vec(),
+        challenges,
+    }
+}
@@ -0,0 +1,136 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{
+    AffinePoint, BaseElement, DefaultRandomCoin, ElementHasher, FieldElement, PhantomData,
+    ProofOptions, Prover, PublicInputs, SchnorrAir, Signature, D_COL, E_ACC_COL, E_BIT_COL,
+    GENERATOR, LIMB_BITS, N_COL, PK_COL, P_COL, Q_COL, SIG_CYCLE_LENGTH, S_ACC_COL, S_BIT_COL,
+    TRACE_WIDTH, U_COL, V_COL,
+};
+use winterfell::{
+    matrix::ColMatrix, AuxTraceRandElements, ConstraintCompositionCoefficients,
+    DefaultConstraintEvaluator, DefaultTraceLde, StarkDomain, TraceInfo, TracePolyTable,
+    TraceTable,
+};
+
+#[cfg(feature = "concurrent")]
+use winterfell::iterators::*;
+
+// SCHNORR PROVER
+// ================================================================================================
+
+pub struct SchnorrProver<H: ElementHasher> {
+    pub_inputs: PublicInputs,
+    options: ProofOptions,
+    _hasher: PhantomData<H>,
+}
+
+impl<H: ElementHasher> SchnorrProver<H> {
+    pub fn new(pub_inputs: PublicInputs, options: ProofOptions) -> Self {
+        Self {
+            pub_inputs,
+            options,
+            _hasher: PhantomData,
+        }
+    }
+
+    /// Builds an execution trace for verifying the specified signatures; the challenges of the
+    /// signatures are taken from the public inputs of this prover.
+    pub fn build_trace(
+        &self,
+        pub_keys: &[AffinePoint],
+        signatures: &[Signature],
+    ) -> TraceTable<BaseElement> {
+        let trace_length = SIG_CYCLE_LENGTH * signatures.len();
+        let mut trace = TraceTable::new(TRACE_WIDTH, trace_length);
+
+        trace.fragments(SIG_CYCLE_LENGTH).for_each(|mut sig_trace| {
+            let i = sig_trace.index();
+            let pub_key = pub_keys[i];
+            let q = GENERATOR + (-pub_key);
+            let s_bits = signatures[i].s.bits();
+            let e_bits = self.pub_inputs.challenges[i].bits();
+
+            // compute s * G - e * P by processing bits of both scalars at the same time; since
+            // the most significant bits are 0, the first step leaves the accumulator unchanged
+            let mut acc = AffinePoint::IDENTITY;
+            let mut s_acc = 0u64;
+            let mut e_acc = 0u64;
+            let mut state = [BaseElement::ZERO; TRACE_WIDTH];
+            for (step, (s_bit, e_bit)) in s_bits.zip(e_bits).enumerate() {
+                let doubled = acc + acc;
+                let addend = match (s_bit, e_bit) {
+                    (false, false) => AffinePoint::IDENTITY,
+                    (true, false) => GENERATOR,
+                    (false, true) => -pub_key,
+                    (true, true) => q,
+                };
+                acc = doubled + addend;
+
+                if step % LIMB_BITS == 0 {
+                    s_acc = 0;
+                    e_acc = 0;
+                }
+                s_acc = 2 * s_acc + s_bit as u64;
+                e_acc = 2 * e_acc + e_bit as u64;
+
+                state[D_COL..D_COL + 6].copy_from_slice(&doubled.to_elements());
+                state[P_COL..P_COL + 6].copy_from_slice(&addend.to_elements());
+                state[N_COL..N_COL + 6].copy_from_slice(&acc.to_elements());
+                state[U_COL..U_COL + 3].copy_from_slice(&(doubled.x * addend.x).to_base_elements());
+                state[V_COL..V_COL + 3].copy_from_slice(&(doubled.y * addend.y).to_base_elements());
+                state[PK_COL..PK_COL + 6].copy_from_slice(&pub_key.to_elements());
+                state[Q_COL..Q_COL + 6].copy_from_slice(&q.to_elements());
+                state[S_BIT_COL] = BaseElement::from(s_bit as u8);
+                state[E_BIT_COL] = BaseElement::from(e_bit as u8);
+                state[S_ACC_COL] = BaseElement::new(s_acc);
+                state[E_ACC_COL] = BaseElement::new(e_acc);
+                sig_trace.update_row(step, &state);
+            }
+        });
+
+        trace
+    }
+}
+
+impl<H: ElementHasher> Prover for SchnorrProver<H>
+where
+    H: ElementHasher<BaseField = BaseElement>,
+{
+    type BaseField = BaseElement;
+    type Air = SchnorrAir;
+    type Trace = TraceTable<BaseElement>;
+    type HashFn = H;
+    type RandomCoin = DefaultRandomCoin<Self::HashFn>;
+    type TraceLde<E: FieldElement<BaseField = Self::BaseField>> = DefaultTraceLde<E, Self::HashFn>;
+    type ConstraintEvaluator<'a, E: FieldElement<BaseField = Self::BaseField>> =
+        DefaultConstraintEvaluator<'a, Self::Air, E>;
+
+    fn get_pub_inputs(&self, _trace: &Self::Trace) -> PublicInputs {
+        self.pub_inputs.clone()
+    }
+
+    fn options(&self) -> &ProofOptions {
+        &self.options
+    }
+
+    fn new_trace_lde<E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        trace_info: &TraceInfo,
+        main_trace: &ColMatrix<Self::BaseField>,
+        domain: &StarkDomain<Self::BaseField>,
+    ) -> (Self::TraceLde<E>, TracePolyTable<E>) {
+        DefaultTraceLde::new(trace_info, main_trace, domain)
+    }
+
+    fn new_evaluator<'a, E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        air: &'a Self::Air,
+        aux_rand_elements: AuxTraceRandElements<E>,
+        composition_coefficients: ConstraintCompositionCoefficients<E>,
+    ) -> Self::ConstraintEvaluator<'a, E> {
+        DefaultConstraintEvaluator::new(air, aux_rand_elements, composition_coefficients)
+    }
+}
@@ -0,0 +1,153 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{AffinePoint, BaseElement, StarkField, GENERATOR};
+use rand_utils::prng_array;
+use winterfell::crypto::{hashers::Rp64_256, ElementHasher, Hasher};
+
+// SCALAR
+// ================================================================================================
+
+/// An unsigned 256-bit integer by which curve points are multiplied.
+///
+/// The integer is stored as four 64-bit limbs in little-endian order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Scalar([u64; 4]);
+
+impl Scalar {
+    /// Number of bits in a scalar.
+    pub const NUM_BITS: usize = 256;
+
+    /// Returns `k + e * x`.
+    ///
+    /// # Panics
+    /// Panics if the result does not fit into 256 bits.
+    pub fn mul_add(k: Self, e: u64, x: u128) -> Self {
+        let lo = e as u128 * (x as u64) as u128;
+        let hi = e as u128 * (x >> 64) + (lo >> 64);
+        let product = [lo as u64, hi as u64, (hi >> 64) as u64, 0];
+
+        let mut result = [0; 4];
+        let mut carry = false;
+        for (i, limb) in result.iter_mut().enumerate() {
+            let (sum, overflow1) = k.0[i].overflowing_add(product[i]);
+            let (sum, overflow2) = sum.overflowing_add(carry as u64);
+            *limb = sum;
+            carry = overflow1 || overflow2;
+        }
+        assert!(!carry, "scalar overflow");
+        Self(result)
+    }
+
+    /// Returns bits of this scalar starting with the most significant bit.
+    pub fn bits(&self) -> impl Iterator<Item = bool> + '_ {
+        (0..Self::NUM_BITS).rev().map(|i| (self.0[i / 64] >> (i % 64)) & 1 == 1)
+    }
+
+    /// Returns 32-bit limbs of this scalar starting with the most significant limb.
+    pub fn limbs(&self) -> [u32; 8] {
+        let mut result = [0; 8];
+        for (i, limb) in self.0.iter().rev().enumerate() {
+            result[2 * i] = (limb >> 32) as u32;
+            result[2 * i + 1] = *limb as u32;
+        }
+        result
+    }
+}
+
+impl From<u64> for Scalar {
+    fn from(value: u64) -> Self {
+        Self([value, 0, 0, 0])
+    }
+}
+
+impl From<u128> for Scalar {
+    fn from(value: u128) -> Self {
+        Self([value as u64, (value >> 64) as u64, 0, 0])
+    }
+}
+
+// PRIVATE KEY
+// ================================================================================================
+
+/// A private key of the signature scheme: a secret 128-bit integer `x` and the corresponding
+/// public key `x * G`.
+pub struct PrivateKey {
+    secret: u128,
+    pub_key: AffinePoint,
+}
+
+impl PrivateKey {
+    /// Returns a private key generated from the specified `seed`.
+    pub fn from_seed(seed: [u8; 32]) -> Self {
+        let [secret] = prng_array::<u128, 1>(seed);
+        let pub_key = GENERATOR.mul_by_bits(Scalar::from(secret).bits());
+        PrivateKey { secret, pub_key }
+    }
+
+    /// Returns the public key corresponding to this private key.
+    pub fn pub_key(&self) -> AffinePoint {
+        self.pub_key
+    }
+
+    /// Signs the specified message.
+    ///
+    /// The signature is a pair (R, s) such that R = k * G and s = k + e * x, where the nonce k is
+    /// derived deterministically from the private key and the message, and e is the challenge
+    /// computed by [challenge()].
+    ///
+    /// The group order is not known, and thus, s is computed over integers rather than modulo
+    /// the group order. To keep x hidden, k is much larger than e * x: k has 254 bits, while e
+    /// has 64 bits and x has 128 bits.
+    pub fn sign(&self, message: &[u8]) -> Signature {
+        let mut elements = vec![
+            BaseElement::new(self.secret as u64),
+            BaseElement::new((self.secret >> 64) as u64),
+        ];
+        elements.extend_from_slice(Rp64_256::hash(message).as_elements());
+        let nonce = Rp64_256::hash_elements(&elements);
+        let mut k = [0; 4];
+        for (limb, element) in k.iter_mut().zip(nonce.as_elements()) {
+            *limb = element.as_int();
+        }
+        k[3] >>= 2;
+        let k = Scalar(k);
+
+        let r = GENERATOR.mul_by_bits(k.bits());
+        let e = challenge(&self.pub_key, &r, message);
+        Signature {
+            r,
+            s: Scalar::mul_add(k, e, self.secret),
+        }
+    }
+}
+
+// SIGNATURE
+// ================================================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Signature {
+    pub r: AffinePoint,
+    pub s: Scalar,
+}
+
+/// Returns true if the signature is valid for the specified public key and message.
+///
+/// The signature is valid if s * G - e * P = R, where P is the public key.
+pub fn verify(pub_key: &AffinePoint, message: &[u8], signature: &Signature) -> bool {
+    let e = Scalar::from(challenge(pub_key, &signature.r, message));
+    let result = GENERATOR.mul_by_bits(signature.s.bits()) + (-*pub_key).mul_by_bits(e.bits());
+    result == signature.r
+}
+
+/// Returns the challenge e for the specified public key, nonce commitment R, and message.
+///
+/// The challenge is the first element of Rp64_256 hash of (R, P, hash(message)).
+pub fn challenge(pub_key: &AffinePoint, r: &AffinePoint, message: &[u8]) -> u64 {
+    let mut elements = r.to_elements().to_vec();
+    elements.extend_from_slice(&pub_key.to_elements());
+    elements.extend_from_slice(Rp64_256::hash(message).as_elements());
+    Rp64_256::hash_elements(&elements).as_elements()[0].as_int()
+}
@@ -0,0 +1,60 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{verify, AffinePoint, PrivateKey, Rp64_256, Scalar, GENERATOR};
+use winterfell::{FieldExtension, ProofOptions};
+
+type Blake3_256 = winterfell::crypto::hashers::Blake3_256<super::BaseElement>;
+
+#[test]
+fn schnorr_test_basic_proof_verification() {
+    let schnorr_eg =
+        Box::new(super::SchnorrExample::<Blake3_256>::with_seed(2, build_options(false), [1; 32]));
+    crate::tests::test_basic_proof_verification(schnorr_eg);
+}
+
+#[test]
+fn schnorr_test_basic_proof_verification_extension() {
+    let schnorr_eg =
+        Box::new(super::SchnorrExample::<Rp64_256>::with_seed(2, build_options(true), [2; 32]));
+    crate::tests::test_basic_proof_verification(schnorr_eg);
+}
+
+#[test]
+fn schnorr_test_basic_proof_verification_fail() {
+    let schnorr_eg =
+        Box::new(super::SchnorrExample::<Blake3_256>::with_seed(2, build_options(false), [3; 32]));
+    crate::tests::test_basic_proof_verification_fail(schnorr_eg);
+}
+
+#[test]
+fn schnorr_test_signatures() {
+    assert!(GENERATOR.is_on_curve());
+    assert_ne!(AffinePoint::IDENTITY, GENERATOR.mul_by_bits(Scalar::from(8u64).bits()));
+    assert_eq!(AffinePoint::IDENTITY, GENERATOR + (-GENERATOR));
+
+    let key = PrivateKey::from_seed([4; 32]);
+    assert!(key.pub_key().is_on_curve());
+    let signature = key.sign(b"message");
+    assert!(verify(&key.pub_key(), b"message", &signature));
+    assert!(!verify(&key.pub_key(), b"another message", &signature));
+
+    let other_key = PrivateKey::from_seed([5; 32]);
+    assert!(!verify(&other_key.pub_key(), b"message", &signature));
+
+    // scalars are decomposed into limbs starting with the most significant limb
+    let scalar = Scalar::mul_add(Scalar::from(u64::MAX), u64::MAX, u128::MAX);
+    let limbs = scalar.limbs();
+    assert_eq!([0, 0, u32::MAX, u32::MAX, 0, 0, 0, 0], limbs);
+}
+
+fn build_options(use_extension_field: bool) -> ProofOptions {
+    let extension = if use_extension_field {
+        FieldExtension::Quadratic
+    } else {
+        FieldExtension::None
+    };
+    ProofOptions::new(28, 8, 0, extension, 4, 31)
+}
@@ -0,0 +1,474 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+//! Golden test vectors for Winterfell primitives and example proofs.
+//!
+//! Test vectors are stored as JSON fixtures in the `test-vectors` directory of this crate (proofs
+//! are stored in the `proofs` subdirectory), and cover:
+//!
+//! * Field arithmetic in `f62`, `f64`, and `f128` fields and their extensions.
+//! * Digests of all hash functions provided by Winterfell.
+//! * Merkle tree roots.
+//! * Elements, integers, and proof-of-work checks drawn from random coins instantiated with
+//!   fixed seeds.
+//! * One proof for each of the example AIRs, generated with small proof parameters.
+//!
+//! Fixtures are written via [write()] and are compared against the current implementation via
+//! [check()]. A mismatch means that values computed by the current implementation differ from
+//! the values computed when the fixtures were written; this is expected only for changes which
+//! intentionally modify the protocol. Implementations of Winterfell primitives and verifiers in
+//! other languages can use the fixtures to check their outputs against the reference
+//! implementation.
+
+use crate::{Example, ExampleOptions};
+use serde_json::{json, Value};
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+use structopt::StructOpt;
+use winterfell::{
+    crypto::{
+        hashers::{
+            Blake3_192, Blake3_256, GriffinJive64_256, Keccak256, Rp62_248, Rp64_256, RpJive64_256,
+            Sha3_256,
+        },
+        DefaultRandomCoin, ElementHasher, ElementRandomCoin, EthStarkRandomCoin, Hasher,
+        MerkleTree, RandomCoin,
+    },
+    math::{
+        fields::{f128, f62, f64, CubeExtension, QuadExtension},
+        ExtensibleField, FieldElement, StarkField,
+    },
+    Serializable, StarkProof,
+};
+
+#[cfg(test)]
+mod tests;
+
+// CONSTANTS
+// ================================================================================================
+
+/// Command-line arguments shared by all example proofs; proof parameters are kept small so that
+/// the fixtures remain small.
+const PROOF_OPTIONS_ARGS: [&str; 11] =
+    ["winterfell", "-q", "4", "-g", "0", "-f", "4", "-r", "7", "-s", "1"];
+
+/// Names of example proof fixtures and command-line arguments which specify the examples.
+const PROOF_VECTORS: [(&str, &[&str]); 14] = [
+    ("proofs/fib", &["fib", "-n", "32"]),
+    ("proofs/fib8", &["fib8", "-n", "64"]),
+    ("proofs/mulfib", &["mulfib", "-n", "32"]),
+    ("proofs/mulfib8", &["mulfib8", "-n", "64"]),
+    ("proofs/fib_small", &["fib-small", "-n", "32"]),
+    ("proofs/vdf", &["vdf", "-n", "16"]),
+    ("proofs/vdf_exempt", &["vdf-exempt", "-n", "15"]),
+    ("proofs/rescue", &["rescue", "-n", "4"]),
+    ("proofs/rescue_raps", &["rescue-raps", "-n", "4"]),
+    ("proofs/merkle", &["merkle", "-n", "3"]),
+    ("proofs/lamport_a", &["lamport-a", "-n", "1"]),
+    ("proofs/lamport_t", &["lamport-t", "-n", "3"]),
+    ("proofs/schnorr", &["schnorr", "-n", "2"]),
+    ("proofs/vm", &["vm", "-n", "4"]),
+];
+
+// TEST VECTORS
+// ================================================================================================
+
+/// Returns the directory in which the fixtures are stored in this crate.
+pub fn fixtures_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("test-vectors")
+}
+
+/// Writes all fixtures into the specified directory, overwriting existing fixtures.
+pub fn write(dir: &Path) -> io::Result<()> {
+    fs::create_dir_all(dir.join("proofs"))?;
+    for (name, vector) in primitive_vectors() {
+        fs::write(fixture_path(dir, name), to_json_string(&vector))?;
+    }
+    for (name, args) in PROOF_VECTORS {
+        fs::write(fixture_path(dir, name), to_json_string(&proof_vector(args)))?;
+    }
+    Ok(())
+}
+
+/// Checks that the fixtures in the specified directory match the current implementation.
+///
+/// For primitives, the fixtures must be identical to the ones which would be written by
+/// [write()]. For example proofs, the stored proofs must be accepted by the current verifier,
+/// and (unless `concurrent` feature is enabled, in which case proof generation is not
+/// deterministic) must be identical to the proofs generated by the current prover.
+///
+/// # Errors
+/// Returns an error listing all fixtures which are missing or do not match the current
+/// implementation.
+pub fn check(dir: &Path) -> Result<(), String> {
+    let mut errors = Vec::new();
+    for (name, vector) in primitive_vectors() {
+        match read_fixture(dir, name) {
+            Ok(fixture) if fixture == vector => (),
+            Ok(_) => errors.push(format!("{name}: values do not match")),
+            Err(err) => errors.push(format!("{name}: {err}")),
+        }
+    }
+
+    for (name, args) in PROOF_VECTORS {
+        if let Err(err) = check_proof_vector(dir, name, args) {
+            errors.push(format!("{name}: {err}"));
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors.join("\n"))
+    }
+}
+
+// PRIMITIVES
+// ================================================================================================
+
+fn primitive_vectors() -> [(&'static str, Value); 4] {
+    [
+        ("fields", field_vectors()),
+        ("hashes", hash_vectors()),
+        ("merkle_trees", merkle_tree_vectors()),
+        ("random_coins", random_coin_vectors()),
+    ]
+}
+
+// FIELD ARITHMETIC
+// ------------------------------------------------------------------------------------------------
+
+fn field_vectors() -> Value {
+    json!({
+        "f62": base_field_vectors::<f62::BaseElement>(),
+        "f62_quadratic": extension_field_vectors::<QuadExtension<f62::BaseElement>>(),
+        "f62_cubic": extension_field_vectors::<CubeExtension<f62::BaseElement>>(),
+        "f64": base_field_vectors::<f64::BaseElement>(),
+        "f64_quadratic": extension_field_vectors::<QuadExtension<f64::BaseElement>>(),
+        "f64_cubic": extension_field_vectors::<CubeExtension<f64::BaseElement>>(),
+        "f128": base_field_vectors::<f128::BaseElement>(),
+        "f128_quadratic": extension_field_vectors::<QuadExtension<f128::BaseElement>>(),
+    })
+}
+
+fn base_field_vectors<B: StarkField>() -> Value {
+    let elements = sample_elements::<B>();
+
+    let binary_ops = pairs(&elements)
+        .map(|(a, b)| {
+            json!({
+                "a": int(a),
+                "b": int(b),
+                "add": int(a + b),
+                "sub": int(a - b),
+                "mul": int(a * b),
+                "div": (b != B::ZERO).then(|| int(a / b)),
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let unary_ops = elements
+        .iter()
+        .map(|&a| {
+            json!({
+                "a": int(a),
+                "neg": int(-a),
+                "inv": int(a.inv()),
+                "square": int(a.square()),
+                "exp": int(a.exp(EXPONENT.into())),
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let roots_of_unity =
+        (1..=B::TWO_ADICITY).map(|n| int(B::get_root_of_unity(n))).collect::<Vec<_>>();
+
+    json!({
+        "modulus": format!("{:?}", B::MODULUS),
+        "generator": int(B::GENERATOR),
+        "exponent": EXPONENT.to_string(),
+        "binary_ops": binary_ops,
+        "unary_ops": unary_ops,
+        "roots_of_unity": roots_of_unity,
+    })
+}
+
+fn extension_field_vectors<E: FieldElement>() -> Value {
+    let base_elements = sample_elements::<E::BaseField>();
+    let elements = base_elements
+        .iter()
+        .zip(base_elements.iter().cycle().skip(1))
+        .map(|(&a, &b)| {
+            // build an extension element from base elements a, b, a, b, ...
+            let coefficients = [a, b, a, b];
+            let element_bytes = E::ELEMENT_BYTES;
+            let bytes = coefficients
+                .iter()
+                .flat_map(|c| c.to_bytes())
+                .take(element_bytes)
+                .collect::<Vec<_>>();
+            E::read_from_bytes(&bytes).expect("failed to build extension element")
+        })
+        .collect::<Vec<_>>();
+
+    let ops = pairs(&elements)
+        .map(|(a, b)| {
+            json!({
+                "a": ext(a),
+                "b": ext(b),
+                "add": ext(a + b),
+                "sub": ext(a - b),
+                "mul": ext(a * b),
+                "inv_a": ext(a.inv()),
+            })
+        })
+        .collect::<Vec<_>>();
+
+    json!({ "ops": ops })
+}
+
+/// Exponent used in field exponentiation vectors.
+const EXPONENT: u64 = 0x1234_5678_9abc_def1;
+
+/// Returns a small set of elements covering edge cases of field arithmetic.
+fn sample_elements<B: StarkField>() -> Vec<B> {
+    vec![
+        B::ZERO,
+        B::ONE,
+        B::from(2u32),
+        -B::ONE,
+        -B::from(2u32),
+        B::GENERATOR,
+        B::from(0x9e37_79b9_7f4a_7c15u64),
+        B::get_root_of_unity(B::TWO_ADICITY),
+    ]
+}
+
+/// Returns an iterator over pairs of elements at adjacent positions of the slice, including the
+/// pair of the last and the first element, as well as pairs of each element with itself.
+fn pairs<E: FieldElement>(elements: &[E]) -> impl Iterator<Item = (E, E)> + '_ {
+    let shifted = elements.iter().cycle().skip(1);
+    elements
+        .iter()
+        .zip(shifted)
+        .chain(elements.iter().zip(elements.iter()))
+        .map(|(&a, &b)| (a, b))
+}
+
+// HASH FUNCTIONS
+// ------------------------------------------------------------------------------------------------
+
+fn hash_vectors() -> Value {
+    json!({
+        "blake3_192": hasher_vectors::<Blake3_192<f128::BaseElement>>(),
+        "blake3_256": hasher_vectors::<Blake3_256<f128::BaseElement>>(),
+        "sha3_256": hasher_vectors::<Sha3_256<f128::BaseElement>>(),
+        "keccak256": hasher_vectors::<Keccak256<f128::BaseElement>>(),
+        "rp62_248": hasher_vectors::<Rp62_248>(),
+        "rp64_256": hasher_vectors::<Rp64_256>(),
+        "rp_jive64_256": hasher_vectors::<RpJive64_256>(),
+        "griffin_jive64_256": hasher_vectors::<GriffinJive64_256>(),
+    })
+}
+
+fn hasher_vectors<H: ElementHasher>() -> Value {
+    let byte_inputs: [Vec<u8>; 4] = [
+        vec![],
+        b"abc".to_vec(),
+        (0..=255).collect(),
+        (0..1000).map(|i| (i % 251) as u8).collect(),
+    ];
+    let bytes = byte_inputs
+        .iter()
+        .map(|input| json!({ "input": hex::encode(input), "digest": digest::<H>(H::hash(input)) }))
+        .collect::<Vec<_>>();
+
+    let elements = [0, 1, 4, 8, 9, 16]
+        .iter()
+        .map(|&n| {
+            let input = sample_elements::<H::BaseField>()
+                .into_iter()
+                .cycle()
+                .take(n)
+                .collect::<Vec<_>>();
+            json!({
+                "input": input.iter().map(|&e| int(e)).collect::<Vec<_>>(),
+                "digest": digest::<H>(H::hash_elements(&input)),
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let left = H::hash(b"left");
+    let right = H::hash(b"right");
+
+    json!({
+        "bytes": bytes,
+        "elements": elements,
+        "merge": {
+            "left": digest::<H>(left),
+            "right": digest::<H>(right),
+            "digest": digest::<H>(H::merge(&[left, right])),
+        },
+        "merge_with_int": {
+            "seed": digest::<H>(left),
+            "value": 42,
+            "digest": digest::<H>(H::merge_with_int(left, 42)),
+        },
+    })
+}
+
+// MERKLE TREES
+// ------------------------------------------------------------------------------------------------
+
+fn merkle_tree_vectors() -> Value {
+    json!({
+        "blake3_256": merkle_vectors::<Blake3_256<f128::BaseElement>>(),
+        "sha3_256": merkle_vectors::<Sha3_256<f128::BaseElement>>(),
+        "rp64_256": merkle_vectors::<Rp64_256>(),
+    })
+}
+
+fn merkle_vectors<H: Hasher>() -> Value {
+    let vectors = [2, 8, 32]
+        .iter()
+        .map(|&num_leaves| {
+            // leaf i is set to hash(i), where i is encoded as 8 little-endian bytes
+            let leaves =
+                (0..num_leaves as u64).map(|i| H::hash(&i.to_le_bytes())).collect::<Vec<_>>();
+            let tree = MerkleTree::<H>::new(leaves).expect("failed to build Merkle tree");
+            json!({ "num_leaves": num_leaves, "root": digest::<H>(*tree.root()) })
+        })
+        .collect::<Vec<_>>();
+
+    json!(vectors)
+}
+
+// RANDOM COINS
+// ------------------------------------------------------------------------------------------------
+
+fn random_coin_vectors() -> Value {
+    json!({
+        "default_blake3_256": coin_vectors::<DefaultRandomCoin<Blake3_256<f128::BaseElement>>>(),
+        "default_rp64_256": coin_vectors::<DefaultRandomCoin<Rp64_256>>(),
+        "element_rp62_248": coin_vectors::<ElementRandomCoin<Rp62_248>>(),
+        "ethstark_keccak256": coin_vectors::<EthStarkRandomCoin<Keccak256<f128::BaseElement>>>(),
+    })
+}
+
+fn coin_vectors<R: RandomCoin>() -> Value
+where
+    R::BaseField: ExtensibleField<2>,
+{
+    let seed = (1..=4u32).map(R::BaseField::from).collect::<Vec<_>>();
+    let mut coin = R::new(&seed);
+
+    let elements = (0..4).map(|_| int(draw::<R, R::BaseField>(&mut coin))).collect::<Vec<_>>();
+    let quad_elements = (0..2)
+        .map(|_| ext(draw::<R, QuadExtension<R::BaseField>>(&mut coin)))
+        .collect::<Vec<_>>();
+
+    let commitment = R::Hasher::hash(b"commitment");
+    coin.reseed(commitment);
+    let reseeded_elements =
+        (0..2).map(|_| int(draw::<R, R::BaseField>(&mut coin))).collect::<Vec<_>>();
+
+    let leading_zeros = (1..=8).map(|nonce| coin.check_leading_zeros(nonce)).collect::<Vec<_>>();
+    let pow_nonce = (1..)
+        .find(|&nonce| coin.check_proof_of_work(nonce, 8))
+        .expect("failed to find proof-of-work nonce");
+    let integers = coin.draw_integers(8, 1024, pow_nonce).expect("failed to draw integers");
+
+    json!({
+        "seed": seed.iter().map(|&e| int(e)).collect::<Vec<_>>(),
+        "elements": elements,
+        "quadratic_elements": quad_elements,
+        "reseed_with": digest::<R::Hasher>(commitment),
+        "reseeded_elements": reseeded_elements,
+        "leading_zeros": leading_zeros,
+        "pow_grinding_factor": 8,
+        "pow_nonce": pow_nonce,
+        "integers": integers,
+    })
+}
+
+fn draw<R: RandomCoin, E: FieldElement<BaseField = R::BaseField>>(coin: &mut R) -> E {
+    coin.draw().expect("failed to draw field element")
+}
+
+// EXAMPLE PROOFS
+// ================================================================================================
+
+fn proof_vector(args: &[&str]) -> Value {
+    let example = build_example(args);
+    let proof = example.prove();
+    example.verify(proof.clone()).expect("example proof is not valid");
+    json!({ "args": args, "proof": hex::encode(proof.to_bytes()) })
+}
+
+fn check_proof_vector(dir: &Path, name: &str, args: &[&str]) -> Result<(), String> {
+    let fixture = read_fixture(dir, name)?;
+    if fixture["args"] != json!(args) {
+        return Err("example arguments do not match".to_string());
+    }
+    let proof_bytes = fixture["proof"]
+        .as_str()
+        .and_then(|proof| hex::decode(proof).ok())
+        .ok_or("proof is not a hex string")?;
+
+    let example = build_example(args);
+    let proof = StarkProof::from_bytes(&proof_bytes).map_err(|err| err.to_string())?;
+    example
+        .verify(proof)
+        .map_err(|err| format!("stored proof is not valid: {err}"))?;
+
+    // proofs generated in multiple threads may use different proof-of-work nonces
+    #[cfg(not(feature = "concurrent"))]
+    if example.prove().to_bytes() != proof_bytes {
+        return Err("proof does not match".to_string());
+    }
+
+    Ok(())
+}
+
+fn build_example(args: &[&str]) -> Box<dyn Example> {
+    let args = PROOF_OPTIONS_ARGS.iter().chain(args.iter());
+    let options = ExampleOptions::from_iter(args);
+    options.get_example().expect("failed to build example")
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+fn fixture_path(dir: &Path, name: &str) -> PathBuf {
+    dir.join(format!("{name}.json"))
+}
+
+fn read_fixture(dir: &Path, name: &str) -> Result<Value, String> {
+    let contents = fs::read_to_string(fixture_path(dir, name)).map_err(|err| err.to_string())?;
+    serde_json::from_str(&contents).map_err(|err| err.to_string())
+}
+
+fn to_json_string(value: &Value) -> String {
+    let mut result = serde_json::to_string_pretty(value).expect("failed to serialize fixture");
+    result.push('\n');
+    result
+}
+
+/// Returns a decimal representation of a base field element.
+fn int<B: StarkField>(element: B) -> String {
+    format!("{:?}", element.as_int())
+}
+
+/// Returns decimal representations of base field coefficients of an extension field element.
+fn ext<E: FieldElement>(element: E) -> Vec<String> {
+    E::slice_as_base_elements(&[element]).iter().map(|&e| int(e)).collect()
+}
+
+/// Returns a hex representation of a digest.
+fn digest<H: Hasher>(digest: H::Digest) -> String {
+    hex::encode(digest.to_bytes())
+}
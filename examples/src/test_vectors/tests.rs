@@ -0,0 +1,35 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{check, fixtures_dir, write};
+
+#[test]
+fn test_vectors_match_fixtures() {
+    // if a change is intentional, fixtures can be updated via `test-vectors emit` command
+    if let Err(err) = check(&fixtures_dir()) {
+        panic!("test vectors do not match fixtures:\n{err}");
+    }
+}
+
+#[test]
+fn test_vectors_detect_changes() {
+    let dir = std::env::temp_dir().join(format!("winterfell-test-vectors-{}", std::process::id()));
+    write(&dir).unwrap();
+    assert!(check(&dir).is_ok());
+
+    // modify a single digest in the hash function vectors
+    let path = dir.join("hashes.json");
+    let contents = std::fs::read_to_string(&path).unwrap();
+    let digest = super::digest::<super::Sha3_256<super::f128::BaseElement>>(<super::Sha3_256<
+        super::f128::BaseElement,
+    > as super::Hasher>::hash(
+        b"abc"
+    ));
+    std::fs::write(&path, contents.replace(&digest, &"0".repeat(digest.len()))).unwrap();
+
+    let err = check(&dir).unwrap_err();
+    assert!(err.starts_with("hashes:"));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
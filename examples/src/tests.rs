@@ -4,6 +4,8 @@
 // LICENSE file in the root directory of this source tree.
 
 use crate::Example;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use winterfell::StarkProof;
 
 pub fn test_basic_proof_verification(e: Box<dyn Example>) {
     let proof = e.prove();
@@ -15,3 +17,54 @@ pub fn test_basic_proof_verification_fail(e: Box<dyn Example>) {
     let verified = e.verify_with_wrong_inputs(proof);
     assert!(verified.is_err());
 }
+
+/// Checks that truncated and corrupted versions of a valid proof are rejected without panicking.
+///
+/// Each proof is truncated at every byte offset, every byte of the proof is corrupted with
+/// several bit masks, and the field modulus recorded in the proof is replaced with a larger one.
+/// Corrupted proofs which describe an execution trace of a different shape are not passed to the
+/// verifier, since AIR implementations are trusted to reject such traces.
+pub fn test_malformed_proof_rejection(e: Box<dyn Example>) {
+    let proof = e.prove();
+    let layout = proof.get_trace_info().layout().clone();
+    let bytes = proof.to_bytes();
+
+    for i in 0..bytes.len() {
+        let result = catch_unwind(AssertUnwindSafe(|| StarkProof::from_bytes(&bytes[..i])));
+        assert!(matches!(result, Ok(Err(_))), "proof truncated to {i} bytes was not rejected");
+
+        for mask in [0x01, 0x80, 0xff] {
+            let mut corrupted = bytes.clone();
+            corrupted[i] ^= mask;
+            let result = catch_unwind(AssertUnwindSafe(|| {
+                if let Ok(proof) = StarkProof::from_bytes(&corrupted) {
+                    if proof.get_trace_info().layout() == &layout {
+                        let _ = e.verify(proof);
+                    }
+                }
+            }));
+            assert!(
+                result.is_ok(),
+                "proof corrupted at byte {i} with mask {mask:#04x} caused a panic"
+            );
+        }
+    }
+
+    // proofs which record a field modulus too large to be converted into field elements are
+    // rejected as well
+    let modulus = proof.context.field_modulus_bytes();
+    let offset = bytes
+        .windows(modulus.len() + 1)
+        .position(|w| w[0] as usize == modulus.len() && &w[1..] == modulus)
+        .unwrap();
+    let mut corrupted = bytes[..offset].to_vec();
+    corrupted.push(32);
+    corrupted.extend_from_slice(&[0xff; 32]);
+    corrupted.extend_from_slice(&bytes[offset + modulus.len() + 1..]);
+    let proof = StarkProof::from_bytes(&corrupted).unwrap();
+    let result = catch_unwind(AssertUnwindSafe(|| e.verify(proof)));
+    assert!(
+        matches!(result, Ok(Err(_))),
+        "proof with an invalid field modulus was not rejected"
+    );
+}
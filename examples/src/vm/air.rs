@@ -0,0 +1,132 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{BaseElement, FieldElement, Program, ProofOptions, TRACE_WIDTH};
+use crate::utils::{are_equal, is_zero, EvaluationResult};
+use winterfell::{
+    math::ToElements, Air, AirContext, Assertion, EvaluationFrame, TraceInfo,
+    TransitionConstraintDegree,
+};
+
+// VM AIR
+// ================================================================================================
+
+pub struct PublicInputs {
+    pub program: Program,
+    pub inputs: [BaseElement; 2],
+    pub outputs: [BaseElement; 2],
+}
+
+impl ToElements<BaseElement> for PublicInputs {
+    fn to_elements(&self) -> Vec<BaseElement> {
+        let mut result = self.program.to_elements();
+        result.extend_from_slice(&self.inputs);
+        result.extend_from_slice(&self.outputs);
+        result
+    }
+}
+
+pub struct VmAir {
+    context: AirContext<BaseElement>,
+    program: Program,
+    inputs: [BaseElement; 2],
+    outputs: [BaseElement; 2],
+}
+
+impl Air for VmAir {
+    type BaseField = BaseElement;
+    type PublicInputs = PublicInputs;
+
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+    fn new(trace_info: TraceInfo, pub_inputs: PublicInputs, options: ProofOptions) -> Self {
+        // all constraints are of degree 2 and are multiplied by the selectors of the program
+        let cycle_length = pub_inputs.program.num_instructions();
+        let degrees = vec![TransitionConstraintDegree::with_cycles(2, vec![cycle_length]); 4];
+        assert_eq!(TRACE_WIDTH, trace_info.width());
+        assert!(
+            trace_info.length() >= cycle_length,
+            "execution trace must be at least as long as the program"
+        );
+        VmAir {
+            context: AirContext::new(trace_info, degrees, 5, options),
+            program: pub_inputs.program,
+            inputs: pub_inputs.inputs,
+            outputs: pub_inputs.outputs,
+        }
+    }
+
+    fn context(&self) -> &AirContext<Self::BaseField> {
+        &self.context
+    }
+
+    fn evaluate_transition<E: FieldElement + From<Self::BaseField>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        let current = frame.current();
+        let next = frame.next();
+        debug_assert_eq!(TRACE_WIDTH, current.len());
+        debug_assert_eq!(TRACE_WIDTH, next.len());
+
+        let (r0, r1, flag) = (current[0], current[1], current[2]);
+        let (next_r0, next_r1, next_flag, next_inv) = (next[0], next[1], next[2], next[3]);
+
+        // split periodic values into instruction selectors and the immediate value
+        let load = periodic_values[0];
+        let add = periodic_values[1];
+        let mul = periodic_values[2];
+        let swap = periodic_values[3];
+        let eqz = periodic_values[4];
+        let cswap = periodic_values[5];
+        let imm = periodic_values[6];
+
+        // registers and the flag are carried over to the next step, unless the executed
+        // instruction changes them; NOOP has no selector and changes nothing
+        result[0] = are_equal(next_r0, r0);
+        result[1] = are_equal(next_r1, r1);
+        result[2] = are_equal(next_flag, flag);
+
+        // LOAD: r1' = imm
+        result.agg_constraint(1, load, r1 - imm);
+
+        // ADD: r0' = r0 + r1
+        result.agg_constraint(0, add, -r1);
+
+        // MUL: r0' = r0 * r1
+        result.agg_constraint(0, mul, r0 - r0 * r1);
+
+        // SWAP: r0' = r1, r1' = r0
+        result.agg_constraint(0, swap, r0 - r1);
+        result.agg_constraint(1, swap, r1 - r0);
+
+        // CSWAP: same as SWAP but only when the flag is set
+        result.agg_constraint(0, cswap * flag, r0 - r1);
+        result.agg_constraint(1, cswap * flag, r1 - r0);
+
+        // EQZ: f' = 1 - r0 * inv', and r0 * f' = 0; together, these imply that f' = 1 when
+        // r0 = 0, and f' = 0 otherwise
+        result.agg_constraint(2, eqz, flag + r0 * next_inv - E::ONE);
+        result[3] = eqz * is_zero(r0 * next_flag);
+    }
+
+    fn get_assertions(&self) -> Vec<Assertion<Self::BaseField>> {
+        // the registers start with the inputs and end with the outputs; the flag starts unset
+        let last_step = self.trace_length() - 1;
+        vec![
+            Assertion::single(0, 0, self.inputs[0]),
+            Assertion::single(1, 0, self.inputs[1]),
+            Assertion::single(2, 0, BaseElement::ZERO),
+            Assertion::single(0, last_step, self.outputs[0]),
+            Assertion::single(1, last_step, self.outputs[1]),
+        ]
+    }
+
+    fn get_periodic_column_values(&self) -> Vec<Vec<Self::BaseField>> {
+        self.program.get_periodic_columns()
+    }
+}
@@ -0,0 +1,170 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use crate::{Blake3_192, Blake3_256, Example, ExampleOptions, HashFunction, Sha3_256};
+use core::marker::PhantomData;
+use log::debug;
+use std::time::Instant;
+use winterfell::{
+    crypto::{DefaultRandomCoin, ElementHasher},
+    math::{fields::f128::BaseElement, FieldElement},
+    ProofOptions, Prover, StarkProof, Trace, VerifierError,
+};
+
+mod air;
+use air::{PublicInputs, VmAir};
+
+mod program;
+pub use program::{Instruction, Program};
+
+mod prover;
+use prover::VmProver;
+
+#[cfg(test)]
+mod tests;
+
+// CONSTANTS
+// ================================================================================================
+
+/// Number of columns in the execution trace: two registers, a condition flag, and an auxiliary
+/// column holding the inverse of the first register for `EQZ` instructions.
+const TRACE_WIDTH: usize = 4;
+
+/// Minimum length of a padded program.
+const MIN_PROGRAM_LENGTH: usize = 8;
+
+// REGISTER VM EXAMPLE
+// ================================================================================================
+
+pub fn get_example(
+    options: &ExampleOptions,
+    num_iterations: usize,
+) -> Result<Box<dyn Example>, String> {
+    let (options, hash_fn) = options.to_proof_options(28, 8);
+
+    let program = Program::sample();
+    let inputs = [BaseElement::new(3), BaseElement::new(5)];
+    match hash_fn {
+        HashFunction::Blake3_192 => {
+            Ok(Box::new(VmExample::<Blake3_192>::new(program, inputs, num_iterations, options)))
+        }
+        HashFunction::Blake3_256 => {
+            Ok(Box::new(VmExample::<Blake3_256>::new(program, inputs, num_iterations, options)))
+        }
+        HashFunction::Sha3_256 => {
+            Ok(Box::new(VmExample::<Sha3_256>::new(program, inputs, num_iterations, options)))
+        }
+        _ => Err("The specified hash function cannot be used with this example.".to_string()),
+    }
+}
+
+pub struct VmExample<H: ElementHasher> {
+    options: ProofOptions,
+    program: Program,
+    inputs: [BaseElement; 2],
+    num_iterations: usize,
+    outputs: [BaseElement; 2],
+    _hasher: PhantomData<H>,
+}
+
+impl<H: ElementHasher> VmExample<H> {
+    /// Returns a new example which executes the `program` in a loop `num_iterations` times
+    /// starting with registers set to `inputs`.
+    ///
+    /// # Panics
+    /// Panics if `num_iterations` is not a power of two.
+    pub fn new(
+        program: Program,
+        inputs: [BaseElement; 2],
+        num_iterations: usize,
+        options: ProofOptions,
+    ) -> Self {
+        assert!(num_iterations.is_power_of_two(), "number of iterations must be a power of 2");
+
+        // execute the program to get the outputs
+        let now = Instant::now();
+        let outputs = program.execute(inputs, num_iterations);
+        debug!(
+            "Executed a program of {} instructions {} times in {} ms",
+            program.num_instructions(),
+            num_iterations,
+            now.elapsed().as_millis(),
+        );
+
+        VmExample {
+            options,
+            program,
+            inputs,
+            num_iterations,
+            outputs,
+            _hasher: PhantomData,
+        }
+    }
+
+    fn verify_with_pub_inputs(
+        &self,
+        proof: StarkProof,
+        pub_inputs: PublicInputs,
+    ) -> Result<(), VerifierError>
+    where
+        H: ElementHasher<BaseField = BaseElement>,
+    {
+        let acceptable_options =
+            winterfell::AcceptableOptions::OptionSet(vec![proof.options().clone()]);
+        winterfell::verify::<VmAir, H, DefaultRandomCoin<H>>(proof, pub_inputs, &acceptable_options)
+    }
+}
+
+// EXAMPLE IMPLEMENTATION
+// ================================================================================================
+
+impl<H: ElementHasher> Example for VmExample<H>
+where
+    H: ElementHasher<BaseField = BaseElement>,
+{
+    fn prove(&self) -> StarkProof {
+        debug!(
+            "Generating proof for executing a program of {} instructions {} times\n\
+            ---------------------",
+            self.program.num_instructions(),
+            self.num_iterations
+        );
+
+        // create a prover
+        let prover = VmProver::<H>::new(self.program.clone(), self.options.clone());
+
+        // generate the execution trace
+        let now = Instant::now();
+        let trace = prover.build_trace(self.inputs, self.num_iterations);
+        let trace_length = trace.length();
+        debug!(
+            "Generated execution trace of {} registers and 2^{} steps in {} ms",
+            trace.width(),
+            trace_length.ilog2(),
+            now.elapsed().as_millis()
+        );
+
+        // generate the proof
+        prover.prove(trace).unwrap()
+    }
+
+    fn verify(&self, proof: StarkProof) -> Result<(), VerifierError> {
+        let pub_inputs = PublicInputs {
+            program: self.program.clone(),
+            inputs: self.inputs,
+            outputs: self.outputs,
+        };
+        self.verify_with_pub_inputs(proof, pub_inputs)
+    }
+
+    fn verify_with_wrong_inputs(&self, proof: StarkProof) -> Result<(), VerifierError> {
+        let pub_inputs = PublicInputs {
+            program: self.program.clone(),
+            inputs: self.inputs,
+            outputs: [self.outputs[0] + BaseElement::ONE, self.outputs[1]],
+        };
+        self.verify_with_pub_inputs(proof, pub_inputs)
+    }
+}
@@ -0,0 +1,190 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{BaseElement, FieldElement, MIN_PROGRAM_LENGTH, TRACE_WIDTH};
+
+// INSTRUCTIONS
+// ================================================================================================
+
+/// Instructions of the VM.
+///
+/// The VM has two registers `r0` and `r1`, and a condition flag `f` which is set by `EQZ` and
+/// read by `CSWAP` instructions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Instruction {
+    /// Leaves the state of the VM unchanged.
+    Noop,
+    /// Sets `r1` to the specified value.
+    Load(BaseElement),
+    /// Sets `r0` to `r0 + r1`.
+    Add,
+    /// Sets `r0` to `r0 * r1`.
+    Mul,
+    /// Swaps values of `r0` and `r1`.
+    Swap,
+    /// Sets `f` to 1 if `r0` is zero, and to 0 otherwise.
+    Eqz,
+    /// Swaps values of `r0` and `r1` if `f` is 1.
+    CSwap,
+}
+
+impl Instruction {
+    /// Returns the opcode of this instruction.
+    pub fn opcode(&self) -> u8 {
+        match self {
+            Self::Noop => 0,
+            Self::Load(_) => 1,
+            Self::Add => 2,
+            Self::Mul => 3,
+            Self::Swap => 4,
+            Self::Eqz => 5,
+            Self::CSwap => 6,
+        }
+    }
+
+    /// Returns the immediate value of this instruction; this is zero for all instructions
+    /// other than `LOAD`.
+    pub fn immediate(&self) -> BaseElement {
+        match self {
+            Self::Load(value) => *value,
+            _ => BaseElement::ZERO,
+        }
+    }
+
+    /// Applies this instruction to the specified trace row, and writes the next row into it.
+    ///
+    /// The row consists of `r0`, `r1`, `f`, and an auxiliary column which is set to the inverse
+    /// of `r0` in rows following `EQZ` instructions, and to zero otherwise.
+    pub fn apply(&self, state: &mut [BaseElement]) {
+        debug_assert_eq!(TRACE_WIDTH, state.len());
+        state[3] = BaseElement::ZERO;
+        match self {
+            Self::Noop => (),
+            Self::Load(value) => state[1] = *value,
+            Self::Add => state[0] += state[1],
+            Self::Mul => state[0] *= state[1],
+            Self::Swap => state.swap(0, 1),
+            Self::Eqz => {
+                state[3] = state[0].inv();
+                state[2] = BaseElement::ONE - state[0] * state[3];
+            }
+            Self::CSwap => {
+                if state[2] == BaseElement::ONE {
+                    state.swap(0, 1);
+                }
+            }
+        }
+    }
+}
+
+// PROGRAM
+// ================================================================================================
+
+/// A program executed by the VM.
+///
+/// The program is padded with `NOOP` instructions to the next power of two (and to at least
+/// 8 instructions) such that the last instruction is always `NOOP`. This way, the program can
+/// be described by periodic columns, and executing the program in a loop `n` times takes exactly
+/// `n * num_instructions()` steps.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Program {
+    instructions: Vec<Instruction>,
+}
+
+impl Program {
+    // CONSTRUCTORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a new program consisting of the specified instructions padded with `NOOP`s.
+    pub fn new(mut instructions: Vec<Instruction>) -> Self {
+        let num_instructions = (instructions.len() + 1).next_power_of_two();
+        instructions.resize(num_instructions.max(MIN_PROGRAM_LENGTH), Instruction::Noop);
+        Self { instructions }
+    }
+
+    /// Returns a sample program which uses all instructions of the VM.
+    ///
+    /// For `r0 = x` and `r1 = 5` the program sets `r0` to `5 * (x + 5) + 5`; the conditional
+    /// swap is taken in the second half of the program only.
+    pub fn sample() -> Self {
+        use Instruction::*;
+        Self::new(vec![
+            Eqz,
+            CSwap,
+            Add,
+            Swap,
+            Mul,
+            Load(BaseElement::ZERO),
+            Swap,
+            Eqz,
+            CSwap,
+            Load(BaseElement::new(5)),
+            Add,
+        ])
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the number of instructions in this program, including padding.
+    pub fn num_instructions(&self) -> usize {
+        self.instructions.len()
+    }
+
+    /// Returns instructions of this program, including padding.
+    pub fn instructions(&self) -> &[Instruction] {
+        &self.instructions
+    }
+
+    // EXECUTION
+    // --------------------------------------------------------------------------------------------
+
+    /// Executes this program in a loop `num_iterations` times starting with registers set to
+    /// `inputs`, and returns the final values of the registers.
+    pub fn execute(&self, inputs: [BaseElement; 2], num_iterations: usize) -> [BaseElement; 2] {
+        let mut state = [inputs[0], inputs[1], BaseElement::ZERO, BaseElement::ZERO];
+        for _ in 0..num_iterations {
+            for instruction in self.instructions.iter() {
+                instruction.apply(&mut state);
+            }
+        }
+        [state[0], state[1]]
+    }
+
+    // ENCODING
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns an encoding of this program as a sequence of (opcode, immediate value) pairs.
+    ///
+    /// This encoding is included in the public inputs, and thus, the proof commits to the
+    /// executed program.
+    pub fn to_elements(&self) -> Vec<BaseElement> {
+        let mut result = Vec::with_capacity(self.instructions.len() * 2);
+        for instruction in self.instructions.iter() {
+            result.push(BaseElement::from(instruction.opcode()));
+            result.push(instruction.immediate());
+        }
+        result
+    }
+
+    /// Returns periodic columns describing this program.
+    ///
+    /// The columns are selectors for `LOAD`, `ADD`, `MUL`, `SWAP`, `EQZ`, and `CSWAP`
+    /// instructions (a selector is set to 1 on the steps at which the instruction is executed,
+    /// and to 0 otherwise), followed by the immediate values of all instructions. `NOOP` needs
+    /// no selector since it is executed whenever all selectors are 0.
+    pub fn get_periodic_columns(&self) -> Vec<Vec<BaseElement>> {
+        let selector = |opcode: u8| {
+            self.instructions
+                .iter()
+                .map(|instruction| BaseElement::from((instruction.opcode() == opcode) as u8))
+                .collect::<Vec<_>>()
+        };
+
+        let mut result = (1..=6).map(selector).collect::<Vec<_>>();
+        result.push(self.instructions.iter().map(Instruction::immediate).collect());
+        result
+    }
+}
@@ -0,0 +1,104 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{
+    BaseElement, DefaultRandomCoin, ElementHasher, FieldElement, PhantomData, Program,
+    ProofOptions, Prover, PublicInputs, VmAir, TRACE_WIDTH,
+};
+use winterfell::{
+    matrix::ColMatrix, AuxTraceRandElements, ConstraintCompositionCoefficients,
+    DefaultConstraintEvaluator, DefaultTraceLde, StarkDomain, Trace, TraceInfo, TracePolyTable,
+    TraceTable,
+};
+
+// VM PROVER
+// ================================================================================================
+
+pub struct VmProver<H: ElementHasher> {
+    program: Program,
+    options: ProofOptions,
+    _hasher: PhantomData<H>,
+}
+
+impl<H: ElementHasher> VmProver<H> {
+    pub fn new(program: Program, options: ProofOptions) -> Self {
+        Self {
+            program,
+            options,
+            _hasher: PhantomData,
+        }
+    }
+
+    /// Builds an execution trace for executing the program in a loop `num_iterations` times
+    /// starting with registers set to `inputs`.
+    pub fn build_trace(
+        &self,
+        inputs: [BaseElement; 2],
+        num_iterations: usize,
+    ) -> TraceTable<BaseElement> {
+        let instructions = self.program.instructions();
+        let trace_length = num_iterations * instructions.len();
+        let mut trace = TraceTable::new(TRACE_WIDTH, trace_length);
+
+        trace.fill(
+            |state| {
+                state[0] = inputs[0];
+                state[1] = inputs[1];
+                state[2] = BaseElement::ZERO;
+                state[3] = BaseElement::ZERO;
+            },
+            |step, state| {
+                instructions[step % instructions.len()].apply(state);
+            },
+        );
+
+        trace
+    }
+}
+
+impl<H: ElementHasher> Prover for VmProver<H>
+where
+    H: ElementHasher<BaseField = BaseElement>,
+{
+    type BaseField = BaseElement;
+    type Air = VmAir;
+    type Trace = TraceTable<BaseElement>;
+    type HashFn = H;
+    type RandomCoin = DefaultRandomCoin<Self::HashFn>;
+    type TraceLde<E: FieldElement<BaseField = Self::BaseField>> = DefaultTraceLde<E, Self::HashFn>;
+    type ConstraintEvaluator<'a, E: FieldElement<BaseField = Self::BaseField>> =
+        DefaultConstraintEvaluator<'a, Self::Air, E>;
+
+    fn get_pub_inputs(&self, trace: &Self::Trace) -> PublicInputs {
+        let last_step = trace.length() - 1;
+        PublicInputs {
+            program: self.program.clone(),
+            inputs: [trace.get(0, 0), trace.get(1, 0)],
+            outputs: [trace.get(0, last_step), trace.get(1, last_step)],
+        }
+    }
+
+    fn options(&self) -> &ProofOptions {
+        &self.options
+    }
+
+    fn new_trace_lde<E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        trace_info: &TraceInfo,
+        main_trace: &ColMatrix<Self::BaseField>,
+        domain: &StarkDomain<Self::BaseField>,
+    ) -> (Self::TraceLde<E>, TracePolyTable<E>) {
+        DefaultTraceLde::new(trace_info, main_trace, domain)
+    }
+
+    fn new_evaluator<'a, E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        air: &'a Self::Air,
+        aux_rand_elements: AuxTraceRandElements<E>,
+        composition_coefficients: ConstraintCompositionCoefficients<E>,
+    ) -> Self::ConstraintEvaluator<'a, E> {
+        DefaultConstraintEvaluator::new(air, aux_rand_elements, composition_coefficients)
+    }
+}
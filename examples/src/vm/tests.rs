@@ -0,0 +1,79 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{
+    BaseElement, Blake3_256, Example, FieldElement, Instruction, Program, PublicInputs, VmExample,
+};
+use winterfell::{FieldExtension, ProofOptions};
+
+#[test]
+fn vm_test_basic_proof_verification() {
+    let vm_eg = Box::new(build_example(build_options(false)));
+    crate::tests::test_basic_proof_verification(vm_eg);
+}
+
+#[test]
+fn vm_test_basic_proof_verification_extension() {
+    let vm_eg = Box::new(build_example(build_options(true)));
+    crate::tests::test_basic_proof_verification(vm_eg);
+}
+
+#[test]
+fn vm_test_basic_proof_verification_fail() {
+    let vm_eg = Box::new(build_example(build_options(false)));
+    crate::tests::test_basic_proof_verification_fail(vm_eg);
+}
+
+#[test]
+fn vm_test_program_execution() {
+    use Instruction::*;
+
+    // programs are padded to a power of two with at least one trailing NOOP
+    let program = Program::new(vec![Add; 8]);
+    assert_eq!(16, program.num_instructions());
+    assert_eq!(Noop, program.instructions()[15]);
+    assert_eq!(8, Program::new(vec![]).num_instructions());
+
+    // sample program maps x to 5 * (x + 5) + 5 when r1 = 5
+    let five = BaseElement::new(5);
+    let outputs = Program::sample().execute([BaseElement::new(3), five], 2);
+    assert_eq!([BaseElement::new(255), five], outputs);
+
+    // CSWAP swaps the registers only after EQZ encountered a zero
+    let program = Program::new(vec![Eqz, CSwap, Eqz, CSwap]);
+    let outputs = program.execute([BaseElement::ZERO, five], 1);
+    assert_eq!([five, BaseElement::ZERO], outputs);
+}
+
+#[test]
+fn vm_test_wrong_program() {
+    let example = build_example(build_options(false));
+    let proof = example.prove();
+
+    // the proof commits to the executed program, and thus, is not valid for a different program
+    let mut instructions = Program::sample().instructions().to_vec();
+    instructions[5] = Instruction::Load(BaseElement::ONE);
+    let pub_inputs = PublicInputs {
+        program: Program::new(instructions),
+        inputs: example.inputs,
+        outputs: example.outputs,
+    };
+    assert!(example.verify_with_pub_inputs(proof.clone(), pub_inputs).is_err());
+    assert!(example.verify(proof).is_ok());
+}
+
+fn build_example(options: ProofOptions) -> VmExample<Blake3_256> {
+    let inputs = [BaseElement::new(3), BaseElement::new(5)];
+    VmExample::new(Program::sample(), inputs, 32, options)
+}
+
+fn build_options(use_extension_field: bool) -> ProofOptions {
+    let extension = if use_extension_field {
+        FieldExtension::Quadratic
+    } else {
+        FieldExtension::None
+    };
+    ProofOptions::new(28, 8, 0, extension, 4, 31)
+}
@@ -0,0 +1,117 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+
+/*
+ * C bindings for Winterfell STARK prover and verifier.
+ *
+ * Functions declared in this header are exported by libraries built with the winterfell-ffi
+ * crate. wf_verify() is available only if the library was built with export_verifier! macro,
+ * and wf_prove() is available only if the library was built with export_prover! macro.
+ */
+
+#ifndef WINTERFELL_H
+#define WINTERFELL_H
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* Result of a call to an exported function. */
+typedef enum wf_status {
+    WF_STATUS_OK = 0,
+    WF_STATUS_NULL_POINTER = 1,
+    WF_STATUS_INVALID_ARGUMENT = 2,
+    WF_STATUS_DESERIALIZATION_FAILED = 3,
+    WF_STATUS_VERIFICATION_FAILED = 4,
+    WF_STATUS_PROVING_FAILED = 5,
+    WF_STATUS_BUFFER_TOO_SMALL = 6,
+    WF_STATUS_PANIC = 7,
+} wf_status_t;
+
+/* Opaque handle to proof options. */
+typedef struct WfProofOptions wf_proof_options_t;
+
+/* Opaque handle to a STARK proof. */
+typedef struct WfProof wf_proof_t;
+
+/*
+ * Callback which writes the state of the execution trace at the specified step into row. The
+ * state is written as row_len / ELEMENT_BYTES field elements, each in its canonical little-endian
+ * byte representation. Returning false aborts proof generation.
+ */
+typedef bool (*wf_trace_row_fn)(void *user_data, size_t step, uint8_t *row, size_t row_len);
+
+/* PROOF OPTIONS */
+
+/*
+ * Returns a new proof options handle, or NULL if the parameters are not valid. field_extension
+ * is the degree of the field extension (1, 2, or 3). The handle must be released via
+ * wf_options_free().
+ */
+wf_proof_options_t *wf_options_new(size_t num_queries, size_t blowup_factor,
+                                   uint32_t grinding_factor, uint32_t field_extension,
+                                   size_t fri_folding_factor, size_t fri_remainder_max_degree);
+
+/* Releases the specified proof options handle; NULL is ignored. */
+void wf_options_free(wf_proof_options_t *options);
+
+/* PROOFS */
+
+/*
+ * Returns a new handle to a proof deserialized from the specified bytes, or NULL if a valid
+ * proof could not be deserialized. The handle must be released via wf_proof_free().
+ */
+wf_proof_t *wf_proof_from_bytes(const uint8_t *bytes, size_t len);
+
+/* Returns the number of bytes in the serialized form of the proof, or 0 if proof is NULL. */
+size_t wf_proof_serialized_len(const wf_proof_t *proof);
+
+/*
+ * Serializes the proof into out; returns WF_STATUS_BUFFER_TOO_SMALL if out_len is smaller than
+ * the value returned by wf_proof_serialized_len().
+ */
+wf_status_t wf_proof_to_bytes(const wf_proof_t *proof, uint8_t *out, size_t out_len);
+
+/*
+ * Returns conjectured (if conjectured is true) or proven security level of the proof in bits,
+ * or 0 if proof is NULL or the hash function used to generate the proof is not recorded in it.
+ */
+uint32_t wf_proof_security_level(const wf_proof_t *proof, bool conjectured);
+
+/* Releases the specified proof handle; NULL is ignored. */
+void wf_proof_free(wf_proof_t *proof);
+
+/* VERIFIER */
+
+/*
+ * Verifies a serialized proof against serialized public inputs of the computation for which the
+ * library was built. Returns WF_STATUS_OK if the proof is valid.
+ */
+wf_status_t wf_verify(const uint8_t *proof, size_t proof_len, const uint8_t *pub_inputs,
+                      size_t pub_inputs_len);
+
+/* PROVER */
+
+/*
+ * Generates a proof for the computation for which the library was built. The execution trace of
+ * trace_width columns and trace_length rows (a power of two, at least 8) is built by invoking
+ * fill_row for each step in order, with user_data passed through unchanged. On success, a handle
+ * to the proof is written into proof_out; the handle must be released via wf_proof_free().
+ */
+wf_status_t wf_prove(const wf_proof_options_t *options, size_t trace_width, size_t trace_length,
+                     wf_trace_row_fn fill_row, void *user_data, const uint8_t *pub_inputs,
+                     size_t pub_inputs_len, wf_proof_t **proof_out);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* WINTERFELL_H */
@@ -0,0 +1,580 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+//! This crate contains a C ABI surface for the Winterfell STARK verifier and (optionally) prover,
+//! so that applications written in other languages (e.g., C, C++, Go, or Swift) can link against
+//! a prebuilt library instead of building Rust code.
+//!
+//! The crate consists of two parts:
+//!
+//! * Functions which do not depend on the computation being proved, such as functions for
+//!   managing [WfProofOptions] and [WfProof] handles. These functions are exported with
+//!   unmangled `wf_*` names by any library which links this crate.
+//! * Macros which define the functions depending on the computation. Since the prover and the
+//!   verifier are generic over the computation, a single set of bindings cannot cover all
+//!   computations. Instead, [export_verifier!] defines `wf_verify()` function for a specific
+//!   [Air], hash function, and set of acceptable proof options, and [export_prover!] (available
+//!   with `prover` feature) defines `wf_prove()` function which builds the execution trace via a
+//!   caller-supplied callback.
+//!
+//! A library exposing these functions for a computation is built as a `cdylib` or `staticlib`
+//! crate which invokes the macros once:
+//!
+//! ```ignore
+//! winterfell_ffi::export_verifier!(
+//!     FibAir,
+//!     Blake3_256<BaseElement>,
+//!     AcceptableOptions::MinConjecturedSecurity(96)
+//! );
+//! winterfell_ffi::export_prover!(FibAir, Blake3_256<BaseElement>);
+//! ```
+//!
+//! Declarations of all exported functions are provided in `include/winterfell.h` header. Public
+//! inputs of the computation must implement [Deserializable] and are expected to be serialized via
+//! [Serializable]; field elements are passed as their canonical little-endian byte
+//! representations.
+//!
+//! All exported functions catch panics, and thus, a panic never unwinds into the calling code;
+//! instead, [WfStatus::Panic] is returned (or a null pointer, for functions returning handles).
+//!
+//! [Serializable]: verifier::Serializable
+
+use core::slice;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use verifier::{
+    crypto::{ElementHasher, RandomCoin},
+    AcceptableOptions, Air, ByteReader, Deserializable, DeserializationError, FieldExtension,
+    ProofOptions, SliceReader, StarkProof, VerifierError,
+};
+
+pub use verifier;
+
+#[cfg(feature = "prover")]
+pub use prover;
+#[cfg(feature = "prover")]
+use prover::{
+    crypto::DefaultRandomCoin, math::FieldElement, matrix::ColMatrix, AuxTraceRandElements,
+    ConstraintCompositionCoefficients, DefaultConstraintEvaluator, DefaultTraceLde, Prover,
+    StarkDomain, TraceInfo, TracePolyTable, TraceTable,
+};
+#[cfg(feature = "prover")]
+use std::{ffi::c_void, marker::PhantomData};
+
+// STATUS CODES
+// ================================================================================================
+
+/// Result of a call to an exported function.
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum WfStatus {
+    /// The call completed successfully (e.g., the proof is valid).
+    Ok = 0,
+    /// A required pointer argument was null.
+    NullPointer = 1,
+    /// An argument had an invalid value (e.g., the execution trace could not be built).
+    InvalidArgument = 2,
+    /// A proof or public inputs could not be deserialized.
+    DeserializationFailed = 3,
+    /// The proof was deserialized but is not valid.
+    VerificationFailed = 4,
+    /// The prover failed to generate a proof.
+    ProvingFailed = 5,
+    /// The output buffer is too small to hold the result.
+    BufferTooSmall = 6,
+    /// A panic occurred while executing the call.
+    Panic = 7,
+}
+
+impl From<VerifierError> for WfStatus {
+    fn from(err: VerifierError) -> Self {
+        match err {
+            VerifierError::ProofDeserializationError(_) => WfStatus::DeserializationFailed,
+            _ => WfStatus::VerificationFailed,
+        }
+    }
+}
+
+// PROOF OPTIONS
+// ================================================================================================
+
+/// Opaque handle to [ProofOptions].
+///
+/// Handles are created via [wf_options_new()] and must be released via [wf_options_free()].
+pub struct WfProofOptions(ProofOptions);
+
+impl WfProofOptions {
+    /// Returns the proof options referenced by this handle.
+    pub fn options(&self) -> &ProofOptions {
+        &self.0
+    }
+}
+
+/// Returns a new handle to proof options with the specified parameters, or a null pointer if the
+/// parameters are not valid.
+///
+/// `field_extension` is the degree of the field extension (1, 2, or 3); the meaning of other
+/// parameters is the same as in [ProofOptions::new()].
+#[no_mangle]
+pub extern "C" fn wf_options_new(
+    num_queries: usize,
+    blowup_factor: usize,
+    grinding_factor: u32,
+    field_extension: u32,
+    fri_folding_factor: usize,
+    fri_remainder_max_degree: usize,
+) -> *mut WfProofOptions {
+    let field_extension = match field_extension {
+        1 => FieldExtension::None,
+        2 => FieldExtension::Quadratic,
+        3 => FieldExtension::Cubic,
+        _ => return core::ptr::null_mut(),
+    };
+    catch_unwind(|| {
+        ProofOptions::new(
+            num_queries,
+            blowup_factor,
+            grinding_factor,
+            field_extension,
+            fri_folding_factor,
+            fri_remainder_max_degree,
+        )
+    })
+    .map_or(core::ptr::null_mut(), |options| {
+        Box::into_raw(Box::new(WfProofOptions(options)))
+    })
+}
+
+/// Releases the specified proof options handle; null pointers are ignored.
+///
+/// # Safety
+/// `options` must be either null or a handle returned by [wf_options_new()] which has not been
+/// released yet.
+#[no_mangle]
+pub unsafe extern "C" fn wf_options_free(options: *mut WfProofOptions) {
+    if !options.is_null() {
+        drop(Box::from_raw(options));
+    }
+}
+
+// PROOFS
+// ================================================================================================
+
+/// Opaque handle to a [StarkProof].
+///
+/// Handles are created via [wf_proof_from_bytes()] or by `wf_prove()`, and must be released via
+/// [wf_proof_free()].
+pub struct WfProof(StarkProof);
+
+impl WfProof {
+    /// Returns the proof referenced by this handle.
+    pub fn proof(&self) -> &StarkProof {
+        &self.0
+    }
+}
+
+/// Returns a new handle to a proof deserialized from the specified bytes, or a null pointer if a
+/// valid proof could not be deserialized.
+///
+/// # Safety
+/// `bytes` must point to `len` readable bytes; it may be null only if `len` is 0.
+#[no_mangle]
+pub unsafe extern "C" fn wf_proof_from_bytes(bytes: *const u8, len: usize) -> *mut WfProof {
+    let bytes = match read_slice(bytes, len) {
+        Ok(bytes) => bytes,
+        Err(_) => return core::ptr::null_mut(),
+    };
+    match catch_unwind(|| StarkProof::from_bytes(bytes)) {
+        Ok(Ok(proof)) => Box::into_raw(Box::new(WfProof(proof))),
+        _ => core::ptr::null_mut(),
+    }
+}
+
+/// Returns the number of bytes in the serialized form of the specified proof, or 0 if `proof` is
+/// null.
+///
+/// # Safety
+/// `proof` must be either null or a valid proof handle.
+#[no_mangle]
+pub unsafe extern "C" fn wf_proof_serialized_len(proof: *const WfProof) -> usize {
+    match proof.as_ref() {
+        Some(proof) => proof.0.to_bytes().len(),
+        None => 0,
+    }
+}
+
+/// Serializes the specified proof into the buffer `out` of size `out_len`.
+///
+/// Returns [WfStatus::BufferTooSmall] if `out_len` is smaller than the value returned by
+/// [wf_proof_serialized_len()].
+///
+/// # Safety
+/// `proof` must be a valid proof handle, and `out` must point to `out_len` writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn wf_proof_to_bytes(
+    proof: *const WfProof,
+    out: *mut u8,
+    out_len: usize,
+) -> WfStatus {
+    let proof = match proof.as_ref() {
+        Some(proof) => proof,
+        None => return WfStatus::NullPointer,
+    };
+    if out.is_null() {
+        return WfStatus::NullPointer;
+    }
+    let bytes = proof.0.to_bytes();
+    if bytes.len() > out_len {
+        return WfStatus::BufferTooSmall;
+    }
+    core::ptr::copy_nonoverlapping(bytes.as_ptr(), out, bytes.len());
+    WfStatus::Ok
+}
+
+/// Returns security level (in bits) of the specified proof, or 0 if `proof` is null or if the
+/// hash function used to generate the proof is not recorded in the proof.
+///
+/// Conjectured security level is returned if `conjectured` is true, and proven security level is
+/// returned otherwise.
+///
+/// # Safety
+/// `proof` must be either null or a valid proof handle.
+#[no_mangle]
+pub unsafe extern "C" fn wf_proof_security_level(proof: *const WfProof, conjectured: bool) -> u32 {
+    proof
+        .as_ref()
+        .and_then(|proof| proof.0.security_level_auto(conjectured))
+        .unwrap_or(0)
+}
+
+/// Releases the specified proof handle; null pointers are ignored.
+///
+/// # Safety
+/// `proof` must be either null or a proof handle which has not been released yet.
+#[no_mangle]
+pub unsafe extern "C" fn wf_proof_free(proof: *mut WfProof) {
+    if !proof.is_null() {
+        drop(Box::from_raw(proof));
+    }
+}
+
+// VERIFIER
+// ================================================================================================
+
+/// Verifies a serialized proof against serialized public inputs of the computation specified by
+/// `AIR`, `HashFn`, and `RandCoin` type parameters.
+///
+/// This is the function called by `wf_verify()` defined via [export_verifier!]. All bytes of both
+/// inputs must be consumed during deserialization.
+///
+/// # Safety
+/// `proof_bytes` must point to `proof_len` readable bytes, and `pub_inputs_bytes` must point to
+/// `pub_inputs_len` readable bytes; either pointer may be null only if the corresponding length
+/// is 0.
+pub unsafe fn verify_raw<AIR, HashFn, RandCoin>(
+    proof_bytes: *const u8,
+    proof_len: usize,
+    pub_inputs_bytes: *const u8,
+    pub_inputs_len: usize,
+    acceptable_options: &AcceptableOptions,
+) -> WfStatus
+where
+    AIR: Air,
+    AIR::PublicInputs: Deserializable,
+    HashFn: ElementHasher<BaseField = AIR::BaseField>,
+    RandCoin: RandomCoin<BaseField = AIR::BaseField, Hasher = HashFn>,
+{
+    let proof_bytes = match read_slice(proof_bytes, proof_len) {
+        Ok(bytes) => bytes,
+        Err(status) => return status,
+    };
+    let pub_inputs_bytes = match read_slice(pub_inputs_bytes, pub_inputs_len) {
+        Ok(bytes) => bytes,
+        Err(status) => return status,
+    };
+
+    guard(|| {
+        let proof = match StarkProof::from_bytes(proof_bytes) {
+            Ok(proof) => proof,
+            Err(_) => return WfStatus::DeserializationFailed,
+        };
+        let pub_inputs = match read_pub_inputs::<AIR::PublicInputs>(pub_inputs_bytes) {
+            Ok(pub_inputs) => pub_inputs,
+            Err(_) => return WfStatus::DeserializationFailed,
+        };
+        match verifier::verify::<AIR, HashFn, RandCoin>(proof, pub_inputs, acceptable_options) {
+            Ok(()) => WfStatus::Ok,
+            Err(err) => err.into(),
+        }
+    })
+}
+
+/// Defines `wf_verify()` function which verifies proofs of a specific computation.
+///
+/// The macro takes the [Air] of the computation, the hash function used to generate proofs, and
+/// an expression evaluating to [AcceptableOptions]. The defined function has the following C
+/// signature:
+///
+/// ```c
+/// wf_status_t wf_verify(const uint8_t *proof, size_t proof_len, const uint8_t *pub_inputs,
+///                       size_t pub_inputs_len);
+/// ```
+///
+/// The function returns [WfStatus::Ok] if the proof is valid (see [verify_raw()] for details).
+/// Proofs are expected to be generated using
+/// [DefaultRandomCoin](verifier::crypto::DefaultRandomCoin). The macro can be invoked at most once
+/// per library.
+#[macro_export]
+macro_rules! export_verifier {
+    ($air:ty, $hash_fn:ty, $acceptable_options:expr) => {
+        /// Verifies a serialized proof against serialized public inputs.
+        ///
+        /// # Safety
+        /// Both pointers must point to the specified number of readable bytes.
+        #[no_mangle]
+        pub unsafe extern "C" fn wf_verify(
+            proof: *const u8,
+            proof_len: usize,
+            pub_inputs: *const u8,
+            pub_inputs_len: usize,
+        ) -> $crate::WfStatus {
+            $crate::verify_raw::<
+                $air,
+                $hash_fn,
+                $crate::verifier::crypto::DefaultRandomCoin<$hash_fn>,
+            >(proof, proof_len, pub_inputs, pub_inputs_len, &$acceptable_options)
+        }
+    };
+}
+
+// PROVER
+// ================================================================================================
+
+/// Callback which writes the state of the execution trace at the specified `step` into `row`.
+///
+/// The state must be written as `row_len / ELEMENT_BYTES` base field elements, each in its
+/// canonical little-endian byte representation. The callback returns `false` to abort proof
+/// generation.
+#[cfg(feature = "prover")]
+pub type WfTraceRowFn =
+    unsafe extern "C" fn(user_data: *mut c_void, step: usize, row: *mut u8, row_len: usize) -> bool;
+
+/// Generates a proof for the computation specified by `AIR` and `HashFn` type parameters.
+///
+/// This is the function called by `wf_prove()` defined via [export_prover!]. The execution trace
+/// of `trace_width` columns and `trace_length` rows is built by invoking `fill_row` for each step
+/// of the computation, in order, with `user_data` passed through unchanged. On success, a handle
+/// to the generated proof is written into `proof_out`.
+///
+/// # Errors
+/// Returns [WfStatus::InvalidArgument] if the trace dimensions are not valid, if `fill_row`
+/// aborts, or if a trace value is not a valid field element; [WfStatus::DeserializationFailed]
+/// if the public inputs could not be deserialized; and [WfStatus::ProvingFailed] if the prover
+/// fails to generate a proof (e.g., because the trace does not satisfy the assertions of the
+/// computation).
+///
+/// # Safety
+/// `options` must be a valid proof options handle, `pub_inputs_bytes` must point to
+/// `pub_inputs_len` readable bytes (it may be null only if `pub_inputs_len` is 0), `proof_out`
+/// must point to writable memory for a pointer, and `fill_row` must write at most `row_len`
+/// bytes into `row`.
+#[cfg(feature = "prover")]
+#[allow(clippy::too_many_arguments)]
+pub unsafe fn prove_raw<AIR, HashFn>(
+    options: *const WfProofOptions,
+    trace_width: usize,
+    trace_length: usize,
+    fill_row: Option<WfTraceRowFn>,
+    user_data: *mut c_void,
+    pub_inputs_bytes: *const u8,
+    pub_inputs_len: usize,
+    proof_out: *mut *mut WfProof,
+) -> WfStatus
+where
+    AIR: Air + 'static,
+    AIR::PublicInputs: Deserializable,
+    HashFn: ElementHasher<BaseField = AIR::BaseField>,
+{
+    let (options, fill_row) = match (options.as_ref(), fill_row) {
+        (Some(options), Some(fill_row)) if !proof_out.is_null() => (options, fill_row),
+        _ => return WfStatus::NullPointer,
+    };
+    let pub_inputs_bytes = match read_slice(pub_inputs_bytes, pub_inputs_len) {
+        Ok(bytes) => bytes,
+        Err(status) => return status,
+    };
+
+    guard(|| {
+        if read_pub_inputs::<AIR::PublicInputs>(pub_inputs_bytes).is_err() {
+            return WfStatus::DeserializationFailed;
+        }
+        if trace_width == 0
+            || trace_width > TraceInfo::MAX_TRACE_WIDTH
+            || trace_length < TraceInfo::MIN_TRACE_LENGTH
+            || !trace_length.is_power_of_two()
+        {
+            return WfStatus::InvalidArgument;
+        }
+
+        // build the execution trace row by row
+        let element_bytes = AIR::BaseField::ELEMENT_BYTES;
+        let mut row = vec![0u8; trace_width * element_bytes];
+        let mut columns = vec![Vec::with_capacity(trace_length); trace_width];
+        for step in 0..trace_length {
+            if !fill_row(user_data, step, row.as_mut_ptr(), row.len()) {
+                return WfStatus::InvalidArgument;
+            }
+            for (column, bytes) in columns.iter_mut().zip(row.chunks(element_bytes)) {
+                match AIR::BaseField::read_from_bytes(bytes) {
+                    Ok(value) => column.push(value),
+                    Err(_) => return WfStatus::InvalidArgument,
+                }
+            }
+        }
+
+        let prover = CallbackProver::<AIR, HashFn> {
+            options: options.0.clone(),
+            pub_inputs: pub_inputs_bytes.to_vec(),
+            _phantom: PhantomData,
+        };
+        match prover.prove(TraceTable::init(columns)) {
+            Ok(proof) => {
+                *proof_out = Box::into_raw(Box::new(WfProof(proof)));
+                WfStatus::Ok
+            }
+            Err(_) => WfStatus::ProvingFailed,
+        }
+    })
+}
+
+/// Defines `wf_prove()` function which generates proofs of a specific computation.
+///
+/// The macro takes the [Air] of the computation and the hash function used to generate proofs.
+/// The defined function has the following C signature:
+///
+/// ```c
+/// wf_status_t wf_prove(const wf_proof_options_t *options, size_t trace_width,
+///                      size_t trace_length, wf_trace_row_fn fill_row, void *user_data,
+///                      const uint8_t *pub_inputs, size_t pub_inputs_len,
+///                      wf_proof_t **proof_out);
+/// ```
+///
+/// See [prove_raw()] for details. Proofs are generated using
+/// [DefaultRandomCoin](prover::crypto::DefaultRandomCoin). The macro can be invoked at most once
+/// per library.
+#[cfg(feature = "prover")]
+#[macro_export]
+macro_rules! export_prover {
+    ($air:ty, $hash_fn:ty) => {
+        /// Generates a proof using an execution trace built by the specified callback.
+        ///
+        /// # Safety
+        /// All pointers must satisfy the requirements of `winterfell_ffi::prove_raw()`.
+        #[no_mangle]
+        #[allow(clippy::too_many_arguments)]
+        pub unsafe extern "C" fn wf_prove(
+            options: *const $crate::WfProofOptions,
+            trace_width: usize,
+            trace_length: usize,
+            fill_row: Option<$crate::WfTraceRowFn>,
+            user_data: *mut ::std::ffi::c_void,
+            pub_inputs: *const u8,
+            pub_inputs_len: usize,
+            proof_out: *mut *mut $crate::WfProof,
+        ) -> $crate::WfStatus {
+            $crate::prove_raw::<$air, $hash_fn>(
+                options,
+                trace_width,
+                trace_length,
+                fill_row,
+                user_data,
+                pub_inputs,
+                pub_inputs_len,
+                proof_out,
+            )
+        }
+    };
+}
+
+/// Prover for an execution trace built by [prove_raw()] with public inputs supplied in serialized
+/// form.
+#[cfg(feature = "prover")]
+struct CallbackProver<AIR, HashFn> {
+    options: ProofOptions,
+    pub_inputs: Vec<u8>,
+    _phantom: PhantomData<fn() -> (AIR, HashFn)>,
+}
+
+#[cfg(feature = "prover")]
+impl<AIR, HashFn> Prover for CallbackProver<AIR, HashFn>
+where
+    AIR: Air + 'static,
+    AIR::PublicInputs: Deserializable,
+    HashFn: ElementHasher<BaseField = AIR::BaseField>,
+{
+    type BaseField = AIR::BaseField;
+    type Air = AIR;
+    type Trace = TraceTable<AIR::BaseField>;
+    type HashFn = HashFn;
+    type RandomCoin = DefaultRandomCoin<HashFn>;
+    type TraceLde<E: FieldElement<BaseField = Self::BaseField>> = DefaultTraceLde<E, HashFn>;
+    type ConstraintEvaluator<'a, E: FieldElement<BaseField = Self::BaseField>> =
+        DefaultConstraintEvaluator<'a, AIR, E>;
+
+    fn get_pub_inputs(&self, _trace: &Self::Trace) -> AIR::PublicInputs {
+        // public inputs are validated by prove_raw() before the prover is created
+        read_pub_inputs(&self.pub_inputs).expect("failed to read public inputs")
+    }
+
+    fn options(&self) -> &ProofOptions {
+        &self.options
+    }
+
+    fn new_trace_lde<E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        trace_info: &TraceInfo,
+        main_trace: &ColMatrix<Self::BaseField>,
+        domain: &StarkDomain<Self::BaseField>,
+    ) -> (Self::TraceLde<E>, TracePolyTable<E>) {
+        DefaultTraceLde::new(trace_info, main_trace, domain)
+    }
+
+    fn new_evaluator<'a, E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        air: &'a AIR,
+        aux_rand_elements: AuxTraceRandElements<E>,
+        composition_coefficients: ConstraintCompositionCoefficients<E>,
+    ) -> Self::ConstraintEvaluator<'a, E> {
+        DefaultConstraintEvaluator::new(air, aux_rand_elements, composition_coefficients)
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Executes the specified function returning [WfStatus::Panic] if the function panics.
+fn guard<F: FnOnce() -> WfStatus>(f: F) -> WfStatus {
+    catch_unwind(AssertUnwindSafe(f)).unwrap_or(WfStatus::Panic)
+}
+
+/// Returns a slice of `len` bytes starting at `ptr`; `ptr` may be null only if `len` is 0.
+unsafe fn read_slice<'a>(ptr: *const u8, len: usize) -> Result<&'a [u8], WfStatus> {
+    if len == 0 {
+        Ok(&[])
+    } else if ptr.is_null() {
+        Err(WfStatus::NullPointer)
+    } else {
+        Ok(slice::from_raw_parts(ptr, len))
+    }
+}
+
+/// Deserializes public inputs from the provided bytes making sure all bytes are consumed.
+fn read_pub_inputs<P: Deserializable>(bytes: &[u8]) -> Result<P, DeserializationError> {
+    let mut source = SliceReader::new(bytes);
+    let pub_inputs = P::read_from(&mut source)?;
+    if source.has_more_bytes() {
+        return Err(DeserializationError::UnconsumedBytes);
+    }
+    Ok(pub_inputs)
+}
@@ -0,0 +1,205 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use std::ffi::c_void;
+use winterfell_ffi::{
+    export_prover, export_verifier,
+    verifier::{
+        crypto::hashers::Blake3_256,
+        math::{fields::f128::BaseElement, FieldElement},
+        AcceptableOptions, Air, AirContext, Assertion, EvaluationFrame, ProofOptions, Serializable,
+        TraceInfo, TransitionConstraintDegree,
+    },
+    wf_options_free, wf_options_new, wf_proof_free, wf_proof_from_bytes, wf_proof_security_level,
+    wf_proof_serialized_len, wf_proof_to_bytes, WfProof, WfStatus,
+};
+
+type Blake3 = Blake3_256<BaseElement>;
+
+export_verifier!(FibAir, Blake3, AcceptableOptions::MinConjecturedSecurity(40));
+export_prover!(FibAir, Blake3);
+
+// TESTS
+// ================================================================================================
+
+#[test]
+fn prove_and_verify() {
+    let trace = build_trace(64);
+    let pub_inputs = trace[trace.len() - 1][1].to_bytes();
+    let user_data = &trace as *const Vec<[BaseElement; 2]> as *mut c_void;
+
+    unsafe {
+        // invalid options are rejected
+        assert!(wf_options_new(0, 8, 0, 1, 4, 7).is_null());
+        assert!(wf_options_new(28, 8, 0, 4, 4, 7).is_null());
+
+        // generate a proof via the callback-based prover
+        let options = wf_options_new(28, 8, 0, 1, 4, 7);
+        assert!(!options.is_null());
+        let mut proof: *mut WfProof = std::ptr::null_mut();
+        let status = wf_prove(
+            options,
+            2,
+            trace.len(),
+            Some(fill_row),
+            user_data,
+            pub_inputs.as_ptr(),
+            pub_inputs.len(),
+            &mut proof,
+        );
+        assert_eq!(WfStatus::Ok, status);
+        assert!(wf_proof_security_level(proof, true) > 0);
+
+        // serialize the proof and verify it
+        let mut bytes = vec![0; wf_proof_serialized_len(proof)];
+        assert_eq!(WfStatus::BufferTooSmall, wf_proof_to_bytes(proof, bytes.as_mut_ptr(), 1));
+        assert_eq!(WfStatus::Ok, wf_proof_to_bytes(proof, bytes.as_mut_ptr(), bytes.len()));
+        assert_eq!((*proof).proof().to_bytes(), bytes);
+        let status = wf_verify(bytes.as_ptr(), bytes.len(), pub_inputs.as_ptr(), pub_inputs.len());
+        assert_eq!(WfStatus::Ok, status);
+
+        // serialized proofs can be read back into handles
+        let decoded = wf_proof_from_bytes(bytes.as_ptr(), bytes.len());
+        assert!(!decoded.is_null());
+        assert_eq!((*proof).proof(), (*decoded).proof());
+        assert!(wf_proof_from_bytes(bytes.as_ptr(), bytes.len() - 1).is_null());
+        wf_proof_free(decoded);
+        wf_proof_free(proof);
+
+        // wrong public inputs, trailing bytes, and malformed proofs are rejected
+        let wrong_inputs = BaseElement::ONE.to_bytes();
+        let status =
+            wf_verify(bytes.as_ptr(), bytes.len(), wrong_inputs.as_ptr(), wrong_inputs.len());
+        assert_eq!(WfStatus::VerificationFailed, status);
+        let long_inputs = [pub_inputs.clone(), vec![0]].concat();
+        let status =
+            wf_verify(bytes.as_ptr(), bytes.len(), long_inputs.as_ptr(), long_inputs.len());
+        assert_eq!(WfStatus::DeserializationFailed, status);
+        let status =
+            wf_verify(bytes.as_ptr(), bytes.len() - 1, pub_inputs.as_ptr(), pub_inputs.len());
+        assert_eq!(WfStatus::DeserializationFailed, status);
+        let status = wf_verify(std::ptr::null(), 1, pub_inputs.as_ptr(), pub_inputs.len());
+        assert_eq!(WfStatus::NullPointer, status);
+
+        // aborted callbacks, invalid trace dimensions, and wrong public inputs are reported
+        let mut proof: *mut WfProof = std::ptr::null_mut();
+        let status = wf_prove(
+            options,
+            2,
+            trace.len(),
+            Some(abort_row),
+            user_data,
+            pub_inputs.as_ptr(),
+            pub_inputs.len(),
+            &mut proof,
+        );
+        assert_eq!(WfStatus::InvalidArgument, status);
+        let status = wf_prove(
+            options,
+            2,
+            trace.len() - 1,
+            Some(fill_row),
+            user_data,
+            pub_inputs.as_ptr(),
+            pub_inputs.len(),
+            &mut proof,
+        );
+        assert_eq!(WfStatus::InvalidArgument, status);
+        let status = wf_prove(
+            options,
+            2,
+            trace.len(),
+            Some(fill_row),
+            user_data,
+            wrong_inputs.as_ptr(),
+            wrong_inputs.len(),
+            &mut proof,
+        );
+        assert_eq!(WfStatus::ProvingFailed, status);
+        assert!(proof.is_null());
+        wf_options_free(options);
+    }
+}
+
+// TRACE CALLBACKS
+// ================================================================================================
+
+/// Builds rows of an execution trace which advances the Fibonacci sequence by 2 terms per row.
+fn build_trace(length: usize) -> Vec<[BaseElement; 2]> {
+    let mut state = [BaseElement::ONE, BaseElement::ONE];
+    let mut trace = Vec::with_capacity(length);
+    for _ in 0..length {
+        trace.push(state);
+        state[0] += state[1];
+        state[1] += state[0];
+    }
+    trace
+}
+
+/// Writes the row of the trace passed via `user_data` at the specified step into `row`.
+unsafe extern "C" fn fill_row(
+    user_data: *mut c_void,
+    step: usize,
+    row: *mut u8,
+    row_len: usize,
+) -> bool {
+    let trace = &*(user_data as *const Vec<[BaseElement; 2]>);
+    let row = std::slice::from_raw_parts_mut(row, row_len);
+    for (bytes, value) in row.chunks_mut(BaseElement::ELEMENT_BYTES).zip(trace[step]) {
+        bytes.copy_from_slice(&value.to_bytes());
+    }
+    true
+}
+
+/// Fails to write any row of the trace.
+unsafe extern "C" fn abort_row(_: *mut c_void, _: usize, _: *mut u8, _: usize) -> bool {
+    false
+}
+
+// FIBONACCI AIR
+// ================================================================================================
+
+pub struct FibAir {
+    context: AirContext<BaseElement>,
+    result: BaseElement,
+}
+
+impl Air for FibAir {
+    type BaseField = BaseElement;
+    type PublicInputs = BaseElement;
+
+    fn new(trace_info: TraceInfo, result: BaseElement, options: ProofOptions) -> Self {
+        let degrees = vec![TransitionConstraintDegree::new(1), TransitionConstraintDegree::new(1)];
+        FibAir {
+            context: AirContext::new(trace_info, degrees, 3, options),
+            result,
+        }
+    }
+
+    fn context(&self) -> &AirContext<Self::BaseField> {
+        &self.context
+    }
+
+    fn evaluate_transition<E: FieldElement + From<Self::BaseField>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        _periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        let current = frame.current();
+        let next = frame.next();
+        result[0] = next[0] - (current[0] + current[1]);
+        result[1] = next[1] - (current[1] + next[0]);
+    }
+
+    fn get_assertions(&self) -> Vec<Assertion<Self::BaseField>> {
+        let last_step = self.trace_length() - 1;
+        vec![
+            Assertion::single(0, 0, BaseElement::ONE),
+            Assertion::single(1, 0, BaseElement::ONE),
+            Assertion::single(1, last_step, self.result),
+        ]
+    }
+}
@@ -4,7 +4,10 @@
 // LICENSE file in the root directory of this source tree.
 
 use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
-use math::{fields::f128::BaseElement, get_power_series, polynom, StarkField};
+use math::{
+    fields::{f128::BaseElement, f64, QuadExtension},
+    get_power_series, polynom, StarkField,
+};
 use rand_utils::{rand_value, rand_vector};
 use utils::group_vector_elements;
 use winter_fri::folding;
@@ -31,6 +34,13 @@ pub fn apply_drp(c: &mut Criterion) {
         group.bench_function(BenchmarkId::new("base field", size), |b| {
             b.iter(|| folding::apply_drp(&ys, BaseElement::GENERATOR, alpha))
         });
+
+        let ys =
+            group_vector_elements::<_, 4>(rand_vector::<QuadExtension<f64::BaseElement>>(size));
+        let alpha: QuadExtension<f64::BaseElement> = rand_value();
+        group.bench_function(BenchmarkId::new("quadratic extension", size), |b| {
+            b.iter(|| folding::apply_drp(&ys, f64::BaseElement::GENERATOR, alpha))
+        });
     }
 }
 
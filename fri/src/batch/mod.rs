@@ -0,0 +1,321 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+//! Contains an implementation of FRI over a random linear combination of several functions.
+//!
+//! Batched FRI allows a prover to convince a verifier that each of several committed functions
+//! is a polynomial of bounded degree using a single FRI proof. The protocol proceeds as follows:
+//!
+//! 1. The prover commits to evaluations of all functions using a single Merkle tree, where the
+//!    leaf at position *i* is a hash of evaluations of all functions at the *i*-th point of the
+//!    domain.
+//! 2. After the commitment is absorbed by the public coin, random coefficients (one per function)
+//!    are drawn from the coin, and the prover executes regular FRI protocol against the linear
+//!    combination of the functions using these coefficients.
+//! 3. At each of the queried positions, the prover opens evaluations of all individual functions
+//!    against the batch commitment. The verifier computes evaluations of the combined function
+//!    from these openings, and checks them against the FRI proof.
+//!
+//! Since openings of individual functions are returned by the verifier, they can be used by the
+//! caller to perform additional protocol-specific checks.
+
+use crate::{
+    DefaultProverChannel, DefaultVerifierChannel, FriOptions, FriProver, FriVerifier, VerifierError,
+};
+use core::marker::PhantomData;
+use crypto::{ElementHasher, MerkleTree, RandomCoin};
+use math::{FieldElement, StarkField};
+use utils::{collections::Vec, string::ToString};
+
+mod proof;
+pub use proof::BatchedFriProof;
+
+#[cfg(test)]
+mod tests;
+
+// BATCHED FRI PROVER
+// ================================================================================================
+
+/// Implements the prover component of the batched FRI protocol.
+///
+/// The prover commits to evaluations of several functions over the same domain, draws random
+/// coefficients from the public coin, and generates a FRI proof for the linear combination of
+/// the functions with these coefficients. The resulting [BatchedFriProof] also contains
+/// evaluations of each individual function at the queried positions.
+pub struct BatchedFriProver<B, E, H, R>
+where
+    B: StarkField,
+    E: FieldElement<BaseField = B>,
+    H: ElementHasher<BaseField = B>,
+    R: RandomCoin<BaseField = B, Hasher = H>,
+{
+    options: FriOptions,
+    num_queries: usize,
+    _field_element: PhantomData<E>,
+    _public_coin: PhantomData<R>,
+}
+
+impl<B, E, H, R> BatchedFriProver<B, E, H, R>
+where
+    B: StarkField,
+    E: FieldElement<BaseField = B>,
+    H: ElementHasher<BaseField = B>,
+    R: RandomCoin<BaseField = B, Hasher = H>,
+{
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+    /// Returns a new batched FRI prover instantiated with the provided `options` which will
+    /// query the committed functions at `num_queries` positions.
+    ///
+    /// # Panics
+    /// Panics if `num_queries` is zero.
+    pub fn new(options: FriOptions, num_queries: usize) -> Self {
+        assert!(num_queries > 0, "number of queries must be greater than zero");
+        BatchedFriProver {
+            options,
+            num_queries,
+            _field_element: PhantomData,
+            _public_coin: PhantomData,
+        }
+    }
+
+    // ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the FRI options of this prover.
+    pub fn options(&self) -> &FriOptions {
+        &self.options
+    }
+
+    /// Returns the number of positions at which the committed functions are queried.
+    pub fn num_queries(&self) -> usize {
+        self.num_queries
+    }
+
+    // PROVING
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a proof that each of the functions described by `evaluations` is a polynomial of
+    /// degree smaller than the length of its evaluations divided by the blowup factor.
+    ///
+    /// Each vector in `evaluations` must contain evaluations of a single function over the FRI
+    /// evaluation domain (i.e., the domain shifted by the domain offset specified in the options
+    /// of this prover). The `public_coin` may be seeded with the transcript of an enclosing
+    /// protocol; to verify the proof, the verifier must use a public coin in the same state.
+    ///
+    /// # Panics
+    /// Panics if:
+    /// * `evaluations` is empty.
+    /// * Evaluation vectors do not all have the same length.
+    /// * The length of evaluation vectors is smaller than 8 or is not a power of two.
+    /// * The number of unique query positions could not be drawn from the evaluation domain.
+    pub fn prove(&self, mut public_coin: R, evaluations: &[Vec<E>]) -> BatchedFriProof<E, H> {
+        assert!(!evaluations.is_empty(), "at least one function must be provided");
+        let domain_size = evaluations[0].len();
+        assert!(
+            evaluations.iter().all(|column| column.len() == domain_size),
+            "evaluations of all functions must be over the same domain"
+        );
+
+        // commit to evaluations of all functions; each leaf of the tree commits to evaluations
+        // of all functions at a single point of the domain
+        let rows = transpose_columns(evaluations);
+        let leaves = rows.iter().map(|row| H::hash_elements(row)).collect();
+        let batch_tree = MerkleTree::<H>::new(leaves).expect("failed to construct batch tree");
+        public_coin.reseed(*batch_tree.root());
+
+        // draw coefficients for the linear combination and combine the functions
+        let coefficients = draw_coefficients::<E, R>(&mut public_coin, evaluations.len())
+            .expect("failed to draw batching coefficients");
+        let combined_evaluations = rows.iter().map(|row| combine_row(row, &coefficients)).collect();
+
+        // execute FRI protocol against the combined function
+        let mut channel = DefaultProverChannel::<E, H, R>::with_public_coin(
+            public_coin,
+            domain_size,
+            self.num_queries,
+        );
+        let mut fri_prover = FriProver::new(self.options.clone());
+        fri_prover.build_layers(&mut channel, combined_evaluations);
+
+        let positions = sort_positions(channel.draw_query_positions(0));
+        let fri_proof = fri_prover.build_proof(&positions);
+
+        // open evaluations of all functions at the queried positions
+        let batch_proof = batch_tree.prove_batch(&positions).expect("failed to open batch tree");
+        let queried_values = positions.iter().map(|&position| rows[position].clone()).collect();
+
+        BatchedFriProof {
+            batch_commitment: *batch_tree.root(),
+            layer_commitments: channel.layer_commitments().to_vec(),
+            queried_values,
+            batch_proof,
+            fri_proof,
+        }
+    }
+}
+
+// BATCHED FRI VERIFIER
+// ================================================================================================
+
+/// Implements the verifier component of the batched FRI protocol.
+///
+/// The verifier checks that all functions committed to in a [BatchedFriProof] are polynomials
+/// of bounded degree, and returns evaluations of each function at the queried positions.
+pub struct BatchedFriVerifier<B, E, H, R>
+where
+    B: StarkField,
+    E: FieldElement<BaseField = B>,
+    H: ElementHasher<BaseField = B>,
+    R: RandomCoin<BaseField = B, Hasher = H>,
+{
+    options: FriOptions,
+    num_queries: usize,
+    _field_element: PhantomData<E>,
+    _public_coin: PhantomData<R>,
+}
+
+impl<B, E, H, R> BatchedFriVerifier<B, E, H, R>
+where
+    B: StarkField,
+    E: FieldElement<BaseField = B>,
+    H: ElementHasher<BaseField = B>,
+    R: RandomCoin<BaseField = B, Hasher = H>,
+{
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+    /// Returns a new batched FRI verifier instantiated with the provided `options` which expects
+    /// the committed functions to be queried at `num_queries` positions.
+    ///
+    /// # Panics
+    /// Panics if `num_queries` is zero.
+    pub fn new(options: FriOptions, num_queries: usize) -> Self {
+        assert!(num_queries > 0, "number of queries must be greater than zero");
+        BatchedFriVerifier {
+            options,
+            num_queries,
+            _field_element: PhantomData,
+            _public_coin: PhantomData,
+        }
+    }
+
+    // VERIFICATION
+    // --------------------------------------------------------------------------------------------
+
+    /// Verifies that all functions committed to in the specified `proof` are polynomials of
+    /// degree at most `max_poly_degree`.
+    ///
+    /// The `public_coin` must be in the same state as the public coin passed to the prover. If
+    /// the proof is valid, returns the queried positions (sorted in ascending order) together
+    /// with evaluations of all functions at these positions.
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// * The number of FRI layer commitments in the proof is inconsistent with `max_poly_degree`
+    ///   and the options of this verifier.
+    /// * The FRI proof could not be parsed.
+    /// * The number of opened evaluations does not match the number of queried positions.
+    /// * Opened evaluations do not match the batch commitment.
+    /// * The FRI proof for the combined function is not valid.
+    pub fn verify(
+        &self,
+        proof: BatchedFriProof<E, H>,
+        mut public_coin: R,
+        max_poly_degree: usize,
+    ) -> Result<(Vec<usize>, Vec<Vec<E>>), VerifierError> {
+        let domain_size = max_poly_degree.next_power_of_two() * self.options.blowup_factor();
+
+        // draw the same coefficients as the prover did after committing to the batch
+        public_coin.reseed(proof.batch_commitment);
+        let coefficients = draw_coefficients::<E, R>(&mut public_coin, proof.num_polys())?;
+
+        let num_layers = self.options.num_fri_layers(domain_size) + 1;
+        if proof.layer_commitments.len() != num_layers {
+            return Err(VerifierError::ProofDeserializationError(format!(
+                "expected {num_layers} FRI layer commitments, but {} were provided",
+                proof.layer_commitments.len()
+            )));
+        }
+
+        let mut channel = DefaultVerifierChannel::<E, H>::new(
+            proof.fri_proof,
+            proof.layer_commitments,
+            domain_size,
+            &self.options,
+        )
+        .map_err(|err| VerifierError::ProofDeserializationError(err.to_string()))?;
+        let fri_verifier = FriVerifier::new(
+            &mut channel,
+            &mut public_coin,
+            self.options.clone(),
+            max_poly_degree,
+        )?;
+
+        let positions = sort_positions(
+            public_coin
+                .draw_integers(self.num_queries, domain_size, 0)
+                .map_err(VerifierError::RandomCoinError)?,
+        );
+        if proof.queried_values.len() != positions.len() {
+            return Err(VerifierError::NumPositionEvaluationMismatch(
+                positions.len(),
+                proof.queried_values.len(),
+            ));
+        }
+
+        // make sure the opened evaluations are consistent with the batch commitment
+        let leaves = proof.queried_values.iter().map(|row| H::hash_elements(row)).collect();
+        let mut batch_proof = proof.batch_proof;
+        batch_proof.leaves = leaves;
+        MerkleTree::<H>::verify_batch(&proof.batch_commitment, &positions, &batch_proof)
+            .map_err(|_| VerifierError::BatchQueryDoesNotMatchCommitment)?;
+
+        // verify FRI proof against evaluations of the combined function
+        let combined_evaluations: Vec<E> =
+            proof.queried_values.iter().map(|row| combine_row(row, &coefficients)).collect();
+        fri_verifier.verify(&mut channel, &combined_evaluations, &positions)?;
+
+        Ok((positions, proof.queried_values))
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Transposes a list of evaluation vectors into a list of rows, where the *i*-th row contains
+/// evaluations of all functions at the *i*-th point of the domain.
+fn transpose_columns<E: FieldElement>(columns: &[Vec<E>]) -> Vec<Vec<E>> {
+    (0..columns[0].len())
+        .map(|i| columns.iter().map(|column| column[i]).collect())
+        .collect()
+}
+
+/// Draws `num_coefficients` random coefficients for the linear combination from the public coin.
+fn draw_coefficients<E, R>(
+    public_coin: &mut R,
+    num_coefficients: usize,
+) -> Result<Vec<E>, VerifierError>
+where
+    E: FieldElement,
+    R: RandomCoin<BaseField = E::BaseField>,
+{
+    (0..num_coefficients)
+        .map(|_| public_coin.draw().map_err(VerifierError::RandomCoinError))
+        .collect()
+}
+
+/// Computes an evaluation of the combined function from evaluations of individual functions.
+fn combine_row<E: FieldElement>(row: &[E], coefficients: &[E]) -> E {
+    row.iter()
+        .zip(coefficients)
+        .fold(E::ZERO, |acc, (&value, &coeff)| acc + value * coeff)
+}
+
+/// Sorts query positions in ascending order and removes duplicates.
+fn sort_positions(mut positions: Vec<usize>) -> Vec<usize> {
+    positions.sort_unstable();
+    positions.dedup();
+    positions
+}
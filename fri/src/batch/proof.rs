@@ -0,0 +1,118 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use crate::FriProof;
+use crypto::{BatchMerkleProof, ElementHasher};
+use math::FieldElement;
+use utils::{
+    collections::Vec, ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable,
+};
+
+// BATCHED FRI PROOF
+// ================================================================================================
+
+/// A proof generated by a [BatchedFriProver](super::BatchedFriProver).
+///
+/// In addition to a regular [FriProof] for a random linear combination of the committed
+/// functions, the proof contains all commitments made by the prover, and evaluations of every
+/// committed function at the queried positions together with a batch Merkle proof against the
+/// batch commitment.
+#[derive(Debug, PartialEq, Eq)]
+pub struct BatchedFriProof<E, H>
+where
+    E: FieldElement,
+    H: ElementHasher<BaseField = E::BaseField>,
+{
+    /// Root of the Merkle tree committing to evaluations of all functions in the batch.
+    pub batch_commitment: H::Digest,
+    /// Commitments to all FRI layers of the combined function, including the remainder.
+    pub layer_commitments: Vec<H::Digest>,
+    /// Evaluations of all functions at each of the queried positions; the positions are sorted in
+    /// ascending order and contain no duplicates.
+    pub queried_values: Vec<Vec<E>>,
+    /// Authentication paths for the queried evaluations against the batch commitment.
+    pub batch_proof: BatchMerkleProof<H>,
+    /// FRI proof for the combined function.
+    pub fri_proof: FriProof,
+}
+
+impl<E, H> BatchedFriProof<E, H>
+where
+    E: FieldElement,
+    H: ElementHasher<BaseField = E::BaseField>,
+{
+    /// Returns the number of functions committed to in this proof.
+    pub fn num_polys(&self) -> usize {
+        self.queried_values.first().map(|values| values.len()).unwrap_or(0)
+    }
+}
+
+// SERIALIZATION / DESERIALIZATION
+// ------------------------------------------------------------------------------------------------
+
+impl<E, H> Serializable for BatchedFriProof<E, H>
+where
+    E: FieldElement,
+    H: ElementHasher<BaseField = E::BaseField>,
+{
+    /// Serializes `self` and writes the resulting bytes into the `target` writer.
+    ///
+    /// # Panics
+    /// Panics if the proof contains more than 255 layer commitments or queried positions, or if
+    /// more than 65535 functions were committed to.
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write(self.batch_commitment);
+        target.write_u8(self.layer_commitments.len() as u8);
+        target.write(&self.layer_commitments);
+
+        // write queried values; leaves of the batch Merkle proof are not written as they can be
+        // recomputed from the values
+        target.write_u8(self.queried_values.len() as u8);
+        target.write_u16(self.num_polys() as u16);
+        for values in self.queried_values.iter() {
+            target.write(values);
+        }
+        target.write_u8(self.batch_proof.depth);
+        target.write_bytes(&self.batch_proof.serialize_nodes());
+
+        self.fri_proof.write_into(target);
+    }
+}
+
+impl<E, H> Deserializable for BatchedFriProof<E, H>
+where
+    E: FieldElement,
+    H: ElementHasher<BaseField = E::BaseField>,
+{
+    /// Reads a batched FRI proof from the specified `source` and returns the result.
+    ///
+    /// # Errors
+    /// Returns an error if a valid proof could not be read from the source.
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        let batch_commitment = H::Digest::read_from(source)?;
+        let num_layer_commitments = source.read_u8()? as usize;
+        let layer_commitments = H::Digest::read_batch_from(source, num_layer_commitments)?;
+
+        let num_queries = source.read_u8()? as usize;
+        let num_polys = source.read_u16()? as usize;
+        let mut queried_values = Vec::with_capacity(num_queries);
+        for _ in 0..num_queries {
+            queried_values.push(E::read_batch_from(source, num_polys)?);
+        }
+        let leaves = queried_values.iter().map(|values| H::hash_elements(values)).collect();
+        let depth = source.read_u8()?;
+        let batch_proof = BatchMerkleProof::deserialize(source, leaves, depth)?;
+
+        let fri_proof = FriProof::read_from(source)?;
+
+        Ok(BatchedFriProof {
+            batch_commitment,
+            layer_commitments,
+            queried_values,
+            batch_proof,
+            fri_proof,
+        })
+    }
+}
@@ -0,0 +1,129 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{BatchedFriProof, BatchedFriProver, BatchedFriVerifier};
+use crate::{FriOptions, VerifierError};
+use crypto::{hashers::Blake3_256, DefaultRandomCoin, RandomCoin};
+use math::{fft, fields::f128::BaseElement, FieldElement};
+use utils::{collections::Vec, Deserializable, Serializable, SliceReader};
+
+type Blake3 = Blake3_256<BaseElement>;
+type Coin = DefaultRandomCoin<Blake3>;
+type Prover = BatchedFriProver<BaseElement, BaseElement, Blake3, Coin>;
+type Verifier = BatchedFriVerifier<BaseElement, BaseElement, Blake3, Coin>;
+
+const NUM_QUERIES: usize = 32;
+const SEED: [BaseElement; 1] = [BaseElement::new(42)];
+
+// PROVE/VERIFY TESTS
+// ================================================================================================
+
+#[test]
+fn batched_fri_prove_verify() {
+    let options = FriOptions::new(8, 4, 7);
+    let max_degree = 255;
+    let evaluations = build_batch(3, max_degree + 1, &options);
+
+    let prover = Prover::new(options.clone(), NUM_QUERIES);
+    let proof = prover.prove(Coin::new(&SEED), &evaluations);
+    assert_eq!(3, proof.num_polys());
+
+    let verifier = Verifier::new(options, NUM_QUERIES);
+    let (positions, values) = verifier.verify(proof, Coin::new(&SEED), max_degree).unwrap();
+
+    // returned openings must be evaluations of the individual functions
+    assert_eq!(positions.len(), values.len());
+    for (&position, row) in positions.iter().zip(values.iter()) {
+        let expected: Vec<BaseElement> = evaluations.iter().map(|e| e[position]).collect();
+        assert_eq!(&expected, row);
+    }
+}
+
+#[test]
+fn batched_fri_high_degree_poly() {
+    let options = FriOptions::new(8, 4, 7);
+    let max_degree = 255;
+    let mut evaluations = build_batch(2, max_degree + 1, &options);
+    // the last function has degree twice as high as allowed
+    evaluations.extend(build_batch(1, 2 * (max_degree + 1), &FriOptions::new(4, 4, 7)));
+
+    let prover = Prover::new(options.clone(), NUM_QUERIES);
+    let proof = prover.prove(Coin::new(&SEED), &evaluations);
+
+    let verifier = Verifier::new(options, NUM_QUERIES);
+    assert!(verifier.verify(proof, Coin::new(&SEED), max_degree).is_err());
+}
+
+#[test]
+fn batched_fri_tampered_opening() {
+    let options = FriOptions::new(8, 4, 7);
+    let max_degree = 255;
+    let evaluations = build_batch(3, max_degree + 1, &options);
+
+    let prover = Prover::new(options.clone(), NUM_QUERIES);
+    let mut proof = prover.prove(Coin::new(&SEED), &evaluations);
+    proof.queried_values[0][1] += BaseElement::ONE;
+
+    let verifier = Verifier::new(options, NUM_QUERIES);
+    let result = verifier.verify(proof, Coin::new(&SEED), max_degree);
+    assert_eq!(Err(VerifierError::BatchQueryDoesNotMatchCommitment), result);
+}
+
+#[test]
+fn batched_fri_different_public_coin() {
+    let options = FriOptions::new(8, 4, 7);
+    let max_degree = 255;
+    let evaluations = build_batch(3, max_degree + 1, &options);
+
+    let prover = Prover::new(options.clone(), NUM_QUERIES);
+    let proof = prover.prove(Coin::new(&SEED), &evaluations);
+
+    let verifier = Verifier::new(options, NUM_QUERIES);
+    let coin = Coin::new(&[BaseElement::new(43)]);
+    assert!(verifier.verify(proof, coin, max_degree).is_err());
+}
+
+// SERIALIZATION TESTS
+// ================================================================================================
+
+#[test]
+fn batched_fri_proof_serialization() {
+    let options = FriOptions::new(8, 4, 7);
+    let max_degree = 255;
+    let evaluations = build_batch(3, max_degree + 1, &options);
+
+    let prover = Prover::new(options.clone(), NUM_QUERIES);
+    let proof = prover.prove(Coin::new(&SEED), &evaluations);
+
+    let proof_bytes = proof.to_bytes();
+    let mut reader = SliceReader::new(&proof_bytes);
+    let parsed_proof = BatchedFriProof::<BaseElement, Blake3>::read_from(&mut reader).unwrap();
+    assert_eq!(proof, parsed_proof);
+
+    let verifier = Verifier::new(options, NUM_QUERIES);
+    assert!(verifier.verify(parsed_proof, Coin::new(&SEED), max_degree).is_ok());
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns evaluations of `num_polys` distinct polynomials with `poly_size` coefficients over
+/// the FRI evaluation domain defined by the `options`.
+fn build_batch(num_polys: usize, poly_size: usize, options: &FriOptions) -> Vec<Vec<BaseElement>> {
+    let twiddles = fft::get_twiddles::<BaseElement>(poly_size);
+    (0..num_polys)
+        .map(|i| {
+            let poly: Vec<BaseElement> = (0..poly_size as u128)
+                .map(|j| BaseElement::new(j * (i as u128 + 1) + 1))
+                .collect();
+            fft::evaluate_poly_with_offset(
+                &poly,
+                &twiddles,
+                options.domain_offset(),
+                options.blowup_factor(),
+            )
+        })
+        .collect()
+}
@@ -6,6 +6,7 @@
 use core::fmt;
 
 use crypto::RandomCoinError;
+use utils::string::String;
 
 // VERIFIER ERROR
 // ================================================================================================
@@ -20,20 +21,55 @@ pub enum VerifierError {
     UnsupportedFoldingFactor(usize),
     /// Number of query positions does not match the number of provided evaluations.
     NumPositionEvaluationMismatch(usize, usize),
-    /// Evaluations at queried positions did not match layer commitment made by the prover.
-    LayerCommitmentMismatch,
-    /// Degree-respecting projection was not performed correctly at one of the layers.
-    InvalidLayerFolding(usize),
+    /// Evaluations at queried positions did not match commitment to the specified layer made by
+    /// the prover.
+    LayerCommitmentMismatch(usize),
+    /// Degree-respecting projection was not performed correctly at the specified layer; the
+    /// second value is the first query position (in the domain of the layer) at which the
+    /// projection is inconsistent.
+    InvalidLayerFolding(usize, usize),
     /// FRI remainder did not match the commitment.
     RemainderCommitmentMismatch,
-    /// Degree-respecting projection was not performed correctly at the last layer.
-    InvalidRemainderFolding,
+    /// Degree-respecting projection was not performed correctly at the last layer; the value is
+    /// the first query position (in the domain of the remainder) at which the remainder is
+    /// inconsistent with the last layer.
+    InvalidRemainderFolding(usize),
     /// FRI remainder expected degree is greater than number of remainder values.
     RemainderDegreeNotValid,
     /// FRI remainder degree is greater than the polynomial degree expected for the last layer.
     RemainderDegreeMismatch(usize),
     /// Polynomial degree at one of the FRI layers could not be divided evenly by the folding factor.
     DegreeTruncation(usize, usize, usize),
+    /// Number of per-layer proof-of-work nonces in the proof is inconsistent with the number of FRI
+    /// layers and the layer grinding factor; the first value is the expected number of nonces.
+    NumLayerNoncesMismatch(usize, usize),
+    /// Proof-of-work nonce for the specified FRI layer does not satisfy the layer grinding factor.
+    LayerProofOfWorkVerificationFailed(usize),
+    /// FRI proof could not be parsed; the value describes the reason.
+    ProofDeserializationError(String),
+    /// Evaluations of batched functions at queried positions did not match the batch commitment
+    /// made by the prover.
+    BatchQueryDoesNotMatchCommitment,
+    /// Options specify a folding schedule, per-layer proof-of-work, or query deduplication, none
+    /// of which are supported by STIR.
+    #[cfg(feature = "experimental-stir")]
+    UnsupportedStirOptions,
+    /// A denominator of a STIR quotient at the specified layer is zero: either the points by which
+    /// the layer is divided are not distinct, a queried point is one of them, or the combination
+    /// challenge is the inverse of a queried point.
+    #[cfg(feature = "experimental-stir")]
+    ZeroQuotientDenominator(usize),
+    /// Number of layer commitments in the proof is inconsistent with the options and the maximum
+    /// polynomial degree; the first value is the expected number of commitments.
+    NumLayerCommitmentsMismatch(usize, usize),
+    /// Number of out-of-domain evaluations in the proof is inconsistent with the number of
+    /// layers; the first value is the expected number of evaluations.
+    NumOodEvaluationsMismatch(usize, usize),
+    /// Number of FRI layer openings (query values or Merkle proofs) is inconsistent with the
+    /// number of FRI layers; the first value is the expected number of openings.
+    NumLayerOpeningsMismatch(usize, usize),
+    /// Options specify a low-degree test which is not supported by the verification procedure.
+    UnsupportedLowDegreeTest,
 }
 
 impl fmt::Display for VerifierError {
@@ -49,17 +85,17 @@ impl fmt::Display for VerifierError {
             Self::NumPositionEvaluationMismatch(num_positions, num_evaluations) => write!(f,
                 "the number of query positions must be the same as the number of polynomial evaluations, but {num_positions} and {num_evaluations} were provided"
             ),
-            Self::LayerCommitmentMismatch => {
-                write!(f, "FRI queries did not match layer commitment made by the prover")
+            Self::LayerCommitmentMismatch(layer) => {
+                write!(f, "FRI queries did not match commitment to layer {layer} made by the prover")
             }
-            Self::InvalidLayerFolding(layer) => {
-                write!(f, "degree-respecting projection is not consistent at layer {layer}")
+            Self::InvalidLayerFolding(layer, position) => {
+                write!(f, "degree-respecting projection is not consistent at layer {layer} for query at position {position}")
             }
             Self::RemainderCommitmentMismatch => {
                 write!(f, "FRI remainder did not match the commitment")
             }
-            Self::InvalidRemainderFolding => {
-                write!(f, "degree-respecting projection is inconsistent at the last FRI layer")
+            Self::InvalidRemainderFolding(position) => {
+                write!(f, "degree-respecting projection is inconsistent at the last FRI layer for query at position {position}")
             }
             Self::RemainderDegreeNotValid => {
                 write!(f, "FRI remainder expected degree is greater than number of remainder values")
@@ -70,6 +106,38 @@ impl fmt::Display for VerifierError {
             Self::DegreeTruncation(degree, folding, layer) => {
                 write!(f, "degree reduction from {degree} by {folding} at layer {layer} results in degree truncation")
             }
+            Self::NumLayerNoncesMismatch(expected, actual) => {
+                write!(f, "expected {expected} FRI layer proof-of-work nonces, but {actual} were provided")
+            }
+            Self::LayerProofOfWorkVerificationFailed(layer) => {
+                write!(f, "FRI layer proof-of-work verification failed at layer {layer}")
+            }
+            Self::ProofDeserializationError(msg) => {
+                write!(f, "FRI proof deserialization failed: {msg}")
+            }
+            Self::BatchQueryDoesNotMatchCommitment => {
+                write!(f, "batched function queries did not match the batch commitment made by the prover")
+            }
+            #[cfg(feature = "experimental-stir")]
+            Self::UnsupportedStirOptions => {
+                write!(f, "folding schedules, per-layer proof-of-work, and query deduplication are not supported by STIR")
+            }
+            #[cfg(feature = "experimental-stir")]
+            Self::ZeroQuotientDenominator(layer) => {
+                write!(f, "a denominator of the STIR quotient at layer {layer} is zero")
+            }
+            Self::NumLayerCommitmentsMismatch(expected, actual) => {
+                write!(f, "expected {expected} layer commitments, but {actual} were provided")
+            }
+            Self::NumOodEvaluationsMismatch(expected, actual) => {
+                write!(f, "expected {expected} out-of-domain evaluations, but {actual} were provided")
+            }
+            Self::NumLayerOpeningsMismatch(expected, actual) => {
+                write!(f, "expected openings for {expected} FRI layers, but {actual} were provided")
+            }
+            Self::UnsupportedLowDegreeTest => {
+                write!(f, "low-degree test specified by the options is not supported")
+            }
         }
     }
 }
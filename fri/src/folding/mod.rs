@@ -13,7 +13,7 @@ use utils::iterators::*;
 
 use math::{
     fft::{get_inv_twiddles, serial_fft},
-    get_power_series_with_offset, polynom, FieldElement, StarkField,
+    get_power_series_with_offset, FieldElement, StarkField,
 };
 use utils::{collections::Vec, iter_mut, uninit_vector};
 
@@ -40,6 +40,11 @@ use utils::{collections::Vec, iter_mut, uninit_vector};
 /// This function expect the `evaluations` to be already in a transposed form such that all
 /// evaluations needed to compute a single evaluation in the folded domain are next to each other.
 ///
+/// When the evaluations are in an extension field, only the final step of the projection (i.e.,
+/// multiplication by powers of α) is performed in the extension field; all preceding steps are
+/// performed coordinate-wise in the base field. This reduces the cost of folding, which matters
+/// the most for the first (and largest) FRI layers.
+///
 /// The example below shows the equivalence of performing the projection via coefficient form and
 /// via evaluation form for `N` = 2.
 /// ```
@@ -90,7 +95,11 @@ where
     // build offset inverses and twiddles used during polynomial interpolation
     let inv_offsets = get_inv_offsets(values.len(), domain_offset, N);
     let inv_twiddles = get_inv_twiddles::<B>(N);
-    let len_offset = E::inv((N as u64).into());
+
+    // powers of alpha used to evaluate the interpolated polynomials; they are scaled by 1 / N so
+    // that this scaling does not need to be applied to every polynomial
+    let len_offset = B::inv((N as u64).into());
+    let alpha_powers = get_power_series_with_offset(alpha, E::from(len_offset), N);
 
     let mut result = unsafe { uninit_vector(values.len()) };
     iter_mut!(result)
@@ -98,19 +107,22 @@ where
         .zip(inv_offsets)
         .for_each(|((result, values), domain_offset)| {
             // interpolate the values into a polynomial; this is similar to interpolation with
-            // offset implemented in math::fft module
+            // offset implemented in math::fft module. twiddles and domain offsets are in the base
+            // field, and thus, when values are in an extension field, interpolation is performed
+            // coordinate-wise (i.e., using only multiplications by base field elements).
             let mut poly = *values;
             serial_fft(&mut poly, &inv_twiddles);
 
-            let mut offset = len_offset;
-            let domain_offset = E::from(domain_offset);
-            for coeff in poly.iter_mut() {
-                *coeff *= offset;
+            // evaluate the polynomial at alpha; coordinates of the coefficients get mixed only
+            // here, via multiplications by powers of alpha. these multiplications are independent
+            // of each other, unlike multiplications in Horner evaluation.
+            let mut offset = B::ONE;
+            let mut acc = E::ZERO;
+            for (&coeff, &alpha_power) in poly.iter().zip(alpha_powers.iter()) {
+                acc += coeff.mul_base(offset) * alpha_power;
                 offset *= domain_offset;
             }
-
-            // evaluate the polynomial at alpha, and save the result
-            *result = polynom::eval(&poly, alpha)
+            *result = acc;
         });
 
     result
@@ -44,6 +44,93 @@
 //!    are provided to the [verify()](FriVerifier::verify()) function directly. The values at
 //!    remaining layers, the verifier reads from the specified verifier channel.
 //!
+//! # Using FRI in other protocols
+//! The prover and the verifier in this crate do not depend on the rest of the STARK protocol, and
+//! can be used as a low-degree test in other polynomial IOPs. To do so:
+//!
+//! * Evaluations passed to [FriProver::build_layers()] must be evaluations of a function over
+//!   a multiplicative coset of size `(max_degree + 1) * blowup_factor`, where the coset is shifted
+//!   by [FriOptions::domain_offset()]. That is, the evaluation at index `i` must be the value of
+//!   the function at `offset * g^i`, where `g` is the generator of the subgroup of that size.
+//! * The transcript of FRI can be bound to the transcript of the enclosing protocol either by
+//!   implementing [ProverChannel] and [VerifierChannel] traits directly, or by instantiating
+//!   a [DefaultProverChannel] with a public coin which has already absorbed the transcript
+//!   of the enclosing protocol (see [DefaultProverChannel::with_public_coin()]). In the latter
+//!   case, the verifier must pass a public coin in the same state to [FriVerifier::new()].
+//! * Query positions must be drawn after all FRI layer commitments have been absorbed by the
+//!   public coin; the queried evaluations at the first layer (and any checks linking them to the
+//!   enclosing protocol) are the responsibility of the caller.
+//!
+//! To prove that several functions over the same domain are all of bounded degree, use
+//! [BatchedFriProver](batch::BatchedFriProver) and [BatchedFriVerifier](batch::BatchedFriVerifier)
+//! which execute FRI against a random linear combination of the functions while still opening
+//! each individual function at the queried positions.
+//!
+//! When the components of a FRI proof (layer commitments, layer openings, and the remainder) are
+//! committed to or transmitted separately from each other, they can be verified without
+//! re-encoding them as a [FriProof] via [verify_proof_parts()].
+//!
+//! When `experimental-stir` feature is enabled, the `stir` module contains an experimental
+//! implementation of the STIR protocol, which can be
+//! used in place of FRI via the same channel and proof types, and results in smaller proofs for
+//! large domains.
+//!
+//! The example below generates and verifies a FRI proof for a polynomial of degree 63.
+//!
+//! ```
+//! # use crypto::{hashers::Blake3_256, DefaultRandomCoin, RandomCoin};
+//! # use math::{fft, fields::f128::BaseElement, FieldElement};
+//! # use winter_fri::{
+//! #     DefaultProverChannel, DefaultVerifierChannel, FriOptions, FriProver, FriVerifier,
+//! # };
+//! type Hasher = Blake3_256<BaseElement>;
+//! type Coin = DefaultRandomCoin<Hasher>;
+//!
+//! let options = FriOptions::new(8, 4, 7);
+//! let max_degree = 63;
+//! let domain_size = (max_degree + 1) * options.blowup_factor();
+//! let num_queries = 32;
+//!
+//! // evaluate a polynomial of degree 63 over the shifted domain
+//! let poly: Vec<BaseElement> = (0..=max_degree as u128).map(BaseElement::new).collect();
+//! let twiddles = fft::get_twiddles::<BaseElement>(poly.len());
+//! let evaluations = fft::evaluate_poly_with_offset(
+//!     &poly,
+//!     &twiddles,
+//!     options.domain_offset(),
+//!     options.blowup_factor(),
+//! );
+//!
+//! // the transcript of an enclosing protocol is absorbed into the public coin
+//! let transcript = [BaseElement::new(42)];
+//!
+//! // commit to the evaluations and generate a proof
+//! let coin = Coin::new(&transcript);
+//! let mut channel = DefaultProverChannel::<BaseElement, Hasher, Coin>::with_public_coin(
+//!     coin,
+//!     domain_size,
+//!     num_queries,
+//! );
+//! let mut prover = FriProver::new(options.clone());
+//! prover.build_layers(&mut channel, evaluations.clone());
+//! let positions = channel.draw_query_positions(0);
+//! let proof = prover.build_proof(&positions);
+//! let commitments = channel.layer_commitments().to_vec();
+//!
+//! // verify the proof against a public coin in the same state
+//! let mut coin = Coin::new(&transcript);
+//! let mut channel = DefaultVerifierChannel::<BaseElement, Hasher>::new(
+//!     proof,
+//!     commitments,
+//!     domain_size,
+//!     &options,
+//! )
+//! .unwrap();
+//! let verifier = FriVerifier::new(&mut channel, &mut coin, options, max_degree).unwrap();
+//! let queried_evaluations: Vec<BaseElement> = positions.iter().map(|&p| evaluations[p]).collect();
+//! assert!(verifier.verify(&mut channel, &queried_evaluations, &positions).is_ok());
+//! ```
+//!
 //! # Protocol parameters
 //! The current implementation supports executing FRI protocol with dynamically configurable
 //! parameters including:
@@ -52,7 +139,8 @@
 //! * Extension field,
 //! * Domain blowup factor,
 //! * Hash function (used for Merkle tree commitments),
-//! * Folding factor (used for degree reduction for each FRI layer),
+//! * Folding factor (used for degree reduction for each FRI layer), optionally varying from layer
+//!   to layer,
 //! * Maximum size of the last FRI layer.
 //!
 //! # References
@@ -73,15 +161,22 @@ mod prover;
 pub use prover::{DefaultProverChannel, FriProver, ProverChannel};
 
 mod verifier;
-pub use verifier::{DefaultVerifierChannel, FriVerifier, VerifierChannel};
+pub use verifier::{
+    verify_proof_parts, DefaultVerifierChannel, FriProofParts, FriVerifier, VerifierChannel,
+};
 
 mod options;
-pub use options::FriOptions;
+pub use options::{FriOptions, LowDegreeTest};
 
 mod proof;
-pub use proof::FriProof;
+pub use proof::{FriProof, FriProofLayer};
 
 mod errors;
 pub use errors::VerifierError;
 
+pub mod batch;
+
+#[cfg(feature = "experimental-stir")]
+pub mod stir;
+
 pub mod utils;
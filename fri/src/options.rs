@@ -4,6 +4,30 @@
 // LICENSE file in the root directory of this source tree.
 
 use math::StarkField;
+use utils::collections::Vec;
+
+// CONSTANTS
+// ================================================================================================
+
+/// Maximum number of leading zeros which can be required from a per-layer proof-of-work.
+const MAX_LAYER_GRINDING_FACTOR: u32 = 32;
+
+// LOW-DEGREE TEST
+// ================================================================================================
+
+/// Defines the protocol used to prove that a function is a polynomial of bounded degree.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[repr(u8)]
+pub enum LowDegreeTest {
+    /// FRI protocol implemented by [FriProver](crate::FriProver) and
+    /// [FriVerifier](crate::FriVerifier).
+    #[default]
+    Fri = 0,
+    /// STIR protocol implemented by `StirProver` and `StirVerifier` of the `stir` module;
+    /// support for this protocol is experimental, and proofs using it can be generated and
+    /// verified only when `experimental-stir` feature is enabled.
+    Stir = 1,
+}
 
 // FRI OPTIONS
 // ================================================================================================
@@ -14,6 +38,10 @@ pub struct FriOptions {
     folding_factor: usize,
     remainder_max_degree: usize,
     blowup_factor: usize,
+    folding_schedule: Vec<usize>,
+    layer_grinding_factor: u32,
+    query_deduplication: bool,
+    low_degree_test: LowDegreeTest,
 }
 
 impl FriOptions {
@@ -30,17 +58,85 @@ impl FriOptions {
             "blowup factor must be a power of two, but was {blowup_factor}"
         );
         assert!(
-            folding_factor == 2
-                || folding_factor == 4
-                || folding_factor == 8
-                || folding_factor == 16,
+            is_supported_folding_factor(folding_factor),
             "folding factor {folding_factor} is not supported"
         );
         FriOptions {
             folding_factor,
             remainder_max_degree,
             blowup_factor,
+            folding_schedule: Vec::new(),
+            layer_grinding_factor: 0,
+            query_deduplication: false,
+            low_degree_test: LowDegreeTest::Fri,
+        }
+    }
+
+    /// Returns these options with the specified per-layer folding schedule.
+    ///
+    /// The i-th FRI layer is folded by the i-th factor in the `schedule`; once the schedule is
+    /// exhausted, the remaining layers are folded by the `folding_factor`. In either case, folding
+    /// stops as soon as the degree of the remaining polynomial is small enough to be sent as the
+    /// remainder, and thus, trailing factors of the schedule may go unused for small domains.
+    ///
+    /// # Panics
+    /// Panics if any of the factors in the `schedule` is not 2, 4, 8, or 16.
+    pub fn with_folding_schedule(mut self, schedule: &[usize]) -> Self {
+        for &folding_factor in schedule {
+            assert!(
+                is_supported_folding_factor(folding_factor),
+                "folding factor {folding_factor} is not supported"
+            );
         }
+        self.folding_schedule = schedule.to_vec();
+        self
+    }
+
+    /// Returns these options with the specified per-layer grinding factor.
+    ///
+    /// When the grinding factor is greater than zero, after committing to each FRI layer (except
+    /// for the remainder), the prover must find a nonce such that hashing it together with the
+    /// current state of the public coin results in a value with at least `grinding_factor`
+    /// leading zeros. The nonce is then absorbed by the public coin before the folding
+    /// challenge for the layer is drawn. This makes it more expensive for a malicious prover to
+    /// search for favorable folding challenges.
+    ///
+    /// # Panics
+    /// Panics if `grinding_factor` is greater than 32.
+    pub fn with_layer_grinding_factor(mut self, grinding_factor: u32) -> Self {
+        assert!(
+            grinding_factor <= MAX_LAYER_GRINDING_FACTOR,
+            "layer grinding factor cannot be greater than {MAX_LAYER_GRINDING_FACTOR}, but was {grinding_factor}"
+        );
+        self.layer_grinding_factor = grinding_factor;
+        self
+    }
+
+    /// Returns these options with deduplication of queried values across FRI layers enabled.
+    ///
+    /// At every FRI layer, the verifier already knows the values at the positions it queries:
+    /// at the first layer, these values are provided to the verifier directly, and at all
+    /// subsequent layers, they are computed by folding the values of the previous layer. When
+    /// deduplication is enabled, these values are omitted from the proof, and the verifier
+    /// restores them before checking the queried values against the layer commitment. This
+    /// reduces the size of a proof by one field element per distinct query position at each
+    /// layer.
+    pub fn with_query_deduplication(mut self) -> Self {
+        self.query_deduplication = true;
+        self
+    }
+
+    /// Returns these options with the specified low-degree test.
+    ///
+    /// By default, FRI protocol is used. When `LowDegreeTest::Stir` is specified (available under
+    /// `experimental-stir` feature), the options describe the STIR protocol instead: the folding
+    /// factor, the blowup factor and the maximum remainder degree have the same meaning as for
+    /// FRI, but every committed layer is evaluated over a domain only half the size of the
+    /// previous one (see the `stir` module). Folding
+    /// schedules, per-layer proof-of-work and query deduplication are not supported by STIR.
+    pub fn with_low_degree_test(mut self, low_degree_test: LowDegreeTest) -> Self {
+        self.low_degree_test = low_degree_test;
+        self
     }
 
     /// Returns the offset by which the evaluation domain is shifted.
@@ -61,14 +157,41 @@ impl FriOptions {
         self.folding_factor
     }
 
+    /// Returns the per-layer folding schedule; the schedule is empty if all layers are folded by
+    /// the same `folding_factor`.
+    pub fn folding_schedule(&self) -> &[usize] {
+        &self.folding_schedule
+    }
+
     /// Returns maximum allowed remainder polynomial degree.
     ///
     /// In combination with `folding_factor` this property defines how many FRI layers are needed
-    /// for an evaluation domain of a given size.
+    /// for an evaluation domain of a given size. Folding stops as soon as the remaining
+    /// evaluations describe a polynomial of at most this degree; the remainder polynomial is then
+    /// included in a proof in coefficient form, and thus, contributes at most
+    /// `remainder_max_degree + 1` field elements to the proof.
     pub fn remainder_max_degree(&self) -> usize {
         self.remainder_max_degree
     }
 
+    /// Returns the number of leading zeros required for the proof-of-work performed after
+    /// committing to each FRI layer; zero means that no per-layer proof-of-work is performed.
+    pub fn layer_grinding_factor(&self) -> u32 {
+        self.layer_grinding_factor
+    }
+
+    /// Returns `true` if values known to the verifier are omitted from FRI proofs (see
+    /// [with_query_deduplication()](FriOptions::with_query_deduplication)).
+    pub fn query_deduplication(&self) -> bool {
+        self.query_deduplication
+    }
+
+    /// Returns the low-degree test described by these options (see
+    /// [with_low_degree_test()](FriOptions::with_low_degree_test)).
+    pub fn low_degree_test(&self) -> LowDegreeTest {
+        self.low_degree_test
+    }
+
     /// Returns a blowup factor of the evaluation domain.
     ///
     /// Specifically, if the polynomial for which the FRI protocol is executed is of degree `d`
@@ -78,17 +201,61 @@ impl FriOptions {
         self.blowup_factor
     }
 
-    /// Computes and return the number of FRI layers required for a domain of the specified size.
+    /// Returns the factors by which each FRI layer is folded for a domain of the specified size.
     ///
-    /// The number of layers for a given domain size is defined by the `folding_factor` and
-    /// `remainder_max_degree` and `blowup_factor` settings.
-    pub fn num_fri_layers(&self, mut domain_size: usize) -> usize {
-        let mut result = 0;
+    /// The number of returned factors is equal to the number of FRI layers; it is defined by the
+    /// `folding_factor`, `folding_schedule`, `remainder_max_degree` and `blowup_factor` settings.
+    pub fn layer_folding_factors(&self, mut domain_size: usize) -> Vec<usize> {
+        let mut result = Vec::new();
         let max_remainder_size = (self.remainder_max_degree + 1) * self.blowup_factor;
         while domain_size > max_remainder_size {
-            domain_size /= self.folding_factor;
-            result += 1;
+            let folding_factor =
+                self.folding_schedule.get(result.len()).copied().unwrap_or(self.folding_factor);
+            domain_size /= folding_factor;
+            result.push(folding_factor);
         }
         result
     }
+
+    /// Returns the sizes of domains over which all committed layers (except for the remainder)
+    /// are evaluated for a domain of the specified size.
+    ///
+    /// For FRI, the domain of each layer is smaller than the domain of the previous layer by the
+    /// folding factor of the previous layer; for STIR, each domain is half the size of the
+    /// previous one.
+    pub fn layer_domain_sizes(&self, domain_size: usize) -> Vec<usize> {
+        let folding_factors = self.layer_folding_factors(domain_size);
+        let mut layer_domain_size = domain_size;
+        let mut result = Vec::with_capacity(folding_factors.len());
+        for folding_factor in folding_factors {
+            result.push(layer_domain_size);
+            layer_domain_size /= match self.low_degree_test {
+                LowDegreeTest::Fri => folding_factor,
+                LowDegreeTest::Stir => 2,
+            };
+        }
+        result
+    }
+
+    /// Computes and return the number of FRI layers required for a domain of the specified size.
+    ///
+    /// The number of layers for a given domain size is defined by the `folding_factor`,
+    /// `folding_schedule`, `remainder_max_degree` and `blowup_factor` settings.
+    pub fn num_fri_layers(&self, domain_size: usize) -> usize {
+        self.layer_folding_factors(domain_size).len()
+    }
+
+    /// Returns the size of the domain of the remainder polynomial for a domain of the specified
+    /// size (i.e., the size of the domain after all FRI layers have been folded).
+    pub fn remainder_domain_size(&self, domain_size: usize) -> usize {
+        let num_folds: usize = self.layer_folding_factors(domain_size).iter().product();
+        domain_size / num_folds
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+fn is_supported_folding_factor(folding_factor: usize) -> bool {
+    matches!(folding_factor, 2 | 4 | 8 | 16)
 }
@@ -3,17 +3,18 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
+use crate::FriOptions;
 use crypto::{BatchMerkleProof, ElementHasher, Hasher};
 use math::FieldElement;
 use utils::{
-    collections::Vec, string::ToString, ByteReader, ByteWriter, Deserializable,
-    DeserializationError, Serializable, SliceReader,
+    collections::Vec, flatten_vector_elements, string::ToString, ByteReader, ByteWriter,
+    Deserializable, DeserializationError, Serializable, SliceReader,
 };
 
 // FRI PROOF
 // ================================================================================================
 
-/// A proof generated by a FRI prover.
+/// A proof generated by a FRI prover (or by a STIR prover).
 ///
 /// A FRI proof contains information proving that a function *f* is a polynomial of some bounded
 /// degree *d*. FRI proofs cannot be instantiated directly - they must be generated by a instance
@@ -23,7 +24,17 @@ use utils::{
 /// A proof consists of zero or more layers and a remainder polynomial. Each layer contains a set of
 /// polynomial evaluations at positions queried by the verifier as well as Merkle authentication
 /// paths for these evaluations (the Merkle paths are compressed into a batch Merkle proof). The
-/// remainder polynomial is given by its list of coefficients i.e. field elements.
+/// remainder polynomial is given by its list of coefficients i.e. field elements. If FRI layers
+/// were built with per-layer proof-of-work, the proof also contains a nonce for each layer.
+///
+/// Proofs generated by a STIR prover have the same structure, but additionally contain
+/// evaluations of layer polynomials at out-of-domain points (one per layer except the first one).
+///
+/// When queried values are deduplicated across layers (see
+/// [FriOptions::with_query_deduplication()](crate::FriOptions::with_query_deduplication)), the
+/// values known to the verifier are omitted from each layer. For such proofs, the values returned
+/// from [parse_layers()](FriProof::parse_layers()) do not include the omitted values, and leaves
+/// of the returned Merkle proofs must be recomputed by the verifier after restoring them.
 ///
 /// All values in a proof are stored as vectors of bytes. Thus, the values must be parsed before
 /// they can be returned to the user. To do this, [parse_layers()](FriProof::parse_layers())
@@ -32,13 +43,16 @@ use utils::{
 pub struct FriProof {
     layers: Vec<FriProofLayer>,
     remainder: Vec<u8>,
+    ood_evaluations: Vec<u8>,
+    layer_nonces: Vec<u64>,
     num_partitions: u8, // stored as power of 2
 }
 
 impl FriProof {
     // CONSTRUCTOR
     // --------------------------------------------------------------------------------------------
-    /// Creates a new FRI proof from the provided layers and remainder polynomial.
+    /// Creates a new FRI proof from the provided layers, remainder polynomial, and proof-of-work
+    /// nonces for each layer (empty if per-layer proof-of-work was not performed).
     ///
     /// # Panics
     /// Panics if:
@@ -47,6 +61,7 @@ impl FriProof {
     pub(crate) fn new<E: FieldElement>(
         layers: Vec<FriProofLayer>,
         remainder: Vec<E>,
+        layer_nonces: Vec<u64>,
         num_partitions: usize,
     ) -> Self {
         assert!(!remainder.is_empty(), "number of remainder elements must be greater than zero");
@@ -63,15 +78,26 @@ impl FriProof {
         FriProof {
             layers,
             remainder: remainder.to_bytes(),
+            ood_evaluations: Vec::new(),
+            layer_nonces,
             num_partitions: num_partitions.trailing_zeros() as u8,
         }
     }
 
+    /// Returns this proof with the specified out-of-domain evaluations of layer polynomials.
+    #[cfg(feature = "experimental-stir")]
+    pub(crate) fn with_ood_evaluations<E: FieldElement>(mut self, evaluations: &[E]) -> Self {
+        self.ood_evaluations = evaluations.to_bytes();
+        self
+    }
+
     /// Creates a dummy `FriProof` for use in tests.
     pub fn new_dummy() -> Self {
         Self {
             layers: Vec::new(),
             remainder: Vec::new(),
+            ood_evaluations: Vec::new(),
+            layer_nonces: Vec::new(),
             num_partitions: 0,
         }
     }
@@ -84,6 +110,21 @@ impl FriProof {
         self.layers.len()
     }
 
+    /// Returns the size (in bytes) of each layer in this proof.
+    pub fn layer_sizes(&self) -> Vec<usize> {
+        self.layers.iter().map(|layer| layer.size()).collect()
+    }
+
+    /// Returns the number of bytes taken up by the remainder in this proof.
+    pub fn remainder_size(&self) -> usize {
+        self.remainder.len()
+    }
+
+    /// Returns `true` if values known to the verifier are omitted from the layers of this proof.
+    pub fn is_deduplicated(&self) -> bool {
+        self.layers.iter().any(|layer| layer.num_queries.is_some())
+    }
+
     /// Returns the number of remainder elements in this proof.
     ///
     /// The number of elements is computed by dividing the number of remainder bytes by the size
@@ -92,6 +133,20 @@ impl FriProof {
         self.remainder.len() / E::ELEMENT_BYTES
     }
 
+    /// Returns the number of out-of-domain evaluations in this proof.
+    ///
+    /// The number of evaluations is computed by dividing the number of evaluation bytes by the
+    /// size of the field element specified by `E` type parameter; it is zero for FRI proofs.
+    pub fn num_ood_evaluations<E: FieldElement>(&self) -> usize {
+        self.ood_evaluations.len() / E::ELEMENT_BYTES
+    }
+
+    /// Returns proof-of-work nonces for each FRI layer; the list is empty if per-layer
+    /// proof-of-work was not performed during proof generation.
+    pub fn layer_nonces(&self) -> &[u64] {
+        &self.layer_nonces
+    }
+
     /// Returns the number of partitions used during proof generation.
     pub fn num_partitions(&self) -> usize {
         2usize.pow(self.num_partitions as u32)
@@ -99,10 +154,11 @@ impl FriProof {
 
     /// Returns the size of this proof in bytes.
     pub fn size(&self) -> usize {
-        // +1 for number of layers, +1 for remainder length, +1 for number of partitions
-        self.layers
-            .iter()
-            .fold(self.remainder.len() + 3, |acc, layer| acc + layer.size())
+        // +1 for number of layers, +1 for deduplication flag, +2 for remainder length, +2 for
+        // length of OOD evaluations, +1 for number of layer nonces, +1 for number of partitions
+        let nonces_size = self.layer_nonces.len() * 8;
+        let base_size = self.remainder.len() + self.ood_evaluations.len() + nonces_size + 8;
+        self.layers.iter().fold(base_size, |acc, layer| acc + layer.size())
     }
 
     // PARSING
@@ -111,6 +167,10 @@ impl FriProof {
     /// Decomposes this proof into vectors of query values for each layer and corresponding Merkle
     /// authentication paths for each query (grouped into batch Merkle proofs).
     ///
+    /// All layers are assumed to be folded by the same `folding_factor`; for proofs generated
+    /// with a folding schedule, use
+    /// [parse_layers_with_schedule()](FriProof::parse_layers_with_schedule).
+    ///
     /// # Panics
     /// Panics if:
     /// * `domain_size` is not a power of two.
@@ -123,24 +183,137 @@ impl FriProof {
     #[allow(clippy::type_complexity)]
     pub fn parse_layers<H, E>(
         self,
-        mut domain_size: usize,
+        domain_size: usize,
         folding_factor: usize,
     ) -> Result<(Vec<Vec<E>>, Vec<BatchMerkleProof<H>>), DeserializationError>
+    where
+        E: FieldElement,
+        H: ElementHasher<BaseField = E::BaseField>,
+    {
+        let folding_factors = vec![folding_factor; self.layers.len()];
+        self.parse_layers_with_schedule(domain_size, &folding_factors)
+    }
+
+    /// Decomposes this proof into vectors of query values for each layer and corresponding Merkle
+    /// authentication paths for each query (grouped into batch Merkle proofs), assuming that the
+    /// i-th layer was folded by the i-th factor in `folding_factors`.
+    ///
+    /// Folding factors of all layers for a given domain size can be obtained via
+    /// [FriOptions::layer_folding_factors()](crate::FriOptions::layer_folding_factors).
+    ///
+    /// # Panics
+    /// Panics if:
+    /// * `domain_size` is not a power of two.
+    /// * Any of the `folding_factors` is smaller than two or is not a power of two.
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// * The number of layers in this proof is not equal to the number of `folding_factors`.
+    /// * This proof is not consistent with the specified `domain_size` and `folding_factors`.
+    /// * Any of the layers could not be parsed successfully.
+    #[allow(clippy::type_complexity)]
+    pub fn parse_layers_with_schedule<H, E>(
+        self,
+        mut domain_size: usize,
+        folding_factors: &[usize],
+    ) -> Result<(Vec<Vec<E>>, Vec<BatchMerkleProof<H>>), DeserializationError>
     where
         E: FieldElement,
         H: ElementHasher<BaseField = E::BaseField>,
     {
         assert!(domain_size.is_power_of_two(), "domain size must be a power of two");
-        assert!(folding_factor.is_power_of_two(), "folding factor must be a power of two");
-        assert!(folding_factor > 1, "folding factor must be greater than 1");
+        let mut layer_domain_sizes = Vec::with_capacity(folding_factors.len());
+        for &folding_factor in folding_factors {
+            assert!(folding_factor.is_power_of_two(), "folding factor must be a power of two");
+            assert!(folding_factor > 1, "folding factor must be greater than 1");
+            layer_domain_sizes.push(domain_size);
+            domain_size /= folding_factor;
+        }
+
+        self.parse_layers_with_domain_sizes(&layer_domain_sizes, folding_factors)
+    }
+
+    /// Decomposes this proof into vectors of query values for each layer and corresponding Merkle
+    /// authentication paths for each query (grouped into batch Merkle proofs), assuming that the
+    /// proof was generated with the specified `options` for a domain of the specified size.
+    ///
+    /// Unlike [parse_layers_with_schedule()](FriProof::parse_layers_with_schedule), this method
+    /// can be used to parse proofs generated by a STIR prover.
+    ///
+    /// # Panics
+    /// Panics if `domain_size` is not a power of two.
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// * The number of layers in this proof is inconsistent with the `options`.
+    /// * This proof is not consistent with the specified `domain_size` and `options`.
+    /// * Any of the layers could not be parsed successfully.
+    #[allow(clippy::type_complexity)]
+    pub fn parse_layers_with_options<H, E>(
+        self,
+        domain_size: usize,
+        options: &FriOptions,
+    ) -> Result<(Vec<Vec<E>>, Vec<BatchMerkleProof<H>>), DeserializationError>
+    where
+        E: FieldElement,
+        H: ElementHasher<BaseField = E::BaseField>,
+    {
+        assert!(domain_size.is_power_of_two(), "domain size must be a power of two");
+        let folding_factors = options.layer_folding_factors(domain_size);
+        let layer_domain_sizes = options.layer_domain_sizes(domain_size);
+        self.parse_layers_with_domain_sizes(&layer_domain_sizes, &folding_factors)
+    }
+
+    /// Returns out-of-domain evaluations parsed from this proof.
+    ///
+    /// # Errors
+    /// Returns an error if the evaluations could not be parsed correctly, or if not all bytes
+    /// have been consumed while parsing the evaluations.
+    pub fn parse_ood_evaluations<E: FieldElement>(&self) -> Result<Vec<E>, DeserializationError> {
+        let num_elements = self.num_ood_evaluations::<E>();
+        let mut reader = SliceReader::new(&self.ood_evaluations);
+        let evaluations = E::read_batch_from(&mut reader, num_elements).map_err(|err| {
+            DeserializationError::InvalidValue(format!(
+                "failed to parse out-of-domain evaluations: {err}"
+            ))
+        })?;
+        if reader.has_more_bytes() {
+            return Err(DeserializationError::UnconsumedBytes);
+        }
+        Ok(evaluations)
+    }
+
+    /// Decomposes this proof into query values and Merkle proofs for each layer, assuming that
+    /// the i-th layer was evaluated over a domain of the i-th size in `layer_domain_sizes` and
+    /// was folded by the i-th factor in `folding_factors`.
+    #[allow(clippy::type_complexity)]
+    fn parse_layers_with_domain_sizes<H, E>(
+        self,
+        layer_domain_sizes: &[usize],
+        folding_factors: &[usize],
+    ) -> Result<(Vec<Vec<E>>, Vec<BatchMerkleProof<H>>), DeserializationError>
+    where
+        E: FieldElement,
+        H: ElementHasher<BaseField = E::BaseField>,
+    {
+        if self.layers.len() != folding_factors.len() {
+            return Err(DeserializationError::InvalidValue(format!(
+                "expected {} FRI layers, but proof contains {}",
+                folding_factors.len(),
+                self.layers.len()
+            )));
+        }
 
         let mut layer_proofs = Vec::new();
         let mut layer_queries = Vec::new();
 
-        // parse all layers
-        for (i, layer) in self.layers.into_iter().enumerate() {
-            domain_size /= folding_factor;
-            let (qv, mp) = layer.parse(domain_size, folding_factor).map_err(|err| {
+        // parse all layers; each leaf of a layer tree commits to as many values as the folding
+        // factor of the layer
+        for (i, ((layer, &folding_factor), &domain_size)) in
+            self.layers.into_iter().zip(folding_factors).zip(layer_domain_sizes).enumerate()
+        {
+            let num_leaves = domain_size / folding_factor;
+            let (qv, mp) = layer.parse(num_leaves, folding_factor).map_err(|err| {
                 DeserializationError::InvalidValue(format!("failed to parse FRI layer {i}: {err}"))
             })?;
             layer_proofs.push(mp);
@@ -176,59 +349,216 @@ impl FriProof {
     }
 }
 
-// SERIALIZATION / DESERIALIZATION
+// STREAMING
 // ------------------------------------------------------------------------------------------------
 
-impl Serializable for FriProof {
-    /// Serializes `self` and writes the resulting bytes into the `target` writer.
-    fn write_into<W: ByteWriter>(&self, target: &mut W) {
-        // write layers
-        target.write_u8(self.layers.len() as u8);
-        for layer in self.layers.iter() {
-            layer.write_into(target);
-        }
-
+impl FriProof {
+    /// Serializes all parts of this proof except for its layers into the `target`.
+    ///
+    /// Together with [write_layer_into()](FriProof::write_layer_into), this can be used to write
+    /// the layers of the proof separately from the rest of the proof (e.g., after other data
+    /// which the verifier needs before it can check the layers). The parts can be read back via
+    /// [read_header_from()](FriProof::read_header_from) and
+    /// [FriProofLayer::read_with_deduplication()].
+    pub fn write_header_into<W: ByteWriter>(&self, target: &mut W) {
         // write remainder
         target.write_u16(self.remainder.len() as u16);
         target.write_bytes(&self.remainder);
 
+        // write out-of-domain evaluations
+        target.write_u16(self.ood_evaluations.len() as u16);
+        target.write_bytes(&self.ood_evaluations);
+
+        // write layer nonces
+        target.write_u8(self.layer_nonces.len() as u8);
+        for &nonce in self.layer_nonces.iter() {
+            target.write_u64(nonce);
+        }
+
         // write number of partitions
         target.write_u8(self.num_partitions);
     }
+
+    /// Reads all parts of a proof except for its layers from the specified `source`, and returns
+    /// a proof without layers.
+    ///
+    /// # Errors
+    /// Returns an error if the parts of a valid proof could not be read from the source.
+    pub fn read_header_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        // read remainder
+        let num_remainder_bytes = source.read_u16()? as usize;
+        let remainder = source.read_vec(num_remainder_bytes)?;
+
+        // read out-of-domain evaluations
+        let num_ood_bytes = source.read_u16()? as usize;
+        let ood_evaluations = source.read_vec(num_ood_bytes)?;
+
+        // read layer nonces
+        let num_nonces = source.read_u8()? as usize;
+        let mut layer_nonces = Vec::with_capacity(num_nonces);
+        for _ in 0..num_nonces {
+            layer_nonces.push(source.read_u64()?);
+        }
+
+        Ok(FriProof {
+            layers: Vec::new(),
+            remainder,
+            ood_evaluations,
+            layer_nonces,
+            num_partitions: read_num_partitions(source)?,
+        })
+    }
+
+    /// Serializes the layer at the specified `index` into the `target`; for deduplicated layers,
+    /// the number of queries in the layer is written before the layer.
+    ///
+    /// # Panics
+    /// Panics if `index` is not smaller than the number of layers in this proof.
+    pub fn write_layer_into<W: ByteWriter>(&self, index: usize, target: &mut W) {
+        let layer = &self.layers[index];
+        if let Some(num_queries) = layer.num_queries {
+            target.write_u8(num_queries as u8);
+        }
+        layer.write_into(target);
+    }
 }
 
-impl Deserializable for FriProof {
-    /// Reads a FRI proof from the specified `source` and returns the result.
+// LEGACY FORMAT
+// ------------------------------------------------------------------------------------------------
+
+impl FriProof {
+    /// Serializes this proof into the `target` in the format used before per-layer
+    /// proof-of-work, query deduplication, and STIR were introduced (i.e., by winter-fri v0.7).
+    ///
+    /// In this format, layers are not preceded by a deduplication flag, and the remainder is
+    /// followed directly by the number of partitions.
+    ///
+    /// # Panics
+    /// Panics if this proof contains layer nonces, out-of-domain evaluations, or deduplicated
+    /// layers, none of which can be represented in this format.
+    pub fn write_legacy_into<W: ByteWriter>(&self, target: &mut W) {
+        assert!(
+            !self.is_deduplicated(),
+            "deduplicated layers cannot be written in legacy format"
+        );
+        target.write_u8(self.layers.len() as u8);
+        for layer in self.layers.iter() {
+            layer.write_into(target);
+        }
+        self.write_legacy_header_into(target);
+    }
+
+    /// Reads a FRI proof serialized via [write_legacy_into()](FriProof::write_legacy_into) from
+    /// the specified `source`.
     ///
     /// # Errors
     /// Returns an error if a valid proof could not be read from the source.
-    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
-        // read layers
+    pub fn read_legacy_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
         let num_layers = source.read_u8()? as usize;
         let layers = FriProofLayer::read_batch_from(source, num_layers)?;
+        let header = Self::read_legacy_header_from(source)?;
+        Ok(FriProof { layers, ..header })
+    }
 
-        // read remainder
+    /// Serializes all parts of this proof except for its layers into the `target` in the legacy
+    /// format (see [write_legacy_into()](FriProof::write_legacy_into)).
+    ///
+    /// # Panics
+    /// Panics if this proof contains layer nonces or out-of-domain evaluations.
+    pub fn write_legacy_header_into<W: ByteWriter>(&self, target: &mut W) {
+        assert!(
+            self.layer_nonces.is_empty() && self.ood_evaluations.is_empty(),
+            "layer nonces and out-of-domain evaluations cannot be written in legacy format"
+        );
+        target.write_u16(self.remainder.len() as u16);
+        target.write_bytes(&self.remainder);
+        target.write_u8(self.num_partitions);
+    }
+
+    /// Reads all parts of a proof except for its layers serialized via
+    /// [write_legacy_header_into()](FriProof::write_legacy_header_into) from the specified
+    /// `source`, and returns a proof without layers.
+    ///
+    /// # Errors
+    /// Returns an error if the parts of a valid proof could not be read from the source.
+    pub fn read_legacy_header_from<R: ByteReader>(
+        source: &mut R,
+    ) -> Result<Self, DeserializationError> {
         let num_remainder_bytes = source.read_u16()? as usize;
         let remainder = source.read_vec(num_remainder_bytes)?;
 
-        // read number of partitions
-        let num_partitions = source.read_u8()?;
-
         Ok(FriProof {
-            layers,
+            layers: Vec::new(),
             remainder,
-            num_partitions,
+            ood_evaluations: Vec::new(),
+            layer_nonces: Vec::new(),
+            num_partitions: read_num_partitions(source)?,
         })
     }
 }
 
+// SERIALIZATION / DESERIALIZATION
+// ------------------------------------------------------------------------------------------------
+
+impl Serializable for FriProof {
+    /// Serializes `self` and writes the resulting bytes into the `target` writer.
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        // write layers; for deduplicated layers, the number of queries cannot be inferred from
+        // the number of values, and thus, it is written explicitly
+        let is_deduplicated = self.is_deduplicated();
+        target.write_u8(self.layers.len() as u8);
+        target.write_bool(is_deduplicated);
+        for layer in self.layers.iter() {
+            if is_deduplicated {
+                target.write_u8(layer.num_queries.unwrap_or_default() as u8);
+            }
+            layer.write_into(target);
+        }
+
+        // write remainder, out-of-domain evaluations, layer nonces, and number of partitions
+        self.write_header_into(target);
+    }
+}
+
+impl Deserializable for FriProof {
+    /// Reads a FRI proof from the specified `source` and returns the result.
+    ///
+    /// # Errors
+    /// Returns an error if a valid proof could not be read from the source.
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        // read layers
+        let num_layers = source.read_u8()? as usize;
+        let is_deduplicated = source.read_bool()?;
+        let mut layers = Vec::with_capacity(num_layers);
+        for _ in 0..num_layers {
+            let num_queries = if is_deduplicated {
+                Some(source.read_u8()? as usize)
+            } else {
+                None
+            };
+            layers.push(FriProofLayer::read_with_num_queries(source, num_queries)?);
+        }
+
+        // read remainder, out-of-domain evaluations, layer nonces, and number of partitions
+        let header = Self::read_header_from(source)?;
+        Ok(FriProof { layers, ..header })
+    }
+}
+
 // FRI PROOF LAYER
 // ================================================================================================
 
+/// A single layer of a [FriProof].
+///
+/// A layer contains polynomial evaluations at the queried positions of the layer together with
+/// Merkle authentication paths for these evaluations. Layers are usually read as a part of a
+/// [FriProof]; a layer written separately from the proof via [FriProof::write_layer_into()] can be
+/// read via [read_with_deduplication()](FriProofLayer::read_with_deduplication).
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct FriProofLayer {
     values: Vec<u8>,
     paths: Vec<u8>,
+    num_queries: Option<usize>, // set only for deduplicated layers
 }
 
 impl FriProofLayer {
@@ -253,6 +583,38 @@ impl FriProofLayer {
         FriProofLayer {
             values: query_values.to_bytes(),
             paths: merkle_proof.serialize_nodes(),
+            num_queries: None,
+        }
+    }
+
+    /// Creates a new proof layer from the specified query values and the corresponding Merkle
+    /// paths, omitting the values at the specified `known_value_indexes`.
+    ///
+    /// Indexes refer to the query values flattened into a single vector; these are the values
+    /// which the verifier can compute on its own.
+    ///
+    /// # Panics
+    /// Panics if `query_values` is an empty slice or contains more than 255 queries.
+    pub(crate) fn new_deduplicated<H: Hasher, E: FieldElement, const N: usize>(
+        query_values: Vec<[E; N]>,
+        known_value_indexes: &[usize],
+        merkle_proof: BatchMerkleProof<H>,
+    ) -> Self {
+        assert!(!query_values.is_empty(), "query values cannot be empty");
+        assert!(query_values.len() <= u8::MAX as usize, "too many queries");
+
+        let num_queries = query_values.len();
+        let values = flatten_vector_elements(query_values)
+            .into_iter()
+            .enumerate()
+            .filter(|(i, _)| !known_value_indexes.contains(i))
+            .map(|(_, value)| value)
+            .collect::<Vec<E>>();
+
+        FriProofLayer {
+            values: values.to_bytes(),
+            paths: merkle_proof.serialize_nodes(),
+            num_queries: Some(num_queries),
         }
     }
 
@@ -261,8 +623,10 @@ impl FriProofLayer {
 
     /// Returns the size of this proof layer in bytes.
     pub fn size(&self) -> usize {
-        // +4 for length of values, +4 for length of paths
-        self.values.len() + 4 + self.paths.len() + 4
+        // +4 for length of values, +4 for length of paths, +1 for number of queries in
+        // deduplicated layers
+        let num_queries_size = self.num_queries.map_or(0, |_| 1);
+        self.values.len() + 4 + self.paths.len() + 4 + num_queries_size
     }
 
     // PARSING
@@ -270,6 +634,9 @@ impl FriProofLayer {
     /// Decomposes this layer into a combination of query values and corresponding Merkle
     /// authentication paths (grouped together into a single batch Merkle proof).
     ///
+    /// If this layer is deduplicated, the values known to the verifier are not included in the
+    /// returned query values, and leaves of the returned Merkle proof are set to default digests.
+    ///
     /// # Errors
     /// Returns an error if:
     /// * This layer does not contain at least one query.
@@ -284,6 +651,10 @@ impl FriProofLayer {
         E: FieldElement,
         H: ElementHasher<BaseField = E::BaseField>,
     {
+        if let Some(num_queries) = self.num_queries {
+            return self.parse_deduplicated(domain_size, folding_factor, num_queries);
+        }
+
         // make sure the number of value bytes can be parsed into a whole number of queries
         let num_query_bytes = E::ELEMENT_BYTES * folding_factor;
         if self.values.len() % num_query_bytes != 0 {
@@ -324,6 +695,57 @@ impl FriProofLayer {
 
         Ok((query_values, merkle_proof))
     }
+
+    /// Decomposes a deduplicated layer into a combination of query values (not including the
+    /// values known to the verifier) and the corresponding Merkle authentication paths with
+    /// default leaves.
+    fn parse_deduplicated<H, E>(
+        self,
+        domain_size: usize,
+        folding_factor: usize,
+        num_queries: usize,
+    ) -> Result<(Vec<E>, BatchMerkleProof<H>), DeserializationError>
+    where
+        E: FieldElement,
+        H: ElementHasher<BaseField = E::BaseField>,
+    {
+        if num_queries == 0 {
+            return Err(DeserializationError::InvalidValue(
+                "a FRI layer must contain at least one query".to_string(),
+            ));
+        }
+        if self.values.len() % E::ELEMENT_BYTES != 0 {
+            return Err(DeserializationError::InvalidValue(format!(
+                "number of value bytes ({}) does not divide into whole number of values",
+                self.values.len(),
+            )));
+        }
+        let num_values = self.values.len() / E::ELEMENT_BYTES;
+        if num_values >= num_queries * folding_factor {
+            return Err(DeserializationError::InvalidValue(format!(
+                "a deduplicated FRI layer with {num_queries} queries must contain fewer than {} values, but contained {num_values}",
+                num_queries * folding_factor
+            )));
+        }
+
+        let mut reader = SliceReader::new(&self.values);
+        let query_values = E::read_batch_from(&mut reader, num_values)?;
+        if reader.has_more_bytes() {
+            return Err(DeserializationError::UnconsumedBytes);
+        }
+
+        // leaves of the batch Merkle proof can be computed only after the omitted values are
+        // restored by the verifier
+        let leaves = vec![H::Digest::default(); num_queries];
+        let mut reader = SliceReader::new(&self.paths);
+        let tree_depth = domain_size.ilog2() as u8;
+        let merkle_proof = BatchMerkleProof::deserialize(&mut reader, leaves, tree_depth)?;
+        if reader.has_more_bytes() {
+            return Err(DeserializationError::UnconsumedBytes);
+        }
+
+        Ok((query_values, merkle_proof))
+    }
 }
 
 // SERIALIZATION / DESERIALIZATION
@@ -348,9 +770,41 @@ impl Deserializable for FriProofLayer {
     /// # Errors
     /// Returns an error if a valid layer could not be read from the specified source.
     fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        Self::read_with_num_queries(source, None)
+    }
+}
+
+impl FriProofLayer {
+    /// Reads a single proof layer written via [FriProof::write_layer_into()] from the `source`;
+    /// `is_deduplicated` specifies whether values known to the verifier were omitted from the
+    /// layer (see [FriOptions::query_deduplication()]).
+    ///
+    /// # Errors
+    /// Returns an error if a valid layer could not be read from the specified source.
+    pub fn read_with_deduplication<R: ByteReader>(
+        source: &mut R,
+        is_deduplicated: bool,
+    ) -> Result<Self, DeserializationError> {
+        let num_queries = if is_deduplicated {
+            Some(source.read_u8()? as usize)
+        } else {
+            None
+        };
+        Self::read_with_num_queries(source, num_queries)
+    }
+
+    /// Reads a single proof layer from the `source`; `num_queries` must be provided for (and
+    /// only for) deduplicated layers.
+    ///
+    /// Since all values of a deduplicated layer may be known to the verifier, such layers are
+    /// allowed to contain no values.
+    fn read_with_num_queries<R: ByteReader>(
+        source: &mut R,
+        num_queries: Option<usize>,
+    ) -> Result<Self, DeserializationError> {
         // read values
         let num_value_bytes = source.read_u32()?;
-        if num_value_bytes == 0 {
+        if num_value_bytes == 0 && num_queries.is_none() {
             return Err(DeserializationError::InvalidValue(
                 "a FRI proof layer must contain at least one queried evaluation".to_string(),
             ));
@@ -361,6 +815,26 @@ impl Deserializable for FriProofLayer {
         let num_paths_bytes = source.read_u32()?;
         let paths = source.read_vec(num_paths_bytes as usize)?;
 
-        Ok(FriProofLayer { values, paths })
+        Ok(FriProofLayer {
+            values,
+            paths,
+            num_queries,
+        })
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Reads the number of partitions (which is stored as a power of two) from the `source`.
+fn read_num_partitions<R: ByteReader>(source: &mut R) -> Result<u8, DeserializationError> {
+    let num_partitions = source.read_u8()?;
+    if num_partitions as u32 >= usize::BITS {
+        return Err(DeserializationError::InvalidValue(format!(
+            "number of partitions cannot be greater than or equal to 2^{}, but was 2^{}",
+            usize::BITS,
+            num_partitions
+        )));
     }
+    Ok(num_partitions)
 }
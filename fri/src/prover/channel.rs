@@ -45,6 +45,30 @@ pub trait ProverChannel<E: FieldElement> {
     /// prover, in the non-interactive version, the α is pseudo-randomly generated based on the
     /// values the prover previously wrote into the channel.
     fn draw_fri_alpha(&mut self) -> E;
+
+    /// Performs a proof-of-work for the most recently committed FRI layer and returns the
+    /// resulting nonce.
+    ///
+    /// The nonce must be such that hashing it together with the values the prover has written
+    /// into the channel results in a value with at least `grinding_factor` leading zeros. The
+    /// nonce is written into the channel, and thus, affects the α drawn for this layer.
+    ///
+    /// This is invoked only when the FRI options specify a non-zero layer grinding factor.
+    fn grind_fri_layer(&mut self, grinding_factor: u32) -> u64;
+
+    /// Sends evaluations of the most recently committed layer polynomial at out-of-domain points
+    /// to the verifier.
+    ///
+    /// The evaluations are written into the channel, and thus, affect all values drawn from the
+    /// channel afterwards. This is invoked only by a STIR prover.
+    fn send_ood_evaluations(&mut self, evaluations: &[E]);
+
+    /// Returns `num_positions` positions drawn pseudo-randomly from a domain of the specified
+    /// size; the returned positions may contain duplicates.
+    ///
+    /// This is invoked only by a STIR prover to draw shift queries for
+    /// a committed layer.
+    fn draw_layer_positions(&mut self, num_positions: usize, domain_size: usize) -> Vec<usize>;
 }
 
 // DEFAULT PROVER CHANNEL IMPLEMENTATION
@@ -80,6 +104,21 @@ where
     /// * `domain_size` is smaller than 8 or is not a power of two.
     /// * `num_queries` is zero.
     pub fn new(domain_size: usize, num_queries: usize) -> Self {
+        Self::with_public_coin(RandomCoin::new(&[]), domain_size, num_queries)
+    }
+
+    /// Returns a new prover channel which draws random values from the specified public coin.
+    ///
+    /// This can be used to bind a FRI proof to the transcript of an enclosing protocol: the
+    /// `public_coin` can be seeded with (or reseeded with all) the values exchanged in the
+    /// enclosing protocol before FRI is executed. To verify such a proof, the verifier must pass
+    /// a public coin in the same state to [FriVerifier::new()](crate::FriVerifier::new).
+    ///
+    /// # Panics
+    /// Panics if:
+    /// * `domain_size` is smaller than 8 or is not a power of two.
+    /// * `num_queries` is zero.
+    pub fn with_public_coin(public_coin: R, domain_size: usize, num_queries: usize) -> Self {
         assert!(domain_size >= 8, "domain size must be at least 8, but was {domain_size}");
         assert!(
             domain_size.is_power_of_two(),
@@ -87,7 +126,7 @@ where
         );
         assert!(num_queries > 0, "number of queries must be greater than zero");
         DefaultProverChannel {
-            public_coin: RandomCoin::new(&[]),
+            public_coin,
             commitments: Vec::new(),
             domain_size,
             num_queries,
@@ -115,6 +154,15 @@ where
     pub fn layer_commitments(&self) -> &[H::Digest] {
         &self.commitments
     }
+
+    /// Returns the public coin of this channel.
+    ///
+    /// The coin is reseeded with every FRI layer commitment written into this channel, and thus,
+    /// can be used to continue the transcript of an enclosing protocol after FRI layers have
+    /// been built.
+    pub fn public_coin(&mut self) -> &mut R {
+        &mut self.public_coin
+    }
 }
 
 impl<E, H, R> ProverChannel<E> for DefaultProverChannel<E, H, R>
@@ -133,4 +181,22 @@ where
     fn draw_fri_alpha(&mut self) -> E {
         self.public_coin.draw().expect("failed to draw FRI alpha")
     }
+
+    fn grind_fri_layer(&mut self, grinding_factor: u32) -> u64 {
+        let nonce = (1..u64::MAX)
+            .find(|&nonce| self.public_coin.check_proof_of_work(nonce, grinding_factor))
+            .expect("nonce not found");
+        self.public_coin.reseed_with_int(nonce);
+        nonce
+    }
+
+    fn send_ood_evaluations(&mut self, evaluations: &[E]) {
+        self.public_coin.reseed(H::hash_elements(evaluations));
+    }
+
+    fn draw_layer_positions(&mut self, num_positions: usize, domain_size: usize) -> Vec<usize> {
+        self.public_coin
+            .draw_integers(num_positions, domain_size, 0)
+            .expect("failed to draw layer positions")
+    }
 }
@@ -6,7 +6,7 @@
 use crate::{
     folding::{apply_drp, fold_positions},
     proof::{FriProof, FriProofLayer},
-    utils::hash_values,
+    utils::{get_query_value_index, hash_values},
     FriOptions,
 };
 use core::marker::PhantomData;
@@ -46,16 +46,17 @@ mod tests;
 /// During the commit phase, which is executed via [build_layers()](FriProver::build_layers())
 /// function, the prover repeatedly applies a degree-respecting projection (DRP) to `evaluations`
 /// (see [folding](crate::folding)). With every application of the DRP, the degree of the function
-/// *f* (and size of the domain over which it is evaluated) is reduced by the `folding_factor`
-/// until the remaining evaluations correspond to a polynomial, called remainder polynomial, with
-/// a number of coefficients less than or equal to `remainder_max_degree_plus_1`.
+/// *f* (and size of the domain over which it is evaluated) is reduced by the folding factor of
+/// the layer (see [FriOptions::layer_folding_factors()]) until the remaining evaluations
+/// correspond to a polynomial, called remainder polynomial, with a number of coefficients less
+/// than or equal to `remainder_max_degree_plus_1`.
 ///
 /// At each layer of reduction, the prover commits to the current set of evaluations. This is done
 /// by building a Merkle tree from the evaluations and sending the root of the tree to the verifier
 /// (via [ProverChannel]). The Merkle tree is build in such a way that all evaluations needed to
 /// compute a single value in the next FRI layer are grouped into the same leaf (the number of
 /// evaluations needed to compute a single element in the next FRI layer is equal to the
-/// `folding_factor`). This allows us to decommit all these values using a single Merkle
+/// folding factor of the layer). This allows us to decommit all these values using a single Merkle
 /// authentication path.
 ///
 /// After committing to the set of evaluations at the current layer, the prover draws a random
@@ -64,8 +65,17 @@ mod tests;
 /// sends it to the prover. In the non-interactive version, α is pseudo-randomly generated based
 /// on the values the prover has written into the channel up to that point.
 ///
+/// If the options specify a non-zero layer grinding factor (see
+/// [FriOptions::layer_grinding_factor()]), the prover performs a proof-of-work via
+/// [ProverChannel::grind_fri_layer()] after committing to each layer and before drawing α. The
+/// resulting nonces are included in the proof.
+///
 /// The prover keeps all FRI layers (consisting of evaluations and corresponding Merkle trees) in
-/// its internal state.
+/// its internal state. A prover created in low-memory mode (see
+/// [with_low_memory()](FriProver::with_low_memory())) keeps Merkle trees of all layers, but
+/// retains evaluations of the first layer only; evaluations of subsequent layers are discarded
+/// as soon as the next layer is built, and are recomputed one layer at a time during the query
+/// phase.
 ///
 /// # Query phase
 /// In the query phase, which is executed via [build_proof()](FriProver::build_proof()) function,
@@ -96,12 +106,15 @@ where
     options: FriOptions,
     layers: Vec<FriLayer<B, E, H>>,
     remainder_poly: FriRemainder<E>,
+    layer_nonces: Vec<u64>,
+    low_memory: bool,
     _channel: PhantomData<C>,
 }
 
 struct FriLayer<B: StarkField, E: FieldElement<BaseField = B>, H: Hasher> {
     tree: MerkleTree<H>,
     evaluations: Vec<E>,
+    alpha: E,
     _base_field: PhantomData<B>,
 }
 
@@ -125,18 +138,41 @@ where
             options,
             layers: Vec::new(),
             remainder_poly: FriRemainder(vec![]),
+            layer_nonces: Vec::new(),
+            low_memory: false,
             _channel: PhantomData,
         }
     }
 
+    /// Returns this prover switched to low-memory mode.
+    ///
+    /// In low-memory mode, evaluations of FRI layers other than the first one are not kept in
+    /// memory after the next layer has been built. Instead, they are recomputed from the first
+    /// layer during the query phase. This reduces the memory retained by the prover between the
+    /// commit and the query phases by the combined size of all layers but the first one at the
+    /// cost of re-applying the degree-respecting projection once per layer. The generated proofs
+    /// are identical to the proofs generated in the default mode.
+    pub fn with_low_memory(mut self) -> Self {
+        self.low_memory = true;
+        self
+    }
+
     // ACCESSORS
     // --------------------------------------------------------------------------------------------
 
     /// Returns folding factor for this prover.
+    ///
+    /// If a folding schedule is specified in the options of this prover, the leading FRI layers
+    /// are folded according to the schedule instead.
     pub fn folding_factor(&self) -> usize {
         self.options.folding_factor()
     }
 
+    /// Returns true if this prover is in low-memory mode.
+    pub fn is_low_memory(&self) -> bool {
+        self.low_memory
+    }
+
     /// Returns offset of the domain over which FRI protocol is executed by this prover.
     pub fn domain_offset(&self) -> B {
         self.options.domain_offset()
@@ -152,6 +188,7 @@ where
     pub fn reset(&mut self) {
         self.layers.clear();
         self.remainder_poly.0.clear();
+        self.layer_nonces.clear();
     }
 
     // COMMIT PHASE
@@ -161,8 +198,8 @@ where
     /// During this phase we repeatedly apply a degree-respecting projection (DRP) to
     /// `evaluations` which contain evaluations of some function *f* over domain *D*. With every
     /// application of the DRP the degree of the function (and size of the domain) is reduced by
-    /// `folding_factor` until the remaining evaluations can be represented by a remainder polynomial
-    /// with at most `remainder_max_degree_plus_1` number of coefficients.
+    /// the folding factor of the layer until the remaining evaluations can be represented by a
+    /// remainder polynomial with at most `remainder_max_degree_plus_1` number of coefficients.
     /// At each layer of reduction the current evaluations are committed to using a Merkle tree,
     /// and the root of this tree is written into the channel. After this the prover draws a random
     /// field element α from the channel, and uses it in the next application of the DRP.
@@ -175,15 +212,15 @@ where
             "a prior proof generation request has not been completed yet"
         );
 
-        // reduce the degree by folding_factor at each iteration until the remaining polynomial
+        // reduce the degree by the folding factor of each layer until the remaining polynomial
         // has small enough degree
-        for _ in 0..self.options.num_fri_layers(evaluations.len()) {
-            match self.folding_factor() {
+        for folding_factor in self.options.layer_folding_factors(evaluations.len()) {
+            match folding_factor {
                 2 => self.build_layer::<2>(channel, &mut evaluations),
                 4 => self.build_layer::<4>(channel, &mut evaluations),
                 8 => self.build_layer::<8>(channel, &mut evaluations),
                 16 => self.build_layer::<16>(channel, &mut evaluations),
-                _ => unimplemented!("folding factor {} is not supported", self.folding_factor()),
+                _ => unimplemented!("folding factor {} is not supported", folding_factor),
             }
         }
 
@@ -203,13 +240,28 @@ where
             MerkleTree::<H>::new(hashed_evaluations).expect("failed to construct FRI layer tree");
         channel.commit_fri_layer(*evaluation_tree.root());
 
+        // if required, perform a proof-of-work before drawing the folding challenge
+        let grinding_factor = self.options.layer_grinding_factor();
+        if grinding_factor > 0 {
+            self.layer_nonces.push(channel.grind_fri_layer(grinding_factor));
+        }
+
         // draw a pseudo-random coefficient from the channel, and use it in degree-respecting
         // projection to reduce the degree of evaluations by N
         let alpha = channel.draw_fri_alpha();
         *evaluations = apply_drp(&transposed_evaluations, self.domain_offset(), alpha);
+
+        // in low-memory mode, only evaluations of the first layer are retained; evaluations of
+        // other layers are recomputed from the first layer during the query phase
+        let layer_evaluations = if self.low_memory && !self.layers.is_empty() {
+            Vec::new()
+        } else {
+            flatten_vector_elements(transposed_evaluations)
+        };
         self.layers.push(FriLayer {
             tree: evaluation_tree,
-            evaluations: flatten_vector_elements(transposed_evaluations),
+            evaluations: layer_evaluations,
+            alpha,
             _base_field: PhantomData,
         });
     }
@@ -244,19 +296,74 @@ where
         if !self.layers.is_empty() {
             let mut positions = positions.to_vec();
             let mut domain_size = self.layers[0].evaluations.len();
-            let folding_factor = self.options.folding_factor();
+            let folding_factors = self.options.layer_folding_factors(domain_size);
+
+            // evaluations of the current layer recomputed from the previous layer; this is used
+            // only when layer evaluations were discarded in low-memory mode
+            let mut recomputed_evaluations = Vec::new();
 
             // for all FRI layers, except the last one, record tree root, determine a set of query
             // positions, and query the layer at these positions.
-            for i in 0..self.layers.len() {
-                positions = fold_positions(&positions, domain_size, folding_factor);
+            for (i, &folding_factor) in folding_factors.iter().enumerate() {
+                let layer_positions = positions;
+                positions = fold_positions(&layer_positions, domain_size, folding_factor);
+
+                // values at positions queried in this layer are known to the verifier, and can
+                // be omitted from the proof if deduplication is enabled
+                let known_positions = if self.options.query_deduplication() {
+                    Some((layer_positions.as_slice(), domain_size))
+                } else {
+                    None
+                };
+
+                let layer = &self.layers[i];
+                if layer.evaluations.is_empty() {
+                    let prev_layer = &self.layers[i - 1];
+                    let prev_evaluations = if prev_layer.evaluations.is_empty() {
+                        &recomputed_evaluations
+                    } else {
+                        &prev_layer.evaluations
+                    };
+                    recomputed_evaluations = refold_layer(
+                        prev_evaluations,
+                        folding_factors[i - 1],
+                        folding_factor,
+                        self.domain_offset(),
+                        prev_layer.alpha,
+                    );
+                }
+                let evaluations = if layer.evaluations.is_empty() {
+                    &recomputed_evaluations
+                } else {
+                    &layer.evaluations
+                };
 
                 // sort of a static dispatch for folding_factor parameter
                 let proof_layer = match folding_factor {
-                    2 => query_layer::<B, E, H, 2>(&self.layers[i], &positions),
-                    4 => query_layer::<B, E, H, 4>(&self.layers[i], &positions),
-                    8 => query_layer::<B, E, H, 8>(&self.layers[i], &positions),
-                    16 => query_layer::<B, E, H, 16>(&self.layers[i], &positions),
+                    2 => query_layer::<E, H, 2>(
+                        &layer.tree,
+                        evaluations,
+                        &positions,
+                        known_positions,
+                    ),
+                    4 => query_layer::<E, H, 4>(
+                        &layer.tree,
+                        evaluations,
+                        &positions,
+                        known_positions,
+                    ),
+                    8 => query_layer::<E, H, 8>(
+                        &layer.tree,
+                        evaluations,
+                        &positions,
+                        known_positions,
+                    ),
+                    16 => query_layer::<E, H, 16>(
+                        &layer.tree,
+                        evaluations,
+                        &positions,
+                        known_positions,
+                    ),
                     _ => unimplemented!("folding factor {} is not supported", folding_factor),
                 };
 
@@ -267,37 +374,85 @@ where
 
         // use the remaining polynomial values directly as proof
         let remainder = self.remainder_poly.0.clone();
+        let layer_nonces = self.layer_nonces.clone();
 
         // clear layers so that another proof can be generated
         self.reset();
 
-        FriProof::new(layers, remainder, 1)
+        FriProof::new(layers, remainder, layer_nonces, 1)
     }
 }
 
 // HELPER FUNCTIONS
 // ================================================================================================
 
-/// Builds a single proof layer by querying the evaluations of the passed in FRI layer at the
-/// specified positions.
-fn query_layer<B: StarkField, E: FieldElement<BaseField = B>, H: Hasher, const N: usize>(
-    layer: &FriLayer<B, E, H>,
+/// Builds a single proof layer by querying the evaluations of a FRI layer committed to by the
+/// specified `tree` at the specified positions.
+///
+/// If `known_positions` are provided (together with the size of the layer domain), the values at
+/// these positions of the layer are omitted from the proof layer.
+fn query_layer<E: FieldElement, H: Hasher, const N: usize>(
+    tree: &MerkleTree<H>,
+    layer_evaluations: &[E],
     positions: &[usize],
+    known_positions: Option<(&[usize], usize)>,
 ) -> FriProofLayer {
     // build Merkle authentication paths for all query positions
-    let proof = layer
-        .tree
+    let proof = tree
         .prove_batch(positions)
         .expect("failed to generate a Merkle proof for FRI layer queries");
 
     // build a list of polynomial evaluations at each position; since evaluations in FRI layers
     // are stored in transposed form, a position refers to N evaluations which are committed
     // in a single leaf
-    let evaluations: &[[E; N]] = group_slice_elements(&layer.evaluations);
+    let evaluations: &[[E; N]] = group_slice_elements(layer_evaluations);
     let mut queried_values: Vec<[E; N]> = Vec::with_capacity(positions.len());
     for &position in positions.iter() {
         queried_values.push(evaluations[position]);
     }
 
-    FriProofLayer::new(queried_values, proof)
+    match known_positions {
+        Some((known_positions, domain_size)) => {
+            let known_value_indexes = known_positions
+                .iter()
+                .map(|&position| get_query_value_index(position, positions, domain_size, N))
+                .collect::<Vec<_>>();
+            FriProofLayer::new_deduplicated(queried_values, &known_value_indexes, proof)
+        }
+        None => FriProofLayer::new(queried_values, proof),
+    }
+}
+
+/// Recomputes evaluations of a FRI layer from evaluations of the previous layer.
+///
+/// `evaluations` must be in the form in which they are committed to at the previous layer (i.e.,
+/// transposed for the `folding_factor` of the previous layer), and the result is returned in the
+/// form in which it is committed to at the next layer (i.e., transposed for the
+/// `next_folding_factor`).
+fn refold_layer<B, E>(
+    evaluations: &[E],
+    folding_factor: usize,
+    next_folding_factor: usize,
+    domain_offset: B,
+    alpha: E,
+) -> Vec<E>
+where
+    B: StarkField,
+    E: FieldElement<BaseField = B>,
+{
+    let folded_evaluations = match folding_factor {
+        2 => apply_drp(group_slice_elements::<E, 2>(evaluations), domain_offset, alpha),
+        4 => apply_drp(group_slice_elements::<E, 4>(evaluations), domain_offset, alpha),
+        8 => apply_drp(group_slice_elements::<E, 8>(evaluations), domain_offset, alpha),
+        16 => apply_drp(group_slice_elements::<E, 16>(evaluations), domain_offset, alpha),
+        _ => unimplemented!("folding factor {} is not supported", folding_factor),
+    };
+
+    match next_folding_factor {
+        2 => flatten_vector_elements(transpose_slice::<E, 2>(&folded_evaluations)),
+        4 => flatten_vector_elements(transpose_slice::<E, 4>(&folded_evaluations)),
+        8 => flatten_vector_elements(transpose_slice::<E, 8>(&folded_evaluations)),
+        16 => flatten_vector_elements(transpose_slice::<E, 16>(&folded_evaluations)),
+        _ => unimplemented!("folding factor {} is not supported", next_folding_factor),
+    }
 }
@@ -3,14 +3,18 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use super::{DefaultProverChannel, FriProver};
+use super::{apply_drp, DefaultProverChannel, FriProver};
 use crate::{
-    verifier::{DefaultVerifierChannel, FriVerifier},
+    verifier::{verify_proof_parts, DefaultVerifierChannel, FriProofParts, FriVerifier},
     FriOptions, FriProof, VerifierError,
 };
 use crypto::{hashers::Blake3_256, DefaultRandomCoin, Hasher, RandomCoin};
-use math::{fft, fields::f128::BaseElement, FieldElement};
-use utils::{collections::Vec, Deserializable, Serializable, SliceReader};
+use math::{
+    fft, fields::f128::BaseElement, fields::QuadExtension, get_power_series_with_offset, polynom,
+    FieldElement, StarkField,
+};
+use rand_utils::{rand_value, rand_vector};
+use utils::{collections::Vec, transpose_slice, Deserializable, Serializable, SliceReader};
 
 type Blake3 = Blake3_256<BaseElement>;
 
@@ -35,6 +39,435 @@ fn fri_folding_4() {
     fri_prove_verify(trace_length_e, lde_blowup_e, folding_factor_e, max_remainder_degree)
 }
 
+#[test]
+fn fri_folding_8() {
+    let trace_length_e = 12;
+    let lde_blowup_e = 3;
+    let folding_factor_e = 3;
+    let max_remainder_degree = 7;
+    fri_prove_verify(trace_length_e, lde_blowup_e, folding_factor_e, max_remainder_degree)
+}
+
+#[test]
+fn fri_folding_16() {
+    let trace_length_e = 12;
+    let lde_blowup_e = 3;
+    let folding_factor_e = 4;
+    let max_remainder_degree = 7;
+    fri_prove_verify(trace_length_e, lde_blowup_e, folding_factor_e, max_remainder_degree)
+}
+
+#[test]
+fn fri_folding_extension() {
+    fn check_folding<const N: usize>() {
+        type E = QuadExtension<BaseElement>;
+        let alpha: E = rand_value();
+        let poly: Vec<E> = rand_vector(64);
+
+        // fold the polynomial in coefficient form
+        let folded_poly =
+            poly.chunks(N).map(|chunk| polynom::eval(chunk, alpha)).collect::<Vec<_>>();
+
+        // evaluate the polynomial and the folded polynomial over their respective domains
+        let n = poly.len() * 4;
+        let offset = BaseElement::GENERATOR;
+        let g = BaseElement::get_root_of_unity(n.ilog2());
+        let domain = get_power_series_with_offset(g, offset, n);
+        let folded_g = BaseElement::get_root_of_unity((n / N).ilog2());
+        let folded_domain = get_power_series_with_offset(folded_g, offset.exp(N as u128), n / N);
+        let evaluations = eval_many(&poly, &domain);
+        let folded_evaluations = eval_many(&folded_poly, &folded_domain);
+
+        let transposed_evaluations = transpose_slice::<E, N>(&evaluations);
+        let drp_evaluations = apply_drp(&transposed_evaluations, offset, alpha);
+        assert_eq!(folded_evaluations, drp_evaluations);
+    }
+
+    fn eval_many(
+        poly: &[QuadExtension<BaseElement>],
+        xs: &[BaseElement],
+    ) -> Vec<QuadExtension<BaseElement>> {
+        xs.iter().map(|&x| polynom::eval(poly, QuadExtension::from(x))).collect()
+    }
+
+    check_folding::<2>();
+    check_folding::<4>();
+    check_folding::<8>();
+    check_folding::<16>();
+}
+
+#[test]
+fn fri_remainder_degree_0() {
+    let trace_length_e = 12;
+    let lde_blowup_e = 3;
+    let folding_factor_e = 2;
+    let max_remainder_degree = 0;
+    fri_prove_verify(trace_length_e, lde_blowup_e, folding_factor_e, max_remainder_degree)
+}
+
+#[test]
+fn fri_remainder_degree_bound() {
+    // the polynomial is of small enough degree to be sent as the remainder without folding
+    let trace_length = 8;
+    let options = FriOptions::new(8, 4, 7);
+    let mut channel = build_prover_channel(trace_length, &options);
+    let evaluations = build_evaluations(trace_length, options.blowup_factor());
+
+    let mut prover = FriProver::new(options.clone());
+    prover.build_layers(&mut channel, evaluations.clone());
+    assert_eq!(0, prover.num_layers());
+    let positions = channel.draw_query_positions(0);
+    let proof = prover.build_proof(&positions);
+    assert_eq!(trace_length, proof.num_remainder_elements::<BaseElement>());
+
+    let commitments = channel.layer_commitments().to_vec();
+    let domain_size = trace_length * options.blowup_factor();
+    let result = verify_proof(
+        proof.clone(),
+        commitments.clone(),
+        &evaluations,
+        trace_length - 1,
+        domain_size,
+        &positions,
+        &options,
+    );
+    assert_eq!(Ok(()), result);
+
+    // padding the remainder with zero coefficients does not change the remainder polynomial,
+    // but the number of coefficients must still be within the degree bound
+    let mut remainder = proof.parse_remainder::<BaseElement>().unwrap();
+    remainder.resize(2 * trace_length, BaseElement::ZERO);
+    let proof = FriProof::new(Vec::new(), remainder, Vec::new(), 1);
+    let result = verify_proof(
+        proof,
+        commitments,
+        &evaluations,
+        trace_length - 1,
+        domain_size,
+        &positions,
+        &options,
+    );
+    assert_eq!(Err(VerifierError::RemainderDegreeMismatch(trace_length - 1)), result);
+}
+
+#[test]
+fn fri_folding_schedule() {
+    let trace_length_e = 12;
+    let options = FriOptions::new(8, 4, 7).with_folding_schedule(&[8, 8]);
+    assert_eq!(vec![8, 8, 4, 4], options.layer_folding_factors(1 << 15));
+    assert_eq!(32, options.remainder_domain_size(1 << 15));
+    fri_prove_verify_with_options(trace_length_e, options);
+
+    // unused trailing factors of the schedule are ignored
+    let options = FriOptions::new(8, 4, 7).with_folding_schedule(&[16, 2, 16, 16, 16]);
+    assert_eq!(vec![16, 2, 16], options.layer_folding_factors(1 << 15));
+    fri_prove_verify_with_options(trace_length_e, options);
+}
+
+#[test]
+fn fri_folding_schedule_mismatch() {
+    let trace_length = 1 << 12;
+    let options = FriOptions::new(8, 4, 7).with_folding_schedule(&[8, 8]);
+    let mut channel = build_prover_channel(trace_length, &options);
+    let evaluations = build_evaluations(trace_length, options.blowup_factor());
+
+    let mut prover = FriProver::new(options.clone());
+    prover.build_layers(&mut channel, evaluations.clone());
+    let positions = channel.draw_query_positions(0);
+    let proof = prover.build_proof(&positions);
+
+    // a proof folded according to one schedule must not verify against a different schedule
+    let options = FriOptions::new(8, 4, 7).with_folding_schedule(&[8, 4, 8]);
+    let commitments = channel.layer_commitments().to_vec();
+    let result = verify_proof(
+        proof,
+        commitments,
+        &evaluations,
+        trace_length - 1,
+        trace_length * options.blowup_factor(),
+        &positions,
+        &options,
+    );
+    assert!(result.is_err());
+}
+
+#[test]
+fn fri_layer_grinding() {
+    let trace_length = 1 << 12;
+    let options = FriOptions::new(8, 4, 7).with_layer_grinding_factor(8);
+    let mut channel = build_prover_channel(trace_length, &options);
+    let evaluations = build_evaluations(trace_length, options.blowup_factor());
+
+    let mut prover = FriProver::new(options.clone());
+    prover.build_layers(&mut channel, evaluations.clone());
+    let positions = channel.draw_query_positions(0);
+    let proof = prover.build_proof(&positions);
+    assert_eq!(proof.num_layers(), proof.layer_nonces().len());
+
+    let commitments = channel.layer_commitments().to_vec();
+    let domain_size = trace_length * options.blowup_factor();
+    let verify = |proof: FriProof, options: &FriOptions| {
+        verify_proof(
+            proof,
+            commitments.clone(),
+            &evaluations,
+            trace_length - 1,
+            domain_size,
+            &positions,
+            options,
+        )
+    };
+    assert_eq!(Ok(()), verify(proof.clone(), &options));
+
+    // nonces are not expected when per-layer proof-of-work is not required
+    let result = verify(proof.clone(), &FriOptions::new(8, 4, 7));
+    assert_eq!(Err(VerifierError::NumLayerNoncesMismatch(0, proof.num_layers())), result);
+
+    // a modified nonce must be rejected; nonces are serialized right before the number of
+    // partitions
+    let mut proof_bytes = proof.to_bytes();
+    let num_bytes = proof_bytes.len();
+    proof_bytes[num_bytes - 2] ^= 1;
+    let proof = FriProof::read_from(&mut SliceReader::new(&proof_bytes)).unwrap();
+    assert!(verify(proof, &options).is_err());
+}
+
+#[test]
+fn fri_proof_num_partitions() {
+    let trace_length = 1 << 10;
+    let options = FriOptions::new(8, 4, 7);
+    let mut channel = build_prover_channel(trace_length, &options);
+    let evaluations = build_evaluations(trace_length, options.blowup_factor());
+
+    let mut prover = FriProver::new(options);
+    prover.build_layers(&mut channel, evaluations);
+    let positions = channel.draw_query_positions(0);
+    let proof = prover.build_proof(&positions);
+    assert_eq!(1, proof.num_partitions());
+
+    // the number of partitions is serialized as the last byte of the proof (as a power of two);
+    // numbers of partitions which do not fit into usize must be rejected
+    let mut proof_bytes = proof.to_bytes();
+    let num_bytes = proof_bytes.len();
+    proof_bytes[num_bytes - 1] = 3;
+    let proof = FriProof::read_from(&mut SliceReader::new(&proof_bytes)).unwrap();
+    assert_eq!(8, proof.num_partitions());
+
+    proof_bytes[num_bytes - 1] = usize::BITS as u8;
+    assert!(FriProof::read_from(&mut SliceReader::new(&proof_bytes)).is_err());
+}
+
+#[test]
+fn fri_low_memory() {
+    let trace_length = 1 << 12;
+    for options in [
+        FriOptions::new(8, 4, 7),
+        FriOptions::new(8, 2, 7).with_folding_schedule(&[16, 4, 8]),
+    ] {
+        let evaluations = build_evaluations(trace_length, options.blowup_factor());
+
+        let mut channel = build_prover_channel(trace_length, &options);
+        let mut prover = FriProver::new(options.clone());
+        prover.build_layers(&mut channel, evaluations.clone());
+        let positions = channel.draw_query_positions(0);
+        let expected_proof = prover.build_proof(&positions);
+
+        // a proof generated in low-memory mode must be the same as in the default mode
+        let mut channel = build_prover_channel(trace_length, &options);
+        let mut prover = FriProver::new(options.clone()).with_low_memory();
+        assert!(prover.is_low_memory());
+        prover.build_layers(&mut channel, evaluations.clone());
+        assert!(prover.layers.iter().skip(1).all(|layer| layer.evaluations.is_empty()));
+        let proof = prover.build_proof(&positions);
+        assert_eq!(expected_proof, proof);
+
+        let result = verify_proof(
+            proof,
+            channel.layer_commitments().to_vec(),
+            &evaluations,
+            trace_length - 1,
+            trace_length * options.blowup_factor(),
+            &positions,
+            &options,
+        );
+        assert_eq!(Ok(()), result);
+    }
+}
+
+#[test]
+fn fri_query_deduplication() {
+    let trace_length = 1 << 12;
+    let options = FriOptions::new(8, 4, 7);
+    let dedup_options = options.clone().with_query_deduplication();
+    let domain_size = trace_length * options.blowup_factor();
+    let evaluations = build_evaluations(trace_length, options.blowup_factor());
+
+    for num_queries in [27, 36, 54] {
+        let build_proof = |options: &FriOptions| {
+            let mut channel =
+                DefaultProverChannel::<BaseElement, Blake3, DefaultRandomCoin<Blake3>>::new(
+                    domain_size,
+                    num_queries,
+                );
+            let mut prover = FriProver::new(options.clone());
+            prover.build_layers(&mut channel, evaluations.clone());
+            let positions = channel.draw_query_positions(0);
+            let proof = prover.build_proof(&positions);
+            (proof, channel.layer_commitments().to_vec(), positions)
+        };
+        let (proof, _, _) = build_proof(&options);
+        let (dedup_proof, commitments, positions) = build_proof(&dedup_options);
+        assert!(!proof.is_deduplicated());
+        assert!(dedup_proof.is_deduplicated());
+
+        let verify = |proof: FriProof, options: &FriOptions| {
+            verify_proof(
+                proof,
+                commitments.clone(),
+                &evaluations,
+                trace_length - 1,
+                domain_size,
+                &positions,
+                options,
+            )
+        };
+        assert_eq!(Ok(()), verify(dedup_proof.clone(), &dedup_options));
+
+        // in every layer, values at all unique positions entering the layer are omitted, and the
+        // number of queries is written instead
+        let mut expected_savings = 0;
+        let mut layer_positions = positions.clone();
+        let mut layer_domain_size = domain_size;
+        for folding_factor in options.layer_folding_factors(domain_size) {
+            layer_positions.sort_unstable();
+            layer_positions.dedup();
+            expected_savings += layer_positions.len() * BaseElement::ELEMENT_BYTES - 1;
+
+            layer_domain_size /= folding_factor;
+            layer_positions.iter_mut().for_each(|p| *p %= layer_domain_size);
+        }
+        let savings = proof.to_bytes().len() - dedup_proof.to_bytes().len();
+        assert_eq!(expected_savings, savings);
+
+        // proofs must be verified with the same deduplication setting as they were generated with
+        assert!(verify(dedup_proof, &options).is_err());
+        assert!(verify(proof, &dedup_options).is_err());
+    }
+}
+
+#[test]
+fn fri_public_coin_binding() {
+    let trace_length = 1 << 10;
+    let options = FriOptions::new(8, 4, 7);
+    let domain_size = trace_length * options.blowup_factor();
+    let evaluations = build_evaluations(trace_length, options.blowup_factor());
+
+    // bind the proof to a transcript of an enclosing protocol
+    let transcript = [BaseElement::new(42)];
+    let coin = DefaultRandomCoin::<Blake3>::new(&transcript);
+    let mut channel =
+        DefaultProverChannel::<BaseElement, Blake3, _>::with_public_coin(coin, domain_size, 32);
+    let mut prover = FriProver::new(options.clone());
+    prover.build_layers(&mut channel, evaluations.clone());
+    let positions = channel.draw_query_positions(0);
+    let proof = prover.build_proof(&positions);
+    let commitments = channel.layer_commitments().to_vec();
+
+    let verify = |transcript: &[BaseElement]| {
+        let mut channel = DefaultVerifierChannel::<BaseElement, Blake3>::new(
+            proof.clone(),
+            commitments.clone(),
+            domain_size,
+            &options,
+        )
+        .unwrap();
+        let mut coin = DefaultRandomCoin::<Blake3>::new(transcript);
+        let verifier =
+            FriVerifier::new(&mut channel, &mut coin, options.clone(), trace_length - 1)?;
+        let queried_evaluations = positions.iter().map(|&p| evaluations[p]).collect::<Vec<_>>();
+        verifier.verify(&mut channel, &queried_evaluations, &positions)
+    };
+
+    // the proof verifies only against a public coin in the same state
+    assert_eq!(Ok(()), verify(&transcript));
+    assert!(verify(&[BaseElement::new(43)]).is_err());
+}
+
+#[test]
+fn fri_proof_parts() {
+    let trace_length = 1 << 10;
+    let options = FriOptions::new(8, 4, 7);
+    let domain_size = trace_length * options.blowup_factor();
+    let evaluations = build_evaluations(trace_length, options.blowup_factor());
+
+    for options in [options.clone(), options.with_query_deduplication()] {
+        let mut channel = build_prover_channel(trace_length, &options);
+        let mut prover = FriProver::new(options.clone());
+        prover.build_layers(&mut channel, evaluations.clone());
+        let positions = channel.draw_query_positions(0);
+        let proof = prover.build_proof(&positions);
+        let commitments = channel.layer_commitments().to_vec();
+        let build_parts = || {
+            FriProofParts::<BaseElement, Blake3>::from_proof(
+                proof.clone(),
+                commitments.clone(),
+                domain_size,
+                &options,
+            )
+            .unwrap()
+        };
+
+        let verify = |parts: FriProofParts<BaseElement, Blake3>, options: &FriOptions| {
+            let mut coin = DefaultRandomCoin::<Blake3>::new(&[]);
+            let queried_evaluations = positions.iter().map(|&p| evaluations[p]).collect::<Vec<_>>();
+            verify_proof_parts(
+                parts,
+                &mut coin,
+                options.clone(),
+                trace_length - 1,
+                &queried_evaluations,
+                &positions,
+            )
+        };
+        assert_eq!(Ok(()), verify(build_parts(), &options));
+
+        // query values are bound to the layer commitments
+        let mut tampered = build_parts();
+        tampered.layer_queries[1][0] += BaseElement::ONE;
+        assert_eq!(Err(VerifierError::LayerCommitmentMismatch(1)), verify(tampered, &options));
+
+        // the remainder is bound to the last layer commitment
+        let mut tampered = build_parts();
+        tampered.remainder[0] += BaseElement::ONE;
+        assert_eq!(Err(VerifierError::RemainderCommitmentMismatch), verify(tampered, &options));
+
+        // the number of commitments and openings must be consistent with the options
+        let num_layers = options.num_fri_layers(domain_size);
+        let mut tampered = build_parts();
+        tampered.layer_commitments.pop();
+        assert_eq!(
+            Err(VerifierError::NumLayerCommitmentsMismatch(num_layers + 1, num_layers)),
+            verify(tampered, &options)
+        );
+        let mut tampered = build_parts();
+        tampered.layer_queries.pop();
+        assert_eq!(
+            Err(VerifierError::NumLayerOpeningsMismatch(num_layers, num_layers - 1)),
+            verify(tampered, &options)
+        );
+
+        // only FRI proofs can be verified from parts
+        #[cfg(feature = "experimental-stir")]
+        {
+            let stir_options = options.clone().with_low_degree_test(crate::LowDegreeTest::Stir);
+            assert_eq!(
+                Err(VerifierError::UnsupportedLowDegreeTest),
+                verify(build_parts(), &stir_options)
+            );
+        }
+    }
+}
+
 // TEST UTILS
 // ================================================================================================
 
@@ -77,7 +510,7 @@ pub fn verify_proof(
         proof,
         commitments,
         domain_size,
-        options.folding_factor(),
+        options,
     )
     .unwrap();
     let mut coin = DefaultRandomCoin::<Blake3>::new(&[]);
@@ -92,11 +525,17 @@ fn fri_prove_verify(
     folding_factor_e: usize,
     max_remainder_degree: usize,
 ) {
-    let trace_length = 1 << trace_length_e;
     let lde_blowup = 1 << lde_blowup_e;
     let folding_factor = 1 << folding_factor_e;
 
     let options = FriOptions::new(lde_blowup, folding_factor, max_remainder_degree);
+    fri_prove_verify_with_options(trace_length_e, options)
+}
+
+fn fri_prove_verify_with_options(trace_length_e: usize, options: FriOptions) {
+    let trace_length = 1 << trace_length_e;
+    let lde_blowup = options.blowup_factor();
+
     let mut channel = build_prover_channel(trace_length, &options);
     let evaluations = build_evaluations(trace_length, lde_blowup);
 
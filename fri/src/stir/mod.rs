@@ -0,0 +1,272 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+//! Contains an experimental implementation of the STIR low-degree test.
+//!
+//! STIR (Shift To Improve Rate) is an alternative to FRI which, similarly to FRI, reduces the
+//! degree of the tested function by the folding factor in every round. Unlike FRI, however, the
+//! evaluation domain of every committed function is only half the size of the domain of the
+//! previous one. The rate of the committed codes thus improves with every round, and fewer
+//! queries are needed at later rounds for the same soundness. This results in smaller proofs,
+//! especially for large domains and large folding factors.
+//!
+//! Denoting the evaluation domain of the i-th round by *L_i* (with *L_0* = *D*), the protocol
+//! proceeds as follows:
+//!
+//! 1. The prover commits to *g_0* = *f* over *L_0*, and the verifier responds with a folding
+//!    challenge.
+//! 2. In every round *i* > 0, the prover commits to evaluations of *g_i* over *L_i*, where *g_i*
+//!    is the polynomial of the folded function from the previous round. The verifier responds
+//!    with an out-of-domain point, the prover sends the evaluation of *g_i* at this point, and
+//!    the verifier draws a combination challenge and a set of shift queries into the folded
+//!    domain of the previous round. Function *f_i* is then defined as the quotient of *g_i* by the
+//!    set of the out-of-domain point and the shift query points (with the out-of-domain
+//!    evaluation and the folded values of *f_{i-1}* at the queries as the expected answers),
+//!    corrected to the degree of *g_i*. Finally, the verifier draws the folding challenge for
+//!    *f_i*.
+//! 3. After the last round, the prover sends the folded polynomial of the last function in
+//!    coefficient form (the remainder), and the verifier draws the last set of shift queries.
+//!
+//! The verifier checks the folded values of every *f_i* at the shift queries of the round only
+//! implicitly: they are used as the expected answers of the quotient which defines *f_{i+1}*.
+//! Folded values of the last function are checked against the remainder directly.
+//!
+//! The STIR prover and verifier use the same [ProverChannel](crate::ProverChannel) and
+//! [VerifierChannel](crate::VerifierChannel) abstractions as FRI, and generate proofs of the same
+//! [FriProof](crate::FriProof) type; the number of layer commitments for a given domain is also
+//! the same as for FRI. Thus, the two protocols can be used interchangeably based on
+//! [FriOptions::low_degree_test()].
+//!
+//! As with FRI, the caller provides the positions at which the tested function is queried at
+//! the first layer, and the first layer is opened at these positions in addition to the shift
+//! queries of the first round. When the caller does not need to draw positions of its own, the
+//! shift queries of the first round (see [StirProver::query_positions()] and
+//! [StirVerifier::query_positions()]) can be used as the query positions; in this case, STIR
+//! proofs are generally smaller than FRI proofs with the same number of queries.
+//!
+//! Support for STIR is experimental: folding schedules, per-layer proof-of-work, and query
+//! deduplication are not supported, and the number of queries in each round is derived from the
+//! number of queries specified for the first round using a simple heuristic (see
+//! [layer_num_queries()]), rather than from a rigorous soundness analysis.
+//!
+//! # References
+//! * [STIR: Reed–Solomon Proximity Testing with Fewer Queries](https://eprint.iacr.org/2024/390)
+
+use crate::{FriOptions, LowDegreeTest};
+use core::convert::TryInto;
+use math::{batch_inversion, polynom, FieldElement, StarkField};
+use utils::collections::Vec;
+
+mod prover;
+pub use prover::StirProver;
+
+mod verifier;
+pub use verifier::StirVerifier;
+
+#[cfg(test)]
+mod tests;
+
+// PUBLIC FUNCTIONS
+// ================================================================================================
+
+/// Returns the number of shift queries made to each committed layer (except for the remainder)
+/// when STIR is executed with the specified `options` over a domain of the specified size.
+///
+/// The first layer is queried at `num_queries` positions. Since the rate of the code improves by
+/// a factor of `folding_factor / 2` with every round, the number of queries at the i-th layer is
+/// reduced proportionally to the number of bits of security provided by a single query at that
+/// layer. The number of queries at each layer is capped so that the queries can be drawn from the
+/// folded domain of the layer.
+pub fn layer_num_queries(
+    options: &FriOptions,
+    num_queries: usize,
+    domain_size: usize,
+) -> Vec<usize> {
+    let log_blowup = options.blowup_factor().ilog2() as usize;
+    let log_folding_factor = options.folding_factor().ilog2() as usize;
+
+    options
+        .layer_domain_sizes(domain_size)
+        .into_iter()
+        .enumerate()
+        .map(|(i, layer_domain_size)| {
+            let bits_per_query = log_blowup + i * (log_folding_factor - 1);
+            let num_layer_queries = if i == 0 || bits_per_query == 0 {
+                num_queries
+            } else {
+                (num_queries * log_blowup).div_ceil(bits_per_query)
+            };
+            num_layer_queries.min(layer_domain_size / options.folding_factor() - 1)
+        })
+        .collect()
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns true if the specified options describe a STIR configuration supported by this
+/// implementation.
+fn is_supported(options: &FriOptions) -> bool {
+    options.low_degree_test() == LowDegreeTest::Stir
+        && options.folding_schedule().is_empty()
+        && options.layer_grinding_factor() == 0
+        && !options.query_deduplication()
+}
+
+/// Removes duplicates from the list of drawn positions while preserving the order in which the
+/// positions were drawn.
+fn dedup_positions(positions: Vec<usize>) -> Vec<usize> {
+    let mut result = Vec::with_capacity(positions.len());
+    for position in positions {
+        if !result.contains(&position) {
+            result.push(position);
+        }
+    }
+    result
+}
+
+/// Returns the points of the folded domain which correspond to the specified positions of a
+/// layer evaluated over a domain of the specified size.
+///
+/// Position *s* of the layer domain *w* * *g*^*i* is mapped to (*w* * *g*^*s*)^`folding_factor`.
+fn shift_points<B: StarkField>(
+    positions: &[usize],
+    domain_size: usize,
+    folding_factor: usize,
+    domain_offset: B,
+) -> Vec<B> {
+    let domain_generator = B::get_root_of_unity(domain_size.ilog2());
+    positions
+        .iter()
+        .map(|&position| {
+            let x = domain_offset * domain_generator.exp_vartime((position as u64).into());
+            x.exp_vartime((folding_factor as u64).into())
+        })
+        .collect()
+}
+
+/// Folds each of the specified rows of a layer by the folding factor `N` using challenge `alpha`.
+///
+/// Rows are located at the specified `positions` of a layer evaluated over a domain of the
+/// specified size; the i-th value of row *s* is the evaluation at *w* * *g*^(*s* + i * *n* / `N`).
+fn fold_rows<E: FieldElement, const N: usize>(
+    rows: &[[E; N]],
+    positions: &[usize],
+    domain_size: usize,
+    domain_offset: E::BaseField,
+    alpha: E,
+) -> Vec<E> {
+    let xs = row_points::<E, N>(positions, domain_size, domain_offset);
+    polynom::interpolate_batch(&xs, rows)
+        .iter()
+        .map(|poly| polynom::eval(poly, alpha))
+        .collect()
+}
+
+/// Returns the points at which the values of the rows at the specified positions of a layer are
+/// evaluated.
+fn row_points<E: FieldElement, const N: usize>(
+    positions: &[usize],
+    domain_size: usize,
+    domain_offset: E::BaseField,
+) -> Vec<[E; N]> {
+    let domain_generator = E::BaseField::get_root_of_unity(domain_size.ilog2());
+    let folding_roots = (0..N)
+        .map(|i| domain_generator.exp_vartime(((domain_size / N * i) as u64).into()))
+        .collect::<Vec<_>>();
+
+    positions
+        .iter()
+        .map(|&position| {
+            let x = domain_generator.exp_vartime((position as u64).into()) * domain_offset;
+            folding_roots
+                .iter()
+                .map(|&root| E::from(x * root))
+                .collect::<Vec<_>>()
+                .try_into()
+                .unwrap()
+        })
+        .collect()
+}
+
+// QUOTIENT
+// ================================================================================================
+
+/// Describes the degree-corrected quotient of a layer polynomial by a set of points at which the
+/// values of the polynomial are known.
+///
+/// For a function *g*, a set of points *G* with answers *a*, and a combination challenge *r*, the
+/// quotient is defined as (*g*(x) - *A*(x)) / *V*(x) * (1 + r * x + ... + (r * x)^|*G*|), where
+/// *A* is the polynomial which interpolates the answers over *G*, and *V* is the vanishing
+/// polynomial of *G*. If *g* is a polynomial of degree smaller than *d* which agrees with the
+/// answers, the quotient is a polynomial of degree smaller than *d* as well.
+struct Quotient<E: FieldElement> {
+    points: Vec<E>,
+    weights: Vec<E>,
+    comb_challenge: E,
+}
+
+impl<E: FieldElement> Quotient<E> {
+    /// Returns a new quotient by the specified points with the specified answers, or `None` if
+    /// the points are not distinct.
+    fn new(points: Vec<E>, answers: &[E], comb_challenge: E) -> Option<Self> {
+        // weights of the answers in the barycentric form of A(x) / V(x)
+        let denominators = points
+            .iter()
+            .enumerate()
+            .map(|(i, &a)| {
+                points
+                    .iter()
+                    .enumerate()
+                    .filter(|&(j, _)| j != i)
+                    .fold(E::ONE, |acc, (_, &b)| acc * (a - b))
+            })
+            .collect::<Vec<_>>();
+        let weights = batch_inversion_nonzero(&denominators)?
+            .into_iter()
+            .zip(answers)
+            .map(|(inv_denominator, &answer)| answer * inv_denominator)
+            .collect();
+
+        Some(Quotient {
+            points,
+            weights,
+            comb_challenge,
+        })
+    }
+
+    /// Returns the value of the quotient at `x` given the value of the layer function at `x`, or
+    /// `None` if `x` is one of the points of the quotient or if r * x = 1.
+    fn evaluate(&self, value: E, x: E) -> Option<E> {
+        // invert all (x - a) terms together with (1 - r * x) using a single inversion
+        let rx = self.comb_challenge * x;
+        let mut denominators = self.points.iter().map(|&a| x - a).collect::<Vec<_>>();
+        denominators.push(E::ONE - rx);
+        let inv_denominators = batch_inversion_nonzero(&denominators)?;
+        let (inv_one_minus_rx, inv_differences) = inv_denominators.split_last().unwrap();
+
+        let (inv_vanishing, interpolant) = inv_differences.iter().zip(&self.weights).fold(
+            (E::ONE, E::ZERO),
+            |(inv_vanishing, interpolant), (&inv_difference, &weight)| {
+                (inv_vanishing * inv_difference, interpolant + weight * inv_difference)
+            },
+        );
+
+        // 1 + r * x + ... + (r * x)^e = (1 - (r * x)^(e + 1)) / (1 - r * x)
+        let num_terms = self.points.len() as u64 + 1;
+        let degree_correction = (E::ONE - rx.exp(num_terms.into())) * *inv_one_minus_rx;
+
+        Some((value * inv_vanishing - interpolant) * degree_correction)
+    }
+}
+
+/// Returns inverses of the specified values computed via [batch_inversion()], or `None` if any
+/// of the values is zero.
+fn batch_inversion_nonzero<E: FieldElement>(values: &[E]) -> Option<Vec<E>> {
+    if values.contains(&E::ZERO) {
+        return None;
+    }
+    Some(batch_inversion(values))
+}
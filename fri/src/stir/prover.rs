@@ -0,0 +1,410 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{dedup_positions, is_supported, layer_num_queries, shift_points, Quotient};
+use crate::{
+    folding::{apply_drp, fold_positions},
+    proof::{FriProof, FriProofLayer},
+    utils::hash_values,
+    FriOptions, ProverChannel,
+};
+use core::marker::PhantomData;
+use crypto::{ElementHasher, Hasher, MerkleTree};
+use math::{fft, get_power_series_with_offset, polynom, FieldElement, StarkField};
+use utils::{
+    collections::Vec, flatten_vector_elements, group_slice_elements, iter_mut, transpose_slice,
+};
+
+#[cfg(feature = "concurrent")]
+use utils::iterators::*;
+
+// STIR PROVER
+// ================================================================================================
+
+/// Implements the prover component of the STIR protocol.
+///
+/// The prover is parametrized in the same way as a [FriProver](crate::FriProver), and is used in
+/// the same way: the commit phase of the protocol is executed via
+/// [build_layers()](StirProver::build_layers()), and the query phase via
+/// [build_proof()](StirProver::build_proof()). In addition to the values written into the channel
+/// by a FRI prover, a STIR prover sends an out-of-domain evaluation of every layer polynomial
+/// (except for the first one) via [ProverChannel::send_ood_evaluations()], and draws shift
+/// queries for every layer via [ProverChannel::draw_layer_positions()].
+///
+/// During the query phase, the first layer is opened at the positions specified by the caller
+/// (folded as in FRI) in addition to the shift queries of the first layer; all other layers are
+/// opened at their shift queries only.
+pub struct StirProver<B, E, C, H>
+where
+    B: StarkField,
+    E: FieldElement<BaseField = B>,
+    C: ProverChannel<E, Hasher = H>,
+    H: ElementHasher<BaseField = B>,
+{
+    options: FriOptions,
+    num_queries: usize,
+    layers: Vec<StirLayer<E, H>>,
+    ood_evaluations: Vec<E>,
+    remainder_poly: Vec<E>,
+    _channel: PhantomData<C>,
+}
+
+struct StirLayer<E: FieldElement, H: Hasher> {
+    tree: MerkleTree<H>,
+    evaluations: Vec<E>,
+    shift_positions: Vec<usize>,
+}
+
+// PROVER IMPLEMENTATION
+// ================================================================================================
+
+impl<B, E, C, H> StirProver<B, E, C, H>
+where
+    B: StarkField,
+    E: FieldElement<BaseField = B>,
+    C: ProverChannel<E, Hasher = H>,
+    H: ElementHasher<BaseField = B>,
+{
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+    /// Returns a new STIR prover instantiated with the provided `options` which will make
+    /// `num_queries` shift queries at the first layer.
+    ///
+    /// # Panics
+    /// Panics if:
+    /// * `options` do not specify STIR as the low-degree test.
+    /// * `options` specify a folding schedule, per-layer proof-of-work, or query deduplication.
+    /// * `num_queries` is zero.
+    pub fn new(options: FriOptions, num_queries: usize) -> Self {
+        assert!(is_supported(&options), "options are not supported by the STIR prover");
+        assert!(num_queries > 0, "number of queries must be greater than zero");
+        StirProver {
+            options,
+            num_queries,
+            layers: Vec::new(),
+            ood_evaluations: Vec::new(),
+            remainder_poly: Vec::new(),
+            _channel: PhantomData,
+        }
+    }
+
+    // ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns folding factor for this prover.
+    pub fn folding_factor(&self) -> usize {
+        self.options.folding_factor()
+    }
+
+    /// Returns offset of the domain over which STIR protocol is executed by this prover.
+    pub fn domain_offset(&self) -> B {
+        self.options.domain_offset()
+    }
+
+    /// Returns number of layers computed during the last execution of the
+    /// [build_layers()](StirProver::build_layers()) method.
+    pub fn num_layers(&self) -> usize {
+        self.layers.len()
+    }
+
+    /// Returns shift queries of the first layer drawn during the last execution of the
+    /// [build_layers()](StirProver::build_layers()) method; the list is empty if no layers were
+    /// built.
+    ///
+    /// The first layer is always opened at these positions. Thus, if the caller has not drawn
+    /// query positions of its own, these positions can be passed to
+    /// [build_proof()](StirProver::build_proof()) without increasing the size of the proof.
+    pub fn query_positions(&self) -> &[usize] {
+        self.layers.first().map(|layer| layer.shift_positions.as_slice()).unwrap_or(&[])
+    }
+
+    /// Clears a vector of internally stored layers.
+    pub fn reset(&mut self) {
+        self.layers.clear();
+        self.ood_evaluations.clear();
+        self.remainder_poly.clear();
+    }
+
+    // COMMIT PHASE
+    // --------------------------------------------------------------------------------------------
+    /// Executes the commit phase of the STIR protocol.
+    ///
+    /// `evaluations` must contain evaluations of some function *f* over domain *D*. In every
+    /// round, the current function is folded by the folding factor, and the folded polynomial is
+    /// committed to over a domain half the size of the current one. The remaining rounds of the
+    /// protocol are executed as described in the [module documentation](crate::stir).
+    ///
+    /// # Panics
+    /// Panics if the prover state is dirty (the vector of layers is not empty).
+    pub fn build_layers(&mut self, channel: &mut C, evaluations: Vec<E>) {
+        assert!(
+            self.layers.is_empty() && self.remainder_poly.is_empty(),
+            "a prior proof generation request has not been completed yet"
+        );
+
+        let domain_size = evaluations.len();
+        let domain_offset = self.domain_offset();
+        let folding_factor = self.folding_factor();
+        let num_layers = self.options.num_fri_layers(domain_size);
+        let layer_num_queries = layer_num_queries(&self.options, self.num_queries, domain_size);
+
+        // if the function is of small enough degree, it is sent as the remainder directly
+        if num_layers == 0 {
+            let mut coefficients = evaluations;
+            let inv_twiddles = fft::get_inv_twiddles(coefficients.len());
+            fft::interpolate_poly_with_offset(&mut coefficients, &inv_twiddles, domain_offset);
+            coefficients.truncate(domain_size / self.options.blowup_factor());
+            self.set_remainder(channel, coefficients);
+            return;
+        }
+
+        let mut layer_evaluations = evaluations;
+        let mut layer_poly = Vec::new();
+        let mut folded_evaluations: Vec<E> = Vec::new();
+        let mut poly_size = domain_size / self.options.blowup_factor();
+
+        for depth in 0..num_layers {
+            let layer_domain_size = layer_evaluations.len();
+            let (tree, transposed_evaluations) =
+                build_layer_tree::<E, H>(&layer_evaluations, folding_factor);
+            channel.commit_fri_layer(*tree.root());
+
+            // for all layers but the first one, bind the layer polynomial to the folded function
+            // of the previous layer via an out-of-domain evaluation and the shift queries
+            if depth > 0 {
+                let ood_point: E = channel.draw_fri_alpha();
+                let ood_evaluation = polynom::eval(&layer_poly, ood_point);
+                channel.send_ood_evaluations(&[ood_evaluation]);
+                self.ood_evaluations.push(ood_evaluation);
+                let comb_challenge = channel.draw_fri_alpha();
+
+                let prev_domain_size = layer_domain_size * 2;
+                let positions = dedup_positions(channel.draw_layer_positions(
+                    layer_num_queries[depth - 1],
+                    prev_domain_size / folding_factor,
+                ));
+                let quotient = build_quotient(
+                    ood_point,
+                    ood_evaluation,
+                    &positions,
+                    &folded_evaluations,
+                    prev_domain_size,
+                    folding_factor,
+                    domain_offset,
+                    comb_challenge,
+                );
+                self.layers[depth - 1].shift_positions = positions;
+
+                let domain_generator = B::get_root_of_unity(layer_domain_size.ilog2());
+                let domain = get_power_series_with_offset(
+                    domain_generator,
+                    domain_offset,
+                    layer_domain_size,
+                );
+                iter_mut!(layer_evaluations).zip(domain).for_each(|(value, x)| {
+                    *value = quotient
+                        .evaluate(*value, E::from(x))
+                        .expect("STIR quotient denominator is zero at a layer domain point");
+                });
+            }
+
+            // fold the current function and interpolate the result into the polynomial of the
+            // next layer, discarding the coefficients above the expected degree
+            let fold_challenge = channel.draw_fri_alpha();
+            folded_evaluations =
+                fold_evaluations(&layer_evaluations, folding_factor, domain_offset, fold_challenge);
+            poly_size /= folding_factor;
+            let mut folded_poly = folded_evaluations.clone();
+            let inv_twiddles = fft::get_inv_twiddles(folded_poly.len());
+            let folded_domain_offset = domain_offset.exp_vartime((folding_factor as u64).into());
+            fft::interpolate_poly_with_offset(
+                &mut folded_poly,
+                &inv_twiddles,
+                folded_domain_offset,
+            );
+            folded_poly.truncate(poly_size);
+
+            self.layers.push(StirLayer {
+                tree,
+                evaluations: transposed_evaluations,
+                shift_positions: Vec::new(),
+            });
+
+            if depth + 1 < num_layers {
+                let twiddles = fft::get_twiddles(poly_size);
+                let next_domain_size = layer_domain_size / 2;
+                layer_evaluations = fft::evaluate_poly_with_offset(
+                    &folded_poly,
+                    &twiddles,
+                    domain_offset,
+                    next_domain_size / poly_size,
+                );
+                layer_poly = folded_poly;
+            } else {
+                self.set_remainder(channel, folded_poly);
+            }
+        }
+
+        // draw shift queries for the last layer; these are checked against the remainder
+        let last_layer_domain_size = self.layers[num_layers - 1].evaluations.len();
+        self.layers[num_layers - 1].shift_positions =
+            dedup_positions(channel.draw_layer_positions(
+                layer_num_queries[num_layers - 1],
+                last_layer_domain_size / folding_factor,
+            ));
+    }
+
+    /// Commits to the remainder polynomial specified by its coefficients.
+    fn set_remainder(&mut self, channel: &mut C, remainder_poly: Vec<E>) {
+        let commitment = <H as ElementHasher>::hash_elements(&remainder_poly);
+        channel.commit_fri_layer(commitment);
+        self.remainder_poly = remainder_poly;
+    }
+
+    // QUERY PHASE
+    // --------------------------------------------------------------------------------------------
+    /// Executes query phase of STIR protocol.
+    ///
+    /// The first layer is opened at the specified `positions` (folded by the folding factor) and
+    /// at the shift queries of the first layer; all subsequent layers are opened at their shift
+    /// queries. The remainder polynomial and the out-of-domain evaluations are included in the
+    /// proof in their entirety.
+    ///
+    /// # Panics
+    /// Panics is the prover state is clean (no layers have been build yet).
+    pub fn build_proof(&mut self, positions: &[usize]) -> FriProof {
+        assert!(!self.remainder_poly.is_empty(), "STIR layers have not been built yet");
+
+        let folding_factor = self.folding_factor();
+        let mut layers = Vec::with_capacity(self.layers.len());
+        for (depth, layer) in self.layers.iter().enumerate() {
+            let layer_positions = if depth == 0 {
+                let mut layer_positions =
+                    fold_positions(positions, layer.evaluations.len(), folding_factor);
+                for &position in layer.shift_positions.iter() {
+                    if !layer_positions.contains(&position) {
+                        layer_positions.push(position);
+                    }
+                }
+                layer_positions
+            } else {
+                layer.shift_positions.clone()
+            };
+
+            // sort of a static dispatch for folding_factor parameter
+            let proof_layer = match folding_factor {
+                2 => query_layer::<E, H, 2>(layer, &layer_positions),
+                4 => query_layer::<E, H, 4>(layer, &layer_positions),
+                8 => query_layer::<E, H, 8>(layer, &layer_positions),
+                16 => query_layer::<E, H, 16>(layer, &layer_positions),
+                _ => unimplemented!("folding factor {} is not supported", folding_factor),
+            };
+            layers.push(proof_layer);
+        }
+
+        let proof = FriProof::new(layers, self.remainder_poly.clone(), Vec::new(), 1)
+            .with_ood_evaluations(&self.ood_evaluations);
+
+        // clear layers so that another proof can be generated
+        self.reset();
+
+        proof
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Builds a Merkle tree from the `evaluations` transposed into rows of `folding_factor` values,
+/// and returns the tree together with the transposed evaluations.
+fn build_layer_tree<E, H>(evaluations: &[E], folding_factor: usize) -> (MerkleTree<H>, Vec<E>)
+where
+    E: FieldElement,
+    H: ElementHasher<BaseField = E::BaseField>,
+{
+    match folding_factor {
+        2 => build_layer_tree_inner::<E, H, 2>(evaluations),
+        4 => build_layer_tree_inner::<E, H, 4>(evaluations),
+        8 => build_layer_tree_inner::<E, H, 8>(evaluations),
+        16 => build_layer_tree_inner::<E, H, 16>(evaluations),
+        _ => unimplemented!("folding factor {} is not supported", folding_factor),
+    }
+}
+
+fn build_layer_tree_inner<E, H, const N: usize>(evaluations: &[E]) -> (MerkleTree<H>, Vec<E>)
+where
+    E: FieldElement,
+    H: ElementHasher<BaseField = E::BaseField>,
+{
+    let transposed_evaluations = transpose_slice::<E, N>(evaluations);
+    let hashed_evaluations = hash_values::<H, E, N>(&transposed_evaluations);
+    let tree =
+        MerkleTree::<H>::new(hashed_evaluations).expect("failed to construct STIR layer tree");
+    (tree, flatten_vector_elements(transposed_evaluations))
+}
+
+/// Applies degree-respecting projection to the `evaluations` using the specified challenge.
+fn fold_evaluations<B, E>(
+    evaluations: &[E],
+    folding_factor: usize,
+    domain_offset: B,
+    alpha: E,
+) -> Vec<E>
+where
+    B: StarkField,
+    E: FieldElement<BaseField = B>,
+{
+    match folding_factor {
+        2 => apply_drp(&transpose_slice::<E, 2>(evaluations), domain_offset, alpha),
+        4 => apply_drp(&transpose_slice::<E, 4>(evaluations), domain_offset, alpha),
+        8 => apply_drp(&transpose_slice::<E, 8>(evaluations), domain_offset, alpha),
+        16 => apply_drp(&transpose_slice::<E, 16>(evaluations), domain_offset, alpha),
+        _ => unimplemented!("folding factor {} is not supported", folding_factor),
+    }
+}
+
+/// Builds the quotient for a layer from the out-of-domain evaluation of the layer polynomial and
+/// the `folded_evaluations` of the previous layer at the shift `positions`.
+#[allow(clippy::too_many_arguments)]
+fn build_quotient<B, E>(
+    ood_point: E,
+    ood_evaluation: E,
+    positions: &[usize],
+    folded_evaluations: &[E],
+    prev_domain_size: usize,
+    folding_factor: usize,
+    domain_offset: B,
+    comb_challenge: E,
+) -> Quotient<E>
+where
+    B: StarkField,
+    E: FieldElement<BaseField = B>,
+{
+    let mut points = vec![ood_point];
+    points.extend(
+        shift_points(positions, prev_domain_size, folding_factor, domain_offset)
+            .into_iter()
+            .map(E::from),
+    );
+    let mut answers = vec![ood_evaluation];
+    answers.extend(positions.iter().map(|&position| folded_evaluations[position]));
+    Quotient::new(points, &answers, comb_challenge)
+        .expect("out-of-domain point of a STIR round coincides with one of its shift queries")
+}
+
+/// Builds a single proof layer by opening the specified layer at the specified positions.
+fn query_layer<E, H, const N: usize>(layer: &StirLayer<E, H>, positions: &[usize]) -> FriProofLayer
+where
+    E: FieldElement,
+    H: ElementHasher<BaseField = E::BaseField>,
+{
+    let proof = layer
+        .tree
+        .prove_batch(positions)
+        .expect("failed to generate a Merkle proof for STIR layer queries");
+    let rows: &[[E; N]] = group_slice_elements(&layer.evaluations);
+    let queried_values = positions.iter().map(|&position| rows[position]).collect::<Vec<_>>();
+    FriProofLayer::new(queried_values, proof)
+}
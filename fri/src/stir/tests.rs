@@ -0,0 +1,245 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{layer_num_queries, Quotient, StirProver, StirVerifier};
+use crate::{
+    DefaultProverChannel, DefaultVerifierChannel, FriOptions, FriProof, FriProver, LowDegreeTest,
+    VerifierError,
+};
+use crypto::{hashers::Blake3_256, DefaultRandomCoin, Hasher, RandomCoin};
+use math::{fft, fields::f128::BaseElement, FieldElement, StarkField};
+use utils::{collections::Vec, Deserializable, Serializable, SliceReader};
+
+type Blake3 = Blake3_256<BaseElement>;
+type Coin = DefaultRandomCoin<Blake3>;
+type Digest = <Blake3 as Hasher>::Digest;
+type ProverChannel = DefaultProverChannel<BaseElement, Blake3, Coin>;
+type VerifierChannel = DefaultVerifierChannel<BaseElement, Blake3>;
+
+const NUM_QUERIES: usize = 32;
+
+// PROVE/VERIFY TESTS
+// ================================================================================================
+
+#[test]
+fn stir_prove_verify() {
+    for folding_factor in [2, 4, 8, 16] {
+        let options = stir_options(8, folding_factor, 7);
+        let (proof, commitments, positions, evaluations) = build_proof(1 << 10, &options);
+        assert_eq!(options.num_fri_layers(1 << 13), proof.num_layers());
+        assert_eq!(proof.num_layers() - 1, proof.num_ood_evaluations::<BaseElement>());
+
+        let result =
+            verify_proof(proof, commitments, &evaluations, &positions, (1 << 10) - 1, &options);
+        assert_eq!(Ok(()), result, "folding factor {folding_factor}");
+    }
+
+    // without any layers, the evaluations are checked against the remainder directly
+    let options = stir_options(8, 4, 255);
+    let (proof, commitments, positions, evaluations) = build_proof(256, &options);
+    assert_eq!(0, proof.num_layers());
+    let result = verify_proof(proof, commitments, &evaluations, &positions, 255, &options);
+    assert_eq!(Ok(()), result);
+}
+
+#[test]
+fn stir_high_degree_poly() {
+    // evaluations of a polynomial of degree 2047 are passed off as evaluations of a polynomial of
+    // degree 1023 over the same domain
+    let options = stir_options(8, 4, 7);
+    let evaluations = build_evaluations(1 << 11, 4);
+    let (proof, commitments, positions) = prove(&evaluations, &options);
+    let result =
+        verify_proof(proof, commitments, &evaluations, &positions, (1 << 10) - 1, &options);
+    assert!(result.is_err());
+}
+
+#[test]
+fn stir_invalid_evaluations() {
+    let options = stir_options(8, 4, 7);
+    let (proof, commitments, positions, mut evaluations) = build_proof(1 << 10, &options);
+
+    // evaluations which do not match the first layer are rejected
+    let position = positions[3];
+    evaluations[position] += BaseElement::ONE;
+    let result = verify_proof(
+        proof.clone(),
+        commitments.clone(),
+        &evaluations,
+        &positions,
+        (1 << 10) - 1,
+        &options,
+    );
+    assert_eq!(Err(VerifierError::InvalidLayerFolding(0, position)), result);
+    evaluations[position] -= BaseElement::ONE;
+
+    // proofs are bound to the options: a verifier using FRI cannot parse the proof, and a
+    // verifier with a different folding factor rejects it
+    let fri_options = FriOptions::new(8, 4, 7);
+    let result = verify_proof(
+        proof.clone(),
+        commitments.clone(),
+        &evaluations,
+        &positions,
+        1023,
+        &fri_options,
+    );
+    assert!(result.is_err());
+    let other_options = stir_options(8, 8, 7);
+    let result = verify_proof(proof, commitments, &evaluations, &positions, 1023, &other_options);
+    assert!(result.is_err());
+
+    // unsupported options are rejected
+    let options = stir_options(8, 4, 7).with_query_deduplication();
+    let (proof, commitments, positions, evaluations) = build_proof(1 << 10, &stir_options(8, 4, 7));
+    let result = verify_proof(proof, commitments, &evaluations, &positions, 1023, &options);
+    assert_eq!(Err(VerifierError::UnsupportedStirOptions), result);
+}
+
+// SERIALIZATION AND PROOF SIZE TESTS
+// ================================================================================================
+
+#[test]
+fn stir_proof_serialization() {
+    let options = stir_options(8, 4, 7);
+    let (proof, commitments, positions, evaluations) = build_proof(1 << 10, &options);
+
+    let proof_bytes = proof.to_bytes();
+    assert_eq!(proof.size(), proof_bytes.len());
+    let mut reader = SliceReader::new(&proof_bytes);
+    let parsed_proof = FriProof::read_from(&mut reader).unwrap();
+    assert_eq!(proof, parsed_proof);
+
+    let result =
+        verify_proof(parsed_proof, commitments, &evaluations, &positions, (1 << 10) - 1, &options);
+    assert_eq!(Ok(()), result);
+}
+
+#[test]
+fn stir_proof_size() {
+    // the number of queries at each layer shrinks as the rate of the code improves
+    let options = stir_options(4, 16, 7);
+    let domain_size = 1 << 18;
+    assert_eq!(
+        vec![NUM_QUERIES, 13, 8, 6],
+        layer_num_queries(&options, NUM_QUERIES, domain_size)
+    );
+
+    // when the shift queries of the first layer are used as query positions, STIR proofs are
+    // smaller than FRI proofs with the same number of queries
+    let evaluations = build_evaluations(domain_size / 4, 4);
+    let mut channel = ProverChannel::new(domain_size, NUM_QUERIES);
+    let mut prover = StirProver::new(options.clone(), NUM_QUERIES);
+    prover.build_layers(&mut channel, evaluations.clone());
+    let positions = prover.query_positions().to_vec();
+    let stir_proof = prover.build_proof(&positions);
+
+    let commitments = channel.layer_commitments().to_vec();
+    let domain_size = evaluations.len();
+    let mut channel =
+        VerifierChannel::new(stir_proof.clone(), commitments, domain_size, &options).unwrap();
+    let mut coin = Coin::new(&[]);
+    let verifier =
+        StirVerifier::new(&mut channel, &mut coin, options, NUM_QUERIES, domain_size / 4 - 1)
+            .unwrap();
+    assert_eq!(positions, verifier.query_positions());
+    let queried_evaluations = positions.iter().map(|&p| evaluations[p]).collect::<Vec<_>>();
+    assert_eq!(Ok(()), verifier.verify(&mut channel, &queried_evaluations, &positions));
+
+    let mut channel = ProverChannel::new(domain_size, NUM_QUERIES);
+    let mut prover = FriProver::new(FriOptions::new(4, 16, 7));
+    prover.build_layers(&mut channel, evaluations);
+    let positions = channel.draw_query_positions(0);
+    let fri_proof = prover.build_proof(&positions);
+
+    assert_eq!(fri_proof.num_layers(), stir_proof.num_layers());
+    assert!(stir_proof.size() < fri_proof.size());
+}
+
+// QUOTIENT TESTS
+// ================================================================================================
+
+#[test]
+fn stir_quotient_zero_denominator() {
+    let points = vec![BaseElement::new(3), BaseElement::new(5)];
+    let answers = [BaseElement::new(7), BaseElement::new(11)];
+    let comb_challenge = BaseElement::new(2);
+    let quotient = Quotient::new(points.clone(), &answers, comb_challenge).unwrap();
+    assert!(quotient.evaluate(BaseElement::ONE, BaseElement::new(9)).is_some());
+
+    // the quotient cannot be evaluated at its points and at the inverse of the challenge
+    assert_eq!(None, quotient.evaluate(BaseElement::ONE, points[1]));
+    assert_eq!(None, quotient.evaluate(BaseElement::ONE, comb_challenge.inv()));
+
+    // a quotient cannot be built for points which are not distinct
+    assert!(Quotient::new(vec![points[0], points[0]], &answers, comb_challenge).is_none());
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+fn stir_options(
+    blowup_factor: usize,
+    folding_factor: usize,
+    remainder_max_degree: usize,
+) -> FriOptions {
+    FriOptions::new(blowup_factor, folding_factor, remainder_max_degree)
+        .with_low_degree_test(LowDegreeTest::Stir)
+}
+
+/// Evaluates a polynomial with `poly_size` coefficients over a domain blown up by the
+/// `blowup_factor`.
+fn build_evaluations(poly_size: usize, blowup_factor: usize) -> Vec<BaseElement> {
+    let poly: Vec<BaseElement> = (0..poly_size as u128).map(BaseElement::new).collect();
+    let twiddles = fft::get_twiddles::<BaseElement>(poly_size);
+    fft::evaluate_poly_with_offset(&poly, &twiddles, BaseElement::GENERATOR, blowup_factor)
+}
+
+/// Generates a STIR proof for a polynomial with `poly_size` coefficients evaluated over a domain
+/// of size `poly_size * blowup_factor`.
+fn build_proof(
+    poly_size: usize,
+    options: &FriOptions,
+) -> (FriProof, Vec<Digest>, Vec<usize>, Vec<BaseElement>) {
+    let evaluations = build_evaluations(poly_size, options.blowup_factor());
+    let (proof, commitments, positions) = prove(&evaluations, options);
+    (proof, commitments, positions, evaluations)
+}
+
+fn prove(evaluations: &[BaseElement], options: &FriOptions) -> (FriProof, Vec<Digest>, Vec<usize>) {
+    let mut channel = ProverChannel::new(evaluations.len(), NUM_QUERIES);
+    let mut prover = StirProver::new(options.clone(), NUM_QUERIES);
+    prover.build_layers(&mut channel, evaluations.to_vec());
+    let mut positions = channel.draw_query_positions(0);
+    positions.sort_unstable();
+    positions.dedup();
+    let proof = prover.build_proof(&positions);
+    let commitments = channel.layer_commitments().to_vec();
+    (proof, commitments, positions)
+}
+
+fn verify_proof(
+    proof: FriProof,
+    commitments: Vec<Digest>,
+    evaluations: &[BaseElement],
+    positions: &[usize],
+    max_degree: usize,
+    options: &FriOptions,
+) -> Result<(), VerifierError> {
+    let domain_size = (max_degree + 1).next_power_of_two() * options.blowup_factor();
+    let mut channel = VerifierChannel::new(proof, commitments, domain_size, options)
+        .map_err(|err| VerifierError::ProofDeserializationError(err.to_string()))?;
+    let mut coin = Coin::new(&[]);
+    let queried_evaluations = positions.iter().map(|&p| evaluations[p]).collect::<Vec<_>>();
+    if options.low_degree_test() == LowDegreeTest::Stir {
+        let verifier =
+            StirVerifier::new(&mut channel, &mut coin, options.clone(), NUM_QUERIES, max_degree)?;
+        verifier.verify(&mut channel, &queried_evaluations, positions)
+    } else {
+        let verifier =
+            crate::FriVerifier::new(&mut channel, &mut coin, options.clone(), max_degree)?;
+        verifier.verify(&mut channel, &queried_evaluations, positions)
+    }
+}
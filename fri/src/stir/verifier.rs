@@ -0,0 +1,417 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{
+    dedup_positions, fold_rows, is_supported, layer_num_queries, row_points, shift_points, Quotient,
+};
+use crate::{
+    folding::fold_positions, verifier::eval_horner, FriOptions, VerifierChannel, VerifierError,
+};
+use core::marker::PhantomData;
+use crypto::{ElementHasher, RandomCoin};
+use math::FieldElement;
+use utils::collections::Vec;
+
+// STIR VERIFIER
+// ================================================================================================
+
+/// Implements the verifier component of the STIR protocol.
+///
+/// The verifier is parametrized in the same way as a [FriVerifier](crate::FriVerifier), and is
+/// used in the same way: the commit phase of the protocol is replayed when the verifier is
+/// instantiated via [new()](StirVerifier::new()), and the query phase is executed via
+/// [verify()](StirVerifier::verify()).
+///
+/// During the commit phase, the verifier reads layer commitments and out-of-domain evaluations
+/// from the channel, and draws all challenges and shift queries from the public coin. During the
+/// query phase, the verifier checks the evaluations at the first layer against the commitment
+/// of the first layer, computes the folded values of every layer function at the shift queries
+/// of the layer, and checks the folded values of the last layer against the remainder.
+pub struct StirVerifier<E, C, H, R>
+where
+    E: FieldElement,
+    C: VerifierChannel<E, Hasher = H>,
+    H: ElementHasher<BaseField = E::BaseField>,
+    R: RandomCoin<BaseField = E::BaseField, Hasher = H>,
+{
+    max_poly_degree: usize,
+    domain_size: usize,
+    layer_commitments: Vec<H::Digest>,
+    fold_challenges: Vec<E>,
+    ood_points: Vec<E>,
+    ood_evaluations: Vec<E>,
+    comb_challenges: Vec<E>,
+    shift_positions: Vec<Vec<usize>>,
+    options: FriOptions,
+    _channel: PhantomData<C>,
+    _public_coin: PhantomData<R>,
+}
+
+impl<E, C, H, R> StirVerifier<E, C, H, R>
+where
+    E: FieldElement,
+    C: VerifierChannel<E, Hasher = H>,
+    H: ElementHasher<BaseField = E::BaseField>,
+    R: RandomCoin<BaseField = E::BaseField, Hasher = H>,
+{
+    /// Returns a new instance of STIR verifier created from the specified parameters.
+    ///
+    /// The `max_poly_degree` parameter specifies the highest polynomial degree accepted by the
+    /// returned verifier, and `num_queries` specifies the number of shift queries at the first
+    /// layer; both must be the same as the values used by the prover.
+    ///
+    /// Creating a STIR verifier executes the commit phase of the STIR protocol from the
+    /// verifier's perspective: layer commitments and out-of-domain evaluations are read from the
+    /// `channel` and absorbed by the `public_coin`, and all challenges and shift queries are
+    /// drawn from the coin.
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// * The `options` specify a folding schedule, per-layer proof-of-work, or query
+    ///   deduplication.
+    /// * The number of layer commitments or out-of-domain evaluations read from the channel is
+    ///   inconsistent with `max_poly_degree` and the `options`.
+    /// * Proof-of-work nonces were read from the channel.
+    /// * `max_poly_degree` cannot be reduced by the folding factor at every layer.
+    /// * An error was encountered while drawing a random value from the coin.
+    pub fn new(
+        channel: &mut C,
+        public_coin: &mut R,
+        options: FriOptions,
+        num_queries: usize,
+        max_poly_degree: usize,
+    ) -> Result<Self, VerifierError> {
+        if !is_supported(&options) {
+            return Err(VerifierError::UnsupportedStirOptions);
+        }
+
+        // infer evaluation domain info
+        let domain_size = max_poly_degree.next_power_of_two() * options.blowup_factor();
+        let folding_factor = options.folding_factor();
+        let layer_domain_sizes = options.layer_domain_sizes(domain_size);
+        let num_layers = layer_domain_sizes.len();
+
+        let layer_commitments = channel.read_fri_layer_commitments();
+        if layer_commitments.len() != num_layers + 1 {
+            return Err(VerifierError::NumLayerCommitmentsMismatch(
+                num_layers + 1,
+                layer_commitments.len(),
+            ));
+        }
+
+        let layer_nonces = channel.read_fri_layer_nonces();
+        if !layer_nonces.is_empty() {
+            return Err(VerifierError::NumLayerNoncesMismatch(0, layer_nonces.len()));
+        }
+
+        let ood_evaluations = channel.read_fri_ood_evaluations();
+        let num_expected_ood_evaluations = num_layers.saturating_sub(1);
+        if ood_evaluations.len() != num_expected_ood_evaluations {
+            return Err(VerifierError::NumOodEvaluationsMismatch(
+                num_expected_ood_evaluations,
+                ood_evaluations.len(),
+            ));
+        }
+
+        // make sure the degree can be reduced by the folding factor at all layers
+        let mut max_degree_plus_1 = max_poly_degree + 1;
+        for depth in 0..num_layers {
+            if max_degree_plus_1 % folding_factor != 0 {
+                return Err(VerifierError::DegreeTruncation(
+                    max_degree_plus_1 - 1,
+                    folding_factor,
+                    depth,
+                ));
+            }
+            max_degree_plus_1 /= folding_factor;
+        }
+
+        // replay the commit phase of the protocol
+        let layer_num_queries = layer_num_queries(&options, num_queries, domain_size);
+        let mut fold_challenges = Vec::with_capacity(num_layers);
+        let mut ood_points = Vec::with_capacity(num_expected_ood_evaluations);
+        let mut comb_challenges = Vec::with_capacity(num_expected_ood_evaluations);
+        let mut shift_positions = Vec::with_capacity(num_layers);
+        for depth in 0..num_layers {
+            public_coin.reseed(layer_commitments[depth]);
+            if depth > 0 {
+                ood_points.push(public_coin.draw().map_err(VerifierError::RandomCoinError)?);
+                public_coin.reseed(H::hash_elements(&[ood_evaluations[depth - 1]]));
+                comb_challenges.push(public_coin.draw().map_err(VerifierError::RandomCoinError)?);
+                shift_positions.push(draw_shift_positions(
+                    public_coin,
+                    layer_num_queries[depth - 1],
+                    layer_domain_sizes[depth - 1] / folding_factor,
+                )?);
+            }
+            fold_challenges.push(public_coin.draw().map_err(VerifierError::RandomCoinError)?);
+        }
+        public_coin.reseed(layer_commitments[num_layers]);
+        if num_layers > 0 {
+            shift_positions.push(draw_shift_positions(
+                public_coin,
+                layer_num_queries[num_layers - 1],
+                layer_domain_sizes[num_layers - 1] / folding_factor,
+            )?);
+        }
+
+        Ok(StirVerifier {
+            max_poly_degree,
+            domain_size,
+            layer_commitments,
+            fold_challenges,
+            ood_points,
+            ood_evaluations,
+            comb_challenges,
+            shift_positions,
+            options,
+            _channel: PhantomData,
+            _public_coin: PhantomData,
+        })
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns maximum degree of a polynomial accepted by this verifier.
+    pub fn max_poly_degree(&self) -> usize {
+        self.max_poly_degree
+    }
+
+    /// Returns size of the domain over which a polynomial commitment checked by this verifier
+    /// has been evaluated.
+    pub fn domain_size(&self) -> usize {
+        self.domain_size
+    }
+
+    /// Returns protocol configuration options for this verifier.
+    pub fn options(&self) -> &FriOptions {
+        &self.options
+    }
+
+    /// Returns shift queries of the first layer; the list is empty if there are no layers.
+    ///
+    /// These are the same positions as returned by
+    /// [StirProver::query_positions()](crate::stir::StirProver::query_positions()).
+    pub fn query_positions(&self) -> &[usize] {
+        self.shift_positions
+            .first()
+            .map(|positions| positions.as_slice())
+            .unwrap_or(&[])
+    }
+
+    // VERIFICATION PROCEDURE
+    // --------------------------------------------------------------------------------------------
+    /// Executes the query phase of the STIR protocol.
+    ///
+    /// Returns `Ok(())` if values in the `evaluations` slice represent evaluations of a polynomial
+    /// with degree <= `max_poly_degree` at x coordinates specified by the `positions` slice.
+    /// Values of all layers, as well as the remainder, are read from the specified `channel`.
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// * The length of `evaluations` is not equal to the length of `positions`.
+    /// * Decommitments to polynomial evaluations don't match the commitment value at any of the
+    ///   layers.
+    /// * The `evaluations` do not match the values committed to at the first layer.
+    /// * The remainder does not match its commitment, its degree is greater than the degree
+    ///   implied by `max_poly_degree` reduced by the folding factor at each layer, or it does not
+    ///   match the folded values of the last layer.
+    pub fn verify(
+        &self,
+        channel: &mut C,
+        evaluations: &[E],
+        positions: &[usize],
+    ) -> Result<(), VerifierError> {
+        if evaluations.len() != positions.len() {
+            return Err(VerifierError::NumPositionEvaluationMismatch(
+                positions.len(),
+                evaluations.len(),
+            ));
+        }
+
+        // 1 ----- verify the committed layers ------------------------------------------------------
+        let folding_factor = self.options.folding_factor();
+        let mut folded_evaluations = Vec::new();
+        for (depth, &domain_size) in
+            self.options.layer_domain_sizes(self.domain_size).iter().enumerate()
+        {
+            // static dispatch for folding factor parameter
+            folded_evaluations = match folding_factor {
+                2 => self.verify_layer::<2>(
+                    channel,
+                    depth,
+                    domain_size,
+                    &folded_evaluations,
+                    positions,
+                    evaluations,
+                ),
+                4 => self.verify_layer::<4>(
+                    channel,
+                    depth,
+                    domain_size,
+                    &folded_evaluations,
+                    positions,
+                    evaluations,
+                ),
+                8 => self.verify_layer::<8>(
+                    channel,
+                    depth,
+                    domain_size,
+                    &folded_evaluations,
+                    positions,
+                    evaluations,
+                ),
+                16 => self.verify_layer::<16>(
+                    channel,
+                    depth,
+                    domain_size,
+                    &folded_evaluations,
+                    positions,
+                    evaluations,
+                ),
+                _ => Err(VerifierError::UnsupportedFoldingFactor(folding_factor)),
+            }?;
+        }
+
+        // 2 ----- verify the remainder polynomial --------------------------------------------------
+        let remainder_poly = channel.read_remainder()?;
+        let num_layers = self.layer_commitments.len() - 1;
+        if H::hash_elements(&remainder_poly) != self.layer_commitments[num_layers] {
+            return Err(VerifierError::RemainderCommitmentMismatch);
+        }
+        let max_degree_plus_1 = (self.max_poly_degree + 1) / folding_factor.pow(num_layers as u32);
+        if remainder_poly.len() > max_degree_plus_1 {
+            return Err(VerifierError::RemainderDegreeMismatch(max_degree_plus_1 - 1));
+        }
+
+        let offset = self.options.domain_offset();
+        if num_layers == 0 {
+            // without any layers, the evaluations are checked against the remainder directly
+            let points = shift_points(positions, self.domain_size, 1, offset);
+            for ((&position, &evaluation), x) in positions.iter().zip(evaluations).zip(points) {
+                if eval_horner(&remainder_poly, x) != evaluation {
+                    return Err(VerifierError::InvalidRemainderFolding(position));
+                }
+            }
+        } else {
+            let last_positions = &self.shift_positions[num_layers - 1];
+            let last_domain_size = self.domain_size >> (num_layers - 1);
+            let points = shift_points(last_positions, last_domain_size, folding_factor, offset);
+            for ((&position, evaluation), x) in
+                last_positions.iter().zip(folded_evaluations).zip(points)
+            {
+                if eval_horner(&remainder_poly, x) != evaluation {
+                    return Err(VerifierError::InvalidRemainderFolding(position));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Verifies a single layer folded by a factor of N, and returns the folded values of the
+    /// layer function at the shift queries of the layer.
+    ///
+    /// `prev_folded_evaluations` are the folded values of the previous layer function at the
+    /// shift queries of the previous layer; `positions` and `evaluations` are the queried
+    /// positions and the evaluations at the first layer.
+    fn verify_layer<const N: usize>(
+        &self,
+        channel: &mut C,
+        depth: usize,
+        domain_size: usize,
+        prev_folded_evaluations: &[E],
+        positions: &[usize],
+        evaluations: &[E],
+    ) -> Result<Vec<E>, VerifierError> {
+        let shift_positions = &self.shift_positions[depth];
+        let offset = self.options.domain_offset();
+
+        // the first layer is opened at the queried positions in addition to the shift queries
+        let layer_positions = if depth == 0 {
+            let mut layer_positions = fold_positions(positions, domain_size, N);
+            for &position in shift_positions.iter() {
+                if !layer_positions.contains(&position) {
+                    layer_positions.push(position);
+                }
+            }
+            layer_positions
+        } else {
+            shift_positions.clone()
+        };
+        let layer_values = channel
+            .read_layer_queries::<N>(&layer_positions, &self.layer_commitments[depth])
+            .map_err(|_| VerifierError::LayerCommitmentMismatch(depth))?;
+
+        let row_length = domain_size / N;
+        if depth == 0 {
+            // make sure the evaluations match the values committed to at the first layer
+            for (&position, &evaluation) in positions.iter().zip(evaluations) {
+                let row_idx = layer_positions
+                    .iter()
+                    .position(|&p| p == position % row_length)
+                    .expect("position does not map to any of the layer positions");
+                if layer_values[row_idx][position / row_length] != evaluation {
+                    return Err(VerifierError::InvalidLayerFolding(depth, position));
+                }
+            }
+        }
+
+        // compute values of the layer function at the shift queries from the layer values
+        let mut rows = shift_positions
+            .iter()
+            .map(|&position| {
+                let row_idx = layer_positions.iter().position(|&p| p == position).unwrap();
+                layer_values[row_idx]
+            })
+            .collect::<Vec<_>>();
+        if depth > 0 {
+            let prev_domain_size = domain_size * 2;
+            let mut points = vec![self.ood_points[depth - 1]];
+            points.extend(
+                shift_points(&self.shift_positions[depth - 1], prev_domain_size, N, offset)
+                    .into_iter()
+                    .map(E::from),
+            );
+            let mut answers = vec![self.ood_evaluations[depth - 1]];
+            answers.extend_from_slice(prev_folded_evaluations);
+            let quotient = Quotient::new(points, &answers, self.comb_challenges[depth - 1])
+                .ok_or(VerifierError::ZeroQuotientDenominator(depth))?;
+
+            let xs = row_points::<E, N>(shift_positions, domain_size, offset);
+            for (row, xs) in rows.iter_mut().zip(xs) {
+                for (value, x) in row.iter_mut().zip(xs) {
+                    *value = quotient
+                        .evaluate(*value, x)
+                        .ok_or(VerifierError::ZeroQuotientDenominator(depth))?;
+                }
+            }
+        }
+
+        Ok(fold_rows(
+            &rows,
+            shift_positions,
+            domain_size,
+            offset,
+            self.fold_challenges[depth],
+        ))
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Draws shift queries from a folded domain of the specified size.
+fn draw_shift_positions<R: RandomCoin>(
+    public_coin: &mut R,
+    num_positions: usize,
+    domain_size: usize,
+) -> Result<Vec<usize>, VerifierError> {
+    let positions = public_coin
+        .draw_integers(num_positions, domain_size, 0)
+        .map_err(VerifierError::RandomCoinError)?;
+    Ok(dedup_positions(positions))
+}
@@ -37,6 +37,29 @@ pub fn map_positions_to_indexes(
     result
 }
 
+/// Returns the index of the value at the specified `position` of a FRI layer among the values
+/// queried at `folded_positions` of the layer.
+///
+/// Queried values of a layer are grouped into rows of `folding_factor` values each, with one row
+/// for each of the folded positions. The value at `position` is located in the row of the folded
+/// position which the `position` maps to.
+///
+/// # Panics
+/// Panics if `position` does not map to any of the `folded_positions`.
+pub fn get_query_value_index(
+    position: usize,
+    folded_positions: &[usize],
+    domain_size: usize,
+    folding_factor: usize,
+) -> usize {
+    let row_length = domain_size / folding_factor;
+    let row_idx = folded_positions
+        .iter()
+        .position(|&folded_position| folded_position == position % row_length)
+        .expect("position does not map to any of the folded positions");
+    row_idx * folding_factor + position / row_length
+}
+
 /// Hashes each of the arrays in the provided slice and returns a vector of resulting hashes.
 pub fn hash_values<H, E, const N: usize>(values: &[[E; N]]) -> Vec<H::Digest>
 where
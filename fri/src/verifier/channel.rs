@@ -3,8 +3,8 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use crate::{FriProof, VerifierError};
-use crypto::{BatchMerkleProof, ElementHasher, Hasher, MerkleTree};
+use crate::{FriOptions, FriProof, VerifierError};
+use crypto::{BatchMerkleProof, ElementHasher, Hasher, MerkleTree, MerkleTreeError};
 use math::FieldElement;
 use utils::{collections::Vec, group_vector_elements, DeserializationError};
 
@@ -40,6 +40,18 @@ pub trait VerifierChannel<E: FieldElement> {
         &mut self,
     ) -> Vec<<<Self as VerifierChannel<E>>::Hasher as Hasher>::Digest>;
 
+    /// Reads and removes from the channel proof-of-work nonces for all FRI layers.
+    ///
+    /// The returned list is expected to be empty if the prover did not perform per-layer
+    /// proof-of-work.
+    fn read_fri_layer_nonces(&mut self) -> Vec<u64>;
+
+    /// Reads and removes from the channel evaluations of layer polynomials at out-of-domain points.
+    ///
+    /// The returned list is expected to be empty for FRI proofs; for STIR proofs, it contains one
+    /// evaluation for every layer except for the first one.
+    fn read_fri_ood_evaluations(&mut self) -> Vec<E>;
+
     /// Reads and removes from the channel evaluations of the polynomial at the queried positions
     /// for the next FRI layer.
     ///
@@ -79,10 +91,9 @@ pub trait VerifierChannel<E: FieldElement> {
         &mut self,
         positions: &[usize],
         commitment: &<<Self as VerifierChannel<E>>::Hasher as Hasher>::Digest,
-    ) -> Result<Vec<[E; N]>, VerifierError> {
+    ) -> Result<Vec<[E; N]>, MerkleTreeError> {
         let layer_proof = self.take_next_fri_layer_proof();
-        MerkleTree::<Self::Hasher>::verify_batch(commitment, positions, &layer_proof)
-            .map_err(|_| VerifierError::LayerCommitmentMismatch)?;
+        MerkleTree::<Self::Hasher>::verify_batch(commitment, positions, &layer_proof)?;
 
         // TODO: make sure layer queries hash into leaves of layer proof
 
@@ -111,6 +122,8 @@ pub struct DefaultVerifierChannel<E: FieldElement, H: ElementHasher<BaseField =
     layer_commitments: Vec<H::Digest>,
     layer_proofs: Vec<BatchMerkleProof<H>>,
     layer_queries: Vec<Vec<E>>,
+    layer_nonces: Vec<u64>,
+    ood_evaluations: Vec<E>,
     remainder: Vec<E>,
     num_partitions: usize,
 }
@@ -122,28 +135,58 @@ where
 {
     /// Builds a new verifier channel from the specified [FriProof].
     ///
+    /// Folding factors and domain sizes of FRI layers are derived from the `options` for the
+    /// specified `domain_size`.
+    ///
     /// # Errors
     /// Returns an error if the specified `proof` could not be parsed correctly.
     pub fn new(
         proof: FriProof,
         layer_commitments: Vec<H::Digest>,
         domain_size: usize,
-        folding_factor: usize,
+        options: &FriOptions,
     ) -> Result<Self, DeserializationError> {
         let num_partitions = proof.num_partitions();
+        let layer_nonces = proof.layer_nonces().to_vec();
 
         let remainder = proof.parse_remainder()?;
+        let ood_evaluations = proof.parse_ood_evaluations()?;
         let (layer_queries, layer_proofs) =
-            proof.parse_layers::<H, E>(domain_size, folding_factor)?;
+            proof.parse_layers_with_options::<H, E>(domain_size, options)?;
 
         Ok(DefaultVerifierChannel {
             layer_commitments,
             layer_proofs,
             layer_queries,
+            layer_nonces,
+            ood_evaluations,
             remainder,
             num_partitions,
         })
     }
+
+    /// Builds a new verifier channel from the components of a FRI proof which have already been
+    /// parsed.
+    ///
+    /// Leaves of the `layer_proofs` are expected to be consistent with the `layer_queries`.
+    pub(crate) fn from_parts(
+        layer_commitments: Vec<H::Digest>,
+        layer_queries: Vec<Vec<E>>,
+        layer_proofs: Vec<BatchMerkleProof<H>>,
+        layer_nonces: Vec<u64>,
+        remainder: Vec<E>,
+        num_partitions: usize,
+    ) -> Self {
+        DefaultVerifierChannel {
+            layer_commitments,
+            layer_proofs,
+            layer_queries,
+            layer_nonces,
+            ood_evaluations: Vec::new(),
+            remainder,
+            num_partitions,
+        }
+    }
 }
 
 impl<E, H> VerifierChannel<E> for DefaultVerifierChannel<E, H>
@@ -161,6 +204,14 @@ where
         self.layer_commitments.drain(..).collect()
     }
 
+    fn read_fri_layer_nonces(&mut self) -> Vec<u64> {
+        self.layer_nonces.drain(..).collect()
+    }
+
+    fn read_fri_ood_evaluations(&mut self) -> Vec<E> {
+        self.ood_evaluations.drain(..).collect()
+    }
+
     fn take_next_fri_layer_proof(&mut self) -> BatchMerkleProof<H> {
         self.layer_proofs.remove(0)
     }
@@ -5,15 +5,22 @@
 
 //! Contains an implementation of FRI verifier and associated components.
 
-use crate::{folding::fold_positions, utils::map_positions_to_indexes, FriOptions, VerifierError};
-use core::{convert::TryInto, marker::PhantomData, mem};
-use crypto::{ElementHasher, RandomCoin};
+use crate::{
+    folding::fold_positions,
+    utils::{get_query_value_index, hash_values, map_positions_to_indexes},
+    FriOptions, VerifierError,
+};
+use core::{convert::TryInto, marker::PhantomData};
+use crypto::{ElementHasher, MerkleTree, RandomCoin};
 use math::{polynom, FieldElement, StarkField};
-use utils::collections::Vec;
+use utils::{collections::Vec, group_vector_elements};
 
 mod channel;
 pub use channel::{DefaultVerifierChannel, VerifierChannel};
 
+mod parts;
+pub use parts::{verify_proof_parts, FriProofParts};
+
 // FRI VERIFIER
 // ================================================================================================
 /// Implements the verifier component of the FRI protocol.
@@ -53,7 +60,7 @@ pub use channel::{DefaultVerifierChannel, VerifierChannel};
 /// * The evaluations are consistent across FRI layers (i.e., the degree-respecting projection
 ///   was applied correctly).
 /// * The degree of the polynomial implied by evaluations at the last FRI layer (the remainder)
-///   is smaller than the degree resulting from reducing degree *d* by `folding_factor` at each
+///   is smaller than the degree resulting from reducing degree *d* by the folding factor of each
 ///   FRI layer.
 pub struct FriVerifier<E, C, H, R>
 where
@@ -89,7 +96,8 @@ where
     /// Creating a FRI verifier executes the commit phase of the FRI protocol from the verifier's
     /// perspective. Specifically, the verifier reads FRI layer commitments from the `channel`,
     /// and for each commitment, updates the `public_coin` with this commitment and then draws
-    /// a random value α from the coin.
+    /// a random value α from the coin. No α is drawn for the commitment to the remainder, since
+    /// the remainder is not folded.
     ///
     /// The verifier stores layer commitments and corresponding α values in its internal state,
     /// and, thus, an instance of FRI verifier can be used to verify only a single proof.
@@ -99,6 +107,9 @@ where
     /// * `max_poly_degree` is inconsistent with the number of FRI layers read from the channel
     ///   and `folding_factor` specified in the `options` parameter.
     /// * An error was encountered while drawing a random α value from the coin.
+    /// * The number of per-layer proof-of-work nonces read from the channel is inconsistent with
+    ///   the number of FRI layers and the `layer_grinding_factor` specified in the `options`.
+    /// * Any of the per-layer proof-of-work nonces does not satisfy the `layer_grinding_factor`.
     pub fn new(
         channel: &mut C,
         public_coin: &mut R,
@@ -114,24 +125,52 @@ where
         // read layer commitments from the channel and use them to build a list of alphas
         let layer_commitments = channel.read_fri_layer_commitments();
         let mut layer_alphas = Vec::with_capacity(layer_commitments.len());
+        let folding_factors = options.layer_folding_factors(domain_size);
+
+        // when per-layer proof-of-work is required, there must be a nonce for every layer but
+        // the remainder layer
+        let layer_nonces = channel.read_fri_layer_nonces();
+        let grinding_factor = options.layer_grinding_factor();
+        let num_expected_nonces = if grinding_factor > 0 {
+            layer_commitments.len().saturating_sub(1)
+        } else {
+            0
+        };
+        if layer_nonces.len() != num_expected_nonces {
+            return Err(VerifierError::NumLayerNoncesMismatch(
+                num_expected_nonces,
+                layer_nonces.len(),
+            ));
+        }
+
         let mut max_degree_plus_1 = max_poly_degree + 1;
         for (depth, commitment) in layer_commitments.iter().enumerate() {
             public_coin.reseed(*commitment);
-            let alpha = public_coin.draw().map_err(VerifierError::RandomCoinError)?;
-            layer_alphas.push(alpha);
+            if let Some(&nonce) = layer_nonces.get(depth) {
+                if !public_coin.check_proof_of_work(nonce, grinding_factor) {
+                    return Err(VerifierError::LayerProofOfWorkVerificationFailed(depth));
+                }
+                public_coin.reseed_with_int(nonce);
+            }
+            // the last commitment is to the remainder, which is not folded; thus, like the
+            // prover, we do not draw an alpha for it
+            if depth < layer_commitments.len() - 1 {
+                let alpha = public_coin.draw().map_err(VerifierError::RandomCoinError)?;
+                layer_alphas.push(alpha);
+            }
 
             // make sure the degree can be reduced by the folding factor at all layers
             // but the remainder layer
-            if depth != layer_commitments.len() - 1
-                && max_degree_plus_1 % options.folding_factor() != 0
-            {
+            let folding_factor =
+                folding_factors.get(depth).copied().unwrap_or(options.folding_factor());
+            if depth != layer_commitments.len() - 1 && max_degree_plus_1 % folding_factor != 0 {
                 return Err(VerifierError::DegreeTruncation(
                     max_degree_plus_1 - 1,
-                    options.folding_factor(),
+                    folding_factor,
                     depth,
                 ));
             }
-            max_degree_plus_1 /= options.folding_factor();
+            max_degree_plus_1 /= folding_factor;
         }
 
         Ok(FriVerifier {
@@ -214,30 +253,6 @@ where
             ));
         }
 
-        // static dispatch for folding factor parameter
-        let folding_factor = self.options.folding_factor();
-        match folding_factor {
-            2 => self.verify_generic::<2>(channel, evaluations, positions),
-            4 => self.verify_generic::<4>(channel, evaluations, positions),
-            8 => self.verify_generic::<8>(channel, evaluations, positions),
-            16 => self.verify_generic::<16>(channel, evaluations, positions),
-            _ => Err(VerifierError::UnsupportedFoldingFactor(folding_factor)),
-        }
-    }
-
-    /// This is the actual implementation of the verification procedure described above, but it
-    /// also takes folding factor as a generic parameter N.
-    fn verify_generic<const N: usize>(
-        &self,
-        channel: &mut C,
-        evaluations: &[E],
-        positions: &[usize],
-    ) -> Result<(), VerifierError> {
-        // pre-compute roots of unity used in computing x coordinates in the folded domain
-        let folding_roots = (0..N)
-            .map(|i| self.domain_generator.exp_vartime(((self.domain_size / N * i) as u64).into()))
-            .collect::<Vec<_>>();
-
         // 1 ----- verify the recursive components of the FRI proof -----------------------------------
         let mut domain_generator = self.domain_generator;
         let mut domain_size = self.domain_size;
@@ -245,57 +260,34 @@ where
         let mut positions = positions.to_vec();
         let mut evaluations = evaluations.to_vec();
 
-        for depth in 0..self.options.num_fri_layers(self.domain_size) {
-            // determine which evaluations were queried in the folded layer
-            let mut folded_positions =
-                fold_positions(&positions, domain_size, self.options.folding_factor());
-            // determine where these evaluations are in the commitment Merkle tree
-            let position_indexes = map_positions_to_indexes(
-                &folded_positions,
-                domain_size,
-                self.options.folding_factor(),
-                self.num_partitions,
-            );
-            // read query values from the specified indexes in the Merkle tree
-            let layer_commitment = self.layer_commitments[depth];
-            // TODO: add layer depth to the potential error message
-            let layer_values = channel.read_layer_queries(&position_indexes, &layer_commitment)?;
-            let query_values =
-                get_query_values::<E, N>(&layer_values, &positions, &folded_positions, domain_size);
-            if evaluations != query_values {
-                return Err(VerifierError::InvalidLayerFolding(depth));
-            }
-
-            // build a set of x coordinates for each row polynomial
-            #[rustfmt::skip]
-            let xs = folded_positions.iter().map(|&i| {
-                let xe = domain_generator.exp_vartime((i as u64).into()) * self.options.domain_offset();
-                folding_roots.iter()
-                    .map(|&r| E::from(xe * r))
-                    .collect::<Vec<_>>().try_into().unwrap()
-            })
-            .collect::<Vec<_>>();
-
-            // interpolate x and y values into row polynomials
-            let row_polys = polynom::interpolate_batch(&xs, &layer_values);
-
-            // calculate the pseudo-random value used for linear combination in layer folding
-            let alpha = self.layer_alphas[depth];
-
-            // check that when the polynomials are evaluated at alpha, the result is equal to
-            // the corresponding column value
-            evaluations = row_polys.iter().map(|p| polynom::eval(p, alpha)).collect();
+        let folding_factors = self.options.layer_folding_factors(self.domain_size);
+        for (depth, &folding_factor) in folding_factors.iter().enumerate() {
+            // static dispatch for folding factor parameter
+            let (folded_positions, folded_evaluations) = match folding_factor {
+                2 => self.verify_layer::<2>(channel, depth, domain_size, &positions, &evaluations),
+                4 => self.verify_layer::<4>(channel, depth, domain_size, &positions, &evaluations),
+                8 => self.verify_layer::<8>(channel, depth, domain_size, &positions, &evaluations),
+                16 => {
+                    self.verify_layer::<16>(channel, depth, domain_size, &positions, &evaluations)
+                }
+                _ => Err(VerifierError::UnsupportedFoldingFactor(folding_factor)),
+            }?;
 
             // make sure next degree reduction does not result in degree truncation
-            if max_degree_plus_1 % N != 0 {
-                return Err(VerifierError::DegreeTruncation(max_degree_plus_1 - 1, N, depth));
+            if max_degree_plus_1 % folding_factor != 0 {
+                return Err(VerifierError::DegreeTruncation(
+                    max_degree_plus_1 - 1,
+                    folding_factor,
+                    depth,
+                ));
             }
 
             // update variables for the next iteration of the loop
-            domain_generator = domain_generator.exp_vartime((N as u32).into());
-            max_degree_plus_1 /= N;
-            domain_size /= N;
-            mem::swap(&mut positions, &mut folded_positions);
+            domain_generator = domain_generator.exp_vartime((folding_factor as u32).into());
+            max_degree_plus_1 /= folding_factor;
+            domain_size /= folding_factor;
+            positions = folded_positions;
+            evaluations = folded_evaluations;
         }
 
         // 2 ----- verify the remainder polynomial of the FRI proof -------------------------------
@@ -314,12 +306,95 @@ where
                 offset * domain_generator.exp_vartime((position as u64).into()),
             );
             if comp_eval != evaluation {
-                return Err(VerifierError::InvalidRemainderFolding);
+                return Err(VerifierError::InvalidRemainderFolding(position));
             }
         }
 
         Ok(())
     }
+
+    /// Verifies a single FRI layer folded by a factor of N, and returns the positions and the
+    /// evaluations queried in the next layer.
+    ///
+    /// `domain_size` is the size of the domain of the layer at the specified `depth`, while
+    /// `positions` and `evaluations` are the queried positions in this domain and the evaluations
+    /// at these positions computed from the previous layer.
+    fn verify_layer<const N: usize>(
+        &self,
+        channel: &mut C,
+        depth: usize,
+        domain_size: usize,
+        positions: &[usize],
+        evaluations: &[E],
+    ) -> Result<(Vec<usize>, Vec<E>), VerifierError> {
+        let domain_generator = E::BaseField::get_root_of_unity(domain_size.ilog2());
+
+        // pre-compute roots of unity used in computing x coordinates in the folded domain
+        let folding_roots = (0..N)
+            .map(|i| domain_generator.exp_vartime(((domain_size / N * i) as u64).into()))
+            .collect::<Vec<_>>();
+
+        // determine which evaluations were queried in the folded layer
+        let folded_positions = fold_positions(positions, domain_size, N);
+        // determine where these evaluations are in the commitment Merkle tree
+        let position_indexes =
+            map_positions_to_indexes(&folded_positions, domain_size, N, self.num_partitions);
+        // read query values from the specified indexes in the Merkle tree
+        let layer_commitment = self.layer_commitments[depth];
+        let layer_values = if self.options.query_deduplication() {
+            // values at the queried positions were omitted by the prover; restore them from the
+            // evaluations before checking the values against the commitment
+            let layer_values = restore_layer_values::<E, N>(
+                channel.take_next_fri_layer_queries(),
+                depth,
+                domain_size,
+                positions,
+                evaluations,
+                &folded_positions,
+            )?;
+            let mut layer_proof = channel.take_next_fri_layer_proof();
+            layer_proof.leaves = hash_values::<C::Hasher, E, N>(&layer_values);
+            MerkleTree::<C::Hasher>::verify_batch(
+                &layer_commitment,
+                &position_indexes,
+                &layer_proof,
+            )
+            .map_err(|_| VerifierError::LayerCommitmentMismatch(depth))?;
+            layer_values
+        } else {
+            let layer_values = channel
+                .read_layer_queries(&position_indexes, &layer_commitment)
+                .map_err(|_| VerifierError::LayerCommitmentMismatch(depth))?;
+            let query_values =
+                get_query_values::<E, N>(&layer_values, positions, &folded_positions, domain_size);
+            if let Some(i) = evaluations.iter().zip(query_values.iter()).position(|(a, b)| a != b) {
+                return Err(VerifierError::InvalidLayerFolding(depth, positions[i]));
+            }
+            layer_values
+        };
+
+        // build a set of x coordinates for each row polynomial
+        #[rustfmt::skip]
+        let xs = folded_positions.iter().map(|&i| {
+            let xe = domain_generator.exp_vartime((i as u64).into()) * self.options.domain_offset();
+            folding_roots.iter()
+                .map(|&r| E::from(xe * r))
+                .collect::<Vec<_>>().try_into().unwrap()
+        })
+        .collect::<Vec<_>>();
+
+        // interpolate x and y values into row polynomials
+        let row_polys = polynom::interpolate_batch(&xs, &layer_values);
+
+        // calculate the pseudo-random value used for linear combination in layer folding
+        let alpha = self.layer_alphas[depth];
+
+        // check that when the polynomials are evaluated at alpha, the result is equal to
+        // the corresponding column value
+        let folded_evaluations = row_polys.iter().map(|p| polynom::eval(p, alpha)).collect();
+
+        Ok((folded_positions, folded_evaluations))
+    }
 }
 
 // HELPER FUNCTIONS
@@ -342,6 +417,43 @@ fn get_query_values<E: FieldElement, const N: usize>(
     result
 }
 
+/// Restores values queried at a FRI layer by inserting `evaluations` at the specified
+/// `positions` of the layer into the `sent_values` which the prover sent for the layer.
+///
+/// Returns an error if evaluations at the same position are not equal, or if the number of sent
+/// values is inconsistent with the number of omitted values.
+fn restore_layer_values<E: FieldElement, const N: usize>(
+    sent_values: Vec<E>,
+    depth: usize,
+    domain_size: usize,
+    positions: &[usize],
+    evaluations: &[E],
+    folded_positions: &[usize],
+) -> Result<Vec<[E; N]>, VerifierError> {
+    let mut values = vec![None; folded_positions.len() * N];
+    for (&position, &evaluation) in positions.iter().zip(evaluations) {
+        let idx = get_query_value_index(position, folded_positions, domain_size, N);
+        match values[idx] {
+            Some(value) if value != evaluation => {
+                return Err(VerifierError::InvalidLayerFolding(depth, position))
+            }
+            _ => values[idx] = Some(evaluation),
+        }
+    }
+
+    let mut sent_values = sent_values.into_iter();
+    let values = values
+        .into_iter()
+        .map(|value| value.or_else(|| sent_values.next()))
+        .collect::<Option<Vec<E>>>()
+        .ok_or(VerifierError::LayerCommitmentMismatch(depth))?;
+    if sent_values.next().is_some() {
+        return Err(VerifierError::LayerCommitmentMismatch(depth));
+    }
+
+    Ok(group_vector_elements(values))
+}
+
 // Evaluates a polynomial with coefficients in an extension field at a point in the base field.
 pub fn eval_horner<E>(p: &[E], x: E::BaseField) -> E
 where
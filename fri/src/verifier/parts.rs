@@ -0,0 +1,177 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{DefaultVerifierChannel, FriVerifier};
+use crate::{FriOptions, FriProof, LowDegreeTest, VerifierError};
+use crypto::{BatchMerkleProof, ElementHasher, RandomCoin};
+use math::FieldElement;
+use utils::{collections::Vec, DeserializationError};
+
+// FRI PROOF PARTS
+// ================================================================================================
+
+/// Components of a FRI proof supplied to the verifier separately from each other.
+///
+/// A [FriProof] packs layer openings and the remainder into a single serialized structure, while
+/// layer commitments are supplied to the verifier separately. When a FRI proof is split into
+/// pieces which are committed to or serialized independently (e.g., when the proof is spread
+/// across several transactions of an on-chain verifier), these pieces can be assembled into
+/// [FriProofParts] and verified via [verify_proof_parts()] without re-encoding them as a
+/// [FriProof].
+///
+/// Layer openings are expected in the same form as they are returned by
+/// [FriProof::parse_layers_with_options()]: for every layer except for the remainder layer,
+/// `layer_queries` contains the queried values of the layer (`folding_factor` values for every
+/// queried leaf, in the order of leaves in the corresponding Merkle proof), and `layer_proofs`
+/// contains a batch Merkle proof for the queried leaves. Leaves of the Merkle proofs are ignored:
+/// the verifier computes them from the queried values.
+pub struct FriProofParts<E, H>
+where
+    E: FieldElement,
+    H: ElementHasher<BaseField = E::BaseField>,
+{
+    /// Commitments to all FRI layers, including the commitment to the remainder polynomial.
+    pub layer_commitments: Vec<H::Digest>,
+    /// Values queried at each FRI layer except for the remainder layer.
+    pub layer_queries: Vec<Vec<E>>,
+    /// Batch Merkle proofs for the leaves queried at each FRI layer except for the remainder
+    /// layer.
+    pub layer_proofs: Vec<BatchMerkleProof<H>>,
+    /// Proof-of-work nonces for all FRI layers; empty if per-layer proof-of-work is not used.
+    pub layer_nonces: Vec<u64>,
+    /// Coefficients of the remainder polynomial.
+    pub remainder: Vec<E>,
+    /// Number of partitions used during proof generation.
+    pub num_partitions: usize,
+}
+
+impl<E, H> FriProofParts<E, H>
+where
+    E: FieldElement,
+    H: ElementHasher<BaseField = E::BaseField>,
+{
+    /// Splits the specified [FriProof] into its components.
+    ///
+    /// Folding factors and domain sizes of FRI layers are derived from the `options` for the
+    /// specified `domain_size`.
+    ///
+    /// # Errors
+    /// Returns an error if the specified `proof` could not be parsed correctly.
+    pub fn from_proof(
+        proof: FriProof,
+        layer_commitments: Vec<H::Digest>,
+        domain_size: usize,
+        options: &FriOptions,
+    ) -> Result<Self, DeserializationError> {
+        let num_partitions = proof.num_partitions();
+        let layer_nonces = proof.layer_nonces().to_vec();
+        let remainder = proof.parse_remainder()?;
+        let (layer_queries, layer_proofs) =
+            proof.parse_layers_with_options::<H, E>(domain_size, options)?;
+
+        Ok(FriProofParts {
+            layer_commitments,
+            layer_queries,
+            layer_proofs,
+            layer_nonces,
+            remainder,
+            num_partitions,
+        })
+    }
+}
+
+// VERIFICATION
+// ================================================================================================
+
+/// Verifies a FRI proof supplied as separate [FriProofParts].
+///
+/// Returns `Ok(())` if values in the `evaluations` slice represent evaluations of a polynomial
+/// with degree <= `max_poly_degree` at the specified `positions`. The commit phase of the
+/// protocol is executed against the `public_coin` exactly as in [FriVerifier::new()], and thus,
+/// the coin must be in the same state as the coin used by the prover before the first layer
+/// commitment was absorbed.
+///
+/// Unlike verification of a deserialized [FriProof], the number of layer openings is not implied
+/// by the proof encoding, and thus, the number of supplied commitments and openings is checked
+/// against the `options` before verification; the remainder is also checked against the last
+/// layer commitment.
+///
+/// # Errors
+/// Returns an error if:
+/// * The `options` specify a low-degree test other than FRI.
+/// * The number of layer commitments, layer openings, or layer proof-of-work nonces is
+///   inconsistent with the `options` and `max_poly_degree`.
+/// * The remainder does not match the last layer commitment.
+/// * Verification of the proof fails for any of the reasons described in [FriVerifier::new()]
+///   and [FriVerifier::verify()].
+pub fn verify_proof_parts<E, H, R>(
+    parts: FriProofParts<E, H>,
+    public_coin: &mut R,
+    options: FriOptions,
+    max_poly_degree: usize,
+    evaluations: &[E],
+    positions: &[usize],
+) -> Result<(), VerifierError>
+where
+    E: FieldElement,
+    H: ElementHasher<BaseField = E::BaseField>,
+    R: RandomCoin<BaseField = E::BaseField, Hasher = H>,
+{
+    if options.low_degree_test() != LowDegreeTest::Fri {
+        return Err(VerifierError::UnsupportedLowDegreeTest);
+    }
+
+    // there must be a commitment for every layer and for the remainder, and an opening for every
+    // layer but the remainder layer
+    let domain_size = max_poly_degree.next_power_of_two() * options.blowup_factor();
+    let folding_factors = options.layer_folding_factors(domain_size);
+    let num_layers = folding_factors.len();
+    if parts.layer_commitments.len() != num_layers + 1 {
+        return Err(VerifierError::NumLayerCommitmentsMismatch(
+            num_layers + 1,
+            parts.layer_commitments.len(),
+        ));
+    }
+    if parts.layer_queries.len() != num_layers {
+        return Err(VerifierError::NumLayerOpeningsMismatch(num_layers, parts.layer_queries.len()));
+    }
+    if parts.layer_proofs.len() != num_layers {
+        return Err(VerifierError::NumLayerOpeningsMismatch(num_layers, parts.layer_proofs.len()));
+    }
+
+    let remainder_commitment = H::hash_elements(&parts.remainder);
+    if parts.layer_commitments[num_layers] != remainder_commitment {
+        return Err(VerifierError::RemainderCommitmentMismatch);
+    }
+
+    // bind the queried values to the Merkle proofs; for deduplicated layers, the leaves are
+    // computed by the verifier after the omitted values are restored
+    let mut layer_proofs = parts.layer_proofs;
+    if !options.query_deduplication() {
+        for (depth, ((layer_proof, layer_queries), &folding_factor)) in layer_proofs
+            .iter_mut()
+            .zip(&parts.layer_queries)
+            .zip(&folding_factors)
+            .enumerate()
+        {
+            if layer_queries.len() != layer_proof.leaves.len() * folding_factor {
+                return Err(VerifierError::LayerCommitmentMismatch(depth));
+            }
+            layer_proof.leaves =
+                layer_queries.chunks(folding_factor).map(H::hash_elements).collect();
+        }
+    }
+
+    let mut channel = DefaultVerifierChannel::from_parts(
+        parts.layer_commitments,
+        parts.layer_queries,
+        layer_proofs,
+        parts.layer_nonces,
+        parts.remainder,
+        parts.num_partitions,
+    );
+    let verifier = FriVerifier::new(&mut channel, public_coin, options, max_poly_degree)?;
+    verifier.verify(&mut channel, evaluations, positions)
+}
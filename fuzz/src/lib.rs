@@ -0,0 +1,277 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+//! Fuzzing harnesses for Winterfell proof deserialization and verification.
+//!
+//! Each harness accepts arbitrary bytes and must never panic: malformed inputs are expected to be
+//! rejected with an error. The harnesses are invoked by the fuzz targets in `fuzz_targets`
+//! directory, but they can also be called directly (e.g., to replay a crashing input).
+
+use std::sync::OnceLock;
+use winter_fri::FriProof;
+use winterfell::{
+    crypto::{hashers::Blake3_256, BatchMerkleProof, DefaultRandomCoin, Hasher, MerkleTree},
+    math::{fields::f128::BaseElement, FieldElement},
+    matrix::ColMatrix,
+    AcceptableOptions, Air, AirContext, Assertion, AuxTraceRandElements,
+    ConstraintCompositionCoefficients, DefaultConstraintEvaluator, DefaultTraceLde, Deserializable,
+    EvaluationFrame, FieldExtension, ProofOptions, Prover, SliceReader, StarkDomain, StarkProof,
+    Trace, TraceInfo, TracePolyTable, TraceTable, TransitionConstraintDegree,
+};
+
+// CONSTANTS
+// ================================================================================================
+
+type Hash = Blake3_256<BaseElement>;
+
+/// Number of steps in the execution trace of the computation proven by the seed proof.
+const TRACE_LENGTH: usize = 64;
+
+/// Blowup factor used to generate the seed proof.
+const BLOWUP_FACTOR: usize = 8;
+
+/// Folding factor used to generate the seed proof.
+const FRI_FOLDING_FACTOR: usize = 4;
+
+/// Number of leaves in the Merkle tree against which batch proofs are verified.
+const NUM_MERKLE_LEAVES: usize = 64;
+
+/// Number of bytes describing a single mutation of the seed proof: a 4-byte little-endian offset
+/// followed by a byte which is XORed into the proof at this offset.
+const MUTATION_SIZE: usize = 5;
+
+// HARNESSES
+// ================================================================================================
+
+/// Deserializes a proof from `data` and checks that a successfully deserialized proof survives a
+/// serialization round-trip.
+pub fn proof_deserialization(data: &[u8]) {
+    if let Ok(proof) = StarkProof::from_bytes(data) {
+        let bytes = proof.to_bytes();
+        let parsed = StarkProof::from_bytes(&bytes).expect("failed to re-read serialized proof");
+        assert_eq!(bytes, parsed.to_bytes(), "proof serialization is not stable");
+    }
+}
+
+/// Deserializes a FRI proof from `data` and parses its layers, remainder, and out-of-domain
+/// evaluations against the parameters of the seed proof.
+pub fn fri_proof_parsing(data: &[u8]) {
+    let proof = match FriProof::read_from_bytes(data) {
+        Ok(proof) => proof,
+        Err(_) => return,
+    };
+
+    let _ = proof.parse_remainder::<BaseElement>();
+    let _ = proof.parse_ood_evaluations::<BaseElement>();
+    let _ = proof.num_partitions();
+    let domain_size = TRACE_LENGTH * BLOWUP_FACTOR;
+    let _ = proof.parse_layers::<Hash, BaseElement>(domain_size, FRI_FOLDING_FACTOR);
+}
+
+/// Verifies a batch Merkle proof read from `data` against a fixed Merkle tree.
+///
+/// The first byte of `data` specifies the number of opened leaves `n`, the next `n` bytes specify
+/// the indexes of these leaves, and the remaining bytes are parsed as the internal nodes of the
+/// batch proof.
+pub fn merkle_batch_verification(data: &[u8]) {
+    let tree = merkle_tree();
+    let (&num_indexes, data) = match data.split_first() {
+        Some(value) => value,
+        None => return,
+    };
+    if data.len() < num_indexes as usize {
+        return;
+    }
+    let (index_bytes, node_bytes) = data.split_at(num_indexes as usize);
+
+    let indexes = index_bytes
+        .iter()
+        .map(|&index| index as usize % NUM_MERKLE_LEAVES)
+        .collect::<Vec<_>>();
+    let leaves = indexes.iter().map(|&index| tree.leaves()[index]).collect::<Vec<_>>();
+    let depth = tree.depth() as u8;
+
+    let mut reader = SliceReader::new(node_bytes);
+    if let Ok(proof) = BatchMerkleProof::<Hash>::deserialize(&mut reader, leaves, depth) {
+        let _ = MerkleTree::verify_batch(tree.root(), &indexes, &proof);
+    }
+}
+
+/// Applies mutations specified by `data` to a valid seed proof, and verifies the mutated proof.
+///
+/// Every 5 bytes of `data` describe a single mutation: a 4-byte little-endian offset (reduced
+/// modulo the length of the serialized proof), and a byte which is XORed into the serialized
+/// proof at this offset. The seed proof is verified as well if `data` contains no mutations.
+pub fn verify_mutated_proof(data: &[u8]) {
+    let mut bytes = seed_proof().to_bytes();
+    for mutation in data.chunks_exact(MUTATION_SIZE) {
+        let offset = u32::from_le_bytes([mutation[0], mutation[1], mutation[2], mutation[3]]);
+        let offset = offset as usize % bytes.len();
+        bytes[offset] ^= mutation[4];
+    }
+
+    let proof = match StarkProof::from_bytes(&bytes) {
+        Ok(proof) => proof,
+        Err(_) => return,
+    };
+
+    // AIR implementations are trusted to reject execution traces of unexpected shape; thus,
+    // proofs for traces of a different shape are not passed to the verifier
+    if proof.get_trace_info().layout() != seed_proof().get_trace_info().layout() {
+        return;
+    }
+
+    let result = winterfell::verify::<FibAir, Hash, DefaultRandomCoin<Hash>>(
+        proof,
+        fib_result(),
+        &AcceptableOptions::MinConjecturedSecurity(0),
+    );
+    if data.len() < MUTATION_SIZE {
+        result.expect("failed to verify seed proof");
+    }
+}
+
+/// Returns a valid proof which serves as the starting point for mutations.
+pub fn seed_proof() -> &'static StarkProof {
+    static PROOF: OnceLock<StarkProof> = OnceLock::new();
+    PROOF.get_or_init(|| {
+        let options =
+            ProofOptions::new(28, BLOWUP_FACTOR, 0, FieldExtension::None, FRI_FOLDING_FACTOR, 7);
+        let prover = FibProver { options };
+        prover.prove(prover.build_trace()).expect("failed to generate seed proof")
+    })
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns a Merkle tree built from deterministically generated leaves.
+fn merkle_tree() -> &'static MerkleTree<Hash> {
+    static TREE: OnceLock<MerkleTree<Hash>> = OnceLock::new();
+    TREE.get_or_init(|| {
+        let leaves = (0..NUM_MERKLE_LEAVES as u64)
+            .map(|i| Hash::hash(&i.to_le_bytes()))
+            .collect::<Vec<_>>();
+        MerkleTree::new(leaves).expect("failed to build Merkle tree")
+    })
+}
+
+/// Returns the last term of the Fibonacci sequence computed by the seed proof.
+fn fib_result() -> BaseElement {
+    let mut state = [BaseElement::ONE, BaseElement::ONE];
+    for _ in 1..TRACE_LENGTH {
+        state[0] += state[1];
+        state[1] += state[0];
+    }
+    state[1]
+}
+
+// FIBONACCI AIR
+// ================================================================================================
+
+/// AIR of a computation which advances a Fibonacci sequence by 2 terms in each step.
+struct FibAir {
+    context: AirContext<BaseElement>,
+    result: BaseElement,
+}
+
+impl Air for FibAir {
+    type BaseField = BaseElement;
+    type PublicInputs = BaseElement;
+
+    fn new(trace_info: TraceInfo, pub_inputs: BaseElement, options: ProofOptions) -> Self {
+        let degrees = vec![TransitionConstraintDegree::new(1), TransitionConstraintDegree::new(1)];
+        FibAir {
+            context: AirContext::new(trace_info, degrees, 3, options),
+            result: pub_inputs,
+        }
+    }
+
+    fn context(&self) -> &AirContext<Self::BaseField> {
+        &self.context
+    }
+
+    fn evaluate_transition<E: FieldElement + From<Self::BaseField>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        _periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        let current = frame.current();
+        let next = frame.next();
+        result[0] = next[0] - (current[0] + current[1]);
+        result[1] = next[1] - (current[1] + next[0]);
+    }
+
+    fn get_assertions(&self) -> Vec<Assertion<Self::BaseField>> {
+        let last_step = self.trace_length() - 1;
+        vec![
+            Assertion::single(0, 0, BaseElement::ONE),
+            Assertion::single(1, 0, BaseElement::ONE),
+            Assertion::single(1, last_step, self.result),
+        ]
+    }
+}
+
+// FIBONACCI PROVER
+// ================================================================================================
+
+struct FibProver {
+    options: ProofOptions,
+}
+
+impl FibProver {
+    fn build_trace(&self) -> TraceTable<BaseElement> {
+        let mut trace = TraceTable::new(2, TRACE_LENGTH);
+        trace.fill(
+            |state| {
+                state[0] = BaseElement::ONE;
+                state[1] = BaseElement::ONE;
+            },
+            |_, state| {
+                state[0] += state[1];
+                state[1] += state[0];
+            },
+        );
+        trace
+    }
+}
+
+impl Prover for FibProver {
+    type BaseField = BaseElement;
+    type Air = FibAir;
+    type Trace = TraceTable<BaseElement>;
+    type HashFn = Hash;
+    type RandomCoin = DefaultRandomCoin<Hash>;
+    type TraceLde<E: FieldElement<BaseField = Self::BaseField>> = DefaultTraceLde<E, Self::HashFn>;
+    type ConstraintEvaluator<'a, E: FieldElement<BaseField = Self::BaseField>> =
+        DefaultConstraintEvaluator<'a, Self::Air, E>;
+
+    fn get_pub_inputs(&self, trace: &Self::Trace) -> BaseElement {
+        trace.get(1, trace.length() - 1)
+    }
+
+    fn options(&self) -> &ProofOptions {
+        &self.options
+    }
+
+    fn new_trace_lde<E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        trace_info: &TraceInfo,
+        main_trace: &ColMatrix<Self::BaseField>,
+        domain: &StarkDomain<Self::BaseField>,
+    ) -> (Self::TraceLde<E>, TracePolyTable<E>) {
+        DefaultTraceLde::new(trace_info, main_trace, domain)
+    }
+
+    fn new_evaluator<'a, E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        air: &'a Self::Air,
+        aux_rand_elements: AuxTraceRandElements<E>,
+        composition_coefficients: ConstraintCompositionCoefficients<E>,
+    ) -> Self::ConstraintEvaluator<'a, E> {
+        DefaultConstraintEvaluator::new(air, aux_rand_elements, composition_coefficients)
+    }
+}
@@ -13,6 +13,7 @@ use winter_math::{
 };
 
 const SIZES: [usize; 3] = [262_144, 524_288, 1_048_576];
+const POLY_BATCH_SIZES: [usize; 2] = [65_536, 262_144];
 
 fn fft_evaluate_poly<B, E>(c: &mut Criterion, field_name: &str)
 where
@@ -87,6 +88,40 @@ where
     group.finish();
 }
 
+fn fft_interpolate_polys<B, E>(c: &mut Criterion, field_name: &str)
+where
+    B: StarkField,
+    E: FieldElement<BaseField = B>,
+{
+    let mut group = c.benchmark_group(format!("{field_name}/fft_interpolate_polys"));
+    group.sample_size(10);
+    group.measurement_time(Duration::from_secs(10));
+
+    // a trace-like batch of many columns; interpolating each polynomial separately is compared
+    // against interpolating all of them in a single pass
+    let num_polys = 100;
+    for &size in POLY_BATCH_SIZES.iter() {
+        let polys: Vec<Vec<E>> = (0..num_polys).map(|_| rand_vector(size)).collect();
+        let inv_twiddles: Vec<B> = fft::get_inv_twiddles(size);
+        group.bench_function(BenchmarkId::new("per_poly", size), |bench| {
+            bench.iter_batched_ref(
+                || polys.clone(),
+                |polys| polys.iter_mut().for_each(|p| fft::interpolate_poly(p, &inv_twiddles)),
+                BatchSize::LargeInput,
+            );
+        });
+        group.bench_function(BenchmarkId::new("batched", size), |bench| {
+            bench.iter_batched_ref(
+                || polys.clone(),
+                |polys| fft::interpolate_polys(polys, &inv_twiddles),
+                BatchSize::LargeInput,
+            );
+        });
+    }
+
+    group.finish();
+}
+
 fn get_twiddles(c: &mut Criterion) {
     let mut group = c.benchmark_group("fft_get_twiddles");
     group.sample_size(10);
@@ -112,6 +147,8 @@ fn bench_fft(c: &mut Criterion) {
     fft_interpolate_poly::<f62::BaseElement, f62::BaseElement>(c, "f62");
     fft_interpolate_poly::<f64::BaseElement, f64::BaseElement>(c, "f64");
     fft_interpolate_poly::<f128::BaseElement, f128::BaseElement>(c, "f128");
+
+    fft_interpolate_polys::<f64::BaseElement, f64::BaseElement>(c, "f64");
 }
 
 criterion_group!(fft_group, bench_fft, get_twiddles);
@@ -253,6 +253,58 @@ pub fn batch_ops<E: FieldElement, M: Measurement>(group: &mut BenchmarkGroup<M>,
     });
 }
 
+// MONTGOMERY REDUCTION
+// ================================================================================================
+
+/// Compares Montgomery reduction strategies of the 64-bit field. Each iteration performs a chain
+/// of dependent multiplications so that latency of the reduction is measured.
+pub fn f64_reduction(c: &mut Criterion) {
+    let mut group = c.benchmark_group("f64_reduction");
+
+    for reduction in f64::Reduction::ALL {
+        // the strategy used by the field is marked in the benchmark ID
+        let id = if reduction == f64::Reduction::SELECTED {
+            BenchmarkId::new("selected", reduction)
+        } else {
+            BenchmarkId::from_parameter(reduction)
+        };
+        group.bench_function(id, |bench| {
+            let x = rand_value::<u64>() % f64::BaseElement::MODULUS;
+            let y = rand_value::<u64>() % f64::BaseElement::MODULUS;
+            // dispatching on a constant allows the compiler to inline each strategy
+            match reduction {
+                f64::Reduction::Shift => {
+                    bench_reduction_chain(bench, x, y, |x| f64::Reduction::Shift.reduce(x))
+                }
+                f64::Reduction::Mul => {
+                    bench_reduction_chain(bench, x, y, |x| f64::Reduction::Mul.reduce(x))
+                }
+                f64::Reduction::AddWithCarry => {
+                    bench_reduction_chain(bench, x, y, |x| f64::Reduction::AddWithCarry.reduce(x))
+                }
+            }
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_reduction_chain<F: Fn(u128) -> u64>(
+    bench: &mut criterion::Bencher,
+    x: u64,
+    y: u64,
+    reduce: F,
+) {
+    bench.iter(|| {
+        let mut acc = black_box(x);
+        let y = black_box(y);
+        for _ in 0..1000 {
+            acc = reduce((acc as u128) * (y as u128));
+        }
+        acc
+    })
+}
+
 // GENERIC BENCHMARK RUNNER
 // ================================================================================================
 
@@ -265,5 +317,5 @@ fn bench_field_ops(c: &mut Criterion) {
 // CRITERION BOILERPLATE
 // ================================================================================================
 
-criterion_group!(field_group, batch_inv, bench_field_ops);
+criterion_group!(field_group, batch_inv, bench_field_ops, f64_reduction);
 criterion_main!(field_group);
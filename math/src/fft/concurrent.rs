@@ -5,7 +5,7 @@
 
 use super::fft_inputs::FftInputs;
 use crate::field::{FieldElement, StarkField};
-use utils::{collections::Vec, iterators::*, rayon, uninit_vector};
+use utils::{collections::Vec, iterators::*, rayon, UninitBuffer};
 
 // POLYNOMIAL EVALUATION
 // ================================================================================================
@@ -28,18 +28,23 @@ pub fn evaluate_poly_with_offset<B: StarkField, E: FieldElement<BaseField = B>>(
 ) -> Vec<E> {
     let domain_size = p.len() * blowup_factor;
     let g = B::get_root_of_unity(domain_size.ilog2());
-    let mut result = unsafe { uninit_vector(domain_size) };
+
+    // copy coefficients of the polynomial into every chunk of the result shifting them by the
+    // chunk's offset; this is done in batches so that all threads are utilized even when the
+    // blowup factor is small
+    let batch_size = usize::max(p.len() / rayon::current_num_threads().next_power_of_two(), 1);
+    let batches_per_chunk = p.len() / batch_size;
+    let mut result = UninitBuffer::new(domain_size).init_chunks(batch_size, |batch_idx| {
+        let (chunk_idx, batch_offset) =
+            (batch_idx / batches_per_chunk, (batch_idx % batches_per_chunk) * batch_size);
+        let idx = super::permute_index(blowup_factor, chunk_idx) as u64;
+        let offset = g.exp(idx.into()) * domain_offset;
+        shift(&p[batch_offset..batch_offset + batch_size], offset, batch_offset)
+    });
 
     result
-        .as_mut_slice()
         .par_chunks_mut(p.len())
-        .enumerate()
-        .for_each(|(i, chunk)| {
-            let idx = super::permute_index(blowup_factor, i) as u64;
-            let offset = g.exp(idx.into()) * domain_offset;
-            clone_and_shift(p, chunk, offset);
-            split_radix_fft(chunk, twiddles);
-        });
+        .for_each(|chunk| split_radix_fft(chunk, twiddles));
 
     permute(&mut result);
     result
@@ -84,6 +89,32 @@ where
     });
 }
 
+/// Uses FFT algorithm to interpolate multiple polynomials from the provided `polys` in a single
+/// parallel pass; each thread interpolates a batch of consecutive polynomials serially. When there
+/// are fewer polynomials than threads and the polynomials are large enough, each polynomial is
+/// interpolated in multiple threads instead.
+pub fn interpolate_polys<B, E, P>(polys: &mut [P], inv_twiddles: &[B], min_concurrent_size: usize)
+where
+    B: StarkField,
+    E: FieldElement<BaseField = B>,
+    P: AsMut<[E]> + Send,
+{
+    let num_threads = rayon::current_num_threads();
+    if polys.len() < num_threads && inv_twiddles.len() * 2 >= min_concurrent_size {
+        for poly in polys.iter_mut() {
+            interpolate_poly(poly.as_mut(), inv_twiddles);
+        }
+        return;
+    }
+
+    let batch_size = polys.len().div_ceil(num_threads);
+    polys.par_chunks_mut(batch_size).for_each(|batch| {
+        for poly in batch.iter_mut() {
+            super::serial::interpolate_poly(poly.as_mut(), inv_twiddles);
+        }
+    });
+}
+
 // PERMUTATIONS
 // ================================================================================================
 
@@ -206,17 +237,17 @@ fn transpose_square_2<T>(matrix: &mut [T], size: usize) {
 // HELPER FUNCTIONS
 // ================================================================================================
 
-fn clone_and_shift<E: FieldElement>(source: &[E], destination: &mut [E], offset: E::BaseField) {
-    let batch_size = source.len() / rayon::current_num_threads().next_power_of_two();
-    source
-        .par_chunks(batch_size)
-        .zip(destination.par_chunks_mut(batch_size))
-        .enumerate()
-        .for_each(|(i, (source, destination))| {
-            let mut factor = offset.exp(((i * batch_size) as u64).into());
-            for (s, d) in source.iter().zip(destination.iter_mut()) {
-                *d = (*s).mul_base(factor);
-                factor = factor * offset;
-            }
-        });
+/// Returns an iterator over elements of `source` multiplied by consecutive powers of `offset`
+/// starting with `offset^first_power`.
+fn shift<E: FieldElement>(
+    source: &[E],
+    offset: E::BaseField,
+    first_power: usize,
+) -> impl Iterator<Item = E> + '_ {
+    let mut factor = offset.exp((first_power as u64).into());
+    source.iter().map(move |&s| {
+        let result = s.mul_base(factor);
+        factor *= offset;
+        result
+    })
 }
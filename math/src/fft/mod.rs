@@ -387,6 +387,76 @@ pub fn interpolate_poly_with_offset<B, E>(
     }
 }
 
+/// Interpolates evaluations of multiple polynomials over the same domain into polynomials in
+/// coefficient form using the FFT algorithm.
+///
+/// This is equivalent to calling [interpolate_poly()] for each of `polys`, but is more efficient
+/// when the number of polynomials is large: when `concurrent` feature is enabled, polynomials are
+/// split into batches of consecutive polynomials, and each batch is interpolated in a single
+/// thread. Thus, all polynomials are interpolated in a single parallel pass over the shared
+/// `inv_twiddles`, which stay in the cache of a thread while it processes its batch, and without
+/// the overhead of scheduling parallel work within each polynomial. If there are fewer
+/// polynomials than threads, each polynomial is instead interpolated in multiple threads.
+///
+/// The interpolation is done in-place, meaning each of `polys` is updated with coefficients of
+/// the interpolated polynomial.
+///
+/// # Panics
+/// Panics if:
+/// * Lengths of `polys` are not all the same.
+/// * Length of `polys` or `inv_twiddles` does not satisfy requirements of [interpolate_poly()].
+///
+/// # Examples
+/// ```
+/// # use winter_math::{fft::*, fields::f128::BaseElement};
+/// # use rand_utils::rand_vector;
+/// let n = 2048;
+/// let inv_twiddles = get_inv_twiddles::<BaseElement>(n);
+///
+/// let mut polys: Vec<Vec<BaseElement>> = (0..8).map(|_| rand_vector(n)).collect();
+/// let mut expected = polys.clone();
+/// expected.iter_mut().for_each(|p| interpolate_poly(p, &inv_twiddles));
+///
+/// interpolate_polys(&mut polys, &inv_twiddles);
+/// assert_eq!(expected, polys);
+/// ```
+pub fn interpolate_polys<B, E, P>(polys: &mut [P], inv_twiddles: &[B])
+where
+    B: StarkField,
+    E: FieldElement<BaseField = B>,
+    P: AsMut<[E]> + Send,
+{
+    let num_evaluations = inv_twiddles.len() * 2;
+    assert!(
+        num_evaluations.is_power_of_two(),
+        "number of evaluations must be a power of 2, but was {num_evaluations}"
+    );
+    assert!(
+        num_evaluations.ilog2() <= B::TWO_ADICITY,
+        "multiplicative subgroup of size {num_evaluations} does not exist in the specified base field"
+    );
+    for poly in polys.iter_mut() {
+        assert_eq!(
+            num_evaluations,
+            poly.as_mut().len(),
+            "invalid number of twiddles: expected {} but received {}",
+            poly.as_mut().len() / 2,
+            inv_twiddles.len()
+        );
+    }
+
+    // when `concurrent` feature is enabled, run the concurrent version of the function; unless
+    // there is a single polynomial, then the concurrent version of interpolate_poly() is used
+    if cfg!(feature = "concurrent") && polys.len() > 1 {
+        #[cfg(feature = "concurrent")]
+        concurrent::interpolate_polys(polys, inv_twiddles, MIN_CONCURRENT_SIZE);
+    } else {
+        for poly in polys.iter_mut() {
+            interpolate_poly(poly.as_mut(), inv_twiddles);
+        }
+    }
+}
+
 // RAW FFT ALGORITHM
 // ================================================================================================
 
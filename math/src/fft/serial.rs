@@ -5,7 +5,7 @@
 
 use super::fft_inputs::FftInputs;
 use crate::{field::StarkField, FieldElement};
-use utils::{collections::Vec, uninit_vector};
+use utils::{collections::Vec, UninitBuffer};
 
 // POLYNOMIAL EVALUATION
 // ================================================================================================
@@ -35,19 +35,22 @@ where
 {
     let domain_size = p.len() * blowup_factor;
     let g = B::get_root_of_unity(domain_size.ilog2());
-    let mut result = unsafe { uninit_vector(domain_size) };
-
-    result.as_mut_slice().chunks_mut(p.len()).enumerate().for_each(|(i, chunk)| {
+    let mut result = UninitBuffer::new(domain_size).init_chunks(p.len(), |i| {
         let idx = super::permute_index(blowup_factor, i) as u64;
         let offset = g.exp(idx.into()) * domain_offset;
         let mut factor = E::BaseField::ONE;
-        for (d, c) in chunk.iter_mut().zip(p.iter()) {
-            *d = (*c).mul_base(factor);
+        p.iter().map(move |&c| {
+            let result = c.mul_base(factor);
             factor *= offset;
-        }
-        chunk.fft_in_place(twiddles);
+            result
+        })
     });
 
+    result
+        .as_mut_slice()
+        .chunks_mut(p.len())
+        .for_each(|chunk| chunk.fft_in_place(twiddles));
+
     result.permute();
     result
 }
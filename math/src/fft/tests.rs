@@ -70,6 +70,29 @@ fn fft_get_twiddles() {
     assert_eq!(expected, twiddles);
 }
 
+#[test]
+fn fft_interpolate_polys() {
+    // batches of small polynomials, and of polynomials large enough for concurrent interpolation
+    for (num_polys, n) in [(0, 8), (1, 8), (5, 16), (40, 64), (3, super::MIN_CONCURRENT_SIZE * 2)] {
+        let polys: Vec<Vec<BaseElement>> = (0..num_polys).map(|_| rand_vector(n)).collect();
+        let domain = build_domain(n);
+        let mut evaluations: Vec<Vec<BaseElement>> =
+            polys.iter().map(|p| polynom::eval_many(p, &domain)).collect();
+
+        let inv_twiddles = super::get_inv_twiddles::<BaseElement>(n);
+        super::interpolate_polys(&mut evaluations, &inv_twiddles);
+        assert_eq!(polys, evaluations);
+    }
+}
+
+#[test]
+#[should_panic(expected = "invalid number of twiddles")]
+fn fft_interpolate_polys_length_mismatch() {
+    let mut polys: Vec<Vec<BaseElement>> = vec![rand_vector(16), rand_vector(8)];
+    let inv_twiddles = super::get_inv_twiddles::<BaseElement>(16);
+    super::interpolate_polys(&mut polys, &inv_twiddles);
+}
+
 // HELPER FUNCTIONS
 // ================================================================================================
 
@@ -331,7 +331,7 @@ impl<B: ExtensibleField<3>> From<u8> for CubeExtension<B> {
     }
 }
 
-impl<'a, B: ExtensibleField<3>> TryFrom<&'a [u8]> for CubeExtension<B> {
+impl<B: ExtensibleField<3>> TryFrom<&[u8]> for CubeExtension<B> {
     type Error = DeserializationError;
 
     /// Converts a slice of bytes into a field element; returns error if the value encoded in bytes
@@ -373,6 +373,10 @@ impl<B: ExtensibleField<3>> Serializable for CubeExtension<B> {
         self.1.write_into(target);
         self.2.write_into(target);
     }
+
+    fn get_size_hint(&self) -> usize {
+        Self::ELEMENT_BYTES
+    }
 }
 
 impl<B: ExtensibleField<3>> Deserializable for CubeExtension<B> {
@@ -10,3 +10,6 @@ mod cubic;
 pub use cubic::CubeExtension;
 
 use super::{ExtensibleField, ExtensionOf, FieldElement};
+
+#[cfg(test)]
+mod tests;
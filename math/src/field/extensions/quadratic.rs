@@ -325,7 +325,7 @@ impl<B: ExtensibleField<2>> From<u8> for QuadExtension<B> {
     }
 }
 
-impl<'a, B: ExtensibleField<2>> TryFrom<&'a [u8]> for QuadExtension<B> {
+impl<B: ExtensibleField<2>> TryFrom<&[u8]> for QuadExtension<B> {
     type Error = DeserializationError;
 
     /// Converts a slice of bytes into a field element; returns error if the value encoded in bytes
@@ -366,6 +366,10 @@ impl<B: ExtensibleField<2>> Serializable for QuadExtension<B> {
         self.0.write_into(target);
         self.1.write_into(target);
     }
+
+    fn get_size_hint(&self) -> usize {
+        Self::ELEMENT_BYTES
+    }
 }
 
 impl<B: ExtensibleField<2>> Deserializable for QuadExtension<B> {
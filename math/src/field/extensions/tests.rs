@@ -0,0 +1,103 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+// CONFORMANCE TESTS
+// ================================================================================================
+
+/// Generates a module of tests checking that serialization, deserialization, and sampling of
+/// random values behave the same way for the specified extension field element type.
+macro_rules! extension_conformance_tests {
+    ($name:ident, $element:ty) => {
+        mod $name {
+            use crate::field::FieldElement;
+            use core::convert::TryFrom;
+            use rand_utils::{rand_value, rand_vector};
+            use utils::{
+                collections::Vec, Deserializable, DeserializationError, Randomizable, Serializable,
+                SliceReader,
+            };
+
+            type E = $element;
+
+            #[test]
+            fn serialization_round_trip() {
+                let element: E = rand_value();
+                let bytes = element.to_bytes();
+                assert_eq!(E::ELEMENT_BYTES, bytes.len());
+                assert_eq!(bytes.len(), element.get_size_hint());
+                assert_eq!(element, E::read_from_bytes(&bytes).unwrap());
+                assert_eq!(element, E::try_from(bytes.as_slice()).unwrap());
+            }
+
+            #[test]
+            fn serialization_matches_base_elements() {
+                let element: E = rand_value();
+                let mut expected = Vec::new();
+                for base_element in E::slice_as_base_elements(&[element]) {
+                    base_element.write_into(&mut expected);
+                }
+                assert_eq!(expected, element.to_bytes());
+            }
+
+            #[test]
+            fn batch_serialization_round_trip() {
+                let elements: Vec<E> = rand_vector(8);
+                let mut bytes = Vec::new();
+                E::write_batch_into(&elements, &mut bytes);
+                assert_eq!(elements.len() * E::ELEMENT_BYTES, bytes.len());
+
+                let mut reader = SliceReader::new(&bytes);
+                assert_eq!(elements, E::read_batch_from(&mut reader, elements.len()).unwrap());
+            }
+
+            #[test]
+            fn from_random_bytes() {
+                assert_eq!(E::ELEMENT_BYTES, E::VALUE_SIZE);
+
+                let element: E = rand_value();
+                let bytes = element.to_bytes();
+                assert_eq!(Some(element), E::from_random_bytes(&bytes));
+                assert_eq!(None, E::from_random_bytes(&bytes[1..]));
+            }
+
+            #[test]
+            fn invalid_bytes() {
+                // not enough bytes
+                let bytes = rand_value::<E>().to_bytes();
+                let result = E::read_from_bytes(&bytes[..bytes.len() - 1]);
+                assert!(matches!(result, Err(DeserializationError::UnexpectedEOF)));
+                let result = E::try_from(&bytes[..bytes.len() - 1]);
+                assert!(matches!(result, Err(DeserializationError::InvalidValue(_))));
+
+                // too many bytes
+                let mut bytes = bytes;
+                bytes.push(0);
+                let result = E::try_from(bytes.as_slice());
+                assert!(matches!(result, Err(DeserializationError::InvalidValue(_))));
+
+                // values of base elements are not smaller than the field modulus
+                let bytes = vec![255u8; E::ELEMENT_BYTES];
+                let result = E::read_from_bytes(&bytes);
+                assert!(matches!(result, Err(DeserializationError::InvalidValue(_))));
+                let result = E::try_from(bytes.as_slice());
+                assert!(matches!(result, Err(DeserializationError::InvalidValue(_))));
+                assert_eq!(None, E::from_random_bytes(&bytes));
+            }
+        }
+    };
+}
+
+extension_conformance_tests!(f62_quad, crate::field::QuadExtension<crate::field::f62::BaseElement>);
+extension_conformance_tests!(f62_cube, crate::field::CubeExtension<crate::field::f62::BaseElement>);
+extension_conformance_tests!(f64_quad, crate::field::QuadExtension<crate::field::f64::BaseElement>);
+extension_conformance_tests!(f64_cube, crate::field::CubeExtension<crate::field::f64::BaseElement>);
+extension_conformance_tests!(
+    f128_quad,
+    crate::field::QuadExtension<crate::field::f128::BaseElement>
+);
+extension_conformance_tests!(
+    f128_cube,
+    crate::field::CubeExtension<crate::field::f128::BaseElement>
+);
@@ -19,10 +19,8 @@ use core::{
     slice,
 };
 use utils::{
-    collections::Vec,
-    string::{String, ToString},
-    AsBytes, ByteReader, ByteWriter, Deserializable, DeserializationError, Randomizable,
-    Serializable,
+    collections::Vec, string::ToString, AsBytes, ByteReader, ByteWriter, Deserializable,
+    DeserializationError, Randomizable, Serializable,
 };
 
 #[cfg(feature = "serde")]
@@ -373,19 +371,34 @@ impl From<[u8; 16]> for BaseElement {
     }
 }
 
-impl<'a> TryFrom<&'a [u8]> for BaseElement {
-    type Error = String;
+impl TryFrom<&[u8]> for BaseElement {
+    type Error = DeserializationError;
 
     /// Converts a slice of bytes into a field element; returns error if the value encoded in bytes
     /// is not a valid field element. The bytes are assumed to be in little-endian byte order.
     fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
-        let value =
-            bytes.try_into().map(u128::from_le_bytes).map_err(|error| format!("{error}"))?;
+        if bytes.len() < ELEMENT_BYTES {
+            return Err(DeserializationError::InvalidValue(format!(
+                "not enough bytes for a full field element; expected {} bytes, but was {} bytes",
+                ELEMENT_BYTES,
+                bytes.len(),
+            )));
+        }
+        if bytes.len() > ELEMENT_BYTES {
+            return Err(DeserializationError::InvalidValue(format!(
+                "too many bytes for a field element; expected {} bytes, but was {} bytes",
+                ELEMENT_BYTES,
+                bytes.len(),
+            )));
+        }
+        let value = bytes
+            .try_into()
+            .map(u128::from_le_bytes)
+            .map_err(|error| DeserializationError::UnknownError(format!("{error}")))?;
         if value >= M {
-            return Err(format!(
-                "cannot convert bytes into a field element: \
-                value {value} is greater or equal to the field modulus"
-            ));
+            return Err(DeserializationError::InvalidValue(format!(
+                "invalid field element: value {value} is greater than or equal to the field modulus"
+            )));
         }
         Ok(BaseElement(value))
     }
@@ -406,6 +419,10 @@ impl Serializable for BaseElement {
     fn write_into<W: ByteWriter>(&self, target: &mut W) {
         target.write_bytes(&self.0.to_le_bytes());
     }
+
+    fn get_size_hint(&self) -> usize {
+        Self::ELEMENT_BYTES
+    }
 }
 
 impl Deserializable for BaseElement {
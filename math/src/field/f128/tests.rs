@@ -70,7 +70,7 @@ fn mul() {
     assert_eq!(BaseElement::from(m - 2), t * BaseElement::from(2u8));
     assert_eq!(BaseElement::from(m - 4), t * BaseElement::from(4u8));
 
-    let t = (m + 1) / 2;
+    let t = m.div_ceil(2);
     assert_eq!(BaseElement::ONE, BaseElement::from(t) * BaseElement::from(2u8));
 
     // test random values
@@ -147,6 +147,25 @@ fn quad_mul_base() {
 // SERIALIZATION / DESERIALIZATION
 // ================================================================================================
 
+#[test]
+fn try_from_slice() {
+    let bytes = 1u128.to_le_bytes();
+    let result = BaseElement::try_from(bytes.as_slice());
+    assert!(result.is_ok());
+    assert_eq!(1, result.unwrap().as_int());
+
+    let result = BaseElement::try_from(&bytes[..15]);
+    assert!(matches!(result, Err(DeserializationError::InvalidValue(_))));
+
+    let bytes = [1u8; 17];
+    let result = BaseElement::try_from(bytes.as_slice());
+    assert!(matches!(result, Err(DeserializationError::InvalidValue(_))));
+
+    let bytes = M.to_le_bytes();
+    let result = BaseElement::try_from(bytes.as_slice());
+    assert!(matches!(result, Err(DeserializationError::InvalidValue(_))));
+}
+
 #[test]
 fn elements_as_bytes() {
     let source = vec![
@@ -252,6 +271,6 @@ impl BaseElement {
         let bytes = value.to_bytes_le();
         let mut buffer = [0u8; 16];
         buffer[0..bytes.len()].copy_from_slice(&bytes);
-        BaseElement::try_from(buffer).unwrap()
+        BaseElement::from(buffer)
     }
 }
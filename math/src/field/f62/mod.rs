@@ -480,7 +480,7 @@ impl From<BaseElement> for u64 {
     }
 }
 
-impl<'a> TryFrom<&'a [u8]> for BaseElement {
+impl TryFrom<&[u8]> for BaseElement {
     type Error = DeserializationError;
 
     /// Converts a slice of bytes into a field element; returns error if the value encoded in bytes
@@ -530,6 +530,10 @@ impl Serializable for BaseElement {
         // convert from Montgomery representation into canonical representation
         target.write_bytes(&self.as_int().to_le_bytes());
     }
+
+    fn get_size_hint(&self) -> usize {
+        Self::ELEMENT_BYTES
+    }
 }
 
 impl Deserializable for BaseElement {
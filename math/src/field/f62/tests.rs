@@ -59,7 +59,7 @@ fn mul() {
     assert_eq!(BaseElement::from(m - 2), t * BaseElement::from(2u8));
     assert_eq!(BaseElement::from(m - 4), t * BaseElement::from(4u8));
 
-    let t = (m + 1) / 2;
+    let t = m.div_ceil(2);
     assert_eq!(BaseElement::ONE, BaseElement::from(t) * BaseElement::from(2u8));
 }
 
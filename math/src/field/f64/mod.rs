@@ -13,6 +13,9 @@
 //! * Field arithmetic in this field can be implemented using a few 32-bit addition, subtractions,
 //!   and shifts.
 //! * $8$ is the 64th root of unity which opens up potential for optimized FFT implementations.
+//!
+//! Montgomery reduction used by field multiplication can be performed using one of several
+//! strategies selected at compile time (see [Reduction]).
 
 use super::{ExtensibleField, FieldElement, StarkField};
 use core::{
@@ -30,6 +33,10 @@ use utils::{
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
+mod reduction;
+use reduction::mont_red;
+pub use reduction::Reduction;
+
 #[cfg(test)]
 mod tests;
 
@@ -367,7 +374,7 @@ impl Mul for BaseElement {
 
     #[inline]
     fn mul(self, rhs: Self) -> Self {
-        Self(mont_red_cst((self.0 as u128) * (rhs.0 as u128)))
+        Self(mont_red((self.0 as u128) * (rhs.0 as u128)))
     }
 }
 
@@ -562,7 +569,7 @@ impl From<[u8; 8]> for BaseElement {
     }
 }
 
-impl<'a> TryFrom<&'a [u8]> for BaseElement {
+impl TryFrom<&[u8]> for BaseElement {
     type Error = DeserializationError;
 
     /// Converts a slice of bytes into a field element; returns error if the value encoded in bytes
@@ -624,6 +631,10 @@ impl Serializable for BaseElement {
         // convert from Montgomery representation into canonical representation
         target.write_bytes(&self.as_int().to_le_bytes());
     }
+
+    fn get_size_hint(&self) -> usize {
+        Self::ELEMENT_BYTES
+    }
 }
 
 impl Deserializable for BaseElement {
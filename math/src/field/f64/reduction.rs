@@ -0,0 +1,138 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{mont_red_cst, M};
+use core::fmt::{Display, Formatter};
+
+/// M^{-1} mod 2^64 = 2^32 + 1.
+const M_INV: u64 = 0x0000000100000001;
+
+// MONTGOMERY REDUCTION
+// ================================================================================================
+
+/// Strategies for Montgomery reduction of 128-bit products in the 64-bit field.
+///
+/// All strategies are constant-time and compute exactly the same values; however, their relative
+/// performance differs between CPU microarchitectures. The strategy used by field multiplication
+/// is selected at compile time via crate features (see [Reduction::SELECTED]); the `field` benchmark
+/// of this crate measures all strategies side by side (under `f64_reduction` group) to help
+/// determine the fastest strategy for a given machine.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Reduction {
+    /// Reduction via shifts and 64-bit subtractions which exploit the special form of the field
+    /// modulus (see <https://eprint.iacr.org/2022/274.pdf>). This is the default strategy.
+    Shift,
+    /// Classic Montgomery reduction which computes the quotient and its product with the field
+    /// modulus using full-width multiplications; selected by `f64-reduce-mul` feature.
+    Mul,
+    /// Reduction via an addition of a multiple of the field modulus computed using shifts and a
+    /// chain of additions with carry, followed by a conditional subtraction; selected by
+    /// `f64-reduce-adc` feature.
+    AddWithCarry,
+}
+
+impl Reduction {
+    /// All available reduction strategies.
+    pub const ALL: [Self; 3] = [Self::Shift, Self::Mul, Self::AddWithCarry];
+
+    /// Reduction strategy used by multiplication of field elements.
+    ///
+    /// If both `f64-reduce-adc` and `f64-reduce-mul` features are enabled, the former takes
+    /// precedence.
+    #[cfg(feature = "f64-reduce-adc")]
+    pub const SELECTED: Self = Self::AddWithCarry;
+
+    /// Reduction strategy used by multiplication of field elements.
+    ///
+    /// If both `f64-reduce-adc` and `f64-reduce-mul` features are enabled, the former takes
+    /// precedence.
+    #[cfg(all(feature = "f64-reduce-mul", not(feature = "f64-reduce-adc")))]
+    pub const SELECTED: Self = Self::Mul;
+
+    /// Reduction strategy used by multiplication of field elements.
+    ///
+    /// If both `f64-reduce-adc` and `f64-reduce-mul` features are enabled, the former takes
+    /// precedence.
+    #[cfg(not(any(feature = "f64-reduce-mul", feature = "f64-reduce-adc")))]
+    pub const SELECTED: Self = Self::Shift;
+
+    /// Returns the name of the crate feature which selects this strategy, or `None` for the
+    /// default strategy.
+    pub const fn feature(&self) -> Option<&'static str> {
+        match self {
+            Self::Shift => None,
+            Self::Mul => Some("f64-reduce-mul"),
+            Self::AddWithCarry => Some("f64-reduce-adc"),
+        }
+    }
+
+    /// Returns x * 2^{-64} mod M computed using this strategy.
+    ///
+    /// The result is in the range [0, M) as long as x < M * 2^64; in particular, this holds for
+    /// products of two values in the range [0, M).
+    #[inline(always)]
+    pub const fn reduce(&self, x: u128) -> u64 {
+        match self {
+            Self::Shift => mont_red_cst(x),
+            Self::Mul => mont_red_mul(x),
+            Self::AddWithCarry => mont_red_adc(x),
+        }
+    }
+}
+
+impl Display for Reduction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Shift => write!(f, "shift"),
+            Self::Mul => write!(f, "mul"),
+            Self::AddWithCarry => write!(f, "adc"),
+        }
+    }
+}
+
+/// Montgomery reduction using the strategy selected at compile time.
+#[inline(always)]
+pub(super) const fn mont_red(x: u128) -> u64 {
+    #[cfg(feature = "f64-reduce-adc")]
+    return mont_red_adc(x);
+
+    #[cfg(all(feature = "f64-reduce-mul", not(feature = "f64-reduce-adc")))]
+    return mont_red_mul(x);
+
+    #[cfg(not(any(feature = "f64-reduce-mul", feature = "f64-reduce-adc")))]
+    return mont_red_cst(x);
+}
+
+// REDUCTION STRATEGIES
+// ================================================================================================
+
+/// Montgomery reduction (constant time) based on 128-bit multiplication.
+#[inline(always)]
+const fn mont_red_mul(x: u128) -> u64 {
+    // q is chosen so that the lower 64 bits of x - q * M are zeros
+    let q = (x as u64).wrapping_mul(M_INV);
+    let m = (q as u128) * (M as u128);
+    let (y, borrow) = x.overflowing_sub(m);
+
+    // if x < q * M, the result is negative; add M to it
+    ((y >> 64) as u64).wrapping_add(M & 0u64.wrapping_sub(borrow as u64))
+}
+
+/// Montgomery reduction (constant time) based on a chain of additions with carry.
+#[inline(always)]
+const fn mont_red_adc(x: u128) -> u64 {
+    // q = -x * M^{-1} mod 2^64 is computed as n + n * 2^32 where n = -x mod 2^64; q * M is then
+    // computed as q * 2^64 - q * 2^32 + q. the lower 64 bits of x + q * M are zeros.
+    let n = (x as u64).wrapping_neg();
+    let q = n.wrapping_add(n << 32) as u128;
+    let qm = (q << 64) - (q << 32) + q;
+    let (y, carry) = x.overflowing_add(qm);
+
+    // (x + q * M) / 2^64 is in the range [0, 2M); subtract M from it if it is not smaller than M
+    let y = (y >> 64) as u64;
+    let (r, borrow) = y.overflowing_sub(M);
+    let mask = 0u64.wrapping_sub((carry | !borrow) as u64);
+    (r & mask) | (y & !mask)
+}
@@ -3,7 +3,9 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use super::{BaseElement, DeserializationError, FieldElement, Serializable, StarkField, M};
+use super::{
+    BaseElement, DeserializationError, FieldElement, Reduction, Serializable, StarkField, M,
+};
 use crate::field::{CubeExtension, ExtensionOf, QuadExtension};
 use core::convert::TryFrom;
 use num_bigint::BigUint;
@@ -68,10 +70,31 @@ fn mul() {
     assert_eq!(BaseElement::from(m - 2), t * BaseElement::from(2u8));
     assert_eq!(BaseElement::from(m - 4), t * BaseElement::from(4u8));
 
-    let t = (m + 1) / 2;
+    let t = m.div_ceil(2);
     assert_eq!(BaseElement::ONE, BaseElement::from(t) * BaseElement::from(2u8));
 }
 
+#[test]
+fn mont_reduction_strategies() {
+    // products of edge-case values, capped at the largest supported input M * 2^64 - 1
+    let values = [0u128, 1, (M - 1) as u128, u64::MAX as u128];
+    for &a in values.iter() {
+        for &b in values.iter() {
+            let x = (a * b).min(((M as u128) << 64) - 1);
+            let expected = Reduction::Shift.reduce(x);
+            assert!(expected < M);
+            for reduction in Reduction::ALL {
+                assert_eq!(expected, reduction.reduce(x), "strategy: {reduction}");
+            }
+        }
+    }
+
+    // multiplication uses the selected strategy
+    let a = rand_value::<BaseElement>();
+    let b = rand_value::<BaseElement>();
+    assert_eq!(a * b, BaseElement(Reduction::SELECTED.reduce((a.0 as u128) * (b.0 as u128))));
+}
+
 #[test]
 fn mul_small() {
     // test overflow
@@ -419,6 +442,16 @@ proptest! {
         prop_assert_eq!(expected, result.as_int());
     }
 
+    #[test]
+    fn mont_reduction_strategies_proptest(a in 0..M, b in 0..M) {
+        let x = (a as u128) * (b as u128);
+        let expected = Reduction::Shift.reduce(x);
+
+        for reduction in Reduction::ALL {
+            prop_assert_eq!(expected, reduction.reduce(x));
+        }
+    }
+
     #[test]
     fn mul_small_proptest(a in any::<u64>(), b in any::<u32>()) {
         let v1 = BaseElement::from(a);
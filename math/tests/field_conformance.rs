@@ -0,0 +1,11 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use test_utils::field_conformance_tests;
+use winter_math::fields::{f128, f62, f64};
+
+field_conformance_tests!(f62_conformance, f62::BaseElement);
+field_conformance_tests!(f64_conformance, f64::BaseElement);
+field_conformance_tests!(f128_conformance, f128::BaseElement);
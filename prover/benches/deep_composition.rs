@@ -0,0 +1,53 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use math::fields::f64::BaseElement;
+use rand_utils::{rand_value, rand_vector};
+use std::time::Duration;
+use winter_prover::{
+    matrix::ColMatrix, DeepCompositionCoefficients, DeepCompositionPoly, TracePolyTable,
+};
+
+// CONSTANTS
+// ================================================================================================
+
+const SIZE: usize = 262_144;
+const NUM_POLYS: [usize; 3] = [32, 128, 255];
+
+fn add_trace_polys(c: &mut Criterion) {
+    let mut group = c.benchmark_group("deep_composition_add_trace_polys");
+    group.sample_size(10);
+    group.measurement_time(Duration::from_secs(10));
+
+    for &num_poly in NUM_POLYS.iter() {
+        let columns: Vec<Vec<BaseElement>> = (0..num_poly).map(|_| rand_vector(SIZE)).collect();
+        let column_matrix = ColMatrix::new(columns);
+        let z: BaseElement = rand_value();
+        let ood_trace_states = TracePolyTable::new(column_matrix.clone()).get_ood_frame(z);
+
+        group.bench_function(BenchmarkId::new(SIZE.to_string(), num_poly), |bench| {
+            bench.iter_batched(
+                || {
+                    let coefficients = DeepCompositionCoefficients {
+                        trace: rand_vector(num_poly),
+                        constraints: Vec::new(),
+                    };
+                    let trace_polys = TracePolyTable::new(column_matrix.clone());
+                    (DeepCompositionPoly::new(z, coefficients), trace_polys)
+                },
+                |(mut deep_composition_poly, trace_polys)| {
+                    deep_composition_poly.add_trace_polys(trace_polys, ood_trace_states.clone());
+                    deep_composition_poly
+                },
+                BatchSize::LargeInput,
+            );
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(deep_composition_group, add_trace_polys);
+criterion_main!(deep_composition_group);
@@ -3,9 +3,10 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
-use math::{fft, fields::f64::BaseElement, StarkField};
-use rand_utils::rand_vector;
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use crypto::{hashers::Blake3_256, MerkleTree};
+use math::{fft, fields::f64::BaseElement, FieldElement, StarkField};
+use rand_utils::{rand_value, rand_vector};
 use std::time::Duration;
 use winter_prover::{
     matrix::{ColMatrix, RowMatrix},
@@ -19,6 +20,17 @@ const SIZE: usize = 524_288;
 const BLOWUP_FACTOR: [usize; 3] = [2, 4, 8];
 const NUM_POLYS: [usize; 3] = [32, 64, 96];
 
+const QUERY_SIZE: usize = 65_536;
+const QUERY_BLOWUP_FACTOR: usize = 8;
+const QUERY_NUM_POLYS: [usize; 2] = [128, 255];
+const NUM_QUERIES: usize = 54;
+
+const FRAME_SIZE: usize = 16_384;
+const FRAME_BLOWUP_FACTOR: usize = 8;
+const FRAME_NUM_POLYS: [usize; 4] = [4, 16, 64, 128];
+
+type Blake3 = Blake3_256<BaseElement>;
+
 fn evaluate_columns(c: &mut Criterion) {
     let mut group = c.benchmark_group("matrix_evaluate_columns");
     group.sample_size(10);
@@ -68,6 +80,110 @@ fn evaluate_matrix(c: &mut Criterion) {
     group.finish();
 }
 
+fn commit_and_query(c: &mut Criterion) {
+    let mut group = c.benchmark_group("matrix_commit_and_query");
+    group.sample_size(10);
+    group.measurement_time(Duration::from_secs(10));
+
+    let lde_size = QUERY_SIZE * QUERY_BLOWUP_FACTOR;
+    let mut positions = (0..NUM_QUERIES)
+        .map(|_| rand_value::<u64>() as usize % lde_size)
+        .collect::<Vec<_>>();
+    positions.sort_unstable();
+    positions.dedup();
+
+    for &num_poly in QUERY_NUM_POLYS.iter() {
+        let columns: Vec<Vec<BaseElement>> =
+            (0..num_poly).map(|_| rand_vector(QUERY_SIZE)).collect();
+        let polys = ColMatrix::new(columns);
+        let twiddles = fft::get_twiddles::<BaseElement>(QUERY_SIZE);
+        let domain =
+            StarkDomain::from_twiddles(twiddles, QUERY_BLOWUP_FACTOR, BaseElement::GENERATOR);
+
+        // column-major layout: every row read touches a separate memory region per column
+        let col_lde = polys.evaluate_columns_over(&domain);
+        group.bench_function(BenchmarkId::new("col_major", num_poly), |bench| {
+            bench.iter_with_large_drop(|| {
+                let tree: MerkleTree<Blake3> = col_lde.commit_to_rows();
+                let rows = positions
+                    .iter()
+                    .map(|&pos| {
+                        let mut row = vec![BaseElement::ZERO; num_poly];
+                        col_lde.read_row_into(pos, &mut row);
+                        row
+                    })
+                    .collect::<Vec<_>>();
+                (tree.prove_batch(&positions).unwrap(), rows)
+            });
+        });
+
+        // row-major layout: this is how the prover stores committed trace LDEs
+        let row_lde = RowMatrix::evaluate_polys_over::<8>(&polys, &domain);
+        group.bench_function(BenchmarkId::new("row_major", num_poly), |bench| {
+            bench.iter_with_large_drop(|| {
+                let tree: MerkleTree<Blake3> = row_lde.commit_to_rows();
+                let rows =
+                    positions.iter().map(|&pos| row_lde.row(pos).to_vec()).collect::<Vec<_>>();
+                (tree.prove_batch(&positions).unwrap(), rows)
+            });
+        });
+    }
+    group.finish();
+}
+
+fn read_frames(c: &mut Criterion) {
+    let mut group = c.benchmark_group("matrix_read_frames");
+    group.sample_size(10);
+    group.measurement_time(Duration::from_secs(10));
+
+    // constraint evaluation reads the current and the next row of the trace LDE at every step of
+    // the evaluation domain; the next row is `blowup` rows away from the current row
+    let lde_size = FRAME_SIZE * FRAME_BLOWUP_FACTOR;
+    let next_row = |step: usize| (step + FRAME_BLOWUP_FACTOR) % lde_size;
+
+    for &num_poly in FRAME_NUM_POLYS.iter() {
+        let columns: Vec<Vec<BaseElement>> =
+            (0..num_poly).map(|_| rand_vector(FRAME_SIZE)).collect();
+        let polys = ColMatrix::new(columns);
+        let twiddles = fft::get_twiddles::<BaseElement>(FRAME_SIZE);
+        let domain =
+            StarkDomain::from_twiddles(twiddles, FRAME_BLOWUP_FACTOR, BaseElement::GENERATOR);
+
+        // column-major layout: every frame read touches a separate memory region per column
+        let col_lde = polys.evaluate_columns_over(&domain);
+        group.bench_function(BenchmarkId::new("col_major", num_poly), |bench| {
+            let mut current = vec![BaseElement::ZERO; num_poly];
+            let mut next = vec![BaseElement::ZERO; num_poly];
+            bench.iter(|| {
+                let mut result = BaseElement::ZERO;
+                for step in 0..lde_size {
+                    col_lde.read_row_into(step, &mut current);
+                    col_lde.read_row_into(next_row(step), &mut next);
+                    result += current[0] * next[num_poly - 1];
+                }
+                black_box(result)
+            });
+        });
+
+        // row-major layout: this is how the prover stores trace LDEs read by the evaluator
+        let row_lde = RowMatrix::evaluate_polys_over::<8>(&polys, &domain);
+        group.bench_function(BenchmarkId::new("row_major", num_poly), |bench| {
+            let mut current = vec![BaseElement::ZERO; num_poly];
+            let mut next = vec![BaseElement::ZERO; num_poly];
+            bench.iter(|| {
+                let mut result = BaseElement::ZERO;
+                for step in 0..lde_size {
+                    current.copy_from_slice(row_lde.row(step));
+                    next.copy_from_slice(row_lde.row(next_row(step)));
+                    result += current[0] * next[num_poly - 1];
+                }
+                black_box(result)
+            });
+        });
+    }
+    group.finish();
+}
+
 /// Benchmark parameters.
 struct BenchmarkParams {
     num_poly: usize,
@@ -80,5 +196,5 @@ impl std::fmt::Display for BenchmarkParams {
     }
 }
 
-criterion_group!(matrix_group, evaluate_columns, evaluate_matrix);
+criterion_group!(matrix_group, evaluate_columns, evaluate_matrix, commit_and_query, read_frames);
 criterion_main!(matrix_group);
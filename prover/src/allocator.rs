@@ -0,0 +1,61 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use math::StarkField;
+use utils::{collections::Vec, uninit_vector};
+
+// BUFFER KIND
+// ================================================================================================
+
+/// Identifies the purpose of a large buffer requested by the prover.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BufferKind {
+    /// Row-major low-degree extension of a trace segment.
+    TraceLde,
+    /// Row-major evaluations of constraint composition polynomial columns over the LDE domain.
+    ConstraintCommitment,
+}
+
+// BUFFER ALLOCATOR
+// ================================================================================================
+
+/// Defines how the prover obtains and gives back the largest buffers used during proof
+/// generation.
+///
+/// A handful of vectors (e.g., low-degree extensions of trace segments) account for most of the
+/// memory used by the prover, and the behavior of the system allocator when these vectors are
+/// requested can dominate variance in proof generation time. Implementing this trait allows
+/// serving these buffers from a pool of pre-allocated (and possibly huge-page-backed) memory and
+/// reusing them across proofs.
+///
+/// Buffers are always requested as vectors of base field elements; the prover re-interprets
+/// them as needed. Every buffer obtained via [allocate()](BufferAllocator::allocate) is passed
+/// back to [release()](BufferAllocator::release) once the prover no longer needs it.
+///
+/// Since buffers are standard vectors, memory backing them must be obtained from the global
+/// allocator; implementations which need custom memory management should retain released
+/// buffers rather than handing out memory obtained from elsewhere.
+#[allow(unused_variables)]
+pub trait BufferAllocator<B: StarkField>: Send + Sync {
+    /// Returns a vector of exactly `len` base field elements to be used for a buffer of the
+    /// specified kind.
+    ///
+    /// The contents of the returned vector may be arbitrary as the prover overwrites all of them
+    /// before reading. By default, a new uninitialized vector is allocated.
+    fn allocate(&self, kind: BufferKind, len: usize) -> Vec<B> {
+        unsafe { uninit_vector(len) }
+    }
+
+    /// Takes back a buffer of the specified kind which is no longer needed by the prover.
+    ///
+    /// By default, the buffer is dropped.
+    fn release(&self, kind: BufferKind, buffer: Vec<B>) {}
+}
+
+/// Buffer allocator which obtains all buffers directly from the global allocator.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DefaultAllocator;
+
+impl<B: StarkField> BufferAllocator<B> for DefaultAllocator {}
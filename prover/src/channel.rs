@@ -8,7 +8,7 @@ use air::{
     Air, ConstraintCompositionCoefficients, DeepCompositionCoefficients,
 };
 use core::marker::PhantomData;
-use crypto::{ElementHasher, RandomCoin};
+use crypto::{ElementHasher, RandomCoin, Transcript};
 use fri::{self, FriProof};
 use math::{FieldElement, ToElements};
 use utils::collections::Vec;
@@ -16,6 +16,12 @@ use utils::collections::Vec;
 #[cfg(feature = "concurrent")]
 use utils::iterators::*;
 
+// CONSTANTS
+// ================================================================================================
+
+/// Number of consecutive candidate nonces checked at a time during proof-of-work grinding.
+const GRINDING_BATCH_SIZE: u64 = 1 << 16;
+
 // TYPES AND INTERFACES
 // ================================================================================================
 
@@ -49,7 +55,8 @@ where
     // --------------------------------------------------------------------------------------------
     /// Creates a new prover channel for the specified `air` and public inputs.
     pub fn new(air: &'a A, mut pub_inputs_elements: Vec<A::BaseField>) -> Self {
-        let context = Context::new::<A::BaseField>(air.trace_info(), air.options().clone());
+        let context = Context::new::<A::BaseField>(air.trace_info(), air.options().clone())
+            .with_hash_function::<H>();
 
         // build a seed for the public coin; the initial seed is a hash of the proof context and
         // the public inputs, but as the protocol progresses, the coin will be reseeded with the
@@ -71,6 +78,14 @@ where
     // COMMITMENT METHODS
     // --------------------------------------------------------------------------------------------
 
+    /// Commits the prover to the extended preprocessed columns of the execution trace.
+    ///
+    /// The commitment is known to the verifier in advance, and thus, it is not included in the
+    /// proof; however, it is still used to reseed the public coin.
+    pub fn commit_preprocessed_trace(&mut self, preprocessed_root: H::Digest) {
+        self.public_coin.reseed(preprocessed_root);
+    }
+
     /// Commits the prover the extended execution trace.
     pub fn commit_trace(&mut self, trace_root: H::Digest) {
         self.commitments.add::<H>(&trace_root);
@@ -87,6 +102,7 @@ where
     /// also reseeds the public coin with the hashes of the evaluation frame states.
     pub fn send_ood_trace_states(&mut self, trace_states: &[Vec<E>]) {
         let result = self.ood_frame.set_trace_states(trace_states);
+        self.public_coin.record_note("ood_trace_states", E::elements_as_bytes(&result));
         self.public_coin.reseed(H::hash_elements(&result));
     }
 
@@ -94,12 +110,19 @@ where
     /// point. This also reseeds the public coin wit the hash of the evaluations.
     pub fn send_ood_constraint_evaluations(&mut self, evaluations: &[E]) {
         self.ood_frame.set_constraint_evaluations(evaluations);
+        self.public_coin
+            .record_note("ood_constraint_evaluations", E::elements_as_bytes(evaluations));
         self.public_coin.reseed(H::hash_elements(evaluations));
     }
 
     // PUBLIC COIN METHODS
     // --------------------------------------------------------------------------------------------
 
+    /// Returns the transcript recorded by the public coin, if the coin records one.
+    pub fn transcript(&self) -> Option<&Transcript> {
+        self.public_coin.transcript()
+    }
+
     /// Returns a set of random elements required for constructing an auxiliary trace segment with
     /// the specified index.
     ///
@@ -136,41 +159,55 @@ where
     /// Returns a set of positions in the LDE domain against which the evaluations of trace and
     /// constraint composition polynomials should be queried.
     ///
-    /// The positions are drawn from the public coin uniformly at random. Duplicate positions
-    /// are removed from the returned vector.
+    /// The positions are drawn from the public coin using the query sampling strategy defined by
+    /// the AIR (uniform sampling by default). Duplicate positions are removed from the returned vector, and the remaining positions are sorted in
+    /// ascending order.
     pub fn get_query_positions(&mut self) -> Vec<usize> {
-        let num_queries = self.context.options().num_queries();
-        let lde_domain_size = self.context.lde_domain_size();
-        let mut positions = self
-            .public_coin
-            .draw_integers(num_queries, lde_domain_size, self.pow_nonce)
-            .expect("failed to draw query position");
-
-        // remove any duplicate positions from the list
-        positions.sort_unstable();
-        positions.dedup();
-
-        positions
+        self.air
+            .get_query_positions(&mut self.public_coin, self.pow_nonce)
+            .expect("failed to draw query position")
     }
 
     /// Determines a nonce, which when hashed with the current seed of the public coin results
     /// in a new seed with the number of leading zeros equal to the grinding_factor specified
     /// in the proof options.
-    pub fn grind_query_seed(&mut self) {
+    ///
+    /// Candidate nonces are streamed in batches of [GRINDING_BATCH_SIZE] consecutive values, and
+    /// the search stops at the first batch which contains a valid nonce. When `concurrent`
+    /// feature is enabled, nonces within a batch are checked in multiple threads and any valid
+    /// nonce found in the batch is used, unless `deterministic` is set. In the latter case (and
+    /// always when `concurrent` feature is not enabled), the smallest valid nonce is used.
+    ///
+    /// Returns the number of candidate nonces in all searched batches.
+    #[cfg_attr(not(feature = "concurrent"), allow(unused_variables))]
+    pub fn grind_query_seed(&mut self, deterministic: bool) -> u64 {
         let grinding_factor = self.context.options().grinding_factor();
+        let public_coin = &self.public_coin;
+        let is_valid = |nonce: &u64| public_coin.check_proof_of_work(*nonce, grinding_factor);
 
-        #[cfg(not(feature = "concurrent"))]
-        let nonce = (1..u64::MAX)
-            .find(|&nonce| self.public_coin.check_leading_zeros(nonce) >= grinding_factor)
-            .expect("nonce not found");
+        let mut batch_start = 1_u64;
+        let (nonce, num_nonces) = loop {
+            let batch_end = batch_start.saturating_add(GRINDING_BATCH_SIZE);
 
-        #[cfg(feature = "concurrent")]
-        let nonce = (1..u64::MAX)
-            .into_par_iter()
-            .find_any(|&nonce| self.public_coin.check_leading_zeros(nonce) >= grinding_factor)
-            .expect("nonce not found");
+            #[cfg(not(feature = "concurrent"))]
+            let nonce = (batch_start..batch_end).find(is_valid);
+
+            #[cfg(feature = "concurrent")]
+            let nonce = if deterministic {
+                (batch_start..batch_end).into_par_iter().find_first(is_valid)
+            } else {
+                (batch_start..batch_end).into_par_iter().find_any(is_valid)
+            };
+
+            if let Some(nonce) = nonce {
+                break (nonce, batch_end - 1);
+            }
+            assert!(batch_end < u64::MAX, "nonce not found");
+            batch_start = batch_end;
+        };
 
         self.pow_nonce = nonce;
+        num_nonces
     }
 
     // PROOF BUILDER
@@ -180,6 +217,7 @@ where
     pub fn build_proof(
         self,
         trace_queries: Vec<Queries>,
+        preprocessed_queries: Option<Queries>,
         constraint_queries: Queries,
         fri_proof: FriProof,
         num_query_positions: usize,
@@ -191,6 +229,7 @@ where
             commitments: self.commitments,
             ood_frame: self.ood_frame,
             trace_queries,
+            preprocessed_queries,
             constraint_queries,
             fri_proof,
             pow_nonce: self.pow_nonce,
@@ -221,4 +260,26 @@ where
     fn draw_fri_alpha(&mut self) -> E {
         self.public_coin.draw().expect("failed to draw FRI alpha")
     }
+
+    /// Finds the smallest nonce which satisfies the specified grinding factor for the current
+    /// state of the public coin, and reseeds the public coin with this nonce.
+    fn grind_fri_layer(&mut self, grinding_factor: u32) -> u64 {
+        let nonce = (1..u64::MAX)
+            .find(|&nonce| self.public_coin.check_proof_of_work(nonce, grinding_factor))
+            .expect("nonce not found");
+        self.public_coin.reseed_with_int(nonce);
+        nonce
+    }
+
+    /// Reseeds the public coin with a hash of the specified out-of-domain evaluations.
+    fn send_ood_evaluations(&mut self, evaluations: &[E]) {
+        self.public_coin.reseed(H::hash_elements(evaluations));
+    }
+
+    /// Returns a list of positions drawn from the public coin.
+    fn draw_layer_positions(&mut self, num_positions: usize, domain_size: usize) -> Vec<usize> {
+        self.public_coin
+            .draw_integers(num_positions, domain_size, 0)
+            .expect("failed to draw layer positions")
+    }
 }
@@ -3,20 +3,37 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use super::{constraints::CompositionPoly, StarkDomain, TracePolyTable};
+use super::{constraints::CompositionPoly, NumaPartitioning, StarkDomain, TracePolyTable};
 use air::DeepCompositionCoefficients;
-use math::{add_in_place, fft, mul_acc, polynom, ExtensionOf, FieldElement, StarkField};
-use utils::{collections::Vec, iter_mut};
+use core::mem;
+use math::{add_in_place, fft, polynom, ExtensionOf, FieldElement, StarkField};
+use utils::{batch_iter_mut, collections::Vec, uninit_vector};
 
 #[cfg(feature = "concurrent")]
 use utils::iterators::*;
 
+// CONSTANTS
+// ================================================================================================
+
+/// Minimum number of coefficients processed by a single thread when polynomials are combined.
+const MIN_BATCH_SIZE: usize = 1024;
+
 // DEEP COMPOSITION POLYNOMIAL
 // ================================================================================================
+/// DEEP composition polynomial in coefficient form.
+///
+/// The polynomial is built by combining trace polynomials and constraint composition polynomial
+/// columns, with out-of-domain evaluations divided out, using a random linear combination (see
+/// [DeepCompositionCoefficients] for details).
+///
+/// Trace polynomials are opened at points z * g^k for all out-of-domain offsets k; by default,
+/// the offsets are 0 and 1 (i.e., the points are z and z * g).
 pub struct DeepCompositionPoly<E: FieldElement> {
     coefficients: Vec<E>,
     cc: DeepCompositionCoefficients<E>,
     z: E,
+    ood_offsets: Vec<usize>,
+    partitioning: Option<NumaPartitioning>,
 }
 
 impl<E: FieldElement> DeepCompositionPoly<E> {
@@ -30,9 +47,29 @@ impl<E: FieldElement> DeepCompositionPoly<E> {
             coefficients: vec![],
             cc,
             z,
+            ood_offsets: vec![0, 1],
+            partitioning: None,
         }
     }
 
+    /// Sets offsets of the out-of-domain points at which trace polynomials are opened; an offset
+    /// k corresponds to the point z * g^k, where g is the generator of the trace domain.
+    ///
+    /// # Panics
+    /// Panics if `ood_offsets` is empty.
+    pub fn with_ood_offsets(mut self, ood_offsets: Vec<usize>) -> Self {
+        assert!(!ood_offsets.is_empty(), "at least one out-of-domain offset must be specified");
+        self.ood_offsets = ood_offsets;
+        self
+    }
+
+    /// Sets partitioning of the work of computing random linear combinations of polynomials among
+    /// NUMA nodes.
+    pub fn with_numa_partitioning(mut self, partitioning: NumaPartitioning) -> Self {
+        self.partitioning = Some(partitioning);
+        self
+    }
+
     // ACCESSORS
     // --------------------------------------------------------------------------------------------
 
@@ -51,15 +88,22 @@ impl<E: FieldElement> DeepCompositionPoly<E> {
     /// Combines all trace polynomials into a single polynomial and saves the result into
     /// the DEEP composition polynomial. The combination is done as follows:
     ///
-    /// - Compute polynomials T'_i(x) = (T_i(x) - T_i(z)) / (x - z) and
-    ///   T''_i(x) = (T_i(x) - T_i(z * g)) / (x - z * g) for all i, where T_i(x) is a trace
-    ///   polynomial for column i.
-    /// - Then, combine together all T'_i(x) and T''_i(x) polynomials using a random linear
-    ///   combination as T(x) = sum((T'_i(x) + T''_i(x)) * cc_i) for all i, where cc_i is
-    ///   the coefficient for the random linear combination drawn from the public coin.
+    /// - For each out-of-domain point z_k = z * g^k, compute polynomials
+    ///   T^k_i(x) = (T_i(x) - T_i(z_k)) / (x - z_k) for all i, where T_i(x) is a trace polynomial
+    ///   for column i. With default offsets, these are T'_i(x) = (T_i(x) - T_i(z)) / (x - z) and
+    ///   T''_i(x) = (T_i(x) - T_i(z * g)) / (x - z * g).
+    /// - Then, combine together all T^k_i(x) polynomials using a random linear combination as
+    ///   T(x) = sum((T^0_i(x) + T^1_i(x) + ...) * cc_i) for all i, where cc_i is the coefficient
+    ///   for the random linear combination drawn from the public coin.
     ///
-    /// Note that evaluations of T_i(z) and T_i(z * g) are passed in via the `ood_trace_state`
-    /// parameter.
+    /// Since both division and random linear combination are linear operations, we first
+    /// compute T(x) = sum(T_i(x) * cc_i) in a single pass over all trace polynomials, and then
+    /// divide the result by (x - z_k) for all k in a single pass over T(x). The quotients do not
+    /// depend on T_i(z_k) values; these values are used only to make sure that the divisions are
+    /// exact.
+    ///
+    /// Note that evaluations of T_i(z_k) are passed in via the `ood_trace_states` parameter (one
+    /// row per out-of-domain point).
     pub fn add_trace_polys(
         &mut self,
         trace_polys: TracePolyTable<E>,
@@ -67,74 +111,47 @@ impl<E: FieldElement> DeepCompositionPoly<E> {
     ) {
         assert!(self.coefficients.is_empty());
 
-        // compute a second out-of-domain point offset from z by exactly trace generator; this
-        // point defines the "next" computation state in relation to point z
+        // compute the out-of-domain points by offsetting z by powers of the trace generator; with
+        // default offsets, the second point defines the "next" computation state in relation to
+        // point z
         let trace_length = trace_polys.poly_size();
-        let g = E::from(E::BaseField::get_root_of_unity(trace_length.ilog2()));
-        let next_z = self.z * g;
-
-        // combine trace polynomials into 2 composition polynomials T'(x) and T''(x)
-        let mut t1_composition = E::zeroed_vector(trace_length);
-        let mut t2_composition = E::zeroed_vector(trace_length);
-
-        // index of a trace polynomial; we declare it here so that we can maintain index continuity
-        // across all trace segments
-        let mut i = 0;
-
-        // --- merge polynomials of the main trace segment ----------------------------------------
-        for poly in trace_polys.main_trace_polys() {
-            // compute T'(x) = T(x) - T(z), multiply it by a pseudo-random coefficient,
-            // and add the result into composition polynomial
-            acc_trace_poly::<E::BaseField, E>(
-                &mut t1_composition,
-                poly,
-                ood_trace_states[0][i],
-                self.cc.trace[i],
-            );
-
-            // compute T''(x) = T(x) - T(z * g), multiply it by a pseudo-random coefficient,
-            // and add the result into composition polynomial
-            acc_trace_poly::<E::BaseField, E>(
-                &mut t2_composition,
-                poly,
-                ood_trace_states[1][i],
-                self.cc.trace[i],
-            );
-
-            i += 1;
-        }
+        let g = E::BaseField::get_root_of_unity(trace_length.ilog2());
+        let ood_points: Vec<E> = self
+            .ood_offsets
+            .iter()
+            .map(|&k| self.z * E::from(g.exp_vartime((k as u64).into())))
+            .collect();
 
-        // --- merge polynomials of the auxiliary trace segments ----------------------------------
-        for poly in trace_polys.aux_trace_polys() {
-            // compute T'(x) = T(x) - T(z), multiply it by a pseudo-random coefficient,
-            // and add the result into composition polynomial
-            acc_trace_poly::<E, E>(
-                &mut t1_composition,
-                poly,
-                ood_trace_states[0][i],
-                self.cc.trace[i],
-            );
-
-            // compute T''(x) = T(x) - T(z * g), multiply it by a pseudo-random coefficient,
-            // and add the result into composition polynomial
-            acc_trace_poly::<E, E>(
-                &mut t2_composition,
-                poly,
-                ood_trace_states[1][i],
-                self.cc.trace[i],
-            );
-
-            i += 1;
-        }
+        // combine all trace polynomials into a single polynomial T(x) using a random linear
+        // combination; this is done in a single pass over chunks of the polynomials so that each
+        // chunk of the accumulator stays in cache while all columns are added into it
+        let num_main_polys = trace_polys.main_trace_polys().count();
+        let (main_cc, aux_cc) = self.cc.trace.split_at(num_main_polys);
+        let mut composition = self.zeroed_vector(trace_length);
+        self.for_each_chunk(&mut composition, |chunk: &mut [E], chunk_offset: usize| {
+            let range = chunk_offset..chunk_offset + chunk.len();
+            for (poly, &k) in trace_polys.main_trace_polys().zip(main_cc) {
+                acc_poly_chunk::<E::BaseField, E>(chunk, &poly[range.clone()], k);
+            }
+            for (poly, &k) in trace_polys.aux_trace_polys().zip(aux_cc) {
+                acc_poly_chunk::<E, E>(chunk, &poly[range.clone()], k);
+            }
+        });
 
-        // divide the composition polynomials by (x - z) and (x - z * g), respectively,
-        // and add the resulting polynomials together; the output of this step
-        // is a single trace polynomial T(x) and deg(T(x)) = trace_length - 2.
-        let trace_poly =
-            merge_trace_compositions(vec![t1_composition, t2_composition], vec![self.z, next_z]);
+        // divide T(x) by (x - z_k) for all out-of-domain points, and add the resulting
+        // polynomials together; the output of this step is a single trace polynomial and its
+        // degree is trace_length - 2.
+        let remainders = syn_div_multi_in_place(&mut composition, &ood_points);
+
+        // the remainders of the divisions must be equal to the random linear combinations of
+        // T_i(z_k) for the respective points
+        debug_assert_eq!(ood_trace_states.len(), remainders.len());
+        for (ood_trace_state, remainder) in ood_trace_states.iter().zip(remainders) {
+            debug_assert_eq!(linear_combination(ood_trace_state, &self.cc.trace), remainder);
+        }
 
         // set the coefficients of the DEEP composition polynomial
-        self.coefficients = trace_poly;
+        self.coefficients = composition;
         assert_eq!(self.poly_size() - 2, self.degree());
     }
 
@@ -150,6 +167,10 @@ impl<E: FieldElement> DeepCompositionPoly<E> {
     ///   all i, where cc_i is the coefficient for the random linear combination drawn from the
     ///   public coin.
     ///
+    /// Similarly to trace polynomial composition, the random linear combination is computed
+    /// first in a single pass over all column polynomials, and then the result is divided by
+    /// (x - z) in a single pass.
+    ///
     /// Note that evaluations of H_i(x) at z are passed in via the `ood_evaluations` parameter.
     pub fn add_composition_poly(
         &mut self,
@@ -158,24 +179,59 @@ impl<E: FieldElement> DeepCompositionPoly<E> {
     ) {
         assert!(!self.coefficients.is_empty());
 
-        let z = self.z;
+        let column_polys = composition_poly.into_columns();
 
-        let mut column_polys = composition_poly.into_columns();
-
-        // Divide out the OOD point z from column polynomials
-        iter_mut!(column_polys).zip(ood_evaluations).for_each(|(poly, value_at_z)| {
-            // compute H'_i(x) = (H_i(x) - H_i(z)) / (x - z)
-            poly[0] -= value_at_z;
-            polynom::syn_div_in_place(poly, 1, z);
+        // compute H(x) = sum(H_i(x) * cc_i) and add it to the DEEP composition polynomial; we can
+        // accumulate H(x) directly into the DEEP composition polynomial because we divide the
+        // result by (x - z) separately below
+        let mut composition = self.zeroed_vector(self.poly_size());
+        self.for_each_chunk(&mut composition, |chunk: &mut [E], chunk_offset: usize| {
+            let range = chunk_offset..chunk_offset + chunk.len();
+            for (poly, &k) in column_polys.iter().zip(self.cc.constraints.iter()) {
+                acc_poly_chunk::<E, E>(chunk, &poly[range.clone()], k);
+            }
         });
 
-        // add H'_i(x) * cc_i for all i into the DEEP composition polynomial
-        for (i, poly) in column_polys.into_iter().enumerate() {
-            mul_acc::<E, E>(&mut self.coefficients, &poly, self.cc.constraints[i]);
-        }
+        // compute H(x) / (x - z); the remainder must be equal to the random linear combination
+        // of H_i(z) values
+        let remainder = syn_div_in_place(&mut composition, self.z);
+        debug_assert_eq!(linear_combination(&ood_evaluations, &self.cc.constraints), remainder);
+
+        add_in_place(&mut self.coefficients, &composition);
         assert_eq!(self.poly_size() - 2, self.degree());
     }
 
+    // HELPER METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a vector of zeros of the specified length; with NUMA partitioning, each range of
+    /// the vector is first touched by the node which processes it in
+    /// [for_each_chunk()](Self::for_each_chunk).
+    fn zeroed_vector(&self, n: usize) -> Vec<E> {
+        match &self.partitioning {
+            Some(partitioning) => {
+                let mut result = unsafe { uninit_vector(n) };
+                partitioning.first_touch(&mut result, MIN_BATCH_SIZE, E::ZERO);
+                result
+            }
+            None => E::zeroed_vector(n),
+        }
+    }
+
+    /// Calls `op` for chunks of `data` in parallel; the second argument of `op` is the offset of
+    /// the chunk in `data`.
+    fn for_each_chunk<F>(&self, data: &mut [E], op: F)
+    where
+        F: Fn(&mut [E], usize) + Sync,
+    {
+        match &self.partitioning {
+            Some(partitioning) => partitioning.for_each_chunk(data, MIN_BATCH_SIZE, op),
+            None => {
+                batch_iter_mut!(data, MIN_BATCH_SIZE, op);
+            }
+        }
+    }
+
     // LOW-DEGREE EXTENSION
     // --------------------------------------------------------------------------------------------
     /// Evaluates DEEP composition polynomial over the specified LDE domain and returns the result.
@@ -192,30 +248,48 @@ impl<E: FieldElement> DeepCompositionPoly<E> {
 // HELPER FUNCTIONS
 // ================================================================================================
 
-/// Divides each polynomial in the list by the corresponding divisor, and computes the
-/// coefficient-wise sum of all resulting polynomials.
-fn merge_trace_compositions<E: FieldElement>(mut polys: Vec<Vec<E>>, divisors: Vec<E>) -> Vec<E> {
-    // divide all polynomials by their corresponding divisor
-    iter_mut!(polys).zip(divisors).for_each(|(poly, divisor)| {
-        polynom::syn_div_in_place(poly, 1, divisor);
-    });
-
-    // add all polynomials together into a single polynomial
-    let mut result = polys.remove(0);
-    for poly in polys.iter() {
-        add_in_place(&mut result, poly);
-    }
-
-    result
-}
-
-/// Computes (P(x) - value) * k and saves the result into the accumulator.
-fn acc_trace_poly<F, E>(accumulator: &mut [E], poly: &[F], value: E, k: E)
+/// Computes a + b * k for all values in the chunk of the accumulator `a`, and saves the results
+/// into the accumulator.
+#[inline(always)]
+fn acc_poly_chunk<F, E>(accumulator: &mut [E], poly: &[F], k: E)
 where
     F: FieldElement,
     E: FieldElement<BaseField = F::BaseField> + ExtensionOf<F>,
 {
-    mul_acc(accumulator, poly, k);
-    let adjusted_tz = value * k;
-    accumulator[0] -= adjusted_tz;
+    for (acc, &value) in accumulator.iter_mut().zip(poly) {
+        *acc += k.mul_base(value);
+    }
+}
+
+/// Divides polynomial `p` by (x - `b`) in place, and returns the remainder of the division.
+///
+/// This is similar to [polynom::syn_div_in_place()] but also returns the remainder.
+fn syn_div_in_place<E: FieldElement>(p: &mut [E], b: E) -> E {
+    let mut c = E::ZERO;
+    for coeff in p.iter_mut().rev() {
+        *coeff += b * c;
+        mem::swap(coeff, &mut c);
+    }
+    c
+}
+
+/// Divides polynomial `p` by (x - `b`) for every `b` in `points`, and saves the sum of the
+/// resulting quotients into `p`. All divisions are performed in a single pass over `p`.
+///
+/// Returns remainders of the divisions in the order of `points`.
+fn syn_div_multi_in_place<E: FieldElement>(p: &mut [E], points: &[E]) -> Vec<E> {
+    let mut c = vec![E::ZERO; points.len()];
+    for coeff in p.iter_mut().rev() {
+        let value = *coeff;
+        *coeff = c.iter().fold(E::ZERO, |acc, &c| acc + c);
+        for (c, &b) in c.iter_mut().zip(points) {
+            *c = value + b * *c;
+        }
+    }
+    c
+}
+
+/// Returns sum(values_i * coefficients_i) for all i.
+fn linear_combination<E: FieldElement>(values: &[E], coefficients: &[E]) -> E {
+    values.iter().zip(coefficients).fold(E::ZERO, |acc, (&v, &k)| acc + v * k)
 }
@@ -4,10 +4,21 @@
 // LICENSE file in the root directory of this source tree.
 
 use super::RowMatrix;
+use crate::{matrix::ColMatrix, ProverError, StarkDomain};
 use air::proof::Queries;
 use crypto::{ElementHasher, MerkleTree};
-use math::FieldElement;
-use utils::collections::Vec;
+use math::{fft, polynom, FieldElement, StarkField};
+use utils::{collections::Vec, uninit_vector};
+
+#[cfg(feature = "std")]
+use std::{
+    fs::{self, File},
+    io::{Read, Seek, SeekFrom, Write},
+    path::PathBuf,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+#[cfg(feature = "std")]
+use utils::SliceReader;
 
 // CONSTRAINT COMMITMENT
 // ================================================================================================
@@ -18,8 +29,12 @@ use utils::collections::Vec;
 /// * Evaluations of composition polynomial columns over the LDE domain.
 /// * Merkle tree where each leaf in the tree corresponds to a row in the composition polynomial
 ///   evaluation matrix.
+///
+/// Depending on the [MemoryStrategy](crate::MemoryStrategy) used by the prover, the evaluations
+/// are either kept in memory, re-evaluated from composition polynomial columns when queried, or
+/// kept in a temporary file.
 pub struct ConstraintCommitment<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> {
-    evaluations: RowMatrix<E>,
+    evaluations: Evaluations<E>,
     commitment: MerkleTree<H>,
 }
 
@@ -33,11 +48,49 @@ impl<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> ConstraintComm
             "number of rows in constraint evaluation matrix must be the same as number of leaves in constraint commitment"
         );
         ConstraintCommitment {
-            evaluations,
+            evaluations: Evaluations::Matrix(evaluations),
+            commitment,
+        }
+    }
+
+    /// Creates a new constraint evaluation commitment by evaluating the provided composition
+    /// polynomial columns over the LDE domain one coset of the trace domain at a time.
+    ///
+    /// Evaluations are not kept; rows at queried positions are re-evaluated from the columns.
+    pub(crate) fn chunked(polys: ColMatrix<E>, domain: &StarkDomain<E::BaseField>) -> Self {
+        let commitment = commit_by_cosets::<E, H, _>(&polys, domain, |_| Ok(()))
+            .expect("chunked evaluation does not fail");
+        ConstraintCommitment {
+            evaluations: Evaluations::Polys {
+                polys,
+                domain_offset: domain.offset(),
+                domain_generator: lde_domain_generator(domain),
+            },
             commitment,
         }
     }
 
+    /// Creates a new constraint evaluation commitment by evaluating the provided composition
+    /// polynomial columns over the LDE domain one coset of the trace domain at a time.
+    ///
+    /// Evaluations are written into a temporary file, which is deleted when the commitment is
+    /// dropped; rows at queried positions are read back from the file.
+    ///
+    /// # Errors
+    /// Returns an error if the temporary file could not be created or written to.
+    #[cfg(feature = "std")]
+    pub(crate) fn disk_backed(
+        polys: &ColMatrix<E>,
+        domain: &StarkDomain<E::BaseField>,
+    ) -> Result<Self, ProverError> {
+        let mut file = SpilledRows::create(polys.num_cols(), polys.num_rows())?;
+        let commitment = commit_by_cosets::<E, H, _>(polys, domain, |rows| file.append(rows))?;
+        Ok(ConstraintCommitment {
+            evaluations: Evaluations::File(file),
+            commitment,
+        })
+    }
+
     /// Returns the root of the commitment Merkle tree.
     pub fn root(&self) -> H::Digest {
         *self.commitment.root()
@@ -51,7 +104,10 @@ impl<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> ConstraintComm
 
     /// Returns constraint evaluations at the specified positions along with Merkle authentication
     /// paths from the root of the commitment to these evaluations.
-    pub fn query(self, positions: &[usize]) -> Queries {
+    ///
+    /// # Errors
+    /// Returns an error if the evaluations are kept in a temporary file which could not be read.
+    pub fn query(&self, positions: &[usize]) -> Result<Queries, ProverError> {
         // build Merkle authentication paths to the leaves specified by positions
         let merkle_proof = self
             .commitment
@@ -61,10 +117,192 @@ impl<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> ConstraintComm
         // determine a set of evaluations corresponding to each position
         let mut evaluations = Vec::new();
         for &position in positions {
-            let row = self.evaluations.row(position).to_vec();
+            let row = match &self.evaluations {
+                Evaluations::Matrix(matrix) => matrix.row(position).to_vec(),
+                Evaluations::Polys {
+                    polys,
+                    domain_offset,
+                    domain_generator,
+                } => {
+                    let x =
+                        E::from(*domain_offset * domain_generator.exp((position as u64).into()));
+                    polys.columns().map(|poly| polynom::eval(poly, x)).collect()
+                }
+                #[cfg(feature = "std")]
+                Evaluations::File(file) => {
+                    file.read_row(position, self.commitment.leaves().len())?
+                }
+            };
             evaluations.push(row);
         }
 
-        Queries::new(merkle_proof, evaluations)
+        Ok(Queries::new(merkle_proof, evaluations))
+    }
+
+    /// Consumes this commitment and returns the underlying composition polynomial evaluations if
+    /// they were kept in memory.
+    pub fn into_evaluations(self) -> Option<RowMatrix<E>> {
+        match self.evaluations {
+            Evaluations::Matrix(matrix) => Some(matrix),
+            _ => None,
+        }
+    }
+}
+
+// EVALUATIONS
+// ================================================================================================
+
+/// Storage of composition polynomial evaluations over the LDE domain.
+enum Evaluations<E: FieldElement> {
+    /// All evaluations are kept in memory.
+    Matrix(RowMatrix<E>),
+    /// Evaluations are computed from the composition polynomial columns when queried.
+    Polys {
+        polys: ColMatrix<E>,
+        domain_offset: E::BaseField,
+        domain_generator: E::BaseField,
+    },
+    /// Evaluations are kept in a temporary file.
+    #[cfg(feature = "std")]
+    File(SpilledRows<E>),
+}
+
+// SPILLED ROWS
+// ================================================================================================
+
+/// Counter used to generate unique names of temporary files within a process.
+#[cfg(feature = "std")]
+static NEXT_FILE_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// Rows of composition polynomial evaluations written into a temporary file.
+///
+/// Rows are written one coset of the trace domain at a time: the row at position `i` of the LDE
+/// domain is stored at index `(i % blowup) * coset_size + i / blowup` in the file.
+#[cfg(feature = "std")]
+struct SpilledRows<E: FieldElement> {
+    file: File,
+    path: PathBuf,
+    num_cols: usize,
+    coset_size: usize,
+    _field: core::marker::PhantomData<E>,
+}
+
+#[cfg(feature = "std")]
+impl<E: FieldElement> SpilledRows<E> {
+    /// Creates a new temporary file for rows of the specified number of columns.
+    fn create(num_cols: usize, coset_size: usize) -> Result<Self, ProverError> {
+        let path = std::env::temp_dir().join(format!(
+            "winterfell-constraints-{}-{}.bin",
+            std::process::id(),
+            NEXT_FILE_ID.fetch_add(1, Ordering::Relaxed)
+        ));
+        let file = File::options()
+            .read(true)
+            .write(true)
+            .create_new(true)
+            .open(&path)
+            .map_err(storage_error)?;
+        Ok(Self {
+            file,
+            path,
+            num_cols,
+            coset_size,
+            _field: core::marker::PhantomData,
+        })
+    }
+
+    /// Appends evaluations of all columns over a single coset of the trace domain to the file.
+    fn append(&mut self, columns: &[Vec<E>]) -> Result<(), ProverError> {
+        let mut bytes = Vec::with_capacity(self.row_size() * self.coset_size);
+        for i in 0..self.coset_size {
+            columns.iter().for_each(|column| column[i].write_into(&mut bytes));
+        }
+        self.file.write_all(&bytes).map_err(storage_error)
+    }
+
+    /// Reads the row at the specified position of an LDE domain of the specified size.
+    fn read_row(&self, position: usize, domain_size: usize) -> Result<Vec<E>, ProverError> {
+        let blowup = domain_size / self.coset_size;
+        let index = (position % blowup) * self.coset_size + position / blowup;
+        let mut bytes = vec![0; self.row_size()];
+        let mut file = &self.file;
+        file.seek(SeekFrom::Start((index * self.row_size()) as u64))
+            .and_then(|_| file.read_exact(&mut bytes))
+            .map_err(storage_error)?;
+
+        let mut reader = SliceReader::new(&bytes);
+        (0..self.num_cols)
+            .map(|_| E::read_from(&mut reader))
+            .collect::<Result<_, _>>()
+            .map_err(|err| ProverError::DiskStorageFailed(err.to_string()))
     }
+
+    fn row_size(&self) -> usize {
+        self.num_cols * E::ELEMENT_BYTES
+    }
+}
+
+#[cfg(feature = "std")]
+impl<E: FieldElement> Drop for SpilledRows<E> {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+#[cfg(feature = "std")]
+fn storage_error(err: std::io::Error) -> ProverError {
+    ProverError::DiskStorageFailed(err.to_string())
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Evaluates the specified polynomial columns over the LDE domain one coset of the trace domain
+/// at a time, passes the evaluations over each coset to `on_coset`, and returns a Merkle tree
+/// built over rows of the evaluations.
+///
+/// Coset `k` consists of points `offset * g^(k + j * blowup)` for all `j`, where `g` is the
+/// generator of the LDE domain; thus, evaluations at these points form rows `k + j * blowup` of
+/// the LDE.
+fn commit_by_cosets<E, H, F>(
+    polys: &ColMatrix<E>,
+    domain: &StarkDomain<E::BaseField>,
+    mut on_coset: F,
+) -> Result<MerkleTree<H>, ProverError>
+where
+    E: FieldElement,
+    H: ElementHasher<BaseField = E::BaseField>,
+    F: FnMut(&[Vec<E>]) -> Result<(), ProverError>,
+{
+    let coset_size = polys.num_rows();
+    let blowup = domain.trace_to_lde_blowup();
+    let generator = lde_domain_generator(domain);
+
+    let mut row_hashes = unsafe { uninit_vector::<H::Digest>(coset_size * blowup) };
+    let mut row = vec![E::ZERO; polys.num_cols()];
+    let mut coset_offset = domain.offset();
+    for k in 0..blowup {
+        let columns = polys
+            .columns()
+            .map(|poly| {
+                fft::evaluate_poly_with_offset(poly, domain.trace_twiddles(), coset_offset, 1)
+            })
+            .collect::<Vec<_>>();
+        for j in 0..coset_size {
+            columns
+                .iter()
+                .zip(row.iter_mut())
+                .for_each(|(column, value)| *value = column[j]);
+            row_hashes[k + j * blowup] = H::hash_elements(&row);
+        }
+        on_coset(&columns)?;
+        coset_offset *= generator;
+    }
+
+    Ok(MerkleTree::new(row_hashes).expect("failed to construct constraint Merkle tree"))
+}
+
+/// Returns the generator of the LDE domain.
+fn lde_domain_generator<B: StarkField>(domain: &StarkDomain<B>) -> B {
+    B::get_root_of_unity(domain.lde_domain_size().ilog2())
 }
@@ -4,14 +4,18 @@
 // LICENSE file in the root directory of this source tree.
 
 use super::{ColMatrix, StarkDomain};
+use crate::ProverError;
 use math::{fft, polynom::degree_of, FieldElement};
-use utils::collections::Vec;
+use utils::{collections::Vec, split_into_vectors};
 
 // CONSTRAINT COMPOSITION POLYNOMIAL TRACE
 // ================================================================================================
 
 /// Represents merged evaluations of all constraint evaluations.
-pub struct CompositionPolyTrace<E>(Vec<E>);
+pub struct CompositionPolyTrace<E> {
+    evaluations: Vec<E>,
+    degree_error: Option<ProverError>,
+}
 
 impl<E: FieldElement> CompositionPolyTrace<E> {
     /// Returns a new instance of [CompositionPolyTrace] instantiated from the provided evaluations.
@@ -25,17 +29,28 @@ impl<E: FieldElement> CompositionPolyTrace<E> {
             evaluations.len(),
         );
 
-        Self(evaluations)
+        Self {
+            evaluations,
+            degree_error: None,
+        }
     }
 
     /// Returns the number of evaluations in this trace.
     pub fn num_rows(&self) -> usize {
-        self.0.len()
+        self.evaluations.len()
     }
 
     /// Returns the internal vector representing this trace.
     pub fn into_inner(self) -> Vec<E> {
-        self.0
+        self.evaluations
+    }
+
+    /// Records an error describing a mismatch between declared and actual degrees of the
+    /// constraints merged into this trace; the error is returned when this trace is converted
+    /// into a [CompositionPoly].
+    #[cfg(debug_assertions)]
+    pub(crate) fn set_degree_error(&mut self, error: ProverError) {
+        self.degree_error = Some(error);
     }
 }
 
@@ -51,28 +66,50 @@ pub struct CompositionPoly<E: FieldElement> {
 
 impl<E: FieldElement> CompositionPoly<E> {
     /// Returns a new composition polynomial.
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// - A mismatch between declared and actual degrees of transition constraints was detected
+    ///   while evaluating the constraints (this is checked only in debug mode).
+    /// - The degree of the interpolated composition polynomial is greater than or equal to
+    ///   `num_cols` times the trace length, and thus, the polynomial cannot be split into
+    ///   `num_cols` columns.
+    ///
+    /// # Panics
+    /// Panics if the length of `composition_trace` is not greater than the trace length.
     pub fn new(
         composition_trace: CompositionPolyTrace<E>,
         domain: &StarkDomain<E::BaseField>,
         num_cols: usize,
-    ) -> Self {
+    ) -> Result<Self, ProverError> {
         assert!(
             domain.trace_length() < composition_trace.num_rows(),
             "trace length must be smaller than length of composition polynomial trace"
         );
 
-        let mut trace = composition_trace.into_inner();
+        if let Some(error) = composition_trace.degree_error {
+            return Err(error);
+        }
+        let mut trace = composition_trace.evaluations;
 
         // at this point, combined_poly contains evaluations of the combined constraint polynomial;
         // we interpolate this polynomial to transform it into coefficient form.
         let inv_twiddles = fft::get_inv_twiddles::<E::BaseField>(trace.len());
         fft::interpolate_poly_with_offset(&mut trace, &inv_twiddles, domain.offset());
 
+        // make sure the polynomial fits into the specified number of columns; this would not be
+        // the case if actual degrees of constraints are greater than the degrees declared by AIR
+        let max_degree = domain.trace_length() * num_cols - 1;
+        let degree = degree_of(&trace);
+        if degree > max_degree {
+            return Err(ProverError::CompositionPolyDegreeTooHigh(max_degree, degree));
+        }
+
         let polys = segment(trace, domain.trace_length(), num_cols);
 
-        CompositionPoly {
+        Ok(CompositionPoly {
             data: ColMatrix::new(polys),
-        }
+        })
     }
 
     // PUBLIC ACCESSORS
@@ -123,13 +160,7 @@ fn segment<E: FieldElement>(
     trace_len: usize,
     num_cols: usize,
 ) -> Vec<Vec<E>> {
-    debug_assert!(degree_of(&coefficients) < trace_len * num_cols);
-
-    coefficients
-        .chunks(trace_len)
-        .take(num_cols)
-        .map(|slice| slice.to_vec())
-        .collect()
+    split_into_vectors(&coefficients[..trace_len * num_cols], trace_len)
 }
 
 // TESTS
@@ -138,6 +169,8 @@ fn segment<E: FieldElement>(
 #[cfg(test)]
 mod tests {
 
+    use super::{CompositionPoly, CompositionPolyTrace};
+    use crate::{tests::MockAir, ProverError, StarkDomain};
     use math::fields::f128::BaseElement;
     use utils::collections::Vec;
 
@@ -156,4 +189,54 @@ mod tests {
 
         assert_eq!(expected, actual)
     }
+
+    #[test]
+    fn composition_poly_degree_too_high() {
+        let air = MockAir::with_trace_length(8);
+        let domain = StarkDomain::new(&air);
+
+        // evaluations of a polynomial of degree 15 cannot be split into a single column
+        let evaluations = (0u128..16).map(|i| BaseElement::new(i * i + 3)).collect();
+        let trace = CompositionPolyTrace::new(evaluations);
+        let result = CompositionPoly::new(trace, &domain, 1);
+        assert_eq!(Some(ProverError::CompositionPolyDegreeTooHigh(7, 15)), result.err());
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    fn transition_constraint_degree_mismatch() {
+        use crate::{
+            ConstraintEvaluator, DefaultConstraintEvaluator, DefaultTraceLde, Trace, TraceLde,
+            TraceTable,
+        };
+        use air::{Air, Assertion, AuxTraceRandElements};
+        use crypto::{hashers::Blake3_256, DefaultRandomCoin, RandomCoin};
+        use math::FieldElement;
+
+        // mock AIR declares a transition constraint of degree 2, but does not evaluate it
+        let air = MockAir::with_assertions(vec![Assertion::single(0, 0, BaseElement::ZERO)], 8);
+        let domain = StarkDomain::new(&air);
+        let columns = (0u128..4).map(|i| (0..8).map(|j| BaseElement::new(i + j)).collect());
+        let trace = TraceTable::init(columns.collect());
+        let (trace_lde, _) = DefaultTraceLde::<BaseElement, Blake3_256<BaseElement>>::new(
+            air.trace_info(),
+            trace.main_segment(),
+            &domain,
+        );
+        assert_eq!(domain.lde_domain_size(), trace_lde.trace_len());
+
+        let mut coin = DefaultRandomCoin::<Blake3_256<BaseElement>>::new(&[BaseElement::ONE]);
+        let coefficients = air
+            .get_constraint_composition_coefficients::<BaseElement, _>(&mut coin)
+            .unwrap();
+        let evaluator =
+            DefaultConstraintEvaluator::new(&air, AuxTraceRandElements::new(), coefficients);
+        let evaluations = evaluator.evaluate(&trace_lde, &domain);
+
+        let result = CompositionPoly::new(evaluations, &domain, 1);
+        assert!(matches!(
+            result.err(),
+            Some(ProverError::TransitionConstraintDegreeMismatch(0, _, _))
+        ));
+    }
 }
@@ -5,7 +5,7 @@
 
 use super::{CompositionPolyTrace, ConstraintDivisor, StarkDomain};
 use math::{batch_inversion, FieldElement, StarkField};
-use utils::{batch_iter_mut, collections::Vec, iter_mut, uninit_vector};
+use utils::{collections::Vec, uninit_vector, UninitBuffer};
 
 #[cfg(debug_assertions)]
 use math::fft;
@@ -13,8 +13,8 @@ use math::fft;
 #[cfg(debug_assertions)]
 use air::TransitionConstraints;
 
-#[cfg(feature = "concurrent")]
-use utils::iterators::*;
+#[cfg(debug_assertions)]
+use crate::ProverError;
 
 // CONSTANTS
 // ================================================================================================
@@ -24,9 +24,22 @@ const MIN_FRAGMENT_SIZE: usize = 16;
 // CONSTRAINT EVALUATION TABLE
 // ================================================================================================
 
+/// Holds evaluations of the constraint composition polynomial over the constraint evaluation
+/// domain.
+///
+/// Constraint evaluations are grouped by their divisors (the first group always contains merged
+/// transition constraint evaluations). Rather than storing a separate column for each group, the
+/// table divides each group by its divisor as soon as a row is computed, and accumulates the
+/// results into a single column. Inverse divisor evaluations are precomputed only over a single
+/// period of each divisor (see [DivisorInverse]), and thus, the table requires memory for just
+/// one column of the constraint evaluation domain size regardless of the number of divisors.
+///
+/// Inverse divisor evaluations are borrowed rather than owned by the table so that they can be
+/// reused across proofs (see [ProverSession](crate::ProverSession)).
 pub struct ConstraintEvaluationTable<'a, E: FieldElement> {
-    evaluations: Vec<Vec<E>>,
+    evaluations: Vec<E>,
     divisors: Vec<ConstraintDivisor<E::BaseField>>,
+    divisor_inverses: Vec<&'a DivisorInverse<E::BaseField>>,
     domain: &'a StarkDomain<E::BaseField>,
 
     #[cfg(debug_assertions)]
@@ -40,18 +53,22 @@ pub struct ConstraintEvaluationTable<'a, E: FieldElement> {
 impl<'a, E: FieldElement> ConstraintEvaluationTable<'a, E> {
     // CONSTRUCTOR
     // --------------------------------------------------------------------------------------------
-    /// Returns a new constraint evaluation table with number of columns equal to the number of
-    /// specified divisors, and number of rows equal to the size of constraint evaluation domain.
+    /// Returns a new constraint evaluation table for constraints grouped by the specified
+    /// divisors, with number of rows equal to the size of constraint evaluation domain.
+    ///
+    /// `divisor_inverses` must contain inverse evaluations of the specified divisors in the same
+    /// order as the divisors.
     #[cfg(not(debug_assertions))]
     pub fn new(
         domain: &'a StarkDomain<E::BaseField>,
         divisors: Vec<ConstraintDivisor<E::BaseField>>,
+        divisor_inverses: Vec<&'a DivisorInverse<E::BaseField>>,
     ) -> Self {
-        let num_columns = divisors.len();
         let num_rows = domain.ce_domain_size();
         ConstraintEvaluationTable {
-            evaluations: uninit_matrix(num_columns, num_rows),
+            evaluations: unsafe { uninit_vector(num_rows) },
             divisors,
+            divisor_inverses,
             domain,
         }
     }
@@ -63,9 +80,10 @@ impl<'a, E: FieldElement> ConstraintEvaluationTable<'a, E> {
     pub fn new(
         domain: &'a StarkDomain<E::BaseField>,
         divisors: Vec<ConstraintDivisor<E::BaseField>>,
+        divisor_inverses: Vec<&'a DivisorInverse<E::BaseField>>,
         transition_constraints: &TransitionConstraints<E>,
     ) -> Self {
-        let num_columns = divisors.len();
+        assert_eq!(divisors.len(), divisor_inverses.len());
         let num_rows = domain.ce_domain_size();
         let num_tm_columns = transition_constraints.num_main_constraints();
         let num_ta_columns = transition_constraints.num_aux_constraints();
@@ -76,8 +94,9 @@ impl<'a, E: FieldElement> ConstraintEvaluationTable<'a, E> {
             build_transition_constraint_degrees(transition_constraints, domain.trace_length());
 
         ConstraintEvaluationTable {
-            evaluations: uninit_matrix(num_columns, num_rows),
+            evaluations: unsafe { uninit_vector(num_rows) },
             divisors,
+            divisor_inverses,
             domain,
             main_transition_evaluations: uninit_matrix(num_tm_columns, num_rows),
             aux_transition_evaluations: uninit_matrix(num_ta_columns, num_rows),
@@ -91,17 +110,16 @@ impl<'a, E: FieldElement> ConstraintEvaluationTable<'a, E> {
     /// Returns the number of rows in this table. This is the same as the size of the constraint
     /// evaluation domain.
     pub fn num_rows(&self) -> usize {
-        self.evaluations[0].len()
+        self.evaluations.len()
     }
 
-    /// Returns number of columns in this table.
+    /// Returns number of constraint groups in this table.
     ///
-    /// The first column always contains the value of combined transition constraint evaluations;
-    /// the remaining columns contain values of assertion constraint evaluations combined based on
-    /// common divisors.
+    /// The first group always contains combined transition constraint evaluations; the remaining
+    /// groups contain assertion constraint evaluations combined based on common divisors.
     #[allow(dead_code)]
     pub fn num_columns(&self) -> usize {
-        self.evaluations.len()
+        self.divisors.len()
     }
 
     // TABLE FRAGMENTS
@@ -109,7 +127,7 @@ impl<'a, E: FieldElement> ConstraintEvaluationTable<'a, E> {
 
     /// Break the table into the number of specified fragments. All fragments can be updated
     /// independently - e.g. in different threads.
-    pub fn fragments(&mut self, num_fragments: usize) -> Vec<EvaluationTableFragment<E>> {
+    pub fn fragments(&mut self, num_fragments: usize) -> Vec<EvaluationTableFragment<'_, E>> {
         let fragment_size = self.num_rows() / num_fragments;
         assert!(
             fragment_size >= MIN_FRAGMENT_SIZE,
@@ -117,7 +135,9 @@ impl<'a, E: FieldElement> ConstraintEvaluationTable<'a, E> {
         );
 
         // break evaluations into fragments
-        let evaluation_data = make_fragments(&mut self.evaluations, num_fragments);
+        let evaluation_data = self.evaluations.chunks_mut(fragment_size);
+        let divisor_inverses = &self.divisor_inverses;
+        let domain = self.domain;
 
         #[cfg(debug_assertions)]
         let result = {
@@ -136,6 +156,8 @@ impl<'a, E: FieldElement> ConstraintEvaluationTable<'a, E> {
                     EvaluationTableFragment {
                         offset: i * fragment_size,
                         evaluations,
+                        divisor_inverses,
+                        domain,
                         tm_evaluations,
                         ta_evaluations,
                     }
@@ -150,6 +172,8 @@ impl<'a, E: FieldElement> ConstraintEvaluationTable<'a, E> {
             .map(|(i, evaluations)| EvaluationTableFragment {
                 offset: i * fragment_size,
                 evaluations,
+                divisor_inverses,
+                domain,
             })
             .collect();
 
@@ -158,28 +182,19 @@ impl<'a, E: FieldElement> ConstraintEvaluationTable<'a, E> {
 
     // CONSTRAINT COMPOSITION
     // --------------------------------------------------------------------------------------------
-    /// Divides constraint evaluation columns by their respective divisor (in evaluation form) and
-    /// combines the results into a single column.
+    /// Returns evaluations of the constraint composition polynomial.
+    ///
+    /// Constraint evaluations are divided by their respective divisors and combined together as
+    /// rows of the table are updated, and thus, this requires no additional computations.
     pub fn combine(self) -> CompositionPolyTrace<E> {
-        // allocate memory for the combined polynomial
-        let mut combined_poly = E::zeroed_vector(self.num_rows());
-
-        // iterate over all columns of the constraint evaluation table, divide each column
-        // by the evaluations of its corresponding divisor, and add all resulting evaluations
-        // together into a single vector
-        for (column, divisor) in self.evaluations.into_iter().zip(self.divisors.iter()) {
-            // divide the column by the divisor and accumulate the result into combined_poly
-            acc_column(column, divisor, self.domain, &mut combined_poly);
-        }
-
-        CompositionPolyTrace::new(combined_poly)
+        CompositionPolyTrace::new(self.evaluations)
     }
 
     // DEBUG HELPERS
     // --------------------------------------------------------------------------------------------
 
     #[cfg(debug_assertions)]
-    pub fn validate_transition_degrees(&mut self) {
+    pub fn validate_transition_degrees(&mut self) -> Result<(), ProverError> {
         // evaluate transition constraint divisor (which is assumed to be the first one in the
         // divisor list) over the constraint evaluation domain. this is used later to compute
         // actual degrees of transition constraint evaluations.
@@ -211,15 +226,22 @@ impl<'a, E: FieldElement> ConstraintEvaluationTable<'a, E> {
         }
 
         // make sure expected and actual degrees are equal
-        assert_eq!(
-            self.expected_transition_degrees, actual_degrees,
-            "transition constraint degrees didn't match\nexpected: {:>3?}\nactual:   {:>3?}",
-            self.expected_transition_degrees, actual_degrees
-        );
+        let mismatch = self
+            .expected_transition_degrees
+            .iter()
+            .zip(actual_degrees.iter())
+            .position(|(expected, actual)| expected != actual);
+        if let Some(index) = mismatch {
+            return Err(ProverError::TransitionConstraintDegreeMismatch(
+                index,
+                self.expected_transition_degrees[index],
+                actual_degrees[index],
+            ));
+        }
 
         // make sure evaluation domain size does not exceed the size required by max degree
         let expected_domain_size =
-            core::cmp::max(max_degree, self.domain.trace_length() + 1).next_power_of_two();
+            core::cmp::max(max_degree + 1, self.domain.trace_length() + 1).next_power_of_two();
         assert_eq!(
             expected_domain_size,
             self.num_rows(),
@@ -227,6 +249,8 @@ impl<'a, E: FieldElement> ConstraintEvaluationTable<'a, E> {
             expected_domain_size,
             self.num_rows()
         );
+
+        Ok(())
     }
 }
 
@@ -235,7 +259,9 @@ impl<'a, E: FieldElement> ConstraintEvaluationTable<'a, E> {
 
 pub struct EvaluationTableFragment<'a, E: FieldElement> {
     offset: usize,
-    evaluations: Vec<&'a mut [E]>,
+    evaluations: &'a mut [E],
+    divisor_inverses: &'a [&'a DivisorInverse<E::BaseField>],
+    domain: &'a StarkDomain<E::BaseField>,
 
     #[cfg(debug_assertions)]
     tm_evaluations: Vec<&'a mut [E::BaseField]>,
@@ -244,6 +270,30 @@ pub struct EvaluationTableFragment<'a, E: FieldElement> {
 }
 
 impl<'a, E: FieldElement> EvaluationTableFragment<'a, E> {
+    /// Returns a fragment which is not backed by a [ConstraintEvaluationTable], and which records
+    /// evaluations for steps starting at `offset` into the provided `evaluations` slice.
+    ///
+    /// Such fragments are used to evaluate constraints over a part of the constraint evaluation
+    /// domain only. In debug mode, individual transition constraint evaluations are not recorded
+    /// for detached fragments, and thus, their degrees cannot be validated.
+    pub fn detached(
+        offset: usize,
+        evaluations: &'a mut [E],
+        divisor_inverses: &'a [&'a DivisorInverse<E::BaseField>],
+        domain: &'a StarkDomain<E::BaseField>,
+    ) -> Self {
+        EvaluationTableFragment {
+            offset,
+            evaluations,
+            divisor_inverses,
+            domain,
+            #[cfg(debug_assertions)]
+            tm_evaluations: Vec::new(),
+            #[cfg(debug_assertions)]
+            ta_evaluations: Vec::new(),
+        }
+    }
+
     /// Returns the row at which the fragment starts.
     pub fn offset(&self) -> usize {
         self.offset
@@ -251,19 +301,27 @@ impl<'a, E: FieldElement> EvaluationTableFragment<'a, E> {
 
     /// Returns the number of evaluation rows in the fragment.
     pub fn num_rows(&self) -> usize {
-        self.evaluations[0].len()
+        self.evaluations.len()
     }
 
     /// Returns the number of columns in every evaluation row.
     pub fn num_columns(&self) -> usize {
-        self.evaluations.len()
+        self.divisor_inverses.len()
     }
 
     /// Updates a single row in the fragment with provided data.
+    ///
+    /// Each value in `row_data` is divided by the divisor of the corresponding constraint group,
+    /// and the results are added together to get the value of the constraint composition
+    /// polynomial at this row.
     pub fn update_row(&mut self, row_idx: usize, row_data: &[E]) {
-        for (column, &value) in self.evaluations.iter_mut().zip(row_data) {
-            column[row_idx] = value;
-        }
+        let step = self.offset + row_idx;
+        self.evaluations[row_idx] = row_data
+            .iter()
+            .zip(self.divisor_inverses.iter())
+            .fold(E::ZERO, |acc, (&value, divisor)| {
+                acc + value.mul_base(divisor.get_inv_at(step, self.domain))
+            });
     }
 
     /// Updates transition evaluations row with the provided data; available only in debug mode.
@@ -287,6 +345,7 @@ impl<'a, E: FieldElement> EvaluationTableFragment<'a, E> {
 // ================================================================================================
 
 /// Allocates memory for a two-dimensional data structure without initializing it.
+#[cfg(debug_assertions)]
 fn uninit_matrix<E: FieldElement>(num_cols: usize, num_rows: usize) -> Vec<Vec<E>> {
     unsafe { (0..num_cols).map(|_| uninit_vector(num_rows)).collect() }
 }
@@ -297,6 +356,7 @@ fn uninit_matrix<E: FieldElement>(num_cols: usize, num_rows: usize) -> Vec<Vec<E
 ///
 /// If the source data is empty, the returned vector will contain number of empty vectors equal
 /// to `num_fragments` parameter.
+#[cfg(debug_assertions)]
 fn make_fragments<E: FieldElement>(
     source: &mut [Vec<E>],
     num_fragments: usize,
@@ -313,96 +373,75 @@ fn make_fragments<E: FieldElement>(
     result
 }
 
-#[allow(clippy::many_single_char_names)]
-fn acc_column<E: FieldElement>(
-    column: Vec<E>,
-    divisor: &ConstraintDivisor<E::BaseField>,
-    domain: &StarkDomain<E::BaseField>,
-    result: &mut [E],
-) {
-    let numerator = divisor.numerator();
-    assert_eq!(numerator.len(), 1, "complex divisors are not yet supported");
-
-    // compute inverse evaluations of the divisor's numerator, which has the form (x^a - b)
-    let z = get_inv_evaluation(divisor, domain);
-
-    // divide column values by the divisor; for boundary constraints this computed simply as
-    // multiplication of column value by the inverse of divisor numerator; for transition
-    // constraints, it is computed similarly, but the result is also multiplied by the divisor's
-    // denominator (exclusion point).
-    if divisor.exemptions().is_empty() {
-        // the column represents merged evaluations of boundary constraints, and divisor has the
-        // form of (x^a - b); thus to divide the column by the divisor, we compute: value * z,
-        // where z = 1 / (x^a - 1) and has already been computed above.
-        iter_mut!(result, 1024)
-            .zip(column)
-            .enumerate()
-            .for_each(|(i, (acc_value, value))| {
-                // determine which value of z corresponds to the current domain point
-                let z = z[i % z.len()];
-                // compute value * z and add it to the result
-                *acc_value += value.mul_base(z);
-            });
-    } else {
-        // the column represents merged evaluations of transition constraints, and divisor has the
-        // form of (x^a - 1) / e(x), where e(x) describes the exemption points; thus, to divide
-        // the column by the divisor, we compute: value * e(x) * z, where z = 1 / (x^a - 1) and has
-        // already been computed above.
-        batch_iter_mut!(
-            result,
-            128, // min batch size
-            |batch: &mut [E], batch_offset: usize| {
-                for (i, acc_value) in batch.iter_mut().enumerate() {
-                    // compute value of e(x) and compute next value of x
-                    let x = domain.get_ce_x_at(batch_offset + i);
-                    let e = divisor.evaluate_exemptions_at(x);
-                    // determine which value of z corresponds to the current domain point
-                    let z = z[i % z.len()];
-                    // compute value * e(x) * z and add it to the result
-                    *acc_value += column[batch_offset + i].mul_base(z * e);
-                }
-            }
+// DIVISOR INVERSES
+// ================================================================================================
+
+/// Inverse evaluations of a constraint divisor over the constraint evaluation domain.
+///
+/// Numerator of a divisor has the form (x^a - b). Since the constraint evaluation domain is a
+/// coset of a multiplicative subgroup, values of x^a over the domain repeat with period n / a,
+/// where n is the size of the domain. Thus, we need to compute (and invert) only n / a values of
+/// the numerator to be able to divide by it at every step of the domain. Exemption points (if
+/// any) are evaluated at each step individually.
+pub(crate) struct DivisorInverse<B: StarkField> {
+    inv_numerator: Vec<B>,
+    exemptions: Vec<B>,
+}
+
+impl<B: StarkField> DivisorInverse<B> {
+    /// Computes inverse evaluations of the divisor's numerator over a single period of the
+    /// specified domain.
+    pub fn new(divisor: &ConstraintDivisor<B>, domain: &StarkDomain<B>) -> Self {
+        let numerator = divisor.numerator();
+        assert_eq!(numerator.len(), 1, "complex divisors are not yet supported");
+
+        let a = numerator[0].0 as u64; // numerator degree
+        let b = numerator[0].1;
+
+        // this guarantees that we can use get_ce_x_power_at() below but limits execution trace
+        // length to be at most 2^32. in the future, we should revisit this to allow execution
+        // traces of greater length.
+        assert!(
+            a <= u32::MAX as u64,
+            "constraint divisor numerator degree cannot exceed {}, but was {}",
+            u32::MAX,
+            a
         );
+
+        let n = domain.ce_domain_size() / a as usize;
+        let domain_offset_exp = domain.offset().exp(a.into());
+
+        // compute x^a - b for all x in a single period of the domain
+        let evaluations = UninitBuffer::new(n)
+            .init_with(|i| domain.get_ce_x_power_at(i, a, domain_offset_exp) - b);
+
+        // compute 1 / (x^a - b)
+        DivisorInverse {
+            inv_numerator: batch_inversion(&evaluations),
+            exemptions: divisor.exemptions().to_vec(),
+        }
     }
-}
 
-/// Computes evaluations of the divisor's numerator over the domain of the specified size and offset.
-fn get_inv_evaluation<B: StarkField>(
-    divisor: &ConstraintDivisor<B>,
-    domain: &StarkDomain<B>,
-) -> Vec<B> {
-    let numerator = divisor.numerator();
-    let a = numerator[0].0 as u64; // numerator degree
-    let b = numerator[0].1;
-
-    // this guarantees that we can use get_ce_x_power_at() below but limits execution trace length
-    // to be at most 2^32. in the future, we should revisit this to allow execution traces of
-    // greater length.
-    assert!(
-        a <= u32::MAX as u64,
-        "constraint divisor numerator degree cannot exceed {}, but was {}",
-        u32::MAX,
-        a
-    );
-
-    let n = domain.ce_domain_size() / a as usize;
-    let domain_offset_exp = domain.offset().exp(a.into());
-
-    // compute x^a - b for all x
-    let mut evaluations = unsafe { uninit_vector(n) };
-    batch_iter_mut!(
-        &mut evaluations,
-        128, // min batch size
-        |batch: &mut [B], batch_offset: usize| {
-            for (i, evaluation) in batch.iter_mut().enumerate() {
-                let x = domain.get_ce_x_power_at(batch_offset + i, a, domain_offset_exp);
-                *evaluation = x - b;
-            }
+    /// Returns the inverse of the divisor evaluated at the specified step of the constraint
+    /// evaluation domain.
+    ///
+    /// For divisors of the form (x^a - b), this is just 1 / (x^a - b). For divisors of the form
+    /// (x^a - 1) / e(x), where e(x) describes the exemption points, this is e(x) / (x^a - 1).
+    #[inline(always)]
+    pub fn get_inv_at(&self, step: usize, domain: &StarkDomain<B>) -> B {
+        let z = self.inv_numerator[step % self.inv_numerator.len()];
+        if self.exemptions.is_empty() {
+            z
+        } else {
+            let x = domain.get_ce_x_at(step);
+            self.exemptions.iter().fold(z, |r, &e| r * (x - e))
         }
-    );
+    }
 
-    // compute 1 / (x^a - b)
-    batch_inversion(&evaluations)
+    /// Returns the number of bytes occupied by the precomputed inverse evaluations.
+    pub fn size_in_bytes(&self) -> usize {
+        (self.inv_numerator.len() + self.exemptions.len()) * B::ELEMENT_BYTES
+    }
 }
 
 // DEBUG HELPERS
@@ -141,8 +141,7 @@ impl<E: FieldElement> BoundaryConstraintGroup<E> {
     // CONSTRUCTORS
     // --------------------------------------------------------------------------------------------
 
-    /// Returns an empty [BoundaryConstraintGroup] instantiated with the specified divisor and
-    /// degree adjustment factor.
+    /// Returns an empty [BoundaryConstraintGroup] instantiated with the specified divisor.
     fn new(divisor: ConstraintDivisor<E::BaseField>) -> Self {
         Self {
             divisor,
@@ -293,13 +292,15 @@ impl<E: FieldElement> BoundaryConstraintGroup<E> {
 // ================================================================================================
 
 /// A constraint where the numerator can be represented by p(x) - v, where v is the asserted value,
-/// and p(x) is the trace polynomial for the column against which the constraint is applied.
+/// and p(x) is the trace polynomial for the column against which the constraint is applied (or a
+/// linear combination of trace polynomials for constraints against multiple columns).
 struct SingleValueConstraint<F, E>
 where
     F: FieldElement,
     E: FieldElement<BaseField = F::BaseField> + ExtensionOf<F>,
 {
     column: usize,
+    terms: Vec<(usize, F)>,
     value: F,
     coefficients: E,
 }
@@ -315,6 +316,7 @@ where
         debug_assert!(source.poly().len() == 1, "not a single constraint");
         Self {
             column: source.column(),
+            terms: source.terms().to_vec(),
             value: source.poly()[0],
             coefficients: *source.cc(),
         }
@@ -324,7 +326,7 @@ where
     ///
     /// This also multiplies by the composition coefficient.
     pub fn evaluate(&self, state: &[F]) -> E {
-        let evaluation = state[self.column] - self.value;
+        let evaluation = get_trace_value(state, self.column, &self.terms) - self.value;
         self.coefficients.mul_base(evaluation)
     }
 }
@@ -341,6 +343,7 @@ where
     E: FieldElement<BaseField = F::BaseField> + ExtensionOf<F>,
 {
     column: usize,
+    terms: Vec<(usize, F)>,
     poly: Vec<F>,
     x_offset: F::BaseField,
     coefficients: E,
@@ -360,6 +363,7 @@ where
         );
         Self {
             column: source.column(),
+            terms: source.terms().to_vec(),
             poly: source.poly().to_vec(),
             x_offset: source.poly_offset().1,
             coefficients: *source.cc(),
@@ -376,7 +380,7 @@ where
         let assertion_value =
             self.poly.iter().rev().fold(F::ZERO, |acc, &coeff| acc.mul_base(x) + coeff);
         // evaluate the constraint
-        let evaluation = state[self.column] - assertion_value;
+        let evaluation = get_trace_value(state, self.column, &self.terms) - assertion_value;
         self.coefficients.mul_base(evaluation)
     }
 }
@@ -390,6 +394,7 @@ where
     E: FieldElement<BaseField = F::BaseField> + ExtensionOf<F>,
 {
     column: usize,
+    terms: Vec<(usize, F)>,
     values: Vec<F>,
     step_offset: usize,
     coefficients: E,
@@ -423,6 +428,7 @@ where
 
         LargePolyConstraint {
             column: source.column(),
+            terms: source.terms().to_vec(),
             values,
             step_offset: source.poly_offset().0 * air.ce_blowup_factor(),
             coefficients: *source.cc(),
@@ -431,8 +437,9 @@ where
 
     /// Evaluates this constraint at the specified step of the constraint evaluation domain.
     ///
-    /// This also applies composition coefficients as well as the degree adjustment factor
-    /// (defined by `xp` parameter) to the evaluation before it is returned.
+    /// This also applies the composition coefficient of the constraint to the evaluation before
+    /// it is returned; constraint degrees are not adjusted since the composition polynomial is
+    /// bounded by the number of its columns instead.
     pub fn evaluate(&self, state: &[F], ce_step: usize) -> E {
         let value_index = if self.step_offset > 0 {
             // if the assertion happens on steps which are not a power of 2, we need to offset the
@@ -446,7 +453,24 @@ where
         } else {
             ce_step
         };
-        let evaluation = state[self.column] - self.values[value_index];
+        let evaluation =
+            get_trace_value(state, self.column, &self.terms) - self.values[value_index];
         (self.coefficients).mul_base(evaluation)
     }
 }
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns the value of `column` in the specified `state`, or the linear combination of values of
+/// the columns specified by `terms` if the constraint is placed against multiple columns.
+#[inline(always)]
+fn get_trace_value<F: FieldElement>(state: &[F], column: usize, terms: &[(usize, F)]) -> F {
+    if terms.is_empty() {
+        state[column]
+    } else {
+        terms
+            .iter()
+            .fold(F::ZERO, |acc, &(column, coefficient)| acc + coefficient * state[column])
+    }
+}
@@ -4,15 +4,21 @@
 // LICENSE file in the root directory of this source tree.
 
 use super::{
-    super::EvaluationTableFragment, BoundaryConstraints, CompositionPolyTrace,
-    ConstraintEvaluationTable, ConstraintEvaluator, PeriodicValueTable, StarkDomain, TraceLde,
+    super::{DivisorInverse, EvaluationTableFragment},
+    BoundaryConstraints, CompositionPolyTrace, ConstraintEvaluationTable, ConstraintEvaluator,
+    PeriodicValueTable, StarkDomain, TraceLde,
+};
+use crate::{
+    trace::{LdeFrames, TraceFrames},
+    NumaPartitioning, ProverSession,
 };
 use air::{
-    Air, AuxTraceRandElements, ConstraintCompositionCoefficients, EvaluationFrame,
-    TransitionConstraints,
+    Air, AuxTraceRandElements, ConstraintCompositionCoefficients, ConstraintDivisor,
+    EvaluationFrame, TransitionConstraints,
 };
+use core::ops::Range;
 use math::FieldElement;
-use utils::iter_mut;
+use utils::{collections::Vec, iter_mut, uninit_vector};
 
 #[cfg(feature = "concurrent")]
 use utils::{iterators::*, rayon};
@@ -40,7 +46,7 @@ pub struct DefaultConstraintEvaluator<'a, A: Air, E: FieldElement<BaseField = A:
     boundary_constraints: BoundaryConstraints<E>,
     transition_constraints: TransitionConstraints<E>,
     aux_rand_elements: AuxTraceRandElements<E>,
-    periodic_values: PeriodicValueTable<E::BaseField>,
+    main_transition_evaluations: Option<Vec<A::BaseField>>,
 }
 
 impl<'a, A, E> ConstraintEvaluator<E> for DefaultConstraintEvaluator<'a, A, E>
@@ -54,6 +60,168 @@ where
         self,
         trace: &T,
         domain: &StarkDomain<<E as FieldElement>::BaseField>,
+    ) -> CompositionPolyTrace<E> {
+        self.evaluate_over_domain(trace, domain, None)
+    }
+
+    fn evaluate_partitioned<T: TraceLde<E>>(
+        self,
+        trace: &T,
+        domain: &StarkDomain<E::BaseField>,
+        partitioning: &NumaPartitioning,
+    ) -> CompositionPolyTrace<E> {
+        self.evaluate_over_domain(trace, domain, Some(partitioning))
+    }
+
+    fn evaluate_in_session<T: TraceLde<E>>(
+        self,
+        trace: &T,
+        session: &ProverSession<E::BaseField>,
+    ) -> CompositionPolyTrace<E> {
+        let domain = session.domain();
+
+        // use periodic values precomputed in the session if they match periodic columns of
+        // the AIR; otherwise, build them reusing evaluations of the matching columns
+        let computed_periodic_values;
+        let periodic_values = match session.get_periodic_values(self.air) {
+            Some(periodic_values) => periodic_values,
+            None => {
+                computed_periodic_values =
+                    PeriodicValueTable::with_cached_columns(self.air, |values| {
+                        session.get_periodic_column(values)
+                    });
+                &computed_periodic_values
+            }
+        };
+
+        // compute inverse evaluations only for the divisors which were not precomputed in the
+        // session
+        let divisors = self.get_divisors();
+        let cached_inverses = divisors
+            .iter()
+            .map(|divisor| session.get_divisor_inverse(divisor))
+            .collect::<Vec<_>>();
+        let computed_inverses = divisors
+            .iter()
+            .zip(cached_inverses.iter())
+            .filter(|(_, inverse)| inverse.is_none())
+            .map(|(divisor, _)| DivisorInverse::new(divisor, domain))
+            .collect::<Vec<_>>();
+        let mut computed_inverses = computed_inverses.iter();
+        let divisor_inverses = cached_inverses
+            .into_iter()
+            .map(|inverse| inverse.unwrap_or_else(|| computed_inverses.next().unwrap()))
+            .collect();
+
+        self.evaluate_with(trace, domain, periodic_values, divisors, divisor_inverses, None)
+    }
+}
+
+impl<'a, A, E> DefaultConstraintEvaluator<'a, A, E>
+where
+    A: Air,
+    E: FieldElement<BaseField = A::BaseField>,
+{
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+    /// Returns a new evaluator which can be used to evaluate transition and boundary constraints
+    /// over extended execution trace.
+    pub fn new(
+        air: &'a A,
+        aux_rand_elements: AuxTraceRandElements<E>,
+        composition_coefficients: ConstraintCompositionCoefficients<E>,
+    ) -> Self {
+        // build transition constraint groups; these will be used to compose transition constraint
+        // evaluations
+        let transition_constraints =
+            air.get_transition_constraints(&composition_coefficients.transition);
+
+        // build boundary constraint groups; these will be used to evaluate and compose boundary
+        // constraint evaluations.
+        let boundary_constraints =
+            BoundaryConstraints::new(air, &aux_rand_elements, &composition_coefficients.boundary);
+
+        DefaultConstraintEvaluator {
+            air,
+            boundary_constraints,
+            transition_constraints,
+            aux_rand_elements,
+            main_transition_evaluations: None,
+        }
+    }
+
+    /// Returns this evaluator updated to use the provided evaluations of main trace segment
+    /// transition constraints instead of evaluating these constraints via
+    /// [Air::evaluate_transition()].
+    ///
+    /// The evaluations must be arranged by steps of the constraint evaluation domain, with all
+    /// constraint evaluations at a given step stored next to each other.
+    #[cfg(feature = "gpu")]
+    pub(crate) fn with_main_transition_evaluations(
+        mut self,
+        evaluations: Vec<A::BaseField>,
+    ) -> Self {
+        debug_assert_eq!(
+            0,
+            evaluations.len() % self.num_main_transition_constraints().max(1),
+            "number of evaluations must be a multiple of the number of constraints"
+        );
+        self.main_transition_evaluations = Some(evaluations);
+        self
+    }
+
+    // EVALUATION HELPERS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a list of constraint divisors; currently, all transition constraints have the
+    /// same divisor which we put at the front of the list; boundary constraint divisors are
+    /// appended after that.
+    fn get_divisors(&self) -> Vec<ConstraintDivisor<E::BaseField>> {
+        let mut divisors = vec![self.transition_constraints.divisor().clone()];
+        divisors.append(&mut self.boundary_constraints.get_divisors());
+        debug_assert_eq!(
+            divisors,
+            self.air.get_divisors(&self.aux_rand_elements),
+            "divisors must be the same as the divisors defined by the AIR"
+        );
+        divisors
+    }
+
+    /// Evaluates constraints over the constraint evaluation domain, partitioning the work among
+    /// NUMA nodes if `partitioning` is provided.
+    fn evaluate_over_domain<T: TraceLde<E>>(
+        self,
+        trace: &T,
+        domain: &StarkDomain<E::BaseField>,
+        partitioning: Option<&NumaPartitioning>,
+    ) -> CompositionPolyTrace<E> {
+        let periodic_values = PeriodicValueTable::new(self.air);
+        let divisors = self.get_divisors();
+        let divisor_inverses = divisors
+            .iter()
+            .map(|divisor| DivisorInverse::new(divisor, domain))
+            .collect::<Vec<_>>();
+
+        self.evaluate_with(
+            trace,
+            domain,
+            &periodic_values,
+            divisors,
+            divisor_inverses.iter().collect(),
+            partitioning,
+        )
+    }
+
+    /// Evaluates constraints over the constraint evaluation domain using the provided periodic
+    /// values and inverse evaluations of constraint divisors.
+    fn evaluate_with<T: TraceLde<E>>(
+        self,
+        trace: &T,
+        domain: &StarkDomain<E::BaseField>,
+        periodic_values: &PeriodicValueTable<E::BaseField>,
+        divisors: Vec<ConstraintDivisor<E::BaseField>>,
+        divisor_inverses: Vec<&DivisorInverse<E::BaseField>>,
+        partitioning: Option<&NumaPartitioning>,
     ) -> CompositionPolyTrace<E> {
         assert_eq!(
             trace.trace_len(),
@@ -61,20 +229,19 @@ where
             "extended trace length is not consistent with evaluation domain"
         );
 
-        // build a list of constraint divisors; currently, all transition constraints have the same
-        // divisor which we put at the front of the list; boundary constraint divisors are appended
-        // after that
-        let mut divisors = vec![self.transition_constraints.divisor().clone()];
-        divisors.append(&mut self.boundary_constraints.get_divisors());
-
         // allocate space for constraint evaluations; when we are in debug mode, we also allocate
         // memory to hold all transition constraint evaluations (before they are merged into a
         // single value) so that we can check their degrees later
         #[cfg(not(debug_assertions))]
-        let mut evaluation_table = ConstraintEvaluationTable::<E>::new(domain, divisors);
-        #[cfg(debug_assertions)]
         let mut evaluation_table =
-            ConstraintEvaluationTable::<E>::new(domain, divisors, &self.transition_constraints);
+            ConstraintEvaluationTable::<E>::new(domain, divisors, divisor_inverses);
+        #[cfg(debug_assertions)]
+        let mut evaluation_table = ConstraintEvaluationTable::<E>::new(
+            domain,
+            divisors,
+            divisor_inverses,
+            &self.transition_constraints,
+        );
 
         // when `concurrent` feature is enabled, break the evaluation table into multiple fragments
         // to evaluate them into multiple threads; unless the constraint evaluation domain is small,
@@ -94,74 +261,97 @@ where
         // we evaluate constraints for all segments. otherwise, we evaluate constraints only
         // for the main segment.
         let mut fragments = evaluation_table.fragments(num_fragments);
-        iter_mut!(fragments).for_each(|fragment| {
+        let trace = LdeFrames(trace);
+        let evaluate_fragment = |fragment: &mut EvaluationTableFragment<E>| {
             if self.air.trace_info().is_multi_segment() {
-                self.evaluate_fragment_full(trace, domain, fragment);
+                self.evaluate_fragment_full(&trace, domain, periodic_values, fragment);
             } else {
-                self.evaluate_fragment_main(trace, domain, fragment);
+                self.evaluate_fragment_main(&trace, domain, periodic_values, fragment);
             }
-        });
+        };
+
+        // with NUMA partitioning, contiguous groups of fragments are evaluated by threads of the
+        // same node; since the evaluation table is not initialized before the evaluation, its
+        // memory is first touched by the threads which write into it
+        match partitioning {
+            Some(partitioning) => partitioning.for_each_chunk(&mut fragments, 1, |fragments, _| {
+                fragments.iter_mut().for_each(evaluate_fragment)
+            }),
+            None => iter_mut!(fragments).for_each(evaluate_fragment),
+        }
 
         // when in debug mode, make sure expected transition constraint degrees align with
         // actual degrees we got during constraint evaluation
         #[cfg(debug_assertions)]
-        evaluation_table.validate_transition_degrees();
+        let degree_check = evaluation_table.validate_transition_degrees();
 
-        // combine all evaluations into a single column and return
-        evaluation_table.combine()
+        // combine all evaluations into a single column and return; a degree mismatch is
+        // reported when the composition polynomial is built from the evaluations
+        #[allow(unused_mut)]
+        let mut composition_trace = evaluation_table.combine();
+        #[cfg(debug_assertions)]
+        if let Err(error) = degree_check {
+            composition_trace.set_degree_error(error);
+        }
+        composition_trace
     }
-}
-
-impl<'a, A, E> DefaultConstraintEvaluator<'a, A, E>
-where
-    A: Air,
-    E: FieldElement<BaseField = A::BaseField>,
-{
-    // CONSTRUCTOR
-    // --------------------------------------------------------------------------------------------
-    /// Returns a new evaluator which can be used to evaluate transition and boundary constraints
-    /// over extended execution trace.
-    pub fn new(
-        air: &'a A,
-        aux_rand_elements: AuxTraceRandElements<E>,
-        composition_coefficients: ConstraintCompositionCoefficients<E>,
-    ) -> Self {
-        // build transition constraint groups; these will be used to compose transition constraint
-        // evaluations
-        let transition_constraints =
-            air.get_transition_constraints(&composition_coefficients.transition);
 
-        // build periodic value table
-        let periodic_values = PeriodicValueTable::new(air);
-
-        // build boundary constraint groups; these will be used to evaluate and compose boundary
-        // constraint evaluations.
-        let boundary_constraints =
-            BoundaryConstraints::new(air, &aux_rand_elements, &composition_coefficients.boundary);
+    /// Evaluates constraints at the specified range of steps of the constraint evaluation domain,
+    /// and returns values of the constraint composition polynomial at these steps.
+    ///
+    /// Unlike [ConstraintEvaluator::evaluate()], this requires the `trace` to provide evaluation
+    /// frames only for the LDE steps which correspond to the specified range. Since only a part
+    /// of the domain is evaluated, transition constraint degrees are not validated in debug mode.
+    pub(crate) fn evaluate_steps<T: TraceFrames<E>>(
+        self,
+        trace: &T,
+        domain: &StarkDomain<E::BaseField>,
+        steps: Range<usize>,
+    ) -> Vec<E> {
+        assert!(
+            steps.end <= domain.ce_domain_size(),
+            "steps must be within the constraint evaluation domain"
+        );
 
-        DefaultConstraintEvaluator {
-            air,
-            boundary_constraints,
-            transition_constraints,
-            aux_rand_elements,
-            periodic_values,
+        let periodic_values = PeriodicValueTable::new(self.air);
+        let divisor_inverses = self
+            .get_divisors()
+            .iter()
+            .map(|divisor| DivisorInverse::new(divisor, domain))
+            .collect::<Vec<_>>();
+        let divisor_inverses = divisor_inverses.iter().collect::<Vec<_>>();
+
+        let mut evaluations = unsafe { uninit_vector(steps.len()) };
+        let mut fragment = EvaluationTableFragment::detached(
+            steps.start,
+            &mut evaluations,
+            &divisor_inverses,
+            domain,
+        );
+        if self.air.trace_info().is_multi_segment() {
+            self.evaluate_fragment_full(trace, domain, &periodic_values, &mut fragment);
+        } else {
+            self.evaluate_fragment_main(trace, domain, &periodic_values, &mut fragment);
         }
-    }
 
-    // EVALUATION HELPERS
-    // --------------------------------------------------------------------------------------------
+        evaluations
+    }
 
     /// Evaluates constraints for a single fragment of the evaluation table.
     ///
     /// This evaluates constraints only over the main segment of the execution trace.
-    fn evaluate_fragment_main<T: TraceLde<E>>(
+    fn evaluate_fragment_main<T: TraceFrames<E>>(
         &self,
         trace: &T,
         domain: &StarkDomain<A::BaseField>,
+        periodic_values: &PeriodicValueTable<A::BaseField>,
         fragment: &mut EvaluationTableFragment<E>,
     ) {
-        // initialize buffers to hold trace values and evaluation results at each step;
-        let mut main_frame = EvaluationFrame::new(trace.trace_layout().main_trace_width());
+        // initialize buffers to hold trace values and evaluation results at each step; the frame
+        // holds a row for every out-of-domain offset of the AIR
+        let ood_offsets = self.air.context().ood_offsets();
+        let mut main_frame =
+            EvaluationFrame::with_offsets(trace.layout().main_trace_width(), ood_offsets);
         let mut evaluations = vec![E::ZERO; fragment.num_columns()];
         let mut t_evaluations = vec![E::BaseField::ZERO; self.num_main_transition_constraints()];
 
@@ -173,14 +363,19 @@ where
             let step = i + fragment.offset();
 
             // update evaluation frame buffer with data from the execution trace; this will
-            // read current and next rows from the trace into the buffer; data in the trace
+            // read all rows of the frame from the trace into the buffer; data in the trace
             // table is extended over the LDE domain, so, we need to convert step in constraint
             // evaluation domain, into a step in LDE domain, in case these domains are different
-            trace.read_main_trace_frame_into(step << lde_shift, &mut main_frame);
+            trace.read_main_frame_into(step << lde_shift, &mut main_frame);
 
             // evaluate transition constraints and save the merged result the first slot of the
             // evaluations buffer
-            evaluations[0] = self.evaluate_main_transition(&main_frame, step, &mut t_evaluations);
+            evaluations[0] = self.evaluate_main_transition(
+                &main_frame,
+                periodic_values,
+                step,
+                &mut t_evaluations,
+            );
 
             // when in debug mode, save transition constraint evaluations
             #[cfg(debug_assertions)]
@@ -205,15 +400,20 @@ where
     ///
     /// This evaluates constraints only over all segments of the execution trace (i.e. main segment
     /// and all auxiliary segments).
-    fn evaluate_fragment_full<T: TraceLde<E>>(
+    fn evaluate_fragment_full<T: TraceFrames<E>>(
         &self,
         trace: &T,
         domain: &StarkDomain<A::BaseField>,
+        periodic_values: &PeriodicValueTable<A::BaseField>,
         fragment: &mut EvaluationTableFragment<E>,
     ) {
-        // initialize buffers to hold trace values and evaluation results at each step
-        let mut main_frame = EvaluationFrame::new(trace.trace_layout().main_trace_width());
-        let mut aux_frame = EvaluationFrame::new(trace.trace_layout().aux_trace_width());
+        // initialize buffers to hold trace values and evaluation results at each step; the frames
+        // hold a row for every out-of-domain offset of the AIR
+        let ood_offsets = self.air.context().ood_offsets();
+        let mut main_frame =
+            EvaluationFrame::with_offsets(trace.layout().main_trace_width(), ood_offsets);
+        let mut aux_frame =
+            EvaluationFrame::with_offsets(trace.layout().aux_trace_width(), ood_offsets);
         let mut tm_evaluations = vec![E::BaseField::ZERO; self.num_main_transition_constraints()];
         let mut ta_evaluations = vec![E::ZERO; self.num_aux_transition_constraints()];
         let mut evaluations = vec![E::ZERO; fragment.num_columns()];
@@ -226,15 +426,25 @@ where
             let step = i + fragment.offset();
 
             // read both the main and the auxiliary evaluation frames from the trace
-            trace.read_main_trace_frame_into(step << lde_shift, &mut main_frame);
-            trace.read_aux_trace_frame_into(step << lde_shift, &mut aux_frame);
+            trace.read_main_frame_into(step << lde_shift, &mut main_frame);
+            trace.read_aux_frame_into(step << lde_shift, &mut aux_frame);
 
             // evaluate transition constraints and save the merged result the first slot of the
             // evaluations buffer; we evaluate and compose constraints in the same function, we
             // can just add up the results of evaluating main and auxiliary constraints.
-            evaluations[0] = self.evaluate_main_transition(&main_frame, step, &mut tm_evaluations);
-            evaluations[0] +=
-                self.evaluate_aux_transition(&main_frame, &aux_frame, step, &mut ta_evaluations);
+            evaluations[0] = self.evaluate_main_transition(
+                &main_frame,
+                periodic_values,
+                step,
+                &mut tm_evaluations,
+            );
+            evaluations[0] += self.evaluate_aux_transition(
+                &main_frame,
+                &aux_frame,
+                periodic_values,
+                step,
+                &mut ta_evaluations,
+            );
 
             // when in debug mode, save transition constraint evaluations
             #[cfg(debug_assertions)]
@@ -268,18 +478,30 @@ where
     fn evaluate_main_transition(
         &self,
         main_frame: &EvaluationFrame<E::BaseField>,
+        periodic_values: &PeriodicValueTable<E::BaseField>,
         step: usize,
         evaluations: &mut [E::BaseField],
     ) -> E {
-        // TODO: use a more efficient way to zero out memory
-        evaluations.fill(E::BaseField::ZERO);
+        match &self.main_transition_evaluations {
+            // if the constraints have already been evaluated (e.g., on a GPU), just copy the
+            // evaluations for the specified step
+            Some(precomputed) => {
+                let num_constraints = evaluations.len();
+                let offset = step * num_constraints;
+                evaluations.copy_from_slice(&precomputed[offset..offset + num_constraints]);
+            }
+            None => {
+                // TODO: use a more efficient way to zero out memory
+                evaluations.fill(E::BaseField::ZERO);
 
-        // get periodic values at the evaluation step
-        let periodic_values = self.periodic_values.get_row(step);
+                // get periodic values at the evaluation step
+                let periodic_values = periodic_values.get_row(step);
 
-        // evaluate transition constraints over the main segment of the execution trace and save
-        // the results into evaluations buffer
-        self.air.evaluate_transition(main_frame, periodic_values, evaluations);
+                // evaluate transition constraints over the main segment of the execution trace
+                // and save the results into evaluations buffer
+                self.air.evaluate_transition(main_frame, periodic_values, evaluations);
+            }
+        }
 
         // merge transition constraint evaluations into a single value and return it;
         // we can do this here because all transition constraints have the same divisor.
@@ -298,6 +520,7 @@ where
         &self,
         main_frame: &EvaluationFrame<E::BaseField>,
         aux_frame: &EvaluationFrame<E>,
+        periodic_values: &PeriodicValueTable<E::BaseField>,
         step: usize,
         evaluations: &mut [E],
     ) -> E {
@@ -305,7 +528,7 @@ where
         evaluations.fill(E::ZERO);
 
         // get periodic values at the evaluation step
-        let periodic_values = self.periodic_values.get_row(step);
+        let periodic_values = periodic_values.get_row(step);
 
         // evaluate transition constraints over auxiliary trace segments and save the results into
         // evaluations buffer
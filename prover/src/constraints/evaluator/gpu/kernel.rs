@@ -0,0 +1,469 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use air::symbolic::{ConstraintGraph, Node, SymbolicField};
+use core::fmt::Write;
+use std::sync::{mpsc, OnceLock};
+use utils::collections::Vec;
+use wgpu::util::DeviceExt;
+
+// CONSTANTS
+// ================================================================================================
+
+/// Modulus of the only field for which kernels can currently be compiled: 2^64 - 2^32 + 1.
+pub const GOLDILOCKS_MODULUS: u64 = 0xffff_ffff_0000_0001;
+
+/// Number of invocations in a single workgroup of the kernel.
+const WORKGROUP_SIZE: usize = 64;
+
+/// Maximum number of workgroups which can be dispatched along a single dimension.
+const MAX_WORKGROUPS: usize = 65535;
+
+/// Number of bytes in a single field element when it is stored on a device.
+const ELEMENT_BYTES: usize = 8;
+
+/// Arithmetic in the field with modulus 2^64 - 2^32 + 1. Field elements are represented in
+/// canonical form as `vec2<u32>(low, high)` words; WGSL has no 64-bit integers.
+const GOLDILOCKS_ARITHMETIC: &str = r#"
+const P: vec2<u32> = vec2<u32>(1u, 0xffffffffu);
+const EPSILON: vec2<u32> = vec2<u32>(0xffffffffu, 0u);
+const ONE: vec2<u32> = vec2<u32>(1u, 0u);
+const ZERO: vec2<u32> = vec2<u32>(0u, 0u);
+
+// returns a + b mod 2^64 in the first two words, and the carry in the last word
+fn add64(a: vec2<u32>, b: vec2<u32>) -> vec3<u32> {
+    let lo = a.x + b.x;
+    let hi = a.y + b.y;
+    let c0 = select(0u, 1u, lo < a.x);
+    let c1 = select(0u, 1u, hi < a.y);
+    let hi_c = hi + c0;
+    let c2 = select(0u, 1u, hi_c < hi);
+    return vec3<u32>(lo, hi_c, c1 | c2);
+}
+
+// returns a - b mod 2^64 in the first two words, and the borrow in the last word
+fn sub64(a: vec2<u32>, b: vec2<u32>) -> vec3<u32> {
+    let lo = a.x - b.x;
+    let hi = a.y - b.y;
+    let b0 = select(0u, 1u, a.x < b.x);
+    let b1 = select(0u, 1u, a.y < b.y);
+    let b2 = select(0u, 1u, hi < b0);
+    return vec3<u32>(lo, hi - b0, b1 | b2);
+}
+
+// returns the full 64-bit product of two 32-bit words
+fn mul32(a: u32, b: u32) -> vec2<u32> {
+    let a0 = a & 0xffffu;
+    let a1 = a >> 16u;
+    let b0 = b & 0xffffu;
+    let b1 = b >> 16u;
+    let p00 = a0 * b0;
+    let p01 = a0 * b1;
+    let p10 = a1 * b0;
+    let p11 = a1 * b1;
+    let mid = p01 + p10;
+    let mid_carry = select(0u, 0x10000u, mid < p01);
+    let lo = p00 + (mid << 16u);
+    let lo_carry = select(0u, 1u, lo < p00);
+    return vec2<u32>(lo, p11 + (mid >> 16u) + mid_carry + lo_carry);
+}
+
+fn canonicalize(a: vec2<u32>) -> vec2<u32> {
+    if (a.y == 0xffffffffu && a.x >= 1u) {
+        return sub64(a, P).xy;
+    }
+    return a;
+}
+
+fn gl_add(a: vec2<u32>, b: vec2<u32>) -> vec2<u32> {
+    let r = add64(a, b);
+    if (r.z == 1u) {
+        return add64(r.xy, EPSILON).xy;
+    }
+    return canonicalize(r.xy);
+}
+
+fn gl_sub(a: vec2<u32>, b: vec2<u32>) -> vec2<u32> {
+    let r = sub64(a, b);
+    if (r.z == 1u) {
+        return sub64(r.xy, EPSILON).xy;
+    }
+    return r.xy;
+}
+
+fn gl_mul(a: vec2<u32>, b: vec2<u32>) -> vec2<u32> {
+    // compute the 128-bit product as four 32-bit limbs r0..r3
+    let ll = mul32(a.x, b.x);
+    let lh = mul32(a.x, b.y);
+    let hl = mul32(a.y, b.x);
+    let hh = mul32(a.y, b.y);
+
+    let t1 = ll.y + lh.x;
+    let r1 = t1 + hl.x;
+    let c1 = select(0u, 1u, t1 < lh.x) + select(0u, 1u, r1 < hl.x);
+
+    let t2 = lh.y + hl.y;
+    let t3 = t2 + hh.x;
+    let r2 = t3 + c1;
+    let c2 = select(0u, 1u, t2 < hl.y) + select(0u, 1u, t3 < hh.x) + select(0u, 1u, r2 < t3);
+    let r3 = hh.y + c2;
+
+    // reduce using 2^64 = 2^32 - 1 and 2^96 = -1 (mod p)
+    var t0 = sub64(vec2<u32>(ll.x, r1), vec2<u32>(r3, 0u));
+    if (t0.z == 1u) {
+        t0 = sub64(t0.xy, EPSILON);
+    }
+    let r2_eps = sub64(vec2<u32>(0u, r2), vec2<u32>(r2, 0u)).xy;
+    let r = add64(t0.xy, r2_eps);
+    if (r.z == 1u) {
+        return canonicalize(add64(r.xy, EPSILON).xy);
+    }
+    return canonicalize(r.xy);
+}
+
+fn gl_exp(base: vec2<u32>, power: vec2<u32>) -> vec2<u32> {
+    var result = ONE;
+    var b = base;
+    for (var i = 0u; i < 64u; i = i + 1u) {
+        let word = select(power.y, power.x, i < 32u);
+        if (((word >> (i & 31u)) & 1u) == 1u) {
+            result = gl_mul(result, b);
+        }
+        b = gl_mul(b, b);
+    }
+    return result;
+}
+"#;
+
+// CONSTRAINT KERNEL
+// ================================================================================================
+
+/// A compute kernel which evaluates transition constraints described by a [ConstraintGraph].
+///
+/// The kernel evaluates constraints at a set of steps in parallel, one step per invocation. For
+/// each step, it reads the current and the next rows of the main trace segment as well as
+/// values of periodic columns at that step from separate buffers, and writes evaluations of all
+/// constraints at that step into the output buffer.
+pub struct ConstraintKernel {
+    device: &'static Device,
+    pipeline: wgpu::ComputePipeline,
+    layout: wgpu::BindGroupLayout,
+    max_rows: usize,
+    num_constraints: usize,
+}
+
+impl ConstraintKernel {
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+    /// Compiles the specified constraint graph into a kernel for the default GPU device.
+    ///
+    /// Returns `None` if no device is available, or if the device failed to compile the kernel.
+    pub fn new<B: SymbolicField<PositiveInteger = u64>>(
+        graph: &ConstraintGraph<B>,
+        trace_width: usize,
+        num_periodic_columns: usize,
+    ) -> Option<Self> {
+        debug_assert_eq!(GOLDILOCKS_MODULUS, B::MODULUS, "field must be the Goldilocks field");
+        let device = Device::get()?;
+        let num_constraints = graph.outputs().len();
+        let source = build_source(graph, trace_width, num_periodic_columns);
+
+        // catch validation errors instead of letting the device panic so that the caller can
+        // fall back to evaluating constraints on the CPU
+        device.device.push_error_scope(wgpu::ErrorFilter::Validation);
+        let module = device.device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("transition constraints"),
+            source: wgpu::ShaderSource::Wgsl(source.into()),
+        });
+        let layout = device.device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("transition constraints"),
+            entries: &[
+                storage_layout_entry(0, true),
+                storage_layout_entry(1, true),
+                storage_layout_entry(2, true),
+                storage_layout_entry(3, false),
+            ],
+        });
+        let pipeline_layout =
+            device.device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("transition constraints"),
+                bind_group_layouts: &[&layout],
+                push_constant_ranges: &[],
+            });
+        let pipeline = device.device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some("transition constraints"),
+            layout: Some(&pipeline_layout),
+            module: &module,
+            entry_point: Some("main"),
+            compilation_options: Default::default(),
+            cache: None,
+        });
+        if pollster::block_on(device.device.pop_error_scope()).is_some() {
+            return None;
+        }
+
+        // the number of rows processed in a single dispatch is limited by the size of the
+        // largest buffer bound to the kernel, and by the number of workgroups
+        let row_bytes = ELEMENT_BYTES * trace_width.max(num_periodic_columns).max(num_constraints);
+        let max_rows = (device.max_binding_size / row_bytes).min(MAX_WORKGROUPS * WORKGROUP_SIZE);
+
+        Some(Self {
+            device,
+            pipeline,
+            layout,
+            max_rows,
+            num_constraints,
+        })
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the maximum number of steps which can be evaluated via a single call to
+    /// [evaluate()](Self::evaluate).
+    pub fn max_rows(&self) -> usize {
+        self.max_rows
+    }
+
+    // EVALUATOR
+    // --------------------------------------------------------------------------------------------
+
+    /// Evaluates constraints at `num_rows` steps, and returns the evaluations arranged by steps.
+    ///
+    /// Buffers contain canonical field elements as 8-byte little-endian words: `current` and
+    /// `next` hold rows of the main trace segment, and `periodic` holds rows of periodic values.
+    /// The returned vector has the same encoding.
+    ///
+    /// Returns `None` if the device ran out of memory, rejected any of the submitted commands, or
+    /// has been lost before the evaluations were read back.
+    pub fn evaluate(
+        &self,
+        current: &[u8],
+        next: &[u8],
+        periodic: &[u8],
+        num_rows: usize,
+    ) -> Option<Vec<u8>> {
+        assert!(num_rows <= self.max_rows, "too many rows for a single dispatch");
+        let device = &self.device.device;
+
+        // catch errors instead of letting the device panic so that the caller can fall back to
+        // evaluating constraints on the CPU
+        device.push_error_scope(wgpu::ErrorFilter::OutOfMemory);
+        device.push_error_scope(wgpu::ErrorFilter::Validation);
+        let current = self.create_input_buffer(current);
+        let next = self.create_input_buffer(next);
+        let periodic = self.create_input_buffer(periodic);
+        let output_size = (num_rows * self.num_constraints * ELEMENT_BYTES) as u64;
+        let output = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("constraint evaluations"),
+            size: output_size,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+        let staging = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("constraint evaluations staging"),
+            size: output_size,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("transition constraints"),
+            layout: &self.layout,
+            entries: &[
+                buffer_entry(0, &current),
+                buffer_entry(1, &next),
+                buffer_entry(2, &periodic),
+                buffer_entry(3, &output),
+            ],
+        });
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("transition constraints"),
+        });
+        {
+            let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                label: Some("transition constraints"),
+                timestamp_writes: None,
+            });
+            pass.set_pipeline(&self.pipeline);
+            pass.set_bind_group(0, &bind_group, &[]);
+            pass.dispatch_workgroups(num_rows.div_ceil(WORKGROUP_SIZE) as u32, 1, 1);
+        }
+        encoder.copy_buffer_to_buffer(&output, 0, &staging, 0, output_size);
+        self.device.queue.submit(Some(encoder.finish()));
+        let validation_error = pollster::block_on(device.pop_error_scope());
+        let memory_error = pollster::block_on(device.pop_error_scope());
+        if validation_error.is_some() || memory_error.is_some() {
+            return None;
+        }
+
+        // wait for the device to finish, and read the evaluations back
+        let slice = staging.slice(..);
+        let (sender, receiver) = mpsc::channel();
+        slice.map_async(wgpu::MapMode::Read, move |result| {
+            let _ = sender.send(result);
+        });
+        device.poll(wgpu::Maintain::Wait);
+        receiver.recv().ok()?.ok()?;
+        let result = slice.get_mapped_range().to_vec();
+        staging.unmap();
+        Some(result)
+    }
+
+    // HELPER METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a storage buffer initialized with the specified bytes; buffers must not be empty,
+    /// and thus, empty inputs are replaced with a single zero element.
+    fn create_input_buffer(&self, contents: &[u8]) -> wgpu::Buffer {
+        let contents = if contents.is_empty() {
+            &[0; ELEMENT_BYTES][..]
+        } else {
+            contents
+        };
+        self.device.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("constraint inputs"),
+            contents,
+            usage: wgpu::BufferUsages::STORAGE,
+        })
+    }
+}
+
+// DEVICE
+// ================================================================================================
+
+/// A GPU device (together with its queue) shared by all kernels.
+struct Device {
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    max_binding_size: usize,
+}
+
+static DEVICE: OnceLock<Option<Device>> = OnceLock::new();
+
+impl Device {
+    /// Returns the default device, requesting it on the first call; returns `None` if no device
+    /// is available.
+    fn get() -> Option<&'static Self> {
+        DEVICE.get_or_init(|| pollster::block_on(Self::request())).as_ref()
+    }
+
+    async fn request() -> Option<Self> {
+        let instance = wgpu::Instance::default();
+        let adapter = instance
+            .request_adapter(&wgpu::RequestAdapterOptions {
+                power_preference: wgpu::PowerPreference::HighPerformance,
+                ..Default::default()
+            })
+            .await?;
+        let limits = adapter.limits();
+        let (device, queue) = adapter
+            .request_device(
+                &wgpu::DeviceDescriptor {
+                    label: Some("winterfell prover"),
+                    required_features: wgpu::Features::empty(),
+                    required_limits: limits.clone(),
+                    memory_hints: wgpu::MemoryHints::Performance,
+                },
+                None,
+            )
+            .await
+            .ok()?;
+        let max_binding_size =
+            (limits.max_storage_buffer_binding_size as u64).min(limits.max_buffer_size) as usize;
+
+        Some(Self {
+            device,
+            queue,
+            max_binding_size,
+        })
+    }
+}
+
+// KERNEL SOURCE
+// ================================================================================================
+
+/// Returns WGSL source of a kernel evaluating the constraints described by the specified graph.
+fn build_source<B: SymbolicField<PositiveInteger = u64>>(
+    graph: &ConstraintGraph<B>,
+    trace_width: usize,
+    num_periodic_columns: usize,
+) -> String {
+    let num_constraints = graph.outputs().len();
+    let mut s = String::from(GOLDILOCKS_ARITHMETIC);
+    s.push_str(
+        r#"
+@group(0) @binding(0) var<storage, read> current: array<vec2<u32>>;
+@group(0) @binding(1) var<storage, read> next: array<vec2<u32>>;
+@group(0) @binding(2) var<storage, read> periodic: array<vec2<u32>>;
+@group(0) @binding(3) var<storage, read_write> result: array<vec2<u32>>;
+"#,
+    );
+    writeln!(s, "\n@compute @workgroup_size({WORKGROUP_SIZE})").unwrap();
+    writeln!(s, "fn main(@builtin(global_invocation_id) id: vec3<u32>) {{").unwrap();
+    writeln!(s, "    let row = id.x;").unwrap();
+    writeln!(s, "    if (row >= arrayLength(&result) / {num_constraints}u) {{ return; }}").unwrap();
+    writeln!(s, "    let trace_row = row * {trace_width}u;").unwrap();
+    writeln!(s, "    let periodic_row = row * {num_periodic_columns}u;").unwrap();
+
+    for (i, node) in graph.nodes().iter().enumerate() {
+        let value = match *node {
+            Node::Constant(value) => word(value.as_int()),
+            Node::Current(col) => format!("current[trace_row + {col}u]"),
+            Node::Next(col) => format!("next[trace_row + {col}u]"),
+            Node::Periodic(col) => format!("periodic[periodic_row + {col}u]"),
+            Node::Add(a, b) => format!("gl_add(v{a}, v{b})"),
+            Node::Sub(a, b) => format!("gl_sub(v{a}, v{b})"),
+            Node::Mul(a, b) => format!("gl_mul(v{a}, v{b})"),
+            Node::Neg(a) => format!("gl_sub(ZERO, v{a})"),
+            Node::Inv(a) => format!("gl_exp(v{a}, {})", word(GOLDILOCKS_MODULUS - 2)),
+            Node::Exp(a, power) => format!("gl_exp(v{a}, {})", word(reduce_power(power))),
+        };
+        writeln!(s, "    let v{i} = {value};").unwrap();
+    }
+    for (i, output) in graph.outputs().iter().enumerate() {
+        writeln!(s, "    result[row * {num_constraints}u + {i}u] = v{output};").unwrap();
+    }
+    s.push_str("}\n");
+    s
+}
+
+/// Returns a WGSL expression for the specified 64-bit word.
+fn word(value: u64) -> String {
+    format!("vec2<u32>({}u, {}u)", value as u32, (value >> 32) as u32)
+}
+
+/// Reduces a non-zero exponent modulo the order of the multiplicative group so that it fits into
+/// a 64-bit word; zero exponents are left as is.
+///
+/// For a non-zero x, x^n = x^(n mod (p - 1)), and the reduced exponent is replaced with p - 1
+/// when it is zero so that zero is still raised to a non-zero power.
+fn reduce_power(power: u128) -> u64 {
+    let group_order = (GOLDILOCKS_MODULUS - 1) as u128;
+    match power % group_order {
+        0 if power != 0 => GOLDILOCKS_MODULUS - 1,
+        reduced => reduced as u64,
+    }
+}
+
+fn storage_layout_entry(binding: u32, read_only: bool) -> wgpu::BindGroupLayoutEntry {
+    wgpu::BindGroupLayoutEntry {
+        binding,
+        visibility: wgpu::ShaderStages::COMPUTE,
+        ty: wgpu::BindingType::Buffer {
+            ty: wgpu::BufferBindingType::Storage { read_only },
+            has_dynamic_offset: false,
+            min_binding_size: None,
+        },
+        count: None,
+    }
+}
+
+fn buffer_entry(binding: u32, buffer: &wgpu::Buffer) -> wgpu::BindGroupEntry<'_> {
+    wgpu::BindGroupEntry {
+        binding,
+        resource: buffer.as_entire_binding(),
+    }
+}
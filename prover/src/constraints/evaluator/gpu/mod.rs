@@ -0,0 +1,208 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{
+    CompositionPolyTrace, ConstraintEvaluator, DefaultConstraintEvaluator, PeriodicValueTable,
+    StarkDomain, TraceLde,
+};
+use crate::{NumaPartitioning, ProverSession};
+use air::{
+    symbolic::{ConstraintGraph, SymbolicField},
+    Air, AuxTraceRandElements, ConstraintCompositionCoefficients, EvaluationFrame,
+};
+use math::{FieldElement, StarkField};
+use utils::collections::Vec;
+
+mod kernel;
+use kernel::{ConstraintKernel, GOLDILOCKS_MODULUS};
+
+#[cfg(test)]
+mod tests;
+
+// GPU CONSTRAINT EVALUATOR
+// ================================================================================================
+
+/// A [ConstraintEvaluator] which evaluates transition constraints of the main trace segment on a
+/// GPU.
+///
+/// Transition constraints are extracted from [Air::evaluate_transition()] by evaluating it over
+/// symbolic field elements (see [ConstraintGraph]), and the resulting expression graph is
+/// compiled into a compute kernel. Thus, no changes to the AIR are required, but the AIR must
+/// express its transition constraints via [FieldElement] arithmetic only. Evaluations computed
+/// by the kernel are then combined with boundary and auxiliary constraint evaluations (computed
+/// on the CPU) exactly as done by [DefaultConstraintEvaluator], and the result is identical to
+/// the result of [DefaultConstraintEvaluator].
+///
+/// Constraints are evaluated on the CPU via [DefaultConstraintEvaluator] when:
+/// * the base field of the computation is not the 64-bit field with modulus 2^64 - 2^32 + 1
+///   (this is the only field for which kernels can currently be compiled),
+/// * the AIR reads rows of the execution trace other than the current and the next rows (see
+///   [AirContext::set_ood_offsets()](air::AirContext::set_ood_offsets)),
+/// * no GPU device is available, or the device failed to compile the kernel or to evaluate it.
+///
+/// This evaluator is available only when `gpu` feature is enabled. To use it, specify it as the
+/// [Prover::ConstraintEvaluator](crate::Prover::ConstraintEvaluator) associated type and
+/// instantiate it in [Prover::new_evaluator()](crate::Prover::new_evaluator).
+pub struct GpuConstraintEvaluator<'a, A: Air, E: FieldElement<BaseField = A::BaseField>> {
+    air: &'a A,
+    graph: Option<ConstraintGraph<A::BaseField>>,
+    evaluator: DefaultConstraintEvaluator<'a, A, E>,
+}
+
+impl<'a, A, E> GpuConstraintEvaluator<'a, A, E>
+where
+    A: Air,
+    A::BaseField: SymbolicField + StarkField<PositiveInteger = u64>,
+    E: FieldElement<BaseField = A::BaseField>,
+{
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+    /// Returns a new evaluator which can be used to evaluate transition and boundary constraints
+    /// over extended execution trace.
+    ///
+    /// If transition constraints of the AIR can be evaluated on a GPU, this also builds the
+    /// graph of these constraints.
+    pub fn new(
+        air: &'a A,
+        aux_rand_elements: AuxTraceRandElements<E>,
+        composition_coefficients: ConstraintCompositionCoefficients<E>,
+    ) -> Self {
+        let graph = Self::is_supported(air).then(|| ConstraintGraph::new(air));
+        let evaluator =
+            DefaultConstraintEvaluator::new(air, aux_rand_elements, composition_coefficients);
+        Self {
+            air,
+            graph,
+            evaluator,
+        }
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the graph of transition constraints which will be evaluated on a GPU, or `None`
+    /// if constraints of the AIR will be evaluated on the CPU.
+    pub fn constraint_graph(&self) -> Option<&ConstraintGraph<A::BaseField>> {
+        self.graph.as_ref()
+    }
+
+    // HELPER METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns true if transition constraints of the specified AIR can be compiled into a kernel.
+    fn is_supported(air: &A) -> bool {
+        A::BaseField::MODULUS == GOLDILOCKS_MODULUS
+            && air.context().ood_offsets() == [0, 1]
+            && air.context().num_main_transition_constraints() > 0
+    }
+
+    /// Evaluates transition constraints of the main trace segment at all steps of the constraint
+    /// evaluation domain on a GPU.
+    ///
+    /// Returns `None` if the constraints cannot be evaluated on a GPU, or if evaluation on the GPU
+    /// failed; otherwise, evaluations are arranged by steps, with evaluations of all constraints
+    /// at a given step stored next to each other.
+    fn evaluate_main_transitions<T: TraceLde<E>>(
+        &self,
+        trace: &T,
+        domain: &StarkDomain<A::BaseField>,
+    ) -> Option<Vec<A::BaseField>> {
+        let graph = self.graph.as_ref()?;
+        let trace_width = trace.trace_layout().main_trace_width();
+        let periodic_values = PeriodicValueTable::new(self.air);
+        let num_periodic_columns = self.air.get_periodic_column_values().len();
+        let kernel = ConstraintKernel::new(graph, trace_width, num_periodic_columns)?;
+
+        // read the main segment of the trace at every step of the constraint evaluation domain;
+        // the next row of step i is at step i + trace_to_ce_blowup (wrapping around)
+        let ce_domain_size = domain.ce_domain_size();
+        let lde_shift = domain.ce_to_lde_blowup().trailing_zeros();
+        let next_step_offset = domain.trace_to_ce_blowup();
+        let row_bytes = trace_width * 8;
+        let mut rows = Vec::with_capacity(ce_domain_size * row_bytes);
+        let mut frame = EvaluationFrame::new(trace_width);
+        for step in 0..ce_domain_size {
+            trace.read_main_trace_frame_into(step << lde_shift, &mut frame);
+            rows.extend(frame.current().iter().flat_map(|value| value.as_int().to_le_bytes()));
+        }
+
+        // evaluate constraints in chunks small enough to be processed in a single dispatch
+        let mut result = Vec::with_capacity(ce_domain_size * graph.outputs().len());
+        let mut start = 0;
+        while start < ce_domain_size {
+            let end = ce_domain_size.min(start + kernel.max_rows());
+            let current = &rows[start * row_bytes..end * row_bytes];
+            let next = (start..end)
+                .flat_map(|step| {
+                    let next_step = (step + next_step_offset) % ce_domain_size;
+                    &rows[next_step * row_bytes..(next_step + 1) * row_bytes]
+                })
+                .copied()
+                .collect::<Vec<_>>();
+            let periodic = (start..end)
+                .flat_map(|step| periodic_values.get_row(step))
+                .flat_map(|value| value.as_int().to_le_bytes())
+                .collect::<Vec<_>>();
+
+            let evaluations = kernel.evaluate(current, &next, &periodic, end - start)?;
+            result.extend(
+                evaluations
+                    .chunks_exact(8)
+                    .map(|bytes| A::BaseField::from(u64::from_le_bytes(bytes.try_into().unwrap()))),
+            );
+            start = end;
+        }
+
+        Some(result)
+    }
+
+    /// Returns the underlying CPU evaluator updated to use transition constraint evaluations
+    /// computed on a GPU, if possible.
+    fn into_evaluator<T: TraceLde<E>>(
+        self,
+        trace: &T,
+        domain: &StarkDomain<A::BaseField>,
+    ) -> DefaultConstraintEvaluator<'a, A, E> {
+        match self.evaluate_main_transitions(trace, domain) {
+            Some(evaluations) => self.evaluator.with_main_transition_evaluations(evaluations),
+            None => self.evaluator,
+        }
+    }
+}
+
+impl<'a, A, E> ConstraintEvaluator<E> for GpuConstraintEvaluator<'a, A, E>
+where
+    A: Air,
+    A::BaseField: SymbolicField + StarkField<PositiveInteger = u64>,
+    E: FieldElement<BaseField = A::BaseField>,
+{
+    type Air = A;
+
+    fn evaluate<T: TraceLde<E>>(
+        self,
+        trace: &T,
+        domain: &StarkDomain<E::BaseField>,
+    ) -> CompositionPolyTrace<E> {
+        self.into_evaluator(trace, domain).evaluate(trace, domain)
+    }
+
+    fn evaluate_in_session<T: TraceLde<E>>(
+        self,
+        trace: &T,
+        session: &ProverSession<E::BaseField>,
+    ) -> CompositionPolyTrace<E> {
+        self.into_evaluator(trace, session.domain()).evaluate_in_session(trace, session)
+    }
+
+    fn evaluate_partitioned<T: TraceLde<E>>(
+        self,
+        trace: &T,
+        domain: &StarkDomain<E::BaseField>,
+        partitioning: &NumaPartitioning,
+    ) -> CompositionPolyTrace<E> {
+        self.into_evaluator(trace, domain)
+            .evaluate_partitioned(trace, domain, partitioning)
+    }
+}
@@ -0,0 +1,214 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{kernel::ConstraintKernel, GpuConstraintEvaluator};
+use crate::{
+    ConstraintEvaluator, DefaultConstraintEvaluator, DefaultTraceLde, StarkDomain, Trace,
+    TraceTable,
+};
+use air::{
+    symbolic::ConstraintGraph, Air, AirContext, Assertion, AuxTraceRandElements, EvaluationFrame,
+    FieldExtension, ProofOptions, TraceInfo, TransitionConstraintDegree,
+};
+use crypto::{hashers::Blake3_256, DefaultRandomCoin, RandomCoin};
+use math::{
+    fields::{f64::BaseElement, QuadExtension},
+    FieldElement, StarkField,
+};
+use rand_utils::rand_vector;
+use utils::collections::Vec;
+
+type Blake3 = Blake3_256<BaseElement>;
+
+// TESTS
+// ================================================================================================
+
+#[test]
+fn gpu_kernel_arithmetic() {
+    let air = ArithmeticAir::new(TraceInfo::new(2, 8), (), options());
+    let graph = ConstraintGraph::new(&air);
+    let Some(kernel) = ConstraintKernel::new(&graph, 2, 0) else {
+        return;
+    };
+
+    // operands include values at the boundaries of 32-bit words and of the field
+    let p = BaseElement::MODULUS;
+    let mut values = [0, 1, 2, 0xffff_ffff, 1 << 32, (1 << 32) + 1, p - 1, p - 2, p >> 1, 1 << 63]
+        .into_iter()
+        .map(BaseElement::new)
+        .collect::<Vec<_>>();
+    values.extend(rand_vector::<BaseElement>(32));
+    let (current, next): (Vec<_>, Vec<_>) = values
+        .iter()
+        .flat_map(|&a| values.iter().map(move |&b| ([a, b], [b, a])))
+        .unzip();
+
+    let num_rows = current.len();
+    let evaluations = kernel.evaluate(&to_bytes(&current), &to_bytes(&next), &[], num_rows);
+    let evaluations = from_bytes(&evaluations.unwrap());
+    let num_constraints = graph.outputs().len();
+    for (i, (current, next)) in current.iter().zip(next.iter()).enumerate() {
+        let expected = graph.evaluate(current, next, &[]);
+        assert_eq!(
+            expected,
+            evaluations[i * num_constraints..(i + 1) * num_constraints],
+            "mismatch for operands {current:?}"
+        );
+    }
+}
+
+#[test]
+fn gpu_constraint_evaluator_matches_default() {
+    let trace_length = 64;
+    let air = GpuTestAir::new(TraceInfo::new(3, trace_length), (), options());
+    let domain = StarkDomain::new(&air);
+    let trace = TraceTable::init((0..3).map(|_| rand_vector(trace_length)).collect());
+    let (trace_lde, _) = DefaultTraceLde::<QuadExtension<BaseElement>, Blake3>::new(
+        &trace.get_info(),
+        trace.main_segment(),
+        &domain,
+    );
+
+    let mut coin = DefaultRandomCoin::<Blake3>::new(&[BaseElement::new(42)]);
+    let coefficients = air.get_constraint_composition_coefficients(&mut coin).unwrap();
+
+    let expected =
+        DefaultConstraintEvaluator::new(&air, AuxTraceRandElements::new(), coefficients.clone())
+            .evaluate(&trace_lde, &domain);
+
+    let evaluator = GpuConstraintEvaluator::new(&air, AuxTraceRandElements::new(), coefficients);
+    assert!(evaluator.constraint_graph().is_some());
+    let actual = evaluator.evaluate(&trace_lde, &domain);
+
+    assert_eq!(expected.into_inner(), actual.into_inner());
+}
+
+#[test]
+fn gpu_constraint_evaluator_falls_back_for_extra_ood_offsets() {
+    let mut air = GpuTestAir::new(TraceInfo::new(3, 64), (), options());
+    air.context = air.context.set_ood_offsets(vec![0, 1, 2]);
+
+    let mut coin = DefaultRandomCoin::<Blake3>::new(&[BaseElement::new(42)]);
+    let coefficients = air.get_constraint_composition_coefficients(&mut coin).unwrap();
+    let evaluator = GpuConstraintEvaluator::<_, BaseElement>::new(
+        &air,
+        AuxTraceRandElements::new(),
+        coefficients,
+    );
+    assert!(evaluator.constraint_graph().is_none());
+}
+
+// TEST AIRS
+// ================================================================================================
+
+fn options() -> ProofOptions {
+    ProofOptions::new(32, 8, 0, FieldExtension::Quadratic, 4, 31)
+}
+
+/// An AIR with constraints exercising all kinds of graph nodes, including periodic columns and
+/// constraints of different degrees.
+struct GpuTestAir {
+    context: AirContext<BaseElement>,
+}
+
+impl Air for GpuTestAir {
+    type BaseField = BaseElement;
+    type PublicInputs = ();
+
+    fn new(trace_info: TraceInfo, _pub_inputs: (), options: ProofOptions) -> Self {
+        let degrees = vec![
+            TransitionConstraintDegree::new(1),
+            TransitionConstraintDegree::with_cycles(2, vec![4]),
+            TransitionConstraintDegree::new(7),
+            TransitionConstraintDegree::new(2),
+        ];
+        Self {
+            context: AirContext::new(trace_info, degrees, 1, options),
+        }
+    }
+
+    fn context(&self) -> &AirContext<Self::BaseField> {
+        &self.context
+    }
+
+    fn evaluate_transition<E: FieldElement + From<Self::BaseField>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        let current = frame.current();
+        let next = frame.next();
+        result[0] = next[0] - (current[0] + current[1] * E::from(3u32));
+        result[1] = next[1] - current[0] * current[1] * periodic_values[0];
+        result[2] = next[2] + current[2].exp(7u32.into()) - E::from(BaseElement::new(5));
+        result[3] = -(current[0] - next[2]) * (current[1] - E::ONE);
+    }
+
+    fn get_assertions(&self) -> Vec<Assertion<Self::BaseField>> {
+        vec![Assertion::single(0, 0, BaseElement::ONE)]
+    }
+
+    fn get_periodic_column_values(&self) -> Vec<Vec<Self::BaseField>> {
+        vec![vec![1u32.into(), 2u32.into(), 3u32.into(), 4u32.into()]]
+    }
+}
+
+/// An AIR with a constraint for each arithmetic operation over two columns.
+struct ArithmeticAir {
+    context: AirContext<BaseElement>,
+}
+
+impl Air for ArithmeticAir {
+    type BaseField = BaseElement;
+    type PublicInputs = ();
+
+    fn new(trace_info: TraceInfo, _pub_inputs: (), options: ProofOptions) -> Self {
+        let degrees = vec![TransitionConstraintDegree::new(2); 9];
+        Self {
+            context: AirContext::new(trace_info, degrees, 1, options),
+        }
+    }
+
+    fn context(&self) -> &AirContext<Self::BaseField> {
+        &self.context
+    }
+
+    fn evaluate_transition<E: FieldElement + From<Self::BaseField>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        _periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        let (a, b) = (frame.current()[0], frame.current()[1]);
+        result[0] = a + b;
+        result[1] = a - b;
+        result[2] = a * b;
+        result[3] = -a;
+        result[4] = a.inv();
+        result[5] = a / b;
+        result[6] = a.exp(u64::MAX.into());
+        result[7] = a.exp((BaseElement::MODULUS - 1).into()) - frame.next()[0];
+        result[8] = b.square() * E::from(BaseElement::new(BaseElement::MODULUS - 1));
+    }
+
+    fn get_assertions(&self) -> Vec<Assertion<Self::BaseField>> {
+        vec![Assertion::single(0, 0, BaseElement::ONE)]
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+fn to_bytes(rows: &[[BaseElement; 2]]) -> Vec<u8> {
+    rows.iter().flatten().flat_map(|value| value.as_int().to_le_bytes()).collect()
+}
+
+fn from_bytes(bytes: &[u8]) -> Vec<BaseElement> {
+    bytes
+        .chunks_exact(8)
+        .map(|bytes| BaseElement::new(u64::from_le_bytes(bytes.try_into().unwrap())))
+        .collect()
+}
@@ -4,17 +4,23 @@
 // LICENSE file in the root directory of this source tree.
 
 use super::{super::TraceLde, CompositionPolyTrace, ConstraintEvaluationTable, StarkDomain};
+use crate::{NumaPartitioning, ProverSession};
 use air::Air;
 use math::FieldElement;
 
 mod default;
 pub use default::DefaultConstraintEvaluator;
 
+#[cfg(feature = "gpu")]
+mod gpu;
+#[cfg(feature = "gpu")]
+pub use gpu::GpuConstraintEvaluator;
+
 mod boundary;
 use boundary::BoundaryConstraints;
 
 mod periodic_table;
-use periodic_table::PeriodicValueTable;
+pub(crate) use periodic_table::PeriodicValueTable;
 
 // CONSTRAINT EVALUATOR TRAIT
 // ================================================================================================
@@ -24,6 +30,18 @@ use periodic_table::PeriodicValueTable;
 /// The logic for evaluating AIR constraints over a single evaluation frame is defined by the [Air]
 /// associated type, and the purpose of this trait is to execute this logic over all evaluation
 /// frames in an extended execution trace.
+///
+/// For AIRs with many high-degree constraints, constraint evaluation may take as much time as
+/// all FFTs performed by the prover. Implementing this trait is the way to move this work to
+/// non-CPU hardware (e.g., GPUs): an implementation could upload the LDE of the trace exposed by
+/// [TraceLde] to the device, evaluate a kernel equivalent to
+/// [Air::evaluate_transition()] and [Air::evaluate_aux_transition()] there, and fall back to
+/// [DefaultConstraintEvaluator] for computations not supported by the kernel. In all cases, the
+/// returned evaluations must be identical to the ones computed by [DefaultConstraintEvaluator],
+/// including the random linear combination of constraints and division by constraint divisors.
+///
+/// When `gpu` feature is enabled, [GpuConstraintEvaluator] provides such an implementation for
+/// transition constraints of the main trace segment.
 pub trait ConstraintEvaluator<E: FieldElement> {
     /// AIR constraints for the computation described by this evaluator.
     type Air: Air<BaseField = E::BaseField>;
@@ -38,4 +56,45 @@ pub trait ConstraintEvaluator<E: FieldElement> {
         trace: &T,
         domain: &StarkDomain<E::BaseField>,
     ) -> CompositionPolyTrace<E>;
+
+    /// Evaluates constraints against the provided extended execution trace using values
+    /// precomputed in the specified session, and returns evaluations of the resulting
+    /// polynomial.
+    ///
+    /// The prover invokes this method instead of [evaluate()](ConstraintEvaluator::evaluate) only
+    /// if the session was built for a computation with the same shape as the one described by
+    /// [Self::Air](ConstraintEvaluator::Air). The result must be identical to the result of
+    /// [evaluate()](ConstraintEvaluator::evaluate) over the session's domain, which is what the
+    /// default implementation does.
+    fn evaluate_in_session<T: TraceLde<E>>(
+        self,
+        trace: &T,
+        session: &ProverSession<E::BaseField>,
+    ) -> CompositionPolyTrace<E>
+    where
+        Self: Sized,
+    {
+        self.evaluate(trace, session.domain())
+    }
+
+    /// Evaluates constraints against the provided extended execution trace partitioning the work
+    /// among NUMA nodes as described by `partitioning`, and returns evaluations of the resulting
+    /// polynomial.
+    ///
+    /// The prover invokes this method instead of [evaluate()](ConstraintEvaluator::evaluate) if
+    /// it was configured with [Prover::numa_partitioning()](crate::Prover::numa_partitioning).
+    /// The result must be identical to the result of [evaluate()](ConstraintEvaluator::evaluate),
+    /// which is what the default implementation does.
+    fn evaluate_partitioned<T: TraceLde<E>>(
+        self,
+        trace: &T,
+        domain: &StarkDomain<E::BaseField>,
+        partitioning: &NumaPartitioning,
+    ) -> CompositionPolyTrace<E>
+    where
+        Self: Sized,
+    {
+        let _ = partitioning;
+        self.evaluate(trace, domain)
+    }
 }
@@ -7,9 +7,12 @@ use air::Air;
 use math::{fft, StarkField};
 use utils::{
     collections::{BTreeMap, Vec},
-    uninit_vector,
+    iter, UninitBuffer,
 };
 
+#[cfg(feature = "concurrent")]
+use utils::iterators::*;
+
 pub struct PeriodicValueTable<B: StarkField> {
     values: Vec<B>,
     length: usize,
@@ -23,10 +26,25 @@ impl<B: StarkField> PeriodicValueTable<B> {
     /// values of all periodic columns normalized to the same length. This enables simple lookup
     /// into the able using step index of the constraint evaluation domain.
     pub fn new<A: Air<BaseField = B>>(air: &A) -> PeriodicValueTable<B> {
-        // get a list of polynomials describing periodic columns from AIR. if there are no
-        // periodic columns return an empty table
-        let polys = air.get_periodic_column_polys();
-        if polys.is_empty() {
+        Self::with_cached_columns(air, |_| None)
+    }
+
+    /// Builds a table of periodic column values for the specified AIR using evaluations of
+    /// periodic columns returned by `get_cached` where possible.
+    ///
+    /// `get_cached` is invoked with the values of each periodic column over a single cycle, and
+    /// should return evaluations of the column over the constraint evaluation domain of the AIR
+    /// (as returned by [get_column()](PeriodicValueTable::get_column)) if these are available.
+    /// All other columns are evaluated from scratch; when `concurrent` feature is enabled,
+    /// columns are evaluated in multiple threads.
+    pub fn with_cached_columns<A, F>(air: &A, get_cached: F) -> PeriodicValueTable<B>
+    where
+        A: Air<BaseField = B>,
+        F: Fn(&[B]) -> Option<Vec<B>>,
+    {
+        // if there are no periodic columns return an empty table
+        let columns = air.get_periodic_column_values();
+        if columns.is_empty() {
             return PeriodicValueTable {
                 values: Vec::new(),
                 length: 0,
@@ -34,37 +52,51 @@ impl<B: StarkField> PeriodicValueTable<B> {
             };
         }
 
-        // determine the size of the biggest polynomial in the set. unwrap is OK here
-        // because if we get here, there must be at least one polynomial in the set.
-        let max_poly_size = polys.iter().max_by_key(|p| p.len()).unwrap().len();
+        // look up cached evaluations for all columns; polynomials describing periodic columns
+        // are needed only if at least one column was not found in the cache
+        let cached = columns.iter().map(|column| get_cached(column)).collect::<Vec<_>>();
+        let polys = if cached.iter().any(|evaluations| evaluations.is_none()) {
+            air.get_periodic_column_polys()
+        } else {
+            Vec::new()
+        };
 
-        // cache twiddles used for polynomial evaluation here so that we don't have to re-build
+        // build twiddles used for polynomial evaluation here so that we don't have to re-build
         // them for polynomials of the same size
         let mut twiddle_map = BTreeMap::new();
+        for (poly, cached) in polys.iter().zip(cached.iter()) {
+            if cached.is_none() {
+                twiddle_map.entry(poly.len()).or_insert_with(|| fft::get_twiddles(poly.len()));
+            }
+        }
 
-        let evaluations = polys
-            .iter()
-            .map(|poly| {
-                let poly_size = poly.len();
-                let num_cycles = (air.trace_length() / poly_size) as u64;
-                let offset = air.domain_offset().exp(num_cycles.into());
-                let twiddles =
-                    twiddle_map.entry(poly_size).or_insert_with(|| fft::get_twiddles(poly_size));
-
-                fft::evaluate_poly_with_offset(poly, twiddles, offset, air.ce_blowup_factor())
+        // evaluate polynomials of all columns not found in the cache over the constraint
+        // evaluation domain
+        let evaluations = iter!(cached)
+            .enumerate()
+            .map(|(i, cached)| match cached {
+                Some(evaluations) => evaluations.clone(),
+                None => {
+                    let poly = &polys[i];
+                    let num_cycles = (air.trace_length() / poly.len()) as u64;
+                    let offset = air.domain_offset().exp(num_cycles.into());
+                    let twiddles = &twiddle_map[&poly.len()];
+                    fft::evaluate_poly_with_offset(poly, twiddles, offset, air.ce_blowup_factor())
+                }
             })
             .collect::<Vec<_>>();
 
+        // determine the length of the longest column in the set. unwrap is OK here because if
+        // we get here, there must be at least one column in the set.
+        let column_length = evaluations.iter().map(|e| e.len()).max().unwrap();
+
         // allocate memory to hold all expanded values and copy polynomial evaluations into the
         // table in such a way that values for the same row are adjacent to each other.
-        let row_width = polys.len();
-        let column_length = max_poly_size * air.ce_blowup_factor();
-        let mut values = unsafe { uninit_vector(row_width * column_length) };
-        for i in 0..column_length {
-            for (j, column) in evaluations.iter().enumerate() {
-                values[i * row_width + j] = column[i % column.len()];
-            }
-        }
+        let row_width = evaluations.len();
+        let values = UninitBuffer::new(row_width * column_length).init_with(|k| {
+            let (i, j) = (k / row_width, k % row_width);
+            evaluations[j][i % evaluations[j].len()]
+        });
 
         PeriodicValueTable {
             values,
@@ -88,6 +120,19 @@ impl<B: StarkField> PeriodicValueTable<B> {
             &self.values[start..start + self.width]
         }
     }
+
+    /// Returns evaluations of the column at the specified index over the constraint evaluation
+    /// domain; the column is expected to repeat every `cycle_length * ce_blowup_factor` steps.
+    pub fn get_column(&self, index: usize, cycle_length: usize, ce_blowup_factor: usize) -> Vec<B> {
+        let column_length = cycle_length * ce_blowup_factor;
+        debug_assert!(index < self.width, "column index out of bounds");
+        debug_assert!(column_length <= self.length, "column length out of bounds");
+        (0..column_length).map(|i| self.values[i * self.width + index]).collect()
+    }
+
+    pub fn size_in_bytes(&self) -> usize {
+        self.values.len() * B::ELEMENT_BYTES
+    }
 }
 
 // TESTS
@@ -95,7 +140,7 @@ impl<B: StarkField> PeriodicValueTable<B> {
 
 #[cfg(test)]
 mod tests {
-    use crate::tests::MockAir;
+    use crate::{tests::MockAir, ProverSession};
     use air::Air;
     use math::{
         fields::f128::BaseElement, get_power_series_with_offset, polynom, FieldElement, StarkField,
@@ -146,6 +191,31 @@ mod tests {
         assert_eq!(expected, actual);
     }
 
+    #[test]
+    fn periodic_value_table_with_cached_columns() {
+        let trace_length = 32;
+        let col1 = vec![1u128, 2].into_iter().map(BaseElement::new).collect::<Vec<_>>();
+        let col2 = vec![3u128, 4, 5, 6].into_iter().map(BaseElement::new).collect::<Vec<_>>();
+        let col3 = vec![7u128, 8, 9, 10].into_iter().map(BaseElement::new).collect::<Vec<_>>();
+
+        // build a session for an AIR with 2 periodic columns
+        let air = MockAir::with_periodic_columns(vec![col1.clone(), col2.clone()], trace_length);
+        let session = ProverSession::new(&air);
+        assert!(session.get_periodic_column(&col3).is_none());
+
+        // build a table for an AIR where only one of the columns matches columns of the session;
+        // the resulting table must be the same as the table built from scratch
+        let air = MockAir::with_periodic_columns(vec![col3, col2, col1], trace_length);
+        let expected = super::PeriodicValueTable::new(&air);
+        let actual = super::PeriodicValueTable::with_cached_columns(&air, |values| {
+            session.get_periodic_column(values)
+        });
+
+        assert_eq!(expected.width, actual.width);
+        assert_eq!(expected.length, actual.length);
+        assert_eq!(expected.values, actual.values);
+    }
+
     fn build_ce_domain(domain_size: usize, domain_offset: BaseElement) -> Vec<BaseElement> {
         let g = BaseElement::get_root_of_unity(domain_size.ilog2());
         get_power_series_with_offset(g, domain_offset, domain_size)
@@ -6,12 +6,16 @@
 use super::{ColMatrix, ConstraintDivisor, RowMatrix, StarkDomain};
 
 mod evaluator;
+#[cfg(feature = "gpu")]
+pub use evaluator::GpuConstraintEvaluator;
+pub(crate) use evaluator::PeriodicValueTable;
 pub use evaluator::{ConstraintEvaluator, DefaultConstraintEvaluator};
 
 mod composition_poly;
 pub use composition_poly::{CompositionPoly, CompositionPolyTrace};
 
 mod evaluation_table;
+pub(crate) use evaluation_table::DivisorInverse;
 pub use evaluation_table::{ConstraintEvaluationTable, EvaluationTableFragment};
 
 mod commitment;
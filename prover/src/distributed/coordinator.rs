@@ -0,0 +1,390 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{RowRangeOpening, SegmentOpening, TracePartition};
+use crate::{matrix::ColMatrix, CompositionPolyTrace, StarkDomain, TraceLde, TracePolyTable};
+use air::{proof::Queries, EvaluationFrame, TraceInfo, TraceLayout};
+use crypto::{BatchMerkleProof, ElementHasher, Hasher, MerkleTree};
+use math::FieldElement;
+use utils::{
+    collections::{BTreeMap, Vec},
+    flatten_vectors,
+};
+
+// TRACE WORKERS
+// ================================================================================================
+
+/// Defines an interface through which [DistributedTraceLde] communicates with the workers which
+/// hold parts of the extended execution trace.
+///
+/// The worker at index `i` is expected to hold the `i`-th part of the trace as described by
+/// [TracePartition]. An implementation may keep workers in the current process (see
+/// [LocalWorkers](super::LocalWorkers)), or forward the requests to remote processes; in the
+/// latter case, workers can be backed by [RowRangeLde](super::RowRangeLde), and messages
+/// exchanged with them can be serialized using their [Serializable](utils::Serializable)
+/// implementations.
+pub trait TraceWorkers<E: FieldElement>: Sync {
+    /// The hash function used for building the Merkle tree commitments to trace segment LDEs.
+    type HashFn: ElementHasher<BaseField = E::BaseField>;
+
+    /// Returns the number of workers; this must be a power of two.
+    fn num_parts(&self) -> usize;
+
+    /// Instructs every worker to extend the main trace segment polynomials over its part of the
+    /// LDE domain and to commit to the resulting rows.
+    ///
+    /// Returns the roots of the Merkle trees built by the workers, ordered by worker index.
+    fn commit_main_segment(
+        &mut self,
+        trace_info: &TraceInfo,
+        main_trace_polys: &ColMatrix<E::BaseField>,
+        domain: &StarkDomain<E::BaseField>,
+        partition: TracePartition,
+    ) -> Vec<<Self::HashFn as Hasher>::Digest>;
+
+    /// Instructs every worker to extend the auxiliary trace segment polynomials over its part of
+    /// the LDE domain and to commit to the resulting rows.
+    ///
+    /// Returns the roots of the Merkle trees built by the workers, ordered by worker index.
+    fn commit_aux_segment(
+        &mut self,
+        aux_trace_polys: &ColMatrix<E>,
+        domain: &StarkDomain<E::BaseField>,
+    ) -> Vec<<Self::HashFn as Hasher>::Digest>;
+
+    /// Reads all rows of the provided frame from the main trace segment at the specified LDE
+    /// step; row `i` of the frame is the row at `lde_step + frame.offsets()[i] * blowup`.
+    fn read_main_trace_frame_into(
+        &self,
+        lde_step: usize,
+        frame: &mut EvaluationFrame<E::BaseField>,
+    );
+
+    /// Reads all rows of the provided frame from the auxiliary trace segment at the specified LDE
+    /// step; row `i` of the frame is the row at `lde_step + frame.offsets()[i] * blowup`.
+    fn read_aux_trace_frame_into(&self, lde_step: usize, frame: &mut EvaluationFrame<E>);
+
+    /// Returns rows of all trace segments at the specified positions together with Merkle
+    /// authentication paths from the roots of the worker at `part_index` to these rows.
+    ///
+    /// All positions are guaranteed to be within the row range of the specified part.
+    fn open(&self, part_index: usize, positions: &[usize]) -> RowRangeOpening<E, Self::HashFn>;
+}
+
+// DISTRIBUTED TRACE LOW DEGREE EXTENSION
+// ================================================================================================
+
+/// Implementation of the [TraceLde] trait in which the extended execution trace is split across
+/// multiple workers.
+///
+/// The coordinator keeps only trace polynomials (which are returned to the prover) and the top
+/// levels of the Merkle trees committing to the trace segments; rows of the extended trace are
+/// held by the workers. Commitments and query proofs are identical to the ones produced by
+/// [DefaultTraceLde](crate::DefaultTraceLde), and thus, proofs generated with this trace LDE can
+/// be verified by the standard verifier.
+pub struct DistributedTraceLde<E: FieldElement, W: TraceWorkers<E>> {
+    workers: W,
+    partition: TracePartition,
+    // commitment to the main segment of the trace
+    main_segment_commitment: PartitionedCommitment<W::HashFn>,
+    // commitments to the auxiliary segments of the trace
+    aux_segment_commitments: Vec<PartitionedCommitment<W::HashFn>>,
+    blowup: usize,
+    trace_info: TraceInfo,
+    _field: core::marker::PhantomData<E>,
+}
+
+impl<E: FieldElement, W: TraceWorkers<E>> DistributedTraceLde<E, W> {
+    /// Takes the main trace segment columns as input, interpolates them into polynomials in
+    /// coefficient form, and instructs the `workers` to extend and commit to parts of the main
+    /// trace segment.
+    ///
+    /// Returns a tuple containing a [TracePolyTable] with the trace polynomials for the main trace
+    /// segment and the new [DistributedTraceLde].
+    ///
+    /// Workers keep enough rows to read evaluation frames containing the current and the next
+    /// rows; for AIRs with additional out-of-domain offsets, use
+    /// [with_ood_offsets()](DistributedTraceLde::with_ood_offsets).
+    ///
+    /// # Panics
+    /// Panics if the LDE domain cannot be split into the number of parts specified by the
+    /// `workers`, or if the workers return an unexpected number of roots.
+    pub fn new(
+        trace_info: &TraceInfo,
+        main_trace: &ColMatrix<E::BaseField>,
+        domain: &StarkDomain<E::BaseField>,
+        workers: W,
+    ) -> (Self, TracePolyTable<E>) {
+        Self::with_ood_offsets(trace_info, main_trace, domain, workers, &[0, 1])
+    }
+
+    /// Same as [new()](DistributedTraceLde::new), but the workers keep enough rows to read
+    /// evaluation frames with rows at all of the specified out-of-domain offsets (see
+    /// [AirContext::ood_offsets()](air::AirContext::ood_offsets)).
+    ///
+    /// # Panics
+    /// Panics if the LDE domain cannot be split into the number of parts specified by the
+    /// `workers`, or if the workers return an unexpected number of roots.
+    pub fn with_ood_offsets(
+        trace_info: &TraceInfo,
+        main_trace: &ColMatrix<E::BaseField>,
+        domain: &StarkDomain<E::BaseField>,
+        mut workers: W,
+        ood_offsets: &[usize],
+    ) -> (Self, TracePolyTable<E>) {
+        let max_frame_offset = ood_offsets.iter().copied().max().unwrap_or(1).max(1);
+        let partition = TracePartition::new(workers.num_parts(), domain.lde_domain_size())
+            .with_max_frame_offset(max_frame_offset);
+
+        let main_trace_polys = main_trace.interpolate_columns();
+        let roots = workers.commit_main_segment(trace_info, &main_trace_polys, domain, partition);
+        let main_segment_commitment = PartitionedCommitment::new(roots, partition);
+
+        let trace_lde = DistributedTraceLde {
+            workers,
+            partition,
+            main_segment_commitment,
+            aux_segment_commitments: Vec::new(),
+            blowup: domain.trace_to_lde_blowup(),
+            trace_info: trace_info.clone(),
+            _field: core::marker::PhantomData,
+        };
+
+        (trace_lde, TracePolyTable::new(main_trace_polys))
+    }
+
+    /// Returns the partition of the LDE domain across the workers.
+    pub fn partition(&self) -> TracePartition {
+        self.partition
+    }
+
+    /// Returns a reference to the workers holding parts of the extended trace.
+    pub fn workers(&self) -> &W {
+        &self.workers
+    }
+}
+
+impl<E: FieldElement, W: TraceWorkers<E>> TraceLde<E> for DistributedTraceLde<E, W> {
+    type HashFn = W::HashFn;
+
+    /// Returns the commitment to the low-degree extension of the main trace segment.
+    fn get_main_trace_commitment(&self) -> <Self::HashFn as Hasher>::Digest {
+        self.main_segment_commitment.root()
+    }
+
+    /// Takes auxiliary trace segment columns as input, interpolates them into polynomials in
+    /// coefficient form, and instructs the workers to extend and commit to parts of the
+    /// auxiliary trace segment.
+    ///
+    /// Returns a tuple containing the column polynomials in coefficient from and the commitment
+    /// to the polynomial evaluations over the LDE domain.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if any of the following are true:
+    /// - the number of rows in the provided `aux_trace` does not match the main trace.
+    /// - this segment would exceed the number of segments specified by the trace layout.
+    fn add_aux_segment(
+        &mut self,
+        aux_trace: &ColMatrix<E>,
+        domain: &StarkDomain<E::BaseField>,
+    ) -> (ColMatrix<E>, <Self::HashFn as Hasher>::Digest) {
+        assert!(
+            self.aux_segment_commitments.len() < self.trace_info.layout().num_aux_segments(),
+            "the specified number of auxiliary segments has already been added"
+        );
+        assert_eq!(
+            self.trace_info.length(),
+            aux_trace.num_rows(),
+            "the number of rows in the auxiliary segment must be the same as in the main segment"
+        );
+
+        let aux_trace_polys = aux_trace.interpolate_columns();
+        let roots = self.workers.commit_aux_segment(&aux_trace_polys, domain);
+        let commitment = PartitionedCommitment::new(roots, self.partition);
+        let root = commitment.root();
+        self.aux_segment_commitments.push(commitment);
+
+        (aux_trace_polys, root)
+    }
+
+    /// Reads all rows of the specified frame from the main trace segment.
+    fn read_main_trace_frame_into(
+        &self,
+        lde_step: usize,
+        frame: &mut EvaluationFrame<E::BaseField>,
+    ) {
+        self.workers.read_main_trace_frame_into(lde_step, frame);
+    }
+
+    /// Reads all rows of the specified frame from the auxiliary trace segment.
+    fn read_aux_trace_frame_into(&self, lde_step: usize, frame: &mut EvaluationFrame<E>) {
+        self.workers.read_aux_trace_frame_into(lde_step, frame);
+    }
+
+    /// Returns trace table rows at the specified positions along with Merkle authentication paths
+    /// from the commitment root to these rows.
+    ///
+    /// Each worker is asked to open only the positions which fall into its part of the trace.
+    fn query(&self, positions: &[usize]) -> Vec<Queries> {
+        // group positions by the parts they belong to, and request openings from the workers;
+        // also record where each position is located within the opening of its part
+        let mut part_positions = BTreeMap::<usize, Vec<usize>>::new();
+        let locations = positions
+            .iter()
+            .map(|&position| {
+                let part_index = self.partition.part_index(position);
+                let positions = part_positions.entry(part_index).or_default();
+                positions.push(position);
+                (part_index, positions.len() - 1)
+            })
+            .collect::<Vec<_>>();
+        let openings = part_positions
+            .iter()
+            .map(|(&part_index, positions)| (part_index, self.workers.open(part_index, positions)))
+            .collect::<BTreeMap<_, _>>();
+
+        // build queries for the main trace segment
+        let main_openings = openings
+            .iter()
+            .map(|(&part_index, opening)| (part_index, &opening.main))
+            .collect();
+        let mut result = vec![build_segment_queries(
+            &self.main_segment_commitment,
+            &main_openings,
+            &locations,
+            positions,
+        )];
+
+        // build queries for auxiliary trace segments
+        for (i, commitment) in self.aux_segment_commitments.iter().enumerate() {
+            let aux_openings = openings
+                .iter()
+                .map(|(&part_index, opening)| (part_index, &opening.aux[i]))
+                .collect();
+            result.push(build_segment_queries(commitment, &aux_openings, &locations, positions));
+        }
+
+        result
+    }
+
+    /// Returns the number of rows in the extended execution trace.
+    fn trace_len(&self) -> usize {
+        self.partition.lde_domain_size()
+    }
+
+    /// Returns blowup factor which was used to extend original execution trace into trace LDE.
+    fn blowup(&self) -> usize {
+        self.blowup
+    }
+
+    /// Returns the trace layout of the execution trace.
+    fn trace_layout(&self) -> &TraceLayout {
+        self.trace_info.layout()
+    }
+}
+
+// CONSTRAINT EVALUATION MERGING
+// ================================================================================================
+
+/// Merges constraint evaluations computed by workers over their parts of the trace (see
+/// [RowRangeLde::evaluate_constraints()](super::RowRangeLde::evaluate_constraints)) into the
+/// evaluations of the constraint composition polynomial over the entire constraint evaluation
+/// domain.
+///
+/// Evaluations must be ordered by worker index.
+///
+/// # Panics
+/// Panics if the total number of evaluations is not a power of two.
+pub fn merge_constraint_evaluations<E: FieldElement>(
+    parts: Vec<Vec<E>>,
+) -> CompositionPolyTrace<E> {
+    CompositionPolyTrace::new(flatten_vectors(&parts))
+}
+
+// PARTITIONED COMMITMENT
+// ================================================================================================
+
+/// Top levels of a Merkle tree whose leaves are the roots of the trees built by the workers.
+///
+/// When the trace consists of a single part, the root of the worker's tree is the commitment
+/// root, and no tree is built.
+struct PartitionedCommitment<H: Hasher> {
+    roots: Vec<H::Digest>,
+    tree: Option<MerkleTree<H>>,
+}
+
+impl<H: Hasher> PartitionedCommitment<H> {
+    /// Builds a Merkle tree from the provided roots of the parts of a trace segment.
+    fn new(roots: Vec<H::Digest>, partition: TracePartition) -> Self {
+        assert_eq!(
+            partition.num_parts(),
+            roots.len(),
+            "expected a root for each of {} parts, but received {}",
+            partition.num_parts(),
+            roots.len()
+        );
+        let tree = if roots.len() > 1 {
+            Some(MerkleTree::new(roots.clone()).expect("failed to construct trace Merkle tree"))
+        } else {
+            None
+        };
+        PartitionedCommitment { roots, tree }
+    }
+
+    /// Returns the root of the Merkle tree committing to the entire trace segment.
+    fn root(&self) -> H::Digest {
+        match &self.tree {
+            Some(tree) => *tree.root(),
+            None => self.roots[0],
+        }
+    }
+
+    /// Extends a Merkle path to the root of the specified part into a path to the commitment
+    /// root.
+    fn extend_path(&self, mut path: Vec<H::Digest>, part_index: usize) -> Vec<H::Digest> {
+        if let Some(tree) = &self.tree {
+            let top_path = tree.prove(part_index).expect("failed to generate a Merkle path");
+            // the first element of the top path is the root of the part, which is already
+            // implied by the path to the part's root
+            path.extend_from_slice(&top_path[1..]);
+        }
+        path
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Assembles rows opened by the workers into queries against the commitment to an entire trace
+/// segment.
+///
+/// `locations` specify the part and the index within the part's opening for every position.
+fn build_segment_queries<F, H>(
+    commitment: &PartitionedCommitment<H>,
+    openings: &BTreeMap<usize, &SegmentOpening<F, H>>,
+    locations: &[(usize, usize)],
+    positions: &[usize],
+) -> Queries
+where
+    F: FieldElement,
+    H: Hasher,
+{
+    let mut rows = Vec::with_capacity(locations.len());
+    let mut paths = Vec::with_capacity(locations.len());
+    for &(part_index, i) in locations.iter() {
+        let opening = openings[&part_index];
+        rows.push(opening.rows[i].clone());
+        paths.push(commitment.extend_path(opening.paths[i].clone(), part_index));
+    }
+
+    let trace_proof = BatchMerkleProof::<H>::from_paths(&paths, positions);
+    debug_assert_eq!(
+        Ok(commitment.root()),
+        trace_proof.get_root(positions),
+        "Merkle paths assembled from worker openings are not consistent with the commitment"
+    );
+    Queries::new(trace_proof, rows)
+}
@@ -0,0 +1,108 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{RowRangeLde, RowRangeOpening, TracePartition, TraceWorkers};
+use crate::{matrix::ColMatrix, StarkDomain};
+use air::{EvaluationFrame, TraceInfo};
+use crypto::ElementHasher;
+use math::FieldElement;
+use utils::collections::Vec;
+
+// LOCAL WORKERS
+// ================================================================================================
+
+/// Implementation of the [TraceWorkers] trait which keeps all workers in the current process.
+///
+/// This implementation does not reduce the memory required to hold the extended trace, but it
+/// can be used to test proving flows built on top of [DistributedTraceLde](super::DistributedTraceLde)
+/// before workers are moved to separate processes.
+pub struct LocalWorkers<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> {
+    num_parts: usize,
+    partition: Option<TracePartition>,
+    workers: Vec<RowRangeLde<E, H>>,
+}
+
+impl<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> LocalWorkers<E, H> {
+    /// Returns a new set of `num_parts` local workers.
+    ///
+    /// # Panics
+    /// Panics if `num_parts` is not a power of two.
+    pub fn new(num_parts: usize) -> Self {
+        assert!(num_parts.is_power_of_two(), "number of parts must be a power of two");
+        Self {
+            num_parts,
+            partition: None,
+            workers: Vec::new(),
+        }
+    }
+
+    /// Returns the workers holding parts of the extended trace, ordered by part index.
+    ///
+    /// The list is empty until the main trace segment is committed to.
+    pub fn workers(&self) -> &[RowRangeLde<E, H>] {
+        &self.workers
+    }
+
+    /// Returns the worker holding the specified LDE step.
+    fn get_worker(&self, lde_step: usize) -> &RowRangeLde<E, H> {
+        let partition = self.partition.expect("main trace segment has not been committed to");
+        &self.workers[partition.part_index(lde_step)]
+    }
+}
+
+impl<E, H> TraceWorkers<E> for LocalWorkers<E, H>
+where
+    E: FieldElement,
+    H: ElementHasher<BaseField = E::BaseField>,
+{
+    type HashFn = H;
+
+    fn num_parts(&self) -> usize {
+        self.num_parts
+    }
+
+    fn commit_main_segment(
+        &mut self,
+        trace_info: &TraceInfo,
+        main_trace_polys: &ColMatrix<E::BaseField>,
+        domain: &StarkDomain<E::BaseField>,
+        partition: TracePartition,
+    ) -> Vec<H::Digest> {
+        self.workers = (0..partition.num_parts())
+            .map(|part_index| {
+                RowRangeLde::new(trace_info, main_trace_polys, domain, partition, part_index)
+            })
+            .collect();
+        self.partition = Some(partition);
+        self.workers.iter().map(|worker| worker.main_segment_root()).collect()
+    }
+
+    fn commit_aux_segment(
+        &mut self,
+        aux_trace_polys: &ColMatrix<E>,
+        domain: &StarkDomain<E::BaseField>,
+    ) -> Vec<H::Digest> {
+        self.workers
+            .iter_mut()
+            .map(|worker| worker.add_aux_segment(aux_trace_polys, domain))
+            .collect()
+    }
+
+    fn read_main_trace_frame_into(
+        &self,
+        lde_step: usize,
+        frame: &mut EvaluationFrame<E::BaseField>,
+    ) {
+        self.get_worker(lde_step).read_main_trace_frame_into(lde_step, frame);
+    }
+
+    fn read_aux_trace_frame_into(&self, lde_step: usize, frame: &mut EvaluationFrame<E>) {
+        self.get_worker(lde_step).read_aux_trace_frame_into(lde_step, frame);
+    }
+
+    fn open(&self, part_index: usize, positions: &[usize]) -> RowRangeOpening<E, H> {
+        self.workers[part_index].open(positions)
+    }
+}
@@ -0,0 +1,136 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+//! Components for distributing the extended execution trace across multiple workers.
+//!
+//! For the largest computations, the low-degree extension (LDE) of the execution trace does not
+//! fit into memory of a single machine. This module splits the LDE domain into a set of
+//! contiguous row ranges (called parts) such that each part can be extended, hashed, and
+//! committed to by a separate worker:
+//!
+//! - The coordinator interpolates trace columns into polynomials and sends the polynomials to
+//!   the workers.
+//! - Each worker ([RowRangeLde]) evaluates the polynomials over the LDE domain, retains only the
+//!   rows of its part, hashes these rows into Merkle tree leaves, and builds a Merkle tree over
+//!   them. Since the number of parts is a power of two, the root of this tree is a node of the
+//!   Merkle tree which [DefaultTraceLde](crate::DefaultTraceLde) would have built over the
+//!   entire LDE.
+//! - The coordinator ([DistributedTraceLde]) builds the top of the Merkle tree from the roots
+//!   reported by the workers. When the trace is queried, the coordinator requests rows and
+//!   partial Merkle paths from the workers ([RowRangeOpening]), and extends the paths to the
+//!   commitment root.
+//!
+//! Thus, the generated proof is identical to the proof which would have been generated with
+//! [DefaultTraceLde](crate::DefaultTraceLde). Communication between the coordinator and workers
+//! is abstracted by the [TraceWorkers] trait; [LocalWorkers] is an implementation of this trait
+//! which keeps all workers in the current process.
+//!
+//! Workers can also evaluate constraints over their parts of the trace
+//! (see [RowRangeLde::evaluate_constraints()]). Evaluations returned by all workers can be
+//! merged into the evaluations of the constraint composition polynomial over the entire
+//! constraint evaluation domain via [merge_constraint_evaluations()].
+
+mod coordinator;
+pub use coordinator::{merge_constraint_evaluations, DistributedTraceLde, TraceWorkers};
+
+mod worker;
+pub use worker::{RowRangeLde, RowRangeOpening, SegmentOpening};
+
+mod local;
+pub use local::LocalWorkers;
+
+#[cfg(test)]
+mod tests;
+
+use core::ops::Range;
+
+// TRACE PARTITION
+// ================================================================================================
+
+/// Describes how the LDE domain of an execution trace is split into contiguous row ranges.
+///
+/// The number of parts must be a power of two, and each part must contain at least two rows.
+/// Thus, every part corresponds to a complete subtree of the Merkle tree built over the rows of
+/// the extended trace.
+///
+/// To read evaluation frames for all steps of its part, a worker also keeps the rows which follow
+/// its part up to the largest out-of-domain offset of the AIR (see
+/// [with_max_frame_offset()](TracePartition::with_max_frame_offset)).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TracePartition {
+    num_parts: usize,
+    lde_domain_size: usize,
+    max_frame_offset: usize,
+}
+
+impl TracePartition {
+    /// Returns a new partition of an LDE domain of the specified size into `num_parts` parts.
+    ///
+    /// # Panics
+    /// Panics if:
+    /// - `num_parts` is zero or is not a power of two.
+    /// - `lde_domain_size` is not a power of two.
+    /// - A part would contain fewer than two rows.
+    pub fn new(num_parts: usize, lde_domain_size: usize) -> Self {
+        assert!(num_parts.is_power_of_two(), "number of parts must be a power of two");
+        assert!(lde_domain_size.is_power_of_two(), "LDE domain size must be a power of two");
+        assert!(
+            lde_domain_size / num_parts >= 2,
+            "each part must contain at least 2 rows, but LDE domain of {lde_domain_size} rows \
+            cannot be split into {num_parts} parts"
+        );
+        Self {
+            num_parts,
+            lde_domain_size,
+            max_frame_offset: 1,
+        }
+    }
+
+    /// Sets the largest offset (in trace steps) of a row in evaluation frames read from this
+    /// partition; by default, the offset is 1 (i.e., frames contain the current and the next
+    /// rows).
+    ///
+    /// # Panics
+    /// Panics if `max_frame_offset` is zero.
+    pub fn with_max_frame_offset(mut self, max_frame_offset: usize) -> Self {
+        assert!(max_frame_offset > 0, "max frame offset must be greater than zero");
+        self.max_frame_offset = max_frame_offset;
+        self
+    }
+
+    /// Returns the number of parts in this partition.
+    pub fn num_parts(&self) -> usize {
+        self.num_parts
+    }
+
+    /// Returns the size of the partitioned LDE domain.
+    pub fn lde_domain_size(&self) -> usize {
+        self.lde_domain_size
+    }
+
+    /// Returns the largest offset (in trace steps) of a row in evaluation frames read from this
+    /// partition.
+    pub fn max_frame_offset(&self) -> usize {
+        self.max_frame_offset
+    }
+
+    /// Returns the number of rows in every part.
+    pub fn part_size(&self) -> usize {
+        self.lde_domain_size / self.num_parts
+    }
+
+    /// Returns the range of LDE rows of the part at the specified index.
+    pub fn row_range(&self, part_index: usize) -> Range<usize> {
+        assert!(part_index < self.num_parts, "part index {part_index} is out of bounds");
+        let start = part_index * self.part_size();
+        start..start + self.part_size()
+    }
+
+    /// Returns the index of the part which contains the specified LDE row.
+    pub fn part_index(&self, lde_step: usize) -> usize {
+        assert!(lde_step < self.lde_domain_size, "LDE step {lde_step} is out of bounds");
+        lde_step / self.part_size()
+    }
+}
@@ -0,0 +1,351 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{
+    merge_constraint_evaluations, DistributedTraceLde, LocalWorkers, RowRangeOpening,
+    TracePartition,
+};
+use crate::{
+    matrix::ColMatrix,
+    tests::{build_fib_trace, MockAir},
+    ConstraintEvaluator, DefaultConstraintEvaluator, DefaultTraceLde, Prover, StarkDomain, Trace,
+    TraceLde, TracePolyTable, TraceTable,
+};
+use air::{
+    Air, AirContext, Assertion, AuxTraceRandElements, ConstraintCompositionCoefficients,
+    EvaluationFrame, FieldExtension, ProofOptions, TraceInfo, TraceLayout,
+    TransitionConstraintDegree,
+};
+use crypto::{hashers::Blake3_256, DefaultRandomCoin, RandomCoin};
+use math::{fields::f128::BaseElement, FieldElement};
+use utils::{collections::Vec, Deserializable, Serializable};
+
+type Blake3 = Blake3_256<BaseElement>;
+
+#[test]
+fn trace_partition() {
+    let partition = TracePartition::new(4, 64);
+    assert_eq!(4, partition.num_parts());
+    assert_eq!(16, partition.part_size());
+    assert_eq!(16..32, partition.row_range(1));
+    assert_eq!(0, partition.part_index(15));
+    assert_eq!(3, partition.part_index(63));
+}
+
+#[test]
+#[should_panic(expected = "each part must contain at least 2 rows")]
+fn trace_partition_too_many_parts() {
+    TracePartition::new(64, 64);
+}
+
+#[test]
+fn distributed_trace_lde_matches_default() {
+    // build the trace and the domain
+    let trace_length = 8;
+    let air = MockAir::with_trace_length(trace_length);
+    let trace = build_fib_trace(trace_length * 2);
+    let domain = StarkDomain::new(&air);
+    let trace_info = TraceInfo::new_multi_segment(TraceLayout::new(2, [1], [1]), 8, Vec::new());
+
+    let aux_trace = ColMatrix::new(vec![(0..trace_length as u32)
+        .map(|i| BaseElement::from(i * i + 7))
+        .collect::<Vec<_>>()]);
+
+    // build the reference trace LDE
+    let (mut expected_lde, expected_polys) =
+        DefaultTraceLde::<BaseElement, Blake3>::new(&trace_info, trace.main_segment(), &domain);
+    let (expected_aux_polys, expected_aux_root) = expected_lde.add_aux_segment(&aux_trace, &domain);
+
+    let positions = [1, 9, 17, 63, 40, 3, 32];
+    let ood_offsets = [0, 1, 3];
+    for num_parts in [1, 2, 4, 8, 16, 32] {
+        let workers = LocalWorkers::<BaseElement, Blake3>::new(num_parts);
+        let (mut trace_lde, trace_polys) = DistributedTraceLde::with_ood_offsets(
+            &trace_info,
+            trace.main_segment(),
+            &domain,
+            workers,
+            &ood_offsets,
+        );
+        let (aux_polys, aux_root) = trace_lde.add_aux_segment(&aux_trace, &domain);
+
+        // commitments and polynomials must be the same as for the default trace LDE
+        assert_eq!(expected_lde.get_main_trace_commitment(), trace_lde.get_main_trace_commitment());
+        assert_eq!(expected_aux_root, aux_root);
+        assert_eq!(
+            expected_polys.main_trace_polys().count(),
+            trace_polys.main_trace_polys().count()
+        );
+        for (expected, actual) in
+            expected_polys.main_trace_polys().zip(trace_polys.main_trace_polys())
+        {
+            assert_eq!(expected, actual);
+        }
+        assert_eq!(expected_aux_polys.get_column(0), aux_polys.get_column(0));
+
+        // frames (including rows at additional offsets) must be the same at every step of the
+        // LDE domain
+        let mut expected_frame = EvaluationFrame::with_offsets(2, &ood_offsets);
+        let mut frame = EvaluationFrame::with_offsets(2, &ood_offsets);
+        let mut expected_aux_frame = EvaluationFrame::with_offsets(1, &ood_offsets);
+        let mut aux_frame = EvaluationFrame::with_offsets(1, &ood_offsets);
+        for step in 0..domain.lde_domain_size() {
+            expected_lde.read_main_trace_frame_into(step, &mut expected_frame);
+            trace_lde.read_main_trace_frame_into(step, &mut frame);
+            expected_lde.read_aux_trace_frame_into(step, &mut expected_aux_frame);
+            trace_lde.read_aux_trace_frame_into(step, &mut aux_frame);
+            for row in 0..ood_offsets.len() {
+                assert_eq!(expected_frame.row(row), frame.row(row));
+                assert_eq!(expected_aux_frame.row(row), aux_frame.row(row));
+            }
+        }
+
+        // queries must be the same as for the default trace LDE
+        assert_eq!(expected_lde.query(&positions), trace_lde.query(&positions));
+    }
+}
+
+#[test]
+fn row_range_opening_serialization() {
+    let trace_length = 8;
+    let air = MockAir::with_trace_length(trace_length);
+    let trace = build_fib_trace(trace_length * 2);
+    let domain = StarkDomain::new(&air);
+
+    let workers = LocalWorkers::<BaseElement, Blake3>::new(4);
+    let (trace_lde, _) =
+        DistributedTraceLde::new(&trace.get_info(), trace.main_segment(), &domain, workers);
+
+    let worker = &trace_lde.workers().workers()[2];
+    assert_eq!(2, worker.part_index());
+    assert_eq!(32..48, worker.row_range());
+
+    let opening = worker.open(&[33, 47, 40]);
+    assert_eq!(3, opening.main.rows.len());
+    assert!(opening.aux.is_empty());
+
+    let bytes = opening.to_bytes();
+    let parsed = RowRangeOpening::<BaseElement, Blake3>::read_from_bytes(&bytes).unwrap();
+    assert_eq!(opening, parsed);
+
+    // truncated openings must be rejected
+    assert!(
+        RowRangeOpening::<BaseElement, Blake3>::read_from_bytes(&bytes[..bytes.len() - 1]).is_err()
+    );
+}
+
+// PROOF GENERATION TESTS
+// ================================================================================================
+
+#[test]
+fn distributed_proofs_match_default() {
+    for field_extension in [FieldExtension::None, FieldExtension::Quadratic] {
+        let options = ProofOptions::new(28, 8, 0, field_extension, 4, 7);
+        let trace = build_fib_trace(128);
+        let expected = FibProver(options.clone()).prove(trace.clone()).unwrap();
+
+        // proofs generated with the trace split across workers must be identical to proofs
+        // generated with the trace held in a single place
+        for num_parts in [1, 4, 32] {
+            let prover = DistributedFibProver {
+                options: options.clone(),
+                num_parts,
+            };
+            let proof = prover.prove(trace.clone()).unwrap();
+            assert_eq!(expected.to_bytes(), proof.to_bytes());
+        }
+    }
+}
+
+#[test]
+fn distributed_constraint_evaluation() {
+    let options = ProofOptions::new(28, 8, 0, FieldExtension::None, 4, 7);
+    let trace = build_fib_trace(128);
+    let air = FibAir::new(trace.get_info(), get_result(&trace), options);
+    let domain = StarkDomain::new(&air);
+
+    let mut coin = DefaultRandomCoin::<Blake3>::new(&[BaseElement::ONE]);
+    let coefficients = air
+        .get_constraint_composition_coefficients::<BaseElement, _>(&mut coin)
+        .unwrap();
+
+    // evaluate constraints over the entire trace
+    let (trace_lde, _) = DefaultTraceLde::<BaseElement, Blake3>::new(
+        &trace.get_info(),
+        trace.main_segment(),
+        &domain,
+    );
+    let expected =
+        DefaultConstraintEvaluator::new(&air, AuxTraceRandElements::new(), coefficients.clone())
+            .evaluate(&trace_lde, &domain)
+            .into_inner();
+
+    // evaluations computed by individual workers must merge into the same evaluations
+    for num_parts in [1, 2, 8] {
+        let workers = LocalWorkers::<BaseElement, Blake3>::new(num_parts);
+        let (trace_lde, _) =
+            DistributedTraceLde::new(&trace.get_info(), trace.main_segment(), &domain, workers);
+        let evaluations = trace_lde
+            .workers()
+            .workers()
+            .iter()
+            .map(|worker| {
+                worker.evaluate_constraints(
+                    &air,
+                    AuxTraceRandElements::new(),
+                    coefficients.clone(),
+                    &domain,
+                )
+            })
+            .collect();
+        let merged = merge_constraint_evaluations(evaluations);
+        assert_eq!(expected, merged.into_inner());
+    }
+}
+
+// FIBONACCI AIR AND PROVERS
+// ================================================================================================
+
+/// AIR for the sequence built by [build_fib_trace()]; the public input is the last value in the
+/// second column.
+struct FibAir {
+    context: AirContext<BaseElement>,
+    result: BaseElement,
+}
+
+impl Air for FibAir {
+    type BaseField = BaseElement;
+    type PublicInputs = BaseElement;
+
+    fn new(trace_info: TraceInfo, result: BaseElement, options: ProofOptions) -> Self {
+        let degrees = vec![TransitionConstraintDegree::new(1), TransitionConstraintDegree::new(1)];
+        FibAir {
+            context: AirContext::new(trace_info, degrees, 3, options),
+            result,
+        }
+    }
+
+    fn context(&self) -> &AirContext<Self::BaseField> {
+        &self.context
+    }
+
+    fn evaluate_transition<E: FieldElement + From<Self::BaseField>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        _periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        let current = frame.current();
+        let next = frame.next();
+        result[0] = next[0] - (current[0] + current[1]);
+        result[1] = next[1] - (current[0] + current[1].double());
+    }
+
+    fn get_assertions(&self) -> Vec<Assertion<Self::BaseField>> {
+        let last_step = self.trace_length() - 1;
+        vec![
+            Assertion::single(0, 0, BaseElement::ONE),
+            Assertion::single(1, 0, BaseElement::ONE),
+            Assertion::single(1, last_step, self.result),
+        ]
+    }
+}
+
+/// Fibonacci prover which holds the entire extended execution trace in one place.
+struct FibProver(ProofOptions);
+
+impl Prover for FibProver {
+    type BaseField = BaseElement;
+    type Air = FibAir;
+    type Trace = TraceTable<BaseElement>;
+    type HashFn = Blake3;
+    type RandomCoin = DefaultRandomCoin<Blake3>;
+    type TraceLde<E: FieldElement<BaseField = Self::BaseField>> = DefaultTraceLde<E, Self::HashFn>;
+    type ConstraintEvaluator<'a, E: FieldElement<BaseField = Self::BaseField>> =
+        DefaultConstraintEvaluator<'a, Self::Air, E>;
+
+    fn get_pub_inputs(&self, trace: &Self::Trace) -> BaseElement {
+        get_result(trace)
+    }
+
+    fn options(&self) -> &ProofOptions {
+        &self.0
+    }
+
+    fn is_deterministic(&self) -> bool {
+        true
+    }
+
+    fn new_trace_lde<E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        trace_info: &TraceInfo,
+        main_trace: &ColMatrix<Self::BaseField>,
+        domain: &StarkDomain<Self::BaseField>,
+    ) -> (Self::TraceLde<E>, TracePolyTable<E>) {
+        DefaultTraceLde::new(trace_info, main_trace, domain)
+    }
+
+    fn new_evaluator<'a, E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        air: &'a Self::Air,
+        aux_rand_elements: AuxTraceRandElements<E>,
+        composition_coefficients: ConstraintCompositionCoefficients<E>,
+    ) -> Self::ConstraintEvaluator<'a, E> {
+        DefaultConstraintEvaluator::new(air, aux_rand_elements, composition_coefficients)
+    }
+}
+
+/// Fibonacci prover which splits the extended execution trace across in-process workers.
+struct DistributedFibProver {
+    options: ProofOptions,
+    num_parts: usize,
+}
+
+impl Prover for DistributedFibProver {
+    type BaseField = BaseElement;
+    type Air = FibAir;
+    type Trace = TraceTable<BaseElement>;
+    type HashFn = Blake3;
+    type RandomCoin = DefaultRandomCoin<Blake3>;
+    type TraceLde<E: FieldElement<BaseField = Self::BaseField>> =
+        DistributedTraceLde<E, LocalWorkers<E, Self::HashFn>>;
+    type ConstraintEvaluator<'a, E: FieldElement<BaseField = Self::BaseField>> =
+        DefaultConstraintEvaluator<'a, Self::Air, E>;
+
+    fn get_pub_inputs(&self, trace: &Self::Trace) -> BaseElement {
+        get_result(trace)
+    }
+
+    fn options(&self) -> &ProofOptions {
+        &self.options
+    }
+
+    fn is_deterministic(&self) -> bool {
+        true
+    }
+
+    fn new_trace_lde<E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        trace_info: &TraceInfo,
+        main_trace: &ColMatrix<Self::BaseField>,
+        domain: &StarkDomain<Self::BaseField>,
+    ) -> (Self::TraceLde<E>, TracePolyTable<E>) {
+        let workers = LocalWorkers::new(self.num_parts);
+        DistributedTraceLde::new(trace_info, main_trace, domain, workers)
+    }
+
+    fn new_evaluator<'a, E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        air: &'a Self::Air,
+        aux_rand_elements: AuxTraceRandElements<E>,
+        composition_coefficients: ConstraintCompositionCoefficients<E>,
+    ) -> Self::ConstraintEvaluator<'a, E> {
+        DefaultConstraintEvaluator::new(air, aux_rand_elements, composition_coefficients)
+    }
+}
+
+fn get_result(trace: &TraceTable<BaseElement>) -> BaseElement {
+    trace.get(1, trace.length() - 1)
+}
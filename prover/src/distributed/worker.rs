@@ -0,0 +1,442 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::TracePartition;
+use crate::{matrix::ColMatrix, trace::TraceFrames, DefaultConstraintEvaluator, StarkDomain};
+use air::{
+    Air, AuxTraceRandElements, ConstraintCompositionCoefficients, EvaluationFrame, TraceInfo,
+    TraceLayout,
+};
+use core::ops::Range;
+use crypto::{ElementHasher, Hasher, MerkleTree};
+use math::{fft, FieldElement};
+use utils::{
+    collections::Vec, ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable,
+};
+
+// ROW RANGE LOW DEGREE EXTENSION
+// ================================================================================================
+
+/// Contains a single part of the extended execution trace and commitments to this part.
+///
+/// A part is a contiguous range of rows of the LDE domain (see [TracePartition]). For every
+/// trace segment, the rows of the part are hashed into leaves of a Merkle tree, and the root of
+/// this tree is reported to the coordinator as the commitment to the part.
+///
+/// In addition to the rows of its part, a worker keeps `blowup * max_frame_offset` rows which
+/// follow the part (wrapping around at the end of the LDE domain), where `max_frame_offset` is
+/// specified by the [TracePartition]. Thus, the worker can read evaluation frames for all steps
+/// of its part without communicating with other workers.
+pub struct RowRangeLde<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> {
+    main_segment: RowRangeSegment<E::BaseField, H>,
+    aux_segments: Vec<RowRangeSegment<E, H>>,
+    partition: TracePartition,
+    part_index: usize,
+    blowup: usize,
+    trace_info: TraceInfo,
+}
+
+impl<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> RowRangeLde<E, H> {
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+    /// Evaluates the main trace segment polynomials over the rows of the specified part of the
+    /// LDE domain, and commits to the resulting rows.
+    ///
+    /// Polynomials are evaluated one at a time, and only the rows required by this part are
+    /// retained. Thus, apart from the rows of the part, memory required by the worker is
+    /// bounded by the size of a single extended column.
+    ///
+    /// # Panics
+    /// Panics if the `partition` was built for an LDE domain of a different size, or if
+    /// `part_index` is out of bounds.
+    pub fn new(
+        trace_info: &TraceInfo,
+        main_trace_polys: &ColMatrix<E::BaseField>,
+        domain: &StarkDomain<E::BaseField>,
+        partition: TracePartition,
+        part_index: usize,
+    ) -> Self {
+        assert_eq!(
+            partition.lde_domain_size(),
+            domain.lde_domain_size(),
+            "partition is not consistent with the LDE domain"
+        );
+        let rows = partition.row_range(part_index);
+        let main_segment =
+            RowRangeSegment::new(main_trace_polys, domain, rows, partition.max_frame_offset());
+
+        RowRangeLde {
+            main_segment,
+            aux_segments: Vec::new(),
+            partition,
+            part_index,
+            blowup: domain.trace_to_lde_blowup(),
+            trace_info: trace_info.clone(),
+        }
+    }
+
+    // AUXILIARY SEGMENTS
+    // --------------------------------------------------------------------------------------------
+
+    /// Evaluates auxiliary trace segment polynomials over the rows of this part, and commits to
+    /// the resulting rows.
+    ///
+    /// Returns the root of the Merkle tree built over the rows of this part.
+    ///
+    /// # Panics
+    /// Panics if this segment would exceed the number of segments specified by the trace layout.
+    pub fn add_aux_segment(
+        &mut self,
+        aux_trace_polys: &ColMatrix<E>,
+        domain: &StarkDomain<E::BaseField>,
+    ) -> H::Digest {
+        assert!(
+            self.aux_segments.len() < self.trace_info.layout().num_aux_segments(),
+            "the specified number of auxiliary segments has already been added"
+        );
+        let segment = RowRangeSegment::new(
+            aux_trace_polys,
+            domain,
+            self.row_range(),
+            self.partition.max_frame_offset(),
+        );
+        let root = segment.root();
+        self.aux_segments.push(segment);
+        root
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the index of the part held by this worker.
+    pub fn part_index(&self) -> usize {
+        self.part_index
+    }
+
+    /// Returns the range of LDE rows held by this worker.
+    pub fn row_range(&self) -> Range<usize> {
+        self.partition.row_range(self.part_index)
+    }
+
+    /// Returns the root of the Merkle tree built over the rows of this part of the main trace
+    /// segment.
+    pub fn main_segment_root(&self) -> H::Digest {
+        self.main_segment.root()
+    }
+
+    /// Returns the roots of the Merkle trees built over the rows of this part of the auxiliary
+    /// trace segments.
+    pub fn aux_segment_roots(&self) -> Vec<H::Digest> {
+        self.aux_segments.iter().map(|segment| segment.root()).collect()
+    }
+
+    // TRACE ACCESS
+    // --------------------------------------------------------------------------------------------
+
+    /// Reads all rows of the specified frame from the main trace segment; row `i` of the frame
+    /// is read from `lde_step + frame.offsets()[i] * blowup`.
+    ///
+    /// # Panics
+    /// Panics if `lde_step` is not in the row range of this part, or if an offset of the frame
+    /// exceeds the max frame offset of the partition.
+    pub fn read_main_trace_frame_into(
+        &self,
+        lde_step: usize,
+        frame: &mut EvaluationFrame<E::BaseField>,
+    ) {
+        let row_idx = self.get_row_idx(lde_step);
+        for row in 0..frame.num_rows() {
+            let offset_row_idx = self.get_offset_row_idx(row_idx, frame.offsets()[row]);
+            frame.row_mut(row).copy_from_slice(self.main_segment.row(offset_row_idx));
+        }
+    }
+
+    /// Reads all rows of the specified frame from the auxiliary trace segment; row `i` of the
+    /// frame is read from `lde_step + frame.offsets()[i] * blowup`.
+    ///
+    /// # Panics
+    /// Panics if `lde_step` is not in the row range of this part, if an offset of the frame
+    /// exceeds the max frame offset of the partition, or if no auxiliary segments have been
+    /// added to this worker.
+    pub fn read_aux_trace_frame_into(&self, lde_step: usize, frame: &mut EvaluationFrame<E>) {
+        let row_idx = self.get_row_idx(lde_step);
+        let segment = &self.aux_segments[0];
+        for row in 0..frame.num_rows() {
+            let offset_row_idx = self.get_offset_row_idx(row_idx, frame.offsets()[row]);
+            frame.row_mut(row).copy_from_slice(segment.row(offset_row_idx));
+        }
+    }
+
+    /// Returns rows of all trace segments at the specified LDE positions together with Merkle
+    /// authentication paths from the roots of this part to these rows.
+    ///
+    /// # Panics
+    /// Panics if any of the positions is not in the row range of this part.
+    pub fn open(&self, positions: &[usize]) -> RowRangeOpening<E, H> {
+        let row_indexes =
+            positions.iter().map(|&position| self.get_row_idx(position)).collect::<Vec<_>>();
+
+        RowRangeOpening {
+            main: self.main_segment.open(&row_indexes),
+            aux: self.aux_segments.iter().map(|segment| segment.open(&row_indexes)).collect(),
+        }
+    }
+
+    // CONSTRAINT EVALUATION
+    // --------------------------------------------------------------------------------------------
+
+    /// Evaluates constraints of the specified AIR over the steps of the constraint evaluation
+    /// domain which correspond to the rows of this part, and returns values of the constraint
+    /// composition polynomial at these steps.
+    ///
+    /// All auxiliary segments must be added to the worker before constraints are evaluated.
+    ///
+    /// # Panics
+    /// Panics if a part contains fewer rows than the blowup factor of the LDE domain relative to
+    /// the constraint evaluation domain.
+    pub fn evaluate_constraints<A: Air<BaseField = E::BaseField>>(
+        &self,
+        air: &A,
+        aux_rand_elements: AuxTraceRandElements<E>,
+        composition_coefficients: ConstraintCompositionCoefficients<E>,
+        domain: &StarkDomain<E::BaseField>,
+    ) -> Vec<E> {
+        let ce_to_lde_blowup = domain.ce_to_lde_blowup();
+        assert!(
+            self.partition.part_size() >= ce_to_lde_blowup,
+            "a part must contain at least {ce_to_lde_blowup} rows to evaluate constraints, but \
+            contained {} rows",
+            self.partition.part_size()
+        );
+        let rows = self.row_range();
+        let steps = rows.start / ce_to_lde_blowup..rows.end / ce_to_lde_blowup;
+
+        DefaultConstraintEvaluator::new(air, aux_rand_elements, composition_coefficients)
+            .evaluate_steps(self, domain, steps)
+    }
+
+    // HELPER METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the index of the retained row which corresponds to the specified LDE step.
+    fn get_row_idx(&self, lde_step: usize) -> usize {
+        let rows = self.row_range();
+        assert!(
+            rows.contains(&lde_step),
+            "LDE step {lde_step} is not in the row range {rows:?} of this part"
+        );
+        lde_step - rows.start
+    }
+
+    /// Returns the index of the retained row which is `offset` trace steps away from the
+    /// retained row at the specified index.
+    fn get_offset_row_idx(&self, row_idx: usize, offset: usize) -> usize {
+        let max_offset = self.partition.max_frame_offset();
+        assert!(
+            offset <= max_offset,
+            "frame offset {offset} exceeds the max frame offset {max_offset} of the partition"
+        );
+        row_idx + offset * self.blowup
+    }
+}
+
+// ROW RANGE OPENING
+// ================================================================================================
+
+/// Rows of a single trace segment at a set of positions together with partial Merkle
+/// authentication paths to these rows.
+///
+/// Each path starts with the leaf of the row and ends with the sibling of the part's root. Thus,
+/// the paths are complete only if the trace consists of a single part; otherwise, the coordinator
+/// extends them with paths from the part's root to the commitment root.
+#[derive(Debug, PartialEq, Eq)]
+pub struct SegmentOpening<F: FieldElement, H: Hasher> {
+    /// Segment rows at the opened positions.
+    pub rows: Vec<Vec<F>>,
+    /// Merkle authentication paths from the part's root to the opened rows.
+    pub paths: Vec<Vec<H::Digest>>,
+}
+
+impl<F: FieldElement, H: Hasher> Serializable for SegmentOpening<F, H> {
+    /// Serializes `self` and writes the resulting bytes into the `target`.
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        assert!(self.rows.len() <= u16::MAX as usize, "too many rows");
+        target.write_u16(self.rows.len() as u16);
+        let width = self.rows.first().map(|row| row.len()).unwrap_or(0);
+        target.write_u16(width as u16);
+        for row in self.rows.iter() {
+            debug_assert_eq!(width, row.len(), "all rows must have the same width");
+            F::write_batch_into(row, target);
+        }
+
+        let depth = self.paths.first().map(|path| path.len()).unwrap_or(0);
+        target.write_u8(depth as u8);
+        for path in self.paths.iter() {
+            debug_assert_eq!(depth, path.len(), "all paths must have the same length");
+            H::Digest::write_batch_into(path, target);
+        }
+    }
+}
+
+impl<F: FieldElement, H: Hasher> Deserializable for SegmentOpening<F, H> {
+    /// Reads a segment opening from the specified `source` and returns the result.
+    ///
+    /// # Errors
+    /// Returns an error if a valid segment opening could not be read from the specified `source`.
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        let num_rows = source.read_u16()? as usize;
+        let width = source.read_u16()? as usize;
+        let mut rows = Vec::new();
+        for _ in 0..num_rows {
+            rows.push(F::read_batch_from(source, width)?);
+        }
+
+        let depth = source.read_u8()? as usize;
+        let mut paths = Vec::new();
+        for _ in 0..num_rows {
+            paths.push(H::Digest::read_batch_from(source, depth)?);
+        }
+
+        Ok(SegmentOpening { rows, paths })
+    }
+}
+
+/// Rows of all trace segments opened by a worker at a set of positions together with partial
+/// Merkle authentication paths to these rows.
+#[derive(Debug, PartialEq, Eq)]
+pub struct RowRangeOpening<E: FieldElement, H: Hasher> {
+    /// Opening of the main trace segment.
+    pub main: SegmentOpening<E::BaseField, H>,
+    /// Openings of the auxiliary trace segments.
+    pub aux: Vec<SegmentOpening<E, H>>,
+}
+
+impl<E: FieldElement, H: Hasher> Serializable for RowRangeOpening<E, H> {
+    /// Serializes `self` and writes the resulting bytes into the `target`.
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        self.main.write_into(target);
+        target.write_u8(self.aux.len() as u8);
+        for segment in self.aux.iter() {
+            segment.write_into(target);
+        }
+    }
+}
+
+impl<E: FieldElement, H: Hasher> Deserializable for RowRangeOpening<E, H> {
+    /// Reads a row range opening from the specified `source` and returns the result.
+    ///
+    /// # Errors
+    /// Returns an error if a valid row range opening could not be read from the specified
+    /// `source`.
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        let main = SegmentOpening::read_from(source)?;
+        let num_aux_segments = source.read_u8()? as usize;
+        let mut aux = Vec::new();
+        for _ in 0..num_aux_segments {
+            aux.push(SegmentOpening::read_from(source)?);
+        }
+        Ok(RowRangeOpening { main, aux })
+    }
+}
+
+// ROW RANGE SEGMENT
+// ================================================================================================
+
+/// Rows of a single trace segment retained by a worker, and a Merkle tree built over the rows of
+/// the worker's part.
+struct RowRangeSegment<F: FieldElement, H: Hasher> {
+    data: Vec<F>,
+    width: usize,
+    tree: MerkleTree<H>,
+}
+
+impl<F, H> RowRangeSegment<F, H>
+where
+    F: FieldElement,
+    H: ElementHasher<BaseField = F::BaseField>,
+{
+    /// Evaluates the provided polynomials over the specified rows of the LDE domain (as well as
+    /// over `blowup * max_frame_offset` rows which follow them), and builds a Merkle tree over
+    /// the specified rows.
+    fn new(
+        polys: &ColMatrix<F>,
+        domain: &StarkDomain<F::BaseField>,
+        rows: Range<usize>,
+        max_frame_offset: usize,
+    ) -> Self {
+        let lde_domain_size = domain.lde_domain_size();
+        let num_rows = rows.len() + domain.trace_to_lde_blowup() * max_frame_offset;
+        let width = polys.num_cols();
+
+        let mut data = vec![F::ZERO; num_rows * width];
+        for (col_idx, poly) in polys.columns().enumerate() {
+            let evaluations = fft::evaluate_poly_with_offset(
+                poly,
+                domain.trace_twiddles(),
+                domain.offset(),
+                domain.trace_to_lde_blowup(),
+            );
+            for (row_idx, row) in data.chunks_mut(width).enumerate() {
+                row[col_idx] = evaluations[(rows.start + row_idx) % lde_domain_size];
+            }
+        }
+
+        let row_hashes = data
+            .chunks(width)
+            .take(rows.len())
+            .map(|row| domain.leaf_encoding().hash_row::<H, F>(row))
+            .collect::<Vec<_>>();
+        let tree = MerkleTree::new(row_hashes).expect("failed to construct trace Merkle tree");
+
+        RowRangeSegment { data, width, tree }
+    }
+
+    /// Returns the root of the Merkle tree built over the rows of the part.
+    fn root(&self) -> H::Digest {
+        *self.tree.root()
+    }
+
+    /// Returns the retained row at the specified index.
+    fn row(&self, row_idx: usize) -> &[F] {
+        &self.data[row_idx * self.width..(row_idx + 1) * self.width]
+    }
+
+    /// Returns rows at the specified indexes together with Merkle authentication paths to them.
+    fn open(&self, row_indexes: &[usize]) -> SegmentOpening<F, H> {
+        let rows = row_indexes.iter().map(|&row_idx| self.row(row_idx).to_vec()).collect();
+        let paths = row_indexes
+            .iter()
+            .map(|&row_idx| {
+                self.tree
+                    .prove(row_idx)
+                    .expect("failed to generate a Merkle path for trace queries")
+            })
+            .collect();
+        SegmentOpening { rows, paths }
+    }
+}
+
+// TRACE FRAMES
+// ================================================================================================
+
+/// Exposes evaluation frames of a part of the trace so that constraints can be evaluated over the
+/// part by the [DefaultConstraintEvaluator].
+impl<E, H> TraceFrames<E> for RowRangeLde<E, H>
+where
+    E: FieldElement,
+    H: ElementHasher<BaseField = E::BaseField>,
+{
+    fn layout(&self) -> &TraceLayout {
+        self.trace_info.layout()
+    }
+
+    fn read_main_frame_into(&self, lde_step: usize, frame: &mut EvaluationFrame<E::BaseField>) {
+        self.read_main_trace_frame_into(lde_step, frame)
+    }
+
+    fn read_aux_frame_into(&self, lde_step: usize, frame: &mut EvaluationFrame<E>) {
+        self.read_aux_trace_frame_into(lde_step, frame)
+    }
+}
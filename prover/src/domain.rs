@@ -3,7 +3,7 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use air::Air;
+use air::{Air, LeafEncoding};
 use math::{fft, get_power_series, StarkField};
 use utils::collections::Vec;
 
@@ -29,6 +29,10 @@ pub struct StarkDomain<B: StarkField> {
 
     /// Offset of the low-degree extension domain.
     domain_offset: B,
+
+    /// Encoding of trace rows into leaves of Merkle trees committing to trace segments extended
+    /// over the LDE domain.
+    leaf_encoding: LeafEncoding,
 }
 
 // STARK DOMAIN IMPLEMENTATION
@@ -40,8 +44,7 @@ impl<B: StarkField> StarkDomain<B> {
         let trace_twiddles = fft::get_twiddles(air.trace_length());
 
         // build constraint evaluation domain
-        let domain_gen = B::get_root_of_unity(air.ce_domain_size().ilog2());
-        let ce_domain = get_power_series(domain_gen, air.ce_domain_size());
+        let ce_domain = get_power_series(air.ce_domain_generator(), air.ce_domain_size());
 
         StarkDomain {
             trace_twiddles,
@@ -49,6 +52,7 @@ impl<B: StarkField> StarkDomain<B> {
             ce_to_lde_blowup: air.lde_domain_size() / air.ce_domain_size(),
             ce_domain_mod_mask: air.ce_domain_size() - 1,
             domain_offset: air.domain_offset(),
+            leaf_encoding: air.options().leaf_encoding(),
         }
     }
 
@@ -71,6 +75,7 @@ impl<B: StarkField> StarkDomain<B> {
             ce_to_lde_blowup: 1,
             ce_domain_mod_mask: ce_domain_size - 1,
             domain_offset,
+            leaf_encoding: LeafEncoding::default(),
         }
     }
 
@@ -151,4 +156,12 @@ impl<B: StarkField> StarkDomain<B> {
     pub fn offset(&self) -> B {
         self.domain_offset
     }
+
+    /// Returns the encoding of trace rows into leaves of Merkle trees committing to trace
+    /// segments extended over the LDE domain.
+    ///
+    /// For domains built via [StarkDomain::from_twiddles()], the default encoding is returned.
+    pub fn leaf_encoding(&self) -> LeafEncoding {
+        self.leaf_encoding
+    }
 }
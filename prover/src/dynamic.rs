@@ -0,0 +1,70 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use crate::{Prover, ProverError, StarkProof, Trace};
+use crypto::{
+    hashers::{Blake3_192, Blake3_256, Keccak256, Sha3_256},
+    DefaultRandomCoin, ElementHasher, HashFunction,
+};
+use math::{ExtensibleField, StarkField};
+
+// PROVER FACTORY
+// ================================================================================================
+
+/// Builds instances of a [Prover] for a computation parameterized by a hash function.
+///
+/// A [Prover] fixes the hash function used during proof generation via its
+/// [HashFn](Prover::HashFn) associated type. Implementing this trait for a computation allows
+/// selecting the hash function at runtime via the [prove_dyn()] function, without requiring the
+/// caller to enumerate all hash function monomorphizations manually.
+///
+/// Provers built by a factory must use [DefaultRandomCoin] so that the resulting proofs can be
+/// verified by the matching dynamic verifier entry point.
+pub trait ProverFactory {
+    /// Base field for the computation described by the built provers.
+    type BaseField: StarkField + ExtensibleField<2> + ExtensibleField<3>;
+
+    /// Execution trace of the computation described by the built provers.
+    type Trace: Trace<BaseField = Self::BaseField>;
+
+    /// Prover for the computation instantiated with hash function `H`.
+    type Prover<H>: Prover<
+        BaseField = Self::BaseField,
+        Trace = Self::Trace,
+        HashFn = H,
+        RandomCoin = DefaultRandomCoin<H>,
+    >
+    where
+        H: ElementHasher<BaseField = Self::BaseField>;
+
+    /// Returns a new prover which uses hash function `H`.
+    fn build_prover<H>(&self) -> Self::Prover<H>
+    where
+        H: ElementHasher<BaseField = Self::BaseField>;
+}
+
+// DYNAMIC PROVER
+// ================================================================================================
+
+/// Returns a STARK proof attesting to a correct execution of a computation defined by the
+/// provided trace, using a hash function selected at runtime.
+///
+/// The prover is built by the specified `factory` for the hash function identified by `hash_fn`,
+/// and then [Prover::prove()] is invoked on it with the provided `trace`.
+///
+/// # Errors
+/// Returns an error if a valid STARK proof could not be generated.
+pub fn prove_dyn<F: ProverFactory>(
+    factory: &F,
+    trace: F::Trace,
+    hash_fn: HashFunction,
+) -> Result<StarkProof, ProverError> {
+    match hash_fn {
+        HashFunction::Blake3_192 => factory.build_prover::<Blake3_192<F::BaseField>>().prove(trace),
+        HashFunction::Blake3_256 => factory.build_prover::<Blake3_256<F::BaseField>>().prove(trace),
+        HashFunction::Sha3_256 => factory.build_prover::<Sha3_256<F::BaseField>>().prove(trace),
+        HashFunction::Keccak256 => factory.build_prover::<Keccak256<F::BaseField>>().prove(trace),
+    }
+}
@@ -5,7 +5,10 @@
 
 //! Contains common error types for prover and verifier.
 
+use air::AssertionError;
 use core::fmt;
+#[cfg(feature = "self-verify")]
+use verifier::VerifierError;
 
 // PROVER ERROR
 // ================================================================================================
@@ -21,6 +24,62 @@ pub enum ProverError {
     /// This error occurs when the base field specified by the AIR does not support field extension
     /// of degree specified by proof options.
     UnsupportedFieldExtension(usize),
+    /// This error occurs when the width of the main segment of the execution trace is not
+    /// consistent with the width expected by the AIR.
+    InconsistentTraceWidth(usize, usize),
+    /// This error occurs when the number of assertions against the main trace segment returned
+    /// by the AIR is not consistent with the number of assertions specified in the AIR context.
+    InconsistentNumAssertions(usize, usize),
+    /// This error occurs when an assertion is not valid in the context of the execution trace
+    /// (e.g., assertion column index is out of bounds).
+    InvalidAssertion(AssertionError),
+    /// This error occurs when the execution trace does not satisfy an assertion placed against
+    /// the specified column at the specified step.
+    UnsatisfiedAssertion(usize, usize),
+    /// This error occurs when the number of constraint evaluations is not consistent with the
+    /// size of the constraint evaluation domain.
+    InconsistentConstraintEvaluationLength(usize, usize),
+    /// This error occurs when the estimated amount of memory needed to generate a proof exceeds
+    /// the memory budget specified by the prover.
+    MemoryBudgetExceeded(usize, usize),
+    /// This error occurs when queries are normalized, but the number of queries is greater than
+    /// the size of the FRI remainder domain, and thus, distinct query positions cannot be drawn.
+    TooManyNormalizedQueries(usize, usize),
+    /// This error occurs when proof options specify the STIR low-degree test, but the prover was
+    /// built without `experimental-stir` feature.
+    UnsupportedLowDegreeTest,
+    /// This error occurs when the actual degree of a transition constraint does not match the
+    /// degree declared by the AIR. The values are the index of the constraint (main trace
+    /// constraints are followed by auxiliary trace constraints), and the declared and the actual
+    /// degrees of the constraint evaluation polynomial. This is checked only in debug mode.
+    TransitionConstraintDegreeMismatch(usize, usize, usize),
+    /// This error occurs when the degree of the constraint composition polynomial is too high
+    /// to split the polynomial into the number of columns specified by the AIR. The values are
+    /// the maximum and the actual degrees of the polynomial.
+    CompositionPolyDegreeTooHigh(usize, usize),
+    /// This error occurs when the degree of a polynomial provided for a column of the main trace
+    /// segment is too high. The values are the index of the column, and the maximum and the
+    /// actual degrees of the polynomial.
+    TracePolyDegreeTooHigh(usize, usize, usize),
+    /// This error occurs when a polynomial provided for the column at the specified index of the
+    /// main trace segment does not interpolate the values of the column. This is checked only in
+    /// debug mode.
+    TracePolyMismatch(usize),
+    /// This error occurs when self-verification is enabled (see
+    /// [Prover::self_verification_options()](crate::Prover::self_verification_options)) and
+    /// the verifier rejects a freshly generated proof; the wrapped error is the reason the
+    /// verifier gave for rejecting the proof.
+    #[cfg(feature = "self-verify")]
+    SelfVerificationFailed(VerifierError),
+    /// This error occurs when the number of preprocessed columns held by the trace LDE (see
+    /// [TraceLde::preprocessed_trace()](crate::TraceLde::preprocessed_trace)) is not consistent
+    /// with the number of preprocessed columns declared by the AIR.
+    InconsistentNumPreprocessedColumns(usize, usize),
+    /// This error occurs when evaluations of the constraint composition polynomial could not be
+    /// written into or read from a temporary file (see
+    /// [MemoryStrategy::DiskBacked](crate::MemoryStrategy::DiskBacked)).
+    #[cfg(feature = "std")]
+    DiskStorageFailed(String),
 }
 
 impl fmt::Display for ProverError {
@@ -36,6 +95,53 @@ impl fmt::Display for ProverError {
             Self::UnsupportedFieldExtension(degree) => {
                 write!(f, "field extension of degree {degree} is not supported for the specified base field")
             }
+            Self::InconsistentTraceWidth(expected, actual) => {
+                write!(f, "inconsistent main trace width; expected {expected}, but was {actual}")
+            }
+            Self::InconsistentNumAssertions(expected, actual) => {
+                write!(f, "expected {expected} assertions against main trace segment, but received {actual}")
+            }
+            Self::InvalidAssertion(err) => {
+                write!(f, "an assertion is invalid: {err}")
+            }
+            Self::UnsatisfiedAssertion(column, step) => {
+                write!(f, "trace does not satisfy an assertion against column {column} at step {step}")
+            }
+            Self::InconsistentConstraintEvaluationLength(expected, actual) => {
+                write!(f, "expected {expected} constraint evaluations, but was {actual}")
+            }
+            Self::MemoryBudgetExceeded(budget, estimate) => {
+                write!(f, "proof generation requires an estimated {estimate} bytes of memory, but the budget is {budget} bytes")
+            }
+            Self::TooManyNormalizedQueries(num_queries, domain_size) => {
+                write!(f, "cannot draw {num_queries} normalized queries from a FRI remainder domain of size {domain_size}")
+            }
+            Self::UnsupportedLowDegreeTest => {
+                write!(f, "STIR low-degree test is not supported without experimental-stir feature")
+            }
+            Self::TransitionConstraintDegreeMismatch(index, expected, actual) => {
+                write!(f, "transition constraint {index} was declared to have degree {expected}, but its evaluations have degree {actual}")
+            }
+            Self::CompositionPolyDegreeTooHigh(max_degree, degree) => {
+                write!(f, "constraint composition polynomial has degree {degree}, but the maximum degree is {max_degree}; check degrees of constraints declared by the AIR")
+            }
+            Self::TracePolyDegreeTooHigh(column, max_degree, degree) => {
+                write!(f, "polynomial for main trace column {column} has degree {degree}, but the maximum degree is {max_degree}")
+            }
+            Self::TracePolyMismatch(column) => {
+                write!(f, "polynomial for main trace column {column} does not interpolate the values of the column")
+            }
+            #[cfg(feature = "self-verify")]
+            Self::SelfVerificationFailed(err) => {
+                write!(f, "generated proof failed self-verification: {err}")
+            }
+            Self::InconsistentNumPreprocessedColumns(expected, actual) => {
+                write!(f, "expected {expected} preprocessed columns in the trace LDE, but was {actual}")
+            }
+            #[cfg(feature = "std")]
+            Self::DiskStorageFailed(err) => {
+                write!(f, "failed to access constraint evaluations stored on disk: {err}")
+            }
         }
     }
 }
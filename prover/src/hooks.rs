@@ -0,0 +1,94 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use crate::{CompositionPoly, CompositionPolyTrace, TracePolyTable};
+use crypto::Transcript;
+use math::{FieldElement, StarkField};
+use utils::collections::Vec;
+
+// PROVER HOOKS
+// ================================================================================================
+
+/// Defines a set of callbacks which are invoked as proof generation progresses through its
+/// phases.
+///
+/// Hooks provide read-only access to intermediate artifacts of proof generation which are not
+/// otherwise exposed by the prover. This could be useful, for example, when building custom
+/// protocols on top of the prover which need access to trace polynomials or out-of-domain
+/// evaluations. Hooks cannot alter the proof: the proof generated by
+/// [Prover::prove_with_hooks()](crate::Prover::prove_with_hooks) is always identical to the proof
+/// generated by [Prover::prove()](crate::Prover::prove) for the same trace.
+///
+/// All methods have empty default implementations, and thus, implementers need to override only
+/// the hooks they are interested in. Hooks are invoked in the order in which they are defined
+/// below. Type parameter `E` in all hooks is the field in which the protocol is executed (i.e.,
+/// either the base field or one of its extensions, as specified by the proof options).
+///
+/// # Stability
+/// The set of hooks and the artifacts they expose mirror the internal structure of the prover.
+/// These may change between minor versions of this crate whenever the proof generation procedure
+/// changes, and thus, this API should be considered unstable.
+#[allow(unused_variables)]
+pub trait ProverHooks<B: StarkField> {
+    /// Invoked after all trace segments have been extended and committed to.
+    ///
+    /// `trace_polys` contains polynomials (in coefficient form) for all columns of the main
+    /// and auxiliary trace segments.
+    fn on_trace_committed<E: FieldElement<BaseField = B>>(
+        &mut self,
+        trace_polys: &TracePolyTable<E>,
+    ) {
+    }
+
+    /// Invoked after the constraints have been evaluated over the constraint evaluation domain.
+    ///
+    /// `evaluations` contains evaluations of the random linear combination of all constraints
+    /// (i.e., evaluations of the constraint composition polynomial).
+    fn on_constraints_evaluated<E: FieldElement<BaseField = B>>(
+        &mut self,
+        evaluations: &CompositionPolyTrace<E>,
+    ) {
+    }
+
+    /// Invoked after the constraint composition polynomial has been decomposed into column
+    /// polynomials and committed to.
+    fn on_constraints_committed<E: FieldElement<BaseField = B>>(
+        &mut self,
+        composition_poly: &CompositionPoly<E>,
+    ) {
+    }
+
+    /// Invoked after the out-of-domain point has been drawn and the trace and constraint
+    /// composition polynomials have been evaluated at it.
+    ///
+    /// `ood_trace_states` contains evaluations of all trace polynomials at points `z * g^k` for
+    /// all out-of-domain offsets `k` of the AIR (by default, at points `z` and `z * g`), where
+    /// `g` is the generator of the trace domain; `ood_evaluations` contains evaluations of all
+    /// constraint composition column polynomials at `z`.
+    fn on_ood_frame<E: FieldElement<BaseField = B>>(
+        &mut self,
+        z: E,
+        ood_trace_states: &[Vec<E>],
+        ood_evaluations: &[E],
+    ) {
+    }
+
+    /// Invoked after the query positions have been drawn.
+    ///
+    /// `positions` contains the positions in the LDE domain at which trace and constraint
+    /// commitments are opened. The positions are unique and sorted in ascending order.
+    fn on_query_positions(&mut self, positions: &[usize]) {}
+
+    /// Invoked after the query positions have been drawn, if the public coin records a transcript
+    /// (e.g., when [Prover::RandomCoin](crate::Prover::RandomCoin) is a
+    /// [TranscriptCoin](crypto::TranscriptCoin)).
+    ///
+    /// `transcript` contains all interactions with the public coin over the course of proof
+    /// generation. The verifier records the same transcript for a valid proof, and thus, the
+    /// transcript can be used to find where the prover and the verifier diverge.
+    fn on_transcript(&mut self, transcript: &Transcript) {}
+}
+
+impl<B: StarkField> ProverHooks<B> for () {}
@@ -44,17 +44,24 @@
 extern crate alloc;
 
 pub use air::{
-    proof, proof::StarkProof, Air, AirContext, Assertion, AuxTraceRandElements, BoundaryConstraint,
-    BoundaryConstraintGroup, ConstraintCompositionCoefficients, ConstraintDivisor,
-    DeepCompositionCoefficients, EvaluationFrame, FieldExtension, ProofOptions, TraceInfo,
-    TraceLayout, TransitionConstraintDegree,
+    gadgets, proof, proof::StarkProof, AggregateAir, AggregateInputs, Air, AirContext, Assertion,
+    AuxTraceRandElements, BoundaryConstraint, BoundaryConstraintGroup, Component, ComponentId,
+    ComponentLayout, ConstraintCompositionCoefficients, ConstraintDivisor,
+    DeepCompositionCoefficients, EvaluationFrame, FieldExtension, LeafEncoding, LowDegreeTest,
+    MerkleInputs, ProofOptions, PublicInputsError, QuerySampler, QuerySampling, StratifiedSampler,
+    TraceAllocator, TraceInfo, TraceLayout, TransitionConstraintDegree, UniformSampler,
 };
+
+#[cfg(feature = "protobuf")]
+pub use air::protobuf;
 pub use utils::{
     iterators, ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable,
     SliceReader,
 };
 
-use fri::FriProver;
+#[cfg(feature = "std")]
+pub use utils::WriteAdapter;
+
 use utils::collections::Vec;
 
 pub use math;
@@ -65,13 +72,28 @@ use math::{
 };
 
 pub use crypto;
-use crypto::{ElementHasher, RandomCoin};
+use crypto::{ElementHasher, Hasher, RandomCoin};
+
+#[cfg(feature = "self-verify")]
+pub use verifier::AcceptableOptions;
 
 #[cfg(feature = "std")]
 use log::debug;
 #[cfg(feature = "std")]
 use std::time::Instant;
 
+#[cfg(feature = "tracing")]
+pub(crate) use tracing::info_span;
+
+#[cfg(not(feature = "tracing"))]
+macro_rules! noop_span {
+    ($($args:tt)*) => {
+        $crate::NoopSpan
+    };
+}
+#[cfg(not(feature = "tracing"))]
+pub(crate) use noop_span as info_span;
+
 mod domain;
 pub use domain::StarkDomain;
 
@@ -79,26 +101,81 @@ pub mod matrix;
 use matrix::{ColMatrix, RowMatrix};
 
 mod constraints;
+#[cfg(feature = "gpu")]
+pub use constraints::GpuConstraintEvaluator;
 pub use constraints::{
     CompositionPoly, CompositionPolyTrace, ConstraintCommitment, ConstraintEvaluator,
     DefaultConstraintEvaluator,
 };
 
 mod composer;
-use composer::DeepCompositionPoly;
+pub use composer::DeepCompositionPoly;
 
 mod trace;
-pub use trace::{DefaultTraceLde, Trace, TraceLde, TracePolyTable, TraceTable, TraceTableFragment};
+pub use trace::{
+    CompressedColumn, DefaultTraceLde, PreprocessedTrace, RowIter, Trace, TraceColumn,
+    TraceDiagnostics, TraceIssue, TraceLde, TracePolyTable, TraceRow, TraceTable,
+    TraceTableFragment, TraceTableFragmentView, TraceTableWriter,
+};
+
+#[cfg(feature = "parquet")]
+pub use trace::{FIELD_MODULUS_KEY, TRACE_META_KEY};
+#[cfg(feature = "parquet")]
+pub use {arrow_array, parquet};
+
+pub mod distributed;
 
 mod channel;
 use channel::ProverChannel;
 
+mod low_degree;
+use low_degree::LowDegreeProver;
+
+mod hooks;
+pub use hooks::ProverHooks;
+
+mod allocator;
+pub use allocator::{BufferAllocator, BufferKind, DefaultAllocator};
+
+mod dynamic;
+pub use dynamic::{prove_dyn, ProverFactory};
+
+mod numa;
+pub use numa::NumaPartitioning;
+
+mod session;
+pub use session::{ProverSession, SessionStats};
+
+mod memory;
+pub use memory::{MemoryEstimate, MemoryStrategy};
+
 mod errors;
 pub use errors::ProverError;
 
 #[cfg(test)]
 pub mod tests;
 
+// NO-OP SPAN
+// ================================================================================================
+
+/// Stand-in for `tracing` spans used when `tracing` feature is disabled; instrumented code is
+/// executed as is.
+#[cfg(not(feature = "tracing"))]
+pub(crate) struct NoopSpan;
+
+#[cfg(not(feature = "tracing"))]
+impl NoopSpan {
+    #[inline(always)]
+    pub fn in_scope<F: FnOnce() -> T, T>(&self, f: F) -> T {
+        f()
+    }
+
+    #[inline(always)]
+    pub fn entered(self) -> Self {
+        self
+    }
+}
+
 // PROVER
 // ================================================================================================
 
@@ -197,6 +274,107 @@ pub trait Prover {
     // PROVIDED METHODS
     // --------------------------------------------------------------------------------------------
 
+    /// Takes polynomials in coefficient form for all columns of the main trace segment as input,
+    /// and evaluates the polynomials over the LDE domain.
+    ///
+    /// This is used by [Prover::prove_from_polys()] instead of [Prover::new_trace_lde()]; both
+    /// `main_trace` and `main_trace_polys` describe the same main trace segment (the former in
+    /// evaluation form, and the latter in coefficient form). The default implementation ignores
+    /// `main_trace_polys` and delegates to [Prover::new_trace_lde()]; implementations should
+    /// override it to skip the interpolation step (e.g., via [DefaultTraceLde::from_polys()]).
+    ///
+    /// Returns a tuple containing a [TracePolyTable] with the trace polynomials for the main trace
+    /// and a new [TraceLde] instance from which the LDE and trace commitments can be obtained.
+    fn new_trace_lde_from_polys<E>(
+        &self,
+        trace_info: &TraceInfo,
+        main_trace: &ColMatrix<Self::BaseField>,
+        main_trace_polys: ColMatrix<Self::BaseField>,
+        domain: &StarkDomain<Self::BaseField>,
+    ) -> (Self::TraceLde<E>, TracePolyTable<E>)
+    where
+        E: FieldElement<BaseField = Self::BaseField>,
+    {
+        let _ = main_trace_polys;
+        self.new_trace_lde(trace_info, main_trace, domain)
+    }
+
+    /// Returns the allocator used to obtain large buffers which are owned by the prover itself
+    /// (e.g., evaluations of the constraint composition polynomial over the LDE domain).
+    ///
+    /// Buffers owned by [TraceLde](Prover::TraceLde) are obtained from the allocator provided to
+    /// it on construction (see [DefaultTraceLde::with_allocator()]). By default, all buffers are
+    /// obtained directly from the global allocator.
+    fn allocator(&self) -> &dyn BufferAllocator<Self::BaseField> {
+        &DefaultAllocator
+    }
+
+    /// Returns `true` if this prover is required to generate byte-identical proofs for identical
+    /// inputs.
+    ///
+    /// All steps of proof generation are deterministic except for proof-of-work grinding: when
+    /// `concurrent` feature is enabled, the nonce found by whichever thread finishes first is
+    /// used, and thus, the nonce (and all values derived from it, such as query positions) may
+    /// differ between runs. In deterministic mode, the smallest valid nonce is always used, at
+    /// the cost of slightly slower grinding. This is useful, for example, for golden tests and
+    /// reproducibility audits.
+    ///
+    /// Proofs generated in either mode are equally valid. By default, deterministic mode is
+    /// disabled.
+    fn is_deterministic(&self) -> bool {
+        false
+    }
+
+    /// Returns the maximum number of bytes this prover is allowed to use for generating a proof,
+    /// or `None` if memory usage is not limited.
+    ///
+    /// Before doing any heavy work, the prover estimates memory needed for proof generation (see
+    /// [MemoryEstimate]) with each [MemoryStrategy] in turn, and uses the first strategy for
+    /// which the estimate fits into the budget; if the estimate exceeds the budget for all
+    /// strategies, [ProverError::MemoryBudgetExceeded] is returned. Generated proofs do not
+    /// depend on the selected strategy. By default, memory usage is not limited, and all
+    /// buffers are kept in memory.
+    ///
+    /// Strategies determine only how the low-degree extension of the constraint composition
+    /// polynomial is held. The low-degree extension of the execution trace is held by
+    /// [Prover::TraceLde] regardless of the budget (for [DefaultTraceLde], it is kept in
+    /// memory), and is counted toward the estimate for every strategy; thus, a budget smaller
+    /// than the memory needed to extend and commit to the execution trace cannot be met.
+    fn memory_budget(&self) -> Option<usize> {
+        None
+    }
+
+    /// Returns partitioning of the work of the largest data-parallel loops of the prover among
+    /// NUMA nodes, or `None` if the work is scheduled by the global thread pool.
+    ///
+    /// When set, constraint evaluation (via
+    /// [ConstraintEvaluator::evaluate_partitioned()]) and construction of the DEEP composition
+    /// polynomial split their data into one contiguous range per node, and process each range
+    /// using threads of that node (see [NumaPartitioning] for details). This reduces cross-node
+    /// memory traffic on multi-socket machines. Partitioning has effect only when `concurrent`
+    /// feature is enabled, and does not affect generated proofs. By default, work is not
+    /// partitioned.
+    fn numa_partitioning(&self) -> Option<&NumaPartitioning> {
+        None
+    }
+
+    /// Returns the options against which every generated proof is verified before it is returned,
+    /// or `None` if generated proofs are not verified.
+    ///
+    /// When set, the prover runs the verifier on each freshly generated proof and returns
+    /// [ProverError::SelfVerificationFailed] with the verifier's error if the proof is rejected.
+    /// This catches integration bugs such as public inputs which do not match the ones expected
+    /// by the AIR, or proof options which drifted away from the ones accepted by remote verifiers,
+    /// at proof generation time. Verification is much cheaper than proof generation, but it is
+    /// not free; thus, this is intended primarily for debug and staging builds. By default,
+    /// self-verification is disabled.
+    ///
+    /// Self-verification is available only when `self-verify` feature is enabled.
+    #[cfg(feature = "self-verify")]
+    fn self_verification_options(&self) -> Option<&AcceptableOptions> {
+        None
+    }
+
     /// Returns a STARK proof attesting to a correct execution of a computation defined by the
     /// provided trace.
     ///
@@ -205,46 +383,214 @@ pub trait Prover {
     /// secret and public inputs. Public inputs must match the value returned from
     /// [Self::get_pub_inputs()](Prover::get_pub_inputs) for the provided trace.
     fn prove(&self, trace: Self::Trace) -> Result<StarkProof, ProverError> {
+        self.prove_with_hooks(trace, &mut ())
+    }
+
+    /// Returns a STARK proof attesting to a correct execution of a computation defined by the
+    /// provided trace, invoking the specified `hooks` as proof generation progresses.
+    ///
+    /// The returned proof is identical to the proof returned from [Prover::prove()] for the same
+    /// trace. The hooks can be used to inspect intermediate artifacts of proof generation, such
+    /// as trace polynomials and out-of-domain evaluations; see [ProverHooks] for details and for
+    /// stability caveats.
+    fn prove_with_hooks<H>(
+        &self,
+        trace: Self::Trace,
+        hooks: &mut H,
+    ) -> Result<StarkProof, ProverError>
+    where
+        H: ProverHooks<Self::BaseField>,
+    {
+        self.dispatch_proof(trace, None, hooks, None)
+    }
+
+    /// Returns a STARK proof attesting to a correct execution of a computation defined by the
+    /// provided trace, using the provided polynomials for the columns of the main trace segment.
+    ///
+    /// This is useful when the polynomials are already known (e.g., when they are produced by
+    /// the executor directly in coefficient form): the columns of the main trace segment are not
+    /// interpolated, and the polynomials are evaluated over the LDE domain directly (see
+    /// [Prover::new_trace_lde_from_polys()]). The returned proof is identical to the proof
+    /// returned from [Prover::prove()] for the same trace.
+    ///
+    /// `main_trace_polys` must contain one polynomial per column of the main trace segment, and
+    /// the polynomials may have more coefficients than the length of the trace as long as the
+    /// extra coefficients are zeros.
+    ///
+    /// # Errors
+    /// Returns an error if, in addition to the errors returned from [Prover::prove()]:
+    /// * The number of polynomials is not equal to the width of the main trace segment.
+    /// * The degree of any of the polynomials is greater than or equal to the trace length.
+    /// * In debug mode, any of the polynomials does not interpolate the corresponding column of
+    ///   the main trace segment.
+    fn prove_from_polys(
+        &self,
+        trace: Self::Trace,
+        main_trace_polys: ColMatrix<Self::BaseField>,
+    ) -> Result<StarkProof, ProverError> {
+        self.dispatch_proof(trace, Some(main_trace_polys), &mut (), None)
+    }
+
+    /// Returns a STARK proof attesting to a correct execution of a computation defined by the
+    /// provided trace, reusing values precomputed in the specified `session`.
+    ///
+    /// The returned proof is identical to the proof returned from [Prover::prove()] for the same
+    /// trace. If the session was built for a computation of a different shape (e.g., for a
+    /// different trace length), it is ignored and all values are computed from scratch; this is
+    /// reflected in the session's [stats()](ProverSession::stats).
+    fn prove_in_session(
+        &self,
+        trace: Self::Trace,
+        session: &ProverSession<Self::BaseField>,
+    ) -> Result<StarkProof, ProverError> {
+        self.dispatch_proof(trace, None, &mut (), Some(session))
+    }
+
+    // HELPER METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Selects the field in which proof generation procedure is executed based on the field
+    /// extension specified in proof options and generates the proof.
+    #[doc(hidden)]
+    fn dispatch_proof<H>(
+        &self,
+        trace: Self::Trace,
+        main_trace_polys: Option<ColMatrix<Self::BaseField>>,
+        hooks: &mut H,
+        session: Option<&ProverSession<Self::BaseField>>,
+    ) -> Result<StarkProof, ProverError>
+    where
+        H: ProverHooks<Self::BaseField>,
+    {
+        // public inputs are needed for self-verification, but the trace is consumed by proof
+        // generation; so, we derive them up front
+        #[cfg(feature = "self-verify")]
+        let self_verification = self
+            .self_verification_options()
+            .map(|acceptable_options| (acceptable_options, self.get_pub_inputs(&trace)));
+
         // figure out which version of the generic proof generation procedure to run. this is a sort
         // of static dispatch for selecting two generic parameter: extension field and hash function.
-        match self.options().field_extension() {
-            FieldExtension::None => self.generate_proof::<Self::BaseField>(trace),
+        #[cfg_attr(not(feature = "self-verify"), allow(unused_variables))]
+        let (proof, preprocessed_commitment) = match self.options().field_extension() {
+            FieldExtension::None => {
+                self.generate_proof::<Self::BaseField, H>(trace, main_trace_polys, hooks, session)
+            }
             FieldExtension::Quadratic => {
                 if !<QuadExtension<Self::BaseField>>::is_supported() {
                     return Err(ProverError::UnsupportedFieldExtension(2));
                 }
-                self.generate_proof::<QuadExtension<Self::BaseField>>(trace)
+                self.generate_proof::<QuadExtension<Self::BaseField>, H>(
+                    trace,
+                    main_trace_polys,
+                    hooks,
+                    session,
+                )
             }
             FieldExtension::Cubic => {
                 if !<CubeExtension<Self::BaseField>>::is_supported() {
                     return Err(ProverError::UnsupportedFieldExtension(3));
                 }
-                self.generate_proof::<CubeExtension<Self::BaseField>>(trace)
+                self.generate_proof::<CubeExtension<Self::BaseField>, H>(
+                    trace,
+                    main_trace_polys,
+                    hooks,
+                    session,
+                )
+            }
+        }?;
+
+        // verify the proof before returning it, if self-verification is enabled
+        #[cfg(feature = "self-verify")]
+        if let Some((acceptable_options, pub_inputs)) = self_verification {
+            match preprocessed_commitment {
+                Some(commitment) => {
+                    verifier::verify_with_preprocessed_commitment::<
+                        Self::Air,
+                        Self::HashFn,
+                        Self::RandomCoin,
+                    >(proof.clone(), pub_inputs, acceptable_options, &commitment)
+                }
+                None => verifier::verify::<Self::Air, Self::HashFn, Self::RandomCoin>(
+                    proof.clone(),
+                    pub_inputs,
+                    acceptable_options,
+                ),
             }
+            .map_err(ProverError::SelfVerificationFailed)?;
         }
-    }
 
-    // HELPER METHODS
-    // --------------------------------------------------------------------------------------------
+        Ok(proof)
+    }
 
     /// Performs the actual proof generation procedure, generating the proof that the provided
     /// execution `trace` is valid against this prover's AIR.
+    ///
+    /// Returns the proof together with the commitment to the preprocessed columns of the trace
+    /// (if any), which the verifier needs in order to verify the proof.
     /// TODO: make this function un-callable externally?
     #[doc(hidden)]
-    fn generate_proof<E>(&self, mut trace: Self::Trace) -> Result<StarkProof, ProverError>
+    #[allow(clippy::type_complexity)]
+    fn generate_proof<E, H>(
+        &self,
+        mut trace: Self::Trace,
+        main_trace_polys: Option<ColMatrix<Self::BaseField>>,
+        hooks: &mut H,
+        session: Option<&ProverSession<Self::BaseField>>,
+    ) -> Result<(StarkProof, Option<<Self::HashFn as Hasher>::Digest>), ProverError>
     where
         E: FieldElement<BaseField = Self::BaseField>,
+        H: ProverHooks<Self::BaseField>,
     {
         // 0 ----- instantiate AIR and prover channel ---------------------------------------------
 
-        // serialize public inputs; these will be included in the seed for the public coin
-        let pub_inputs = self.get_pub_inputs(&trace);
+        // derive public inputs which can be computed from other public inputs, and serialize
+        // them; these will be included in the seed for the public coin
+        let pub_inputs = Self::Air::derive_public_inputs(self.get_pub_inputs(&trace));
         let pub_inputs_elements = pub_inputs.to_elements();
 
         // create an instance of AIR for the provided parameters. this takes a generic description
         // of the computation (provided via AIR type), and creates a description of a specific
         // execution of the computation for the provided public inputs.
         let air = Self::Air::new(trace.get_info(), pub_inputs, self.options().clone());
+        let _span = info_span!(
+            "generate_proof",
+            trace_length = air.trace_length(),
+            trace_width = air.trace_layout().main_trace_width(),
+            aux_trace_width = air.trace_layout().aux_trace_width(),
+            lde_domain_size = air.lde_domain_size()
+        )
+        .entered();
+
+        // select a strategy for holding constraint evaluations which fits into the memory budget
+        // (if any) before doing any heavy work, rather than running out of memory halfway through
+        let memory_estimate =
+            MemoryEstimate::select::<Self::Air, E, Self::HashFn>(&air, self.memory_budget())?;
+        #[cfg(feature = "std")]
+        debug!(
+            "Estimated peak memory usage of {} MB using {:?} strategy",
+            memory_estimate.peak() / (1024 * 1024),
+            memory_estimate.strategy
+        );
+
+        // make sure normalized query positions can be drawn for this computation
+        if air.options().normalized_queries() {
+            let remainder_domain_size =
+                air.options().to_fri_options().remainder_domain_size(air.lde_domain_size());
+            if air.options().num_queries() > remainder_domain_size {
+                return Err(ProverError::TooManyNormalizedQueries(
+                    air.options().num_queries(),
+                    remainder_domain_size,
+                ));
+            }
+        }
+
+        // make sure the trace is consistent with the AIR before doing any heavy work; this is a
+        // cheap check which does not evaluate transition constraints
+        trace::validate_main_assertions(&air, &trace)?;
+        let main_trace_polys = main_trace_polys
+            .map(|polys| trace::validate_main_trace_polys(&air, &trace, polys))
+            .transpose()?;
 
         // create a channel which is used to simulate interaction between the prover and the
         // verifier; the channel will be used to commit to values and to draw randomness that
@@ -256,27 +602,73 @@ pub trait Prover {
 
         // 1 ----- Commit to the execution trace --------------------------------------------------
 
-        // build computation domain; this is used later for polynomial evaluations
+        // build computation domain; this is used later for polynomial evaluations. if a session
+        // built for a computation of the same shape was provided, the domain is taken from it
+        let session = session.filter(|session| {
+            let is_compatible = session.is_compatible_with(&air);
+            session.record_proof(is_compatible);
+            is_compatible
+        });
         #[cfg(feature = "std")]
         let now = Instant::now();
-        let domain = StarkDomain::new(&air);
+        let built_domain;
+        let domain = match session {
+            Some(session) => session.domain(),
+            None => {
+                built_domain = info_span!("build_domain", lde_domain_size = air.lde_domain_size())
+                    .in_scope(|| StarkDomain::new(&air));
+                &built_domain
+            }
+        };
         #[cfg(feature = "std")]
         debug!(
-            "Built domain of 2^{} elements in {} ms",
+            "{} domain of 2^{} elements in {} ms",
+            if session.is_some() { "Reused" } else { "Built" },
             domain.lde_domain_size().ilog2(),
             now.elapsed().as_millis()
         );
 
         // extend the main execution trace and build a Merkle tree from the extended trace
         let (mut trace_lde, mut trace_polys): (Self::TraceLde<E>, TracePolyTable<E>) =
-            self.new_trace_lde(&trace.get_info(), trace.main_segment(), &domain);
+            info_span!("commit_to_main_trace_segment").in_scope(|| {
+                let (trace_lde, trace_polys) = match main_trace_polys {
+                    Some(polys) => self.new_trace_lde_from_polys(
+                        &trace.get_info(),
+                        trace.main_segment(),
+                        polys,
+                        domain,
+                    ),
+                    None => self.new_trace_lde(&trace.get_info(), trace.main_segment(), domain),
+                };
+
+                // make sure the trace LDE holds as many preprocessed columns as the AIR declares;
+                // the commitment to these columns is known to the verifier, and thus, it is not
+                // sent in the proof, but it is still bound to the transcript
+                let num_preprocessed_columns = air.context().num_preprocessed_columns();
+                match trace_lde.preprocessed_trace() {
+                    Some(preprocessed)
+                        if preprocessed.num_columns() == num_preprocessed_columns =>
+                    {
+                        channel.commit_preprocessed_trace(preprocessed.commitment());
+                    }
+                    None if num_preprocessed_columns == 0 => (),
+                    preprocessed => {
+                        return Err(ProverError::InconsistentNumPreprocessedColumns(
+                            num_preprocessed_columns,
+                            preprocessed.map_or(0, |preprocessed| preprocessed.num_columns()),
+                        ));
+                    }
+                }
 
-        // get the commitment to the main trace segment LDE
-        let main_trace_root = trace_lde.get_main_trace_commitment();
+                // get the commitment to the main trace segment LDE
+                let main_trace_root = trace_lde.get_main_trace_commitment();
 
-        // commit to the LDE of the main trace by writing the root of its Merkle tree into
-        // the channel
-        channel.commit_trace(main_trace_root);
+                // commit to the LDE of the main trace by writing the root of its Merkle tree into
+                // the channel
+                channel.commit_trace(main_trace_root);
+
+                Ok((trace_lde, trace_polys))
+            })?;
 
         // build auxiliary trace segments (if any), and append the resulting segments to trace
         // commitment and trace polynomial table structs
@@ -290,9 +682,11 @@ pub trait Prover {
             let rand_elements = channel.get_aux_trace_segment_rand_elements(i);
 
             // build the trace segment
-            let aux_segment = trace
-                .build_aux_segment(&aux_trace_segments, &rand_elements)
-                .expect("failed build auxiliary trace segment");
+            let aux_segment = info_span!("build_aux_trace_segment", segment = i).in_scope(|| {
+                trace
+                    .build_aux_segment(&aux_trace_segments, &rand_elements)
+                    .expect("failed build auxiliary trace segment")
+            });
             #[cfg(feature = "std")]
             debug!(
                 "Built auxiliary trace segment of {} columns and 2^{} steps in {} ms",
@@ -302,12 +696,21 @@ pub trait Prover {
             );
 
             // extend the auxiliary trace segment and build a Merkle tree from the extended trace
-            let (aux_segment_polys, aux_segment_root) =
-                trace_lde.add_aux_segment(&aux_segment, &domain);
-
-            // commit to the LDE of the extended auxiliary trace segment by writing the root of
-            // its Merkle tree into the channel
-            channel.commit_trace(aux_segment_root);
+            let aux_segment_polys = info_span!(
+                "commit_to_aux_trace_segment",
+                segment = i,
+                width = aux_segment.num_cols()
+            )
+            .in_scope(|| {
+                let (aux_segment_polys, aux_segment_root) =
+                    trace_lde.add_aux_segment(&aux_segment, domain);
+
+                // commit to the LDE of the extended auxiliary trace segment by writing the root
+                // of its Merkle tree into the channel
+                channel.commit_trace(aux_segment_root);
+
+                aux_segment_polys
+            });
 
             trace_polys.add_aux_segment(aux_segment_polys);
             aux_trace_rand_elements.add_segment_elements(rand_elements);
@@ -320,6 +723,8 @@ pub trait Prover {
         #[cfg(debug_assertions)]
         trace.validate(&air, &aux_trace_segments, &aux_trace_rand_elements);
 
+        hooks.on_trace_committed(&trace_polys);
+
         // drop the main trace and aux trace segments as they are no longer needed
         drop(trace);
         drop(aux_trace_segments);
@@ -331,8 +736,27 @@ pub trait Prover {
         #[cfg(feature = "std")]
         let now = Instant::now();
         let constraint_coeffs = channel.get_constraint_composition_coeffs();
-        let evaluator = self.new_evaluator(&air, aux_trace_rand_elements, constraint_coeffs);
-        let composition_poly_trace = evaluator.evaluate(&trace_lde, &domain);
+        let composition_poly_trace = info_span!(
+            "evaluate_constraints",
+            ce_domain_size = air.ce_domain_size(),
+            num_constraints = air.context().num_transition_constraints()
+        )
+        .in_scope(|| {
+            let evaluator = self.new_evaluator(&air, aux_trace_rand_elements, constraint_coeffs);
+            match (session, self.numa_partitioning()) {
+                (Some(session), _) => evaluator.evaluate_in_session(&trace_lde, session),
+                (None, Some(partitioning)) => {
+                    evaluator.evaluate_partitioned(&trace_lde, domain, partitioning)
+                }
+                (None, None) => evaluator.evaluate(&trace_lde, domain),
+            }
+        });
+        if composition_poly_trace.num_rows() != air.ce_domain_size() {
+            return Err(ProverError::InconsistentConstraintEvaluationLength(
+                air.ce_domain_size(),
+                composition_poly_trace.num_rows(),
+            ));
+        }
         #[cfg(feature = "std")]
         debug!(
             "Evaluated constraints over domain of 2^{} elements in {} ms",
@@ -340,19 +764,32 @@ pub trait Prover {
             now.elapsed().as_millis()
         );
 
+        hooks.on_constraints_evaluated(&composition_poly_trace);
+
         // 3 ----- commit to constraint evaluations -----------------------------------------------
 
         // first, build a commitment to the evaluations of the constraint composition polynomial
         // columns
-        let (constraint_commitment, composition_poly) = self.build_constraint_commitment::<E>(
-            composition_poly_trace,
-            air.context().num_constraint_composition_columns(),
-            &domain,
-        );
-
-        // then, commit to the evaluations of constraints by writing the root of the constraint
-        // Merkle tree into the channel
-        channel.commit_constraints(constraint_commitment.root());
+        let num_composition_columns = air.context().num_constraint_composition_columns();
+        let (constraint_commitment, composition_poly) =
+            info_span!("commit_to_constraint_evaluations", num_columns = num_composition_columns)
+                .in_scope(|| {
+                let (constraint_commitment, composition_poly) = self
+                    .build_constraint_commitment::<E>(
+                        composition_poly_trace,
+                        num_composition_columns,
+                        domain,
+                        memory_estimate.strategy,
+                    )?;
+
+                // then, commit to the evaluations of constraints by writing the root of the
+                // constraint Merkle tree into the channel
+                channel.commit_constraints(constraint_commitment.root());
+
+                Ok((constraint_commitment, composition_poly))
+            })?;
+
+        hooks.on_constraints_committed(&composition_poly);
 
         // 4 ----- build DEEP composition polynomial ----------------------------------------------
         #[cfg(feature = "std")]
@@ -365,28 +802,46 @@ pub trait Prover {
         // increase security. Soundness is limited by the size of the field that the random point
         // is drawn from, and we can potentially save on performance by only drawing this point
         // from an extension field, rather than increasing the size of the field overall.
-        let z = channel.get_ood_point();
-
-        // evaluate trace and constraint polynomials at the OOD point z, and send the results to
-        // the verifier. the trace polynomials are actually evaluated over two points: z and z * g,
-        // where g is the generator of the trace domain.
-        let ood_trace_states = trace_polys.get_ood_frame(z);
-        channel.send_ood_trace_states(&ood_trace_states);
-
-        let ood_evaluations = composition_poly.evaluate_at(z);
-        channel.send_ood_constraint_evaluations(&ood_evaluations);
+        let (z, ood_trace_states, ood_evaluations) =
+            info_span!("evaluate_ood_frame").in_scope(|| {
+                let z = channel.get_ood_point();
+
+                // evaluate trace and constraint polynomials at the OOD point z, and send the results
+                // to the verifier. the trace polynomials are actually evaluated at points z * g^k
+                // for all out-of-domain offsets k of the AIR, where g is the generator of the trace
+                // domain; by default, these are z and z * g.
+                let ood_trace_states = trace_polys.get_ood_frame_at(z, air.context().ood_offsets());
+                channel.send_ood_trace_states(&ood_trace_states);
+
+                let ood_evaluations = composition_poly.evaluate_at(z);
+                channel.send_ood_constraint_evaluations(&ood_evaluations);
+
+                (z, ood_trace_states, ood_evaluations)
+            });
+
+        hooks.on_ood_frame(z, &ood_trace_states, &ood_evaluations);
+
+        let deep_composition_poly = info_span!("build_deep_composition_poly").in_scope(|| {
+            // draw random coefficients to use during DEEP polynomial composition, and use them to
+            // initialize the DEEP composition polynomial
+            let deep_coefficients = channel.get_deep_composition_coeffs();
+            let mut deep_composition_poly = DeepCompositionPoly::new(z, deep_coefficients)
+                .with_ood_offsets(air.context().ood_offsets().to_vec());
+            if let Some(partitioning) = self.numa_partitioning() {
+                deep_composition_poly =
+                    deep_composition_poly.with_numa_partitioning(partitioning.clone());
+            }
 
-        // draw random coefficients to use during DEEP polynomial composition, and use them to
-        // initialize the DEEP composition polynomial
-        let deep_coefficients = channel.get_deep_composition_coeffs();
-        let mut deep_composition_poly = DeepCompositionPoly::new(z, deep_coefficients);
+            // combine all trace polynomials together and merge them into the DEEP composition
+            // polynomial
+            deep_composition_poly.add_trace_polys(trace_polys, ood_trace_states);
 
-        // combine all trace polynomials together and merge them into the DEEP composition
-        // polynomial
-        deep_composition_poly.add_trace_polys(trace_polys, ood_trace_states);
+            // merge columns of constraint composition polynomial into the DEEP composition
+            // polynomial
+            deep_composition_poly.add_composition_poly(composition_poly, ood_evaluations);
 
-        // merge columns of constraint composition polynomial into the DEEP composition polynomial;
-        deep_composition_poly.add_composition_poly(composition_poly, ood_evaluations);
+            deep_composition_poly
+        });
 
         #[cfg(feature = "std")]
         debug!(
@@ -402,7 +857,11 @@ pub trait Prover {
         // 5 ----- evaluate DEEP composition polynomial over LDE domain ---------------------------
         #[cfg(feature = "std")]
         let now = Instant::now();
-        let deep_evaluations = deep_composition_poly.evaluate(&domain);
+        let deep_evaluations = info_span!(
+            "evaluate_deep_composition_poly",
+            lde_domain_size = domain.lde_domain_size()
+        )
+        .in_scope(|| deep_composition_poly.evaluate(domain));
         // we check the following condition in debug mode only because infer_degree is an expensive
         // operation
         debug_assert_eq!(
@@ -419,8 +878,13 @@ pub trait Prover {
         // 6 ----- compute FRI layers for the composition polynomial ------------------------------
         #[cfg(feature = "std")]
         let now = Instant::now();
-        let mut fri_prover = FriProver::new(air.options().to_fri_options());
-        fri_prover.build_layers(&mut channel, deep_evaluations);
+        let mut fri_prover =
+            LowDegreeProver::new(air.options().to_fri_options(), air.options().num_queries())?;
+        info_span!(
+            "compute_fri_layers",
+            folding_factor = air.options().to_fri_options().folding_factor()
+        )
+        .in_scope(|| fri_prover.build_layers(&mut channel, deep_evaluations));
         #[cfg(feature = "std")]
         debug!(
             "Computed {} FRI layers from composition polynomial evaluations in {} ms",
@@ -433,10 +897,32 @@ pub trait Prover {
         let now = Instant::now();
 
         // apply proof-of-work to the query seed
-        channel.grind_query_seed();
+        #[cfg_attr(not(feature = "std"), allow(unused_variables))]
+        let num_nonces =
+            info_span!("grind_query_seed", grinding_factor = air.options().grinding_factor())
+                .in_scope(|| channel.grind_query_seed(self.is_deterministic()));
+        #[cfg(feature = "std")]
+        {
+            let elapsed = now.elapsed();
+            debug!(
+                "Performed {}-bit proof-of-work grinding by checking {} nonces in {} ms ({:.0} nonces/sec)",
+                air.options().grinding_factor(),
+                num_nonces,
+                elapsed.as_millis(),
+                num_nonces as f64 / elapsed.as_secs_f64()
+            );
+        }
 
         // generate pseudo-random query positions
-        let query_positions = channel.get_query_positions();
+        #[cfg(feature = "std")]
+        let now = Instant::now();
+        let query_positions =
+            info_span!("determine_query_positions", num_queries = air.options().num_queries())
+                .in_scope(|| channel.get_query_positions());
+        hooks.on_query_positions(&query_positions);
+        if let Some(transcript) = channel.transcript() {
+            hooks.on_transcript(transcript);
+        }
         #[cfg(feature = "std")]
         debug!(
             "Determined {} unique query positions in {} ms",
@@ -448,29 +934,48 @@ pub trait Prover {
         #[cfg(feature = "std")]
         let now = Instant::now();
 
-        // generate FRI proof
-        let fri_proof = fri_prover.build_proof(&query_positions);
+        let (fri_proof, trace_queries, preprocessed_queries, constraint_queries) =
+            info_span!("extract_queries", num_queries = query_positions.len()).in_scope(|| {
+                // generate FRI proof
+                let fri_proof = fri_prover.build_proof(&query_positions);
 
-        // query the execution trace at the selected position; for each query, we need the
-        // state of the trace at that position + Merkle authentication path
-        let trace_queries = trace_lde.query(&query_positions);
+                // query the execution trace at the selected position; for each query, we need the
+                // state of the trace at that position + Merkle authentication path
+                let trace_queries = trace_lde.query(&query_positions);
+                let preprocessed_queries = trace_lde
+                    .preprocessed_trace()
+                    .map(|preprocessed| preprocessed.query(&query_positions));
 
-        // query the constraint commitment at the selected positions; for each query, we need just
-        // a Merkle authentication path. this is because constraint evaluations for each step are
-        // merged into a single value and Merkle authentication paths contain these values already
-        let constraint_queries = constraint_commitment.query(&query_positions);
+                // query the constraint commitment at the selected positions; for each query, we
+                // need just a Merkle authentication path. this is because constraint evaluations
+                // for each step are merged into a single value and Merkle authentication paths
+                // contain these values already
+                let constraint_queries = constraint_commitment.query(&query_positions)?;
+
+                Ok((fri_proof, trace_queries, preprocessed_queries, constraint_queries))
+            })?;
 
         // build the proof object
         let proof = channel.build_proof(
             trace_queries,
+            preprocessed_queries,
             constraint_queries,
             fri_proof,
             query_positions.len(),
         );
+
+        // hand the constraint evaluation buffer (if it was kept in memory) back to the allocator;
+        // trace LDE buffers are managed by the trace LDE itself
+        if let Some(evaluations) = constraint_commitment.into_evaluations() {
+            self.allocator()
+                .release(BufferKind::ConstraintCommitment, evaluations.into_data());
+        }
         #[cfg(feature = "std")]
         debug!("Built proof object in {} ms", now.elapsed().as_millis());
 
-        Ok(proof)
+        let preprocessed_commitment =
+            trace_lde.preprocessed_trace().map(|preprocessed| preprocessed.commitment());
+        Ok((proof, preprocessed_commitment))
     }
 
     /// Extends constraint composition polynomial over the LDE domain and builds a commitment to
@@ -482,13 +987,21 @@ pub trait Prover {
     /// polynomial column over the LDE domain.
     ///
     /// The commitment is computed by hashing each row in the evaluation matrix, and then building
-    /// a Merkle tree from the resulting hashes.
+    /// a Merkle tree from the resulting hashes. Evaluations are held as described by `strategy`;
+    /// the commitment is the same for all strategies.
+    ///
+    /// # Errors
+    /// Returns an error if the composition polynomial could not be built from its trace (see
+    /// [CompositionPoly::new()] for details), or if evaluations could not be written into a
+    /// temporary file.
+    #[allow(clippy::type_complexity)]
     fn build_constraint_commitment<E>(
         &self,
         composition_poly_trace: CompositionPolyTrace<E>,
         num_trace_poly_columns: usize,
         domain: &StarkDomain<Self::BaseField>,
-    ) -> (ConstraintCommitment<E, Self::HashFn>, CompositionPoly<E>)
+        strategy: MemoryStrategy,
+    ) -> Result<(ConstraintCommitment<E, Self::HashFn>, CompositionPoly<E>), ProverError>
     where
         E: FieldElement<BaseField = Self::BaseField>,
     {
@@ -499,7 +1012,7 @@ pub trait Prover {
         #[cfg(feature = "std")]
         let now = Instant::now();
         let composition_poly =
-            CompositionPoly::new(composition_poly_trace, domain, num_trace_poly_columns);
+            CompositionPoly::new(composition_poly_trace, domain, num_trace_poly_columns)?;
         #[cfg(feature = "std")]
         debug!(
             "Converted constraint evaluations into {} composition polynomial columns of degree {} in {} ms",
@@ -508,33 +1021,48 @@ pub trait Prover {
             now.elapsed().as_millis()
         );
 
-        // then, evaluate composition polynomial columns over the LDE domain
+        // then, evaluate composition polynomial columns over the LDE domain and build a commitment
+        // to the evaluations
         #[cfg(feature = "std")]
         let now = Instant::now();
-        let composed_evaluations = RowMatrix::evaluate_polys_over::<DEFAULT_SEGMENT_WIDTH>(
-            composition_poly.data(),
-            domain,
-        );
-        #[cfg(feature = "std")]
-        debug!(
-            "Evaluated {} composition polynomial columns over LDE domain (2^{} elements) in {} ms",
-            composed_evaluations.num_cols(),
-            composed_evaluations.num_rows().ilog2(),
-            now.elapsed().as_millis()
-        );
-
-        // finally, build constraint evaluation commitment
-        #[cfg(feature = "std")]
-        let now = Instant::now();
-        let commitment = composed_evaluations.commit_to_rows();
-        let constraint_commitment = ConstraintCommitment::new(composed_evaluations, commitment);
+        let constraint_commitment = match strategy {
+            MemoryStrategy::InMemory => {
+                #[cfg(feature = "std")]
+                let now = Instant::now();
+                let composed_evaluations =
+                    RowMatrix::evaluate_polys_over_with::<DEFAULT_SEGMENT_WIDTH, _>(
+                        composition_poly.data(),
+                        domain,
+                        self.allocator(),
+                        BufferKind::ConstraintCommitment,
+                    );
+                #[cfg(feature = "std")]
+                debug!(
+                    "Evaluated {} composition polynomial columns over LDE domain (2^{} elements) in {} ms",
+                    composed_evaluations.num_cols(),
+                    composed_evaluations.num_rows().ilog2(),
+                    now.elapsed().as_millis()
+                );
+
+                let commitment = composed_evaluations.commit_to_rows();
+                ConstraintCommitment::new(composed_evaluations, commitment)
+            }
+            MemoryStrategy::Chunked => {
+                ConstraintCommitment::chunked(composition_poly.data().clone(), domain)
+            }
+            #[cfg(feature = "std")]
+            MemoryStrategy::DiskBacked => {
+                ConstraintCommitment::disk_backed(composition_poly.data(), domain)?
+            }
+        };
         #[cfg(feature = "std")]
         debug!(
-            "Computed constraint evaluation commitment (Merkle tree of depth {}) in {} ms",
+            "Computed constraint evaluation commitment (Merkle tree of depth {}) using {:?} strategy in {} ms",
             constraint_commitment.tree_depth(),
+            strategy,
             now.elapsed().as_millis()
         );
 
-        (constraint_commitment, composition_poly)
+        Ok((constraint_commitment, composition_poly))
     }
 }
@@ -0,0 +1,84 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use crate::ProverError;
+use crypto::ElementHasher;
+#[cfg(feature = "experimental-stir")]
+use fri::stir::StirProver;
+use fri::{FriOptions, FriProof, FriProver, LowDegreeTest, ProverChannel};
+use math::{FieldElement, StarkField};
+use utils::collections::Vec;
+
+// LOW-DEGREE PROVER
+// ================================================================================================
+
+/// Prover component of the low-degree test specified by the FRI options of a computation.
+///
+/// Both FRI and STIR provers read from and write to the same channel, and generate the same type
+/// of proof; thus, the rest of the proof generation procedure does not depend on which of the
+/// protocols is used.
+pub(crate) enum LowDegreeProver<B, E, C, H>
+where
+    B: StarkField,
+    E: FieldElement<BaseField = B>,
+    C: ProverChannel<E, Hasher = H>,
+    H: ElementHasher<BaseField = B>,
+{
+    Fri(FriProver<B, E, C, H>),
+    #[cfg(feature = "experimental-stir")]
+    Stir(StirProver<B, E, C, H>),
+}
+
+impl<B, E, C, H> LowDegreeProver<B, E, C, H>
+where
+    B: StarkField,
+    E: FieldElement<BaseField = B>,
+    C: ProverChannel<E, Hasher = H>,
+    H: ElementHasher<BaseField = B>,
+{
+    /// Returns a prover for the low-degree test specified by the `options`; `num_queries` is
+    /// used only by the STIR prover.
+    ///
+    /// # Errors
+    /// Returns an error if the options specify STIR, but `experimental-stir` feature is disabled.
+    #[cfg_attr(not(feature = "experimental-stir"), allow(unused_variables))]
+    pub fn new(options: FriOptions, num_queries: usize) -> Result<Self, ProverError> {
+        match options.low_degree_test() {
+            LowDegreeTest::Fri => Ok(Self::Fri(FriProver::new(options))),
+            #[cfg(feature = "experimental-stir")]
+            LowDegreeTest::Stir => Ok(Self::Stir(StirProver::new(options, num_queries))),
+            #[cfg(not(feature = "experimental-stir"))]
+            LowDegreeTest::Stir => Err(ProverError::UnsupportedLowDegreeTest),
+        }
+    }
+
+    /// Returns number of layers computed during the commit phase.
+    #[cfg(feature = "std")]
+    pub fn num_layers(&self) -> usize {
+        match self {
+            Self::Fri(prover) => prover.num_layers(),
+            #[cfg(feature = "experimental-stir")]
+            Self::Stir(prover) => prover.num_layers(),
+        }
+    }
+
+    /// Executes the commit phase of the low-degree test.
+    pub fn build_layers(&mut self, channel: &mut C, evaluations: Vec<E>) {
+        match self {
+            Self::Fri(prover) => prover.build_layers(channel, evaluations),
+            #[cfg(feature = "experimental-stir")]
+            Self::Stir(prover) => prover.build_layers(channel, evaluations),
+        }
+    }
+
+    /// Executes the query phase of the low-degree test.
+    pub fn build_proof(&mut self, positions: &[usize]) -> FriProof {
+        match self {
+            Self::Fri(prover) => prover.build_proof(positions),
+            #[cfg(feature = "experimental-stir")]
+            Self::Stir(prover) => prover.build_proof(positions),
+        }
+    }
+}
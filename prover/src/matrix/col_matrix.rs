@@ -7,7 +7,7 @@ use crate::StarkDomain;
 use core::{iter::FusedIterator, slice};
 use crypto::{ElementHasher, MerkleTree};
 use math::{fft, polynom, FieldElement};
-use utils::{batch_iter_mut, collections::Vec, iter, iter_mut, uninit_vector};
+use utils::{batch_iter_mut, collections::Vec, iter, uninit_vector};
 
 #[cfg(feature = "concurrent")]
 use utils::iterators::*;
@@ -156,12 +156,12 @@ impl<E: FieldElement> ColMatrix<E> {
     // --------------------------------------------------------------------------------------------
 
     /// Returns an iterator over the columns of this matrix.
-    pub fn columns(&self) -> ColumnIter<E> {
+    pub fn columns(&self) -> ColumnIter<'_, E> {
         ColumnIter::new(self)
     }
 
     /// Returns a mutable iterator over the columns of this matrix.
-    pub fn columns_mut(&mut self) -> ColumnIterMut<E> {
+    pub fn columns_mut(&mut self) -> ColumnIterMut<'_, E> {
         ColumnIterMut::new(self)
     }
 
@@ -178,16 +178,12 @@ impl<E: FieldElement> ColMatrix<E> {
     ///   form.
     /// * The resulting polynomials are returned as a single matrix where each column contains
     ///   coefficients of a degree `num_rows - 1` polynomial.
+    ///
+    /// All columns are interpolated in a single pass sharing the same inverse twiddles (see
+    /// [fft::interpolate_polys()]).
     pub fn interpolate_columns(&self) -> Self {
-        let inv_twiddles = fft::get_inv_twiddles::<E::BaseField>(self.num_rows());
-        let columns = iter!(self.columns)
-            .map(|evaluations| {
-                let mut column = evaluations.clone();
-                fft::interpolate_poly(&mut column, &inv_twiddles);
-                column
-            })
-            .collect();
-        Self { columns }
+        let columns = iter!(self.columns).map(|column| column.clone()).collect();
+        Self { columns }.interpolate_columns_into()
     }
 
     /// Interpolates columns of the matrix into polynomials in coefficient form and returns the
@@ -200,9 +196,12 @@ impl<E: FieldElement> ColMatrix<E> {
     ///   coefficient form.
     /// * The resulting polynomials are returned as a single matrix where each column contains
     ///   coefficients of a degree `num_rows - 1` polynomial.
+    ///
+    /// All columns are interpolated in a single pass sharing the same inverse twiddles (see
+    /// [fft::interpolate_polys()]).
     pub fn interpolate_columns_into(mut self) -> Self {
         let inv_twiddles = fft::get_inv_twiddles::<E::BaseField>(self.num_rows());
-        iter_mut!(self.columns).for_each(|column| fft::interpolate_poly(column, &inv_twiddles));
+        fft::interpolate_polys(&mut self.columns, &inv_twiddles);
         self
     }
 
@@ -318,7 +317,7 @@ impl<'a, E: FieldElement> Iterator for ColumnIter<'a, E> {
 
 impl<'a, E: FieldElement> ExactSizeIterator for ColumnIter<'a, E> {
     fn len(&self) -> usize {
-        self.matrix.num_cols()
+        self.matrix.num_cols() - self.cursor
     }
 }
 
@@ -361,7 +360,7 @@ impl<'a, E: FieldElement> Iterator for ColumnIterMut<'a, E> {
 
 impl<'a, E: FieldElement> ExactSizeIterator for ColumnIterMut<'a, E> {
     fn len(&self) -> usize {
-        self.matrix.num_cols()
+        self.matrix.num_cols() - self.cursor
     }
 }
 
@@ -4,11 +4,15 @@
 // LICENSE file in the root directory of this source tree.
 
 use super::{ColMatrix, Segment};
-use crate::StarkDomain;
+use crate::{BufferAllocator, BufferKind, StarkDomain};
+use air::LeafEncoding;
+use core::iter;
 use crypto::{ElementHasher, MerkleTree};
 use math::{fft, FieldElement, StarkField};
 use utils::collections::Vec;
-use utils::{batch_iter_mut, flatten_vector_elements, uninit_vector};
+use utils::{
+    batch_iter_mut, flatten_vector_elements, group_vector_elements, uninit_vector, UninitBuffer,
+};
 
 #[cfg(feature = "concurrent")]
 use utils::iterators::*;
@@ -97,6 +101,56 @@ impl<E: FieldElement> RowMatrix<E> {
         Self::from_segments(segments, polys.num_base_cols())
     }
 
+    /// Returns a new [RowMatrix] constructed by evaluating the provided polynomials over the
+    /// specified [StarkDomain], storing the result in a buffer obtained from `allocator`.
+    ///
+    /// This is equivalent to [RowMatrix::evaluate_polys_over()] except that memory for the
+    /// returned matrix is requested from the provided allocator as a buffer of the specified
+    /// `kind`. The buffer can be handed back to the allocator via [RowMatrix::into_data()].
+    ///
+    /// # Panics
+    /// Panics if the buffer returned by the allocator does not have the requested length.
+    pub fn evaluate_polys_over_with<const N: usize, A>(
+        polys: &ColMatrix<E>,
+        domain: &StarkDomain<E::BaseField>,
+        allocator: &A,
+        kind: BufferKind,
+    ) -> Self
+    where
+        A: BufferAllocator<E::BaseField> + ?Sized,
+    {
+        assert!(N > 0, "batch size N must be greater than zero");
+
+        // pre-compute offsets for each row
+        let poly_size = polys.num_rows();
+        let offsets =
+            get_evaluation_offsets::<E>(poly_size, domain.trace_to_lde_blowup(), domain.offset());
+
+        // build matrix segments by evaluating all polynomials
+        let segments = build_segments::<E, N>(polys, domain.trace_twiddles(), &offsets);
+
+        // request a buffer for the entire matrix from the allocator
+        let row_width = segments.len() * N;
+        let data_len = segments[0].num_rows() * row_width;
+        let data = allocator.allocate(kind, data_len);
+        assert_eq!(
+            data.len(),
+            data_len,
+            "allocated buffer must contain {data_len} elements, but contained {}",
+            data.len()
+        );
+
+        // transpose data in individual segments into the allocated buffer
+        let mut result = group_vector_elements::<_, N>(data);
+        transpose_into(&segments, &mut result);
+
+        RowMatrix {
+            data: flatten_vector_elements(result),
+            row_width,
+            elements_per_row: polys.num_base_cols(),
+        }
+    }
+
     /// Returns a new [RowMatrix] instantiated from the specified matrix segments.
     ///
     /// `elements_per_row` specifies how many base field elements are considered to form a single
@@ -168,6 +222,17 @@ impl<E: FieldElement> RowMatrix<E> {
         &self.data
     }
 
+    /// Consumes this matrix and returns the underlying vector of base field elements.
+    pub fn into_data(self) -> Vec<E::BaseField> {
+        self.data
+    }
+
+    /// Moves the underlying vector of base field elements out of this matrix, leaving the matrix
+    /// empty.
+    pub(crate) fn take_data(&mut self) -> Vec<E::BaseField> {
+        core::mem::take(&mut self.data)
+    }
+
     // COMMITMENTS
     // --------------------------------------------------------------------------------------------
 
@@ -180,6 +245,18 @@ impl<E: FieldElement> RowMatrix<E> {
     ///   number of rows in the matrix.
     /// * The resulting Merkle tree is returned as the commitment to the entire matrix.
     pub fn commit_to_rows<H>(&self) -> MerkleTree<H>
+    where
+        H: ElementHasher<BaseField = E::BaseField>,
+    {
+        self.commit_to_rows_with_encoding(LeafEncoding::Packed)
+    }
+
+    /// Returns a commitment to this matrix in which rows are encoded into Merkle tree leaves
+    /// using the specified `leaf_encoding`.
+    ///
+    /// The commitment is built in the same way as in [RowMatrix::commit_to_rows()], but each row
+    /// is hashed via [LeafEncoding::hash_row()].
+    pub fn commit_to_rows_with_encoding<H>(&self, leaf_encoding: LeafEncoding) -> MerkleTree<H>
     where
         H: ElementHasher<BaseField = E::BaseField>,
     {
@@ -192,7 +269,7 @@ impl<E: FieldElement> RowMatrix<E> {
             128, // min batch size
             |batch: &mut [H::Digest], batch_offset: usize| {
                 for (i, row_hash) in batch.iter_mut().enumerate() {
-                    *row_hash = H::hash_elements(self.row(batch_offset + i));
+                    *row_hash = leaf_encoding.hash_row::<H, E>(self.row(batch_offset + i));
                 }
             }
         );
@@ -202,6 +279,65 @@ impl<E: FieldElement> RowMatrix<E> {
     }
 }
 
+impl<B: StarkField> RowMatrix<B> {
+    // MIXED MATRICES
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a new [RowMatrix] constructed by evaluating a mixed set of base field and extension
+    /// field polynomials over the specified [StarkDomain].
+    ///
+    /// Each row of the returned matrix contains evaluations of all `base_polys` followed by base
+    /// field coefficients of evaluations of all `ext_polys`. Thus, hashing a row of this matrix
+    /// via [ElementHasher::hash_elements()] is equivalent to hashing the row values in which
+    /// extension field elements are represented by their coefficients. Rows can be split back
+    /// into base field and extension field values via [RowMatrix::mixed_row()].
+    ///
+    /// Evaluations of each extension field polynomial are computed by evaluating its coefficient
+    /// polynomials (one per extension degree) over the base field.
+    pub fn evaluate_mixed_polys_over<const N: usize, E>(
+        base_polys: &ColMatrix<B>,
+        ext_polys: &ColMatrix<E>,
+        domain: &StarkDomain<B>,
+    ) -> Self
+    where
+        E: FieldElement<BaseField = B>,
+    {
+        assert_eq!(
+            base_polys.num_rows(),
+            ext_polys.num_rows(),
+            "base field and extension field polynomials must have the same size"
+        );
+
+        // decompose extension field polynomials into their coefficient polynomials, and append
+        // them to the base field polynomials
+        let mut columns = base_polys.columns().map(|column| column.to_vec()).collect::<Vec<_>>();
+        for column in ext_polys.columns() {
+            let coefficients = E::slice_as_base_elements(column);
+            for i in 0..E::EXTENSION_DEGREE {
+                columns.push(
+                    coefficients.iter().skip(i).step_by(E::EXTENSION_DEGREE).copied().collect(),
+                );
+            }
+        }
+
+        Self::evaluate_polys_over::<N>(&ColMatrix::new(columns), domain)
+    }
+
+    /// Returns base field and extension field values of a row at the specified index in a matrix
+    /// constructed via [RowMatrix::evaluate_mixed_polys_over()].
+    ///
+    /// # Panics
+    /// Panics if the specified row index is out of bounds, or if `num_base_cols` does not split
+    /// the row into base field values and a whole number of extension field values.
+    pub fn mixed_row<E>(&self, row_idx: usize, num_base_cols: usize) -> (&[B], &[E])
+    where
+        E: FieldElement<BaseField = B>,
+    {
+        let (base_values, ext_values) = self.row(row_idx).split_at(num_base_cols);
+        (base_values, E::slice_from_base_elements(ext_values))
+    }
+}
+
 // HELPER FUNCTIONS
 // ================================================================================================
 
@@ -217,31 +353,14 @@ pub fn get_evaluation_offsets<E: FieldElement>(
     let domain_size = poly_size * blowup_factor;
     let g = E::BaseField::get_root_of_unity(domain_size.ilog2());
 
-    // allocate memory to hold the offsets
-    let mut offsets = unsafe { uninit_vector(domain_size) };
-
-    // define a closure to compute offsets for a given chunk of the result; the number of chunks
-    // is defined by the blowup factor. for example, for blowup factor = 2, the number of chunks
-    // will be 2, for blowup factor = 8, the number of chunks will be 8 etc.
-    let compute_offsets = |(chunk_idx, chunk): (usize, &mut [E::BaseField])| {
+    // compute offsets for each chunk of the result; the number of chunks is defined by the
+    // blowup factor. for example, for blowup factor = 2, the number of chunks will be 2, for
+    // blowup factor = 8, the number of chunks will be 8 etc.
+    UninitBuffer::new(domain_size).init_chunks(poly_size, |chunk_idx| {
         let idx = fft::permute_index(blowup_factor, chunk_idx) as u64;
         let offset = g.exp_vartime(idx.into()) * domain_offset;
-        let mut factor = E::BaseField::ONE;
-        for res in chunk.iter_mut() {
-            *res = factor;
-            factor *= offset;
-        }
-    };
-
-    // compute offsets for each chunk using either parallel or regular iterators
-
-    #[cfg(not(feature = "concurrent"))]
-    offsets.chunks_mut(poly_size).enumerate().for_each(compute_offsets);
-
-    #[cfg(feature = "concurrent")]
-    offsets.par_chunks_mut(poly_size).enumerate().for_each(compute_offsets);
-
-    offsets
+        iter::successors(Some(E::BaseField::ONE), move |&factor| Some(factor * offset))
+    })
 }
 
 /// Returns matrix segments constructed by evaluating polynomials in the specified matrix over the
@@ -267,22 +386,35 @@ pub fn build_segments<E: FieldElement, const N: usize>(
 }
 
 /// Transposes a vector of segments into a single vector of fixed-size arrays.
-///
-/// When `concurrent` feature is enabled, transposition is performed in multiple threads.
 fn transpose<B: StarkField, const N: usize>(mut segments: Vec<Segment<B, N>>) -> Vec<[B; N]> {
-    let num_rows = segments[0].num_rows();
-    let num_segs = segments.len();
-    let result_len = num_rows * num_segs;
-
     // if there is only one segment, there is nothing to transpose as it is already in row
     // major form
     if segments.len() == 1 {
         return segments.remove(0).into_data();
     }
 
-    // allocate memory to hold the transposed result;
+    // allocate memory to hold the transposed result and fill it in batches of rows;
     // TODO: investigate transposing in-place
-    let mut result = unsafe { uninit_vector::<[B; N]>(result_len) };
+    let num_rows = segments[0].num_rows();
+    let num_segs = segments.len();
+    let result_len = num_rows * num_segs;
+    let rows_per_batch = usize::max(num_rows / get_num_batches(result_len), 1);
+    UninitBuffer::new(result_len).init_chunks(rows_per_batch * num_segs, |batch_idx| {
+        transposed_rows(&segments, batch_idx * rows_per_batch, rows_per_batch)
+    })
+}
+
+/// Transposes a list of segments into the provided slice of fixed-size arrays.
+///
+/// When `concurrent` feature is enabled, transposition is performed in multiple threads.
+fn transpose_into<B: StarkField, const N: usize>(
+    segments: &[Segment<B, N>],
+    result: &mut [[B; N]],
+) {
+    let num_rows = segments[0].num_rows();
+    let num_segs = segments.len();
+    let result_len = num_rows * num_segs;
+    debug_assert_eq!(result.len(), result_len);
 
     // determine number of batches in which transposition will be preformed; if `concurrent`
     // feature is not enabled, the number of batches will always be 1
@@ -291,13 +423,9 @@ fn transpose<B: StarkField, const N: usize>(mut segments: Vec<Segment<B, N>>) ->
 
     // define a closure for transposing a given batch
     let transpose_batch = |(batch_idx, batch): (usize, &mut [[B; N]])| {
-        let row_offset = batch_idx * rows_per_batch;
-        for i in 0..rows_per_batch {
-            let row_idx = i + row_offset;
-            for j in 0..num_segs {
-                let v = &segments[j][row_idx];
-                batch[i * num_segs + j].copy_from_slice(v);
-            }
+        let rows = transposed_rows(segments, batch_idx * rows_per_batch, rows_per_batch);
+        for (target, row) in batch.iter_mut().zip(rows) {
+            *target = row;
         }
     };
 
@@ -305,15 +433,24 @@ fn transpose<B: StarkField, const N: usize>(mut segments: Vec<Segment<B, N>>) ->
     // iterator (for multi-threaded transposition)
 
     #[cfg(not(feature = "concurrent"))]
-    transpose_batch((0, &mut result));
+    transpose_batch((0, result));
 
     #[cfg(feature = "concurrent")]
     result
         .par_chunks_mut(result_len / num_batches)
         .enumerate()
         .for_each(transpose_batch);
+}
 
-    result
+/// Returns an iterator over the elements of `num_rows` consecutive rows of the transposed
+/// segments starting with `first_row`, in row-major order.
+fn transposed_rows<B: StarkField, const N: usize>(
+    segments: &[Segment<B, N>],
+    first_row: usize,
+    num_rows: usize,
+) -> impl Iterator<Item = [B; N]> + '_ {
+    (first_row..first_row + num_rows)
+        .flat_map(move |row_idx| segments.iter().map(move |segment| segment[row_idx]))
 }
 
 #[cfg(not(feature = "concurrent"))]
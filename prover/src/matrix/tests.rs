@@ -4,12 +4,21 @@
 // LICENSE file in the root directory of this source tree.
 
 use crate::{
-    math::{fields::f64::BaseElement, get_power_series, polynom, StarkField},
-    ColMatrix, RowMatrix,
+    math::{
+        fft,
+        fields::{f64::BaseElement, QuadExtension},
+        get_power_series, polynom, StarkField,
+    },
+    proof::Queries,
+    ColMatrix, RowMatrix, StarkDomain,
 };
+use crypto::{hashers::Blake3_256, MerkleTree};
 use rand_utils::rand_vector;
 use utils::collections::Vec;
 
+type QuadElement = QuadExtension<BaseElement>;
+type Blake3 = Blake3_256<BaseElement>;
+
 #[test]
 fn test_eval_poly_with_offset_matrix() {
     let n = 256;
@@ -39,6 +48,48 @@ fn test_eval_poly_with_offset_matrix() {
     }
 }
 
+#[test]
+fn commit_to_mixed_matrix() {
+    let n = 16;
+    let blowup_factor = 4;
+    let domain =
+        StarkDomain::from_twiddles(fft::get_twiddles(n), blowup_factor, BaseElement::GENERATOR);
+
+    let base_polys = ColMatrix::new((0..3).map(|_| rand_vector::<BaseElement>(n)).collect());
+    let ext_polys = ColMatrix::new((0..2).map(|_| rand_vector::<QuadElement>(n)).collect());
+
+    // mixed rows must be consistent with separately evaluated base and extension polynomials
+    let matrix = RowMatrix::evaluate_mixed_polys_over::<8, _>(&base_polys, &ext_polys, &domain);
+    let base_lde = RowMatrix::evaluate_polys_over::<8>(&base_polys, &domain);
+    let ext_lde = RowMatrix::evaluate_polys_over::<8>(&ext_polys, &domain);
+    assert_eq!(n * blowup_factor, matrix.num_rows());
+    for row_idx in 0..matrix.num_rows() {
+        let (base_values, ext_values) = matrix.mixed_row::<QuadElement>(row_idx, 3);
+        assert_eq!(base_lde.row(row_idx), base_values);
+        assert_eq!(ext_lde.row(row_idx), ext_values);
+    }
+
+    // queries against the mixed matrix commitment must be verifiable after parsing
+    let tree: MerkleTree<Blake3> = matrix.commit_to_rows();
+    let positions = [1, 6, 17, 42];
+    let base_values = positions.iter().map(|&p| base_lde.row(p).to_vec()).collect::<Vec<_>>();
+    let ext_values = positions.iter().map(|&p| ext_lde.row(p).to_vec()).collect::<Vec<_>>();
+    let queries = Queries::new_mixed::<Blake3, QuadElement>(
+        tree.prove_batch(&positions).unwrap(),
+        base_values.clone(),
+        ext_values.clone(),
+    );
+
+    let (merkle_proof, parsed_base_values, parsed_ext_values) = queries
+        .parse_mixed::<Blake3, QuadElement>(matrix.num_rows(), positions.len(), 3, 2)
+        .unwrap();
+    assert!(MerkleTree::verify_batch(tree.root(), &positions, &merkle_proof).is_ok());
+    for (i, (base_row, ext_row)) in base_values.iter().zip(ext_values.iter()).enumerate() {
+        assert_eq!(base_row.as_slice(), parsed_base_values.get_row(i));
+        assert_eq!(ext_row.as_slice(), parsed_ext_values.get_row(i));
+    }
+}
+
 // HELPER FUNCTIONS
 // ================================================================================================
 
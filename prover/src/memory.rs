@@ -0,0 +1,263 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use crate::{ProverError, DEFAULT_SEGMENT_WIDTH};
+use air::Air;
+use core::mem::size_of;
+use crypto::Hasher;
+use math::FieldElement;
+use utils::collections::Vec;
+
+// MEMORY STRATEGY
+// ================================================================================================
+
+/// Defines how the prover holds evaluations of the constraint composition polynomial over the
+/// LDE domain between committing to them and answering queries against them.
+///
+/// Strategies are listed in the order of decreasing memory requirements (and increasing cost):
+/// when a memory budget is set (see [Prover::memory_budget()](crate::Prover::memory_budget)),
+/// the prover uses the first strategy for which the estimated peak memory usage fits into the
+/// budget. All strategies result in identical proofs.
+///
+/// Strategies do not apply to the low-degree extension of the execution trace, which is held by
+/// the [TraceLde](crate::TraceLde) of the prover.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MemoryStrategy {
+    /// The entire low-degree extension of the composition polynomial columns is kept in memory.
+    #[default]
+    InMemory,
+    /// The low-degree extension is computed one coset of the trace domain at a time, and only
+    /// row hashes are kept; composition polynomial columns are kept instead of their
+    /// evaluations, and the rows at queried positions are re-evaluated from them.
+    Chunked,
+    /// The low-degree extension is computed one coset of the trace domain at a time and written
+    /// into a temporary file from which the rows at queried positions are read back.
+    #[cfg(feature = "std")]
+    DiskBacked,
+}
+
+impl MemoryStrategy {
+    /// All strategies in the order in which they are tried by the prover.
+    #[cfg(feature = "std")]
+    pub const ALL: [Self; 3] = [Self::InMemory, Self::Chunked, Self::DiskBacked];
+
+    /// All strategies in the order in which they are tried by the prover.
+    #[cfg(not(feature = "std"))]
+    pub const ALL: [Self; 2] = [Self::InMemory, Self::Chunked];
+}
+
+// MEMORY ESTIMATE
+// ================================================================================================
+
+/// Estimated number of bytes the prover needs during each phase of proof generation.
+///
+/// The estimate accounts for the largest buffers live during each phase: the execution trace,
+/// trace polynomials, low-degree extensions of trace segments and of the constraint composition
+/// polynomial, Merkle trees built over these extensions, and FRI layers. Smaller allocations
+/// (e.g., periodic column values or inverse divisor evaluations) are ignored, while short-lived
+/// buffers used when extending polynomials are assumed to coexist with all other buffers of a
+/// phase; as a result, the estimate is usually somewhat above the actual peak memory usage.
+///
+/// The estimate assumes that the prover uses [DefaultTraceLde](crate::DefaultTraceLde); a prover
+/// with a custom [TraceLde](crate::TraceLde) can use the estimate to decide how to extend the
+/// trace (e.g., in chunks) before proof generation starts. Evaluations of the constraint
+/// composition polynomial are assumed to be held as described by the [MemoryStrategy] of the
+/// estimate.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MemoryEstimate {
+    /// Strategy for holding constraint composition polynomial evaluations assumed by this
+    /// estimate.
+    pub strategy: MemoryStrategy,
+    /// Bytes needed while extending and committing to the execution trace segments.
+    pub trace_commitment: usize,
+    /// Bytes needed while evaluating constraints over the constraint evaluation domain.
+    pub constraint_evaluation: usize,
+    /// Bytes needed while extending and committing to the constraint composition polynomial.
+    pub constraint_commitment: usize,
+    /// Bytes needed while building the DEEP composition polynomial and FRI layers.
+    pub fri: usize,
+}
+
+impl MemoryEstimate {
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+    /// Returns an estimate of memory needed to generate a proof for the computation described by
+    /// the specified AIR, when the protocol is executed in field `E` using hash function `H`, and
+    /// evaluations of the constraint composition polynomial are kept in memory.
+    pub fn new<A, E, H>(air: &A) -> Self
+    where
+        A: Air,
+        E: FieldElement<BaseField = A::BaseField>,
+        H: Hasher,
+    {
+        Self::with_strategy::<A, E, H>(air, MemoryStrategy::InMemory)
+    }
+
+    /// Returns an estimate of memory needed to generate a proof for the computation described by
+    /// the specified AIR using the first [MemoryStrategy] for which the estimated peak memory
+    /// usage does not exceed `budget`; if `budget` is `None`, evaluations of the constraint
+    /// composition polynomial are kept in memory.
+    ///
+    /// # Errors
+    /// Returns [ProverError::MemoryBudgetExceeded] with the estimate for the least
+    /// memory-intensive strategy if no strategy fits into the budget.
+    pub fn select<A, E, H>(air: &A, budget: Option<usize>) -> Result<Self, ProverError>
+    where
+        A: Air,
+        E: FieldElement<BaseField = A::BaseField>,
+        H: Hasher,
+    {
+        let Some(budget) = budget else {
+            return Ok(Self::new::<A, E, H>(air));
+        };
+
+        let mut estimate = Self::default();
+        for strategy in MemoryStrategy::ALL {
+            estimate = Self::with_strategy::<A, E, H>(air, strategy);
+            if estimate.peak() <= budget {
+                return Ok(estimate);
+            }
+        }
+        Err(ProverError::MemoryBudgetExceeded(budget, estimate.peak()))
+    }
+
+    /// Returns an estimate of memory needed to generate a proof for the computation described by
+    /// the specified AIR, when evaluations of the constraint composition polynomial are held as
+    /// described by `strategy`.
+    pub fn with_strategy<A, E, H>(air: &A, strategy: MemoryStrategy) -> Self
+    where
+        A: Air,
+        E: FieldElement<BaseField = A::BaseField>,
+        H: Hasher,
+    {
+        let base_bytes = <A::BaseField as FieldElement>::ELEMENT_BYTES;
+        let ext_bytes = E::ELEMENT_BYTES;
+        let ext_degree = E::EXTENSION_DEGREE;
+        let digest_bytes = size_of::<H::Digest>();
+
+        let trace_length = air.trace_length();
+        let lde_domain_size = air.lde_domain_size();
+        let layout = air.trace_layout();
+        let row_bytes =
+            layout.main_trace_width() * base_bytes + layout.aux_trace_width() * ext_bytes;
+
+        // the execution trace is kept until all of its segments are committed to; trace
+        // polynomials are kept until the DEEP composition polynomial is built
+        let trace = trace_length * row_bytes;
+        let trace_polys = trace_length * row_bytes;
+
+        // the domain holds the constraint evaluation domain and twiddles for the trace domain
+        // throughout proof generation
+        let ce_domain_size = air.ce_domain_size();
+        let domain = (ce_domain_size + trace_length / 2) * base_bytes;
+
+        // extended segments are built column-wise in segments and then transposed into a
+        // row-major buffer of the same size, and thus, while a segment is being extended, its
+        // LDE occupies twice as much memory. each segment is committed to with a Merkle tree
+        // which holds a leaf and a node per row. the trace LDE is kept until queries are
+        // extracted.
+        let main_lde = lde_size(layout.main_trace_width(), lde_domain_size, base_bytes);
+        let aux_lde = lde_size(layout.aux_trace_width() * ext_degree, lde_domain_size, base_bytes);
+        let num_segments = 1 + layout.num_aux_segments();
+        let trace_lde = main_lde + aux_lde + num_segments * 2 * lde_domain_size * digest_bytes;
+
+        // the same applies to the LDE of the constraint composition polynomial columns when it is
+        // kept in memory. otherwise, the LDE is built one coset of the trace domain at a time
+        // (and, in case of a disk-backed strategy, the coset is serialized before it is written
+        // out); only the Merkle tree is kept, together with the composition polynomial columns
+        // when queried rows are re-evaluated from them
+        let num_composition_columns = air.context().num_constraint_composition_columns();
+        let composition_trace = ce_domain_size * ext_bytes;
+        let composition_poly = num_composition_columns * trace_length * ext_bytes;
+        let composition_tree = 2 * lde_domain_size * digest_bytes;
+        let (composition_lde, constraint_lde) = match strategy {
+            MemoryStrategy::InMemory => {
+                let composition_lde =
+                    lde_size(num_composition_columns * ext_degree, lde_domain_size, base_bytes);
+                (composition_lde, composition_lde + composition_tree)
+            }
+            MemoryStrategy::Chunked => (composition_poly, composition_poly + composition_tree),
+            #[cfg(feature = "std")]
+            MemoryStrategy::DiskBacked => (2 * composition_poly, composition_tree),
+        };
+
+        // constraints are evaluated together with inverses of constraint divisors over a single
+        // period of the constraint evaluation domain; the divisor of transition constraints is
+        // negligible, and divisors of assertions against auxiliary segments are not accounted
+        // for. in debug mode, evaluations of each transition constraint are also kept and
+        // interpolated one at a time to check their degrees.
+        let mut assertion_divisors = Vec::new();
+        for assertion in air.get_assertions() {
+            let divisor = (assertion.first_step(), assertion.get_num_steps(trace_length));
+            if !assertion_divisors.contains(&divisor) {
+                assertion_divisors.push(divisor);
+            }
+        }
+        let mut divisors = 0;
+        for (_, num_steps) in assertion_divisors {
+            divisors += 2 * (ce_domain_size / num_steps) * base_bytes;
+        }
+        #[cfg(debug_assertions)]
+        {
+            let context = air.context();
+            divisors += ce_domain_size
+                * (context.num_main_transition_constraints() * base_bytes
+                    + context.num_aux_transition_constraints() * ext_bytes
+                    + base_bytes
+                    + base_bytes / 2
+                    + ext_bytes);
+        }
+
+        // DEEP composition polynomial is built from trace polynomials and composition polynomial
+        // columns. it is then evaluated over the LDE domain, and the evaluations are folded into
+        // FRI layers; while a layer is being built, evaluations of the layer are transposed into
+        // a separate buffer and committed to with a Merkle tree
+        let deep_poly = trace_length * ext_bytes;
+        let deep_composition = trace_polys + composition_poly + 2 * deep_poly;
+        let fri_options = air.options().to_fri_options();
+        let mut fri_layers = lde_domain_size * ext_bytes;
+        let mut domain_size = lde_domain_size;
+        for folding_factor in fri_options.layer_folding_factors(lde_domain_size) {
+            fri_layers +=
+                domain_size * ext_bytes + 2 * (domain_size / folding_factor) * digest_bytes;
+            domain_size /= folding_factor;
+        }
+
+        MemoryEstimate {
+            strategy,
+            trace_commitment: domain + trace + trace_polys + trace_lde + main_lde.max(aux_lde),
+            constraint_evaluation: domain + trace_polys + trace_lde + divisors + composition_trace,
+            constraint_commitment: domain
+                + trace_polys
+                + trace_lde
+                + composition_poly
+                + constraint_lde
+                + composition_lde,
+            fri: domain + trace_lde + constraint_lde + deep_composition.max(deep_poly + fri_layers),
+        }
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the estimated number of bytes needed by the most memory-intensive phase of proof
+    /// generation.
+    pub fn peak(&self) -> usize {
+        self.trace_commitment
+            .max(self.constraint_evaluation)
+            .max(self.constraint_commitment)
+            .max(self.fri)
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns the number of bytes occupied by a row-major LDE of the specified number of base field
+/// columns; rows are padded to a multiple of the segment width used by the prover.
+fn lde_size(num_base_cols: usize, lde_domain_size: usize, base_bytes: usize) -> usize {
+    let row_width = num_base_cols.div_ceil(DEFAULT_SEGMENT_WIDTH) * DEFAULT_SEGMENT_WIDTH;
+    lde_domain_size * row_width * base_bytes
+}
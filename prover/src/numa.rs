@@ -0,0 +1,192 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use core::fmt;
+
+#[cfg(feature = "concurrent")]
+use std::sync::{Arc, OnceLock};
+#[cfg(feature = "concurrent")]
+use utils::{
+    collections::Vec,
+    iterators::*,
+    rayon::{ThreadPool, ThreadPoolBuilder},
+};
+
+// NUMA PARTITIONING
+// ================================================================================================
+
+/// Describes how the prover partitions work of its largest data-parallel loops (constraint
+/// evaluation and DEEP composition) among NUMA nodes.
+///
+/// On machines with multiple NUMA nodes (e.g., dual-socket servers), letting a single thread pool
+/// schedule chunks of these loops on arbitrary threads causes most memory accesses to cross node
+/// boundaries. With NUMA partitioning, the data processed by a loop is split into one contiguous
+/// range per node, and each range is processed by a dedicated thread pool of that node.
+/// Additionally, buffers produced by these loops are initialized by the threads which process
+/// them; since operating systems commonly back memory by pages of the node which first touches
+/// them, this places each range of the buffer on the node which works with it.
+///
+/// Thread pools are created on first use and are shared by all clones of a partitioning; the
+/// global thread pool is split evenly between the nodes. When thread pinning is enabled, threads
+/// of the pool for node `i` are pinned to the `i`-th of `num_nodes` equal contiguous groups of
+/// cores reported by the operating system; this assumes that cores of the same node are numbered
+/// consecutively.
+///
+/// Partitioning has effect only when `concurrent` feature is enabled; otherwise, all loops are
+/// executed in the current thread.
+#[derive(Clone)]
+pub struct NumaPartitioning {
+    num_nodes: usize,
+    pin_threads: bool,
+    #[cfg(feature = "concurrent")]
+    pools: Arc<OnceLock<Vec<ThreadPool>>>,
+}
+
+impl NumaPartitioning {
+    // CONSTRUCTORS
+    // --------------------------------------------------------------------------------------------
+    /// Returns a new partitioning of work among the specified number of NUMA nodes.
+    ///
+    /// # Panics
+    /// Panics if `num_nodes` is zero.
+    pub fn new(num_nodes: usize) -> Self {
+        assert!(num_nodes > 0, "number of NUMA nodes must be greater than zero");
+        Self {
+            num_nodes,
+            pin_threads: false,
+            #[cfg(feature = "concurrent")]
+            pools: Arc::new(OnceLock::new()),
+        }
+    }
+
+    /// Returns this partitioning with threads of each node pinned to the cores of that node.
+    pub fn with_thread_pinning(mut self) -> Self {
+        self.pin_threads = true;
+        #[cfg(feature = "concurrent")]
+        {
+            self.pools = Arc::new(OnceLock::new());
+        }
+        self
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the number of NUMA nodes among which work is partitioned.
+    pub fn num_nodes(&self) -> usize {
+        self.num_nodes
+    }
+
+    /// Returns `true` if threads of each node are pinned to the cores of that node.
+    pub fn pins_threads(&self) -> bool {
+        self.pin_threads
+    }
+
+    // WORK PARTITIONING
+    // --------------------------------------------------------------------------------------------
+
+    /// Splits `data` into one contiguous range per node, and calls `op` for chunks of each range
+    /// using the thread pool of the respective node. The second argument of `op` is the offset
+    /// of the chunk in `data`.
+    ///
+    /// Chunks are never smaller than `min_chunk_size`; if `data` does not contain at least
+    /// `min_chunk_size` elements per node, `op` is called once for all of `data` in the current
+    /// thread.
+    pub(crate) fn for_each_chunk<T, F>(&self, data: &mut [T], min_chunk_size: usize, op: F)
+    where
+        T: Send,
+        F: Fn(&mut [T], usize) + Sync,
+    {
+        #[cfg(not(feature = "concurrent"))]
+        {
+            let _ = min_chunk_size;
+            op(data, 0);
+        }
+
+        #[cfg(feature = "concurrent")]
+        {
+            // when there is not enough data for every node, process all of it in this thread
+            if data.len() < self.num_nodes * min_chunk_size.max(1) {
+                op(data, 0);
+                return;
+            }
+            let node_size = data.len().div_ceil(self.num_nodes);
+
+            let op = &op;
+            let pools = self.pools();
+            std::thread::scope(|s| {
+                for (node, range) in data.chunks_mut(node_size).enumerate() {
+                    let pool = &pools[node];
+                    let range_offset = node * node_size;
+                    s.spawn(move || {
+                        pool.install(|| {
+                            let num_threads = pool.current_num_threads().next_power_of_two();
+                            let chunk_size = (range.len() / num_threads).max(min_chunk_size).max(1);
+                            range
+                                .par_chunks_mut(chunk_size)
+                                .enumerate()
+                                .for_each(|(i, chunk)| op(chunk, range_offset + i * chunk_size));
+                        })
+                    });
+                }
+            });
+        }
+    }
+
+    /// Sets all elements of `data` to `value` such that each range of `data` is first touched by
+    /// the threads of the node which processes it in [for_each_chunk()](Self::for_each_chunk).
+    pub(crate) fn first_touch<T: Copy + Send + Sync>(
+        &self,
+        data: &mut [T],
+        min_chunk_size: usize,
+        value: T,
+    ) {
+        self.for_each_chunk(data, min_chunk_size, |chunk, _| chunk.fill(value));
+    }
+
+    // HELPER METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns thread pools of all nodes, building them if needed.
+    #[cfg(feature = "concurrent")]
+    fn pools(&self) -> &[ThreadPool] {
+        self.pools.get_or_init(|| {
+            let num_threads = (utils::rayon::current_num_threads() / self.num_nodes).max(1);
+            let cores = if self.pin_threads {
+                core_affinity::get_core_ids().unwrap_or_default()
+            } else {
+                Vec::new()
+            };
+
+            (0..self.num_nodes)
+                .map(|node| {
+                    // cores of a node are the node-th of num_nodes equal groups of all cores
+                    let node_cores = cores[node * cores.len() / self.num_nodes
+                        ..(node + 1) * cores.len() / self.num_nodes]
+                        .to_vec();
+                    ThreadPoolBuilder::new()
+                        .num_threads(num_threads)
+                        .thread_name(move |i| format!("winter-numa-{node}-{i}"))
+                        .start_handler(move |i| {
+                            if !node_cores.is_empty() {
+                                core_affinity::set_for_current(node_cores[i % node_cores.len()]);
+                            }
+                        })
+                        .build()
+                        .expect("failed to build a thread pool for a NUMA node")
+                })
+                .collect()
+        })
+    }
+}
+
+impl fmt::Debug for NumaPartitioning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NumaPartitioning")
+            .field("num_nodes", &self.num_nodes)
+            .field("pin_threads", &self.pin_threads)
+            .finish()
+    }
+}
@@ -0,0 +1,212 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use crate::{
+    constraints::{DivisorInverse, PeriodicValueTable},
+    StarkDomain,
+};
+use air::{Air, ConstraintDivisor, ProofOptions, TraceLayout};
+use core::sync::atomic::{AtomicUsize, Ordering};
+use math::StarkField;
+use utils::collections::Vec;
+
+// PROVER SESSION
+// ================================================================================================
+
+/// Values precomputed for a fixed computation shape which can be reused across proofs.
+///
+/// Before the prover can start working on a trace, it needs to build the STARK domain (including
+/// twiddles for trace polynomial interpolation), evaluate periodic columns over the constraint
+/// evaluation domain, and compute inverse evaluations of constraint divisors. None of these
+/// depend on the contents of the execution trace, and thus, a service generating many proofs for
+/// the same computation can compute them once and share them between proofs via a session (see
+/// [Prover::prove_in_session()](crate::Prover::prove_in_session)).
+///
+/// A session is built from an instance of AIR and can be used for any instance of AIR with the
+/// same trace layout, trace length, proof options, and constraint evaluation domain size. Values
+/// which may differ between AIR instances of the same shape are looked up individually:
+/// evaluations of periodic columns are reused for every column which matches a column of the
+/// session's AIR (the entire table of periodic values is reused only if all columns match), and
+/// inverse divisor evaluations are reused only for divisors defined by the session's AIR (i.e.,
+/// the transition constraint divisor and divisors of the main trace assertions). Everything else
+/// is computed on the fly, and the number of such lookup misses is reported via
+/// [stats()](ProverSession::stats).
+///
+/// Sessions are immutable (apart from the statistics counters), and thus, a single session can be
+/// shared between multiple threads generating proofs concurrently.
+pub struct ProverSession<B: StarkField> {
+    trace_layout: TraceLayout,
+    trace_length: usize,
+    options: ProofOptions,
+    domain: StarkDomain<B>,
+    periodic_column_values: Vec<Vec<B>>,
+    periodic_values: PeriodicValueTable<B>,
+    divisors: Vec<(ConstraintDivisor<B>, DivisorInverse<B>)>,
+    num_proofs: AtomicUsize,
+    num_rejected: AtomicUsize,
+    num_hits: AtomicUsize,
+    num_misses: AtomicUsize,
+}
+
+impl<B: StarkField> ProverSession<B> {
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+    /// Returns a new session with values precomputed for the computation described by the
+    /// specified AIR.
+    pub fn new<A: Air<BaseField = B>>(air: &A) -> Self {
+        let domain = StarkDomain::new(air);
+
+        // all transition constraints share the same divisor; divisors of main trace assertions
+        // do not depend on randomness drawn during proof generation and thus can be precomputed
+        let mut divisors = vec![air.get_transition_divisor()];
+        for assertion in air.get_assertions() {
+            let divisor = ConstraintDivisor::from_assertion(&assertion, air.trace_length());
+            if !divisors.contains(&divisor) {
+                divisors.push(divisor);
+            }
+        }
+        let divisors = divisors
+            .into_iter()
+            .map(|divisor| {
+                let inverse = DivisorInverse::new(&divisor, &domain);
+                (divisor, inverse)
+            })
+            .collect();
+
+        ProverSession {
+            trace_layout: air.trace_layout().clone(),
+            trace_length: air.trace_length(),
+            options: air.options().clone(),
+            periodic_column_values: air.get_periodic_column_values(),
+            periodic_values: PeriodicValueTable::new(air),
+            domain,
+            divisors,
+            num_proofs: AtomicUsize::new(0),
+            num_rejected: AtomicUsize::new(0),
+            num_hits: AtomicUsize::new(0),
+            num_misses: AtomicUsize::new(0),
+        }
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the STARK domain precomputed for this session.
+    pub fn domain(&self) -> &StarkDomain<B> {
+        &self.domain
+    }
+
+    /// Returns true if values precomputed in this session can be used to generate a proof for
+    /// the computation described by the specified AIR.
+    pub fn is_compatible_with<A: Air<BaseField = B>>(&self, air: &A) -> bool {
+        air.trace_layout() == &self.trace_layout
+            && air.trace_length() == self.trace_length
+            && air.options() == &self.options
+            && air.ce_domain_size() == self.domain.ce_domain_size()
+    }
+
+    /// Returns statistics about usage of this session.
+    pub fn stats(&self) -> SessionStats {
+        SessionStats {
+            num_proofs: self.num_proofs.load(Ordering::Relaxed),
+            num_rejected: self.num_rejected.load(Ordering::Relaxed),
+            num_hits: self.num_hits.load(Ordering::Relaxed),
+            num_misses: self.num_misses.load(Ordering::Relaxed),
+            size_in_bytes: self.size_in_bytes(),
+        }
+    }
+
+    /// Returns the number of bytes occupied by values precomputed in this session.
+    pub fn size_in_bytes(&self) -> usize {
+        let domain_size = self.domain.trace_twiddles().len() + self.domain.ce_domain_size();
+        let divisors_size: usize =
+            self.divisors.iter().map(|(_, inverse)| inverse.size_in_bytes()).sum();
+        domain_size * B::ELEMENT_BYTES + self.periodic_values.size_in_bytes() + divisors_size
+    }
+
+    // LOOKUPS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the table of periodic values precomputed in this session if periodic columns of
+    /// the specified AIR are the same as periodic columns of the AIR used to build this session.
+    pub(crate) fn get_periodic_values<A: Air<BaseField = B>>(
+        &self,
+        air: &A,
+    ) -> Option<&PeriodicValueTable<B>> {
+        let result = if air.get_periodic_column_values() == self.periodic_column_values {
+            Some(&self.periodic_values)
+        } else {
+            None
+        };
+        self.record_lookup(result.is_some());
+        result
+    }
+
+    /// Returns evaluations of a periodic column with the specified cycle values over the
+    /// constraint evaluation domain if a column with the same values was evaluated in this
+    /// session.
+    ///
+    /// This is used to build tables of periodic values for AIR instances whose periodic columns
+    /// only partially match the columns of the session's AIR. Such lookups are not recorded in
+    /// session statistics, as the table itself is already counted as a miss.
+    pub(crate) fn get_periodic_column(&self, values: &[B]) -> Option<Vec<B>> {
+        let index = self.periodic_column_values.iter().position(|column| column == values)?;
+        let ce_blowup_factor = self.domain.ce_domain_size() / self.trace_length;
+        Some(self.periodic_values.get_column(index, values.len(), ce_blowup_factor))
+    }
+
+    /// Returns inverse evaluations of the specified divisor if they were precomputed in this
+    /// session.
+    pub(crate) fn get_divisor_inverse(
+        &self,
+        divisor: &ConstraintDivisor<B>,
+    ) -> Option<&DivisorInverse<B>> {
+        let result = self.divisors.iter().find(|(d, _)| d == divisor).map(|(_, inverse)| inverse);
+        self.record_lookup(result.is_some());
+        result
+    }
+
+    // USAGE TRACKING
+    // --------------------------------------------------------------------------------------------
+
+    /// Records that a proof was generated using this session, or that this session was ignored
+    /// because it was not compatible with the computation being proven.
+    pub(crate) fn record_proof(&self, compatible: bool) {
+        if compatible {
+            self.num_proofs.fetch_add(1, Ordering::Relaxed);
+        } else {
+            self.num_rejected.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    fn record_lookup(&self, hit: bool) {
+        if hit {
+            self.num_hits.fetch_add(1, Ordering::Relaxed);
+        } else {
+            self.num_misses.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+// SESSION STATISTICS
+// ================================================================================================
+
+/// Usage statistics of a [ProverSession].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SessionStats {
+    /// Number of proofs generated using values precomputed in the session.
+    pub num_proofs: usize,
+    /// Number of proofs for which the session was ignored because it was built for a computation
+    /// of a different shape.
+    pub num_rejected: usize,
+    /// Number of times periodic values or inverse divisor evaluations were served from the
+    /// session.
+    pub num_hits: usize,
+    /// Number of times periodic values or inverse divisor evaluations had to be computed because
+    /// they were not precomputed in the session.
+    pub num_misses: usize,
+    /// Number of bytes occupied by values precomputed in the session.
+    pub size_in_bytes: usize,
+}
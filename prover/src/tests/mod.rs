@@ -59,6 +59,12 @@ impl MockAir {
         result
     }
 
+    pub fn with_preprocessed_columns(num_columns: usize, trace_length: usize) -> Self {
+        let mut result = Self::with_trace_length(trace_length);
+        result.context = result.context.set_num_preprocessed_columns(num_columns);
+        result
+    }
+
     pub fn with_assertions(assertions: Vec<Assertion<BaseElement>>, trace_length: usize) -> Self {
         let mut result = Self::new(
             TraceInfo::new(4, trace_length),
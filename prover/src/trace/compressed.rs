@@ -0,0 +1,325 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use core::mem::{size_of, size_of_val};
+use math::StarkField;
+use utils::collections::Vec;
+
+// COMPRESSED COLUMN
+// ================================================================================================
+
+/// A column of an execution trace stored in a compressed form.
+///
+/// Many execution traces contain columns which are structurally simple (e.g., selector columns
+/// of a VM are mostly zero, and columns holding a program counter or a memory address are
+/// piecewise-constant). Storing such columns densely makes trace memory grow with the number of
+/// such columns regardless of how little information they carry. Two encodings are supported:
+/// * Run-length encoding, which stores each run of consecutive equal values once; this is
+///   efficient for piecewise-constant columns.
+/// * Sparse encoding, which stores a default value and the rows at which values differ from it;
+///   this is efficient for columns which are mostly zero (or mostly any other single value).
+///
+/// Compressed columns are expanded into their dense form only when needed (see
+/// [TraceTable::compress()](crate::TraceTable::compress)).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompressedColumn<B: StarkField> {
+    length: usize,
+    encoding: Encoding<B>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Encoding<B: StarkField> {
+    /// Values of runs of consecutive equal values, and the rows at which the runs end (exclusive).
+    RunLength { values: Vec<B>, ends: Vec<usize> },
+    /// Values which differ from the default value, and the rows (in increasing order) at which
+    /// they are located.
+    Sparse {
+        default: B,
+        rows: Vec<usize>,
+        values: Vec<B>,
+    },
+}
+
+impl<B: StarkField> CompressedColumn<B> {
+    // CONSTRUCTORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the specified column values in run-length encoding.
+    ///
+    /// # Panics
+    /// Panics if `values` is empty.
+    pub fn run_length(values: &[B]) -> Self {
+        assert!(!values.is_empty(), "a column must contain at least one value");
+        let mut run_values = vec![values[0]];
+        let mut ends = Vec::new();
+        for (row, window) in values.windows(2).enumerate() {
+            if window[0] != window[1] {
+                ends.push(row + 1);
+                run_values.push(window[1]);
+            }
+        }
+        ends.push(values.len());
+
+        Self {
+            length: values.len(),
+            encoding: Encoding::RunLength {
+                values: run_values,
+                ends,
+            },
+        }
+    }
+
+    /// Returns a column of the specified length built from runs of equal values without
+    /// expanding it; each run is described by its value and the number of rows it covers.
+    ///
+    /// # Panics
+    /// Panics if `runs` is empty or if any of the runs covers zero rows.
+    pub fn from_runs(runs: &[(B, usize)]) -> Self {
+        assert!(!runs.is_empty(), "a column must contain at least one run");
+        let mut values = Vec::with_capacity(runs.len());
+        let mut ends = Vec::with_capacity(runs.len());
+        let mut length = 0;
+        for &(value, run_length) in runs {
+            assert!(run_length > 0, "a run must cover at least one row");
+            length += run_length;
+            // merge adjacent runs of the same value
+            if values.last() == Some(&value) {
+                *ends.last_mut().expect("not empty") = length;
+            } else {
+                values.push(value);
+                ends.push(length);
+            }
+        }
+
+        Self {
+            length,
+            encoding: Encoding::RunLength { values, ends },
+        }
+    }
+
+    /// Returns the specified column values in sparse encoding, storing only the values which
+    /// differ from `default`.
+    ///
+    /// # Panics
+    /// Panics if `values` is empty.
+    pub fn sparse(values: &[B], default: B) -> Self {
+        assert!(!values.is_empty(), "a column must contain at least one value");
+        let (rows, sparse_values) = values
+            .iter()
+            .enumerate()
+            .filter(|(_, &value)| value != default)
+            .map(|(row, &value)| (row, value))
+            .unzip();
+
+        Self {
+            length: values.len(),
+            encoding: Encoding::Sparse {
+                default,
+                rows,
+                values: sparse_values,
+            },
+        }
+    }
+
+    /// Returns a column of the specified length built from the values which differ from
+    /// `default` without expanding it; each such value is described by its row and the value.
+    ///
+    /// # Panics
+    /// Panics if `length` is zero, or if rows of `entries` are not in strictly increasing order
+    /// or are out of bounds.
+    pub fn from_sparse(length: usize, default: B, entries: &[(usize, B)]) -> Self {
+        assert!(length > 0, "a column must contain at least one value");
+        for window in entries.windows(2) {
+            assert!(window[0].0 < window[1].0, "rows of sparse values must be strictly increasing");
+        }
+        if let Some(&(row, _)) = entries.last() {
+            assert!(row < length, "row {row} is out of bounds for a column of {length} rows");
+        }
+
+        let (rows, values) = entries.iter().copied().unzip();
+        Self {
+            length,
+            encoding: Encoding::Sparse {
+                default,
+                rows,
+                values,
+            },
+        }
+    }
+
+    /// Compresses the specified column values using the encoding which takes the least amount of
+    /// memory (zero is used as the default value for sparse encoding).
+    ///
+    /// Returns `None` if neither of the encodings takes less memory than the values themselves.
+    pub fn compress(values: &[B]) -> Option<Self> {
+        if values.is_empty() {
+            return None;
+        }
+        let num_runs = 1 + values.windows(2).filter(|window| window[0] != window[1]).count();
+        let num_non_zeros = values.iter().filter(|&&value| value != B::ZERO).count();
+
+        let column = if num_runs <= num_non_zeros {
+            Self::run_length(values)
+        } else {
+            Self::sparse(values, B::ZERO)
+        };
+        (column.size_in_bytes() < size_of_val(values)).then_some(column)
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the number of rows in this column.
+    pub fn len(&self) -> usize {
+        self.length
+    }
+
+    /// Returns true if this column contains no rows; this is never the case for valid columns.
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    /// Returns the value at the specified row of this column.
+    ///
+    /// # Panics
+    /// Panics if `row` is out of bounds.
+    pub fn get(&self, row: usize) -> B {
+        assert!(
+            row < self.length,
+            "row {row} is out of bounds for a column of {} rows",
+            self.length
+        );
+        match &self.encoding {
+            Encoding::RunLength { values, ends } => values[ends.partition_point(|&end| end <= row)],
+            Encoding::Sparse {
+                default,
+                rows,
+                values,
+            } => rows.binary_search(&row).map_or(*default, |idx| values[idx]),
+        }
+    }
+
+    /// Returns the number of bytes occupied by this column.
+    pub fn size_in_bytes(&self) -> usize {
+        match &self.encoding {
+            Encoding::RunLength { values, ends } => {
+                values.len() * size_of::<B>() + ends.len() * size_of::<usize>()
+            }
+            Encoding::Sparse { rows, values, .. } => {
+                (values.len() + 1) * size_of::<B>() + rows.len() * size_of::<usize>()
+            }
+        }
+    }
+
+    // EXPANSION
+    // --------------------------------------------------------------------------------------------
+
+    /// Writes all values of this column into the provided target.
+    ///
+    /// # Panics
+    /// Panics if the length of `target` is not the same as the number of rows in this column.
+    pub fn expand_into(&self, target: &mut [B]) {
+        assert_eq!(
+            self.length,
+            target.len(),
+            "expected target of {} elements, but was {}",
+            self.length,
+            target.len()
+        );
+        match &self.encoding {
+            Encoding::RunLength { values, ends } => {
+                let mut start = 0;
+                for (&value, &end) in values.iter().zip(ends) {
+                    target[start..end].fill(value);
+                    start = end;
+                }
+            }
+            Encoding::Sparse {
+                default,
+                rows,
+                values,
+            } => {
+                target.fill(*default);
+                for (&row, &value) in rows.iter().zip(values) {
+                    target[row] = value;
+                }
+            }
+        }
+    }
+
+    /// Returns all values of this column.
+    pub fn expand(&self) -> Vec<B> {
+        let mut result = vec![B::ZERO; self.length];
+        self.expand_into(&mut result);
+        result
+    }
+}
+
+// TRACE COLUMN
+// ================================================================================================
+
+/// A column of an execution trace stored either densely or in a compressed form.
+///
+/// Trace tables with compressed columns can be built via
+/// [TraceTable::init_columns()](crate::TraceTable::init_columns) without ever allocating memory
+/// for the dense form of the compressed columns.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TraceColumn<B: StarkField> {
+    /// All values of the column.
+    Dense(Vec<B>),
+    /// The column in a compressed form.
+    Compressed(CompressedColumn<B>),
+}
+
+impl<B: StarkField> TraceColumn<B> {
+    /// Returns the number of rows in this column.
+    pub fn len(&self) -> usize {
+        match self {
+            Self::Dense(values) => values.len(),
+            Self::Compressed(column) => column.len(),
+        }
+    }
+
+    /// Returns true if this column contains no rows.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the value at the specified row of this column.
+    pub fn get(&self, row: usize) -> B {
+        match self {
+            Self::Dense(values) => values[row],
+            Self::Compressed(column) => column.get(row),
+        }
+    }
+
+    /// Returns the number of bytes occupied by this column.
+    pub fn size_in_bytes(&self) -> usize {
+        match self {
+            Self::Dense(values) => values.len() * size_of::<B>(),
+            Self::Compressed(column) => column.size_in_bytes(),
+        }
+    }
+
+    /// Returns all values of this column; dense columns are returned without copying.
+    pub fn into_values(self) -> Vec<B> {
+        match self {
+            Self::Dense(values) => values,
+            Self::Compressed(column) => column.expand(),
+        }
+    }
+}
+
+impl<B: StarkField> From<Vec<B>> for TraceColumn<B> {
+    fn from(values: Vec<B>) -> Self {
+        Self::Dense(values)
+    }
+}
+
+impl<B: StarkField> From<CompressedColumn<B>> for TraceColumn<B> {
+    fn from(column: CompressedColumn<B>) -> Self {
+        Self::Compressed(column)
+    }
+}
@@ -0,0 +1,177 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::Trace;
+use air::{Air, AssertionError};
+use core::fmt;
+use math::polynom;
+use utils::collections::Vec;
+
+// TRACE DIAGNOSTICS
+// ================================================================================================
+
+/// Describes all issues found while validating an execution trace against an AIR.
+///
+/// Diagnostics are produced by [TraceTable::validate_against()](crate::TraceTable::validate_against)
+/// before the trace is passed to the prover. Unlike [ProverError](crate::ProverError), which
+/// reports only the first problem encountered during proof generation, diagnostics list every
+/// issue found in the trace.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TraceDiagnostics {
+    issues: Vec<TraceIssue>,
+}
+
+impl TraceDiagnostics {
+    /// Returns true if no issues were found in the trace.
+    pub fn is_valid(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    /// Returns the list of issues found in the trace in the order in which they were found.
+    pub fn issues(&self) -> &[TraceIssue] {
+        &self.issues
+    }
+}
+
+impl fmt::Display for TraceDiagnostics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_valid() {
+            return write!(f, "no issues found in the execution trace");
+        }
+        for issue in self.issues.iter() {
+            writeln!(f, "{issue}")?;
+        }
+        Ok(())
+    }
+}
+
+// TRACE ISSUE
+// ================================================================================================
+
+/// Represents a single issue found while validating an execution trace against an AIR.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TraceIssue {
+    /// The width of the main trace segment is not consistent with the width expected by the AIR.
+    /// The values are the expected and the actual widths.
+    InconsistentTraceWidth(usize, usize),
+    /// The length of the trace is not consistent with the length expected by the AIR. The values
+    /// are the expected and the actual lengths.
+    InconsistentTraceLength(usize, usize),
+    /// The number of assertions against the main trace segment returned by the AIR is not
+    /// consistent with the number of assertions specified in the AIR context. The values are the
+    /// expected and the actual numbers of assertions.
+    InconsistentNumAssertions(usize, usize),
+    /// An assertion is not valid in the context of the trace (e.g., assertion column index is out
+    /// of bounds).
+    InvalidAssertion(AssertionError),
+    /// The trace does not satisfy an assertion placed against the specified column at the
+    /// specified step; only the first unsatisfied step of every assertion is reported.
+    UnsatisfiedAssertion(usize, usize),
+    /// The polynomial interpolated from the specified column has a degree greater than the
+    /// expected maximum degree. The values are the column index, the maximum degree, and the
+    /// actual degree.
+    ColumnDegreeTooHigh(usize, usize, usize),
+}
+
+impl fmt::Display for TraceIssue {
+    #[rustfmt::skip]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InconsistentTraceWidth(expected, actual) => {
+                write!(f, "inconsistent main trace width; expected {expected}, but was {actual}")
+            }
+            Self::InconsistentTraceLength(expected, actual) => {
+                write!(f, "inconsistent trace length; expected {expected}, but was {actual}")
+            }
+            Self::InconsistentNumAssertions(expected, actual) => {
+                write!(f, "expected {expected} assertions against main trace segment, but received {actual}")
+            }
+            Self::InvalidAssertion(err) => {
+                write!(f, "an assertion is invalid: {err}")
+            }
+            Self::UnsatisfiedAssertion(column, step) => {
+                write!(f, "trace does not satisfy an assertion against column {column} at step {step}")
+            }
+            Self::ColumnDegreeTooHigh(column, max_degree, degree) => {
+                write!(f, "column {column} interpolates to a polynomial of degree {degree}, but the maximum degree is {max_degree}")
+            }
+        }
+    }
+}
+
+// VALIDATION
+// ================================================================================================
+
+/// Validates the main segment of the specified trace against the specified AIR and returns all
+/// issues found.
+///
+/// If `max_column_degree` is provided, all columns are interpolated and the degrees of the
+/// resulting polynomials are checked against it; columns are interpolated only if the trace has
+/// the width and the length expected by the AIR.
+pub(super) fn diagnose<A, T>(
+    air: &A,
+    trace: &T,
+    max_column_degree: Option<usize>,
+) -> TraceDiagnostics
+where
+    A: Air,
+    T: Trace<BaseField = A::BaseField>,
+{
+    let mut issues = Vec::new();
+
+    // --- 1. check trace shape -------------------------------------------------------------------
+    let expected_width = air.trace_layout().main_trace_width();
+    if trace.main_trace_width() != expected_width {
+        issues.push(TraceIssue::InconsistentTraceWidth(expected_width, trace.main_trace_width()));
+    }
+    if trace.length() != air.trace_length() {
+        issues.push(TraceIssue::InconsistentTraceLength(air.trace_length(), trace.length()));
+    }
+    let is_shape_valid = issues.is_empty();
+
+    // --- 2. check assertions --------------------------------------------------------------------
+    let assertions = air.get_assertions();
+    let expected_num_assertions = air.context().num_main_assertions();
+    if assertions.len() != expected_num_assertions {
+        issues
+            .push(TraceIssue::InconsistentNumAssertions(expected_num_assertions, assertions.len()));
+    }
+
+    let main_segment = trace.main_segment();
+    for assertion in assertions {
+        let validation = assertion
+            .validate_trace_width(trace.main_trace_width())
+            .and_then(|_| assertion.validate_trace_length(trace.length()));
+        if let Err(err) = validation {
+            issues.push(TraceIssue::InvalidAssertion(err));
+            continue;
+        }
+
+        let mut unsatisfied_step = None;
+        assertion.apply(trace.length(), |step, value| {
+            if unsatisfied_step.is_none()
+                && assertion.combine_columns(|column| main_segment.get(column, step)) != value
+            {
+                unsatisfied_step = Some(step);
+            }
+        });
+        if let Some(step) = unsatisfied_step {
+            issues.push(TraceIssue::UnsatisfiedAssertion(assertion.column(), step));
+        }
+    }
+
+    // --- 3. check column degrees ----------------------------------------------------------------
+    if let Some(max_degree) = max_column_degree.filter(|_| is_shape_valid) {
+        let polys = main_segment.interpolate_columns();
+        for (column, poly) in polys.columns().enumerate() {
+            let degree = polynom::degree_of(poly);
+            if degree > max_degree {
+                issues.push(TraceIssue::ColumnDegreeTooHigh(column, max_degree, degree));
+            }
+        }
+    }
+
+    TraceDiagnostics { issues }
+}
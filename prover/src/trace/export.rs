@@ -0,0 +1,104 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{Trace, TraceTable};
+use arrow_array::{ArrayRef, FixedSizeBinaryArray, RecordBatch, UInt64Array};
+use arrow_schema::{ArrowError, DataType, Field, Schema};
+use math::StarkField;
+use parquet::{arrow::ArrowWriter, errors::ParquetError};
+use std::{collections::HashMap, fmt::Write as _, io::Write, sync::Arc};
+
+// CONSTANTS
+// ================================================================================================
+
+/// Key of the schema metadata entry which holds hex-encoded trace metadata.
+pub const TRACE_META_KEY: &str = "winterfell.trace.meta";
+
+/// Key of the schema metadata entry which holds hex-encoded little-endian bytes of the field
+/// modulus.
+pub const FIELD_MODULUS_KEY: &str = "winterfell.field.modulus";
+
+// ARROW AND PARQUET EXPORT
+// ================================================================================================
+
+impl<B: StarkField> TraceTable<B> {
+    /// Returns an Arrow record batch containing all values of this execution trace.
+    ///
+    /// The record batch contains a non-nullable column named `col_{i}` for each column `i` of the
+    /// trace, and a row for each step of the trace. Values are stored as their canonical integer
+    /// representations: as `UInt64` values for fields with elements of at most 8 bytes, and as
+    /// little-endian `FixedSizeBinary` values otherwise. Trace metadata and the modulus of the
+    /// base field are recorded in the schema metadata under [TRACE_META_KEY] and
+    /// [FIELD_MODULUS_KEY] keys respectively.
+    ///
+    /// # Errors
+    /// Returns an error if the record batch could not be built.
+    pub fn to_record_batch(&self) -> Result<RecordBatch, ArrowError> {
+        let (data_type, columns) = if B::ELEMENT_BYTES <= 8 {
+            let columns = self
+                .columns()
+                .map(|column| {
+                    let values = column.iter().map(|value| to_u64(*value)).collect::<Vec<_>>();
+                    Arc::new(UInt64Array::from(values)) as ArrayRef
+                })
+                .collect::<Vec<_>>();
+            (DataType::UInt64, columns)
+        } else {
+            let columns = self
+                .columns()
+                .map(|column| {
+                    let values = column.iter().map(|value| value.to_bytes());
+                    Ok(Arc::new(FixedSizeBinaryArray::try_from_iter(values)?) as ArrayRef)
+                })
+                .collect::<Result<Vec<_>, ArrowError>>()?;
+            (DataType::FixedSizeBinary(B::ELEMENT_BYTES as i32), columns)
+        };
+
+        let fields = (0..self.width())
+            .map(|i| Field::new(format!("col_{i}"), data_type.clone(), false))
+            .collect::<Vec<_>>();
+        let metadata = HashMap::from([
+            (TRACE_META_KEY.to_string(), to_hex(self.meta())),
+            (FIELD_MODULUS_KEY.to_string(), to_hex(&B::get_modulus_le_bytes())),
+        ]);
+        let schema = Schema::new(fields).with_metadata(metadata);
+
+        RecordBatch::try_new(Arc::new(schema), columns)
+    }
+
+    /// Writes this execution trace into the specified `writer` as a Parquet file.
+    ///
+    /// The file contains a single row group with the record batch returned by
+    /// [to_record_batch()](TraceTable::to_record_batch).
+    ///
+    /// # Errors
+    /// Returns an error if the trace could not be converted into a record batch or written into
+    /// the `writer`.
+    pub fn write_parquet<W: Write + Send>(&self, writer: W) -> Result<(), ParquetError> {
+        let batch = self.to_record_batch()?;
+        let mut writer = ArrowWriter::try_new(writer, batch.schema(), None)?;
+        writer.write(&batch)?;
+        writer.close()?;
+        Ok(())
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns the canonical integer representation of a field element which fits into 8 bytes.
+fn to_u64<B: StarkField>(value: B) -> u64 {
+    let mut bytes = [0u8; 8];
+    bytes[..B::ELEMENT_BYTES].copy_from_slice(&value.to_bytes());
+    u64::from_le_bytes(bytes)
+}
+
+/// Returns a lowercase hex encoding of the specified bytes.
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().fold(String::with_capacity(bytes.len() * 2), |mut result, byte| {
+        let _ = write!(result, "{byte:02x}");
+        result
+    })
+}
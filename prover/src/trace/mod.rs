@@ -3,18 +3,33 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use super::{matrix::MultiColumnIter, ColMatrix};
+use super::{matrix::MultiColumnIter, ColMatrix, ProverError};
 use air::{Air, AuxTraceRandElements, EvaluationFrame, TraceInfo, TraceLayout};
 use math::{polynom, FieldElement, StarkField};
 
 mod trace_lde;
-pub use trace_lde::{DefaultTraceLde, TraceLde};
+pub use trace_lde::{DefaultTraceLde, PreprocessedTrace, TraceLde};
+pub(crate) use trace_lde::{LdeFrames, TraceFrames};
 
 mod poly_table;
 pub use poly_table::TracePolyTable;
 
+mod diagnostics;
+use diagnostics::diagnose;
+pub use diagnostics::{TraceDiagnostics, TraceIssue};
+
+mod compressed;
+pub use compressed::{CompressedColumn, TraceColumn};
+
 mod trace_table;
-pub use trace_table::{TraceTable, TraceTableFragment};
+pub use trace_table::{
+    RowIter, TraceRow, TraceTable, TraceTableFragment, TraceTableFragmentView, TraceTableWriter,
+};
+
+#[cfg(feature = "parquet")]
+mod export;
+#[cfg(feature = "parquet")]
+pub use export::{FIELD_MODULUS_KEY, TRACE_META_KEY};
 
 #[cfg(test)]
 mod tests;
@@ -70,6 +85,9 @@ pub trait Trace: Sized {
     ) -> Option<ColMatrix<E>>;
 
     /// Reads an evaluation frame from the main trace segment at the specified row.
+    ///
+    /// Row `i` of the frame must be filled with the trace row at `row_idx + frame.offsets()[i]`,
+    /// wrapping around at the end of the trace.
     fn read_main_frame(&self, row_idx: usize, frame: &mut EvaluationFrame<Self::BaseField>);
 
     // PROVIDED METHODS
@@ -118,39 +136,40 @@ pub trait Trace: Sized {
         // first, check assertions against the main segment of the execution trace
         for assertion in air.get_assertions() {
             assertion.apply(self.length(), |step, value| {
+                let main_segment = self.main_segment();
                 assert!(
-                    value == self.main_segment().get(assertion.column(), step),
-                    "trace does not satisfy assertion main_trace({}, {}) == {}",
-                    assertion.column(),
+                    value == assertion.combine_columns(|column| main_segment.get(column, step)),
+                    "trace does not satisfy assertion {} at step {}",
+                    assertion,
                     step,
-                    value
                 );
             });
         }
 
         // then, check assertions against auxiliary trace segments
         for assertion in air.get_aux_assertions(aux_rand_elements) {
-            // find which segment the assertion is for and remap assertion column index to the
-            // column index in the context of this segment
-            let mut column_idx = assertion.column();
-            let mut segment_idx = 0;
-            for i in 0..self.layout().num_aux_segments() {
-                let segment_width = self.layout().get_aux_segment_width(i);
-                if column_idx < segment_width {
-                    segment_idx = i;
-                    break;
+            // find which segment each column of the assertion is in and remap the column index to
+            // the column index in the context of this segment
+            let get_value = |mut column_idx: usize, step: usize| {
+                let mut segment_idx = 0;
+                for i in 0..self.layout().num_aux_segments() {
+                    let segment_width = self.layout().get_aux_segment_width(i);
+                    if column_idx < segment_width {
+                        segment_idx = i;
+                        break;
+                    }
+                    column_idx -= segment_width;
                 }
-                column_idx -= segment_width;
-            }
+                aux_segments[segment_idx].get(column_idx, step)
+            };
 
-            // get the matrix and verify the assertion against it
+            // verify the assertion against the auxiliary segments
             assertion.apply(self.length(), |step, value| {
                 assert!(
-                    value == aux_segments[segment_idx].get(column_idx, step),
-                    "trace does not satisfy assertion aux_trace({}, {}) == {}",
-                    assertion.column(),
+                    value == assertion.combine_columns(|column| get_value(column, step)),
+                    "trace does not satisfy assertion {} against auxiliary trace at step {}",
+                    assertion,
                     step,
-                    value
                 );
             });
         }
@@ -164,9 +183,10 @@ pub trait Trace: Sized {
 
         // initialize buffers to hold evaluation frames and results of constraint evaluations
         let mut x = Self::BaseField::ONE;
-        let mut main_frame = EvaluationFrame::new(self.main_trace_width());
+        let ood_offsets = air.context().ood_offsets();
+        let mut main_frame = EvaluationFrame::with_offsets(self.main_trace_width(), ood_offsets);
         let mut aux_frame = if air.trace_info().is_multi_segment() {
-            Some(EvaluationFrame::<E>::new(self.aux_trace_width()))
+            Some(EvaluationFrame::<E>::with_offsets(self.aux_trace_width(), ood_offsets))
         } else {
             None
         };
@@ -223,6 +243,100 @@ pub trait Trace: Sized {
 // HELPER FUNCTIONS
 // ================================================================================================
 
+/// Checks that the main segment of the provided execution trace is consistent with the specified
+/// AIR and satisfies all assertions placed against it.
+///
+/// Unlike [Trace::validate()], this does not evaluate transition constraints, and thus, is cheap
+/// enough to be performed at the start of every proof generation.
+pub(crate) fn validate_main_assertions<A, T>(air: &A, trace: &T) -> Result<(), ProverError>
+where
+    A: Air,
+    T: Trace<BaseField = A::BaseField>,
+{
+    let expected_width = air.trace_layout().main_trace_width();
+    if trace.main_trace_width() != expected_width {
+        return Err(ProverError::InconsistentTraceWidth(expected_width, trace.main_trace_width()));
+    }
+
+    let assertions = air.get_assertions();
+    let expected_num_assertions = air.context().num_main_assertions();
+    if assertions.len() != expected_num_assertions {
+        return Err(ProverError::InconsistentNumAssertions(
+            expected_num_assertions,
+            assertions.len(),
+        ));
+    }
+
+    let main_segment = trace.main_segment();
+    for assertion in assertions {
+        assertion
+            .validate_trace_width(trace.main_trace_width())
+            .map_err(ProverError::InvalidAssertion)?;
+        assertion
+            .validate_trace_length(trace.length())
+            .map_err(ProverError::InvalidAssertion)?;
+
+        let mut unsatisfied_step = None;
+        assertion.apply(trace.length(), |step, value| {
+            if unsatisfied_step.is_none()
+                && assertion.combine_columns(|column| main_segment.get(column, step)) != value
+            {
+                unsatisfied_step = Some(step);
+            }
+        });
+        if let Some(step) = unsatisfied_step {
+            return Err(ProverError::UnsatisfiedAssertion(assertion.column(), step));
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks that the provided polynomials for the columns of the main trace segment are consistent
+/// with the specified AIR, and returns the polynomials truncated to the length of the trace.
+///
+/// In debug mode, this also checks that the polynomials interpolate the columns of the main
+/// segment of the provided execution trace; this is as expensive as the interpolation itself.
+pub(crate) fn validate_main_trace_polys<A, T>(
+    air: &A,
+    trace: &T,
+    polys: ColMatrix<A::BaseField>,
+) -> Result<ColMatrix<A::BaseField>, ProverError>
+where
+    A: Air,
+    T: Trace<BaseField = A::BaseField>,
+{
+    let expected_width = air.trace_layout().main_trace_width();
+    if polys.num_cols() != expected_width {
+        return Err(ProverError::InconsistentTraceWidth(expected_width, polys.num_cols()));
+    }
+
+    let trace_length = air.trace_length();
+    let mut columns = polys.into_columns();
+    for (i, column) in columns.iter_mut().enumerate() {
+        let degree = polynom::degree_of(column);
+        if degree >= trace_length {
+            return Err(ProverError::TracePolyDegreeTooHigh(i, trace_length - 1, degree));
+        }
+        column.resize(trace_length, A::BaseField::ZERO);
+    }
+    let polys = ColMatrix::new(columns);
+
+    #[cfg(debug_assertions)]
+    {
+        let expected_polys = trace.main_segment().interpolate_columns();
+        for (i, (poly, expected)) in polys.columns().zip(expected_polys.columns()).enumerate() {
+            if poly != expected {
+                return Err(ProverError::TracePolyMismatch(i));
+            }
+        }
+    }
+    #[cfg(not(debug_assertions))]
+    let _ = trace;
+
+    Ok(polys)
+}
+
 /// Reads an evaluation frame from the set of provided auxiliary segments. This expects that
 /// `aux_segments` contains at least one entry.
 ///
@@ -233,12 +347,11 @@ fn read_aux_frame<E>(aux_segments: &[ColMatrix<E>], row_idx: usize, frame: &mut
 where
     E: FieldElement,
 {
-    for (column, current_value) in MultiColumnIter::new(aux_segments).zip(frame.current_mut()) {
-        *current_value = column[row_idx];
-    }
-
-    let next_row_idx = (row_idx + 1) % aux_segments[0].num_rows();
-    for (column, next_value) in MultiColumnIter::new(aux_segments).zip(frame.next_mut()) {
-        *next_value = column[next_row_idx];
+    let num_rows = aux_segments[0].num_rows();
+    for row in 0..frame.num_rows() {
+        let offset_row_idx = (row_idx + frame.offsets()[row]) % num_rows;
+        for (column, value) in MultiColumnIter::new(aux_segments).zip(frame.row_mut(row)) {
+            *value = column[offset_row_idx];
+        }
     }
 }
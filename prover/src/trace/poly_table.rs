@@ -67,17 +67,27 @@ impl<E: FieldElement> TracePolyTable<E> {
     /// Returns an out-of-domain evaluation frame constructed by evaluating trace polynomials
     /// for all columns at points z and z * g, where g is the generator of the trace domain.
     pub fn get_ood_frame(&self, z: E) -> Vec<Vec<E>> {
-        let g = E::from(E::BaseField::get_root_of_unity(self.poly_size().ilog2()));
-        vec![self.evaluate_at(z), self.evaluate_at(z * g)]
+        self.get_ood_frame_at(z, &[0, 1])
+    }
+
+    /// Returns an out-of-domain evaluation frame constructed by evaluating trace polynomials
+    /// for all columns at points z * g^k for every k in `offsets`, where g is the generator of
+    /// the trace domain.
+    pub fn get_ood_frame_at(&self, z: E, offsets: &[usize]) -> Vec<Vec<E>> {
+        let g = E::BaseField::get_root_of_unity(self.poly_size().ilog2());
+        offsets
+            .iter()
+            .map(|&k| self.evaluate_at(z * E::from(g.exp_vartime((k as u64).into()))))
+            .collect()
     }
 
     /// Returns an iterator over the polynomials of the main trace segment.
-    pub fn main_trace_polys(&self) -> ColumnIter<E::BaseField> {
+    pub fn main_trace_polys(&self) -> ColumnIter<'_, E::BaseField> {
         self.main_segment_polys.columns()
     }
 
     /// Returns an iterator over the polynomials of all auxiliary trace segments.
-    pub fn aux_trace_polys(&self) -> MultiColumnIter<E> {
+    pub fn aux_trace_polys(&self) -> MultiColumnIter<'_, E> {
         MultiColumnIter::new(self.aux_segment_polys.as_slice())
     }
 
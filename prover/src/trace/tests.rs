@@ -3,9 +3,19 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use crate::{tests::build_fib_trace, Trace};
-use math::fields::f128::BaseElement;
-use utils::collections::Vec;
+use crate::{
+    tests::{build_fib_trace, MockAir},
+    CompressedColumn, Trace, TraceColumn, TraceIssue, TraceTable, TraceTableWriter,
+};
+use air::{Air, Assertion, FieldExtension, ProofOptions, TraceInfo};
+use math::{
+    fields::{f128::BaseElement, f64},
+    FieldElement,
+};
+use utils::{collections::Vec, Deserializable, Serializable};
+
+#[cfg(feature = "concurrent")]
+use utils::iterators::*;
 
 #[test]
 fn new_trace_table() {
@@ -27,3 +37,414 @@ fn new_trace_table() {
         .collect();
     assert_eq!(expected, trace.get_column(1));
 }
+
+#[test]
+fn stack_trace_tables() {
+    let trace = TraceTable::stack(vec![build_fib_trace(16), build_fib_trace(16)]);
+
+    assert_eq!(4, trace.main_trace_width());
+    assert_eq!(8, trace.length());
+    assert_eq!(trace.get_column(0), trace.get_column(2));
+    assert_eq!(trace.get_column(1), trace.get_column(3));
+}
+
+#[test]
+fn fill_trace_table_par() {
+    let init = |step: usize, state: &mut [BaseElement]| {
+        state[0] = BaseElement::from(step as u64);
+        state[1] = state[0].square();
+    };
+    let update = |_: usize, state: &mut [BaseElement]| {
+        state[0] += BaseElement::ONE;
+        state[1] = state[0].square();
+    };
+
+    let mut expected = TraceTable::new(2, 64);
+    expected.fill(|state| init(0, state), update);
+
+    let mut trace = TraceTable::new(2, 64);
+    trace.fill_par(init, update);
+    assert_eq!(expected.get_column(0), trace.get_column(0));
+    assert_eq!(expected.get_column(1), trace.get_column(1));
+}
+
+#[test]
+fn write_trace_table_in_chunks() {
+    let expected = build_fib_trace(16);
+
+    // append the rows of the trace in chunks of different sizes
+    let mut writer = TraceTableWriter::new(2, 8);
+    assert_eq!(None, writer.last_row());
+    let rows = expected.rows().flat_map(|row| row.to_vec()).collect::<Vec<_>>();
+    writer.append_row(&rows[..2]);
+    writer.append_rows(&rows[2..8]);
+    assert_eq!(4, writer.num_rows());
+    assert!(!writer.is_complete());
+    assert_eq!(Some(rows[6..8].to_vec()), writer.last_row());
+    writer.append_rows(&rows[8..]);
+    assert!(writer.is_complete());
+
+    let trace = writer.finish();
+    assert_eq!(expected.get_column(0), trace.get_column(0));
+    assert_eq!(expected.get_column(1), trace.get_column(1));
+}
+
+#[test]
+#[should_panic(expected = "cannot append 2 rows to a trace with 1 remaining rows")]
+fn write_trace_table_overflow() {
+    let mut writer = TraceTableWriter::<BaseElement>::new(2, 8);
+    writer.append_rows(&[BaseElement::ONE; 14]);
+    writer.append_rows(&[BaseElement::ONE; 4]);
+}
+
+#[test]
+#[should_panic(expected = "after only 4 rows have been appended")]
+fn write_trace_table_incomplete() {
+    let mut writer = TraceTableWriter::<BaseElement>::new(2, 8);
+    writer.append_rows(&[BaseElement::ONE; 8]);
+    writer.finish();
+}
+
+#[test]
+fn trace_table_column_views() {
+    let mut trace = build_fib_trace(16);
+    trace.get_column_mut(0).fill(BaseElement::ONE);
+    for column in trace.columns_mut() {
+        column[7] = BaseElement::ZERO;
+    }
+    trace.fragments(4).for_each(|mut fragment| {
+        fragment.get_column_mut(1)[0] = BaseElement::from(fragment.index() as u64);
+    });
+
+    assert_eq!(&[BaseElement::ONE; 7], &trace.get_column(0)[..7]);
+    assert_eq!(BaseElement::ZERO, trace.get(0, 7));
+    assert_eq!(BaseElement::ZERO, trace.get(1, 0));
+    assert_eq!(BaseElement::ONE, trace.get(1, 4));
+    assert_eq!(BaseElement::ZERO, trace.get(1, 7));
+}
+
+#[test]
+fn trace_table_read_iterators() {
+    let trace = build_fib_trace(32);
+
+    let columns = trace.columns().collect::<Vec<_>>();
+    assert_eq!(vec![trace.get_column(0), trace.get_column(1)], columns);
+    assert_eq!(&trace.get_column(1)[4..12], trace.get_column_slice(1, 4..12));
+
+    let mut rows = trace.rows();
+    assert_eq!(16, rows.len());
+    let first = rows.next().unwrap();
+    assert_eq!(15, rows.len());
+    assert_eq!(0, first.index());
+    assert_eq!(vec![BaseElement::ONE, BaseElement::ONE], first.to_vec());
+    let last = rows.next_back().unwrap();
+    assert_eq!(15, last.index());
+    assert_eq!(trace.get(1, 15), last.get(1));
+
+    let mut state = vec![BaseElement::ZERO; 2];
+    for row in trace.rows() {
+        trace.read_row_into(row.index(), &mut state);
+        assert_eq!(state, row.iter().collect::<Vec<_>>());
+    }
+
+    let fragments = trace.fragment_views(4).collect::<Vec<_>>();
+    assert_eq!(4, fragments.len());
+    for fragment in fragments {
+        assert_eq!(4, fragment.length());
+        assert_eq!(fragment.index() * 4, fragment.offset());
+        for row_idx in 0..fragment.length() {
+            fragment.read_row_into(row_idx, &mut state);
+            assert_eq!(trace.rows().nth(fragment.offset() + row_idx).unwrap().to_vec(), state);
+            assert_eq!(trace.get(0, fragment.offset() + row_idx), fragment.get(0, row_idx));
+        }
+    }
+}
+
+#[cfg(feature = "concurrent")]
+#[test]
+fn trace_table_par_iterators() {
+    let trace = build_fib_trace(32);
+
+    let columns = trace.par_columns().collect::<Vec<_>>();
+    assert_eq!(trace.columns().collect::<Vec<_>>(), columns);
+
+    let rows = trace.par_rows().map(|row| row.to_vec()).collect::<Vec<_>>();
+    assert_eq!(trace.rows().map(|row| row.to_vec()).collect::<Vec<_>>(), rows);
+}
+
+#[test]
+fn trace_table_serialization() {
+    let mut trace = build_fib_trace(32);
+    trace.set_meta(vec![1, 2, 3]);
+
+    let bytes = trace.to_bytes();
+    let expected_len = 1 + 1 + 16 + 1 + 1 + 2 + 3 + 2 * 16 * BaseElement::ELEMENT_BYTES;
+    assert_eq!(expected_len, bytes.len());
+
+    let parsed = TraceTable::<BaseElement>::read_from_bytes(&bytes).unwrap();
+    assert_eq!(trace.main_trace_width(), parsed.main_trace_width());
+    assert_eq!(trace.length(), parsed.length());
+    assert_eq!(trace.meta(), parsed.meta());
+    assert_eq!(trace.columns().collect::<Vec<_>>(), parsed.columns().collect::<Vec<_>>());
+
+    // truncated traces and traces in a different field are rejected
+    assert!(TraceTable::<BaseElement>::read_from_bytes(&bytes[..bytes.len() - 1]).is_err());
+    assert!(TraceTable::<f64::BaseElement>::read_from_bytes(&bytes).is_err());
+
+    // unsupported versions and trace lengths are rejected
+    let mut corrupted = bytes.clone();
+    corrupted[0] = 2;
+    assert!(TraceTable::<BaseElement>::read_from_bytes(&corrupted).is_err());
+    let mut corrupted = bytes.clone();
+    corrupted[19] = 2;
+    assert!(TraceTable::<BaseElement>::read_from_bytes(&corrupted).is_err());
+    corrupted[19] = 64;
+    assert!(TraceTable::<BaseElement>::read_from_bytes(&corrupted).is_err());
+}
+
+#[test]
+fn compressed_column_encodings() {
+    let one = BaseElement::ONE;
+    let two = BaseElement::new(2);
+    let mut values = vec![BaseElement::ZERO; 64];
+    values[10..20].fill(one);
+    values[40..64].fill(two);
+
+    let run_length = CompressedColumn::run_length(&values);
+    assert_eq!(values, run_length.expand());
+    assert_eq!(
+        run_length,
+        CompressedColumn::from_runs(&[
+            (BaseElement::ZERO, 10),
+            (one, 10),
+            (BaseElement::ZERO, 20),
+            (two, 24)
+        ])
+    );
+    assert_eq!(
+        run_length,
+        CompressedColumn::from_runs(&[
+            (BaseElement::ZERO, 10),
+            (one, 4),
+            (one, 6),
+            (BaseElement::ZERO, 20),
+            (two, 24)
+        ])
+    );
+
+    let sparse = CompressedColumn::sparse(&values, two);
+    assert_eq!(values, sparse.expand());
+    let entries: Vec<(usize, BaseElement)> = (0..40).map(|row| (row, values[row])).collect();
+    assert_eq!(sparse, CompressedColumn::from_sparse(64, two, &entries));
+
+    for (row, &value) in values.iter().enumerate() {
+        assert_eq!(value, run_length.get(row));
+        assert_eq!(value, sparse.get(row));
+    }
+
+    // a piecewise-constant column is compressed via run-length encoding
+    let compressed = CompressedColumn::compress(&values).unwrap();
+    assert_eq!(run_length, compressed);
+    assert!(compressed.size_in_bytes() < core::mem::size_of_val(values.as_slice()));
+
+    // a mostly zero column with few repeated values is compressed via sparse encoding
+    let mut values = vec![BaseElement::ZERO; 64];
+    values[3] = one;
+    values[50] = two;
+    let compressed = CompressedColumn::compress(&values).unwrap();
+    assert_eq!(CompressedColumn::sparse(&values, BaseElement::ZERO), compressed);
+    assert_eq!(values, compressed.expand());
+
+    // a column without structure is not compressed
+    let values: Vec<BaseElement> = (0..64u32).map(BaseElement::from).collect();
+    assert!(CompressedColumn::compress(&values).is_none());
+}
+
+#[test]
+fn trace_table_compression() {
+    let mut trace = build_compressible_trace(64);
+    let expected = trace.clone();
+    let dense_size = trace.size_in_bytes();
+
+    // the constant and the sparse columns are compressed; the fibonacci column is not
+    assert_eq!(0, trace.num_compressed_columns());
+    assert_eq!(2, trace.compress());
+    assert_eq!(2, trace.num_compressed_columns());
+    assert!(trace.size_in_bytes() < dense_size);
+
+    // values can be read without building the dense form of the trace
+    let compressed_size = trace.size_in_bytes();
+    let mut row = vec![BaseElement::ZERO; 3];
+    for step in 0..trace.length() {
+        for column in 0..trace.main_trace_width() {
+            assert_eq!(expected.get(column, step), trace.get(column, step));
+        }
+        trace.read_row_into(step, &mut row);
+        assert_eq!(expected.get(2, step), row[2]);
+    }
+    assert_eq!(expected.to_bytes(), trace.to_bytes());
+    assert_eq!(compressed_size, trace.size_in_bytes());
+
+    // the dense form of the trace is built on first access to the main segment
+    assert_eq!(
+        expected.main_segment().columns().collect::<Vec<_>>(),
+        trace.main_segment().columns().collect::<Vec<_>>()
+    );
+    assert_eq!(compressed_size + dense_size, trace.size_in_bytes());
+    assert_eq!(expected.to_bytes(), trace.to_bytes());
+
+    // mutating the trace decompresses it
+    trace.set(2, 5, BaseElement::new(7));
+    assert_eq!(0, trace.num_compressed_columns());
+    assert_eq!(dense_size, trace.size_in_bytes());
+    assert_eq!(BaseElement::new(7), trace.get(2, 5));
+}
+
+#[test]
+fn trace_table_init_compressed_columns() {
+    let expected = build_compressible_trace(64);
+    let columns = vec![
+        TraceColumn::from(expected.get_column(0).to_vec()),
+        TraceColumn::from(CompressedColumn::from_runs(&[(BaseElement::new(3), 64)])),
+        TraceColumn::from(CompressedColumn::from_sparse(
+            64,
+            BaseElement::ZERO,
+            &[(1, BaseElement::ONE), (33, BaseElement::ONE)],
+        )),
+    ];
+
+    let trace = TraceTable::init_columns(columns);
+    assert_eq!(3, trace.main_trace_width());
+    assert_eq!(64, trace.length());
+    assert_eq!(2, trace.num_compressed_columns());
+    assert_eq!(expected.to_bytes(), trace.to_bytes());
+    assert_eq!(
+        expected.main_segment().columns().collect::<Vec<_>>(),
+        trace.main_segment().columns().collect::<Vec<_>>()
+    );
+
+    let mut trace = trace;
+    trace.decompress();
+    assert_eq!(0, trace.num_compressed_columns());
+    assert_eq!(expected.get_column(2), trace.get_column(2));
+}
+
+#[test]
+#[should_panic(expected = "all columns traces must have the same length")]
+fn trace_table_init_compressed_columns_length_mismatch() {
+    let columns = vec![
+        TraceColumn::from(vec![BaseElement::ONE; 16]),
+        TraceColumn::from(CompressedColumn::from_runs(&[(BaseElement::ONE, 8)])),
+    ];
+    TraceTable::init_columns(columns);
+}
+
+#[cfg(feature = "parquet")]
+#[test]
+fn trace_table_parquet_export() {
+    use arrow_array::{Array, FixedSizeBinaryArray, UInt64Array};
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+    // fields with elements of at most 8 bytes are exported as integers
+    let trace = TraceTable::init(vec![
+        (0..16u64).map(f64::BaseElement::new).collect(),
+        (0..16u64).map(|i| f64::BaseElement::new(i * i)).collect(),
+    ]);
+    let batch = trace.to_record_batch().unwrap();
+    assert_eq!(2, batch.num_columns());
+    assert_eq!(16, batch.num_rows());
+    let column = batch.column(1).as_any().downcast_ref::<UInt64Array>().unwrap();
+    assert_eq!(225, column.value(15));
+
+    // other fields are exported as little-endian bytes
+    let mut trace = build_fib_trace(32);
+    trace.set_meta(vec![0xab, 0xcd]);
+    let batch = trace.to_record_batch().unwrap();
+    let column = batch.column(0).as_any().downcast_ref::<FixedSizeBinaryArray>().unwrap();
+    assert_eq!(trace.get(0, 3).to_bytes(), column.value(3));
+    assert_eq!("abcd", batch.schema().metadata()[crate::TRACE_META_KEY]);
+
+    // the trace survives a round-trip through a parquet file
+    let mut file = Vec::new();
+    trace.write_parquet(&mut file).unwrap();
+    let reader = ParquetRecordBatchReaderBuilder::try_new(bytes::Bytes::from(file))
+        .unwrap()
+        .build()
+        .unwrap();
+    let batches = reader.collect::<Result<Vec<_>, _>>().unwrap();
+    assert_eq!(1, batches.len());
+    assert_eq!(batch.columns(), batches[0].columns());
+}
+
+#[test]
+fn validate_trace_against_air() {
+    // the first column is constant, while the other columns interpolate to degree 7 polynomials
+    let trace = build_validation_trace();
+    let assertion = Assertion::single(0, 0, BaseElement::from(5u32));
+    let air = MockAir::with_assertions(vec![assertion], 8);
+    let diagnostics = trace.validate_against(&air, Some(7));
+    assert!(diagnostics.is_valid(), "{diagnostics}");
+
+    let diagnostics = trace.validate_against(&air, Some(3));
+    assert_eq!(
+        &[
+            TraceIssue::ColumnDegreeTooHigh(1, 3, 7),
+            TraceIssue::ColumnDegreeTooHigh(2, 3, 7),
+            TraceIssue::ColumnDegreeTooHigh(3, 3, 7)
+        ],
+        diagnostics.issues()
+    );
+
+    // all shape issues are reported, and columns are not interpolated for a malformed trace
+    let air = MockAir::new(
+        TraceInfo::new(2, 16),
+        (),
+        ProofOptions::new(32, 8, 0, FieldExtension::None, 4, 31),
+    );
+    let diagnostics = trace.validate_against(&air, Some(3));
+    assert_eq!(
+        &[
+            TraceIssue::InconsistentTraceWidth(2, 4),
+            TraceIssue::InconsistentTraceLength(16, 8),
+            TraceIssue::InconsistentNumAssertions(1, 0)
+        ],
+        diagnostics.issues()
+    );
+}
+
+#[test]
+fn validate_trace_assertions() {
+    let trace = build_validation_trace();
+
+    let air = MockAir::with_assertions(vec![Assertion::single(1, 3, BaseElement::from(11u32))], 8);
+    assert!(trace.validate_against(&air, None).is_valid());
+
+    let assertion = Assertion::periodic(2, 1, 4, BaseElement::from(17u32));
+    let air = MockAir::with_assertions(vec![assertion], 8);
+    assert_eq!(
+        &[TraceIssue::UnsatisfiedAssertion(2, 5)],
+        trace.validate_against(&air, None).issues()
+    );
+
+    let air = MockAir::with_assertions(vec![Assertion::single(5, 0, BaseElement::ONE)], 8);
+    let diagnostics = trace.validate_against(&air, None);
+    assert!(matches!(diagnostics.issues(), [TraceIssue::InvalidAssertion(_)]));
+}
+
+/// Builds a trace with a constant first column and columns holding consecutive integers.
+fn build_validation_trace() -> TraceTable<BaseElement> {
+    let mut columns = vec![vec![BaseElement::from(5u32); 8]];
+    for i in 1..4u32 {
+        columns.push((0..8u32).map(|j| BaseElement::from(i * 8 + j)).collect());
+    }
+    TraceTable::init(columns)
+}
+
+/// Builds a trace with a fibonacci column, a constant column, and a column which is mostly zero.
+fn build_compressible_trace(length: usize) -> TraceTable<BaseElement> {
+    let fib = build_fib_trace(length * 2);
+    let mut sparse = vec![BaseElement::ZERO; length];
+    sparse[1] = BaseElement::ONE;
+    sparse[length / 2 + 1] = BaseElement::ONE;
+    TraceTable::init(vec![fib.get_column(0).to_vec(), vec![BaseElement::new(3); length], sparse])
+}
@@ -4,16 +4,21 @@
 // LICENSE file in the root directory of this source tree.
 
 use super::{
-    ColMatrix, ElementHasher, EvaluationFrame, FieldElement, Hasher, Queries, StarkDomain,
-    TraceInfo, TraceLayout, TraceLde, TracePolyTable, Vec,
+    ColMatrix, ElementHasher, EvaluationFrame, FieldElement, Hasher, PreprocessedTrace, Queries,
+    StarkDomain, TraceInfo, TraceLayout, TraceLde, TracePolyTable, Vec,
+};
+use crate::{
+    info_span, BufferAllocator, BufferKind, DefaultAllocator, RowMatrix, DEFAULT_SEGMENT_WIDTH,
 };
-use crate::{RowMatrix, DEFAULT_SEGMENT_WIDTH};
 use crypto::MerkleTree;
 
+#[cfg(not(feature = "std"))]
+use alloc::sync::Arc;
+
 #[cfg(feature = "std")]
 use log::debug;
 #[cfg(feature = "std")]
-use std::time::Instant;
+use std::{sync::Arc, time::Instant};
 
 #[cfg(test)]
 mod tests;
@@ -28,17 +33,38 @@ mod tests;
 ///   will always be elements in the base field (even when an extension field is used).
 /// - Auxiliary segments: a list of 0 or more segments for traces generated after the prover
 ///   commits to the first trace segment. Currently, at most 1 auxiliary segment is possible.
-pub struct DefaultTraceLde<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> {
+///
+/// Extended segments are stored in row-major order. Hashing rows into Merkle tree leaves, reading
+/// evaluation frames during constraint evaluation, and opening the trace at query positions all
+/// read full rows across all columns, and thus, keeping rows in contiguous memory avoids a cache
+/// miss per column for wide traces. Since the row-major layout is not slower than the
+/// column-major layout even for narrow traces (see `matrix_read_frames` and
+/// `matrix_commit_and_query` benchmarks), the layout is not configurable.
+///
+/// Memory for the extended segments is obtained from the allocator specified via `A` type
+/// parameter, and is handed back to it when the trace LDE is dropped.
+///
+/// When the computation has preprocessed columns, the extended preprocessed columns are taken from
+/// a shared [PreprocessedTrace] (see [DefaultTraceLde::with_preprocessed()]), and only the
+/// remaining columns of the main trace segment are extended and committed to.
+pub struct DefaultTraceLde<
+    E: FieldElement,
+    H: ElementHasher<BaseField = E::BaseField>,
+    A: BufferAllocator<E::BaseField> = DefaultAllocator,
+> {
     // low-degree extension of the main segment of the trace
     main_segment_lde: RowMatrix<E::BaseField>,
     // commitment to the main segment of the trace
     main_segment_tree: MerkleTree<H>,
+    // extended preprocessed columns which follow the columns of the main segment (if any)
+    preprocessed: Option<Arc<PreprocessedTrace<E::BaseField, H>>>,
     // low-degree extensions of the auxiliary segments of the trace
     aux_segment_ldes: Vec<RowMatrix<E>>,
     // commitment to the auxiliary segments of the trace
     aux_segment_trees: Vec<MerkleTree<H>>,
     blowup: usize,
     trace_info: TraceInfo,
+    allocator: A,
 }
 
 impl<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> DefaultTraceLde<E, H> {
@@ -53,19 +79,152 @@ impl<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> DefaultTraceLd
         trace_info: &TraceInfo,
         main_trace: &ColMatrix<E::BaseField>,
         domain: &StarkDomain<E::BaseField>,
+    ) -> (Self, TracePolyTable<E>) {
+        Self::with_allocator(trace_info, main_trace, domain, DefaultAllocator)
+    }
+
+    /// Takes polynomials in coefficient form for all columns of the main trace segment as input,
+    /// evaluates the polynomials over the LDE domain, commits to the polynomial evaluations, and
+    /// creates a new [DefaultTraceLde] with the LDE of the main trace segment and the commitment.
+    ///
+    /// This is the same as [DefaultTraceLde::new()] applied to the evaluations of the polynomials
+    /// over the trace domain, but the interpolation step is skipped.
+    ///
+    /// Returns a tuple containing a [TracePolyTable] with the provided polynomials and the new
+    /// [DefaultTraceLde].
+    pub fn from_polys(
+        trace_info: &TraceInfo,
+        main_trace_polys: ColMatrix<E::BaseField>,
+        domain: &StarkDomain<E::BaseField>,
+    ) -> (Self, TracePolyTable<E>) {
+        Self::from_polys_with_allocator(trace_info, main_trace_polys, domain, DefaultAllocator)
+    }
+
+    /// Same as [DefaultTraceLde::new()], but the last columns of the main trace segment are
+    /// taken from the specified `preprocessed` trace rather than extended and committed to.
+    ///
+    /// Only the columns of the main trace segment which precede the preprocessed columns are
+    /// interpolated, extended, and committed to via the main trace commitment; polynomials and
+    /// extensions of the preprocessed columns are shared with the `preprocessed` trace. The
+    /// returned [TracePolyTable] contains polynomials for all columns of the main trace segment.
+    ///
+    /// # Panics
+    /// Panics if:
+    /// * The preprocessed trace has as many or more columns than the main trace segment.
+    /// * The preprocessed trace was built for a different trace length or LDE domain.
+    /// * The last columns of the main trace segment are not the same as the preprocessed columns.
+    pub fn with_preprocessed(
+        trace_info: &TraceInfo,
+        main_trace: &ColMatrix<E::BaseField>,
+        domain: &StarkDomain<E::BaseField>,
+        preprocessed: Arc<PreprocessedTrace<E::BaseField, H>>,
+    ) -> (Self, TracePolyTable<E>) {
+        let num_preprocessed_cols = preprocessed.num_columns();
+        assert!(
+            num_preprocessed_cols < main_trace.num_cols(),
+            "number of preprocessed columns must be smaller than main trace width {}, but was {}",
+            main_trace.num_cols(),
+            num_preprocessed_cols
+        );
+        assert_eq!(
+            main_trace.num_rows(),
+            preprocessed.trace_length(),
+            "preprocessed trace must have the same length as the main trace segment"
+        );
+        assert!(
+            preprocessed.is_extended_over(domain),
+            "preprocessed trace was not extended over the specified domain"
+        );
+        let num_committed_cols = main_trace.num_cols() - num_preprocessed_cols;
+        for (i, column) in preprocessed.columns().columns().enumerate() {
+            assert!(
+                column == main_trace.get_column(num_committed_cols + i),
+                "column {} of the main trace segment does not match preprocessed column {i}",
+                num_committed_cols + i
+            );
+        }
+
+        // extend and commit to the columns which are not preprocessed
+        let committed_trace = ColMatrix::new(
+            main_trace
+                .columns()
+                .take(num_committed_cols)
+                .map(|column| column.to_vec())
+                .collect(),
+        );
+        let committed_polys = interpolate_trace(&committed_trace);
+        let (main_segment_lde, main_segment_tree) = build_trace_commitment::<E, E::BaseField, H, _>(
+            &committed_polys,
+            domain,
+            &DefaultAllocator,
+        );
+
+        // the polynomial table holds polynomials for all columns of the main segment
+        let mut main_trace_polys = committed_polys;
+        for poly in preprocessed.polys().columns() {
+            main_trace_polys.merge_column(poly.to_vec());
+        }
+
+        let trace_poly_table = TracePolyTable::new(main_trace_polys);
+        let trace_lde = DefaultTraceLde {
+            main_segment_lde,
+            main_segment_tree,
+            preprocessed: Some(preprocessed),
+            aux_segment_ldes: Vec::new(),
+            aux_segment_trees: Vec::new(),
+            blowup: domain.trace_to_lde_blowup(),
+            trace_info: trace_info.clone(),
+            allocator: DefaultAllocator,
+        };
+
+        (trace_lde, trace_poly_table)
+    }
+}
+
+impl<E, H, A> DefaultTraceLde<E, H, A>
+where
+    E: FieldElement,
+    H: ElementHasher<BaseField = E::BaseField>,
+    A: BufferAllocator<E::BaseField>,
+{
+    /// Same as [DefaultTraceLde::new()], but obtains memory for the extended trace segments from
+    /// the specified `allocator`.
+    ///
+    /// Buffers for all segments (including auxiliary segments added later) are requested as
+    /// [BufferKind::TraceLde] buffers, and are released back to the allocator when the returned
+    /// trace LDE is dropped.
+    pub fn with_allocator(
+        trace_info: &TraceInfo,
+        main_trace: &ColMatrix<E::BaseField>,
+        domain: &StarkDomain<E::BaseField>,
+        allocator: A,
+    ) -> (Self, TracePolyTable<E>) {
+        let main_segment_polys = interpolate_trace(main_trace);
+        Self::from_polys_with_allocator(trace_info, main_segment_polys, domain, allocator)
+    }
+
+    /// Same as [DefaultTraceLde::from_polys()], but obtains memory for the extended trace
+    /// segments from the specified `allocator`.
+    pub fn from_polys_with_allocator(
+        trace_info: &TraceInfo,
+        main_trace_polys: ColMatrix<E::BaseField>,
+        domain: &StarkDomain<E::BaseField>,
+        allocator: A,
     ) -> (Self, TracePolyTable<E>) {
         // extend the main execution trace and build a Merkle tree from the extended trace
-        let (main_segment_lde, main_segment_tree, main_segment_polys) =
-            build_trace_commitment::<E, E::BaseField, H>(main_trace, domain);
+        let (main_segment_lde, main_segment_tree) =
+            build_trace_commitment::<E, E::BaseField, H, A>(&main_trace_polys, domain, &allocator);
 
-        let trace_poly_table = TracePolyTable::new(main_segment_polys);
+        let trace_poly_table = TracePolyTable::new(main_trace_polys);
         let trace_lde = DefaultTraceLde {
             main_segment_lde,
             main_segment_tree,
+            preprocessed: None,
             aux_segment_ldes: Vec::new(),
             aux_segment_trees: Vec::new(),
             blowup: domain.trace_to_lde_blowup(),
             trace_info: trace_info.clone(),
+            allocator,
         };
 
         (trace_lde, trace_poly_table)
@@ -95,10 +254,11 @@ impl<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> DefaultTraceLd
     }
 }
 
-impl<E, H> TraceLde<E> for DefaultTraceLde<E, H>
+impl<E, H, A> TraceLde<E> for DefaultTraceLde<E, H, A>
 where
     E: FieldElement,
     H: ElementHasher<BaseField = E::BaseField>,
+    A: BufferAllocator<E::BaseField>,
 {
     type HashFn = H;
 
@@ -126,8 +286,9 @@ where
         domain: &StarkDomain<E::BaseField>,
     ) -> (ColMatrix<E>, <Self::HashFn as Hasher>::Digest) {
         // extend the auxiliary trace segment and build a Merkle tree from the extended trace
-        let (aux_segment_lde, aux_segment_tree, aux_segment_polys) =
-            build_trace_commitment::<E, E, H>(aux_trace, domain);
+        let aux_segment_polys = interpolate_trace(aux_trace);
+        let (aux_segment_lde, aux_segment_tree) =
+            build_trace_commitment::<E, E, H, A>(&aux_segment_polys, domain, &self.allocator);
 
         // check errors
         assert!(
@@ -154,12 +315,20 @@ where
         lde_step: usize,
         frame: &mut EvaluationFrame<E::BaseField>,
     ) {
-        // at the end of the trace, next state wraps around and we read the first step again
-        let next_lde_step = (lde_step + self.blowup()) % self.trace_len();
-
-        // copy main trace segment values into the frame
-        frame.current_mut().copy_from_slice(self.main_segment_lde.row(lde_step));
-        frame.next_mut().copy_from_slice(self.main_segment_lde.row(next_lde_step));
+        // copy main trace segment values into the frame; preprocessed columns (if any) follow
+        // the committed columns
+        let num_committed_cols = self.main_segment_lde.num_cols();
+        for row in 0..frame.num_rows() {
+            // at the end of the trace, offset rows wrap around to the first steps again
+            let row_lde_step = (lde_step + frame.offsets()[row] * self.blowup()) % self.trace_len();
+            let frame_row = frame.row_mut(row);
+            frame_row[..num_committed_cols]
+                .copy_from_slice(self.main_segment_lde.row(row_lde_step));
+            if let Some(preprocessed) = &self.preprocessed {
+                frame_row[num_committed_cols..]
+                    .copy_from_slice(preprocessed.lde().row(row_lde_step));
+            }
+        }
     }
 
     /// Reads current and next rows from the auxiliary trace segment into the specified frame.
@@ -168,13 +337,13 @@ where
     /// This currently assumes that there is exactly one auxiliary trace segment, and will panic
     /// otherwise.
     fn read_aux_trace_frame_into(&self, lde_step: usize, frame: &mut EvaluationFrame<E>) {
-        // at the end of the trace, next state wraps around and we read the first step again
-        let next_lde_step = (lde_step + self.blowup()) % self.trace_len();
-
         // copy auxiliary trace segment values into the frame
         let segment = &self.aux_segment_ldes[0];
-        frame.current_mut().copy_from_slice(segment.row(lde_step));
-        frame.next_mut().copy_from_slice(segment.row(next_lde_step));
+        for row in 0..frame.num_rows() {
+            // at the end of the trace, offset rows wrap around to the first steps again
+            let row_lde_step = (lde_step + frame.offsets()[row] * self.blowup()) % self.trace_len();
+            frame.row_mut(row).copy_from_slice(segment.row(row_lde_step));
+        }
     }
 
     /// Returns trace table rows at the specified positions along with Merkle authentication paths
@@ -210,34 +379,87 @@ where
     fn trace_layout(&self) -> &TraceLayout {
         self.trace_info.layout()
     }
+
+    /// Returns the preprocessed trace which holds the last columns of the main trace segment, or
+    /// `None` if this trace LDE was built without a preprocessed trace.
+    fn preprocessed_trace(&self) -> Option<&PreprocessedTrace<E::BaseField, Self::HashFn>> {
+        self.preprocessed.as_deref()
+    }
+}
+
+impl<E, H, A> Drop for DefaultTraceLde<E, H, A>
+where
+    E: FieldElement,
+    H: ElementHasher<BaseField = E::BaseField>,
+    A: BufferAllocator<E::BaseField>,
+{
+    /// Hands memory of all extended trace segments back to the allocator.
+    fn drop(&mut self) {
+        self.allocator.release(BufferKind::TraceLde, self.main_segment_lde.take_data());
+        for segment_lde in self.aux_segment_ldes.drain(..) {
+            self.allocator.release(BufferKind::TraceLde, segment_lde.into_data());
+        }
+    }
 }
 
 // HELPER FUNCTIONS
 // ================================================================================================
 
-/// Computes a low-degree extension (LDE) of the provided execution trace over the specified
-/// domain and builds a commitment to the extended trace.
+/// Interpolates all columns of the provided execution trace segment into polynomials in
+/// coefficient form.
+pub(super) fn interpolate_trace<F: FieldElement>(trace: &ColMatrix<F>) -> ColMatrix<F> {
+    #[cfg(feature = "std")]
+    let now = Instant::now();
+    let trace_polys = info_span!("interpolate_execution_trace", num_cols = trace.num_cols())
+        .in_scope(|| trace.interpolate_columns());
+    #[cfg(feature = "std")]
+    debug!(
+        "Interpolated execution trace of {} columns and 2^{} steps in {} ms",
+        trace_polys.num_cols(),
+        trace_polys.num_rows().ilog2(),
+        now.elapsed().as_millis()
+    );
+    trace_polys
+}
+
+/// Computes a low-degree extension (LDE) of an execution trace segment defined by the provided
+/// polynomials over the specified domain and builds a commitment to the extended trace.
+///
+/// The extension is performed by evaluating each polynomial (of degree < trace_length) over the
+/// LDE domain.
 ///
-/// The extension is performed by interpolating each column of the execution trace into a
-/// polynomial of degree = trace_length - 1, and then evaluating the polynomial over the LDE
-/// domain.
+/// The trace commitment is computed by hashing each row of the extended execution trace encoded
+/// as specified by the leaf encoding of the domain, then building a Merkle tree from the
+/// resulting hashes.
 ///
-/// The trace commitment is computed by hashing each row of the extended execution trace, then
-/// building a Merkle tree from the resulting hashes.
-fn build_trace_commitment<E, F, H>(
-    trace: &ColMatrix<F>,
+/// Memory for the extended trace is obtained from the provided `allocator`.
+pub(super) fn build_trace_commitment<E, F, H, A>(
+    trace_polys: &ColMatrix<F>,
     domain: &StarkDomain<E::BaseField>,
-) -> (RowMatrix<F>, MerkleTree<H>, ColMatrix<F>)
+    allocator: &A,
+) -> (RowMatrix<F>, MerkleTree<H>)
 where
     E: FieldElement,
     F: FieldElement<BaseField = E::BaseField>,
     H: ElementHasher<BaseField = E::BaseField>,
+    A: BufferAllocator<E::BaseField>,
 {
     // extend the execution trace
     #[cfg(feature = "std")]
     let now = Instant::now();
-    let trace_polys = trace.interpolate_columns();
-    let trace_lde = RowMatrix::evaluate_polys_over::<DEFAULT_SEGMENT_WIDTH>(&trace_polys, domain);
+    let trace_lde = info_span!(
+        "extend_execution_trace",
+        num_cols = trace_polys.num_cols(),
+        blowup = domain.trace_to_lde_blowup()
+    )
+    .in_scope(|| {
+        RowMatrix::evaluate_polys_over_with::<DEFAULT_SEGMENT_WIDTH, _>(
+            trace_polys,
+            domain,
+            allocator,
+            BufferKind::TraceLde,
+        )
+    });
     #[cfg(feature = "std")]
     debug!(
         "Extended execution trace of {} columns from 2^{} to 2^{} steps ({}x blowup) in {} ms",
@@ -251,7 +473,11 @@ where
     // build trace commitment
     #[cfg(feature = "std")]
     let now = Instant::now();
-    let trace_tree = trace_lde.commit_to_rows();
+    let trace_tree = info_span!(
+        "compute_execution_trace_commitment",
+        tree_depth = domain.lde_domain_size().ilog2()
+    )
+    .in_scope(|| trace_lde.commit_to_rows_with_encoding(domain.leaf_encoding()));
     #[cfg(feature = "std")]
     debug!(
         "Computed execution trace commitment (Merkle tree of depth {}) in {} ms",
@@ -259,10 +485,10 @@ where
         now.elapsed().as_millis()
     );
 
-    (trace_lde, trace_tree, trace_polys)
+    (trace_lde, trace_tree)
 }
 
-fn build_segment_queries<E, H>(
+pub(super) fn build_segment_queries<E, H>(
     segment_lde: &RowMatrix<E>,
     segment_tree: &MerkleTree<H>,
     positions: &[usize],
@@ -4,9 +4,12 @@
 // LICENSE file in the root directory of this source tree.
 
 use crate::{
+    matrix::ColMatrix,
     tests::{build_fib_trace, MockAir},
-    DefaultTraceLde, StarkDomain, Trace, TraceLde,
+    BufferAllocator, BufferKind, DefaultTraceLde, PreprocessedTrace, StarkDomain, Trace, TraceLde,
 };
+use air::EvaluationFrame;
+use core::sync::atomic::{AtomicUsize, Ordering};
 use crypto::{hashers::Blake3_256, ElementHasher, MerkleTree};
 use math::{
     fields::f128::BaseElement, get_power_series, get_power_series_with_offset, polynom,
@@ -14,6 +17,11 @@ use math::{
 };
 use utils::collections::Vec;
 
+#[cfg(not(feature = "std"))]
+use alloc::sync::Arc;
+#[cfg(feature = "std")]
+use std::sync::Arc;
+
 type Blake3 = Blake3_256<BaseElement>;
 
 #[test]
@@ -98,6 +106,111 @@ fn commit_trace_table() {
     assert_eq!(*expected_tree.root(), trace_lde.get_main_trace_commitment())
 }
 
+#[test]
+fn extend_trace_table_with_allocator() {
+    // build the trace and the domain
+    let trace_length = 8;
+    let air = MockAir::with_trace_length(trace_length);
+    let trace = build_fib_trace(trace_length * 2);
+    let domain = StarkDomain::new(&air);
+
+    // build the trace LDE using the default allocator and a counting allocator
+    let (expected_lde, _) = DefaultTraceLde::<BaseElement, Blake3>::new(
+        &trace.get_info(),
+        trace.main_segment(),
+        &domain,
+    );
+    let allocator = CountingAllocator::default();
+    let (trace_lde, _) = DefaultTraceLde::<BaseElement, Blake3, _>::with_allocator(
+        &trace.get_info(),
+        trace.main_segment(),
+        &domain,
+        &allocator,
+    );
+
+    // the main segment buffer was obtained from the allocator and the LDE is not affected
+    assert_eq!(1, allocator.allocated.load(Ordering::Relaxed));
+    assert_eq!(0, allocator.released.load(Ordering::Relaxed));
+    assert_eq!(expected_lde.get_main_segment().data(), trace_lde.get_main_segment().data());
+    assert_eq!(expected_lde.get_main_trace_commitment(), trace_lde.get_main_trace_commitment());
+
+    // dropping the trace LDE hands the buffer back to the allocator
+    drop(trace_lde);
+    assert_eq!(1, allocator.released.load(Ordering::Relaxed));
+}
+
+#[test]
+fn extend_trace_table_from_polys() {
+    // build the trace and the domain
+    let trace_length = 8;
+    let air = MockAir::with_trace_length(trace_length);
+    let trace = build_fib_trace(trace_length * 2);
+    let domain = StarkDomain::new(&air);
+
+    // build the trace LDE from the trace and from the interpolated trace polynomials
+    let (expected_lde, expected_polys) = DefaultTraceLde::<BaseElement, Blake3>::new(
+        &trace.get_info(),
+        trace.main_segment(),
+        &domain,
+    );
+    let (trace_lde, trace_polys) = DefaultTraceLde::<BaseElement, Blake3>::from_polys(
+        &trace.get_info(),
+        trace.main_segment().interpolate_columns(),
+        &domain,
+    );
+
+    // the resulting LDEs, commitments, and polynomials are the same
+    assert_eq!(expected_lde.get_main_segment().data(), trace_lde.get_main_segment().data());
+    assert_eq!(expected_lde.get_main_trace_commitment(), trace_lde.get_main_trace_commitment());
+    for i in 0..trace_polys.num_main_trace_polys() {
+        assert_eq!(expected_polys.get_main_trace_poly(i), trace_polys.get_main_trace_poly(i));
+    }
+}
+
+#[test]
+fn extend_trace_table_with_preprocessed() {
+    // build the trace and the domain
+    let trace_length = 8;
+    let air = MockAir::with_preprocessed_columns(1, trace_length);
+    let trace = build_fib_trace(trace_length * 2);
+    let domain = StarkDomain::new(&air);
+
+    // take the second column of the trace from a preprocessed trace
+    let columns = ColMatrix::new(vec![trace.main_segment().get_column(1).to_vec()]);
+    let preprocessed = Arc::new(PreprocessedTrace::<BaseElement, Blake3>::new(&air, columns));
+    let (trace_lde, trace_polys) = DefaultTraceLde::<BaseElement, Blake3>::with_preprocessed(
+        &trace.get_info(),
+        trace.main_segment(),
+        &domain,
+        preprocessed,
+    );
+    let (expected_lde, expected_polys) = DefaultTraceLde::<BaseElement, Blake3>::new(
+        &trace.get_info(),
+        trace.main_segment(),
+        &domain,
+    );
+
+    // only the first column is committed to via the main trace commitment, but polynomials are
+    // built for all columns
+    assert_eq!(1, trace_lde.main_segment_width());
+    assert!(trace_lde.preprocessed_trace().is_some());
+    assert_ne!(expected_lde.get_main_trace_commitment(), trace_lde.get_main_trace_commitment());
+    assert_eq!(2, trace_polys.num_main_trace_polys());
+    for i in 0..2 {
+        assert_eq!(expected_polys.get_main_trace_poly(i), trace_polys.get_main_trace_poly(i));
+    }
+
+    // frames read from the trace LDE include values of the preprocessed column
+    let mut frame = EvaluationFrame::new(2);
+    let mut expected_frame = EvaluationFrame::new(2);
+    for lde_step in 0..trace_lde.trace_len() {
+        trace_lde.read_main_trace_frame_into(lde_step, &mut frame);
+        expected_lde.read_main_trace_frame_into(lde_step, &mut expected_frame);
+        assert_eq!(expected_frame.current(), frame.current());
+        assert_eq!(expected_frame.next(), frame.next());
+    }
+}
+
 // HELPER FUNCTIONS
 // ================================================================================================
 
@@ -105,3 +218,23 @@ fn build_lde_domain<B: StarkField>(domain_size: usize) -> Vec<B> {
     let g = B::get_root_of_unity(domain_size.ilog2());
     get_power_series_with_offset(g, B::GENERATOR, domain_size)
 }
+
+#[derive(Default)]
+struct CountingAllocator {
+    allocated: AtomicUsize,
+    released: AtomicUsize,
+}
+
+impl BufferAllocator<BaseElement> for &CountingAllocator {
+    fn allocate(&self, kind: BufferKind, len: usize) -> Vec<BaseElement> {
+        assert_eq!(BufferKind::TraceLde, kind);
+        self.allocated.fetch_add(1, Ordering::Relaxed);
+        vec![BaseElement::ZERO; len]
+    }
+
+    fn release(&self, kind: BufferKind, buffer: Vec<BaseElement>) {
+        assert_eq!(BufferKind::TraceLde, kind);
+        assert!(!buffer.is_empty());
+        self.released.fetch_add(1, Ordering::Relaxed);
+    }
+}
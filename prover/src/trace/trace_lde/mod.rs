@@ -12,6 +12,9 @@ use utils::collections::Vec;
 mod default;
 pub use default::DefaultTraceLde;
 
+mod preprocessed;
+pub use preprocessed::PreprocessedTrace;
+
 // TRACE LOW DEGREE EXTENSION
 // ================================================================================================
 /// Contains all segments of the extended execution trace and their commitments.
@@ -21,6 +24,10 @@ pub use default::DefaultTraceLde;
 ///   will always be elements in the base field (even when an extension field is used).
 /// - Auxiliary segments: a list of 0 or more segments for traces generated after the prover
 ///   commits to the first trace segment. Currently, at most 1 auxiliary segment is possible.
+///
+/// Rows of all segments must be hashed into Merkle tree leaves using the leaf encoding of the
+/// domain over which the segments are extended (see [StarkDomain::leaf_encoding()]); otherwise,
+/// the verifier will not be able to authenticate queried rows against the commitments.
 pub trait TraceLde<E: FieldElement>: Sync {
     /// The hash function used for building the Merkle tree commitments to trace segment LDEs.
     type HashFn: ElementHasher<BaseField = E::BaseField>;
@@ -47,6 +54,9 @@ pub trait TraceLde<E: FieldElement>: Sync {
     ) -> (ColMatrix<E>, <Self::HashFn as Hasher>::Digest);
 
     /// Reads current and next rows from the main trace segment into the specified frame.
+    ///
+    /// When the frame contains additional rows, row `i` of the frame is read from the LDE step
+    /// `lde_step + frame.offsets()[i] * blowup`, wrapping around at the end of the LDE domain.
     fn read_main_trace_frame_into(
         &self,
         lde_step: usize,
@@ -54,6 +64,9 @@ pub trait TraceLde<E: FieldElement>: Sync {
     );
 
     /// Reads current and next rows from the auxiliary trace segment into the specified frame.
+    ///
+    /// When the frame contains additional rows, row `i` of the frame is read from the LDE step
+    /// `lde_step + frame.offsets()[i] * blowup`, wrapping around at the end of the LDE domain.
     fn read_aux_trace_frame_into(&self, lde_step: usize, frame: &mut EvaluationFrame<E>);
 
     /// Returns trace table rows at the specified positions along with Merkle authentication paths
@@ -68,4 +81,52 @@ pub trait TraceLde<E: FieldElement>: Sync {
 
     /// Returns the trace layout of the execution trace.
     fn trace_layout(&self) -> &TraceLayout;
+
+    /// Returns the preprocessed trace which holds the last columns of the main trace segment, or
+    /// `None` if all columns of the main trace segment are committed to via the main trace
+    /// commitment.
+    ///
+    /// When a preprocessed trace is returned, the main trace commitment and queries of the main
+    /// trace segment must cover only the columns which precede the preprocessed columns, while
+    /// [read_main_trace_frame_into()](TraceLde::read_main_trace_frame_into) must read all columns
+    /// of the main trace segment. The default implementation returns `None`.
+    fn preprocessed_trace(&self) -> Option<&PreprocessedTrace<E::BaseField, Self::HashFn>> {
+        None
+    }
+}
+
+// TRACE FRAMES
+// ================================================================================================
+/// Provides evaluation frames of an extended execution trace.
+///
+/// This is the part of [TraceLde] required to evaluate constraints over the trace. Any [TraceLde]
+/// provides frames via [LdeFrames]; the trait is also implemented for parts of a trace LDE which
+/// can be read, but cannot be committed to or queried on their own (e.g., a part of the trace
+/// held by a worker of a distributed prover).
+pub(crate) trait TraceFrames<E: FieldElement> {
+    /// Returns the trace layout of the execution trace.
+    fn layout(&self) -> &TraceLayout;
+
+    /// Reads current and next rows from the main trace segment into the specified frame.
+    fn read_main_frame_into(&self, lde_step: usize, frame: &mut EvaluationFrame<E::BaseField>);
+
+    /// Reads current and next rows from the auxiliary trace segment into the specified frame.
+    fn read_aux_frame_into(&self, lde_step: usize, frame: &mut EvaluationFrame<E>);
+}
+
+/// Exposes evaluation frames of a [TraceLde] via the [TraceFrames] interface.
+pub(crate) struct LdeFrames<'a, T>(pub &'a T);
+
+impl<'a, E: FieldElement, T: TraceLde<E>> TraceFrames<E> for LdeFrames<'a, T> {
+    fn layout(&self) -> &TraceLayout {
+        self.0.trace_layout()
+    }
+
+    fn read_main_frame_into(&self, lde_step: usize, frame: &mut EvaluationFrame<E::BaseField>) {
+        self.0.read_main_trace_frame_into(lde_step, frame)
+    }
+
+    fn read_aux_frame_into(&self, lde_step: usize, frame: &mut EvaluationFrame<E>) {
+        self.0.read_aux_trace_frame_into(lde_step, frame)
+    }
 }
@@ -0,0 +1,145 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{
+    default::{build_segment_queries, build_trace_commitment, interpolate_trace},
+    ColMatrix, ElementHasher, Queries, StarkDomain,
+};
+use crate::{DefaultAllocator, RowMatrix};
+use air::{Air, LeafEncoding};
+use crypto::MerkleTree;
+use math::StarkField;
+
+// PREPROCESSED TRACE
+// ================================================================================================
+
+/// Preprocessed columns of an execution trace together with their low-degree extension and the
+/// commitment to it.
+///
+/// Preprocessed columns hold values which are the same for all executions of a computation, such
+/// as instruction ROMs or lookup tables (see
+/// [AirContext::num_preprocessed_columns()](air::AirContext::num_preprocessed_columns)). Since
+/// extending and committing to large tables is expensive, a preprocessed trace is built once and
+/// can then be shared between all proofs of the computation (e.g., via
+/// [DefaultTraceLde::with_preprocessed()](crate::DefaultTraceLde::with_preprocessed)). The
+/// verifier checks queried values of the preprocessed columns against the
+/// [commitment()](PreprocessedTrace::commitment), which must be distributed to verifiers out of
+/// band.
+///
+/// A preprocessed trace is built from an instance of AIR, and can be used for any instance of AIR
+/// with the same trace length, proof options, and number of preprocessed columns (see
+/// [is_compatible_with()](PreprocessedTrace::is_compatible_with)).
+pub struct PreprocessedTrace<B: StarkField, H: ElementHasher<BaseField = B>> {
+    columns: ColMatrix<B>,
+    polys: ColMatrix<B>,
+    lde: RowMatrix<B>,
+    tree: MerkleTree<H>,
+    domain_offset: B,
+    leaf_encoding: LeafEncoding,
+}
+
+impl<B: StarkField, H: ElementHasher<BaseField = B>> PreprocessedTrace<B, H> {
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+    /// Interpolates the specified preprocessed columns into polynomials, evaluates the
+    /// polynomials over the LDE domain of the computation described by the specified AIR, and
+    /// commits to the polynomial evaluations.
+    ///
+    /// # Panics
+    /// Panics if:
+    /// * The number of columns is not the number of preprocessed columns declared by the AIR.
+    /// * The number of rows is not the trace length of the AIR.
+    pub fn new<A: Air<BaseField = B>>(air: &A, columns: ColMatrix<B>) -> Self {
+        assert_eq!(
+            air.context().num_preprocessed_columns(),
+            columns.num_cols(),
+            "expected {} preprocessed columns, but received {}",
+            air.context().num_preprocessed_columns(),
+            columns.num_cols()
+        );
+        assert_eq!(
+            air.trace_length(),
+            columns.num_rows(),
+            "preprocessed columns must contain {} rows, but contained {}",
+            air.trace_length(),
+            columns.num_rows()
+        );
+
+        let domain = StarkDomain::new(air);
+        let polys = interpolate_trace(&columns);
+        let (lde, tree) = build_trace_commitment::<B, B, H, _>(&polys, &domain, &DefaultAllocator);
+
+        PreprocessedTrace {
+            columns,
+            polys,
+            lde,
+            tree,
+            domain_offset: domain.offset(),
+            leaf_encoding: domain.leaf_encoding(),
+        }
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the commitment to the low-degree extension of the preprocessed columns.
+    pub fn commitment(&self) -> H::Digest {
+        *self.tree.root()
+    }
+
+    /// Returns the number of preprocessed columns.
+    pub fn num_columns(&self) -> usize {
+        self.columns.num_cols()
+    }
+
+    /// Returns the number of rows in the preprocessed columns.
+    pub fn trace_length(&self) -> usize {
+        self.columns.num_rows()
+    }
+
+    /// Returns the preprocessed columns.
+    ///
+    /// These are expected to be placed at the end of the main segment of every execution trace
+    /// for which the preprocessed trace is used.
+    pub fn columns(&self) -> &ColMatrix<B> {
+        &self.columns
+    }
+
+    /// Returns true if this preprocessed trace can be used to generate a proof for the
+    /// computation described by the specified AIR.
+    pub fn is_compatible_with<A: Air<BaseField = B>>(&self, air: &A) -> bool {
+        air.context().num_preprocessed_columns() == self.num_columns()
+            && air.trace_length() == self.trace_length()
+            && air.lde_domain_size() == self.lde.num_rows()
+            && air.domain_offset() == self.domain_offset
+            && air.options().leaf_encoding() == self.leaf_encoding
+    }
+
+    // CRATE ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns true if this preprocessed trace was extended over the specified domain.
+    pub(crate) fn is_extended_over(&self, domain: &StarkDomain<B>) -> bool {
+        domain.lde_domain_size() == self.lde.num_rows()
+            && domain.offset() == self.domain_offset
+            && domain.leaf_encoding() == self.leaf_encoding
+    }
+
+    /// Returns polynomials in coefficient form for all preprocessed columns.
+    pub(crate) fn polys(&self) -> &ColMatrix<B> {
+        &self.polys
+    }
+
+    /// Returns the low-degree extension of the preprocessed columns.
+    pub(crate) fn lde(&self) -> &RowMatrix<B> {
+        &self.lde
+    }
+
+    /// Returns rows of the extended preprocessed columns at the specified positions along with
+    /// Merkle authentication paths from the commitment root to these rows.
+    pub(crate) fn query(&self, positions: &[usize]) -> Queries {
+        build_segment_queries(&self.lde, &self.tree, positions)
+    }
+}
@@ -3,10 +3,20 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use super::{ColMatrix, Trace};
-use air::{EvaluationFrame, TraceInfo, TraceLayout};
+use super::{diagnose, ColMatrix, CompressedColumn, Trace, TraceColumn, TraceDiagnostics};
+use crate::matrix::{ColumnIter, ColumnIterMut};
+use air::{Air, EvaluationFrame, TraceInfo, TraceLayout};
+use core::{iter::FusedIterator, ops::Range};
 use math::{FieldElement, StarkField};
-use utils::{collections::Vec, uninit_vector};
+use utils::{
+    collections::Vec, iter, uninit_vector, ByteReader, ByteWriter, Deserializable,
+    DeserializationError, Serializable,
+};
+
+#[cfg(not(feature = "std"))]
+use core::cell::OnceCell as OnceLock;
+#[cfg(feature = "std")]
+use std::sync::OnceLock;
 
 #[cfg(not(feature = "concurrent"))]
 use utils::collections::vec;
@@ -19,6 +29,9 @@ use utils::{iterators::*, rayon};
 
 const MIN_FRAGMENT_LENGTH: usize = 2;
 
+/// Version of the binary layout in which trace tables are serialized.
+const TRACE_FORMAT_VERSION: u8 = 1;
+
 // TRACE TABLE
 // ================================================================================================
 /// A concrete implementation of the [Trace] trait.
@@ -59,10 +72,52 @@ const MIN_FRAGMENT_LENGTH: usize = 2;
 /// [fill()](TraceTableFragment::fill) method to fill all fragments with data in parallel.
 /// The semantics of the fragment's [TraceTableFragment::fill()] method are identical to the
 /// semantics of the [TraceTable::fill()] method.
+///
+/// Alternatively, [fill_par()](TraceTable::fill_par) method can be used to break the trace into
+/// fragments automatically (based on the number of available threads) and fill them in parallel.
+/// This works for computations in which the state at any step can be initialized independently
+/// of the preceding steps.
+///
+/// # Compressed columns
+/// Columns which are mostly zero or piecewise-constant can be stored as [CompressedColumn]s to
+/// reduce the amount of memory occupied by the trace. A trace with compressed columns can be
+/// built directly via [TraceTable::init_columns()], or existing columns can be compressed via
+/// [compress()](TraceTable::compress).
+///
+/// Values of compressed columns can be read via [get()](TraceTable::get) and
+/// [read_row_into()](TraceTable::read_row_into) without expanding the columns. The dense form of
+/// the trace is built (and retained) on first access to it as a whole, e.g., when the prover
+/// reads the main trace segment to interpolate it, or when columns are accessed as slices via
+/// [get_column()](TraceTable::get_column) or [rows()](TraceTable::rows). Methods which mutate the
+/// trace decompress it first (see [decompress()](TraceTable::decompress)).
+///
+/// # Serialization
+/// Trace tables can be serialized via [Serializable] trait and read via [Deserializable] trait.
+/// This allows execution traces generated by external executors (e.g., written in other
+/// languages) to be handed to the prover. The binary layout is as follows (all integers are
+/// encoded in little-endian byte order):
+///
+/// | Field          | Size                            | Description                          |
+/// | -------------- | ------------------------------- | ------------------------------------ |
+/// | version        | 1 byte                          | format version; currently `1`        |
+/// | modulus length | 1 byte                          | number of bytes in the field modulus |
+/// | modulus        | `modulus length` bytes          | modulus of the base field            |
+/// | width          | 1 byte                          | number of columns in the trace       |
+/// | length         | 1 byte                          | log2 of the number of rows           |
+/// | meta length    | 2 bytes                         | number of metadata bytes             |
+/// | meta           | `meta length` bytes             | trace metadata                       |
+/// | columns        | `width * length * ELEMENT_BYTES` | trace values in column-major order   |
+///
+/// Each value is encoded as the canonical (i.e., not Montgomery) integer representation of the
+/// field element using [ELEMENT_BYTES](StarkField::ELEMENT_BYTES) bytes.
 #[derive(Debug, Clone)]
 pub struct TraceTable<B: StarkField> {
     layout: TraceLayout,
-    trace: ColMatrix<B>,
+    // dense form of the trace; for traces with compressed columns, it is built from `columns`
+    // on first access
+    trace: OnceLock<ColMatrix<B>>,
+    // columns of a trace with compressed columns; empty when the trace is stored densely
+    columns: Vec<TraceColumn<B>>,
     meta: Vec<u8>,
 }
 
@@ -125,11 +180,7 @@ impl<B: StarkField> TraceTable<B> {
         );
 
         let columns = unsafe { (0..width).map(|_| uninit_vector(length)).collect() };
-        Self {
-            layout: TraceLayout::new(width, [0], [0]),
-            trace: ColMatrix::new(columns),
-            meta,
-        }
+        Self::from_dense(ColMatrix::new(columns), meta)
     }
 
     /// Creates a new execution trace from a list of provided trace columns.
@@ -166,13 +217,90 @@ impl<B: StarkField> TraceTable<B> {
             assert_eq!(column.len(), trace_length, "all columns traces must have the same length");
         }
 
+        Self::from_dense(ColMatrix::new(columns), vec![])
+    }
+
+    /// Creates a new execution trace from a list of provided trace columns, some of which may be
+    /// compressed.
+    ///
+    /// Compressed columns are not expanded until the dense form of the trace is needed (see
+    /// [TraceTable] docs); if none of the columns are compressed, this is equivalent to
+    /// [TraceTable::init()].
+    ///
+    /// # Panics
+    /// Panics if the provided columns do not satisfy the requirements of [TraceTable::init()].
+    pub fn init_columns(columns: Vec<TraceColumn<B>>) -> Self {
+        if columns.iter().all(|column| matches!(column, TraceColumn::Dense(_))) {
+            return Self::init(columns.into_iter().map(TraceColumn::into_values).collect());
+        }
+
+        assert!(
+            columns.len() <= TraceInfo::MAX_TRACE_WIDTH,
+            "execution trace width cannot be greater than {}, but was {}",
+            TraceInfo::MAX_TRACE_WIDTH,
+            columns.len()
+        );
+        let trace_length = columns[0].len();
+        assert!(
+            trace_length >= TraceInfo::MIN_TRACE_LENGTH,
+            "execution trace must be at least {} steps long, but was {}",
+            TraceInfo::MIN_TRACE_LENGTH,
+            trace_length
+        );
+        assert!(trace_length.is_power_of_two(), "execution trace length must be a power of 2");
+        assert!(
+            trace_length.ilog2() <= B::TWO_ADICITY,
+            "execution trace length cannot exceed 2^{} steps, but was 2^{}",
+            B::TWO_ADICITY,
+            trace_length.ilog2()
+        );
+        for column in columns.iter().skip(1) {
+            assert_eq!(column.len(), trace_length, "all columns traces must have the same length");
+        }
+
         Self {
             layout: TraceLayout::new(columns.len(), [0], [0]),
-            trace: ColMatrix::new(columns),
+            trace: OnceLock::new(),
+            columns,
             meta: vec![],
         }
     }
 
+    /// Returns a trace stored densely in the specified matrix.
+    fn from_dense(trace: ColMatrix<B>, meta: Vec<u8>) -> Self {
+        Self {
+            layout: TraceLayout::new(trace.num_cols(), [0], [0]),
+            trace: OnceLock::from(trace),
+            columns: Vec::new(),
+            meta,
+        }
+    }
+
+    /// Creates a new execution trace by stacking the provided traces side by side.
+    ///
+    /// Columns of the first trace come first, followed by columns of the second trace etc. The
+    /// metadata of the resulting trace is taken from the first trace. This is the layout expected
+    /// by [AggregateAir](air::AggregateAir) when proving multiple instances of the same
+    /// computation in a single proof.
+    ///
+    /// # Panics
+    /// Panics if:
+    /// * The `traces` vector is empty, or the combined width of all traces exceeds 255 columns.
+    /// * Lengths or metadata of the provided traces are not identical.
+    pub fn stack(traces: Vec<Self>) -> Self {
+        assert!(!traces.is_empty(), "at least one trace must be provided");
+        let meta = traces[0].meta.clone();
+        let mut columns = Vec::new();
+        for trace in traces {
+            assert_eq!(meta, trace.meta, "all stacked traces must have the same metadata");
+            columns.extend(trace.into_dense().into_columns());
+        }
+
+        let mut result = Self::init(columns);
+        result.meta = meta;
+        result
+    }
+
     // DATA MUTATORS
     // --------------------------------------------------------------------------------------------
 
@@ -184,7 +312,7 @@ impl<B: StarkField> TraceTable<B> {
     /// # Panics
     /// Panics if either `column` or `step` are out of bounds for this execution trace.
     pub fn set(&mut self, column: usize, step: usize, value: B) {
-        self.trace.set(column, step, value)
+        self.dense_mut().set(column, step, value)
     }
 
     /// Updates metadata for this execution trace to the specified vector of bytes.
@@ -227,9 +355,112 @@ impl<B: StarkField> TraceTable<B> {
         }
     }
 
+    /// Fills all rows in the execution trace in parallel.
+    ///
+    /// The trace is broken into fragments of equal length such that there is at least one
+    /// fragment per available thread (when `concurrent` feature is not enabled, the entire trace
+    /// is a single fragment). Each fragment is then filled by executing the provided closures as
+    /// follows:
+    /// - `init` closure is used to initialize the first row of the fragment; it receives two
+    ///   parameters:
+    ///   - index of the row in the execution trace at which the fragment starts.
+    ///   - a mutable reference to the state initialized to all zeros; the contents of the state
+    ///     are copied into the first row of the fragment after the closure returns.
+    /// - `update` closure is used to populate all subsequent rows of the fragment; it receives two
+    ///   parameters:
+    ///   - index of the last updated row in the execution trace.
+    ///   - a mutable reference to the last updated state; the contents of the state are copied
+    ///     into the next row of the trace after the closure returns.
+    ///
+    /// Thus, `init` must be able to compute the state at an arbitrary step of the computation,
+    /// and the resulting trace is identical to the trace built by [TraceTable::fill()] when
+    /// `init` closure for [TraceTable::fill()] is equivalent to `init(0, state)`.
+    pub fn fill_par<I, U>(&mut self, init: I, update: U)
+    where
+        I: Fn(usize, &mut [B]) + Send + Sync,
+        U: Fn(usize, &mut [B]) + Send + Sync,
+    {
+        let fragment_length = get_par_fragment_length(self.length());
+        self.fragments(fragment_length).for_each(|mut fragment| {
+            let offset = fragment.offset();
+            fragment.fill(|state| init(offset, state), |i, state| update(offset + i, state));
+        });
+    }
+
     /// Updates a single row in the execution trace with provided data.
     pub fn update_row(&mut self, step: usize, state: &[B]) {
-        self.trace.update_row(step, state);
+        self.dense_mut().update_row(step, state);
+    }
+
+    /// Returns a mutable reference to the entire trace column at the specified index.
+    ///
+    /// # Panics
+    /// Panics if `col_idx` is out of bounds for this execution trace.
+    pub fn get_column_mut(&mut self, col_idx: usize) -> &mut [B] {
+        self.dense_mut().get_column_mut(col_idx)
+    }
+
+    /// Returns an iterator over mutable columns of this execution trace.
+    pub fn columns_mut(&mut self) -> ColumnIterMut<'_, B> {
+        self.dense_mut().columns_mut()
+    }
+
+    // COMPRESSION
+    // --------------------------------------------------------------------------------------------
+
+    /// Compresses all columns of this trace which take less memory in a compressed form (see
+    /// [CompressedColumn::compress()]), and returns the number of compressed columns.
+    ///
+    /// The dense form of the trace is released if at least one column was compressed.
+    pub fn compress(&mut self) -> usize {
+        let columns = self.take_dense().into_columns();
+        let columns: Vec<TraceColumn<B>> = columns
+            .into_iter()
+            .map(|column| match CompressedColumn::compress(&column) {
+                Some(compressed) => TraceColumn::Compressed(compressed),
+                None => TraceColumn::Dense(column),
+            })
+            .collect();
+
+        let num_compressed = columns
+            .iter()
+            .filter(|column| matches!(column, TraceColumn::Compressed(_)))
+            .count();
+        if num_compressed == 0 {
+            let columns = columns.into_iter().map(TraceColumn::into_values).collect();
+            self.trace = OnceLock::from(ColMatrix::new(columns));
+        } else {
+            self.columns = columns;
+        }
+        num_compressed
+    }
+
+    /// Expands all compressed columns of this trace, and stores the trace densely.
+    ///
+    /// This is done automatically before the trace is mutated, but can also be done explicitly
+    /// to control when the memory for the dense form of the trace is allocated.
+    pub fn decompress(&mut self) {
+        let trace = self.take_dense();
+        self.trace = OnceLock::from(trace);
+    }
+
+    /// Returns the number of columns of this trace which are stored in a compressed form.
+    pub fn num_compressed_columns(&self) -> usize {
+        self.columns
+            .iter()
+            .filter(|column| matches!(column, TraceColumn::Compressed(_)))
+            .count()
+    }
+
+    /// Returns the number of bytes occupied by values of this trace, including the dense form of
+    /// a trace with compressed columns if it has been built.
+    pub fn size_in_bytes(&self) -> usize {
+        let compressed_size: usize = self.columns.iter().map(TraceColumn::size_in_bytes).sum();
+        let dense_size = self
+            .trace
+            .get()
+            .map_or(0, |trace| trace.num_cols() * trace.num_rows() * core::mem::size_of::<B>());
+        compressed_size + dense_size
     }
 
     // FRAGMENTS
@@ -244,7 +475,10 @@ impl<B: StarkField> TraceTable<B> {
     /// Panics if `fragment_length` is smaller than 2, greater than the length of the trace,
     /// or is not a power of two.
     #[cfg(not(feature = "concurrent"))]
-    pub fn fragments(&mut self, fragment_length: usize) -> vec::IntoIter<TraceTableFragment<B>> {
+    pub fn fragments(
+        &mut self,
+        fragment_length: usize,
+    ) -> vec::IntoIter<TraceTableFragment<'_, B>> {
         self.build_fragments(fragment_length).into_iter()
     }
 
@@ -260,28 +494,54 @@ impl<B: StarkField> TraceTable<B> {
     pub fn fragments(
         &mut self,
         fragment_length: usize,
-    ) -> rayon::vec::IntoIter<TraceTableFragment<B>> {
+    ) -> rayon::vec::IntoIter<TraceTableFragment<'_, B>> {
         self.build_fragments(fragment_length).into_par_iter()
     }
 
+    /// Breaks the execution trace into read-only fragments.
+    ///
+    /// The number of rows in each fragment will be equal to `fragment_length` parameter. Unlike
+    /// [fragments()](TraceTable::fragments), this method does not require a mutable reference to
+    /// the trace, and thus, can be used to process the trace (e.g., to check its consistency) in
+    /// chunks without copying it.
+    ///
+    /// # Panics
+    /// Panics if `fragment_length` is smaller than 2, greater than the length of the trace,
+    /// or is not a power of two.
+    #[cfg(not(feature = "concurrent"))]
+    pub fn fragment_views(
+        &self,
+        fragment_length: usize,
+    ) -> vec::IntoIter<TraceTableFragmentView<'_, B>> {
+        self.build_fragment_views(fragment_length).into_iter()
+    }
+
+    /// Breaks the execution trace into read-only fragments.
+    ///
+    /// The number of rows in each fragment will be equal to `fragment_length` parameter. Unlike
+    /// [fragments()](TraceTable::fragments), this method does not require a mutable reference to
+    /// the trace, and thus, can be used to process the trace (e.g., to check its consistency) in
+    /// chunks from multiple threads without copying it.
+    ///
+    /// # Panics
+    /// Panics if `fragment_length` is smaller than 2, greater than the length of the trace,
+    /// or is not a power of two.
+    #[cfg(feature = "concurrent")]
+    pub fn fragment_views(
+        &self,
+        fragment_length: usize,
+    ) -> rayon::vec::IntoIter<TraceTableFragmentView<'_, B>> {
+        self.build_fragment_views(fragment_length).into_par_iter()
+    }
+
     /// Returns a vector of trace fragments each covering the number of steps specified by the
     /// `fragment_length` parameter.
-    fn build_fragments(&mut self, fragment_length: usize) -> Vec<TraceTableFragment<B>> {
-        assert!(
-            fragment_length >= MIN_FRAGMENT_LENGTH,
-            "fragment length must be at least {MIN_FRAGMENT_LENGTH}, but was {fragment_length}"
-        );
-        assert!(
-            fragment_length <= self.length(),
-            "length of a fragment cannot exceed {}, but was {}",
-            self.length(),
-            fragment_length
-        );
-        assert!(fragment_length.is_power_of_two(), "fragment length must be a power of 2");
+    fn build_fragments(&mut self, fragment_length: usize) -> Vec<TraceTableFragment<'_, B>> {
+        self.check_fragment_length(fragment_length);
         let num_fragments = self.length() / fragment_length;
 
         let mut fragment_data = (0..num_fragments).map(|_| Vec::new()).collect::<Vec<_>>();
-        self.trace.columns_mut().for_each(|column| {
+        self.dense_mut().columns_mut().for_each(|column| {
             for (i, fragment) in column.chunks_mut(fragment_length).enumerate() {
                 fragment_data[i].push(fragment);
             }
@@ -298,6 +558,45 @@ impl<B: StarkField> TraceTable<B> {
             .collect()
     }
 
+    /// Returns a vector of read-only trace fragments each covering the number of steps specified
+    /// by the `fragment_length` parameter.
+    fn build_fragment_views(&self, fragment_length: usize) -> Vec<TraceTableFragmentView<'_, B>> {
+        self.check_fragment_length(fragment_length);
+        let num_fragments = self.length() / fragment_length;
+
+        let mut fragment_data = (0..num_fragments).map(|_| Vec::new()).collect::<Vec<_>>();
+        self.dense().columns().for_each(|column| {
+            for (i, fragment) in column.chunks(fragment_length).enumerate() {
+                fragment_data[i].push(fragment);
+            }
+        });
+
+        fragment_data
+            .into_iter()
+            .enumerate()
+            .map(|(i, data)| TraceTableFragmentView {
+                index: i,
+                offset: i * fragment_length,
+                data,
+            })
+            .collect()
+    }
+
+    /// Makes sure the trace can be broken into fragments of the specified length.
+    fn check_fragment_length(&self, fragment_length: usize) {
+        assert!(
+            fragment_length >= MIN_FRAGMENT_LENGTH,
+            "fragment length must be at least {MIN_FRAGMENT_LENGTH}, but was {fragment_length}"
+        );
+        assert!(
+            fragment_length <= self.length(),
+            "length of a fragment cannot exceed {}, but was {}",
+            self.length(),
+            fragment_length
+        );
+        assert!(fragment_length.is_power_of_two(), "fragment length must be a power of 2");
+    }
+
     // PUBLIC ACCESSORS
     // --------------------------------------------------------------------------------------------
 
@@ -308,17 +607,124 @@ impl<B: StarkField> TraceTable<B> {
 
     /// Returns the entire trace column at the specified index.
     pub fn get_column(&self, col_idx: usize) -> &[B] {
-        self.trace.get_column(col_idx)
+        self.dense().get_column(col_idx)
+    }
+
+    /// Returns values of the trace column at the specified index in the specified range of rows.
+    ///
+    /// # Panics
+    /// Panics if `col_idx` is out of bounds for this execution trace, or if `rows` is not a valid
+    /// range of rows of this execution trace.
+    pub fn get_column_slice(&self, col_idx: usize, rows: Range<usize>) -> &[B] {
+        &self.dense().get_column(col_idx)[rows]
+    }
+
+    /// Returns an iterator over columns of this execution trace.
+    pub fn columns(&self) -> ColumnIter<'_, B> {
+        self.dense().columns()
+    }
+
+    /// Returns a parallel iterator over columns of this execution trace.
+    #[cfg(feature = "concurrent")]
+    pub fn par_columns(&self) -> impl IndexedParallelIterator<Item = &[B]> + '_ {
+        let trace = self.dense();
+        (0..self.width()).into_par_iter().map(move |col_idx| trace.get_column(col_idx))
+    }
+
+    /// Returns an iterator over rows of this execution trace.
+    ///
+    /// Rows are not stored contiguously; thus, the iterator yields lightweight [TraceRow] views
+    /// which read values directly from the columns of the trace.
+    pub fn rows(&self) -> RowIter<'_, B> {
+        RowIter::new(self.dense())
+    }
+
+    /// Returns a parallel iterator over rows of this execution trace.
+    #[cfg(feature = "concurrent")]
+    pub fn par_rows(&self) -> impl IndexedParallelIterator<Item = TraceRow<'_, B>> + '_ {
+        let trace = self.dense();
+        (0..self.length()).into_par_iter().map(move |index| TraceRow { trace, index })
     }
 
     /// Returns value of the cell in the specified column at the specified row of this trace.
     pub fn get(&self, column: usize, step: usize) -> B {
-        self.trace.get(column, step)
+        match self.trace.get() {
+            Some(trace) => trace.get(column, step),
+            None => self.columns[column].get(step),
+        }
     }
 
     /// Reads a single row from this execution trace into the provided target.
     pub fn read_row_into(&self, step: usize, target: &mut [B]) {
-        self.trace.read_row_into(step, target);
+        match self.trace.get() {
+            Some(trace) => trace.read_row_into(step, target),
+            None => {
+                for (value, column) in target.iter_mut().zip(self.columns.iter()) {
+                    *value = column.get(step);
+                }
+            }
+        }
+    }
+
+    // VALIDATION
+    // --------------------------------------------------------------------------------------------
+
+    /// Validates this execution trace against the specified AIR and returns diagnostics listing
+    /// all issues found.
+    ///
+    /// The following checks are performed:
+    /// - The width and the length of the trace are consistent with the ones expected by the AIR.
+    /// - The trace satisfies all assertions placed against the main trace segment by the AIR.
+    /// - If `max_column_degree` is provided, each column is interpolated over the trace domain,
+    ///   and the degree of the resulting polynomial must not exceed `max_column_degree`. This is
+    ///   the most expensive check: it requires an inverse FFT for every column.
+    ///
+    /// Transition constraints are not evaluated. Thus, this is much cheaper than generating a
+    /// proof and can be used to catch malformed traces before the proving pipeline starts.
+    pub fn validate_against<A>(&self, air: &A, max_column_degree: Option<usize>) -> TraceDiagnostics
+    where
+        A: Air<BaseField = B>,
+    {
+        diagnose(air, self, max_column_degree)
+    }
+
+    // HELPER METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the dense form of this trace, building it from compressed columns if needed.
+    fn dense(&self) -> &ColMatrix<B> {
+        self.trace.get_or_init(|| {
+            ColMatrix::new(
+                iter!(self.columns)
+                    .map(|column| match column {
+                        TraceColumn::Dense(values) => values.clone(),
+                        TraceColumn::Compressed(column) => column.expand(),
+                    })
+                    .collect(),
+            )
+        })
+    }
+
+    /// Returns a mutable reference to the dense form of this trace, decompressing it if needed.
+    fn dense_mut(&mut self) -> &mut ColMatrix<B> {
+        self.decompress();
+        self.trace.get_mut().expect("trace is stored densely")
+    }
+
+    /// Takes the dense form of this trace out of this trace, building it from compressed columns
+    /// if needed; dense columns are moved rather than copied. This leaves the trace empty, and
+    /// thus, the trace must be restored by the caller.
+    fn take_dense(&mut self) -> ColMatrix<B> {
+        let columns = core::mem::take(&mut self.columns);
+        match self.trace.take() {
+            Some(trace) => trace,
+            None => ColMatrix::new(columns.into_iter().map(TraceColumn::into_values).collect()),
+        }
+    }
+
+    /// Returns the dense form of this trace, consuming the trace.
+    fn into_dense(mut self) -> ColMatrix<B> {
+        self.take_dense()
     }
 }
 
@@ -333,7 +739,10 @@ impl<B: StarkField> Trace for TraceTable<B> {
     }
 
     fn length(&self) -> usize {
-        self.trace.num_rows()
+        match self.trace.get() {
+            Some(trace) => trace.num_rows(),
+            None => self.columns[0].len(),
+        }
     }
 
     fn meta(&self) -> &[u8] {
@@ -341,13 +750,14 @@ impl<B: StarkField> Trace for TraceTable<B> {
     }
 
     fn read_main_frame(&self, row_idx: usize, frame: &mut EvaluationFrame<Self::BaseField>) {
-        let next_row_idx = (row_idx + 1) % self.length();
-        self.trace.read_row_into(row_idx, frame.current_mut());
-        self.trace.read_row_into(next_row_idx, frame.next_mut());
+        for row in 0..frame.num_rows() {
+            let offset_row_idx = (row_idx + frame.offsets()[row]) % self.length();
+            self.read_row_into(offset_row_idx, frame.row_mut(row));
+        }
     }
 
     fn main_segment(&self) -> &ColMatrix<B> {
-        &self.trace
+        self.dense()
     }
 
     fn build_aux_segment<E>(
@@ -362,6 +772,235 @@ impl<B: StarkField> Trace for TraceTable<B> {
     }
 }
 
+// SERIALIZATION
+// ================================================================================================
+
+impl<B: StarkField> Serializable for TraceTable<B> {
+    /// Serializes `self` and writes the resulting bytes into the `target`.
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        let modulus = B::get_modulus_le_bytes();
+        target.write_u8(TRACE_FORMAT_VERSION);
+        target.write_u8(modulus.len() as u8);
+        target.write_bytes(&modulus);
+        target.write_u8(self.width() as u8);
+        target.write_u8(self.length().ilog2() as u8);
+        target.write_u16(self.meta.len() as u16);
+        target.write_bytes(&self.meta);
+        // compressed columns are expanded one at a time, so that the dense form of the entire
+        // trace is never built for serialization
+        match self.trace.get() {
+            Some(trace) => trace.columns().for_each(|column| B::write_batch_into(column, target)),
+            None => self.columns.iter().for_each(|column| match column {
+                TraceColumn::Dense(values) => B::write_batch_into(values, target),
+                TraceColumn::Compressed(column) => B::write_batch_into(&column.expand(), target),
+            }),
+        }
+    }
+}
+
+impl<B: StarkField> Deserializable for TraceTable<B> {
+    /// Reads a trace table from the specified `source` and returns the result.
+    ///
+    /// # Errors
+    /// Returns an error if a valid trace table in field `B` could not be read from the specified
+    /// `source`.
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        let version = source.read_u8()?;
+        if version != TRACE_FORMAT_VERSION {
+            return Err(DeserializationError::InvalidValue(format!(
+                "trace format version {version} is not supported"
+            )));
+        }
+
+        let num_modulus_bytes = source.read_u8()? as usize;
+        let modulus = source.read_vec(num_modulus_bytes)?;
+        if modulus != B::get_modulus_le_bytes() {
+            return Err(DeserializationError::InvalidValue(
+                "trace was generated in a different base field".into(),
+            ));
+        }
+
+        let width = source.read_u8()? as usize;
+        if width == 0 {
+            return Err(DeserializationError::InvalidValue(
+                "execution trace must consist of at least one column".into(),
+            ));
+        }
+
+        let num_steps = source.read_u8()? as u32;
+        if num_steps > B::TWO_ADICITY || num_steps >= usize::BITS {
+            return Err(DeserializationError::InvalidValue(format!(
+                "execution trace length cannot exceed 2^{} steps, but was 2^{num_steps}",
+                B::TWO_ADICITY
+            )));
+        }
+        let length = 2_usize.pow(num_steps);
+        if length < TraceInfo::MIN_TRACE_LENGTH {
+            return Err(DeserializationError::InvalidValue(format!(
+                "execution trace must be at least {} steps long, but was {length}",
+                TraceInfo::MIN_TRACE_LENGTH
+            )));
+        }
+
+        let num_meta_bytes = source.read_u16()? as usize;
+        let meta = source.read_vec(num_meta_bytes)?;
+
+        // columns are not pre-allocated so that malformed inputs with a large declared length
+        // fail on missing bytes rather than on memory allocation
+        let mut columns = Vec::with_capacity(width);
+        for _ in 0..width {
+            let column = (0..length).map(|_| B::read_from(source)).collect::<Result<_, _>>()?;
+            columns.push(column);
+        }
+
+        let mut trace = Self::init(columns);
+        trace.meta = meta;
+        Ok(trace)
+    }
+}
+
+// TRACE TABLE WRITER
+// ================================================================================================
+/// Builds an execution trace by appending chunks of rows as the underlying computation proceeds.
+///
+/// The writer allocates memory for the entire trace upfront, and rows are appended in order via
+/// [append_row()](TraceTableWriter::append_row) and [append_rows()](TraceTableWriter::append_rows)
+/// methods. Once all rows have been appended, the trace can be obtained via
+/// [finish()](TraceTableWriter::finish). This allows streaming computations to hand over chunks
+/// of the trace as soon as they are generated without keeping their own copy of the trace.
+///
+/// Interpolation and low-degree extension of trace columns cannot start before the trace is
+/// complete since every polynomial coefficient depends on every row of the trace. However, work
+/// which does not depend on the contents of the trace (i.e., building the STARK domain, evaluating
+/// periodic columns and constraint divisors) can overlap with trace generation: a
+/// [ProverSession](crate::ProverSession) can be built on a separate thread while rows are being
+/// appended, and then passed to [Prover::prove_in_session()](crate::Prover::prove_in_session).
+pub struct TraceTableWriter<B: StarkField> {
+    trace: TraceTable<B>,
+    num_rows: usize,
+}
+
+impl<B: StarkField> TraceTableWriter<B> {
+    // CONSTRUCTORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Creates a new writer for an execution trace of the specified width and length.
+    ///
+    /// # Panics
+    /// Panics if:
+    /// * `width` is zero or greater than 255.
+    /// * `length` is smaller than 8, greater than biggest multiplicative subgroup in the field
+    ///   `B`, or is not a power of two.
+    pub fn new(width: usize, length: usize) -> Self {
+        Self::with_meta(width, length, vec![])
+    }
+
+    /// Creates a new writer for an execution trace of the specified width and length, and with
+    /// the specified metadata.
+    ///
+    /// # Panics
+    /// Panics if:
+    /// * `width` is zero or greater than 255.
+    /// * `length` is smaller than 8, greater than the biggest multiplicative subgroup in the
+    ///   field `B`, or is not a power of two.
+    /// * Length of `meta` is greater than 65535;
+    pub fn with_meta(width: usize, length: usize, meta: Vec<u8>) -> Self {
+        Self {
+            trace: TraceTable::with_meta(width, length, meta),
+            num_rows: 0,
+        }
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the number of columns in the trace being built.
+    pub fn width(&self) -> usize {
+        self.trace.width()
+    }
+
+    /// Returns the number of rows in the trace being built once it is complete.
+    pub fn length(&self) -> usize {
+        self.trace.length()
+    }
+
+    /// Returns the number of rows which have been appended so far.
+    pub fn num_rows(&self) -> usize {
+        self.num_rows
+    }
+
+    /// Returns true if all rows of the trace have been appended.
+    pub fn is_complete(&self) -> bool {
+        self.num_rows == self.length()
+    }
+
+    /// Returns the last appended row, or `None` if no rows have been appended yet.
+    ///
+    /// This can be used by streaming computations to resume from the last generated state.
+    pub fn last_row(&self) -> Option<Vec<B>> {
+        let step = self.num_rows.checked_sub(1)?;
+        let mut row = vec![B::ZERO; self.width()];
+        self.trace.read_row_into(step, &mut row);
+        Some(row)
+    }
+
+    // DATA MUTATORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Appends a single row to the trace.
+    ///
+    /// # Panics
+    /// Panics if the length of `row` is not equal to the width of the trace, or if all rows of
+    /// the trace have already been appended.
+    pub fn append_row(&mut self, row: &[B]) {
+        self.append_rows(row);
+    }
+
+    /// Appends a chunk of rows to the trace; the rows are expected to be concatenated into a
+    /// single slice (i.e., `rows` contains values of the chunk in row-major order).
+    ///
+    /// # Panics
+    /// Panics if the length of `rows` is not a multiple of the width of the trace, or if the
+    /// chunk contains more rows than there are remaining rows in the trace.
+    pub fn append_rows(&mut self, rows: &[B]) {
+        let width = self.width();
+        assert!(
+            rows.len() % width == 0,
+            "number of values in appended rows must be a multiple of trace width {width}, but was {}",
+            rows.len()
+        );
+        let num_rows = rows.len() / width;
+        assert!(
+            self.num_rows + num_rows <= self.length(),
+            "cannot append {num_rows} rows to a trace with {} remaining rows",
+            self.length() - self.num_rows
+        );
+
+        let offset = self.num_rows;
+        for (col_idx, column) in self.trace.columns_mut().enumerate() {
+            let column = &mut column[offset..offset + num_rows];
+            for (value, row) in column.iter_mut().zip(rows.chunks_exact(width)) {
+                *value = row[col_idx];
+            }
+        }
+        self.num_rows += num_rows;
+    }
+
+    /// Returns the trace built by this writer.
+    ///
+    /// # Panics
+    /// Panics if not all rows of the trace have been appended.
+    pub fn finish(self) -> TraceTable<B> {
+        assert!(
+            self.is_complete(),
+            "cannot finish a trace of {} rows after only {} rows have been appended",
+            self.length(),
+            self.num_rows
+        );
+        self.trace
+    }
+}
+
 // TRACE FRAGMENTS
 // ================================================================================================
 /// A set of consecutive rows of an execution trace.
@@ -440,4 +1079,178 @@ impl<'a, B: StarkField> TraceTableFragment<'a, B> {
             column[row_idx] = value;
         }
     }
+
+    /// Returns a mutable reference to the segment of the trace column at the specified index
+    /// covered by this fragment.
+    ///
+    /// # Panics
+    /// Panics if `col_idx` is out of bounds for this fragment.
+    pub fn get_column_mut(&mut self, col_idx: usize) -> &mut [B] {
+        self.data[col_idx]
+    }
+}
+
+// TRACE FRAGMENT VIEWS
+// ================================================================================================
+
+/// A read-only view into a fragment of an execution trace.
+///
+/// A fragment view covers a range of rows of the trace; all fragment views of a trace have the
+/// same length. Fragment views are created via [TraceTable::fragment_views()] method.
+pub struct TraceTableFragmentView<'a, B: StarkField> {
+    index: usize,
+    offset: usize,
+    data: Vec<&'a [B]>,
+}
+
+impl<'a, B: StarkField> TraceTableFragmentView<'a, B> {
+    /// Returns the index of this fragment.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Returns the step at which the fragment starts in the context of the original execution
+    /// trace.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Returns the number of rows in this execution trace fragment.
+    pub fn length(&self) -> usize {
+        self.data[0].len()
+    }
+
+    /// Returns the width of the fragment (same as the width of the underlying execution trace).
+    pub fn width(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns the segment of the trace column at the specified index covered by this fragment.
+    ///
+    /// # Panics
+    /// Panics if `col_idx` is out of bounds for this fragment.
+    pub fn get_column(&self, col_idx: usize) -> &'a [B] {
+        self.data[col_idx]
+    }
+
+    /// Returns value of the cell in the specified column at the specified row of this fragment.
+    pub fn get(&self, col_idx: usize, row_idx: usize) -> B {
+        self.data[col_idx][row_idx]
+    }
+
+    /// Reads a single row of this fragment into the provided target.
+    pub fn read_row_into(&self, row_idx: usize, target: &mut [B]) {
+        for (value, column) in target.iter_mut().zip(self.data.iter()) {
+            *value = column[row_idx];
+        }
+    }
+}
+
+// TRACE ROWS
+// ================================================================================================
+
+/// A read-only view of a single row of an execution trace.
+///
+/// Row views are yielded by [TraceTable::rows()] method.
+#[derive(Debug, Clone, Copy)]
+pub struct TraceRow<'a, B: StarkField> {
+    trace: &'a ColMatrix<B>,
+    index: usize,
+}
+
+impl<'a, B: StarkField> TraceRow<'a, B> {
+    /// Returns the index of this row in the execution trace.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Returns the number of values in this row (same as the width of the execution trace).
+    pub fn width(&self) -> usize {
+        self.trace.num_cols()
+    }
+
+    /// Returns the value of this row in the column at the specified index.
+    ///
+    /// # Panics
+    /// Panics if `col_idx` is out of bounds for the execution trace.
+    pub fn get(&self, col_idx: usize) -> B {
+        self.trace.get(col_idx, self.index)
+    }
+
+    /// Returns an iterator over values of this row.
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = B> + 'a {
+        let (trace, index) = (self.trace, self.index);
+        trace.columns().map(move |column| column[index])
+    }
+
+    /// Reads values of this row into the provided target.
+    pub fn read_into(&self, target: &mut [B]) {
+        self.trace.read_row_into(self.index, target);
+    }
+
+    /// Returns values of this row as a vector.
+    pub fn to_vec(&self) -> Vec<B> {
+        self.iter().collect()
+    }
+}
+
+/// Iterator over rows of [TraceTable].
+pub struct RowIter<'a, B: StarkField> {
+    trace: &'a ColMatrix<B>,
+    rows: Range<usize>,
+}
+
+impl<'a, B: StarkField> RowIter<'a, B> {
+    fn new(trace: &'a ColMatrix<B>) -> Self {
+        Self {
+            trace,
+            rows: 0..trace.num_rows(),
+        }
+    }
+}
+
+impl<'a, B: StarkField> Iterator for RowIter<'a, B> {
+    type Item = TraceRow<'a, B>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.rows.next().map(|index| TraceRow {
+            trace: self.trace,
+            index,
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.rows.size_hint()
+    }
+}
+
+impl<'a, B: StarkField> DoubleEndedIterator for RowIter<'a, B> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.rows.next_back().map(|index| TraceRow {
+            trace: self.trace,
+            index,
+        })
+    }
+}
+
+impl<'a, B: StarkField> ExactSizeIterator for RowIter<'a, B> {}
+
+impl<'a, B: StarkField> FusedIterator for RowIter<'a, B> {}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns the length of fragments into which a trace of the specified length should be broken
+/// to be filled in parallel.
+#[cfg(not(feature = "concurrent"))]
+fn get_par_fragment_length(trace_length: usize) -> usize {
+    trace_length
+}
+
+/// Returns the length of fragments into which a trace of the specified length should be broken
+/// to be filled in parallel.
+#[cfg(feature = "concurrent")]
+fn get_par_fragment_length(trace_length: usize) -> usize {
+    let num_fragments = rayon::current_num_threads().next_power_of_two();
+    (trace_length / num_fragments).max(MIN_FRAGMENT_LENGTH)
 }
@@ -0,0 +1,252 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+//! Checks memory estimates and strategy selection of the prover against memory usage measured
+//! by a counting global allocator. All checks are made in a single test so that allocations of
+//! concurrently running tests do not interfere with the measurements.
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+use winter_prover::{
+    crypto::{hashers::Blake3_256, DefaultRandomCoin},
+    math::{fields::f128::BaseElement, FieldElement},
+    matrix::ColMatrix,
+    Air, AirContext, Assertion, AuxTraceRandElements, ConstraintCompositionCoefficients,
+    DefaultConstraintEvaluator, DefaultTraceLde, EvaluationFrame, FieldExtension, MemoryEstimate,
+    MemoryStrategy, ProofOptions, Prover, ProverError, StarkDomain, Trace, TraceInfo,
+    TracePolyTable, TraceTable, TransitionConstraintDegree,
+};
+
+type Blake3 = Blake3_256<BaseElement>;
+
+// COUNTING ALLOCATOR
+// ================================================================================================
+
+/// Global allocator which keeps track of the number of currently allocated bytes and of the
+/// largest number of bytes allocated at any point.
+struct CountingAllocator;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static PEAK_ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            record_allocation(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        ALLOCATED.fetch_sub(layout.size(), Ordering::SeqCst);
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            ALLOCATED.fetch_sub(layout.size(), Ordering::SeqCst);
+            record_allocation(new_size);
+        }
+        new_ptr
+    }
+}
+
+fn record_allocation(size: usize) {
+    let allocated = ALLOCATED.fetch_add(size, Ordering::SeqCst) + size;
+    PEAK_ALLOCATED.fetch_max(allocated, Ordering::SeqCst);
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+// TESTS
+// ================================================================================================
+
+#[test]
+fn memory_budget() {
+    let trace_length = 1 << 12;
+    let options = ProofOptions::new(32, 8, 0, FieldExtension::None, 4, 31);
+    let trace = build_trace(trace_length);
+    let air = FibAir::new(trace.get_info(), (), options.clone());
+
+    let estimates = MemoryStrategy::ALL
+        .map(|strategy| MemoryEstimate::with_strategy::<_, BaseElement, Blake3>(&air, strategy));
+    assert_eq!(MemoryEstimate::new::<_, BaseElement, Blake3>(&air), estimates[0]);
+    for pair in estimates.windows(2) {
+        assert!(pair[1].peak() < pair[0].peak(), "estimates must decrease: {estimates:?}");
+    }
+
+    // without a budget, evaluations are kept in memory
+    let (expected_proof, measured) = prove(&options, trace_length, None).unwrap();
+    assert_estimate(&estimates[0], measured);
+
+    // with a budget, the first strategy which fits into it is used; all strategies result in
+    // the same proof
+    let mut measured_peaks = vec![measured];
+    for (i, estimate) in estimates.iter().enumerate() {
+        let budget = estimate.peak();
+        let selected = MemoryEstimate::select::<_, BaseElement, Blake3>(&air, Some(budget));
+        assert_eq!(Ok(*estimate), selected);
+
+        let (proof, measured) = prove(&options, trace_length, Some(budget)).unwrap();
+        assert_eq!(expected_proof, proof, "strategy {:?} changed the proof", estimate.strategy);
+        assert_estimate(estimate, measured);
+        if i > 0 {
+            assert!(measured < measured_peaks[i], "{:?} did not save memory", estimate.strategy);
+        }
+        measured_peaks.push(measured);
+    }
+
+    // proof generation fails up front if no strategy fits into the budget
+    let lowest_peak = estimates.last().unwrap().peak();
+    assert_eq!(
+        Err(ProverError::MemoryBudgetExceeded(lowest_peak - 1, lowest_peak)),
+        prove(&options, trace_length, Some(lowest_peak - 1))
+    );
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Generates a proof and returns it (serialized) together with the peak number of bytes
+/// allocated while building the trace and generating the proof.
+fn prove(
+    options: &ProofOptions,
+    trace_length: usize,
+    memory_budget: Option<usize>,
+) -> Result<(Vec<u8>, usize), ProverError> {
+    let baseline = ALLOCATED.load(Ordering::SeqCst);
+    PEAK_ALLOCATED.store(baseline, Ordering::SeqCst);
+
+    let trace = build_trace(trace_length);
+    let prover = FibProver {
+        options: options.clone(),
+        memory_budget,
+    };
+    let proof = prover.prove(trace)?;
+
+    let measured = PEAK_ALLOCATED.load(Ordering::SeqCst) - baseline;
+    Ok((proof.to_bytes(), measured))
+}
+
+/// Checks that the estimate is above the measured peak memory usage, but not by more than 25%.
+fn assert_estimate(estimate: &MemoryEstimate, measured: usize) {
+    assert!(
+        measured <= estimate.peak() && estimate.peak() <= measured + measured / 4,
+        "{:?} strategy: estimated {} bytes, but measured {measured} bytes",
+        estimate.strategy,
+        estimate.peak()
+    );
+}
+
+fn build_trace(length: usize) -> TraceTable<BaseElement> {
+    let mut trace = TraceTable::new(2, length);
+    trace.fill(
+        |state| {
+            state[0] = BaseElement::ONE;
+            state[1] = BaseElement::ONE;
+        },
+        |_, state| {
+            state[0] += state[1];
+            state[1] += state[0];
+        },
+    );
+    trace
+}
+
+// FIBONACCI AIR AND PROVER
+// ================================================================================================
+
+struct FibAir {
+    context: AirContext<BaseElement>,
+}
+
+impl Air for FibAir {
+    type BaseField = BaseElement;
+    type PublicInputs = ();
+
+    fn new(trace_info: TraceInfo, _pub_inputs: (), options: ProofOptions) -> Self {
+        // constraints are of high degree so that evaluations of the constraint composition
+        // polynomial take up a noticeable share of memory
+        let degrees = vec![TransitionConstraintDegree::new(8), TransitionConstraintDegree::new(8)];
+        Self {
+            context: AirContext::new(trace_info, degrees, 2, options),
+        }
+    }
+
+    fn context(&self) -> &AirContext<Self::BaseField> {
+        &self.context
+    }
+
+    fn evaluate_transition<E: FieldElement + From<Self::BaseField>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        _periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        let current = frame.current();
+        let next = frame.next();
+        // trace values are never zero, and thus multiplying by their powers does not change the
+        // set of valid traces
+        let factor = current[0].exp(7u32.into());
+        result[0] = (next[0] - (current[0] + current[1])) * factor;
+        result[1] = (next[1] - (current[1] + next[0])) * factor;
+    }
+
+    fn get_assertions(&self) -> Vec<Assertion<Self::BaseField>> {
+        vec![
+            Assertion::single(0, 0, BaseElement::ONE),
+            Assertion::single(1, 0, BaseElement::ONE),
+        ]
+    }
+}
+
+struct FibProver {
+    options: ProofOptions,
+    memory_budget: Option<usize>,
+}
+
+impl Prover for FibProver {
+    type BaseField = BaseElement;
+    type Air = FibAir;
+    type Trace = TraceTable<BaseElement>;
+    type HashFn = Blake3;
+    type RandomCoin = DefaultRandomCoin<Blake3>;
+    type TraceLde<E: FieldElement<BaseField = Self::BaseField>> = DefaultTraceLde<E, Self::HashFn>;
+    type ConstraintEvaluator<'a, E: FieldElement<BaseField = Self::BaseField>> =
+        DefaultConstraintEvaluator<'a, Self::Air, E>;
+
+    fn get_pub_inputs(&self, _trace: &Self::Trace) {}
+
+    fn options(&self) -> &ProofOptions {
+        &self.options
+    }
+
+    fn memory_budget(&self) -> Option<usize> {
+        self.memory_budget
+    }
+
+    fn new_trace_lde<E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        trace_info: &TraceInfo,
+        main_trace: &ColMatrix<Self::BaseField>,
+        domain: &StarkDomain<Self::BaseField>,
+    ) -> (Self::TraceLde<E>, TracePolyTable<E>) {
+        DefaultTraceLde::new(trace_info, main_trace, domain)
+    }
+
+    fn new_evaluator<'a, E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        air: &'a FibAir,
+        aux_rand_elements: AuxTraceRandElements<E>,
+        composition_coefficients: ConstraintCompositionCoefficients<E>,
+    ) -> Self::ConstraintEvaluator<'a, E> {
+        DefaultConstraintEvaluator::new(air, aux_rand_elements, composition_coefficients)
+    }
+}
@@ -0,0 +1,148 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use pyo3::{exceptions::PyValueError, prelude::*, types::PyBytes};
+use winterfell::{
+    math::{fields, FieldElement, StarkField},
+    Deserializable, Serializable,
+};
+
+// FIELD ELEMENT CLASSES
+// ================================================================================================
+
+/// Defines a Python class wrapping elements of the specified base field.
+///
+/// Elements are constructed from integers in the range [0, MODULUS), and support arithmetic
+/// operators (`+`, `-`, `*`, `/`, unary `-`, and `**` with a non-negative integer exponent) with
+/// other elements of the same field. Elements are converted back into integers via `int()`, and
+/// into bytes via `to_bytes()`, which returns the canonical little-endian representation of the
+/// element.
+macro_rules! field_class {
+    ($class:ident, $name:literal, $element:ty, $int:ty) => {
+        #[doc = concat!("Element of ", $name, " field.")]
+        #[pyclass(name = $name, module = "winterfell", frozen, eq)]
+        #[derive(Clone, PartialEq, Eq)]
+        pub struct $class(pub $element);
+
+        #[pymethods]
+        impl $class {
+            /// Modulus of the field.
+            #[classattr]
+            const MODULUS: $int = <$element>::MODULUS;
+
+            /// Number of bits needed to represent the modulus of the field.
+            #[classattr]
+            const MODULUS_BITS: u32 = <$element>::MODULUS_BITS;
+
+            /// Number of bytes in the serialized form of an element.
+            #[classattr]
+            const ELEMENT_BYTES: usize = <$element>::ELEMENT_BYTES;
+
+            #[new]
+            fn new(value: $int) -> PyResult<Self> {
+                if value >= <$element>::MODULUS {
+                    return Err(PyValueError::new_err(format!(
+                        "value {value} is not smaller than the field modulus"
+                    )));
+                }
+                Ok(Self(<$element>::new(value)))
+            }
+
+            /// Returns the multiplicative identity of the field.
+            #[staticmethod]
+            fn one() -> Self {
+                Self(<$element>::ONE)
+            }
+
+            /// Returns the additive identity of the field.
+            #[staticmethod]
+            fn zero() -> Self {
+                Self(<$element>::ZERO)
+            }
+
+            /// Returns a root of unity of order 2^n.
+            #[staticmethod]
+            fn get_root_of_unity(n: u32) -> PyResult<Self> {
+                if n == 0 || n > <$element>::TWO_ADICITY {
+                    return Err(PyValueError::new_err(format!(
+                        "order of the root of unity must be between 1 and {}",
+                        <$element>::TWO_ADICITY
+                    )));
+                }
+                Ok(Self(<$element>::get_root_of_unity(n)))
+            }
+
+            /// Returns an element read from its canonical little-endian representation.
+            #[staticmethod]
+            fn from_bytes(bytes: &[u8]) -> PyResult<Self> {
+                <$element>::read_from_bytes(bytes)
+                    .map(Self)
+                    .map_err(|err| PyValueError::new_err(err.to_string()))
+            }
+
+            /// Returns the canonical little-endian representation of this element.
+            fn to_bytes<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+                PyBytes::new(py, &self.0.to_bytes())
+            }
+
+            /// Returns the multiplicative inverse of this element; the inverse of zero is zero.
+            fn inv(&self) -> Self {
+                Self(self.0.inv())
+            }
+
+            fn __int__(&self) -> $int {
+                self.0.as_int()
+            }
+
+            fn __hash__(&self) -> u64 {
+                self.0.as_int() as u64
+            }
+
+            fn __repr__(&self) -> String {
+                format!("{}({})", $name, self.0.as_int())
+            }
+
+            fn __str__(&self) -> String {
+                self.0.as_int().to_string()
+            }
+
+            fn __add__(&self, other: &Self) -> Self {
+                Self(self.0 + other.0)
+            }
+
+            fn __sub__(&self, other: &Self) -> Self {
+                Self(self.0 - other.0)
+            }
+
+            fn __mul__(&self, other: &Self) -> Self {
+                Self(self.0 * other.0)
+            }
+
+            fn __truediv__(&self, other: &Self) -> PyResult<Self> {
+                if other.0 == <$element>::ZERO {
+                    return Err(pyo3::exceptions::PyZeroDivisionError::new_err(
+                        "division by zero field element",
+                    ));
+                }
+                Ok(Self(self.0 / other.0))
+            }
+
+            fn __neg__(&self) -> Self {
+                Self(-self.0)
+            }
+
+            fn __pow__(&self, exponent: $int, modulo: Option<$int>) -> PyResult<Self> {
+                if modulo.is_some() {
+                    return Err(PyValueError::new_err("modular exponentiation is not supported"));
+                }
+                Ok(Self(self.0.exp(exponent)))
+            }
+        }
+    };
+}
+
+field_class!(F62, "F62", fields::f62::BaseElement, u64);
+field_class!(F64, "F64", fields::f64::BaseElement, u64);
+field_class!(F128, "F128", fields::f128::BaseElement, u128);
@@ -0,0 +1,210 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+//! This crate contains Python bindings for Winterfell built with [PyO3](https://pyo3.rs).
+//!
+//! The bindings are intended for experimentation: scripting parameter sweeps, generating test
+//! vectors for other implementations, and validating external verifiers against the reference
+//! implementation. The `winterfell` Python module exposes:
+//!
+//! * Field element classes `F62`, `F64`, and `F128` supporting field arithmetic.
+//! * `hash()` and `merge()` functions for hash functions which can be recorded in proofs.
+//! * `Proof` class for reading and writing proofs (in binary, compressed, and canonical JSON
+//!   forms) and inspecting proof parameters.
+//! * `VerifierError` exception raised by verification functions.
+//!
+//! Since the verifier is generic over the computation being verified, verification functions
+//! need to be defined for each computation separately. This can be done in a crate which builds
+//! its own Python module via the [export_verifier!] macro and includes the classes and functions
+//! listed above via [register()]:
+//!
+//! ```ignore
+//! use pyo3::prelude::*;
+//!
+//! winterfell_py::export_verifier!(
+//!     verify_fib,
+//!     FibAir,
+//!     Blake3_256<BaseElement>,
+//!     AcceptableOptions::MinConjecturedSecurity(96)
+//! );
+//!
+//! #[pymodule]
+//! fn fib(m: &Bound<'_, PyModule>) -> PyResult<()> {
+//!     winterfell_py::register(m)?;
+//!     m.add_function(wrap_pyfunction!(verify_fib, m)?)
+//! }
+//! ```
+
+use core::str::FromStr;
+use pyo3::{
+    create_exception,
+    exceptions::{PyException, PyValueError},
+    prelude::*,
+    types::PyBytes,
+};
+use winterfell::{
+    crypto::{hashers, ElementHasher, HashFunction, Hasher, RandomCoin},
+    math::fields,
+    AcceptableOptions, Air, ByteReader, Deserializable, DeserializationError, Serializable,
+    SliceReader,
+};
+
+pub use pyo3;
+pub use winterfell;
+
+mod field;
+pub use field::{F128, F62, F64};
+
+mod proof;
+pub use proof::Proof;
+
+create_exception!(
+    winterfell,
+    VerifierError,
+    PyException,
+    "Raised when a proof cannot be verified."
+);
+
+// PYTHON MODULE
+// ================================================================================================
+
+/// Winterfell STARK prover/verifier.
+#[pymodule]
+#[pyo3(name = "winterfell")]
+fn winterfell_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    register(m)
+}
+
+/// Adds classes, functions, and exceptions of the `winterfell` Python module to the specified
+/// module.
+pub fn register(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<F62>()?;
+    m.add_class::<F64>()?;
+    m.add_class::<F128>()?;
+    m.add_class::<Proof>()?;
+    m.add_function(wrap_pyfunction!(hash, m)?)?;
+    m.add_function(wrap_pyfunction!(merge, m)?)?;
+    m.add("VerifierError", m.py().get_type::<VerifierError>())?;
+    Ok(())
+}
+
+// HASHING
+// ================================================================================================
+
+/// Returns the digest of the specified bytes computed with the specified hash function
+/// (`"blake3_192"`, `"blake3_256"`, `"sha3_256"`, or `"keccak256"`).
+#[pyfunction]
+fn hash<'py>(py: Python<'py>, hash_function: &str, data: &[u8]) -> PyResult<Bound<'py, PyBytes>> {
+    // hashing of bytes does not depend on the base field
+    type B = fields::f128::BaseElement;
+    let digest = match parse_hash_function(hash_function)? {
+        HashFunction::Blake3_192 => hashers::Blake3_192::<B>::hash(data).to_bytes(),
+        HashFunction::Blake3_256 => hashers::Blake3_256::<B>::hash(data).to_bytes(),
+        HashFunction::Sha3_256 => hashers::Sha3_256::<B>::hash(data).to_bytes(),
+        HashFunction::Keccak256 => hashers::Keccak256::<B>::hash(data).to_bytes(),
+    };
+    Ok(PyBytes::new(py, &digest))
+}
+
+/// Returns the digest of the concatenation of two digests computed with the specified hash
+/// function, in the same way as internal nodes of Merkle trees are computed.
+#[pyfunction]
+fn merge<'py>(
+    py: Python<'py>,
+    hash_function: &str,
+    left: &[u8],
+    right: &[u8],
+) -> PyResult<Bound<'py, PyBytes>> {
+    type B = fields::f128::BaseElement;
+    let digest = match parse_hash_function(hash_function)? {
+        HashFunction::Blake3_192 => merge_digests::<hashers::Blake3_192<B>>(left, right),
+        HashFunction::Blake3_256 => merge_digests::<hashers::Blake3_256<B>>(left, right),
+        HashFunction::Sha3_256 => merge_digests::<hashers::Sha3_256<B>>(left, right),
+        HashFunction::Keccak256 => merge_digests::<hashers::Keccak256<B>>(left, right),
+    };
+    Ok(PyBytes::new(py, &digest.map_err(to_value_error)?))
+}
+
+// VERIFIER
+// ================================================================================================
+
+/// Verifies a proof against serialized public inputs of the computation specified by `AIR`,
+/// `HashFn`, and `RandCoin` type parameters.
+///
+/// This is the function called by verification functions defined via [export_verifier!]. All
+/// bytes of the public inputs must be consumed during deserialization.
+///
+/// # Errors
+/// Returns `ValueError` if the public inputs could not be deserialized, and [VerifierError] if the
+/// proof is not valid.
+pub fn verify_proof<AIR, HashFn, RandCoin>(
+    proof: &Proof,
+    pub_inputs: &[u8],
+    acceptable_options: &AcceptableOptions,
+) -> PyResult<()>
+where
+    AIR: Air,
+    AIR::PublicInputs: Deserializable,
+    HashFn: ElementHasher<BaseField = AIR::BaseField>,
+    RandCoin: RandomCoin<BaseField = AIR::BaseField, Hasher = HashFn>,
+{
+    let pub_inputs = read_pub_inputs::<AIR::PublicInputs>(pub_inputs).map_err(to_value_error)?;
+    winterfell::verify::<AIR, HashFn, RandCoin>(proof.0.clone(), pub_inputs, acceptable_options)
+        .map_err(|err| VerifierError::new_err(err.to_string()))
+}
+
+/// Defines a Python function which verifies proofs of a specific computation.
+///
+/// The macro takes the name of the function to define, the [Air] of the computation, the hash
+/// function used to generate proofs, and an expression evaluating to [AcceptableOptions]. The
+/// defined function takes a `Proof` and serialized public inputs (`bytes`), and raises
+/// `VerifierError` if the proof is not valid (see [verify_proof()] for details). Proofs are
+/// expected to be generated using
+/// [DefaultRandomCoin](winterfell::crypto::DefaultRandomCoin).
+///
+/// The crate invoking the macro must depend on `pyo3` crate re-exported as [pyo3].
+#[macro_export]
+macro_rules! export_verifier {
+    ($name:ident, $air:ty, $hash_fn:ty, $acceptable_options:expr) => {
+        #[::pyo3::pyfunction]
+        pub fn $name(proof: &$crate::Proof, pub_inputs: &[u8]) -> ::pyo3::PyResult<()> {
+            $crate::verify_proof::<
+                $air,
+                $hash_fn,
+                $crate::winterfell::crypto::DefaultRandomCoin<$hash_fn>,
+            >(proof, pub_inputs, &$acceptable_options)
+        }
+    };
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Parses the name of a hash function, raising `ValueError` if the name is not known.
+fn parse_hash_function(name: &str) -> PyResult<HashFunction> {
+    HashFunction::from_str(name)
+        .map_err(|_| PyValueError::new_err(format!("unknown hash function '{name}'")))
+}
+
+/// Converts a deserialization error into Python `ValueError`.
+fn to_value_error(err: DeserializationError) -> PyErr {
+    PyValueError::new_err(err.to_string())
+}
+
+fn merge_digests<H: Hasher>(left: &[u8], right: &[u8]) -> Result<Vec<u8>, DeserializationError> {
+    let left = H::Digest::read_from_bytes(left)?;
+    let right = H::Digest::read_from_bytes(right)?;
+    Ok(H::merge(&[left, right]).to_bytes())
+}
+
+/// Deserializes public inputs from the provided bytes making sure all bytes are consumed.
+fn read_pub_inputs<P: Deserializable>(bytes: &[u8]) -> Result<P, DeserializationError> {
+    let mut source = SliceReader::new(bytes);
+    let pub_inputs = P::read_from(&mut source)?;
+    if source.has_more_bytes() {
+        return Err(DeserializationError::UnconsumedBytes);
+    }
+    Ok(pub_inputs)
+}
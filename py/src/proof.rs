@@ -0,0 +1,205 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use crate::{parse_hash_function, to_value_error};
+use pyo3::{exceptions::PyValueError, prelude::*, types::PyBytes};
+use std::collections::HashMap;
+use winterfell::{
+    crypto::{hashers, HashFunction},
+    math::{fields, StarkField},
+    proof::CompressionCodec,
+    DeserializationError, FieldExtension, StarkProof,
+};
+
+// PROOF
+// ================================================================================================
+
+/// A proof generated by Winterfell prover.
+///
+/// Proofs are read via `Proof.from_bytes()` (compressed proofs are decompressed transparently)
+/// or `Proof.from_json()`, and written via `to_bytes()`, `to_compressed_bytes()`, or `to_json()`.
+#[pyclass(name = "Proof", module = "winterfell", frozen, eq)]
+#[derive(Clone, PartialEq, Eq)]
+pub struct Proof(pub StarkProof);
+
+#[pymethods]
+impl Proof {
+    /// Returns a proof read from the specified bytes.
+    #[staticmethod]
+    fn from_bytes(bytes: &[u8]) -> PyResult<Self> {
+        StarkProof::from_bytes(bytes).map(Self).map_err(to_value_error)
+    }
+
+    /// Returns a proof decoded from the specified canonical JSON encoding.
+    ///
+    /// The name of the base field (`"f62"`, `"f64"`, or `"f128"`) and the name of the hash
+    /// function used to generate the proof must be specified.
+    #[staticmethod]
+    fn from_json(json: &str, field: &str, hash_function: &str) -> PyResult<Self> {
+        let hash_fn = parse_hash_function(hash_function)?;
+        let proof = match field {
+            "f62" => from_json::<fields::f62::BaseElement>(json, hash_fn),
+            "f64" => from_json::<fields::f64::BaseElement>(json, hash_fn),
+            "f128" => from_json::<fields::f128::BaseElement>(json, hash_fn),
+            _ => return Err(PyValueError::new_err(format!("unknown field '{field}'"))),
+        };
+        proof.map(Self).map_err(to_value_error)
+    }
+
+    /// Returns the serialized form of this proof.
+    fn to_bytes<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        PyBytes::new(py, &self.0.to_bytes())
+    }
+
+    /// Returns the serialized form of this proof compressed with the specified codec
+    /// (`"deflate"` or `"zstd"`).
+    fn to_compressed_bytes<'py>(
+        &self,
+        py: Python<'py>,
+        codec: &str,
+    ) -> PyResult<Bound<'py, PyBytes>> {
+        let codec = match codec {
+            "deflate" => CompressionCodec::Deflate,
+            "zstd" => CompressionCodec::Zstd,
+            _ => return Err(PyValueError::new_err(format!("unknown compression codec '{codec}'"))),
+        };
+        Ok(PyBytes::new(py, &self.0.to_compressed_bytes(codec)))
+    }
+
+    /// Returns the canonical JSON encoding of this proof.
+    ///
+    /// The base field is determined from the proof context; the hash function must be specified
+    /// unless it is recorded in the proof context.
+    #[pyo3(signature = (hash_function=None))]
+    fn to_json(&self, hash_function: Option<&str>) -> PyResult<String> {
+        let hash_fn = match (hash_function, self.0.hash_function()) {
+            (Some(name), _) => parse_hash_function(name)?,
+            (None, Some(hash_fn)) => hash_fn,
+            (None, None) => {
+                return Err(PyValueError::new_err(
+                    "hash function is not recorded in the proof and must be specified",
+                ))
+            }
+        };
+        let modulus = self.0.context.field_modulus_bytes();
+        let json = if modulus == fields::f62::BaseElement::get_modulus_le_bytes() {
+            to_json::<fields::f62::BaseElement>(&self.0, hash_fn)
+        } else if modulus == fields::f64::BaseElement::get_modulus_le_bytes() {
+            to_json::<fields::f64::BaseElement>(&self.0, hash_fn)
+        } else if modulus == fields::f128::BaseElement::get_modulus_le_bytes() {
+            to_json::<fields::f128::BaseElement>(&self.0, hash_fn)
+        } else {
+            return Err(PyValueError::new_err("base field of the proof is not supported"));
+        };
+        json.map_err(to_value_error)
+    }
+
+    /// Number of columns in all segments of the execution trace.
+    #[getter]
+    fn trace_width(&self) -> usize {
+        self.0.trace_width()
+    }
+
+    /// Number of steps in the execution trace.
+    #[getter]
+    fn trace_length(&self) -> usize {
+        self.0.context.trace_length()
+    }
+
+    /// Number of queries made by the verifier.
+    #[getter]
+    fn num_queries(&self) -> usize {
+        self.0.num_queries()
+    }
+
+    /// Blowup factor of the low-degree extension.
+    #[getter]
+    fn blowup_factor(&self) -> usize {
+        self.0.context.options().blowup_factor()
+    }
+
+    /// Number of bits in the modulus of the base field.
+    #[getter]
+    fn field_modulus_bits(&self) -> u32 {
+        self.0.field_modulus_bits()
+    }
+
+    /// Degree of the field extension used for the composition polynomial (1, 2, or 3).
+    #[getter]
+    fn field_extension(&self) -> u32 {
+        match self.0.field_extension() {
+            FieldExtension::None => 1,
+            FieldExtension::Quadratic => 2,
+            FieldExtension::Cubic => 3,
+        }
+    }
+
+    /// Name of the hash function recorded in the proof, or `None` if it is not recorded.
+    #[getter]
+    fn hash_function(&self) -> Option<String> {
+        self.0.hash_function().map(|hash_fn| hash_fn.to_string())
+    }
+
+    /// Returns conjectured (or proven) security level of this proof in bits, or `None` if the
+    /// hash function is not recorded in the proof.
+    #[pyo3(signature = (conjectured=true))]
+    fn security_level(&self, conjectured: bool) -> Option<u32> {
+        self.0.security_level_auto(conjectured)
+    }
+
+    /// Returns the number of bytes taken by each section of the serialized proof.
+    fn size_breakdown(&self) -> HashMap<&'static str, usize> {
+        let size = self.0.size_breakdown();
+        HashMap::from([
+            ("context", size.context),
+            ("commitments", size.commitments),
+            ("trace_queries", size.trace_queries),
+            ("constraint_queries", size.constraint_queries),
+            ("ood_frame", size.ood_frame),
+            ("fri_proof", size.fri_proof),
+            ("other", size.other),
+        ])
+    }
+
+    fn __len__(&self) -> usize {
+        self.0.to_bytes().len()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Proof(trace_width={}, trace_length={}, num_queries={})",
+            self.0.trace_width(),
+            self.0.context.trace_length(),
+            self.0.num_queries()
+        )
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+fn to_json<B: StarkField>(
+    proof: &StarkProof,
+    hash_fn: HashFunction,
+) -> Result<String, DeserializationError> {
+    match hash_fn {
+        HashFunction::Blake3_192 => proof.to_json::<B, hashers::Blake3_192<B>>(),
+        HashFunction::Blake3_256 => proof.to_json::<B, hashers::Blake3_256<B>>(),
+        HashFunction::Sha3_256 => proof.to_json::<B, hashers::Sha3_256<B>>(),
+        HashFunction::Keccak256 => proof.to_json::<B, hashers::Keccak256<B>>(),
+    }
+}
+
+fn from_json<B: StarkField>(
+    json: &str,
+    hash_fn: HashFunction,
+) -> Result<StarkProof, DeserializationError> {
+    match hash_fn {
+        HashFunction::Blake3_192 => StarkProof::from_json::<B, hashers::Blake3_192<B>>(json),
+        HashFunction::Blake3_256 => StarkProof::from_json::<B, hashers::Blake3_256<B>>(json),
+        HashFunction::Sha3_256 => StarkProof::from_json::<B, hashers::Sha3_256<B>>(json),
+        HashFunction::Keccak256 => StarkProof::from_json::<B, hashers::Keccak256<B>>(json),
+    }
+}
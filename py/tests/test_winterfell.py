@@ -0,0 +1,71 @@
+# Copyright (c) Facebook, Inc. and its affiliates.
+#
+# This source code is licensed under the MIT license found in the
+# LICENSE file in the root directory of this source tree.
+
+"""Tests for the winterfell Python module; run via `pytest` after `maturin develop`."""
+
+import hashlib
+
+import pytest
+import winterfell
+from winterfell import F62, F64, F128
+
+
+@pytest.mark.parametrize("field", [F62, F64, F128])
+def test_field_arithmetic(field):
+    a = field(field.MODULUS - 1)
+    b = field(5)
+    assert int(a + b) == 4
+    assert int(b - a) == 6
+    assert a * b == -field(5)
+    assert (a / b) * b == a
+    assert b * b.inv() == field.one()
+    assert b ** 3 == field(125)
+    assert field.zero() - b == -b
+    with pytest.raises(ZeroDivisionError):
+        b / field.zero()
+
+
+@pytest.mark.parametrize("field", [F62, F64, F128])
+def test_field_encoding(field):
+    value = field(field.MODULUS - 2)
+    assert len(value.to_bytes()) == field.ELEMENT_BYTES
+    assert field.from_bytes(value.to_bytes()) == value
+    assert int.from_bytes(value.to_bytes(), "little") == field.MODULUS - 2
+    with pytest.raises(ValueError):
+        field(field.MODULUS)
+
+
+@pytest.mark.parametrize("field", [F62, F64, F128])
+def test_roots_of_unity(field):
+    root = field.get_root_of_unity(4)
+    assert root ** 16 == field.one()
+    assert root ** 8 != field.one()
+
+
+def test_hash():
+    data = b"winterfell"
+    assert winterfell.hash("sha3_256", data) == hashlib.sha3_256(data).digest()
+    assert len(winterfell.hash("blake3_192", data)) == 24
+    assert len(winterfell.hash("blake3_256", data)) == 32
+    assert len(winterfell.hash("keccak256", data)) == 32
+    with pytest.raises(ValueError):
+        winterfell.hash("md5", data)
+
+
+def test_merge():
+    left = winterfell.hash("sha3_256", b"left")
+    right = winterfell.hash("sha3_256", b"right")
+    assert winterfell.merge("sha3_256", left, right) == hashlib.sha3_256(left + right).digest()
+    with pytest.raises(ValueError):
+        winterfell.merge("sha3_256", left, right[1:])
+
+
+def test_malformed_proofs():
+    with pytest.raises(ValueError):
+        winterfell.Proof.from_bytes(b"")
+    with pytest.raises(ValueError):
+        winterfell.Proof.from_bytes(bytes([0, 2, 1, 2, 3]))
+    with pytest.raises(ValueError):
+        winterfell.Proof.from_json("{}", "f128", "blake3_256")
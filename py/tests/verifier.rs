@@ -0,0 +1,146 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use winterfell_py::{
+    winterfell::{
+        crypto::{hashers::Blake3_256, DefaultRandomCoin},
+        math::{fields::f128::BaseElement, FieldElement},
+        matrix::ColMatrix,
+        AcceptableOptions, Air, AirContext, Assertion, AuxTraceRandElements,
+        ConstraintCompositionCoefficients, DefaultConstraintEvaluator, DefaultTraceLde,
+        EvaluationFrame, FieldExtension, ProofOptions, Prover, Serializable, StarkDomain, Trace,
+        TraceInfo, TracePolyTable, TraceTable, TransitionConstraintDegree,
+    },
+    Proof,
+};
+
+type Blake3 = Blake3_256<BaseElement>;
+
+winterfell_py::export_verifier!(
+    verify_fib,
+    FibAir,
+    Blake3,
+    AcceptableOptions::MinConjecturedSecurity(40)
+);
+
+// TESTS
+// ================================================================================================
+
+#[test]
+fn exported_verifier() {
+    let prover = FibProver {
+        options: ProofOptions::new(28, 8, 0, FieldExtension::None, 4, 7),
+    };
+    let trace = build_trace(64);
+    let pub_inputs = prover.get_pub_inputs(&trace).to_bytes();
+    let proof = Proof(prover.prove(trace).unwrap());
+    assert!(verify_fib(&proof, &pub_inputs).is_ok());
+
+    // wrong public inputs and trailing bytes must be rejected
+    assert!(verify_fib(&proof, &BaseElement::ONE.to_bytes()).is_err());
+    assert!(verify_fib(&proof, &[pub_inputs, vec![0]].concat()).is_err());
+}
+
+// FIBONACCI AIR AND PROVER
+// ================================================================================================
+
+fn build_trace(length: usize) -> TraceTable<BaseElement> {
+    let mut trace = TraceTable::new(2, length);
+    trace.fill(
+        |state| {
+            state[0] = BaseElement::ONE;
+            state[1] = BaseElement::ONE;
+        },
+        |_, state| {
+            state[0] += state[1];
+            state[1] += state[0];
+        },
+    );
+    trace
+}
+
+pub struct FibAir {
+    context: AirContext<BaseElement>,
+    result: BaseElement,
+}
+
+impl Air for FibAir {
+    type BaseField = BaseElement;
+    type PublicInputs = BaseElement;
+
+    fn new(trace_info: TraceInfo, result: BaseElement, options: ProofOptions) -> Self {
+        let degrees = vec![TransitionConstraintDegree::new(1), TransitionConstraintDegree::new(1)];
+        FibAir {
+            context: AirContext::new(trace_info, degrees, 3, options),
+            result,
+        }
+    }
+
+    fn context(&self) -> &AirContext<Self::BaseField> {
+        &self.context
+    }
+
+    fn evaluate_transition<E: FieldElement + From<Self::BaseField>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        _periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        let current = frame.current();
+        let next = frame.next();
+        result[0] = next[0] - (current[0] + current[1]);
+        result[1] = next[1] - (current[1] + next[0]);
+    }
+
+    fn get_assertions(&self) -> Vec<Assertion<Self::BaseField>> {
+        let last_step = self.trace_length() - 1;
+        vec![
+            Assertion::single(0, 0, BaseElement::ONE),
+            Assertion::single(1, 0, BaseElement::ONE),
+            Assertion::single(1, last_step, self.result),
+        ]
+    }
+}
+
+struct FibProver {
+    options: ProofOptions,
+}
+
+impl Prover for FibProver {
+    type BaseField = BaseElement;
+    type Air = FibAir;
+    type Trace = TraceTable<BaseElement>;
+    type HashFn = Blake3;
+    type RandomCoin = DefaultRandomCoin<Blake3>;
+    type TraceLde<E: FieldElement<BaseField = Self::BaseField>> = DefaultTraceLde<E, Self::HashFn>;
+    type ConstraintEvaluator<'a, E: FieldElement<BaseField = Self::BaseField>> =
+        DefaultConstraintEvaluator<'a, Self::Air, E>;
+
+    fn get_pub_inputs(&self, trace: &Self::Trace) -> BaseElement {
+        trace.get(1, trace.length() - 1)
+    }
+
+    fn options(&self) -> &ProofOptions {
+        &self.options
+    }
+
+    fn new_trace_lde<E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        trace_info: &TraceInfo,
+        main_trace: &ColMatrix<Self::BaseField>,
+        domain: &StarkDomain<Self::BaseField>,
+    ) -> (Self::TraceLde<E>, TracePolyTable<E>) {
+        DefaultTraceLde::new(trace_info, main_trace, domain)
+    }
+
+    fn new_evaluator<'a, E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        air: &'a FibAir,
+        aux_rand_elements: AuxTraceRandElements<E>,
+        composition_coefficients: ConstraintCompositionCoefficients<E>,
+    ) -> Self::ConstraintEvaluator<'a, E> {
+        DefaultConstraintEvaluator::new(air, aux_rand_elements, composition_coefficients)
+    }
+}
@@ -0,0 +1,125 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use crate::{
+    gadgets::rescue::{self, CYCLE_LENGTH},
+    BaseElement, PermutationRecord, STATE_WIDTH,
+};
+use air::{
+    Air, AirContext, Assertion, EvaluationFrame, ProofOptions, TraceInfo,
+    TransitionConstraintDegree,
+};
+use math::{FieldElement, ToElements};
+
+// PERMUTATION BATCH
+// ================================================================================================
+
+/// A list of [Rp64_256](crate::Rp64_256) permutations proven by [PermutationAir].
+///
+/// The list is padded with permutations of the all-zero state so that the number of
+/// permutations is a power of two greater than one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PermutationBatch {
+    permutations: Vec<PermutationRecord>,
+}
+
+impl PermutationBatch {
+    /// Returns a batch consisting of the specified permutations followed by padding.
+    pub fn new(mut permutations: Vec<PermutationRecord>) -> Self {
+        let num_permutations = permutations.len().next_power_of_two().max(2);
+        let padding = PermutationRecord::new([BaseElement::ZERO; STATE_WIDTH]);
+        permutations.resize(num_permutations, padding);
+        Self { permutations }
+    }
+
+    /// Returns permutations of this batch including padding.
+    pub fn permutations(&self) -> &[PermutationRecord] {
+        &self.permutations
+    }
+
+    /// Returns length of the execution trace proving this batch.
+    pub fn trace_length(&self) -> usize {
+        self.permutations.len() * CYCLE_LENGTH
+    }
+}
+
+impl ToElements<BaseElement> for PermutationBatch {
+    fn to_elements(&self) -> Vec<BaseElement> {
+        self.permutations
+            .iter()
+            .flat_map(|p| p.input.iter().chain(p.output.iter()).copied())
+            .collect()
+    }
+}
+
+// PERMUTATION AIR
+// ================================================================================================
+
+/// AIR of a computation which applies the [Rp64_256](crate::Rp64_256) permutation to a list of
+/// states.
+///
+/// Each permutation occupies [CYCLE_LENGTH] rows of a trace with `STATE_WIDTH` columns: the
+/// first row holds the input state, and each of the following rows holds the state after a
+/// single round. Input and output states of all permutations are public inputs.
+pub struct PermutationAir {
+    context: AirContext<BaseElement>,
+    batch: PermutationBatch,
+}
+
+impl Air for PermutationAir {
+    type BaseField = BaseElement;
+    type PublicInputs = PermutationBatch;
+
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+    fn new(trace_info: TraceInfo, pub_inputs: PermutationBatch, options: ProofOptions) -> Self {
+        // round constants are absorbed by the degree of trace columns; only the round flag
+        // contributes to the degree of constraints
+        let degrees = vec![
+            TransitionConstraintDegree::with_cycles(
+                rescue::CONSTRAINT_DEGREE,
+                vec![CYCLE_LENGTH]
+            );
+            rescue::NUM_CONSTRAINTS
+        ];
+        assert_eq!(STATE_WIDTH, trace_info.width());
+        PermutationAir {
+            context: AirContext::new(trace_info, degrees, 2 * STATE_WIDTH, options),
+            batch: pub_inputs,
+        }
+    }
+
+    fn context(&self) -> &AirContext<Self::BaseField> {
+        &self.context
+    }
+
+    fn evaluate_transition<E: FieldElement + From<Self::BaseField>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        let flag = periodic_values[0];
+        let ark = &periodic_values[1..];
+        rescue::enforce_round(frame.current(), frame.next(), ark, flag, result);
+    }
+
+    fn get_assertions(&self) -> Vec<Assertion<Self::BaseField>> {
+        let mut result = Vec::with_capacity(2 * STATE_WIDTH);
+        for column in 0..STATE_WIDTH {
+            let inputs = self.batch.permutations.iter().map(|p| p.input[column]).collect();
+            result.push(Assertion::sequence(column, 0, CYCLE_LENGTH, inputs));
+        }
+        for column in 0..STATE_WIDTH {
+            let outputs = self.batch.permutations.iter().map(|p| p.output[column]).collect();
+            result.push(Assertion::sequence(column, CYCLE_LENGTH - 1, CYCLE_LENGTH, outputs));
+        }
+        result
+    }
+
+    fn get_periodic_column_values(&self) -> Vec<Vec<Self::BaseField>> {
+        rescue::get_periodic_column_values()
+    }
+}
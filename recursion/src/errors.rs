@@ -0,0 +1,92 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use core::fmt;
+use verifier::VerifierError;
+
+// WITNESS ERROR
+// ================================================================================================
+/// Represents an error returned when a verifier witness cannot be generated for a proof.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WitnessError {
+    /// The proof was generated in an extension field of the specified degree; witnesses can be
+    /// generated only for proofs generated in the base field.
+    UnsupportedFieldExtension(u32),
+    /// The execution trace consists of more than one segment; witnesses can be generated only
+    /// for computations without auxiliary trace segments.
+    UnsupportedAuxTraceSegments(usize),
+    /// FRI folding factor of the proof is not 2.
+    UnsupportedFoldingFactor(usize),
+    /// Query positions of the proof are normalized; witnesses can be generated only for proofs
+    /// with independently drawn query positions.
+    UnsupportedNormalizedQueries,
+    /// Proof-of-work is performed after each FRI layer commitment of the proof; witnesses can be
+    /// generated only for proofs without per-layer proof-of-work.
+    UnsupportedFriLayerGrinding,
+    /// FRI query values known to the verifier are omitted from the proof; witnesses can be
+    /// generated only for proofs in which all FRI query values are included.
+    UnsupportedFriQueryDeduplication,
+    /// Low-degree of the constraint composition polynomial is proven using a protocol other than
+    /// FRI; witnesses can be generated only for FRI proofs.
+    UnsupportedLowDegreeTest,
+    /// Rows of the execution trace are hashed into commitment leaves using an encoding other
+    /// than the packed one; witnesses can be generated only for proofs with packed leaves.
+    UnsupportedLeafEncoding,
+    /// Query positions are sampled using a strategy other than uniform sampling; witnesses can be
+    /// generated only for proofs with uniformly sampled query positions.
+    UnsupportedQuerySampling,
+    /// Trace polynomials are opened at the specified number of out-of-domain points; witnesses
+    /// can be generated only for proofs with openings at the default points z and z * g.
+    UnsupportedOodPoints(usize),
+    /// The proof could not be verified.
+    InvalidProof(VerifierError),
+}
+
+impl fmt::Display for WitnessError {
+    #[rustfmt::skip]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnsupportedFieldExtension(degree) => {
+                write!(f, "field extension of degree {degree} is not supported; proofs must be generated in the base field")
+            }
+            Self::UnsupportedAuxTraceSegments(num_segments) => {
+                write!(f, "execution trace has {num_segments} auxiliary segments, but auxiliary segments are not supported")
+            }
+            Self::UnsupportedFoldingFactor(factor) => {
+                write!(f, "folding factor {factor} is not supported; folding factor must be 2")
+            }
+            Self::UnsupportedNormalizedQueries => {
+                write!(f, "normalized query positions are not supported")
+            }
+            Self::UnsupportedFriLayerGrinding => {
+                write!(f, "FRI layer proof-of-work is not supported")
+            }
+            Self::UnsupportedFriQueryDeduplication => {
+                write!(f, "FRI query deduplication is not supported")
+            }
+            Self::UnsupportedLowDegreeTest => {
+                write!(f, "low-degree tests other than FRI are not supported")
+            }
+            Self::UnsupportedLeafEncoding => {
+                write!(f, "leaf encodings other than packed are not supported")
+            }
+            Self::UnsupportedQuerySampling => {
+                write!(f, "query sampling strategies other than uniform are not supported")
+            }
+            Self::UnsupportedOodPoints(num_points) => {
+                write!(f, "opening trace polynomials at {num_points} out-of-domain points is not supported; only the default 2 points are supported")
+            }
+            Self::InvalidProof(err) => {
+                write!(f, "proof is not valid: {err}")
+            }
+        }
+    }
+}
+
+impl From<VerifierError> for WitnessError {
+    fn from(err: VerifierError) -> Self {
+        Self::InvalidProof(err)
+    }
+}
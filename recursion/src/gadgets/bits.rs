@@ -0,0 +1,41 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+//! Constraints for binary decomposition of field elements.
+//!
+//! The verifier interprets some field elements as integers: query positions are the lowest
+//! bits of elements drawn from the public coin, and the proof-of-work is checked by counting
+//! trailing zeros of a hash output element. Both operations are expressed via a binary
+//! decomposition of the element.
+
+use crate::BaseElement;
+use math::{FieldElement, StarkField};
+
+/// Enforces that `bits` is a binary decomposition of `value` (least significant bit first).
+///
+/// Writes `bits.len() + 1` constraints into `result`: one constraint per bit requiring the bit
+/// to be binary, and a constraint requiring the bits to sum up to `value`. If `bits` contains
+/// 64 values, callers must additionally ensure that the decomposition is canonical (i.e., that
+/// the integer is smaller than the field modulus).
+pub fn enforce_decomposition<E>(value: E, bits: &[E], result: &mut [E])
+where
+    E: FieldElement + From<BaseElement>,
+{
+    let mut sum = E::ZERO;
+    let mut power = E::ONE;
+    for (i, &bit) in bits.iter().enumerate() {
+        result[i] = bit * (bit - E::ONE);
+        sum += bit * power;
+        power = power.double();
+    }
+    result[bits.len()] = value - sum;
+}
+
+/// Returns the first `num_bits` bits of the canonical representation of `value` (least
+/// significant bit first).
+pub fn decompose(value: BaseElement, num_bits: usize) -> Vec<BaseElement> {
+    let value = value.as_int();
+    (0..num_bits).map(|i| BaseElement::from((value >> i) & 1)).collect()
+}
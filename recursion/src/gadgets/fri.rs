@@ -0,0 +1,33 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+//! Constraints for FRI folding with folding factor 2.
+//!
+//! A polynomial `f(x) = f_e(x^2) + x * f_o(x^2)` is folded into `f'(y) = f_e(y) + alpha * f_o(y)`.
+//! Given evaluations of `f` at `x` and `-x`, the value of the folded polynomial at `x^2` is
+//! `((x + alpha) * f(x) + (x - alpha) * f(-x)) / 2x`.
+
+use crate::BaseElement;
+use math::FieldElement;
+
+/// Returns the evaluation of the folding constraint, which is zero if and only if `folded` is
+/// the value at `x^2` of the polynomial folded from `f` using `alpha`, where `f_x` and
+/// `f_neg_x` are values of `f` at `x` and `-x`.
+///
+/// The constraint has degree 2.
+pub fn evaluate_folding<E: FieldElement>(x: E, alpha: E, f_x: E, f_neg_x: E, folded: E) -> E {
+    x.double() * folded - (x + alpha) * f_x - (x - alpha) * f_neg_x
+}
+
+/// Returns the value at `x^2` of the polynomial folded from `f` using `alpha`, where `f_x` and
+/// `f_neg_x` are values of `f` at `x` and `-x`.
+pub fn fold(
+    x: BaseElement,
+    alpha: BaseElement,
+    f_x: BaseElement,
+    f_neg_x: BaseElement,
+) -> BaseElement {
+    ((x + alpha) * f_x + (x - alpha) * f_neg_x) / x.double()
+}
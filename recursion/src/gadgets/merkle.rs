@@ -0,0 +1,43 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+//! Constraints for steps of Merkle path verification.
+//!
+//! A step of a path merges a node with its sibling; the order in which the two are merged is
+//! determined by the lowest bit of the node index. The merge itself is an invocation of the
+//! [Rp64_256](crate::Rp64_256) permutation, and thus, this gadget only constrains the input
+//! state of the permutation.
+
+use crate::{BaseElement, DIGEST_SIZE, STATE_WIDTH};
+use math::FieldElement;
+
+/// Number of constraints enforced by [enforce_merge_input()].
+pub const NUM_CONSTRAINTS: usize = 1 + STATE_WIDTH;
+
+/// Enforces that `state` is the input state of the permutation which merges `node` with
+/// `sibling`, where `bit` is the lowest bit of the node index.
+///
+/// The constraints require `bit` to be binary, the capacity portion of the state to be
+/// initialized for hashing two digests, and the rate portion of the state to hold `node`
+/// followed by `sibling` if `bit` is zero, and `sibling` followed by `node` otherwise.
+pub fn enforce_merge_input<E>(bit: E, node: &[E], sibling: &[E], state: &[E], result: &mut [E])
+where
+    E: FieldElement + From<BaseElement>,
+{
+    result[0] = bit * (bit - E::ONE);
+
+    // capacity portion of the state
+    result[1] = state[0] - E::from(2 * DIGEST_SIZE as u32);
+    result[2] = state[1];
+    result[3] = state[2];
+    result[4] = state[3];
+
+    // rate portion of the state
+    for i in 0..DIGEST_SIZE {
+        let delta = sibling[i] - node[i];
+        result[5 + i] = state[4 + i] - (node[i] + bit * delta);
+        result[5 + DIGEST_SIZE + i] = state[4 + DIGEST_SIZE + i] - (sibling[i] - bit * delta);
+    }
+}
@@ -0,0 +1,21 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+//! Constraint evaluators for the operations performed by the STARK verifier.
+//!
+//! Each gadget writes evaluations of its constraints into a slice, in the same way as
+//! [Air::evaluate_transition()](air::Air::evaluate_transition) does, so that gadgets can be
+//! combined into the transition constraints of an AIR. Gadgets are generic over the field in
+//! which constraints are evaluated; all constraints evaluate to zero if and only if the
+//! corresponding verifier operation was performed correctly.
+//!
+//! Rounds of the hash permutation are constrained by [rescue], which is re-exported from the
+//! general-purpose gadgets in [air::gadgets].
+
+pub mod bits;
+pub mod fri;
+pub mod merkle;
+
+pub use air::gadgets::rescue;
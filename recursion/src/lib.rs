@@ -0,0 +1,69 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+//! This crate contains building blocks for recursive verification of STARK proofs generated by
+//! the Winterfell prover, i.e., for proving statements of the form "I verified a Winterfell
+//! proof". Such statements can be used to aggregate many proofs into a single proof.
+//!
+//! Recursive verification requires every operation of the verifier to be expressible as
+//! constraints. This crate targets the following fixed parameter set, for which this is the
+//! case:
+//! * Base field is the 64-bit field [f64](math::fields::f64), and proofs are generated without
+//!   field extensions.
+//! * Commitments and the public coin use the algebraic [Rp64_256] hash function together with
+//!   [DefaultRandomCoin](crypto::DefaultRandomCoin). With [Rp64_256], elements drawn from the
+//!   public coin are simply the first element of a hash output, and thus, the coin can be
+//!   emulated by the permutation alone.
+//! * FRI folding factor is 2, and the execution trace consists of a single segment.
+//!
+//! The crate provides:
+//! * [VerifierWitness] - host-side witness generation which replays verification of a
+//!   [StarkProof](air::proof::StarkProof) and records every value computed by the verifier,
+//!   including all invocations of the hash permutation.
+//! * [gadgets] - constraint evaluators for the operations performed by the verifier:
+//!   permutation rounds, Merkle path steps, FRI folding, and binary decomposition of elements
+//!   drawn from the public coin.
+//! * [PermutationAir] and [PermutationProver] - a complete AIR and prover for the dominant cost
+//!   of verification: proving that all hash permutations recorded in a witness were computed
+//!   correctly.
+//!
+//! Evaluating constraints of the verified computation at the out-of-domain point depends on the
+//! computation itself; the witness contains all values needed to do this (out-of-domain frame,
+//! composition coefficients, and the out-of-domain point).
+
+pub use crypto::hashers::Rp64_256;
+pub use math::fields::f64::BaseElement;
+
+mod air;
+pub use crate::air::{PermutationAir, PermutationBatch};
+
+mod prover;
+pub use crate::prover::PermutationProver;
+
+pub mod gadgets;
+
+mod sponge;
+pub use sponge::PermutationRecord;
+
+mod witness;
+pub use witness::{FriFolding, MerkleStep, VerifierWitness};
+
+mod errors;
+pub use errors::WitnessError;
+
+#[cfg(test)]
+mod tests;
+
+// CONSTANTS
+// ================================================================================================
+
+/// Number of elements in the state of the [Rp64_256] permutation.
+pub const STATE_WIDTH: usize = Rp64_256::STATE_WIDTH;
+
+/// Number of elements in an [Rp64_256] digest.
+pub const DIGEST_SIZE: usize = 4;
+
+/// An [Rp64_256] digest represented by its elements.
+pub type Word = [BaseElement; DIGEST_SIZE];
@@ -0,0 +1,105 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use crate::{
+    gadgets::rescue::CYCLE_LENGTH, BaseElement, PermutationAir, PermutationBatch,
+    PermutationRecord, Rp64_256, STATE_WIDTH,
+};
+use air::{AuxTraceRandElements, ConstraintCompositionCoefficients, ProofOptions, TraceInfo};
+use core::marker::PhantomData;
+use crypto::{DefaultRandomCoin, ElementHasher};
+use math::FieldElement;
+use prover::{
+    matrix::ColMatrix, DefaultConstraintEvaluator, DefaultTraceLde, Prover, StarkDomain, Trace,
+    TracePolyTable, TraceTable,
+};
+
+// PERMUTATION PROVER
+// ================================================================================================
+
+/// Prover for [PermutationAir].
+pub struct PermutationProver<H: ElementHasher> {
+    options: ProofOptions,
+    _hasher: PhantomData<H>,
+}
+
+impl<H: ElementHasher> PermutationProver<H> {
+    /// Returns a new prover which generates proofs with the specified options.
+    pub fn new(options: ProofOptions) -> Self {
+        Self {
+            options,
+            _hasher: PhantomData,
+        }
+    }
+
+    /// Returns an execution trace of applying permutations of the specified batch.
+    pub fn build_trace(&self, batch: &PermutationBatch) -> TraceTable<BaseElement> {
+        let mut trace = TraceTable::new(STATE_WIDTH, batch.trace_length());
+        let permutations = batch.permutations();
+        trace.fill(
+            |state| state.copy_from_slice(&permutations[0].input),
+            |step, state| {
+                let round = step % CYCLE_LENGTH;
+                if round < CYCLE_LENGTH - 1 {
+                    let mut next: [BaseElement; STATE_WIDTH] = (*state).try_into().unwrap();
+                    Rp64_256::apply_round(&mut next, round);
+                    state.copy_from_slice(&next);
+                } else {
+                    state.copy_from_slice(&permutations[(step + 1) / CYCLE_LENGTH].input);
+                }
+            },
+        );
+        trace
+    }
+}
+
+impl<H> Prover for PermutationProver<H>
+where
+    H: ElementHasher<BaseField = BaseElement>,
+{
+    type BaseField = BaseElement;
+    type Air = PermutationAir;
+    type Trace = TraceTable<BaseElement>;
+    type HashFn = H;
+    type RandomCoin = DefaultRandomCoin<Self::HashFn>;
+    type TraceLde<E: FieldElement<BaseField = Self::BaseField>> = DefaultTraceLde<E, Self::HashFn>;
+    type ConstraintEvaluator<'a, E: FieldElement<BaseField = Self::BaseField>> =
+        DefaultConstraintEvaluator<'a, Self::Air, E>;
+
+    fn get_pub_inputs(&self, trace: &Self::Trace) -> PermutationBatch {
+        let permutations = (0..trace.length() / CYCLE_LENGTH)
+            .map(|i| {
+                let mut input = [BaseElement::ZERO; STATE_WIDTH];
+                let mut output = [BaseElement::ZERO; STATE_WIDTH];
+                trace.read_row_into(i * CYCLE_LENGTH, &mut input);
+                trace.read_row_into((i + 1) * CYCLE_LENGTH - 1, &mut output);
+                PermutationRecord { input, output }
+            })
+            .collect();
+        PermutationBatch::new(permutations)
+    }
+
+    fn options(&self) -> &ProofOptions {
+        &self.options
+    }
+
+    fn new_trace_lde<E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        trace_info: &TraceInfo,
+        main_trace: &ColMatrix<Self::BaseField>,
+        domain: &StarkDomain<Self::BaseField>,
+    ) -> (Self::TraceLde<E>, TracePolyTable<E>) {
+        DefaultTraceLde::new(trace_info, main_trace, domain)
+    }
+
+    fn new_evaluator<'a, E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        air: &'a Self::Air,
+        aux_rand_elements: AuxTraceRandElements<E>,
+        composition_coefficients: ConstraintCompositionCoefficients<E>,
+    ) -> Self::ConstraintEvaluator<'a, E> {
+        DefaultConstraintEvaluator::new(air, aux_rand_elements, composition_coefficients)
+    }
+}
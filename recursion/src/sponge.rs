@@ -0,0 +1,101 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use crate::{BaseElement, Rp64_256, Word, STATE_WIDTH};
+use math::{FieldElement, StarkField};
+
+// PERMUTATION RECORD
+// ================================================================================================
+
+/// Input and output states of a single invocation of the Rescue Prime permutation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PermutationRecord {
+    pub input: [BaseElement; STATE_WIDTH],
+    pub output: [BaseElement; STATE_WIDTH],
+}
+
+impl PermutationRecord {
+    /// Applies the permutation to the specified input state and returns the resulting record.
+    pub fn new(input: [BaseElement; STATE_WIDTH]) -> Self {
+        let mut output = input;
+        Rp64_256::apply_permutation(&mut output);
+        Self { input, output }
+    }
+}
+
+// RECORDING SPONGE
+// ================================================================================================
+
+/// Computes [Rp64_256] hashes while recording every invocation of the underlying permutation.
+///
+/// Results of all methods are identical to the results of the corresponding [Rp64_256] methods.
+#[derive(Debug, Default)]
+pub(crate) struct Sponge {
+    permutations: Vec<PermutationRecord>,
+}
+
+impl Sponge {
+    /// Returns the permutations recorded so far.
+    pub fn into_permutations(self) -> Vec<PermutationRecord> {
+        self.permutations
+    }
+
+    /// Equivalent of [Rp64_256::hash_elements()] for base field elements.
+    pub fn hash_elements(&mut self, elements: &[BaseElement]) -> Word {
+        let mut state = [BaseElement::ZERO; STATE_WIDTH];
+        state[Rp64_256::CAPACITY_RANGE.start] = BaseElement::new(elements.len() as u64);
+
+        let rate = Rp64_256::RATE_RANGE;
+        let mut i = 0;
+        for &element in elements {
+            state[rate.start + i] += element;
+            i += 1;
+            if i == rate.len() {
+                state = self.permute(state);
+                i = 0;
+            }
+        }
+        if i > 0 {
+            state = self.permute(state);
+        }
+
+        state[Rp64_256::DIGEST_RANGE].try_into().unwrap()
+    }
+
+    /// Equivalent of [Rp64_256::merge()].
+    pub fn merge(&mut self, a: &Word, b: &Word) -> Word {
+        let mut state = [BaseElement::ZERO; STATE_WIDTH];
+        let rate = Rp64_256::RATE_RANGE;
+        state[rate.start..rate.start + 4].copy_from_slice(a);
+        state[rate.start + 4..rate.end].copy_from_slice(b);
+        state[Rp64_256::CAPACITY_RANGE.start] = BaseElement::new(rate.len() as u64);
+
+        let state = self.permute(state);
+        state[Rp64_256::DIGEST_RANGE].try_into().unwrap()
+    }
+
+    /// Equivalent of [Rp64_256::merge_with_int()].
+    pub fn merge_with_int(&mut self, seed: &Word, value: u64) -> Word {
+        let mut state = [BaseElement::ZERO; STATE_WIDTH];
+        let rate = Rp64_256::RATE_RANGE;
+        state[rate.start..rate.start + 4].copy_from_slice(seed);
+        state[rate.start + 4] = BaseElement::new(value);
+        if value < BaseElement::MODULUS {
+            state[Rp64_256::CAPACITY_RANGE.start] = BaseElement::new(5);
+        } else {
+            state[rate.start + 5] = BaseElement::new(value / BaseElement::MODULUS);
+            state[Rp64_256::CAPACITY_RANGE.start] = BaseElement::new(6);
+        }
+
+        let state = self.permute(state);
+        state[Rp64_256::DIGEST_RANGE].try_into().unwrap()
+    }
+
+    fn permute(&mut self, state: [BaseElement; STATE_WIDTH]) -> [BaseElement; STATE_WIDTH] {
+        let record = PermutationRecord::new(state);
+        self.permutations.push(record);
+        record.output
+    }
+}
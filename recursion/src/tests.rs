@@ -0,0 +1,249 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{
+    gadgets::{bits, fri, merkle, rescue},
+    sponge::Sponge,
+    BaseElement, PermutationAir, PermutationBatch, PermutationProver, PermutationRecord, Rp64_256,
+    VerifierWitness, WitnessError, Word, STATE_WIDTH,
+};
+use air::{FieldExtension, LeafEncoding, LowDegreeTest, ProofOptions};
+use crypto::{DefaultRandomCoin, ElementHasher, Hasher};
+use math::{FieldElement, StarkField};
+use prover::Prover;
+use rand_utils::{rand_array, rand_vector};
+use verifier::{AcceptableOptions, VerifierError};
+
+// SPONGE
+// ================================================================================================
+
+#[test]
+fn sponge_matches_hasher() {
+    let mut sponge = Sponge::default();
+    for num_elements in [1, 7, 8, 9, 16, 21] {
+        let elements = rand_vector::<BaseElement>(num_elements);
+        let expected: Word = Rp64_256::hash_elements(&elements).into();
+        assert_eq!(expected, sponge.hash_elements(&elements));
+    }
+
+    let a: Word = rand_array();
+    let b: Word = rand_array();
+    let expected: Word = Rp64_256::merge(&[a.into(), b.into()]).into();
+    assert_eq!(expected, sponge.merge(&a, &b));
+
+    for value in [0, 42, u64::MAX] {
+        let expected: Word = Rp64_256::merge_with_int(a.into(), value).into();
+        assert_eq!(expected, sponge.merge_with_int(&a, value));
+    }
+
+    // every permutation is recorded
+    assert_eq!(10 + 1 + 3, sponge.into_permutations().len());
+}
+
+// GADGETS
+// ================================================================================================
+
+#[test]
+fn bits_gadget() {
+    let value = BaseElement::new(0b1011_0110);
+    let decomposition = bits::decompose(value, 64);
+    let mut result = vec![BaseElement::ZERO; 65];
+    bits::enforce_decomposition(value, &decomposition, &mut result);
+    assert!(result.iter().all(|&v| v == BaseElement::ZERO));
+
+    bits::enforce_decomposition(value + BaseElement::ONE, &decomposition, &mut result);
+    assert_ne!(BaseElement::ZERO, result[64]);
+}
+
+// VERIFIER WITNESS
+// ================================================================================================
+
+#[test]
+fn verifier_witness() {
+    let (proof, batch) = prove_batch(16);
+    let witness = VerifierWitness::new::<PermutationAir>(proof, batch).unwrap();
+    assert_eq!(witness.trace_rows.len(), witness.positions.len());
+    assert_eq!(witness.constraint_rows.len(), witness.positions.len());
+
+    // query positions are the lowest bits of drawn elements
+    let num_bits = lde_domain_size(16).ilog2() as usize;
+    let mut positions = witness
+        .query_draws
+        .iter()
+        .map(|&draw| {
+            let bits = bits::decompose(draw, num_bits);
+            bits.iter().rev().fold(0, |acc, &b| 2 * acc + b.as_int() as usize)
+        })
+        .collect::<Vec<_>>();
+    positions.sort_unstable();
+    positions.dedup();
+    assert_eq!(witness.positions, positions);
+
+    // every Merkle step satisfies the merge gadget and is a recorded permutation
+    let mut result = [BaseElement::ZERO; merkle::NUM_CONSTRAINTS];
+    for step in witness.merkle_steps.iter() {
+        let bit = BaseElement::from((step.index & 1) as u64);
+        let (left, right) = if step.index & 1 == 0 {
+            (step.node, step.sibling)
+        } else {
+            (step.sibling, step.node)
+        };
+        let mut input = [BaseElement::ZERO; STATE_WIDTH];
+        input[0] = BaseElement::new(8);
+        input[4..8].copy_from_slice(&left);
+        input[8..].copy_from_slice(&right);
+
+        merkle::enforce_merge_input(bit, &step.node, &step.sibling, &input, &mut result);
+        assert!(result.iter().all(|&v| v == BaseElement::ZERO));
+        let record = PermutationRecord::new(input);
+        assert_eq!(step.parent[..], record.output[4..8]);
+        assert!(witness.permutations.contains(&record));
+    }
+
+    // DEEP evaluations are the values of the first FRI layer, and each folding step yields a
+    // value of the next layer
+    for folding in witness.fri_foldings.iter() {
+        let constraint = fri::evaluate_folding(
+            folding.x,
+            folding.alpha,
+            folding.f_x,
+            folding.f_neg_x,
+            folding.folded,
+        );
+        assert_eq!(BaseElement::ZERO, constraint);
+    }
+    for (&position, &evaluation) in witness.positions.iter().zip(&witness.deep_evaluations) {
+        let row_length = lde_domain_size(16) / 2;
+        let folding = witness
+            .fri_foldings
+            .iter()
+            .find(|f| f.layer == 0 && f.position == position % row_length)
+            .unwrap();
+        let value = if position < row_length {
+            folding.f_x
+        } else {
+            folding.f_neg_x
+        };
+        assert_eq!(evaluation, value);
+    }
+    for folding in witness.fri_foldings.iter() {
+        let row_length = lde_domain_size(16) >> (folding.layer + 2);
+        if let Some(next) = witness
+            .fri_foldings
+            .iter()
+            .find(|f| f.layer == folding.layer + 1 && f.position == folding.position % row_length)
+        {
+            let value = if folding.position < row_length {
+                next.f_x
+            } else {
+                next.f_neg_x
+            };
+            assert_eq!(folding.folded, value);
+        }
+    }
+}
+
+#[test]
+fn verifier_witness_aggregation() {
+    let (proof, batch) = prove_batch(2);
+    let witness = VerifierWitness::new::<PermutationAir>(proof, batch).unwrap();
+
+    // prove that all hash permutations performed by the verifier were computed correctly
+    let batch = PermutationBatch::new(witness.permutations.clone());
+    let (proof, batch) = prove(batch);
+    let result = verifier::verify::<PermutationAir, Rp64_256, DefaultRandomCoin<Rp64_256>>(
+        proof,
+        batch,
+        &AcceptableOptions::OptionSet(vec![build_options(FieldExtension::None)]),
+    );
+    assert_eq!(Ok(()), result);
+}
+
+#[test]
+fn verifier_witness_unsupported_parameters() {
+    let (_, batch) = prove_batch(2);
+    let prover = PermutationProver::<Rp64_256>::new(build_options(FieldExtension::Quadratic));
+    let proof = prover.prove(prover.build_trace(&batch)).unwrap();
+    assert_eq!(
+        Err(WitnessError::UnsupportedFieldExtension(2)),
+        VerifierWitness::new::<PermutationAir>(proof, batch.clone())
+    );
+
+    let options = build_options(FieldExtension::None).with_fri_folding_schedule(&[2, 4]);
+    let prover = PermutationProver::<Rp64_256>::new(options);
+    let proof = prover.prove(prover.build_trace(&batch)).unwrap();
+    assert_eq!(
+        Err(WitnessError::UnsupportedFoldingFactor(4)),
+        VerifierWitness::new::<PermutationAir>(proof, batch.clone())
+    );
+
+    let options = build_options(FieldExtension::None).with_fri_layer_grinding_factor(4);
+    let prover = PermutationProver::<Rp64_256>::new(options);
+    let proof = prover.prove(prover.build_trace(&batch)).unwrap();
+    assert_eq!(
+        Err(WitnessError::UnsupportedFriLayerGrinding),
+        VerifierWitness::new::<PermutationAir>(proof, batch.clone())
+    );
+
+    let options = build_options(FieldExtension::None).with_fri_query_deduplication();
+    let prover = PermutationProver::<Rp64_256>::new(options);
+    let proof = prover.prove(prover.build_trace(&batch)).unwrap();
+    assert_eq!(
+        Err(WitnessError::UnsupportedFriQueryDeduplication),
+        VerifierWitness::new::<PermutationAir>(proof, batch.clone())
+    );
+
+    let options = build_options(FieldExtension::None).with_low_degree_test(LowDegreeTest::Stir);
+    let prover = PermutationProver::<Rp64_256>::new(options);
+    let proof = prover.prove(prover.build_trace(&batch)).unwrap();
+    assert_eq!(
+        Err(WitnessError::UnsupportedLowDegreeTest),
+        VerifierWitness::new::<PermutationAir>(proof, batch.clone())
+    );
+
+    let options = build_options(FieldExtension::None).with_leaf_encoding(LeafEncoding::PaddedLe);
+    let prover = PermutationProver::<Rp64_256>::new(options);
+    let proof = prover.prove(prover.build_trace(&batch)).unwrap();
+    assert_eq!(
+        Err(WitnessError::UnsupportedLeafEncoding),
+        VerifierWitness::new::<PermutationAir>(proof, batch)
+    );
+}
+
+#[test]
+fn verifier_witness_invalid_proof() {
+    let (proof, _) = prove_batch(2);
+    let batch =
+        PermutationBatch::new(vec![PermutationRecord::new([BaseElement::ONE; STATE_WIDTH])]);
+    assert_eq!(
+        Err(WitnessError::InvalidProof(VerifierError::InconsistentOodConstraintEvaluations)),
+        VerifierWitness::new::<PermutationAir>(proof, batch)
+    );
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+fn build_options(field_extension: FieldExtension) -> ProofOptions {
+    ProofOptions::new(8, 16, 0, field_extension, 2, 7)
+}
+
+fn lde_domain_size(num_permutations: usize) -> usize {
+    num_permutations * rescue::CYCLE_LENGTH * 16
+}
+
+fn prove(batch: PermutationBatch) -> (air::proof::StarkProof, PermutationBatch) {
+    let prover = PermutationProver::<Rp64_256>::new(build_options(FieldExtension::None));
+    let trace = prover.build_trace(&batch);
+    assert_eq!(batch, prover.get_pub_inputs(&trace));
+    (prover.prove(trace).unwrap(), batch)
+}
+
+fn prove_batch(num_permutations: usize) -> (air::proof::StarkProof, PermutationBatch) {
+    let permutations = (0..num_permutations)
+        .map(|i| PermutationRecord::new([BaseElement::from(i as u64); STATE_WIDTH]))
+        .collect();
+    prove(PermutationBatch::new(permutations))
+}
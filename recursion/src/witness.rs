@@ -0,0 +1,445 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use crate::{
+    gadgets::fri::fold, sponge::Sponge, BaseElement, PermutationRecord, Rp64_256, WitnessError,
+    Word,
+};
+use air::{proof::StarkProof, Air, FieldExtension, LeafEncoding, LowDegreeTest, QuerySampling};
+use crypto::{BatchMerkleProof, DefaultRandomCoin};
+use fri::{folding::fold_positions, utils::map_positions_to_indexes};
+use math::{FieldElement, StarkField, ToElements};
+use utils::DeserializationError;
+use verifier::{AcceptableOptions, VerifierError};
+
+// WITNESS RECORDS
+// ================================================================================================
+
+/// A single step of a Merkle path verification.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MerkleStep {
+    /// Index of `node` at its level of the tree; the lowest bit of the index determines the
+    /// order in which `node` and `sibling` are merged.
+    pub index: usize,
+    pub node: Word,
+    pub sibling: Word,
+    /// Result of merging `node` and `sibling`.
+    pub parent: Word,
+}
+
+/// A single FRI folding step for folding factor 2.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FriFolding {
+    /// Index of the FRI layer from which values are folded.
+    pub layer: usize,
+    /// Position in the folded domain.
+    pub position: usize,
+    /// Domain point at which `f_x` is evaluated.
+    pub x: BaseElement,
+    pub alpha: BaseElement,
+    /// Value of the layer polynomial at `x`.
+    pub f_x: BaseElement,
+    /// Value of the layer polynomial at `-x`.
+    pub f_neg_x: BaseElement,
+    /// Value of the folded polynomial at `x^2`.
+    pub folded: BaseElement,
+}
+
+// VERIFIER WITNESS
+// ================================================================================================
+
+/// Values computed by the STARK verifier while verifying a single proof.
+///
+/// The witness is generated by replaying verification of a proof on the host. It contains all
+/// values a constraint system needs in order to re-execute the verification: values read from
+/// the proof, values drawn from the public coin, Merkle path steps, FRI folding steps, and
+/// every invocation of the hash permutation (including those used by the public coin).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerifierWitness {
+    /// Elements of the proof context followed by public inputs; the public coin is seeded with
+    /// a hash of these elements.
+    pub seed: Vec<BaseElement>,
+    pub trace_commitment: Word,
+    pub constraint_commitment: Word,
+    /// Coefficients for transition constraints followed by coefficients for assertions.
+    pub constraint_coefficients: Vec<BaseElement>,
+    /// The out-of-domain point.
+    pub z: BaseElement,
+    /// Out-of-domain trace frame with current and next rows interleaved.
+    pub ood_trace: Vec<BaseElement>,
+    pub ood_evaluations: Vec<BaseElement>,
+    /// Coefficients for trace columns followed by coefficients for constraint composition
+    /// columns.
+    pub deep_coefficients: Vec<BaseElement>,
+    /// Commitments to FRI layers followed by the commitment to the remainder polynomial.
+    pub fri_commitments: Vec<Word>,
+    /// Folding challenges drawn after each of the FRI commitments.
+    pub fri_alphas: Vec<BaseElement>,
+    pub pow_nonce: u64,
+    /// Elements drawn from the public coin to derive query positions (before masking).
+    pub query_draws: Vec<BaseElement>,
+    /// Sorted unique query positions.
+    pub positions: Vec<usize>,
+    /// Trace rows at query positions.
+    pub trace_rows: Vec<Vec<BaseElement>>,
+    /// Constraint composition rows at query positions.
+    pub constraint_rows: Vec<Vec<BaseElement>>,
+    /// Evaluations of the DEEP composition polynomial at query positions.
+    pub deep_evaluations: Vec<BaseElement>,
+    /// Steps of all verified Merkle paths, leaf to root.
+    pub merkle_steps: Vec<MerkleStep>,
+    pub fri_foldings: Vec<FriFolding>,
+    /// Coefficients of the FRI remainder polynomial.
+    pub remainder: Vec<BaseElement>,
+    /// All invocations of the hash permutation in the order in which they were performed.
+    pub permutations: Vec<PermutationRecord>,
+}
+
+impl VerifierWitness {
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+    /// Verifies the specified proof and returns values computed during verification.
+    ///
+    /// The proof must be generated using [Rp64_256] hash function and
+    /// [DefaultRandomCoin](crypto::DefaultRandomCoin) in the base field with FRI folding
+    /// factor 2, and the execution trace must consist of a single segment opened at the default
+    /// out-of-domain points.
+    ///
+    /// # Errors
+    /// Returns an error if the proof parameters are not supported, or if the proof is not
+    /// valid.
+    pub fn new<A>(proof: StarkProof, pub_inputs: A::PublicInputs) -> Result<Self, WitnessError>
+    where
+        A: Air<BaseField = BaseElement>,
+        A::PublicInputs: Clone,
+    {
+        let options = proof.options().clone();
+        if options.field_extension() != FieldExtension::None {
+            return Err(WitnessError::UnsupportedFieldExtension(
+                options.field_extension().degree(),
+            ));
+        }
+        if proof.trace_layout().num_aux_segments() != 0 {
+            return Err(WitnessError::UnsupportedAuxTraceSegments(
+                proof.trace_layout().num_aux_segments(),
+            ));
+        }
+        let folding_factor = options.to_fri_options().folding_factor();
+        if folding_factor != 2 {
+            return Err(WitnessError::UnsupportedFoldingFactor(folding_factor));
+        }
+        if let Some(factor) = options.fri_folding_schedule().into_iter().find(|&f| f != 2) {
+            return Err(WitnessError::UnsupportedFoldingFactor(factor));
+        }
+        if options.normalized_queries() {
+            return Err(WitnessError::UnsupportedNormalizedQueries);
+        }
+        if options.fri_layer_grinding_factor() > 0 {
+            return Err(WitnessError::UnsupportedFriLayerGrinding);
+        }
+        if options.fri_query_deduplication() {
+            return Err(WitnessError::UnsupportedFriQueryDeduplication);
+        }
+        if options.low_degree_test() != LowDegreeTest::Fri {
+            return Err(WitnessError::UnsupportedLowDegreeTest);
+        }
+        if options.leaf_encoding() != LeafEncoding::Packed {
+            return Err(WitnessError::UnsupportedLeafEncoding);
+        }
+        if options.query_sampling() != QuerySampling::Uniform {
+            return Err(WitnessError::UnsupportedQuerySampling);
+        }
+
+        verifier::verify::<A, Rp64_256, DefaultRandomCoin<Rp64_256>>(
+            proof.clone(),
+            pub_inputs.clone(),
+            &AcceptableOptions::OptionSet(vec![options.clone()]),
+        )?;
+
+        let pub_inputs = A::derive_public_inputs(pub_inputs);
+        let mut seed = ToElements::<BaseElement>::to_elements(&proof.context);
+        seed.extend(pub_inputs.to_elements());
+        let air = A::new(proof.get_trace_info(), pub_inputs, options);
+        if air.context().num_ood_points() != 2 {
+            return Err(WitnessError::UnsupportedOodPoints(air.context().num_ood_points()));
+        }
+        WitnessBuilder::new(&air, seed).build(proof).map_err(WitnessError::InvalidProof)
+    }
+}
+
+// WITNESS BUILDER
+// ================================================================================================
+
+/// Replays verification of a proof which is already known to be valid.
+struct WitnessBuilder<'a, A: Air<BaseField = BaseElement>> {
+    air: &'a A,
+    sponge: Sponge,
+    coin_seed: Word,
+    counter: u64,
+    witness: VerifierWitness,
+}
+
+impl<'a, A: Air<BaseField = BaseElement>> WitnessBuilder<'a, A> {
+    fn new(air: &'a A, seed: Vec<BaseElement>) -> Self {
+        let mut sponge = Sponge::default();
+        let coin_seed = sponge.hash_elements(&seed);
+        let witness = VerifierWitness {
+            seed,
+            trace_commitment: Word::default(),
+            constraint_commitment: Word::default(),
+            constraint_coefficients: Vec::new(),
+            z: BaseElement::ZERO,
+            ood_trace: Vec::new(),
+            ood_evaluations: Vec::new(),
+            deep_coefficients: Vec::new(),
+            fri_commitments: Vec::new(),
+            fri_alphas: Vec::new(),
+            pow_nonce: 0,
+            query_draws: Vec::new(),
+            positions: Vec::new(),
+            trace_rows: Vec::new(),
+            constraint_rows: Vec::new(),
+            deep_evaluations: Vec::new(),
+            merkle_steps: Vec::new(),
+            fri_foldings: Vec::new(),
+            remainder: Vec::new(),
+            permutations: Vec::new(),
+        };
+        Self {
+            air,
+            sponge,
+            coin_seed,
+            counter: 0,
+            witness,
+        }
+    }
+
+    fn build(mut self, mut proof: StarkProof) -> Result<VerifierWitness, VerifierError> {
+        let air = self.air;
+        let trace_width = air.trace_info().width();
+        let num_composition_columns = air.context().num_constraint_composition_columns();
+        let lde_domain_size = air.lde_domain_size();
+        let num_fri_layers = air.options().to_fri_options().num_fri_layers(lde_domain_size);
+
+        // commitments and out-of-domain frame
+        let (trace_commitments, constraint_commitment, fri_commitments) = proof
+            .commitments
+            .parse::<Rp64_256>(1, num_fri_layers)
+            .map_err(deserialization_error)?;
+        let (ood_trace, ood_evaluations) = proof
+            .ood_frame
+            .parse::<BaseElement>(trace_width, 0, num_composition_columns)
+            .map_err(deserialization_error)?;
+
+        // replay the transcript
+        self.witness.trace_commitment = trace_commitments[0].into();
+        self.reseed(self.witness.trace_commitment);
+        let num_coefficients =
+            air.context().num_main_transition_constraints() + air.context().num_assertions();
+        self.witness.constraint_coefficients = self.draw_elements(num_coefficients);
+
+        self.witness.constraint_commitment = constraint_commitment.into();
+        self.reseed(self.witness.constraint_commitment);
+        self.witness.z = self.draw();
+
+        let ood_trace_digest = self.sponge.hash_elements(&ood_trace);
+        self.reseed(ood_trace_digest);
+        let ood_evaluations_digest = self.sponge.hash_elements(&ood_evaluations);
+        self.reseed(ood_evaluations_digest);
+        self.witness.ood_trace = ood_trace;
+        self.witness.ood_evaluations = ood_evaluations;
+        self.witness.deep_coefficients = self.draw_elements(trace_width + num_composition_columns);
+
+        for commitment in fri_commitments {
+            let commitment = commitment.into();
+            self.witness.fri_commitments.push(commitment);
+            self.reseed(commitment);
+            let alpha = self.draw();
+            self.witness.fri_alphas.push(alpha);
+        }
+
+        // proof-of-work and query positions; the proof-of-work is checked against the first
+        // element of hash(seed || nonce), which also becomes the new seed of the public coin
+        self.witness.pow_nonce = proof.pow_nonce;
+        self.coin_seed = self.sponge.merge_with_int(&self.coin_seed, proof.pow_nonce);
+        self.counter = 0;
+        let mask = (lde_domain_size - 1) as u64;
+        let mut positions = Vec::new();
+        for _ in 0..air.options().num_queries() {
+            let value = self.draw();
+            self.witness.query_draws.push(value);
+            positions.push((value.as_int() & mask) as usize);
+        }
+        positions.sort_unstable();
+        positions.dedup();
+
+        // trace and constraint queries
+        let (merkle_proof, table) = proof
+            .trace_queries
+            .remove(0)
+            .parse::<Rp64_256, BaseElement>(lde_domain_size, positions.len(), trace_width)
+            .map_err(deserialization_error)?;
+        let paths = get_paths(merkle_proof, &positions)?;
+        for ((row, path), &position) in table.rows().zip(paths).zip(positions.iter()) {
+            self.witness.trace_rows.push(row.to_vec());
+            self.verify_path(row, &path, position);
+        }
+        let (merkle_proof, table) = proof
+            .constraint_queries
+            .parse::<Rp64_256, BaseElement>(
+                lde_domain_size,
+                positions.len(),
+                num_composition_columns,
+            )
+            .map_err(deserialization_error)?;
+        let paths = get_paths(merkle_proof, &positions)?;
+        for ((row, path), &position) in table.rows().zip(paths).zip(positions.iter()) {
+            self.witness.constraint_rows.push(row.to_vec());
+            self.verify_path(row, &path, position);
+        }
+        self.witness.positions = positions;
+        self.witness.deep_evaluations = self.compose_deep();
+
+        // FRI layers and remainder
+        let num_partitions = proof.fri_proof.num_partitions();
+        let remainder = proof
+            .fri_proof
+            .parse_remainder::<BaseElement>()
+            .map_err(deserialization_error)?;
+        let (layer_values, layer_proofs) = proof
+            .fri_proof
+            .parse_layers::<Rp64_256, BaseElement>(lde_domain_size, 2)
+            .map_err(deserialization_error)?;
+
+        let mut positions = self.witness.positions.clone();
+        let mut domain_size = lde_domain_size;
+        let mut domain_generator = air.lde_domain_generator();
+        for (layer, (values, merkle_proof)) in
+            layer_values.into_iter().zip(layer_proofs).enumerate()
+        {
+            let folded_positions = fold_positions(&positions, domain_size, 2);
+            let indexes =
+                map_positions_to_indexes(&folded_positions, domain_size, 2, num_partitions);
+            let paths = get_paths(merkle_proof, &indexes)?;
+            let alpha = self.witness.fri_alphas[layer];
+            for ((values, path), (&position, &index)) in
+                values.chunks(2).zip(paths).zip(folded_positions.iter().zip(indexes.iter()))
+            {
+                self.verify_path(values, &path, index);
+                let x = air.domain_offset() * domain_generator.exp_vartime(position as u64);
+                self.witness.fri_foldings.push(FriFolding {
+                    layer,
+                    position,
+                    x,
+                    alpha,
+                    f_x: values[0],
+                    f_neg_x: values[1],
+                    folded: fold(x, alpha, values[0], values[1]),
+                });
+            }
+            positions = folded_positions;
+            domain_size /= 2;
+            domain_generator = domain_generator.square();
+        }
+        self.sponge.hash_elements(&remainder);
+        self.witness.remainder = remainder;
+
+        self.witness.permutations = self.sponge.into_permutations();
+        Ok(self.witness)
+    }
+
+    // PUBLIC COIN
+    // --------------------------------------------------------------------------------------------
+
+    fn reseed(&mut self, data: Word) {
+        self.coin_seed = self.sponge.merge(&self.coin_seed, &data);
+        self.counter = 0;
+    }
+
+    /// Draws the next element from the public coin; for [Rp64_256] digests, this is always the
+    /// first element of hash(seed || counter).
+    fn draw(&mut self) -> BaseElement {
+        self.counter += 1;
+        self.sponge.merge_with_int(&self.coin_seed, self.counter)[0]
+    }
+
+    fn draw_elements(&mut self, num_elements: usize) -> Vec<BaseElement> {
+        (0..num_elements).map(|_| self.draw()).collect()
+    }
+
+    // HELPER METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Hashes the specified leaf values and records steps of the path from the leaf to the root.
+    fn verify_path(&mut self, values: &[BaseElement], path: &[Word], mut index: usize) {
+        let mut node = self.sponge.hash_elements(values);
+        for &sibling in path {
+            let parent = if index & 1 == 0 {
+                self.sponge.merge(&node, &sibling)
+            } else {
+                self.sponge.merge(&sibling, &node)
+            };
+            self.witness.merkle_steps.push(MerkleStep {
+                index,
+                node,
+                sibling,
+                parent,
+            });
+            node = parent;
+            index >>= 1;
+        }
+    }
+
+    /// Computes evaluations of the DEEP composition polynomial at query positions.
+    fn compose_deep(&self) -> Vec<BaseElement> {
+        let witness = &self.witness;
+        let z = witness.z;
+        let z_next = z * self.air.trace_domain_generator();
+        let (trace_coefficients, constraint_coefficients) =
+            witness.deep_coefficients.split_at(self.air.trace_info().width());
+
+        let mut result = Vec::with_capacity(witness.positions.len());
+        for (q, &position) in witness.positions.iter().enumerate() {
+            let x = self.air.domain_offset()
+                * self.air.lde_domain_generator().exp_vartime(position as u64);
+            let mut t1 = BaseElement::ZERO;
+            let mut t2 = BaseElement::ZERO;
+            for (i, (&value, &coefficient)) in
+                witness.trace_rows[q].iter().zip(trace_coefficients).enumerate()
+            {
+                t1 += coefficient * (value - witness.ood_trace[2 * i]);
+                t2 += coefficient * (value - witness.ood_trace[2 * i + 1]);
+            }
+            for (j, (&value, &coefficient)) in
+                witness.constraint_rows[q].iter().zip(constraint_coefficients).enumerate()
+            {
+                t1 += coefficient * (value - witness.ood_evaluations[j]);
+            }
+            result.push(t1 / (x - z) + t2 / (x - z_next));
+        }
+        result
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns Merkle paths for the specified leaf indexes; leaves are not included in the paths.
+fn get_paths(
+    proof: BatchMerkleProof<Rp64_256>,
+    indexes: &[usize],
+) -> Result<Vec<Vec<Word>>, VerifierError> {
+    let paths = proof
+        .into_paths(indexes)
+        .map_err(|err| VerifierError::ProofDeserializationError(err.to_string()))?;
+    Ok(paths
+        .into_iter()
+        .map(|path| path[1..].iter().map(|&digest| digest.into()).collect())
+        .collect())
+}
+
+fn deserialization_error(err: DeserializationError) -> VerifierError {
+    VerifierError::ProofDeserializationError(err.to_string())
+}
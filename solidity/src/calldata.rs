@@ -0,0 +1,255 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use crate::{Keccak256, SolidityVerifier, SymbolicField};
+use air::proof::StarkProof;
+use crypto::{BatchMerkleProof, DefaultRandomCoin, Digest, ElementHasher, Hasher, RandomCoin};
+use fri::{folding::fold_positions, utils::map_positions_to_indexes};
+use math::{StarkField, ToElements};
+use sha3::Digest as _;
+use utils::DeserializationError;
+use verifier::VerifierError;
+
+/// A 32-byte EVM word.
+///
+/// Field elements are encoded as big-endian unsigned integers, while hash digests are encoded as
+/// `bytes32` values (i.e., bytes of a digest are copied into a word as is).
+pub type Word = [u8; 32];
+
+/// Signature of the verification function of generated contracts.
+const VERIFY_SIGNATURE: &str = "verify(uint256[],uint256[])";
+
+// CALLDATA
+// ================================================================================================
+
+/// Arguments of the `verify()` function of a generated verifier contract.
+///
+/// Words of the proof are laid out in the order in which the verifier reads them (see
+/// [SolidityVerifier::encode_proof()]).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Calldata {
+    /// Public inputs of the computation, one field element per word.
+    pub public_inputs: Vec<Word>,
+    /// STARK proof of the computation.
+    pub proof: Vec<Word>,
+}
+
+impl Calldata {
+    /// Returns ABI-encoded calldata of a `verify(uint256[],uint256[])` call, including the
+    /// function selector.
+    pub fn to_abi_bytes(&self) -> Vec<u8> {
+        let mut result = Vec::new();
+        let selector = sha3::Keccak256::digest(VERIFY_SIGNATURE.as_bytes());
+        result.extend_from_slice(&selector[..4]);
+
+        // offsets of both dynamic arrays are followed by lengths and elements of the arrays
+        let proof_offset = 64 + 32 * (self.public_inputs.len() + 1);
+        result.extend_from_slice(&u64_to_word(64));
+        result.extend_from_slice(&u64_to_word(proof_offset as u64));
+        for words in [&self.public_inputs, &self.proof] {
+            result.extend_from_slice(&u64_to_word(words.len() as u64));
+            words.iter().for_each(|word| result.extend_from_slice(word));
+        }
+
+        result
+    }
+}
+
+// PROOF ENCODER
+// ================================================================================================
+
+impl<B: SymbolicField> SolidityVerifier<B> {
+    /// Converts a proof and public inputs of the computation into arguments of the `verify()`
+    /// function of the generated contract.
+    ///
+    /// The proof must be generated using [Keccak256] and
+    /// [DefaultRandomCoin](crypto::DefaultRandomCoin). Words of the encoded proof are laid out
+    /// as follows (`w` is the width of the execution trace, `m` is the number of constraint
+    /// composition columns, and `N` is the FRI folding factor):
+    /// 1. Commitments to the trace and to the constraint composition polynomial.
+    /// 2. Out-of-domain trace frame with current and next rows interleaved (`2w` elements),
+    ///    followed by `m` out-of-domain constraint evaluations.
+    /// 3. Commitments to FRI layers including the commitment to the remainder polynomial.
+    /// 4. Proof-of-work nonce and the number of partitions of FRI layers.
+    /// 5. For each unique query position in ascending order, `w` trace values followed by
+    ///    siblings of the Merkle path from the queried leaf to the trace commitment.
+    /// 6. The same for constraint composition columns (`m` values per query).
+    /// 7. For each FRI layer, for each folded query position (in the order in which positions are
+    ///    folded), `N` layer values followed by the Merkle path siblings.
+    /// 8. Coefficients of the FRI remainder polynomial.
+    ///
+    /// Query positions are derived by replaying the proof transcript.
+    ///
+    /// Generated contracts do not derive public inputs, and thus, `pub_inputs` must already
+    /// contain values derived via [Air::derive_public_inputs()](air::Air::derive_public_inputs).
+    ///
+    /// # Errors
+    /// Returns an error if the proof was not generated for the computation and proof options
+    /// of this verifier, or if the proof could not be parsed.
+    pub fn encode_proof<P: ToElements<B>>(
+        &self,
+        proof: StarkProof,
+        pub_inputs: &P,
+    ) -> Result<Calldata, VerifierError> {
+        type H<B> = Keccak256<B>;
+
+        if proof.context.try_to_elements::<B>().as_ref() != Some(&self.context) {
+            return Err(VerifierError::UnacceptableProofOptions);
+        }
+        let pub_inputs = pub_inputs.to_elements();
+        let mut words = Vec::new();
+
+        // commitments and out-of-domain frame
+        let (trace_commitments, constraint_commitment, fri_commitments) = proof
+            .commitments
+            .parse::<H<B>>(1, self.num_fri_layers)
+            .map_err(deserialization_error)?;
+        let (ood_trace, ood_evaluations) = proof
+            .ood_frame
+            .parse::<B>(self.trace_width, 0, self.num_composition_columns)
+            .map_err(deserialization_error)?;
+        words.push(digest_to_word(&trace_commitments[0]));
+        words.push(digest_to_word(&constraint_commitment));
+        words.extend(ood_trace.iter().chain(ood_evaluations.iter()).map(|&v| element_to_word(v)));
+        words.extend(fri_commitments.iter().map(digest_to_word));
+        words.push(u64_to_word(proof.pow_nonce));
+        words.push(u64_to_word(proof.fri_proof.num_partitions() as u64));
+
+        // replay the transcript to derive query positions
+        let mut seed = self.context.clone();
+        seed.extend_from_slice(&pub_inputs);
+        let mut coin = DefaultRandomCoin::<H<B>>::new(&seed);
+        coin.reseed(trace_commitments[0]);
+        draw_elements(&mut coin, self.num_transition_constraints() + self.num_assertions)?;
+        coin.reseed(constraint_commitment);
+        draw_elements(&mut coin, 1)?;
+        coin.reseed(H::<B>::hash_elements(&ood_trace));
+        coin.reseed(H::<B>::hash_elements(&ood_evaluations));
+        draw_elements(&mut coin, self.trace_width + self.num_composition_columns)?;
+        for commitment in fri_commitments.iter() {
+            coin.reseed(*commitment);
+            draw_elements(&mut coin, 1)?;
+        }
+        let mut positions = proof
+            .context
+            .options()
+            .draw_query_positions(&mut coin, self.lde_domain_size, proof.pow_nonce)
+            .map_err(|_| VerifierError::RandomCoinError)?;
+        if positions.len() != proof.num_unique_queries as usize {
+            return Err(VerifierError::InconsistentNumQueries(
+                positions.len(),
+                proof.num_unique_queries as usize,
+            ));
+        }
+
+        // trace and constraint queries
+        let queries = [
+            (proof.trace_queries[0].clone(), self.trace_width),
+            (proof.constraint_queries.clone(), self.num_composition_columns),
+        ];
+        for (queries, num_values) in queries {
+            let (merkle_proof, table) = queries
+                .parse::<H<B>, B>(self.lde_domain_size, positions.len(), num_values)
+                .map_err(deserialization_error)?;
+            let paths = get_paths(merkle_proof, &positions)?;
+            for (row, path) in table.rows().zip(paths.iter()) {
+                words.extend(row.iter().map(|&v| element_to_word(v)));
+                words.extend(path.iter().map(digest_to_word));
+            }
+        }
+
+        // FRI layers and remainder
+        let num_partitions = proof.fri_proof.num_partitions();
+        let remainder = proof.fri_proof.parse_remainder::<B>().map_err(deserialization_error)?;
+        let (layer_values, layer_proofs) = proof
+            .fri_proof
+            .parse_layers::<H<B>, B>(self.lde_domain_size, self.folding_factor)
+            .map_err(deserialization_error)?;
+        let mut domain_size = self.lde_domain_size;
+        for (values, merkle_proof) in layer_values.into_iter().zip(layer_proofs) {
+            let folded_positions = fold_positions(&positions, domain_size, self.folding_factor);
+            let indexes = map_positions_to_indexes(
+                &folded_positions,
+                domain_size,
+                self.folding_factor,
+                num_partitions,
+            );
+            let paths = get_paths(merkle_proof, &indexes)?;
+            for (values, path) in values.chunks(self.folding_factor).zip(paths.iter()) {
+                words.extend(values.iter().map(|&v| element_to_word(v)));
+                words.extend(path.iter().map(digest_to_word));
+            }
+            positions = folded_positions;
+            domain_size /= self.folding_factor;
+        }
+        words.extend(remainder.iter().map(|&v| element_to_word(v)));
+
+        Ok(Calldata {
+            public_inputs: pub_inputs.into_iter().map(element_to_word).collect(),
+            proof: words,
+        })
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Draws the specified number of field elements from the public coin, discarding them.
+fn draw_elements<B: StarkField>(
+    coin: &mut DefaultRandomCoin<Keccak256<B>>,
+    num_elements: usize,
+) -> Result<(), VerifierError> {
+    for _ in 0..num_elements {
+        coin.draw::<B>().map_err(|_| VerifierError::RandomCoinError)?;
+    }
+    Ok(())
+}
+
+/// Returns Merkle paths for the specified leaf indexes; leaves are not included in the paths.
+fn get_paths<B: StarkField>(
+    proof: BatchMerkleProof<Keccak256<B>>,
+    indexes: &[usize],
+) -> Result<Vec<Vec<<Keccak256<B> as Hasher>::Digest>>, VerifierError> {
+    let paths = proof
+        .into_paths(indexes)
+        .map_err(|err| VerifierError::ProofDeserializationError(err.to_string()))?;
+    Ok(paths.into_iter().map(|path| path[1..].to_vec()).collect())
+}
+
+fn deserialization_error(err: DeserializationError) -> VerifierError {
+    VerifierError::ProofDeserializationError(err.to_string())
+}
+
+/// Encodes a field element as a big-endian integer.
+pub(crate) fn element_to_word<B: StarkField>(value: B) -> Word {
+    let mut result = [0; 32];
+    for (i, byte) in value.to_bytes().into_iter().enumerate() {
+        result[31 - i] = byte;
+    }
+    result
+}
+
+/// Decodes a field element from a big-endian integer; returns `None` if the integer is not a
+/// valid field element.
+pub(crate) fn word_to_element<B: StarkField>(word: &Word) -> Option<B> {
+    let (head, tail) = word.split_at(32 - B::ELEMENT_BYTES);
+    if head.iter().any(|&byte| byte != 0) {
+        return None;
+    }
+    let bytes = tail.iter().rev().copied().collect::<Vec<_>>();
+    B::try_from(bytes.as_slice()).ok()
+}
+
+/// Encodes an integer as a big-endian integer.
+pub(crate) fn u64_to_word(value: u64) -> Word {
+    let mut result = [0; 32];
+    result[24..].copy_from_slice(&value.to_be_bytes());
+    result
+}
+
+/// Encodes a digest as `bytes32` value.
+pub(crate) fn digest_to_word<D: Digest>(digest: &D) -> Word {
+    digest.as_bytes()
+}
@@ -0,0 +1,672 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use crate::{BoundaryValue, Node, SolidityVerifier, SymbolicField};
+use core::fmt::Write;
+use math::StarkField;
+
+// CONTRACT GENERATOR
+// ================================================================================================
+
+impl<B: SymbolicField> SolidityVerifier<B> {
+    /// Returns source code of a Solidity contract with the specified name which verifies proofs
+    /// of the computation described by this verifier.
+    ///
+    /// The contract exposes a single `verify(uint256[] publicInputs, uint256[] proof)` function
+    /// which reverts with a descriptive message if the proof is not valid, and returns `true`
+    /// otherwise. Arguments of the function can be built via
+    /// [encode_proof()](SolidityVerifier::encode_proof).
+    pub fn generate_contract(&self, name: &str) -> String {
+        let mut source = String::new();
+        writeln!(source, "// SPDX-License-Identifier: MIT").unwrap();
+        writeln!(source, "pragma solidity ^0.8.19;").unwrap();
+        writeln!(source).unwrap();
+        writeln!(source, "/// @title STARK verifier generated by winter-solidity.").unwrap();
+        writeln!(
+            source,
+            "/// @notice Verifies Winterfell proofs generated using Keccak256 hash function."
+        )
+        .unwrap();
+        writeln!(source, "contract {name} {{").unwrap();
+        self.write_constants(&mut source);
+        source.push_str(STATE);
+        source.push_str(VERIFY);
+        self.write_constraint_evaluation(&mut source);
+        source.push_str(HELPERS);
+        writeln!(source, "}}").unwrap();
+        source
+    }
+
+    // HELPER METHODS
+    // --------------------------------------------------------------------------------------------
+
+    fn write_constants(&self, s: &mut String) {
+        let context = self.context.iter().flat_map(|v| v.to_bytes()).collect::<Vec<_>>();
+        let constants = [
+            ("P", (to_u128(B::ZERO - B::ONE) + 1).to_string()),
+            ("ELEMENT_BYTES", B::ELEMENT_BYTES.to_string()),
+            ("NUM_PUBLIC_INPUTS", self.num_public_inputs.to_string()),
+            ("TRACE_WIDTH", self.trace_width.to_string()),
+            ("TRACE_LENGTH", self.trace_length.to_string()),
+            ("TRACE_GENERATOR", to_decimal(self.trace_generator)),
+            ("LDE_DOMAIN_SIZE", self.lde_domain_size.to_string()),
+            ("LDE_DEPTH", self.lde_domain_size.ilog2().to_string()),
+            ("LDE_GENERATOR", to_decimal(self.lde_generator)),
+            ("DOMAIN_OFFSET", to_decimal(self.domain_offset)),
+            ("NUM_TRANSITION_CONSTRAINTS", self.num_transition_constraints().to_string()),
+            ("NUM_ASSERTIONS", self.num_assertions.to_string()),
+            ("NUM_COMPOSITION_COLUMNS", self.num_composition_columns.to_string()),
+            ("NUM_QUERIES", self.num_queries.to_string()),
+            ("QUERY_MODULUS", self.query_modulus().to_string()),
+            ("GRINDING_FACTOR", self.grinding_factor.to_string()),
+            ("FOLDING_FACTOR", self.folding_factor.to_string()),
+            ("FOLDING_ROOT", to_decimal(self.folding_root())),
+            ("NUM_FRI_LAYERS", self.num_fri_layers.to_string()),
+            ("REMAINDER_SIZE", self.remainder_size.to_string()),
+        ];
+        for (name, value) in constants {
+            writeln!(s, "    uint256 internal constant {name} = {value};").unwrap();
+        }
+        writeln!(
+            s,
+            "    bool internal constant NORMALIZED_QUERIES = {};",
+            self.normalized_queries
+        )
+        .unwrap();
+        let context = context.iter().map(|b| format!("{b:02x}")).collect::<String>();
+        writeln!(s, "    bytes internal constant CONTEXT = hex\"{context}\";").unwrap();
+    }
+
+    /// Writes functions which evaluate transition constraints, periodic columns, and the random
+    /// linear combination of all constraints at the out-of-domain point.
+    fn write_constraint_evaluation(&self, s: &mut String) {
+        // transition constraints
+        let nodes = self.transition_graph.nodes();
+        writeln!(s).unwrap();
+        writeln!(
+            s,
+            "    /// Evaluates transition constraints over an out-of-domain frame with current and"
+        )
+        .unwrap();
+        writeln!(s, "    /// next rows interleaved.").unwrap();
+        writeln!(
+            s,
+            "    function _evaluateTransition(uint256[] memory frame, uint256[] memory periodic)"
+        )
+        .unwrap();
+        writeln!(s, "        internal pure returns (uint256[] memory result)").unwrap();
+        writeln!(s, "    {{").unwrap();
+        writeln!(s, "        uint256[] memory v = new uint256[]({});", nodes.len()).unwrap();
+        for (i, node) in nodes.iter().enumerate() {
+            let value = match *node {
+                Node::Constant(value) => to_decimal(value),
+                Node::Current(col) => format!("frame[{}]", 2 * col),
+                Node::Next(col) => format!("frame[{}]", 2 * col + 1),
+                Node::Periodic(col) => format!("periodic[{col}]"),
+                Node::Add(a, b) => format!("addmod(v[{a}], v[{b}], P)"),
+                Node::Sub(a, b) => format!("_sub(v[{a}], v[{b}])"),
+                Node::Mul(a, b) => format!("mulmod(v[{a}], v[{b}], P)"),
+                Node::Neg(a) => format!("_sub(0, v[{a}])"),
+                Node::Inv(a) => format!("_inv(v[{a}])"),
+                Node::Exp(a, power) => format!("_exp(v[{a}], {power})"),
+            };
+            writeln!(s, "        v[{i}] = {value};").unwrap();
+        }
+        writeln!(s, "        result = new uint256[](NUM_TRANSITION_CONSTRAINTS);").unwrap();
+        for (i, output) in self.transition_graph.outputs().iter().enumerate() {
+            writeln!(s, "        result[{i}] = v[{output}];").unwrap();
+        }
+        writeln!(s, "    }}").unwrap();
+
+        // periodic columns
+        writeln!(s).unwrap();
+        writeln!(s, "    /// Evaluates periodic column polynomials at the specified point.")
+            .unwrap();
+        writeln!(s, "    function _evaluatePeriodic(uint256 z) internal pure returns (uint256[] memory result) {{").unwrap();
+        writeln!(s, "        result = new uint256[]({});", self.periodic_column_polys.len())
+            .unwrap();
+        if !self.periodic_column_polys.is_empty() {
+            writeln!(s, "        uint256 x;").unwrap();
+            writeln!(s, "        uint256 value;").unwrap();
+        }
+        for (i, poly) in self.periodic_column_polys.iter().enumerate() {
+            writeln!(s, "        x = _exp(z, {});", self.trace_length / poly.len()).unwrap();
+            write_horner(s, poly);
+            writeln!(s, "        result[{i}] = value;").unwrap();
+        }
+        writeln!(s, "    }}").unwrap();
+
+        // all constraints
+        writeln!(s).unwrap();
+        writeln!(s, "    /// Computes a random linear combination of transition and boundary constraints divided").unwrap();
+        writeln!(s, "    /// by their divisors at the out-of-domain point.").unwrap();
+        writeln!(s, "    function _evaluateConstraints(State memory s) internal pure returns (uint256 result) {{").unwrap();
+        writeln!(s, "        uint256 z = s.z;").unwrap();
+        writeln!(s, "        uint256[] memory c = s.coefficients;").unwrap();
+        writeln!(
+            s,
+            "        uint256[] memory t = _evaluateTransition(s.oodTrace, _evaluatePeriodic(z));"
+        )
+        .unwrap();
+        writeln!(s, "        for (uint256 i = 0; i < NUM_TRANSITION_CONSTRAINTS; i++) {{").unwrap();
+        writeln!(s, "            result = addmod(result, mulmod(c[i], t[i], P), P);").unwrap();
+        writeln!(s, "        }}").unwrap();
+        writeln!(s, "        uint256 numerator = 1;").unwrap();
+        for &exemption in self.transition_exemptions.iter() {
+            writeln!(
+                s,
+                "        numerator = mulmod(numerator, _sub(z, {}), P);",
+                to_decimal(exemption)
+            )
+            .unwrap();
+        }
+        writeln!(s, "        result = mulmod(mulmod(result, numerator, P), _inv(_sub(_exp(z, TRACE_LENGTH), 1)), P);").unwrap();
+        if !self.boundary_groups.is_empty() {
+            writeln!(s, "        uint256 divisor;").unwrap();
+        }
+        let has_polynomials = self.boundary_groups.iter().any(|group| {
+            group
+                .constraints
+                .iter()
+                .any(|c| matches!(c.value, BoundaryValue::Polynomial(..)))
+        });
+        if has_polynomials {
+            writeln!(s, "        uint256 x;").unwrap();
+            writeln!(s, "        uint256 value;").unwrap();
+        }
+        let offset = self.num_transition_constraints();
+        for (i, group) in self.boundary_groups.iter().enumerate() {
+            writeln!(s, "        // boundary constraint group {i}").unwrap();
+            writeln!(s, "        numerator = 0;").unwrap();
+            for constraint in group.constraints.iter() {
+                let value = match &constraint.value {
+                    BoundaryValue::PublicInput(index) => format!("s.publicInputs[{index}]"),
+                    BoundaryValue::Constant(value) => to_decimal(*value),
+                    BoundaryValue::Polynomial(poly, poly_offset) => {
+                        writeln!(s, "        x = mulmod(z, {}, P);", to_decimal(*poly_offset))
+                            .unwrap();
+                        write_horner(s, poly);
+                        "value".to_string()
+                    }
+                };
+                writeln!(
+                    s,
+                    "        numerator = addmod(numerator, mulmod(c[{}], _sub(s.oodTrace[{}], {value}), P), P);",
+                    offset + constraint.coefficient,
+                    2 * constraint.column,
+                )
+                .unwrap();
+            }
+            for &exemption in group.exemptions.iter() {
+                writeln!(
+                    s,
+                    "        numerator = mulmod(numerator, _sub(z, {}), P);",
+                    to_decimal(exemption)
+                )
+                .unwrap();
+            }
+            writeln!(s, "        divisor = 1;").unwrap();
+            for &(degree, constant) in group.numerator.iter() {
+                writeln!(
+                    s,
+                    "        divisor = mulmod(divisor, _sub(_exp(z, {degree}), {}), P);",
+                    to_decimal(constant)
+                )
+                .unwrap();
+            }
+            writeln!(s, "        result = addmod(result, mulmod(numerator, _inv(divisor), P), P);")
+                .unwrap();
+        }
+        writeln!(s, "    }}").unwrap();
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns the canonical integer representation of the specified field element.
+fn to_u128<B: StarkField>(value: B) -> u128 {
+    let mut bytes = [0; 16];
+    bytes[..B::ELEMENT_BYTES].copy_from_slice(&value.to_bytes());
+    u128::from_le_bytes(bytes)
+}
+
+/// Returns decimal representation of the specified field element.
+fn to_decimal<B: StarkField>(value: B) -> String {
+    to_u128(value).to_string()
+}
+
+/// Writes statements which evaluate the specified polynomial at `x` into variable `value`.
+fn write_horner<B: StarkField>(s: &mut String, poly: &[B]) {
+    let mut coefficients = poly.iter().rev();
+    let leading = coefficients.next().copied().unwrap_or(B::ZERO);
+    writeln!(s, "        value = {};", to_decimal(leading)).unwrap();
+    for &coefficient in coefficients {
+        writeln!(
+            s,
+            "        value = addmod(mulmod(value, x, P), {}, P);",
+            to_decimal(coefficient)
+        )
+        .unwrap();
+    }
+}
+
+// STATIC CONTRACT PARTS
+// ================================================================================================
+
+const STATE: &str = r#"
+    /// Values of the proof transcript shared by verification steps.
+    struct State {
+        bytes32 seed;
+        uint64 counter;
+        uint256 cursor;
+        uint256[] publicInputs;
+        bytes32 traceCommitment;
+        bytes32 constraintCommitment;
+        uint256[] coefficients;
+        uint256 z;
+        uint256[] oodTrace;
+        uint256[] oodEvaluations;
+        uint256[] deepCoefficients;
+        bytes32[] friCommitments;
+        uint256[] friAlphas;
+        uint256 numPartitions;
+        uint256[] positions;
+        uint256[] evaluations;
+    }
+"#;
+
+const VERIFY: &str = r#"
+    /// Verifies a STARK proof against the specified public inputs; reverts if the proof is not
+    /// valid.
+    function verify(uint256[] calldata publicInputs, uint256[] calldata proof)
+        external pure returns (bool)
+    {
+        require(publicInputs.length == NUM_PUBLIC_INPUTS, "invalid number of public inputs");
+        State memory s;
+        s.publicInputs = publicInputs;
+        for (uint256 i = 0; i < NUM_PUBLIC_INPUTS; i++) {
+            require(publicInputs[i] < P, "invalid field element");
+        }
+        s.seed = keccak256(abi.encodePacked(CONTEXT, _toBytes(s.publicInputs)));
+
+        // commitments to the trace and to the constraint composition polynomial
+        s.traceCommitment = _readWord(s, proof);
+        _reseed(s, s.traceCommitment);
+        s.coefficients = _drawElements(s, NUM_TRANSITION_CONSTRAINTS + NUM_ASSERTIONS);
+        s.constraintCommitment = _readWord(s, proof);
+        _reseed(s, s.constraintCommitment);
+        s.z = _draw(s);
+
+        // out-of-domain consistency check
+        s.oodTrace = _readElements(s, proof, 2 * TRACE_WIDTH);
+        s.oodEvaluations = _readElements(s, proof, NUM_COMPOSITION_COLUMNS);
+        _reseed(s, keccak256(_toBytes(s.oodTrace)));
+        _reseed(s, keccak256(_toBytes(s.oodEvaluations)));
+        require(_evaluateConstraints(s) == _combineEvaluations(s), "inconsistent OOD constraint evaluations");
+
+        // FRI commitments
+        s.deepCoefficients = _drawElements(s, TRACE_WIDTH + NUM_COMPOSITION_COLUMNS);
+        s.friCommitments = new bytes32[](NUM_FRI_LAYERS + 1);
+        s.friAlphas = new uint256[](NUM_FRI_LAYERS + 1);
+        for (uint256 i = 0; i <= NUM_FRI_LAYERS; i++) {
+            s.friCommitments[i] = _readWord(s, proof);
+            _reseed(s, s.friCommitments[i]);
+            s.friAlphas[i] = _draw(s);
+        }
+
+        // proof-of-work and query positions
+        uint256 nonce = _readU64(s, proof);
+        s.numPartitions = _readU64(s, proof);
+        require(s.numPartitions > 0 && s.numPartitions < 256, "invalid number of FRI partitions");
+        uint256 pow = _leInt(_mergeWithInt(s.seed, nonce), 8);
+        require((pow & ((uint256(1) << GRINDING_FACTOR) - 1)) == 0, "query seed proof-of-work verification failed");
+        s.positions = _drawPositions(s, nonce);
+
+        // trace and constraint queries, DEEP composition, and FRI
+        _verifyQueries(s, proof);
+        _verifyFri(s, proof);
+
+        require(s.cursor == proof.length, "proof contains unconsumed words");
+        return true;
+    }
+
+    /// Combines out-of-domain evaluations of constraint composition columns into an evaluation
+    /// of the constraint composition polynomial.
+    function _combineEvaluations(State memory s) internal pure returns (uint256 result) {
+        uint256 zn = _exp(s.z, TRACE_LENGTH);
+        uint256 power = 1;
+        for (uint256 i = 0; i < NUM_COMPOSITION_COLUMNS; i++) {
+            result = addmod(result, mulmod(power, s.oodEvaluations[i], P), P);
+            power = mulmod(power, zn, P);
+        }
+    }
+
+    /// Checks trace and constraint queries against their commitments and computes evaluations
+    /// of the DEEP composition polynomial at query positions.
+    function _verifyQueries(State memory s, uint256[] calldata proof) internal pure {
+        uint256 numQueries = s.positions.length;
+        uint256[] memory t1 = new uint256[](numQueries);
+        uint256[] memory t2 = new uint256[](numQueries);
+        for (uint256 q = 0; q < numQueries; q++) {
+            uint256[] memory row = _readElements(s, proof, TRACE_WIDTH);
+            bytes32 root = _readRoot(s, proof, keccak256(_toBytes(row)), s.positions[q], LDE_DEPTH);
+            require(root == s.traceCommitment, "trace query does not match commitment");
+            for (uint256 i = 0; i < TRACE_WIDTH; i++) {
+                t1[q] = addmod(t1[q], mulmod(s.deepCoefficients[i], _sub(row[i], s.oodTrace[2 * i]), P), P);
+                t2[q] = addmod(t2[q], mulmod(s.deepCoefficients[i], _sub(row[i], s.oodTrace[2 * i + 1]), P), P);
+            }
+        }
+
+        uint256 zNext = mulmod(s.z, TRACE_GENERATOR, P);
+        s.evaluations = new uint256[](numQueries);
+        for (uint256 q = 0; q < numQueries; q++) {
+            uint256[] memory row = _readElements(s, proof, NUM_COMPOSITION_COLUMNS);
+            bytes32 root = _readRoot(s, proof, keccak256(_toBytes(row)), s.positions[q], LDE_DEPTH);
+            require(root == s.constraintCommitment, "constraint query does not match commitment");
+            uint256 c = t1[q];
+            for (uint256 j = 0; j < NUM_COMPOSITION_COLUMNS; j++) {
+                c = addmod(c, mulmod(s.deepCoefficients[TRACE_WIDTH + j], _sub(row[j], s.oodEvaluations[j]), P), P);
+            }
+            uint256 x = mulmod(DOMAIN_OFFSET, _exp(LDE_GENERATOR, s.positions[q]), P);
+            s.evaluations[q] = addmod(
+                mulmod(c, _inv(_sub(x, s.z)), P),
+                mulmod(t2[q], _inv(_sub(x, zNext)), P),
+                P
+            );
+        }
+    }
+
+    /// Verifies that evaluations of the DEEP composition polynomial at query positions are
+    /// evaluations of a polynomial of bounded degree.
+    function _verifyFri(State memory s, uint256[] calldata proof) internal pure {
+        uint256 domainSize = LDE_DOMAIN_SIZE;
+        uint256 generator = LDE_GENERATOR;
+        uint256[] memory positions = s.positions;
+        uint256[] memory evaluations = s.evaluations;
+        for (uint256 layer = 0; layer < NUM_FRI_LAYERS; layer++) {
+            uint256 rowLength = domainSize / FOLDING_FACTOR;
+            uint256[] memory folded = _foldPositions(positions, rowLength);
+            uint256[] memory values = _readLayer(s, proof, s.friCommitments[layer], folded, rowLength);
+            _checkLayer(positions, evaluations, folded, values, rowLength);
+            evaluations = _foldValues(folded, values, s.friAlphas[layer], generator);
+            positions = folded;
+            generator = _exp(generator, FOLDING_FACTOR);
+            domainSize = rowLength;
+        }
+
+        uint256[] memory remainder = _readElements(s, proof, REMAINDER_SIZE);
+        require(
+            keccak256(_toBytes(remainder)) == s.friCommitments[NUM_FRI_LAYERS],
+            "FRI remainder commitment mismatch"
+        );
+        for (uint256 q = 0; q < positions.length; q++) {
+            uint256 x = mulmod(DOMAIN_OFFSET, _exp(generator, positions[q]), P);
+            uint256 value = 0;
+            for (uint256 i = REMAINDER_SIZE; i > 0; i--) {
+                value = addmod(mulmod(value, x, P), remainder[i - 1], P);
+            }
+            require(value == evaluations[q], "invalid FRI remainder folding");
+        }
+    }
+
+    /// Returns unique positions in the folded domain in the order in which they first appear.
+    function _foldPositions(uint256[] memory positions, uint256 rowLength)
+        internal pure returns (uint256[] memory folded)
+    {
+        folded = new uint256[](positions.length);
+        uint256 count = 0;
+        for (uint256 i = 0; i < positions.length; i++) {
+            uint256 position = positions[i] % rowLength;
+            bool found = false;
+            for (uint256 j = 0; j < count; j++) {
+                if (folded[j] == position) {
+                    found = true;
+                    break;
+                }
+            }
+            if (!found) {
+                folded[count] = position;
+                count++;
+            }
+        }
+        assembly {
+            mstore(folded, count)
+        }
+    }
+
+    /// Reads values of a FRI layer at folded positions and checks them against the commitment
+    /// to the layer.
+    function _readLayer(
+        State memory s,
+        uint256[] calldata proof,
+        bytes32 commitment,
+        uint256[] memory folded,
+        uint256 rowLength
+    ) internal pure returns (uint256[] memory values) {
+        values = new uint256[](folded.length * FOLDING_FACTOR);
+        uint256 depth = _log2(rowLength);
+        for (uint256 k = 0; k < folded.length; k++) {
+            uint256 index = folded[k];
+            if (s.numPartitions > 1) {
+                uint256 partition = index % s.numPartitions;
+                index = partition * (rowLength / s.numPartitions) + (index - partition) / s.numPartitions;
+            }
+            uint256[] memory row = _readElements(s, proof, FOLDING_FACTOR);
+            bytes32 root = _readRoot(s, proof, keccak256(_toBytes(row)), index, depth);
+            require(root == commitment, "FRI layer commitment mismatch");
+            for (uint256 i = 0; i < FOLDING_FACTOR; i++) {
+                values[k * FOLDING_FACTOR + i] = row[i];
+            }
+        }
+    }
+
+    /// Checks that values of a FRI layer are consistent with evaluations from the previous layer.
+    function _checkLayer(
+        uint256[] memory positions,
+        uint256[] memory evaluations,
+        uint256[] memory folded,
+        uint256[] memory values,
+        uint256 rowLength
+    ) internal pure {
+        for (uint256 q = 0; q < positions.length; q++) {
+            uint256 k = 0;
+            while (folded[k] != positions[q] % rowLength) {
+                k++;
+            }
+            require(
+                values[k * FOLDING_FACTOR + positions[q] / rowLength] == evaluations[q],
+                "invalid FRI layer folding"
+            );
+        }
+    }
+
+    /// Evaluates polynomials interpolated over cosets x_i = xe * r^i at alpha via
+    /// f(alpha) = (alpha^n - xe^n) / (n * xe^n) * sum_i(y_i * x_i / (alpha - x_i)).
+    function _foldValues(uint256[] memory folded, uint256[] memory values, uint256 alpha, uint256 generator)
+        internal pure returns (uint256[] memory result)
+    {
+        result = new uint256[](folded.length);
+        uint256 alphaN = _exp(alpha, FOLDING_FACTOR);
+        for (uint256 k = 0; k < folded.length; k++) {
+            uint256 x = mulmod(DOMAIN_OFFSET, _exp(generator, folded[k]), P);
+            uint256 xeN = _exp(x, FOLDING_FACTOR);
+            uint256 sum = 0;
+            for (uint256 i = 0; i < FOLDING_FACTOR; i++) {
+                uint256 y = mulmod(values[k * FOLDING_FACTOR + i], x, P);
+                sum = addmod(sum, mulmod(y, _inv(_sub(alpha, x)), P), P);
+                x = mulmod(x, FOLDING_ROOT, P);
+            }
+            sum = mulmod(sum, _sub(alphaN, xeN), P);
+            result[k] = mulmod(sum, _inv(mulmod(FOLDING_FACTOR, xeN, P)), P);
+        }
+    }
+"#;
+
+const HELPERS: &str = r#"
+    // PUBLIC COIN
+
+    function _reseed(State memory s, bytes32 data) internal pure {
+        s.seed = keccak256(abi.encodePacked(s.seed, data));
+        s.counter = 0;
+    }
+
+    function _mergeWithInt(bytes32 seed, uint256 value) internal pure returns (bytes32) {
+        return keccak256(abi.encodePacked(seed, bytes8(uint64(_reverseBytes(value, 8)))));
+    }
+
+    function _draw(State memory s) internal pure returns (uint256) {
+        for (uint256 i = 0; i < 1000; i++) {
+            s.counter++;
+            uint256 value = _leInt(_mergeWithInt(s.seed, s.counter), ELEMENT_BYTES);
+            if (value < P) {
+                return value;
+            }
+        }
+        revert("failed to draw a random field element");
+    }
+
+    function _drawElements(State memory s, uint256 n) internal pure returns (uint256[] memory result) {
+        result = new uint256[](n);
+        for (uint256 i = 0; i < n; i++) {
+            result[i] = _draw(s);
+        }
+    }
+
+    /// Draws query positions, and returns them sorted and deduplicated. When queries are
+    /// normalized, positions are redrawn until there are NUM_QUERIES positions which are distinct
+    /// modulo QUERY_MODULUS.
+    function _drawPositions(State memory s, uint256 nonce) internal pure returns (uint256[] memory result) {
+        result = new uint256[](NUM_QUERIES);
+        uint256 count = 0;
+        uint256 numDraws = NUM_QUERIES;
+        for (uint256 round = 0; round < 1000; round++) {
+            s.seed = _mergeWithInt(s.seed, nonce);
+            s.counter = 0;
+            for (uint256 i = 0; i < numDraws; i++) {
+                s.counter++;
+                uint256 value = _leInt(_mergeWithInt(s.seed, s.counter), 8) & (LDE_DOMAIN_SIZE - 1);
+                bool isDuplicate = false;
+                for (uint256 j = 0; j < count; j++) {
+                    if (result[j] % QUERY_MODULUS == value % QUERY_MODULUS) {
+                        isDuplicate = true;
+                        break;
+                    }
+                }
+                if (isDuplicate) {
+                    continue;
+                }
+                uint256 k = count;
+                while (k > 0 && result[k - 1] > value) {
+                    result[k] = result[k - 1];
+                    k--;
+                }
+                result[k] = value;
+                count++;
+            }
+            if (!NORMALIZED_QUERIES || count == NUM_QUERIES) {
+                break;
+            }
+            numDraws = NUM_QUERIES - count;
+        }
+        require(!NORMALIZED_QUERIES || count == NUM_QUERIES, "failed to draw query positions");
+        assembly {
+            mstore(result, count)
+        }
+    }
+
+    // PROOF READING
+
+    function _readWord(State memory s, uint256[] calldata proof) internal pure returns (bytes32) {
+        require(s.cursor < proof.length, "proof is too short");
+        s.cursor++;
+        return bytes32(proof[s.cursor - 1]);
+    }
+
+    function _readU64(State memory s, uint256[] calldata proof) internal pure returns (uint256 value) {
+        value = uint256(_readWord(s, proof));
+        require(value <= type(uint64).max, "integer does not fit into 64 bits");
+    }
+
+    function _readElements(State memory s, uint256[] calldata proof, uint256 n)
+        internal pure returns (uint256[] memory result)
+    {
+        result = new uint256[](n);
+        for (uint256 i = 0; i < n; i++) {
+            result[i] = uint256(_readWord(s, proof));
+            require(result[i] < P, "invalid field element");
+        }
+    }
+
+    /// Reads siblings of a Merkle path and returns the root computed from the path.
+    function _readRoot(State memory s, uint256[] calldata proof, bytes32 leaf, uint256 index, uint256 depth)
+        internal pure returns (bytes32 node)
+    {
+        node = leaf;
+        for (uint256 i = 0; i < depth; i++) {
+            bytes32 sibling = _readWord(s, proof);
+            if ((index & 1) == 0) {
+                node = keccak256(abi.encodePacked(node, sibling));
+            } else {
+                node = keccak256(abi.encodePacked(sibling, node));
+            }
+            index >>= 1;
+        }
+    }
+
+    // FIELD ARITHMETIC
+
+    function _sub(uint256 a, uint256 b) internal pure returns (uint256) {
+        return addmod(a, P - b, P);
+    }
+
+    function _exp(uint256 base, uint256 power) internal pure returns (uint256 result) {
+        result = 1;
+        while (power > 0) {
+            if (power & 1 == 1) {
+                result = mulmod(result, base, P);
+            }
+            base = mulmod(base, base, P);
+            power >>= 1;
+        }
+    }
+
+    function _inv(uint256 value) internal pure returns (uint256) {
+        return _exp(value, P - 2);
+    }
+
+    // ENCODING
+
+    /// Serializes field elements into little-endian bytes.
+    function _toBytes(uint256[] memory values) internal pure returns (bytes memory result) {
+        result = new bytes(values.length * ELEMENT_BYTES);
+        for (uint256 i = 0; i < values.length; i++) {
+            uint256 value = values[i];
+            for (uint256 j = 0; j < ELEMENT_BYTES; j++) {
+                result[i * ELEMENT_BYTES + j] = bytes1(uint8(value >> (8 * j)));
+            }
+        }
+    }
+
+    /// Interprets the first n bytes of a digest as a little-endian integer.
+    function _leInt(bytes32 digest, uint256 n) internal pure returns (uint256 value) {
+        for (uint256 i = 0; i < n; i++) {
+            value |= uint256(uint8(digest[i])) << (8 * i);
+        }
+    }
+
+    function _reverseBytes(uint256 value, uint256 n) internal pure returns (uint256 result) {
+        for (uint256 i = 0; i < n; i++) {
+            result = (result << 8) | ((value >> (8 * i)) & 0xff);
+        }
+    }
+
+    function _log2(uint256 value) internal pure returns (uint256 result) {
+        while (value > 1) {
+            value >>= 1;
+            result++;
+        }
+    }
+"#;
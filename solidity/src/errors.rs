@@ -0,0 +1,105 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use core::fmt;
+
+// CODEGEN ERROR
+// ================================================================================================
+/// Represents an error returned when a Solidity verifier cannot be generated for a computation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CodegenError {
+    /// Proofs of the computation are generated in an extension field of the specified degree;
+    /// generated verifiers support only proofs generated in the base field.
+    UnsupportedFieldExtension(u32),
+    /// Execution trace of the computation consists of more than one segment; generated verifiers
+    /// support only computations without auxiliary trace segments.
+    UnsupportedAuxTraceSegments(usize),
+    /// FRI folding factor is not one of the factors supported by the verifier.
+    UnsupportedFoldingFactor(usize),
+    /// FRI layers are folded according to a schedule with different folding factors; generated
+    /// verifiers support only proofs in which all FRI layers are folded by the same factor.
+    UnsupportedFoldingSchedule,
+    /// Proof-of-work is required after each FRI layer commitment; generated verifiers support
+    /// only proofs without per-layer proof-of-work.
+    UnsupportedFriLayerGrinding,
+    /// FRI query values known to the verifier are omitted from proofs; generated verifiers
+    /// support only proofs in which all FRI query values are included.
+    UnsupportedFriQueryDeduplication,
+    /// Low-degree of the constraint composition polynomial is proven using a protocol other than
+    /// FRI; generated verifiers support only FRI proofs.
+    UnsupportedLowDegreeTest,
+    /// Rows of the execution trace are hashed into commitment leaves using an encoding other
+    /// than the packed one; generated verifiers support only packed leaf encoding.
+    UnsupportedLeafEncoding,
+    /// Query positions are sampled using a strategy other than uniform sampling; generated
+    /// verifiers support only uniformly sampled query positions.
+    UnsupportedQuerySampling,
+    /// The number of queries is not smaller than the size of the LDE domain.
+    TooManyQueries(usize, usize),
+    /// Queries are normalized, but the number of queries is greater than the size of the FRI
+    /// remainder domain.
+    TooManyNormalizedQueries(usize, usize),
+    /// A sequence assertion against the specified column and step is defined by values which
+    /// depend on public inputs; generated verifiers support such values only in single-value and
+    /// periodic assertions.
+    UnsupportedSequenceAssertion(usize, usize),
+    /// An assertion with the specified first column and step is placed against a linear
+    /// combination of columns; generated verifiers support only assertions against single
+    /// columns.
+    UnsupportedLinearCombinationAssertion(usize, usize),
+    /// Trace polynomials of the computation are opened at the specified number of out-of-domain
+    /// points; generated verifiers support only openings at the default points z and z * g.
+    UnsupportedOodPoints(usize),
+}
+
+impl fmt::Display for CodegenError {
+    #[rustfmt::skip]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnsupportedFieldExtension(degree) => {
+                write!(f, "field extension of degree {degree} is not supported; proofs must be generated in the base field")
+            }
+            Self::UnsupportedAuxTraceSegments(num_segments) => {
+                write!(f, "execution trace has {num_segments} auxiliary segments, but auxiliary segments are not supported")
+            }
+            Self::UnsupportedFoldingFactor(factor) => {
+                write!(f, "folding factor {factor} is not supported")
+            }
+            Self::UnsupportedFoldingSchedule => {
+                write!(f, "FRI folding schedules are not supported; all FRI layers must be folded by the same factor")
+            }
+            Self::UnsupportedFriLayerGrinding => {
+                write!(f, "FRI layer proof-of-work is not supported")
+            }
+            Self::UnsupportedFriQueryDeduplication => {
+                write!(f, "FRI query deduplication is not supported")
+            }
+            Self::UnsupportedLowDegreeTest => {
+                write!(f, "low-degree tests other than FRI are not supported")
+            }
+            Self::UnsupportedLeafEncoding => {
+                write!(f, "leaf encodings other than packed are not supported")
+            }
+            Self::UnsupportedQuerySampling => {
+                write!(f, "query sampling strategies other than uniform are not supported")
+            }
+            Self::TooManyQueries(num_queries, domain_size) => {
+                write!(f, "number of queries ({num_queries}) must be smaller than the LDE domain size ({domain_size})")
+            }
+            Self::TooManyNormalizedQueries(num_queries, domain_size) => {
+                write!(f, "number of normalized queries ({num_queries}) cannot be greater than the FRI remainder domain size ({domain_size})")
+            }
+            Self::UnsupportedSequenceAssertion(column, step) => {
+                write!(f, "sequence assertion against column {column} at step {step} depends on public inputs, which is not supported")
+            }
+            Self::UnsupportedLinearCombinationAssertion(column, step) => {
+                write!(f, "assertion against a linear combination of columns starting with column {column} at step {step} is not supported")
+            }
+            Self::UnsupportedOodPoints(num_points) => {
+                write!(f, "opening trace polynomials at {num_points} out-of-domain points is not supported; only the default 2 points are supported")
+            }
+        }
+    }
+}
@@ -0,0 +1,337 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+//! This crate contains a code generator of Solidity verifiers for STARK proofs generated by the
+//! Winterfell prover.
+//!
+//! A verifier is generated for a specific computation (described by an [Air]), trace length, and
+//! set of [ProofOptions]. Transition constraints of the computation are extracted from
+//! [Air::evaluate_transition()] by evaluating it over symbolic field elements (see
+//! [ConstraintGraph]), and thus, no changes to the AIR are required:
+//!
+//! ```ignore
+//! let verifier = SolidityVerifier::new::<FibAir>(trace_info, pub_inputs, options)?;
+//! let source = verifier.generate_contract("FibVerifier");
+//! ```
+//!
+//! The generated contract exposes `verify(uint256[] publicInputs, uint256[] proof)` function
+//! which reverts if the proof is not valid. Proofs must be generated using [Keccak256] hash
+//! function (which is the same as `keccak256` available in the EVM) and
+//! [DefaultRandomCoin](crypto::DefaultRandomCoin), and must be converted into calldata via
+//! [SolidityVerifier::encode_proof()]. The contract follows the transcript of the Winterfell
+//! verifier exactly; [SolidityVerifier::verify_calldata()] executes the same steps as the
+//! contract in Rust, which can be used to check calldata without an EVM.
+//!
+//! Currently, the following restrictions apply:
+//! * Proofs must be generated in the base field (i.e., without field extensions), and the
+//!   execution trace must consist of a single segment.
+//! * Values of single-value and periodic assertions can be either constants or public inputs; a
+//!   value is read from public inputs if it is equal to a public input element of the instance
+//!   of the computation the verifier was generated for. Values of sequence assertions must be
+//!   constants.
+
+use air::{
+    proof::Context, Air, AuxTraceRandElements, ConstraintDivisor, FieldExtension, LeafEncoding,
+    LowDegreeTest, ProofOptions, QuerySampling, TraceInfo,
+};
+use math::{StarkField, ToElements};
+
+pub use crypto::hashers::Keccak256;
+
+pub use air::symbolic::{ConstraintGraph, Node, SymbolicField};
+
+mod calldata;
+pub use calldata::{Calldata, Word};
+
+mod contract;
+
+mod verifier;
+
+mod errors;
+pub use errors::CodegenError;
+
+#[cfg(test)]
+mod tests;
+
+// SOLIDITY VERIFIER
+// ================================================================================================
+
+/// Description of a STARK verifier for a specific computation which can be compiled into a
+/// Solidity contract.
+#[derive(Debug, Clone)]
+pub struct SolidityVerifier<B: SymbolicField> {
+    context: Vec<B>,
+    num_public_inputs: usize,
+    trace_width: usize,
+    trace_length: usize,
+    trace_generator: B,
+    lde_domain_size: usize,
+    lde_generator: B,
+    domain_offset: B,
+    num_queries: usize,
+    normalized_queries: bool,
+    grinding_factor: u32,
+    folding_factor: usize,
+    num_fri_layers: usize,
+    remainder_size: usize,
+    num_composition_columns: usize,
+    transition_graph: ConstraintGraph<B>,
+    transition_exemptions: Vec<B>,
+    periodic_column_polys: Vec<Vec<B>>,
+    num_assertions: usize,
+    boundary_groups: Vec<BoundaryGroup<B>>,
+}
+
+impl<B: SymbolicField> SolidityVerifier<B> {
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+    /// Returns a verifier for proofs of the computation specified by `A` with the specified
+    /// trace info and proof options.
+    ///
+    /// `pub_inputs` are public inputs of a sample instance of the computation; they are used to
+    /// determine which assertion values are read from public inputs (see crate documentation),
+    /// and the number of public input elements accepted by the verifier.
+    ///
+    /// # Errors
+    /// Returns an error if the computation or proof options are not supported by the generated
+    /// verifier.
+    pub fn new<A: Air<BaseField = B>>(
+        trace_info: TraceInfo,
+        pub_inputs: A::PublicInputs,
+        options: ProofOptions,
+    ) -> Result<Self, CodegenError> {
+        if options.field_extension() != FieldExtension::None {
+            return Err(CodegenError::UnsupportedFieldExtension(
+                options.field_extension().degree(),
+            ));
+        }
+        if trace_info.layout().num_aux_segments() != 0 {
+            return Err(CodegenError::UnsupportedAuxTraceSegments(
+                trace_info.layout().num_aux_segments(),
+            ));
+        }
+        let fri_options = options.to_fri_options();
+        if ![2, 4, 8, 16].contains(&fri_options.folding_factor()) {
+            return Err(CodegenError::UnsupportedFoldingFactor(fri_options.folding_factor()));
+        }
+
+        let context = Context::new::<B>(&trace_info, options.clone())
+            .with_hash_function::<Keccak256<B>>()
+            .to_elements();
+        let pub_inputs = A::derive_public_inputs(pub_inputs);
+        let pub_elements = pub_inputs.to_elements();
+        let air = A::new(trace_info, pub_inputs, options);
+        if air.options().num_queries() >= air.lde_domain_size() {
+            return Err(CodegenError::TooManyQueries(
+                air.options().num_queries(),
+                air.lde_domain_size(),
+            ));
+        }
+
+        if air.context().num_ood_points() != 2 {
+            return Err(CodegenError::UnsupportedOodPoints(air.context().num_ood_points()));
+        }
+
+        let layer_folding_factors = fri_options.layer_folding_factors(air.lde_domain_size());
+        if layer_folding_factors.iter().any(|&f| f != fri_options.folding_factor()) {
+            return Err(CodegenError::UnsupportedFoldingSchedule);
+        }
+        if fri_options.layer_grinding_factor() > 0 {
+            return Err(CodegenError::UnsupportedFriLayerGrinding);
+        }
+        if fri_options.query_deduplication() {
+            return Err(CodegenError::UnsupportedFriQueryDeduplication);
+        }
+        if fri_options.low_degree_test() != LowDegreeTest::Fri {
+            return Err(CodegenError::UnsupportedLowDegreeTest);
+        }
+        if air.options().leaf_encoding() != LeafEncoding::Packed {
+            return Err(CodegenError::UnsupportedLeafEncoding);
+        }
+        if air.options().query_sampling() != QuerySampling::Uniform {
+            return Err(CodegenError::UnsupportedQuerySampling);
+        }
+
+        let num_fri_layers = layer_folding_factors.len();
+        let remainder_domain_size = fri_options.remainder_domain_size(air.lde_domain_size());
+        if air.options().normalized_queries() && air.options().num_queries() > remainder_domain_size
+        {
+            return Err(CodegenError::TooManyNormalizedQueries(
+                air.options().num_queries(),
+                remainder_domain_size,
+            ));
+        }
+
+        Ok(SolidityVerifier {
+            num_public_inputs: pub_elements.len(),
+            trace_width: air.trace_info().width(),
+            trace_length: air.trace_length(),
+            trace_generator: air.trace_domain_generator(),
+            lde_domain_size: air.lde_domain_size(),
+            lde_generator: air.lde_domain_generator(),
+            domain_offset: air.domain_offset(),
+            num_queries: air.options().num_queries(),
+            normalized_queries: air.options().normalized_queries(),
+            grinding_factor: air.options().grinding_factor(),
+            folding_factor: fri_options.folding_factor(),
+            num_fri_layers,
+            remainder_size: remainder_domain_size / fri_options.blowup_factor(),
+            num_composition_columns: air.context().num_constraint_composition_columns(),
+            transition_graph: ConstraintGraph::new(&air),
+            transition_exemptions: ConstraintDivisor::<B>::from_transition(
+                air.trace_length(),
+                air.context().num_transition_exemptions(),
+            )
+            .exemptions()
+            .to_vec(),
+            periodic_column_polys: air.get_periodic_column_polys(),
+            num_assertions: air.context().num_assertions(),
+            boundary_groups: build_boundary_groups(&air, &pub_elements)?,
+            context,
+        })
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the graph of transition constraints checked by this verifier.
+    pub fn transition_graph(&self) -> &ConstraintGraph<B> {
+        &self.transition_graph
+    }
+
+    /// Returns the number of public input elements accepted by this verifier.
+    pub fn num_public_inputs(&self) -> usize {
+        self.num_public_inputs
+    }
+
+    // HELPERS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the number of transition constraints checked by this verifier.
+    fn num_transition_constraints(&self) -> usize {
+        self.transition_graph.outputs().len()
+    }
+
+    /// Returns the depth of the Merkle tree committing to the specified FRI layer.
+    fn fri_layer_depth(&self, layer: usize) -> usize {
+        let domain_size = self.lde_domain_size / self.folding_factor.pow(layer as u32);
+        (domain_size / self.folding_factor).ilog2() as usize
+    }
+
+    /// Returns the size of the domain of the FRI remainder polynomial.
+    fn remainder_domain_size(&self) -> usize {
+        self.lde_domain_size / self.folding_factor.pow(self.num_fri_layers as u32)
+    }
+
+    /// Returns the modulus with respect to which query positions must be distinct: the size of
+    /// the FRI remainder domain if queries are normalized, and the LDE domain size otherwise.
+    fn query_modulus(&self) -> usize {
+        if self.normalized_queries {
+            self.remainder_domain_size()
+        } else {
+            self.lde_domain_size
+        }
+    }
+
+    /// Returns a primitive root of unity of order equal to the folding factor.
+    fn folding_root(&self) -> B {
+        self.lde_generator
+            .exp(((self.lde_domain_size / self.folding_factor) as u64).into())
+    }
+}
+
+// BOUNDARY CONSTRAINTS
+// ================================================================================================
+
+/// Boundary constraints sharing the same divisor.
+#[derive(Debug, Clone)]
+struct BoundaryGroup<B: StarkField> {
+    /// Terms of the divisor numerator as (degree, constant) pairs.
+    numerator: Vec<(usize, B)>,
+    /// Points excluded from the divisor.
+    exemptions: Vec<B>,
+    constraints: Vec<BoundaryTerm<B>>,
+}
+
+/// A single boundary constraint.
+#[derive(Debug, Clone)]
+struct BoundaryTerm<B: StarkField> {
+    /// Index of the constraint composition coefficient among boundary coefficients.
+    coefficient: usize,
+    column: usize,
+    value: BoundaryValue<B>,
+}
+
+/// Value of a trace column asserted by a boundary constraint.
+#[derive(Debug, Clone)]
+enum BoundaryValue<B: StarkField> {
+    /// The element of public inputs at the specified index.
+    PublicInput(usize),
+    /// A constant value.
+    Constant(B),
+    /// A polynomial with the specified coefficients evaluated at `x * offset`.
+    Polynomial(Vec<B>, B),
+}
+
+/// Extracts boundary constraints from the specified AIR; public inputs of the AIR are used to
+/// determine which assertion values are read from public inputs.
+fn build_boundary_groups<A: Air>(
+    air: &A,
+    pub_inputs: &[A::BaseField],
+) -> Result<Vec<BoundaryGroup<A::BaseField>>, CodegenError> {
+    for assertion in air.get_assertions() {
+        if assertion.is_linear_combination() {
+            return Err(CodegenError::UnsupportedLinearCombinationAssertion(
+                assertion.column(),
+                assertion.first_step(),
+            ));
+        }
+        if assertion.values().len() > 1 && assertion.values().iter().any(|v| pub_inputs.contains(v))
+        {
+            return Err(CodegenError::UnsupportedSequenceAssertion(
+                assertion.column(),
+                assertion.first_step(),
+            ));
+        }
+    }
+
+    // constraints are sorted and grouped by the AIR; distinct composition coefficients allow us
+    // to recover the index of the coefficient assigned to each constraint
+    let markers = (1..=air.context().num_assertions() as u64)
+        .map(A::BaseField::from)
+        .collect::<Vec<_>>();
+    let constraints = air.get_boundary_constraints(&AuxTraceRandElements::new(), &markers);
+
+    let groups = constraints
+        .main_constraints()
+        .iter()
+        .map(|group| BoundaryGroup {
+            numerator: group.divisor().numerator().to_vec(),
+            exemptions: group.divisor().exemptions().to_vec(),
+            constraints: group
+                .constraints()
+                .iter()
+                .map(|constraint| {
+                    let value = match constraint.poly() {
+                        [value] => match pub_inputs.iter().position(|v| v == value) {
+                            Some(index) => BoundaryValue::PublicInput(index),
+                            None => BoundaryValue::Constant(*value),
+                        },
+                        poly => {
+                            BoundaryValue::Polynomial(poly.to_vec(), constraint.poly_offset().1)
+                        }
+                    };
+                    BoundaryTerm {
+                        coefficient: markers.iter().position(|m| m == constraint.cc()).unwrap(),
+                        column: constraint.column(),
+                        value,
+                    }
+                })
+                .collect(),
+        })
+        .collect();
+
+    Ok(groups)
+}
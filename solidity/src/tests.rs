@@ -0,0 +1,205 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{CodegenError, ConstraintGraph, Node, SolidityVerifier};
+use air::{
+    Air, AirContext, Assertion, EvaluationFrame, FieldExtension, ProofOptions, TraceInfo,
+    TransitionConstraintDegree,
+};
+use math::{fields::f128::BaseElement, FieldElement};
+use rand_utils::{rand_value, rand_vector};
+
+// CONSTRAINT GRAPH
+// ================================================================================================
+
+#[test]
+fn constraint_graph_evaluation() {
+    let air = MockAir::new(
+        TraceInfo::new(3, 16),
+        BaseElement::new(5),
+        build_options(FieldExtension::None),
+    );
+    let graph = ConstraintGraph::new(&air);
+    assert_eq!(3, graph.outputs().len());
+
+    // frame columns and periodic values are recorded only once
+    let num_frame_nodes = graph
+        .nodes()
+        .iter()
+        .filter(|node| matches!(node, Node::Current(_) | Node::Next(_) | Node::Periodic(_)))
+        .count();
+    assert_eq!(7, num_frame_nodes);
+
+    for _ in 0..10 {
+        let current = rand_vector::<BaseElement>(3);
+        let next = rand_vector::<BaseElement>(3);
+        let periodic_values = vec![rand_value::<BaseElement>()];
+
+        let frame = EvaluationFrame::from_rows(current.clone(), next.clone());
+        let mut expected = vec![BaseElement::ZERO; 3];
+        air.evaluate_transition(&frame, &periodic_values, &mut expected);
+
+        assert_eq!(expected, graph.evaluate(&current, &next, &periodic_values));
+    }
+}
+
+#[test]
+fn constraint_graph_constant_folding() {
+    let air = MockAir::new(
+        TraceInfo::new(3, 16),
+        BaseElement::new(5),
+        build_options(FieldExtension::None),
+    );
+    let graph = ConstraintGraph::new(&air);
+
+    // constant sub-expressions are folded into a single constant
+    let constants = graph
+        .nodes()
+        .iter()
+        .filter_map(|node| match node {
+            Node::Constant(value) => Some(*value),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert!(constants.contains(&(BaseElement::new(3) / BaseElement::new(7))));
+    assert!(!constants.contains(&BaseElement::new(7)));
+}
+
+// VERIFIER GENERATION
+// ================================================================================================
+
+#[test]
+fn unsupported_field_extension() {
+    let result = SolidityVerifier::new::<MockAir>(
+        TraceInfo::new(3, 16),
+        BaseElement::new(5),
+        build_options(FieldExtension::Quadratic),
+    );
+    assert_eq!(Some(CodegenError::UnsupportedFieldExtension(2)), result.err());
+}
+
+#[test]
+fn generate_contract() {
+    let verifier = SolidityVerifier::new::<MockAir>(
+        TraceInfo::new(3, 16),
+        BaseElement::new(5),
+        build_options(FieldExtension::None),
+    )
+    .unwrap();
+    assert_eq!(1, verifier.num_public_inputs());
+
+    let source = verifier.generate_contract("MockVerifier");
+    assert!(source.contains("contract MockVerifier {"));
+    assert!(source.contains("uint256 internal constant TRACE_LENGTH = 16;"));
+    assert!(source.contains("uint256 internal constant NUM_TRANSITION_CONSTRAINTS = 3;"));
+    // the first assertion is bound to the public input, while the second one is a constant
+    assert!(source.contains("_sub(s.oodTrace[0], s.publicInputs[0])"));
+    assert!(source.contains("_sub(s.oodTrace[2], 2)"));
+}
+
+#[test]
+fn unsupported_linear_combination_assertion() {
+    let result = SolidityVerifier::new::<LinearCombinationAir>(
+        TraceInfo::new(3, 16),
+        BaseElement::new(5),
+        build_options(FieldExtension::None),
+    );
+    assert_eq!(Some(CodegenError::UnsupportedLinearCombinationAssertion(1, 15)), result.err());
+}
+
+// MOCK AIR
+// ================================================================================================
+
+struct MockAir {
+    context: AirContext<BaseElement>,
+    input: BaseElement,
+}
+
+impl Air for MockAir {
+    type BaseField = BaseElement;
+    type PublicInputs = BaseElement;
+
+    fn new(trace_info: TraceInfo, pub_inputs: BaseElement, options: ProofOptions) -> Self {
+        let degrees = vec![
+            TransitionConstraintDegree::with_cycles(3, vec![4]),
+            TransitionConstraintDegree::new(5),
+            TransitionConstraintDegree::new(2),
+        ];
+        MockAir {
+            context: AirContext::new(trace_info, degrees, 2, options),
+            input: pub_inputs,
+        }
+    }
+
+    fn context(&self) -> &AirContext<Self::BaseField> {
+        &self.context
+    }
+
+    fn evaluate_transition<E: FieldElement + From<Self::BaseField>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        let current = frame.current();
+        let next = frame.next();
+        let k = E::from(3u32) / E::from(7u32);
+
+        result[0] = next[0] - (current[0] * current[0] * current[1] * k + periodic_values[0]);
+        result[1] = next[1] - current[1].exp(5u32.into()) + E::ONE;
+        result[2] = next[2] * current[2] - (current[0] - current[1]) - E::ZERO * current[2];
+    }
+
+    fn get_periodic_column_values(&self) -> Vec<Vec<Self::BaseField>> {
+        vec![vec![1u32.into(), 2u32.into(), 3u32.into(), 4u32.into()]]
+    }
+
+    fn get_assertions(&self) -> Vec<Assertion<Self::BaseField>> {
+        vec![
+            Assertion::single(0, 0, self.input),
+            Assertion::single(1, 0, BaseElement::new(2)),
+        ]
+    }
+}
+
+/// [MockAir] with an additional assertion against a linear combination of the last row.
+struct LinearCombinationAir(MockAir);
+
+impl Air for LinearCombinationAir {
+    type BaseField = BaseElement;
+    type PublicInputs = BaseElement;
+
+    fn new(trace_info: TraceInfo, pub_inputs: BaseElement, options: ProofOptions) -> Self {
+        Self(MockAir::new(trace_info, pub_inputs, options))
+    }
+
+    fn context(&self) -> &AirContext<Self::BaseField> {
+        self.0.context()
+    }
+
+    fn evaluate_transition<E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        self.0.evaluate_transition(frame, periodic_values, result)
+    }
+
+    fn get_periodic_column_values(&self) -> Vec<Vec<Self::BaseField>> {
+        self.0.get_periodic_column_values()
+    }
+
+    fn get_assertions(&self) -> Vec<Assertion<Self::BaseField>> {
+        let mut result = self.0.get_assertions();
+        let terms = vec![(1, BaseElement::ONE), (2, BaseElement::new(1 << 32))];
+        result.push(Assertion::linear_combination(terms, 15, BaseElement::new(7)));
+        result
+    }
+}
+
+fn build_options(field_extension: FieldExtension) -> ProofOptions {
+    ProofOptions::new(28, 8, 0, field_extension, 4, 7)
+}
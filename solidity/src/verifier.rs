@@ -0,0 +1,510 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use crate::{
+    calldata::word_to_element, BoundaryValue, Calldata, SolidityVerifier, SymbolicField, Word,
+};
+use fri::VerifierError as FriVerifierError;
+use math::StarkField;
+use sha3::{Digest as _, Keccak256};
+use verifier::VerifierError;
+
+// REFERENCE VERIFIER
+// ================================================================================================
+
+impl<B: SymbolicField> SolidityVerifier<B> {
+    /// Verifies calldata produced by [encode_proof()](SolidityVerifier::encode_proof) by executing
+    /// the same steps as the generated contract.
+    ///
+    /// This function mirrors the generated contract operation by operation: it reads the proof
+    /// word by word, and uses only the primitives available in the EVM (i.e., arithmetic over
+    /// the base field and `keccak256` hashing). Thus, it can be used to check calldata for the
+    /// contract without executing the contract.
+    ///
+    /// # Errors
+    /// Returns an error if the calldata does not attest to a correct execution of the
+    /// computation; the errors are the same as the errors returned by
+    /// [verify()](verifier::verify) for the same proof.
+    pub fn verify_calldata(&self, calldata: &Calldata) -> Result<(), VerifierError> {
+        // 1 ----- public inputs and public coin seed ---------------------------------------------
+        if calldata.public_inputs.len() != self.num_public_inputs {
+            return Err(VerifierError::ProofDeserializationError(format!(
+                "expected {} public input elements, but was {}",
+                self.num_public_inputs,
+                calldata.public_inputs.len()
+            )));
+        }
+        let pub_inputs = calldata
+            .public_inputs
+            .iter()
+            .map(|word| word_to_element::<B>(word).ok_or_else(invalid_element))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut seed = elements_to_bytes(&self.context);
+        seed.extend_from_slice(&elements_to_bytes(&pub_inputs));
+        let mut coin = PublicCoin::new(keccak(&seed));
+        let mut proof = ProofReader::new(&calldata.proof);
+
+        // 2 ----- trace and constraint commitments -----------------------------------------------
+        let trace_commitment = proof.read_word()?;
+        coin.reseed(&trace_commitment);
+        let constraint_coeffs =
+            coin.draw_elements::<B>(self.num_transition_constraints() + self.num_assertions)?;
+        let constraint_commitment = proof.read_word()?;
+        coin.reseed(&constraint_commitment);
+        let z = coin.draw::<B>()?;
+
+        // 3 ----- OOD consistency check ----------------------------------------------------------
+        let ood_trace = proof.read_elements::<B>(2 * self.trace_width)?;
+        let ood_evaluations = proof.read_elements::<B>(self.num_composition_columns)?;
+        coin.reseed(&hash_elements(&ood_trace));
+        coin.reseed(&hash_elements(&ood_evaluations));
+
+        let z_n = exp(z, self.trace_length as u128);
+        let mut power = B::ONE;
+        let mut expected = B::ZERO;
+        for &evaluation in ood_evaluations.iter() {
+            expected += power * evaluation;
+            power *= z_n;
+        }
+        if self.evaluate_constraints(&ood_trace, &constraint_coeffs, z, &pub_inputs) != expected {
+            return Err(VerifierError::InconsistentOodConstraintEvaluations);
+        }
+
+        // 4 ----- FRI commitments ----------------------------------------------------------------
+        let deep_coeffs =
+            coin.draw_elements::<B>(self.trace_width + self.num_composition_columns)?;
+        let fri_commitments = (0..=self.num_fri_layers)
+            .map(|_| proof.read_word())
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut fri_alphas = Vec::with_capacity(fri_commitments.len());
+        for commitment in fri_commitments.iter() {
+            coin.reseed(commitment);
+            fri_alphas.push(coin.draw::<B>()?);
+        }
+
+        // 5 ----- proof-of-work and query positions ----------------------------------------------
+        let pow_nonce = proof.read_u64()?;
+        let num_partitions = proof.read_u64()? as usize;
+        if num_partitions == 0 || num_partitions > u8::MAX as usize {
+            return Err(VerifierError::ProofDeserializationError(format!(
+                "invalid number of FRI partitions: {num_partitions}"
+            )));
+        }
+        if coin.trailing_zeros(pow_nonce) < self.grinding_factor {
+            return Err(VerifierError::QuerySeedProofOfWorkVerificationFailed);
+        }
+        let positions = coin.draw_positions(
+            pow_nonce,
+            self.num_queries,
+            self.lde_domain_size,
+            self.normalized_queries.then(|| self.query_modulus()),
+        )?;
+
+        // 6 ----- trace and constraint queries ---------------------------------------------------
+        let depth = self.lde_domain_size.ilog2() as usize;
+        let mut t1_sums = Vec::with_capacity(positions.len());
+        let mut t2_sums = Vec::with_capacity(positions.len());
+        for &position in positions.iter() {
+            let row = proof.read_elements::<B>(self.trace_width)?;
+            if proof.read_root(hash_elements(&row), position, depth)? != trace_commitment {
+                return Err(VerifierError::TraceQueryDoesNotMatchCommitment(0, position));
+            }
+            let mut t1 = B::ZERO;
+            let mut t2 = B::ZERO;
+            for (i, &value) in row.iter().enumerate() {
+                t1 += deep_coeffs[i] * (value - ood_trace[2 * i]);
+                t2 += deep_coeffs[i] * (value - ood_trace[2 * i + 1]);
+            }
+            t1_sums.push(t1);
+            t2_sums.push(t2);
+        }
+
+        let mut c_sums = Vec::with_capacity(positions.len());
+        for &position in positions.iter() {
+            let row = proof.read_elements::<B>(self.num_composition_columns)?;
+            if proof.read_root(hash_elements(&row), position, depth)? != constraint_commitment {
+                return Err(VerifierError::ConstraintQueryDoesNotMatchCommitment(position));
+            }
+            let mut c = B::ZERO;
+            for (j, &value) in row.iter().enumerate() {
+                c += deep_coeffs[self.trace_width + j] * (value - ood_evaluations[j]);
+            }
+            c_sums.push(c);
+        }
+
+        // 7 ----- DEEP composition ---------------------------------------------------------------
+        let z_next = z * self.trace_generator;
+        let evaluations = positions
+            .iter()
+            .enumerate()
+            .map(|(q, &position)| {
+                let x = self.domain_offset * exp(self.lde_generator, position as u128);
+                (t1_sums[q] + c_sums[q]) / (x - z) + t2_sums[q] / (x - z_next)
+            })
+            .collect::<Vec<_>>();
+
+        // 8 ----- FRI ----------------------------------------------------------------------------
+        self.verify_fri(
+            &mut proof,
+            &fri_commitments,
+            &fri_alphas,
+            num_partitions,
+            positions,
+            evaluations,
+        )?;
+
+        if !proof.is_empty() {
+            return Err(VerifierError::ProofDeserializationError(
+                "proof contains unconsumed words".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    // HELPER METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Evaluates the random linear combination of transition and boundary constraints, divided
+    /// by their divisors, over the specified out-of-domain frame.
+    fn evaluate_constraints(&self, ood_trace: &[B], coeffs: &[B], z: B, pub_inputs: &[B]) -> B {
+        let current = ood_trace.iter().step_by(2).copied().collect::<Vec<_>>();
+        let next = ood_trace.iter().skip(1).step_by(2).copied().collect::<Vec<_>>();
+
+        // transition constraints
+        let periodic_values = self
+            .periodic_column_polys
+            .iter()
+            .map(|poly| horner(poly, exp(z, (self.trace_length / poly.len()) as u128)))
+            .collect::<Vec<_>>();
+        let evaluations = self.transition_graph.evaluate(&current, &next, &periodic_values);
+        let mut result = B::ZERO;
+        for (i, &evaluation) in evaluations.iter().enumerate() {
+            result += coeffs[i] * evaluation;
+        }
+        let exemptions = self.transition_exemptions.iter().fold(B::ONE, |acc, &e| acc * (z - e));
+        result = result * exemptions / (exp(z, self.trace_length as u128) - B::ONE);
+
+        // boundary constraints
+        let coeffs = &coeffs[self.num_transition_constraints()..];
+        for group in self.boundary_groups.iter() {
+            let mut numerator = B::ZERO;
+            for constraint in group.constraints.iter() {
+                let value = match &constraint.value {
+                    BoundaryValue::PublicInput(index) => pub_inputs[*index],
+                    BoundaryValue::Constant(value) => *value,
+                    BoundaryValue::Polynomial(poly, offset) => horner(poly, z * *offset),
+                };
+                numerator += coeffs[constraint.coefficient] * (current[constraint.column] - value);
+            }
+            let exemptions = group.exemptions.iter().fold(B::ONE, |acc, &e| acc * (z - e));
+            let divisor = group
+                .numerator
+                .iter()
+                .fold(B::ONE, |acc, &(degree, constant)| acc * (exp(z, degree as u128) - constant));
+            result += numerator * exemptions / divisor;
+        }
+
+        result
+    }
+
+    /// Verifies that the specified evaluations of the DEEP composition polynomial are
+    /// evaluations of a polynomial of bounded degree.
+    fn verify_fri(
+        &self,
+        proof: &mut ProofReader,
+        commitments: &[Word],
+        alphas: &[B],
+        num_partitions: usize,
+        mut positions: Vec<usize>,
+        mut evaluations: Vec<B>,
+    ) -> Result<(), VerifierError> {
+        let n = self.folding_factor;
+        let folding_root = self.folding_root();
+        let mut domain_size = self.lde_domain_size;
+        let mut domain_generator = self.lde_generator;
+
+        for layer in 0..self.num_fri_layers {
+            // read layer values at folded positions and check them against the layer commitment
+            let row_length = domain_size / n;
+            let mut folded_positions: Vec<usize> = Vec::new();
+            for &position in positions.iter() {
+                if !folded_positions.contains(&(position % row_length)) {
+                    folded_positions.push(position % row_length);
+                }
+            }
+            let mut values = Vec::with_capacity(folded_positions.len() * n);
+            for &position in folded_positions.iter() {
+                let index = if num_partitions == 1 {
+                    position
+                } else {
+                    let partition_size = row_length / num_partitions;
+                    let partition = position % num_partitions;
+                    partition * partition_size + (position - partition) / num_partitions
+                };
+                let row = proof.read_elements::<B>(n)?;
+                let root =
+                    proof.read_root(hash_elements(&row), index, self.fri_layer_depth(layer))?;
+                if root != commitments[layer] {
+                    return Err(fri_error(FriVerifierError::LayerCommitmentMismatch(layer)));
+                }
+                values.extend_from_slice(&row);
+            }
+
+            // make sure the values are consistent with evaluations from the previous layer
+            for (&position, &evaluation) in positions.iter().zip(evaluations.iter()) {
+                let idx = folded_positions.iter().position(|&p| p == position % row_length);
+                if values[idx.unwrap() * n + position / row_length] != evaluation {
+                    return Err(fri_error(FriVerifierError::InvalidLayerFolding(layer, position)));
+                }
+            }
+
+            // evaluate polynomials interpolated over cosets x_i = xe * r^i at alpha via
+            // f(alpha) = (alpha^n - xe^n) / (n * xe^n) * sum_i(y_i * x_i / (alpha - x_i))
+            let alpha = alphas[layer];
+            evaluations = folded_positions
+                .iter()
+                .enumerate()
+                .map(|(k, &position)| {
+                    let xe = self.domain_offset * exp(domain_generator, position as u128);
+                    let xe_n = exp(xe, n as u128);
+                    let mut x = xe;
+                    let mut sum = B::ZERO;
+                    for &y in values[k * n..(k + 1) * n].iter() {
+                        sum += y * x / (alpha - x);
+                        x *= folding_root;
+                    }
+                    (exp(alpha, n as u128) - xe_n) / (B::from(n as u64) * xe_n) * sum
+                })
+                .collect();
+
+            positions = folded_positions;
+            domain_generator = exp(domain_generator, n as u128);
+            domain_size /= n;
+        }
+
+        // check the remainder polynomial against its commitment and the last layer evaluations
+        let remainder = proof.read_elements::<B>(self.remainder_size)?;
+        if hash_elements(&remainder) != commitments[self.num_fri_layers] {
+            return Err(fri_error(FriVerifierError::RemainderCommitmentMismatch));
+        }
+        for (&position, &evaluation) in positions.iter().zip(evaluations.iter()) {
+            let x = self.domain_offset * exp(domain_generator, position as u128);
+            if horner(&remainder, x) != evaluation {
+                return Err(fri_error(FriVerifierError::InvalidRemainderFolding(position)));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// PUBLIC COIN
+// ================================================================================================
+
+/// Public coin which draws values in the same way as [DefaultRandomCoin](crypto::DefaultRandomCoin)
+/// instantiated with Keccak256, using only operations available in the EVM.
+struct PublicCoin {
+    seed: Word,
+    counter: u64,
+}
+
+impl PublicCoin {
+    fn new(seed: Word) -> Self {
+        Self { seed, counter: 0 }
+    }
+
+    fn reseed(&mut self, data: &Word) {
+        self.seed = merge(&self.seed, data);
+        self.counter = 0;
+    }
+
+    fn next(&mut self) -> Word {
+        self.counter += 1;
+        merge_with_int(&self.seed, self.counter)
+    }
+
+    fn draw<B: StarkField>(&mut self) -> Result<B, VerifierError> {
+        for _ in 0..1000 {
+            let value = self.next();
+            let element = B::try_from(&value[..B::ELEMENT_BYTES]).ok();
+            if let Some(element) = element {
+                return Ok(element);
+            }
+        }
+        Err(VerifierError::RandomCoinError)
+    }
+
+    fn draw_elements<B: StarkField>(
+        &mut self,
+        num_elements: usize,
+    ) -> Result<Vec<B>, VerifierError> {
+        (0..num_elements).map(|_| self.draw()).collect()
+    }
+
+    fn trailing_zeros(&self, nonce: u64) -> u32 {
+        read_u64_le(&merge_with_int(&self.seed, nonce)).trailing_zeros()
+    }
+
+    /// Returns sorted unique positions drawn from the domain of the specified size.
+    ///
+    /// If `modulus` is provided, positions are redrawn until there are `num_positions` positions
+    /// which are distinct modulo `modulus`.
+    fn draw_positions(
+        &mut self,
+        nonce: u64,
+        num_positions: usize,
+        domain_size: usize,
+        modulus: Option<usize>,
+    ) -> Result<Vec<usize>, VerifierError> {
+        let mut positions: Vec<usize> = Vec::with_capacity(num_positions);
+        let mut num_draws = num_positions;
+        for _ in 0..1000 {
+            self.seed = merge_with_int(&self.seed, nonce);
+            self.counter = 0;
+            for _ in 0..num_draws {
+                let value = (read_u64_le(&self.next()) & (domain_size as u64 - 1)) as usize;
+                let m = modulus.unwrap_or(domain_size);
+                if !positions.iter().any(|&p| p % m == value % m) {
+                    positions.push(value);
+                }
+            }
+            if modulus.is_none() || positions.len() == num_positions {
+                break;
+            }
+            num_draws = num_positions - positions.len();
+        }
+        if modulus.is_some() && positions.len() != num_positions {
+            return Err(VerifierError::RandomCoinError);
+        }
+        positions.sort_unstable();
+        Ok(positions)
+    }
+}
+
+// PROOF READER
+// ================================================================================================
+
+/// Reads words of an encoded proof in order.
+struct ProofReader<'a> {
+    words: &'a [Word],
+    pos: usize,
+}
+
+impl<'a> ProofReader<'a> {
+    fn new(words: &'a [Word]) -> Self {
+        Self { words, pos: 0 }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.pos == self.words.len()
+    }
+
+    fn read_word(&mut self) -> Result<Word, VerifierError> {
+        let word = self.words.get(self.pos).ok_or_else(|| {
+            VerifierError::ProofDeserializationError("proof is too short".to_string())
+        })?;
+        self.pos += 1;
+        Ok(*word)
+    }
+
+    fn read_u64(&mut self) -> Result<u64, VerifierError> {
+        let word = self.read_word()?;
+        if word[..24].iter().any(|&byte| byte != 0) {
+            return Err(VerifierError::ProofDeserializationError(
+                "integer does not fit into 64 bits".to_string(),
+            ));
+        }
+        Ok(u64::from_be_bytes(word[24..].try_into().unwrap()))
+    }
+
+    fn read_elements<B: StarkField>(
+        &mut self,
+        num_elements: usize,
+    ) -> Result<Vec<B>, VerifierError> {
+        (0..num_elements)
+            .map(|_| word_to_element(&self.read_word()?).ok_or_else(invalid_element))
+            .collect()
+    }
+
+    /// Reads siblings of a Merkle path for the specified leaf and returns the root of the tree
+    /// computed from the path.
+    fn read_root(
+        &mut self,
+        leaf: Word,
+        mut index: usize,
+        depth: usize,
+    ) -> Result<Word, VerifierError> {
+        let mut node = leaf;
+        for _ in 0..depth {
+            let sibling = self.read_word()?;
+            node = if index & 1 == 0 {
+                merge(&node, &sibling)
+            } else {
+                merge(&sibling, &node)
+            };
+            index >>= 1;
+        }
+        Ok(node)
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+fn keccak(bytes: &[u8]) -> Word {
+    Keccak256::digest(bytes).into()
+}
+
+fn merge(a: &Word, b: &Word) -> Word {
+    let mut data = [0; 64];
+    data[..32].copy_from_slice(a);
+    data[32..].copy_from_slice(b);
+    keccak(&data)
+}
+
+fn merge_with_int(seed: &Word, value: u64) -> Word {
+    let mut data = [0; 40];
+    data[..32].copy_from_slice(seed);
+    data[32..].copy_from_slice(&value.to_le_bytes());
+    keccak(&data)
+}
+
+/// Returns little-endian encoding of the specified elements.
+fn elements_to_bytes<B: StarkField>(elements: &[B]) -> Vec<u8> {
+    elements.iter().flat_map(|element| element.to_bytes()).collect()
+}
+
+fn hash_elements<B: StarkField>(elements: &[B]) -> Word {
+    keccak(&elements_to_bytes(elements))
+}
+
+fn read_u64_le(word: &Word) -> u64 {
+    u64::from_le_bytes(word[..8].try_into().unwrap())
+}
+
+fn horner<B: StarkField>(poly: &[B], x: B) -> B {
+    poly.iter().rev().fold(B::ZERO, |acc, &coeff| acc * x + coeff)
+}
+
+fn invalid_element() -> VerifierError {
+    VerifierError::ProofDeserializationError("invalid field element".to_string())
+}
+
+fn fri_error(err: FriVerifierError) -> VerifierError {
+    VerifierError::FriVerificationFailed(err)
+}
+
+/// Raises `base` to the specified power.
+fn exp<B: StarkField>(base: B, power: u128) -> B {
+    let mut result = B::ONE;
+    for i in (0..128 - power.leading_zeros()).rev() {
+        result = result.square();
+        if (power >> i) & 1 == 1 {
+            result *= base;
+        }
+    }
+    result
+}
@@ -0,0 +1,351 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use prover::{
+    crypto::DefaultRandomCoin,
+    math::{fields::f128::BaseElement, FieldElement},
+    matrix::ColMatrix,
+    Air, AirContext, Assertion, AuxTraceRandElements, ConstraintCompositionCoefficients,
+    DefaultConstraintEvaluator, DefaultTraceLde, EvaluationFrame, FieldExtension, LeafEncoding,
+    LowDegreeTest, ProofOptions, Prover, StarkDomain, Trace, TraceInfo, TracePolyTable, TraceTable,
+    TransitionConstraintDegree,
+};
+use verifier::{AcceptableOptions, VerifierError};
+use winter_solidity::{CodegenError, Keccak256, SolidityVerifier};
+
+type Keccak = Keccak256<BaseElement>;
+
+// TESTS
+// ================================================================================================
+
+#[test]
+fn solidity_verifier() {
+    let prover = FibProver::new(build_options());
+    let trace = build_trace(64);
+    let result = trace.get(1, trace.length() - 1);
+    let verifier =
+        SolidityVerifier::new::<FibAir>(trace.get_info(), result, build_options()).unwrap();
+    let proof = prover.prove(trace).unwrap();
+    let calldata = verifier.encode_proof(proof.clone(), &result).unwrap();
+    assert_eq!(Ok(()), verifier.verify_calldata(&calldata));
+
+    // the proof is also accepted by the regular verifier
+    let acceptable_options = AcceptableOptions::OptionSet(vec![build_options()]);
+    let verified = verifier::verify::<FibAir, Keccak, DefaultRandomCoin<Keccak>>(
+        proof,
+        result,
+        &acceptable_options,
+    );
+    assert_eq!(Ok(()), verified);
+
+    // the last assertion is bound to the public input, and wrong inputs are rejected
+    let mut tampered = calldata.clone();
+    tampered.public_inputs[0][31] ^= 1;
+    assert_eq!(
+        Err(VerifierError::InconsistentOodConstraintEvaluations),
+        verifier.verify_calldata(&tampered)
+    );
+
+    // any modification of the proof is detected
+    for index in [0, 1, 5, calldata.proof.len() / 2, calldata.proof.len() - 1] {
+        let mut tampered = calldata.clone();
+        tampered.proof[index][31] ^= 1;
+        assert!(verifier.verify_calldata(&tampered).is_err());
+    }
+    let mut tampered = calldata.clone();
+    tampered.proof.push([0; 32]);
+    assert!(matches!(
+        verifier.verify_calldata(&tampered),
+        Err(VerifierError::ProofDeserializationError(_))
+    ));
+
+    // calldata is ABI-encoded as arguments of verify(uint256[],uint256[])
+    let bytes = calldata.to_abi_bytes();
+    assert_eq!(4 + 32 * (4 + calldata.public_inputs.len() + calldata.proof.len()), bytes.len());
+    assert_eq!(&bytes[36..68], &[&[0; 31][..], &[128]].concat()[..]);
+
+    let source = verifier.generate_contract("Fib2Verifier");
+    assert!(source.contains("contract Fib2Verifier {"));
+    assert!(source
+        .contains("function verify(uint256[] calldata publicInputs, uint256[] calldata proof)"));
+    assert!(source.contains("s.publicInputs[0]"));
+
+    // verifiers are generated for proofs with normalized queries as well
+    let options = build_options().with_normalized_queries();
+    let prover = FibProver::new(options.clone());
+    let trace = build_trace(64);
+    let verifier = SolidityVerifier::new::<FibAir>(trace.get_info(), result, options).unwrap();
+    let normalized = verifier.encode_proof(prover.prove(trace).unwrap(), &result).unwrap();
+    assert_eq!(Ok(()), verifier.verify_calldata(&normalized));
+    assert!(verifier.generate_contract("FibVerifier").contains("NORMALIZED_QUERIES = true"));
+
+    // verifiers cannot be generated for proofs folded according to a schedule
+    let options = build_options().with_fri_folding_schedule(&[8]);
+    let trace_info = build_trace(64).get_info();
+    let verifier = SolidityVerifier::new::<FibAir>(trace_info, result, options);
+    assert_eq!(Err(CodegenError::UnsupportedFoldingSchedule), verifier.map(|_| ()));
+
+    // verifiers cannot be generated for proofs with per-layer proof-of-work
+    let options = build_options().with_fri_layer_grinding_factor(4);
+    let trace_info = build_trace(64).get_info();
+    let verifier = SolidityVerifier::new::<FibAir>(trace_info, result, options);
+    assert_eq!(Err(CodegenError::UnsupportedFriLayerGrinding), verifier.map(|_| ()));
+
+    // verifiers cannot be generated for proofs with deduplicated FRI queries
+    let options = build_options().with_fri_query_deduplication();
+    let trace_info = build_trace(64).get_info();
+    let verifier = SolidityVerifier::new::<FibAir>(trace_info, result, options);
+    assert_eq!(Err(CodegenError::UnsupportedFriQueryDeduplication), verifier.map(|_| ()));
+
+    // verifiers cannot be generated for proofs which use STIR instead of FRI
+    let options = build_options().with_low_degree_test(LowDegreeTest::Stir);
+    let trace_info = build_trace(64).get_info();
+    let verifier = SolidityVerifier::new::<FibAir>(trace_info, result, options);
+    assert_eq!(Err(CodegenError::UnsupportedLowDegreeTest), verifier.map(|_| ()));
+
+    // verifiers cannot be generated for proofs with padded commitment leaves
+    let options = build_options().with_leaf_encoding(LeafEncoding::PaddedBe);
+    let trace_info = build_trace(64).get_info();
+    let verifier = SolidityVerifier::new::<FibAir>(trace_info, result, options);
+    assert_eq!(Err(CodegenError::UnsupportedLeafEncoding), verifier.map(|_| ()));
+}
+
+#[test]
+fn periodic_columns() {
+    let prover = PeriodicProver::new(build_options());
+    let trace = build_periodic_trace(32);
+    let result = trace.get(0, trace.length() - 1);
+    let verifier =
+        SolidityVerifier::new::<PeriodicAir>(trace.get_info(), result, build_options()).unwrap();
+
+    // periodic columns are evaluated by the verifier
+    let proof = prover.prove(trace).unwrap();
+    let calldata = verifier.encode_proof(proof, &result).unwrap();
+    assert_eq!(Ok(()), verifier.verify_calldata(&calldata));
+
+    let source = verifier.generate_contract("PeriodicVerifier");
+    assert!(source.contains("function _evaluatePeriodic(uint256 z)"));
+    assert!(source.contains("result[0] = value;"));
+}
+
+// FIBONACCI AIR AND PROVER
+// ================================================================================================
+
+fn build_trace(length: usize) -> TraceTable<BaseElement> {
+    let mut trace = TraceTable::new(2, length);
+    trace.fill(
+        |state| {
+            state[0] = BaseElement::ONE;
+            state[1] = BaseElement::ONE;
+        },
+        |_, state| {
+            state[0] += state[1];
+            state[1] += state[0];
+        },
+    );
+    trace
+}
+
+fn build_options() -> ProofOptions {
+    ProofOptions::new(28, 8, 0, FieldExtension::None, 4, 7)
+}
+
+pub struct FibAir {
+    context: AirContext<BaseElement>,
+    result: BaseElement,
+}
+
+impl Air for FibAir {
+    type BaseField = BaseElement;
+    type PublicInputs = BaseElement;
+
+    fn new(trace_info: TraceInfo, result: BaseElement, options: ProofOptions) -> Self {
+        let degrees = vec![TransitionConstraintDegree::new(1), TransitionConstraintDegree::new(1)];
+        FibAir {
+            context: AirContext::new(trace_info, degrees, 3, options),
+            result,
+        }
+    }
+
+    fn context(&self) -> &AirContext<Self::BaseField> {
+        &self.context
+    }
+
+    fn evaluate_transition<E: FieldElement + From<Self::BaseField>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        _periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        let current = frame.current();
+        let next = frame.next();
+        result[0] = next[0] - (current[0] + current[1]);
+        result[1] = next[1] - (current[1] + next[0]);
+    }
+
+    fn get_assertions(&self) -> Vec<Assertion<Self::BaseField>> {
+        let last_step = self.trace_length() - 1;
+        vec![
+            Assertion::single(0, 0, BaseElement::ONE),
+            Assertion::single(1, 0, BaseElement::ONE),
+            Assertion::single(1, last_step, self.result),
+        ]
+    }
+}
+
+struct FibProver {
+    options: ProofOptions,
+}
+
+impl FibProver {
+    fn new(options: ProofOptions) -> Self {
+        Self { options }
+    }
+}
+
+impl Prover for FibProver {
+    type BaseField = BaseElement;
+    type Air = FibAir;
+    type Trace = TraceTable<BaseElement>;
+    type HashFn = Keccak;
+    type RandomCoin = DefaultRandomCoin<Keccak>;
+    type TraceLde<E: FieldElement<BaseField = Self::BaseField>> = DefaultTraceLde<E, Self::HashFn>;
+    type ConstraintEvaluator<'a, E: FieldElement<BaseField = Self::BaseField>> =
+        DefaultConstraintEvaluator<'a, Self::Air, E>;
+
+    fn get_pub_inputs(&self, trace: &Self::Trace) -> BaseElement {
+        trace.get(1, trace.length() - 1)
+    }
+
+    fn options(&self) -> &ProofOptions {
+        &self.options
+    }
+
+    fn new_trace_lde<E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        trace_info: &TraceInfo,
+        main_trace: &ColMatrix<Self::BaseField>,
+        domain: &StarkDomain<Self::BaseField>,
+    ) -> (Self::TraceLde<E>, TracePolyTable<E>) {
+        DefaultTraceLde::new(trace_info, main_trace, domain)
+    }
+
+    fn new_evaluator<'a, E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        air: &'a FibAir,
+        aux_rand_elements: AuxTraceRandElements<E>,
+        composition_coefficients: ConstraintCompositionCoefficients<E>,
+    ) -> Self::ConstraintEvaluator<'a, E> {
+        DefaultConstraintEvaluator::new(air, aux_rand_elements, composition_coefficients)
+    }
+}
+
+// PERIODIC AIR AND PROVER
+// ================================================================================================
+
+const FACTORS: [u128; 4] = [2, 3, 5, 7];
+
+fn build_periodic_trace(length: usize) -> TraceTable<BaseElement> {
+    let mut trace = TraceTable::new(1, length);
+    trace.fill(
+        |state| state[0] = BaseElement::new(3),
+        |step, state| {
+            state[0] = state[0].square() * BaseElement::new(FACTORS[step % FACTORS.len()])
+        },
+    );
+    trace
+}
+
+/// Squares the value in the only column and multiplies it by a periodic constant at every step.
+pub struct PeriodicAir {
+    context: AirContext<BaseElement>,
+    result: BaseElement,
+}
+
+impl Air for PeriodicAir {
+    type BaseField = BaseElement;
+    type PublicInputs = BaseElement;
+
+    fn new(trace_info: TraceInfo, result: BaseElement, options: ProofOptions) -> Self {
+        let degrees = vec![TransitionConstraintDegree::with_cycles(2, vec![FACTORS.len()])];
+        PeriodicAir {
+            context: AirContext::new(trace_info, degrees, 2, options),
+            result,
+        }
+    }
+
+    fn context(&self) -> &AirContext<Self::BaseField> {
+        &self.context
+    }
+
+    fn evaluate_transition<E: FieldElement + From<Self::BaseField>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        let current = frame.current()[0];
+        result[0] = frame.next()[0] - current.square() * periodic_values[0];
+    }
+
+    fn get_periodic_column_values(&self) -> Vec<Vec<Self::BaseField>> {
+        vec![FACTORS.iter().map(|&v| BaseElement::new(v)).collect()]
+    }
+
+    fn get_assertions(&self) -> Vec<Assertion<Self::BaseField>> {
+        let last_step = self.trace_length() - 1;
+        vec![
+            Assertion::single(0, 0, BaseElement::new(3)),
+            Assertion::single(0, last_step, self.result),
+        ]
+    }
+}
+
+struct PeriodicProver {
+    options: ProofOptions,
+}
+
+impl PeriodicProver {
+    fn new(options: ProofOptions) -> Self {
+        Self { options }
+    }
+}
+
+impl Prover for PeriodicProver {
+    type BaseField = BaseElement;
+    type Air = PeriodicAir;
+    type Trace = TraceTable<BaseElement>;
+    type HashFn = Keccak;
+    type RandomCoin = DefaultRandomCoin<Keccak>;
+    type TraceLde<E: FieldElement<BaseField = Self::BaseField>> = DefaultTraceLde<E, Self::HashFn>;
+    type ConstraintEvaluator<'a, E: FieldElement<BaseField = Self::BaseField>> =
+        DefaultConstraintEvaluator<'a, Self::Air, E>;
+
+    fn get_pub_inputs(&self, trace: &Self::Trace) -> BaseElement {
+        trace.get(0, trace.length() - 1)
+    }
+
+    fn options(&self) -> &ProofOptions {
+        &self.options
+    }
+
+    fn new_trace_lde<E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        trace_info: &TraceInfo,
+        main_trace: &ColMatrix<Self::BaseField>,
+        domain: &StarkDomain<Self::BaseField>,
+    ) -> (Self::TraceLde<E>, TracePolyTable<E>) {
+        DefaultTraceLde::new(trace_info, main_trace, domain)
+    }
+
+    fn new_evaluator<'a, E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        air: &'a PeriodicAir,
+        aux_rand_elements: AuxTraceRandElements<E>,
+        composition_coefficients: ConstraintCompositionCoefficients<E>,
+    ) -> Self::ConstraintEvaluator<'a, E> {
+        DefaultConstraintEvaluator::new(air, aux_rand_elements, composition_coefficients)
+    }
+}
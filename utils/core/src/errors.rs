@@ -32,3 +32,22 @@ impl fmt::Display for DeserializationError {
         }
     }
 }
+
+impl DeserializationError {
+    /// Returns this error annotated with the offset (in bytes from the start of the input) at
+    /// which it occurred.
+    ///
+    /// The offset is appended to the message of [InvalidValue](Self::InvalidValue) and
+    /// [UnknownError](Self::UnknownError) errors; other errors are returned unchanged.
+    pub fn at_offset(self, offset: usize) -> Self {
+        match self {
+            Self::InvalidValue(err_msg) => {
+                Self::InvalidValue(format!("{err_msg} (at byte offset {offset})"))
+            }
+            Self::UnknownError(err_msg) => {
+                Self::UnknownError(format!("{err_msg} (at byte offset {offset})"))
+            }
+            err => err,
+        }
+    }
+}
@@ -22,9 +22,18 @@ use core::{convert::TryInto, mem, slice};
 mod serde;
 pub use serde::{ByteReader, ByteWriter, Deserializable, Serializable, SliceReader};
 
+#[cfg(feature = "std")]
+pub use serde::{ReadAdapter, WriteAdapter};
+
+#[cfg(feature = "derive")]
+pub use winter_utils_derive::{Deserializable, Serializable};
+
 mod errors;
 pub use errors::DeserializationError;
 
+mod uninit;
+pub use uninit::UninitBuffer;
+
 #[cfg(test)]
 mod tests;
 
@@ -96,7 +105,10 @@ pub unsafe fn uninit_vector<T>(length: usize) -> Vec<T> {
 /// Transmutes a vector of `n` elements into a vector of `n` / `N` elements, each of which is
 /// an array of `N` elements.
 ///
-/// This function just re-interprets the underlying memory and is thus zero-copy.
+/// This function just re-interprets the underlying memory and is thus zero-copy, unless the
+/// capacity of the vector is not divisible by `N`, in which case the vector is first re-allocated
+/// to fit its length exactly.
+///
 /// # Panics
 /// Panics if `n` is not divisible by `N`.
 ///
@@ -116,6 +128,16 @@ pub fn group_vector_elements<T, const N: usize>(source: Vec<T>) -> Vec<[T; N]> {
         N,
         source.len()
     );
+
+    // the capacity of the vector must also be divisible by N, otherwise the memory would be
+    // de-allocated with a layout different from the one it was allocated with; converting the
+    // vector into a boxed slice re-allocates it with capacity equal to its length
+    let source = if source.capacity() % N != 0 {
+        source.into_boxed_slice().into_vec()
+    } else {
+        source
+    };
+
     let mut v = mem::ManuallyDrop::new(source);
     let p = v.as_mut_ptr();
     let len = v.len() / N;
@@ -181,6 +203,82 @@ pub fn flatten_vector_elements<T, const N: usize>(source: Vec<[T; N]>) -> Vec<T>
     unsafe { Vec::from_raw_parts(p as *mut T, len, cap) }
 }
 
+/// Copies elements of the specified vectors into a single vector, placing elements of every
+/// vector right after the elements of the vector which precedes it.
+///
+/// This is useful, for example, for flattening a list of row vectors into a single vector in
+/// row-major order. When `concurrent` feature is enabled, the vectors are copied using multiple
+/// threads.
+///
+/// # Example
+/// ```
+/// # use winter_utils::flatten_vectors;
+/// let a = vec![vec![1, 2, 3], vec![4], vec![], vec![5, 6]];
+///
+/// let b = flatten_vectors(&a);
+/// assert_eq!(vec![1, 2, 3, 4, 5, 6], b);
+/// ```
+pub fn flatten_vectors<T: Copy + Send + Sync>(vectors: &[Vec<T>]) -> Vec<T> {
+    let result_len = vectors.iter().map(|vector| vector.len()).sum();
+    let mut result = UninitBuffer::new(result_len);
+
+    // split the result into regions corresponding to each of the source vectors
+    let mut regions = Vec::with_capacity(vectors.len());
+    let mut remainder = result.as_uninit_slice_mut();
+    for vector in vectors.iter() {
+        let (region, rest) = remainder.split_at_mut(vector.len());
+        regions.push(region);
+        remainder = rest;
+    }
+
+    iter_mut!(regions).zip(iter!(vectors)).for_each(|(region, vector)| {
+        for (target, &value) in region.iter_mut().zip(vector.iter()) {
+            target.write(value);
+        }
+    });
+
+    // SAFETY: regions cover the entire result, and every region has been fully initialized with
+    // the values of the source vector of the same length
+    unsafe { result.assume_init() }
+}
+
+/// Copies elements of the specified slice into a list of vectors of `vector_len` elements each,
+/// such that the first vector contains the first `vector_len` elements of the slice, the second
+/// vector contains the next `vector_len` elements etc.
+///
+/// This is the inverse of [flatten_vectors()] for vectors of the same length. When `concurrent`
+/// feature is enabled, the vectors are built using multiple threads.
+///
+/// # Panics
+/// Panics if `vector_len` is zero or if the length of the slice is not divisible by `vector_len`.
+///
+/// # Example
+/// ```
+/// # use winter_utils::split_into_vectors;
+/// let a = [1, 2, 3, 4, 5, 6];
+///
+/// let b = split_into_vectors(&a, 2);
+/// assert_eq!(vec![vec![1, 2], vec![3, 4], vec![5, 6]], b);
+/// ```
+pub fn split_into_vectors<T: Copy + Send + Sync>(source: &[T], vector_len: usize) -> Vec<Vec<T>> {
+    assert!(vector_len > 0, "vector length must be greater than zero");
+    assert_eq!(
+        source.len() % vector_len,
+        0,
+        "source length must be divisible by {}, but was {}",
+        vector_len,
+        source.len()
+    );
+
+    #[cfg(not(feature = "concurrent"))]
+    let chunks = source.chunks(vector_len);
+
+    #[cfg(feature = "concurrent")]
+    let chunks = source.par_chunks(vector_len);
+
+    chunks.map(|chunk| chunk.to_vec()).collect()
+}
+
 // TRANSPOSING
 // ================================================================================================
 
@@ -209,13 +307,7 @@ pub fn transpose_slice<T: Copy + Send + Sync, const N: usize>(source: &[T]) -> V
         source.len()
     );
 
-    let mut result = unsafe { group_vector_elements(uninit_vector(row_count * N)) };
-    iter_mut!(result, 1024).enumerate().for_each(|(i, element)| {
-        for j in 0..N {
-            element[j] = source[i + j * row_count]
-        }
-    });
-    result
+    UninitBuffer::new(row_count).init_with(|i| core::array::from_fn(|j| source[i + j * row_count]))
 }
 
 // RANDOMNESS
@@ -5,6 +5,16 @@
 
 use super::{Deserializable, DeserializationError, Vec};
 
+#[cfg(feature = "std")]
+use core::cell::RefCell;
+
+// CONSTANTS
+// ================================================================================================
+
+/// Maximum number of bytes requested from the source of a [ReadAdapter] in a single read.
+#[cfg(feature = "std")]
+const READ_CHUNK_SIZE: usize = 4096;
+
 // BYTE READER TRAIT
 // ================================================================================================
 
@@ -128,6 +138,11 @@ impl<'a> SliceReader<'a> {
     pub fn new(source: &'a [u8]) -> Self {
         SliceReader { source, pos: 0 }
     }
+
+    /// Returns the number of bytes consumed from the source slice so far.
+    pub fn position(&self) -> usize {
+        self.pos
+    }
 }
 
 impl<'a> ByteReader for SliceReader<'a> {
@@ -169,3 +184,149 @@ impl<'a> ByteReader for SliceReader<'a> {
         self.pos < self.source.len()
     }
 }
+
+// READ ADAPTER
+// ================================================================================================
+
+/// Implements [ByteReader] trait for a source of bytes implementing [std::io::Read].
+///
+/// Bytes are read from the source only when they are needed, and are buffered only until they are
+/// consumed. Thus, values can be deserialized from a source (e.g., a network stream) without first
+/// reading all bytes of the source into memory, and once a value is deserialized, the source is
+/// positioned right after the last byte of the value (apart from bytes looked at via
+/// [peek_u8()](ByteReader::peek_u8), [check_eor()](ByteReader::check_eor), or
+/// [has_more_bytes()](ByteReader::has_more_bytes), which are buffered by the adapter).
+///
+/// Since the adapter may issue many small reads, sources for which each read is expensive (e.g.,
+/// a socket) should be wrapped into a [BufReader](std::io::BufReader).
+///
+/// The adapter keeps track of the number of bytes consumed from the source (see
+/// [position()](ReadAdapter::position)). Errors returned when reading from the source fails
+/// include the offset at which the failure occurred, and errors returned by [Deserializable]
+/// implementations can be annotated with the offset via
+/// [DeserializationError::at_offset()].
+#[cfg(feature = "std")]
+pub struct ReadAdapter<R: std::io::Read> {
+    buffer: RefCell<ReadBuffer<R>>,
+}
+
+#[cfg(feature = "std")]
+impl<R: std::io::Read> ReadAdapter<R> {
+    /// Creates a new reader adapter for the specified source.
+    pub fn new(source: R) -> Self {
+        let buffer = ReadBuffer {
+            source,
+            bytes: Vec::new(),
+            pos: 0,
+            offset: 0,
+        };
+        ReadAdapter {
+            buffer: RefCell::new(buffer),
+        }
+    }
+
+    /// Returns the number of bytes consumed from the source so far.
+    ///
+    /// Bytes which were read from the source but were not consumed (e.g., bytes looked at via
+    /// [peek_u8()](ByteReader::peek_u8)) are not counted.
+    pub fn position(&self) -> usize {
+        let buffer = self.buffer.borrow();
+        buffer.offset + buffer.pos
+    }
+
+    /// Returns the underlying source; bytes which were read from the source but were not
+    /// consumed are discarded.
+    pub fn into_inner(self) -> R {
+        self.buffer.into_inner().source
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R: std::io::Read> ByteReader for ReadAdapter<R> {
+    fn read_u8(&mut self) -> Result<u8, DeserializationError> {
+        let buffer = self.buffer.get_mut();
+        buffer.fill(1)?;
+        let result = buffer.bytes[buffer.pos];
+        buffer.pos += 1;
+        Ok(result)
+    }
+
+    fn peek_u8(&self) -> Result<u8, DeserializationError> {
+        let mut buffer = self.buffer.borrow_mut();
+        buffer.fill(1)?;
+        Ok(buffer.bytes[buffer.pos])
+    }
+
+    fn read_vec(&mut self, len: usize) -> Result<Vec<u8>, DeserializationError> {
+        let buffer = self.buffer.get_mut();
+        buffer.fill(len)?;
+        let result = buffer.bytes[buffer.pos..buffer.pos + len].to_vec();
+        buffer.pos += len;
+        Ok(result)
+    }
+
+    fn read_array<const N: usize>(&mut self) -> Result<[u8; N], DeserializationError> {
+        let buffer = self.buffer.get_mut();
+        buffer.fill(N)?;
+        let mut result = [0_u8; N];
+        result.copy_from_slice(&buffer.bytes[buffer.pos..buffer.pos + N]);
+        buffer.pos += N;
+        Ok(result)
+    }
+
+    fn check_eor(&self, num_bytes: usize) -> Result<(), DeserializationError> {
+        self.buffer.borrow_mut().fill(num_bytes)
+    }
+
+    fn has_more_bytes(&self) -> bool {
+        self.buffer.borrow_mut().fill(1).is_ok()
+    }
+}
+
+/// Bytes read from a source of a [ReadAdapter] which have not been consumed yet.
+#[cfg(feature = "std")]
+struct ReadBuffer<R: std::io::Read> {
+    source: R,
+    bytes: Vec<u8>,
+    pos: usize,
+    /// Number of bytes consumed and discarded before the first byte of `bytes`.
+    offset: usize,
+}
+
+#[cfg(feature = "std")]
+impl<R: std::io::Read> ReadBuffer<R> {
+    /// Makes sure at least `num_bytes` unconsumed bytes are in the buffer, reading only as many
+    /// bytes from the source as needed.
+    ///
+    /// # Errors
+    /// Returns [DeserializationError::UnexpectedEOF] if the source ends before enough bytes could
+    /// be read, and [DeserializationError::UnknownError] if reading from the source fails; in the
+    /// latter case, the error message contains the offset at which reading failed.
+    fn fill(&mut self, num_bytes: usize) -> Result<(), DeserializationError> {
+        if self.bytes.len() - self.pos >= num_bytes {
+            return Ok(());
+        }
+
+        // discard consumed bytes before reading more bytes from the source
+        self.bytes.drain(..self.pos);
+        self.offset += self.pos;
+        self.pos = 0;
+
+        let mut chunk = [0_u8; READ_CHUNK_SIZE];
+        while self.bytes.len() < num_bytes {
+            let chunk_size = (num_bytes - self.bytes.len()).min(READ_CHUNK_SIZE);
+            match self.source.read(&mut chunk[..chunk_size]) {
+                Ok(0) => return Err(DeserializationError::UnexpectedEOF),
+                Ok(n) => self.bytes.extend_from_slice(&chunk[..n]),
+                Err(err) if err.kind() == std::io::ErrorKind::Interrupted => continue,
+                Err(err) => {
+                    let offset = self.offset + self.bytes.len();
+                    return Err(DeserializationError::UnknownError(format!(
+                        "failed to read from source at byte offset {offset}: {err}"
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+}
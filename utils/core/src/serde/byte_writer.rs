@@ -83,3 +83,70 @@ impl ByteWriter for Vec<u8> {
         self.extend_from_slice(values);
     }
 }
+
+// WRITE ADAPTER
+// ================================================================================================
+
+/// Implements [ByteWriter] trait for a target implementing [std::io::Write].
+///
+/// Bytes are written to the target as soon as they are written into the adapter, and thus, values
+/// can be serialized directly into a file or a network stream without first serializing them into
+/// a vector of bytes. Since the adapter may issue many small writes, targets for which each write
+/// is expensive should be wrapped into a [BufWriter](std::io::BufWriter).
+///
+/// Unlike other [ByteWriter] implementations, the adapter does not panic if writing fails. Instead,
+/// the first error returned by the target is recorded, all subsequent writes are ignored, and the
+/// error is returned from [finish()](WriteAdapter::finish).
+#[cfg(feature = "std")]
+pub struct WriteAdapter<W: std::io::Write> {
+    target: W,
+    num_bytes: usize,
+    error: Option<std::io::Error>,
+}
+
+#[cfg(feature = "std")]
+impl<W: std::io::Write> WriteAdapter<W> {
+    /// Creates a new writer adapter for the specified target.
+    pub fn new(target: W) -> Self {
+        WriteAdapter {
+            target,
+            num_bytes: 0,
+            error: None,
+        }
+    }
+
+    /// Returns the number of bytes successfully written to the target so far.
+    pub fn position(&self) -> usize {
+        self.num_bytes
+    }
+
+    /// Flushes the target and returns it.
+    ///
+    /// # Errors
+    /// Returns the first error which occurred while writing to the target, or an error if the
+    /// target could not be flushed.
+    pub fn finish(mut self) -> std::io::Result<W> {
+        if let Some(err) = self.error {
+            return Err(err);
+        }
+        self.target.flush()?;
+        Ok(self.target)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<W: std::io::Write> ByteWriter for WriteAdapter<W> {
+    fn write_u8(&mut self, value: u8) {
+        self.write_bytes(&[value]);
+    }
+
+    fn write_bytes(&mut self, values: &[u8]) {
+        if self.error.is_some() {
+            return;
+        }
+        match self.target.write_all(values) {
+            Ok(()) => self.num_bytes += values.len(),
+            Err(err) => self.error = Some(err),
+        }
+    }
+}
@@ -8,9 +8,15 @@ use super::{flatten_slice_elements, DeserializationError, Vec};
 mod byte_reader;
 pub use byte_reader::{ByteReader, SliceReader};
 
+#[cfg(feature = "std")]
+pub use byte_reader::ReadAdapter;
+
 mod byte_writer;
 pub use byte_writer::ByteWriter;
 
+#[cfg(feature = "std")]
+pub use byte_writer::WriteAdapter;
+
 // SERIALIZABLE TRAIT
 // ================================================================================================
 
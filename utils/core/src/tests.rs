@@ -3,7 +3,10 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use super::{collections::Vec, ByteReader, ByteWriter, Serializable, SliceReader};
+use super::{
+    collections::Vec, ByteReader, ByteWriter, DeserializationError, Serializable, SliceReader,
+    UninitBuffer,
+};
 
 // VECTOR UTILS TESTS
 // ================================================================================================
@@ -28,6 +31,62 @@ fn group_vector_elements() {
     }
 }
 
+#[test]
+fn group_vector_elements_with_odd_capacity() {
+    let mut a = Vec::with_capacity(11);
+    a.extend((0..8).map(|v| v as u64));
+
+    let b = super::group_vector_elements::<u64, 4>(a);
+    assert_eq!(vec![[0, 1, 2, 3], [4, 5, 6, 7]], b);
+    assert_eq!((0..8).collect::<Vec<u64>>(), super::flatten_vector_elements(b));
+}
+
+#[test]
+fn flatten_and_split_vectors() {
+    let rows = (0..64)
+        .map(|i| (0..8).map(|j| (i * 8 + j) as u64).collect())
+        .collect::<Vec<Vec<_>>>();
+
+    let flat = super::flatten_vectors(&rows);
+    assert_eq!((0..512).collect::<Vec<u64>>(), flat);
+    assert_eq!(rows, super::split_into_vectors(&flat, 8));
+
+    let empty: Vec<Vec<u64>> = Vec::new();
+    assert!(super::flatten_vectors(&empty).is_empty());
+    assert!(super::split_into_vectors::<u64>(&[], 8).is_empty());
+}
+
+#[test]
+fn uninit_buffer_init_with() {
+    let n = 4096;
+    let a = UninitBuffer::<u64>::new(n).init_with(|i| (i * 3) as u64);
+    assert_eq!((0..n).map(|i| (i * 3) as u64).collect::<Vec<_>>(), a);
+
+    let b = UninitBuffer::<u64>::new(0).init_with(|i| i as u64);
+    assert!(b.is_empty());
+}
+
+#[test]
+fn uninit_buffer_init_chunks() {
+    // the last chunk is shorter than the others; extra values returned by iterators are ignored
+    let a =
+        UninitBuffer::<u64>::new(10).init_chunks(4, |i| (0..).map(move |j| (i * 10 + j) as u64));
+    assert_eq!(vec![0, 1, 2, 3, 10, 11, 12, 13, 20, 21], a);
+
+    let mut buffer = UninitBuffer::<u64>::new(3);
+    assert_eq!(3, buffer.len());
+    buffer.as_uninit_slice_mut().iter_mut().for_each(|e| {
+        e.write(7);
+    });
+    assert_eq!(vec![7, 7, 7], unsafe { buffer.assume_init() });
+}
+
+#[test]
+#[should_panic(expected = "not enough values to initialize chunk 1")]
+fn uninit_buffer_init_chunks_too_few_values() {
+    UninitBuffer::<u64>::new(8).init_chunks(4, |i| 0..4 - i as u64);
+}
+
 // SLICE READER TESTS
 // ================================================================================================
 
@@ -88,6 +147,103 @@ fn read_u8_vec() {
     assert!(a.read_vec(2).is_err());
 }
 
+#[cfg(feature = "std")]
+#[test]
+fn read_adapter() {
+    let source = [1u8, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+    let mut a = crate::ReadAdapter::new(&source[..]);
+
+    assert_eq!(1, a.peek_u8().unwrap());
+    assert_eq!(1, a.read_u8().unwrap());
+    assert_eq!(vec![2, 3, 4], a.read_vec(3).unwrap());
+    assert_eq!(u32::from_le_bytes([5, 6, 7, 8]), a.read_u32().unwrap());
+    assert!(a.check_eor(2).is_ok());
+    assert!(a.check_eor(3).is_err());
+    assert_eq!(vec![9, 10], a.read_vec(2).unwrap());
+    assert!(!a.has_more_bytes());
+    assert!(a.read_u8().is_err());
+
+    // only the bytes which were consumed are read from the source
+    let mut a = crate::ReadAdapter::new(&source[..]);
+    assert_eq!(vec![1, 2, 3], a.read_vec(3).unwrap());
+    assert_eq!(&source[3..], a.into_inner());
+}
+
+#[test]
+fn reader_position() {
+    let source = [1u8, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+
+    let mut a = SliceReader::new(&source);
+    assert_eq!(0, a.position());
+    a.read_u32().unwrap();
+    assert_eq!(4, a.position());
+
+    // peeked bytes are not counted, and the position is preserved across reads from the source
+    let mut a = crate::ReadAdapter::new(&source[..]);
+    assert_eq!(1, a.peek_u8().unwrap());
+    assert_eq!(0, a.position());
+    a.read_vec(3).unwrap();
+    assert_eq!(3, a.position());
+    a.read_u32().unwrap();
+    assert_eq!(7, a.position());
+
+    // a failed read does not consume any bytes
+    assert!(a.read_u32().is_err());
+    assert_eq!(7, a.position());
+}
+
+#[test]
+fn read_adapter_error_offset() {
+    struct FailingSource(usize);
+
+    impl std::io::Read for FailingSource {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            if self.0 == 0 {
+                return Err(std::io::Error::new(std::io::ErrorKind::Other, "broken"));
+            }
+            let n = buf.len().min(self.0);
+            buf[..n].fill(0);
+            self.0 -= n;
+            Ok(n)
+        }
+    }
+
+    let mut a = crate::ReadAdapter::new(FailingSource(5));
+    a.read_u32().unwrap();
+    let err = a.read_u32().unwrap_err();
+    assert_eq!(
+        DeserializationError::UnknownError(
+            "failed to read from source at byte offset 5: broken".into()
+        ),
+        err
+    );
+
+    let err = DeserializationError::InvalidValue("bad value".into()).at_offset(12);
+    assert_eq!(DeserializationError::InvalidValue("bad value (at byte offset 12)".into()), err);
+    assert_eq!(
+        DeserializationError::UnexpectedEOF,
+        DeserializationError::UnexpectedEOF.at_offset(3)
+    );
+}
+
+#[test]
+fn write_adapter() {
+    let mut a = crate::WriteAdapter::new(Vec::new());
+    a.write_u8(1);
+    a.write_u32(u32::from_le_bytes([2, 3, 4, 5]));
+    assert_eq!(5, a.position());
+    assert_eq!(vec![1, 2, 3, 4, 5], a.finish().unwrap());
+
+    // writes after a failed write are ignored, and the error is returned from finish()
+    let mut target = [0u8; 3];
+    let mut a = crate::WriteAdapter::new(&mut target[..]);
+    a.write_u8(1);
+    a.write_u32(2);
+    a.write_u8(3);
+    assert_eq!(1, a.position());
+    assert_eq!(std::io::ErrorKind::WriteZero, a.finish().unwrap_err().kind());
+}
+
 // SERIALIZATION TESTS
 // ================================================================================================
 
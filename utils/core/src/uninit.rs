@@ -0,0 +1,159 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use crate::collections::Vec;
+use core::mem::{ManuallyDrop, MaybeUninit};
+
+#[cfg(feature = "concurrent")]
+use crate::iterators::*;
+
+// CONSTANTS
+// ================================================================================================
+
+/// Minimum number of elements initialized by a single thread in [UninitBuffer::init_with()].
+#[cfg(feature = "concurrent")]
+const MIN_INIT_BATCH_SIZE: usize = 1024;
+
+// UNINITIALIZED BUFFER
+// ================================================================================================
+
+/// A buffer of the specified length backed by un-initialized memory.
+///
+/// The buffer is a safe alternative to [uninit_vector()](crate::uninit_vector): memory is
+/// allocated without being initialized, but the only way to convert the buffer into a `Vec<T>`
+/// without `unsafe` code is to initialize it via [init_with()](UninitBuffer::init_with) or
+/// [init_chunks()](UninitBuffer::init_chunks), both of which guarantee that every element of the
+/// buffer is written exactly once. When `concurrent` feature is enabled, both methods initialize
+/// the buffer in multiple threads.
+///
+/// For cases in which a buffer is filled in some other way, the buffer can be accessed as a slice
+/// of [MaybeUninit] values and converted into a vector via the unsafe
+/// [assume_init()](UninitBuffer::assume_init) method.
+///
+/// # Examples
+/// ```
+/// # use winter_utils::UninitBuffer;
+/// let squares = UninitBuffer::<u64>::new(4).init_with(|i| (i * i) as u64);
+/// assert_eq!(vec![0, 1, 4, 9], squares);
+///
+/// let rows = UninitBuffer::<u64>::new(6).init_chunks(3, |i| (0..3).map(move |j| (i * 10 + j) as u64));
+/// assert_eq!(vec![0, 1, 2, 10, 11, 12], rows);
+/// ```
+pub struct UninitBuffer<T> {
+    data: Vec<MaybeUninit<T>>,
+}
+
+impl<T> UninitBuffer<T> {
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a new buffer of the specified length with un-initialized memory.
+    pub fn new(length: usize) -> Self {
+        let mut data = Vec::with_capacity(length);
+        // SAFETY: MaybeUninit<T> does not require initialization, and the capacity of the vector
+        // is at least `length`.
+        unsafe { data.set_len(length) };
+        Self { data }
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the number of elements in this buffer.
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns true if this buffer has no elements.
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Returns the contents of this buffer as a mutable slice of possibly un-initialized values.
+    pub fn as_uninit_slice_mut(&mut self) -> &mut [MaybeUninit<T>] {
+        &mut self.data
+    }
+
+    // INITIALIZATION
+    // --------------------------------------------------------------------------------------------
+
+    /// Converts this buffer into a vector without checking whether its elements have been
+    /// initialized.
+    ///
+    /// # Safety
+    /// All elements of the buffer must have been initialized (e.g., via
+    /// [as_uninit_slice_mut()](UninitBuffer::as_uninit_slice_mut)); otherwise, using the returned
+    /// vector will lead to undefined behavior.
+    pub unsafe fn assume_init(self) -> Vec<T> {
+        let mut data = ManuallyDrop::new(self.data);
+        let (ptr, length, capacity) = (data.as_mut_ptr(), data.len(), data.capacity());
+        // MaybeUninit<T> is guaranteed to have the same size, alignment, and ABI as T
+        Vec::from_raw_parts(ptr as *mut T, length, capacity)
+    }
+
+    /// Initializes every element of this buffer with the value returned by `init` for the
+    /// element's index, and returns the buffer as a vector.
+    ///
+    /// When `concurrent` feature is enabled, elements are initialized in multiple threads.
+    pub fn init_with<F>(mut self, init: F) -> Vec<T>
+    where
+        T: Send,
+        F: Fn(usize) -> T + Send + Sync,
+    {
+        #[cfg(not(feature = "concurrent"))]
+        let iter = self.data.iter_mut();
+
+        #[cfg(feature = "concurrent")]
+        let iter = self.data.par_iter_mut().with_min_len(MIN_INIT_BATCH_SIZE);
+
+        iter.enumerate().for_each(|(i, element)| {
+            element.write(init(i));
+        });
+
+        // SAFETY: every element of the buffer has been written above
+        unsafe { self.assume_init() }
+    }
+
+    /// Initializes this buffer in chunks of `chunk_size` elements, and returns the buffer as a
+    /// vector.
+    ///
+    /// The values of each chunk are taken from the iterator returned by `init` for the chunk's
+    /// index; the last chunk may be shorter than `chunk_size` if the length of the buffer is not
+    /// a multiple of `chunk_size`. When `concurrent` feature is enabled, chunks are initialized in
+    /// multiple threads.
+    ///
+    /// # Panics
+    /// Panics if:
+    /// - `chunk_size` is zero.
+    /// - An iterator returned by `init` yields fewer values than the length of its chunk.
+    pub fn init_chunks<F, I>(mut self, chunk_size: usize, init: F) -> Vec<T>
+    where
+        T: Send,
+        F: Fn(usize) -> I + Send + Sync,
+        I: IntoIterator<Item = T>,
+    {
+        assert!(chunk_size > 0, "chunk size must be greater than zero");
+
+        let init_chunk = |(chunk_idx, chunk): (usize, &mut [MaybeUninit<T>])| {
+            let mut values = init(chunk_idx).into_iter();
+            for element in chunk.iter_mut() {
+                let value = values
+                    .next()
+                    .unwrap_or_else(|| panic!("not enough values to initialize chunk {chunk_idx}"));
+                element.write(value);
+            }
+        };
+
+        #[cfg(not(feature = "concurrent"))]
+        self.data.chunks_mut(chunk_size).enumerate().for_each(init_chunk);
+
+        #[cfg(feature = "concurrent")]
+        self.data.par_chunks_mut(chunk_size).enumerate().for_each(init_chunk);
+
+        // SAFETY: every element of every chunk has been written above; if any iterator runs out
+        // of values, we panic before getting here
+        unsafe { self.assume_init() }
+    }
+}
@@ -0,0 +1,109 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use syn::{parse_quote, Attribute, Error, LitInt, LitStr, Path};
+
+/// Name of the helper attribute recognized by the derive macros.
+const ATTRIBUTE_NAME: &str = "winter";
+
+// CONTAINER ATTRIBUTES
+// ================================================================================================
+
+/// Attributes specified on the struct or enum for which the traits are derived.
+pub struct ContainerAttributes {
+    /// Path to the `winter-utils` crate.
+    pub crate_path: Path,
+    /// Current serialization format version; `None` if the serialized form is not versioned.
+    pub version: Option<u8>,
+}
+
+impl ContainerAttributes {
+    /// Parses container attributes from the specified list of attributes.
+    ///
+    /// Supported attributes are `#[winter(crate = "path")]` and `#[winter(version = N)]`, where
+    /// `N` must be between 1 and 255.
+    pub fn parse(attrs: &[Attribute]) -> Result<Self, Error> {
+        let mut crate_path = parse_quote!(::winter_utils);
+        let mut version = None;
+
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident(ATTRIBUTE_NAME)) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("crate") {
+                    let path: LitStr = meta.value()?.parse()?;
+                    crate_path = path.parse()?;
+                    Ok(())
+                } else if meta.path.is_ident("version") {
+                    let value: LitInt = meta.value()?.parse()?;
+                    let value = value.base10_parse::<u8>()?;
+                    if value == 0 {
+                        return Err(meta.error("version must be greater than 0"));
+                    }
+                    version = Some(value);
+                    Ok(())
+                } else {
+                    Err(meta.error("unsupported container attribute"))
+                }
+            })?;
+        }
+
+        Ok(ContainerAttributes {
+            crate_path,
+            version,
+        })
+    }
+}
+
+// FIELD ATTRIBUTES
+// ================================================================================================
+
+/// Attributes specified on a single field of a struct or an enum variant.
+#[derive(Default)]
+pub struct FieldAttributes {
+    /// The field is not serialized, and is set to its default value on deserialization.
+    pub skip: bool,
+    /// The first serialization format version in which the field is present.
+    pub since: Option<u8>,
+}
+
+impl FieldAttributes {
+    /// Parses field attributes from the specified list of attributes.
+    ///
+    /// Supported attributes are `#[winter(skip)]` and `#[winter(since = N)]`; `since` is allowed
+    /// only if the container is versioned, and `N` may not be greater than the version of the
+    /// container.
+    pub fn parse(attrs: &[Attribute], container: &ContainerAttributes) -> Result<Self, Error> {
+        let mut result = FieldAttributes::default();
+
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident(ATTRIBUTE_NAME)) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("skip") {
+                    result.skip = true;
+                    Ok(())
+                } else if meta.path.is_ident("since") {
+                    let value: LitInt = meta.value()?.parse()?;
+                    let value = value.base10_parse::<u8>()?;
+                    match container.version {
+                        None => Err(meta.error("`since` requires a versioned container")),
+                        Some(version) if value == 0 || value > version => Err(meta.error(format!(
+                            "`since` must be between 1 and the container version {version}"
+                        ))),
+                        Some(_) => {
+                            result.since = Some(value);
+                            Ok(())
+                        }
+                    }
+                } else {
+                    Err(meta.error("unsupported field attribute"))
+                }
+            })?;
+
+            if result.skip && result.since.is_some() {
+                return Err(Error::new_spanned(attr, "`skip` cannot be combined with `since`"));
+            }
+        }
+
+        Ok(result)
+    }
+}
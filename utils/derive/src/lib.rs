@@ -0,0 +1,317 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+//! This crate contains derive macros for `Serializable` and `Deserializable` traits defined in
+//! the `winter-utils` crate.
+//!
+//! The traits can be derived for structs (with named fields, unnamed fields, or without fields)
+//! and enums. The serialized form of a value is defined as follows:
+//!
+//! * Fields are written in the order in which they are declared; reordering fields changes the
+//!   serialized form.
+//! * `bool`, `u8`, `u16`, `u32`, `u64`, and `u128` fields are written in little-endian byte order
+//!   via the corresponding `ByteWriter` methods; `usize` fields are written as `u64` values.
+//! * `Vec<T>` fields are written as the number of elements (as a `u32` value) followed by the
+//!   elements; `Option<T>` fields are written as a `bool` flag followed by the value if it is
+//!   present; `[T; N]` fields are written as `N` elements. These rules are applied recursively to
+//!   the element types.
+//! * Fields of all other types are written via their `Serializable` implementations, and are
+//!   read via their `Deserializable` implementations.
+//! * For enums, the index of the variant (as a `u8` value) is written before the fields of the
+//!   variant; enums with more than 256 variants are not supported.
+//!
+//! The serialized form can be customized via the `#[winter(...)]` attribute:
+//!
+//! * `#[winter(crate = "path")]` on the container specifies the path to the `winter-utils` crate;
+//!   by default, the crate is expected to be available as `::winter_utils`.
+//! * `#[winter(version = N)]` on the container prefixes the serialized form with the format
+//!   version `N` (as a `u8` value between 1 and 255). Values written by all versions from 1 to
+//!   `N` can be read, and values with any other version are rejected.
+//! * `#[winter(since = M)]` on a field of a versioned container specifies that the field was added
+//!   in version `M`. When a value written by an earlier version is read, the field is set to its
+//!   default value.
+//! * `#[winter(skip)]` on a field excludes the field from the serialized form; the field is set to
+//!   its default value on deserialization.
+//!
+//! Generic type parameters of the container are required to implement the derived trait.
+//!
+//! # Example
+//! ```ignore
+//! use winter_utils::{Deserializable, Serializable};
+//!
+//! #[derive(Serializable, Deserializable)]
+//! #[winter(version = 2)]
+//! struct PublicInputs {
+//!     start: [u64; 2],
+//!     result: u64,
+//!     #[winter(since = 2)]
+//!     num_steps: Option<u32>,
+//! }
+//! ```
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote};
+use syn::{
+    parse_macro_input, parse_quote, Data, DeriveInput, Error, Fields, Generics, Ident, Index, Path,
+};
+
+mod attributes;
+use attributes::{ContainerAttributes, FieldAttributes};
+
+mod types;
+use types::{read_value, write_value};
+
+// DERIVE MACROS
+// ================================================================================================
+
+/// Derives `Serializable` trait for a struct or an enum.
+///
+/// See the crate-level documentation for the description of the serialized form.
+#[proc_macro_derive(Serializable, attributes(winter))]
+pub fn derive_serializable(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_serializable(input).unwrap_or_else(Error::into_compile_error).into()
+}
+
+/// Derives `Deserializable` trait for a struct or an enum.
+///
+/// See the crate-level documentation for the description of the serialized form.
+#[proc_macro_derive(Deserializable, attributes(winter))]
+pub fn derive_deserializable(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_deserializable(input).unwrap_or_else(Error::into_compile_error).into()
+}
+
+// SERIALIZABLE
+// ================================================================================================
+
+fn expand_serializable(input: DeriveInput) -> Result<TokenStream2, Error> {
+    let container = ContainerAttributes::parse(&input.attrs)?;
+    let krate = &container.crate_path;
+    let name = &input.ident;
+
+    let write_version = container
+        .version
+        .map(|version| quote!(#krate::ByteWriter::write_u8(target, #version);));
+
+    let write_body = match &input.data {
+        Data::Struct(data) => {
+            let fields = parse_fields(&data.fields, &container)?;
+            let bindings = field_bindings(&data.fields);
+            let pattern = destructure(quote!(Self), &data.fields, &bindings);
+            let writes = write_fields(&fields, &bindings, krate);
+            quote! {
+                let #pattern = self;
+                #(#writes)*
+            }
+        }
+        Data::Enum(data) => {
+            if data.variants.len() > 256 {
+                return Err(Error::new_spanned(
+                    name,
+                    "enums with more than 256 variants are not supported",
+                ));
+            }
+            let mut arms = Vec::with_capacity(data.variants.len());
+            for (index, variant) in data.variants.iter().enumerate() {
+                let fields = parse_fields(&variant.fields, &container)?;
+                let bindings = field_bindings(&variant.fields);
+                let variant_name = &variant.ident;
+                let pattern = destructure(quote!(Self::#variant_name), &variant.fields, &bindings);
+                let writes = write_fields(&fields, &bindings, krate);
+                let index = index as u8;
+                arms.push(quote! {
+                    #pattern => {
+                        #krate::ByteWriter::write_u8(target, #index);
+                        #(#writes)*
+                    },
+                });
+            }
+            quote! {
+                match self {
+                    #(#arms)*
+                }
+            }
+        }
+        Data::Union(_) => {
+            return Err(Error::new_spanned(name, "Serializable cannot be derived for unions"))
+        }
+    };
+
+    let generics = add_trait_bounds(&input.generics, &parse_quote!(#krate::Serializable));
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics #krate::Serializable for #name #ty_generics #where_clause {
+            #[allow(unused_variables)]
+            fn write_into<W: #krate::ByteWriter>(&self, target: &mut W) {
+                #write_version
+                #write_body
+            }
+        }
+    })
+}
+
+/// Returns statements which write all fields which are not skipped into `target`.
+fn write_fields(fields: &[Field], bindings: &[Ident], krate: &Path) -> Vec<TokenStream2> {
+    fields
+        .iter()
+        .zip(bindings)
+        .filter(|(field, _)| !field.attrs.skip)
+        .map(|(field, binding)| write_value(&field.ty, quote!(#binding), krate, 0))
+        .collect()
+}
+
+// DESERIALIZABLE
+// ================================================================================================
+
+fn expand_deserializable(input: DeriveInput) -> Result<TokenStream2, Error> {
+    let container = ContainerAttributes::parse(&input.attrs)?;
+    let krate = &container.crate_path;
+    let name = &input.ident;
+
+    let read_version = container.version.map(|version| {
+        quote! {
+            let version = #krate::ByteReader::read_u8(source)?;
+            if version == 0 || version > #version {
+                return ::core::result::Result::Err(#krate::DeserializationError::InvalidValue(
+                    #krate::string::String::from("unsupported serialization format version"),
+                ));
+            }
+        }
+    });
+
+    let read_body = match &input.data {
+        Data::Struct(data) => {
+            let fields = parse_fields(&data.fields, &container)?;
+            let bindings = field_bindings(&data.fields);
+            let reads = read_fields(&fields, &bindings, krate);
+            let constructor = destructure(quote!(Self), &data.fields, &bindings);
+            quote! {
+                #(#reads)*
+                ::core::result::Result::Ok(#constructor)
+            }
+        }
+        Data::Enum(data) => {
+            let mut arms = Vec::with_capacity(data.variants.len());
+            for (index, variant) in data.variants.iter().enumerate() {
+                let fields = parse_fields(&variant.fields, &container)?;
+                let bindings = field_bindings(&variant.fields);
+                let variant_name = &variant.ident;
+                let reads = read_fields(&fields, &bindings, krate);
+                let constructor =
+                    destructure(quote!(Self::#variant_name), &variant.fields, &bindings);
+                let index = index as u8;
+                arms.push(quote! {
+                    #index => {
+                        #(#reads)*
+                        ::core::result::Result::Ok(#constructor)
+                    },
+                });
+            }
+            quote! {
+                match #krate::ByteReader::read_u8(source)? {
+                    #(#arms)*
+                    _ => ::core::result::Result::Err(#krate::DeserializationError::InvalidValue(
+                        #krate::string::String::from("unknown enum variant"),
+                    )),
+                }
+            }
+        }
+        Data::Union(_) => {
+            return Err(Error::new_spanned(name, "Deserializable cannot be derived for unions"))
+        }
+    };
+
+    let generics = add_trait_bounds(&input.generics, &parse_quote!(#krate::Deserializable));
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics #krate::Deserializable for #name #ty_generics #where_clause {
+            fn read_from<R: #krate::ByteReader>(
+                source: &mut R,
+            ) -> ::core::result::Result<Self, #krate::DeserializationError> {
+                #read_version
+                #read_body
+            }
+        }
+    })
+}
+
+/// Returns statements which read all fields into variables with the specified names.
+fn read_fields(fields: &[Field], bindings: &[Ident], krate: &Path) -> Vec<TokenStream2> {
+    fields
+        .iter()
+        .zip(bindings)
+        .map(|(field, binding)| {
+            let read = read_value(&field.ty, krate);
+            match (field.attrs.skip, field.attrs.since) {
+                (true, _) => quote!(let #binding = ::core::default::Default::default();),
+                (false, Some(since)) => quote! {
+                    let #binding = if version >= #since {
+                        #read
+                    } else {
+                        ::core::default::Default::default()
+                    };
+                },
+                (false, None) => quote!(let #binding = #read;),
+            }
+        })
+        .collect()
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// A field of a struct or an enum variant together with its attributes.
+struct Field {
+    ty: syn::Type,
+    attrs: FieldAttributes,
+}
+
+fn parse_fields(fields: &Fields, container: &ContainerAttributes) -> Result<Vec<Field>, Error> {
+    fields
+        .iter()
+        .map(|field| {
+            Ok(Field {
+                ty: field.ty.clone(),
+                attrs: FieldAttributes::parse(&field.attrs, container)?,
+            })
+        })
+        .collect()
+}
+
+/// Returns names of local variables to which the fields are bound.
+fn field_bindings(fields: &Fields) -> Vec<Ident> {
+    (0..fields.len())
+        .map(|i| format_ident!("field_{}", i, span = Span::call_site()))
+        .collect()
+}
+
+/// Returns a pattern (or a constructor expression) of the specified struct or enum variant in
+/// which the fields are bound to the specified variables.
+fn destructure(path: TokenStream2, fields: &Fields, bindings: &[Ident]) -> TokenStream2 {
+    match fields {
+        Fields::Named(named) => {
+            let names = named.named.iter().map(|field| &field.ident);
+            quote!(#path { #(#names: #bindings),* })
+        }
+        Fields::Unnamed(unnamed) => {
+            let indexes = (0..unnamed.unnamed.len()).map(Index::from);
+            quote!(#path { #(#indexes: #bindings),* })
+        }
+        Fields::Unit => quote!(#path),
+    }
+}
+
+/// Adds the specified trait bound to all type parameters of the specified generics.
+fn add_trait_bounds(generics: &Generics, bound: &Path) -> Generics {
+    let mut generics = generics.clone();
+    let params = generics.type_params().map(|param| param.ident.clone()).collect::<Vec<_>>();
+    let where_clause = generics.make_where_clause();
+    for param in params {
+        where_clause.predicates.push(parse_quote!(#param: #bound));
+    }
+    generics
+}
@@ -0,0 +1,182 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{Expr, GenericArgument, Path, PathArguments, Type};
+
+// TYPE CLASSIFICATION
+// ================================================================================================
+
+/// Describes how values of a field type are serialized.
+enum TypeKind<'a> {
+    /// Primitive type written via a dedicated [ByteWriter] method.
+    Primitive(&'a str),
+    /// Vector of values prefixed with its length as a `u32`.
+    Vec(&'a Type),
+    /// Optional value prefixed with a `bool` flag.
+    Option(&'a Type),
+    /// Fixed-size array of values written one after another.
+    Array(&'a Type, &'a Expr),
+    /// Any other type; it must implement `Serializable` and `Deserializable` traits.
+    Other,
+}
+
+impl<'a> TypeKind<'a> {
+    fn of(ty: &'a Type) -> Self {
+        match ty {
+            Type::Group(group) => Self::of(&group.elem),
+            Type::Paren(paren) => Self::of(&paren.elem),
+            Type::Array(array) => Self::Array(&array.elem, &array.len),
+            Type::Path(path) if path.qself.is_none() => {
+                let segment = path.path.segments.last().expect("type path is empty");
+                let name = segment.ident.to_string();
+                match &segment.arguments {
+                    PathArguments::None if path.path.segments.len() == 1 => match name.as_str() {
+                        "bool" => Self::Primitive("bool"),
+                        "u8" => Self::Primitive("u8"),
+                        "u16" => Self::Primitive("u16"),
+                        "u32" => Self::Primitive("u32"),
+                        "u64" => Self::Primitive("u64"),
+                        "u128" => Self::Primitive("u128"),
+                        "usize" => Self::Primitive("usize"),
+                        _ => Self::Other,
+                    },
+                    PathArguments::AngleBracketed(args) if args.args.len() == 1 => {
+                        match (name.as_str(), &args.args[0]) {
+                            ("Vec", GenericArgument::Type(inner)) => Self::Vec(inner),
+                            ("Option", GenericArgument::Type(inner)) => Self::Option(inner),
+                            _ => Self::Other,
+                        }
+                    }
+                    _ => Self::Other,
+                }
+            }
+            _ => Self::Other,
+        }
+    }
+}
+
+// CODE GENERATION
+// ================================================================================================
+
+/// Returns statements which write the value referenced by `value` expression into `target`.
+///
+/// `depth` is used to generate unique names of loop variables for nested types.
+pub fn write_value(ty: &Type, value: TokenStream, krate: &Path, depth: usize) -> TokenStream {
+    let item = format_ident!("item_{}", depth);
+    match TypeKind::of(ty) {
+        TypeKind::Primitive(name) => match name {
+            "bool" => quote!(#krate::ByteWriter::write_bool(target, *#value);),
+            "u8" => quote!(#krate::ByteWriter::write_u8(target, *#value);),
+            "u16" => quote!(#krate::ByteWriter::write_u16(target, *#value);),
+            "u32" => quote!(#krate::ByteWriter::write_u32(target, *#value);),
+            "u64" => quote!(#krate::ByteWriter::write_u64(target, *#value);),
+            "u128" => quote!(#krate::ByteWriter::write_bytes(target, &(#value).to_le_bytes());),
+            "usize" => quote!(#krate::ByteWriter::write_u64(target, *#value as u64);),
+            _ => unreachable!("unknown primitive type {name}"),
+        },
+        TypeKind::Vec(inner) => {
+            let write_item = write_value(inner, quote!(#item), krate, depth + 1);
+            quote! {
+                let len = ::core::convert::TryFrom::try_from((#value).len())
+                    .expect("vector length does not fit into u32");
+                #krate::ByteWriter::write_u32(target, len);
+                for #item in (#value).iter() {
+                    #write_item
+                }
+            }
+        }
+        TypeKind::Option(inner) => {
+            let write_item = write_value(inner, quote!(#item), krate, depth + 1);
+            quote! {
+                match #value {
+                    ::core::option::Option::Some(#item) => {
+                        #krate::ByteWriter::write_bool(target, true);
+                        #write_item
+                    },
+                    ::core::option::Option::None => #krate::ByteWriter::write_bool(target, false),
+                }
+            }
+        }
+        TypeKind::Array(inner, _) => {
+            let write_item = write_value(inner, quote!(#item), krate, depth + 1);
+            quote! {
+                for #item in (#value).iter() {
+                    #write_item
+                }
+            }
+        }
+        TypeKind::Other => quote!(#krate::Serializable::write_into(#value, target);),
+    }
+}
+
+/// Returns an expression which reads a value of the specified type from `source`.
+///
+/// The expression uses `?` operator, and thus, must be evaluated in a function returning
+/// `Result<_, DeserializationError>`.
+pub fn read_value(ty: &Type, krate: &Path) -> TokenStream {
+    match TypeKind::of(ty) {
+        TypeKind::Primitive(name) => match name {
+            "bool" => quote!(#krate::ByteReader::read_bool(source)?),
+            "u8" => quote!(#krate::ByteReader::read_u8(source)?),
+            "u16" => quote!(#krate::ByteReader::read_u16(source)?),
+            "u32" => quote!(#krate::ByteReader::read_u32(source)?),
+            "u64" => quote!(#krate::ByteReader::read_u64(source)?),
+            "u128" => quote!(#krate::ByteReader::read_u128(source)?),
+            "usize" => quote! {
+                match <usize as ::core::convert::TryFrom<u64>>::try_from(
+                    #krate::ByteReader::read_u64(source)?,
+                ) {
+                    ::core::result::Result::Ok(value) => value,
+                    ::core::result::Result::Err(_) => {
+                        return ::core::result::Result::Err(
+                            #krate::DeserializationError::InvalidValue(
+                                #krate::string::String::from("value does not fit into usize"),
+                            ),
+                        )
+                    },
+                }
+            },
+            _ => unreachable!("unknown primitive type {name}"),
+        },
+        TypeKind::Vec(inner) => {
+            let read_item = read_value(inner, krate);
+            quote! {{
+                let len = #krate::ByteReader::read_u32(source)? as usize;
+                let mut items = #krate::collections::Vec::new();
+                for _ in 0..len {
+                    items.push(#read_item);
+                }
+                items
+            }}
+        }
+        TypeKind::Option(inner) => {
+            let read_item = read_value(inner, krate);
+            quote! {
+                if #krate::ByteReader::read_bool(source)? {
+                    ::core::option::Option::Some(#read_item)
+                } else {
+                    ::core::option::Option::None
+                }
+            }
+        }
+        TypeKind::Array(inner, len) => {
+            let read_item = read_value(inner, krate);
+            quote! {{
+                let mut items = #krate::collections::Vec::with_capacity(#len);
+                for _ in 0..#len {
+                    items.push(#read_item);
+                }
+                let items: [#inner; #len] = match ::core::convert::TryFrom::try_from(items) {
+                    ::core::result::Result::Ok(items) => items,
+                    ::core::result::Result::Err(_) => unreachable!(),
+                };
+                items
+            }}
+        }
+        TypeKind::Other => quote!(<#ty as #krate::Deserializable>::read_from(source)?),
+    }
+}
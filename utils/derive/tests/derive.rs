@@ -0,0 +1,167 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use winter_utils::{Deserializable, DeserializationError, Serializable};
+
+// TYPES
+// ================================================================================================
+
+#[derive(Debug, Default, PartialEq, Serializable, Deserializable)]
+struct Point(u32, u64);
+
+#[derive(Debug, PartialEq, Serializable, Deserializable)]
+struct Inputs {
+    flag: bool,
+    small: u8,
+    medium: u16,
+    large: u128,
+    size: usize,
+    values: Vec<u64>,
+    maybe: Option<u32>,
+    array: [u16; 3],
+    pairs: Vec<[u8; 2]>,
+    point: Point,
+    points: Option<Vec<Point>>,
+}
+
+#[derive(Debug, PartialEq, Serializable, Deserializable)]
+enum Shape {
+    Empty,
+    Segment(Point, Point),
+    Polygon { points: Vec<Point>, closed: bool },
+}
+
+#[derive(Debug, PartialEq, Serializable, Deserializable)]
+struct Wrapper<T> {
+    items: Vec<T>,
+    last: Option<T>,
+}
+
+#[derive(Debug, PartialEq, Serializable, Deserializable)]
+#[winter(version = 1)]
+struct RecordV1 {
+    id: u32,
+    point: Point,
+}
+
+#[derive(Debug, PartialEq, Serializable, Deserializable)]
+#[winter(version = 2)]
+struct RecordV2 {
+    id: u32,
+    point: Point,
+    #[winter(since = 2)]
+    label: Vec<u8>,
+    #[winter(skip)]
+    cached: u64,
+}
+
+// TESTS
+// ================================================================================================
+
+#[test]
+fn derive_field_order() {
+    let point = Point(1, 2);
+    assert_eq!([1, 0, 0, 0, 2, 0, 0, 0, 0, 0, 0, 0].to_vec(), point.to_bytes());
+
+    // vectors are prefixed with their length, and options with a flag
+    let shape = Shape::Polygon {
+        points: vec![point],
+        closed: true,
+    };
+    let mut expected = vec![2, 1, 0, 0, 0];
+    expected.extend(Point(1, 2).to_bytes());
+    expected.push(1);
+    assert_eq!(expected, shape.to_bytes());
+}
+
+#[test]
+fn derive_round_trip() {
+    let inputs = Inputs {
+        flag: true,
+        small: 7,
+        medium: 300,
+        large: u128::MAX - 1,
+        size: 12345,
+        values: vec![1, 2, 3],
+        maybe: None,
+        array: [4, 5, 6],
+        pairs: vec![[7, 8], [9, 10]],
+        point: Point(11, 12),
+        points: Some(vec![Point(13, 14), Point(15, 16)]),
+    };
+    let bytes = inputs.to_bytes();
+    assert_eq!(inputs, Inputs::read_from_bytes(&bytes).unwrap());
+
+    // missing bytes are reported as an error
+    assert_eq!(
+        Err(DeserializationError::UnexpectedEOF),
+        Inputs::read_from_bytes(&bytes[..bytes.len() - 1])
+    );
+
+    for shape in [
+        Shape::Empty,
+        Shape::Segment(Point(1, 2), Point(3, 4)),
+        Shape::Polygon {
+            points: vec![Point(5, 6)],
+            closed: false,
+        },
+    ] {
+        assert_eq!(shape, Shape::read_from_bytes(&shape.to_bytes()).unwrap());
+    }
+    assert!(Shape::read_from_bytes(&[3]).is_err());
+
+    let wrapper = Wrapper {
+        items: vec![Point(1, 2)],
+        last: Some(Point(3, 4)),
+    };
+    assert_eq!(wrapper, Wrapper::read_from_bytes(&wrapper.to_bytes()).unwrap());
+}
+
+#[test]
+fn derive_versioning() {
+    let record = RecordV1 {
+        id: 1,
+        point: Point(2, 3),
+    };
+    let bytes = record.to_bytes();
+    assert_eq!(1, bytes[0]);
+
+    // fields added in later versions are set to their defaults when older versions are read
+    let upgraded = RecordV2::read_from_bytes(&bytes).unwrap();
+    assert_eq!(
+        RecordV2 {
+            id: 1,
+            point: Point(2, 3),
+            label: vec![],
+            cached: 0
+        },
+        upgraded
+    );
+
+    // skipped fields are not serialized
+    let record = RecordV2 {
+        id: 1,
+        point: Point(2, 3),
+        label: vec![4],
+        cached: 5,
+    };
+    let bytes = record.to_bytes();
+    assert_eq!(2, bytes[0]);
+    assert_eq!(1 + 4 + 12 + 4 + 1, bytes.len());
+    let parsed = RecordV2::read_from_bytes(&bytes).unwrap();
+    assert_eq!(
+        RecordV2 {
+            cached: 0,
+            ..record
+        },
+        parsed
+    );
+
+    // unknown versions are rejected
+    assert!(RecordV1::read_from_bytes(&bytes).is_err());
+    let mut bytes = bytes;
+    bytes[0] = 0;
+    assert!(RecordV2::read_from_bytes(&bytes).is_err());
+}
@@ -22,12 +22,10 @@ mod internal {
     /// Returns a single random value of the specified type.
     ///
     /// # Panics
-    /// Panics if:
-    /// * A valid value requires over 32 bytes.
-    /// * A valid value could not be generated after 1000 tries.
+    /// Panics if a valid value could not be generated after 1000 tries.
     pub fn rand_value<R: Randomizable>() -> R {
         for _ in 0..1000 {
-            let bytes = rand::thread_rng().gen::<[u8; 32]>();
+            let bytes = gen_bytes(&mut rand::thread_rng(), R::VALUE_SIZE);
             if let Some(value) = R::from_random_bytes(&bytes[..R::VALUE_SIZE]) {
                 return value;
             }
@@ -39,15 +37,13 @@ mod internal {
     /// Returns a vector of random value of the specified type and the specified length.
     ///
     /// # Panics
-    /// Panics if:
-    /// * A valid value requires at over 32 bytes.
-    /// * A valid value could not be generated after 1000 tries.
+    /// Panics if a valid value could not be generated after 1000 tries.
     pub fn rand_vector<R: Randomizable>(n: usize) -> Vec<R> {
         let mut result = Vec::with_capacity(n);
         let seed = rand::thread_rng().gen::<[u8; 32]>();
         let mut g = StdRng::from_seed(seed);
         for _ in 0..1000 * n {
-            let bytes = g.gen::<[u8; 32]>();
+            let bytes = gen_bytes(&mut g, R::VALUE_SIZE);
             if let Some(element) = R::from_random_bytes(&bytes[..R::VALUE_SIZE]) {
                 result.push(element);
                 if result.len() == n {
@@ -62,9 +58,7 @@ mod internal {
     /// Returns an array of random value of the specified type and the specified length.
     ///
     /// # Panics
-    /// Panics if:
-    /// * A valid value requires at over 32 bytes.
-    /// * A valid value could not be generated after 1000 tries.
+    /// Panics if a valid value could not be generated after 1000 tries.
     pub fn rand_array<R: Randomizable + Debug, const N: usize>() -> [R; N] {
         let elements = rand_vector(N);
         elements.try_into().expect("failed to convert vector to array")
@@ -74,14 +68,12 @@ mod internal {
     /// pseudo-randomly from the specified `seed`.
     ///
     /// # Panics
-    /// Panics if:
-    /// * A valid value requires at over 32 bytes.
-    /// * A valid value could not be generated after 1000 tries.
+    /// Panics if a valid value could not be generated after 1000 tries.
     pub fn prng_vector<R: Randomizable>(seed: [u8; 32], n: usize) -> Vec<R> {
         let mut result = Vec::with_capacity(n);
         let mut g = StdRng::from_seed(seed);
         for _ in 0..1000 * n {
-            let bytes = g.gen::<[u8; 32]>();
+            let bytes = gen_bytes(&mut g, R::VALUE_SIZE);
             if let Some(element) = R::from_random_bytes(&bytes[..R::VALUE_SIZE]) {
                 result.push(element);
                 if result.len() == n {
@@ -97,9 +89,7 @@ mod internal {
     /// pseudo-randomly from the specified `seed`.
     ///
     /// # Panics
-    /// Panics if:
-    /// * A valid value requires at over 32 bytes.
-    /// * A valid value could not be generated after 1000 tries.
+    /// Panics if a valid value could not be generated after 1000 tries.
     pub fn prng_array<R: Randomizable + Debug, const N: usize>(seed: [u8; 32]) -> [R; N] {
         let elements = prng_vector(seed, N);
         elements.try_into().expect("failed to convert vector to array")
@@ -112,6 +102,19 @@ mod internal {
     pub fn shuffle<T>(values: &mut [T]) {
         values.shuffle(&mut thread_rng());
     }
+
+    // HELPER FUNCTIONS
+    // ============================================================================================
+
+    /// Returns at least `num_bytes` random bytes generated in chunks of 32 bytes.
+    fn gen_bytes<G: Rng>(g: &mut G, num_bytes: usize) -> Vec<u8> {
+        let num_chunks = num_bytes.div_ceil(32).max(1);
+        let mut result = Vec::with_capacity(num_chunks * 32);
+        for _ in 0..num_chunks {
+            result.extend_from_slice(&g.gen::<[u8; 32]>());
+        }
+        result
+    }
 }
 
 #[cfg(target_family = "wasm")]
@@ -0,0 +1,332 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+//! Conformance checks for field implementations.
+//!
+//! Functions prefixed with `test_` run a group of checks against randomly generated elements
+//! and panic with a description of the minimal failing input if any of the checks fails. The
+//! remaining functions check a single group of properties for the provided elements, and can be
+//! used within custom [proptest] tests.
+
+use math::{ExtensionOf, FieldElement, StarkField};
+use proptest::{
+    collection,
+    prelude::*,
+    test_runner::{TestCaseError, TestRunner},
+};
+use utils::{Serializable, SliceReader};
+
+// STRATEGIES
+// ================================================================================================
+
+/// Returns a strategy which generates uniformly random elements of the specified field.
+pub fn any_element<E: FieldElement>() -> impl Strategy<Value = E> {
+    collection::vec(any::<u8>(), E::VALUE_SIZE)
+        .prop_filter_map("bytes do not encode a field element", |bytes| {
+            E::from_random_bytes(&bytes)
+        })
+}
+
+// TEST SUITES
+// ================================================================================================
+
+/// Checks that arithmetic operations in the field `E` satisfy field axioms.
+///
+/// # Panics
+/// Panics if any of the properties checked by [check_addition()], [check_multiplication()],
+/// [check_inversion()], or [check_exponentiation()] does not hold.
+pub fn test_field_axioms<E: FieldElement>() {
+    let strategy = (any_element::<E>(), any_element::<E>(), any_element::<E>());
+    run(&strategy, |(a, b, c)| {
+        check_addition(a, b, c)?;
+        check_multiplication(a, b, c)?;
+        check_inversion(a, b)
+    });
+
+    run(&(any_element::<E>(), any::<u32>(), any::<u32>()), |(a, p, q)| {
+        check_exponentiation(a, p, q)
+    });
+
+    // zero must be handled as a special case by inversion and exponentiation
+    check_inversion(E::ZERO, E::ONE).unwrap_or_else(|err| panic!("{err}"));
+    check_exponentiation(E::ZERO, 0, 1).unwrap_or_else(|err| panic!("{err}"));
+}
+
+/// Checks that elements of the field `E` survive serialization round-trips.
+///
+/// # Panics
+/// Panics if any of the properties checked by [check_serialization()] does not hold.
+pub fn test_serialization<E: FieldElement>() {
+    run(&(any_element::<E>(), any_element::<E>()), |(a, b)| check_serialization(a, b));
+}
+
+/// Checks that integer representation of elements in the field `B` is consistent with the
+/// modulus of the field.
+///
+/// # Panics
+/// Panics if any of the properties checked by [check_integer_representation()] does not hold.
+pub fn test_integer_representation<B: StarkField>() {
+    run(&any_element::<B>(), check_integer_representation);
+    check_integer_representation(B::ZERO).unwrap_or_else(|err| panic!("{err}"));
+    check_integer_representation(-B::ONE).unwrap_or_else(|err| panic!("{err}"));
+}
+
+/// Checks that the constants defining the multiplicative group of the field `B` are consistent.
+///
+/// Specifically, checks that:
+/// - `GENERATOR` is a quadratic non-residue.
+/// - `TWO_ADIC_ROOT_OF_UNITY` is a root of unity of order exactly 2^`TWO_ADICITY`. This root
+///   does not need to be equal to `GENERATOR`^`k` (e.g., in `f64` field it is not).
+/// - For every `n` in [1, `TWO_ADICITY`], the root of unity returned by `get_root_of_unity(n)`
+///   has order exactly 2^`n`.
+///
+/// # Panics
+/// Panics if any of the above properties does not hold.
+pub fn test_roots_of_unity<B: StarkField>() {
+    // since the modulus is odd, shifting it right by one bit yields (MODULUS - 1) / 2; a
+    // generator of the multiplicative group is not a square, and thus, this power must be -1
+    assert_eq!(
+        -B::ONE,
+        B::GENERATOR.exp(B::MODULUS >> 1),
+        "generator {} is a quadratic residue",
+        B::GENERATOR
+    );
+
+    assert_eq!(B::TWO_ADIC_ROOT_OF_UNITY, B::get_root_of_unity(B::TWO_ADICITY));
+
+    for n in 1..=B::TWO_ADICITY {
+        let root = B::get_root_of_unity(n);
+        let mut power = root;
+        for _ in 1..n {
+            power = power.square();
+        }
+        assert_eq!(-B::ONE, power, "root of unity of order 2^{n} has a smaller order");
+        assert_eq!(B::ONE, power.square(), "root of unity of order 2^{n} has a larger order");
+
+        if n > 1 {
+            assert_eq!(B::get_root_of_unity(n - 1), root.square());
+        }
+    }
+}
+
+/// Checks that the extension field `E` is consistent with its base field.
+///
+/// # Panics
+/// Panics if any of the properties checked by [check_extension_tower()] does not hold.
+pub fn test_extension_tower<B, E>()
+where
+    B: StarkField,
+    E: FieldElement<BaseField = B, PositiveInteger = B::PositiveInteger>,
+{
+    let strategy = (any_element::<E>(), any_element::<E>(), any_element::<B>(), any_element::<B>());
+    run(&strategy, |(a, b, x, y)| check_extension_tower(a, b, x, y));
+}
+
+// PROPERTIES
+// ================================================================================================
+
+/// Checks that addition is associative and commutative, that `ZERO` is the additive identity,
+/// and that subtraction, negation, and doubling are consistent with addition.
+pub fn check_addition<E: FieldElement>(a: E, b: E, c: E) -> Result<(), TestCaseError> {
+    prop_assert_eq!(a + b, b + a);
+    prop_assert_eq!((a + b) + c, a + (b + c));
+    prop_assert_eq!(a, a + E::ZERO);
+
+    prop_assert_eq!(E::ZERO, a + (-a));
+    prop_assert_eq!(a, -(-a));
+    prop_assert_eq!(a - b, a + (-b));
+    prop_assert_eq!(a, (a - b) + b);
+    prop_assert_eq!(a + a, a.double());
+
+    let mut r = a;
+    r += b;
+    prop_assert_eq!(a + b, r);
+    r -= b;
+    prop_assert_eq!(a, r);
+
+    Ok(())
+}
+
+/// Checks that multiplication is associative, commutative, and distributes over addition, that
+/// `ONE` is the multiplicative identity, and that squaring and cubing are consistent with
+/// multiplication.
+pub fn check_multiplication<E: FieldElement>(a: E, b: E, c: E) -> Result<(), TestCaseError> {
+    prop_assert_eq!(a * b, b * a);
+    prop_assert_eq!((a * b) * c, a * (b * c));
+    prop_assert_eq!(a * (b + c), a * b + a * c);
+    prop_assert_eq!(a, a * E::ONE);
+    prop_assert_eq!(E::ZERO, a * E::ZERO);
+    prop_assert_eq!(-a, a * (-E::ONE));
+
+    prop_assert_eq!(a * a, a.square());
+    prop_assert_eq!(a * a * a, a.cube());
+
+    let mut r = a;
+    r *= b;
+    prop_assert_eq!(a * b, r);
+
+    Ok(())
+}
+
+/// Checks that every non-zero element has a multiplicative inverse, that the inverse of `ZERO`
+/// is `ZERO`, and that division is consistent with inversion.
+pub fn check_inversion<E: FieldElement>(a: E, b: E) -> Result<(), TestCaseError> {
+    if a == E::ZERO {
+        prop_assert_eq!(E::ZERO, a.inv());
+    } else {
+        prop_assert_eq!(E::ONE, a * a.inv());
+        prop_assert_eq!(a, a.inv().inv());
+    }
+
+    if b != E::ZERO {
+        prop_assert_eq!(a * b.inv(), a / b);
+        prop_assert_eq!(a, (a / b) * b);
+
+        let mut r = a;
+        r /= b;
+        prop_assert_eq!(a / b, r);
+    }
+
+    Ok(())
+}
+
+/// Checks that exponentiation is consistent with multiplication for exponents `p` and `q`, and
+/// that constant-time and variable-time exponentiation agree.
+pub fn check_exponentiation<E: FieldElement>(a: E, p: u32, q: u32) -> Result<(), TestCaseError> {
+    let exp = |power: u64| a.exp(power.into());
+
+    prop_assert_eq!(E::ONE, exp(0));
+    prop_assert_eq!(a, exp(1));
+    prop_assert_eq!(a.cube(), exp(3));
+
+    prop_assert_eq!(exp(p as u64) * exp(q as u64), exp(p as u64 + q as u64));
+    prop_assert_eq!(exp(p as u64).exp(q.into()), exp(p as u64 * q as u64));
+    prop_assert_eq!(exp(p as u64), a.exp_vartime(p.into()));
+
+    Ok(())
+}
+
+/// Checks that elements `a` and `b` are recovered from their serialized forms, and that
+/// serialized forms of invalid lengths are rejected.
+pub fn check_serialization<E: FieldElement>(a: E, b: E) -> Result<(), TestCaseError> {
+    // single element serialization
+    let bytes = a.to_bytes();
+    prop_assert_eq!(E::ELEMENT_BYTES, bytes.len());
+    prop_assert_eq!(Ok(a), E::read_from_bytes(&bytes));
+    prop_assert_eq!(Some(a), E::try_from(bytes.as_slice()).ok());
+    prop_assert!(E::try_from(&bytes[..bytes.len() - 1]).is_err());
+    prop_assert!(E::read_from_bytes(&bytes[..bytes.len() - 1]).is_err());
+
+    // batch serialization
+    let elements = vec![a, b];
+    let bytes = elements.to_bytes();
+    let result = E::read_batch_from(&mut SliceReader::new(&bytes), elements.len());
+    prop_assert_eq!(Ok(elements.clone()), result);
+
+    // in-memory representation
+    let bytes = E::elements_as_bytes(&elements);
+    prop_assert_eq!(E::ELEMENT_BYTES * elements.len(), bytes.len());
+    if E::IS_CANONICAL {
+        prop_assert_eq!(elements.to_bytes(), bytes);
+    }
+    let result = unsafe { E::bytes_as_elements(bytes) };
+    prop_assert_eq!(Ok(&elements[..]), result);
+    let result = unsafe { E::bytes_as_elements(&bytes[..bytes.len() - 1]) };
+    prop_assert!(result.is_err());
+
+    // decomposition into base field elements
+    let base_elements = E::slice_as_base_elements(&elements);
+    prop_assert_eq!(E::EXTENSION_DEGREE * elements.len(), base_elements.len());
+    for (i, element) in elements.iter().enumerate() {
+        for j in 0..E::EXTENSION_DEGREE {
+            prop_assert_eq!(element.base_element(j), base_elements[i * E::EXTENSION_DEGREE + j]);
+        }
+    }
+    prop_assert_eq!(&elements[..], E::slice_from_base_elements(base_elements));
+
+    Ok(())
+}
+
+/// Checks that the integer representation of `a` is smaller than the modulus of the field, that
+/// it is consistent with the canonical serialization of `a`, and that `a` satisfies Fermat's
+/// little theorem.
+pub fn check_integer_representation<B: StarkField>(a: B) -> Result<(), TestCaseError> {
+    prop_assert_eq!(1, B::EXTENSION_DEGREE);
+    prop_assert!(a.as_int() < B::MODULUS);
+    prop_assert_eq!(a, a.conjugate());
+    prop_assert_eq!(a, a.exp(B::MODULUS));
+
+    // the canonical serialization of an element is the little-endian encoding of its integer
+    // representation; since the integer type is opaque, compare it bit-by-bit
+    let bytes = a.to_bytes();
+    let one = B::PositiveInteger::from(1u32);
+    for (i, byte) in bytes.iter().enumerate() {
+        for j in 0..8 {
+            let bit = (a.as_int() >> (i * 8 + j) as u32) & one;
+            prop_assert_eq!(bit == one, (byte >> j) & 1 == 1);
+        }
+    }
+
+    let modulus_bytes = B::get_modulus_le_bytes();
+    prop_assert!(modulus_bytes.len() <= B::ELEMENT_BYTES);
+    prop_assert_eq!(one, B::MODULUS >> (B::MODULUS_BITS - 1));
+
+    Ok(())
+}
+
+/// Checks that the base field of `E` is embedded into `E` via a ring homomorphism, that
+/// multiplication by base field elements is consistent with this embedding, and that
+/// conjugation in `E` is the Frobenius automorphism (i.e., raising to the power of the modulus
+/// of the base field).
+pub fn check_extension_tower<B, E>(a: E, b: E, x: B, y: B) -> Result<(), TestCaseError>
+where
+    B: StarkField,
+    E: FieldElement<BaseField = B, PositiveInteger = B::PositiveInteger>,
+{
+    prop_assert_eq!(E::ELEMENT_BYTES, B::ELEMENT_BYTES * E::EXTENSION_DEGREE);
+
+    // embedding of the base field
+    prop_assert_eq!(E::ONE, E::from(B::ONE));
+    prop_assert_eq!(E::from(x + y), E::from(x) + E::from(y));
+    prop_assert_eq!(E::from(x * y), E::from(x) * E::from(y));
+    prop_assert_eq!(x, E::from(x).base_element(0));
+    for i in 1..E::EXTENSION_DEGREE {
+        prop_assert_eq!(B::ZERO, E::from(x).base_element(i));
+    }
+
+    // multiplication by base field elements
+    prop_assert_eq!(a * E::from(x), <E as ExtensionOf<B>>::mul_base(a, x));
+
+    // Frobenius automorphism
+    prop_assert_eq!(a.exp(B::MODULUS), a.conjugate());
+    prop_assert_eq!(a.conjugate() * b.conjugate(), (a * b).conjugate());
+    prop_assert_eq!(E::from(x), E::from(x).conjugate());
+
+    let mut conjugate = a;
+    let mut norm = a;
+    for _ in 1..E::EXTENSION_DEGREE {
+        conjugate = conjugate.conjugate();
+        norm *= conjugate;
+    }
+    prop_assert_eq!(a, conjugate.conjugate());
+    prop_assert_eq!(E::from(norm.base_element(0)), norm);
+
+    Ok(())
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Runs the test against values generated by the specified strategy, and panics if the test
+/// fails for any of them.
+fn run<S: Strategy>(strategy: &S, test: impl Fn(S::Value) -> Result<(), TestCaseError>)
+where
+    S::Value: core::fmt::Debug,
+{
+    let mut runner = TestRunner::default();
+    if let Err(err) = runner.run(strategy, test) {
+        panic!("{err}");
+    }
+}
@@ -0,0 +1,128 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+//! This crate contains a conformance test suite for implementations of finite fields used in
+//! Winterfell.
+//!
+//! The suite verifies that an implementation of the [StarkField](math::StarkField) trait (and
+//! the quadratic and cubic extensions built on top of it) behaves as a field: arithmetic
+//! operations satisfy field axioms, elements survive serialization round-trips, roots of unity
+//! have the expected orders, and extension fields are consistent with the base field.
+//!
+//! The simplest way to use the suite is via the [field_conformance_tests] macro, which expands
+//! into a module with a `#[test]` function for each group of checks:
+//! ```ignore
+//! use winter_test_utils::field_conformance_tests;
+//!
+//! field_conformance_tests!(f64_conformance, winter_math::fields::f64::BaseElement);
+//! ```
+//!
+//! The checks are randomized (property-based) and are executed using
+//! [proptest](https://docs.rs/proptest); thus, the number of cases can be controlled via the
+//! `PROPTEST_CASES` environment variable. Individual properties are exposed in the [field]
+//! module and can be combined with custom strategies.
+
+pub mod field;
+
+pub use math;
+pub use proptest;
+
+// FIELD CONFORMANCE TESTS
+// ================================================================================================
+
+/// Generates a module named `$name` with conformance tests for the base field `$field`, as well
+/// as for quadratic and cubic extensions of this field.
+///
+/// Extensions for which [ExtensibleField::is_supported()](math::ExtensibleField::is_supported)
+/// returns `false` are skipped.
+#[macro_export]
+macro_rules! field_conformance_tests {
+    ($name:ident, $field:ty) => {
+        mod $name {
+            #[allow(unused_imports)]
+            use super::*;
+            use $crate::{
+                field,
+                math::{
+                    fields::{CubeExtension, QuadExtension},
+                    ExtensibleField,
+                },
+            };
+
+            type Base = $field;
+
+            // BASE FIELD
+            // ------------------------------------------------------------------------------------
+
+            #[test]
+            fn base_field_axioms() {
+                field::test_field_axioms::<Base>();
+            }
+
+            #[test]
+            fn base_field_serialization() {
+                field::test_serialization::<Base>();
+            }
+
+            #[test]
+            fn base_field_integers() {
+                field::test_integer_representation::<Base>();
+            }
+
+            #[test]
+            fn base_field_roots_of_unity() {
+                field::test_roots_of_unity::<Base>();
+            }
+
+            // QUADRATIC EXTENSION
+            // ------------------------------------------------------------------------------------
+
+            #[test]
+            fn quad_extension_axioms() {
+                if <Base as ExtensibleField<2>>::is_supported() {
+                    field::test_field_axioms::<QuadExtension<Base>>();
+                }
+            }
+
+            #[test]
+            fn quad_extension_serialization() {
+                if <Base as ExtensibleField<2>>::is_supported() {
+                    field::test_serialization::<QuadExtension<Base>>();
+                }
+            }
+
+            #[test]
+            fn quad_extension_tower() {
+                if <Base as ExtensibleField<2>>::is_supported() {
+                    field::test_extension_tower::<Base, QuadExtension<Base>>();
+                }
+            }
+
+            // CUBIC EXTENSION
+            // ------------------------------------------------------------------------------------
+
+            #[test]
+            fn cube_extension_axioms() {
+                if <Base as ExtensibleField<3>>::is_supported() {
+                    field::test_field_axioms::<CubeExtension<Base>>();
+                }
+            }
+
+            #[test]
+            fn cube_extension_serialization() {
+                if <Base as ExtensibleField<3>>::is_supported() {
+                    field::test_serialization::<CubeExtension<Base>>();
+                }
+            }
+
+            #[test]
+            fn cube_extension_tower() {
+                if <Base as ExtensibleField<3>>::is_supported() {
+                    field::test_extension_tower::<Base, CubeExtension<Base>>();
+                }
+            }
+        }
+    };
+}
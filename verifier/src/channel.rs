@@ -5,13 +5,13 @@
 
 use crate::VerifierError;
 use air::{
-    proof::{Queries, StarkProof, Table},
+    proof::{Commitments, Context, OodFrame, Queries, StarkProof, Table},
     Air, EvaluationFrame,
 };
-use crypto::{BatchMerkleProof, ElementHasher, MerkleTree};
-use fri::VerifierChannel as FriVerifierChannel;
+use crypto::{BatchMerkleProof, ElementHasher, Hasher, MerkleTree};
+use fri::{FriProof, FriProofLayer, VerifierChannel as FriVerifierChannel};
 use math::{FieldElement, StarkField};
-use utils::{collections::Vec, string::ToString};
+use utils::{collections::Vec, string::ToString, ByteReader, Deserializable};
 
 // VERIFIER CHANNEL
 // ================================================================================================
@@ -21,39 +21,61 @@ use utils::{collections::Vec, string::ToString};
 /// A channel is instantiated for a specific proof, which is parsed into structs over the
 /// appropriate field (specified by type parameter `E`). This also validates that the proof is
 /// well-formed in the context of the computation for the specified [Air].
-pub struct VerifierChannel<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> {
+///
+/// Trace, constraint, and FRI layer queries are obtained from a [QuerySource] `S` only when the
+/// verifier requests them. For proofs deserialized in full, the queries are parsed when the
+/// channel is created ([ParsedQueries]); for proofs read incrementally, each section of queries
+/// is read and parsed only when it is requested, and is dropped as soon as it is checked against
+/// its commitment ([StreamedQueries]).
+pub struct VerifierChannel<E, H, S = ParsedQueries<E, H>>
+where
+    E: FieldElement,
+    H: ElementHasher<BaseField = E::BaseField>,
+{
     // trace queries
     trace_roots: Vec<H::Digest>,
-    trace_queries: Option<TraceQueries<E, H>>,
+    preprocessed_root: Option<H::Digest>,
     // constraint queries
     constraint_root: H::Digest,
-    constraint_queries: Option<ConstraintQueries<E, H>>,
     // FRI proof
     fri_roots: Option<Vec<H::Digest>>,
     fri_layer_proofs: Vec<BatchMerkleProof<H>>,
     fri_layer_queries: Vec<Vec<E>>,
     fri_remainder: Option<Vec<E>>,
+    fri_layer_nonces: Option<Vec<u64>>,
+    fri_ood_evaluations: Option<Vec<E>>,
     fri_num_partitions: usize,
     // out-of-domain frame
     ood_trace_frame: Option<TraceOodFrame<E>>,
     ood_constraint_evaluations: Option<Vec<E>>,
     // query proof-of-work
     pow_nonce: u64,
+    // number of unique queries
+    num_unique_queries: usize,
+    // source of trace, constraint, and FRI layer queries
+    queries: S,
+    // the first error encountered while reading FRI layer queries from the source
+    read_error: Option<VerifierError>,
 }
 
 impl<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> VerifierChannel<E, H> {
-    // CONSTRUCTOR
+    // CONSTRUCTORS
     // --------------------------------------------------------------------------------------------
     /// Creates and returns a new [VerifierChannel] initialized from the specified `proof`.
+    ///
+    /// `preprocessed_root` is the commitment to the preprocessed columns of the computation
+    /// known to the verifier; it is required only if the AIR declares preprocessed columns.
     pub fn new<A: Air<BaseField = E::BaseField>>(
         air: &A,
         proof: StarkProof,
+        preprocessed_root: Option<H::Digest>,
     ) -> Result<Self, VerifierError> {
         let StarkProof {
             context,
             num_unique_queries,
             commitments,
             trace_queries,
+            preprocessed_queries,
             constraint_queries,
             ood_frame,
             fri_proof,
@@ -61,9 +83,128 @@ impl<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> VerifierChanne
         } = proof;
 
         // make sure AIR and proof base fields are the same
-        if E::BaseField::get_modulus_le_bytes() != context.field_modulus_bytes() {
-            return Err(VerifierError::InconsistentBaseField);
+        check_base_field::<E::BaseField>(&context)?;
+        check_hash_function::<H>(&context)?;
+        let preprocessed_root = check_preprocessed_root(air, preprocessed_root)?;
+
+        // --- parse trace, constraint, and FRI layer queries -------------------------------------
+        let num_unique_queries = num_unique_queries as usize;
+        let trace_queries =
+            TraceQueries::new(trace_queries, preprocessed_queries, air, num_unique_queries)?;
+        let constraint_queries =
+            ConstraintQueries::new(constraint_queries, air, num_unique_queries)?;
+
+        let fri_header = FriHeader::parse(&fri_proof)?;
+        let (fri_layer_queries, fri_layer_proofs) = fri_proof
+            .parse_layers_with_options::<H, E>(
+                air.lde_domain_size(),
+                &air.options().to_fri_options(),
+            )
+            .map_err(deserialization_error)?;
+
+        let queries = ParsedQueries {
+            trace_queries: Some(trace_queries),
+            constraint_queries: Some(constraint_queries),
+            fri_layer_queries,
+            fri_layer_proofs,
+        };
+
+        Self::from_parts(
+            air,
+            num_unique_queries,
+            commitments,
+            preprocessed_root,
+            ood_frame,
+            fri_header,
+            pow_nonce,
+            queries,
+        )
+    }
+}
+
+impl<'a, E, H, A, R> VerifierChannel<E, H, StreamedQueries<'a, A, R>>
+where
+    E: FieldElement,
+    H: ElementHasher<BaseField = E::BaseField>,
+    A: Air<BaseField = E::BaseField>,
+    R: ByteReader,
+{
+    /// Creates and returns a new [VerifierChannel] for a proof read incrementally from the
+    /// specified `source`.
+    ///
+    /// The source is expected to contain a [StarkProof] serialized via
+    /// [StarkProof::write_streaming_into()] with the specified format `version` and with the
+    /// already read `context` section omitted. Only the commitments, the out-of-domain frame, the
+    /// FRI proof without its layers, and the proof-of-work nonce are read here; each section of
+    /// queries is read from the source only when the verifier requests it.
+    pub fn read_from(
+        air: &'a A,
+        context: &Context,
+        version: u8,
+        source: &'a mut R,
+        preprocessed_root: Option<H::Digest>,
+    ) -> Result<Self, VerifierError> {
+        // make sure AIR and proof base fields are the same
+        check_base_field::<E::BaseField>(context)?;
+        check_hash_function::<H>(context)?;
+        let preprocessed_root = check_preprocessed_root(air, preprocessed_root)?;
+
+        let num_unique_queries = source.read_u8().map_err(deserialization_error)? as usize;
+        let commitments = Commitments::read_from(source).map_err(deserialization_error)?;
+        let ood_frame = OodFrame::read_from(source).map_err(deserialization_error)?;
+        let fri_proof = if version == 1 {
+            FriProof::read_legacy_header_from(source)
+        } else {
+            FriProof::read_header_from(source)
         }
+        .map_err(deserialization_error)?;
+        let fri_header = FriHeader::parse(&fri_proof)?;
+        let pow_nonce = source.read_u64().map_err(deserialization_error)?;
+
+        let fri_options = air.options().to_fri_options();
+        let queries = StreamedQueries {
+            air,
+            source,
+            version,
+            num_unique_queries,
+            fri_layer_domain_sizes: fri_options.layer_domain_sizes(air.lde_domain_size()),
+            fri_folding_factors: fri_options.layer_folding_factors(air.lde_domain_size()),
+            fri_query_deduplication: fri_options.query_deduplication(),
+            num_read_fri_layers: 0,
+        };
+
+        Self::from_parts(
+            air,
+            num_unique_queries,
+            commitments,
+            preprocessed_root,
+            ood_frame,
+            fri_header,
+            pow_nonce,
+            queries,
+        )
+    }
+}
+
+impl<E, H, S> VerifierChannel<E, H, S>
+where
+    E: FieldElement,
+    H: ElementHasher<BaseField = E::BaseField>,
+    S: QuerySource<E, H>,
+{
+    /// Creates a new [VerifierChannel] from the specified source of queries and the remaining
+    /// unparsed sections of a proof.
+    #[allow(clippy::too_many_arguments)]
+    fn from_parts<A: Air<BaseField = E::BaseField>>(
+        air: &A,
+        num_unique_queries: usize,
+        commitments: Commitments,
+        preprocessed_root: Option<H::Digest>,
+        ood_frame: OodFrame,
+        fri_header: FriHeader<E>,
+        pow_nonce: u64,
+        queries: S,
+    ) -> Result<Self, VerifierError> {
         let constraint_frame_width = air.context().num_constraint_composition_columns();
 
         let num_trace_segments = air.trace_layout().num_segments();
@@ -75,47 +216,49 @@ impl<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> VerifierChanne
         // --- parse commitments ------------------------------------------------------------------
         let (trace_roots, constraint_root, fri_roots) = commitments
             .parse::<H>(num_trace_segments, fri_options.num_fri_layers(lde_domain_size))
-            .map_err(|err| VerifierError::ProofDeserializationError(err.to_string()))?;
-
-        // --- parse trace and constraint queries -------------------------------------------------
-        let trace_queries = TraceQueries::new(trace_queries, air, num_unique_queries as usize)?;
-        let constraint_queries =
-            ConstraintQueries::new(constraint_queries, air, num_unique_queries as usize)?;
-
-        // --- parse FRI proofs -------------------------------------------------------------------
-        let fri_num_partitions = fri_proof.num_partitions();
-        let fri_remainder = fri_proof
-            .parse_remainder()
-            .map_err(|err| VerifierError::ProofDeserializationError(err.to_string()))?;
-        let (fri_layer_queries, fri_layer_proofs) = fri_proof
-            .parse_layers::<H, E>(lde_domain_size, fri_options.folding_factor())
-            .map_err(|err| VerifierError::ProofDeserializationError(err.to_string()))?;
+            .map_err(deserialization_error)?;
 
         // --- parse out-of-domain evaluation frame -----------------------------------------------
         let (ood_trace_evaluations, ood_constraint_evaluations) = ood_frame
             .parse(main_trace_width, aux_trace_width, constraint_frame_width)
-            .map_err(|err| VerifierError::ProofDeserializationError(err.to_string()))?;
-        let ood_trace_frame =
-            TraceOodFrame::new(ood_trace_evaluations, main_trace_width, aux_trace_width);
+            .map_err(deserialization_error)?;
+        let num_ood_rows = air.context().num_ood_points();
+        if ood_trace_evaluations.len() != (main_trace_width + aux_trace_width) * num_ood_rows {
+            return Err(VerifierError::ProofDeserializationError(format!(
+                "out-of-domain trace frame must contain {num_ood_rows} rows"
+            )));
+        }
+        let ood_trace_frame = TraceOodFrame::new(
+            ood_trace_evaluations,
+            main_trace_width,
+            aux_trace_width,
+            air.context().ood_offsets(),
+        );
 
         Ok(VerifierChannel {
             // trace queries
             trace_roots,
-            trace_queries: Some(trace_queries),
+            preprocessed_root,
             // constraint queries
             constraint_root,
-            constraint_queries: Some(constraint_queries),
             // FRI proof
             fri_roots: Some(fri_roots),
-            fri_layer_proofs,
-            fri_layer_queries,
-            fri_remainder: Some(fri_remainder),
-            fri_num_partitions,
+            fri_layer_proofs: Vec::new(),
+            fri_layer_queries: Vec::new(),
+            fri_remainder: Some(fri_header.remainder),
+            fri_layer_nonces: Some(fri_header.layer_nonces),
+            fri_ood_evaluations: Some(fri_header.ood_evaluations),
+            fri_num_partitions: fri_header.num_partitions,
             // out-of-domain evaluation
             ood_trace_frame: Some(ood_trace_frame),
             ood_constraint_evaluations: Some(ood_constraint_evaluations),
             // query seed
             pow_nonce,
+            // number of unique queries
+            num_unique_queries,
+            // query source
+            queries,
+            read_error: None,
         })
     }
 
@@ -130,6 +273,12 @@ impl<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> VerifierChanne
         &self.trace_roots
     }
 
+    /// Returns the commitment to the preprocessed columns known to the verifier, or `None` if the
+    /// computation has no preprocessed columns.
+    pub fn read_preprocessed_commitment(&self) -> Option<H::Digest> {
+        self.preprocessed_root
+    }
+
     /// Returns constraint evaluation commitment sent by the prover.
     pub fn read_constraint_commitment(&self) -> H::Digest {
         self.constraint_root
@@ -155,23 +304,43 @@ impl<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> VerifierChanne
         self.pow_nonce
     }
 
+    /// Returns the number of unique query positions for which the prover sent openings.
+    pub fn read_num_unique_queries(&self) -> usize {
+        self.num_unique_queries
+    }
+
     /// Returns trace states at the specified positions of the LDE domain. This also checks if
     /// the trace states are valid against the trace commitment sent by the prover.
     ///
     /// For computations requiring multiple trace segments, trace states for auxiliary segments
     /// are also included as the second value of the returned tuple (trace states for all auxiliary
     /// segments are merged into a single table). Otherwise, the second value is None.
+    ///
+    /// For computations with preprocessed columns, values of these columns are checked against
+    /// the commitment to the preprocessed columns known to the verifier, and are included at the
+    /// end of each row of the main trace states.
     #[allow(clippy::type_complexity)]
     pub fn read_queried_trace_states(
         &mut self,
         positions: &[usize],
     ) -> Result<(Table<E::BaseField>, Option<Table<E>>), VerifierError> {
-        let queries = self.trace_queries.take().expect("already read");
+        let queries = self.queries.read_trace_queries()?;
 
         // make sure the states included in the proof correspond to the trace commitment
-        for (root, proof) in self.trace_roots.iter().zip(queries.query_proofs.iter()) {
-            MerkleTree::verify_batch(root, positions, proof)
-                .map_err(|_| VerifierError::TraceQueryDoesNotMatchCommitment)?;
+        for (segment, (root, proof)) in
+            self.trace_roots.iter().zip(queries.query_proofs.iter()).enumerate()
+        {
+            verify_openings(root, positions, proof, |position| {
+                VerifierError::TraceQueryDoesNotMatchCommitment(segment, position)
+            })?;
+        }
+        if let (Some(root), Some(proof)) = (&self.preprocessed_root, &queries.preprocessed_proof) {
+            verify_openings(
+                root,
+                positions,
+                proof,
+                VerifierError::PreprocessedQueryDoesNotMatchCommitment,
+            )?;
         }
 
         Ok((queries.main_states, queries.aux_states))
@@ -184,22 +353,59 @@ impl<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> VerifierChanne
         &mut self,
         positions: &[usize],
     ) -> Result<Table<E>, VerifierError> {
-        let queries = self.constraint_queries.take().expect("already read");
+        let queries = self.queries.read_constraint_queries()?;
 
-        MerkleTree::verify_batch(&self.constraint_root, positions, &queries.query_proofs)
-            .map_err(|_| VerifierError::ConstraintQueryDoesNotMatchCommitment)?;
+        verify_openings(
+            &self.constraint_root,
+            positions,
+            &queries.query_proofs,
+            VerifierError::ConstraintQueryDoesNotMatchCommitment,
+        )?;
 
         Ok(queries.evaluations)
     }
+
+    /// Returns the first error encountered while reading FRI layer queries from the query
+    /// source, if any.
+    ///
+    /// The FRI verifier cannot receive such errors from the channel; thus, when a layer cannot
+    /// be read, the channel returns an empty layer (which fails FRI verification), and records
+    /// the error to be reported instead of the FRI verification failure.
+    pub fn take_read_error(&mut self) -> Option<VerifierError> {
+        self.read_error.take()
+    }
+
+    /// Reads the next FRI layer from the query source into the buffers of this channel.
+    ///
+    /// The FRI verifier takes query values and the Merkle proof of each layer in either order;
+    /// thus, a layer is read when either of its parts is requested, and the other part is kept
+    /// in the buffers until it is taken.
+    fn load_next_fri_layer(&mut self) {
+        let (queries, proof) = match self.queries.read_next_fri_layer() {
+            Ok(layer) => layer,
+            Err(err) => {
+                self.read_error.get_or_insert(err);
+                let proof = BatchMerkleProof {
+                    leaves: Vec::new(),
+                    nodes: Vec::new(),
+                    depth: 0,
+                };
+                (Vec::new(), proof)
+            }
+        };
+        self.fri_layer_queries.push(queries);
+        self.fri_layer_proofs.push(proof);
+    }
 }
 
 // FRI VERIFIER CHANNEL IMPLEMENTATION
 // ================================================================================================
 
-impl<E, H> FriVerifierChannel<E> for VerifierChannel<E, H>
+impl<E, H, S> FriVerifierChannel<E> for VerifierChannel<E, H, S>
 where
     E: FieldElement,
     H: ElementHasher<BaseField = E::BaseField>,
+    S: QuerySource<E, H>,
 {
     type Hasher = H;
 
@@ -211,11 +417,25 @@ where
         self.fri_roots.take().expect("already read")
     }
 
+    fn read_fri_layer_nonces(&mut self) -> Vec<u64> {
+        self.fri_layer_nonces.take().expect("already read")
+    }
+
+    fn read_fri_ood_evaluations(&mut self) -> Vec<E> {
+        self.fri_ood_evaluations.take().expect("already read")
+    }
+
     fn take_next_fri_layer_proof(&mut self) -> BatchMerkleProof<H> {
+        if self.fri_layer_proofs.is_empty() {
+            self.load_next_fri_layer();
+        }
         self.fri_layer_proofs.remove(0)
     }
 
     fn take_next_fri_layer_queries(&mut self) -> Vec<E> {
+        if self.fri_layer_queries.is_empty() {
+            self.load_next_fri_layer();
+        }
         self.fri_layer_queries.remove(0)
     }
 
@@ -224,16 +444,166 @@ where
     }
 }
 
+// QUERY SOURCES
+// ================================================================================================
+
+/// Defines how a [VerifierChannel] obtains trace, constraint, and FRI layer queries of a proof.
+///
+/// Each of the trace and constraint queries is requested at most once, and FRI layers are
+/// requested in order, one layer at a time.
+pub trait QuerySource<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> {
+    /// Returns parsed trace queries of the proof.
+    fn read_trace_queries(&mut self) -> Result<TraceQueries<E, H>, VerifierError>;
+
+    /// Returns parsed constraint evaluation queries of the proof.
+    fn read_constraint_queries(&mut self) -> Result<ConstraintQueries<E, H>, VerifierError>;
+
+    /// Returns query values and the batch Merkle proof of the next FRI layer of the proof.
+    fn read_next_fri_layer(&mut self) -> Result<(Vec<E>, BatchMerkleProof<H>), VerifierError>;
+}
+
+/// Queries of a proof which were all parsed before verification started.
+pub struct ParsedQueries<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> {
+    trace_queries: Option<TraceQueries<E, H>>,
+    constraint_queries: Option<ConstraintQueries<E, H>>,
+    fri_layer_queries: Vec<Vec<E>>,
+    fri_layer_proofs: Vec<BatchMerkleProof<H>>,
+}
+
+impl<E, H> QuerySource<E, H> for ParsedQueries<E, H>
+where
+    E: FieldElement,
+    H: ElementHasher<BaseField = E::BaseField>,
+{
+    fn read_trace_queries(&mut self) -> Result<TraceQueries<E, H>, VerifierError> {
+        Ok(self.trace_queries.take().expect("already read"))
+    }
+
+    fn read_constraint_queries(&mut self) -> Result<ConstraintQueries<E, H>, VerifierError> {
+        Ok(self.constraint_queries.take().expect("already read"))
+    }
+
+    fn read_next_fri_layer(&mut self) -> Result<(Vec<E>, BatchMerkleProof<H>), VerifierError> {
+        if self.fri_layer_queries.is_empty() {
+            return Err(VerifierError::ProofDeserializationError(
+                "proof contains no more FRI layers".to_string(),
+            ));
+        }
+        Ok((self.fri_layer_queries.remove(0), self.fri_layer_proofs.remove(0)))
+    }
+}
+
+/// Queries of a proof which are read from a [ByteReader] one section at a time.
+///
+/// The sections are expected in the order in which they are written by
+/// [StarkProof::write_streaming_into()]: trace queries, constraint queries, and then FRI layers.
+pub struct StreamedQueries<'a, A, R> {
+    air: &'a A,
+    source: &'a mut R,
+    version: u8,
+    num_unique_queries: usize,
+    fri_layer_domain_sizes: Vec<usize>,
+    fri_folding_factors: Vec<usize>,
+    fri_query_deduplication: bool,
+    num_read_fri_layers: usize,
+}
+
+impl<E, H, A, R> QuerySource<E, H> for StreamedQueries<'_, A, R>
+where
+    E: FieldElement,
+    H: ElementHasher<BaseField = E::BaseField>,
+    A: Air<BaseField = E::BaseField>,
+    R: ByteReader,
+{
+    fn read_trace_queries(&mut self) -> Result<TraceQueries<E, H>, VerifierError> {
+        let num_trace_segments = self.air.trace_layout().num_segments();
+        let mut trace_queries = Vec::with_capacity(num_trace_segments);
+        for _ in 0..num_trace_segments {
+            trace_queries.push(Queries::read_from(self.source).map_err(deserialization_error)?);
+        }
+        let has_preprocessed_queries = self.version >= 5 && {
+            match self.source.read_u8().map_err(deserialization_error)? {
+                0 => false,
+                1 => true,
+                value => {
+                    return Err(VerifierError::ProofDeserializationError(format!(
+                        "value {value} cannot be deserialized as a preprocessed queries flag"
+                    )))
+                }
+            }
+        };
+        let preprocessed_queries = if has_preprocessed_queries {
+            Some(Queries::read_from(self.source).map_err(deserialization_error)?)
+        } else {
+            None
+        };
+        TraceQueries::new(trace_queries, preprocessed_queries, self.air, self.num_unique_queries)
+    }
+
+    fn read_constraint_queries(&mut self) -> Result<ConstraintQueries<E, H>, VerifierError> {
+        let constraint_queries = Queries::read_from(self.source).map_err(deserialization_error)?;
+        ConstraintQueries::new(constraint_queries, self.air, self.num_unique_queries)
+    }
+
+    fn read_next_fri_layer(&mut self) -> Result<(Vec<E>, BatchMerkleProof<H>), VerifierError> {
+        let index = self.num_read_fri_layers;
+        let (&domain_size, &folding_factor) = self
+            .fri_layer_domain_sizes
+            .get(index)
+            .zip(self.fri_folding_factors.get(index))
+            .ok_or_else(|| {
+                VerifierError::ProofDeserializationError(
+                    "proof contains no more FRI layers".to_string(),
+                )
+            })?;
+        self.num_read_fri_layers += 1;
+
+        let layer =
+            FriProofLayer::read_with_deduplication(self.source, self.fri_query_deduplication)
+                .map_err(deserialization_error)?;
+        layer.parse(domain_size / folding_factor, folding_factor).map_err(|err| {
+            VerifierError::ProofDeserializationError(format!(
+                "failed to parse FRI layer {index}: {err}"
+            ))
+        })
+    }
+}
+
+// FRI PROOF HEADER
+// ================================================================================================
+
+/// Parts of a FRI proof other than its layers, parsed into the specified field.
+struct FriHeader<E: FieldElement> {
+    remainder: Vec<E>,
+    ood_evaluations: Vec<E>,
+    layer_nonces: Vec<u64>,
+    num_partitions: usize,
+}
+
+impl<E: FieldElement> FriHeader<E> {
+    /// Parses all parts of the specified FRI proof except for its layers.
+    fn parse(fri_proof: &FriProof) -> Result<Self, VerifierError> {
+        Ok(Self {
+            remainder: fri_proof.parse_remainder().map_err(deserialization_error)?,
+            ood_evaluations: fri_proof.parse_ood_evaluations().map_err(deserialization_error)?,
+            layer_nonces: fri_proof.layer_nonces().to_vec(),
+            num_partitions: fri_proof.num_partitions(),
+        })
+    }
+}
+
 // TRACE QUERIES
 // ================================================================================================
 
 /// Container of trace query data, including:
-/// * Queried states for all trace segments.
+/// * Queried states for all trace segments and for preprocessed columns.
 /// * Merkle authentication paths for all queries.
 ///
-/// Trace states for all auxiliary segments are stored in a single table.
-struct TraceQueries<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> {
+/// States of preprocessed columns are appended to the states of the main trace segment, and
+/// trace states for all auxiliary segments are stored in a single table.
+pub struct TraceQueries<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> {
     query_proofs: Vec<BatchMerkleProof<H>>,
+    preprocessed_proof: Option<BatchMerkleProof<H>>,
     main_states: Table<E::BaseField>,
     aux_states: Option<Table<E>>,
 }
@@ -243,6 +613,7 @@ impl<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> TraceQueries<E
     /// corresponding Merkle authentication paths.
     pub fn new<A: Air<BaseField = E::BaseField>>(
         mut queries: Vec<Queries>,
+        preprocessed_queries: Option<Queries>,
         air: &A,
         num_queries: usize,
     ) -> Result<Self, VerifierError> {
@@ -256,16 +627,57 @@ impl<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> TraceQueries<E
 
         // parse main trace segment queries; parsing also validates that hashes of each table row
         // form the leaves of Merkle authentication paths in the proofs
-        let main_segment_width = air.trace_layout().main_trace_width();
+        let leaf_encoding = air.options().leaf_encoding();
+        let num_preprocessed_columns = air.context().num_preprocessed_columns();
+        let main_segment_width = air.trace_layout().main_trace_width() - num_preprocessed_columns;
         let main_segment_queries = queries.remove(0);
         let (main_segment_query_proofs, main_segment_states) = main_segment_queries
-            .parse::<H, E::BaseField>(air.lde_domain_size(), num_queries, main_segment_width)
+            .parse_with_encoding::<H, E::BaseField>(
+                air.lde_domain_size(),
+                num_queries,
+                main_segment_width,
+                leaf_encoding,
+            )
             .map_err(|err| {
                 VerifierError::ProofDeserializationError(format!(
                     "main trace segment query deserialization failed: {err}"
                 ))
             })?;
 
+        // parse preprocessed column queries (if any), and append the resulting table to the main
+        // segment states
+        let (preprocessed_proof, main_segment_states) =
+            match (num_preprocessed_columns, preprocessed_queries) {
+                (0, None) => (None, main_segment_states),
+                (0, Some(_)) => {
+                    return Err(VerifierError::ProofDeserializationError(
+                        "proof contains queries of preprocessed columns, but the computation has \
+                        no preprocessed columns"
+                            .to_string(),
+                    ))
+                }
+                (_, None) => {
+                    return Err(VerifierError::ProofDeserializationError(
+                        "proof does not contain queries of preprocessed columns".to_string(),
+                    ))
+                }
+                (num_columns, Some(preprocessed_queries)) => {
+                    let (proof, states) = preprocessed_queries
+                        .parse_with_encoding::<H, E::BaseField>(
+                            air.lde_domain_size(),
+                            num_queries,
+                            num_columns,
+                            leaf_encoding,
+                        )
+                        .map_err(|err| {
+                            VerifierError::ProofDeserializationError(format!(
+                                "preprocessed column query deserialization failed: {err}"
+                            ))
+                        })?;
+                    (Some(proof), Table::merge(vec![main_segment_states, states]))
+                }
+            };
+
         // all query proofs will be aggregated into a single vector
         let mut query_proofs = vec![main_segment_query_proofs];
 
@@ -277,7 +689,12 @@ impl<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> TraceQueries<E
             for (i, segment_queries) in queries.into_iter().enumerate() {
                 let segment_width = air.trace_layout().get_aux_segment_width(i);
                 let (segment_query_proof, segment_trace_states) = segment_queries
-                    .parse::<H, E>(air.lde_domain_size(), num_queries, segment_width)
+                    .parse_with_encoding::<H, E>(
+                        air.lde_domain_size(),
+                        num_queries,
+                        segment_width,
+                        leaf_encoding,
+                    )
                     .map_err(|err| {
                         VerifierError::ProofDeserializationError(format!(
                             "auxiliary trace segment query deserialization failed: {err}"
@@ -296,6 +713,7 @@ impl<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> TraceQueries<E
 
         Ok(Self {
             query_proofs,
+            preprocessed_proof,
             main_states: main_segment_states,
             aux_states: aux_trace_states,
         })
@@ -308,7 +726,7 @@ impl<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> TraceQueries<E
 /// Container of constraint evaluation query data, including:
 /// * Queried constraint evaluation values.
 /// * Merkle authentication paths for all queries.
-struct ConstraintQueries<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> {
+pub struct ConstraintQueries<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> {
     query_proofs: BatchMerkleProof<H>,
     evaluations: Table<E>,
 }
@@ -345,14 +763,25 @@ pub struct TraceOodFrame<E: FieldElement> {
     values: Vec<E>,
     main_trace_width: usize,
     aux_trace_width: usize,
+    num_rows: usize,
+    offsets: Vec<usize>,
 }
 
 impl<E: FieldElement> TraceOodFrame<E> {
-    pub fn new(values: Vec<E>, main_trace_width: usize, aux_trace_width: usize) -> Self {
+    pub fn new(
+        values: Vec<E>,
+        main_trace_width: usize,
+        aux_trace_width: usize,
+        offsets: &[usize],
+    ) -> Self {
+        let num_rows = offsets.len();
+        debug_assert_eq!(values.len(), (main_trace_width + aux_trace_width) * num_rows);
         Self {
             values,
             main_trace_width,
             aux_trace_width,
+            num_rows,
+            offsets: offsets.to_vec(),
         }
     }
 
@@ -360,11 +789,11 @@ impl<E: FieldElement> TraceOodFrame<E> {
         &self.values
     }
 
-    // The out-of-domain frame is stored as one vector of interleaved values, one from the
-    // current row and the other from the next row. See `OodFrame::set_trace_states`.
-    // Thus we need to untangle the current and next rows stored in `Self::values` and we
-    // do that for the main and auxiliary traces separately.
-    // Pictorially, for the main trace portion:
+    // The out-of-domain frame is stored as one vector of interleaved values, one from each
+    // out-of-domain row (with default offsets, these are the current and the next rows). See
+    // `OodFrame::set_trace_states`. Thus we need to untangle the rows stored in `Self::values`
+    // and we do that for the main and auxiliary traces separately.
+    // Pictorially, for the main trace portion with two rows:
     //
     // Input vector: [a1, b1, a2, b2, ..., an, bn, c1, d1, c2, d2, ..., cm, dm]
     // with n being the main trace width and m the auxiliary trace width.
@@ -375,43 +804,129 @@ impl<E: FieldElement> TraceOodFrame<E> {
     //          +-------+-------+-------+-------+-------+
     //          |  b1   |   b2  |   b3  |  ...  |   bn  |
     //          +-------+-------+-------+-------+-------+
-    pub fn main_frame(&self) -> EvaluationFrame<E> {
-        let mut current = vec![E::ZERO; self.main_trace_width];
-        let mut next = vec![E::ZERO; self.main_trace_width];
+    pub fn main_rows(&self) -> Vec<Vec<E>> {
+        let values = &self.values[..self.main_trace_width * self.num_rows];
+        untangle_rows(values, self.main_trace_width, self.num_rows)
+    }
 
-        for (i, a) in self.values.chunks(2).take(self.main_trace_width).enumerate() {
-            current[i] = a[0];
-            next[i] = a[1];
+    // Similar to `Self::main_rows`, the following untangles the rows stored in `Self::values`
+    // for the auxiliary trace portion when it exists else it returns `None`.
+    pub fn aux_rows(&self) -> Option<Vec<Vec<E>>> {
+        if self.aux_trace_width == 0 {
+            None
+        } else {
+            let values = &self.values[self.main_trace_width * self.num_rows..];
+            Some(untangle_rows(values, self.aux_trace_width, self.num_rows))
         }
+    }
 
-        EvaluationFrame::from_rows(current, next)
+    /// Returns the evaluation frame of the main trace consisting of all rows of the
+    /// out-of-domain frame (i.e., the rows at z * g^k for every out-of-domain offset k).
+    pub fn main_frame(&self) -> EvaluationFrame<E> {
+        EvaluationFrame::from_offset_rows(&self.offsets, self.main_rows())
     }
 
-    // Similar to `Self::main_frame`, the following untangles the current and next rows stored
-    // in `Self::values` for the auxiliary trace portion when it exists else it returns `None`.
-    // Pictorially:
-    //
-    // Input vector: [a1, b1, a2, b2, ..., an, bn, c1, d1, c2, d2, ..., cm, dm]
-    // with n being the main trace width and m the auxiliary trace width.
-    //
-    // Output:
-    //          +-------+-------+-------+-------+-------+
-    //          |  c1   |   c2  |   c3  |  ...  |   cm  |
-    //          +-------+-------+-------+-------+-------+
-    //          |  d1   |   d2  |   d3  |  ...  |   dm  |
-    //          +-------+-------+-------+-------+-------+
+    /// Returns the evaluation frame of the auxiliary trace consisting of all rows of the
+    /// out-of-domain frame, or `None` if the trace has no auxiliary segments.
     pub fn aux_frame(&self) -> Option<EvaluationFrame<E>> {
-        if self.aux_trace_width == 0 {
-            None
-        } else {
-            let mut current_aux = vec![E::ZERO; self.aux_trace_width];
-            let mut next_aux = vec![E::ZERO; self.aux_trace_width];
+        Some(EvaluationFrame::from_offset_rows(&self.offsets, self.aux_rows()?))
+    }
+}
 
-            for (i, a) in self.values.chunks(2).skip(self.main_trace_width).enumerate() {
-                current_aux[i] = a[0];
-                next_aux[i] = a[1];
-            }
-            Some(EvaluationFrame::from_rows(current_aux, next_aux))
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns an error if the base field of the proof described by the specified context is not
+/// the same as base field `B`.
+pub(crate) fn check_base_field<B: StarkField>(context: &Context) -> Result<(), VerifierError> {
+    if B::get_modulus_le_bytes() != context.field_modulus_bytes() {
+        return Err(VerifierError::InconsistentBaseField);
+    }
+    Ok(())
+}
+
+/// Returns an error if the proof described by the specified context records a hash function
+/// which is different from hash function `H`; proofs which do not record a hash function are not
+/// checked.
+fn check_hash_function<H: Hasher>(context: &Context) -> Result<(), VerifierError> {
+    match context.hash_function() {
+        Some(hash_fn) if Some(hash_fn) != H::HASH_FUNCTION => {
+            Err(VerifierError::InconsistentHashFunction)
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Returns the commitment to preprocessed columns to be used by the verifier, or an error if the
+/// computation described by the specified AIR has preprocessed columns, but the commitment to them
+/// was not provided. The commitment is ignored for computations without preprocessed columns.
+fn check_preprocessed_root<A: Air, D>(
+    air: &A,
+    root: Option<D>,
+) -> Result<Option<D>, VerifierError> {
+    if air.context().num_preprocessed_columns() == 0 {
+        return Ok(None);
+    }
+    root.map(Some).ok_or(VerifierError::PreprocessedCommitmentMissing)
+}
+
+/// Splits values of `width` columns with `num_rows` values interleaved for each column into
+/// `num_rows` rows.
+fn untangle_rows<E: FieldElement>(values: &[E], width: usize, num_rows: usize) -> Vec<Vec<E>> {
+    let mut rows = vec![Vec::with_capacity(width); num_rows];
+    for column in values.chunks(num_rows) {
+        for (row, &value) in rows.iter_mut().zip(column) {
+            row.push(value);
         }
     }
+    rows
+}
+
+/// Converts an error encountered while parsing a proof into a [VerifierError].
+fn deserialization_error<T: ToString>(err: T) -> VerifierError {
+    VerifierError::ProofDeserializationError(err.to_string())
+}
+
+/// Checks that openings at the specified positions are valid against the specified commitment
+/// root.
+///
+/// A batch Merkle proof does not reveal which of the openings is invalid; thus, if the batch
+/// proof does not resolve to the root, it is split into individual authentication paths, and the
+/// error built by `mismatch_error` for the smallest position with an invalid path is returned.
+/// Since paths of different openings share nodes, an invalid opening may also invalidate paths
+/// of other openings, and thus, the reported position is not necessarily the one which was
+/// tampered with.
+fn verify_openings<H, F>(
+    root: &H::Digest,
+    positions: &[usize],
+    proof: &BatchMerkleProof<H>,
+    mismatch_error: F,
+) -> Result<(), VerifierError>
+where
+    H: Hasher,
+    F: Fn(usize) -> VerifierError,
+{
+    if MerkleTree::verify_batch(root, positions, proof).is_ok() {
+        return Ok(());
+    }
+
+    let proof = BatchMerkleProof::<H> {
+        leaves: proof.leaves.clone(),
+        nodes: proof.nodes.clone(),
+        depth: proof.depth,
+    };
+    let paths = proof.into_paths(positions).map_err(|err| {
+        VerifierError::ProofDeserializationError(format!("invalid batch Merkle proof: {err}"))
+    })?;
+    for (&position, path) in positions.iter().zip(paths.iter()) {
+        if path.len() < 2 || MerkleTree::<H>::verify(*root, position, path).is_err() {
+            return Err(mismatch_error(position));
+        }
+    }
+
+    // all individual paths are valid, but the batch proof is not; this can happen only if the
+    // batch proof is malformed
+    Err(VerifierError::ProofDeserializationError(
+        "invalid batch Merkle proof".to_string(),
+    ))
 }
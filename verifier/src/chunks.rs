@@ -0,0 +1,146 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use crate::{verify, AcceptableOptions, VerifierError};
+use air::{
+    proof::{ProofChunk, StarkProof},
+    Air,
+};
+use crypto::{Digest, ElementHasher, RandomCoin};
+use utils::{collections::Vec, string::ToString};
+
+// CHUNKED PROOF VERIFIER
+// ================================================================================================
+
+/// Verifies a STARK proof which was split into chunks via
+/// [StarkProof::to_chunks()](air::proof::StarkProof::to_chunks).
+///
+/// Chunks are accepted one at a time and in any order via
+/// [add_chunk()](ChunkedProofVerifier::add_chunk). Every chunk is checked against the linking
+/// headers of the chunks accepted before it as soon as it is added, so that chunks of a
+/// different proof are rejected without waiting for the rest of the proof. Re-submitting an
+/// already accepted chunk has no effect. Once all chunks are received, the proof is reassembled,
+/// checked against the proof digest in the headers, and verified via
+/// [verify()](ChunkedProofVerifier::verify).
+#[derive(Debug, Clone, Default)]
+pub struct ChunkedProofVerifier {
+    chunks: Vec<Option<ProofChunk>>,
+    num_received: usize,
+}
+
+impl ChunkedProofVerifier {
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a new verifier which has not received any chunks yet.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the total number of chunks of the proof, or 0 if no chunks were received yet.
+    pub fn num_chunks(&self) -> usize {
+        self.chunks.len()
+    }
+
+    /// Returns the number of distinct chunks received so far.
+    pub fn num_received(&self) -> usize {
+        self.num_received
+    }
+
+    /// Returns true if all chunks of the proof were received.
+    pub fn is_complete(&self) -> bool {
+        !self.chunks.is_empty() && self.num_received == self.chunks.len()
+    }
+
+    /// Returns indexes of the chunks which were not received yet.
+    pub fn missing_chunks(&self) -> Vec<usize> {
+        self.chunks
+            .iter()
+            .enumerate()
+            .filter_map(|(index, chunk)| chunk.is_none().then_some(index))
+            .collect()
+    }
+
+    /// Returns the digest of the proof being received, or None if no chunks were received yet.
+    pub fn proof_digest(&self) -> Option<&[u8; 32]> {
+        self.chunks.iter().flatten().next().map(|chunk| chunk.proof_digest())
+    }
+
+    // CHUNK PROCESSING
+    // --------------------------------------------------------------------------------------------
+
+    /// Adds the specified chunk to the proof being received, and returns true if all chunks of
+    /// the proof were received.
+    ///
+    /// # Errors
+    /// Returns [VerifierError::InconsistentProofChunk] if the total number of chunks or the proof
+    /// digest of the chunk differs from those of the previously accepted chunks, or if a
+    /// different chunk with the same index was accepted before.
+    pub fn add_chunk(&mut self, chunk: ProofChunk) -> Result<bool, VerifierError> {
+        if self.chunks.is_empty() {
+            self.chunks.resize(chunk.num_chunks(), None);
+        }
+        if chunk.num_chunks() != self.chunks.len()
+            || self.proof_digest().is_some_and(|digest| digest != chunk.proof_digest())
+        {
+            return Err(VerifierError::InconsistentProofChunk(chunk.index()));
+        }
+
+        match &self.chunks[chunk.index()] {
+            Some(accepted) if *accepted != chunk => {
+                return Err(VerifierError::InconsistentProofChunk(chunk.index()));
+            }
+            Some(_) => (),
+            None => {
+                let index = chunk.index();
+                self.chunks[index] = Some(chunk);
+                self.num_received += 1;
+            }
+        }
+        Ok(self.is_complete())
+    }
+
+    // VERIFICATION
+    // --------------------------------------------------------------------------------------------
+
+    /// Reassembles the proof from the received chunks and verifies it against the specified
+    /// public inputs.
+    ///
+    /// The digest of the reassembled proof is computed using `HashFn`; thus, the proof must be
+    /// split into chunks using the same hash function as the one it was generated with.
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// * Some chunks of the proof were not received.
+    /// * The digest of the reassembled proof does not match the proof digest of the chunks.
+    /// * The proof could not be deserialized, or is not valid (see [verify()](crate::verify)).
+    pub fn verify<AIR, HashFn, RandCoin>(
+        self,
+        pub_inputs: AIR::PublicInputs,
+        acceptable_options: &AcceptableOptions,
+    ) -> Result<(), VerifierError>
+    where
+        AIR: Air,
+        HashFn: ElementHasher<BaseField = AIR::BaseField>,
+        RandCoin: RandomCoin<BaseField = AIR::BaseField, Hasher = HashFn>,
+    {
+        if !self.is_complete() {
+            return Err(VerifierError::MissingProofChunks(self.num_received, self.chunks.len()));
+        }
+
+        let chunks = self.chunks.into_iter().flatten().collect::<Vec<_>>();
+        let bytes = chunks.iter().map(|chunk| chunk.data()).collect::<Vec<_>>().concat();
+        if HashFn::hash(&bytes).as_bytes() != *chunks[0].proof_digest() {
+            return Err(VerifierError::ProofDigestMismatch);
+        }
+
+        let proof = StarkProof::from_bytes(&bytes)
+            .map_err(|err| VerifierError::ProofDeserializationError(err.to_string()))?;
+        verify::<AIR, HashFn, RandCoin>(proof, pub_inputs, acceptable_options)
+    }
+}
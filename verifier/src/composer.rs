@@ -3,7 +3,7 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use air::{proof::Table, Air, DeepCompositionCoefficients, EvaluationFrame};
+use air::{proof::Table, Air, DeepCompositionCoefficients};
 use math::{batch_inversion, FieldElement};
 use utils::collections::Vec;
 
@@ -13,11 +13,14 @@ use utils::collections::Vec;
 pub struct DeepComposer<E: FieldElement> {
     cc: DeepCompositionCoefficients<E>,
     x_coordinates: Vec<E>,
-    z: [E; 2],
+    z: Vec<E>,
 }
 
 impl<E: FieldElement> DeepComposer<E> {
     /// Creates a new composer for computing DEEP composition polynomial values.
+    ///
+    /// Trace polynomials are assumed to be opened at points z * g^k for all out-of-domain
+    /// offsets k defined by the `air`, where g is the trace domain generator.
     pub fn new<A: Air<BaseField = E::BaseField>>(
         air: &A,
         query_positions: &[usize],
@@ -32,10 +35,19 @@ impl<E: FieldElement> DeepComposer<E> {
             .map(|&p| E::from(g_lde.exp_vartime((p as u64).into()) * domain_offset))
             .collect();
 
+        // compute out-of-domain points at which trace polynomials are opened
+        let g = air.trace_domain_generator();
+        let z = air
+            .context()
+            .ood_offsets()
+            .iter()
+            .map(|&k| z * E::from(g.exp_vartime((k as u64).into())))
+            .collect();
+
         DeepComposer {
             cc,
             x_coordinates,
-            z: [z, z * E::from(air.trace_domain_generator())],
+            z,
         }
     }
 
@@ -43,91 +55,79 @@ impl<E: FieldElement> DeepComposer<E> {
     /// their random linear combinations as follows:
     ///
     /// - Assume each column value is an evaluation of a trace polynomial T_i(x).
-    /// - For each T_i(x) compute T'_i(x) = (T_i(x) - T_i(z)) / (x - z) and
-    ///   T''_i = (T_i(x) - T_i(z * g)) / (x - z * g), where z is the out-of-domain point and
-    ///   g is the the LDE domain generator.
-    /// - Then, combine all T'_i(x) and T''_i(x) values together by computing
-    ///   T(x) = sum((T'_i(x) + T''_i(x)) * cc_i) for all i, where cc_i is the coefficient for
+    /// - For each T_i(x) and each out-of-domain point z_k = z * g^k compute
+    ///   T^k_i(x) = (T_i(x) - T_i(z_k)) / (x - z_k), where z is the out-of-domain point and g is
+    ///   the trace domain generator. With default offsets, these are
+    ///   T'_i(x) = (T_i(x) - T_i(z)) / (x - z) and T''_i = (T_i(x) - T_i(z * g)) / (x - z * g).
+    /// - Then, combine all T^k_i(x) values together by computing
+    ///   T(x) = sum((T^0_i(x) + T^1_i(x) + ...) * cc_i) for all i, where cc_i is the coefficient
     ///   for the random linear combination drawn from the public coin.
     ///
-    /// Note that values of T_i(z) and T_i(z * g) are received from the prover and passed into
-    /// this function via the `ood_main_frame` and `ood_aux_frame` parameters.
+    /// Note that values of T_i(z_k) are received from the prover and passed into this function
+    /// via the `ood_main_rows` and `ood_aux_rows` parameters (one row per out-of-domain point).
     pub fn compose_trace_columns(
         &self,
         queried_main_trace_states: Table<E::BaseField>,
         queried_aux_trace_states: Option<Table<E>>,
-        ood_main_frame: EvaluationFrame<E>,
-        ood_aux_frame: Option<EvaluationFrame<E>>,
+        ood_main_rows: Vec<Vec<E>>,
+        ood_aux_rows: Option<Vec<Vec<E>>>,
     ) -> Vec<E> {
-        let ood_main_trace_states = [ood_main_frame.current(), ood_main_frame.next()];
+        assert_eq!(ood_main_rows.len(), self.z.len());
 
         // compose columns of of the main trace segment; we do this separately for numerators of
-        // each query; we also track common denominator for each query separately; this way we can
-        // use a batch inversion in the end.
+        // each query and each out-of-domain point; this way we can use a batch inversion of the
+        // denominators in the end.
         let n = queried_main_trace_states.num_rows();
-        let mut result_num = Vec::<E>::with_capacity(n);
-        let mut result_den = Vec::<E>::with_capacity(n);
+        let num_points = self.z.len();
+        let mut result_num = Vec::<E>::with_capacity(n * num_points);
+        let mut result_den = Vec::<E>::with_capacity(n * num_points);
 
         for ((_, row), &x) in (0..n).zip(queried_main_trace_states.rows()).zip(&self.x_coordinates)
         {
-            let mut t1_num = E::ZERO;
-            let mut t2_num = E::ZERO;
-
-            for (i, &value) in row.iter().enumerate() {
-                let value = E::from(value);
-                // compute the numerator of T'_i(x) as (T_i(x) - T_i(z)), multiply it by a
-                // composition coefficient, and add the result to the numerator aggregator
-                t1_num += (value - ood_main_trace_states[0][i]) * self.cc.trace[i];
-
-                // compute the numerator of T''_i(x) as (T_i(x) - T_i(z * g)), multiply it by a
+            for (ood_row, &z) in ood_main_rows.iter().zip(&self.z) {
+                // compute the numerator of T^k_i(x) as (T_i(x) - T_i(z_k)), multiply it by a
                 // composition coefficient, and add the result to the numerator aggregator
-                t2_num += (value - ood_main_trace_states[1][i]) * self.cc.trace[i];
+                let mut t_num = E::ZERO;
+                for (i, &value) in row.iter().enumerate() {
+                    t_num += (E::from(value) - ood_row[i]) * self.cc.trace[i];
+                }
+                result_num.push(t_num);
+                result_den.push(x - z);
             }
-            // compute the common denominator as (x - z) * (x - z * g)
-            let t1_den = x - self.z[0];
-            let t2_den = x - self.z[1];
-            result_den.push(t1_den * t2_den);
-
-            // add the numerators of T'_i(x) and T''_i(x) together; we can do this because later on
-            // we'll use the common denominator computed above.
-            result_num.push(t1_num * t2_den + t2_num * t1_den);
         }
 
-        // if the trace has auxiliary segments, compose columns from these segments as well; we
-        // also do this separately for numerators and denominators.
+        // if the trace has auxiliary segments, compose columns from these segments as well; the
+        // numerators are added to the numerators computed for the main trace since the
+        // denominators are the same.
         if let Some(queried_aux_trace_states) = queried_aux_trace_states {
-            let ood_aux_frame = ood_aux_frame.expect("missing auxiliary OOD frame");
-            let ood_aux_trace_states = [ood_aux_frame.current(), ood_aux_frame.next()];
+            let ood_aux_rows = ood_aux_rows.expect("missing auxiliary OOD frame");
 
             // we define this offset here because composition of the main trace columns has
             // consumed some number of composition coefficients already.
             let cc_offset = queried_main_trace_states.num_columns();
 
-            for ((j, row), &x) in
-                (0..n).zip(queried_aux_trace_states.rows()).zip(&self.x_coordinates)
-            {
-                let mut t1_num = E::ZERO;
-                let mut t2_num = E::ZERO;
-                for (i, &value) in row.iter().enumerate() {
-                    // compute the numerator of T'_i(x) as (T_i(x) - T_i(z)), multiply it by a
-                    // composition coefficient, and add the result to the numerator aggregator
-                    t1_num += (value - ood_aux_trace_states[0][i]) * self.cc.trace[cc_offset + i];
-
-                    // compute the numerator of T''_i(x) as (T_i(x) - T_i(z * g)), multiply it by a
-                    // composition coefficient, and add the result to the numerator aggregator
-                    t2_num += (value - ood_aux_trace_states[1][i]) * self.cc.trace[cc_offset + i];
+            for (j, row) in queried_aux_trace_states.rows().enumerate() {
+                for (k, ood_row) in ood_aux_rows.iter().enumerate() {
+                    let mut t_num = E::ZERO;
+                    for (i, &value) in row.iter().enumerate() {
+                        t_num += (value - ood_row[i]) * self.cc.trace[cc_offset + i];
+                    }
+                    result_num[j * num_points + k] += t_num;
                 }
-
-                // compute the common denominators (x - z) and (x - z * g), and use the to aggregate
-                // numerators into the common numerator computed for the main trace of this query
-                let t1_den = x - self.z[0];
-                let t2_den = x - self.z[1];
-                result_num[j] += t1_num * t2_den + t2_num * t1_den;
             }
         }
 
+        // divide the numerators by their denominators, and add up the results for all
+        // out-of-domain points of each query
         result_den = batch_inversion(&result_den);
-        result_num.iter().zip(result_den).map(|(n, d)| *n * d).collect()
+        result_num
+            .iter()
+            .zip(result_den)
+            .map(|(n, d)| *n * d)
+            .collect::<Vec<_>>()
+            .chunks(num_points)
+            .map(|values| values.iter().fold(E::ZERO, |acc, &v| acc + v))
+            .collect()
     }
 
     /// For each queried set of composition polynomial column evaluations, combine evaluations
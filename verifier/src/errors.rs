@@ -5,6 +5,7 @@
 
 //! Contains common error types for prover and verifier.
 
+use air::{PublicInputsError, QuerySampling};
 use core::fmt;
 use utils::string::String;
 
@@ -16,6 +17,9 @@ pub enum VerifierError {
     /// This error occurs when base field read by a verifier from a proof does not match the
     /// base field of AIR with which the verifier was instantiated.
     InconsistentBaseField,
+    /// This error occurs when the hash function recorded in the proof context does not match the
+    /// hash function with which the verifier was instantiated.
+    InconsistentHashFunction,
     /// This error occurs when the base field in which the proof was generated does not support
     /// field extension of degree specified by the proof.
     UnsupportedFieldExtension(usize),
@@ -27,16 +31,25 @@ pub enum VerifierError {
     /// This error occurs when constraints evaluated over out-of-domain trace rows do not match
     /// evaluations of the constraint composition polynomial at the out-of-domain point.
     InconsistentOodConstraintEvaluations,
-    /// This error occurs when Merkle authentication paths of trace queries do not resolve to the
-    /// execution trace commitment included in the proof.
-    TraceQueryDoesNotMatchCommitment,
-    /// This error occurs when Merkle authentication paths of constraint evaluation queries do not
-    /// resolve to the constraint evaluation commitment included in the proof.
-    ConstraintQueryDoesNotMatchCommitment,
+    /// This error occurs when the Merkle authentication path of a trace query does not resolve to
+    /// the commitment to the specified trace segment included in the proof. The second value is
+    /// the smallest position in the LDE domain of a query with such a path.
+    TraceQueryDoesNotMatchCommitment(usize, usize),
+    /// This error occurs when the Merkle authentication path of a constraint evaluation query does
+    /// not resolve to the constraint evaluation commitment included in the proof. The value is
+    /// the smallest position in the LDE domain of a query with such a path.
+    ConstraintQueryDoesNotMatchCommitment(usize),
+    /// This error occurs when the Merkle authentication path of a query of preprocessed columns
+    /// does not resolve to the commitment to the preprocessed columns known to the verifier. The
+    /// value is the smallest position in the LDE domain of a query with such a path.
+    PreprocessedQueryDoesNotMatchCommitment(usize),
     /// This error occurs when the proof-of-work nonce hashed with the current state of the public
     /// coin resolves to a value which does not meet the proof-of-work threshold specified by the
     // proof options.
     QuerySeedProofOfWorkVerificationFailed,
+    /// This error occurs when the number of unique query positions drawn by the verifier does not
+    /// match the number of unique queries for which the proof contains openings.
+    InconsistentNumQueries(usize, usize),
     /// This error occurs when the DEEP composition polynomial evaluations derived from trace and
     /// constraint evaluation queries do not represent a polynomial of the degree expected by the
     /// verifier.
@@ -52,6 +65,50 @@ pub enum VerifierError {
     /// This error occurs when the parameters, that were used to generate the proof, do not match
     /// any of the set of parameters expected by the verifier.
     UnacceptableProofOptions,
+    /// This error occurs when the proof was generated using a hash function which is not allowed
+    /// by the acceptance policy of the verifier.
+    UnacceptableHashFunction,
+    /// This error occurs when the hash function used to generate the proof is not recorded in the
+    /// proof context, and thus, cannot be selected at runtime.
+    UnknownHashFunction,
+    /// This error occurs when the proof was generated in a combination of base field and field
+    /// extension which is not allowed by the acceptance policy of the verifier. The values are
+    /// the number of bits in the base field modulus and the degree of the field extension.
+    UnacceptableField(u32, u32),
+    /// This error occurs when the execution trace of the computation described by the proof is
+    /// longer than the maximum trace length allowed by the acceptance policy of the verifier.
+    TraceLengthTooLarge(usize, usize),
+    /// This error occurs when execution trace metadata of the proof contains more bytes than
+    /// allowed by the acceptance policy of the verifier.
+    TraceMetaTooLong(usize, usize),
+    /// This error occurs when execution trace metadata of the proof differs from the metadata
+    /// required by the acceptance policy of the verifier.
+    UnexpectedTraceMeta,
+    /// This error occurs when FRI layers of the proof are folded according to a schedule which
+    /// is not allowed by the acceptance policy of the verifier.
+    UnacceptableFriFoldingSchedule,
+    /// This error occurs when query positions of the proof are sampled using a strategy which is
+    /// not allowed by the acceptance policy of the verifier.
+    UnacceptableQuerySampling(QuerySampling),
+    /// This error occurs when interactions of the verifier with the public coin diverge from the
+    /// expected transcript. The value is the index of the first diverging transcript event.
+    TranscriptMismatch(usize),
+    /// This error occurs when public inputs provided to the verifier fail validation defined by
+    /// the AIR (e.g., when revealed leaves of Merkle-ized public inputs do not match the root).
+    InvalidPublicInputs(PublicInputsError),
+    /// This error occurs when the computation has preprocessed columns, but the verifier was not
+    /// provided with the commitment to these columns.
+    PreprocessedCommitmentMissing,
+    /// This error occurs when a proof chunk does not belong to the same proof as the chunks
+    /// accepted before it, or conflicts with a previously accepted chunk with the same index.
+    /// The value is the index of the chunk.
+    InconsistentProofChunk(usize),
+    /// This error occurs when a chunked proof is verified before all of its chunks are received.
+    /// The values are the number of received chunks and the total number of chunks.
+    MissingProofChunks(usize, usize),
+    /// This error occurs when the digest of a proof reassembled from chunks does not match the
+    /// proof digest in the headers of the chunks.
+    ProofDigestMismatch,
 }
 
 impl fmt::Display for VerifierError {
@@ -61,6 +118,9 @@ impl fmt::Display for VerifierError {
             Self::InconsistentBaseField =>  {
                 write!(f, "base field of the proof does not match base field of the specified AIR")
             }
+            Self::InconsistentHashFunction => {
+                write!(f, "hash function recorded in the proof does not match hash function of the verifier")
+            }
             Self::UnsupportedFieldExtension(degree) => {
                 write!(f, "field extension of degree {degree} is not supported for the proof base field")
             }
@@ -73,15 +133,21 @@ impl fmt::Display for VerifierError {
             Self::InconsistentOodConstraintEvaluations => {
                 write!(f, "constraint evaluations over the out-of-domain frame are inconsistent")
             }
-            Self::TraceQueryDoesNotMatchCommitment => {
-                write!(f, "trace query did not match the commitment")
+            Self::TraceQueryDoesNotMatchCommitment(segment, position) => {
+                write!(f, "trace query at position {position} did not match the commitment to trace segment {segment}")
+            }
+            Self::ConstraintQueryDoesNotMatchCommitment(position) => {
+                write!(f, "constraint query at position {position} did not match the commitment")
             }
-            Self::ConstraintQueryDoesNotMatchCommitment => {
-                write!(f, "constraint query did not match the commitment")
+            Self::PreprocessedQueryDoesNotMatchCommitment(position) => {
+                write!(f, "preprocessed column query at position {position} did not match the commitment")
             }
             Self::QuerySeedProofOfWorkVerificationFailed => {
                 write!(f, "query seed proof-of-work verification failed")
             }
+            Self::InconsistentNumQueries(expected, actual) => {
+                write!(f, "expected openings for {expected} unique queries, but the proof contains {actual}")
+            }
             Self::FriVerificationFailed(err) => {
                 write!(f, "verification of low-degree proof failed: {err}")
             }
@@ -92,6 +158,48 @@ impl fmt::Display for VerifierError {
                 write!(f, "insufficient proof security level: expected at least {minimal_security} bits of proven security, but was {proof_security} bits")
             }
             Self::UnacceptableProofOptions => {write!(f, "invalid proof options: security parameters do not match the acceptable parameter set")}
+            Self::UnacceptableHashFunction => {
+                write!(f, "proof was generated using a hash function which is not acceptable")
+            }
+            Self::UnknownHashFunction => {
+                write!(f, "proof context does not specify a hash function which can be selected at runtime")
+            }
+            Self::UnacceptableField(num_modulus_bits, extension_degree) => {
+                write!(f, "proof was generated in a {num_modulus_bits}-bit field with extension of degree {extension_degree}, which is not acceptable")
+            }
+            Self::TraceLengthTooLarge(max_trace_length, trace_length) => {
+                write!(f, "trace length of {trace_length} exceeds the maximum acceptable trace length of {max_trace_length}")
+            }
+            Self::TraceMetaTooLong(max_meta_length, meta_length) => {
+                write!(f, "trace metadata of {meta_length} bytes exceeds the maximum acceptable length of {max_meta_length} bytes")
+            }
+            Self::UnexpectedTraceMeta => {
+                write!(f, "trace metadata of the proof does not match the expected metadata")
+            }
+            Self::UnacceptableFriFoldingSchedule => {
+                write!(f, "FRI layers of the proof are folded according to a schedule which is not acceptable")
+            }
+            Self::UnacceptableQuerySampling(strategy) => {
+                write!(f, "query positions of the proof are sampled using an unacceptable strategy {strategy:?}")
+            }
+            Self::TranscriptMismatch(index) => {
+                write!(f, "public coin transcript diverged from the expected transcript at event {index}")
+            }
+            Self::InvalidPublicInputs(err) => {
+                write!(f, "public inputs are invalid: {err}")
+            }
+            Self::PreprocessedCommitmentMissing => {
+                write!(f, "computation has preprocessed columns, but their commitment was not provided to the verifier")
+            }
+            Self::InconsistentProofChunk(index) => {
+                write!(f, "proof chunk {index} is inconsistent with previously received chunks")
+            }
+            Self::MissingProofChunks(received, total) => {
+                write!(f, "only {received} out of {total} proof chunks were received")
+            }
+            Self::ProofDigestMismatch => {
+                write!(f, "digest of the reassembled proof does not match the digest of its chunks")
+            }
         }
     }
 }
@@ -0,0 +1,36 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use air::{AuxTraceRandElements, ConstraintCompositionCoefficients, DeepCompositionCoefficients};
+use math::FieldElement;
+use utils::collections::Vec;
+
+// PROOF INSPECTION
+// ================================================================================================
+
+/// Values derived from the public coin while verifying a proof.
+///
+/// These values are not included in a proof; instead, they are derived by the verifier from
+/// the commitments and out-of-domain evaluations sent by the prover. They are returned by
+/// [inspect()](crate::inspect) so that tools which audit or visualize proofs can explain what a
+/// proof commits to without re-implementing the verification protocol. Values contained in the
+/// proof itself (e.g., commitments and out-of-domain evaluations) can be read directly from the
+/// proof via [StarkProof::parse_commitments()](air::proof::StarkProof::parse_commitments),
+/// [StarkProof::ood_trace_rows()](air::proof::StarkProof::ood_trace_rows), and
+/// [StarkProof::ood_constraint_evaluations()](air::proof::StarkProof::ood_constraint_evaluations).
+#[derive(Debug, Clone)]
+pub struct ProofInspection<E: FieldElement> {
+    /// Random elements used to build auxiliary trace segments (if any).
+    pub aux_rand_elements: AuxTraceRandElements<E>,
+    /// Coefficients used to compute the random linear combination of constraints.
+    pub constraint_coefficients: ConstraintCompositionCoefficients<E>,
+    /// Out-of-domain point at which trace and constraint composition polynomials are evaluated.
+    pub z: E,
+    /// Coefficients used to build the DEEP composition polynomial.
+    pub deep_coefficients: DeepCompositionCoefficients<E>,
+    /// Positions in the LDE domain at which trace and constraint commitments are opened; the
+    /// positions are deduplicated and sorted in ascending order.
+    pub query_positions: Vec<usize>,
+}
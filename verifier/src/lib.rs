@@ -33,16 +33,24 @@
 extern crate alloc;
 
 pub use air::{
-    proof::StarkProof, Air, AirContext, Assertion, AuxTraceRandElements, BoundaryConstraint,
-    BoundaryConstraintGroup, ConstraintCompositionCoefficients, ConstraintDivisor,
-    DeepCompositionCoefficients, EvaluationFrame, FieldExtension, ProofOptions, TraceInfo,
+    proof::{AggregatedProof, StarkProof},
+    AggregateAir, AggregateInputs, Air, AirContext, Assertion, AuxTraceRandElements,
+    BoundaryConstraint, BoundaryConstraintGroup, ConstraintCompositionCoefficients,
+    ConstraintDivisor, DeepCompositionCoefficients, EvaluationFrame, FieldExtension, LeafEncoding,
+    LowDegreeTest, MerkleInputs, ProofOptions, PublicInputsError, QuerySampling, TraceInfo,
     TransitionConstraintDegree,
 };
 
+#[cfg(feature = "compression")]
+pub use air::proof::CompressionCodec;
+
+#[cfg(feature = "protobuf")]
+pub use air::protobuf;
+
 pub use math;
 use math::{
     fields::{CubeExtension, QuadExtension},
-    FieldElement, ToElements,
+    FieldElement, StarkField, ToElements,
 };
 
 pub use utils::{
@@ -50,28 +58,44 @@ pub use utils::{
     SliceReader,
 };
 
+use utils::string::{String, ToString};
+#[cfg(feature = "std")]
+pub use utils::{ReadAdapter, WriteAdapter};
+
 pub use crypto;
-use crypto::{ElementHasher, Hasher, RandomCoin};
+use crypto::{
+    hashers::{Blake3_192, Blake3_256, Keccak256, Sha3_256},
+    DefaultRandomCoin, ElementHasher, HashFunction, Hasher, RandomCoin, Transcript, TranscriptCoin,
+};
 
-use fri::FriVerifier;
+use air::proof::Context;
 
-mod channel;
-use channel::VerifierChannel;
+#[cfg(feature = "concurrent")]
+use utils::iterators::*;
 
-mod evaluator;
-use evaluator::evaluate_constraints;
+mod channel;
+use channel::{check_base_field, QuerySource, VerifierChannel};
 
 mod composer;
 use composer::DeepComposer;
 
+mod low_degree;
+use low_degree::LowDegreeVerifier;
+
 mod errors;
 pub use errors::VerifierError;
 
+mod inspection;
+pub use inspection::ProofInspection;
+
+mod chunks;
+pub use chunks::ChunkedProofVerifier;
+
 // VERIFIER
 // ================================================================================================
 /// Verifies that the specified computation was executed correctly against the specified inputs.
 ///
-/// Specifically, for a computation specified by `AIR` and `HashFn` type parameter, verifies that 
+/// Specifically, for a computation specified by `AIR` and `HashFn` type parameter, verifies that
 /// the provided `proof` attests to the correct execution of the computation against public inputs
 /// specified by `pub_inputs`. If the verification is successful, `Ok(())` is returned.
 ///
@@ -81,14 +105,214 @@ pub use errors::VerifierError;
 /// - The specified proof was generated for a different computation.
 /// - The specified proof was generated for this computation but for different public inputs.
 /// - The specified proof was generated with parameters not providing an acceptable security level.
-#[rustfmt::skip]
+/// - The specified proof was generated using a hash function other than `HashFn`.
+///
+/// Malformed proofs are rejected with an error rather than a panic, provided that `AIR::new()`
+/// does not panic for the trace info recorded in the proof.
 pub fn verify<AIR, HashFn, RandCoin>(
     proof: StarkProof,
     pub_inputs: AIR::PublicInputs,
     acceptable_options: &AcceptableOptions,
-) -> Result<(), VerifierError> 
-where 
-    AIR: Air, 
+) -> Result<(), VerifierError>
+where
+    AIR: Air,
+    HashFn: ElementHasher<BaseField = AIR::BaseField>,
+    RandCoin: RandomCoin<BaseField = AIR::BaseField, Hasher = HashFn>,
+{
+    verify_with_coin::<AIR, HashFn, RandCoin>(
+        proof,
+        pub_inputs,
+        acceptable_options,
+        None,
+        &mut None,
+    )
+    .map(|_| ())
+}
+
+/// Verifies that the specified computation was executed correctly against the specified inputs,
+/// and returns the positions at which the proof opens trace and constraint commitments.
+///
+/// This is equivalent to calling [verify()], but for a valid proof, the query positions derived
+/// by the verifier from the public coin are returned. The positions are deduplicated and sorted
+/// in ascending order, and thus, correspond one-to-one to the openings included in the proof
+/// (see [StarkProof::num_unique_queries](air::proof::StarkProof::num_unique_queries)). This is
+/// intended for tools which audit proofs.
+///
+/// # Errors
+/// Returns an error if combination of the provided proof and public inputs does not attest to
+/// a correct execution of the computation (see [verify()] for details).
+pub fn verify_with_query_positions<AIR, HashFn, RandCoin>(
+    proof: StarkProof,
+    pub_inputs: AIR::PublicInputs,
+    acceptable_options: &AcceptableOptions,
+) -> Result<Vec<usize>, VerifierError>
+where
+    AIR: Air,
+    HashFn: ElementHasher<BaseField = AIR::BaseField>,
+    RandCoin: RandomCoin<BaseField = AIR::BaseField, Hasher = HashFn>,
+{
+    verify_with_coin::<AIR, HashFn, RandCoin>(
+        proof,
+        pub_inputs,
+        acceptable_options,
+        None,
+        &mut None,
+    )
+}
+
+/// Verifies that the specified computation was executed correctly against the specified inputs,
+/// recording all interactions with the public coin into a [Transcript].
+///
+/// This is equivalent to calling [verify()] with `RandCoin` wrapped into a [TranscriptCoin]. The
+/// returned transcript covers the protocol up to the point at which verification completed or
+/// failed; for a valid proof, it is identical to the transcript recorded by the prover when the
+/// proof is generated with the same [TranscriptCoin].
+#[rustfmt::skip]
+pub fn verify_with_transcript<AIR, HashFn, RandCoin>(
+    proof: StarkProof,
+    pub_inputs: AIR::PublicInputs,
+    acceptable_options: &AcceptableOptions,
+) -> (Result<(), VerifierError>, Transcript)
+where
+    AIR: Air,
+    HashFn: ElementHasher<BaseField = AIR::BaseField>,
+    RandCoin: RandomCoin<BaseField = AIR::BaseField, Hasher = HashFn>,
+{
+    let mut public_coin = None;
+    let result = verify_with_coin::<AIR, HashFn, TranscriptCoin<RandCoin>>(proof, pub_inputs, acceptable_options, None, &mut public_coin).map(|_| ());
+    let transcript = public_coin.map(TranscriptCoin::into_transcript).unwrap_or_default();
+    (result, transcript)
+}
+
+/// Verifies that the specified computation was executed correctly against the specified inputs,
+/// and returns the values derived from the public coin during verification.
+///
+/// This is equivalent to calling [verify()], but in addition, the returned [ProofInspection]
+/// contains the values which are not included in the proof but determine what the proof
+/// commits to: random elements for auxiliary trace segments, constraint and DEEP composition
+/// coefficients, the out-of-domain point, and the query positions. This is intended for tools
+/// which audit or visualize proofs.
+///
+/// Unlike [verify()], the field in which the values are returned is selected statically: `E`
+/// must be the field (i.e., the base field of the `AIR` or one of its extensions) in which the
+/// proof was generated.
+///
+/// # Errors
+/// Returns [VerifierError::UnsupportedFieldExtension] if `E` is not the field in which the proof
+/// was generated. Otherwise, returns an error if combination of the provided proof and public
+/// inputs does not attest to a correct execution of the computation (see [verify()] for
+/// details).
+pub fn inspect<AIR, E, HashFn, RandCoin>(
+    proof: StarkProof,
+    pub_inputs: AIR::PublicInputs,
+    acceptable_options: &AcceptableOptions,
+) -> Result<ProofInspection<E>, VerifierError>
+where
+    AIR: Air,
+    E: FieldElement<BaseField = AIR::BaseField>,
+    HashFn: ElementHasher<BaseField = AIR::BaseField>,
+    RandCoin: RandomCoin<BaseField = AIR::BaseField, Hasher = HashFn>,
+{
+    acceptable_options.validate::<HashFn>(&proof)?;
+    let extension_degree = proof.options().field_extension().degree() as usize;
+    if E::EXTENSION_DEGREE != extension_degree {
+        return Err(VerifierError::UnsupportedFieldExtension(extension_degree));
+    }
+
+    // build the public coin and the AIR in the same way as in [verify()]
+    let pub_inputs = AIR::derive_public_inputs(pub_inputs);
+    AIR::validate_public_inputs(&pub_inputs).map_err(VerifierError::InvalidPublicInputs)?;
+    let public_coin_seed = build_public_coin_seed::<AIR>(&proof.context, &pub_inputs)?;
+    let air = AIR::new(proof.get_trace_info(), pub_inputs, proof.options().clone());
+
+    let mut public_coin = RandCoin::new(&public_coin_seed);
+    let channel = VerifierChannel::new(&air, proof, None)?;
+    perform_inspected_verification::<AIR, E, HashFn, RandCoin, _>(&air, channel, &mut public_coin)
+}
+
+/// Verifies that the specified computation was executed correctly against the specified inputs,
+/// and that the verifier interacts with the public coin exactly as recorded in the `expected`
+/// transcript.
+///
+/// The `expected` transcript would usually be recorded by the prover (see
+/// `ProverHooks::on_transcript()` in the prover crate) or by an external implementation of the
+/// protocol. Replaying it makes it possible to pinpoint the first
+/// reseed, draw, or out-of-domain value at which the verifier diverges from the prover.
+///
+/// # Errors
+/// Returns [VerifierError::TranscriptMismatch] with the index of the first diverging event if
+/// the transcript recorded by the verifier diverges from the `expected` transcript before
+/// verification completes. Otherwise, returns the result of verification (see [verify()] for
+/// details).
+pub fn verify_against_transcript<AIR, HashFn, RandCoin>(
+    proof: StarkProof,
+    pub_inputs: AIR::PublicInputs,
+    acceptable_options: &AcceptableOptions,
+    expected: &Transcript,
+) -> Result<(), VerifierError>
+where
+    AIR: Air,
+    HashFn: ElementHasher<BaseField = AIR::BaseField>,
+    RandCoin: RandomCoin<BaseField = AIR::BaseField, Hasher = HashFn>,
+{
+    let (result, transcript) =
+        verify_with_transcript::<AIR, HashFn, RandCoin>(proof, pub_inputs, acceptable_options);
+
+    // if verification failed, the verifier transcript ends early; in such cases, the transcripts
+    // diverge only if one of the recorded events differs
+    match expected.first_divergence(&transcript) {
+        Some(index) if index < transcript.len() || result.is_ok() => {
+            Err(VerifierError::TranscriptMismatch(index))
+        }
+        _ => result,
+    }
+}
+
+/// Verifies that the specified computation with preprocessed columns was executed correctly
+/// against the specified inputs.
+///
+/// This is equivalent to calling [verify()], but in addition, values of the preprocessed columns
+/// opened by the proof are checked against `preprocessed_commitment`. The commitment must be
+/// obtained by the verifier from a trusted source (e.g., computed once from the preprocessed
+/// columns of the computation) rather than from the prover.
+///
+/// # Errors
+/// Returns an error if combination of the provided proof, public inputs, and commitment to the
+/// preprocessed columns does not attest to a correct execution of the computation (see
+/// [verify()] for details).
+pub fn verify_with_preprocessed_commitment<AIR, HashFn, RandCoin>(
+    proof: StarkProof,
+    pub_inputs: AIR::PublicInputs,
+    acceptable_options: &AcceptableOptions,
+    preprocessed_commitment: &HashFn::Digest,
+) -> Result<(), VerifierError>
+where
+    AIR: Air,
+    HashFn: ElementHasher<BaseField = AIR::BaseField>,
+    RandCoin: RandomCoin<BaseField = AIR::BaseField, Hasher = HashFn>,
+{
+    verify_with_coin::<AIR, HashFn, RandCoin>(
+        proof,
+        pub_inputs,
+        acceptable_options,
+        Some(*preprocessed_commitment),
+        &mut None,
+    )
+    .map(|_| ())
+}
+
+/// Verifies the proof using the random coin stored in `public_coin`; the coin is instantiated
+/// once the seed for it is known. For a valid proof, returns the query positions.
+#[rustfmt::skip]
+fn verify_with_coin<AIR, HashFn, RandCoin>(
+    proof: StarkProof,
+    pub_inputs: AIR::PublicInputs,
+    acceptable_options: &AcceptableOptions,
+    preprocessed_commitment: Option<HashFn::Digest>,
+    public_coin: &mut Option<RandCoin>,
+) -> Result<Vec<usize>, VerifierError>
+where
+    AIR: Air,
     HashFn: ElementHasher<BaseField = AIR::BaseField>,
     RandCoin: RandomCoin<BaseField = AIR::BaseField, Hasher = HashFn>,
 {
@@ -96,38 +320,297 @@ where
     // of the verifier
     acceptable_options.validate::<HashFn>(&proof)?;
 
+    // derive public inputs which can be computed from other public inputs in the same way as
+    // the prover does, and make sure they pass the checks defined by the AIR
+    let pub_inputs = AIR::derive_public_inputs(pub_inputs);
+    AIR::validate_public_inputs(&pub_inputs).map_err(VerifierError::InvalidPublicInputs)?;
+
     // build a seed for the public coin; the initial seed is a hash of the proof context and the
     // public inputs, but as the protocol progresses, the coin will be reseeded with the info
     // received from the prover
-    let mut public_coin_seed = proof.context.to_elements();
-    public_coin_seed.append(&mut pub_inputs.to_elements());
+    let public_coin_seed = build_public_coin_seed::<AIR>(&proof.context, &pub_inputs)?;
     
     // create AIR instance for the computation specified in the proof
     let air = AIR::new(proof.get_trace_info(), pub_inputs, proof.options().clone());
 
+    // verify the proof in the field specified by the proof options
+    let proof = InMemoryProof::<HashFn> { proof, preprocessed_commitment };
+    verify_in_proof_field::<AIR, HashFn, RandCoin, _>(&air, proof, &public_coin_seed, public_coin)
+}
+
+/// Verifies that the specified computation was executed correctly against the specified inputs,
+/// using a hash function selected at runtime.
+///
+/// This is equivalent to calling [verify()] with the hash function identified by `hash_fn` and
+/// [DefaultRandomCoin] instantiated with this hash function.
+///
+/// # Errors
+/// Returns an error if combination of the provided proof and public inputs does not attest to
+/// a correct execution of the computation (see [verify()] for details).
+pub fn verify_dyn<AIR: Air>(
+    proof: StarkProof,
+    pub_inputs: AIR::PublicInputs,
+    acceptable_options: &AcceptableOptions,
+    hash_fn: HashFunction,
+) -> Result<(), VerifierError> {
+    match hash_fn {
+        HashFunction::Blake3_192 => verify::<
+            AIR,
+            Blake3_192<AIR::BaseField>,
+            DefaultRandomCoin<Blake3_192<AIR::BaseField>>,
+        >(proof, pub_inputs, acceptable_options),
+        HashFunction::Blake3_256 => verify::<
+            AIR,
+            Blake3_256<AIR::BaseField>,
+            DefaultRandomCoin<Blake3_256<AIR::BaseField>>,
+        >(proof, pub_inputs, acceptable_options),
+        HashFunction::Sha3_256 => verify::<
+            AIR,
+            Sha3_256<AIR::BaseField>,
+            DefaultRandomCoin<Sha3_256<AIR::BaseField>>,
+        >(proof, pub_inputs, acceptable_options),
+        HashFunction::Keccak256 => verify::<
+            AIR,
+            Keccak256<AIR::BaseField>,
+            DefaultRandomCoin<Keccak256<AIR::BaseField>>,
+        >(proof, pub_inputs, acceptable_options),
+    }
+}
+
+/// Verifies that the specified computation was executed correctly against the specified inputs,
+/// using the hash function recorded in the proof context.
+///
+/// This is equivalent to calling [verify_dyn()] with the hash function read from the context of
+/// `proof`. The field extension is already selected at runtime by [verify()]; the base field of
+/// the proof is checked against the base field of the `AIR` before the proof is verified.
+///
+/// # Errors
+/// Returns [VerifierError::InconsistentBaseField] if the proof was generated in a base field
+/// different from the base field of the `AIR`, and [VerifierError::UnknownHashFunction] if the
+/// proof context does not record a hash function which can be selected at runtime. Otherwise,
+/// returns an error if combination of the provided proof and public inputs does not attest to
+/// a correct execution of the computation (see [verify()] for details).
+pub fn verify_auto<AIR: Air>(
+    proof: StarkProof,
+    pub_inputs: AIR::PublicInputs,
+    acceptable_options: &AcceptableOptions,
+) -> Result<(), VerifierError> {
+    if proof.context.field_modulus_bytes() != AIR::BaseField::get_modulus_le_bytes() {
+        return Err(VerifierError::InconsistentBaseField);
+    }
+    let hash_fn = proof.context.hash_function().ok_or(VerifierError::UnknownHashFunction)?;
+    verify_dyn::<AIR>(proof, pub_inputs, acceptable_options, hash_fn)
+}
+
+/// Verifies that the specified computation was executed correctly against the specified inputs,
+/// reading the proof incrementally from the specified `source`.
+///
+/// This is equivalent to deserializing a [StarkProof] from `source` and calling [verify()], but
+/// the proof is never fully loaded into memory. The proof must be serialized via
+/// [StarkProof::write_streaming_into()] (or [StarkProof::to_streaming_bytes()]), which orders
+/// the proof so that query positions can be derived before any queries are read: the proof
+/// context is read and checked against `acceptable_options` before any other section of the
+/// proof is read; then, trace queries, constraint queries, and FRI layers are read one section
+/// at a time, each section only when the verifier needs it, and each is discarded as soon as it
+/// is checked against its commitment. Thus, apart from the small sections preceding the queries,
+/// at most one section of queries is kept in memory at a time. This allows memory-constrained
+/// verifiers to verify large proofs received over the network; in `std` environments, any
+/// [std::io::Read] source can be used via [ReadAdapter].
+///
+/// Once the proof is read, `source` is not checked for any remaining bytes. Compressed proofs
+/// cannot be read incrementally; such proofs must be deserialized via [StarkProof::from_bytes()]
+/// and verified via [verify()].
+/// Similarly, proofs for computations with preprocessed columns must be verified via
+/// [verify_with_preprocessed_commitment()].
+///
+/// # Errors
+/// Returns [VerifierError::ProofDeserializationError] if a proof could not be read from
+/// `source`, and any other [VerifierError] if the proof is not valid (see [verify()] for
+/// details).
+#[rustfmt::skip]
+pub fn verify_from_reader<AIR, HashFn, RandCoin, R>(
+    source: &mut R,
+    pub_inputs: AIR::PublicInputs,
+    acceptable_options: &AcceptableOptions,
+) -> Result<(), VerifierError>
+where
+    AIR: Air,
+    HashFn: ElementHasher<BaseField = AIR::BaseField>,
+    RandCoin: RandomCoin<BaseField = AIR::BaseField, Hasher = HashFn>,
+    R: ByteReader,
+{
+    // read the proof format version and the proof context
+    let version = StarkProof::read_format_version(source)
+        .map_err(|err| VerifierError::ProofDeserializationError(err.to_string()))?;
+    let context = Context::read_from_version(source, version)
+        .map_err(|err| VerifierError::ProofDeserializationError(err.to_string()))?;
+
+    // derive public inputs and build a seed for the public coin in the same way as in [verify()]
+    let pub_inputs = AIR::derive_public_inputs(pub_inputs);
+    AIR::validate_public_inputs(&pub_inputs).map_err(VerifierError::InvalidPublicInputs)?;
+    let public_coin_seed = build_public_coin_seed::<AIR>(&context, &pub_inputs)?;
+
+    // create AIR instance for the computation specified in the proof context, and check that the
+    // proof was generated with an acceptable set of parameters before reading the rest of the
+    // proof; the out-of-domain frame has not been read yet, and thus, security level is computed
+    // for the number of out-of-domain points specified by the AIR (the proof is rejected later
+    // if its out-of-domain frame contains a different number of points)
+    let air = AIR::new(context.get_trace_info(), pub_inputs, context.options().clone());
+    acceptable_options.validate_context::<HashFn>(&context, air.context().num_ood_points())?;
+
+    // read the rest of the proof into a channel over the extension field specified by the proof
+    let proof = StreamedProof { source, context, version };
+    verify_in_proof_field::<AIR, HashFn, RandCoin, _>(&air, proof, &public_coin_seed, &mut None)
+        .map(|_| ())
+}
+
+/// Verifies all proofs contained in the specified [AggregatedProof].
+///
+/// Since proofs in the container may attest to different computations, each proof is verified by
+/// calling `verify_proof` with the proof's label and the proof itself; the closure is expected to
+/// select the AIR and public inputs based on the label and to invoke [verify()] (or one of its
+/// variants). When `concurrent` feature is enabled, the proofs are verified in multiple threads.
+/// Verification stops as soon as an invalid proof is found. An empty container is considered
+/// valid.
+///
+/// # Errors
+/// Returns the index of the first proof in the container which could not be verified together
+/// with the error returned by `verify_proof` for this proof.
+pub fn verify_all<F>(proof: AggregatedProof, verify_proof: F) -> Result<(), (usize, VerifierError)>
+where
+    F: Fn(&str, StarkProof) -> Result<(), VerifierError> + Sync,
+{
+    let verify_proof = |(index, (label, proof)): (usize, (String, StarkProof))| {
+        verify_proof(&label, proof).err().map(|err| (index, err))
+    };
+
+    #[cfg(not(feature = "concurrent"))]
+    let result = proof.into_proofs().into_iter().enumerate().find_map(verify_proof);
+
+    #[cfg(feature = "concurrent")]
+    let result = proof.into_proofs().into_par_iter().enumerate().find_map_first(verify_proof);
+
+    match result {
+        Some(err) => Err(err),
+        None => Ok(()),
+    }
+}
+
+/// Builds a seed for the public coin from the specified proof context and public inputs.
+///
+/// The field modulus recorded in the context is checked before the context is converted into
+/// field elements, since a context read from untrusted bytes may record a modulus which cannot be
+/// converted into elements of the base field of the `AIR`.
+fn build_public_coin_seed<AIR: Air>(
+    context: &Context,
+    pub_inputs: &AIR::PublicInputs,
+) -> Result<Vec<AIR::BaseField>, VerifierError> {
+    check_base_field::<AIR::BaseField>(context)?;
+    let mut seed = context.try_to_elements().ok_or(VerifierError::InconsistentBaseField)?;
+    seed.append(&mut pub_inputs.to_elements());
+    Ok(seed)
+}
+
+// PROOF SOURCES
+// ================================================================================================
+
+/// Defines how the sections of a proof following the proof context are obtained by the verifier.
+trait ProofSource<A: Air, H: ElementHasher<BaseField = A::BaseField>> {
+    /// Builds a verifier channel over field `E` for the proof, and verifies the proof read from
+    /// the channel; returns the query positions at which the proof was opened.
+    fn verify<E, R>(self, air: &A, public_coin: &mut R) -> Result<Vec<usize>, VerifierError>
+    where
+        E: FieldElement<BaseField = A::BaseField>,
+        R: RandomCoin<BaseField = A::BaseField, Hasher = H>;
+}
+
+/// A proof which was deserialized in full, together with the commitment to the preprocessed
+/// columns known to the verifier.
+struct InMemoryProof<H: Hasher> {
+    proof: StarkProof,
+    preprocessed_commitment: Option<H::Digest>,
+}
+
+impl<A, H> ProofSource<A, H> for InMemoryProof<H>
+where
+    A: Air,
+    H: ElementHasher<BaseField = A::BaseField>,
+{
+    fn verify<E, R>(self, air: &A, public_coin: &mut R) -> Result<Vec<usize>, VerifierError>
+    where
+        E: FieldElement<BaseField = A::BaseField>,
+        R: RandomCoin<BaseField = A::BaseField, Hasher = H>,
+    {
+        let channel = VerifierChannel::<E, H>::new(air, self.proof, self.preprocessed_commitment)?;
+        perform_verification(air, channel, public_coin)
+    }
+}
+
+/// A proof read incrementally from a `source` which is positioned right after the proof context.
+struct StreamedProof<'a, S> {
+    source: &'a mut S,
+    context: Context,
+    version: u8,
+}
+
+impl<A, H, S> ProofSource<A, H> for StreamedProof<'_, S>
+where
+    A: Air,
+    H: ElementHasher<BaseField = A::BaseField>,
+    S: ByteReader,
+{
+    fn verify<E, R>(self, air: &A, public_coin: &mut R) -> Result<Vec<usize>, VerifierError>
+    where
+        E: FieldElement<BaseField = A::BaseField>,
+        R: RandomCoin<BaseField = A::BaseField, Hasher = H>,
+    {
+        let channel = VerifierChannel::<E, H, _>::read_from(
+            air,
+            &self.context,
+            self.version,
+            self.source,
+            None,
+        )?;
+        perform_verification(air, channel, public_coin)
+    }
+}
+
+/// Verifies the proof obtained from `proof` in the field specified by the proof options; the
+/// random coin is instantiated in `public_coin` with the specified seed once the field is known
+/// to be supported.
+#[rustfmt::skip]
+fn verify_in_proof_field<AIR, HashFn, RandCoin, P>(
+    air: &AIR,
+    proof: P,
+    public_coin_seed: &[AIR::BaseField],
+    public_coin: &mut Option<RandCoin>,
+) -> Result<Vec<usize>, VerifierError>
+where
+    AIR: Air,
+    HashFn: ElementHasher<BaseField = AIR::BaseField>,
+    RandCoin: RandomCoin<BaseField = AIR::BaseField, Hasher = HashFn>,
+    P: ProofSource<AIR, HashFn>,
+{
     // figure out which version of the generic proof verification procedure to run. this is a sort
     // of static dispatch for selecting two generic parameter: extension field and hash function.
     match air.options().field_extension() {
         FieldExtension::None => {
-            let public_coin = RandCoin::new(&public_coin_seed);
-            let channel = VerifierChannel::new(&air, proof)?;
-            perform_verification::<AIR, AIR::BaseField, HashFn, RandCoin>(air, channel, public_coin)
+            let public_coin = public_coin.insert(RandCoin::new(public_coin_seed));
+            proof.verify::<AIR::BaseField, RandCoin>(air, public_coin)
         },
         FieldExtension::Quadratic => {
             if !<QuadExtension<AIR::BaseField>>::is_supported() {
                 return Err(VerifierError::UnsupportedFieldExtension(2));
             }
-            let public_coin = RandCoin::new(&public_coin_seed);
-            let channel = VerifierChannel::new(&air, proof)?;
-            perform_verification::<AIR, QuadExtension<AIR::BaseField>, HashFn, RandCoin>(air, channel, public_coin)
+            let public_coin = public_coin.insert(RandCoin::new(public_coin_seed));
+            proof.verify::<QuadExtension<AIR::BaseField>, RandCoin>(air, public_coin)
         },
         FieldExtension::Cubic => {
             if !<CubeExtension<AIR::BaseField>>::is_supported() {
                 return Err(VerifierError::UnsupportedFieldExtension(3));
             }
-            let public_coin = RandCoin::new(&public_coin_seed);
-            let channel = VerifierChannel::new(&air, proof)?;
-            perform_verification::<AIR, CubeExtension<AIR::BaseField>, HashFn, RandCoin>(air, channel, public_coin)
+            let public_coin = public_coin.insert(RandCoin::new(public_coin_seed));
+            proof.verify::<CubeExtension<AIR::BaseField>, RandCoin>(air, public_coin)
         },
     }
 }
@@ -135,17 +618,37 @@ where
 // VERIFICATION PROCEDURE
 // ================================================================================================
 /// Performs the actual verification by reading the data from the `channel` and making sure it
-/// attests to a correct execution of the computation specified by the provided `air`.
-fn perform_verification<A, E, H, R>(
-    air: A,
-    mut channel: VerifierChannel<E, H>,
-    mut public_coin: R,
-) -> Result<(), VerifierError>
+/// attests to a correct execution of the computation specified by the provided `air`; returns
+/// the query positions at which the proof was opened.
+fn perform_verification<A, E, H, R, S>(
+    air: &A,
+    channel: VerifierChannel<E, H, S>,
+    public_coin: &mut R,
+) -> Result<Vec<usize>, VerifierError>
+where
+    A: Air,
+    E: FieldElement<BaseField = A::BaseField>,
+    H: ElementHasher<BaseField = A::BaseField>,
+    R: RandomCoin<BaseField = A::BaseField, Hasher = H>,
+    S: QuerySource<E, H>,
+{
+    perform_inspected_verification(air, channel, public_coin)
+        .map(|inspection| inspection.query_positions)
+}
+
+/// Performs the actual verification in the same way as [perform_verification()], and returns
+/// the values derived from the public coin along the way.
+fn perform_inspected_verification<A, E, H, R, S>(
+    air: &A,
+    mut channel: VerifierChannel<E, H, S>,
+    public_coin: &mut R,
+) -> Result<ProofInspection<E>, VerifierError>
 where
     A: Air,
     E: FieldElement<BaseField = A::BaseField>,
     H: ElementHasher<BaseField = A::BaseField>,
     R: RandomCoin<BaseField = A::BaseField, Hasher = H>,
+    S: QuerySource<E, H>,
 {
     // 1 ----- trace commitment -------------------------------------------------------------------
     // Read the commitments to evaluations of the trace polynomials over the LDE domain sent by the
@@ -158,14 +661,18 @@ where
     // constraint composition polynomial.
     let trace_commitments = channel.read_trace_commitments();
 
-    // reseed the coin with the commitment to the main trace segment
+    // reseed the coin with the commitment to the preprocessed columns (if any), and then with the
+    // commitment to the main trace segment
+    if let Some(preprocessed_commitment) = channel.read_preprocessed_commitment() {
+        public_coin.reseed(preprocessed_commitment);
+    }
     public_coin.reseed(trace_commitments[0]);
 
     // process auxiliary trace segments (if any), to build a set of random elements for each segment
     let mut aux_trace_rand_elements = AuxTraceRandElements::<E>::new();
     for (i, commitment) in trace_commitments.iter().skip(1).enumerate() {
         let rand_elements = air
-            .get_aux_trace_segment_random_elements(i, &mut public_coin)
+            .get_aux_trace_segment_random_elements(i, public_coin)
             .map_err(|_| VerifierError::RandomCoinError)?;
         aux_trace_rand_elements.add_segment_elements(rand_elements);
         public_coin.reseed(*commitment);
@@ -173,7 +680,7 @@ where
 
     // build random coefficients for the composition polynomial
     let constraint_coeffs = air
-        .get_constraint_composition_coefficients(&mut public_coin)
+        .get_constraint_composition_coefficients(public_coin)
         .map_err(|_| VerifierError::RandomCoinError)?;
 
     // 2 ----- constraint commitment --------------------------------------------------------------
@@ -196,13 +703,14 @@ where
     let ood_trace_frame = channel.read_ood_trace_frame();
     let ood_main_trace_frame = ood_trace_frame.main_frame();
     let ood_aux_trace_frame = ood_trace_frame.aux_frame();
-    let ood_constraint_evaluation_1 = evaluate_constraints(
-        &air,
-        constraint_coeffs,
-        &ood_main_trace_frame,
-        &ood_aux_trace_frame,
-        aux_trace_rand_elements,
+    public_coin.record_note("ood_trace_states", E::elements_as_bytes(ood_trace_frame.values()));
+    let ood_constraint_evaluation_1 = air.evaluate_constraints_at(
         z,
+        &ood_main_trace_frame,
+        ood_aux_trace_frame.as_ref(),
+        &air.get_periodic_column_values_at(z),
+        &aux_trace_rand_elements,
+        &constraint_coeffs,
     );
     public_coin.reseed(H::hash_elements(ood_trace_frame.values()));
 
@@ -214,6 +722,10 @@ where
     // H(X) = \sum_{i=0}^{m-1} X^{i * l} H_i(X).
     // Also, reseed the public coin with the OOD constraint evaluations received from the prover.
     let ood_constraint_evaluations = channel.read_ood_constraint_evaluations();
+    public_coin.record_note(
+        "ood_constraint_evaluations",
+        E::elements_as_bytes(&ood_constraint_evaluations),
+    );
     let ood_constraint_evaluation_2 =
         ood_constraint_evaluations
             .iter()
@@ -234,7 +746,7 @@ where
     // and the prover uses them to compute the DEEP composition polynomial. the prover, then
     // applies FRI protocol to the evaluations of the DEEP composition polynomial.
     let deep_coefficients = air
-        .get_deep_composition_coefficients::<E, R>(&mut public_coin)
+        .get_deep_composition_coefficients::<E, R>(public_coin)
         .map_err(|_| VerifierError::RandomCoinError)?;
 
     // instantiates a FRI verifier with the FRI layer commitments read from the channel. From the
@@ -242,10 +754,11 @@ where
     // The verifier uses these commitments to update the public coin and draw random points alpha
     // from them; in the interactive version of the protocol, the verifier sends these alphas to
     // the prover, and the prover uses them to compute and commit to the subsequent FRI layers.
-    let fri_verifier = FriVerifier::new(
+    let fri_verifier = LowDegreeVerifier::new(
         &mut channel,
-        &mut public_coin,
+        public_coin,
         air.options().to_fri_options(),
+        air.options().num_queries(),
         air.trace_poly_degree(),
     )
     .map_err(VerifierError::FriVerificationFailed)?;
@@ -256,7 +769,7 @@ where
     let pow_nonce = channel.read_pow_nonce();
 
     // make sure the proof-of-work specified by the grinding factor is satisfied
-    if public_coin.check_leading_zeros(pow_nonce) < air.options().grinding_factor() {
+    if !public_coin.check_proof_of_work(pow_nonce, air.options().grinding_factor()) {
         return Err(VerifierError::QuerySeedProofOfWorkVerificationFailed);
     }
 
@@ -264,14 +777,19 @@ where
     // interactive version of the protocol, the verifier sends these query positions to the prover,
     // and the prover responds with decommitments against these positions for trace and constraint
     // composition polynomial evaluations.
-    let mut query_positions = public_coin
-        .draw_integers(air.options().num_queries(), air.lde_domain_size(), pow_nonce)
+    // the positions are deduplicated and sorted in the same way as on the prover side.
+    let query_positions = air
+        .get_query_positions(public_coin, pow_nonce)
         .map_err(|_| VerifierError::RandomCoinError)?;
 
-    // remove any potential duplicates from the positions as the prover will send openings only
-    // for unique queries
-    query_positions.sort_unstable();
-    query_positions.dedup();
+    // the prover sends openings only for unique queries; make sure the number of these openings
+    // is consistent with the positions we've drawn
+    if query_positions.len() != channel.read_num_unique_queries() {
+        return Err(VerifierError::InconsistentNumQueries(
+            query_positions.len(),
+            channel.read_num_unique_queries(),
+        ));
+    }
 
     // read evaluations of trace and constraint composition polynomials at the queried positions;
     // this also checks that the read values are valid against trace and constraint commitments
@@ -281,12 +799,12 @@ where
 
     // 6 ----- DEEP composition -------------------------------------------------------------------
     // compute evaluations of the DEEP composition polynomial at the queried positions
-    let composer = DeepComposer::new(&air, &query_positions, z, deep_coefficients);
+    let composer = DeepComposer::new(air, &query_positions, z, deep_coefficients.clone());
     let t_composition = composer.compose_trace_columns(
         queried_main_trace_states,
         queried_aux_trace_states,
-        ood_main_trace_frame,
-        ood_aux_trace_frame,
+        ood_trace_frame.main_rows(),
+        ood_trace_frame.aux_rows(),
     );
     let c_composition = composer
         .compose_constraint_evaluations(queried_constraint_evaluations, ood_constraint_evaluations);
@@ -295,9 +813,21 @@ where
     // 7 ----- Verify low-degree proof -------------------------------------------------------------
     // make sure that evaluations of the DEEP composition polynomial we computed in the previous
     // step are in fact evaluations of a polynomial of degree equal to trace polynomial degree
-    fri_verifier
-        .verify(&mut channel, &deep_evaluations, &query_positions)
-        .map_err(VerifierError::FriVerificationFailed)
+    let fri_result = fri_verifier.verify(&mut channel, &deep_evaluations, &query_positions);
+    // a FRI layer which could not be read from the proof fails FRI verification; in such cases,
+    // the error encountered while reading the layer is reported instead
+    if let Some(err) = channel.take_read_error() {
+        return Err(err);
+    }
+    fri_result.map_err(VerifierError::FriVerificationFailed)?;
+
+    Ok(ProofInspection {
+        aux_rand_elements: aux_trace_rand_elements,
+        constraint_coefficients: constraint_coeffs,
+        z,
+        deep_coefficients,
+        query_positions,
+    })
 }
 
 // ACCEPTABLE OPTIONS
@@ -311,14 +841,28 @@ pub enum AcceptableOptions {
     MinProvenSecurity(u32),
     /// Set of acceptable proof parameters
     OptionSet(Vec<ProofOptions>),
+    /// Policy constraining security level, hash function, field, trace length, FRI folding
+    /// schedule, and query sampling strategy of a proof
+    Policy(AcceptancePolicy),
 }
 
 impl AcceptableOptions {
     /// Checks that a proof was generated using an acceptable set of parameters.
     pub fn validate<H: Hasher>(&self, proof: &StarkProof) -> Result<(), VerifierError> {
+        self.validate_context::<H>(&proof.context, proof.num_ood_points())
+    }
+
+    /// Checks that a proof with the specified context and opening trace polynomials at
+    /// `num_ood_points` out-of-domain points was generated using an acceptable set of parameters.
+    fn validate_context<H: Hasher>(
+        &self,
+        context: &Context,
+        num_ood_points: usize,
+    ) -> Result<(), VerifierError> {
         match self {
             AcceptableOptions::MinConjecturedSecurity(minimal_security) => {
-                let proof_security = proof.security_level::<H>(true);
+                let proof_security =
+                    context.security_level_with_ood_points::<H>(true, num_ood_points);
                 if proof_security < *minimal_security {
                     return Err(VerifierError::InsufficientConjecturedSecurity(
                         *minimal_security,
@@ -327,7 +871,8 @@ impl AcceptableOptions {
                 }
             }
             AcceptableOptions::MinProvenSecurity(minimal_security) => {
-                let proof_security = proof.security_level::<H>(false);
+                let proof_security =
+                    context.security_level_with_ood_points::<H>(false, num_ood_points);
                 if proof_security < *minimal_security {
                     return Err(VerifierError::InsufficientProvenSecurity(
                         *minimal_security,
@@ -336,11 +881,232 @@ impl AcceptableOptions {
                 }
             }
             AcceptableOptions::OptionSet(options) => {
-                if !options.iter().any(|opt| opt == proof.options()) {
+                if !options.iter().any(|opt| opt == context.options()) {
                     return Err(VerifierError::UnacceptableProofOptions);
                 }
             }
+            AcceptableOptions::Policy(policy) => {
+                policy.validate_context::<H>(context, num_ood_points)?
+            }
+        }
+        Ok(())
+    }
+}
+
+// ACCEPTANCE POLICY
+// ================================================================================================
+
+/// Describes which proofs are acceptable to the verifier without fixing exact proof parameters.
+///
+/// Unlike [AcceptableOptions::OptionSet], a policy accepts any [ProofOptions] as long as the
+/// resulting proof satisfies all of the following:
+/// - The proof provides at least the specified conjectured and proven security levels.
+/// - The proof was generated using one of the allowed hash functions, if these are specified.
+/// - The proof was generated in one of the allowed base field and field extension combinations,
+///   if these are specified.
+/// - The execution trace of the proven computation is not longer than the specified maximum
+///   trace length, if it is specified.
+/// - FRI layers of the proof are folded according to one of the allowed folding schedules, if
+///   these are specified.
+/// - Query positions of the proof are sampled using one of the allowed strategies; unless
+///   specified otherwise, only uniform sampling is allowed.
+///
+/// Since the trace length is checked before an instance of [Air] is created, limiting the trace
+/// length also bounds the amount of work the verifier does for proofs which are rejected.
+///
+/// A new policy accepts all proofs; each of the `with_*` methods narrows the set of accepted
+/// proofs.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AcceptancePolicy {
+    min_conjectured_security: u32,
+    min_proven_security: u32,
+    hash_functions: Option<Vec<HashFunction>>,
+    fields: Vec<(Vec<u8>, FieldExtension)>,
+    max_trace_length: Option<usize>,
+    fri_folding_schedules: Option<Vec<Vec<usize>>>,
+    query_sampling: Vec<QuerySampling>,
+    max_trace_meta_length: Option<usize>,
+    trace_meta: Option<Vec<u8>>,
+}
+
+impl AcceptancePolicy {
+    // CONSTRUCTORS
+    // --------------------------------------------------------------------------------------------
+    /// Returns a new policy which accepts proofs generated with any parameters and uniformly
+    /// sampled query positions.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the minimal conjectured security level (in bits) of acceptable proofs.
+    pub fn with_min_conjectured_security(mut self, security: u32) -> Self {
+        self.min_conjectured_security = security;
+        self
+    }
+
+    /// Sets the minimal proven security level (in bits) of acceptable proofs.
+    pub fn with_min_proven_security(mut self, security: u32) -> Self {
+        self.min_proven_security = security;
+        self
+    }
+
+    /// Restricts acceptable proofs to the ones generated using one of the specified hash
+    /// functions.
+    ///
+    /// Hash functions which cannot be selected at runtime (see [HashFunction]) are not acceptable
+    /// under such a policy.
+    pub fn with_hash_functions(mut self, hash_functions: &[HashFunction]) -> Self {
+        self.hash_functions = Some(hash_functions.to_vec());
+        self
+    }
+
+    /// Adds base field `B` with the specified field extension to the set of acceptable field
+    /// combinations.
+    ///
+    /// Once at least one combination is added, proofs generated in any other combination of base
+    /// field and field extension are rejected.
+    pub fn with_field<B: StarkField>(mut self, extension: FieldExtension) -> Self {
+        self.fields.push((B::get_modulus_le_bytes(), extension));
+        self
+    }
+
+    /// Sets the maximum execution trace length of acceptable proofs.
+    pub fn with_max_trace_length(mut self, max_trace_length: usize) -> Self {
+        self.max_trace_length = Some(max_trace_length);
+        self
+    }
+
+    /// Restricts acceptable proofs to the ones in which FRI layers are folded according to one of
+    /// the specified schedules.
+    ///
+    /// Each schedule lists the folding factors of all FRI layers of a proof (see
+    /// [FriOptions::layer_folding_factors()](fri::FriOptions::layer_folding_factors)); for
+    /// example, `[8, 8, 4]` accepts proofs with three FRI layers folded by 8, 8, and 4
+    /// respectively, regardless of how the folding factors are specified in [ProofOptions].
+    pub fn with_fri_folding_schedules(mut self, schedules: &[&[usize]]) -> Self {
+        self.fri_folding_schedules = Some(schedules.iter().map(|s| s.to_vec()).collect());
+        self
+    }
+
+    /// Restricts acceptable proofs to the ones in which query positions are sampled using one of
+    /// the specified strategies.
+    ///
+    /// Since a verifier must be able to reproduce the positions, a policy accepts only uniformly
+    /// sampled positions by default; other strategies (including [custom](QuerySampling::Custom)
+    /// ones) must be allowed explicitly.
+    pub fn with_query_sampling(mut self, strategies: &[QuerySampling]) -> Self {
+        self.query_sampling = strategies.to_vec();
+        self
+    }
+
+    /// Sets the maximum number of bytes in execution trace metadata of acceptable proofs.
+    pub fn with_max_trace_meta_length(mut self, max_trace_meta_length: usize) -> Self {
+        self.max_trace_meta_length = Some(max_trace_meta_length);
+        self
+    }
+
+    /// Restricts acceptable proofs to the ones with exactly the specified execution trace
+    /// metadata.
+    ///
+    /// Since trace metadata is bound to the proof transcript, this allows applications to
+    /// require a specific context of the computation (e.g., a program hash) without encoding it
+    /// into public inputs.
+    pub fn with_trace_meta(mut self, meta: &[u8]) -> Self {
+        self.trace_meta = Some(meta.to_vec());
+        self
+    }
+
+    // VALIDATION
+    // --------------------------------------------------------------------------------------------
+    /// Checks that a proof generated using hash function `H` is acceptable under this policy.
+    pub fn validate<H: Hasher>(&self, proof: &StarkProof) -> Result<(), VerifierError> {
+        self.validate_context::<H>(&proof.context, proof.num_ood_points())
+    }
+
+    /// Checks that a proof with the specified context generated using hash function `H` and
+    /// opening trace polynomials at `num_ood_points` out-of-domain points is acceptable under
+    /// this policy.
+    fn validate_context<H: Hasher>(
+        &self,
+        context: &Context,
+        num_ood_points: usize,
+    ) -> Result<(), VerifierError> {
+        if let Some(max_trace_length) = self.max_trace_length {
+            if context.trace_length() > max_trace_length {
+                return Err(VerifierError::TraceLengthTooLarge(
+                    max_trace_length,
+                    context.trace_length(),
+                ));
+            }
+        }
+
+        if let Some(max_trace_meta_length) = self.max_trace_meta_length {
+            if context.trace_meta().len() > max_trace_meta_length {
+                return Err(VerifierError::TraceMetaTooLong(
+                    max_trace_meta_length,
+                    context.trace_meta().len(),
+                ));
+            }
+        }
+
+        if let Some(trace_meta) = &self.trace_meta {
+            if context.trace_meta() != trace_meta.as_slice() {
+                return Err(VerifierError::UnexpectedTraceMeta);
+            }
         }
+
+        if let Some(hash_functions) = &self.hash_functions {
+            match H::HASH_FUNCTION {
+                Some(hash_fn) if hash_functions.contains(&hash_fn) => (),
+                _ => return Err(VerifierError::UnacceptableHashFunction),
+            }
+        }
+
+        if !self.fields.is_empty() {
+            let modulus = context.field_modulus_bytes();
+            let extension = context.options().field_extension();
+            if !self.fields.iter().any(|(m, e)| m == modulus && *e == extension) {
+                return Err(VerifierError::UnacceptableField(
+                    context.num_modulus_bits(),
+                    extension.degree(),
+                ));
+            }
+        }
+
+        if let Some(schedules) = &self.fri_folding_schedules {
+            let fri_options = context.options().to_fri_options();
+            let folding_factors = fri_options.layer_folding_factors(context.lde_domain_size());
+            if !schedules.contains(&folding_factors) {
+                return Err(VerifierError::UnacceptableFriFoldingSchedule);
+            }
+        }
+
+        let query_sampling = context.options().query_sampling();
+        let is_allowed = if self.query_sampling.is_empty() {
+            query_sampling == QuerySampling::Uniform
+        } else {
+            self.query_sampling.contains(&query_sampling)
+        };
+        if !is_allowed {
+            return Err(VerifierError::UnacceptableQuerySampling(query_sampling));
+        }
+
+        let proof_security = context.security_level_with_ood_points::<H>(true, num_ood_points);
+        if proof_security < self.min_conjectured_security {
+            return Err(VerifierError::InsufficientConjecturedSecurity(
+                self.min_conjectured_security,
+                proof_security,
+            ));
+        }
+
+        let proof_security = context.security_level_with_ood_points::<H>(false, num_ood_points);
+        if proof_security < self.min_proven_security {
+            return Err(VerifierError::InsufficientProvenSecurity(
+                self.min_proven_security,
+                proof_security,
+            ));
+        }
+
         Ok(())
     }
 }
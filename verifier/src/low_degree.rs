@@ -0,0 +1,75 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use crypto::{ElementHasher, RandomCoin};
+#[cfg(feature = "experimental-stir")]
+use fri::stir::StirVerifier;
+use fri::{FriOptions, FriVerifier, LowDegreeTest, VerifierChannel, VerifierError};
+use math::FieldElement;
+
+// LOW-DEGREE VERIFIER
+// ================================================================================================
+
+/// Verifier component of the low-degree test specified by the FRI options of a computation.
+///
+/// Both FRI and STIR verifiers read proofs from the same channel; thus, the rest of the
+/// verification procedure does not depend on which of the protocols is used.
+pub(crate) enum LowDegreeVerifier<E, C, H, R>
+where
+    E: FieldElement,
+    C: VerifierChannel<E, Hasher = H>,
+    H: ElementHasher<BaseField = E::BaseField>,
+    R: RandomCoin<BaseField = E::BaseField, Hasher = H>,
+{
+    Fri(FriVerifier<E, C, H, R>),
+    #[cfg(feature = "experimental-stir")]
+    Stir(StirVerifier<E, C, H, R>),
+}
+
+impl<E, C, H, R> LowDegreeVerifier<E, C, H, R>
+where
+    E: FieldElement,
+    C: VerifierChannel<E, Hasher = H>,
+    H: ElementHasher<BaseField = E::BaseField>,
+    R: RandomCoin<BaseField = E::BaseField, Hasher = H>,
+{
+    /// Executes the commit phase of the low-degree test specified by the `options`;
+    /// `num_queries` is used only by the STIR verifier.
+    #[cfg_attr(not(feature = "experimental-stir"), allow(unused_variables))]
+    pub fn new(
+        channel: &mut C,
+        public_coin: &mut R,
+        options: FriOptions,
+        num_queries: usize,
+        max_poly_degree: usize,
+    ) -> Result<Self, VerifierError> {
+        match options.low_degree_test() {
+            LowDegreeTest::Fri => {
+                FriVerifier::new(channel, public_coin, options, max_poly_degree).map(Self::Fri)
+            }
+            #[cfg(feature = "experimental-stir")]
+            LowDegreeTest::Stir => {
+                StirVerifier::new(channel, public_coin, options, num_queries, max_poly_degree)
+                    .map(Self::Stir)
+            }
+            #[cfg(not(feature = "experimental-stir"))]
+            LowDegreeTest::Stir => Err(VerifierError::UnsupportedLowDegreeTest),
+        }
+    }
+
+    /// Executes the query phase of the low-degree test.
+    pub fn verify(
+        &self,
+        channel: &mut C,
+        evaluations: &[E],
+        positions: &[usize],
+    ) -> Result<(), VerifierError> {
+        match self {
+            Self::Fri(verifier) => verifier.verify(channel, evaluations, positions),
+            #[cfg(feature = "experimental-stir")]
+            Self::Stir(verifier) => verifier.verify(channel, evaluations, positions),
+        }
+    }
+}
@@ -0,0 +1,194 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use prover::{
+    crypto::{
+        hashers::{Blake3_256, Sha3_256},
+        DefaultRandomCoin,
+    },
+    math::{fields::f128::BaseElement, FieldElement},
+    matrix::ColMatrix,
+    proof::ProofChunk,
+    Air, AirContext, Assertion, AuxTraceRandElements, ConstraintCompositionCoefficients,
+    DefaultConstraintEvaluator, DefaultTraceLde, EvaluationFrame, FieldExtension, ProofOptions,
+    Prover, StarkDomain, Trace, TraceInfo, TracePolyTable, TraceTable, TransitionConstraintDegree,
+};
+use winter_verifier::{AcceptableOptions, ChunkedProofVerifier, StarkProof, VerifierError};
+
+type Blake3 = Blake3_256<BaseElement>;
+type Sha3 = Sha3_256<BaseElement>;
+
+// TESTS
+// ================================================================================================
+
+#[test]
+fn proof_chunks() {
+    let prover = FibProver::new(build_options());
+    let trace = prover.build_trace(64);
+    let result = trace.get(1, trace.length() - 1);
+    let proof = prover.prove(trace).unwrap();
+    let acceptable_options = AcceptableOptions::OptionSet(vec![build_options()]);
+
+    // every serialized chunk fits into the size limit, and chunks reassemble into the proof
+    let chunks = proof.to_chunks::<Blake3>(1024);
+    assert!(chunks.len() > 1);
+    assert!(chunks.iter().all(|chunk| chunk.to_bytes().len() <= 1024));
+    let chunks = chunks
+        .iter()
+        .map(|chunk| ProofChunk::from_bytes(&chunk.to_bytes()).unwrap())
+        .collect::<Vec<_>>();
+    let mut reversed = chunks.clone();
+    reversed.reverse();
+    assert_eq!(proof, StarkProof::from_chunks::<Blake3>(&reversed).unwrap());
+    assert!(StarkProof::from_chunks::<Blake3>(&chunks[1..]).is_err());
+
+    // chunks are accepted in any order, and re-submitted chunks are ignored
+    let mut verifier = ChunkedProofVerifier::new();
+    for chunk in reversed.iter().skip(1) {
+        assert!(!verifier.add_chunk(chunk.clone()).unwrap());
+    }
+    assert!(!verifier.add_chunk(reversed[1].clone()).unwrap());
+    assert_eq!(vec![chunks.len() - 1], verifier.missing_chunks());
+
+    // chunks of other proofs are rejected as soon as they are added
+    let other_prover = FibProver::new(build_options());
+    let other_proof = other_prover.prove(other_prover.build_trace(32)).unwrap();
+    let other_chunk = other_proof.to_chunks::<Blake3>(1024).pop().unwrap();
+    assert!(matches!(
+        verifier.add_chunk(other_chunk),
+        Err(VerifierError::InconsistentProofChunk(_))
+    ));
+
+    let incomplete = verifier.clone();
+    let verified =
+        incomplete.verify::<FibAir, Blake3, DefaultRandomCoin<Blake3>>(result, &acceptable_options);
+    assert_eq!(Err(VerifierError::MissingProofChunks(chunks.len() - 1, chunks.len())), verified);
+
+    assert!(verifier.add_chunk(reversed[0].clone()).unwrap());
+    let verified =
+        verifier.verify::<FibAir, Blake3, DefaultRandomCoin<Blake3>>(result, &acceptable_options);
+    assert_eq!(Ok(()), verified);
+
+    // the reassembled proof must match the digest computed with the hash function of the verifier
+    let mut verifier = ChunkedProofVerifier::new();
+    for chunk in proof.to_chunks::<Sha3>(1024) {
+        verifier.add_chunk(chunk).unwrap();
+    }
+    let verified =
+        verifier.verify::<FibAir, Blake3, DefaultRandomCoin<Blake3>>(result, &acceptable_options);
+    assert_eq!(Err(VerifierError::ProofDigestMismatch), verified);
+}
+
+// FIBONACCI AIR AND PROVER
+// ================================================================================================
+
+fn build_options() -> ProofOptions {
+    ProofOptions::new(28, 8, 0, FieldExtension::None, 4, 7)
+}
+
+pub struct FibAir {
+    context: AirContext<BaseElement>,
+    result: BaseElement,
+}
+
+impl Air for FibAir {
+    type BaseField = BaseElement;
+    type PublicInputs = BaseElement;
+
+    fn new(trace_info: TraceInfo, result: BaseElement, options: ProofOptions) -> Self {
+        let degrees = vec![TransitionConstraintDegree::new(1), TransitionConstraintDegree::new(1)];
+        FibAir {
+            context: AirContext::new(trace_info, degrees, 3, options),
+            result,
+        }
+    }
+
+    fn context(&self) -> &AirContext<Self::BaseField> {
+        &self.context
+    }
+
+    fn evaluate_transition<E: FieldElement + From<Self::BaseField>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        _periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        let current = frame.current();
+        let next = frame.next();
+        result[0] = next[0] - (current[0] + current[1]);
+        result[1] = next[1] - (current[1] + next[0]);
+    }
+
+    fn get_assertions(&self) -> Vec<Assertion<Self::BaseField>> {
+        let last_step = self.trace_length() - 1;
+        vec![
+            Assertion::single(0, 0, BaseElement::ONE),
+            Assertion::single(1, 0, BaseElement::ONE),
+            Assertion::single(1, last_step, self.result),
+        ]
+    }
+}
+
+struct FibProver {
+    options: ProofOptions,
+}
+
+impl FibProver {
+    fn new(options: ProofOptions) -> Self {
+        Self { options }
+    }
+
+    fn build_trace(&self, length: usize) -> TraceTable<BaseElement> {
+        let mut trace = TraceTable::new(2, length);
+        trace.fill(
+            |state| {
+                state[0] = BaseElement::ONE;
+                state[1] = BaseElement::ONE;
+            },
+            |_, state| {
+                state[0] += state[1];
+                state[1] += state[0];
+            },
+        );
+        trace
+    }
+}
+
+impl Prover for FibProver {
+    type BaseField = BaseElement;
+    type Air = FibAir;
+    type Trace = TraceTable<BaseElement>;
+    type HashFn = Blake3;
+    type RandomCoin = DefaultRandomCoin<Blake3>;
+    type TraceLde<E: FieldElement<BaseField = Self::BaseField>> = DefaultTraceLde<E, Self::HashFn>;
+    type ConstraintEvaluator<'a, E: FieldElement<BaseField = Self::BaseField>> =
+        DefaultConstraintEvaluator<'a, Self::Air, E>;
+
+    fn get_pub_inputs(&self, trace: &Self::Trace) -> BaseElement {
+        trace.get(1, trace.length() - 1)
+    }
+
+    fn options(&self) -> &ProofOptions {
+        &self.options
+    }
+
+    fn new_trace_lde<E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        trace_info: &TraceInfo,
+        main_trace: &ColMatrix<Self::BaseField>,
+        domain: &StarkDomain<Self::BaseField>,
+    ) -> (Self::TraceLde<E>, TracePolyTable<E>) {
+        DefaultTraceLde::new(trace_info, main_trace, domain)
+    }
+
+    fn new_evaluator<'a, E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        air: &'a FibAir,
+        aux_rand_elements: AuxTraceRandElements<E>,
+        composition_coefficients: ConstraintCompositionCoefficients<E>,
+    ) -> Self::ConstraintEvaluator<'a, E> {
+        DefaultConstraintEvaluator::new(air, aux_rand_elements, composition_coefficients)
+    }
+}
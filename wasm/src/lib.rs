@@ -0,0 +1,112 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+//! This crate contains helpers for exposing the Winterfell STARK verifier to JavaScript via
+//! [wasm-bindgen](https://docs.rs/wasm-bindgen).
+//!
+//! Since the verifier is generic over the computation being verified, a single set of bindings
+//! cannot cover all computations. Instead, this crate provides the [export_verifier!] macro which
+//! defines a `wasm-bindgen` function for a specific [Air], hash function, and set of acceptable
+//! proof options. The generated function accepts serialized proof and public inputs, and returns
+//! `true` if the proof is valid:
+//!
+//! ```ignore
+//! winter_verifier_wasm::export_verifier!(
+//!     verify_fib,
+//!     FibAir,
+//!     Blake3_256<BaseElement>,
+//!     AcceptableOptions::MinConjecturedSecurity(96)
+//! );
+//! ```
+//!
+//! Compiled to `wasm32-unknown-unknown`, this exposes `verify_fib(proof_bytes, pub_inputs_bytes)`
+//! to JavaScript, which takes two `Uint8Array` arguments. Public inputs of the computation must
+//! implement [Deserializable] and are expected to be serialized via [Serializable].
+//!
+//! The verifier is used in `no_std` mode, and thus, the verification path does not depend on
+//! time, randomness, or I/O facilities of the standard library.
+//!
+//! [Serializable]: verifier::Serializable
+
+pub use verifier;
+use verifier::{
+    crypto::{ElementHasher, RandomCoin},
+    AcceptableOptions, Air, ByteReader, Deserializable, DeserializationError, SliceReader,
+    StarkProof, VerifierError,
+};
+
+#[doc(hidden)]
+pub use wasm_bindgen;
+
+// VERIFIER
+// ================================================================================================
+
+/// Verifies a serialized proof against serialized public inputs of the computation specified by
+/// `AIR`, `HashFn`, and `RandCoin` type parameters.
+///
+/// This is the same as [verify()](verifier::verify), except that the proof and public inputs
+/// are deserialized first. All bytes of both inputs must be consumed during deserialization.
+///
+/// # Errors
+/// Returns [VerifierError::ProofDeserializationError] if either the proof or the public inputs
+/// could not be deserialized, and any other [VerifierError] if the proof is not valid.
+pub fn verify_bytes<AIR, HashFn, RandCoin>(
+    proof_bytes: &[u8],
+    pub_inputs_bytes: &[u8],
+    acceptable_options: &AcceptableOptions,
+) -> Result<(), VerifierError>
+where
+    AIR: Air,
+    AIR::PublicInputs: Deserializable,
+    HashFn: ElementHasher<BaseField = AIR::BaseField>,
+    RandCoin: RandomCoin<BaseField = AIR::BaseField, Hasher = HashFn>,
+{
+    let proof = StarkProof::from_bytes(proof_bytes)
+        .map_err(|err| VerifierError::ProofDeserializationError(err.to_string()))?;
+    let pub_inputs = read_pub_inputs::<AIR::PublicInputs>(pub_inputs_bytes)
+        .map_err(|err| VerifierError::ProofDeserializationError(err.to_string()))?;
+    verifier::verify::<AIR, HashFn, RandCoin>(proof, pub_inputs, acceptable_options)
+}
+
+/// Defines a `wasm-bindgen` function which verifies proofs of a specific computation.
+///
+/// The macro takes the name of the function to define, the [Air] of the computation, the hash
+/// function used to generate proofs, and an expression evaluating to [AcceptableOptions]. The
+/// defined function has the following signature:
+///
+/// ```ignore
+/// pub fn name(proof_bytes: &[u8], pub_inputs_bytes: &[u8]) -> bool
+/// ```
+///
+/// The function returns `true` if the proof is valid, and `false` if the proof or public inputs
+/// could not be deserialized, or if the proof is not valid (see [verify_bytes()]). Proofs are
+/// expected to be generated using [DefaultRandomCoin](verifier::crypto::DefaultRandomCoin).
+#[macro_export]
+macro_rules! export_verifier {
+    ($name:ident, $air:ty, $hash_fn:ty, $acceptable_options:expr) => {
+        #[$crate::wasm_bindgen::prelude::wasm_bindgen(wasm_bindgen = $crate::wasm_bindgen)]
+        pub fn $name(proof_bytes: &[u8], pub_inputs_bytes: &[u8]) -> bool {
+            $crate::verify_bytes::<
+                $air,
+                $hash_fn,
+                $crate::verifier::crypto::DefaultRandomCoin<$hash_fn>,
+            >(proof_bytes, pub_inputs_bytes, &$acceptable_options)
+            .is_ok()
+        }
+    };
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Deserializes public inputs from the provided bytes making sure all bytes are consumed.
+fn read_pub_inputs<P: Deserializable>(bytes: &[u8]) -> Result<P, DeserializationError> {
+    let mut source = SliceReader::new(bytes);
+    let pub_inputs = P::read_from(&mut source)?;
+    if source.has_more_bytes() {
+        return Err(DeserializationError::UnconsumedBytes);
+    }
+    Ok(pub_inputs)
+}
@@ -0,0 +1,175 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+//! Computation shared by prover benchmarks.
+//!
+//! The computation is generic over the base field so that the same AIR can be benchmarked in all
+//! provided fields: each of [TRACE_WIDTH] columns is updated as x' = x^3 + c, where c is
+//! distinct for each column. Constraints of degree 3 over a moderately wide trace are
+//! representative of the workloads for which the shared kernels (FFTs, hashing, and constraint
+//! evaluation) dominate proof generation time.
+
+use core::marker::PhantomData;
+use winterfell::{
+    crypto::{DefaultRandomCoin, ElementHasher},
+    math::{ExtensibleField, FieldElement, StarkField, ToElements},
+    matrix::ColMatrix,
+    Air, AirContext, Assertion, AuxTraceRandElements, ConstraintCompositionCoefficients,
+    DefaultConstraintEvaluator, DefaultTraceLde, EvaluationFrame, ProofOptions, Prover,
+    StarkDomain, Trace, TraceInfo, TracePolyTable, TraceTable, TransitionConstraintDegree,
+};
+
+// CONSTANTS
+// ================================================================================================
+
+/// Number of columns in the execution trace.
+pub const TRACE_WIDTH: usize = 16;
+
+// AIR
+// ================================================================================================
+
+pub struct PublicInputs<B: StarkField> {
+    pub result: Vec<B>,
+}
+
+impl<B: StarkField> ToElements<B> for PublicInputs<B> {
+    fn to_elements(&self) -> Vec<B> {
+        self.result.clone()
+    }
+}
+
+pub struct BenchAir<B: StarkField> {
+    context: AirContext<B>,
+    result: Vec<B>,
+}
+
+impl<B: StarkField + ExtensibleField<2> + ExtensibleField<3>> Air for BenchAir<B> {
+    type BaseField = B;
+    type PublicInputs = PublicInputs<B>;
+
+    fn new(trace_info: TraceInfo, pub_inputs: PublicInputs<B>, options: ProofOptions) -> Self {
+        let degrees = vec![TransitionConstraintDegree::new(3); TRACE_WIDTH];
+        let num_assertions = 2 * TRACE_WIDTH;
+        BenchAir {
+            context: AirContext::new(trace_info, degrees, num_assertions, options),
+            result: pub_inputs.result,
+        }
+    }
+
+    fn context(&self) -> &AirContext<B> {
+        &self.context
+    }
+
+    fn evaluate_transition<E: FieldElement<BaseField = B>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        _periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        let current = frame.current();
+        let next = frame.next();
+        for (i, result) in result.iter_mut().enumerate() {
+            *result = next[i] - (current[i].cube() + E::from(i as u32 + 1));
+        }
+    }
+
+    fn get_assertions(&self) -> Vec<Assertion<B>> {
+        let last_step = self.trace_length() - 1;
+        let mut assertions = Vec::with_capacity(2 * TRACE_WIDTH);
+        for (i, &value) in self.result.iter().enumerate() {
+            assertions.push(Assertion::single(i, 0, initial_value(i)));
+            assertions.push(Assertion::single(i, last_step, value));
+        }
+        assertions
+    }
+}
+
+// PROVER
+// ================================================================================================
+
+pub struct BenchProver<B: StarkField, H: ElementHasher<BaseField = B>> {
+    options: ProofOptions,
+    _field: PhantomData<B>,
+    _hasher: PhantomData<H>,
+}
+
+impl<B: StarkField, H: ElementHasher<BaseField = B>> BenchProver<B, H> {
+    pub fn new(options: ProofOptions) -> Self {
+        Self {
+            options,
+            _field: PhantomData,
+            _hasher: PhantomData,
+        }
+    }
+}
+
+impl<B, H> Prover for BenchProver<B, H>
+where
+    B: StarkField + ExtensibleField<2> + ExtensibleField<3> + 'static,
+    H: ElementHasher<BaseField = B>,
+{
+    type BaseField = B;
+    type Air = BenchAir<B>;
+    type Trace = TraceTable<B>;
+    type HashFn = H;
+    type RandomCoin = DefaultRandomCoin<H>;
+    type TraceLde<E: FieldElement<BaseField = B>> = DefaultTraceLde<E, H>;
+    type ConstraintEvaluator<'a, E: FieldElement<BaseField = B>> =
+        DefaultConstraintEvaluator<'a, BenchAir<B>, E>;
+
+    fn get_pub_inputs(&self, trace: &Self::Trace) -> PublicInputs<B> {
+        let last_step = trace.length() - 1;
+        PublicInputs {
+            result: (0..TRACE_WIDTH).map(|i| trace.get(i, last_step)).collect(),
+        }
+    }
+
+    fn options(&self) -> &ProofOptions {
+        &self.options
+    }
+
+    fn new_trace_lde<E: FieldElement<BaseField = B>>(
+        &self,
+        trace_info: &TraceInfo,
+        main_trace: &ColMatrix<B>,
+        domain: &StarkDomain<B>,
+    ) -> (Self::TraceLde<E>, TracePolyTable<E>) {
+        DefaultTraceLde::new(trace_info, main_trace, domain)
+    }
+
+    fn new_evaluator<'a, E: FieldElement<BaseField = B>>(
+        &self,
+        air: &'a BenchAir<B>,
+        aux_rand_elements: AuxTraceRandElements<E>,
+        composition_coefficients: ConstraintCompositionCoefficients<E>,
+    ) -> Self::ConstraintEvaluator<'a, E> {
+        DefaultConstraintEvaluator::new(air, aux_rand_elements, composition_coefficients)
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Builds an execution trace of the specified length for the benchmarked computation.
+pub fn build_trace<B: StarkField>(trace_length: usize) -> TraceTable<B> {
+    let mut trace = TraceTable::new(TRACE_WIDTH, trace_length);
+    trace.fill(
+        |state: &mut [B]| {
+            for (i, value) in state.iter_mut().enumerate() {
+                *value = initial_value(i);
+            }
+        },
+        |_, state| {
+            for (i, value) in state.iter_mut().enumerate() {
+                *value = value.cube() + B::from(i as u32 + 1);
+            }
+        },
+    );
+    trace
+}
+
+fn initial_value<B: StarkField>(column: usize) -> B {
+    B::from(column as u32 + 2)
+}
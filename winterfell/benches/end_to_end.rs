@@ -0,0 +1,162 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+//! End-to-end benchmarks for proof generation and verification across a grid of fields, field
+//! extensions, blowup factors, and hash functions.
+
+use criterion::{
+    criterion_group, criterion_main, measurement::WallTime, BatchSize, BenchmarkGroup, BenchmarkId,
+    Criterion,
+};
+use std::time::Duration;
+use winterfell::{
+    crypto::{hashers, DefaultRandomCoin, ElementHasher},
+    math::{
+        fields::{self, f64::BaseElement, CubeExtension, QuadExtension},
+        ExtensibleField, StarkField,
+    },
+    AcceptableOptions, FieldExtension, ProofOptions, Prover,
+};
+
+mod common;
+use common::{build_trace, BenchAir, BenchProver};
+
+// CONSTANTS
+// ================================================================================================
+
+const TRACE_LENGTH: usize = 1 << 12;
+const BLOWUP_FACTOR: usize = 8;
+const BLOWUP_FACTORS: [usize; 3] = [8, 16, 32];
+
+// BENCHMARKS
+// ================================================================================================
+
+/// Benchmarks all fields with all supported extensions using BLAKE3 hash function.
+fn fields_and_extensions(c: &mut Criterion) {
+    let mut group = build_group(c, "end_to_end/field");
+    bench_extensions::<fields::f62::BaseElement>(&mut group, "f62");
+    bench_extensions::<fields::f64::BaseElement>(&mut group, "f64");
+    bench_extensions::<fields::f128::BaseElement>(&mut group, "f128");
+    group.finish();
+}
+
+/// Benchmarks different blowup factors in `f64` field.
+fn blowup_factors(c: &mut Criterion) {
+    let mut group = build_group(c, "end_to_end/blowup");
+    for blowup_factor in BLOWUP_FACTORS {
+        let options = build_options(blowup_factor, FieldExtension::None);
+        bench_config::<BaseElement, hashers::Blake3_256<BaseElement>>(
+            &mut group,
+            &blowup_factor.to_string(),
+            options,
+        );
+    }
+    group.finish();
+}
+
+/// Benchmarks all hash functions in `f64` field.
+fn hash_functions(c: &mut Criterion) {
+    let mut group = build_group(c, "end_to_end/hash_fn");
+    let options = build_options(BLOWUP_FACTOR, FieldExtension::None);
+    bench_config::<BaseElement, hashers::Blake3_192<BaseElement>>(
+        &mut group,
+        "blake3_192",
+        options.clone(),
+    );
+    bench_config::<BaseElement, hashers::Blake3_256<BaseElement>>(
+        &mut group,
+        "blake3_256",
+        options.clone(),
+    );
+    bench_config::<BaseElement, hashers::Sha3_256<BaseElement>>(
+        &mut group,
+        "sha3_256",
+        options.clone(),
+    );
+    bench_config::<BaseElement, hashers::Rp64_256>(&mut group, "rp64_256", options.clone());
+    bench_config::<BaseElement, hashers::RpJive64_256>(
+        &mut group,
+        "rp_jive64_256",
+        options.clone(),
+    );
+    bench_config::<BaseElement, hashers::GriffinJive64_256>(
+        &mut group,
+        "griffin_jive64_256",
+        options,
+    );
+    group.finish();
+}
+
+criterion_group!(end_to_end_group, fields_and_extensions, blowup_factors, hash_functions);
+criterion_main!(end_to_end_group);
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+fn build_group<'a>(c: &'a mut Criterion, name: &str) -> BenchmarkGroup<'a, WallTime> {
+    let mut group = c.benchmark_group(name);
+    group.sample_size(10);
+    group.measurement_time(Duration::from_secs(10));
+    group
+}
+
+fn build_options(blowup_factor: usize, field_extension: FieldExtension) -> ProofOptions {
+    ProofOptions::new(32, blowup_factor, 0, field_extension, 4, 255)
+}
+
+/// Benchmarks the specified field without an extension, and with all extensions supported by
+/// the field.
+fn bench_extensions<B>(group: &mut BenchmarkGroup<WallTime>, field: &str)
+where
+    B: StarkField + ExtensibleField<2> + ExtensibleField<3> + 'static,
+{
+    let mut extensions = vec![("1", FieldExtension::None)];
+    if QuadExtension::<B>::is_supported() {
+        extensions.push(("2", FieldExtension::Quadratic));
+    }
+    if CubeExtension::<B>::is_supported() {
+        extensions.push(("3", FieldExtension::Cubic));
+    }
+
+    for (degree, extension) in extensions {
+        let options = build_options(BLOWUP_FACTOR, extension);
+        bench_config::<B, hashers::Blake3_256<B>>(group, &format!("{field}/{degree}"), options);
+    }
+}
+
+/// Benchmarks proof generation and verification for the specified configuration.
+fn bench_config<B, H>(group: &mut BenchmarkGroup<WallTime>, config: &str, options: ProofOptions)
+where
+    B: StarkField + ExtensibleField<2> + ExtensibleField<3> + 'static,
+    H: ElementHasher<BaseField = B>,
+{
+    let prover = BenchProver::<B, H>::new(options);
+    let trace = build_trace::<B>(TRACE_LENGTH);
+
+    group.bench_function(BenchmarkId::new("prove", config), |bench| {
+        bench.iter_batched(
+            || trace.clone(),
+            |trace| prover.prove(trace).unwrap(),
+            BatchSize::LargeInput,
+        );
+    });
+
+    let proof = prover.prove(trace.clone()).unwrap();
+    let acceptable_options = AcceptableOptions::OptionSet(vec![proof.options().clone()]);
+    group.bench_function(BenchmarkId::new("verify", config), |bench| {
+        bench.iter_batched(
+            || (proof.clone(), prover.get_pub_inputs(&trace)),
+            |(proof, pub_inputs)| {
+                winterfell::verify::<BenchAir<B>, H, DefaultRandomCoin<H>>(
+                    proof,
+                    pub_inputs,
+                    &acceptable_options,
+                )
+                .unwrap()
+            },
+            BatchSize::SmallInput,
+        );
+    });
+}
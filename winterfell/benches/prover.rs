@@ -0,0 +1,116 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+//! Benchmarks for individual phases of proof generation: trace low-degree extension, trace
+//! commitment, constraint evaluation, and FRI layer construction.
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use rand_utils::rand_vector;
+use std::time::Duration;
+use winter_fri::{DefaultProverChannel, FriOptions, FriProver};
+use winterfell::{
+    crypto::{hashers::Blake3_256, DefaultRandomCoin},
+    math::{fft, fields, ExtensibleField, StarkField},
+    matrix::RowMatrix,
+    Air, AuxTraceRandElements, ConstraintCompositionCoefficients, ConstraintEvaluator,
+    DefaultConstraintEvaluator, DefaultTraceLde, FieldExtension, ProofOptions, Prover, StarkDomain,
+    Trace,
+};
+
+mod common;
+use common::{build_trace, BenchAir, BenchProver, TRACE_WIDTH};
+
+// CONSTANTS
+// ================================================================================================
+
+const TRACE_LENGTH: usize = 1 << 14;
+const BLOWUP_FACTOR: usize = 8;
+
+/// Number of columns in a segment of the trace LDE; this matches the value used by the prover.
+const SEGMENT_WIDTH: usize = 8;
+
+// BENCHMARKS
+// ================================================================================================
+
+fn prover_phases(c: &mut Criterion) {
+    bench_phases::<fields::f62::BaseElement>(c, "f62");
+    bench_phases::<fields::f64::BaseElement>(c, "f64");
+    bench_phases::<fields::f128::BaseElement>(c, "f128");
+}
+
+fn bench_phases<B>(c: &mut Criterion, field: &str)
+where
+    B: StarkField + ExtensibleField<2> + ExtensibleField<3> + 'static,
+{
+    let mut group = c.benchmark_group(format!("prover_phases/{field}"));
+    group.sample_size(10);
+    group.measurement_time(Duration::from_secs(10));
+
+    let options = ProofOptions::new(32, BLOWUP_FACTOR, 0, FieldExtension::None, 4, 255);
+    let prover = BenchProver::<B, Blake3_256<B>>::new(options.clone());
+    let trace = build_trace::<B>(TRACE_LENGTH);
+    let air = BenchAir::new(trace.get_info(), prover.get_pub_inputs(&trace), options);
+    let domain = StarkDomain::new(&air);
+
+    // interpolate trace columns and evaluate them over the LDE domain
+    group.bench_function("trace_lde", |bench| {
+        bench.iter(|| {
+            let polys = trace.main_segment().interpolate_columns();
+            RowMatrix::evaluate_polys_over::<SEGMENT_WIDTH>(&polys, &domain)
+        });
+    });
+
+    // hash rows of the trace LDE and build a Merkle tree from them
+    let polys = trace.main_segment().interpolate_columns();
+    let trace_lde = RowMatrix::evaluate_polys_over::<SEGMENT_WIDTH>(&polys, &domain);
+    group.bench_function("trace_commitment", |bench| {
+        bench.iter(|| trace_lde.commit_to_rows::<Blake3_256<B>>());
+    });
+
+    // evaluate constraints over the constraint evaluation domain
+    let (trace_lde, _) =
+        DefaultTraceLde::<B, Blake3_256<B>>::new(&trace.get_info(), trace.main_segment(), &domain);
+    group.bench_function("constraint_evaluation", |bench| {
+        bench.iter_batched(
+            || ConstraintCompositionCoefficients {
+                transition: rand_vector(TRACE_WIDTH),
+                boundary: rand_vector(2 * TRACE_WIDTH),
+            },
+            |coefficients| {
+                DefaultConstraintEvaluator::new(&air, AuxTraceRandElements::new(), coefficients)
+                    .evaluate(&trace_lde, &domain)
+            },
+            BatchSize::SmallInput,
+        );
+    });
+
+    // build FRI layers for evaluations of a polynomial of degree smaller than trace length
+    let domain_size = TRACE_LENGTH * BLOWUP_FACTOR;
+    let mut evaluations: Vec<B> = rand_vector(TRACE_LENGTH);
+    evaluations.resize(domain_size, B::ZERO);
+    fft::evaluate_poly(&mut evaluations, &fft::get_twiddles::<B>(domain_size));
+    let fri_options = FriOptions::new(BLOWUP_FACTOR, 4, 255);
+    group.bench_function("fri_layers", |bench| {
+        let mut fri_prover = FriProver::new(fri_options.clone());
+        bench.iter_batched(
+            || evaluations.clone(),
+            |evaluations| {
+                let mut channel = DefaultProverChannel::<
+                    B,
+                    Blake3_256<B>,
+                    DefaultRandomCoin<Blake3_256<B>>,
+                >::new(domain_size, 32);
+                fri_prover.build_layers(&mut channel, evaluations);
+                fri_prover.reset();
+            },
+            BatchSize::LargeInput,
+        );
+    });
+
+    group.finish();
+}
+
+criterion_group!(prover_phases_group, prover_phases);
+criterion_main!(prover_phases_group);
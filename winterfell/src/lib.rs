@@ -581,12 +581,35 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
 pub use prover::{
-    crypto, iterators, math, matrix, Air, AirContext, Assertion, AuxTraceRandElements,
-    BoundaryConstraint, BoundaryConstraintGroup, ByteReader, ByteWriter, CompositionPolyTrace,
+    crypto, distributed, gadgets, iterators, math, matrix, proof, prove_dyn, AggregateAir,
+    AggregateInputs, Air, AirContext, Assertion, AuxTraceRandElements, BoundaryConstraint,
+    BoundaryConstraintGroup, BufferAllocator, BufferKind, ByteReader, ByteWriter, Component,
+    ComponentId, ComponentLayout, CompositionPoly, CompositionPolyTrace, CompressedColumn,
     ConstraintCompositionCoefficients, ConstraintDivisor, ConstraintEvaluator,
-    DeepCompositionCoefficients, DefaultConstraintEvaluator, DefaultTraceLde, Deserializable,
-    DeserializationError, EvaluationFrame, FieldExtension, ProofOptions, Prover, ProverError,
-    Serializable, SliceReader, StarkDomain, StarkProof, Trace, TraceInfo, TraceLayout, TraceLde,
-    TracePolyTable, TraceTable, TraceTableFragment, TransitionConstraintDegree,
+    DeepCompositionCoefficients, DefaultAllocator, DefaultConstraintEvaluator, DefaultTraceLde,
+    Deserializable, DeserializationError, EvaluationFrame, FieldExtension, LeafEncoding,
+    LowDegreeTest, MemoryEstimate, MerkleInputs, NumaPartitioning, PreprocessedTrace, ProofOptions,
+    Prover, ProverError, ProverFactory, ProverHooks, ProverSession, QuerySampler, QuerySampling,
+    RowIter, Serializable, SessionStats, SliceReader, StarkDomain, StarkProof, StratifiedSampler,
+    Trace, TraceAllocator, TraceColumn, TraceDiagnostics, TraceInfo, TraceIssue, TraceLayout,
+    TraceLde, TracePolyTable, TraceRow, TraceTable, TraceTableFragment, TraceTableFragmentView,
+    TraceTableWriter, TransitionConstraintDegree, UniformSampler,
 };
-pub use verifier::{verify, AcceptableOptions, VerifierError};
+pub use verifier::{
+    inspect, verify, verify_against_transcript, verify_all, verify_auto, verify_dyn,
+    verify_from_reader, verify_with_preprocessed_commitment, verify_with_query_positions,
+    verify_with_transcript, AcceptableOptions, AcceptancePolicy, ChunkedProofVerifier,
+    ProofInspection, PublicInputsError, VerifierError,
+};
+
+#[cfg(feature = "std")]
+pub use verifier::{ReadAdapter, WriteAdapter};
+
+#[cfg(feature = "gpu")]
+pub use prover::GpuConstraintEvaluator;
+
+#[cfg(feature = "parquet")]
+pub use prover::{arrow_array, parquet, FIELD_MODULUS_KEY, TRACE_META_KEY};
+
+#[cfg(feature = "protobuf")]
+pub use prover::protobuf;
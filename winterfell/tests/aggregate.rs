@@ -0,0 +1,198 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use winterfell::{
+    crypto::{hashers::Blake3_256, DefaultRandomCoin},
+    math::{fields::f128::BaseElement, FieldElement, ToElements},
+    matrix::ColMatrix,
+    verify, AcceptableOptions, AggregateAir, AggregateInputs, Air, AirContext, Assertion,
+    AuxTraceRandElements, ConstraintCompositionCoefficients, DefaultConstraintEvaluator,
+    DefaultTraceLde, EvaluationFrame, FieldExtension, ProofOptions, Prover, StarkDomain, Trace,
+    TraceInfo, TracePolyTable, TraceTable, TransitionConstraintDegree, VerifierError,
+};
+
+type Blake3 = Blake3_256<BaseElement>;
+
+const TRACE_LENGTH: usize = 64;
+
+// TESTS
+// ================================================================================================
+
+#[test]
+fn aggregate_air_prove_verify() {
+    let options = ProofOptions::new(28, 8, 0, FieldExtension::None, 4, 31);
+    let starts = [1u32, 5, 11].map(BaseElement::from);
+    let traces = starts.iter().map(|&start| build_trace(start)).collect::<Vec<_>>();
+    let inputs = traces.iter().map(get_pub_inputs).collect::<Vec<_>>();
+
+    // instances start at different values, and thus, have different results
+    assert_ne!(inputs[0], inputs[1]);
+    assert_ne!(inputs[1], inputs[2]);
+
+    let trace = TraceTable::stack(traces);
+    assert_eq!(3 * 2, trace.main_trace_width());
+    let prover = AggregateProver {
+        options: options.clone(),
+        inputs: inputs.clone(),
+    };
+    let proof = prover.prove(trace).unwrap();
+
+    // the proof is valid for the public inputs of all instances in the order of the columns
+    let acceptable_options = AcceptableOptions::OptionSet(vec![options]);
+    let result = verify::<AggregateAir<FibAir>, Blake3, DefaultRandomCoin<Blake3>>(
+        proof.clone(),
+        AggregateInputs::new(inputs.clone()),
+        &acceptable_options,
+    );
+    assert!(result.is_ok(), "{result:?}");
+
+    // the proof is not valid if public inputs of one of the instances are changed
+    let mut wrong_inputs = inputs.clone();
+    wrong_inputs[1].result += BaseElement::ONE;
+    let result = verify::<AggregateAir<FibAir>, Blake3, DefaultRandomCoin<Blake3>>(
+        proof.clone(),
+        AggregateInputs::new(wrong_inputs),
+        &acceptable_options,
+    );
+    assert_eq!(Err(VerifierError::InconsistentOodConstraintEvaluations), result);
+
+    // nor if instances are reordered
+    let mut swapped_inputs = inputs;
+    swapped_inputs.swap(0, 2);
+    let result = verify::<AggregateAir<FibAir>, Blake3, DefaultRandomCoin<Blake3>>(
+        proof,
+        AggregateInputs::new(swapped_inputs),
+        &acceptable_options,
+    );
+    assert_eq!(Err(VerifierError::InconsistentOodConstraintEvaluations), result);
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+fn build_trace(start: BaseElement) -> TraceTable<BaseElement> {
+    let mut trace = TraceTable::new(2, TRACE_LENGTH);
+    trace.fill(
+        |state| {
+            state[0] = BaseElement::ONE;
+            state[1] = start;
+        },
+        |_, state| {
+            state[0] += state[1];
+            state[1] += state[0];
+        },
+    );
+    trace
+}
+
+fn get_pub_inputs(trace: &TraceTable<BaseElement>) -> FibInputs {
+    FibInputs {
+        start: trace.get(1, 0),
+        result: trace.get(1, TRACE_LENGTH - 1),
+    }
+}
+
+// FIBONACCI AIR
+// ================================================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct FibInputs {
+    start: BaseElement,
+    result: BaseElement,
+}
+
+impl ToElements<BaseElement> for FibInputs {
+    fn to_elements(&self) -> Vec<BaseElement> {
+        vec![self.start, self.result]
+    }
+}
+
+struct FibAir {
+    context: AirContext<BaseElement>,
+    inputs: FibInputs,
+}
+
+impl Air for FibAir {
+    type BaseField = BaseElement;
+    type PublicInputs = FibInputs;
+
+    fn new(trace_info: TraceInfo, inputs: FibInputs, options: ProofOptions) -> Self {
+        let degrees = vec![TransitionConstraintDegree::new(1), TransitionConstraintDegree::new(1)];
+        Self {
+            context: AirContext::new(trace_info, degrees, 3, options),
+            inputs,
+        }
+    }
+
+    fn context(&self) -> &AirContext<Self::BaseField> {
+        &self.context
+    }
+
+    fn evaluate_transition<E: FieldElement + From<Self::BaseField>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        _periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        let current = frame.current();
+        let next = frame.next();
+        result[0] = next[0] - (current[0] + current[1]);
+        result[1] = next[1] - (current[1] + next[0]);
+    }
+
+    fn get_assertions(&self) -> Vec<Assertion<Self::BaseField>> {
+        let last_step = self.trace_length() - 1;
+        vec![
+            Assertion::single(0, 0, BaseElement::ONE),
+            Assertion::single(1, 0, self.inputs.start),
+            Assertion::single(1, last_step, self.inputs.result),
+        ]
+    }
+}
+
+// AGGREGATE PROVER
+// ================================================================================================
+
+struct AggregateProver {
+    options: ProofOptions,
+    inputs: Vec<FibInputs>,
+}
+
+impl Prover for AggregateProver {
+    type BaseField = BaseElement;
+    type Air = AggregateAir<FibAir>;
+    type Trace = TraceTable<BaseElement>;
+    type HashFn = Blake3;
+    type RandomCoin = DefaultRandomCoin<Blake3>;
+    type TraceLde<E: FieldElement<BaseField = Self::BaseField>> = DefaultTraceLde<E, Self::HashFn>;
+    type ConstraintEvaluator<'a, E: FieldElement<BaseField = Self::BaseField>> =
+        DefaultConstraintEvaluator<'a, Self::Air, E>;
+
+    fn get_pub_inputs(&self, _trace: &Self::Trace) -> AggregateInputs<FibInputs> {
+        AggregateInputs::new(self.inputs.clone())
+    }
+
+    fn options(&self) -> &ProofOptions {
+        &self.options
+    }
+
+    fn new_trace_lde<E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        trace_info: &TraceInfo,
+        main_trace: &ColMatrix<Self::BaseField>,
+        domain: &StarkDomain<Self::BaseField>,
+    ) -> (Self::TraceLde<E>, TracePolyTable<E>) {
+        DefaultTraceLde::new(trace_info, main_trace, domain)
+    }
+
+    fn new_evaluator<'a, E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        air: &'a Self::Air,
+        aux_rand_elements: AuxTraceRandElements<E>,
+        composition_coefficients: ConstraintCompositionCoefficients<E>,
+    ) -> Self::ConstraintEvaluator<'a, E> {
+        DefaultConstraintEvaluator::new(air, aux_rand_elements, composition_coefficients)
+    }
+}
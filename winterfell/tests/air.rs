@@ -0,0 +1,656 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use common::{build_options, Blake3, FibAir, FibProver};
+use std::sync::Arc;
+use winterfell::{
+    crypto::{BatchMerkleProof, DefaultRandomCoin, Digest, Hasher},
+    math::{fields::f128::BaseElement, FieldElement, ToElements},
+    matrix::ColMatrix,
+    proof::OodFrame,
+    AcceptableOptions, Air, AirContext, Assertion, AuxTraceRandElements,
+    ConstraintCompositionCoefficients, DefaultConstraintEvaluator, DefaultTraceLde, Deserializable,
+    EvaluationFrame, MerkleInputs, PreprocessedTrace, ProofOptions, Prover, ProverError,
+    PublicInputsError, Serializable, StarkDomain, StarkProof, Trace, TraceInfo, TracePolyTable,
+    TraceTable, TransitionConstraintDegree, VerifierError,
+};
+
+mod common;
+
+// TESTS
+// ================================================================================================
+
+#[test]
+fn ood_offsets() {
+    let prover = WideOodFibProver(FibProver::new(build_options(false)));
+    let trace = prover.build_trace(32);
+    let result = prover.get_pub_inputs(&trace);
+    let proof = prover.prove(trace).unwrap();
+    let acceptable_options = AcceptableOptions::OptionSet(vec![build_options(false)]);
+    let verify = |proof: StarkProof| {
+        winterfell::verify::<WideOodFibAir, Blake3, DefaultRandomCoin<Blake3>>(
+            proof,
+            result,
+            &acceptable_options,
+        )
+    };
+
+    // the proof opens trace polynomials at all out-of-domain points of the AIR
+    let num_points = WIDE_OOD_OFFSETS.len();
+    let (ood_trace, ood_evaluations) =
+        proof.ood_frame.clone().parse::<BaseElement>(2, 0, 1).unwrap();
+    assert_eq!(2 * num_points, ood_trace.len());
+    assert_eq!(num_points, proof.num_ood_points());
+    assert_eq!(Ok(()), verify(proof.clone()));
+
+    // security level accounts for the additional out-of-domain points
+    assert_eq!(
+        proof.context.security_level_with_ood_points::<Blake3>(true, num_points),
+        proof.security_level::<Blake3>(true)
+    );
+
+    // the proof is rejected by a verifier expecting openings at the default points only
+    let result = winterfell::verify::<FibAir, Blake3, DefaultRandomCoin<Blake3>>(
+        proof.clone(),
+        result,
+        &acceptable_options,
+    );
+    assert!(matches!(result, Err(VerifierError::ProofDeserializationError(_))));
+
+    // an inconsistent opening at an additional point is rejected; the rows at additional points
+    // are used by transition constraints, and thus, constraint evaluations at z are inconsistent
+    let mut rows: Vec<Vec<BaseElement>> = (0..num_points)
+        .map(|k| ood_trace.iter().skip(k).step_by(num_points).copied().collect())
+        .collect();
+    rows[2][0] += BaseElement::ONE;
+    let mut tampered = proof.clone();
+    tampered.ood_frame = OodFrame::default();
+    tampered.ood_frame.set_trace_states(&rows);
+    tampered.ood_frame.set_constraint_evaluations(&ood_evaluations);
+    assert_eq!(Err(VerifierError::InconsistentOodConstraintEvaluations), verify(tampered));
+
+    // a proof with openings at the default points only is rejected as well
+    let prover = FibProver::<Blake3>::new(build_options(false));
+    let proof = prover.prove(prover.build_trace(16)).unwrap();
+    assert!(matches!(verify(proof), Err(VerifierError::ProofDeserializationError(_))));
+}
+
+#[test]
+fn linear_combination_assertion() {
+    let prover = LimbFibProver(FibProver::new(build_options(false)));
+    let trace = prover.build_trace(64);
+    let last_step = trace.length() - 1;
+    let limbs = (trace.get(0, last_step), trace.get(1, last_step));
+    let result = prover.get_pub_inputs(&trace);
+    assert_eq!(limbs.0 + limbs.1 * LIMB_SHIFT, result);
+    let proof = prover.prove(trace).unwrap();
+
+    let acceptable_options = AcceptableOptions::OptionSet(vec![build_options(false)]);
+    let verify = |result: BaseElement| {
+        winterfell::verify::<LimbFibAir, Blake3, DefaultRandomCoin<Blake3>>(
+            proof.clone(),
+            result,
+            &acceptable_options,
+        )
+    };
+    assert_eq!(Ok(()), verify(result));
+
+    // the proof is rejected for a value different from the combination of the last row
+    assert!(verify(limbs.1).is_err());
+    assert!(verify(limbs.0 * LIMB_SHIFT + limbs.1).is_err());
+}
+
+#[test]
+fn merkle_public_inputs() {
+    let prover = MerkleFibProver(FibProver::new(build_options(false)));
+    let trace = prover.build_trace(64);
+    let pub_inputs = prover.get_pub_inputs(&trace);
+    let proof = prover.prove(trace).unwrap();
+
+    let acceptable_options = AcceptableOptions::OptionSet(vec![build_options(false)]);
+    let verify = |pub_inputs: MerkleFibInputs| {
+        winterfell::verify::<MerkleFibAir, Blake3, DefaultRandomCoin<Blake3>>(
+            proof.clone(),
+            pub_inputs,
+            &acceptable_options,
+        )
+    };
+    assert_eq!(Ok(()), verify(pub_inputs.clone()));
+
+    // the inputs survive serialization, as they would when sent to a remote verifier
+    let parsed = MerkleFibInputs::read_from_bytes(&pub_inputs.to_bytes()).unwrap();
+    assert_eq!(Ok(()), verify(parsed));
+
+    // a revealed result which is not committed to by the root is rejected before the proof is
+    // verified
+    let mut revealed = pub_inputs.revealed().to_vec();
+    revealed[0].1[0] += BaseElement::ONE;
+    let merkle_proof = pub_inputs.proof().unwrap();
+    let tampered = MerkleInputs::from_parts(
+        *pub_inputs.root(),
+        pub_inputs.num_leaves(),
+        revealed,
+        Some(BatchMerkleProof {
+            leaves: merkle_proof.leaves.clone(),
+            nodes: merkle_proof.nodes.clone(),
+            depth: merkle_proof.depth,
+        }),
+    );
+    assert_eq!(
+        Err(VerifierError::InvalidPublicInputs(PublicInputsError::InvalidMerkleProof)),
+        verify(tampered)
+    );
+
+    // inputs which do not reveal the result are rejected by the AIR
+    let hidden =
+        MerkleInputs::from_parts(*pub_inputs.root(), pub_inputs.num_leaves(), vec![], None);
+    assert!(matches!(
+        verify(hidden),
+        Err(VerifierError::InvalidPublicInputs(PublicInputsError::InvalidValue(_)))
+    ));
+
+    // a different set of hidden leaves changes the root, and thus, the proof is rejected
+    let mut leaves = MERKLE_FIB_SECRETS.map(|value| vec![BaseElement::new(value)]).to_vec();
+    leaves.insert(0, pub_inputs.get(0).unwrap().to_vec());
+    leaves[1][0] += BaseElement::ONE;
+    let other = MerkleInputs::new(&leaves, &[0]).unwrap();
+    assert!(verify(other).is_err());
+}
+
+#[test]
+fn preprocessed_columns() {
+    let options = build_options(false);
+    let prover = RoundFibProver::new(options.clone());
+    let trace = prover.build_trace(BaseElement::new(3), 64);
+    let pub_inputs = prover.get_pub_inputs(&trace);
+
+    // the prover rejects traces with preprocessed columns which are not taken from a
+    // preprocessed trace
+    assert!(matches!(
+        prover.prove(trace.clone()),
+        Err(ProverError::InconsistentNumPreprocessedColumns(1, 0))
+    ));
+
+    // the preprocessed trace is built once and shared between proofs for different inputs
+    let air = RoundFibAir::new(trace.get_info(), pub_inputs, options.clone());
+    let preprocessed = Arc::new(PreprocessedTrace::new(&air, build_round_constants(64)));
+    let commitment = preprocessed.commitment();
+    let prover = prover.with_preprocessed(preprocessed);
+
+    let acceptable_options = AcceptableOptions::OptionSet(vec![options]);
+    let verify = |proof: StarkProof, pub_inputs, commitment| {
+        winterfell::verify_with_preprocessed_commitment::<
+            RoundFibAir,
+            Blake3,
+            DefaultRandomCoin<Blake3>,
+        >(proof, pub_inputs, &acceptable_options, &commitment)
+    };
+    for seed in [3u128, 5] {
+        let trace = prover.build_trace(BaseElement::new(seed), 64);
+        let pub_inputs = prover.get_pub_inputs(&trace);
+        let proof = prover.prove(trace).unwrap();
+        assert!(proof.preprocessed_queries.is_some());
+        assert_eq!(Ok(()), verify(proof.clone(), pub_inputs, commitment));
+
+        // queries of the preprocessed columns survive serialization
+        let parsed = StarkProof::from_bytes(&proof.to_bytes()).unwrap();
+        assert_eq!(Ok(()), verify(parsed, pub_inputs, commitment));
+
+        // the proof cannot be verified without the commitment to the preprocessed columns, or
+        // against a commitment to different preprocessed columns
+        assert_eq!(
+            Err(VerifierError::PreprocessedCommitmentMissing),
+            winterfell::verify::<RoundFibAir, Blake3, DefaultRandomCoin<Blake3>>(
+                proof.clone(),
+                pub_inputs,
+                &acceptable_options,
+            )
+        );
+        let wrong_commitment = Blake3::hash(&commitment.as_bytes());
+        assert!(verify(proof, pub_inputs, wrong_commitment).is_err());
+    }
+}
+
+// WIDE OUT-OF-DOMAIN FRAME
+// ================================================================================================
+
+/// Out-of-domain offsets used by [WideOodFibAir].
+const WIDE_OOD_OFFSETS: [usize; 4] = [0, 1, 3, 5];
+
+/// Fibonacci AIR which opens trace polynomials at additional out-of-domain points, and uses the
+/// additional rows of the evaluation frame to check that the rows 3 and 5 steps ahead of the
+/// current row are consistent with the current row.
+struct WideOodFibAir {
+    air: FibAir,
+    context: AirContext<BaseElement>,
+}
+
+impl Air for WideOodFibAir {
+    type BaseField = BaseElement;
+    type PublicInputs = BaseElement;
+
+    fn new(trace_info: TraceInfo, pub_inputs: BaseElement, options: ProofOptions) -> Self {
+        let air = FibAir::new(trace_info.clone(), pub_inputs, options.clone());
+        let degrees = vec![TransitionConstraintDegree::new(1); 6];
+        // constraints at the last 5 steps would refer to rows beyond the end of the trace
+        let context = AirContext::new(trace_info, degrees, 3, options)
+            .set_ood_offsets(WIDE_OOD_OFFSETS.to_vec())
+            .set_num_transition_exemptions(5);
+        Self { air, context }
+    }
+
+    fn context(&self) -> &AirContext<Self::BaseField> {
+        &self.context
+    }
+
+    fn evaluate_transition<E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        self.air.evaluate_transition(frame, periodic_values, &mut result[..2]);
+
+        // a step of the sequence maps (a, b) to (a + b, a + 2b); thus, 3 steps map (a, b) to
+        // (5a + 8b, 8a + 13b), and 5 steps map (a, b) to (34a + 55b, 55a + 89b)
+        let current = frame.current();
+        let (a, b) = (current[0], current[1]);
+        let coefficients = [(5u32, 8u32, 13u32), (34, 55, 89)];
+        for (i, (c0, c1, c2)) in coefficients.into_iter().enumerate() {
+            let (c0, c1, c2) = (E::from(c0), E::from(c1), E::from(c2));
+            let row = frame.row(i + 2);
+            result[2 + 2 * i] = row[0] - (c0 * a + c1 * b);
+            result[3 + 2 * i] = row[1] - (c1 * a + c2 * b);
+        }
+    }
+
+    fn get_assertions(&self) -> Vec<Assertion<Self::BaseField>> {
+        self.air.get_assertions()
+    }
+}
+
+/// Fibonacci prover for [WideOodFibAir].
+struct WideOodFibProver(FibProver<Blake3>);
+
+impl WideOodFibProver {
+    fn build_trace(&self, sequence_length: usize) -> TraceTable<BaseElement> {
+        self.0.build_trace(sequence_length)
+    }
+}
+
+impl Prover for WideOodFibProver {
+    type BaseField = BaseElement;
+    type Air = WideOodFibAir;
+    type Trace = TraceTable<BaseElement>;
+    type HashFn = Blake3;
+    type RandomCoin = DefaultRandomCoin<Blake3>;
+    type TraceLde<E: FieldElement<BaseField = Self::BaseField>> = DefaultTraceLde<E, Self::HashFn>;
+    type ConstraintEvaluator<'a, E: FieldElement<BaseField = Self::BaseField>> =
+        DefaultConstraintEvaluator<'a, Self::Air, E>;
+
+    fn get_pub_inputs(&self, trace: &Self::Trace) -> BaseElement {
+        self.0.get_pub_inputs(trace)
+    }
+
+    fn options(&self) -> &ProofOptions {
+        self.0.options()
+    }
+
+    fn new_trace_lde<E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        trace_info: &TraceInfo,
+        main_trace: &ColMatrix<Self::BaseField>,
+        domain: &StarkDomain<Self::BaseField>,
+    ) -> (Self::TraceLde<E>, TracePolyTable<E>) {
+        DefaultTraceLde::new(trace_info, main_trace, domain)
+    }
+
+    fn new_evaluator<'a, E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        air: &'a Self::Air,
+        aux_rand_elements: AuxTraceRandElements<E>,
+        composition_coefficients: ConstraintCompositionCoefficients<E>,
+    ) -> Self::ConstraintEvaluator<'a, E> {
+        DefaultConstraintEvaluator::new(air, aux_rand_elements, composition_coefficients)
+    }
+}
+
+// LINEAR COMBINATION ASSERTION
+// ================================================================================================
+
+/// Weight of the second column in the value asserted by [LimbFibAir].
+const LIMB_SHIFT: BaseElement = BaseElement::new(1 << 32);
+
+/// Fibonacci AIR which treats the last row of the trace as two limbs of a single value, and
+/// asserts this value instead of the value of the second column.
+struct LimbFibAir(FibAir);
+
+impl Air for LimbFibAir {
+    type BaseField = BaseElement;
+    type PublicInputs = BaseElement;
+
+    fn new(trace_info: TraceInfo, pub_inputs: BaseElement, options: ProofOptions) -> Self {
+        Self(FibAir::new(trace_info, pub_inputs, options))
+    }
+
+    fn context(&self) -> &AirContext<Self::BaseField> {
+        self.0.context()
+    }
+
+    fn evaluate_transition<E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        self.0.evaluate_transition(frame, periodic_values, result)
+    }
+
+    fn get_assertions(&self) -> Vec<Assertion<Self::BaseField>> {
+        let mut result = self.0.get_assertions();
+        let value = result.pop().unwrap().values()[0];
+        let terms = vec![(0, BaseElement::ONE), (1, LIMB_SHIFT)];
+        result.push(Assertion::linear_combination(terms, self.trace_length() - 1, value));
+        result
+    }
+}
+
+/// Fibonacci prover for [LimbFibAir].
+struct LimbFibProver(FibProver<Blake3>);
+
+impl LimbFibProver {
+    fn build_trace(&self, sequence_length: usize) -> TraceTable<BaseElement> {
+        self.0.build_trace(sequence_length)
+    }
+}
+
+impl Prover for LimbFibProver {
+    type BaseField = BaseElement;
+    type Air = LimbFibAir;
+    type Trace = TraceTable<BaseElement>;
+    type HashFn = Blake3;
+    type RandomCoin = DefaultRandomCoin<Blake3>;
+    type TraceLde<E: FieldElement<BaseField = Self::BaseField>> = DefaultTraceLde<E, Self::HashFn>;
+    type ConstraintEvaluator<'a, E: FieldElement<BaseField = Self::BaseField>> =
+        DefaultConstraintEvaluator<'a, Self::Air, E>;
+
+    fn get_pub_inputs(&self, trace: &Self::Trace) -> BaseElement {
+        let last_step = trace.length() - 1;
+        trace.get(0, last_step) + trace.get(1, last_step) * LIMB_SHIFT
+    }
+
+    fn options(&self) -> &ProofOptions {
+        self.0.options()
+    }
+
+    fn new_trace_lde<E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        trace_info: &TraceInfo,
+        main_trace: &ColMatrix<Self::BaseField>,
+        domain: &StarkDomain<Self::BaseField>,
+    ) -> (Self::TraceLde<E>, TracePolyTable<E>) {
+        DefaultTraceLde::new(trace_info, main_trace, domain)
+    }
+
+    fn new_evaluator<'a, E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        air: &'a Self::Air,
+        aux_rand_elements: AuxTraceRandElements<E>,
+        composition_coefficients: ConstraintCompositionCoefficients<E>,
+    ) -> Self::ConstraintEvaluator<'a, E> {
+        DefaultConstraintEvaluator::new(air, aux_rand_elements, composition_coefficients)
+    }
+}
+
+// MERKLE-IZED PUBLIC INPUTS
+// ================================================================================================
+
+/// Values of the leaves which [MerkleFibProver] commits to alongside the result, but does not
+/// reveal to the verifier.
+const MERKLE_FIB_SECRETS: [u128; 3] = [7, 11, 13];
+
+type MerkleFibInputs = MerkleInputs<BaseElement, Blake3>;
+
+/// Fibonacci AIR which receives the result as the first leaf of Merkle-ized public inputs.
+struct MerkleFibAir(FibAir);
+
+impl Air for MerkleFibAir {
+    type BaseField = BaseElement;
+    type PublicInputs = MerkleFibInputs;
+
+    fn new(trace_info: TraceInfo, pub_inputs: MerkleFibInputs, options: ProofOptions) -> Self {
+        let result = pub_inputs.get(0).expect("result must be revealed")[0];
+        Self(FibAir::new(trace_info, result, options))
+    }
+
+    fn validate_public_inputs(pub_inputs: &MerkleFibInputs) -> Result<(), PublicInputsError> {
+        match pub_inputs.get(0) {
+            Some(leaf) if leaf.len() == 1 => pub_inputs.verify(),
+            _ => Err(PublicInputsError::InvalidValue("result must be revealed".to_string())),
+        }
+    }
+
+    fn context(&self) -> &AirContext<Self::BaseField> {
+        self.0.context()
+    }
+
+    fn evaluate_transition<E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        self.0.evaluate_transition(frame, periodic_values, result)
+    }
+
+    fn get_assertions(&self) -> Vec<Assertion<Self::BaseField>> {
+        self.0.get_assertions()
+    }
+}
+
+/// Fibonacci prover for [MerkleFibAir].
+struct MerkleFibProver(FibProver<Blake3>);
+
+impl MerkleFibProver {
+    fn build_trace(&self, sequence_length: usize) -> TraceTable<BaseElement> {
+        self.0.build_trace(sequence_length)
+    }
+}
+
+impl Prover for MerkleFibProver {
+    type BaseField = BaseElement;
+    type Air = MerkleFibAir;
+    type Trace = TraceTable<BaseElement>;
+    type HashFn = Blake3;
+    type RandomCoin = DefaultRandomCoin<Blake3>;
+    type TraceLde<E: FieldElement<BaseField = Self::BaseField>> = DefaultTraceLde<E, Self::HashFn>;
+    type ConstraintEvaluator<'a, E: FieldElement<BaseField = Self::BaseField>> =
+        DefaultConstraintEvaluator<'a, Self::Air, E>;
+
+    fn get_pub_inputs(&self, trace: &Self::Trace) -> MerkleFibInputs {
+        let mut leaves = MERKLE_FIB_SECRETS.map(|value| vec![BaseElement::new(value)]).to_vec();
+        leaves.insert(0, vec![self.0.get_pub_inputs(trace)]);
+        MerkleInputs::new(&leaves, &[0]).unwrap()
+    }
+
+    fn options(&self) -> &ProofOptions {
+        self.0.options()
+    }
+
+    fn new_trace_lde<E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        trace_info: &TraceInfo,
+        main_trace: &ColMatrix<Self::BaseField>,
+        domain: &StarkDomain<Self::BaseField>,
+    ) -> (Self::TraceLde<E>, TracePolyTable<E>) {
+        DefaultTraceLde::new(trace_info, main_trace, domain)
+    }
+
+    fn new_evaluator<'a, E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        air: &'a Self::Air,
+        aux_rand_elements: AuxTraceRandElements<E>,
+        composition_coefficients: ConstraintCompositionCoefficients<E>,
+    ) -> Self::ConstraintEvaluator<'a, E> {
+        DefaultConstraintEvaluator::new(air, aux_rand_elements, composition_coefficients)
+    }
+}
+
+// PREPROCESSED COLUMNS
+// ================================================================================================
+
+/// Returns values of the preprocessed column of [RoundFibAir] for a trace of the specified length.
+fn build_round_constants(trace_length: usize) -> ColMatrix<BaseElement> {
+    let constants = (0..trace_length).map(|i| BaseElement::new((i * i + 7) as u128)).collect();
+    ColMatrix::new(vec![constants])
+}
+
+/// Public inputs of [RoundFibAir].
+#[derive(Clone, Copy, Debug)]
+struct RoundFibInputs {
+    seed: BaseElement,
+    result: BaseElement,
+}
+
+impl ToElements<BaseElement> for RoundFibInputs {
+    fn to_elements(&self) -> Vec<BaseElement> {
+        vec![self.seed, self.result]
+    }
+}
+
+/// Fibonacci-like AIR which adds a round constant taken from a preprocessed column to every term
+/// of the sequence, i.e., a' = b, b' = a + b + c, where c is the last column of the trace.
+struct RoundFibAir {
+    context: AirContext<BaseElement>,
+    pub_inputs: RoundFibInputs,
+}
+
+impl Air for RoundFibAir {
+    type BaseField = BaseElement;
+    type PublicInputs = RoundFibInputs;
+
+    fn new(trace_info: TraceInfo, pub_inputs: RoundFibInputs, options: ProofOptions) -> Self {
+        let degrees = vec![TransitionConstraintDegree::new(1); 2];
+        let context =
+            AirContext::new(trace_info, degrees, 3, options).set_num_preprocessed_columns(1);
+        Self {
+            context,
+            pub_inputs,
+        }
+    }
+
+    fn context(&self) -> &AirContext<Self::BaseField> {
+        &self.context
+    }
+
+    fn evaluate_transition<E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        _periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        let (current, next) = (frame.current(), frame.next());
+        result[0] = next[0] - current[1];
+        result[1] = next[1] - (current[0] + current[1] + current[2]);
+    }
+
+    fn get_assertions(&self) -> Vec<Assertion<Self::BaseField>> {
+        let last_step = self.trace_length() - 1;
+        vec![
+            Assertion::single(0, 0, BaseElement::ONE),
+            Assertion::single(1, 0, self.pub_inputs.seed),
+            Assertion::single(1, last_step, self.pub_inputs.result),
+        ]
+    }
+}
+
+/// Prover for [RoundFibAir] which takes the preprocessed column from a shared preprocessed trace.
+struct RoundFibProver {
+    options: ProofOptions,
+    preprocessed: Option<Arc<PreprocessedTrace<BaseElement, Blake3>>>,
+}
+
+impl RoundFibProver {
+    fn new(options: ProofOptions) -> Self {
+        Self {
+            options,
+            preprocessed: None,
+        }
+    }
+
+    fn with_preprocessed(self, preprocessed: Arc<PreprocessedTrace<BaseElement, Blake3>>) -> Self {
+        Self {
+            preprocessed: Some(preprocessed),
+            ..self
+        }
+    }
+
+    fn build_trace(&self, seed: BaseElement, trace_length: usize) -> TraceTable<BaseElement> {
+        let constants = build_round_constants(trace_length);
+        let mut trace = TraceTable::new(3, trace_length);
+        trace.fill(
+            |state| {
+                state[0] = BaseElement::ONE;
+                state[1] = seed;
+                state[2] = constants.get(0, 0);
+            },
+            |step, state| {
+                let next = state[0] + state[1] + state[2];
+                state[0] = state[1];
+                state[1] = next;
+                state[2] = constants.get(0, step + 1);
+            },
+        );
+        trace
+    }
+}
+
+impl Prover for RoundFibProver {
+    type BaseField = BaseElement;
+    type Air = RoundFibAir;
+    type Trace = TraceTable<BaseElement>;
+    type HashFn = Blake3;
+    type RandomCoin = DefaultRandomCoin<Blake3>;
+    type TraceLde<E: FieldElement<BaseField = Self::BaseField>> = DefaultTraceLde<E, Self::HashFn>;
+    type ConstraintEvaluator<'a, E: FieldElement<BaseField = Self::BaseField>> =
+        DefaultConstraintEvaluator<'a, Self::Air, E>;
+
+    fn get_pub_inputs(&self, trace: &Self::Trace) -> RoundFibInputs {
+        RoundFibInputs {
+            seed: trace.get(1, 0),
+            result: trace.get(1, trace.length() - 1),
+        }
+    }
+
+    fn options(&self) -> &ProofOptions {
+        &self.options
+    }
+
+    fn new_trace_lde<E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        trace_info: &TraceInfo,
+        main_trace: &ColMatrix<Self::BaseField>,
+        domain: &StarkDomain<Self::BaseField>,
+    ) -> (Self::TraceLde<E>, TracePolyTable<E>) {
+        match &self.preprocessed {
+            Some(preprocessed) => DefaultTraceLde::with_preprocessed(
+                trace_info,
+                main_trace,
+                domain,
+                preprocessed.clone(),
+            ),
+            None => DefaultTraceLde::new(trace_info, main_trace, domain),
+        }
+    }
+
+    fn new_evaluator<'a, E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        air: &'a Self::Air,
+        aux_rand_elements: AuxTraceRandElements<E>,
+        composition_coefficients: ConstraintCompositionCoefficients<E>,
+    ) -> Self::ConstraintEvaluator<'a, E> {
+        DefaultConstraintEvaluator::new(air, aux_rand_elements, composition_coefficients)
+    }
+}
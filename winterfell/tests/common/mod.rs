@@ -0,0 +1,284 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+//! Fibonacci AIR and prover shared by integration tests; not every test target uses all of the
+//! items defined here.
+
+#![allow(dead_code)]
+
+use core::marker::PhantomData;
+use winterfell::{
+    crypto::{hashers::Blake3_256, DefaultRandomCoin, ElementHasher, RandomCoin, Transcript},
+    math::{fields::f128::BaseElement, ExtensibleField, FieldElement, StarkField},
+    matrix::ColMatrix,
+    AcceptableOptions, Air, AirContext, Assertion, AuxTraceRandElements, CompositionPoly,
+    CompositionPolyTrace, ConstraintCompositionCoefficients, DefaultConstraintEvaluator,
+    DefaultTraceLde, EvaluationFrame, FieldExtension, NumaPartitioning, ProofOptions, Prover,
+    ProverFactory, ProverHooks, StarkDomain, Trace, TraceInfo, TracePolyTable, TraceTable,
+    TransitionConstraintDegree,
+};
+
+pub type Blake3 = Blake3_256<BaseElement>;
+
+pub const TRACE_WIDTH: usize = 2;
+
+/// Returns proof options used by most of the tests.
+pub fn build_options(use_extension_field: bool) -> ProofOptions {
+    let extension = if use_extension_field {
+        FieldExtension::Quadratic
+    } else {
+        FieldExtension::None
+    };
+    ProofOptions::new(28, 8, 0, extension, 4, 7)
+}
+
+// FIBONACCI AIR
+// ================================================================================================
+
+/// AIR for a Fibonacci sequence in which each row advances the sequence by 2 terms; the public
+/// input is the last term of the sequence.
+pub struct FibAir<B: StarkField = BaseElement> {
+    context: AirContext<B>,
+    result: B,
+}
+
+impl<B> Air for FibAir<B>
+where
+    B: StarkField + ExtensibleField<2> + ExtensibleField<3>,
+{
+    type BaseField = B;
+    type PublicInputs = B;
+
+    fn new(trace_info: TraceInfo, result: B, options: ProofOptions) -> Self {
+        let degrees = vec![TransitionConstraintDegree::new(1), TransitionConstraintDegree::new(1)];
+        assert_eq!(TRACE_WIDTH, trace_info.width());
+        FibAir {
+            context: AirContext::new(trace_info, degrees, 3, options),
+            result,
+        }
+    }
+
+    fn context(&self) -> &AirContext<Self::BaseField> {
+        &self.context
+    }
+
+    fn evaluate_transition<E: FieldElement + From<Self::BaseField>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        _periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        let current = frame.current();
+        let next = frame.next();
+        result[0] = next[0] - (current[0] + current[1]);
+        result[1] = next[1] - (current[1] + next[0]);
+    }
+
+    fn get_assertions(&self) -> Vec<Assertion<Self::BaseField>> {
+        let last_step = self.trace_length() - 1;
+        vec![
+            Assertion::single(0, 0, B::ONE),
+            Assertion::single(1, 0, B::ONE),
+            Assertion::single(1, last_step, self.result),
+        ]
+    }
+}
+
+// FIBONACCI PROVER
+// ================================================================================================
+
+pub struct FibProver<H: ElementHasher, R = DefaultRandomCoin<H>> {
+    options: ProofOptions,
+    deterministic: bool,
+    self_verification: Option<AcceptableOptions>,
+    numa_partitioning: Option<NumaPartitioning>,
+    _hasher: PhantomData<(H, R)>,
+}
+
+impl<H: ElementHasher, R> FibProver<H, R> {
+    pub fn new(options: ProofOptions) -> Self {
+        Self {
+            options,
+            deterministic: false,
+            self_verification: None,
+            numa_partitioning: None,
+            _hasher: PhantomData,
+        }
+    }
+
+    /// Returns this prover configured to generate byte-identical proofs for identical traces.
+    pub fn deterministic(mut self) -> Self {
+        self.deterministic = true;
+        self
+    }
+
+    /// Returns this prover configured to verify every generated proof against the specified
+    /// options before returning it.
+    pub fn with_self_verification(mut self, options: AcceptableOptions) -> Self {
+        self.self_verification = Some(options);
+        self
+    }
+
+    /// Returns this prover configured to partition its work among the specified NUMA nodes.
+    pub fn with_numa_partitioning(mut self, partitioning: NumaPartitioning) -> Self {
+        self.numa_partitioning = Some(partitioning);
+        self
+    }
+
+    /// Builds an execution trace for computing a Fibonacci sequence of the specified length.
+    pub fn build_trace(&self, sequence_length: usize) -> TraceTable<H::BaseField> {
+        assert!(sequence_length.is_power_of_two(), "sequence length must be a power of 2");
+
+        let mut trace = TraceTable::new(TRACE_WIDTH, sequence_length / 2);
+        trace.fill(
+            |state| {
+                state[0] = H::BaseField::ONE;
+                state[1] = H::BaseField::ONE;
+            },
+            |_, state| {
+                state[0] += state[1];
+                state[1] += state[0];
+            },
+        );
+        trace
+    }
+}
+
+impl<H, R> Prover for FibProver<H, R>
+where
+    H: ElementHasher,
+    H::BaseField: ExtensibleField<2> + ExtensibleField<3> + 'static,
+    R: RandomCoin<BaseField = H::BaseField, Hasher = H>,
+{
+    type BaseField = H::BaseField;
+    type Air = FibAir<H::BaseField>;
+    type Trace = TraceTable<H::BaseField>;
+    type HashFn = H;
+    type RandomCoin = R;
+    type TraceLde<E: FieldElement<BaseField = Self::BaseField>> = DefaultTraceLde<E, Self::HashFn>;
+    type ConstraintEvaluator<'a, E: FieldElement<BaseField = Self::BaseField>> =
+        DefaultConstraintEvaluator<'a, Self::Air, E>;
+
+    fn get_pub_inputs(&self, trace: &Self::Trace) -> H::BaseField {
+        trace.get(1, trace.length() - 1)
+    }
+
+    fn options(&self) -> &ProofOptions {
+        &self.options
+    }
+
+    fn is_deterministic(&self) -> bool {
+        self.deterministic
+    }
+
+    fn self_verification_options(&self) -> Option<&AcceptableOptions> {
+        self.self_verification.as_ref()
+    }
+
+    fn numa_partitioning(&self) -> Option<&NumaPartitioning> {
+        self.numa_partitioning.as_ref()
+    }
+
+    fn new_trace_lde<E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        trace_info: &TraceInfo,
+        main_trace: &ColMatrix<Self::BaseField>,
+        domain: &StarkDomain<Self::BaseField>,
+    ) -> (Self::TraceLde<E>, TracePolyTable<E>) {
+        DefaultTraceLde::new(trace_info, main_trace, domain)
+    }
+
+    fn new_trace_lde_from_polys<E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        trace_info: &TraceInfo,
+        _main_trace: &ColMatrix<Self::BaseField>,
+        main_trace_polys: ColMatrix<Self::BaseField>,
+        domain: &StarkDomain<Self::BaseField>,
+    ) -> (Self::TraceLde<E>, TracePolyTable<E>) {
+        DefaultTraceLde::from_polys(trace_info, main_trace_polys, domain)
+    }
+
+    fn new_evaluator<'a, E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        air: &'a Self::Air,
+        aux_rand_elements: AuxTraceRandElements<E>,
+        composition_coefficients: ConstraintCompositionCoefficients<E>,
+    ) -> Self::ConstraintEvaluator<'a, E> {
+        DefaultConstraintEvaluator::new(air, aux_rand_elements, composition_coefficients)
+    }
+}
+
+// PROVER FACTORY
+// ================================================================================================
+
+/// Builds [FibProver] instances for hash functions selected at runtime.
+pub struct FibProverFactory(pub ProofOptions);
+
+impl ProverFactory for FibProverFactory {
+    type BaseField = BaseElement;
+    type Trace = TraceTable<BaseElement>;
+    type Prover<H: ElementHasher<BaseField = BaseElement>> = FibProver<H>;
+
+    fn build_prover<H: ElementHasher<BaseField = BaseElement>>(&self) -> FibProver<H> {
+        FibProver::new(self.0.clone())
+    }
+}
+
+// RECORDING HOOKS
+// ================================================================================================
+
+/// Prover hooks which record the order of proof generation phases and the data passed to them.
+#[derive(Default)]
+pub struct RecordingHooks {
+    pub phases: Vec<&'static str>,
+    pub num_trace_polys: usize,
+    pub num_ood_rows: usize,
+    pub query_positions: Vec<usize>,
+    pub transcript: Option<Transcript>,
+}
+
+impl ProverHooks<BaseElement> for RecordingHooks {
+    fn on_trace_committed<E: FieldElement<BaseField = BaseElement>>(
+        &mut self,
+        trace_polys: &TracePolyTable<E>,
+    ) {
+        self.phases.push("trace");
+        self.num_trace_polys = trace_polys.main_trace_polys().count();
+    }
+
+    fn on_constraints_evaluated<E: FieldElement<BaseField = BaseElement>>(
+        &mut self,
+        _evaluations: &CompositionPolyTrace<E>,
+    ) {
+        self.phases.push("constraints");
+    }
+
+    fn on_constraints_committed<E: FieldElement<BaseField = BaseElement>>(
+        &mut self,
+        _composition_poly: &CompositionPoly<E>,
+    ) {
+        self.phases.push("composition");
+    }
+
+    fn on_ood_frame<E: FieldElement<BaseField = BaseElement>>(
+        &mut self,
+        _z: E,
+        ood_trace_states: &[Vec<E>],
+        _ood_evaluations: &[E],
+    ) {
+        self.phases.push("ood");
+        self.num_ood_rows = ood_trace_states.len();
+    }
+
+    fn on_query_positions(&mut self, positions: &[usize]) {
+        self.phases.push("queries");
+        self.query_positions = positions.to_vec();
+    }
+
+    fn on_transcript(&mut self, transcript: &Transcript) {
+        self.phases.push("transcript");
+        self.transcript = Some(transcript.clone());
+    }
+}
@@ -0,0 +1,504 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use rand_utils::rand_vector;
+use winterfell::{
+    crypto::{hashers::Rp64_256, DefaultRandomCoin},
+    gadgets::{
+        bitwise::{BitwiseColumns, BitwiseOp, BitwiseOperation},
+        keccak::{self, KeccakColumns, KeccakState},
+        running_hash::{self, RunningHashColumn, Word},
+    },
+    math::{fields::f64::BaseElement, FieldElement, StarkField, ToElements},
+    matrix::ColMatrix,
+    verify, AcceptableOptions, Air, AirContext, Assertion, AuxTraceRandElements,
+    ConstraintCompositionCoefficients, DefaultConstraintEvaluator, DefaultTraceLde,
+    EvaluationFrame, FieldExtension, ProofOptions, Prover, StarkDomain, Trace, TraceInfo,
+    TracePolyTable, TraceTable,
+};
+
+// TESTS
+// ================================================================================================
+
+#[test]
+fn running_hash_air() {
+    let data = rand_vector::<BaseElement>(21);
+    let prover = RunningHashProver::new(build_options(FieldExtension::None));
+    let trace = prover.build_trace(&data);
+    let inputs = prover.get_pub_inputs(&trace);
+    assert_eq!(RunningHashInputs::new(&data), inputs);
+    let proof = prover.prove(trace).unwrap();
+
+    let acceptable = AcceptableOptions::OptionSet(vec![build_options(FieldExtension::None)]);
+    let result = verify::<RunningHashAir, Rp64_256, DefaultRandomCoin<Rp64_256>>(
+        proof.clone(),
+        inputs.clone(),
+        &acceptable,
+    );
+    assert!(result.is_ok());
+
+    // the proof does not verify against a different digest or a different number of elements
+    let mut other_data = data.clone();
+    other_data[20] += BaseElement::ONE;
+    let result = verify::<RunningHashAir, Rp64_256, DefaultRandomCoin<Rp64_256>>(
+        proof.clone(),
+        RunningHashInputs::new(&other_data),
+        &acceptable,
+    );
+    assert!(result.is_err());
+
+    let result = verify::<RunningHashAir, Rp64_256, DefaultRandomCoin<Rp64_256>>(
+        proof,
+        RunningHashInputs {
+            num_elements: 20,
+            ..inputs
+        },
+        &acceptable,
+    );
+    assert!(result.is_err());
+}
+
+#[test]
+fn bitwise_air() {
+    let operations = build_bitwise_operations();
+    let prover = BitwiseProver::new(build_options(FieldExtension::None), operations.clone());
+    let trace = prover.build_trace();
+    let proof = prover.prove(trace).unwrap();
+
+    let acceptable = AcceptableOptions::OptionSet(vec![build_options(FieldExtension::None)]);
+    let verify = |operations: Vec<BitwiseOperation>| {
+        verify::<BitwiseAir, Rp64_256, DefaultRandomCoin<Rp64_256>>(
+            proof.clone(),
+            BitwiseInputs(operations),
+            &acceptable,
+        )
+    };
+    assert!(verify(operations.clone()).is_ok());
+
+    // the proof does not verify against different operations
+    let mut other = operations.clone();
+    other[1].op = BitwiseOp::Or;
+    assert!(verify(other).is_err());
+
+    let mut other = operations;
+    other[2].b ^= 1 << 31;
+    assert!(verify(other).is_err());
+}
+
+#[test]
+fn keccak_air() {
+    let inputs = build_keccak_inputs(1);
+    let prover = KeccakProver::new(build_options(FieldExtension::None), inputs.clone());
+    let trace = prover.build_trace();
+    let proof = prover.prove(trace).unwrap();
+
+    let acceptable = AcceptableOptions::OptionSet(vec![build_options(FieldExtension::None)]);
+    let verify = |inputs: Vec<KeccakState>| {
+        verify::<KeccakAir, Rp64_256, DefaultRandomCoin<Rp64_256>>(
+            proof.clone(),
+            KeccakInputs(inputs),
+            &acceptable,
+        )
+    };
+    assert!(verify(inputs.clone()).is_ok());
+
+    // the proof does not verify against a different input
+    let mut other = inputs;
+    other[0][3] ^= 1 << 40;
+    assert!(verify(other).is_err());
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+fn build_options(field_extension: FieldExtension) -> ProofOptions {
+    ProofOptions::new(8, 16, 0, field_extension, 2, 7)
+}
+
+fn build_bitwise_operations() -> Vec<BitwiseOperation> {
+    let mut operations = vec![
+        BitwiseOperation::new(BitwiseOp::And, 0xdead_beef, 0x0f0f_f0f0),
+        BitwiseOperation::new(BitwiseOp::Xor, 0x1234_5678, 0xffff_0000),
+        BitwiseOperation::new(BitwiseOp::Or, 0x8000_0001, 0x7fff_fffe),
+    ];
+    operations.extend(BitwiseOperation::split_u64(BitwiseOp::Xor, u64::MAX, 0x0123_4567_89ab_cdef));
+    operations
+}
+
+fn build_keccak_inputs(num_permutations: usize) -> Vec<KeccakState> {
+    (0..num_permutations)
+        .map(|i| {
+            core::array::from_fn(|lane| {
+                (lane as u64 + 1).wrapping_mul(0x9e37_79b9_7f4a_7c15) ^ i as u64
+            })
+        })
+        .collect()
+}
+
+// RUNNING HASH AIR
+// ================================================================================================
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct RunningHashInputs {
+    num_elements: usize,
+    digest: Word,
+}
+
+impl RunningHashInputs {
+    fn new(data: &[BaseElement]) -> Self {
+        Self {
+            num_elements: data.len(),
+            digest: running_hash::hash_data(data),
+        }
+    }
+}
+
+impl ToElements<BaseElement> for RunningHashInputs {
+    fn to_elements(&self) -> Vec<BaseElement> {
+        let mut result = vec![BaseElement::new(self.num_elements as u64)];
+        result.extend_from_slice(&self.digest);
+        result
+    }
+}
+
+/// An AIR which only binds a stream of data to its digest.
+struct RunningHashAir {
+    context: AirContext<BaseElement>,
+    inputs: RunningHashInputs,
+    gadget: RunningHashColumn,
+}
+
+impl Air for RunningHashAir {
+    type BaseField = BaseElement;
+    type PublicInputs = RunningHashInputs;
+
+    fn new(trace_info: TraceInfo, pub_inputs: RunningHashInputs, options: ProofOptions) -> Self {
+        let degrees = RunningHashColumn::constraint_degrees();
+        let num_assertions = RunningHashColumn::num_assertions(pub_inputs.num_elements);
+        RunningHashAir {
+            context: AirContext::new(trace_info, degrees, num_assertions, options),
+            inputs: pub_inputs,
+            gadget: RunningHashColumn::new(0),
+        }
+    }
+
+    fn context(&self) -> &AirContext<Self::BaseField> {
+        &self.context
+    }
+
+    fn evaluate_transition<E: FieldElement + From<Self::BaseField>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        self.gadget.evaluate_transition(frame, periodic_values, result);
+    }
+
+    fn get_assertions(&self) -> Vec<Assertion<Self::BaseField>> {
+        self.gadget.get_assertions(
+            self.inputs.num_elements,
+            &self.inputs.digest,
+            self.trace_length(),
+        )
+    }
+
+    fn get_periodic_column_values(&self) -> Vec<Vec<Self::BaseField>> {
+        RunningHashColumn::get_periodic_column_values()
+    }
+}
+
+struct RunningHashProver {
+    options: ProofOptions,
+}
+
+impl RunningHashProver {
+    fn new(options: ProofOptions) -> Self {
+        Self { options }
+    }
+
+    fn build_trace(&self, data: &[BaseElement]) -> TraceTable<BaseElement> {
+        let trace_length = RunningHashColumn::min_trace_length(data.len());
+        TraceTable::init(RunningHashColumn::build_trace_columns(data, trace_length))
+    }
+}
+
+impl Prover for RunningHashProver {
+    type BaseField = BaseElement;
+    type Air = RunningHashAir;
+    type Trace = TraceTable<BaseElement>;
+    type HashFn = Rp64_256;
+    type RandomCoin = DefaultRandomCoin<Self::HashFn>;
+    type TraceLde<E: FieldElement<BaseField = Self::BaseField>> = DefaultTraceLde<E, Self::HashFn>;
+    type ConstraintEvaluator<'a, E: FieldElement<BaseField = Self::BaseField>> =
+        DefaultConstraintEvaluator<'a, Self::Air, E>;
+
+    fn get_pub_inputs(&self, trace: &Self::Trace) -> RunningHashInputs {
+        let gadget = RunningHashColumn::new(0);
+        let state_start = gadget.state_columns().start;
+        let last_step = trace.length() - 1;
+        let num_elements = trace.get(state_start + Rp64_256::CAPACITY_RANGE.start, 0);
+        let digest_start = state_start + Rp64_256::DIGEST_RANGE.start;
+        RunningHashInputs {
+            num_elements: num_elements.as_int() as usize,
+            digest: core::array::from_fn(|i| trace.get(digest_start + i, last_step)),
+        }
+    }
+
+    fn options(&self) -> &ProofOptions {
+        &self.options
+    }
+
+    fn new_trace_lde<E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        trace_info: &TraceInfo,
+        main_trace: &ColMatrix<Self::BaseField>,
+        domain: &StarkDomain<Self::BaseField>,
+    ) -> (Self::TraceLde<E>, TracePolyTable<E>) {
+        DefaultTraceLde::new(trace_info, main_trace, domain)
+    }
+
+    fn new_evaluator<'a, E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        air: &'a Self::Air,
+        aux_rand_elements: AuxTraceRandElements<E>,
+        composition_coefficients: ConstraintCompositionCoefficients<E>,
+    ) -> Self::ConstraintEvaluator<'a, E> {
+        DefaultConstraintEvaluator::new(air, aux_rand_elements, composition_coefficients)
+    }
+}
+
+// BITWISE AIR
+// ================================================================================================
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct BitwiseInputs(Vec<BitwiseOperation>);
+
+impl ToElements<BaseElement> for BitwiseInputs {
+    fn to_elements(&self) -> Vec<BaseElement> {
+        self.0
+            .iter()
+            .flat_map(|operation| {
+                let op = match operation.op {
+                    BitwiseOp::And => 0,
+                    BitwiseOp::Xor => 1,
+                    BitwiseOp::Or => 2,
+                };
+                [op, operation.a, operation.b].map(BaseElement::from)
+            })
+            .collect()
+    }
+}
+
+/// An AIR which only performs a list of public bitwise operations.
+struct BitwiseAir {
+    context: AirContext<BaseElement>,
+    inputs: BitwiseInputs,
+    gadget: BitwiseColumns,
+}
+
+impl Air for BitwiseAir {
+    type BaseField = BaseElement;
+    type PublicInputs = BitwiseInputs;
+
+    fn new(trace_info: TraceInfo, pub_inputs: BitwiseInputs, options: ProofOptions) -> Self {
+        let degrees = BitwiseColumns::constraint_degrees();
+        let num_assertions = 5 * pub_inputs.0.len();
+        BitwiseAir {
+            context: AirContext::new(trace_info, degrees, num_assertions, options),
+            inputs: pub_inputs,
+            gadget: BitwiseColumns::new(0),
+        }
+    }
+
+    fn context(&self) -> &AirContext<Self::BaseField> {
+        &self.context
+    }
+
+    fn evaluate_transition<E: FieldElement + From<Self::BaseField>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        self.gadget.evaluate_transition(frame, periodic_values, result);
+    }
+
+    fn get_assertions(&self) -> Vec<Assertion<Self::BaseField>> {
+        self.gadget.get_assertions(&self.inputs.0, self.trace_length())
+    }
+
+    fn get_periodic_column_values(&self) -> Vec<Vec<Self::BaseField>> {
+        BitwiseColumns::get_periodic_column_values()
+    }
+}
+
+struct BitwiseProver {
+    options: ProofOptions,
+    operations: Vec<BitwiseOperation>,
+}
+
+impl BitwiseProver {
+    fn new(options: ProofOptions, operations: Vec<BitwiseOperation>) -> Self {
+        Self {
+            options,
+            operations,
+        }
+    }
+
+    fn build_trace(&self) -> TraceTable<BaseElement> {
+        let trace_length = BitwiseColumns::min_trace_length(self.operations.len());
+        TraceTable::init(BitwiseColumns::build_trace_columns(&self.operations, trace_length))
+    }
+}
+
+impl Prover for BitwiseProver {
+    type BaseField = BaseElement;
+    type Air = BitwiseAir;
+    type Trace = TraceTable<BaseElement>;
+    type HashFn = Rp64_256;
+    type RandomCoin = DefaultRandomCoin<Self::HashFn>;
+    type TraceLde<E: FieldElement<BaseField = Self::BaseField>> = DefaultTraceLde<E, Self::HashFn>;
+    type ConstraintEvaluator<'a, E: FieldElement<BaseField = Self::BaseField>> =
+        DefaultConstraintEvaluator<'a, Self::Air, E>;
+
+    fn get_pub_inputs(&self, _trace: &Self::Trace) -> BitwiseInputs {
+        BitwiseInputs(self.operations.clone())
+    }
+
+    fn options(&self) -> &ProofOptions {
+        &self.options
+    }
+
+    fn new_trace_lde<E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        trace_info: &TraceInfo,
+        main_trace: &ColMatrix<Self::BaseField>,
+        domain: &StarkDomain<Self::BaseField>,
+    ) -> (Self::TraceLde<E>, TracePolyTable<E>) {
+        DefaultTraceLde::new(trace_info, main_trace, domain)
+    }
+
+    fn new_evaluator<'a, E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        air: &'a Self::Air,
+        aux_rand_elements: AuxTraceRandElements<E>,
+        composition_coefficients: ConstraintCompositionCoefficients<E>,
+    ) -> Self::ConstraintEvaluator<'a, E> {
+        DefaultConstraintEvaluator::new(air, aux_rand_elements, composition_coefficients)
+    }
+}
+
+// KECCAK AIR
+// ================================================================================================
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct KeccakInputs(Vec<KeccakState>);
+
+impl ToElements<BaseElement> for KeccakInputs {
+    fn to_elements(&self) -> Vec<BaseElement> {
+        self.0
+            .iter()
+            .flatten()
+            .flat_map(|&lane| [lane as u32, (lane >> 32) as u32].map(BaseElement::from))
+            .collect()
+    }
+}
+
+/// An AIR which only computes Keccak permutations of public states.
+struct KeccakAir {
+    context: AirContext<BaseElement>,
+    inputs: KeccakInputs,
+    gadget: KeccakColumns,
+}
+
+impl Air for KeccakAir {
+    type BaseField = BaseElement;
+    type PublicInputs = KeccakInputs;
+
+    fn new(trace_info: TraceInfo, pub_inputs: KeccakInputs, options: ProofOptions) -> Self {
+        let degrees = KeccakColumns::constraint_degrees();
+        let num_assertions = 4 * keccak::NUM_LANES * pub_inputs.0.len();
+        KeccakAir {
+            context: AirContext::new(trace_info, degrees, num_assertions, options),
+            inputs: pub_inputs,
+            gadget: KeccakColumns::new(0),
+        }
+    }
+
+    fn context(&self) -> &AirContext<Self::BaseField> {
+        &self.context
+    }
+
+    fn evaluate_transition<E: FieldElement + From<Self::BaseField>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        self.gadget.evaluate_transition(frame, periodic_values, result);
+    }
+
+    fn get_assertions(&self) -> Vec<Assertion<Self::BaseField>> {
+        self.gadget.get_assertions(&self.inputs.0, self.trace_length())
+    }
+
+    fn get_periodic_column_values(&self) -> Vec<Vec<Self::BaseField>> {
+        KeccakColumns::get_periodic_column_values()
+    }
+}
+
+struct KeccakProver {
+    options: ProofOptions,
+    inputs: Vec<KeccakState>,
+}
+
+impl KeccakProver {
+    fn new(options: ProofOptions, inputs: Vec<KeccakState>) -> Self {
+        Self { options, inputs }
+    }
+
+    fn build_trace(&self) -> TraceTable<BaseElement> {
+        let trace_length = KeccakColumns::min_trace_length(self.inputs.len());
+        TraceTable::init(KeccakColumns::build_trace_columns(&self.inputs, trace_length))
+    }
+}
+
+impl Prover for KeccakProver {
+    type BaseField = BaseElement;
+    type Air = KeccakAir;
+    type Trace = TraceTable<BaseElement>;
+    type HashFn = Rp64_256;
+    type RandomCoin = DefaultRandomCoin<Self::HashFn>;
+    type TraceLde<E: FieldElement<BaseField = Self::BaseField>> = DefaultTraceLde<E, Self::HashFn>;
+    type ConstraintEvaluator<'a, E: FieldElement<BaseField = Self::BaseField>> =
+        DefaultConstraintEvaluator<'a, Self::Air, E>;
+
+    fn get_pub_inputs(&self, _trace: &Self::Trace) -> KeccakInputs {
+        KeccakInputs(self.inputs.clone())
+    }
+
+    fn options(&self) -> &ProofOptions {
+        &self.options
+    }
+
+    fn new_trace_lde<E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        trace_info: &TraceInfo,
+        main_trace: &ColMatrix<Self::BaseField>,
+        domain: &StarkDomain<Self::BaseField>,
+    ) -> (Self::TraceLde<E>, TracePolyTable<E>) {
+        DefaultTraceLde::new(trace_info, main_trace, domain)
+    }
+
+    fn new_evaluator<'a, E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        air: &'a Self::Air,
+        aux_rand_elements: AuxTraceRandElements<E>,
+        composition_coefficients: ConstraintCompositionCoefficients<E>,
+    ) -> Self::ConstraintEvaluator<'a, E> {
+        DefaultConstraintEvaluator::new(air, aux_rand_elements, composition_coefficients)
+    }
+}
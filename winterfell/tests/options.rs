@@ -0,0 +1,290 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use common::{build_options, Blake3, FibAir, FibProver};
+use winterfell::{
+    crypto::{
+        hashers::{Keccak256, Rp64_256},
+        DefaultRandomCoin, ElementRandomCoin, EthStarkRandomCoin,
+    },
+    math::fields::f128::BaseElement,
+    AcceptableOptions, AcceptancePolicy, FieldExtension, LeafEncoding, LowDegreeTest, ProofOptions,
+    Prover, ProverError, QuerySampling, Serializable, StarkProof, Trace, VerifierError,
+};
+
+mod common;
+
+// TESTS
+// ================================================================================================
+
+#[test]
+fn normalized_queries() {
+    let options = ProofOptions::new(28, 8, 0, FieldExtension::None, 4, 7).with_normalized_queries();
+    for sequence_length in [64, 256] {
+        let prover = FibProver::<Blake3>::new(options.clone());
+        let trace = prover.build_trace(sequence_length);
+        let result = trace.get(1, trace.length() - 1);
+        let proof = prover.prove(trace).unwrap();
+
+        // every query is opened at a distinct position in every FRI layer
+        assert_eq!(28, proof.num_unique_queries);
+        let (layer_values, _) = proof
+            .fri_proof
+            .clone()
+            .parse_layers::<Blake3, BaseElement>(proof.lde_domain_size(), 4)
+            .unwrap();
+        assert!(!layer_values.is_empty());
+        assert!(layer_values.iter().all(|values| values.len() == 28 * 4));
+
+        let acceptable_options = AcceptableOptions::OptionSet(vec![options.clone()]);
+        let verified = winterfell::verify::<FibAir, Blake3, DefaultRandomCoin<Blake3>>(
+            proof,
+            result,
+            &acceptable_options,
+        );
+        assert_eq!(Ok(()), verified);
+    }
+
+    // the number of queries cannot exceed the size of the FRI remainder domain
+    let options = ProofOptions::new(28, 8, 0, FieldExtension::None, 4, 1).with_normalized_queries();
+    let prover = FibProver::<Blake3>::new(options);
+    let trace = prover.build_trace(64);
+    assert_eq!(Err(ProverError::TooManyNormalizedQueries(28, 16)), prover.prove(trace));
+}
+
+#[test]
+fn fri_folding_schedule() {
+    let options = build_options(false).with_fri_folding_schedule(&[8, 16]);
+    let prover = FibProver::<Blake3>::new(options.clone());
+    let trace = prover.build_trace(1024);
+    let result = trace.get(1, trace.length() - 1);
+    let proof = prover.prove(trace).unwrap();
+    assert_eq!(2, proof.fri_proof.num_layers());
+
+    let verify = |acceptable_options: AcceptableOptions| {
+        winterfell::verify::<FibAir, Blake3, DefaultRandomCoin<Blake3>>(
+            proof.clone(),
+            result,
+            &acceptable_options,
+        )
+    };
+    assert_eq!(Ok(()), verify(AcceptableOptions::OptionSet(vec![options])));
+
+    // the same folding factors without the schedule are not acceptable
+    let result = verify(AcceptableOptions::OptionSet(vec![build_options(false)]));
+    assert_eq!(Err(VerifierError::UnacceptableProofOptions), result);
+
+    // a policy restricts folding factors of FRI layers actually present in the proof
+    let policy = AcceptancePolicy::new().with_fri_folding_schedules(&[&[4, 4, 4], &[8, 16]]);
+    assert_eq!(Ok(()), verify(AcceptableOptions::Policy(policy)));
+
+    let policy = AcceptancePolicy::new().with_fri_folding_schedules(&[&[8, 16, 4]]);
+    let result = verify(AcceptableOptions::Policy(policy));
+    assert_eq!(Err(VerifierError::UnacceptableFriFoldingSchedule), result);
+}
+
+#[test]
+fn fri_layer_grinding() {
+    let options = build_options(false).with_fri_layer_grinding_factor(8);
+    let prover = FibProver::<Blake3>::new(options.clone());
+    let trace = prover.build_trace(1024);
+    let result = trace.get(1, trace.length() - 1);
+    let proof = prover.prove(trace).unwrap();
+    assert_eq!(proof.fri_proof.num_layers(), proof.fri_proof.layer_nonces().len());
+
+    let verify = |acceptable_options: AcceptableOptions| {
+        winterfell::verify::<FibAir, Blake3, DefaultRandomCoin<Blake3>>(
+            proof.clone(),
+            result,
+            &acceptable_options,
+        )
+    };
+    assert_eq!(Ok(()), verify(AcceptableOptions::OptionSet(vec![options])));
+
+    let result = verify(AcceptableOptions::OptionSet(vec![build_options(false)]));
+    assert_eq!(Err(VerifierError::UnacceptableProofOptions), result);
+}
+
+#[test]
+fn fri_query_deduplication() {
+    let options = build_options(false).with_fri_query_deduplication();
+    let prover = FibProver::<Blake3>::new(options.clone());
+    let trace = prover.build_trace(1024);
+    let result = trace.get(1, trace.length() - 1);
+    let proof = prover.prove(trace.clone()).unwrap();
+    assert!(proof.fri_proof.is_deduplicated());
+
+    let acceptable_options = AcceptableOptions::OptionSet(vec![options]);
+    let verified = winterfell::verify::<FibAir, Blake3, DefaultRandomCoin<Blake3>>(
+        proof.clone(),
+        result,
+        &acceptable_options,
+    );
+    assert_eq!(Ok(()), verified);
+
+    // omitting FRI query values known to the verifier makes proofs smaller
+    let prover = FibProver::<Blake3>::new(build_options(false));
+    let full_proof = prover.prove(trace).unwrap();
+    assert!(proof.to_bytes().len() < full_proof.to_bytes().len());
+}
+
+#[test]
+fn leaf_encoding() {
+    let prover = FibProver::<Blake3>::new(build_options(false));
+    let trace = prover.build_trace(256);
+    let result = trace.get(1, trace.length() - 1);
+    let packed_proof = prover.prove(trace.clone()).unwrap();
+
+    for leaf_encoding in [LeafEncoding::PaddedLe, LeafEncoding::PaddedBe] {
+        let options = build_options(false).with_leaf_encoding(leaf_encoding);
+        let prover = FibProver::<Blake3>::new(options.clone());
+        let proof = prover.prove(trace.clone()).unwrap();
+        assert_eq!(leaf_encoding, proof.options().leaf_encoding());
+
+        // the encoding changes the trace commitment
+        assert_ne!(packed_proof.commitments.to_bytes(), proof.commitments.to_bytes());
+
+        // the encoding is recorded in the proof, so serialized proofs can be verified as well
+        let proof = StarkProof::from_bytes(&proof.to_bytes()).unwrap();
+        let verified = winterfell::verify::<FibAir, Blake3, DefaultRandomCoin<Blake3>>(
+            proof.clone(),
+            result,
+            &AcceptableOptions::OptionSet(vec![options]),
+        );
+        assert_eq!(Ok(()), verified);
+
+        // proofs are not accepted when only packed leaves are acceptable
+        let verified = winterfell::verify::<FibAir, Blake3, DefaultRandomCoin<Blake3>>(
+            proof,
+            result,
+            &AcceptableOptions::OptionSet(vec![build_options(false)]),
+        );
+        assert_eq!(Err(VerifierError::UnacceptableProofOptions), verified);
+    }
+}
+
+#[test]
+fn query_sampling() {
+    let options = build_options(false).with_query_sampling(QuerySampling::Stratified);
+    let prover = FibProver::<Blake3>::new(options);
+    let trace = prover.build_trace(256);
+    let result = trace.get(1, trace.length() - 1);
+    let proof = prover.prove(trace).unwrap();
+    assert_eq!(QuerySampling::Stratified, proof.options().query_sampling());
+
+    let verify = |policy: AcceptancePolicy| {
+        winterfell::verify::<FibAir, Blake3, DefaultRandomCoin<Blake3>>(
+            proof.clone(),
+            result,
+            &AcceptableOptions::Policy(policy),
+        )
+    };
+
+    // by default, a policy accepts only uniformly sampled query positions
+    let result = verify(AcceptancePolicy::new());
+    assert_eq!(Err(VerifierError::UnacceptableQuerySampling(QuerySampling::Stratified)), result);
+
+    let policy = AcceptancePolicy::new()
+        .with_query_sampling(&[QuerySampling::Uniform, QuerySampling::Stratified]);
+    assert_eq!(Ok(()), verify(policy));
+}
+
+#[test]
+fn ethstark_random_coin() {
+    type Keccak = Keccak256<BaseElement>;
+    type EthStarkCoin = EthStarkRandomCoin<Keccak>;
+
+    let options = ProofOptions::new(28, 8, 8, FieldExtension::Quadratic, 4, 7)
+        .with_fri_layer_grinding_factor(4);
+    let prover = FibProver::<Keccak, EthStarkCoin>::new(options);
+    let trace = prover.build_trace(1024);
+    let result = trace.get(1, trace.length() - 1);
+    let proof = prover.prove(trace).unwrap();
+
+    let acceptable_options = AcceptableOptions::MinConjecturedSecurity(0);
+    let verified = winterfell::verify::<FibAir, Keccak, EthStarkCoin>(
+        proof.clone(),
+        result,
+        &acceptable_options,
+    );
+    assert_eq!(Ok(()), verified);
+
+    // the proof is bound to the transcript of the coin it was generated with
+    let verified = winterfell::verify::<FibAir, Keccak, DefaultRandomCoin<Keccak>>(
+        proof,
+        result,
+        &acceptable_options,
+    );
+    assert!(verified.is_err());
+}
+
+#[test]
+fn element_random_coin() {
+    type SmallField = winterfell::math::fields::f64::BaseElement;
+    type ElementCoin = ElementRandomCoin<Rp64_256>;
+
+    let options = build_options(true);
+    let prover = FibProver::<Rp64_256, ElementCoin>::new(options);
+    let trace = prover.build_trace(256);
+    let result = trace.get(1, trace.length() - 1);
+    let proof = prover.prove(trace).unwrap();
+
+    // digests in the serialized proof are read back as canonical field elements
+    let proof = StarkProof::from_bytes(&proof.to_bytes()).unwrap();
+
+    let acceptable_options = AcceptableOptions::OptionSet(vec![proof.options().clone()]);
+    let verified = winterfell::verify::<FibAir<SmallField>, Rp64_256, ElementCoin>(
+        proof.clone(),
+        result,
+        &acceptable_options,
+    );
+    assert_eq!(Ok(()), verified);
+
+    // digests of Rp64_256 are serialized as canonical elements, and thus, the default coin
+    // follows the same transcript
+    let verified = winterfell::verify::<FibAir<SmallField>, Rp64_256, DefaultRandomCoin<Rp64_256>>(
+        proof,
+        result,
+        &acceptable_options,
+    );
+    assert_eq!(Ok(()), verified);
+}
+
+#[test]
+fn stir() {
+    let options = build_options(false).with_low_degree_test(LowDegreeTest::Stir);
+    let prover = FibProver::<Blake3>::new(options.clone());
+    let trace = prover.build_trace(1024);
+    let result = trace.get(1, trace.length() - 1);
+    let proof = prover.prove(trace).unwrap();
+    assert_eq!(
+        proof.fri_proof.num_layers() - 1,
+        proof.fri_proof.num_ood_evaluations::<BaseElement>()
+    );
+
+    let verify = |proof: StarkProof, acceptable_options: AcceptableOptions| {
+        winterfell::verify::<FibAir, Blake3, DefaultRandomCoin<Blake3>>(
+            proof,
+            result,
+            &acceptable_options,
+        )
+    };
+    assert_eq!(Ok(()), verify(proof.clone(), AcceptableOptions::OptionSet(vec![options])));
+
+    // serialized proofs can be verified as well
+    let proof = StarkProof::from_bytes(&proof.to_bytes()).unwrap();
+    assert_eq!(Ok(()), verify(proof.clone(), AcceptableOptions::MinConjecturedSecurity(0)));
+
+    // no security is claimed for STIR proofs, and thus, security-based policies reject them
+    assert_eq!(0, proof.security_level::<Blake3>(true));
+    assert_eq!(0, proof.security_level::<Blake3>(false));
+    let result = verify(proof.clone(), AcceptableOptions::MinConjecturedSecurity(1));
+    assert_eq!(Err(VerifierError::InsufficientConjecturedSecurity(1, 0)), result);
+    let result = verify(proof.clone(), AcceptableOptions::MinProvenSecurity(1));
+    assert_eq!(Err(VerifierError::InsufficientProvenSecurity(1, 0)), result);
+
+    let result = verify(proof, AcceptableOptions::OptionSet(vec![build_options(false)]));
+    assert_eq!(Err(VerifierError::UnacceptableProofOptions), result);
+}
@@ -0,0 +1,285 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use common::{build_options, Blake3, FibAir, FibProver};
+use winterfell::{
+    crypto::{hashers::Keccak256, DefaultRandomCoin, Digest, Hasher},
+    math::{fields::f128::BaseElement, FieldElement},
+    proof::{CompressionCodec, Queries},
+    AcceptableOptions, AcceptancePolicy, Air, FieldExtension, LowDegreeTest, ProofOptions, Prover,
+    SliceReader, StarkProof, Trace, TraceTable, VerifierError,
+};
+
+mod common;
+
+// TESTS
+// ================================================================================================
+
+#[test]
+fn describe() {
+    let prover = FibProver::<Blake3>::new(build_options(false));
+    let trace = prover.build_trace(16);
+    let proof = prover.prove(trace).unwrap();
+    let description = proof.describe();
+
+    // every commitment is listed as a hex string
+    let (trace_roots, constraint_root, fri_roots) = proof.parse_commitments::<Blake3>().unwrap();
+    let to_hex = |digest: &<Blake3 as Hasher>::Digest| {
+        digest.as_bytes().iter().map(|byte| format!("{byte:02x}")).collect::<String>()
+    };
+    assert!(description.contains("commitments (32 bytes each)\n"));
+    assert!(description.contains(&to_hex(&trace_roots[0])));
+    assert!(description.contains(&to_hex(&constraint_root)));
+    fri_roots.iter().for_each(|root| assert!(description.contains(&to_hex(root))));
+
+    // every FRI layer is listed with its size
+    for (i, size) in proof.fri_proof.layer_sizes().iter().enumerate() {
+        assert!(description.contains(&format!("  layer {i}:")));
+        assert!(description.contains(&format!("{size} bytes (domain size")));
+    }
+    let security =
+        format!("  conjectured:                {} bits\n", proof.security_level::<Blake3>(true));
+    assert!(description.contains(&security));
+}
+
+#[test]
+fn estimate_proof_size() {
+    let option_sets = [
+        build_options(false),
+        build_options(true),
+        ProofOptions::new(42, 4, 0, FieldExtension::None, 8, 31),
+        ProofOptions::new(20, 16, 0, FieldExtension::Quadratic, 2, 0),
+        build_options(false).with_fri_folding_schedule(&[16, 2]),
+        build_options(false).with_fri_layer_grinding_factor(4),
+        build_options(false).with_fri_query_deduplication(),
+        build_options(false).with_low_degree_test(LowDegreeTest::Stir),
+    ];
+    for options in option_sets {
+        let prover = FibProver::<Blake3>::new(options.clone());
+        let trace = prover.build_trace(1024);
+        let air = FibAir::new(trace.get_info(), prover.get_pub_inputs(&trace), options);
+
+        let estimate =
+            air.options().estimate_proof_size::<Blake3, _>(&trace.get_info(), air.context());
+        let actual = prover.prove(trace).unwrap().to_bytes().len();
+
+        // the estimate is an expectation over query positions; actual sizes should be close, but
+        // for proofs with few queries, the number of distinct authentication path nodes may
+        // differ from the expectation by several percent depending on the transcript
+        let deviation = (estimate as f64 - actual as f64).abs() / actual as f64;
+        assert!(deviation < 0.1, "estimated {estimate} bytes, but proof was {actual} bytes");
+    }
+}
+
+#[test]
+fn trace_meta() {
+    let meta = b"program:fib2".to_vec();
+    let mut trace = TraceTable::with_meta(2, 8, meta.clone());
+    trace.fill(
+        |state| {
+            state[0] = BaseElement::ONE;
+            state[1] = BaseElement::ONE;
+        },
+        |_, state| {
+            state[0] += state[1];
+            state[1] += state[0];
+        },
+    );
+    let result = trace.get(1, trace.length() - 1);
+    let prover = FibProver::<Blake3>::new(build_options(false));
+    let proof = prover.prove(trace).unwrap();
+    assert_eq!(meta.as_slice(), proof.trace_meta());
+
+    let verify = |proof: StarkProof, policy: AcceptancePolicy| {
+        winterfell::verify::<FibAir, Blake3, DefaultRandomCoin<Blake3>>(
+            proof,
+            result,
+            &AcceptableOptions::Policy(policy),
+        )
+    };
+
+    let policy = AcceptancePolicy::new().with_max_trace_meta_length(meta.len());
+    assert_eq!(Ok(()), verify(proof.clone(), policy.clone().with_trace_meta(&meta)));
+
+    // the policy restricts the length and the contents of the metadata
+    let result = verify(proof.clone(), policy.with_max_trace_meta_length(meta.len() - 1));
+    assert_eq!(Err(VerifierError::TraceMetaTooLong(meta.len() - 1, meta.len())), result);
+
+    let result = verify(proof.clone(), AcceptancePolicy::new().with_trace_meta(b"program:fib8"));
+    assert_eq!(Err(VerifierError::UnexpectedTraceMeta), result);
+
+    // the metadata is bound to the transcript, and thus, cannot be replaced in the proof
+    let mut proof_bytes = proof.to_bytes();
+    let meta_offset = proof_bytes.windows(meta.len()).position(|w| w == meta.as_slice()).unwrap();
+    proof_bytes[meta_offset + meta.len() - 1] = b'8';
+    let proof = StarkProof::from_bytes(&proof_bytes).unwrap();
+    assert_eq!(b"program:fib8", proof.trace_meta());
+    assert!(verify(proof, AcceptancePolicy::new()).is_err());
+}
+
+#[test]
+fn json_encoding() {
+    let options = [build_options(true), build_options(false).with_fri_query_deduplication()];
+    for options in options {
+        let prover = FibProver::<Blake3>::new(options.clone());
+        let trace = prover.build_trace(16);
+        let result = trace.get(1, trace.length() - 1);
+        let proof = prover.prove(trace).unwrap();
+
+        // decoded proofs are identical to the encoded ones, and the encoding is canonical
+        let json = proof.to_json::<BaseElement, Blake3>().unwrap();
+        let decoded = StarkProof::from_json::<BaseElement, Blake3>(&json).unwrap();
+        assert_eq!(proof, decoded);
+        assert_eq!(json, decoded.to_json::<BaseElement, Blake3>().unwrap());
+        assert_eq!(options, ProofOptions::from_json(&options.to_json()).unwrap());
+
+        let acceptable_options = AcceptableOptions::OptionSet(vec![options]);
+        let verified = winterfell::verify::<FibAir, Blake3, DefaultRandomCoin<Blake3>>(
+            decoded,
+            result,
+            &acceptable_options,
+        );
+        assert_eq!(Ok(()), verified);
+
+        // proofs cannot be encoded or decoded with a different base field or hash function
+        type OtherField = winterfell::math::fields::f64::BaseElement;
+        assert!(proof.to_json::<OtherField, Blake3>().is_err());
+        assert!(StarkProof::from_json::<OtherField, Blake3>(&json).is_err());
+        assert!(StarkProof::from_json::<BaseElement, Keccak256<BaseElement>>(&json).is_err());
+
+        // field elements must be smaller than the field modulus
+        let start = json.find("\"remainder\":[\"").unwrap() + 14;
+        let json = [&json[..start], &"f".repeat(32), &json[start + 32..]].concat();
+        assert!(StarkProof::from_json::<BaseElement, Blake3>(&json).is_err());
+    }
+
+    let json = build_options(false).to_json().replace("\"fri\"", "\"fri2\"");
+    assert!(ProofOptions::from_json(&json).is_err());
+}
+
+#[test]
+fn protobuf_encoding() {
+    use winterfell::protobuf::{self, Message};
+
+    let options = [build_options(true), build_options(false).with_fri_query_deduplication()];
+    for options in options {
+        let prover = FibProver::<Blake3>::new(options.clone());
+        let trace = prover.build_trace(16);
+        let result = trace.get(1, trace.length() - 1);
+        let proof = prover.prove(trace).unwrap();
+
+        // decoded proofs and their parts are identical to the encoded ones
+        let bytes = proof.to_protobuf::<BaseElement, Blake3>().unwrap();
+        let decoded = StarkProof::from_protobuf::<BaseElement, Blake3>(&bytes).unwrap();
+        assert_eq!(proof, decoded);
+        assert_eq!(options, ProofOptions::from_protobuf(&options.to_protobuf()).unwrap());
+
+        let num_queries = proof.num_unique_queries as usize;
+        let queries = proof.constraint_queries.to_protobuf::<BaseElement>(num_queries).unwrap();
+        let decoded_queries = Queries::from_protobuf::<BaseElement>(&queries).unwrap();
+        assert_eq!(proof.constraint_queries, decoded_queries);
+
+        let acceptable_options = AcceptableOptions::OptionSet(vec![options]);
+        let verified = winterfell::verify::<FibAir, Blake3, DefaultRandomCoin<Blake3>>(
+            decoded,
+            result,
+            &acceptable_options,
+        );
+        assert_eq!(Ok(()), verified);
+
+        // proofs cannot be encoded or decoded with a different base field or hash function
+        type OtherField = winterfell::math::fields::f64::BaseElement;
+        assert!(proof.to_protobuf::<OtherField, Blake3>().is_err());
+        assert!(StarkProof::from_protobuf::<OtherField, Blake3>(&bytes).is_err());
+        assert!(StarkProof::from_protobuf::<BaseElement, Keccak256<BaseElement>>(&bytes).is_err());
+
+        // field elements must be smaller than the field modulus
+        let mut message = protobuf::StarkProof::decode(&bytes[..]).unwrap();
+        message.fri_proof.as_mut().unwrap().remainder[..16].fill(0xff);
+        assert!(message.to_proof::<BaseElement, Blake3>().is_err());
+    }
+
+    // public inputs are decoded only in the field they were encoded in
+    let inputs = protobuf::PublicInputs::from_elements(&[BaseElement::new(1), BaseElement::new(2)]);
+    let decoded = protobuf::PublicInputs::decode(&inputs.encode_to_vec()[..]).unwrap();
+    assert_eq!(
+        vec![BaseElement::new(1), BaseElement::new(2)],
+        decoded.to_elements::<BaseElement>().unwrap()
+    );
+    type OtherField = winterfell::math::fields::f64::BaseElement;
+    assert!(decoded.to_elements::<OtherField>().is_err());
+    let mut invalid = decoded;
+    invalid.elements[0].fill(0xff);
+    assert!(invalid.to_elements::<BaseElement>().is_err());
+
+    // unknown enum values and malformed messages are rejected
+    let mut message = protobuf::ProofOptions::from_options(&build_options(false));
+    message.low_degree_test = 7;
+    assert!(ProofOptions::from_protobuf(&message.encode_to_vec()).is_err());
+    assert!(ProofOptions::from_protobuf(&[0xff, 0xff]).is_err());
+}
+
+#[test]
+fn compressed_proof() {
+    let prover = FibProver::<Blake3>::new(build_options(false));
+    let trace = prover.build_trace(64);
+    let result = trace.get(1, trace.length() - 1);
+    let proof = prover.prove(trace).unwrap();
+    let acceptable_options = AcceptableOptions::OptionSet(vec![build_options(false)]);
+
+    for codec in [CompressionCodec::Deflate, CompressionCodec::Zstd] {
+        let bytes = proof.to_compressed_bytes(codec);
+
+        // compressed proofs are decompressed transparently when they are deserialized
+        let decompressed = StarkProof::from_bytes(&bytes).unwrap();
+        assert_eq!(proof, decompressed);
+        let verified = winterfell::verify::<FibAir, Blake3, DefaultRandomCoin<Blake3>>(
+            decompressed,
+            result,
+            &acceptable_options,
+        );
+        assert_eq!(Ok(()), verified);
+
+        // compressed proofs cannot be verified incrementally
+        let verified = winterfell::verify_from_reader::<FibAir, Blake3, DefaultRandomCoin<Blake3>, _>(
+            &mut SliceReader::new(&bytes),
+            result,
+            &acceptable_options,
+        );
+        assert!(matches!(verified, Err(VerifierError::ProofDeserializationError(_))));
+    }
+}
+
+#[test]
+fn released_format_proof() {
+    // a proof for a Fibonacci sequence of length 64 generated and serialized by winterfell v0.7.1,
+    // i.e., with proof format version 1
+    let bytes = include_bytes!("fixtures/fib_v1.bin");
+    let result = BaseElement::new(10610209857723);
+    let acceptable_options =
+        AcceptableOptions::OptionSet(vec![ProofOptions::new(8, 8, 0, FieldExtension::None, 4, 7)]);
+
+    // the proof is read with the format version it was serialized with, and is serialized again
+    // in the same way
+    let proof = StarkProof::from_bytes(bytes).unwrap();
+    assert_eq!(1, proof.context.format_version());
+    assert_eq!(None, proof.hash_function());
+    assert_eq!(bytes.as_slice(), proof.to_bytes());
+    assert_eq!(bytes.len(), proof.size_breakdown().total());
+
+    // the proof can be verified both after it is deserialized and incrementally
+    let verified = winterfell::verify_from_reader::<FibAir, Blake3, DefaultRandomCoin<Blake3>, _>(
+        &mut SliceReader::new(&proof.to_streaming_bytes()),
+        result,
+        &acceptable_options,
+    );
+    assert_eq!(Ok(()), verified);
+    let verified = winterfell::verify::<FibAir, Blake3, DefaultRandomCoin<Blake3>>(
+        proof,
+        result,
+        &acceptable_options,
+    );
+    assert_eq!(Ok(()), verified);
+}
@@ -0,0 +1,190 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use common::{build_options, Blake3, FibAir, FibProver, FibProverFactory, RecordingHooks};
+use winterfell::{
+    crypto::{DefaultRandomCoin, HashFunction},
+    math::{fields::f128::BaseElement, FieldElement},
+    matrix::ColMatrix,
+    AcceptableOptions, FieldExtension, NumaPartitioning, ProofOptions, Prover, ProverError,
+    ProverFactory, Trace, VerifierError,
+};
+
+mod common;
+
+// TESTS
+// ================================================================================================
+
+#[test]
+fn unsatisfied_assertion() {
+    let prover = FibProver::<Blake3>::new(build_options(false));
+    let mut trace = prover.build_trace(16);
+    trace.set(0, 0, BaseElement::ZERO);
+
+    let result = prover.prove(trace);
+    assert!(matches!(result, Err(ProverError::UnsatisfiedAssertion(0, 0))));
+}
+
+#[test]
+fn prove_from_polys() {
+    let prover = FibProver::<Blake3>::new(build_options(false));
+    let expected_proof = prover.prove(prover.build_trace(16)).unwrap();
+
+    // proofs built from the trace polynomials are the same as proofs built from the trace
+    let trace = prover.build_trace(16);
+    let polys = trace.main_segment().interpolate_columns();
+    let proof = prover.prove_from_polys(trace, polys.clone()).unwrap();
+    assert_eq!(expected_proof.to_bytes(), proof.to_bytes());
+
+    // polynomials may contain extra zero coefficients
+    let padded_polys = ColMatrix::new(
+        polys
+            .columns()
+            .map(|poly| {
+                let mut poly = poly.to_vec();
+                poly.resize(32, BaseElement::ZERO);
+                poly
+            })
+            .collect(),
+    );
+    let proof = prover.prove_from_polys(prover.build_trace(16), padded_polys).unwrap();
+    assert_eq!(expected_proof.to_bytes(), proof.to_bytes());
+
+    // the number of polynomials must match the width of the trace
+    let result = prover.prove_from_polys(
+        prover.build_trace(16),
+        ColMatrix::new(vec![polys.get_column(0).to_vec()]),
+    );
+    assert!(matches!(result, Err(ProverError::InconsistentTraceWidth(2, 1))));
+
+    // the degree of the polynomials must be smaller than the trace length
+    let mut columns = polys.clone().into_columns();
+    columns.iter_mut().for_each(|poly| poly.resize(32, BaseElement::ONE));
+    let result = prover.prove_from_polys(prover.build_trace(16), ColMatrix::new(columns));
+    assert!(matches!(result, Err(ProverError::TracePolyDegreeTooHigh(0, 7, 31))));
+
+    // in debug mode, the polynomials must interpolate the trace
+    if cfg!(debug_assertions) {
+        let mut columns = polys.into_columns();
+        columns[1][0] += BaseElement::ONE;
+        let result = prover.prove_from_polys(prover.build_trace(16), ColMatrix::new(columns));
+        assert!(matches!(result, Err(ProverError::TracePolyMismatch(1))));
+    }
+}
+
+#[test]
+fn prove_with_hooks() {
+    let prover = FibProver::<Blake3>::new(build_options(false));
+    let mut hooks = RecordingHooks::default();
+    let trace = prover.build_trace(16);
+    let result = prover.get_pub_inputs(&trace);
+    let proof = prover.prove_with_hooks(trace, &mut hooks).unwrap();
+
+    // hooks must be invoked in order and must not affect the generated proof
+    assert_eq!(vec!["trace", "constraints", "composition", "ood", "queries"], hooks.phases);
+    assert_eq!(2, hooks.num_trace_polys);
+    assert_eq!(2, hooks.num_ood_rows);
+    assert_eq!(proof.num_unique_queries as usize, hooks.query_positions.len());
+    assert!(hooks.query_positions.windows(2).all(|w| w[0] < w[1]));
+    assert_eq!(proof.to_bytes(), prover.prove(prover.build_trace(16)).unwrap().to_bytes());
+
+    // the verifier returns the same query positions as the ones used by the prover
+    let acceptable_options = AcceptableOptions::OptionSet(vec![build_options(false)]);
+    let query_positions = winterfell::verify_with_query_positions::<
+        FibAir,
+        Blake3,
+        DefaultRandomCoin<Blake3>,
+    >(proof, result, &acceptable_options)
+    .unwrap();
+    assert_eq!(hooks.query_positions, query_positions);
+}
+
+#[test]
+fn self_verification() {
+    let options = build_options(false);
+    let trace = FibProver::<Blake3>::new(options.clone()).build_trace(1024);
+
+    // a proof generated with the expected options passes self-verification
+    let acceptable_options = AcceptableOptions::OptionSet(vec![options.clone()]);
+    let prover =
+        FibProver::<Blake3>::new(options.clone()).with_self_verification(acceptable_options);
+    assert!(prover.prove(trace.clone()).is_ok());
+
+    // if proof options drift away from the ones accepted by the verifier, the proof is rejected
+    // before it is returned
+    let acceptable_options = AcceptableOptions::OptionSet(vec![build_options(true)]);
+    let prover = FibProver::<Blake3>::new(options).with_self_verification(acceptable_options);
+    assert_eq!(
+        Err(ProverError::SelfVerificationFailed(VerifierError::UnacceptableProofOptions)),
+        prover.prove(trace)
+    );
+}
+
+#[test]
+fn deterministic_proofs() {
+    // use a non-trivial grinding factor so that multi-threaded grinding could find different
+    // nonces in different runs
+    let options = ProofOptions::new(28, 8, 12, FieldExtension::None, 4, 7);
+    let prover = FibProver::<Blake3>::new(options).deterministic();
+    let trace = prover.build_trace(64);
+    let result = trace.get(1, trace.length() - 1);
+
+    let proof = prover.prove(trace.clone()).unwrap();
+    for _ in 0..3 {
+        assert_eq!(proof.to_bytes(), prover.prove(trace.clone()).unwrap().to_bytes());
+    }
+
+    let acceptable_options = AcceptableOptions::OptionSet(vec![proof.options().clone()]);
+    let verified = winterfell::verify::<FibAir, Blake3, DefaultRandomCoin<Blake3>>(
+        proof,
+        result,
+        &acceptable_options,
+    );
+    assert!(verified.is_ok());
+}
+
+#[test]
+fn numa_partitioning() {
+    // the trace is long enough for constraint evaluation and DEEP composition to be split among
+    // nodes when `concurrent` feature is enabled
+    let options = build_options(true);
+    let trace = FibProver::<Blake3>::new(options.clone()).build_trace(1 << 14);
+    let expected = FibProver::<Blake3>::new(options.clone())
+        .deterministic()
+        .prove(trace.clone())
+        .unwrap();
+
+    // partitioning does not change the proof
+    for partitioning in [NumaPartitioning::new(2), NumaPartitioning::new(3).with_thread_pinning()] {
+        let prover = FibProver::<Blake3>::new(options.clone())
+            .deterministic()
+            .with_numa_partitioning(partitioning);
+        assert_eq!(expected.to_bytes(), prover.prove(trace.clone()).unwrap().to_bytes());
+    }
+}
+
+#[test]
+fn dynamic_hash_function() {
+    let factory = FibProverFactory(build_options(false));
+    for hash_fn in [HashFunction::Blake3_192, HashFunction::Blake3_256, HashFunction::Sha3_256] {
+        let trace = factory.build_prover::<Blake3>().build_trace(16);
+        let result = trace.get(1, trace.length() - 1);
+        let proof = winterfell::prove_dyn(&factory, trace, hash_fn).unwrap();
+
+        let acceptable_options = AcceptableOptions::OptionSet(vec![proof.options().clone()]);
+        let verified =
+            winterfell::verify_dyn::<FibAir>(proof.clone(), result, &acceptable_options, hash_fn);
+        assert!(verified.is_ok());
+
+        // a proof must not verify against a different hash function
+        let other_hash_fn = match hash_fn {
+            HashFunction::Sha3_256 => HashFunction::Blake3_256,
+            _ => HashFunction::Sha3_256,
+        };
+        let verified =
+            winterfell::verify_dyn::<FibAir>(proof, result, &acceptable_options, other_hash_fn);
+        assert_eq!(Err(VerifierError::InconsistentHashFunction), verified);
+    }
+}
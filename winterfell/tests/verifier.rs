@@ -0,0 +1,469 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use common::{build_options, Blake3, FibAir, FibProver, FibProverFactory, RecordingHooks};
+use winterfell::{
+    crypto::{
+        hashers::Rp64_256, DefaultRandomCoin, HashFunction, Transcript, TranscriptCoin,
+        TranscriptEvent,
+    },
+    math::{
+        fields::{f128::BaseElement, QuadExtension},
+        FieldElement,
+    },
+    proof::{AggregatedProof, Queries},
+    AcceptableOptions, AcceptancePolicy, Deserializable, FieldExtension, ProofOptions, Prover,
+    ProverFactory, ReadAdapter, Serializable, SliceReader, StarkProof, Trace, VerifierError,
+    WriteAdapter,
+};
+
+mod common;
+
+// TESTS
+// ================================================================================================
+
+#[test]
+fn inspect() {
+    let prover = FibProver::<Blake3>::new(build_options(false));
+    let trace = prover.build_trace(16);
+    let result = prover.get_pub_inputs(&trace);
+    let mut hooks = RecordingHooks::default();
+    let proof = prover.prove_with_hooks(trace, &mut hooks).unwrap();
+
+    // values included in the proof can be read without the AIR
+    let (trace_roots, _, fri_roots) = proof.parse_commitments::<Blake3>().unwrap();
+    assert_eq!(1, trace_roots.len());
+    assert_eq!(proof.fri_proof.num_layers() + 1, fri_roots.len());
+    let ood_rows = proof.ood_trace_rows::<BaseElement>().unwrap();
+    assert_eq!(vec![2, 2], ood_rows.iter().map(|row| row.len()).collect::<Vec<_>>());
+    assert_eq!(1, proof.ood_constraint_evaluations::<BaseElement>().unwrap().len());
+    assert!(proof.ood_trace_rows::<QuadExtension<BaseElement>>().is_err());
+
+    // values derived from the public coin are the same as the ones used by the prover
+    let acceptable_options = AcceptableOptions::OptionSet(vec![build_options(false)]);
+    let inspection = winterfell::inspect::<FibAir, BaseElement, Blake3, DefaultRandomCoin<Blake3>>(
+        proof.clone(),
+        result,
+        &acceptable_options,
+    )
+    .unwrap();
+    assert_eq!(hooks.query_positions, inspection.query_positions);
+    assert_eq!(2, inspection.deep_coefficients.trace.len());
+    assert_eq!(2, inspection.constraint_coefficients.transition.len());
+    assert_eq!(3, inspection.constraint_coefficients.boundary.len());
+
+    // values must be requested in the field in which the proof was generated
+    let result = winterfell::inspect::<
+        FibAir,
+        QuadExtension<BaseElement>,
+        Blake3,
+        DefaultRandomCoin<Blake3>,
+    >(proof, result, &acceptable_options);
+    assert!(matches!(result, Err(VerifierError::UnsupportedFieldExtension(1))));
+}
+
+#[test]
+fn transcript_replay() {
+    type Coin = DefaultRandomCoin<Blake3>;
+
+    let options = ProofOptions::new(28, 8, 4, FieldExtension::Quadratic, 4, 7)
+        .with_fri_layer_grinding_factor(4);
+    let prover = FibProver::<Blake3, TranscriptCoin<Coin>>::new(options.clone()).deterministic();
+    let trace = prover.build_trace(64);
+    let result = trace.get(1, trace.length() - 1);
+    let mut hooks = RecordingHooks::default();
+    let proof = prover.prove_with_hooks(trace.clone(), &mut hooks).unwrap();
+    assert_eq!(Some(&"transcript"), hooks.phases.last());
+    let transcript = hooks.transcript.unwrap();
+
+    // recording the transcript must not affect the generated proof
+    let expected = FibProver::<Blake3>::new(options.clone()).deterministic();
+    assert_eq!(expected.prove(trace).unwrap().to_bytes(), proof.to_bytes());
+
+    // the verifier must record the same transcript as the prover
+    let acceptable_options = AcceptableOptions::OptionSet(vec![options]);
+    let (verified, verifier_transcript) = winterfell::verify_with_transcript::<FibAir, Blake3, Coin>(
+        proof.clone(),
+        result,
+        &acceptable_options,
+    );
+    assert_eq!(Ok(()), verified);
+    assert_eq!(transcript, verifier_transcript);
+    assert_eq!(None, transcript.first_divergence(&verifier_transcript));
+    assert!(matches!(transcript.events()[0], TranscriptEvent::Seed(_)));
+    assert!(transcript
+        .events()
+        .iter()
+        .any(|e| matches!(e, TranscriptEvent::ReseedWithInt(_))));
+    assert!(matches!(transcript.events().last(), Some(TranscriptEvent::DrawIntegers(..))));
+    assert_eq!(transcript.len(), transcript.to_string().lines().count());
+
+    // transcripts can be exchanged with other implementations
+    let parsed = Transcript::read_from_bytes(&transcript.to_bytes()).unwrap();
+    assert_eq!(transcript, parsed);
+
+    let replay = |proof, pub_inputs, expected: &Transcript| {
+        winterfell::verify_against_transcript::<FibAir, Blake3, Coin>(
+            proof,
+            pub_inputs,
+            &acceptable_options,
+            expected,
+        )
+    };
+    assert_eq!(Ok(()), replay(proof.clone(), result, &transcript));
+
+    // different public inputs diverge at the coin seed
+    assert_eq!(
+        Err(VerifierError::TranscriptMismatch(0)),
+        replay(proof.clone(), result + BaseElement::ONE, &transcript)
+    );
+
+    // a different out-of-domain evaluation is pinpointed by its note
+    let note_index = transcript
+        .events()
+        .iter()
+        .position(|e| matches!(e, TranscriptEvent::Note(label, _) if label == "ood_constraint_evaluations"))
+        .unwrap();
+    let mut tampered = Transcript::new();
+    for (i, event) in transcript.events().iter().enumerate() {
+        match event {
+            TranscriptEvent::Note(label, _) if i == note_index => {
+                tampered.record(TranscriptEvent::Note(label.clone(), vec![0; 32]))
+            }
+            _ => tampered.record(event.clone()),
+        }
+    }
+    assert_eq!(
+        Err(VerifierError::TranscriptMismatch(note_index)),
+        replay(proof, result, &tampered)
+    );
+}
+
+#[test]
+fn verifier_error_location() {
+    let prover = FibProver::<Blake3>::new(build_options(false));
+    let trace = prover.build_trace(64);
+    let pub_inputs = prover.get_pub_inputs(&trace);
+    let mut hooks = RecordingHooks::default();
+    let proof = prover.prove_with_hooks(trace, &mut hooks).unwrap();
+    let positions = hooks.query_positions;
+    let acceptable_options = AcceptableOptions::OptionSet(vec![build_options(false)]);
+    let verify = |proof| {
+        winterfell::verify::<FibAir, Blake3, DefaultRandomCoin<Blake3>>(
+            proof,
+            pub_inputs,
+            &acceptable_options,
+        )
+    };
+
+    // flip a bit in the first trace row opened by the proof
+    let mut tampered = proof.clone();
+    tampered.trace_queries[0] = flip_query_value_bit(&proof.trace_queries[0], 0);
+    assert_eq!(
+        Err(VerifierError::TraceQueryDoesNotMatchCommitment(0, positions[0])),
+        verify(tampered)
+    );
+
+    // flip a bit in the first constraint evaluation row opened by the proof
+    let mut tampered = proof.clone();
+    tampered.constraint_queries = flip_query_value_bit(&proof.constraint_queries, 0);
+    assert_eq!(
+        Err(VerifierError::ConstraintQueryDoesNotMatchCommitment(positions[0])),
+        verify(tampered)
+    );
+}
+
+#[test]
+fn verify_all() {
+    // proofs in the container are generated with different options and for different inputs;
+    // the label of each proof determines the public inputs it is verified against
+    let mut aggregated = AggregatedProof::new(b"fib".to_vec());
+    let mut results = std::collections::HashMap::new();
+    for (length, extension) in [(16, false), (32, true), (64, false)] {
+        let prover = FibProver::<Blake3>::new(build_options(extension));
+        let trace = prover.build_trace(length);
+        let label = format!("fib-{length}");
+        results.insert(label.clone(), prover.get_pub_inputs(&trace));
+        aggregated.push(label, prover.prove(trace).unwrap());
+    }
+    assert_eq!(2, aggregated.distinct_options().len());
+    let aggregated = AggregatedProof::from_bytes(&aggregated.to_bytes()).unwrap();
+
+    let verify_proof = |results: &std::collections::HashMap<String, BaseElement>| {
+        let results = results.clone();
+        move |label: &str, proof: StarkProof| {
+            let acceptable_options = AcceptableOptions::OptionSet(vec![proof.options().clone()]);
+            winterfell::verify::<FibAir, Blake3, DefaultRandomCoin<Blake3>>(
+                proof,
+                results[label],
+                &acceptable_options,
+            )
+        }
+    };
+    assert!(winterfell::verify_all(aggregated.clone(), verify_proof(&results)).is_ok());
+    assert!(winterfell::verify_all(AggregatedProof::default(), verify_proof(&results)).is_ok());
+
+    // the index of the first invalid proof must be reported
+    results.insert("fib-32".into(), BaseElement::ONE);
+    results.insert("fib-64".into(), BaseElement::ONE);
+    let result = winterfell::verify_all(aggregated, verify_proof(&results));
+    assert!(matches!(result, Err((1, _))));
+}
+
+#[test]
+fn verify_auto() {
+    let factory = FibProverFactory(build_options(false));
+    for hash_fn in [HashFunction::Blake3_192, HashFunction::Blake3_256, HashFunction::Sha3_256] {
+        let trace = factory.build_prover::<Blake3>().build_trace(16);
+        let result = trace.get(1, trace.length() - 1);
+        let proof = winterfell::prove_dyn(&factory, trace, hash_fn).unwrap();
+        assert_eq!(Some(hash_fn), proof.context.hash_function());
+
+        // the hash function is read from the proof context
+        let acceptable_options = AcceptableOptions::OptionSet(vec![proof.options().clone()]);
+        let proof = StarkProof::from_bytes(&proof.to_bytes()).unwrap();
+        let verified = winterfell::verify_auto::<FibAir>(proof, result, &acceptable_options);
+        assert!(verified.is_ok());
+    }
+
+    // proofs generated with hash functions which cannot be selected at runtime are rejected
+    type SmallField = winterfell::math::fields::f64::BaseElement;
+    let prover = FibProver::<Rp64_256>::new(build_options(false));
+    let trace = prover.build_trace(16);
+    let result = trace.get(1, trace.length() - 1);
+    let proof = prover.prove(trace).unwrap();
+    let acceptable_options = AcceptableOptions::OptionSet(vec![build_options(false)]);
+    assert_eq!(
+        Err(VerifierError::UnknownHashFunction),
+        winterfell::verify_auto::<FibAir<SmallField>>(proof, result, &acceptable_options)
+    );
+}
+
+#[test]
+fn acceptance_policy() {
+    let prover = FibProver::<Blake3>::new(build_options(false));
+    let trace = prover.build_trace(16);
+    let result = trace.get(1, trace.length() - 1);
+    let proof = prover.prove(trace).unwrap();
+    let security = proof.security_level::<Blake3>(true);
+    assert_eq!(Some(security), proof.security_level_auto(true));
+
+    let verify = |policy: AcceptancePolicy| {
+        winterfell::verify::<FibAir, Blake3, DefaultRandomCoin<Blake3>>(
+            proof.clone(),
+            result,
+            &AcceptableOptions::Policy(policy),
+        )
+    };
+
+    // a policy which does not fix proof options exactly accepts the proof
+    let policy = AcceptancePolicy::new()
+        .with_min_conjectured_security(security)
+        .with_hash_functions(&[HashFunction::Blake3_192, HashFunction::Blake3_256])
+        .with_field::<BaseElement>(FieldExtension::None)
+        .with_max_trace_length(proof.trace_length());
+    assert_eq!(Ok(()), verify(policy.clone()));
+
+    // each restriction of the policy is enforced
+    let result = verify(policy.clone().with_min_conjectured_security(security + 1));
+    assert_eq!(
+        Err(VerifierError::InsufficientConjecturedSecurity(security + 1, security)),
+        result
+    );
+
+    let result = verify(policy.clone().with_hash_functions(&[HashFunction::Sha3_256]));
+    assert_eq!(Err(VerifierError::UnacceptableHashFunction), result);
+
+    let result =
+        verify(AcceptancePolicy::new().with_field::<BaseElement>(FieldExtension::Quadratic));
+    assert!(matches!(result, Err(VerifierError::UnacceptableField(_, 1))));
+
+    let max_trace_length = proof.trace_length() / 2;
+    let result = verify(policy.with_max_trace_length(max_trace_length));
+    assert_eq!(
+        Err(VerifierError::TraceLengthTooLarge(max_trace_length, proof.trace_length())),
+        result
+    );
+}
+
+#[test]
+fn context_binding() {
+    let options = build_options(false).with_fri_folding_schedule(&[4]);
+    let prover = FibProver::<Blake3>::new(options);
+    let trace = prover.build_trace(64);
+    let result = trace.get(1, trace.length() - 1);
+    let proof = prover.prove(trace).unwrap();
+    let bytes = proof.to_bytes();
+
+    let acceptable_options = AcceptableOptions::MinConjecturedSecurity(0);
+    let verified = winterfell::verify::<FibAir, Blake3, DefaultRandomCoin<Blake3>>(
+        proof.clone(),
+        result,
+        &acceptable_options,
+    );
+    assert_eq!(Ok(()), verified);
+
+    // changing any byte of the proof context makes the proof invalid; the context follows the
+    // 2-byte format version header, and starts with the width of the main trace segment, which
+    // is validated by the AIR constructor instead
+    for i in 3..2 + proof.context.to_bytes().len() {
+        for delta in [1, 128] {
+            let mut bytes = bytes.clone();
+            bytes[i] = bytes[i].wrapping_add(delta);
+            if let Ok(proof) = StarkProof::from_bytes(&bytes) {
+                let verified = winterfell::verify::<FibAir, Blake3, DefaultRandomCoin<Blake3>>(
+                    proof,
+                    result,
+                    &acceptable_options,
+                );
+                assert!(verified.is_err(), "context byte {i} is not bound to the proof");
+            }
+        }
+    }
+}
+
+#[test]
+fn verify_from_reader() {
+    let options = [build_options(true), build_options(false).with_fri_query_deduplication()];
+    for options in options {
+        let prover = FibProver::<Blake3>::new(options.clone());
+        let trace = prover.build_trace(16);
+        let result = trace.get(1, trace.length() - 1);
+        let proof = prover.prove(trace).unwrap();
+        let acceptable_options = AcceptableOptions::OptionSet(vec![options]);
+
+        // the proof is streamed into the target without an intermediate buffer
+        let mut target = WriteAdapter::new(Vec::new());
+        proof.write_streaming_into(&mut target);
+        let mut bytes = target.finish().unwrap();
+        assert_eq!(proof.to_streaming_bytes(), bytes);
+
+        // the proof is read from the source up to its last byte
+        bytes.extend_from_slice(&[1, 2, 3]);
+        let mut source = ReadAdapter::new(&bytes[..]);
+        let verified = winterfell::verify_from_reader::<FibAir, Blake3, DefaultRandomCoin<Blake3>, _>(
+            &mut source,
+            result,
+            &acceptable_options,
+        );
+        assert_eq!(Ok(()), verified);
+        assert_eq!(bytes.len() - 3, source.position());
+        assert_eq!(&[1, 2, 3], source.into_inner());
+        bytes.truncate(bytes.len() - 3);
+
+        // proofs serialized via to_bytes() cannot be verified incrementally
+        let verified = winterfell::verify_from_reader::<FibAir, Blake3, DefaultRandomCoin<Blake3>, _>(
+            &mut SliceReader::new(&proof.to_bytes()),
+            result,
+            &acceptable_options,
+        );
+        assert!(verified.is_err());
+
+        // a truncated proof cannot be verified; the last FRI layer is read only after all other
+        // sections of the proof are checked
+        let mut source = ReadAdapter::new(&bytes[..bytes.len() - 4]);
+        let verified = winterfell::verify_from_reader::<FibAir, Blake3, DefaultRandomCoin<Blake3>, _>(
+            &mut source,
+            result,
+            &acceptable_options,
+        );
+        assert!(matches!(verified, Err(VerifierError::ProofDeserializationError(_))));
+
+        // sections of queries are read only when they are needed: a proof with an invalid trace
+        // opening is rejected without reading constraint queries and FRI layers
+        let header_len = bytes.len()
+            - proof.trace_queries.to_bytes().len()
+            - 1
+            - proof.constraint_queries.to_bytes().len()
+            - (0..proof.fri_proof.num_layers())
+                .map(|i| {
+                    let mut layer = Vec::new();
+                    proof.fri_proof.write_layer_into(i, &mut layer);
+                    layer.len()
+                })
+                .sum::<usize>();
+        let trace_queries_len = proof.trace_queries.to_bytes().len() + 1;
+        let mut tampered = bytes[..header_len + trace_queries_len].to_vec();
+        tampered[header_len + 4] ^= 1;
+        let mut source = ReadAdapter::new(&tampered[..]);
+        let verified = winterfell::verify_from_reader::<FibAir, Blake3, DefaultRandomCoin<Blake3>, _>(
+            &mut source,
+            result,
+            &acceptable_options,
+        );
+        assert!(matches!(verified, Err(VerifierError::TraceQueryDoesNotMatchCommitment(..))));
+        assert!(source.into_inner().is_empty());
+
+        // proofs with unacceptable parameters are rejected after reading only the proof context
+        let policy = AcceptancePolicy::new().with_max_trace_length(proof.trace_length() / 2);
+        let mut source = ReadAdapter::new(&bytes[..]);
+        let verified = winterfell::verify_from_reader::<FibAir, Blake3, DefaultRandomCoin<Blake3>, _>(
+            &mut source,
+            result,
+            &AcceptableOptions::Policy(policy),
+        );
+        assert!(matches!(verified, Err(VerifierError::TraceLengthTooLarge(..))));
+        assert_eq!(bytes.len() - 2 - proof.context.to_bytes().len(), source.into_inner().len());
+    }
+}
+
+#[test]
+fn invalid_field_modulus() {
+    let prover = FibProver::<Blake3>::new(build_options(false));
+    let trace = prover.build_trace(16);
+    let result = trace.get(1, trace.length() - 1);
+    let proof = prover.prove(trace).unwrap();
+    let acceptable_options = AcceptableOptions::OptionSet(vec![build_options(false)]);
+
+    // replaces the field modulus recorded in the proof with a modulus which does not fit into
+    // two field elements; the modulus is checked before the proof context is converted into
+    // field elements, and thus, such proofs are rejected without a panic
+    let modulus = proof.context.field_modulus_bytes().to_vec();
+    let replace_modulus = |bytes: Vec<u8>| {
+        let offset = bytes
+            .windows(modulus.len() + 1)
+            .position(|w| w[0] as usize == modulus.len() && w[1..] == modulus)
+            .unwrap();
+        let mut corrupted = bytes[..offset].to_vec();
+        corrupted.push(32);
+        corrupted.extend_from_slice(&[0xff; 32]);
+        corrupted.extend_from_slice(&bytes[offset + modulus.len() + 1..]);
+        corrupted
+    };
+
+    let corrupted = StarkProof::from_bytes(&replace_modulus(proof.to_bytes())).unwrap();
+    let verified = winterfell::verify::<FibAir, Blake3, DefaultRandomCoin<Blake3>>(
+        corrupted.clone(),
+        result,
+        &acceptable_options,
+    );
+    assert_eq!(Err(VerifierError::InconsistentBaseField), verified);
+    let inspection = winterfell::inspect::<FibAir, BaseElement, Blake3, DefaultRandomCoin<Blake3>>(
+        corrupted,
+        result,
+        &acceptable_options,
+    );
+    assert!(matches!(inspection, Err(VerifierError::InconsistentBaseField)));
+
+    let bytes = replace_modulus(proof.to_streaming_bytes());
+    let verified = winterfell::verify_from_reader::<FibAir, Blake3, DefaultRandomCoin<Blake3>, _>(
+        &mut SliceReader::new(&bytes),
+        result,
+        &acceptable_options,
+    );
+    assert_eq!(Err(VerifierError::InconsistentBaseField), verified);
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns a copy of the specified queries with the lowest bit flipped in the value byte at the
+/// specified offset.
+fn flip_query_value_bit(queries: &Queries, offset: usize) -> Queries {
+    // serialized queries start with a 4-byte length of the values section
+    let mut bytes = queries.to_bytes();
+    bytes[4 + offset] ^= 1;
+    Queries::read_from_bytes(&bytes).unwrap()
+}